@@ -6,13 +6,17 @@ use axum::{
 };
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use chrono::{Duration, Utc};
-use common::auth::{DatabaseAuthService, JwtService};
+use common::auth::{DatabaseAuthService, LoginClient};
+use common::db::repositories::session::SessionRepository;
 use common::db::repositories::user::UserRepository;
+use common::i18n::Locale;
+use common::jwt_keys::JWKS_MAX_AGE_SECONDS;
 use common::models::User;
 use serde::{Deserialize, Serialize};
 
 use crate::handlers::{ErrorResponse, SuccessResponse};
-use crate::middleware::rate_limit::RateLimiter;
+use crate::middleware::locale::LOCALE_COOKIE;
+use crate::middleware::rate_limit::{LoginRateLimitError, RateLimiter};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +50,7 @@ pub struct UserResponse {
     pub username: String,
     pub email: Option<String>,
     pub enabled: bool,
+    pub locale: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -56,6 +61,7 @@ impl From<User> for UserResponse {
             username: user.username,
             email: user.email,
             enabled: user.enabled,
+            locale: user.locale,
             created_at: user.created_at,
         }
     }
@@ -84,6 +90,23 @@ fn get_client_ip(headers: &HeaderMap) -> String {
     "unknown".to_string()
 }
 
+/// Longest User-Agent kept on a session, in characters
+const MAX_USER_AGENT_LEN: usize = 512;
+
+/// Device and IP of a login request, recorded on its session
+pub(crate) fn login_client(headers: &HeaderMap) -> LoginClient {
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|h| h.to_str().ok())
+        .map(|ua| ua.chars().take(MAX_USER_AGENT_LEN).collect());
+    let ip_address = Some(get_client_ip(headers)).filter(|ip| ip != "unknown");
+
+    LoginClient {
+        user_agent,
+        ip_address,
+    }
+}
+
 /// Login endpoint (database mode)
 /// Requirements: 10.2 - Validate credentials against database
 /// Requirements: 10.3 - Generate JWT token on successful login
@@ -99,44 +122,56 @@ pub async fn login(
     let client_ip = get_client_ip(&headers);
 
     // Check rate limit
-    let rate_limiter = RateLimiter::new(state.redis_client.clone());
-    if let Err(error_msg) = rate_limiter.check_login_rate_limit(&client_ip).await {
+    let rate_limiter = RateLimiter::new(state.redis.clone());
+    if let Err(e) = rate_limiter.check_login_rate_limit(&client_ip).await {
         tracing::warn!(
             ip = %client_ip,
             username = %req.username,
-            error = %error_msg,
+            error = %e,
             "Login rate limit exceeded"
         );
-        return Err(ErrorResponse::new("rate_limit_exceeded", error_msg));
+        return Err(match e {
+            LoginRateLimitError::Blocked(seconds) => ErrorResponse::localized_with(
+                "rate_limit_exceeded",
+                "auth.too_many_attempts",
+                &[("seconds", &seconds.to_string())],
+            ),
+            LoginRateLimitError::Redis(_) => {
+                ErrorResponse::localized("rate_limit_exceeded", "error.rate_limit_exceeded")
+            }
+        });
     }
 
     // Validate input
     if req.username.is_empty() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "validation_error",
-            "Username is required",
+            "auth.username_required",
         ));
     }
 
     if req.password.is_empty() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "validation_error",
-            "Password is required",
+            "auth.password_required",
         ));
     }
 
     // Create JWT service from config
-    let jwt_secret = &state.config.auth.jwt_secret;
     let jwt_expiry_hours = state.config.auth.jwt_expiration_hours;
-    let jwt_service = JwtService::new(jwt_secret, jwt_expiry_hours);
+    let jwt_service = state.jwt_service();
 
     // Create user repository and auth service
     let user_repository = UserRepository::new(state.db_pool.clone());
-    let auth_service = DatabaseAuthService::new(jwt_service.clone(), user_repository);
+    let auth_service = DatabaseAuthService::new(
+        jwt_service.clone(),
+        user_repository.clone(),
+        SessionRepository::new(state.db_pool.clone()),
+    );
 
     // Authenticate user
     let token = auth_service
-        .login(&req.username, &req.password)
+        .login(&req.username, &req.password, &login_client(&headers))
         .await
         .map_err(|e| {
             tracing::warn!(
@@ -147,7 +182,7 @@ pub async fn login(
             );
 
             // Record failed login attempt
-            let rate_limiter = RateLimiter::new(state.redis_client.clone());
+            let rate_limiter = RateLimiter::new(state.redis.clone());
             let client_ip_clone = client_ip.clone();
             tokio::spawn(async move {
                 if let Err(e) = rate_limiter.record_failed_login(&client_ip_clone).await {
@@ -157,17 +192,20 @@ pub async fn login(
 
             match e {
                 common::errors::AuthError::InvalidCredentials => {
-                    ErrorResponse::new("unauthorized", "Invalid username or password")
+                    ErrorResponse::localized("unauthorized", "auth.invalid_credentials")
                 }
-                common::errors::AuthError::AuthenticationFailed(msg) => {
-                    ErrorResponse::new("unauthorized", msg)
+                common::errors::AuthError::AccountDisabled => {
+                    ErrorResponse::localized("unauthorized", "auth.account_disabled")
                 }
-                _ => ErrorResponse::new("internal_error", "Authentication failed"),
+                common::errors::AuthError::AuthenticationFailed(_) => {
+                    ErrorResponse::localized("unauthorized", "auth.failed")
+                }
+                _ => ErrorResponse::localized("internal_error", "auth.failed"),
             }
         })?;
 
     // Reset rate limit on successful login
-    let rate_limiter = RateLimiter::new(state.redis_client.clone());
+    let rate_limiter = RateLimiter::new(state.redis.clone());
     let client_ip_clone = client_ip.clone();
     tokio::spawn(async move {
         if let Err(e) = rate_limiter.reset_login_rate_limit(&client_ip_clone).await {
@@ -202,9 +240,35 @@ pub async fn login(
         .headers_mut()
         .insert(header::SET_COOKIE, cookie.to_string().parse().unwrap());
 
+    // Remember the user's preferred locale so later requests are served in it
+    if let Some(locale) = stored_locale(&user_repository, &req.username).await {
+        let locale_cookie = Cookie::build((LOCALE_COOKIE, locale.as_tag()))
+            .path("/")
+            .same_site(SameSite::Lax)
+            .build();
+        if let Ok(value) = locale_cookie.to_string().parse() {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+
     Ok(response)
 }
 
+/// Look up the locale stored on the user's profile, if any
+pub(crate) async fn stored_locale(
+    user_repository: &UserRepository,
+    username: &str,
+) -> Option<Locale> {
+    match user_repository.find_by_username(username).await {
+        Ok(Some(user)) => user.locale.and_then(|locale| locale.parse().ok()),
+        Ok(None) => None,
+        Err(e) => {
+            tracing::warn!(error = %e, username = %username, "Failed to load user locale");
+            None
+        }
+    }
+}
+
 /// Refresh token endpoint
 /// Requirements: 10.3 - Generate new JWT token from valid existing token
 #[tracing::instrument(skip(state, req))]
@@ -214,36 +278,62 @@ pub async fn refresh_token(
 ) -> Result<Json<SuccessResponse<LoginResponse>>, ErrorResponse> {
     // Validate input
     if req.token.is_empty() {
-        return Err(ErrorResponse::new("validation_error", "Token is required"));
+        return Err(ErrorResponse::localized(
+            "validation_error",
+            "auth.token_required",
+        ));
     }
 
-    // Create JWT service from config
-    let jwt_secret = &state.config.auth.jwt_secret;
-    let jwt_expiry_hours = state.config.auth.jwt_expiration_hours;
-    let jwt_service = JwtService::new(jwt_secret, jwt_expiry_hours);
+    let jwt_service = state.jwt_service();
+    state.jwt_keys.ensure_token_key(&req.token).await;
 
     // Validate existing token
     let claims = jwt_service.validate_token(&req.token).map_err(|e| {
         tracing::warn!(error = %e, "Token validation failed");
         match e {
             common::errors::AuthError::TokenExpired => {
-                ErrorResponse::new("unauthorized", "Token has expired")
+                ErrorResponse::localized("unauthorized", "auth.token_expired")
             }
-            common::errors::AuthError::InvalidToken(msg) => ErrorResponse::new("unauthorized", msg),
-            _ => ErrorResponse::new("unauthorized", "Invalid token"),
+            _ => ErrorResponse::localized("unauthorized", "auth.invalid_token"),
         }
     })?;
 
+    // Only tokens of a session that is still active can be refreshed; the session
+    // expires with the new token
+    let session_id = claims.sid.ok_or_else(|| {
+        tracing::warn!(user_id = %claims.sub, "Token without session can't be refreshed");
+        ErrorResponse::localized("unauthorized", "auth.invalid_token")
+    })?;
+    let expires_at = jwt_service.expires_at();
+    let extended = SessionRepository::new(state.db_pool.clone())
+        .extend(session_id, expires_at)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, session_id = %session_id, "Failed to extend session");
+            ErrorResponse::localized("database_error", "auth.session_not_extended")
+        })?;
+    if !extended {
+        tracing::warn!(user_id = %claims.sub, session_id = %session_id, "Refresh of revoked session");
+        return Err(ErrorResponse::localized(
+            "unauthorized",
+            "auth.session_revoked",
+        ));
+    }
+
     // Generate new token with same claims
     let new_token = jwt_service
-        .encode_token(&claims.sub, &claims.username, claims.permissions)
+        .encode_session_token(
+            &claims.sub,
+            &claims.username,
+            claims.permissions,
+            session_id,
+            claims.tenant_id,
+        )
         .map_err(|e| {
             tracing::error!(error = %e, "Failed to generate new token");
-            ErrorResponse::new("internal_error", "Failed to generate new token")
+            ErrorResponse::localized("internal_error", "auth.token_not_issued")
         })?;
-
-    // Calculate expiration time
-    let expires_at = (Utc::now() + Duration::hours(jwt_expiry_hours as i64)).timestamp();
+    let expires_at = expires_at.timestamp();
 
     tracing::info!(user_id = %claims.sub, username = %claims.username, "Token refreshed");
 
@@ -253,6 +343,22 @@ pub async fn refresh_token(
     })))
 }
 
+/// Public keys that validate our tokens, for other internal services
+///
+/// A new key is published here `JWKS_MAX_AGE_SECONDS` before it signs anything, which
+/// is as long as clients may cache the document, and stays until its tokens have
+/// expired.
+#[tracing::instrument(skip(state))]
+pub async fn jwks(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(
+            header::CACHE_CONTROL,
+            format!("public, max-age={}", JWKS_MAX_AGE_SECONDS),
+        )],
+        Json(state.jwt_keys.jwks()),
+    )
+}
+
 /// Create user endpoint (database mode)
 /// Requirements: 10.2 - Create user with bcrypt-hashed password
 /// Requirements: 10.13 - Store user with role assignments
@@ -263,34 +369,35 @@ pub async fn create_user(
 ) -> Result<Json<SuccessResponse<UserResponse>>, ErrorResponse> {
     // Validate input
     if req.username.is_empty() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "validation_error",
-            "Username is required",
+            "auth.username_required",
         ));
     }
 
     if req.password.is_empty() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "validation_error",
-            "Password is required",
+            "auth.password_required",
         ));
     }
 
     if req.password.len() < 8 {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "validation_error",
-            "Password must be at least 8 characters",
+            "auth.password_too_short",
         ));
     }
 
-    // Create JWT service from config
-    let jwt_secret = &state.config.auth.jwt_secret;
-    let jwt_expiry_hours = state.config.auth.jwt_expiration_hours;
-    let jwt_service = JwtService::new(jwt_secret, jwt_expiry_hours);
+    let jwt_service = state.jwt_service();
 
     // Create user repository and auth service
     let user_repository = UserRepository::new(state.db_pool.clone());
-    let auth_service = DatabaseAuthService::new(jwt_service, user_repository.clone());
+    let auth_service = DatabaseAuthService::new(
+        jwt_service,
+        user_repository.clone(),
+        SessionRepository::new(state.db_pool.clone()),
+    );
 
     // Create user
     let user = auth_service
@@ -306,9 +413,9 @@ pub async fn create_user(
                 common::errors::AuthError::AuthenticationFailed(msg)
                     if msg.contains("already exists") =>
                 {
-                    ErrorResponse::new("conflict", "Username already exists")
+                    ErrorResponse::localized("conflict", "auth.username_exists")
                 }
-                _ => ErrorResponse::new("internal_error", "Failed to create user"),
+                _ => ErrorResponse::localized("internal_error", "auth.user_not_created"),
             }
         })?;
 
@@ -324,7 +431,7 @@ pub async fn create_user(
                     error = %e,
                     "Failed to assign role to user"
                 );
-                ErrorResponse::new("internal_error", "Failed to assign roles to user")
+                ErrorResponse::localized("internal_error", "auth.roles_not_assigned")
             })?;
     }
 
@@ -394,6 +501,8 @@ mod tests {
             password_hash: "hashed".to_string(),
             email: Some("test@example.com".to_string()),
             enabled: true,
+            locale: None,
+            tenant_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
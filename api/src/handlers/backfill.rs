@@ -0,0 +1,231 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::handlers::jobs::{apply_definition, publish_execution, wait_for_completion};
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::{AppState, SseEvent};
+use common::db::repositories::execution::ExecutionRepository;
+use common::db::repositories::job::JobRepository;
+use common::db::repositories::CalendarRepository;
+use common::models::{ExecutionStatus, Job, JobExecution, UserClaims};
+
+/// Most runs one backfill may make
+const MAX_BACKFILL_RUNS: usize = 1000;
+
+/// Most runs of one backfill in flight at once
+const MAX_BACKFILL_PARALLELISM: u32 = 10;
+
+/// How long past the job's timeout a run may take before its slot goes to the next
+const RUN_WAIT_GRACE: Duration = Duration::from_secs(300);
+
+/// Request to replay a job's schedule over a past date range
+#[derive(Debug, Deserialize)]
+pub struct BackfillRequest {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Runs in flight at once; 1 when left out, so the windows replay in order
+    #[serde(default = "default_parallelism")]
+    pub parallelism: u32,
+}
+
+fn default_parallelism() -> u32 {
+    1
+}
+
+/// The runs a backfill makes
+#[derive(Debug, Serialize)]
+pub struct BackfillResponse {
+    pub backfill_id: Uuid,
+    /// Due times of the runs queued, oldest first
+    pub runs: Vec<DateTime<Utc>>,
+    /// Due times backfilled before, which aren't run again
+    pub already_backfilled: Vec<DateTime<Utc>>,
+    pub parallelism: u32,
+}
+
+/// Replay the runs a job's schedule was due to make between `from` and `to`
+///
+/// Each run gets an execution triggered by `backfill`, made in the background with
+/// at most `parallelism` running at once. A window is backfilled once: repeating
+/// a backfill skips the windows it already made, so an overlapping range is safe.
+/// The job's `allow_concurrent` is not consulted.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn backfill_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<BackfillRequest>,
+) -> Result<(StatusCode, Json<SuccessResponse<BackfillResponse>>), ErrorResponse> {
+    let invalid = |reason: &str| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_backfill",
+            &[("reason", reason)],
+        )
+    };
+    if req.from >= req.to {
+        return Err(invalid("from must be before to"));
+    }
+    if req.to > Utc::now() {
+        return Err(invalid("to must not be in the future"));
+    }
+    if !(1..=MAX_BACKFILL_PARALLELISM).contains(&req.parallelism) {
+        return Err(invalid("parallelism must be between 1 and 10"));
+    }
+
+    let mut job = JobRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    if let Some(definition) = job.definition.clone() {
+        apply_definition(&mut job, &definition);
+    }
+    let Some(schedule) = &job.schedule else {
+        return Err(invalid("the job has no schedule"));
+    };
+
+    let calendar = match schedule.calendar() {
+        Some(wanted) => CalendarRepository::new(state.db_pool.clone())
+            .find_by_name(&wanted.name)
+            .await
+            .map_err(|e| {
+                ErrorResponse::new("database_error", format!("Failed to load calendar: {}", e))
+            })?,
+        None => None,
+    };
+    let runs = schedule
+        .runs_between(req.from, req.to, calendar.as_ref(), MAX_BACKFILL_RUNS)
+        .map_err(|e| invalid(&e.to_string()))?;
+
+    let execution_repo = ExecutionRepository::new(state.db_pool.clone());
+    let backfill_id = Uuid::new_v4();
+    let mut executions = Vec::new();
+    let mut already_backfilled = Vec::new();
+    for run in runs {
+        let existing = execution_repo
+            .find_by_idempotency_key(&JobExecution::backfill_key(id, run))
+            .await
+            .map_err(|e| {
+                ErrorResponse::new(
+                    "database_error",
+                    format!("Failed to check earlier backfills: {}", e),
+                )
+            })?;
+        match existing {
+            Some(_) => already_backfilled.push(run),
+            None => executions.push(JobExecution::new_backfill(id, run, backfill_id)),
+        }
+    }
+
+    let response = BackfillResponse {
+        backfill_id,
+        runs: executions.iter().filter_map(scheduled_for).collect(),
+        already_backfilled,
+        parallelism: req.parallelism,
+    };
+
+    tracing::info!(
+        job_id = %id,
+        backfill_id = %backfill_id,
+        from = %req.from,
+        to = %req.to,
+        runs = response.runs.len(),
+        skipped = response.already_backfilled.len(),
+        parallelism = req.parallelism,
+        user = %claims.username,
+        "Job backfill started"
+    );
+
+    tokio::spawn(run_backfill(state, job, executions, req.parallelism));
+
+    Ok((StatusCode::ACCEPTED, Json(SuccessResponse::new(response))))
+}
+
+/// Make a backfill's runs oldest first, keeping `parallelism` of them in flight
+async fn run_backfill(state: AppState, job: Job, executions: Vec<JobExecution>, parallelism: u32) {
+    let run_wait = Duration::from_secs(job.timeout_seconds.max(0) as u64) + RUN_WAIT_GRACE;
+    let job = Arc::new(job);
+    let mut in_flight = JoinSet::new();
+    for execution in executions {
+        while in_flight.len() >= parallelism as usize {
+            in_flight.join_next().await;
+        }
+        let state = state.clone();
+        let job = Arc::clone(&job);
+        in_flight.spawn(async move { run_window(&state, &job, execution, run_wait).await });
+    }
+    while in_flight.join_next().await.is_some() {}
+
+    tracing::info!(job_id = %job.id, "Job backfill finished");
+}
+
+/// Make one backfill run and wait for it to finish
+async fn run_window(state: &AppState, job: &Job, execution: JobExecution, run_wait: Duration) {
+    let execution_repo = ExecutionRepository::new(state.db_pool.clone());
+    if let Err(e) = execution_repo.create(&execution).await {
+        tracing::warn!(
+            job_id = %job.id,
+            scheduled_for = ?scheduled_for(&execution),
+            error = %e,
+            "Failed to create backfill execution, skipping the window"
+        );
+        return;
+    }
+
+    // Subscribe before publishing so a fast execution's completion isn't missed
+    let status_rx = state.sse_tx.subscribe();
+    if let Err(e) = publish_execution(state, &execution, job).await {
+        tracing::error!(
+            execution_id = %execution.id,
+            error = %e.message,
+            "Failed to publish backfill execution"
+        );
+        let mut failed_execution = execution.clone();
+        failed_execution.error = Some(format!("Failed to publish to queue: {}", e.message));
+        if let Ok(previous) = failed_execution.transition_to(ExecutionStatus::Failed) {
+            let _ = execution_repo
+                .transition(&failed_execution, &previous)
+                .await;
+        }
+        return;
+    }
+    state.broadcast_event(SseEvent::ExecutionStatusChanged {
+        execution_id: execution.id,
+        job_id: job.id,
+        status: "pending".to_string(),
+    });
+
+    if tokio::time::timeout(
+        run_wait,
+        wait_for_completion(&execution_repo, status_rx, execution.id),
+    )
+    .await
+    .is_err()
+    {
+        tracing::warn!(
+            execution_id = %execution.id,
+            "Backfill execution still running, starting the next window"
+        );
+    }
+}
+
+/// The due time a backfill execution replays
+fn scheduled_for(execution: &JobExecution) -> Option<DateTime<Utc>> {
+    execution
+        .trigger_metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("scheduled_for"))
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
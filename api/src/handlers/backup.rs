@@ -0,0 +1,145 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use common::backup::{snapshot_path, BackupError, Snapshot, SnapshotSummary};
+use common::db::migrations;
+use common::db::repositories::BackupRepository;
+use common::models::UserClaims;
+use serde::Deserialize;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Request to take a snapshot
+#[derive(Debug, Deserialize)]
+pub struct BackupRequest {
+    /// age public keys (`age1...`) the snapshot's secrets are sealed to
+    pub recipients: Vec<String>,
+}
+
+/// Request to restore a snapshot
+#[derive(Debug, Deserialize)]
+pub struct RestoreRequest {
+    /// Snapshot file name as returned by a backup, e.g. `snapshot-20260101T000000Z.json`
+    pub name: String,
+    /// age secret key (`AGE-SECRET-KEY-1...`) matching one of the snapshot's recipients
+    pub identity: String,
+}
+
+/// Take a consistent snapshot of the cluster's configuration into storage (admin only)
+///
+/// Secrets are sealed to the request's recipients before anything is stored.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn create_backup(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<BackupRequest>,
+) -> Result<(StatusCode, Json<SuccessResponse<SnapshotSummary>>), ErrorResponse> {
+    let mut snapshot = BackupRepository::new(state.db_pool.clone())
+        .capture(&claims.username, env!("CARGO_PKG_VERSION"))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to capture snapshot");
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to capture snapshot: {}", e),
+            )
+        })?;
+    snapshot.seal_secrets(&req.recipients).map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "backup.secrets_not_sealed",
+            &[("reason", &e.to_string())],
+        )
+    })?;
+
+    let data = serde_json::to_vec_pretty(&snapshot).map_err(|e| {
+        ErrorResponse::new(
+            "serialization_error",
+            format!("Failed to encode snapshot: {}", e),
+        )
+    })?;
+    let path = state
+        .storage_service
+        .store_file(&snapshot.path(), &data)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to store snapshot");
+            ErrorResponse::new("storage_error", format!("Failed to store snapshot: {}", e))
+        })?;
+
+    let summary = snapshot.summary(path);
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        path = %summary.path,
+        rows = ?summary.rows,
+        recipients = req.recipients.len(),
+        "Audit log: Disaster-recovery snapshot created"
+    );
+
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(summary))))
+}
+
+/// Rebuild the cluster's configuration from a snapshot on an empty cluster (admin only)
+///
+/// The identity only unseals the snapshot's secrets and is never stored.
+#[tracing::instrument(skip(state, claims, req), fields(name = %req.name))]
+pub async fn restore_backup(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<RestoreRequest>,
+) -> Result<Json<SuccessResponse<SnapshotSummary>>, ErrorResponse> {
+    let path = snapshot_path(&req.name).map_err(|e| backup_error(e.into()))?;
+    let data = state.storage_service.load_file(&path).await.map_err(|e| {
+        tracing::warn!(error = %e, path = %path, "Failed to load snapshot");
+        ErrorResponse::localized_with("not_found", "backup.snapshot_not_found", &[("path", &path)])
+    })?;
+
+    let mut snapshot = Snapshot::from_slice(&data).map_err(backup_error)?;
+    snapshot
+        .check_compatible(migrations::latest_version())
+        .map_err(backup_error)?;
+    snapshot.open_secrets(&req.identity).map_err(backup_error)?;
+    BackupRepository::new(state.db_pool.clone())
+        .restore(&snapshot)
+        .await
+        .map_err(backup_error)?;
+
+    let summary = snapshot.summary(path);
+    tracing::warn!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        path = %summary.path,
+        snapshot_created_at = %summary.created_at,
+        rows = ?summary.rows,
+        "Audit log: Cluster restored from snapshot"
+    );
+
+    Ok(Json(SuccessResponse::new(summary)))
+}
+
+/// Map a restore error, surfacing unusable snapshots or identities as 400 and a
+/// non-empty cluster as 409
+fn backup_error(error: BackupError) -> ErrorResponse {
+    match error {
+        BackupError::Validation(_)
+        | BackupError::InvalidSnapshot(_)
+        | BackupError::Incompatible(_)
+        | BackupError::Encryption(_) => ErrorResponse::localized_with(
+            "validation_error",
+            "backup.invalid_snapshot",
+            &[("reason", &error.to_string())],
+        ),
+        BackupError::NotEmpty(ref tables) => ErrorResponse::localized_with(
+            "conflict",
+            "backup.cluster_not_empty",
+            &[("tables", tables)],
+        ),
+        e => {
+            tracing::error!(error = %e, "Failed to restore snapshot");
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to restore snapshot: {}", e),
+            )
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{Datelike, NaiveDate, Utc, Weekday};
+use common::db::repositories::CalendarRepository;
+use common::models::UserClaims;
+use common::schedule::calendar::{Calendar, Holiday};
+use serde::Deserialize;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Longest accepted calendar name, matching the column width
+const MAX_CALENDAR_NAME_LEN: usize = 100;
+
+/// Request to create or replace a calendar
+#[derive(Debug, Deserialize)]
+pub struct UpsertCalendarRequest {
+    pub name: String,
+    pub description: Option<String>,
+    /// Saturday and Sunday when left out
+    pub weekend: Option<Vec<Weekday>>,
+    #[serde(default)]
+    pub vietnam_holidays: bool,
+    #[serde(default)]
+    pub holidays: Vec<Holiday>,
+    #[serde(default)]
+    pub working_days: Vec<NaiveDate>,
+}
+
+/// Query parameters for a calendar's holidays
+#[derive(Debug, Deserialize)]
+pub struct HolidaysQuery {
+    /// The current year when left out
+    pub year: Option<i32>,
+}
+
+/// List all calendars (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_calendars(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<Calendar>>>, ErrorResponse> {
+    let repository = CalendarRepository::new(state.db_pool.clone());
+
+    let calendars = repository.find_all().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to list calendars");
+        ErrorResponse::localized("database_error", "calendar.list_failed")
+    })?;
+
+    Ok(Json(SuccessResponse::new(calendars)))
+}
+
+/// Create or replace a calendar by name (admin only)
+///
+/// Schedules naming the calendar follow the new days from the next scheduler poll.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn upsert_calendar(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<UpsertCalendarRequest>,
+) -> Result<Json<SuccessResponse<Calendar>>, ErrorResponse> {
+    let name = req.name.trim();
+    if name.is_empty() || name.len() > MAX_CALENDAR_NAME_LEN {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "calendar.invalid_name",
+            &[("max", &MAX_CALENDAR_NAME_LEN.to_string())],
+        ));
+    }
+    let mut weekend = req
+        .weekend
+        .unwrap_or_else(|| vec![Weekday::Sat, Weekday::Sun]);
+    weekend.sort_by_key(Weekday::number_from_monday);
+    weekend.dedup();
+    if weekend.len() == 7 {
+        return Err(ErrorResponse::localized(
+            "validation_error",
+            "calendar.all_weekend",
+        ));
+    }
+
+    let repository = CalendarRepository::new(state.db_pool.clone());
+    let calendar = repository
+        .upsert(
+            name,
+            req.description.as_deref(),
+            &weekend,
+            req.vietnam_holidays,
+            &req.holidays,
+            &req.working_days,
+        )
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save calendar");
+            ErrorResponse::localized("database_error", "calendar.not_saved")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        calendar = %calendar.name,
+        "Audit log: Calendar changed"
+    );
+
+    Ok(Json(SuccessResponse::new(calendar)))
+}
+
+/// Holidays of a calendar in a year, to check it before schedules use it (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_calendar_holidays(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<HolidaysQuery>,
+) -> Result<Json<SuccessResponse<Vec<Holiday>>>, ErrorResponse> {
+    let calendar = find_calendar(&state, &name).await?;
+    let year = query.year.unwrap_or_else(|| Utc::now().year());
+
+    Ok(Json(SuccessResponse::new(calendar.holidays_in(year))))
+}
+
+/// Delete a calendar (admin only)
+///
+/// Rejected while any job's schedule still names it; the scheduler would skip
+/// those jobs' runs.
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_calendar(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(name): Path<String>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let repository = CalendarRepository::new(state.db_pool.clone());
+    let jobs = repository.find_job_names_using(&name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to find jobs using calendar");
+        ErrorResponse::localized("database_error", "calendar.not_deleted")
+    })?;
+    if !jobs.is_empty() {
+        return Err(ErrorResponse::localized_with(
+            "conflict",
+            "calendar.in_use",
+            &[("name", &name), ("jobs", &jobs.join(", "))],
+        ));
+    }
+
+    let deleted = repository.delete(&name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to delete calendar");
+        ErrorResponse::localized("database_error", "calendar.not_deleted")
+    })?;
+
+    if !deleted {
+        return Err(ErrorResponse::localized_with(
+            "not_found",
+            "calendar.not_found",
+            &[("name", &name)],
+        ));
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        calendar = %name,
+        "Audit log: Calendar deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+async fn find_calendar(state: &AppState, name: &str) -> Result<Calendar, ErrorResponse> {
+    CalendarRepository::new(state.db_pool.clone())
+        .find_by_name(name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load calendar");
+            ErrorResponse::localized("database_error", "calendar.not_loaded")
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "calendar.not_found", &[("name", name)])
+        })
+}
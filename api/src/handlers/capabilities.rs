@@ -0,0 +1,96 @@
+use axum::{extract::State, Extension, Json};
+use common::capabilities::{self, Capabilities, Versions};
+use common::db::migrations;
+use common::db::repositories::FeatureFlagRepository;
+use common::errors::ValidationError;
+use common::models::{JobStep, UserClaims};
+use std::collections::BTreeMap;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Effective feature flags for a tenant (global flags when `tenant_id` is None)
+async fn tenant_flags(
+    state: &AppState,
+    tenant_id: Option<&str>,
+) -> Result<BTreeMap<String, bool>, ErrorResponse> {
+    let flags = FeatureFlagRepository::new(state.db_pool.clone())
+        .find_for_tenant(tenant_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load feature flags");
+            ErrorResponse::new("database_error", "Failed to load feature flags")
+        })?;
+
+    Ok(capabilities::resolve_flags(&flags, tenant_id))
+}
+
+/// Show enabled executors, backends, versions and the caller's feature flags
+///
+/// Flags are resolved for the caller's tenant.
+#[tracing::instrument(skip(state, claims))]
+pub async fn get_capabilities(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<Capabilities>>, ErrorResponse> {
+    let tenant_id = claims.tenant_id.as_deref();
+    let features = tenant_flags(&state, tenant_id).await?;
+
+    let versions = Versions {
+        api: env!("CARGO_PKG_VERSION").to_string(),
+        schema: migrations::latest_version(),
+    };
+
+    Ok(Json(SuccessResponse::new(Capabilities::new(
+        &state.config.features.experimental_step_types,
+        features,
+        tenant_id.map(str::to_string),
+        versions,
+    ))))
+}
+
+/// Reject job steps the caller may not use: experimental step types that aren't
+/// enabled for the caller's tenant, and system steps from anyone but an admin
+pub async fn ensure_step_types_enabled(
+    state: &AppState,
+    claims: &UserClaims,
+    steps: &[JobStep],
+) -> Result<(), ErrorResponse> {
+    let uses_system_steps = steps.iter().any(|step| {
+        capabilities::step_type_name(&step.step_type) == capabilities::SYSTEM_STEP_TYPE
+    });
+    if uses_system_steps && !claims.permissions.contains(&"system:config".to_string()) {
+        tracing::warn!(username = %claims.username, "System step rejected for non-admin");
+        return Err(ErrorResponse::localized(
+            "forbidden",
+            "job.system_steps_admin_only",
+        ));
+    }
+
+    let experimental = &state.config.features.experimental_step_types;
+    if !capabilities::uses_experimental_step_types(steps, experimental) {
+        return Ok(());
+    }
+
+    let tenant_id = claims.tenant_id.as_deref();
+    let flags = tenant_flags(state, tenant_id).await?;
+    capabilities::check_step_types(steps, experimental, &flags).map_err(|e| {
+        let step_type = match &e {
+            ValidationError::InvalidFieldValue { reason, .. } => reason.clone(),
+            _ => e.to_string(),
+        };
+        tracing::warn!(
+            tenant_id = ?tenant_id,
+            step_type = %step_type,
+            "Experimental step type not enabled for tenant"
+        );
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.step_type_not_enabled",
+            &[
+                ("step_type", &step_type),
+                ("flag", &capabilities::step_type_flag(&step_type)),
+            ],
+        )
+    })
+}
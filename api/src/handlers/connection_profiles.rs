@@ -0,0 +1,271 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use common::connection_profile::{ConnectionEndpoint, ConnectionProfile, VerificationReport};
+use common::db::repositories::{
+    ConnectionProfileRepository, ExecutionRepository, JobRepository, SystemJobRepository,
+};
+use common::models::{JobExecution, UserClaims};
+use common::system_jobs::CONNECTION_VERIFICATION_KEY;
+use serde::Deserialize;
+
+use crate::handlers::jobs::publish_execution;
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Longest accepted profile name, matching the column width
+const MAX_PROFILE_NAME_LEN: usize = 100;
+
+/// Request to create or replace a connection profile
+#[derive(Debug, Deserialize)]
+pub struct UpsertConnectionProfileRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub endpoint: ConnectionEndpoint,
+}
+
+/// List all connection profiles, secrets masked (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_connection_profiles(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<ConnectionProfile>>>, ErrorResponse> {
+    let repository = ConnectionProfileRepository::new(state.db_pool.clone());
+
+    let profiles = repository.find_all().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to list connection profiles");
+        ErrorResponse::localized("database_error", "connection_profile.list_failed")
+    })?;
+
+    Ok(Json(SuccessResponse::new(
+        profiles.iter().map(ConnectionProfile::redacted).collect(),
+    )))
+}
+
+/// Get a connection profile, secrets masked (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn get_connection_profile(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<SuccessResponse<ConnectionProfile>>, ErrorResponse> {
+    let profile = ConnectionProfileRepository::new(state.db_pool.clone())
+        .find_by_name(&name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load connection profile");
+            ErrorResponse::localized("database_error", "connection_profile.not_loaded")
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "connection_profile.not_found",
+                &[("name", &name)],
+            )
+        })?;
+
+    Ok(Json(SuccessResponse::new(profile.redacted())))
+}
+
+/// Create or replace a connection profile by name (admin only)
+///
+/// Steps using the profile connect with the new endpoint and credentials from their
+/// next run on. Changing an existing profile queues a verification probe for every
+/// job using it; `GET /api/system/connections/{name}/verifications` reports which
+/// would break, so the old credential is only revoked once all have passed.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn upsert_connection_profile(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<UpsertConnectionProfileRequest>,
+) -> Result<Json<SuccessResponse<ConnectionProfile>>, ErrorResponse> {
+    let name = req.name.trim();
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "connection_profile.invalid_name",
+            &[("max", &MAX_PROFILE_NAME_LEN.to_string())],
+        ));
+    }
+    req.endpoint.validate().map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "connection_profile.invalid_endpoint",
+            &[("reason", &e.to_string())],
+        )
+    })?;
+
+    let repository = ConnectionProfileRepository::new(state.db_pool.clone());
+    let previous = repository.find_by_name(name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load connection profile");
+        ErrorResponse::localized("database_error", "connection_profile.not_loaded")
+    })?;
+    let profile = repository
+        .upsert(name, req.description.as_deref(), &req.endpoint)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save connection profile");
+            ErrorResponse::localized("database_error", "connection_profile.not_saved")
+        })?;
+
+    let endpoint_changed = previous.is_some_and(|previous| previous.endpoint != profile.endpoint);
+    let verifications = if endpoint_changed {
+        repository
+            .request_verifications(&profile.name)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to queue connection verification");
+                ErrorResponse::localized(
+                    "database_error",
+                    "connection_profile.verification_not_queued",
+                )
+            })?
+    } else {
+        0
+    };
+    if verifications > 0 {
+        trigger_connection_verification(&state, &claims).await;
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        connection = %profile.name,
+        kind = profile.endpoint.kind(),
+        verifications = verifications,
+        "Audit log: Connection profile changed"
+    );
+
+    Ok(Json(SuccessResponse::new(profile.redacted())))
+}
+
+/// Report which jobs using a connection profile would break since it last changed
+/// (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn get_connection_verifications(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<SuccessResponse<VerificationReport>>, ErrorResponse> {
+    let repository = ConnectionProfileRepository::new(state.db_pool.clone());
+    let not_found = || {
+        ErrorResponse::localized_with(
+            "not_found",
+            "connection_profile.not_found",
+            &[("name", &name)],
+        )
+    };
+
+    repository
+        .find_by_name(&name)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load connection profile");
+            ErrorResponse::localized("database_error", "connection_profile.not_loaded")
+        })?
+        .ok_or_else(not_found)?;
+    let verifications = repository.verifications(&name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to load connection verifications");
+        ErrorResponse::localized(
+            "database_error",
+            "connection_profile.verifications_not_loaded",
+        )
+    })?;
+
+    Ok(Json(SuccessResponse::new(VerificationReport::new(
+        &name,
+        verifications,
+    ))))
+}
+
+/// Run the connection verification system job now instead of at its next scheduled run
+///
+/// Failures are only logged: the probes stay queued for the scheduled run.
+async fn trigger_connection_verification(state: &AppState, claims: &UserClaims) {
+    let status = SystemJobRepository::new(state.db_pool.clone())
+        .status(CONNECTION_VERIFICATION_KEY)
+        .await;
+    let job_id = match status {
+        Ok(Some(status)) if status.enabled => status.job_id,
+        Ok(_) => {
+            tracing::warn!(
+                "Connection verification job is disabled or missing, probes stay queued"
+            );
+            return;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to look up connection verification job");
+            return;
+        }
+    };
+
+    let job = match JobRepository::new(state.db_pool.clone())
+        .find_by_id(job_id)
+        .await
+    {
+        Ok(Some(job)) => job,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to load connection verification job");
+            return;
+        }
+    };
+
+    let execution = JobExecution::new_manual(job.id, claims.username.clone());
+    if let Err(e) = ExecutionRepository::new(state.db_pool.clone())
+        .create(&execution)
+        .await
+    {
+        tracing::warn!(error = %e, "Failed to create connection verification execution");
+        return;
+    }
+    if publish_execution(state, &execution, &job).await.is_err() {
+        tracing::warn!(
+            execution_id = %execution.id,
+            "Failed to queue connection verification, probes wait for its scheduled run"
+        );
+    }
+}
+
+/// Delete a connection profile (admin only)
+///
+/// Rejected while any job's steps still name it, since their runs would fail.
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_connection_profile(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(name): Path<String>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let repository = ConnectionProfileRepository::new(state.db_pool.clone());
+    let jobs = repository.find_job_names_using(&name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to find jobs using connection profile");
+        ErrorResponse::localized("database_error", "connection_profile.not_deleted")
+    })?;
+    if !jobs.is_empty() {
+        return Err(ErrorResponse::localized_with(
+            "conflict",
+            "connection_profile.in_use",
+            &[("name", &name), ("jobs", &jobs.join(", "))],
+        ));
+    }
+
+    let deleted = repository.delete(&name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to delete connection profile");
+        ErrorResponse::localized("database_error", "connection_profile.not_deleted")
+    })?;
+
+    if !deleted {
+        return Err(ErrorResponse::localized_with(
+            "not_found",
+            "connection_profile.not_found",
+            &[("name", &name)],
+        ));
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        connection = %name,
+        "Audit log: Connection profile deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
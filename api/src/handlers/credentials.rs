@@ -0,0 +1,240 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use common::db::repositories::tls_material::TlsMaterialRepository;
+use common::errors::DatabaseError;
+use common::models::{CredentialExpiry, TlsMaterial, TlsMaterialKind, UserClaims};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Furthest ahead an expiration listing may look
+const MAX_WITHIN_DAYS: u32 = 3650;
+
+/// Request to start tracking a certificate or key
+#[derive(Debug, Deserialize)]
+pub struct CreateTlsMaterialRequest {
+    pub name: String,
+    pub kind: TlsMaterialKind,
+    pub description: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request to update a tracked certificate or key, e.g. after renewing it
+#[derive(Debug, Deserialize)]
+pub struct UpdateTlsMaterialRequest {
+    pub name: Option<String>,
+    pub kind: Option<TlsMaterialKind>,
+    pub description: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Query parameters for listing expirations
+#[derive(Debug, Deserialize)]
+pub struct ExpirationsQuery {
+    /// Days ahead to look; defaults to `system_jobs.expiry_warn_days`
+    pub within_days: Option<u32>,
+}
+
+/// List variables and TLS materials expiring within the given days, soonest first;
+/// already expired ones are included (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_credential_expirations(
+    State(state): State<AppState>,
+    Query(query): Query<ExpirationsQuery>,
+) -> Result<Json<SuccessResponse<Vec<CredentialExpiry>>>, ErrorResponse> {
+    let within_days = query
+        .within_days
+        .unwrap_or(state.config.system_jobs.expiry_warn_days);
+    if within_days > MAX_WITHIN_DAYS {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "credential.invalid_within_days",
+            &[("max", &MAX_WITHIN_DAYS.to_string())],
+        ));
+    }
+
+    let before = Utc::now() + Duration::days(within_days as i64);
+    let expiring = TlsMaterialRepository::new(state.db_pool.clone())
+        .expiring_credentials(before)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list credential expirations");
+            ErrorResponse::localized("database_error", "credential.expirations_not_loaded")
+        })?;
+
+    Ok(Json(SuccessResponse::new(expiring)))
+}
+
+/// List tracked certificates and keys, soonest expiry first (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_tls_materials(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<TlsMaterial>>>, ErrorResponse> {
+    let materials = TlsMaterialRepository::new(state.db_pool.clone())
+        .list()
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list TLS materials");
+            ErrorResponse::localized("database_error", "tls_material.not_loaded")
+        })?;
+
+    Ok(Json(SuccessResponse::new(materials)))
+}
+
+/// Start tracking the expiry of a certificate or key (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn create_tls_material(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<CreateTlsMaterialRequest>,
+) -> Result<Json<SuccessResponse<TlsMaterial>>, ErrorResponse> {
+    let name = validate_name(&req.name)?;
+    let repo = TlsMaterialRepository::new(state.db_pool.clone());
+    ensure_name_free(&repo, &name, None).await?;
+
+    let now = Utc::now();
+    let material = TlsMaterial {
+        id: Uuid::new_v4(),
+        name,
+        kind: req.kind,
+        description: req.description,
+        expires_at: req.expires_at,
+        created_at: now,
+        updated_at: now,
+    };
+    repo.create(&material).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to create TLS material");
+        ErrorResponse::localized("database_error", "tls_material.not_saved")
+    })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        tls_material_id = %material.id,
+        name = %material.name,
+        expires_at = %material.expires_at,
+        "Audit log: TLS material created"
+    );
+
+    Ok(Json(SuccessResponse::new(material)))
+}
+
+/// Update a tracked certificate or key (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn update_tls_material(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<UpdateTlsMaterialRequest>,
+) -> Result<Json<SuccessResponse<TlsMaterial>>, ErrorResponse> {
+    let repo = TlsMaterialRepository::new(state.db_pool.clone());
+    let mut material = repo
+        .find_by_id(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, tls_material_id = %id, "Failed to get TLS material");
+            ErrorResponse::localized("database_error", "tls_material.not_loaded")
+        })?
+        .ok_or_else(|| ErrorResponse::localized("not_found", "tls_material.not_found"))?;
+
+    if let Some(name) = req.name {
+        let name = validate_name(&name)?;
+        ensure_name_free(&repo, &name, Some(id)).await?;
+        material.name = name;
+    }
+    if let Some(kind) = req.kind {
+        material.kind = kind;
+    }
+    if let Some(description) = req.description {
+        material.description = Some(description);
+    }
+    if let Some(expires_at) = req.expires_at {
+        material.expires_at = expires_at;
+    }
+
+    repo.update(&material).await.map_err(|e| match e {
+        DatabaseError::NotFound(_) => {
+            ErrorResponse::localized("not_found", "tls_material.not_found")
+        }
+        e => {
+            tracing::error!(error = %e, tls_material_id = %id, "Failed to update TLS material");
+            ErrorResponse::localized("database_error", "tls_material.not_saved")
+        }
+    })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        tls_material_id = %id,
+        expires_at = %material.expires_at,
+        "Audit log: TLS material updated"
+    );
+
+    Ok(Json(SuccessResponse::new(material)))
+}
+
+/// Stop tracking a certificate or key (admin only)
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_tls_material(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    TlsMaterialRepository::new(state.db_pool.clone())
+        .delete(id)
+        .await
+        .map_err(|e| match e {
+            DatabaseError::NotFound(_) => {
+                ErrorResponse::localized("not_found", "tls_material.not_found")
+            }
+            e => {
+                tracing::error!(error = %e, tls_material_id = %id, "Failed to delete TLS material");
+                ErrorResponse::localized("database_error", "tls_material.not_deleted")
+            }
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        tls_material_id = %id,
+        "Audit log: TLS material deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+fn validate_name(name: &str) -> Result<String, ErrorResponse> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ErrorResponse::localized(
+            "validation_error",
+            "tls_material.empty_name",
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Reject a name already used by another material
+async fn ensure_name_free(
+    repo: &TlsMaterialRepository,
+    name: &str,
+    id: Option<Uuid>,
+) -> Result<(), ErrorResponse> {
+    let existing = repo.find_by_name(name).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to get TLS material");
+        ErrorResponse::localized("database_error", "tls_material.not_loaded")
+    })?;
+    match existing {
+        Some(existing) if Some(existing.id) != id => Err(ErrorResponse::localized_with(
+            "conflict",
+            "tls_material.name_exists",
+            &[("name", name)],
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,136 @@
+// Dead letter queue handler
+// Purpose: Show executions and outbound webhook deliveries that ran out of retries,
+// with replay/redelivery actions
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::Html,
+};
+use chrono::{DateTime, Utc};
+use common::db::repositories::{WebhookDeliveryFilter, WebhookDeliveryRepository};
+use common::models::WebhookDeliveryStatus;
+use tera::Context;
+use uuid::Uuid;
+
+use super::shared_utils::{calculate_pagination, db_error, setup_htmx_context};
+use super::ExecutionQueryParams;
+use crate::handlers::ErrorResponse;
+use crate::state::AppState;
+use crate::templates::TEMPLATES;
+
+/// Dead-letter webhook deliveries shown below the executions
+const DEAD_LETTER_DELIVERIES_LIMIT: i64 = 50;
+
+/// Dead letter queue partial (HTMX)
+#[tracing::instrument(skip(state, headers))]
+pub async fn dlq_partial(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<ExecutionQueryParams>,
+) -> Result<Html<String>, ErrorResponse> {
+    let mut context = Context::new();
+    context.insert("active_page", "dlq");
+
+    let limit = params.limit.unwrap_or(20);
+    let offset = params.offset.unwrap_or(0);
+
+    let total_count: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM job_executions WHERE status = 'dead_letter'")
+            .fetch_one(state.db_pool.pool())
+            .await
+            .unwrap_or(0);
+
+    let (page, total_pages) = calculate_pagination(offset, limit, total_count);
+
+    let rows = sqlx::query(
+        r#"
+        SELECT
+            je.id, je.job_id, je.trigger_source, je.attempt, je.error,
+            je.completed_at, je.created_at, je.dependency, je.replayed_at,
+            j.name as job_name
+        FROM job_executions je
+        LEFT JOIN jobs j ON je.job_id = j.id
+        WHERE je.status = 'dead_letter'
+        ORDER BY je.created_at DESC
+        LIMIT $1 OFFSET $2
+        "#,
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(state.db_pool.pool())
+    .await
+    .map_err(db_error)?;
+
+    let executions: Vec<serde_json::Value> = rows
+        .iter()
+        .map(|row| {
+            use sqlx::Row;
+            let id: Uuid = row.get("id");
+            let job_id: Uuid = row.get("job_id");
+            let trigger_source: String = row.get("trigger_source");
+            let attempt: i32 = row.get("attempt");
+            let error: Option<String> = row.get("error");
+            let completed_at: Option<DateTime<Utc>> = row.get("completed_at");
+            let created_at: DateTime<Utc> = row.get("created_at");
+            let job_name: Option<String> = row.get("job_name");
+            let dependency: Option<String> = row.get("dependency");
+            let replayed_at: Option<DateTime<Utc>> = row.get("replayed_at");
+
+            serde_json::json!({
+                "id": id.to_string(),
+                "job_id": job_id.to_string(),
+                "job_name": job_name,
+                "trigger_source": trigger_source,
+                "attempt": attempt,
+                "error": error,
+                "completed_at": completed_at.map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+                "created_at": created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                "dependency": dependency,
+                "replayed_at": replayed_at.map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+            })
+        })
+        .collect();
+
+    let deliveries = WebhookDeliveryRepository::new(state.db_pool.clone())
+        .list(&WebhookDeliveryFilter {
+            status: Some(WebhookDeliveryStatus::DeadLetter),
+            limit: DEAD_LETTER_DELIVERIES_LIMIT,
+            ..Default::default()
+        })
+        .await
+        .map_err(db_error)?;
+
+    let deliveries: Vec<serde_json::Value> = deliveries
+        .iter()
+        .map(|delivery| {
+            serde_json::json!({
+                "id": delivery.id.to_string(),
+                "endpoint_url": delivery.endpoint_url,
+                "event": delivery.event,
+                "job_id": delivery.job_id.map(|id| id.to_string()),
+                "attempts": delivery.attempts,
+                "last_response_status": delivery.last_response_status,
+                "last_error": delivery.last_error,
+                "created_at": delivery.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            })
+        })
+        .collect();
+
+    context.insert("executions", &executions);
+    context.insert("deliveries", &deliveries);
+    context.insert("limit", &limit);
+    context.insert("offset", &offset);
+    context.insert("page", &page);
+    context.insert("total_pages", &total_pages);
+    context.insert("total_count", &total_count);
+
+    let template = setup_htmx_context(&mut context, &headers, "_dlq_content.html", "dlq.html");
+
+    let html = TEMPLATES.render(template, &context).map_err(|e| {
+        tracing::error!(error = %e, "Template rendering failed");
+        ErrorResponse::new("template_error", format!("Failed to render '{}'", template))
+    })?;
+
+    Ok(Html(html))
+}
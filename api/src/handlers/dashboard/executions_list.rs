@@ -7,11 +7,14 @@ use axum::{
     response::Html,
 };
 use chrono::{DateTime, Utc};
+use common::execution_filter::{ExecutionFilterCriteria, SavedFilter};
+use common::models::{heartbeat_stale, ExecutionStatus};
 use tera::Context;
 use uuid::Uuid;
 
 use super::shared_utils::{calculate_pagination, db_error, setup_htmx_context};
 use super::ExecutionQueryParams;
+use crate::handlers::execution_filters::resolve_criteria;
 use crate::handlers::ErrorResponse;
 use crate::state::AppState;
 use crate::templates::TEMPLATES;
@@ -29,6 +32,9 @@ pub async fn executions_partial(
     let limit = params.limit.unwrap_or(20);
     let offset = params.offset.unwrap_or(0);
 
+    let (criteria, saved_filter) = dashboard_criteria(&state, &params).await?;
+    let conditions = filter_conditions(&criteria);
+
     // Build count query with filters
     let mut count_query = String::from(
         r#"
@@ -38,34 +44,7 @@ pub async fn executions_partial(
         WHERE je.created_at >= NOW() - INTERVAL '30 days'
         "#,
     );
-
-    if let Some(job_id) = params.job_id {
-        count_query.push_str(&format!(" AND je.job_id = '{}'", job_id));
-    }
-
-    if let Some(status) = &params.status {
-        if !status.is_empty() {
-            count_query.push_str(&format!(" AND je.status = '{}'", status.to_lowercase()));
-        }
-    }
-
-    if let Some(trigger_source) = &params.trigger_source {
-        if !trigger_source.is_empty() {
-            count_query.push_str(&format!(
-                " AND je.trigger_source = '{}'",
-                trigger_source.to_lowercase()
-            ));
-        }
-    }
-
-    if let Some(job_name) = &params.job_name {
-        if !job_name.is_empty() {
-            count_query.push_str(&format!(
-                " AND j.name ILIKE '%{}%'",
-                job_name.replace("'", "''")
-            ));
-        }
-    }
+    count_query.push_str(&conditions);
 
     let total_count: i64 = sqlx::query_scalar(&count_query)
         .fetch_one(state.db_pool.pool())
@@ -79,40 +58,15 @@ pub async fn executions_partial(
         r#"
         SELECT 
             je.id, je.job_id, je.status, je.trigger_source, je.attempt,
-            je.started_at, je.completed_at, je.created_at, j.name as job_name
+            je.started_at, je.completed_at, je.created_at, je.heartbeat_at, je.progress,
+            j.name as job_name
         FROM job_executions je
         LEFT JOIN jobs j ON je.job_id = j.id
         WHERE je.created_at >= NOW() - INTERVAL '30 days'
         "#,
     );
 
-    if let Some(job_id) = params.job_id {
-        query.push_str(&format!(" AND je.job_id = '{}'", job_id));
-    }
-
-    if let Some(status) = &params.status {
-        if !status.is_empty() {
-            query.push_str(&format!(" AND je.status = '{}'", status.to_lowercase()));
-        }
-    }
-
-    if let Some(trigger_source) = &params.trigger_source {
-        if !trigger_source.is_empty() {
-            query.push_str(&format!(
-                " AND je.trigger_source = '{}'",
-                trigger_source.to_lowercase()
-            ));
-        }
-    }
-
-    if let Some(job_name) = &params.job_name {
-        if !job_name.is_empty() {
-            query.push_str(&format!(
-                " AND j.name ILIKE '%{}%'",
-                job_name.replace("'", "''")
-            ));
-        }
-    }
+    query.push_str(&conditions);
 
     query.push_str(" ORDER BY je.created_at DESC");
     query.push_str(&format!(" LIMIT {} OFFSET {}", limit, offset));
@@ -123,6 +77,10 @@ pub async fn executions_partial(
         .await
         .map_err(db_error)?;
 
+    // Running executions that stopped sending heartbeats are shown as hung
+    let now = Utc::now();
+    let heartbeat_interval = state.config.worker.heartbeat_interval_seconds;
+
     // Map rows to JSON for template
     let executions: Vec<serde_json::Value> = rows
         .iter()
@@ -136,6 +94,8 @@ pub async fn executions_partial(
             let started_at: Option<DateTime<Utc>> = row.get("started_at");
             let completed_at: Option<DateTime<Utc>> = row.get("completed_at");
             let created_at: DateTime<Utc> = row.get("created_at");
+            let heartbeat_at: Option<DateTime<Utc>> = row.get("heartbeat_at");
+            let progress: Option<serde_json::Value> = row.get("progress");
             let job_name: Option<String> = row.get("job_name");
 
             // Calculate duration in seconds if both timestamps exist
@@ -145,6 +105,9 @@ pub async fn executions_partial(
                 None
             };
 
+            let hung = status == "running"
+                && heartbeat_stale(heartbeat_at, started_at, now, heartbeat_interval);
+
             serde_json::json!({
                 "id": id.to_string(),
                 "job_id": job_id.to_string(),
@@ -156,6 +119,9 @@ pub async fn executions_partial(
                 "created_at": created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
                 "job_name": job_name,
                 "duration_seconds": duration_seconds,
+                "heartbeat_at": heartbeat_at.map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
+                "progress": progress,
+                "hung": hung,
             })
         })
         .collect();
@@ -166,19 +132,28 @@ pub async fn executions_partial(
     context.insert("page", &page);
     context.insert("total_pages", &total_pages);
     context.insert("total_count", &total_count);
-    context.insert("status_filter", &params.status.clone().unwrap_or_default());
+    context.insert(
+        "status_filter",
+        &criteria
+            .status
+            .as_ref()
+            .map(|s| s.to_string())
+            .unwrap_or_default(),
+    );
     context.insert(
         "trigger_source_filter",
-        &params.trigger_source.clone().unwrap_or_default(),
+        &criteria.trigger_source.clone().unwrap_or_default(),
     );
     context.insert(
         "job_name_filter",
-        &params.job_name.clone().unwrap_or_default(),
+        &criteria.job_name.clone().unwrap_or_default(),
     );
     context.insert(
         "job_id_filter",
-        &params.job_id.map(|id| id.to_string()).unwrap_or_default(),
+        &criteria.job_id.map(|id| id.to_string()).unwrap_or_default(),
     );
+    context.insert("tag_filter", &criteria.tag.clone().unwrap_or_default());
+    context.insert("saved_filter", &saved_filter);
 
     // Check if this is embedded in job details (has job_id filter)
     let is_embedded = params.job_id.is_some();
@@ -202,3 +177,78 @@ pub async fn executions_partial(
 
     Ok(Html(html))
 }
+
+/// Criteria of the executions table: the query parameters over the saved filter
+pub(super) async fn dashboard_criteria(
+    state: &AppState,
+    params: &ExecutionQueryParams,
+) -> Result<(ExecutionFilterCriteria, Option<SavedFilter>), ErrorResponse> {
+    let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.is_empty());
+
+    let status = match non_empty(&params.status) {
+        Some(status) => match status.to_lowercase().parse::<ExecutionStatus>() {
+            Ok(status) => Some(status),
+            Err(_) => {
+                return Err(ErrorResponse::localized_with(
+                    "validation_error",
+                    "execution.invalid_status",
+                    &[("status", &status)],
+                ));
+            }
+        },
+        None => None,
+    };
+
+    resolve_criteria(
+        state,
+        params.filter_id,
+        ExecutionFilterCriteria {
+            status,
+            job_id: params.job_id,
+            job_name: non_empty(&params.job_name),
+            trigger_source: non_empty(&params.trigger_source).map(|s| s.to_lowercase()),
+            tag: non_empty(&params.tag),
+            from: None,
+            to: None,
+        },
+    )
+    .await
+}
+
+/// SQL conditions for the criteria; free-text values are quoted, the others are
+/// validated or typed
+pub(super) fn filter_conditions(criteria: &ExecutionFilterCriteria) -> String {
+    let quote = |value: &str| value.replace('\'', "''");
+    let mut conditions = String::new();
+
+    if let Some(job_id) = criteria.job_id {
+        conditions.push_str(&format!(" AND je.job_id = '{}'", job_id));
+    }
+    if let Some(status) = &criteria.status {
+        conditions.push_str(&format!(" AND je.status = '{}'", status));
+    }
+    if let Some(trigger_source) = &criteria.trigger_source {
+        conditions.push_str(&format!(
+            " AND je.trigger_source = '{}'",
+            quote(trigger_source)
+        ));
+    }
+    if let Some(job_name) = &criteria.job_name {
+        conditions.push_str(&format!(" AND j.name ILIKE '%{}%'", quote(job_name)));
+    }
+    if let Some((name, value)) = criteria.tag_label() {
+        conditions.push_str(&format!(
+            " AND j.metric_labels ->> '{}' = '{}'",
+            quote(name),
+            quote(value)
+        ));
+    }
+    if let Some(from) = criteria.from {
+        conditions.push_str(&format!(" AND je.created_at >= '{}'", from.to_rfc3339()));
+    }
+    if let Some(to) = criteria.to {
+        conditions.push_str(&format!(" AND je.created_at < '{}'", to.to_rfc3339()));
+    }
+
+    conditions
+}
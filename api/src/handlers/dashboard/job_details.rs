@@ -7,6 +7,8 @@ use axum::{
     response::Html,
 };
 use common::db::repositories::job::JobStats;
+use common::db::repositories::BlackoutWindowRepository;
+use common::models::BlackoutWindow;
 use tera::Context;
 use uuid::Uuid;
 
@@ -28,11 +30,17 @@ fn prepare_job_data(
             common::models::Schedule::Cron {
                 expression,
                 timezone,
+                valid_for_seconds,
+                calendar,
+                misfire_policy,
                 ..
             } => {
                 serde_json::json!({
                     "expression": expression,
-                    "timezone": timezone.to_string()
+                    "timezone": timezone.to_string(),
+                    "valid_for_seconds": valid_for_seconds,
+                    "calendar": calendar,
+                    "misfire_policy": misfire_policy.unwrap_or_default()
                 })
             }
             common::models::Schedule::FixedDelay { delay_seconds } => {
@@ -40,14 +48,35 @@ fn prepare_job_data(
                     "delay_seconds": delay_seconds
                 })
             }
-            common::models::Schedule::FixedRate { interval_seconds } => {
+            common::models::Schedule::FixedRate {
+                interval_seconds,
+                misfire_policy,
+            } => {
                 serde_json::json!({
-                    "interval_seconds": interval_seconds
+                    "interval_seconds": interval_seconds,
+                    "misfire_policy": misfire_policy.unwrap_or_default()
                 })
             }
-            common::models::Schedule::OneTime { execute_at } => {
+            common::models::Schedule::OneTime {
+                execute_at,
+                valid_for_seconds,
+                misfire_policy,
+            } => {
+                serde_json::json!({
+                    "execute_at": execute_at.to_rfc3339(),
+                    "valid_for_seconds": valid_for_seconds,
+                    "misfire_policy": misfire_policy.unwrap_or_default()
+                })
+            }
+            common::models::Schedule::Iso8601 {
+                expression,
+                valid_for_seconds,
+                misfire_policy,
+            } => {
                 serde_json::json!({
-                    "execute_at": execute_at.to_rfc3339()
+                    "expression": expression,
+                    "valid_for_seconds": valid_for_seconds,
+                    "misfire_policy": misfire_policy.unwrap_or_default()
                 })
             }
         })
@@ -60,8 +89,22 @@ fn prepare_job_data(
                 let step_type = match &step.step_type {
                     common::models::JobType::HttpRequest { .. } => "HttpRequest",
                     common::models::JobType::DatabaseQuery { .. } => "DatabaseQuery",
-                    common::models::JobType::Sftp { .. } => "SftpOperation",
+                    common::models::JobType::Sftp { .. } => "SFTP",
+                    common::models::JobType::GraphQL { .. } => "GraphQL",
+                    common::models::JobType::Email { .. } => "Email",
+                    common::models::JobType::Notification { .. } => "Notification",
+                    common::models::JobType::MqttPublish { .. } => "MQTT",
+                    common::models::JobType::ObjectStorage { .. } => "Object Storage",
+                    common::models::JobType::Container { .. } => "Container",
+                    common::models::JobType::Wasm { .. } => "WASM",
+                    common::models::JobType::Script { .. } => "Script",
+                    common::models::JobType::PdfReport { .. } => "PDF Report",
+                    common::models::JobType::Transform { .. } => "Transform",
+                    common::models::JobType::Validate { .. } => "Validate",
+                    common::models::JobType::Checksum { .. } => "Checksum",
+                    common::models::JobType::IncrementalSync { .. } => "IncrementalSync",
                     common::models::JobType::FileProcessing { .. } => "FileProcessing",
+                    common::models::JobType::System { .. } => "System",
                 };
                 serde_json::json!({
                     "name": step.name,
@@ -76,11 +119,14 @@ fn prepare_job_data(
         "name": job.name,
         "description": job.description,
         "enabled": job.enabled,
+        "paused": job.paused,
         "schedule_type": schedule_type,
         "schedule_config": schedule_config,
         "timeout_seconds": job.timeout_seconds,
         "max_retries": job.max_retries,
         "allow_concurrent": job.allow_concurrent,
+        "version": job.version,
+        "owner": job.owner,
         "created_at": job.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
         "updated_at": job.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
         "total_executions": stats.map(|s| s.total_executions).unwrap_or(0),
@@ -91,6 +137,23 @@ fn prepare_job_data(
     })
 }
 
+/// Prepare a job's blackout windows for template rendering
+fn prepare_blackout_windows(windows: &[BlackoutWindow]) -> Vec<serde_json::Value> {
+    windows
+        .iter()
+        .map(|window| {
+            serde_json::json!({
+                "id": window.id.to_string(),
+                "name": window.name,
+                "starts_at": window.starts_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                "ends_at": window.ends_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                "behavior": window.behavior.to_string(),
+                "created_by": window.created_by,
+            })
+        })
+        .collect()
+}
+
 /// Job details modal content (HTMX)
 #[tracing::instrument(skip(state))]
 pub async fn job_details_modal(
@@ -167,6 +230,15 @@ pub async fn job_details_partial(
     let job_data = prepare_job_data(&job, full_job.as_ref(), stats.as_ref());
     context.insert("job", &job_data);
 
+    let blackout_windows = BlackoutWindowRepository::new(state.db_pool.clone())
+        .find_by_job_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", format!("Database error: {}", e)))?;
+    context.insert(
+        "blackout_windows",
+        &prepare_blackout_windows(&blackout_windows),
+    );
+
     // If HTMX request, return only the content partial
     // Otherwise, return the full page with layout
     let template = if is_htmx {
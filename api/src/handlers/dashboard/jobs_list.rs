@@ -6,6 +6,10 @@ use axum::{
     http::HeaderMap,
     response::Html,
 };
+use chrono::{DateTime, Utc};
+use common::db::repositories::JobRepository;
+use common::models::Job;
+use serde::Serialize;
 use tera::Context;
 
 use super::shared_utils::{
@@ -31,7 +35,7 @@ pub async fn jobs_partial(
     let offset = params.offset.unwrap_or(0);
 
     // Fetch jobs with pagination
-    let job_repo = common::db::repositories::JobRepository::new(state.db_pool.clone());
+    let job_repo = JobRepository::new(state.db_pool.clone());
     let all_jobs = job_repo.find_all().await.unwrap_or_default();
 
     // Apply pagination
@@ -40,45 +44,10 @@ pub async fn jobs_partial(
     let end = std::cmp::min(start + limit as usize, all_jobs.len());
     let paginated_jobs = &all_jobs[start..end];
 
-    // Convert jobs to JSON for template with full details
-    let mut jobs_json: Vec<serde_json::Value> = Vec::new();
-
+    // Convert jobs to rows for template with full details
+    let mut jobs_json: Vec<JobRow> = Vec::new();
     for job in paginated_jobs {
-        // Load full job definition from storage (Redis cache → PostgreSQL) using shared utility
-        let (schedule_type, next_run_time, job_type) = if let Some(full_job) =
-            load_job_from_storage(state.storage_service.as_ref(), job.id).await
-        {
-            let sched_type = get_schedule_type_str(&full_job.schedule);
-            let next_run = get_next_run_display(&full_job.schedule, job.enabled);
-            let jtype = get_job_type_str(&full_job.steps);
-            (sched_type, next_run, jtype)
-        } else {
-            (None, None, None)
-        };
-
-        // Get job statistics
-        let stats = job_repo.get_stats(job.id).await.ok().flatten();
-        let last_exec = stats.as_ref().and_then(|s| s.last_execution_at);
-        let total_execs = stats.as_ref().map(|s| s.total_executions).unwrap_or(0);
-        let success_execs = stats.as_ref().map(|s| s.successful_executions).unwrap_or(0);
-
-        jobs_json.push(serde_json::json!({
-            "id": job.id.to_string(),
-            "name": job.name,
-            "description": job.description,
-            "enabled": job.enabled,
-            "schedule_type": schedule_type,
-            "next_run_time": next_run_time,
-            "job_type": job_type,
-            "last_execution_at": last_exec.map(|dt| dt.format("%Y-%m-%d %H:%M:%S").to_string()),
-            "total_executions": total_execs,
-            "successful_executions": success_execs,
-            "timeout_seconds": job.timeout_seconds,
-            "max_retries": job.max_retries,
-            "allow_concurrent": job.allow_concurrent,
-            "created_at": job.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-            "updated_at": job.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        }));
+        jobs_json.push(job_row(&state, &job_repo, job).await);
     }
 
     // Calculate pagination using shared utility
@@ -101,3 +70,76 @@ pub async fn jobs_partial(
 
     Ok(Html(html))
 }
+
+/// One row of the jobs table, also used for its CSV export
+#[derive(Debug, Serialize)]
+pub(super) struct JobRow {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub enabled: bool,
+    pub paused: bool,
+    pub schedule_type: Option<&'static str>,
+    pub next_run_time: Option<String>,
+    pub job_type: Option<&'static str>,
+    pub last_execution_at: Option<String>,
+    /// `success` or `failed`, from the job's stats
+    pub last_status: Option<&'static str>,
+    pub total_executions: i64,
+    pub successful_executions: i64,
+    pub timeout_seconds: i32,
+    pub max_retries: i32,
+    pub allow_concurrent: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Build the table row of a job
+pub(super) async fn job_row(state: &AppState, job_repo: &JobRepository, job: &Job) -> JobRow {
+    // Load full job definition from storage (Redis cache → PostgreSQL) using shared utility
+    let (schedule_type, next_run_time, job_type) = if let Some(full_job) =
+        load_job_from_storage(state.storage_service.as_ref(), job.id).await
+    {
+        let sched_type = get_schedule_type_str(&full_job.schedule);
+        let next_run = get_next_run_display(&full_job.schedule, job.enabled && !job.paused);
+        let jtype = get_job_type_str(&full_job.steps);
+        (sched_type, next_run, jtype)
+    } else {
+        (None, None, None)
+    };
+
+    // Get job statistics
+    let stats = job_repo.get_stats(job.id).await.ok().flatten();
+    let last_exec = stats.as_ref().and_then(|s| s.last_execution_at);
+    let last_status = stats.as_ref().and_then(|s| {
+        let last = s.last_execution_at?;
+        if s.last_success_at == Some(last) {
+            Some("success")
+        } else if s.last_failure_at == Some(last) {
+            Some("failed")
+        } else {
+            None
+        }
+    });
+    let format = |dt: DateTime<Utc>| dt.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    JobRow {
+        id: job.id.to_string(),
+        name: job.name.clone(),
+        description: job.description.clone(),
+        enabled: job.enabled,
+        paused: job.paused,
+        schedule_type,
+        next_run_time,
+        job_type,
+        last_execution_at: last_exec.map(format),
+        last_status,
+        total_executions: stats.as_ref().map(|s| s.total_executions).unwrap_or(0),
+        successful_executions: stats.as_ref().map(|s| s.successful_executions).unwrap_or(0),
+        timeout_seconds: job.timeout_seconds,
+        max_retries: job.max_retries,
+        allow_concurrent: job.allow_concurrent,
+        created_at: format(job.created_at),
+        updated_at: format(job.updated_at),
+    }
+}
@@ -1,20 +1,26 @@
 // Dashboard handlers module
 // Tách theo RECC 2025 rules - File naming & module organization
 
+mod dlq_list;
 mod executions_list;
 mod job_details;
 mod job_form;
 mod jobs_list;
 mod shared_utils;
 mod stats;
+mod table_export;
 mod variables_list;
+mod workers_list;
 
+pub use dlq_list::dlq_partial;
 pub use executions_list::executions_partial;
 pub use job_details::{job_details_modal, job_details_partial};
 pub use job_form::job_form_page;
 pub use jobs_list::jobs_partial;
 pub use stats::dashboard_index;
+pub use table_export::{executions_csv, jobs_csv};
 pub use variables_list::variables_partial;
+pub use workers_list::workers_partial;
 
 // Re-export shared utilities for use within dashboard module
 
@@ -28,6 +34,10 @@ pub struct ExecutionQueryParams {
     pub status: Option<String>,
     pub trigger_source: Option<String>,
     pub job_name: Option<String>,
+    /// Job metric label, as `name=value`
+    pub tag: Option<String>,
+    /// Saved filter supplying the criteria not given above
+    pub filter_id: Option<Uuid>,
     pub offset: Option<i64>,
     pub limit: Option<i64>,
 }
@@ -55,6 +55,7 @@ pub fn get_schedule_type_str(schedule: &Option<Schedule>) -> Option<&'static str
         Schedule::FixedDelay { .. } => "FixedDelay",
         Schedule::FixedRate { .. } => "FixedRate",
         Schedule::OneTime { .. } => "OneTime",
+        Schedule::Iso8601 { .. } => "Iso8601",
     })
 }
 
@@ -70,10 +71,13 @@ pub fn get_next_run_display(schedule: &Option<Schedule>, enabled: bool) -> Optio
     schedule.as_ref().and_then(|s| match s {
         Schedule::Cron { .. } => Some("Scheduled".to_string()),
         Schedule::FixedDelay { delay_seconds } => Some(format!("Every {}s", delay_seconds)),
-        Schedule::FixedRate { interval_seconds } => Some(format!("Every {}s", interval_seconds)),
-        Schedule::OneTime { execute_at } => {
+        Schedule::FixedRate {
+            interval_seconds, ..
+        } => Some(format!("Every {}s", interval_seconds)),
+        Schedule::OneTime { execute_at, .. } => {
             Some(execute_at.format("%Y-%m-%d %H:%M:%S").to_string())
         }
+        Schedule::Iso8601 { expression, .. } => Some(expression.clone()),
     })
 }
 
@@ -85,7 +89,21 @@ pub fn get_job_type_str(steps: &[JobStep]) -> Option<&'static str> {
         JobType::HttpRequest { .. } => "HTTP",
         JobType::DatabaseQuery { .. } => "Database",
         JobType::Sftp { .. } => "SFTP",
+        JobType::GraphQL { .. } => "GraphQL",
+        JobType::Email { .. } => "Email",
+        JobType::Notification { .. } => "Notification",
+        JobType::MqttPublish { .. } => "MQTT",
+        JobType::ObjectStorage { .. } => "Object Storage",
+        JobType::Container { .. } => "Container",
+        JobType::Wasm { .. } => "WASM",
+        JobType::Script { .. } => "Script",
+        JobType::PdfReport { .. } => "PDF Report",
+        JobType::Transform { .. } => "Transform",
+        JobType::Validate { .. } => "Validate",
+        JobType::Checksum { .. } => "Checksum",
+        JobType::IncrementalSync { .. } => "Incremental Sync",
         JobType::FileProcessing { .. } => "File",
+        JobType::System { .. } => "System",
     })
 }
 
@@ -1,10 +1,14 @@
 // Dashboard statistics page handler
 // Requirements: 6.1 - Display dashboard with job statistics
 
-use axum::{extract::State, http::HeaderMap, response::Html};
+use axum::{extract::State, http::HeaderMap, response::Html, Extension};
 use chrono::{Duration, Utc};
 use tera::Context;
 
+use common::db::repositories::StatsRepository;
+use common::models::UserClaims;
+
+use crate::handlers::dashboard_layout::load_layout;
 use crate::handlers::ErrorResponse;
 use crate::state::AppState;
 use crate::templates::TEMPLATES;
@@ -13,6 +17,7 @@ use crate::templates::TEMPLATES;
 #[tracing::instrument(skip(state, headers))]
 pub async fn dashboard_index(
     State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
     headers: HeaderMap,
 ) -> Result<Html<String>, ErrorResponse> {
     let mut context = Context::new();
@@ -38,36 +43,22 @@ pub async fn dashboard_index(
             .await
             .unwrap_or(0);
 
-    // Get 24h statistics
+    // Get 24h and 30-day statistics from the rollup tables
+    let stats_repo = StatsRepository::new(state.db_pool.clone());
     let twenty_four_hours_ago = Utc::now() - Duration::hours(24);
-    let total_executions_24h: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM job_executions WHERE created_at >= $1")
-            .bind(twenty_four_hours_ago)
-            .fetch_one(state.db_pool.pool())
-            .await
-            .unwrap_or(0);
+    let totals_24h = stats_repo
+        .hourly_totals_since(twenty_four_hours_ago)
+        .await
+        .unwrap_or_default();
+    let totals_30d = stats_repo
+        .daily_totals_since(Utc::now() - Duration::days(30))
+        .await
+        .unwrap_or_default();
 
-    let successful_executions_24h: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM job_executions WHERE status = 'success' AND created_at >= $1",
-    )
-    .bind(twenty_four_hours_ago)
-    .fetch_one(state.db_pool.pool())
-    .await
-    .unwrap_or(0);
-
-    let failed_executions_24h: i64 = sqlx::query_scalar(
-        "SELECT COUNT(*) FROM job_executions WHERE status IN ('failed', 'timeout', 'dead_letter') AND created_at >= $1",
-    )
-    .bind(twenty_four_hours_ago)
-    .fetch_one(state.db_pool.pool())
-    .await
-    .unwrap_or(0);
-
-    let success_rate = if total_executions_24h > 0 {
-        (successful_executions_24h as f64 / total_executions_24h as f64) * 100.0
-    } else {
-        0.0
-    };
+    let total_executions_24h = totals_24h.total_executions;
+    let successful_executions_24h = totals_24h.successful_executions;
+    let failed_executions_24h = totals_24h.failed_executions;
+    let success_rate = totals_24h.success_rate().unwrap_or(0.0);
 
     let mut stats = tera::Map::new();
     stats.insert(
@@ -98,15 +89,26 @@ pub async fn dashboard_index(
         "success_rate".to_string(),
         tera::to_value(success_rate).unwrap(),
     );
+    stats.insert(
+        "success_rate_30d".to_string(),
+        tera::to_value(totals_30d.success_rate()).unwrap(),
+    );
+    stats.insert(
+        "avg_duration_seconds_30d".to_string(),
+        tera::to_value(
+            totals_30d
+                .average_duration_ms()
+                .map(|ms| ms as f64 / 1000.0),
+        )
+        .unwrap(),
+    );
     context.insert("stats", &stats);
 
     // Get recent executions
     let execution_repo = common::db::repositories::ExecutionRepository::new(state.db_pool.clone());
     let filter = common::db::repositories::ExecutionFilter {
-        job_id: None,
-        status: None,
-        trigger_source: None,
         limit: Some(10),
+        ..Default::default()
     };
     let recent_executions = execution_repo
         .find_with_filter(filter)
@@ -132,6 +134,23 @@ pub async fn dashboard_index(
         .collect();
     context.insert("active_jobs", &active_jobs);
 
+    // What this user chose to see on the landing page
+    let layout = load_layout(&state, &claims.sub).await?;
+    let pinned_jobs: Vec<serde_json::Value> = layout
+        .pinned_jobs
+        .iter()
+        .filter_map(|id| all_jobs.iter().find(|job| job.id == *id))
+        .map(|job| {
+            serde_json::json!({
+                "id": job.id,
+                "name": job.name,
+                "enabled": job.enabled,
+            })
+        })
+        .collect();
+    context.insert("layout", &layout);
+    context.insert("pinned_jobs", &pinned_jobs);
+
     // If HTMX request, return only the content partial
     // Otherwise, return the full page with layout
     let template = if is_htmx {
@@ -0,0 +1,199 @@
+// Dashboard table export handlers
+// Purpose: "Export what I see" - stream the jobs and executions tables as CSV with the
+// same filters as the pages, for spreadsheet extracts
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use common::db::repositories::JobRepository;
+use common::models::Job;
+use serde::Serialize;
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::executions_list::{dashboard_criteria, filter_conditions};
+use super::jobs_list::job_row;
+use super::shared_utils::db_error;
+use super::ExecutionQueryParams;
+use crate::handlers::ErrorResponse;
+use crate::state::AppState;
+
+/// Jobs loaded from storage per CSV chunk
+const JOBS_PER_CHUNK: usize = 100;
+
+/// Executions fetched from the database per CSV chunk
+const EXECUTIONS_PER_CHUNK: i64 = 1000;
+
+/// One row of the executions CSV, matching the executions table columns
+#[derive(Debug, Serialize)]
+struct ExecutionCsvRow {
+    id: Uuid,
+    job_id: Uuid,
+    job_name: Option<String>,
+    status: String,
+    trigger_source: String,
+    attempt: i32,
+    started_at: Option<String>,
+    completed_at: Option<String>,
+    duration_seconds: Option<i64>,
+    created_at: DateTime<Utc>,
+}
+
+/// Export the jobs table (every page) as CSV
+#[tracing::instrument(skip(state))]
+pub async fn jobs_csv(State(state): State<AppState>) -> Result<Response, ErrorResponse> {
+    let job_repo = JobRepository::new(state.db_pool.clone());
+    let jobs = job_repo.find_all().await.map_err(db_error)?;
+
+    let stream = futures::stream::unfold(
+        (jobs.into_iter(), 0usize),
+        move |(mut jobs, rows_before)| {
+            let state = state.clone();
+            async move {
+                let job_repo = JobRepository::new(state.db_pool.clone());
+                let chunk: Vec<Job> = jobs.by_ref().take(JOBS_PER_CHUNK).collect();
+                if chunk.is_empty() {
+                    return None;
+                }
+
+                let mut rows = Vec::with_capacity(chunk.len());
+                for job in &chunk {
+                    rows.push(job_row(&state, &job_repo, job).await);
+                }
+                let encoded = encode_csv(&rows, rows_before == 0);
+                Some((encoded, (jobs, rows_before + rows.len())))
+            }
+        },
+    );
+
+    Ok(csv_response("jobs", Body::from_stream(stream)))
+}
+
+/// Export the executions table, with the page's filters, as CSV
+#[tracing::instrument(skip(state))]
+pub async fn executions_csv(
+    State(state): State<AppState>,
+    Query(params): Query<ExecutionQueryParams>,
+) -> Result<Response, ErrorResponse> {
+    let (criteria, _) = dashboard_criteria(&state, &params).await?;
+    let conditions = filter_conditions(&criteria);
+
+    // Pages are keyed on (created_at, id) so rows inserted meanwhile don't shift them
+    let stream = futures::stream::unfold(
+        (None::<(DateTime<Utc>, Uuid)>, 0usize, false),
+        move |(after, rows_before, done)| {
+            let state = state.clone();
+            let conditions = conditions.clone();
+            async move {
+                if done {
+                    return None;
+                }
+
+                let rows = match fetch_executions(&state, &conditions, after).await {
+                    Ok(rows) => rows,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Execution CSV export failed");
+                        return Some((Err(std::io::Error::other(e.to_string())), (None, 0, true)));
+                    }
+                };
+
+                let is_last = (rows.len() as i64) < EXECUTIONS_PER_CHUNK;
+                let next = rows.last().map(|row| (row.created_at, row.id));
+                let encoded = encode_csv(&rows, rows_before == 0);
+                Some((encoded, (next, rows_before + rows.len(), is_last)))
+            }
+        },
+    );
+
+    Ok(csv_response("executions", Body::from_stream(stream)))
+}
+
+/// One page of executions matching `conditions`, newest first
+async fn fetch_executions(
+    state: &AppState,
+    conditions: &str,
+    after: Option<(DateTime<Utc>, Uuid)>,
+) -> Result<Vec<ExecutionCsvRow>, sqlx::Error> {
+    let mut query = String::from(
+        r#"
+        SELECT
+            je.id, je.job_id, je.status, je.trigger_source, je.attempt,
+            je.started_at, je.completed_at, je.created_at,
+            j.name as job_name
+        FROM job_executions je
+        LEFT JOIN jobs j ON je.job_id = j.id
+        WHERE je.created_at >= NOW() - INTERVAL '30 days'
+        "#,
+    );
+    query.push_str(conditions);
+    if after.is_some() {
+        query.push_str(" AND (je.created_at, je.id) < ($1, $2)");
+    }
+    query.push_str(&format!(
+        " ORDER BY je.created_at DESC, je.id DESC LIMIT {}",
+        EXECUTIONS_PER_CHUNK
+    ));
+
+    let mut sql = sqlx::query(&query);
+    if let Some((created_at, id)) = after {
+        sql = sql.bind(created_at).bind(id);
+    }
+    let rows = sql.fetch_all(state.db_pool.pool()).await?;
+
+    let format = |dt: DateTime<Utc>| dt.format("%Y-%m-%d %H:%M:%S").to_string();
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let started_at: Option<DateTime<Utc>> = row.get("started_at");
+            let completed_at: Option<DateTime<Utc>> = row.get("completed_at");
+            ExecutionCsvRow {
+                id: row.get("id"),
+                job_id: row.get("job_id"),
+                job_name: row.get("job_name"),
+                status: row.get("status"),
+                trigger_source: row.get("trigger_source"),
+                attempt: row.get("attempt"),
+                started_at: started_at.map(format),
+                completed_at: completed_at.map(format),
+                duration_seconds: started_at
+                    .zip(completed_at)
+                    .map(|(start, end)| (end - start).num_seconds()),
+                created_at: row.get("created_at"),
+            }
+        })
+        .collect())
+}
+
+/// Encode rows as CSV; the header row, derived from the fields, goes on the first chunk only
+fn encode_csv<T: Serialize>(rows: &[T], with_header: bool) -> Result<Vec<u8>, std::io::Error> {
+    let mut chunk = Vec::new();
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(with_header)
+        .from_writer(&mut chunk);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    drop(writer);
+    Ok(chunk)
+}
+
+fn csv_response(table: &str, body: Body) -> Response {
+    let disposition = format!(
+        "attachment; filename=\"{}-{}.csv\"",
+        table,
+        Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response()
+}
@@ -0,0 +1,90 @@
+// Worker registry handler
+// Purpose: Show the workers reporting to the registry and the state of the circuit
+// breakers each of them keeps per target
+
+use axum::{extract::State, http::HeaderMap, response::Html};
+use chrono::Utc;
+use common::circuit_breaker::CircuitState;
+use common::worker::registry::WorkerRegistry;
+use tera::Context;
+
+use super::shared_utils::setup_htmx_context;
+use crate::handlers::ErrorResponse;
+use crate::state::AppState;
+use crate::templates::TEMPLATES;
+
+/// Workers partial (HTMX)
+#[tracing::instrument(skip(state, headers))]
+pub async fn workers_partial(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<Html<String>, ErrorResponse> {
+    let mut context = Context::new();
+    context.insert("active_page", "workers");
+
+    let workers = WorkerRegistry::new(state.redis.clone())
+        .workers()
+        .await
+        .map_err(|e| ErrorResponse::new("redis_error", e.to_string()))?;
+
+    let now = Utc::now();
+    let mut circuit_breakers = Vec::new();
+    let worker_rows: Vec<serde_json::Value> = workers
+        .iter()
+        .map(|worker| {
+            let stale = worker.is_stale(now);
+            let open_circuits = worker
+                .circuit_breakers
+                .iter()
+                .filter(|cb| cb.state != CircuitState::Closed)
+                .count();
+
+            // A stale worker's breakers say nothing about the targets any more
+            if !stale {
+                circuit_breakers.extend(worker.circuit_breakers.iter().map(|cb| {
+                    serde_json::json!({
+                        "target": cb.target,
+                        "state": cb.state,
+                        "failure_count": cb.failure_count,
+                        "instance": worker.instance,
+                    })
+                }));
+            }
+
+            serde_json::json!({
+                "instance": worker.instance,
+                "region": worker.region,
+                "stale": stale,
+                "running_executions": worker.running_executions,
+                "memory_mb": worker.memory_bytes.map(|bytes| bytes / (1024 * 1024)),
+                "open_circuits": open_circuits,
+                "started_at": worker.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+                "reported_at": worker.reported_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            })
+        })
+        .collect();
+
+    // Open and half-open circuits first
+    circuit_breakers.sort_by_key(|cb| (cb["state"] == "closed", cb["target"].to_string()));
+
+    context.insert("workers", &worker_rows);
+    context.insert("circuit_breakers", &circuit_breakers);
+    context.insert(
+        "live_count",
+        &worker_rows.iter().filter(|w| w["stale"] == false).count(),
+    );
+
+    let template = setup_htmx_context(
+        &mut context,
+        &headers,
+        "_workers_content.html",
+        "workers.html",
+    );
+
+    let html = TEMPLATES.render(template, &context).map_err(|e| {
+        tracing::error!(error = %e, "Template rendering failed");
+        ErrorResponse::new("template_error", format!("Failed to render '{}'", template))
+    })?;
+
+    Ok(Html(html))
+}
@@ -0,0 +1,100 @@
+use axum::{extract::State, Extension, Json};
+use common::dashboard_layout::DashboardLayout;
+use common::db::repositories::DashboardLayoutRepository;
+use common::models::UserClaims;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Get the caller's dashboard layout, or the default one if they haven't saved any
+#[tracing::instrument(skip(state, claims))]
+pub async fn get_dashboard_layout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<DashboardLayout>>, ErrorResponse> {
+    let layout = load_layout(&state, &claims.sub).await?;
+    Ok(Json(SuccessResponse::new(layout)))
+}
+
+/// Replace the caller's dashboard layout
+#[tracing::instrument(skip(state, claims, layout))]
+pub async fn update_dashboard_layout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(layout): Json<DashboardLayout>,
+) -> Result<Json<SuccessResponse<DashboardLayout>>, ErrorResponse> {
+    layout.validate().map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "dashboard.invalid_layout",
+            &[("reason", &e.to_string())],
+        )
+    })?;
+
+    // Pinning a job that doesn't exist is a typo, not a layout choice
+    let existing: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM jobs WHERE id = ANY($1)")
+        .bind(&layout.pinned_jobs)
+        .fetch_all(state.db_pool.pool())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check pinned jobs");
+            ErrorResponse::new("database_error", "Failed to check pinned jobs")
+        })?;
+    if let Some(missing) = layout.pinned_jobs.iter().find(|id| !existing.contains(id)) {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "dashboard.unknown_pinned_job",
+            &[("id", &missing.to_string())],
+        ));
+    }
+
+    DashboardLayoutRepository::new(state.db_pool.clone())
+        .upsert(&claims.sub, &layout)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save dashboard layout");
+            ErrorResponse::new("database_error", "Failed to save dashboard layout")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        pinned_jobs = layout.pinned_jobs.len(),
+        saved_filters = layout.saved_filters.len(),
+        "Dashboard layout saved"
+    );
+
+    Ok(Json(SuccessResponse::new(layout)))
+}
+
+/// Reset the caller's dashboard layout to the default one
+#[tracing::instrument(skip(state, claims))]
+pub async fn reset_dashboard_layout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<DashboardLayout>>, ErrorResponse> {
+    DashboardLayoutRepository::new(state.db_pool.clone())
+        .delete(&claims.sub)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to reset dashboard layout");
+            ErrorResponse::new("database_error", "Failed to reset dashboard layout")
+        })?;
+
+    Ok(Json(SuccessResponse::new(DashboardLayout::default())))
+}
+
+/// Saved layout of a user, falling back to the default one
+pub(crate) async fn load_layout(
+    state: &AppState,
+    subject: &str,
+) -> Result<DashboardLayout, ErrorResponse> {
+    let layout = DashboardLayoutRepository::new(state.db_pool.clone())
+        .find(subject)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load dashboard layout");
+            ErrorResponse::new("database_error", "Failed to load dashboard layout")
+        })?;
+    Ok(layout.unwrap_or_default())
+}
@@ -0,0 +1,133 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use common::db::repositories::ErasureRepository;
+use common::erasure::{verify_chain, ErasureError, ErasureReport, ErasureSearch, SubjectErasure};
+use common::models::UserClaims;
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Request to search for a data subject's identifier
+#[derive(Debug, Deserialize)]
+pub struct ErasureSearchRequest {
+    /// Value to find, e.g. an email address or phone number; ASCII case is ignored
+    pub identifier: String,
+}
+
+/// Request to erase a data subject's identifier
+#[derive(Debug, Deserialize)]
+pub struct ErasureRequest {
+    pub identifier: String,
+    /// Confirmation code of the search whose matches the operator reviewed
+    pub confirmation: String,
+}
+
+/// Erasure reports and whether their hash chain is intact
+#[derive(Debug, Serialize)]
+pub struct ErasureReports {
+    pub reports: Vec<ErasureReport>,
+    pub chain_valid: bool,
+}
+
+/// Find where an identifier is stored without changing anything (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn search_subject_data(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<ErasureSearchRequest>,
+) -> Result<Json<SuccessResponse<ErasureSearch>>, ErrorResponse> {
+    let search = SubjectErasure::new(
+        state.db_pool.clone(),
+        state.storage_service.clone(),
+        &state.config.auth.jwt_secret,
+    )
+    .search(&req.identifier)
+    .await
+    .map_err(erasure_error)?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        identifier_hash = %search.identifier_hash,
+        matches = search.matches.len(),
+        "Audit log: Subject data searched for erasure"
+    );
+
+    Ok(Json(SuccessResponse::new(search)))
+}
+
+/// Redact an identifier everywhere it was found, if the matches are still the ones
+/// the operator confirmed, and record a report (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn erase_subject_data(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<ErasureRequest>,
+) -> Result<(StatusCode, Json<SuccessResponse<ErasureReport>>), ErrorResponse> {
+    let report = SubjectErasure::new(
+        state.db_pool.clone(),
+        state.storage_service.clone(),
+        &state.config.auth.jwt_secret,
+    )
+    .erase(&req.identifier, &req.confirmation, &claims.username)
+    .await
+    .map_err(erasure_error)?;
+
+    tracing::warn!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        report_id = %report.id,
+        identifier_hash = %report.identifier_hash,
+        matches = report.matches.len(),
+        "Audit log: Subject data erased"
+    );
+
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(report))))
+}
+
+/// List erasure reports, newest first, and verify their hash chain (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_erasure_reports(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<ErasureReports>>, ErrorResponse> {
+    let mut reports = ErasureRepository::new(state.db_pool.clone())
+        .list()
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list erasure reports");
+            ErrorResponse::new("database_error", "Failed to list erasure reports")
+        })?;
+
+    let chain_valid = verify_chain(&reports);
+    if !chain_valid {
+        tracing::error!("Erasure report chain is broken; reports were edited or deleted");
+    }
+    reports.reverse();
+
+    Ok(Json(SuccessResponse::new(ErasureReports {
+        reports,
+        chain_valid,
+    })))
+}
+
+/// Map an erasure error, surfacing bad identifiers as 400 and changed matches as 409
+fn erasure_error(error: ErasureError) -> ErrorResponse {
+    match error {
+        ErasureError::Validation(e) => ErrorResponse::localized_with(
+            "validation_error",
+            "erasure.invalid_identifier",
+            &[("reason", &e.to_string())],
+        ),
+        ErasureError::ConfirmationMismatch => {
+            ErrorResponse::localized("conflict", "erasure.confirmation_mismatch")
+        }
+        ErasureError::Storage(e) => {
+            tracing::error!(error = %e, "Subject data erasure failed in storage");
+            ErrorResponse::new("storage_error", format!("Storage error: {}", e))
+        }
+        ErasureError::Database(e) => {
+            tracing::error!(error = %e, "Subject data erasure failed in the database");
+            ErrorResponse::new("database_error", format!("Database error: {}", e))
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::Utc;
+use common::db::repositories::ExecutionFilterRepository;
+use common::execution_filter::{validate_saved_filter, ExecutionFilterCriteria, SavedFilter};
+use common::models::UserClaims;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Query parameters for listing saved filters
+#[derive(Debug, Deserialize)]
+pub struct ListExecutionFiltersQuery {
+    /// Also list the filters shared with this team
+    pub team: Option<String>,
+}
+
+/// Request to save or change a filter
+#[derive(Debug, Deserialize)]
+pub struct SaveExecutionFilterRequest {
+    pub name: String,
+    /// Team to share the filter with
+    #[serde(default)]
+    pub team: Option<String>,
+    #[serde(default)]
+    pub criteria: ExecutionFilterCriteria,
+}
+
+impl SaveExecutionFilterRequest {
+    fn validate(&self) -> Result<(), ErrorResponse> {
+        validate_saved_filter(&self.name, self.team.as_deref(), &self.criteria)
+            .map_err(|e| invalid_filter(&e.to_string()))
+    }
+}
+
+/// List the caller's filters and, with `team`, the filters shared with that team
+#[tracing::instrument(skip(state, claims))]
+pub async fn list_execution_filters(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Query(query): Query<ListExecutionFiltersQuery>,
+) -> Result<Json<SuccessResponse<Vec<SavedFilter>>>, ErrorResponse> {
+    let filters = ExecutionFilterRepository::new(state.db_pool.clone())
+        .list_visible(&claims.sub, query.team.as_deref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list saved filters");
+            ErrorResponse::new("database_error", "Failed to list saved filters")
+        })?;
+
+    Ok(Json(SuccessResponse::new(filters)))
+}
+
+/// Save a filter; open it with `?filter_id=` on the executions list
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn create_execution_filter(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<SaveExecutionFilterRequest>,
+) -> Result<Json<SuccessResponse<SavedFilter>>, ErrorResponse> {
+    req.validate()?;
+
+    let now = Utc::now();
+    let filter = SavedFilter {
+        id: Uuid::new_v4(),
+        name: req.name.trim().to_string(),
+        owner: claims.sub.clone(),
+        team: req.team.map(|team| team.trim().to_string()),
+        criteria: req.criteria,
+        created_at: now,
+        updated_at: now,
+    };
+
+    ExecutionFilterRepository::new(state.db_pool.clone())
+        .create(&filter)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save filter");
+            ErrorResponse::new("database_error", "Failed to save filter")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        filter_id = %filter.id,
+        team = ?filter.team,
+        "Execution filter saved"
+    );
+
+    Ok(Json(SuccessResponse::new(filter)))
+}
+
+/// Get a saved filter; anyone with the id may open it
+#[tracing::instrument(skip(state))]
+pub async fn get_execution_filter(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<SavedFilter>>, ErrorResponse> {
+    let filter = find_filter(&state, id).await?;
+    Ok(Json(SuccessResponse::new(filter)))
+}
+
+/// Change a filter the caller saved
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn update_execution_filter(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SaveExecutionFilterRequest>,
+) -> Result<Json<SuccessResponse<SavedFilter>>, ErrorResponse> {
+    req.validate()?;
+
+    let mut filter = find_filter(&state, id).await?;
+    if filter.owner != claims.sub {
+        return Err(ErrorResponse::localized(
+            "forbidden",
+            "execution.filter_not_owner",
+        ));
+    }
+
+    filter.name = req.name.trim().to_string();
+    filter.team = req.team.map(|team| team.trim().to_string());
+    filter.criteria = req.criteria;
+    filter.updated_at = Utc::now();
+
+    ExecutionFilterRepository::new(state.db_pool.clone())
+        .update(&filter)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to update filter");
+            ErrorResponse::new("database_error", "Failed to update filter")
+        })?;
+
+    Ok(Json(SuccessResponse::new(filter)))
+}
+
+/// Delete a filter the caller saved
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_execution_filter(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let filter = find_filter(&state, id).await?;
+    if filter.owner != claims.sub {
+        return Err(ErrorResponse::localized(
+            "forbidden",
+            "execution.filter_not_owner",
+        ));
+    }
+
+    ExecutionFilterRepository::new(state.db_pool.clone())
+        .delete(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to delete filter");
+            ErrorResponse::new("database_error", "Failed to delete filter")
+        })?;
+
+    tracing::info!(user_id = %claims.sub, filter_id = %id, "Execution filter deleted");
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+/// Criteria to apply to an executions list: the query parameters, with the ones left
+/// unset taken from the saved filter `filter_id`
+pub(crate) async fn resolve_criteria(
+    state: &AppState,
+    filter_id: Option<Uuid>,
+    criteria: ExecutionFilterCriteria,
+) -> Result<(ExecutionFilterCriteria, Option<SavedFilter>), ErrorResponse> {
+    let saved = match filter_id {
+        Some(id) => Some(find_filter(state, id).await?),
+        None => None,
+    };
+    let criteria = match &saved {
+        Some(saved) => criteria.or(saved.criteria.clone()),
+        None => criteria,
+    };
+    criteria
+        .validate()
+        .map_err(|e| invalid_filter(&e.to_string()))?;
+
+    Ok((criteria, saved))
+}
+
+async fn find_filter(state: &AppState, id: Uuid) -> Result<SavedFilter, ErrorResponse> {
+    ExecutionFilterRepository::new(state.db_pool.clone())
+        .find(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load saved filter");
+            ErrorResponse::new("database_error", "Failed to load saved filter")
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "execution.filter_not_found",
+                &[("id", &id.to_string())],
+            )
+        })
+}
+
+fn invalid_filter(reason: &str) -> ErrorResponse {
+    ErrorResponse::localized_with(
+        "validation_error",
+        "execution.invalid_filter",
+        &[("reason", reason)],
+    )
+}
@@ -0,0 +1,130 @@
+use axum::{extract::State, Extension, Json};
+use chrono::Utc;
+use common::db::RedisConnection;
+use common::failover::{
+    check_replication, region_ready, ComponentStatus, RegionRole, RegionRoleStore,
+};
+use common::models::UserClaims;
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Request to promote a standby region
+#[derive(Debug, Default, Deserialize)]
+pub struct PromoteRequest {
+    /// Promote even though the region's database is still a read-only replica
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Role and readiness of this region
+#[derive(Debug, Serialize)]
+pub struct RegionStatus {
+    pub region: String,
+    pub role: RegionRole,
+    /// A live scheduler and worker are reporting and all of them are ready
+    pub ready: bool,
+    pub components: Vec<ComponentStatus>,
+}
+
+fn role_store(state: &AppState) -> RegionRoleStore<RedisConnection> {
+    RegionRoleStore::new(state.redis.clone(), state.config.failover.region.clone())
+}
+
+async fn region_status(
+    state: &AppState,
+    store: &RegionRoleStore<RedisConnection>,
+) -> Result<RegionStatus, ErrorResponse> {
+    let storage_error =
+        |e: common::errors::StorageError| ErrorResponse::new("redis_error", e.to_string());
+    let role = RegionRole::resolve(
+        store.role().await.map_err(storage_error)?,
+        &state.config.failover,
+    );
+    let components = store.components().await.map_err(storage_error)?;
+    let ready = region_ready(
+        &components,
+        Utc::now(),
+        state.config.failover.role_check_interval_seconds,
+    );
+
+    Ok(RegionStatus {
+        region: state.config.failover.region.clone(),
+        role,
+        ready,
+        components,
+    })
+}
+
+/// Show this region's failover role and component readiness (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn get_failover_status(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<RegionStatus>>, ErrorResponse> {
+    let store = role_store(&state);
+    Ok(Json(SuccessResponse::new(
+        region_status(&state, &store).await?,
+    )))
+}
+
+/// Make this region active so its scheduler and workers take over (admin only)
+///
+/// Run it only once the other region is down or demoted: both regions active at once
+/// would schedule every job twice.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn promote_region(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    req: Option<Json<PromoteRequest>>,
+) -> Result<Json<SuccessResponse<RegionStatus>>, ErrorResponse> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+
+    let replication = check_replication(&state.db_pool).await.map_err(|e| {
+        ErrorResponse::new(
+            "database_error",
+            format!("Failed to check replication: {}", e),
+        )
+    })?;
+    if replication.in_recovery && !req.force {
+        return Err(ErrorResponse::localized(
+            "conflict",
+            "failover.database_read_only",
+        ));
+    }
+
+    set_role(&state, &claims, RegionRole::Active).await
+}
+
+/// Put this region back in standby, e.g. after failing back (admin only)
+#[tracing::instrument(skip(state, claims))]
+pub async fn demote_region(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<RegionStatus>>, ErrorResponse> {
+    set_role(&state, &claims, RegionRole::Standby).await
+}
+
+async fn set_role(
+    state: &AppState,
+    claims: &UserClaims,
+    role: RegionRole,
+) -> Result<Json<SuccessResponse<RegionStatus>>, ErrorResponse> {
+    let store = role_store(state);
+    store.set_role(role).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to set region role");
+        ErrorResponse::new("redis_error", e.to_string())
+    })?;
+
+    tracing::warn!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        region = %state.config.failover.region,
+        role = %role,
+        "Audit log: Region failover role changed"
+    );
+
+    Ok(Json(SuccessResponse::new(
+        region_status(state, &store).await?,
+    )))
+}
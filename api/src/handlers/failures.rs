@@ -0,0 +1,218 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use common::db::repositories::execution::{ExecutionRepository, FailureGroup};
+use common::db::repositories::FailureNoteRepository;
+use common::fingerprint;
+use common::models::{FailureNote, UserClaims};
+use serde::Deserialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Groups listed when no limit is given
+const DEFAULT_LIST_LIMIT: i64 = 50;
+
+/// Most groups a single list request returns
+const MAX_LIST_LIMIT: i64 = 500;
+
+/// Days of failures grouped when no start is given
+const DEFAULT_WINDOW_DAYS: i64 = 7;
+
+/// Longest accepted remediation note
+const MAX_NOTE_LEN: usize = 4000;
+
+/// Query parameters for listing failure groups
+#[derive(Debug, Deserialize)]
+pub struct ListFailureGroupsQuery {
+    /// Only failures of this job
+    pub job_id: Option<Uuid>,
+    /// Failures created at or after this time; the last 7 days when left out
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+}
+
+/// List failed executions grouped by error fingerprint, most recent first
+///
+/// Executions whose errors differ only in ids, timestamps or counts form one group
+/// with its count and when it was first and last seen.
+#[tracing::instrument(skip(state))]
+pub async fn list_failure_groups(
+    State(state): State<AppState>,
+    Query(query): Query<ListFailureGroupsQuery>,
+) -> Result<Json<SuccessResponse<Vec<FailureGroup>>>, ErrorResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "failure.invalid_limit",
+            &[("max", &MAX_LIST_LIMIT.to_string())],
+        ));
+    }
+    let since = query
+        .since
+        .unwrap_or_else(|| Utc::now() - Duration::days(DEFAULT_WINDOW_DAYS));
+
+    let mut groups = ExecutionRepository::new(state.db_pool.clone())
+        .find_failure_groups(query.job_id, since, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list failure groups");
+            ErrorResponse::localized("database_error", "failure.groups_not_loaded")
+        })?;
+
+    let fingerprints: Vec<String> = groups.iter().map(|g| g.fingerprint.clone()).collect();
+    let mut notes = find_notes(&state, &fingerprints).await?;
+    for group in &mut groups {
+        group.note = notes.remove(&group.fingerprint);
+    }
+
+    Ok(Json(SuccessResponse::new(groups)))
+}
+
+/// Request to attach a remediation note to a failure fingerprint
+#[derive(Debug, Deserialize)]
+pub struct UpsertFailureNoteRequest {
+    pub note: String,
+    pub runbook_url: Option<String>,
+}
+
+/// Get the remediation note of a failure fingerprint
+#[tracing::instrument(skip(state))]
+pub async fn get_failure_note(
+    State(state): State<AppState>,
+    Path(fingerprint): Path<String>,
+) -> Result<Json<SuccessResponse<FailureNote>>, ErrorResponse> {
+    let note = FailureNoteRepository::new(state.db_pool.clone())
+        .find_by_fingerprint(&fingerprint)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get failure note");
+            ErrorResponse::localized("database_error", "failure.note_not_loaded")
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "failure.note_not_found",
+                &[("fingerprint", &fingerprint)],
+            )
+        })?;
+
+    Ok(Json(SuccessResponse::new(note)))
+}
+
+/// Attach a remediation note and runbook link to a failure fingerprint, replacing
+/// any note it had
+///
+/// Executions failing with the fingerprint show the note from then on, including
+/// earlier ones.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn upsert_failure_note(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(fingerprint): Path<String>,
+    Json(req): Json<UpsertFailureNoteRequest>,
+) -> Result<Json<SuccessResponse<FailureNote>>, ErrorResponse> {
+    if !fingerprint::is_fingerprint(&fingerprint) {
+        return Err(ErrorResponse::localized(
+            "validation_error",
+            "failure.invalid_fingerprint",
+        ));
+    }
+    let note = req.note.trim();
+    if note.is_empty() || note.chars().count() > MAX_NOTE_LEN {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "failure.invalid_note",
+            &[("max", &MAX_NOTE_LEN.to_string())],
+        ));
+    }
+    let runbook_url = req
+        .runbook_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+    if let Some(url) = runbook_url {
+        let valid = reqwest::Url::parse(url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+        if !valid {
+            return Err(ErrorResponse::localized(
+                "validation_error",
+                "failure.invalid_runbook_url",
+            ));
+        }
+    }
+
+    let note = FailureNoteRepository::new(state.db_pool.clone())
+        .upsert(&fingerprint, note, runbook_url, &claims.sub)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save failure note");
+            ErrorResponse::localized("database_error", "failure.note_not_saved")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        fingerprint = %fingerprint,
+        "Audit log: Failure note changed"
+    );
+
+    Ok(Json(SuccessResponse::new(note)))
+}
+
+/// Remove the remediation note of a failure fingerprint
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_failure_note(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(fingerprint): Path<String>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let deleted = FailureNoteRepository::new(state.db_pool.clone())
+        .delete(&fingerprint)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to delete failure note");
+            ErrorResponse::localized("database_error", "failure.note_not_deleted")
+        })?;
+
+    if !deleted {
+        return Err(ErrorResponse::localized_with(
+            "not_found",
+            "failure.note_not_found",
+            &[("fingerprint", &fingerprint)],
+        ));
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        fingerprint = %fingerprint,
+        "Audit log: Failure note deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+/// Notes of the fingerprints, by fingerprint
+pub async fn find_notes(
+    state: &AppState,
+    fingerprints: &[String],
+) -> Result<HashMap<String, FailureNote>, ErrorResponse> {
+    let notes = FailureNoteRepository::new(state.db_pool.clone())
+        .find_by_fingerprints(fingerprints)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load failure notes");
+            ErrorResponse::localized("database_error", "failure.notes_not_loaded")
+        })?;
+
+    Ok(notes
+        .into_iter()
+        .map(|note| (note.fingerprint.clone(), note))
+        .collect())
+}
@@ -0,0 +1,118 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use common::db::repositories::FeatureFlagRepository;
+use common::models::{FeatureFlag, UserClaims};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Longest accepted flag name, matching the column width
+const MAX_FLAG_NAME_LEN: usize = 100;
+
+/// Request to create or replace a feature flag
+///
+/// Without `tenant_id` the flag is the global default; with it, the flag
+/// overrides the default for that tenant only.
+#[derive(Debug, Deserialize)]
+pub struct UpsertFeatureFlagRequest {
+    pub name: String,
+    pub tenant_id: Option<String>,
+    pub enabled: bool,
+    pub description: Option<String>,
+}
+
+/// List all feature flags (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_feature_flags(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<FeatureFlag>>>, ErrorResponse> {
+    let repository = FeatureFlagRepository::new(state.db_pool.clone());
+
+    let flags = repository.find_all().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to list feature flags");
+        ErrorResponse::new("database_error", "Failed to list feature flags")
+    })?;
+
+    Ok(Json(SuccessResponse::new(flags)))
+}
+
+/// Create or replace a feature flag (admin only)
+///
+/// Takes effect on the next request; no restart is needed.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn upsert_feature_flag(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<UpsertFeatureFlagRequest>,
+) -> Result<Json<SuccessResponse<FeatureFlag>>, ErrorResponse> {
+    let name = req.name.trim();
+    if name.is_empty() || name.len() > MAX_FLAG_NAME_LEN {
+        return Err(ErrorResponse::new(
+            "validation_error",
+            format!(
+                "Feature flag name must be between 1 and {} characters",
+                MAX_FLAG_NAME_LEN
+            ),
+        ));
+    }
+
+    let tenant_id = req
+        .tenant_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+
+    let repository = FeatureFlagRepository::new(state.db_pool.clone());
+    let flag = repository
+        .upsert(name, tenant_id, req.enabled, req.description.as_deref())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save feature flag");
+            ErrorResponse::new("database_error", "Failed to save feature flag")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        flag = %flag.name,
+        tenant_id = ?flag.tenant_id,
+        enabled = flag.enabled,
+        "Audit log: Feature flag changed"
+    );
+
+    Ok(Json(SuccessResponse::new(flag)))
+}
+
+/// Delete a feature flag; a tenant override falls back to the global value (admin only)
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_feature_flag(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let repository = FeatureFlagRepository::new(state.db_pool.clone());
+    let deleted = repository.delete(id).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to delete feature flag");
+        ErrorResponse::new("database_error", "Failed to delete feature flag")
+    })?;
+
+    if !deleted {
+        return Err(ErrorResponse::new(
+            "not_found",
+            format!("Feature flag not found: {}", id),
+        ));
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        flag_id = %id,
+        "Audit log: Feature flag deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
@@ -1,13 +1,16 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, Extension, Json};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::handlers::capabilities::ensure_step_types_enabled;
 use crate::handlers::{ErrorResponse, SuccessResponse};
 use crate::state::AppState;
 use common::import_export::{
-    ExportedJob, ImportExportService, ImportExportServiceImpl, ImportResult,
+    decrypt_bundle, ExportedJob, ImportExportError, ImportExportService, ImportExportServiceImpl,
+    ImportResult,
 };
+use common::models::{JobStep, UserClaims};
 
 /// Request to export a single job
 /// Requirements: 18.3 - Export single job
@@ -39,6 +42,31 @@ pub struct ExportJobsBulkResponse {
     pub count: usize,
 }
 
+/// Request to export jobs as an encrypted disaster-recovery bundle
+#[derive(Debug, Deserialize)]
+pub struct ExportEncryptedBundleRequest {
+    pub job_ids: Vec<Uuid>,
+    /// age public keys (`age1...`); any matching secret key can decrypt the bundle
+    pub recipients: Vec<String>,
+}
+
+/// Encrypted disaster-recovery bundle
+#[derive(Debug, Serialize)]
+pub struct ExportEncryptedBundleResponse {
+    /// ASCII-armored age file
+    pub bundle: String,
+    pub filename: String,
+    pub count: usize,
+}
+
+/// Request to import an encrypted disaster-recovery bundle
+#[derive(Debug, Deserialize)]
+pub struct ImportEncryptedBundleRequest {
+    pub bundle: String,
+    /// age secret key (`AGE-SECRET-KEY-1...`) matching one of the bundle's recipients
+    pub identity: String,
+}
+
 /// Request to import a single job
 /// Requirements: 18.9 - Import single job
 #[derive(Debug, Deserialize)]
@@ -143,13 +171,128 @@ pub async fn export_jobs_bulk(
     Ok(Json(SuccessResponse::new(response)))
 }
 
+/// Export jobs with their real secret values, encrypted to age recipients (admin only)
+///
+/// Intended for disaster-recovery transfers between environments; the masked export
+/// cannot restore a working job.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn export_encrypted_bundle(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<ExportEncryptedBundleRequest>,
+) -> Result<Json<SuccessResponse<ExportEncryptedBundleResponse>>, ErrorResponse> {
+    let service = ImportExportServiceImpl::new(
+        state.db_pool.clone(),
+        state.storage_service.clone(),
+        env!("CARGO_PKG_VERSION").to_string(),
+    );
+
+    let count = req.job_ids.len();
+    let bundle = service
+        .export_encrypted_bundle(req.job_ids, claims.username.clone(), &req.recipients)
+        .await
+        .map_err(bundle_error)?;
+
+    let response = ExportEncryptedBundleResponse {
+        bundle,
+        filename: format!(
+            "jobs_bundle_{}.age",
+            chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        ),
+        count,
+    };
+
+    tracing::info!(
+        username = %claims.username,
+        count,
+        recipients = req.recipients.len(),
+        "Audit log: Encrypted job bundle exported"
+    );
+    Ok(Json(SuccessResponse::new(response)))
+}
+
+/// Import an encrypted disaster-recovery bundle, restoring its secret values
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn import_encrypted_bundle(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<ImportEncryptedBundleRequest>,
+) -> Result<Json<SuccessResponse<ImportJobsBulkResponse>>, ErrorResponse> {
+    let bundle = decrypt_bundle(&req.bundle, &req.identity).map_err(bundle_error)?;
+
+    let steps: Vec<JobStep> = bundle
+        .jobs
+        .iter()
+        .flat_map(|job| job.steps.clone())
+        .collect();
+    ensure_step_types_enabled(&state, &claims, &steps).await?;
+
+    let service = ImportExportServiceImpl::new(
+        state.db_pool.clone(),
+        state.storage_service.clone(),
+        env!("CARGO_PKG_VERSION").to_string(),
+    );
+
+    let results = service.import_bundle(bundle).await.map_err(|e| {
+        ErrorResponse::new(
+            "bulk_import_failed",
+            format!("Failed to import bundle: {}", e),
+        )
+    })?;
+
+    let success_count = results.iter().filter(|r| r.success).count();
+    let failed_count = results.len() - success_count;
+
+    let response = ImportJobsBulkResponse {
+        total: results.len(),
+        success_count,
+        failed_count,
+        results,
+    };
+
+    tracing::info!(
+        username = %claims.username,
+        total = response.total,
+        success = response.success_count,
+        failed = response.failed_count,
+        "Audit log: Encrypted job bundle imported"
+    );
+    Ok(Json(SuccessResponse::new(response)))
+}
+
+/// Map a bundle error, surfacing bad keys and undecryptable or malformed bundles as 400
+fn bundle_error(error: ImportExportError) -> ErrorResponse {
+    match error {
+        ImportExportError::Validation(_)
+        | ImportExportError::Encryption(_)
+        | ImportExportError::Serialization(_) => ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_bundle",
+            &[("reason", &error.to_string())],
+        ),
+        ImportExportError::NotFound(_) => ErrorResponse::new("not_found", error.to_string()),
+        e => ErrorResponse::new("export_failed", format!("Failed to export bundle: {}", e)),
+    }
+}
+
+/// Steps of an exported job definition; malformed steps are left to the import validation
+fn definition_steps(definition: &serde_json::Value) -> Vec<JobStep> {
+    definition
+        .get("steps")
+        .and_then(|steps| serde_json::from_value(steps.clone()).ok())
+        .unwrap_or_default()
+}
+
 /// Import a single job
 /// Requirements: 18.7, 18.8, 18.9, 18.10 - Import with validation and sensitive data
-#[tracing::instrument(skip(state, req))]
+#[tracing::instrument(skip(state, claims, req))]
 pub async fn import_job(
     State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
     Json(req): Json<ImportJobRequest>,
 ) -> Result<Json<SuccessResponse<Uuid>>, ErrorResponse> {
+    ensure_step_types_enabled(&state, &claims, &definition_steps(&req.job_definition)).await?;
+
     // Create import/export service (using MinIO service with Redis fallback)
     let service = ImportExportServiceImpl::new(
         state.db_pool.clone(),
@@ -171,11 +314,19 @@ pub async fn import_job(
 
 /// Import multiple jobs in bulk
 /// Requirements: 18.13 - Bulk import processing
-#[tracing::instrument(skip(state, req))]
+#[tracing::instrument(skip(state, claims, req))]
 pub async fn import_jobs_bulk(
     State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
     Json(req): Json<ImportJobsBulkRequest>,
 ) -> Result<Json<SuccessResponse<ImportJobsBulkResponse>>, ErrorResponse> {
+    let steps: Vec<JobStep> = req
+        .job_definitions
+        .iter()
+        .flat_map(definition_steps)
+        .collect();
+    ensure_step_types_enabled(&state, &claims, &steps).await?;
+
     // Create import/export service (using MinIO service with Redis fallback)
     let service = ImportExportServiceImpl::new(
         state.db_pool.clone(),
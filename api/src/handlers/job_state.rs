@@ -0,0 +1,133 @@
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use common::db::repositories::{JobRepository, JobStateRepository};
+use common::models::{JobStateEntry, UserClaims};
+use common::storage::residency;
+use serde::Deserialize;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Request to set one key of a job's state
+#[derive(Debug, Deserialize)]
+pub struct SetJobStateRequest {
+    pub value: serde_json::Value,
+}
+
+/// List the state a job keeps across its executions
+#[tracing::instrument(skip(state))]
+pub async fn list_job_state(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<JobStateEntry>>>, ErrorResponse> {
+    ensure_job_exists(&state, id).await?;
+
+    let entries = residency::load_state(
+        state.storage_service.as_ref(),
+        &JobStateRepository::new(state.db_pool.clone()),
+        id,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to get job state");
+        ErrorResponse::new("database_error", "Failed to retrieve job state")
+    })?;
+
+    Ok(Json(SuccessResponse::new(entries)))
+}
+
+/// Set one key of a job's state, e.g. to move a sync cursor back and reprocess
+///
+/// Executions started from then on read the new value.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn set_job_state(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path((id, key)): Path<(Uuid, String)>,
+    Json(req): Json<SetJobStateRequest>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    if !JobStateEntry::is_valid_key(&key) {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_state_key",
+            &[("max", &JobStateEntry::MAX_KEY_LEN.to_string())],
+        ));
+    }
+    ensure_job_exists(&state, id).await?;
+
+    residency::save_state(
+        state.storage_service.as_ref(),
+        &JobStateRepository::new(state.db_pool.clone()),
+        id,
+        &HashMap::from([(key.clone(), req.value)]),
+        None,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to save job state");
+        ErrorResponse::new("database_error", "Failed to save job state")
+    })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        job_id = %id,
+        key = %key,
+        "Audit log: Job state set"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+/// Delete one key of a job's state
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_job_state(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path((id, key)): Path<(Uuid, String)>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let deleted = residency::delete_state(
+        state.storage_service.as_ref(),
+        &JobStateRepository::new(state.db_pool.clone()),
+        id,
+        &key,
+    )
+    .await
+    .map_err(|e| {
+        tracing::error!(error = %e, "Failed to delete job state");
+        ErrorResponse::new("database_error", "Failed to delete job state")
+    })?;
+
+    if !deleted {
+        return Err(ErrorResponse::localized_with(
+            "not_found",
+            "job.state_key_not_found",
+            &[("id", &id.to_string()), ("key", &key)],
+        ));
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        job_id = %id,
+        key = %key,
+        "Audit log: Job state deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+async fn ensure_job_exists(state: &AppState, id: Uuid) -> Result<(), ErrorResponse> {
+    JobRepository::new(state.db_pool.clone())
+        .find_by_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    Ok(())
+}
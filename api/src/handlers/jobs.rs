@@ -1,16 +1,43 @@
 use axum::{
-    extract::{Path, State},
-    Json,
+    body::Bytes,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
 use uuid::Uuid;
 
-use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::handlers::capabilities::ensure_step_types_enabled;
+use crate::handlers::{optional_json, ErrorResponse, SuccessResponse};
 use crate::state::{AppState, SseEvent};
+use common::callback::ExecutionCallback;
+use common::connection_profile;
 use common::db::repositories::execution::ExecutionRepository;
 use common::db::repositories::job::JobRepository;
-use common::models::{Job, JobExecution, JobStep, Schedule, TriggerConfig};
+use common::db::repositories::user::UserRepository;
+use common::db::repositories::{
+    BlackoutWindowRepository, CalendarRepository, ConnectionProfileRepository,
+    ScheduledTriggerRepository, VariableRepository,
+};
+use common::deprecation;
+use common::errors::{DatabaseError, ValidationError};
+use common::executor::sftp::validate_sftp_step;
+use common::models::{
+    validate_metric_labels, validate_step_flow, BlackoutBehavior, BlackoutWindow, CollisionPolicy,
+    ConcurrencyLimit, DeadLetterPolicy, ExecutionStatus, Job, JobExecution, JobOwner, JobPriority,
+    JobStep, Schedule, ScheduledTrigger, ScheduledTriggerStatus, TriggerConfig, UserClaims,
+};
+use common::parameter_matrix::validate_parameter_matrix;
+use common::queue::nats::{shared_job_subject, tenant_job_subject};
+use common::schedule::RepeatingInterval;
+use common::trigger_variables::{
+    apply_trigger_variables, check_sensitive_overrides, normalize_trigger_variables,
+};
 
 /// Request to create a new job
 #[derive(Debug, Deserialize)]
@@ -23,6 +50,26 @@ pub struct CreateJobRequest {
     pub timeout_seconds: Option<i32>,
     pub max_retries: Option<i32>,
     pub allow_concurrent: Option<bool>,
+    /// What a due run does while a previous run is active; defaults to `skip`
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    /// Run executions one at a time in the order they were created
+    #[serde(default)]
+    pub strict_ordering: bool,
+    /// Queued executions of higher priority jobs are taken first; defaults to `normal`
+    #[serde(default)]
+    pub priority: JobPriority,
+    /// Dead-letter runs failing at a step dependency, replaying them once it recovers
+    pub dead_letter_policy: Option<DeadLetterPolicy>,
+    /// Caps how many executions run at once, queuing or rejecting the rest
+    pub concurrency_limit: Option<ConcurrencyLimit>,
+    pub owner: Option<JobOwner>,
+    /// Extra labels on the job's execution metrics
+    #[serde(default)]
+    pub metric_labels: BTreeMap<String, String>,
+    /// Parameter values each scheduled run fans out over
+    #[serde(default)]
+    pub parameter_matrix: BTreeMap<String, Vec<String>>,
 }
 
 /// Request to update an existing job
@@ -36,8 +83,91 @@ pub struct UpdateJobRequest {
     pub timeout_seconds: Option<i32>,
     pub max_retries: Option<i32>,
     pub allow_concurrent: Option<bool>,
+    pub collision_policy: Option<CollisionPolicy>,
+    pub strict_ordering: Option<bool>,
+    pub priority: Option<JobPriority>,
+    /// Absent leaves the policy unchanged, `null` removes it
+    #[serde(default, deserialize_with = "super::variables::deserialize_present")]
+    pub dead_letter_policy: Option<Option<DeadLetterPolicy>>,
+    /// Absent leaves the limit unchanged, `null` removes it
+    #[serde(default, deserialize_with = "super::variables::deserialize_present")]
+    pub concurrency_limit: Option<Option<ConcurrencyLimit>>,
+    /// Absent leaves the owner unchanged, `null` removes it
+    #[serde(default, deserialize_with = "super::variables::deserialize_present")]
+    pub owner: Option<Option<JobOwner>>,
+    /// Replaces the job's metric labels; `{}` removes them
+    pub metric_labels: Option<BTreeMap<String, String>>,
+    /// Replaces the job's parameter matrix; `{}` removes it
+    pub parameter_matrix: Option<BTreeMap<String, Vec<String>>>,
+}
+
+/// Optional body of a trigger request
+#[derive(Debug, Default, Deserialize)]
+pub struct TriggerJobRequest {
+    /// POSTed a signed summary when the execution finishes
+    pub callback: Option<ExecutionCallback>,
+    /// Variable overrides for this run only
+    #[serde(default)]
+    pub variables: HashMap<String, serde_json::Value>,
+}
+
+/// Request to run a job once at a given time
+#[derive(Debug, Deserialize)]
+pub struct TriggerAtRequest {
+    pub execute_at: DateTime<Utc>,
+    /// Variable overrides merged into the execution context
+    #[serde(default)]
+    pub variables: HashMap<String, serde_json::Value>,
+    pub callback: Option<ExecutionCallback>,
 }
 
+/// Request to add a blackout window to a job
+#[derive(Debug, Deserialize)]
+pub struct CreateBlackoutWindowRequest {
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    pub ends_at: DateTime<Utc>,
+    /// `skip` drops runs due in the window, `defer` makes one when it ends
+    #[serde(default)]
+    pub behavior: BlackoutBehavior,
+}
+
+/// Request to deprecate a job
+#[derive(Debug, Deserialize)]
+pub struct DeprecateJobRequest {
+    /// When the scheduler disables the job
+    pub sunset_at: DateTime<Utc>,
+    pub reason: Option<String>,
+}
+
+/// Query parameters of a job details request
+#[derive(Debug, Default, Deserialize)]
+pub struct GetJobQuery {
+    /// Return the job definition that was active at this time
+    pub as_of: Option<DateTime<Utc>>,
+    /// Return this version of the job definition, e.g. an execution's `job_version`
+    pub version: Option<i32>,
+}
+
+/// Query parameters of a trigger request
+#[derive(Debug, Default, Deserialize)]
+pub struct TriggerJobQuery {
+    /// Block until the execution finishes, up to this long (e.g. `60s`, `2m`, `30`)
+    pub wait: Option<String>,
+}
+
+/// Result of a trigger request that waited for completion
+#[derive(Debug, Serialize)]
+pub struct TriggerWaitResponse {
+    pub execution_id: Uuid,
+    /// False when the wait timed out before the execution finished
+    pub completed: bool,
+    pub execution: Option<JobExecution>,
+}
+
+/// Longest a trigger request may wait for its execution to finish
+const MAX_TRIGGER_WAIT: Duration = Duration::from_secs(300);
+
 /// Job with statistics for listing
 #[derive(Debug, Serialize)]
 pub struct JobWithStats {
@@ -64,12 +194,33 @@ pub struct JobStatsResponse {
 /// Create a new job
 ///
 /// Requirements: 6.1, 7.2 - Job creation and dynamic job addition
-#[tracing::instrument(skip(state, req))]
+#[tracing::instrument(skip(state, claims, req))]
 pub async fn create_job(
     State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
     Json(req): Json<CreateJobRequest>,
 ) -> Result<Json<SuccessResponse<Uuid>>, ErrorResponse> {
     let job_id = Uuid::new_v4();
+
+    if let Some(owner) = &req.owner {
+        validate_owner(owner)?;
+    }
+    if let Some(policy) = &req.dead_letter_policy {
+        validate_dead_letter_policy(policy)?;
+    }
+    if let Some(limit) = &req.concurrency_limit {
+        validate_concurrency_limit(limit)?;
+    }
+    validate_job_metric_labels(&req.metric_labels)?;
+    validate_job_parameter_matrix(&state, job_id, &req.parameter_matrix).await?;
+    if let Some(schedule) = &req.schedule {
+        validate_schedule(schedule)?;
+        ensure_calendar_exists(&state, schedule).await?;
+    }
+    validate_steps(&req.steps)?;
+    ensure_step_types_enabled(&state, &claims, &req.steps).await?;
+    ensure_connections_exist(&state, &req.steps).await?;
+
     let now = Utc::now();
 
     // Get triggers or default
@@ -87,6 +238,14 @@ pub async fn create_job(
         "timeout_seconds": req.timeout_seconds.unwrap_or(300),
         "max_retries": req.max_retries.unwrap_or(10),
         "allow_concurrent": req.allow_concurrent.unwrap_or(false),
+        "collision_policy": req.collision_policy,
+        "strict_ordering": req.strict_ordering,
+        "priority": req.priority,
+        "dead_letter_policy": req.dead_letter_policy,
+        "concurrency_limit": req.concurrency_limit,
+        "owner": req.owner,
+        "metric_labels": req.metric_labels,
+        "parameter_matrix": req.parameter_matrix,
     });
 
     // Store job definition in PostgreSQL
@@ -114,10 +273,24 @@ pub async fn create_job(
         steps: req.steps,
         triggers: triggers.clone(),
         enabled: true,
+        paused: false,
         timeout_seconds: req.timeout_seconds.unwrap_or(300),
         max_retries: req.max_retries.unwrap_or(10),
         allow_concurrent: req.allow_concurrent.unwrap_or(false),
+        collision_policy: req.collision_policy,
+        strict_ordering: req.strict_ordering,
+        priority: req.priority,
+        dead_letter_policy: req.dead_letter_policy,
+        concurrency_limit: req.concurrency_limit,
+        owner: req.owner,
+        metric_labels: req.metric_labels,
+        parameter_matrix: req.parameter_matrix,
         definition: Some(definition_value),
+        version: 1,
+        deprecated: false,
+        sunset_at: None,
+        deprecation_reason: None,
+        tenant_id: claims.tenant_id.clone(),
         created_at: now,
         updated_at: now,
     };
@@ -210,19 +383,29 @@ pub async fn list_jobs(
 
 /// Get job details by ID
 ///
+/// With `as_of` or `version`, returns that past definition from the job's version
+/// history instead, without an ETag since it can't be updated.
+///
 /// Requirements: 6.1 - Get job details
 #[tracing::instrument(skip(state))]
 pub async fn get_job(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<SuccessResponse<Job>>, ErrorResponse> {
+    Query(query): Query<GetJobQuery>,
+) -> Result<Response, ErrorResponse> {
     let repo = JobRepository::new(state.db_pool.clone());
 
+    if query.as_of.is_some() || query.version.is_some() {
+        return get_job_version(&repo, id, &query).await;
+    }
+
     let job = repo
         .find_by_id(id)
         .await
         .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Job not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
 
     // Load full job definition from PostgreSQL to get schedule and steps
     let job_definition: serde_json::Value = if let Some(def) = &job.definition {
@@ -250,29 +433,78 @@ pub async fn get_job(
 
     // Reconstruct full job with schedule and steps
     let mut full_job = job;
-    if let Some(schedule) = job_definition.get("schedule") {
-        full_job.schedule = serde_json::from_value(schedule.clone()).ok();
+    apply_definition(&mut full_job, &job_definition);
+
+    tracing::debug!(job_id = %id, "Retrieved job details");
+    Ok(with_job_etag(
+        full_job.version,
+        Json(SuccessResponse::new(full_job)),
+    ))
+}
+
+/// A past version of a job, by time or number
+async fn get_job_version(
+    repo: &JobRepository,
+    id: Uuid,
+    query: &GetJobQuery,
+) -> Result<Response, ErrorResponse> {
+    let (job, wanted) = match (query.as_of, query.version) {
+        (Some(as_of), None) => (repo.find_version_as_of(id, as_of).await, as_of.to_rfc3339()),
+        (None, Some(version)) => (repo.find_version(id, version).await, version.to_string()),
+        _ => {
+            return Err(ErrorResponse::localized(
+                "validation_error",
+                "job.version_query_conflict",
+            ))
+        }
+    };
+
+    let mut job = job
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                &format!("Failed to fetch job version: {}", e),
+            )
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "job.version_not_found",
+                &[("id", &id.to_string()), ("version", &wanted)],
+            )
+        })?;
+    if let Some(definition) = job.definition.clone() {
+        apply_definition(&mut job, &definition);
     }
-    if let Some(steps) = job_definition.get("steps") {
-        full_job.steps = serde_json::from_value(steps.clone()).unwrap_or_default();
+
+    tracing::debug!(job_id = %id, version = job.version, "Retrieved job version");
+    Ok(Json(SuccessResponse::new(job)).into_response())
+}
+
+/// Fill in schedule, steps and triggers from the stored job definition
+pub fn apply_definition(job: &mut Job, definition: &serde_json::Value) {
+    if let Some(schedule) = definition.get("schedule") {
+        job.schedule = serde_json::from_value(schedule.clone()).ok();
     }
-    if let Some(triggers) = job_definition.get("triggers") {
-        full_job.triggers = serde_json::from_value(triggers.clone()).unwrap_or_default();
+    if let Some(steps) = definition.get("steps") {
+        job.steps = serde_json::from_value(steps.clone()).unwrap_or_default();
+    }
+    if let Some(triggers) = definition.get("triggers") {
+        job.triggers = serde_json::from_value(triggers.clone()).unwrap_or_default();
     }
-
-    tracing::debug!(job_id = %id, "Retrieved job details");
-    Ok(Json(SuccessResponse::new(full_job)))
 }
 
 /// Update a job
 ///
 /// Requirements: 6.1, 7.3 - Update job and apply changes to future executions
-#[tracing::instrument(skip(state, req))]
+#[tracing::instrument(skip(state, claims, req))]
 pub async fn update_job(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
     Json(req): Json<UpdateJobRequest>,
-) -> Result<Json<SuccessResponse<Job>>, ErrorResponse> {
+) -> Result<Response, ErrorResponse> {
     let repo = JobRepository::new(state.db_pool.clone());
 
     // Get existing job
@@ -280,7 +512,20 @@ pub async fn update_job(
         .find_by_id(id)
         .await
         .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Job not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    check_if_match(&headers, &job)?;
+    ensure_can_edit(&state, &claims, &job).await?;
+    if let Some(steps) = &req.steps {
+        validate_steps(steps)?;
+        ensure_step_types_enabled(&state, &claims, steps).await?;
+        ensure_connections_exist(&state, steps).await?;
+    }
+    if let Some(schedule) = &req.schedule {
+        validate_schedule(schedule)?;
+        ensure_calendar_exists(&state, schedule).await?;
+    }
 
     // Load existing job definition from PostgreSQL
     let mut job_definition: serde_json::Value = if let Some(def) = &job.definition {
@@ -362,6 +607,56 @@ pub async fn update_job(
         job_definition["allow_concurrent"] = serde_json::json!(allow_concurrent);
     }
 
+    if let Some(collision_policy) = req.collision_policy {
+        job.collision_policy = collision_policy;
+        job_definition["collision_policy"] = serde_json::json!(collision_policy);
+    }
+
+    if let Some(strict_ordering) = req.strict_ordering {
+        job.strict_ordering = strict_ordering;
+        job_definition["strict_ordering"] = serde_json::json!(strict_ordering);
+    }
+
+    if let Some(priority) = req.priority {
+        job.priority = priority;
+        job_definition["priority"] = serde_json::json!(priority);
+    }
+
+    if let Some(dead_letter_policy) = req.dead_letter_policy {
+        if let Some(policy) = &dead_letter_policy {
+            validate_dead_letter_policy(policy)?;
+        }
+        job_definition["dead_letter_policy"] = serde_json::json!(dead_letter_policy);
+        job.dead_letter_policy = dead_letter_policy;
+    }
+
+    if let Some(concurrency_limit) = req.concurrency_limit {
+        if let Some(limit) = &concurrency_limit {
+            validate_concurrency_limit(limit)?;
+        }
+        job_definition["concurrency_limit"] = serde_json::json!(concurrency_limit);
+        job.concurrency_limit = concurrency_limit;
+    }
+
+    if let Some(owner) = req.owner {
+        if let Some(owner) = &owner {
+            validate_owner(owner)?;
+        }
+        job_definition["owner"] = serde_json::json!(owner);
+        job.owner = owner;
+    }
+
+    if let Some(metric_labels) = req.metric_labels {
+        validate_job_metric_labels(&metric_labels)?;
+        job_definition["metric_labels"] = serde_json::json!(metric_labels);
+        job.metric_labels = metric_labels;
+    }
+    if let Some(parameter_matrix) = req.parameter_matrix {
+        validate_job_parameter_matrix(&state, id, &parameter_matrix).await?;
+        job_definition["parameter_matrix"] = serde_json::json!(parameter_matrix);
+        job.parameter_matrix = parameter_matrix;
+    }
+
     // Ensure enabled field exists (for backwards compatibility with old jobs)
     if job_definition.get("enabled").is_none() {
         job_definition["enabled"] = serde_json::json!(job.enabled);
@@ -371,9 +666,12 @@ pub async fn update_job(
     job.definition = Some(job_definition.clone());
 
     // Update job record in database (includes definition)
-    repo.update(&job).await.map_err(|e| {
-        ErrorResponse::new("database_error", &format!("Failed to update job: {}", e))
-    })?;
+    job.version = repo
+        .update(&job)
+        .await
+        .map_err(|e| update_error(e, "Failed to update job"))?;
+
+    state.publish_job_changed(id);
 
     // Broadcast SSE event
     state.broadcast_event(SseEvent::JobStatusChanged {
@@ -381,26 +679,30 @@ pub async fn update_job(
         status: "updated".to_string(),
     });
 
-    tracing::info!(job_id = %id, "Job updated successfully");
-    Ok(Json(SuccessResponse::new(job)))
+    tracing::info!(job_id = %id, version = job.version, "Job updated successfully");
+    Ok(with_job_etag(job.version, Json(SuccessResponse::new(job))))
 }
 
 /// Delete a job
 ///
 /// Requirements: 6.1, 7.4 - Delete job and stop scheduling it
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, claims))]
 pub async fn delete_job(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    Extension(claims): Extension<UserClaims>,
 ) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
     let repo = JobRepository::new(state.db_pool.clone());
 
     // Get job to verify it exists
-    let _job = repo
+    let job = repo
         .find_by_id(id)
         .await
         .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Job not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    ensure_can_edit(&state, &claims, &job).await?;
 
     // Delete job from database (this will cascade delete executions and stats)
     // Job definition is stored in PostgreSQL, so it will be deleted automatically
@@ -408,6 +710,8 @@ pub async fn delete_job(
         ErrorResponse::new("database_error", &format!("Failed to delete job: {}", e))
     })?;
 
+    state.publish_job_changed(id);
+
     // Broadcast SSE event
     state.broadcast_event(SseEvent::JobDeleted { job_id: id });
 
@@ -421,11 +725,38 @@ pub async fn delete_job(
 /// - 6.4: Immediately queue job for execution
 /// - 17.9: Allow concurrent execution if configured
 /// - 17.10: Reject new triggers if concurrent execution not allowed
-#[tracing::instrument(skip(state))]
+///
+/// An optional JSON body `{"callback": {"url": ..., "outputs": [step ids]}}` asks the
+/// worker to POST a signed completion payload to `url` when the execution finishes.
+/// A `"variables"` map overrides job and global variables for this run only.
+///
+/// With `?wait=60s` the request blocks until the execution finishes (200 with the
+/// final execution) or the wait elapses (202 with `completed: false`).
+#[tracing::instrument(skip(state, claims, body))]
 pub async fn trigger_job(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-) -> Result<Json<SuccessResponse<Uuid>>, ErrorResponse> {
+    Query(query): Query<TriggerJobQuery>,
+    Extension(claims): Extension<UserClaims>,
+    body: Bytes,
+) -> Result<Response, ErrorResponse> {
+    let wait = query
+        .wait
+        .as_deref()
+        .map(parse_wait)
+        .transpose()?
+        .map(|wait| wait.min(MAX_TRIGGER_WAIT));
+    let mut req: TriggerJobRequest = optional_json(&body)?;
+    if let Some(callback) = &req.callback {
+        callback.resolve().await.map_err(|e| {
+            ErrorResponse::localized_with(
+                "validation_error",
+                "execution.invalid_callback",
+                &[("reason", &e.to_string())],
+            )
+        })?;
+    }
+
     let repo = JobRepository::new(state.db_pool.clone());
     let execution_repo = ExecutionRepository::new(state.db_pool.clone());
 
@@ -434,7 +765,13 @@ pub async fn trigger_job(
         .find_by_id(id)
         .await
         .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Job not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+
+    let variables =
+        validate_trigger_variables(&state, id, std::mem::take(&mut req.variables)).await?;
+    warn_if_deprecated(&job, &claims);
 
     // Check if concurrent execution is allowed
     // Requirement 17.10: Reject if concurrent execution not allowed and job is running
@@ -450,16 +787,20 @@ pub async fn trigger_job(
             })?;
 
         if has_running {
-            return Err(ErrorResponse::new(
+            return Err(ErrorResponse::localized(
                 "concurrent_execution_not_allowed",
-                "Job is already running and concurrent execution is not allowed",
+                "job.concurrent_not_allowed",
             ));
         }
     }
 
     // Create execution record using factory method
     // TODO: Get user_id from JWT claims in middleware
-    let execution = JobExecution::new_manual(id, "system".to_string());
+    let mut execution = JobExecution::new_manual(id, "system".to_string());
+    if let Some(callback) = &req.callback {
+        callback.attach_to(&mut execution.trigger_metadata);
+    }
+    apply_trigger_variables(&mut execution, &variables, &claims.username, "manual");
     let execution_id = execution.id;
 
     execution_repo.create(&execution).await.map_err(|e| {
@@ -469,13 +810,89 @@ pub async fn trigger_job(
         )
     })?;
 
+    // Subscribe before publishing so a fast execution's completion isn't missed
+    let status_rx = wait.map(|_| state.sse_tx.subscribe());
+
     // Publish job to NATS queue
-    // Publish directly using JetStream
+    publish_execution(&state, &execution, &job).await?;
+
+    // Broadcast SSE event
+    state.broadcast_event(SseEvent::ExecutionStatusChanged {
+        execution_id,
+        job_id: id,
+        status: "pending".to_string(),
+    });
+
+    tracing::info!(job_id = %id, execution_id = %execution_id, "Job manually triggered");
+
+    let (Some(wait), Some(status_rx)) = (wait, status_rx) else {
+        return Ok(with_deprecation_headers(
+            &job,
+            Json(SuccessResponse::new(execution_id)),
+        ));
+    };
+
+    let finished = tokio::time::timeout(
+        wait,
+        wait_for_completion(&execution_repo, status_rx, execution_id),
+    )
+    .await
+    .ok()
+    .flatten();
+
+    let (status, response) = match finished {
+        Some(execution) => (
+            StatusCode::OK,
+            TriggerWaitResponse {
+                execution_id,
+                completed: true,
+                execution: Some(execution),
+            },
+        ),
+        None => {
+            tracing::info!(execution_id = %execution_id, "Execution still running after wait");
+            let execution = execution_repo.find_by_id(execution_id).await.ok().flatten();
+            (
+                StatusCode::ACCEPTED,
+                TriggerWaitResponse {
+                    execution_id,
+                    completed: false,
+                    execution,
+                },
+            )
+        }
+    };
+
+    Ok(with_deprecation_headers(
+        &job,
+        (status, Json(SuccessResponse::new(response))),
+    ))
+}
+
+/// Publish an execution to the job queue via JetStream
+///
+/// The idempotency key doubles as the `Nats-Msg-Id`, so a retried publish is
+/// deduplicated by the stream.
+pub async fn publish_execution(
+    state: &AppState,
+    execution: &JobExecution,
+    job: &Job,
+) -> Result<(), ErrorResponse> {
     let jetstream = async_nats::jetstream::new(state.nats_client.clone());
-    let subject = format!("jobs.{}", id);
+    // Tenants with their own stream are published on its subjects, and jobs go on
+    // their priority's lane when priority subjects are enabled
+    let nats = &state.config.nats;
+    let lane = nats.priority_subjects.then_some(job.priority);
+    let subject = job
+        .tenant_id
+        .as_deref()
+        .filter(|tenant| nats.tenant_streams.contains_key(*tenant))
+        .map(|tenant| tenant_job_subject(tenant, &execution.job_id, nats.shards, lane))
+        .unwrap_or_else(|| {
+            shared_job_subject(&nats.stream_name, &execution.job_id, nats.shards, lane)
+        });
 
-    // Create message
-    let message = common::queue::publisher::JobMessage::from(&execution);
+    let message = common::queue::publisher::JobMessage::from(execution).with_priority(job.priority);
     let payload = serde_json::to_vec(&message).map_err(|e| {
         ErrorResponse::new(
             "serialization_error",
@@ -486,8 +903,8 @@ pub async fn trigger_job(
     // Create headers for deduplication
     let mut headers = async_nats::HeaderMap::new();
     headers.insert("Nats-Msg-Id", execution.idempotency_key.as_str());
-    headers.insert("Job-Id", id.to_string().as_str());
-    headers.insert("Execution-Id", execution_id.to_string().as_str());
+    headers.insert("Job-Id", execution.job_id.to_string().as_str());
+    headers.insert("Execution-Id", execution.id.to_string().as_str());
 
     jetstream
         .publish_with_headers(subject, headers, payload.into())
@@ -506,24 +923,347 @@ pub async fn trigger_job(
             )
         })?;
 
-    // Broadcast SSE event
-    state.broadcast_event(SseEvent::ExecutionStatusChanged {
-        execution_id,
+    Ok(())
+}
+
+/// Validate trigger variable overrides against the job's stored variables
+///
+/// Values are normalized to strings, and overriding a sensitive global or
+/// job-scoped variable is rejected.
+pub async fn validate_trigger_variables(
+    state: &AppState,
+    job_id: Uuid,
+    variables: HashMap<String, serde_json::Value>,
+) -> Result<HashMap<String, serde_json::Value>, ErrorResponse> {
+    if variables.is_empty() {
+        return Ok(variables);
+    }
+
+    let invalid = |e: ValidationError| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "execution.invalid_variables",
+            &[("reason", &e.to_string())],
+        )
+    };
+
+    let variables = normalize_trigger_variables(variables).map_err(invalid)?;
+    let sensitive = VariableRepository::new(state.db_pool.clone(), None)
+        .find_sensitive_names_for_job(job_id)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to load job variables: {}", e),
+            )
+        })?;
+    check_sensitive_overrides(variables.keys(), &sensitive).map_err(invalid)?;
+
+    Ok(variables)
+}
+
+/// Wait for an execution to reach a terminal status, returning its final record
+///
+/// Listens on the status event channel fed by the worker. The database is checked
+/// when a terminal event arrives and whenever events were dropped, so a lagging
+/// receiver can't miss the completion. Returns `None` if the channel closes.
+pub async fn wait_for_completion(
+    execution_repo: &ExecutionRepository,
+    mut status_rx: broadcast::Receiver<SseEvent>,
+    execution_id: Uuid,
+) -> Option<JobExecution> {
+    loop {
+        let check_database = match status_rx.recv().await {
+            Ok(SseEvent::ExecutionStatusChanged {
+                execution_id: event_id,
+                status,
+                ..
+            }) if event_id == execution_id => status
+                .parse::<ExecutionStatus>()
+                .is_ok_and(|status| status.is_terminal()),
+            Ok(_) => false,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::debug!(skipped, "Status receiver lagged, checking execution");
+                true
+            }
+            Err(RecvError::Closed) => return None,
+        };
+
+        if !check_database {
+            continue;
+        }
+
+        match execution_repo.find_by_id(execution_id).await {
+            Ok(Some(execution)) if execution.status.is_terminal() => return Some(execution),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "Failed to load execution while waiting"),
+        }
+    }
+}
+
+/// Parse a `wait` duration: plain seconds or a number with an `s`/`m` suffix
+fn parse_wait(value: &str) -> Result<Duration, ErrorResponse> {
+    let invalid = || {
+        ErrorResponse::localized_with("validation_error", "job.invalid_wait", &[("wait", value)])
+    };
+
+    let value = value.trim();
+    let (number, multiplier) = if let Some(minutes) = value.strip_suffix('m') {
+        (minutes, 60)
+    } else if let Some(seconds) = value.strip_suffix('s') {
+        (seconds, 1)
+    } else {
+        (value, 1)
+    };
+
+    let amount: u64 = number.trim().parse().map_err(|_| invalid())?;
+    if amount == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(amount.saturating_mul(multiplier)))
+}
+
+/// Schedule a one-time execution of a job
+///
+/// The execution runs at `execute_at` with `variables` overriding the job's
+/// variables, and leaves the job's recurring schedule untouched.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn trigger_job_at(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<TriggerAtRequest>,
+) -> Result<Response, ErrorResponse> {
+    if req.execute_at <= Utc::now() {
+        return Err(ErrorResponse::localized(
+            "validation_error",
+            "job.execute_at_in_past",
+        ));
+    }
+    if let Some(callback) = &req.callback {
+        callback.resolve().await.map_err(|e| {
+            ErrorResponse::localized_with(
+                "validation_error",
+                "execution.invalid_callback",
+                &[("reason", &e.to_string())],
+            )
+        })?;
+    }
+
+    let repo = JobRepository::new(state.db_pool.clone());
+    let job = repo
+        .find_by_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    let variables = validate_trigger_variables(&state, id, req.variables).await?;
+    warn_if_deprecated(&job, &claims);
+
+    let trigger = ScheduledTrigger {
+        id: Uuid::new_v4(),
         job_id: id,
-        status: "pending".to_string(),
-    });
+        execute_at: req.execute_at,
+        variables,
+        callback: req
+            .callback
+            .as_ref()
+            .and_then(|callback| serde_json::to_value(callback).ok()),
+        status: ScheduledTriggerStatus::Pending,
+        execution_id: None,
+        error: None,
+        created_by: claims.username,
+        created_at: Utc::now(),
+        dispatched_at: None,
+    };
 
-    tracing::info!(job_id = %id, execution_id = %execution_id, "Job manually triggered");
-    Ok(Json(SuccessResponse::new(execution_id)))
+    ScheduledTriggerRepository::new(state.db_pool.clone())
+        .create(&trigger)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to schedule trigger: {}", e),
+            )
+        })?;
+
+    tracing::info!(
+        job_id = %id,
+        trigger_id = %trigger.id,
+        execute_at = %trigger.execute_at,
+        "One-time execution scheduled"
+    );
+
+    Ok(with_deprecation_headers(
+        &job,
+        (StatusCode::CREATED, Json(SuccessResponse::new(trigger))),
+    ))
+}
+
+/// List a job's one-time scheduled executions
+#[tracing::instrument(skip(state))]
+pub async fn list_scheduled_triggers(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<ScheduledTrigger>>>, ErrorResponse> {
+    let triggers = ScheduledTriggerRepository::new(state.db_pool.clone())
+        .find_by_job_id(id)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to list scheduled triggers: {}", e),
+            )
+        })?;
+
+    Ok(Json(SuccessResponse::new(triggers)))
+}
+
+/// Cancel a pending one-time scheduled execution
+#[tracing::instrument(skip(state))]
+pub async fn cancel_scheduled_trigger(
+    State(state): State<AppState>,
+    Path((id, trigger_id)): Path<(Uuid, Uuid)>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let cancelled = ScheduledTriggerRepository::new(state.db_pool.clone())
+        .cancel(id, trigger_id)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to cancel scheduled trigger: {}", e),
+            )
+        })?;
+
+    if !cancelled {
+        return Err(ErrorResponse::localized_with(
+            "not_found",
+            "job.scheduled_trigger_not_found",
+            &[("id", &trigger_id.to_string())],
+        ));
+    }
+
+    tracing::info!(job_id = %id, trigger_id = %trigger_id, "Scheduled trigger cancelled");
+    Ok(Json(SuccessResponse::new(())))
+}
+
+/// Add a blackout window during which the scheduler doesn't publish the job
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn create_blackout_window(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<CreateBlackoutWindowRequest>,
+) -> Result<Response, ErrorResponse> {
+    let name = req.name.trim();
+    if name.is_empty() || req.ends_at <= req.starts_at {
+        return Err(ErrorResponse::localized(
+            "validation_error",
+            "job.invalid_blackout_window",
+        ));
+    }
+
+    let job = find_job(&JobRepository::new(state.db_pool.clone()), id).await?;
+    ensure_can_edit(&state, &claims, &job).await?;
+
+    let window = BlackoutWindow {
+        id: Uuid::new_v4(),
+        job_id: id,
+        name: name.to_string(),
+        starts_at: req.starts_at,
+        ends_at: req.ends_at,
+        behavior: req.behavior,
+        deferred_trigger_id: None,
+        created_by: claims.username.clone(),
+        created_at: Utc::now(),
+    };
+
+    BlackoutWindowRepository::new(state.db_pool.clone())
+        .create(&window)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to create blackout window: {}", e),
+            )
+        })?;
+
+    tracing::info!(
+        job_id = %id,
+        window_id = %window.id,
+        username = %claims.username,
+        "Audit log: Blackout window created"
+    );
+
+    Ok((StatusCode::CREATED, Json(SuccessResponse::new(window))).into_response())
+}
+
+/// List a job's blackout windows
+#[tracing::instrument(skip(state))]
+pub async fn list_blackout_windows(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<BlackoutWindow>>>, ErrorResponse> {
+    let windows = BlackoutWindowRepository::new(state.db_pool.clone())
+        .find_by_job_id(id)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to list blackout windows: {}", e),
+            )
+        })?;
+
+    Ok(Json(SuccessResponse::new(windows)))
+}
+
+/// Delete a blackout window; a run already deferred to its end stays scheduled
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_blackout_window(
+    State(state): State<AppState>,
+    Path((id, window_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let job = find_job(&JobRepository::new(state.db_pool.clone()), id).await?;
+    ensure_can_edit(&state, &claims, &job).await?;
+
+    let deleted = BlackoutWindowRepository::new(state.db_pool.clone())
+        .delete(id, window_id)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to delete blackout window: {}", e),
+            )
+        })?;
+
+    if !deleted {
+        return Err(ErrorResponse::localized_with(
+            "not_found",
+            "job.blackout_window_not_found",
+            &[("id", &window_id.to_string())],
+        ));
+    }
+
+    tracing::info!(
+        job_id = %id,
+        window_id = %window_id,
+        username = %claims.username,
+        "Audit log: Blackout window deleted"
+    );
+    Ok(Json(SuccessResponse::new(())))
 }
 
 /// Enable a job
 ///
 /// Requirements: 6.6 - Resume scheduling executions
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, claims))]
 pub async fn enable_job(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
 ) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
     let repo = JobRepository::new(state.db_pool.clone());
 
@@ -532,7 +1272,24 @@ pub async fn enable_job(
         .find_by_id(id)
         .await
         .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Job not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    check_if_match(&headers, &job)?;
+    ensure_can_edit(&state, &claims, &job).await?;
+    if deprecation::is_past_sunset(&job, Utc::now()) {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "job.past_sunset",
+            &[
+                ("name", &job.name),
+                (
+                    "sunset_at",
+                    &job.sunset_at.map(|s| s.to_rfc3339()).unwrap_or_default(),
+                ),
+            ],
+        ));
+    }
 
     // Update enabled flag
     job.enabled = true;
@@ -544,9 +1301,11 @@ pub async fn enable_job(
     }
 
     // Save the updated job
-    repo.update(&job).await.map_err(|e| {
-        ErrorResponse::new("database_error", &format!("Failed to enable job: {}", e))
-    })?;
+    repo.update(&job)
+        .await
+        .map_err(|e| update_error(e, "Failed to enable job"))?;
+
+    state.publish_job_changed(id);
 
     // Broadcast SSE event
     state.broadcast_event(SseEvent::JobStatusChanged {
@@ -561,10 +1320,12 @@ pub async fn enable_job(
 /// Disable a job
 ///
 /// Requirements: 6.5 - Stop scheduling future executions
-#[tracing::instrument(skip(state))]
+#[tracing::instrument(skip(state, claims))]
 pub async fn disable_job(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
 ) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
     let repo = JobRepository::new(state.db_pool.clone());
 
@@ -573,7 +1334,11 @@ pub async fn disable_job(
         .find_by_id(id)
         .await
         .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Job not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    check_if_match(&headers, &job)?;
+    ensure_can_edit(&state, &claims, &job).await?;
 
     // Update enabled flag
     job.enabled = false;
@@ -585,9 +1350,11 @@ pub async fn disable_job(
     }
 
     // Save the updated job
-    repo.update(&job).await.map_err(|e| {
-        ErrorResponse::new("database_error", &format!("Failed to disable job: {}", e))
-    })?;
+    repo.update(&job)
+        .await
+        .map_err(|e| update_error(e, "Failed to disable job"))?;
+
+    state.publish_job_changed(id);
 
     // Broadcast SSE event
     state.broadcast_event(SseEvent::JobStatusChanged {
@@ -598,3 +1365,573 @@ pub async fn disable_job(
     tracing::info!(job_id = %id, "Job disabled");
     Ok(Json(SuccessResponse::new(())))
 }
+
+/// Pause a job's schedule
+///
+/// Unlike disabling, the job can still be triggered manually or by webhook.
+#[tracing::instrument(skip(state, claims))]
+pub async fn pause_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    set_job_paused(&state, &claims, id, &headers, true).await
+}
+
+/// Resume a paused job's schedule
+///
+/// Runs due while the job was paused are handled by its misfire policy.
+#[tracing::instrument(skip(state, claims))]
+pub async fn resume_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    set_job_paused(&state, &claims, id, &headers, false).await
+}
+
+async fn set_job_paused(
+    state: &AppState,
+    claims: &UserClaims,
+    id: Uuid,
+    headers: &HeaderMap,
+    paused: bool,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let repo = JobRepository::new(state.db_pool.clone());
+    let mut job = repo
+        .find_by_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", &format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })?;
+    check_if_match(headers, &job)?;
+    ensure_can_edit(state, claims, &job).await?;
+
+    job.paused = paused;
+    let (action, status) = if paused {
+        ("Failed to pause job", "paused")
+    } else {
+        ("Failed to resume job", "resumed")
+    };
+    repo.update(&job).await.map_err(|e| update_error(e, action))?;
+
+    state.publish_job_changed(id);
+
+    // Broadcast SSE event
+    state.broadcast_event(SseEvent::JobStatusChanged {
+        job_id: id,
+        status: status.to_string(),
+    });
+
+    tracing::info!(job_id = %id, paused, "Job {}", status);
+    Ok(Json(SuccessResponse::new(())))
+}
+
+/// Deprecate a job
+///
+/// The job keeps running until `sunset_at`, warning on every trigger and only
+/// accepting edits from its owners; the scheduler then disables it and notifies them.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn deprecate_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<DeprecateJobRequest>,
+) -> Result<Response, ErrorResponse> {
+    let reason = req
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|r| !r.is_empty());
+    deprecation::validate_deprecation(req.sunset_at, reason, Utc::now()).map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_deprecation",
+            &[("reason", &e.to_string())],
+        )
+    })?;
+
+    let repo = JobRepository::new(state.db_pool.clone());
+    let mut job = find_job(&repo, id).await?;
+    check_if_match(&headers, &job)?;
+    ensure_can_edit(&state, &claims, &job).await?;
+
+    job.version = repo
+        .set_deprecation(id, Some(req.sunset_at), reason)
+        .await
+        .map_err(|e| update_error(e, "Failed to deprecate job"))?;
+    job.deprecated = true;
+    job.sunset_at = Some(req.sunset_at);
+    job.deprecation_reason = reason.map(str::to_string);
+
+    state.broadcast_event(SseEvent::JobStatusChanged {
+        job_id: id,
+        status: "deprecated".to_string(),
+    });
+
+    tracing::info!(
+        job_id = %id,
+        username = %claims.username,
+        sunset_at = %req.sunset_at,
+        "Audit log: Job deprecated"
+    );
+    Ok(with_job_etag(job.version, Json(SuccessResponse::new(job))))
+}
+
+/// Remove a job's deprecation
+///
+/// A job already disabled at its sunset stays disabled until it is enabled again.
+#[tracing::instrument(skip(state, claims))]
+pub async fn undeprecate_job(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    Extension(claims): Extension<UserClaims>,
+) -> Result<Response, ErrorResponse> {
+    let repo = JobRepository::new(state.db_pool.clone());
+    let mut job = find_job(&repo, id).await?;
+    check_if_match(&headers, &job)?;
+    ensure_can_edit(&state, &claims, &job).await?;
+
+    job.version = repo
+        .set_deprecation(id, None, None)
+        .await
+        .map_err(|e| update_error(e, "Failed to remove job deprecation"))?;
+    job.deprecated = false;
+    job.sunset_at = None;
+    job.deprecation_reason = None;
+
+    state.broadcast_event(SseEvent::JobStatusChanged {
+        job_id: id,
+        status: "undeprecated".to_string(),
+    });
+
+    tracing::info!(
+        job_id = %id,
+        username = %claims.username,
+        "Audit log: Job deprecation removed"
+    );
+    Ok(with_job_etag(job.version, Json(SuccessResponse::new(job))))
+}
+
+async fn find_job(repo: &JobRepository, id: Uuid) -> Result<Job, ErrorResponse> {
+    repo.find_by_id(id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with("not_found", "job.not_found", &[("id", &id.to_string())])
+        })
+}
+
+/// Reject edits to a deprecated job from anyone but its owners or an admin
+async fn ensure_can_edit(
+    state: &AppState,
+    claims: &UserClaims,
+    job: &Job,
+) -> Result<(), ErrorResponse> {
+    if !job.deprecated {
+        return Ok(());
+    }
+
+    let is_admin = claims.permissions.contains(&"system:config".to_string());
+    // Keycloak subjects aren't local user ids; those users match by admin rights only
+    let email = match Uuid::parse_str(&claims.sub) {
+        Ok(user_id) => UserRepository::new(state.db_pool.clone())
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| {
+                ErrorResponse::new("database_error", format!("Failed to fetch user: {}", e))
+            })?
+            .and_then(|user| user.email),
+        Err(_) => None,
+    };
+
+    if deprecation::can_edit(job, email.as_deref(), is_admin) {
+        Ok(())
+    } else {
+        tracing::info!(job_id = %job.id, username = %claims.username, "Edit of deprecated job rejected");
+        Err(ErrorResponse::localized_with(
+            "forbidden",
+            "job.deprecated_owner_only",
+            &[("name", &job.name)],
+        ))
+    }
+}
+
+fn warn_if_deprecated(job: &Job, claims: &UserClaims) {
+    if job.deprecated {
+        tracing::warn!(
+            job_id = %job.id,
+            username = %claims.username,
+            sunset_at = ?job.sunset_at,
+            "Deprecated job triggered"
+        );
+    }
+}
+
+/// Attach `Deprecation`, `Sunset` and `Warning` headers when the job is deprecated
+fn with_deprecation_headers(job: &Job, body: impl IntoResponse) -> Response {
+    let mut response = body.into_response();
+    if !job.deprecated {
+        return response;
+    }
+
+    let headers = response.headers_mut();
+    headers.insert("deprecation", HeaderValue::from_static("true"));
+    let mut warning = format!("Job {} is deprecated", job.name);
+    if let Some(sunset_at) = job.sunset_at {
+        let http_date = sunset_at.format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        if let Ok(value) = HeaderValue::from_str(&http_date) {
+            headers.insert("sunset", value);
+        }
+        warning.push_str(&format!(
+            " and will be disabled at {}",
+            sunset_at.to_rfc3339()
+        ));
+    }
+    if let Some(reason) = &job.deprecation_reason {
+        warning.push_str(&format!(": {}", reason));
+    }
+    // Quotes and non-visible characters would break the quoted warn-text
+    let warning: String = warning
+        .chars()
+        .map(|c| {
+            if c == '"' || c == '\\' || !(' '..='~').contains(&c) {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    if let Ok(value) = HeaderValue::from_str(&format!("299 - \"{}\"", warning)) {
+        headers.insert(header::WARNING, value);
+    }
+    response
+}
+
+/// Validate job ownership metadata, surfacing the offending field
+fn validate_owner(owner: &JobOwner) -> Result<(), ErrorResponse> {
+    owner.validate().map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_owner",
+            &[("reason", &e.to_string())],
+        )
+    })
+}
+
+/// Reject a repeating interval schedule whose expression doesn't parse
+fn validate_schedule(schedule: &Schedule) -> Result<(), ErrorResponse> {
+    let Schedule::Iso8601 { expression, .. } = schedule else {
+        return Ok(());
+    };
+    RepeatingInterval::parse(expression)
+        .map(|_| ())
+        .map_err(|e| {
+            ErrorResponse::localized_with(
+                "validation_error",
+                "job.invalid_schedule",
+                &[("reason", &e.to_string())],
+            )
+        })
+}
+
+/// Reject a schedule naming a calendar that doesn't exist
+async fn ensure_calendar_exists(
+    state: &AppState,
+    schedule: &Schedule,
+) -> Result<(), ErrorResponse> {
+    let Some(calendar) = schedule.calendar() else {
+        return Ok(());
+    };
+    let found = CalendarRepository::new(state.db_pool.clone())
+        .find_by_name(&calendar.name)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new("database_error", format!("Failed to load calendar: {}", e))
+        })?;
+    if found.is_none() {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "job.unknown_calendar",
+            &[("name", &calendar.name)],
+        ));
+    }
+    Ok(())
+}
+
+/// Reject steps the worker couldn't run, such as an SFTP step without a remote path
+/// or an on_failure route to a step that doesn't follow
+fn validate_steps(steps: &[JobStep]) -> Result<(), ErrorResponse> {
+    let invalid = |e: ValidationError| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_step",
+            &[("reason", &e.to_string())],
+        )
+    };
+    for step in steps {
+        validate_sftp_step(step).map_err(invalid)?;
+    }
+    validate_step_flow(steps).map_err(invalid)
+}
+
+/// Reject steps naming a connection profile that doesn't exist
+async fn ensure_connections_exist(
+    state: &AppState,
+    steps: &[JobStep],
+) -> Result<(), ErrorResponse> {
+    let names: Vec<String> = connection_profile::referenced_connections(steps)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let found = ConnectionProfileRepository::new(state.db_pool.clone())
+        .find_by_names(&names)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to load connection profiles: {}", e),
+            )
+        })?;
+    if let Some(missing) = names
+        .iter()
+        .find(|name| !found.iter().any(|profile| &profile.name == *name))
+    {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "job.unknown_connection",
+            &[("name", missing)],
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a job's dead letter policy
+fn validate_dead_letter_policy(policy: &DeadLetterPolicy) -> Result<(), ErrorResponse> {
+    policy.validate().map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_dead_letter_policy",
+            &[("reason", &e.to_string())],
+        )
+    })
+}
+
+/// Validate a job's concurrency limit
+fn validate_concurrency_limit(limit: &ConcurrencyLimit) -> Result<(), ErrorResponse> {
+    limit.validate().map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_concurrency_limit",
+            &[("reason", &e.to_string())],
+        )
+    })
+}
+
+/// Validate a job's custom metric labels, surfacing the offending label
+fn validate_job_metric_labels(labels: &BTreeMap<String, String>) -> Result<(), ErrorResponse> {
+    validate_metric_labels(labels).map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_metric_labels",
+            &[("reason", &e.to_string())],
+        )
+    })
+}
+
+/// Validate a job's parameter matrix, surfacing the offending parameter
+///
+/// Parameters are set as variables of the runs, so like trigger variables they may
+/// not override a sensitive global or job-scoped variable.
+async fn validate_job_parameter_matrix(
+    state: &AppState,
+    job_id: Uuid,
+    matrix: &BTreeMap<String, Vec<String>>,
+) -> Result<(), ErrorResponse> {
+    let invalid = |e: ValidationError| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_parameter_matrix",
+            &[("reason", &e.to_string())],
+        )
+    };
+
+    validate_parameter_matrix(matrix).map_err(invalid)?;
+    if matrix.is_empty() {
+        return Ok(());
+    }
+    let sensitive = VariableRepository::new(state.db_pool.clone(), None)
+        .find_sensitive_names_for_job(job_id)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new(
+                "database_error",
+                format!("Failed to load job variables: {}", e),
+            )
+        })?;
+    check_sensitive_overrides(matrix.keys(), &sensitive).map_err(invalid)
+}
+
+/// Entity tag for a job version
+fn job_etag(version: i32) -> String {
+    format!("\"{}\"", version)
+}
+
+/// Attach the job version as the response `ETag`
+fn with_job_etag(version: i32, body: impl IntoResponse) -> Response {
+    let mut response = body.into_response();
+    if let Ok(value) = HeaderValue::from_str(&job_etag(version)) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+/// Parse the job version from an `If-Match` header
+///
+/// Returns `None` when the header is absent or `*`. Weak tags are accepted since
+/// the version is a semantic tag, not a hash of the response bytes.
+fn parse_if_match(headers: &HeaderMap) -> Result<Option<i32>, ErrorResponse> {
+    let Some(value) = headers.get(header::IF_MATCH) else {
+        return Ok(None);
+    };
+
+    let invalid = || ErrorResponse::localized("validation_error", "job.invalid_if_match");
+    let value = value.to_str().map_err(|_| invalid())?.trim();
+    if value == "*" {
+        return Ok(None);
+    }
+
+    value
+        .trim_start_matches("W/")
+        .trim_matches('"')
+        .parse::<i32>()
+        .map(Some)
+        .map_err(|_| invalid())
+}
+
+/// Reject the request with 409 if `If-Match` names a different version than the stored job
+fn check_if_match(headers: &HeaderMap, job: &Job) -> Result<(), ErrorResponse> {
+    match parse_if_match(headers)? {
+        Some(expected) if expected != job.version => {
+            tracing::info!(job_id = %job.id, expected, current = job.version, "Job version conflict");
+            Err(version_conflict(job.version))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn version_conflict(current: i32) -> ErrorResponse {
+    ErrorResponse::localized("conflict", "job.version_conflict")
+        .with_details(serde_json::json!({ "current_version": current }))
+}
+
+/// Map a job update error, surfacing concurrent modifications as 409
+fn update_error(error: DatabaseError, context: &str) -> ErrorResponse {
+    match error {
+        // The job changed between our read and write
+        DatabaseError::VersionConflict(_) => {
+            ErrorResponse::localized("conflict", "job.version_conflict")
+        }
+        e => ErrorResponse::new("database_error", format!("{}: {}", context, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers_with_if_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_parse_if_match_accepts_job_etags() {
+        assert_eq!(parse_if_match(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(parse_if_match(&headers_with_if_match("*")).unwrap(), None);
+        assert_eq!(
+            parse_if_match(&headers_with_if_match(&job_etag(3))).unwrap(),
+            Some(3)
+        );
+        assert_eq!(
+            parse_if_match(&headers_with_if_match("W/\"7\"")).unwrap(),
+            Some(7)
+        );
+        assert_eq!(
+            parse_if_match(&headers_with_if_match("12")).unwrap(),
+            Some(12)
+        );
+    }
+
+    #[test]
+    fn test_parse_wait_durations() {
+        assert_eq!(parse_wait("60s").unwrap(), Duration::from_secs(60));
+        assert_eq!(parse_wait("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(parse_wait("45").unwrap(), Duration::from_secs(45));
+        assert!(parse_wait("0s").is_err());
+        assert!(parse_wait("soon").is_err());
+        assert!(parse_wait("1h").is_err());
+    }
+
+    #[test]
+    fn test_parse_if_match_rejects_garbage() {
+        let error = parse_if_match(&headers_with_if_match("\"abc\"")).unwrap_err();
+        assert_eq!(error.error, "validation_error");
+    }
+
+    #[test]
+    fn test_update_request_clears_owner_with_null() {
+        let cleared: UpdateJobRequest =
+            serde_json::from_value(serde_json::json!({ "owner": null })).unwrap();
+        assert!(matches!(cleared.owner, Some(None)));
+
+        let unchanged: UpdateJobRequest = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(unchanged.owner.is_none());
+    }
+
+    #[test]
+    fn test_apply_definition_to_backfilled_version() {
+        // Shape of the snapshots the job_versions migration builds from existing rows
+        let definition = serde_json::json!({
+            "schedule": null,
+            "steps": [],
+            "triggers": { "scheduled": false, "manual": true, "webhook": null }
+        });
+        let mut job: Job = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "name": "daily-report",
+            "description": null,
+            "schedule": null,
+            "steps": [],
+            "triggers": { "scheduled": true, "manual": true, "webhook": null },
+            "enabled": true,
+            "timeout_seconds": 300,
+            "max_retries": 10,
+            "allow_concurrent": false,
+            "collision_policy": "skip",
+            "owner": null,
+            "metric_labels": {},
+            "parameter_matrix": {},
+            "definition": definition,
+            "version": 4,
+            "deprecated": false,
+            "sunset_at": null,
+            "deprecation_reason": null,
+            "tenant_id": null,
+            "created_at": "2024-04-01T08:00:00+07:00",
+            "updated_at": "2024-05-01T00:00:00+00:00"
+        }))
+        .unwrap();
+
+        apply_definition(&mut job, &definition);
+        assert_eq!(job.version, 4);
+        assert!(!job.triggers.scheduled);
+        assert!(job.schedule.is_none());
+    }
+}
@@ -1,13 +1,19 @@
 use axum::{
     extract::{Query, State},
-    response::{Html, IntoResponse, Redirect},
+    http::{header, HeaderMap},
+    response::{AppendHeaders, Html, IntoResponse, Redirect, Response},
     Form,
 };
-use common::auth::{DatabaseAuthService, JwtService};
+use axum_extra::extract::cookie::{Cookie, SameSite};
+use common::auth::DatabaseAuthService;
+use common::db::repositories::session::SessionRepository;
 use common::db::repositories::user::UserRepository;
+use common::i18n;
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use crate::handlers::auth::{login_client, stored_locale};
+use crate::middleware::locale::{current_locale, LOCALE_COOKIE};
 use crate::state::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -30,7 +36,12 @@ pub async fn login_page(
     State(state): State<AppState>,
     Query(params): Query<LoginQuery>,
 ) -> impl IntoResponse {
-    let error_message = params.error.unwrap_or_default();
+    // Errors are passed as catalog keys, so only our own messages are shown
+    let error_message = params
+        .error
+        .filter(|key| i18n::has_message(key))
+        .map(|key| i18n::translate(current_locale(), &key).to_string())
+        .unwrap_or_default();
     let auth_mode = match &state.config.auth.mode {
         common::config::AuthMode::Database => "Database",
         common::config::AuthMode::Keycloak => "Keycloak",
@@ -488,31 +499,36 @@ pub async fn login_page(
 
 /// Handle form-based login (for no-JS fallback)
 /// Requirements: 19.11, 19.12 - Graceful degradation without JavaScript
-#[tracing::instrument(skip(state, form))]
+#[tracing::instrument(skip(state, headers, form))]
 pub async fn login_form_submit(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Form(form): Form<LoginFormData>,
-) -> impl IntoResponse {
+) -> Response {
     // Validate input
     if form.username.trim().is_empty() {
-        return Redirect::to("/?error=Username%20is%20required");
+        return Redirect::to("/?error=auth.username_required").into_response();
     }
 
     if form.password.is_empty() {
-        return Redirect::to("/?error=Password%20is%20required");
+        return Redirect::to("/?error=auth.password_required").into_response();
     }
 
-    // Create JWT service from config
-    let jwt_secret = &state.config.auth.jwt_secret;
-    let jwt_expiry_hours = state.config.auth.jwt_expiration_hours;
-    let jwt_service = JwtService::new(jwt_secret, jwt_expiry_hours);
+    let jwt_service = state.jwt_service();
 
     // Create user repository and auth service
     let user_repository = UserRepository::new(state.db_pool.clone());
-    let auth_service = DatabaseAuthService::new(jwt_service, user_repository);
+    let auth_service = DatabaseAuthService::new(
+        jwt_service,
+        user_repository.clone(),
+        SessionRepository::new(state.db_pool.clone()),
+    );
 
     // Authenticate user
-    match auth_service.login(&form.username, &form.password).await {
+    match auth_service
+        .login(&form.username, &form.password, &login_client(&headers))
+        .await
+    {
         Ok(token) => {
             tracing::info!(
                 username = %form.username,
@@ -523,10 +539,26 @@ pub async fn login_form_submit(
             // For now, redirect to a page that sets the token via JS
             // Simple URL encoding for token (replace special chars)
             let encoded_token = token.replace('&', "%26").replace('=', "%3D");
-            Redirect::to(&format!(
+            let redirect = Redirect::to(&format!(
                 "/auth/set-token?token={}&redirect=/dashboard",
                 encoded_token
-            ))
+            ));
+
+            // Carry the user's preferred locale over to the dashboard
+            match stored_locale(&user_repository, &form.username).await {
+                Some(locale) => {
+                    let locale_cookie = Cookie::build((LOCALE_COOKIE, locale.as_tag()))
+                        .path("/")
+                        .same_site(SameSite::Lax)
+                        .build();
+                    (
+                        AppendHeaders([(header::SET_COOKIE, locale_cookie.to_string())]),
+                        redirect,
+                    )
+                        .into_response()
+                }
+                None => redirect.into_response(),
+            }
         }
         Err(e) => {
             tracing::warn!(
@@ -535,14 +567,12 @@ pub async fn login_form_submit(
                 "Form-based login failed"
             );
 
-            let error_msg = match e {
-                common::errors::AuthError::InvalidCredentials => "Invalid username or password",
-                _ => "Authentication failed. Please try again.",
+            let error_key = match e {
+                common::errors::AuthError::InvalidCredentials => "auth.invalid_credentials",
+                common::errors::AuthError::AccountDisabled => "auth.account_disabled",
+                _ => "auth.failed",
             };
-
-            // Simple URL encoding for error message
-            let encoded_error = error_msg.replace(' ', "%20").replace('&', "%26");
-            Redirect::to(&format!("/?error={}", encoded_error))
+            Redirect::to(&format!("/?error={}", error_key)).into_response()
         }
     }
 }
@@ -1,15 +1,38 @@
 pub mod auth;
+pub mod backfill;
+pub mod backup;
+pub mod calendars;
+pub mod capabilities;
+pub mod connection_profiles;
+pub mod credentials;
 pub mod dashboard;
+pub mod dashboard_layout;
+pub mod erasure;
+pub mod execution_filters;
 pub mod executions;
+pub mod failover;
+pub mod failures;
+pub mod feature_flags;
 pub mod health;
 pub mod import_export;
 pub mod index;
+pub mod job_state;
 pub mod jobs;
 pub mod login;
 pub mod metrics;
+pub mod permissions;
+pub mod queue;
+pub mod rate_limits;
+pub mod scheduler_control;
+pub mod scheduler_journal;
+pub mod schedules;
+pub mod sessions;
 pub mod sse;
+pub mod status_pages;
+pub mod usage;
 pub mod users;
 pub mod variables;
+pub mod webhook_deliveries;
 pub mod webhooks;
 
 // Common response types
@@ -18,15 +41,18 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::middleware::current_locale;
 
 /// Standard API error response
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
+    /// Boxed to keep `Result<_, ErrorResponse>` small
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
+    pub details: Option<Box<serde_json::Value>>,
     pub trace_id: String,
 }
 
@@ -40,9 +66,22 @@ impl ErrorResponse {
         }
     }
 
-    #[allow(dead_code)]
+    /// Build an error whose message is looked up in the i18n catalog
+    /// using the locale of the current request
+    pub fn localized(error: impl Into<String>, key: &str) -> Self {
+        Self::new(error, common::i18n::translate(current_locale(), key))
+    }
+
+    /// Like `localized`, substituting `{name}` placeholders in the message
+    pub fn localized_with(error: impl Into<String>, key: &str, args: &[(&str, &str)]) -> Self {
+        Self::new(
+            error,
+            common::i18n::translate_with(current_locale(), key, args),
+        )
+    }
+
     pub fn with_details(mut self, details: serde_json::Value) -> Self {
-        self.details = Some(details);
+        self.details = Some(Box::new(details));
         self
     }
 }
@@ -55,6 +94,7 @@ impl IntoResponse for ErrorResponse {
             "not_found" => StatusCode::NOT_FOUND,
             "validation_error" => StatusCode::BAD_REQUEST,
             "conflict" => StatusCode::CONFLICT,
+            "idempotency_key_mismatch" => StatusCode::UNPROCESSABLE_ENTITY,
             "rate_limit_exceeded" => StatusCode::TOO_MANY_REQUESTS,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         };
@@ -63,6 +103,21 @@ impl IntoResponse for ErrorResponse {
     }
 }
 
+/// Parse an optional JSON body: an empty body is the default request and a
+/// malformed one a validation error, rather than silently the default
+pub fn optional_json<T: DeserializeOwned + Default>(body: &[u8]) -> Result<T, ErrorResponse> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(T::default());
+    }
+    serde_json::from_slice(body).map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "error.invalid_json",
+            &[("reason", &e.to_string())],
+        )
+    })
+}
+
 /// Standard API success response
 #[derive(Debug, Serialize)]
 pub struct SuccessResponse<T: Serialize> {
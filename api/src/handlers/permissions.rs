@@ -0,0 +1,12 @@
+use axum::Json;
+
+use crate::handlers::SuccessResponse;
+use crate::permissions::{RoutePermission, ROUTE_PERMISSIONS};
+
+/// List the permissions required by every protected endpoint
+///
+/// Generated from the registry the RBAC middleware enforces, so it can't drift.
+#[tracing::instrument]
+pub async fn list_permissions() -> Json<SuccessResponse<&'static [RoutePermission]>> {
+    Json(SuccessResponse::new(ROUTE_PERMISSIONS))
+}
@@ -0,0 +1,156 @@
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use common::models::{JobPriority, UserClaims};
+use common::queue::inspect::{PeekedMessage, PurgeResult, QueueStats, MAX_PEEK_MESSAGES};
+use common::queue::nats::shared_job_subject;
+use common::queue::{NatsClient, NatsConfig, QueueInspector};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Messages returned by a peek when no limit is given
+const DEFAULT_PEEK_MESSAGES: usize = 20;
+
+/// Query parameters for peeking at queued messages
+#[derive(Debug, Deserialize)]
+pub struct PeekQuery {
+    /// First stream sequence to read (defaults to the oldest message)
+    pub start: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// Request to purge the job stream
+///
+/// `confirm` must repeat the stream name so a purge can't be sent by accident.
+/// With `job_id`, only that job's queued messages are removed.
+#[derive(Debug, Deserialize)]
+pub struct PurgeQueueRequest {
+    pub confirm: String,
+    pub job_id: Option<Uuid>,
+}
+
+fn nats_client(state: &AppState) -> NatsClient {
+    let config = NatsConfig {
+        url: state.config.nats.url.clone(),
+        stream_name: state.config.nats.stream_name.clone(),
+        consumer_name: state.config.nats.consumer_name.clone(),
+        tenant_streams: state.config.nats.tenant_streams.clone(),
+        shards: state.config.nats.shards,
+        ..NatsConfig::default()
+    };
+    NatsClient::from_client(state.nats_client.clone(), config)
+}
+
+/// Show JetStream stream and consumer stats (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn get_queue_stats(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<QueueStats>>, ErrorResponse> {
+    let client = nats_client(&state);
+    let stats = QueueInspector::new(&client).stats().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to read queue stats");
+        ErrorResponse::new("queue_error", e.to_string())
+    })?;
+
+    Ok(Json(SuccessResponse::new(stats)))
+}
+
+/// Read queued messages without consuming them (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn peek_queue_messages(
+    State(state): State<AppState>,
+    Query(query): Query<PeekQuery>,
+) -> Result<Json<SuccessResponse<Vec<PeekedMessage>>>, ErrorResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_PEEK_MESSAGES);
+    if limit == 0 || limit > MAX_PEEK_MESSAGES {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "queue.invalid_peek_limit",
+            &[("max", &MAX_PEEK_MESSAGES.to_string())],
+        ));
+    }
+
+    let client = nats_client(&state);
+    let messages = QueueInspector::new(&client)
+        .peek(query.start, limit)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to peek queue messages");
+            ErrorResponse::new("queue_error", e.to_string())
+        })?;
+
+    Ok(Json(SuccessResponse::new(messages)))
+}
+
+/// Purge the job stream, or one job's messages (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn purge_queue(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<PurgeQueueRequest>,
+) -> Result<Json<SuccessResponse<PurgeResult>>, ErrorResponse> {
+    let stream_name = &state.config.nats.stream_name;
+    if req.confirm != *stream_name {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "queue.purge_not_confirmed",
+            &[("stream", stream_name)],
+        ));
+    }
+
+    // A job's messages sit in its current priority lane or, after a priority
+    // change, in another one, so a job purge covers every lane
+    let nats = &state.config.nats;
+    let subjects: Vec<Option<String>> = match req.job_id {
+        Some(job_id) if nats.priority_subjects => JobPriority::ALL
+            .iter()
+            .map(|priority| {
+                Some(shared_job_subject(
+                    stream_name,
+                    &job_id,
+                    nats.shards,
+                    Some(*priority),
+                ))
+            })
+            .collect(),
+        Some(job_id) => vec![Some(shared_job_subject(
+            stream_name,
+            &job_id,
+            nats.shards,
+            None,
+        ))],
+        None => vec![None],
+    };
+    let client = nats_client(&state);
+    let inspector = QueueInspector::new(&client);
+    let mut result = PurgeResult {
+        purged: 0,
+        subject: None,
+    };
+    for subject in &subjects {
+        let purged = inspector.purge(subject.as_deref()).await.map_err(|e| {
+            tracing::error!(error = %e, "Failed to purge queue");
+            ErrorResponse::new("queue_error", e.to_string())
+        })?;
+        result.purged += purged.purged;
+        result.subject = match (result.subject, purged.subject) {
+            (Some(previous), Some(subject)) => Some(format!("{}, {}", previous, subject)),
+            (previous, subject) => previous.or(subject),
+        };
+    }
+
+    tracing::warn!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        stream = %stream_name,
+        subject = ?result.subject,
+        purged = result.purged,
+        "Audit log: Job queue purged"
+    );
+
+    Ok(Json(SuccessResponse::new(result)))
+}
@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use common::db::repositories::RateLimitPolicyRepository;
+use common::models::{RateLimitPolicy, RateLimitScope};
+use serde::Deserialize;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::middleware::rate_limit::hash_api_key;
+use crate::state::AppState;
+
+/// Request to create or replace a rate limit policy
+///
+/// For the `api_key` scope, `identity` is the API key itself; only its
+/// SHA-256 hash is stored and returned.
+#[derive(Debug, Deserialize)]
+pub struct UpsertRateLimitRequest {
+    pub scope: RateLimitScope,
+    pub identity: String,
+    pub requests_per_minute: i32,
+    pub burst: i32,
+}
+
+/// List all rate limit policies (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_rate_limits(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<RateLimitPolicy>>>, ErrorResponse> {
+    let repository = RateLimitPolicyRepository::new(state.db_pool.clone());
+
+    let policies = repository.find_all().await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to list rate limit policies");
+        ErrorResponse::new("database_error", "Failed to list rate limit policies")
+    })?;
+
+    Ok(Json(SuccessResponse::new(policies)))
+}
+
+/// Create or replace a rate limit policy (admin only)
+#[tracing::instrument(skip(state, req))]
+pub async fn upsert_rate_limit(
+    State(state): State<AppState>,
+    Json(req): Json<UpsertRateLimitRequest>,
+) -> Result<Json<SuccessResponse<RateLimitPolicy>>, ErrorResponse> {
+    let identity = req.identity.trim();
+    if identity.is_empty() {
+        return Err(ErrorResponse::new(
+            "validation_error",
+            "Rate limit identity cannot be empty",
+        ));
+    }
+
+    if req.requests_per_minute <= 0 || req.burst <= 0 {
+        return Err(ErrorResponse::new(
+            "validation_error",
+            "requests_per_minute and burst must be greater than 0",
+        ));
+    }
+
+    let identity = match req.scope {
+        RateLimitScope::ApiKey => hash_api_key(identity),
+        RateLimitScope::User | RateLimitScope::Tenant => identity.to_string(),
+    };
+
+    let repository = RateLimitPolicyRepository::new(state.db_pool.clone());
+    let policy = repository
+        .upsert(req.scope, &identity, req.requests_per_minute, req.burst)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to save rate limit policy");
+            ErrorResponse::new("database_error", "Failed to save rate limit policy")
+        })?;
+    state.rate_limit_policies.invalidate(req.scope, &identity);
+
+    Ok(Json(SuccessResponse::new(policy)))
+}
+
+/// Delete a rate limit policy so the identity falls back to the defaults (admin only)
+///
+/// `identity` is the stored value as returned by the list endpoint.
+#[tracing::instrument(skip(state))]
+pub async fn delete_rate_limit(
+    State(state): State<AppState>,
+    Path((scope, identity)): Path<(String, String)>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let scope = scope
+        .parse::<RateLimitScope>()
+        .map_err(|e| ErrorResponse::new("validation_error", e))?;
+
+    let repository = RateLimitPolicyRepository::new(state.db_pool.clone());
+    let deleted = repository.delete(scope, &identity).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to delete rate limit policy");
+        ErrorResponse::new("database_error", "Failed to delete rate limit policy")
+    })?;
+
+    state.rate_limit_policies.invalidate(scope, &identity);
+
+    if !deleted {
+        return Err(ErrorResponse::new(
+            "not_found",
+            format!("Rate limit policy not found: {}/{}", scope, identity),
+        ));
+    }
+
+    Ok(Json(SuccessResponse::new(())))
+}
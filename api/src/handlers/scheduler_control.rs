@@ -0,0 +1,185 @@
+use axum::{extract::State, http::StatusCode, Extension, Json};
+use common::db::repositories::{SchedulerDrainRepository, SchedulerJournalRepository};
+use common::models::UserClaims;
+use common::scheduler::{
+    SchedulerCommand, SchedulerControl, SchedulerControlAck, SCHEDULER_CONTROL_ACK_TIMEOUT,
+    SCHEDULER_CONTROL_SUBJECT,
+};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Which scheduler nodes a drain or resume applies to
+#[derive(Debug, Default, Deserialize)]
+pub struct SchedulerControlRequest {
+    /// Node id as recorded in the scheduler journal; every node when left out
+    #[serde(default)]
+    pub node: Option<String>,
+}
+
+/// A stored drain or resume and the nodes that applied it at once
+#[derive(Debug, Serialize)]
+pub struct SchedulerControlResult {
+    #[serde(flatten)]
+    pub control: SchedulerControl,
+    /// Nodes that acknowledged the control message; the others apply it on their
+    /// next tick
+    pub acknowledged_by: Vec<SchedulerControlAck>,
+}
+
+/// Drain the schedulers before a deploy or maintenance (admin only)
+///
+/// Drained schedulers stop publishing new executions but keep polling, journaling
+/// `draining` ticks and reporting readiness. The drain is stored, so restarted
+/// schedulers stay drained until resumed.
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn drain_scheduler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    req: Option<Json<SchedulerControlRequest>>,
+) -> Result<(StatusCode, Json<SuccessResponse<SchedulerControlResult>>), ErrorResponse> {
+    send_control(&state, &claims, SchedulerCommand::Drain, req).await
+}
+
+/// Let drained schedulers publish new executions again (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn resume_scheduler(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    req: Option<Json<SchedulerControlRequest>>,
+) -> Result<(StatusCode, Json<SuccessResponse<SchedulerControlResult>>), ErrorResponse> {
+    send_control(&state, &claims, SchedulerCommand::Resume, req).await
+}
+
+/// Store a drain or resume, then ask the scheduler nodes to apply it at once
+///
+/// Responds 200 once a targeted node, or any node, acknowledged the message and 202
+/// when none did within the ack timeout.
+async fn send_control(
+    state: &AppState,
+    claims: &UserClaims,
+    command: SchedulerCommand,
+    req: Option<Json<SchedulerControlRequest>>,
+) -> Result<(StatusCode, Json<SuccessResponse<SchedulerControlResult>>), ErrorResponse> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    let database_error = |e: common::errors::DatabaseError| {
+        tracing::error!(error = %e, "Failed to update scheduler drains");
+        ErrorResponse::localized_with(
+            "database_error",
+            "scheduler.drain_not_saved",
+            &[("reason", &e.to_string())],
+        )
+    };
+
+    let drains = SchedulerDrainRepository::new(state.db_pool.clone());
+    if let Some(node) = req.node.as_deref() {
+        if node.trim().is_empty() {
+            return Err(ErrorResponse::localized(
+                "validation_error",
+                "scheduler.empty_node",
+            ));
+        }
+        let known = SchedulerJournalRepository::new(state.db_pool.clone())
+            .has_node(node)
+            .await
+            .map_err(database_error)?;
+        if !known {
+            return Err(ErrorResponse::localized_with(
+                "not_found",
+                "scheduler.node_not_found",
+                &[("node", node)],
+            ));
+        }
+        // A node can't be resumed on its own out of a drain of every node
+        if command == SchedulerCommand::Resume
+            && drains.all_drained().await.map_err(database_error)?
+        {
+            return Err(ErrorResponse::localized(
+                "conflict",
+                "scheduler.all_nodes_drained",
+            ));
+        }
+    }
+
+    match command {
+        SchedulerCommand::Drain => drains
+            .drain(req.node.as_deref(), &claims.username)
+            .await
+            .map_err(database_error)?,
+        SchedulerCommand::Resume => drains
+            .resume(req.node.as_deref())
+            .await
+            .map_err(database_error)?,
+    }
+
+    let control = SchedulerControl {
+        command,
+        node: req.node,
+    };
+    tracing::warn!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        command = ?control.command,
+        node = control.node.as_deref().unwrap_or("all"),
+        "Audit log: Scheduler drain changed"
+    );
+
+    // The change is stored; nodes that miss the message apply it on their next tick
+    let acknowledged_by = match request_acks(state, &control).await {
+        Ok(acks) => acks,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to send scheduler control message");
+            Vec::new()
+        }
+    };
+    let status = if acknowledged_by.is_empty() {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((
+        status,
+        Json(SuccessResponse::new(SchedulerControlResult {
+            control,
+            acknowledged_by,
+        })),
+    ))
+}
+
+/// Publish the control message with a reply inbox and collect the nodes' acks
+/// until the targeted node answers or the ack timeout passes
+async fn request_acks(
+    state: &AppState,
+    control: &SchedulerControl,
+) -> Result<Vec<SchedulerControlAck>, Box<dyn std::error::Error + Send + Sync>> {
+    let client = &state.nats_client;
+    let inbox = client.new_inbox();
+    let mut replies = client.subscribe(inbox.clone()).await?;
+    client
+        .publish_with_reply(
+            SCHEDULER_CONTROL_SUBJECT,
+            inbox,
+            serde_json::to_vec(control)?.into(),
+        )
+        .await?;
+    client.flush().await?;
+
+    let mut acks = Vec::new();
+    let deadline = tokio::time::Instant::now() + SCHEDULER_CONTROL_ACK_TIMEOUT;
+    while let Ok(Some(reply)) = tokio::time::timeout_at(deadline, replies.next()).await {
+        match serde_json::from_slice::<SchedulerControlAck>(&reply.payload) {
+            Ok(ack) => {
+                let targeted = control.node.as_deref() == Some(ack.node.as_str());
+                acks.push(ack);
+                if targeted {
+                    break;
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Ignoring malformed scheduler control ack"),
+        }
+    }
+    Ok(acks)
+}
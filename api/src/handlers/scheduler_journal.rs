@@ -0,0 +1,70 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use common::db::repositories::scheduler_journal::{
+    SchedulerJournalFilter, SchedulerJournalRepository,
+};
+use common::models::SchedulerTick;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Ticks listed when no limit is given
+const DEFAULT_LIST_LIMIT: i64 = 100;
+
+/// Most ticks a single list request returns
+const MAX_LIST_LIMIT: i64 = 1000;
+
+/// Query parameters for reading the scheduler journal
+#[derive(Debug, Deserialize)]
+pub struct ListTicksQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Only the decisions about this job, plus ticks that evaluated no jobs
+    pub job_id: Option<Uuid>,
+    /// Only ticks of this scheduler node
+    pub node: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// List scheduler ticks with their per-job decisions, most recent first (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_scheduler_ticks(
+    State(state): State<AppState>,
+    Query(query): Query<ListTicksQuery>,
+) -> Result<Json<SuccessResponse<Vec<SchedulerTick>>>, ErrorResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+        return Err(ErrorResponse::new(
+            "validation_error",
+            format!("limit must be between 1 and {}", MAX_LIST_LIMIT),
+        ));
+    }
+
+    let filter = SchedulerJournalFilter {
+        from: query.from,
+        to: query.to,
+        job_id: query.job_id,
+        node: query.node,
+        limit,
+    };
+    let mut ticks = SchedulerJournalRepository::new(state.db_pool.clone())
+        .list(&filter)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list scheduler journal");
+            ErrorResponse::new("database_error", "Failed to retrieve scheduler journal")
+        })?;
+
+    if let Some(job_id) = filter.job_id {
+        for tick in &mut ticks {
+            tick.decisions.retain(|decision| decision.job_id == job_id);
+        }
+    }
+
+    Ok(Json(SuccessResponse::new(ticks)))
+}
@@ -0,0 +1,62 @@
+use axum::{extract::Query, Json};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use common::schedule::{
+    default_timezone, describe_cron_expression, expand_cron_macro, parse_cron_expression,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+
+/// Upcoming runs listed with a description
+const PREVIEW_RUNS: usize = 5;
+
+/// Query parameters for describing a cron expression
+#[derive(Debug, Deserialize)]
+pub struct DescribeScheduleQuery {
+    /// Cron expression or macro such as `@daily` or `@every 5m`
+    pub expression: String,
+    /// Timezone the upcoming runs are computed in; Asia/Ho_Chi_Minh when left out
+    pub timezone: Option<Tz>,
+}
+
+/// What a cron expression means, for the job form
+#[derive(Debug, Serialize)]
+pub struct ScheduleDescription {
+    pub expression: String,
+    /// The expression with any macro expanded
+    pub cron: String,
+    pub description: String,
+    pub next_runs: Vec<DateTime<Utc>>,
+}
+
+/// Describe a cron expression in words with its next runs
+#[tracing::instrument]
+pub async fn describe_schedule(
+    Query(query): Query<DescribeScheduleQuery>,
+) -> Result<Json<SuccessResponse<ScheduleDescription>>, ErrorResponse> {
+    let invalid = |e: common::errors::ScheduleError| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "job.invalid_schedule",
+            &[("reason", &e.to_string())],
+        )
+    };
+    let schedule = parse_cron_expression(&query.expression).map_err(invalid)?;
+    let description = describe_cron_expression(&query.expression).map_err(invalid)?;
+    let cron = expand_cron_macro(&query.expression).map_err(invalid)?;
+
+    let timezone = query.timezone.unwrap_or_else(default_timezone);
+    let next_runs = schedule
+        .after(&Utc::now().with_timezone(&timezone))
+        .take(PREVIEW_RUNS)
+        .map(|run| run.with_timezone(&Utc))
+        .collect();
+
+    Ok(Json(SuccessResponse::new(ScheduleDescription {
+        expression: query.expression,
+        cron,
+        description,
+        next_runs,
+    })))
+}
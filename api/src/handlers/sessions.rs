@@ -0,0 +1,107 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use common::db::repositories::SessionRepository;
+use common::models::{UserClaims, UserSession};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Query parameters for listing sessions
+#[derive(Debug, Deserialize)]
+pub struct ListSessionsQuery {
+    /// Whose sessions to list; defaults to the caller (others need `user:manage`)
+    pub user_id: Option<Uuid>,
+}
+
+/// An active session, marked if the request was made with it
+#[derive(Debug, Serialize)]
+pub struct SessionResponse {
+    #[serde(flatten)]
+    pub session: UserSession,
+    pub current: bool,
+}
+
+/// List active sessions of the caller, or of any user for admins
+#[tracing::instrument(skip(state, claims))]
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Query(params): Query<ListSessionsQuery>,
+) -> Result<Json<SuccessResponse<Vec<SessionResponse>>>, ErrorResponse> {
+    let user_id = match params.user_id {
+        Some(user_id) => user_id,
+        None => claims
+            .sub
+            .parse::<Uuid>()
+            .map_err(|_| ErrorResponse::localized("validation_error", "session.not_tracked"))?,
+    };
+    ensure_own_or_admin(&claims, user_id)?;
+
+    let sessions = SessionRepository::new(state.db_pool.clone())
+        .list_active(user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, user_id = %user_id, "Failed to list sessions");
+            ErrorResponse::new("database_error", "Failed to list sessions")
+        })?;
+
+    let sessions = sessions
+        .into_iter()
+        .map(|session| SessionResponse {
+            current: claims.sid == Some(session.id),
+            session,
+        })
+        .collect();
+
+    Ok(Json(SuccessResponse::new(sessions)))
+}
+
+/// Revoke a session; its tokens are rejected from the next request on. Users can
+/// revoke their own sessions, admins anyone's
+#[tracing::instrument(skip(state, claims))]
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let repo = SessionRepository::new(state.db_pool.clone());
+    let session = repo
+        .find(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, session_id = %id, "Failed to load session");
+            ErrorResponse::new("database_error", "Failed to load session")
+        })?
+        .filter(UserSession::is_active)
+        .ok_or_else(|| ErrorResponse::localized("not_found", "session.not_found"))?;
+    ensure_own_or_admin(&claims, session.user_id)?;
+
+    repo.revoke(id).await.map_err(|e| {
+        tracing::error!(error = %e, session_id = %id, "Failed to revoke session");
+        ErrorResponse::new("database_error", "Failed to revoke session")
+    })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        session_id = %id,
+        session_user_id = %session.user_id,
+        ip_address = ?session.ip_address,
+        "Audit log: session revoked"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+fn ensure_own_or_admin(claims: &UserClaims, user_id: Uuid) -> Result<(), ErrorResponse> {
+    let is_own = claims.sub == user_id.to_string();
+    let is_admin = claims.permissions.contains(&"user:manage".to_string());
+    if !is_own && !is_admin {
+        return Err(ErrorResponse::localized("forbidden", "session.own_only"));
+    }
+    Ok(())
+}
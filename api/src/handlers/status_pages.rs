@@ -0,0 +1,309 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{Html, IntoResponse},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use common::db::repositories::StatusPageRepository;
+use common::models::UserClaims;
+use common::status_page::{
+    generate_token, hash_token, validate_status_page, PublicJobStatus, StatusPage,
+};
+use serde::{Deserialize, Serialize};
+use tera::Context;
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+use crate::templates::TEMPLATES;
+
+/// How long partners' browsers and proxies may cache a public status page
+const PUBLIC_CACHE_CONTROL: &str = "public, max-age=60";
+
+/// Request to create or change a status page
+#[derive(Debug, Deserialize)]
+pub struct SaveStatusPageRequest {
+    pub name: String,
+    /// Jobs to show, in display order
+    pub job_ids: Vec<Uuid>,
+}
+
+/// A status page with its token; only returned when the token is created or rotated
+#[derive(Debug, Serialize)]
+pub struct StatusPageWithToken {
+    #[serde(flatten)]
+    pub page: StatusPage,
+    pub token: String,
+    /// Path of the HTML page; the JSON is at `/api/status/{token}`
+    pub url: String,
+}
+
+impl StatusPageWithToken {
+    fn new(page: StatusPage, token: String) -> Self {
+        Self {
+            url: format!("/status/{}", token),
+            page,
+            token,
+        }
+    }
+}
+
+/// What a public status page shows
+#[derive(Debug, Serialize)]
+pub struct PublicStatusPage {
+    pub name: String,
+    pub jobs: Vec<PublicJobStatus>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// List status pages (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_status_pages(
+    State(state): State<AppState>,
+) -> Result<Json<SuccessResponse<Vec<StatusPage>>>, ErrorResponse> {
+    let pages = StatusPageRepository::new(state.db_pool.clone())
+        .list()
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list status pages");
+            ErrorResponse::new("database_error", "Failed to list status pages")
+        })?;
+
+    Ok(Json(SuccessResponse::new(pages)))
+}
+
+/// Create a status page; the response holds its token, which isn't shown again (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn create_status_page(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Json(req): Json<SaveStatusPageRequest>,
+) -> Result<Json<SuccessResponse<StatusPageWithToken>>, ErrorResponse> {
+    validate_request(&state, &req).await?;
+
+    let now = Utc::now();
+    let page = StatusPage {
+        id: Uuid::new_v4(),
+        name: req.name.trim().to_string(),
+        job_ids: req.job_ids,
+        created_by: claims.sub.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+    let token = generate_token();
+
+    StatusPageRepository::new(state.db_pool.clone())
+        .create(&page, &hash_token(&token))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to create status page");
+            ErrorResponse::new("database_error", "Failed to create status page")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        status_page_id = %page.id,
+        jobs = page.job_ids.len(),
+        "Audit log: status page created"
+    );
+
+    Ok(Json(SuccessResponse::new(StatusPageWithToken::new(
+        page, token,
+    ))))
+}
+
+/// Change the name and jobs of a status page; its token stays the same (admin only)
+#[tracing::instrument(skip(state, claims, req))]
+pub async fn update_status_page(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SaveStatusPageRequest>,
+) -> Result<Json<SuccessResponse<StatusPage>>, ErrorResponse> {
+    validate_request(&state, &req).await?;
+
+    let repo = StatusPageRepository::new(state.db_pool.clone());
+    let mut page = find_page(&repo, id).await?;
+    page.name = req.name.trim().to_string();
+    page.job_ids = req.job_ids;
+    page.updated_at = Utc::now();
+
+    repo.update(&page).await.map_err(|e| {
+        tracing::error!(error = %e, "Failed to update status page");
+        ErrorResponse::new("database_error", "Failed to update status page")
+    })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        status_page_id = %id,
+        jobs = page.job_ids.len(),
+        "Audit log: status page updated"
+    );
+
+    Ok(Json(SuccessResponse::new(page)))
+}
+
+/// Give a status page a new token, e.g. after a link leaked; the old link stops
+/// working (admin only)
+#[tracing::instrument(skip(state, claims))]
+pub async fn rotate_status_page_token(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<StatusPageWithToken>>, ErrorResponse> {
+    let repo = StatusPageRepository::new(state.db_pool.clone());
+    let page = find_page(&repo, id).await?;
+    let token = generate_token();
+
+    repo.rotate_token(id, &hash_token(&token))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to rotate status page token");
+            ErrorResponse::new("database_error", "Failed to rotate status page token")
+        })?;
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        status_page_id = %id,
+        "Audit log: status page token rotated"
+    );
+
+    Ok(Json(SuccessResponse::new(StatusPageWithToken::new(
+        page, token,
+    ))))
+}
+
+/// Delete a status page (admin only)
+#[tracing::instrument(skip(state, claims))]
+pub async fn delete_status_page(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let deleted = StatusPageRepository::new(state.db_pool.clone())
+        .delete(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to delete status page");
+            ErrorResponse::new("database_error", "Failed to delete status page")
+        })?;
+    if !deleted {
+        return Err(ErrorResponse::localized(
+            "not_found",
+            "status_page.not_found",
+        ));
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        status_page_id = %id,
+        "Audit log: status page deleted"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
+
+/// Public status page as JSON; the token is the only credential
+#[tracing::instrument(skip(state, token))]
+pub async fn get_public_status(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let status = load_public_status(&state, &token).await?;
+    Ok((
+        [(header::CACHE_CONTROL, PUBLIC_CACHE_CONTROL)],
+        Json(SuccessResponse::new(status)),
+    ))
+}
+
+/// Public status page as HTML, for linking or embedding in an iframe
+#[tracing::instrument(skip(state, token))]
+pub async fn public_status_page(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+) -> Result<impl IntoResponse, ErrorResponse> {
+    let status = load_public_status(&state, &token).await?;
+
+    let mut context = Context::new();
+    context.insert("page", &status);
+    let html = TEMPLATES
+        .render("status_page.html", &context)
+        .map_err(|e| {
+            tracing::error!(error = %e, "Template rendering failed");
+            ErrorResponse::new("template_error", "Failed to render 'status_page.html'")
+        })?;
+
+    Ok(([(header::CACHE_CONTROL, PUBLIC_CACHE_CONTROL)], Html(html)))
+}
+
+async fn load_public_status(
+    state: &AppState,
+    token: &str,
+) -> Result<PublicStatusPage, ErrorResponse> {
+    let repo = StatusPageRepository::new(state.db_pool.clone());
+    let page = repo
+        .find_by_token_hash(&hash_token(token))
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to load status page");
+            ErrorResponse::new("database_error", "Failed to load status page")
+        })?
+        .ok_or_else(|| ErrorResponse::localized("not_found", "status_page.not_found"))?;
+
+    let jobs = repo.job_statuses(&page.job_ids).await.map_err(|e| {
+        tracing::error!(error = %e, status_page_id = %page.id, "Failed to load job statuses");
+        ErrorResponse::new("database_error", "Failed to load status page")
+    })?;
+
+    Ok(PublicStatusPage {
+        name: page.name,
+        jobs,
+        generated_at: Utc::now(),
+    })
+}
+
+async fn validate_request(
+    state: &AppState,
+    req: &SaveStatusPageRequest,
+) -> Result<(), ErrorResponse> {
+    validate_status_page(&req.name, &req.job_ids).map_err(|e| {
+        ErrorResponse::localized_with(
+            "validation_error",
+            "status_page.invalid",
+            &[("reason", &e.to_string())],
+        )
+    })?;
+
+    let existing: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM jobs WHERE id = ANY($1)")
+        .bind(&req.job_ids)
+        .fetch_all(state.db_pool.pool())
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to check status page jobs");
+            ErrorResponse::new("database_error", "Failed to check status page jobs")
+        })?;
+    if let Some(missing) = req.job_ids.iter().find(|id| !existing.contains(id)) {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "status_page.unknown_job",
+            &[("id", &missing.to_string())],
+        ));
+    }
+
+    Ok(())
+}
+
+async fn find_page(repo: &StatusPageRepository, id: Uuid) -> Result<StatusPage, ErrorResponse> {
+    repo.find(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, status_page_id = %id, "Failed to load status page");
+            ErrorResponse::new("database_error", "Failed to load status page")
+        })?
+        .ok_or_else(|| ErrorResponse::localized("not_found", "status_page.not_found"))
+}
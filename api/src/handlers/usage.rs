@@ -0,0 +1,173 @@
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use common::db::repositories::usage::{TenantUsage, UsageRepository};
+use common::usage::UsageMonth;
+use serde::{Deserialize, Serialize};
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Column order of the CSV export
+const USAGE_CSV_HEADER: [&str; 6] = [
+    "month",
+    "tenant_id",
+    "executions",
+    "compute_seconds",
+    "storage_bytes",
+    "notifications",
+];
+
+/// Query parameters for a usage report
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Restrict the report to one tenant
+    pub tenant: Option<String>,
+    /// `YYYY-MM`, defaults to the current month (UTC)
+    pub month: Option<String>,
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
+
+/// Usage of every tenant in one month
+#[derive(Debug, Serialize)]
+pub struct UsageReport {
+    pub month: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub tenants: Vec<TenantUsage>,
+}
+
+/// Per-tenant usage for a month, for internal chargeback (admin only)
+///
+/// Executions are counted in the month they were created; jobs created by users
+/// without a tenant are reported under a null tenant.
+#[tracing::instrument(skip(state))]
+pub async fn get_usage(
+    State(state): State<AppState>,
+    Query(query): Query<UsageQuery>,
+) -> Result<Response, ErrorResponse> {
+    let csv = match query.format.as_deref().unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        other => {
+            return Err(ErrorResponse::new(
+                "validation_error",
+                format!("Unsupported usage format: {}. Use json or csv", other),
+            ));
+        }
+    };
+    let month = match query.month.as_deref() {
+        Some(month) => UsageMonth::parse(month).map_err(|e| {
+            ErrorResponse::localized_with(
+                "validation_error",
+                "usage.invalid_month",
+                &[("reason", &e.to_string())],
+            )
+        })?,
+        None => UsageMonth::containing(Utc::now()),
+    };
+    let tenant = query
+        .tenant
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+
+    let tenants = UsageRepository::new(state.db_pool.clone())
+        .tenant_usage(month.start(), month.end(), tenant)
+        .await
+        .map_err(|e| {
+            ErrorResponse::new("database_error", format!("Failed to compute usage: {}", e))
+        })?;
+
+    let report = UsageReport {
+        month: month.to_string(),
+        from: month.start(),
+        to: month.end(),
+        tenants,
+    };
+
+    if !csv {
+        return Ok(Json(SuccessResponse::new(report)).into_response());
+    }
+
+    let body = encode_usage_csv(&report).map_err(|e| {
+        ErrorResponse::new(
+            "serialization_error",
+            format!("Failed to encode usage: {}", e),
+        )
+    })?;
+    let disposition = format!("attachment; filename=\"usage-{}.csv\"", report.month);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+fn encode_usage_csv(report: &UsageReport) -> Result<Vec<u8>, csv::Error> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(USAGE_CSV_HEADER)?;
+    for usage in &report.tenants {
+        writer.write_record([
+            report.month.clone(),
+            usage.tenant_id.clone().unwrap_or_default(),
+            usage.executions.to_string(),
+            format!("{:.3}", usage.compute_seconds),
+            usage.storage_bytes.to_string(),
+            usage.notifications.to_string(),
+        ])?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| csv::Error::from(e.into_error()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_csv_has_one_row_per_tenant() {
+        let month = UsageMonth::parse("2026-10").unwrap();
+        let report = UsageReport {
+            month: month.to_string(),
+            from: month.start(),
+            to: month.end(),
+            tenants: vec![
+                TenantUsage {
+                    tenant_id: None,
+                    executions: 3,
+                    compute_seconds: 1.5,
+                    storage_bytes: 512,
+                    notifications: 0,
+                },
+                TenantUsage {
+                    tenant_id: Some("acme, inc".to_string()),
+                    executions: 120,
+                    compute_seconds: 3600.25,
+                    storage_bytes: 1_048_576,
+                    notifications: 4,
+                },
+            ],
+        };
+
+        let csv = String::from_utf8(encode_usage_csv(&report).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "month,tenant_id,executions,compute_seconds,storage_bytes,notifications",
+                "2026-10,,3,1.500,512,0",
+                "2026-10,\"acme, inc\",120,3600.250,1048576,4",
+            ]
+        );
+    }
+}
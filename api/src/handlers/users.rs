@@ -3,6 +3,7 @@ use axum::{
     Extension, Json,
 };
 use common::db::repositories::user::UserRepository;
+use common::i18n::Locale;
 use common::models::{Role, UserClaims};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -16,6 +17,8 @@ pub struct UserResponse {
     pub username: String,
     pub email: Option<String>,
     pub enabled: bool,
+    pub locale: Option<String>,
+    pub tenant_id: Option<String>,
     pub roles: Vec<RoleResponse>,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
@@ -41,6 +44,10 @@ impl From<Role> for RoleResponse {
 pub struct UpdateUserRequest {
     pub email: Option<String>,
     pub enabled: Option<bool>,
+    /// Preferred message locale ("vi-VN" or "en-US")
+    pub locale: Option<String>,
+    /// Tenant the user acts for (admin only); an empty string removes it
+    pub tenant_id: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -79,6 +86,8 @@ pub async fn list_users(
             username: user.username,
             email: user.email,
             enabled: user.enabled,
+            locale: user.locale,
+            tenant_id: user.tenant_id,
             roles: roles.into_iter().map(RoleResponse::from).collect(),
             created_at: user.created_at,
         });
@@ -100,10 +109,7 @@ pub async fn get_user(
     let is_admin = claims.permissions.contains(&"user:manage".to_string());
 
     if !is_own_profile && !is_admin {
-        return Err(ErrorResponse::new(
-            "forbidden",
-            "You can only view your own profile",
-        ));
+        return Err(ErrorResponse::localized("forbidden", "user.view_own_only"));
     }
 
     let user_repository = UserRepository::new(state.db_pool.clone());
@@ -115,7 +121,7 @@ pub async fn get_user(
             tracing::error!(error = %e, user_id = %user_id, "Failed to get user");
             ErrorResponse::new("internal_error", "Failed to get user")
         })?
-        .ok_or_else(|| ErrorResponse::new("not_found", "User not found"))?;
+        .ok_or_else(|| ErrorResponse::localized("not_found", "user.not_found"))?;
 
     let roles = user_repository.get_user_roles(user.id).await.map_err(|e| {
         tracing::error!(error = %e, user_id = %user.id, "Failed to get user roles");
@@ -127,6 +133,8 @@ pub async fn get_user(
         username: user.username,
         email: user.email,
         enabled: user.enabled,
+        locale: user.locale,
+        tenant_id: user.tenant_id,
         roles: roles.into_iter().map(RoleResponse::from).collect(),
         created_at: user.created_at,
     };
@@ -148,16 +156,23 @@ pub async fn update_user(
 
     // Users can only update their own email, admins can update everything
     if !is_admin && !is_own_profile {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "forbidden",
-            "You can only update your own profile",
+            "user.update_own_only",
         ));
     }
 
     if !is_admin && req.enabled.is_some() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
+            "forbidden",
+            "user.admin_only_enable",
+        ));
+    }
+
+    if !is_admin && req.tenant_id.is_some() {
+        return Err(ErrorResponse::localized(
             "forbidden",
-            "Only admins can enable/disable users",
+            "user.admin_only_tenant",
         ));
     }
 
@@ -170,7 +185,7 @@ pub async fn update_user(
             tracing::error!(error = %e, user_id = %user_id, "Failed to get user");
             ErrorResponse::new("internal_error", "Failed to get user")
         })?
-        .ok_or_else(|| ErrorResponse::new("not_found", "User not found"))?;
+        .ok_or_else(|| ErrorResponse::localized("not_found", "user.not_found"))?;
 
     // Update fields
     if let Some(email) = req.email {
@@ -181,6 +196,23 @@ pub async fn update_user(
         user.enabled = enabled;
     }
 
+    if let Some(locale) = req.locale {
+        let locale = locale.parse::<Locale>().map_err(|_| {
+            ErrorResponse::localized_with(
+                "validation_error",
+                "user.unsupported_locale",
+                &[("locale", &locale)],
+            )
+        })?;
+        user.locale = Some(locale.as_tag().to_string());
+    }
+
+    // Takes effect on the user's next login
+    if let Some(tenant_id) = req.tenant_id {
+        let tenant_id = tenant_id.trim();
+        user.tenant_id = (!tenant_id.is_empty()).then(|| tenant_id.to_string());
+    }
+
     user.updated_at = chrono::Utc::now();
 
     user_repository.update(&user).await.map_err(|e| {
@@ -198,6 +230,8 @@ pub async fn update_user(
         username: user.username,
         email: user.email,
         enabled: user.enabled,
+        locale: user.locale,
+        tenant_id: user.tenant_id,
         roles: roles.into_iter().map(RoleResponse::from).collect(),
         created_at: user.created_at,
     };
@@ -222,9 +256,9 @@ pub async fn delete_user(
 ) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
     // Prevent users from deleting themselves
     if claims.sub == user_id.to_string() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "forbidden",
-            "You cannot delete your own account",
+            "user.cannot_delete_self",
         ));
     }
 
@@ -256,9 +290,9 @@ pub async fn assign_roles(
 ) -> Result<Json<SuccessResponse<UserResponse>>, ErrorResponse> {
     // Prevent users from modifying their own roles (anti-privilege escalation)
     if claims.sub == user_id.to_string() {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "forbidden",
-            "You cannot modify your own roles",
+            "user.cannot_modify_own_roles",
         ));
     }
 
@@ -272,7 +306,7 @@ pub async fn assign_roles(
             tracing::error!(error = %e, user_id = %user_id, "Failed to get user");
             ErrorResponse::new("internal_error", "Failed to get user")
         })?
-        .ok_or_else(|| ErrorResponse::new("not_found", "User not found"))?;
+        .ok_or_else(|| ErrorResponse::localized("not_found", "user.not_found"))?;
 
     // Get existing roles and remove them
     let existing_roles = user_repository.get_user_roles(user_id).await.map_err(|e| {
@@ -323,6 +357,8 @@ pub async fn assign_roles(
         username: user.username,
         email: user.email,
         enabled: user.enabled,
+        locale: user.locale,
+        tenant_id: user.tenant_id,
         roles: roles.into_iter().map(RoleResponse::from).collect(),
         created_at: user.created_at,
     };
@@ -350,17 +386,17 @@ pub async fn update_password(
     let is_admin = claims.permissions.contains(&"user:manage".to_string());
 
     if !is_own_profile && !is_admin {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "forbidden",
-            "You can only change your own password",
+            "user.change_own_password_only",
         ));
     }
 
     // Validate password
     if req.new_password.len() < 8 {
-        return Err(ErrorResponse::new(
+        return Err(ErrorResponse::localized(
             "validation_error",
-            "Password must be at least 8 characters",
+            "auth.password_too_short",
         ));
     }
 
@@ -379,7 +415,7 @@ pub async fn update_password(
             tracing::error!(error = %e, user_id = %user_id, "Failed to get user");
             ErrorResponse::new("internal_error", "Failed to get user")
         })?
-        .ok_or_else(|| ErrorResponse::new("not_found", "User not found"))?;
+        .ok_or_else(|| ErrorResponse::localized("not_found", "user.not_found"))?;
 
     user.password_hash = password_hash;
     user.updated_at = chrono::Utc::now();
@@ -20,6 +20,9 @@ pub struct CreateVariableRequest {
     pub value: String,
     pub is_sensitive: bool,
     pub scope: VariableScope,
+    /// When the secret stops working; warned about ahead of time by the expiry check
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<Utc>>,
 }
 
 /// Request to update an existing variable
@@ -30,6 +33,18 @@ pub struct UpdateVariableRequest {
     pub name: Option<String>,
     pub value: Option<String>,
     pub is_sensitive: Option<bool>,
+    /// Absent leaves the expiry unchanged, `null` clears it
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub expires_at: Option<Option<chrono::DateTime<Utc>>>,
+}
+
+/// Deserialize a field that is present, even as `null`, into `Some`
+pub(crate) fn deserialize_present<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: serde::Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 /// Response for listing variables with masked sensitive values
@@ -42,6 +57,8 @@ pub struct VariableResponse {
     pub value: String,
     pub is_sensitive: bool,
     pub scope: VariableScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<chrono::DateTime<Utc>>,
     pub created_at: chrono::DateTime<Utc>,
     pub updated_at: chrono::DateTime<Utc>,
 }
@@ -54,6 +71,7 @@ impl From<Variable> for VariableResponse {
             value: var.value,
             is_sensitive: var.is_sensitive,
             scope: var.scope,
+            expires_at: var.expires_at,
             created_at: var.created_at,
             updated_at: var.updated_at,
         }
@@ -108,6 +126,7 @@ pub async fn create_variable(
         value: req.value,
         is_sensitive: req.is_sensitive,
         scope: req.scope.clone(),
+        expires_at: req.expires_at,
         created_at: now,
         updated_at: now,
     };
@@ -182,7 +201,13 @@ pub async fn update_variable(
                 &format!("Failed to fetch variable: {}", e),
             )
         })?
-        .ok_or_else(|| ErrorResponse::new("not_found", &format!("Variable not found: {}", id)))?;
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "variable.not_found",
+                &[("id", &id.to_string())],
+            )
+        })?;
 
     // Update fields if provided
     if let Some(name) = req.name {
@@ -220,6 +245,10 @@ pub async fn update_variable(
         variable.is_sensitive = is_sensitive;
     }
 
+    if let Some(expires_at) = req.expires_at {
+        variable.expires_at = expires_at;
+    }
+
     variable.updated_at = Utc::now();
 
     // Update variable in database
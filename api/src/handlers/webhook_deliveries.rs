@@ -0,0 +1,151 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use common::db::repositories::webhook_delivery::{
+    WebhookDeliveryFilter, WebhookDeliveryRepository,
+};
+use common::models::{UserClaims, WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::handlers::{ErrorResponse, SuccessResponse};
+use crate::state::AppState;
+
+/// Deliveries listed when no limit is given
+const DEFAULT_LIST_LIMIT: i64 = 50;
+
+/// Most deliveries a single list request returns
+const MAX_LIST_LIMIT: i64 = 500;
+
+/// Query parameters for listing deliveries
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesQuery {
+    /// Only deliveries to this endpoint URL
+    pub endpoint: Option<String>,
+    /// `pending`, `delivered` or `dead_letter`
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+/// A delivery with its attempt log
+#[derive(Debug, Serialize)]
+pub struct WebhookDeliveryDetail {
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+    pub attempts: Vec<WebhookDeliveryAttempt>,
+}
+
+/// List outbound webhook deliveries, most recent first (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Query(query): Query<ListDeliveriesQuery>,
+) -> Result<Json<SuccessResponse<Vec<WebhookDelivery>>>, ErrorResponse> {
+    let status = query
+        .status
+        .as_deref()
+        .map(|status| {
+            status.parse::<WebhookDeliveryStatus>().map_err(|_| {
+                ErrorResponse::localized_with(
+                    "validation_error",
+                    "webhook_delivery.invalid_status",
+                    &[("status", status)],
+                )
+            })
+        })
+        .transpose()?;
+    let limit = query.limit.unwrap_or(DEFAULT_LIST_LIMIT);
+    if !(1..=MAX_LIST_LIMIT).contains(&limit) {
+        return Err(ErrorResponse::localized_with(
+            "validation_error",
+            "webhook_delivery.invalid_limit",
+            &[("max", &MAX_LIST_LIMIT.to_string())],
+        ));
+    }
+
+    let filter = WebhookDeliveryFilter {
+        endpoint_url: query.endpoint,
+        status,
+        limit,
+    };
+    let deliveries = WebhookDeliveryRepository::new(state.db_pool.clone())
+        .list(&filter)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to list webhook deliveries");
+            ErrorResponse::localized("database_error", "webhook_delivery.list_failed")
+        })?;
+
+    Ok(Json(SuccessResponse::new(deliveries)))
+}
+
+/// Get a delivery and every attempt made to send it (admin only)
+#[tracing::instrument(skip(state))]
+pub async fn get_webhook_delivery(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<WebhookDeliveryDetail>>, ErrorResponse> {
+    let repo = WebhookDeliveryRepository::new(state.db_pool.clone());
+    let delivery = repo
+        .find_by_id(id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, delivery_id = %id, "Failed to get webhook delivery");
+            ErrorResponse::localized("database_error", "webhook_delivery.not_loaded")
+        })?
+        .ok_or_else(|| ErrorResponse::localized("not_found", "webhook_delivery.not_found"))?;
+    let attempts = repo.find_attempts(id).await.map_err(|e| {
+        tracing::error!(error = %e, delivery_id = %id, "Failed to get webhook delivery attempts");
+        ErrorResponse::localized("database_error", "webhook_delivery.attempts_not_loaded")
+    })?;
+
+    Ok(Json(SuccessResponse::new(WebhookDeliveryDetail {
+        delivery,
+        attempts,
+    })))
+}
+
+/// Queue a dead-lettered delivery again with a fresh set of attempts (admin only)
+#[tracing::instrument(skip(state, claims))]
+pub async fn redeliver_webhook_delivery(
+    State(state): State<AppState>,
+    Extension(claims): Extension<UserClaims>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<()>>, ErrorResponse> {
+    let repo = WebhookDeliveryRepository::new(state.db_pool.clone());
+    let requeued = repo.redeliver(id).await.map_err(|e| {
+        tracing::error!(error = %e, delivery_id = %id, "Failed to redeliver webhook");
+        ErrorResponse::localized("database_error", "webhook_delivery.not_redelivered")
+    })?;
+
+    if !requeued {
+        return match repo.find_by_id(id).await {
+            Ok(Some(delivery)) => Err(ErrorResponse::localized_with(
+                "conflict",
+                "webhook_delivery.not_dead_letter",
+                &[("status", &delivery.status.to_string())],
+            )),
+            Ok(None) => Err(ErrorResponse::localized(
+                "not_found",
+                "webhook_delivery.not_found",
+            )),
+            Err(e) => {
+                tracing::error!(error = %e, delivery_id = %id, "Failed to get webhook delivery");
+                Err(ErrorResponse::localized(
+                    "database_error",
+                    "webhook_delivery.not_loaded",
+                ))
+            }
+        };
+    }
+
+    tracing::info!(
+        user_id = %claims.sub,
+        username = %claims.username,
+        delivery_id = %id,
+        "Audit log: Webhook delivery requeued"
+    );
+
+    Ok(Json(SuccessResponse::new(())))
+}
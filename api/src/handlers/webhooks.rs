@@ -4,17 +4,24 @@ use axum::{
     http::{HeaderMap, StatusCode},
     Json,
 };
-use common::db::repositories::{ExecutionRepository, JobRepository, WebhookRepository};
+use common::db::repositories::{
+    ExecutionRepository, JobRepository, WebhookPayloadRepository, WebhookRepository,
+};
 use common::models::{
-    ExecutionStatus, JobContext, JobExecution, TriggerSource, WebhookData, WebhookResponse,
+    CapturedWebhookPayload, ExecutionStatus, JobContext, JobExecution, JobStep, TriggerSource,
+    WebhookData, WebhookResponse,
 };
 use common::queue::publisher::JobPublisher;
+use common::simulation::{simulate_steps, SimulationReport};
+use common::storage::residency;
+use common::trigger_variables::apply_trigger_variables;
 
 use common::webhook::validate_webhook_signature;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::handlers::jobs::validate_trigger_variables;
 use crate::handlers::{ErrorResponse, SuccessResponse};
 use crate::state::AppState;
 
@@ -107,6 +114,15 @@ pub async fn handle_webhook(
         ));
     }
 
+    if job.deprecated {
+        tracing::warn!(
+            job_id = %job.id,
+            job_name = %job.name,
+            sunset_at = ?job.sunset_at,
+            "Deprecated job triggered by webhook"
+        );
+    }
+
     // 4. Check rate limit
     // Requirements: 16.11 - Enforce per-job rate limits, return 429 for violations
     if let (Some(max_requests), Some(window_seconds)) = (
@@ -115,7 +131,7 @@ pub async fn handle_webhook(
     ) {
         use common::rate_limit::RateLimiter;
 
-        let rate_limiter = RateLimiter::new(state.redis_client.clone());
+        let rate_limiter = RateLimiter::new(state.redis.clone());
         let allowed = rate_limiter
             .check_rate_limit(webhook.id, max_requests as u32, window_seconds as u32)
             .await
@@ -193,6 +209,18 @@ pub async fn handle_webhook(
     // Requirements: 16.3 - Store webhook payload in Job Context
     let payload: serde_json::Value = serde_json::from_slice(&body).unwrap_or(serde_json::json!({}));
 
+    // A top-level "variables" object overrides job and global variables for this run
+    let variables = match payload.get("variables") {
+        Some(serde_json::Value::Object(map)) => map.clone().into_iter().collect(),
+        _ => HashMap::new(),
+    };
+    let variables = validate_trigger_variables(&state, job.id, variables)
+        .await
+        .map_err(|e| {
+            tracing::warn!(webhook_id = %webhook.id, error = %e.message, "Invalid webhook variables");
+            (StatusCode::BAD_REQUEST, Json(e))
+        })?;
+
     // 6. Extract custom headers (filter out standard headers)
     // Requirements: 16.5 - Store custom headers in Job Context
     let mut custom_headers = HashMap::new();
@@ -219,7 +247,7 @@ pub async fn handle_webhook(
     let idempotency_key = format!("webhook-{}-{}", webhook.id, execution_id);
     let _minio_context_path = format!("jobs/{}/executions/{}/context.json", job.id, execution_id);
 
-    let execution = JobExecution {
+    let mut execution = JobExecution {
         id: execution_id,
         job_id: job.id,
         idempotency_key: idempotency_key.clone(),
@@ -236,12 +264,14 @@ pub async fn handle_webhook(
         result: None,
         error: None,
         created_at: chrono::Utc::now(),
+        job_version: None,
     };
+    apply_trigger_variables(&mut execution, &variables, &full_path, "webhook");
 
     // 9. Initialize Job Context with webhook data
     // Requirements: 16.3, 16.4, 16.5 - Store webhook data in Job Context
     let mut context = JobContext::new(execution_id, job.id);
-    context.set_webhook_data(webhook_data);
+    context.set_webhook_data(webhook_data.clone());
 
     // 10. Store Job Context to PostgreSQL (with Redis cache)
     // Requirements: 13.7 - Persist Job Context
@@ -279,24 +309,36 @@ pub async fn handle_webhook(
         url: state.config.nats.url.clone(),
         stream_name: state.config.nats.stream_name.clone(),
         subject: "jobs.>".to_string(),
-        max_age_seconds: 86400,
-        max_messages: 1_000_000,
+        max_age_seconds: state.config.nats.limits.max_age_seconds,
+        max_messages: state.config.nats.limits.max_messages,
+        max_bytes: state.config.nats.limits.max_bytes,
         consumer_name: state.config.nats.consumer_name.clone(),
         max_deliver: 10,
+        tenant_streams: state.config.nats.tenant_streams.clone(),
+        max_message_bytes: state.config.nats.max_message_bytes,
+        compression: state.config.nats.compression,
+        compression_min_bytes: state.config.nats.compression_min_bytes,
+        shards: state.config.nats.shards,
+        worker_shards: state.config.nats.worker_shards.clone(),
+        priority_subjects: state.config.nats.priority_subjects,
     };
     let nats_client =
         common::queue::nats::NatsClient::from_client(state.nats_client.clone(), nats_config);
-    let publisher = common::queue::publisher::NatsJobPublisher::new(nats_client);
-    publisher.publish(&execution).await.map_err(|e| {
-        tracing::error!(error = %e, "Failed to publish job to queue");
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(
-                "internal_error",
-                "Failed to queue job execution",
-            )),
-        )
-    })?;
+    let publisher = common::queue::publisher::NatsJobPublisher::new(nats_client)
+        .with_payload_store(state.storage_service.clone());
+    publisher
+        .publish_for_job(&execution, &job)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to publish job to queue");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(
+                    "internal_error",
+                    "Failed to queue job execution",
+                )),
+            )
+        })?;
 
     tracing::info!(
         execution_id = %execution_id,
@@ -305,7 +347,26 @@ pub async fn handle_webhook(
         "Webhook triggered job execution"
     );
 
-    // 13. Return 202 Accepted with execution_id
+    // 13. Keep the request so the job can be simulated against it later
+    let captured = CapturedWebhookPayload {
+        id: Uuid::new_v4(),
+        webhook_id: webhook.id,
+        job_id: job.id,
+        execution_id: Some(execution_id),
+        data: webhook_data,
+        received_at: chrono::Utc::now(),
+    };
+    if let Err(e) = residency::record_webhook_payload(
+        state.storage_service.as_ref(),
+        &WebhookPayloadRepository::new(state.db_pool.clone()),
+        &captured,
+    )
+    .await
+    {
+        tracing::warn!(error = %e, webhook_id = %webhook.id, "Failed to capture webhook payload");
+    }
+
+    // 14. Return 202 Accepted with execution_id
     // Requirements: 16.9 - Return 202 Accepted with execution_id
     Ok(Json(WebhookResponse {
         execution_id,
@@ -313,6 +374,153 @@ pub async fn handle_webhook(
     }))
 }
 
+/// List the webhook requests captured for a job, newest first
+#[tracing::instrument(skip(state))]
+pub async fn list_webhook_payloads(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<SuccessResponse<Vec<CapturedWebhookPayload>>>, ErrorResponse> {
+    let payloads = WebhookPayloadRepository::new(state.db_pool.clone())
+        .find_by_job(job_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, job_id = %job_id, "Failed to list webhook payloads");
+            ErrorResponse::new("database_error", "Failed to retrieve webhook payloads")
+        })?;
+    let mut loaded = Vec::with_capacity(payloads.len());
+    for payload in payloads {
+        let payload = residency::load_webhook_payload(state.storage_service.as_ref(), payload)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, job_id = %job_id, "Failed to load webhook payload");
+                ErrorResponse::new("storage_error", "Failed to retrieve webhook payloads")
+            })?;
+        loaded.push(payload);
+    }
+
+    Ok(Json(SuccessResponse::new(loaded)))
+}
+
+/// Query parameters for simulating a job
+#[derive(Debug, Deserialize)]
+pub struct SimulateJobQuery {
+    /// Captured webhook request to simulate the job against
+    pub payload_id: Uuid,
+}
+
+/// Dry-run result of a job against a captured webhook request
+#[derive(Debug, Serialize)]
+pub struct JobSimulation {
+    pub job_id: Uuid,
+    pub payload_id: Uuid,
+    pub received_at: chrono::DateTime<chrono::Utc>,
+    #[serde(flatten)]
+    pub report: SimulationReport,
+}
+
+/// Dry-run a job against a captured webhook request
+///
+/// Resolves the job's current steps against the request as the worker would, without
+/// running them, so mapping changes can be checked against real partner events.
+#[tracing::instrument(skip(state))]
+pub async fn simulate_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+    Query(query): Query<SimulateJobQuery>,
+) -> Result<Json<SuccessResponse<JobSimulation>>, ErrorResponse> {
+    let job = JobRepository::new(state.db_pool.clone())
+        .find_by_id(job_id)
+        .await
+        .map_err(|e| ErrorResponse::new("database_error", format!("Failed to fetch job: {}", e)))?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "job.not_found",
+                &[("id", &job_id.to_string())],
+            )
+        })?;
+
+    let captured = WebhookPayloadRepository::new(state.db_pool.clone())
+        .find_for_job(job_id, query.payload_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, job_id = %job_id, "Failed to get webhook payload");
+            ErrorResponse::new("database_error", "Failed to retrieve webhook payload")
+        })?
+        .ok_or_else(|| {
+            ErrorResponse::localized_with(
+                "not_found",
+                "job.webhook_payload_not_found",
+                &[("id", &query.payload_id.to_string())],
+            )
+        })?;
+    let captured = residency::load_webhook_payload(state.storage_service.as_ref(), captured)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, job_id = %job_id, "Failed to load webhook payload");
+            ErrorResponse::new("storage_error", "Failed to retrieve webhook payload")
+        })?;
+
+    let definition = match job.definition {
+        Some(definition) => definition,
+        None => {
+            let definition_json = state
+                .storage_service
+                .load_job_definition(job.id)
+                .await
+                .map_err(|e| {
+                    ErrorResponse::new(
+                        "storage_error",
+                        format!("Failed to load job definition: {}", e),
+                    )
+                })?;
+            serde_json::from_str(&definition_json).map_err(|e| {
+                ErrorResponse::new(
+                    "deserialization_error",
+                    format!("Failed to parse job definition: {}", e),
+                )
+            })?
+        }
+    };
+    let steps: Vec<JobStep> = definition
+        .get("steps")
+        .map(|steps| serde_json::from_value(steps.clone()))
+        .transpose()
+        .map_err(|e| {
+            ErrorResponse::new(
+                "deserialization_error",
+                format!("Failed to parse job steps: {}", e),
+            )
+        })?
+        .unwrap_or_default();
+
+    // The request's trigger variables apply as they would in a real run
+    let variables = match captured.data.payload.get("variables") {
+        Some(serde_json::Value::Object(map)) => map.clone().into_iter().collect(),
+        _ => HashMap::new(),
+    };
+    let variables = validate_trigger_variables(&state, job.id, variables).await?;
+
+    let mut context = JobContext::new(Uuid::new_v4(), job.id);
+    context.set_webhook_data(captured.data);
+    context.variables.extend(variables);
+
+    let report = simulate_steps(&steps, &context);
+    tracing::info!(
+        job_id = %job_id,
+        payload_id = %captured.id,
+        valid = report.valid,
+        "Simulated job against captured webhook payload"
+    );
+
+    Ok(Json(SuccessResponse::new(JobSimulation {
+        job_id,
+        payload_id: captured.id,
+        received_at: captured.received_at,
+        report,
+    })))
+}
+
 /// Create a webhook for a job
 /// Requirements: 16.1 - Generate unique webhook URL for job
 #[tracing::instrument(skip(state))]
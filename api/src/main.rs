@@ -1,11 +1,16 @@
 use anyhow::Result;
 use common::bootstrap;
 use common::config::Settings;
+use common::db::migrations;
+use common::db::repositories::{JwtKeyRepository, StatsRepository};
+use common::jwt_keys::JwtKeyRing;
 use futures::StreamExt;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 mod handlers;
 mod middleware;
+mod permissions;
 mod routes;
 mod state;
 mod templates;
@@ -46,6 +51,17 @@ async fn subscribe_to_status_changes(state: AppState) -> Result<()> {
                 StatusChangeEvent::JobStatusChanged { job_id, status } => {
                     state.broadcast_event(SseEvent::JobStatusChanged { job_id, status });
                 }
+                StatusChangeEvent::ExecutionHeartbeat {
+                    execution_id,
+                    job_id,
+                    progress,
+                } => {
+                    state.broadcast_event(SseEvent::ExecutionHeartbeat {
+                        execution_id,
+                        job_id,
+                        progress,
+                    });
+                }
             }
         } else {
             tracing::warn!(
@@ -58,6 +74,62 @@ async fn subscribe_to_status_changes(state: AppState) -> Result<()> {
     Ok(())
 }
 
+/// Periodically refresh the execution statistics rollup tables
+///
+/// Upserts are idempotent, so running this in several API replicas is safe.
+#[tracing::instrument(skip(state))]
+async fn run_stats_rollup(state: AppState) {
+    let repo = StatsRepository::new(state.db_pool.clone());
+    let lookback = chrono::Duration::hours(state.config.stats.rollup_lookback_hours as i64);
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+        state.config.stats.rollup_interval_seconds,
+    ));
+
+    loop {
+        interval.tick().await;
+
+        let since = chrono::Utc::now() - lookback;
+        let refreshed = match repo.refresh_hourly(since).await {
+            Ok(hourly) => repo.refresh_daily(since).await.map(|daily| (hourly, daily)),
+            Err(e) => Err(e),
+        };
+
+        match refreshed {
+            Ok((hourly, daily)) => {
+                tracing::debug!(hourly, daily, "Execution stats rollups refreshed");
+            }
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to refresh execution stats rollups");
+            }
+        }
+    }
+}
+
+/// How often the signing keys are checked for rotation and reloaded
+const JWT_KEY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Rotate the token signing key when it's due, drop keys whose tokens have all
+/// expired, and pick up keys rotated by other instances
+async fn run_jwt_key_rotation(keys: Arc<JwtKeyRing>) {
+    let mut interval = tokio::time::interval(JWT_KEY_CHECK_INTERVAL);
+    // The first tick completes immediately; startup already rotated if due
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = keys.rotate_if_due().await {
+            tracing::error!(error = %e, "Failed to rotate JWT signing keys");
+            continue;
+        }
+        match keys.prune().await {
+            Ok(0) => {}
+            Ok(pruned) => tracing::info!(pruned, "Expired JWT signing keys removed"),
+            Err(e) => tracing::error!(error = %e, "Failed to remove expired JWT signing keys"),
+        }
+    }
+}
+
 /// Status change events published by worker
 #[derive(Debug, serde::Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -71,6 +143,11 @@ enum StatusChangeEvent {
         job_id: uuid::Uuid,
         status: String,
     },
+    ExecutionHeartbeat {
+        execution_id: uuid::Uuid,
+        job_id: uuid::Uuid,
+        progress: common::models::ExecutionProgress,
+    },
 }
 
 #[tokio::main]
@@ -91,26 +168,26 @@ async fn main() -> Result<()> {
     // Initialize database connection pool
     let db_pool = bootstrap::init_database_pool(&config).await?;
 
-    // Run migrations (commented out for local development - migrations already exist)
-    // sqlx::migrate!("../migrations").run(db_pool.pool()).await?;
-    tracing::info!("Database migrations skipped (already applied)");
-
-    // Initialize Redis client (for rate limiting and other features)
-    let redis_client = redis::Client::open(config.redis.url.clone())?;
-    tracing::info!("Redis client initialized");
+    // `api --migrate` applies migrations and exits; otherwise refuse to serve
+    // against a schema this build doesn't match
+    if migrations::migrate_requested() {
+        return bootstrap::run_migrations(&db_pool).await;
+    }
+    bootstrap::verify_schema(&db_pool).await?;
 
-    // Initialize Redis connection manager for storage cache
-    let redis_conn_manager = bootstrap::init_redis_connection_manager(&config).await?;
+    // Initialize Redis connection for the storage cache, rate limiting and other
+    // features
+    let redis_connection = bootstrap::init_redis_connection(&config).await?;
 
     // Initialize NATS client
-    let nats_client = async_nats::connect(&config.nats.url).await?;
+    let nats_client = common::queue::nats::connect(&config.nats).await?;
     tracing::info!("NATS client connected");
 
     // Initialize Storage service (PostgreSQL + Redis + Filesystem)
     let storage_service = bootstrap::init_storage_service(
         &config,
         db_pool.clone(),
-        std::sync::Arc::new(redis_conn_manager),
+        std::sync::Arc::new(redis_connection.clone()),
     )
     .await?;
 
@@ -119,13 +196,24 @@ async fn main() -> Result<()> {
         metrics_exporter_prometheus::PrometheusBuilder::new().install_recorder()?;
     tracing::info!(port = %config.observability.metrics_port, "Metrics exporter initialized");
 
+    // Load the token signing keys, creating the first one if needed
+    let jwt_keys = Arc::new(JwtKeyRing::new(
+        JwtKeyRepository::new(db_pool.clone()),
+        &config.auth.jwt_secret,
+        config.auth.jwt_key_rotation_days,
+        config.auth.jwt_expiration_hours,
+    ));
+    jwt_keys.rotate_if_due().await?;
+    tracing::info!("JWT signing keys loaded");
+
     // Create application state
     let state = AppState::new(
         db_pool,
-        redis_client,
+        redis_connection,
         nats_client.clone(),
         storage_service,
         config.clone(),
+        Arc::clone(&jwt_keys),
     );
 
     // Start background task to listen for status changes from worker
@@ -136,6 +224,12 @@ async fn main() -> Result<()> {
         }
     });
 
+    // Start background task to maintain execution statistics rollups
+    tokio::spawn(run_stats_rollup(state.clone()));
+
+    // Start background task to rotate token signing keys
+    tokio::spawn(run_jwt_key_rotation(jwt_keys));
+
     // Create router
     let app = routes::create_router(state);
 
@@ -4,8 +4,10 @@ use axum::{
     middleware::Next,
     response::Response,
 };
+use common::db::repositories::SessionRepository;
 use common::models::UserClaims;
 
+use crate::permissions::GUEST_PERMISSION;
 use crate::state::AppState;
 
 /// Authentication middleware that validates JWT tokens
@@ -27,7 +29,7 @@ pub async fn auth_middleware(
             tracing::warn!("Invalid authorization header format");
             return Err(StatusCode::UNAUTHORIZED);
         }
-        auth_header[7..].to_string() // Skip "Bearer "
+        Some(auth_header[7..].to_string()) // Skip "Bearer "
     } else {
         // Fallback: Extract token from cookie
         req.headers()
@@ -43,10 +45,17 @@ pub async fn auth_middleware(
                     }
                 })
             })
-            .ok_or_else(|| {
-                tracing::warn!("No authorization token found in header or cookie");
-                StatusCode::UNAUTHORIZED
-            })?
+    };
+
+    // Without a token, visitors get the read-only guest identity if it's enabled;
+    // a token that fails validation is still rejected
+    let Some(token) = token else {
+        if !state.config.auth.guest_access {
+            tracing::warn!("No authorization token found in header or cookie");
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        req.extensions_mut().insert(guest_claims());
+        return Ok(next.run(req).await);
     };
 
     // Validate token based on authentication mode
@@ -61,6 +70,20 @@ pub async fn auth_middleware(
     Ok(next.run(req).await)
 }
 
+/// Synthetic identity of visitors without a token when guest access is enabled
+fn guest_claims() -> UserClaims {
+    let now = chrono::Utc::now().timestamp();
+    UserClaims {
+        sub: "guest".to_string(),
+        username: "guest".to_string(),
+        permissions: vec![GUEST_PERMISSION.to_string()],
+        exp: now,
+        iat: now,
+        sid: None,
+        tenant_id: None,
+    }
+}
+
 /// Validate JWT token issued by Keycloak
 #[tracing::instrument(skip(token, state))]
 async fn validate_keycloak_token(token: &str, state: &AppState) -> Result<UserClaims, StatusCode> {
@@ -99,26 +122,65 @@ async fn validate_keycloak_token(token: &str, state: &AppState) -> Result<UserCl
 /// Validate JWT token issued by the system (database mode)
 #[tracing::instrument(skip(token, state))]
 async fn validate_database_token(token: &str, state: &AppState) -> Result<UserClaims, StatusCode> {
-    let jwt_secret = &state.config.auth.jwt_secret;
-
-    let validation = jsonwebtoken::Validation::default();
+    // Tokens are signed with rotating keys; one created by another instance may not
+    // be loaded yet
+    state.jwt_keys.ensure_token_key(token).await;
 
-    let token_data = jsonwebtoken::decode::<UserClaims>(
-        token,
-        &jsonwebtoken::DecodingKey::from_secret(jwt_secret.as_bytes()),
-        &validation,
-    )
-    .map_err(|e| {
+    // Signature and expiry
+    let claims = state.jwt_service().validate_token(token).map_err(|e| {
         tracing::warn!(error = %e, "Failed to validate database token");
         StatusCode::UNAUTHORIZED
     })?;
 
-    // Check if token is expired
-    let now = chrono::Utc::now().timestamp();
-    if token_data.claims.exp < now {
-        tracing::warn!("Token expired");
+    // Tokens issued before sessions were tracked carry no session; they stay valid
+    // until they expire
+    if let Some(session_id) = claims.sid {
+        check_session(session_id, &claims, state).await?;
+    }
+
+    Ok(claims)
+}
+
+/// How often a session's last-seen time is written
+const SESSION_TOUCH_INTERVAL_SECONDS: i64 = 60;
+
+/// Reject tokens of a revoked or expired session, and keep its last-seen time current
+async fn check_session(
+    session_id: uuid::Uuid,
+    claims: &UserClaims,
+    state: &AppState,
+) -> Result<(), StatusCode> {
+    let sessions = SessionRepository::new(state.db_pool.clone());
+    let session = sessions
+        .find(session_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(error = %e, session_id = %session_id, "Failed to load session");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .filter(|session| session.user_id.to_string() == claims.sub)
+        .ok_or_else(|| {
+            tracing::warn!(session_id = %session_id, "Token of unknown session");
+            StatusCode::UNAUTHORIZED
+        })?;
+
+    if !session.is_active() {
+        tracing::warn!(
+            user_id = %claims.sub,
+            session_id = %session_id,
+            "Token of revoked or expired session"
+        );
         return Err(StatusCode::UNAUTHORIZED);
     }
 
-    Ok(token_data.claims)
+    let since_seen = chrono::Utc::now() - session.last_seen_at;
+    if since_seen.num_seconds() >= SESSION_TOUCH_INTERVAL_SECONDS {
+        tokio::spawn(async move {
+            if let Err(e) = sessions.touch(session_id).await {
+                tracing::warn!(error = %e, session_id = %session_id, "Failed to update session last seen");
+            }
+        });
+    }
+
+    Ok(())
 }
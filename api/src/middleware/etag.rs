@@ -0,0 +1,152 @@
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Largest response body hashed for an ETag; bigger bodies are passed through
+const MAX_ETAG_BODY_BYTES: u64 = 32 * 1024 * 1024;
+
+/// ETag middleware for conditional GET requests
+///
+/// Successful GET/HEAD responses with a fully buffered body get a weak `ETag`
+/// derived from the body. If the request's `If-None-Match` matches, the body is
+/// dropped and `304 Not Modified` is returned. Handlers may set their own `ETag`
+/// (e.g. a job version), which is used as-is. Streaming responses (SSE) are
+/// left untouched. The computed tag is weak because the compression layer may
+/// re-encode the body.
+#[tracing::instrument(skip(req, next))]
+pub async fn etag_middleware(req: Request<Body>, next: Next) -> Response {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return next.run(req).await;
+    }
+
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+    let response = next.run(req).await;
+
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    if let Some(etag) = response.headers().get(header::ETAG).cloned() {
+        return match etag.to_str() {
+            Ok(etag) if if_none_match_satisfied(if_none_match.as_ref(), etag) => {
+                not_modified(response.headers())
+            }
+            _ => response,
+        };
+    }
+
+    // Only buffer bodies whose size is known up front (skips SSE and other streams)
+    match response.body().size_hint().exact() {
+        Some(len) if len <= MAX_ETAG_BODY_BYTES => {}
+        _ => return response,
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_ETAG_BODY_BYTES as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to buffer response body for ETag");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = compute_etag(&body);
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+
+    if if_none_match_satisfied(if_none_match.as_ref(), &etag) {
+        return not_modified(&parts.headers);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn if_none_match_satisfied(if_none_match: Option<&HeaderValue>, etag: &str) -> bool {
+    if_none_match
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| etag_matches(h, etag))
+}
+
+/// Empty 304 response repeating the cache headers of the full response
+fn not_modified(headers: &HeaderMap) -> Response {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    copy_cache_headers(headers, response.headers_mut());
+    response
+}
+
+/// Weak entity tag derived from the response body
+fn compute_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("W/\"{}\"", hex)
+}
+
+/// Weak comparison of an `If-None-Match` header against our tag
+fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let etag = opaque(etag);
+
+    if_none_match
+        .split(',')
+        .any(|candidate| candidate.trim() == "*" || opaque(candidate) == etag)
+}
+
+/// Headers a 304 response must repeat from the full response
+fn copy_cache_headers(from: &HeaderMap, to: &mut HeaderMap) {
+    for name in [
+        header::ETAG,
+        header::CACHE_CONTROL,
+        header::CONTENT_LOCATION,
+        header::VARY,
+        header::EXPIRES,
+    ] {
+        if let Some(value) = from.get(&name) {
+            to.insert(name, value.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_is_stable_and_weak() {
+        let etag = compute_etag(b"{\"data\":[]}");
+        assert!(etag.starts_with("W/\""));
+        assert_eq!(etag, compute_etag(b"{\"data\":[]}"));
+        assert_ne!(etag, compute_etag(b"{\"data\":[1]}"));
+    }
+
+    #[test]
+    fn test_if_none_match_comparison() {
+        let etag = compute_etag(b"body");
+        let strong = etag.trim_start_matches("W/").to_string();
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&strong, &etag));
+        assert!(etag_matches(&format!("\"other\", {}", etag), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"other\"", &etag));
+    }
+
+    #[test]
+    fn test_not_modified_repeats_handler_etag() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ETAG, HeaderValue::from_static("\"4\""));
+        let if_none_match = HeaderValue::from_static("\"4\"");
+
+        assert!(if_none_match_satisfied(Some(&if_none_match), "\"4\""));
+        assert!(!if_none_match_satisfied(None, "\"4\""));
+
+        let response = not_modified(&headers);
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], "\"4\"");
+    }
+}
@@ -0,0 +1,175 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use common::idempotency::{IdempotencyState, IdempotencyStore, StoredResponse};
+use common::models::UserClaims;
+
+use crate::handlers::ErrorResponse;
+use crate::state::AppState;
+
+/// Request header carrying the client-chosen idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Response header set when a stored response is replayed
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Largest request or response body buffered for idempotency handling
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Longest accepted idempotency key
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Idempotency middleware for mutating POST requests
+///
+/// When a POST request carries an `Idempotency-Key` header, the first request with
+/// that key (per user) is executed and its response stored in Redis for
+/// `idempotency.ttl_seconds`. Retries with the same key and the same request body
+/// get the stored response replayed with `Idempotent-Replayed: true`; a retry
+/// while the original is still running gets 409, and reusing the key for a
+/// different request gets 422. Server errors are not stored so they can be retried.
+#[tracing::instrument(skip(state, req, next))]
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    if req.method() != Method::POST {
+        return next.run(req).await;
+    }
+
+    let Some(client_key) = req
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(|k| k.trim().to_string())
+    else {
+        return next.run(req).await;
+    };
+
+    if client_key.is_empty() || client_key.len() > MAX_KEY_LENGTH {
+        return ErrorResponse::localized("validation_error", "idempotency.invalid_key")
+            .into_response();
+    }
+
+    // Keys are scoped per user so different callers cannot collide
+    let key = match req.extensions().get::<UserClaims>() {
+        Some(claims) => format!("{}:{}", claims.sub, client_key),
+        None => client_key,
+    };
+
+    let (parts, body) = req.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to buffer request body");
+            return ErrorResponse::localized("validation_error", "idempotency.body_too_large")
+                .into_response();
+        }
+    };
+
+    let request_hash =
+        IdempotencyStore::request_hash(parts.method.as_str(), parts.uri.path(), &body);
+    let store = IdempotencyStore::new(state.redis.clone(), state.config.idempotency.ttl_seconds);
+    let req = Request::from_parts(parts, Body::from(body));
+
+    match store.begin(&key, &request_hash).await {
+        Ok(IdempotencyState::New) => {}
+        Ok(IdempotencyState::Completed(stored)) => {
+            tracing::info!(idempotency_key = %key, "Replaying stored response");
+            return replay(stored);
+        }
+        Ok(IdempotencyState::InProgress) => {
+            return ErrorResponse::localized("conflict", "idempotency.in_progress").into_response();
+        }
+        Ok(IdempotencyState::Mismatch) => {
+            return ErrorResponse::localized(
+                "idempotency_key_mismatch",
+                "idempotency.key_mismatch",
+            )
+            .into_response();
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, "Idempotency store unavailable, processing request");
+            return next.run(req).await;
+        }
+    }
+
+    let response = next.run(req).await;
+
+    if response.status().is_server_error() {
+        if let Err(e) = store.release(&key).await {
+            tracing::warn!(error = %e, idempotency_key = %key, "Failed to release idempotency key");
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to buffer response body");
+            if let Err(e) = store.release(&key).await {
+                tracing::warn!(error = %e, idempotency_key = %key, "Failed to release idempotency key");
+            }
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let content_type = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+    let stored = StoredResponse::new(parts.status.as_u16(), content_type, &body);
+    if let Err(e) = store.complete(&key, &request_hash, stored).await {
+        tracing::warn!(error = %e, idempotency_key = %key, "Failed to store idempotent response");
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+/// Rebuild a stored response for replay
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body()).into_response();
+
+    let headers = response.headers_mut();
+    if let Some(content_type) = stored
+        .content_type
+        .as_deref()
+        .and_then(|ct| HeaderValue::from_str(ct).ok())
+    {
+        headers.insert(header::CONTENT_TYPE, content_type);
+    }
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replay_restores_status_body_and_headers() {
+        let stored = StoredResponse::new(
+            202,
+            Some("application/json".to_string()),
+            b"{\"data\":\"abc\"}",
+        );
+
+        let response = replay(stored);
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+
+        let body = to_bytes(response.into_body(), MAX_BODY_BYTES)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"{\"data\":\"abc\"}");
+    }
+}
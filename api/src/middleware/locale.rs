@@ -0,0 +1,139 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use common::i18n::Locale;
+
+use crate::state::AppState;
+
+/// Cookie holding the caller's preferred locale (set at login from the user profile)
+pub const LOCALE_COOKIE: &str = "locale";
+
+tokio::task_local! {
+    static REQUEST_LOCALE: Locale;
+}
+
+/// Locale of the request currently being handled
+///
+/// Falls back to the catalog default outside of a request scope (e.g. background tasks).
+pub fn current_locale() -> Locale {
+    REQUEST_LOCALE
+        .try_with(|locale| *locale)
+        .unwrap_or_default()
+}
+
+/// Locale middleware that resolves the message locale for each request
+///
+/// Resolution order: `?lang=` query parameter → `locale` cookie → `Accept-Language`
+/// header → `i18n.default_locale` from configuration. The resolved locale is available
+/// to handlers via `current_locale()` and as a request extension, and is echoed back
+/// in the `Content-Language` response header.
+#[tracing::instrument(skip(state, req, next))]
+pub async fn locale_middleware(
+    State(state): State<AppState>,
+    mut req: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let default_locale = state.config.i18n.locale();
+
+    let locale = resolve_locale(req.uri().query(), req.headers()).unwrap_or(default_locale);
+    req.extensions_mut().insert(locale);
+
+    let mut response = REQUEST_LOCALE.scope(locale, next.run(req)).await;
+    response.headers_mut().insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(locale.as_tag()),
+    );
+
+    response
+}
+
+/// Resolve the requested locale from query string, cookie and `Accept-Language`
+fn resolve_locale(query: Option<&str>, headers: &HeaderMap) -> Option<Locale> {
+    let from_query = query.and_then(|query| {
+        query.split('&').find_map(|pair| {
+            pair.strip_prefix("lang=")
+                .and_then(|value| value.parse::<Locale>().ok())
+        })
+    });
+
+    let from_cookie = || {
+        headers
+            .get(header::COOKIE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|cookies| {
+                cookies.split(';').find_map(|cookie| {
+                    let (name, value) = cookie.trim().split_once('=')?;
+                    if name == LOCALE_COOKIE {
+                        value.parse::<Locale>().ok()
+                    } else {
+                        None
+                    }
+                })
+            })
+    };
+
+    let from_accept_language = || {
+        headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|h| h.to_str().ok())
+            .and_then(Locale::from_accept_language)
+    };
+
+    from_query
+        .or_else(from_cookie)
+        .or_else(from_accept_language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_parameter_takes_precedence() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("locale=vi-VN"));
+        headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("vi"));
+
+        assert_eq!(
+            resolve_locale(Some("page=2&lang=en"), &headers),
+            Some(Locale::EnUs)
+        );
+    }
+
+    #[test]
+    fn test_cookie_beats_accept_language() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("auth_token=abc; locale=en-US"),
+        );
+        headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("vi-VN"));
+
+        assert_eq!(resolve_locale(None, &headers), Some(Locale::EnUs));
+    }
+
+    #[test]
+    fn test_accept_language_fallback_and_none() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::ACCEPT_LANGUAGE,
+            HeaderValue::from_static("fr;q=0.9, vi;q=0.8"),
+        );
+        assert_eq!(resolve_locale(None, &headers), Some(Locale::ViVn));
+
+        assert_eq!(resolve_locale(Some("lang=fr"), &HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn test_current_locale_uses_request_scope() {
+        assert_eq!(current_locale(), Locale::default());
+
+        let scoped = REQUEST_LOCALE
+            .scope(Locale::EnUs, async { current_locale() })
+            .await;
+        assert_eq!(scoped, Locale::EnUs);
+    }
+}
@@ -1,6 +1,13 @@
 mod auth;
+mod etag;
+pub mod idempotency;
+pub mod locale;
 pub mod rate_limit;
 mod rbac;
 
 pub use auth::auth_middleware;
+pub use etag::etag_middleware;
+pub use idempotency::idempotency_middleware;
+pub use locale::{current_locale, locale_middleware};
+pub use rate_limit::api_rate_limit_middleware;
 pub use rbac::rbac_middleware;
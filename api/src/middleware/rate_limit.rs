@@ -1,15 +1,41 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use common::db::repositories::RateLimitPolicyRepository;
+use common::db::RedisConnection;
+use common::models::{RateLimitScope, UserClaims};
+use common::rate_limit::RateLimitDecision;
 use redis::AsyncCommands;
-use std::time::Duration;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+use crate::handlers::ErrorResponse;
+use crate::state::AppState;
+
+/// Why a login attempt was refused before its credentials were checked
+#[derive(Debug, thiserror::Error)]
+pub enum LoginRateLimitError {
+    /// Too many failed attempts from the IP, blocked for this many more seconds
+    #[error("Too many failed login attempts, blocked for {0} seconds")]
+    Blocked(u64),
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+}
+
 /// Rate limiter for webhook and login endpoints
 pub struct RateLimiter {
-    redis_client: redis::Client,
+    redis: RedisConnection,
 }
 
 impl RateLimiter {
-    pub fn new(redis_client: redis::Client) -> Self {
-        Self { redis_client }
+    pub fn new(redis: RedisConnection) -> Self {
+        Self { redis }
     }
 
     /// Check if a webhook request should be rate limited
@@ -22,7 +48,7 @@ impl RateLimiter {
         max_requests: u32,
         window_seconds: u32,
     ) -> Result<(), anyhow::Error> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:webhook:{}", job_id);
         let window = Duration::from_secs(window_seconds as u64);
@@ -52,12 +78,8 @@ impl RateLimiter {
     /// Check if a login attempt should be rate limited
     /// Requirements: 19.14 - Rate limit after multiple failed login attempts (5+ in 15 min)
     #[tracing::instrument(skip(self))]
-    pub async fn check_login_rate_limit(&self, ip: &str) -> Result<(), String> {
-        let mut conn = self
-            .redis_client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| format!("Redis connection error: {}", e))?;
+    pub async fn check_login_rate_limit(&self, ip: &str) -> Result<(), LoginRateLimitError> {
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:login:{}", ip);
         let window_seconds = 15 * 60; // 15 minutes
@@ -65,20 +87,11 @@ impl RateLimiter {
 
         // Check if currently blocked
         let blocked_key = format!("rate_limit:login:blocked:{}", ip);
-        let is_blocked: Option<String> = conn
-            .get(&blocked_key)
-            .await
-            .map_err(|e| format!("Redis error: {}", e))?;
+        let is_blocked: Option<String> = conn.get(&blocked_key).await?;
 
         if is_blocked.is_some() {
-            let ttl: i64 = conn
-                .ttl(&blocked_key)
-                .await
-                .map_err(|e| format!("Redis error: {}", e))?;
-            return Err(format!(
-                "Too many failed login attempts. Please try again in {} seconds.",
-                ttl.max(0)
-            ));
+            let ttl: i64 = conn.ttl(&blocked_key).await?;
+            return Err(LoginRateLimitError::Blocked(ttl.max(0) as u64));
         }
 
         // Get current count
@@ -88,13 +101,9 @@ impl RateLimiter {
             // Block this IP for 15 minutes
             let _: () = conn
                 .set_ex(&blocked_key, "blocked", window_seconds as u64)
-                .await
-                .map_err(|e| format!("Redis error: {}", e))?;
+                .await?;
 
-            return Err(format!(
-                "Too many failed login attempts. Blocked for {} seconds.",
-                window_seconds
-            ));
+            return Err(LoginRateLimitError::Blocked(window_seconds as u64));
         }
 
         Ok(())
@@ -104,11 +113,7 @@ impl RateLimiter {
     /// Requirements: 19.14 - Track failed login attempts
     #[tracing::instrument(skip(self))]
     pub async fn record_failed_login(&self, ip: &str) -> Result<(), String> {
-        let mut conn = self
-            .redis_client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| format!("Redis connection error: {}", e))?;
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:login:{}", ip);
         let window_seconds = 15 * 60; // 15 minutes
@@ -140,11 +145,7 @@ impl RateLimiter {
     /// Requirements: 19.14 - Reset counter on successful login
     #[tracing::instrument(skip(self))]
     pub async fn reset_login_rate_limit(&self, ip: &str) -> Result<(), String> {
-        let mut conn = self
-            .redis_client
-            .get_multiplexed_async_connection()
-            .await
-            .map_err(|e| format!("Redis connection error: {}", e))?;
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:login:{}", ip);
         let _: () = conn
@@ -157,3 +158,302 @@ impl RateLimiter {
         Ok(())
     }
 }
+
+/// How long a policy lookup, including finding none, is reused before the
+/// database is asked again
+const POLICY_CACHE_TTL: Duration = Duration::from_secs(30);
+
+/// Identities whose policies are cached; the oldest lookup is dropped past this
+const MAX_CACHED_POLICIES: usize = 10_000;
+
+/// Requests per minute and burst of an identity's policy, `None` without one
+type CachedLimits = Option<(u32, u32)>;
+
+/// In-process cache of rate limit policy lookups, so requests don't each query
+/// the policy table
+///
+/// Policy changes made through this instance apply at once; other instances pick
+/// them up within `POLICY_CACHE_TTL`.
+#[derive(Default)]
+pub struct RateLimitPolicyCache {
+    entries: Mutex<HashMap<(RateLimitScope, String), (CachedLimits, Instant)>>,
+}
+
+impl RateLimitPolicyCache {
+    /// The identity's limits if they were looked up less than `POLICY_CACHE_TTL` ago
+    fn get(&self, scope: RateLimitScope, identity: &str) -> Option<CachedLimits> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(&(scope, identity.to_string()))
+            .filter(|(_, looked_up)| looked_up.elapsed() < POLICY_CACHE_TTL)
+            .map(|(limits, _)| *limits)
+    }
+
+    fn insert(&self, scope: RateLimitScope, identity: &str, limits: CachedLimits) {
+        let key = (scope, identity.to_string());
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_CACHED_POLICIES && !entries.contains_key(&key) {
+            entries.retain(|_, (_, looked_up)| looked_up.elapsed() < POLICY_CACHE_TTL);
+        }
+        if entries.len() >= MAX_CACHED_POLICIES && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (_, looked_up))| *looked_up)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(key, (limits, Instant::now()));
+    }
+
+    /// Forget the identity's limits after its policy changed
+    pub fn invalidate(&self, scope: RateLimitScope, identity: &str) {
+        self.entries
+            .lock()
+            .unwrap()
+            .remove(&(scope, identity.to_string()));
+    }
+}
+
+/// The identity's policy limits, from the cache or the database
+async fn policy_limits(
+    state: &AppState,
+    scope: RateLimitScope,
+    identity: &str,
+) -> Result<CachedLimits, common::errors::DatabaseError> {
+    if let Some(limits) = state.rate_limit_policies.get(scope, identity) {
+        return Ok(limits);
+    }
+
+    let limits = RateLimitPolicyRepository::new(state.db_pool.clone())
+        .find(scope, identity)
+        .await?
+        .map(|policy| (policy.requests_per_minute as u32, policy.burst as u32));
+    state.rate_limit_policies.insert(scope, identity, limits);
+    Ok(limits)
+}
+
+/// API rate limiting middleware with per-identity token buckets
+///
+/// Every identity present on the request is charged one token: the authenticated
+/// user, the `X-API-Key` header (stored as its SHA-256 hash) and the user's tenant
+/// from their token. Limits come from admin-configured policies, cached for
+/// `POLICY_CACHE_TTL`, falling back to the `[rate_limit]` defaults. The most
+/// restrictive bucket is reported through `X-RateLimit-Limit`,
+/// `X-RateLimit-Remaining`, `X-RateLimit-Reset` and, when the request is rejected,
+/// `Retry-After`.
+///
+/// Redis or database failures are logged and the request is let through.
+#[tracing::instrument(skip(state, req, next))]
+pub async fn api_rate_limit_middleware(
+    State(state): State<AppState>,
+    req: Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let config = &state.config.rate_limit;
+    if !config.enabled {
+        return next.run(req).await;
+    }
+
+    let identities = request_identities(&req);
+    let limiter = common::rate_limit::RateLimiter::new(state.redis.clone());
+
+    let mut most_restrictive: Option<RateLimitDecision> = None;
+    for (scope, identity) in &identities {
+        let (requests_per_minute, burst) = match policy_limits(&state, *scope, identity).await {
+            Ok(Some(limits)) => limits,
+            Ok(None) => (config.requests_per_minute, config.burst),
+            Err(e) => {
+                tracing::warn!(error = %e, scope = %scope, "Failed to load rate limit policy");
+                (config.requests_per_minute, config.burst)
+            }
+        };
+
+        match limiter
+            .check_token_bucket(*scope, identity, requests_per_minute, burst)
+            .await
+        {
+            Ok(decision) => {
+                most_restrictive = Some(match most_restrictive {
+                    Some(current) => more_restrictive(current, decision),
+                    None => decision,
+                });
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, scope = %scope, "Rate limit check failed, allowing request");
+            }
+        }
+    }
+
+    let Some(decision) = most_restrictive else {
+        return next.run(req).await;
+    };
+
+    let mut response = if decision.allowed {
+        next.run(req).await
+    } else {
+        ErrorResponse::localized("rate_limit_exceeded", "error.rate_limit_exceeded").into_response()
+    };
+
+    apply_rate_limit_headers(response.headers_mut(), &decision);
+    response
+}
+
+/// Collect the identities a request is charged against
+fn request_identities(req: &Request<axum::body::Body>) -> Vec<(RateLimitScope, String)> {
+    let mut identities = Vec::new();
+
+    let claims = req.extensions().get::<UserClaims>();
+    if let Some(claims) = claims {
+        identities.push((RateLimitScope::User, claims.sub.clone()));
+    }
+
+    if let Some(api_key) = header_value(req.headers(), API_KEY_HEADER) {
+        identities.push((RateLimitScope::ApiKey, hash_api_key(api_key)));
+    }
+
+    if let Some(tenant) = claims.and_then(|claims| claims.tenant_id.as_ref()) {
+        identities.push((RateLimitScope::Tenant, tenant.clone()));
+    }
+
+    identities
+}
+
+/// Header carrying an API key
+const API_KEY_HEADER: &str = "x-api-key";
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|h| h.to_str().ok())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+/// API keys are never stored in Redis or the policy table in clear text
+pub fn hash_api_key(api_key: &str) -> String {
+    format!("{:x}", Sha256::digest(api_key.as_bytes()))
+}
+
+/// Pick the decision to report: a rejection wins, then the fewest remaining tokens
+fn more_restrictive(a: RateLimitDecision, b: RateLimitDecision) -> RateLimitDecision {
+    match (a.allowed, b.allowed) {
+        (false, true) => a,
+        (true, false) => b,
+        _ if b.remaining < a.remaining => b,
+        _ => a,
+    }
+}
+
+fn apply_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    headers.insert("x-ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert(
+        "x-ratelimit-remaining",
+        HeaderValue::from(decision.remaining),
+    );
+    headers.insert(
+        "x-ratelimit-reset",
+        HeaderValue::from(decision.reset_after_secs),
+    );
+    if let Some(retry_after) = decision.retry_after_secs {
+        headers.insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(allowed: bool, remaining: u32) -> RateLimitDecision {
+        RateLimitDecision {
+            allowed,
+            limit: 10,
+            remaining,
+            reset_after_secs: 5,
+            retry_after_secs: (!allowed).then_some(2),
+        }
+    }
+
+    #[test]
+    fn test_policy_cache_invalidate() {
+        let cache = RateLimitPolicyCache::default();
+        assert_eq!(cache.get(RateLimitScope::User, "alice"), None);
+
+        cache.insert(RateLimitScope::User, "alice", Some((60, 10)));
+        cache.insert(RateLimitScope::Tenant, "alice", None);
+        assert_eq!(
+            cache.get(RateLimitScope::User, "alice"),
+            Some(Some((60, 10)))
+        );
+        assert_eq!(cache.get(RateLimitScope::Tenant, "alice"), Some(None));
+
+        cache.invalidate(RateLimitScope::User, "alice");
+        assert_eq!(cache.get(RateLimitScope::User, "alice"), None);
+        assert_eq!(cache.get(RateLimitScope::Tenant, "alice"), Some(None));
+    }
+
+    #[test]
+    fn test_rejection_is_most_restrictive() {
+        let picked = more_restrictive(decision(true, 0), decision(false, 3));
+        assert!(!picked.allowed);
+
+        let picked = more_restrictive(decision(true, 8), decision(true, 2));
+        assert_eq!(picked.remaining, 2);
+    }
+
+    #[test]
+    fn test_identities_from_headers() {
+        let req = Request::builder()
+            .uri("/api/jobs")
+            .header("X-API-Key", "secret-key")
+            .header("X-Tenant-ID", "acme")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        // The tenant header is the client's word and isn't charged
+        let identities = request_identities(&req);
+        assert_eq!(identities.len(), 1);
+        assert_eq!(identities[0].0, RateLimitScope::ApiKey);
+        assert_ne!(identities[0].1, "secret-key");
+        assert_eq!(identities[0].1.len(), 64);
+    }
+
+    #[test]
+    fn test_tenant_identity_from_claims() {
+        let mut req = Request::builder()
+            .uri("/api/jobs")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        req.extensions_mut().insert(UserClaims {
+            sub: "user-1".to_string(),
+            username: "alice".to_string(),
+            permissions: vec![],
+            exp: 0,
+            iat: 0,
+            sid: None,
+            tenant_id: Some("acme".to_string()),
+        });
+
+        let identities = request_identities(&req);
+        assert_eq!(
+            identities,
+            vec![
+                (RateLimitScope::User, "user-1".to_string()),
+                (RateLimitScope::Tenant, "acme".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_headers_include_retry_after_only_when_denied() {
+        let mut headers = HeaderMap::new();
+        apply_rate_limit_headers(&mut headers, &decision(true, 4));
+        assert_eq!(headers["x-ratelimit-remaining"], "4");
+        assert!(headers.get(header::RETRY_AFTER).is_none());
+
+        let mut headers = HeaderMap::new();
+        apply_rate_limit_headers(&mut headers, &decision(false, 0));
+        assert_eq!(headers[header::RETRY_AFTER], "2");
+    }
+}
@@ -0,0 +1,424 @@
+// Route permission registry
+// Requirements: 19.1.15-67 - Permission-based endpoint access control
+//
+// Every protected route has exactly one entry per method here. The RBAC middleware
+// rejects requests to routes without an entry, so a new handler is denied until
+// its permission is declared, and `/api/permissions` publishes the same table.
+
+use axum::http::Method;
+use serde::Serialize;
+
+/// Any authenticated user; the handler checks ownership itself
+const AUTHENTICATED: &[&str] = &[];
+const JOB_READ: &[&str] = &["job:read"];
+const JOB_WRITE: &[&str] = &["job:write"];
+const JOB_EXECUTE: &[&str] = &["job:execute"];
+const JOB_DELETE: &[&str] = &["job:delete"];
+const JOB_EXPORT: &[&str] = &["job:export"];
+const JOB_IMPORT: &[&str] = &["job:import"];
+const EXECUTION_READ: &[&str] = &["execution:read"];
+const EXECUTION_STOP: &[&str] = &["execution:stop"];
+const VARIABLE_READ: &[&str] = &["variable:read"];
+const VARIABLE_WRITE: &[&str] = &["variable:write"];
+const WEBHOOK_WRITE: &[&str] = &["webhook:write"];
+const USER_MANAGE: &[&str] = &["user:manage"];
+const SYSTEM_CONFIG: &[&str] = &["system:config"];
+/// Both regular users and admins see the dashboard
+const DASHBOARD: &[&str] = &["dashboard:user", "dashboard:admin"];
+
+/// Only permission of the identity given to visitors without a token when
+/// `auth.guest_access` is enabled; it is limited to routes marked for guests
+pub const GUEST_PERMISSION: &str = "dashboard:guest";
+
+/// Permissions required for one method on one route
+#[derive(Debug, Serialize)]
+pub struct RoutePermission {
+    pub method: &'static str,
+    /// Route pattern as registered in the router, with `:param` segments
+    pub path: &'static str,
+    /// Any one of these grants access; empty means any authenticated user
+    pub permissions: &'static [&'static str],
+    /// Also open to guests: read-only and without secrets
+    pub guest: bool,
+}
+
+const fn rule(
+    method: &'static str,
+    path: &'static str,
+    permissions: &'static [&'static str],
+) -> RoutePermission {
+    RoutePermission {
+        method,
+        path,
+        permissions,
+        guest: false,
+    }
+}
+
+/// A page for wallboards; only reads are ever opened to guests
+const fn guest_rule(path: &'static str, permissions: &'static [&'static str]) -> RoutePermission {
+    RoutePermission {
+        method: "GET",
+        path,
+        permissions,
+        guest: true,
+    }
+}
+
+/// Permissions of all protected routes
+///
+/// The first matching entry wins, so literal routes come before parameterized
+/// routes of the same shape (`/api/jobs/export` before `/api/jobs/:id`).
+pub static ROUTE_PERMISSIONS: &[RoutePermission] = &[
+    // Jobs
+    rule("GET", "/api/jobs", JOB_READ),
+    rule("POST", "/api/jobs", JOB_WRITE),
+    rule("POST", "/api/jobs/export", JOB_EXPORT),
+    rule("POST", "/api/jobs/export/bulk", JOB_EXPORT),
+    rule("POST", "/api/jobs/export/encrypted", SYSTEM_CONFIG),
+    rule("POST", "/api/jobs/import", JOB_IMPORT),
+    rule("POST", "/api/jobs/import/bulk", JOB_IMPORT),
+    rule("POST", "/api/jobs/import/encrypted", JOB_IMPORT),
+    rule("GET", "/api/jobs/:id", JOB_READ),
+    rule("PUT", "/api/jobs/:id", JOB_WRITE),
+    rule("DELETE", "/api/jobs/:id", JOB_DELETE),
+    rule("POST", "/api/jobs/:id/trigger", JOB_EXECUTE),
+    rule("POST", "/api/jobs/:id/backfill", JOB_EXECUTE),
+    rule("GET", "/api/jobs/:id/trigger-at", JOB_EXECUTE),
+    rule("POST", "/api/jobs/:id/trigger-at", JOB_EXECUTE),
+    rule(
+        "DELETE",
+        "/api/jobs/:id/trigger-at/:trigger_id",
+        JOB_EXECUTE,
+    ),
+    rule("GET", "/api/jobs/:id/blackout-windows", JOB_READ),
+    rule("POST", "/api/jobs/:id/blackout-windows", JOB_WRITE),
+    rule(
+        "DELETE",
+        "/api/jobs/:id/blackout-windows/:window_id",
+        JOB_WRITE,
+    ),
+    rule("GET", "/api/jobs/:id/webhook-payloads", JOB_READ),
+    rule("POST", "/api/jobs/:id/simulate", JOB_WRITE),
+    rule("PUT", "/api/jobs/:id/enable", JOB_EXECUTE),
+    rule("PUT", "/api/jobs/:id/disable", JOB_EXECUTE),
+    rule("PUT", "/api/jobs/:id/pause", JOB_EXECUTE),
+    rule("PUT", "/api/jobs/:id/resume", JOB_EXECUTE),
+    rule("GET", "/api/jobs/:id/state", JOB_READ),
+    rule("PUT", "/api/jobs/:id/state/:key", JOB_WRITE),
+    rule("DELETE", "/api/jobs/:id/state/:key", JOB_WRITE),
+    // Removing a deprecation is an edit, not a delete
+    rule("PUT", "/api/jobs/:id/deprecation", JOB_WRITE),
+    rule("DELETE", "/api/jobs/:id/deprecation", JOB_WRITE),
+    rule("GET", "/api/schedules/describe", JOB_READ),
+    // Executions
+    rule("GET", "/api/executions", EXECUTION_READ),
+    rule("GET", "/api/executions/export", EXECUTION_READ),
+    rule("GET", "/api/executions/matrix/:group_id", EXECUTION_READ),
+    rule("GET", "/api/executions/:id", EXECUTION_READ),
+    rule("GET", "/api/executions/:id/progress", EXECUTION_READ),
+    rule("GET", "/api/executions/:id/history", EXECUTION_READ),
+    rule("POST", "/api/executions/:id/stop", EXECUTION_STOP),
+    rule("POST", "/api/executions/:id/replay", JOB_EXECUTE),
+    rule("GET", "/api/failures/groups", EXECUTION_READ),
+    rule(
+        "GET",
+        "/api/failures/groups/:fingerprint/note",
+        EXECUTION_READ,
+    ),
+    rule("PUT", "/api/failures/groups/:fingerprint/note", JOB_WRITE),
+    rule(
+        "DELETE",
+        "/api/failures/groups/:fingerprint/note",
+        JOB_WRITE,
+    ),
+    // Saved execution filters only describe what to read; owners are checked in
+    // the handlers
+    rule("GET", "/api/execution-filters", EXECUTION_READ),
+    rule("POST", "/api/execution-filters", EXECUTION_READ),
+    rule("GET", "/api/execution-filters/:id", EXECUTION_READ),
+    rule("PUT", "/api/execution-filters/:id", EXECUTION_READ),
+    rule("DELETE", "/api/execution-filters/:id", EXECUTION_READ),
+    // Variables
+    rule("GET", "/api/variables", VARIABLE_READ),
+    rule("POST", "/api/variables", VARIABLE_WRITE),
+    rule("PUT", "/api/variables/:id", VARIABLE_WRITE),
+    rule("DELETE", "/api/variables/:id", VARIABLE_WRITE),
+    // Users; anyone may view their own profile, the handler checks the id
+    rule("GET", "/api/users", USER_MANAGE),
+    rule("POST", "/api/users", USER_MANAGE),
+    rule("GET", "/api/users/:id", JOB_READ),
+    rule("PUT", "/api/users/:id", USER_MANAGE),
+    rule("DELETE", "/api/users/:id", USER_MANAGE),
+    rule("PUT", "/api/users/:id/roles", USER_MANAGE),
+    rule("PUT", "/api/users/:id/password", USER_MANAGE),
+    rule("GET", "/api/roles", AUTHENTICATED),
+    // Login sessions; admins may pass any user
+    rule("GET", "/api/auth/sessions", AUTHENTICATED),
+    rule("DELETE", "/api/auth/sessions/:id", AUTHENTICATED),
+    rule("GET", "/api/permissions", AUTHENTICATED),
+    // System configuration (admin only)
+    rule("GET", "/api/system/rate-limits", SYSTEM_CONFIG),
+    rule("PUT", "/api/system/rate-limits", SYSTEM_CONFIG),
+    rule(
+        "DELETE",
+        "/api/system/rate-limits/:scope/:identity",
+        SYSTEM_CONFIG,
+    ),
+    rule("GET", "/api/system/feature-flags", SYSTEM_CONFIG),
+    rule("PUT", "/api/system/feature-flags", SYSTEM_CONFIG),
+    rule("DELETE", "/api/system/feature-flags/:id", SYSTEM_CONFIG),
+    rule("GET", "/api/system/calendars", SYSTEM_CONFIG),
+    rule("PUT", "/api/system/calendars", SYSTEM_CONFIG),
+    rule("DELETE", "/api/system/calendars/:name", SYSTEM_CONFIG),
+    rule("GET", "/api/system/calendars/:name/holidays", SYSTEM_CONFIG),
+    rule("GET", "/api/system/connections", SYSTEM_CONFIG),
+    rule("PUT", "/api/system/connections", SYSTEM_CONFIG),
+    rule("GET", "/api/system/connections/:name", SYSTEM_CONFIG),
+    rule("DELETE", "/api/system/connections/:name", SYSTEM_CONFIG),
+    rule(
+        "GET",
+        "/api/system/connections/:name/verifications",
+        SYSTEM_CONFIG,
+    ),
+    // System steps are checked against system:config in the handler
+    rule("GET", "/api/capabilities", AUTHENTICATED),
+    // Queue, usage, backup/restore, failover, webhook delivery, credential expiry,
+    // status page and subject data erasure administration (admin only)
+    rule("GET", "/api/admin/queue", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/queue/messages", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/queue/purge", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/usage", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/backup", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/restore", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/failover", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/failover/promote", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/failover/demote", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/webhook-deliveries", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/webhook-deliveries/:id", SYSTEM_CONFIG),
+    rule(
+        "POST",
+        "/api/admin/webhook-deliveries/:id/redeliver",
+        SYSTEM_CONFIG,
+    ),
+    rule("GET", "/api/admin/scheduler-journal", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/scheduler/drain", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/scheduler/resume", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/credential-expirations", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/tls-materials", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/tls-materials", SYSTEM_CONFIG),
+    rule("PUT", "/api/admin/tls-materials/:id", SYSTEM_CONFIG),
+    rule("DELETE", "/api/admin/tls-materials/:id", SYSTEM_CONFIG),
+    rule("GET", "/api/admin/status-pages", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/status-pages", SYSTEM_CONFIG),
+    rule("PUT", "/api/admin/status-pages/:id", SYSTEM_CONFIG),
+    rule("DELETE", "/api/admin/status-pages/:id", SYSTEM_CONFIG),
+    rule(
+        "POST",
+        "/api/admin/status-pages/:id/rotate-token",
+        SYSTEM_CONFIG,
+    ),
+    rule("GET", "/api/admin/erasures", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/erasures", SYSTEM_CONFIG),
+    rule("POST", "/api/admin/erasures/search", SYSTEM_CONFIG),
+    // Webhooks
+    rule("POST", "/api/webhooks/:path", WEBHOOK_WRITE),
+    // Server-Sent Events
+    guest_rule("/api/events", AUTHENTICATED),
+    // Dashboard; the layout API only touches the caller's own dashboard. Guests
+    // don't see job definitions, variables, exports or webhook URLs in the DLQ
+    rule("GET", "/api/dashboard/layout", DASHBOARD),
+    rule("PUT", "/api/dashboard/layout", DASHBOARD),
+    rule("DELETE", "/api/dashboard/layout", DASHBOARD),
+    guest_rule("/dashboard", DASHBOARD),
+    guest_rule("/dashboard/jobs", DASHBOARD),
+    rule("GET", "/dashboard/jobs/export", DASHBOARD),
+    rule("GET", "/dashboard/jobs/new", DASHBOARD),
+    rule("GET", "/dashboard/jobs/:id", DASHBOARD),
+    rule("GET", "/dashboard/jobs/:id/modal", DASHBOARD),
+    guest_rule("/dashboard/executions", DASHBOARD),
+    rule("GET", "/dashboard/executions/export", DASHBOARD),
+    rule("GET", "/dashboard/variables", DASHBOARD),
+    rule("GET", "/dashboard/dlq", DASHBOARD),
+    guest_rule("/dashboard/workers", DASHBOARD),
+];
+
+impl RoutePermission {
+    /// Whether this entry covers the request; HEAD is answered by GET routes
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        let method = if method == Method::HEAD {
+            "GET"
+        } else {
+            method.as_str()
+        };
+        if self.method != method {
+            return false;
+        }
+
+        let mut pattern = self.path.split('/');
+        let mut segments = path.split('/');
+        loop {
+            match (pattern.next(), segments.next()) {
+                (None, None) => return true,
+                (Some(p), Some(s)) if p.starts_with(':') && !s.is_empty() => {}
+                (Some(p), Some(s)) if p == s => {}
+                _ => return false,
+            }
+        }
+    }
+
+    /// Whether a user with the given permissions may call the route
+    pub fn allows(&self, granted: &[String]) -> bool {
+        if granted.iter().any(|g| g == GUEST_PERMISSION) {
+            return self.guest;
+        }
+        self.permissions.is_empty()
+            || self
+                .permissions
+                .iter()
+                .any(|required| granted.iter().any(|g| g == required))
+    }
+}
+
+/// Entry for a request, or None if the route isn't registered
+pub fn find(method: &Method, path: &str) -> Option<&'static RoutePermission> {
+    ROUTE_PERMISSIONS
+        .iter()
+        .find(|entry| entry.matches(method, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (method, route pattern) of every protected route in routes.rs
+    fn protected_routes() -> Vec<(String, String)> {
+        let source = include_str!("routes.rs");
+        let start = source
+            .find("let protected_routes")
+            .expect("protected routes");
+        let end = start + source[start..].find(".layer(").expect("middleware layers");
+
+        let mut routes = Vec::new();
+        for block in source[start..end].split(".route(").skip(1) {
+            let path = block.split('"').nth(1).expect("route path").to_string();
+            for method in ["get", "post", "put", "delete"] {
+                let call = format!("{}(", method);
+                let registered = block.match_indices(&call).any(|(i, _)| {
+                    !block[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == ':')
+                });
+                if registered {
+                    routes.push((method.to_uppercase(), path.clone()));
+                }
+            }
+        }
+        routes
+    }
+
+    fn concrete(pattern: &str) -> String {
+        pattern
+            .split('/')
+            .map(|s| if s.starts_with(':') { "42" } else { s })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn required(method: Method, path: &str) -> Option<&'static [&'static str]> {
+        find(&method, path).map(|entry| entry.permissions)
+    }
+
+    #[test]
+    fn test_every_protected_route_is_registered() {
+        let routes = protected_routes();
+        assert!(routes.len() > 50, "parsed {} routes", routes.len());
+
+        for (method, path) in &routes {
+            let method = Method::from_bytes(method.as_bytes()).unwrap();
+            let entry = find(&method, &concrete(path));
+            assert_eq!(
+                entry.map(|e| e.path),
+                Some(path.as_str()),
+                "{} {} resolves to the wrong registry entry",
+                method,
+                path
+            );
+        }
+
+        for entry in ROUTE_PERMISSIONS {
+            assert!(
+                routes
+                    .iter()
+                    .any(|(m, p)| m == entry.method && p == entry.path),
+                "{} {} is registered but not routed",
+                entry.method,
+                entry.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_authorization_matrix() {
+        assert_eq!(required(Method::GET, "/api/jobs"), Some(JOB_READ));
+        assert_eq!(required(Method::HEAD, "/api/jobs/7"), Some(JOB_READ));
+        assert_eq!(required(Method::DELETE, "/api/jobs/7"), Some(JOB_DELETE));
+        assert_eq!(required(Method::POST, "/api/jobs/export"), Some(JOB_EXPORT));
+        assert_eq!(
+            required(Method::POST, "/api/jobs/export/encrypted"),
+            Some(SYSTEM_CONFIG)
+        );
+        assert_eq!(
+            required(Method::GET, "/api/jobs/7/trigger-at"),
+            Some(JOB_EXECUTE)
+        );
+        assert_eq!(
+            required(Method::POST, "/api/executions/7/replay"),
+            Some(JOB_EXECUTE)
+        );
+        assert_eq!(required(Method::GET, "/api/users/7"), Some(JOB_READ));
+        assert_eq!(required(Method::GET, "/api/users"), Some(USER_MANAGE));
+        assert_eq!(
+            required(Method::POST, "/api/admin/queue/purge"),
+            Some(SYSTEM_CONFIG)
+        );
+        assert_eq!(
+            required(Method::DELETE, "/api/auth/sessions/7"),
+            Some(AUTHENTICATED)
+        );
+        assert_eq!(required(Method::GET, "/dashboard/jobs/7"), Some(DASHBOARD));
+
+        // Unknown routes and methods are not registered and so denied
+        assert_eq!(required(Method::PATCH, "/api/jobs/7"), None);
+        assert_eq!(required(Method::GET, "/api/jobs/7/unknown"), None);
+        assert_eq!(required(Method::GET, "/api/jobs/"), None);
+    }
+
+    #[test]
+    fn test_allows_any_listed_permission() {
+        let entry = find(&Method::GET, "/dashboard").unwrap();
+        assert!(entry.allows(&["dashboard:user".to_string()]));
+        assert!(entry.allows(&["dashboard:admin".to_string()]));
+        assert!(!entry.allows(&["job:read".to_string()]));
+
+        let entry = find(&Method::GET, "/api/events").unwrap();
+        assert!(entry.allows(&[]));
+    }
+
+    #[test]
+    fn test_guest_is_limited_to_guest_routes() {
+        let guest = [GUEST_PERMISSION.to_string()];
+        assert!(find(&Method::GET, "/dashboard").unwrap().allows(&guest));
+        assert!(find(&Method::GET, "/api/events").unwrap().allows(&guest));
+        assert!(!find(&Method::GET, "/dashboard/jobs/7")
+            .unwrap()
+            .allows(&guest));
+        assert!(!find(&Method::GET, "/dashboard/variables")
+            .unwrap()
+            .allows(&guest));
+        // Routes open to any signed-in user are not open to guests
+        assert!(!find(&Method::GET, "/api/roles").unwrap().allows(&guest));
+
+        for entry in ROUTE_PERMISSIONS.iter().filter(|entry| entry.guest) {
+            assert_eq!(entry.method, "GET", "{} is open to guests", entry.path);
+        }
+    }
+}
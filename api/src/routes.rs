@@ -3,11 +3,15 @@ use axum::{
     Router,
 };
 use tower::ServiceBuilder;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
 
 use crate::handlers;
-use crate::middleware::{auth_middleware, rbac_middleware};
+use crate::middleware::{
+    api_rate_limit_middleware, auth_middleware, etag_middleware, idempotency_middleware,
+    locale_middleware, rbac_middleware,
+};
 use crate::state::AppState;
 
 /// Create the main application router with all routes and middleware
@@ -31,7 +35,17 @@ pub fn create_router(state: AppState) -> Router {
             post(handlers::login::login_form_submit),
         )
         .route("/auth/set-token", get(handlers::login::set_token_page))
-        .route("/api/auth/refresh", post(handlers::auth::refresh_token));
+        .route("/api/auth/refresh", post(handlers::auth::refresh_token))
+        .route("/.well-known/jwks.json", get(handlers::auth::jwks))
+        // Token-scoped public status pages
+        .route(
+            "/status/:token",
+            get(handlers::status_pages::public_status_page),
+        )
+        .route(
+            "/api/status/:token",
+            get(handlers::status_pages::get_public_status),
+        );
 
     // Protected routes (authentication required)
     let protected_routes = Router::new()
@@ -42,21 +56,113 @@ pub fn create_router(state: AppState) -> Router {
         .route("/api/jobs/:id", put(handlers::jobs::update_job))
         .route("/api/jobs/:id", delete(handlers::jobs::delete_job))
         .route("/api/jobs/:id/trigger", post(handlers::jobs::trigger_job))
+        .route(
+            "/api/jobs/:id/backfill",
+            post(handlers::backfill::backfill_job),
+        )
+        .route(
+            "/api/jobs/:id/trigger-at",
+            post(handlers::jobs::trigger_job_at).get(handlers::jobs::list_scheduled_triggers),
+        )
+        .route(
+            "/api/jobs/:id/trigger-at/:trigger_id",
+            delete(handlers::jobs::cancel_scheduled_trigger),
+        )
+        .route(
+            "/api/jobs/:id/blackout-windows",
+            post(handlers::jobs::create_blackout_window).get(handlers::jobs::list_blackout_windows),
+        )
+        .route(
+            "/api/jobs/:id/blackout-windows/:window_id",
+            delete(handlers::jobs::delete_blackout_window),
+        )
+        .route(
+            "/api/jobs/:id/webhook-payloads",
+            get(handlers::webhooks::list_webhook_payloads),
+        )
+        .route(
+            "/api/jobs/:id/simulate",
+            post(handlers::webhooks::simulate_job),
+        )
         .route("/api/jobs/:id/enable", put(handlers::jobs::enable_job))
         .route("/api/jobs/:id/disable", put(handlers::jobs::disable_job))
+        .route("/api/jobs/:id/pause", put(handlers::jobs::pause_job))
+        .route("/api/jobs/:id/resume", put(handlers::jobs::resume_job))
+        // State a job keeps across executions, read by steps as {{state.<key>}}
+        .route(
+            "/api/jobs/:id/state",
+            get(handlers::job_state::list_job_state),
+        )
+        .route(
+            "/api/jobs/:id/state/:key",
+            put(handlers::job_state::set_job_state)
+                .delete(handlers::job_state::delete_job_state),
+        )
+        .route(
+            "/api/jobs/:id/deprecation",
+            put(handlers::jobs::deprecate_job).delete(handlers::jobs::undeprecate_job),
+        )
+        // Cron expression descriptions for the job form
+        .route(
+            "/api/schedules/describe",
+            get(handlers::schedules::describe_schedule),
+        )
         // Execution history endpoints
         .route(
             "/api/executions",
             get(handlers::executions::list_executions),
         )
+        .route(
+            "/api/executions/export",
+            get(handlers::executions::export_executions),
+        )
+        .route(
+            "/api/executions/matrix/:group_id",
+            get(handlers::executions::get_matrix_group),
+        )
         .route(
             "/api/executions/:id",
             get(handlers::executions::get_execution),
         )
+        .route(
+            "/api/executions/:id/progress",
+            get(handlers::executions::get_execution_progress),
+        )
+        .route(
+            "/api/executions/:id/history",
+            get(handlers::executions::get_execution_status_history),
+        )
         .route(
             "/api/executions/:id/stop",
             post(handlers::executions::stop_execution),
         )
+        .route(
+            "/api/executions/:id/replay",
+            post(handlers::executions::replay_execution),
+        )
+        // Failed executions grouped by error fingerprint
+        .route(
+            "/api/failures/groups",
+            get(handlers::failures::list_failure_groups),
+        )
+        .route(
+            "/api/failures/groups/:fingerprint/note",
+            get(handlers::failures::get_failure_note)
+                .put(handlers::failures::upsert_failure_note)
+                .delete(handlers::failures::delete_failure_note),
+        )
+        // Saved execution filters, opened with ?filter_id= on the executions lists
+        .route(
+            "/api/execution-filters",
+            get(handlers::execution_filters::list_execution_filters)
+                .post(handlers::execution_filters::create_execution_filter),
+        )
+        .route(
+            "/api/execution-filters/:id",
+            get(handlers::execution_filters::get_execution_filter)
+                .put(handlers::execution_filters::update_execution_filter)
+                .delete(handlers::execution_filters::delete_execution_filter),
+        )
         // Variable management endpoints
         .route("/api/variables", post(handlers::variables::create_variable))
         .route("/api/variables", get(handlers::variables::list_variables))
@@ -81,6 +187,165 @@ pub fn create_router(state: AppState) -> Router {
             put(handlers::users::update_password),
         )
         .route("/api/roles", get(handlers::users::list_roles))
+        // Login sessions (own sessions; admins can pass any user)
+        .route("/api/auth/sessions", get(handlers::sessions::list_sessions))
+        .route(
+            "/api/auth/sessions/:id",
+            delete(handlers::sessions::revoke_session),
+        )
+        // Rate limit policy endpoints (admin only)
+        .route(
+            "/api/system/rate-limits",
+            get(handlers::rate_limits::list_rate_limits),
+        )
+        .route(
+            "/api/system/rate-limits",
+            put(handlers::rate_limits::upsert_rate_limit),
+        )
+        .route(
+            "/api/system/rate-limits/:scope/:identity",
+            delete(handlers::rate_limits::delete_rate_limit),
+        )
+        // Feature flag endpoints (admin only)
+        .route(
+            "/api/system/feature-flags",
+            get(handlers::feature_flags::list_feature_flags)
+                .put(handlers::feature_flags::upsert_feature_flag),
+        )
+        .route(
+            "/api/system/feature-flags/:id",
+            delete(handlers::feature_flags::delete_feature_flag),
+        )
+        // Business-day calendar endpoints (admin only)
+        .route(
+            "/api/system/calendars",
+            get(handlers::calendars::list_calendars).put(handlers::calendars::upsert_calendar),
+        )
+        .route(
+            "/api/system/calendars/:name",
+            delete(handlers::calendars::delete_calendar),
+        )
+        .route(
+            "/api/system/calendars/:name/holidays",
+            get(handlers::calendars::list_calendar_holidays),
+        )
+        // Connection profile endpoints (admin only)
+        .route(
+            "/api/system/connections",
+            get(handlers::connection_profiles::list_connection_profiles)
+                .put(handlers::connection_profiles::upsert_connection_profile),
+        )
+        .route(
+            "/api/system/connections/:name",
+            get(handlers::connection_profiles::get_connection_profile)
+                .delete(handlers::connection_profiles::delete_connection_profile),
+        )
+        .route(
+            "/api/system/connections/:name/verifications",
+            get(handlers::connection_profiles::get_connection_verifications),
+        )
+        // Route permission registry
+        .route(
+            "/api/permissions",
+            get(handlers::permissions::list_permissions),
+        )
+        // Capability registry
+        .route(
+            "/api/capabilities",
+            get(handlers::capabilities::get_capabilities),
+        )
+        // Queue inspection endpoints (admin only)
+        .route("/api/admin/queue", get(handlers::queue::get_queue_stats))
+        .route(
+            "/api/admin/queue/messages",
+            get(handlers::queue::peek_queue_messages),
+        )
+        .route("/api/admin/queue/purge", post(handlers::queue::purge_queue))
+        // Tenant usage report (admin only)
+        .route("/api/admin/usage", get(handlers::usage::get_usage))
+        // Disaster-recovery snapshot and restore (admin only)
+        .route("/api/admin/backup", post(handlers::backup::create_backup))
+        .route("/api/admin/restore", post(handlers::backup::restore_backup))
+        // Multi-region failover (admin only)
+        .route(
+            "/api/admin/failover",
+            get(handlers::failover::get_failover_status),
+        )
+        .route(
+            "/api/admin/failover/promote",
+            post(handlers::failover::promote_region),
+        )
+        .route(
+            "/api/admin/failover/demote",
+            post(handlers::failover::demote_region),
+        )
+        // Outbound webhook delivery log and redelivery (admin only)
+        .route(
+            "/api/admin/webhook-deliveries",
+            get(handlers::webhook_deliveries::list_webhook_deliveries),
+        )
+        .route(
+            "/api/admin/webhook-deliveries/:id",
+            get(handlers::webhook_deliveries::get_webhook_delivery),
+        )
+        .route(
+            "/api/admin/webhook-deliveries/:id/redeliver",
+            post(handlers::webhook_deliveries::redeliver_webhook_delivery),
+        )
+        // Per-tick scheduler decisions (admin only)
+        .route(
+            "/api/admin/scheduler-journal",
+            get(handlers::scheduler_journal::list_scheduler_ticks),
+        )
+        // Scheduler drain for deploys and maintenance (admin only)
+        .route(
+            "/api/admin/scheduler/drain",
+            post(handlers::scheduler_control::drain_scheduler),
+        )
+        .route(
+            "/api/admin/scheduler/resume",
+            post(handlers::scheduler_control::resume_scheduler),
+        )
+        // Secret and certificate expiry tracking (admin only)
+        .route(
+            "/api/admin/credential-expirations",
+            get(handlers::credentials::list_credential_expirations),
+        )
+        .route(
+            "/api/admin/tls-materials",
+            get(handlers::credentials::list_tls_materials)
+                .post(handlers::credentials::create_tls_material),
+        )
+        .route(
+            "/api/admin/tls-materials/:id",
+            put(handlers::credentials::update_tls_material)
+                .delete(handlers::credentials::delete_tls_material),
+        )
+        // Public status page management (admin only)
+        .route(
+            "/api/admin/status-pages",
+            get(handlers::status_pages::list_status_pages)
+                .post(handlers::status_pages::create_status_page),
+        )
+        .route(
+            "/api/admin/status-pages/:id",
+            put(handlers::status_pages::update_status_page)
+                .delete(handlers::status_pages::delete_status_page),
+        )
+        .route(
+            "/api/admin/status-pages/:id/rotate-token",
+            post(handlers::status_pages::rotate_status_page_token),
+        )
+        // Subject data erasure (admin only)
+        .route(
+            "/api/admin/erasures",
+            get(handlers::erasure::list_erasure_reports)
+                .post(handlers::erasure::erase_subject_data),
+        )
+        .route(
+            "/api/admin/erasures/search",
+            post(handlers::erasure::search_subject_data),
+        )
         // Webhook endpoints
         .route(
             "/api/webhooks/:path",
@@ -95,19 +360,35 @@ pub fn create_router(state: AppState) -> Router {
             "/api/jobs/export/bulk",
             post(handlers::import_export::export_jobs_bulk),
         )
+        .route(
+            "/api/jobs/export/encrypted",
+            post(handlers::import_export::export_encrypted_bundle),
+        )
         .route(
             "/api/jobs/import",
             post(handlers::import_export::import_job),
         )
+        .route(
+            "/api/jobs/import/encrypted",
+            post(handlers::import_export::import_encrypted_bundle),
+        )
         .route(
             "/api/jobs/import/bulk",
             post(handlers::import_export::import_jobs_bulk),
         )
         // Server-Sent Events for real-time updates
         .route("/api/events", get(handlers::sse::sse_handler))
+        // Dashboard landing page layout of the current user
+        .route(
+            "/api/dashboard/layout",
+            get(handlers::dashboard_layout::get_dashboard_layout)
+                .put(handlers::dashboard_layout::update_dashboard_layout)
+                .delete(handlers::dashboard_layout::reset_dashboard_layout),
+        )
         // Dashboard routes (HTMX)
         .route("/dashboard", get(handlers::dashboard::dashboard_index))
         .route("/dashboard/jobs", get(handlers::dashboard::jobs_partial))
+        .route("/dashboard/jobs/export", get(handlers::dashboard::jobs_csv))
         .route(
             "/dashboard/jobs/new",
             get(handlers::dashboard::job_form_page),
@@ -124,10 +405,19 @@ pub fn create_router(state: AppState) -> Router {
             "/dashboard/executions",
             get(handlers::dashboard::executions_partial),
         )
+        .route(
+            "/dashboard/executions/export",
+            get(handlers::dashboard::executions_csv),
+        )
         .route(
             "/dashboard/variables",
             get(handlers::dashboard::variables_partial),
         )
+        .route("/dashboard/dlq", get(handlers::dashboard::dlq_partial))
+        .route(
+            "/dashboard/workers",
+            get(handlers::dashboard::workers_partial),
+        )
         .layer(
             ServiceBuilder::new()
                 .layer(axum::middleware::from_fn_with_state(
@@ -137,6 +427,14 @@ pub fn create_router(state: AppState) -> Router {
                 .layer(axum::middleware::from_fn_with_state(
                     state.clone(),
                     rbac_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    api_rate_limit_middleware,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    idempotency_middleware,
                 )),
         );
 
@@ -151,7 +449,14 @@ pub fn create_router(state: AppState) -> Router {
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
-                .layer(cors),
+                .layer(cors)
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    locale_middleware,
+                ))
+                // gzip/br compression, then ETags computed on the uncompressed body
+                .layer(CompressionLayer::new())
+                .layer(axum::middleware::from_fn(etag_middleware)),
         )
         .with_state(state)
 }
@@ -1,19 +1,27 @@
 use std::sync::Arc;
 use tokio::sync::broadcast;
 
+use common::auth::JwtService;
 use common::config::Settings;
-use common::db::DbPool;
+use common::db::{DbPool, RedisConnection};
+use common::jwt_keys::JwtKeyRing;
 use common::storage::StorageService;
 
+use crate::middleware::rate_limit::RateLimitPolicyCache;
+
 /// Application state shared across all handlers
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: DbPool,
-    pub redis_client: redis::Client,
+    /// Redis of whichever topology is configured (single node, Sentinel or cluster)
+    pub redis: RedisConnection,
     pub nats_client: async_nats::Client,
     pub storage_service: Arc<dyn StorageService>,
     pub config: Arc<Settings>,
     pub sse_tx: broadcast::Sender<SseEvent>,
+    pub jwt_keys: Arc<JwtKeyRing>,
+    /// Rate limit policies looked up by the API rate limit middleware
+    pub rate_limit_policies: Arc<RateLimitPolicyCache>,
 }
 
 // Manual Debug implementation for cleaner output
@@ -21,11 +29,13 @@ impl std::fmt::Debug for AppState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AppState")
             .field("db_pool", &self.db_pool)
-            .field("redis_client", &"<redis::Client>")
+            .field("redis", &self.redis.topology())
             .field("nats_client", &"<async_nats::Client>")
             .field("storage_service", &"<Arc<dyn StorageService>>")
             .field("config", &self.config)
             .field("sse_tx", &self.sse_tx)
+            .field("jwt_keys", &"<Arc<JwtKeyRing>>")
+            .field("rate_limit_policies", &"<Arc<RateLimitPolicyCache>>")
             .finish()
     }
 }
@@ -43,6 +53,12 @@ pub enum SseEvent {
         job_id: uuid::Uuid,
         status: String,
     },
+    /// A running execution is still making progress
+    ExecutionHeartbeat {
+        execution_id: uuid::Uuid,
+        job_id: uuid::Uuid,
+        progress: common::models::ExecutionProgress,
+    },
     JobCreated {
         job_id: uuid::Uuid,
         name: String,
@@ -56,23 +72,46 @@ impl AppState {
     /// Create a new AppState instance with Storage service (PostgreSQL + Redis + Filesystem)
     pub fn new(
         db_pool: DbPool,
-        redis_client: redis::Client,
+        redis: RedisConnection,
         nats_client: async_nats::Client,
         storage_service: Arc<dyn StorageService>,
         config: Settings,
+        jwt_keys: Arc<JwtKeyRing>,
     ) -> Self {
         let (sse_tx, _) = broadcast::channel(100);
 
         Self {
             db_pool,
-            redis_client,
+            redis,
             nats_client,
             storage_service,
             config: Arc::new(config),
             sse_tx,
+            jwt_keys,
+            rate_limit_policies: Arc::new(RateLimitPolicyCache::default()),
         }
     }
 
+    /// Token service signing with the rotating keys
+    pub fn jwt_service(&self) -> JwtService {
+        JwtService::new(
+            &self.config.auth.jwt_secret,
+            self.config.auth.jwt_expiration_hours,
+        )
+        .with_key_ring(Arc::clone(&self.jwt_keys))
+    }
+
+    /// Tell workers a job changed so they drop its cached definition
+    pub fn publish_job_changed(&self, job_id: uuid::Uuid) {
+        let client = self.nats_client.clone();
+        tokio::spawn(async move {
+            let subject = common::worker::consumer::job_changed_subject(job_id);
+            if let Err(e) = client.publish(subject, Vec::new().into()).await {
+                tracing::warn!(job_id = %job_id, error = %e, "Failed to publish job change");
+            }
+        });
+    }
+
     /// Broadcast an SSE event to all connected clients
     pub fn broadcast_event(&self, event: SseEvent) {
         // Ignore send errors (no receivers is fine)
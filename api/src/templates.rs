@@ -1,9 +1,15 @@
-use tera::Tera;
+use std::collections::HashMap;
+use tera::{Tera, Value};
+
+use crate::middleware::current_locale;
 
 lazy_static::lazy_static! {
     pub static ref TEMPLATES: Tera = {
         match Tera::new("api/templates/**/*.html") {
-            Ok(t) => t,
+            Ok(mut t) => {
+                t.register_function("t", translate);
+                t
+            }
             Err(e) => {
                 tracing::error!("Template parsing error: {}", e);
                 std::process::exit(1);
@@ -11,3 +17,15 @@ lazy_static::lazy_static! {
         }
     };
 }
+
+/// Tera function `t(key="...")` translating a catalog key into the request locale
+fn translate(args: &HashMap<String, Value>) -> tera::Result<Value> {
+    let key = args
+        .get("key")
+        .and_then(Value::as_str)
+        .ok_or_else(|| tera::Error::msg("t() requires a string `key` argument"))?;
+
+    Ok(Value::String(
+        common::i18n::translate(current_locale(), key).to_string(),
+    ))
+}
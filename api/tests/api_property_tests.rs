@@ -55,6 +55,9 @@ fn property_48_job_listing_completeness() {
                     expression: "0 0 * * * *".to_string(),
                     timezone: chrono_tz::Asia::Ho_Chi_Minh,
                     end_date: None,
+                    valid_for_seconds: None,
+                    calendar: None,
+                    misfire_policy: None,
                 }),
                 steps: vec![],
                 triggers: TriggerConfig::default(),
@@ -270,6 +273,9 @@ fn property_52_job_disable_effect() {
                 expression: "0 0 * * * *".to_string(),
                 timezone: chrono_tz::Asia::Ho_Chi_Minh,
                 end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
             }),
             steps: vec![],
             triggers: TriggerConfig {
@@ -327,6 +333,9 @@ fn property_53_job_enable_effect() {
                 expression: "0 0 * * * *".to_string(),
                 timezone: chrono_tz::Asia::Ho_Chi_Minh,
                 end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
             }),
             steps: vec![],
             triggers: TriggerConfig {
@@ -383,6 +392,7 @@ fn property_15_sensitive_variable_masking() {
             value: var_value.clone(),
             is_sensitive,
             scope: VariableScope::Global,
+            expires_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
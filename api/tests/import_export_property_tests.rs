@@ -12,7 +12,7 @@ use uuid::Uuid;
 use common::import_export::{
     ExportMetadata, ExportedJob, ImportExportService, ImportExportServiceImpl, ImportResult,
 };
-use common::models::{Schedule, TriggerConfig};
+use common::models::{CollisionPolicy, JobPriority, Schedule, TriggerConfig};
 
 // Constant for sensitive data placeholder (matches the one in import_export.rs)
 const SENSITIVE_DATA_PLACEHOLDER: &str = "***MASKED***";
@@ -188,6 +188,9 @@ fn property_128_export_completeness() {
                 expression: "0 0 * * * *".to_string(),
                 timezone: chrono_tz::Asia::Ho_Chi_Minh,
                 end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
             }),
             steps: vec![], // Simplified for test
             triggers: TriggerConfig {
@@ -198,6 +201,12 @@ fn property_128_export_completeness() {
             timeout_seconds,
             max_retries,
             allow_concurrent,
+            collision_policy: CollisionPolicy::Skip,
+            strict_ordering: false,
+            priority: JobPriority::Normal,
+            dead_letter_policy: None,
+            concurrency_limit: None,
+            parameter_matrix: Default::default(),
             metadata: ExportMetadata {
                 export_date: Utc::now(),
                 exported_by: "test-user".to_string(),
@@ -587,12 +596,21 @@ fn property_134_bulk_export_format() {
                     expression: "0 0 * * * *".to_string(),
                     timezone: chrono_tz::Asia::Ho_Chi_Minh,
                     end_date: None,
+                    valid_for_seconds: None,
+                    calendar: None,
+                    misfire_policy: None,
                 }),
                 steps: vec![],
                 triggers: TriggerConfig::default(),
                 timeout_seconds: 300,
                 max_retries: 10,
                 allow_concurrent: false,
+                collision_policy: CollisionPolicy::Skip,
+                strict_ordering: false,
+                priority: JobPriority::Normal,
+                dead_letter_policy: None,
+                concurrency_limit: None,
+                parameter_matrix: Default::default(),
                 metadata: ExportMetadata {
                     export_date: Utc::now(),
                     exported_by: "test-user".to_string(),
@@ -713,12 +731,21 @@ fn property_136_export_metadata_inclusion() {
                 expression: "0 0 * * * *".to_string(),
                 timezone: chrono_tz::Asia::Ho_Chi_Minh,
                 end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
             }),
             steps: vec![],
             triggers: TriggerConfig::default(),
             timeout_seconds: 300,
             max_retries: 10,
             allow_concurrent: false,
+            collision_policy: CollisionPolicy::Skip,
+            strict_ordering: false,
+            priority: JobPriority::Normal,
+            dead_letter_policy: None,
+            concurrency_limit: None,
+            parameter_matrix: Default::default(),
             metadata: ExportMetadata {
                 export_date: Utc::now(),
                 exported_by: exported_by.clone(),
@@ -0,0 +1,174 @@
+// Command line arguments for the benchmark harness
+
+use anyhow::{anyhow, bail, Context, Result};
+use common::models::Schedule;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: bench [OPTIONS]
+
+Creates synthetic jobs with no-op HTTP steps, lets the running scheduler and
+workers execute them, then reports scheduling accuracy, queue latency and
+throughput. Database settings come from the usual config files and APP_* env.
+
+Options:
+  --jobs <N>               Number of synthetic jobs to create [default: 100]
+  --schedule <SPEC>        rate:<seconds> or cron:<quartz expression> [default: rate:10]
+  --duration <SECONDS>     How long to let the jobs run [default: 60]
+  --drain <SECONDS>        How long to wait for in-flight executions afterwards [default: 30]
+  --listen <ADDR>          Address of the built-in no-op HTTP target [default: 0.0.0.0:18080]
+  --target-url <URL>       URL workers call for each step [default: http://127.0.0.1:<port>/noop]
+  --keep                   Keep the synthetic jobs and executions after the run
+  --json                   Print the report as JSON
+  -h, --help               Print this help";
+
+/// Benchmark run settings
+#[derive(Debug, Clone)]
+pub struct BenchArgs {
+    pub jobs: usize,
+    pub schedule: Schedule,
+    pub duration: Duration,
+    pub drain: Duration,
+    pub listen: SocketAddr,
+    pub target_url: String,
+    pub keep: bool,
+    pub json: bool,
+}
+
+impl BenchArgs {
+    /// Parse arguments, returning `None` when help was requested
+    pub fn parse<I>(args: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut jobs = 100;
+        let mut schedule = Schedule::FixedRate {
+            interval_seconds: 10,
+            misfire_policy: None,
+        };
+        let mut duration = Duration::from_secs(60);
+        let mut drain = Duration::from_secs(30);
+        let mut listen: SocketAddr = "0.0.0.0:18080".parse().expect("valid default address");
+        let mut target_url = None;
+        let mut keep = false;
+        let mut json = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--jobs" => jobs = value()?.parse().context("Invalid --jobs")?,
+                "--schedule" => schedule = parse_schedule(&value()?)?,
+                "--duration" => {
+                    duration = parse_seconds(&value()?).context("Invalid --duration")?
+                }
+                "--drain" => drain = parse_seconds(&value()?).context("Invalid --drain")?,
+                "--listen" => listen = value()?.parse().context("Invalid --listen")?,
+                "--target-url" => target_url = Some(value()?),
+                "--keep" => keep = true,
+                "--json" => json = true,
+                "-h" | "--help" => return Ok(None),
+                other => bail!("Unknown argument: {}\n\n{}", other, USAGE),
+            }
+        }
+
+        if jobs == 0 {
+            bail!("--jobs must be greater than 0");
+        }
+
+        let target_url =
+            target_url.unwrap_or_else(|| format!("http://127.0.0.1:{}/noop", listen.port()));
+
+        Ok(Some(Self {
+            jobs,
+            schedule,
+            duration,
+            drain,
+            listen,
+            target_url,
+            keep,
+            json,
+        }))
+    }
+}
+
+fn parse_seconds(value: &str) -> Result<Duration> {
+    Ok(Duration::from_secs(value.parse()?))
+}
+
+/// Parse `rate:<seconds>` or `cron:<expression>` into a schedule
+fn parse_schedule(spec: &str) -> Result<Schedule> {
+    match spec.split_once(':') {
+        Some(("rate", seconds)) => {
+            let interval_seconds: u32 = seconds.parse().context("Invalid rate interval")?;
+            if interval_seconds == 0 {
+                bail!("Rate interval must be greater than 0");
+            }
+            Ok(Schedule::FixedRate {
+                interval_seconds,
+                misfire_policy: None,
+            })
+        }
+        Some(("cron", expression)) => {
+            common::schedule::parse_cron_expression(expression)?;
+            Ok(Schedule::Cron {
+                expression: expression.to_string(),
+                timezone: chrono_tz::UTC,
+                end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
+            })
+        }
+        _ => bail!(
+            "Invalid schedule '{}', expected rate:<seconds> or cron:<expression>",
+            spec
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<BenchArgs>> {
+        BenchArgs::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let args = parse(&[]).unwrap().unwrap();
+        assert_eq!(args.jobs, 100);
+        assert_eq!(args.target_url, "http://127.0.0.1:18080/noop");
+
+        let args = parse(&[
+            "--jobs",
+            "500",
+            "--schedule",
+            "cron:*/5 * * * * *",
+            "--listen",
+            "0.0.0.0:9000",
+            "--keep",
+        ])
+        .unwrap()
+        .unwrap();
+        assert_eq!(args.jobs, 500);
+        assert!(matches!(args.schedule, Schedule::Cron { .. }));
+        assert_eq!(args.target_url, "http://127.0.0.1:9000/noop");
+        assert!(args.keep);
+    }
+
+    #[test]
+    fn test_rejects_bad_arguments() {
+        assert!(parse(&["--help"]).unwrap().is_none());
+        assert!(parse(&["--jobs", "0"]).is_err());
+        assert!(parse(&["--jobs"]).is_err());
+        assert!(parse(&["--schedule", "rate:0"]).is_err());
+        assert!(parse(&["--schedule", "every minute"]).is_err());
+        assert!(parse(&["--frobnicate"]).is_err());
+    }
+}
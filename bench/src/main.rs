@@ -0,0 +1,258 @@
+// Benchmark binary entry point
+// Purpose: Size hardware by driving synthetic jobs through the full
+// scheduler → queue → worker path and reporting how the deployment kept up
+
+mod args;
+mod report;
+
+use anyhow::{Context, Result};
+use args::{BenchArgs, USAGE};
+use axum::routing::get;
+use axum::Router;
+use chrono::Utc;
+use common::bootstrap;
+use common::config::Settings;
+use common::db::repositories::execution::ExecutionRepository;
+use common::db::repositories::job::JobRepository;
+use common::db::DbPool;
+use common::models::{HttpMethod, Job, JobPriority, JobStep, JobType, TriggerConfig};
+use report::{JobRun, Report};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// How often in-flight executions are checked while draining
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let Some(args) = BenchArgs::parse(std::env::args().skip(1))? else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    bootstrap::init_human_tracing();
+
+    let settings = Settings::load()?;
+    let db_pool = bootstrap::init_database_pool(&settings).await?;
+    bootstrap::verify_schema(&db_pool).await?;
+
+    let step_requests = start_noop_target(&args).await?;
+
+    let run_id = Uuid::new_v4().simple().to_string()[..8].to_string();
+    let job_repo = JobRepository::new(db_pool.clone());
+
+    info!(
+        run_id = %run_id,
+        jobs = args.jobs,
+        target_url = %args.target_url,
+        "Creating synthetic jobs"
+    );
+    let started_at = Utc::now();
+    let mut jobs = Vec::with_capacity(args.jobs);
+    for index in 0..args.jobs {
+        let job = synthetic_job(&args, &run_id, index)?;
+        job_repo.create(&job).await?;
+        jobs.push(job);
+    }
+
+    info!(
+        duration_seconds = args.duration.as_secs(),
+        "Jobs created, waiting for the scheduler and workers"
+    );
+    tokio::select! {
+        _ = tokio::time::sleep(args.duration) => {}
+        _ = signal::ctrl_c() => warn!("Interrupted, stopping the run early"),
+    }
+
+    // Stop new fires before measuring so the window is fixed
+    let finished_at = Utc::now();
+    for job in &jobs {
+        job_repo.disable(job.id).await?;
+    }
+
+    let execution_repo = ExecutionRepository::new(db_pool.clone());
+    drain(&execution_repo, &jobs, args.drain).await?;
+
+    let runs = collect_runs(&execution_repo, &jobs).await?;
+    let report = Report::build(
+        &args.schedule,
+        &runs,
+        started_at,
+        finished_at,
+        step_requests.load(Ordering::Relaxed),
+    );
+
+    if args.keep {
+        info!(run_id = %run_id, "Keeping synthetic jobs");
+    } else {
+        cleanup(&db_pool, &jobs).await?;
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report);
+    }
+
+    Ok(())
+}
+
+/// Serve the endpoint every synthetic step calls, counting requests
+async fn start_noop_target(args: &BenchArgs) -> Result<Arc<AtomicU64>> {
+    let step_requests = Arc::new(AtomicU64::new(0));
+    let counter = Arc::clone(&step_requests);
+    let app = Router::new().route(
+        "/noop",
+        get(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            async { "ok" }
+        }),
+    );
+
+    let listener = tokio::net::TcpListener::bind(args.listen)
+        .await
+        .with_context(|| format!("Failed to bind no-op target on {}", args.listen))?;
+    info!(address = %args.listen, "No-op step target listening");
+
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            warn!(error = %e, "No-op step target stopped");
+        }
+    });
+
+    Ok(step_requests)
+}
+
+/// A scheduled job with a single no-op HTTP step
+fn synthetic_job(args: &BenchArgs, run_id: &str, index: usize) -> Result<Job> {
+    let id = Uuid::new_v4();
+    let name = format!("bench-{}-{:05}", run_id, index);
+    let now = Utc::now();
+    let steps = vec![JobStep {
+        id: "noop".to_string(),
+        name: "No-op".to_string(),
+        step_type: JobType::HttpRequest {
+            method: HttpMethod::Get,
+            url: args.target_url.clone(),
+            headers: HashMap::new(),
+            body: None,
+            auth: None,
+        },
+        condition: None,
+        on_failure: None,
+        compensate_with: Vec::new(),
+        acquire_lock: None,
+        set_state: Default::default(),
+        env: Default::default(),
+        timeout_seconds: None,
+        retry_count: None,
+        stream_output: false,
+        pii: false,
+        dependency: None,
+        connection: None,
+    }];
+    let triggers = TriggerConfig {
+        scheduled: true,
+        manual: false,
+        webhook: None,
+    };
+
+    let definition = serde_json::json!({
+        "id": id,
+        "name": name,
+        "description": "Synthetic benchmark job",
+        "schedule": args.schedule,
+        "steps": steps,
+        "triggers": triggers,
+        "enabled": true,
+        "timeout_seconds": 30,
+        "max_retries": 0,
+        "allow_concurrent": true,
+    });
+
+    Ok(Job {
+        id,
+        name,
+        description: Some("Synthetic benchmark job".to_string()),
+        schedule: Some(args.schedule.clone()),
+        steps,
+        triggers,
+        enabled: true,
+        paused: false,
+        timeout_seconds: 30,
+        max_retries: 0,
+        allow_concurrent: true,
+        collision_policy: Default::default(),
+        strict_ordering: false,
+        priority: JobPriority::Normal,
+        dead_letter_policy: None,
+        concurrency_limit: None,
+        owner: None,
+        metric_labels: Default::default(),
+        parameter_matrix: Default::default(),
+        definition: Some(definition),
+        version: 1,
+        deprecated: false,
+        sunset_at: None,
+        deprecation_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+/// Wait until the run's executions reach a terminal status or the drain time is up
+async fn drain(execution_repo: &ExecutionRepository, jobs: &[Job], limit: Duration) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + limit;
+    loop {
+        let mut in_flight = 0;
+        for job in jobs {
+            in_flight += execution_repo
+                .find_by_job_id(job.id)
+                .await?
+                .iter()
+                .filter(|e| !e.status.is_terminal())
+                .count();
+        }
+
+        if in_flight == 0 {
+            return Ok(());
+        }
+        if tokio::time::Instant::now() >= deadline {
+            warn!(
+                in_flight,
+                "Drain time exceeded, reporting unfinished executions"
+            );
+            return Ok(());
+        }
+
+        info!(in_flight, "Waiting for in-flight executions");
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    }
+}
+
+async fn collect_runs(execution_repo: &ExecutionRepository, jobs: &[Job]) -> Result<Vec<JobRun>> {
+    let mut runs = Vec::with_capacity(jobs.len());
+    for job in jobs {
+        runs.push(JobRun {
+            created_at: job.created_at,
+            executions: execution_repo.find_by_job_id(job.id).await?,
+        });
+    }
+    Ok(runs)
+}
+
+/// Delete the synthetic jobs; their executions go with them
+async fn cleanup(db_pool: &DbPool, jobs: &[Job]) -> Result<()> {
+    let job_repo = JobRepository::new(db_pool.clone());
+    for job in jobs {
+        job_repo.delete(job.id).await?;
+    }
+    info!(jobs = jobs.len(), "Synthetic jobs deleted");
+    Ok(())
+}
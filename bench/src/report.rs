@@ -0,0 +1,287 @@
+// Benchmark report: scheduling accuracy, queue latency and throughput
+
+use chrono::{DateTime, Utc};
+use common::models::{ExecutionStatus, JobExecution, Schedule};
+use common::schedule::ScheduleTrigger;
+use serde::Serialize;
+use std::fmt;
+
+/// Fires more than this far ahead of the schedule count as early
+const EARLY_FIRE_TOLERANCE_MS: f64 = 1_000.0;
+
+/// Distribution of a latency in milliseconds
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Distribution {
+    pub samples: usize,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Distribution {
+    pub fn from_samples(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(|a, b| a.total_cmp(b));
+
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+            samples[rank.saturating_sub(1).min(samples.len() - 1)]
+        };
+
+        Self {
+            samples: samples.len(),
+            mean_ms: samples.iter().sum::<f64>() / samples.len() as f64,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Results of one benchmark run
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub jobs: usize,
+    pub duration_seconds: f64,
+    pub executions: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub unfinished: usize,
+    /// Fires the schedule called for during the run
+    pub expected_fires: usize,
+    /// Fires that happened well before the schedule called for
+    pub early_fires: usize,
+    /// Actual fire time minus scheduled fire time
+    pub scheduling_lag: Distribution,
+    /// Execution created until a worker started it
+    pub queue_latency: Distribution,
+    /// Execution created until it completed
+    pub end_to_end_latency: Distribution,
+    /// Successful executions per second
+    pub throughput_per_second: f64,
+    /// Requests the no-op step target served
+    pub step_requests: u64,
+}
+
+/// Executions of one synthetic job
+pub struct JobRun {
+    pub created_at: DateTime<Utc>,
+    pub executions: Vec<JobExecution>,
+}
+
+fn millis_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_microseconds().unwrap_or(i64::MAX) as f64 / 1_000.0
+}
+
+/// Scheduled fire times in `(start, end]`
+fn expected_fire_times(
+    schedule: &Schedule,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Vec<DateTime<Utc>> {
+    let mut fires = Vec::new();
+    let mut last = start;
+    while let Ok(Some(next)) = schedule.next_execution_time(Some(last)) {
+        if next > end || next <= last {
+            break;
+        }
+        fires.push(next);
+        last = next;
+    }
+    fires
+}
+
+impl Report {
+    pub fn build(
+        schedule: &Schedule,
+        runs: &[JobRun],
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+        step_requests: u64,
+    ) -> Self {
+        let duration_seconds = millis_between(started_at, finished_at).max(0.0) / 1_000.0;
+        let mut report = Report {
+            jobs: runs.len(),
+            duration_seconds,
+            executions: 0,
+            succeeded: 0,
+            failed: 0,
+            unfinished: 0,
+            expected_fires: 0,
+            early_fires: 0,
+            scheduling_lag: Distribution::default(),
+            queue_latency: Distribution::default(),
+            end_to_end_latency: Distribution::default(),
+            throughput_per_second: 0.0,
+            step_requests,
+        };
+
+        let mut lags = Vec::new();
+        let mut queue_latencies = Vec::new();
+        let mut end_to_end_latencies = Vec::new();
+
+        for run in runs {
+            report.expected_fires +=
+                expected_fire_times(schedule, run.created_at, finished_at).len();
+
+            let mut executions: Vec<&JobExecution> = run.executions.iter().collect();
+            executions.sort_by_key(|e| e.created_at);
+
+            // Each fire is measured against the fire time the previous one implies
+            for pair in executions.windows(2) {
+                if let Ok(Some(scheduled)) = schedule.next_execution_time(Some(pair[0].created_at))
+                {
+                    let lag = millis_between(scheduled, pair[1].created_at);
+                    if lag < -EARLY_FIRE_TOLERANCE_MS {
+                        report.early_fires += 1;
+                    }
+                    lags.push(lag);
+                }
+            }
+
+            for execution in executions {
+                report.executions += 1;
+                match &execution.status {
+                    ExecutionStatus::Success => report.succeeded += 1,
+                    status if status.is_terminal() => report.failed += 1,
+                    _ => report.unfinished += 1,
+                }
+                if let Some(started_at) = execution.started_at {
+                    queue_latencies.push(millis_between(execution.created_at, started_at));
+                }
+                if let Some(completed_at) = execution.completed_at {
+                    end_to_end_latencies.push(millis_between(execution.created_at, completed_at));
+                }
+            }
+        }
+
+        report.scheduling_lag = Distribution::from_samples(lags);
+        report.queue_latency = Distribution::from_samples(queue_latencies);
+        report.end_to_end_latency = Distribution::from_samples(end_to_end_latencies);
+        if duration_seconds > 0.0 {
+            report.throughput_per_second = report.succeeded as f64 / duration_seconds;
+        }
+        report
+    }
+}
+
+fn write_distribution(f: &mut fmt::Formatter<'_>, name: &str, d: &Distribution) -> fmt::Result {
+    writeln!(
+        f,
+        "  {:<20} n={:<7} mean={:>9.1}ms p50={:>9.1}ms p95={:>9.1}ms p99={:>9.1}ms max={:>9.1}ms",
+        name, d.samples, d.mean_ms, d.p50_ms, d.p95_ms, d.p99_ms, d.max_ms
+    )
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Benchmark report")?;
+        writeln!(
+            f,
+            "  jobs={} duration={:.1}s step_requests={}",
+            self.jobs, self.duration_seconds, self.step_requests
+        )?;
+        writeln!(
+            f,
+            "  executions={} succeeded={} failed={} unfinished={}",
+            self.executions, self.succeeded, self.failed, self.unfinished
+        )?;
+        writeln!(
+            f,
+            "  expected_fires={} early_fires={}",
+            self.expected_fires, self.early_fires
+        )?;
+        write_distribution(f, "scheduling_lag", &self.scheduling_lag)?;
+        write_distribution(f, "queue_latency", &self.queue_latency)?;
+        write_distribution(f, "end_to_end_latency", &self.end_to_end_latency)?;
+        writeln!(
+            f,
+            "  throughput={:.2} executions/s",
+            self.throughput_per_second
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    fn execution(
+        created_at: DateTime<Utc>,
+        status: ExecutionStatus,
+        queue_ms: i64,
+        run_ms: i64,
+    ) -> JobExecution {
+        let mut execution = JobExecution::new_scheduled(Uuid::new_v4(), Uuid::new_v4().to_string());
+        execution.created_at = created_at;
+        execution.status = status;
+        execution.started_at = Some(created_at + Duration::milliseconds(queue_ms));
+        execution.completed_at = Some(created_at + Duration::milliseconds(queue_ms + run_ms));
+        execution
+    }
+
+    #[test]
+    fn test_distribution_percentiles() {
+        let d = Distribution::from_samples((1..=100).map(f64::from).collect());
+        assert_eq!(d.samples, 100);
+        assert_eq!(d.p50_ms, 50.0);
+        assert_eq!(d.p95_ms, 95.0);
+        assert_eq!(d.p99_ms, 99.0);
+        assert_eq!(d.max_ms, 100.0);
+        assert_eq!(d.mean_ms, 50.5);
+
+        assert_eq!(
+            Distribution::from_samples(Vec::new()),
+            Distribution::default()
+        );
+    }
+
+    #[test]
+    fn test_report_measures_lag_latency_and_throughput() {
+        let schedule = Schedule::FixedRate {
+            interval_seconds: 10,
+            misfire_policy: None,
+        };
+        let start = Utc::now();
+        let executions = vec![
+            execution(start, ExecutionStatus::Success, 20, 5),
+            // 500ms late
+            execution(
+                start + Duration::milliseconds(10_500),
+                ExecutionStatus::Success,
+                40,
+                5,
+            ),
+            // 8.5s early
+            execution(
+                start + Duration::milliseconds(12_000),
+                ExecutionStatus::Failed,
+                60,
+                5,
+            ),
+        ];
+        let runs = vec![JobRun {
+            created_at: start,
+            executions,
+        }];
+
+        let report = Report::build(&schedule, &runs, start, start + Duration::seconds(30), 3);
+        assert_eq!(report.executions, 3);
+        assert_eq!(report.succeeded, 2);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.expected_fires, 3);
+        assert_eq!(report.early_fires, 1);
+        assert_eq!(report.scheduling_lag.max_ms, 500.0);
+        assert_eq!(report.queue_latency.p50_ms, 40.0);
+        assert_eq!(report.end_to_end_latency.max_ms, 65.0);
+        assert!((report.throughput_per_second - 2.0 / 30.0).abs() < 1e-9);
+        assert!(report.to_string().contains("executions=3"));
+    }
+}
@@ -1,22 +1,31 @@
 // Authentication and JWT token handling
 // Requirements: 10.2, 10.3, 10.4
 
+use crate::db::repositories::session::SessionRepository;
 use crate::db::repositories::user::UserRepository;
 use crate::errors::{AuthError, DatabaseError};
-use crate::models::{User, UserClaims};
+use crate::jwt_keys::JwtKeyRing;
+use crate::models::{User, UserClaims, UserSession};
 use chrono::{Duration, Utc};
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{
+    decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{error, instrument};
 use uuid::Uuid;
 
 /// JWT token service for encoding and decoding tokens
+///
+/// With a key ring, tokens are signed with its current key and carry its `kid`, and
+/// tokens without a `kid` are rejected; without one, tokens are signed and checked
+/// with the shared secret.
 #[derive(Clone)]
 pub struct JwtService {
     encoding_key: Arc<EncodingKey>,
     decoding_key: Arc<DecodingKey>,
     expiration_hours: i64,
+    key_ring: Option<Arc<JwtKeyRing>>,
 }
 
 impl JwtService {
@@ -27,9 +36,16 @@ impl JwtService {
             encoding_key: Arc::new(EncodingKey::from_secret(secret.as_bytes())),
             decoding_key: Arc::new(DecodingKey::from_secret(secret.as_bytes())),
             expiration_hours: expiration_hours as i64,
+            key_ring: None,
         }
     }
 
+    /// Sign tokens with the rotating keys of a key ring
+    pub fn with_key_ring(mut self, key_ring: Arc<JwtKeyRing>) -> Self {
+        self.key_ring = Some(key_ring);
+        self
+    }
+
     /// Encode user claims into a JWT token
     /// Requirements: 10.3 - Generate JWT tokens on successful login
     #[instrument(skip(self))]
@@ -38,6 +54,36 @@ impl JwtService {
         user_id: &str,
         username: &str,
         permissions: Vec<String>,
+    ) -> Result<String, AuthError> {
+        self.encode_claims(user_id, username, permissions, None, None)
+    }
+
+    /// Encode user claims into a JWT token that belongs to a login session of a
+    /// user of `tenant_id`
+    #[instrument(skip(self))]
+    pub fn encode_session_token(
+        &self,
+        user_id: &str,
+        username: &str,
+        permissions: Vec<String>,
+        session_id: Uuid,
+        tenant_id: Option<String>,
+    ) -> Result<String, AuthError> {
+        self.encode_claims(user_id, username, permissions, Some(session_id), tenant_id)
+    }
+
+    /// When a token issued now expires
+    pub fn expires_at(&self) -> chrono::DateTime<Utc> {
+        Utc::now() + Duration::hours(self.expiration_hours)
+    }
+
+    fn encode_claims(
+        &self,
+        user_id: &str,
+        username: &str,
+        permissions: Vec<String>,
+        sid: Option<Uuid>,
+        tenant_id: Option<String>,
     ) -> Result<String, AuthError> {
         let now = Utc::now();
         let exp = (now + Duration::hours(self.expiration_hours)).timestamp();
@@ -49,9 +95,24 @@ impl JwtService {
             permissions,
             exp,
             iat,
+            sid,
+            tenant_id,
         };
 
-        encode(&Header::default(), &claims, &self.encoding_key).map_err(|e| {
+        let (header, key) = match &self.key_ring {
+            Some(ring) => {
+                let (kid, key) = ring.signing_key().ok_or_else(|| {
+                    error!("No JWT signing key loaded");
+                    AuthError::AuthenticationFailed("No signing key available".to_string())
+                })?;
+                let mut header = Header::new(Algorithm::EdDSA);
+                header.kid = Some(kid);
+                (header, key)
+            }
+            None => (Header::default(), (*self.encoding_key).clone()),
+        };
+
+        encode(&header, &claims, &key).map_err(|e| {
             error!(error = %e, "Failed to encode JWT token");
             AuthError::AuthenticationFailed(format!("Failed to encode token: {}", e))
         })
@@ -61,17 +122,39 @@ impl JwtService {
     /// Requirements: 10.4 - JWT token validation
     #[instrument(skip(self, token))]
     pub fn decode_token(&self, token: &str) -> Result<UserClaims, AuthError> {
-        let mut validation = Validation::new(Algorithm::HS256);
+        let header = decode_header(token).map_err(|e| {
+            error!(error = %e, "Failed to decode JWT header");
+            AuthError::InvalidToken(format!("Token validation failed: {}", e))
+        })?;
+        let (algorithm, decoding_key) = match (header.kid, &self.key_ring) {
+            (Some(kid), Some(ring)) => {
+                let key = ring.decoding_key(&kid).ok_or_else(|| {
+                    error!(kid = %kid, "Token signed with unknown key");
+                    AuthError::InvalidToken(format!("Unknown signing key {}", kid))
+                })?;
+                (Algorithm::EdDSA, key)
+            }
+            // Tokens signed with the shared secret would otherwise stay forgeable by
+            // anyone holding it, however often the keys rotate
+            (None, Some(_)) => {
+                error!("Token without a key id");
+                return Err(AuthError::InvalidToken(
+                    "Token has no signing key id".to_string(),
+                ));
+            }
+            (_, None) => (Algorithm::HS256, (*self.decoding_key).clone()),
+        };
+
+        let mut validation = Validation::new(algorithm);
         validation.validate_exp = true;
 
-        let token_data =
-            decode::<UserClaims>(token, &self.decoding_key, &validation).map_err(|e| {
-                error!(error = %e, "Failed to decode JWT token");
-                match e.kind() {
-                    jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
-                    _ => AuthError::InvalidToken(format!("Token validation failed: {}", e)),
-                }
-            })?;
+        let token_data = decode::<UserClaims>(token, &decoding_key, &validation).map_err(|e| {
+            error!(error = %e, "Failed to decode JWT token");
+            match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+                _ => AuthError::InvalidToken(format!("Token validation failed: {}", e)),
+            }
+        })?;
 
         Ok(token_data.claims)
     }
@@ -84,19 +167,32 @@ impl JwtService {
     }
 }
 
+/// Device a login comes from, recorded on its session
+#[derive(Debug, Clone, Default)]
+pub struct LoginClient {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
 /// Database authentication service for validating credentials and managing users
 #[derive(Clone)]
 pub struct DatabaseAuthService {
     jwt_service: JwtService,
     user_repository: Arc<UserRepository>,
+    session_repository: Arc<SessionRepository>,
 }
 
 impl DatabaseAuthService {
     /// Create a new database authentication service
-    pub fn new(jwt_service: JwtService, user_repository: UserRepository) -> Self {
+    pub fn new(
+        jwt_service: JwtService,
+        user_repository: UserRepository,
+        session_repository: SessionRepository,
+    ) -> Self {
         Self {
             jwt_service,
             user_repository: Arc::new(user_repository),
+            session_repository: Arc::new(session_repository),
         }
     }
 
@@ -104,7 +200,12 @@ impl DatabaseAuthService {
     /// Requirements: 10.2 - Validate credentials against bcrypt-hashed passwords
     /// Requirements: 10.3 - Generate JWT tokens on successful login
     #[instrument(skip(self, password))]
-    pub async fn login(&self, username: &str, password: &str) -> Result<String, AuthError> {
+    pub async fn login(
+        &self,
+        username: &str,
+        password: &str,
+        client: &LoginClient,
+    ) -> Result<String, AuthError> {
         // Find user by username
         let user = self
             .user_repository
@@ -122,9 +223,7 @@ impl DatabaseAuthService {
         // Check if user is enabled
         if !user.enabled {
             error!(username = %username, "User account is disabled");
-            return Err(AuthError::AuthenticationFailed(
-                "User account is disabled".to_string(),
-            ));
+            return Err(AuthError::AccountDisabled);
         }
 
         // Verify password
@@ -148,14 +247,39 @@ impl DatabaseAuthService {
                 AuthError::AuthenticationFailed(format!("Failed to get permissions: {}", e))
             })?;
 
+        // Every login gets its own session, so its tokens can be revoked on their own
+        let now = Utc::now();
+        let session = UserSession {
+            id: Uuid::new_v4(),
+            user_id: user.id,
+            user_agent: client.user_agent.clone(),
+            ip_address: client.ip_address.clone(),
+            created_at: now,
+            last_seen_at: now,
+            expires_at: self.jwt_service.expires_at(),
+            revoked_at: None,
+        };
+        self.session_repository
+            .create(&session)
+            .await
+            .map_err(|e| {
+                error!(error = %e, user_id = %user.id, "Failed to create session");
+                AuthError::AuthenticationFailed(format!("Failed to create session: {}", e))
+            })?;
+
         // Generate JWT token
-        let token =
-            self.jwt_service
-                .encode_token(&user.id.to_string(), &user.username, permissions)?;
+        let token = self.jwt_service.encode_session_token(
+            &user.id.to_string(),
+            &user.username,
+            permissions,
+            session.id,
+            user.tenant_id.clone(),
+        )?;
 
         tracing::info!(
             user_id = %user.id,
             username = %user.username,
+            session_id = %session.id,
             "User logged in successfully"
         );
 
@@ -183,6 +307,8 @@ impl DatabaseAuthService {
             password_hash,
             email,
             enabled: true,
+            locale: None,
+            tenant_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -415,6 +541,8 @@ impl KeycloakJwtService {
             permissions,
             exp: claims.exp,
             iat: claims.iat,
+            sid: None,
+            tenant_id: claims.tenant_id,
         })
     }
 }
@@ -430,6 +558,9 @@ struct KeycloakClaims {
     realm_access: Option<RealmAccess>,
     #[serde(default)]
     resource_access: Option<serde_json::Value>,
+    /// Tenant of the user, set by a Keycloak user attribute mapper
+    #[serde(default)]
+    tenant_id: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -496,6 +627,64 @@ mod tests {
         assert_eq!(claims.permissions, permissions);
     }
 
+    #[tokio::test]
+    async fn test_key_ring_tokens_need_a_key_id() {
+        let ring = Arc::new(JwtKeyRing::test_ring("test-secret"));
+        let service = JwtService::new("test-secret", 24).with_key_ring(Arc::clone(&ring));
+
+        // Without a loaded key nothing is signed with the shared secret instead
+        assert!(service
+            .encode_token("user-123", "testuser", vec![])
+            .is_err());
+
+        ring.insert_test_key(Utc::now() - Duration::days(1));
+        let token = service
+            .encode_token("user-123", "testuser", vec![])
+            .expect("Failed to encode token");
+        assert!(decode_header(&token).unwrap().kid.is_some());
+        assert_eq!(service.decode_token(&token).unwrap().sub, "user-123");
+
+        // A token signed with the shared secret is refused once keys rotate
+        let legacy = JwtService::new("test-secret", 24)
+            .encode_token("user-123", "testuser", vec![])
+            .expect("Failed to encode token");
+        assert!(matches!(
+            service.decode_token(&legacy),
+            Err(AuthError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_jwt_service_session_token() {
+        let service = JwtService::new("test-secret", 24);
+        let session_id = Uuid::new_v4();
+
+        let token = service
+            .encode_session_token(
+                "user-123",
+                "testuser",
+                vec![],
+                session_id,
+                Some("acme".to_string()),
+            )
+            .expect("Failed to encode token");
+        let claims = service
+            .decode_token(&token)
+            .expect("Failed to decode token");
+        assert_eq!(claims.sid, Some(session_id));
+        assert_eq!(claims.tenant_id.as_deref(), Some("acme"));
+
+        // Tokens issued before sessions existed still decode
+        let token = service
+            .encode_token("user-123", "testuser", vec![])
+            .expect("Failed to encode token");
+        let claims = service
+            .decode_token(&token)
+            .expect("Failed to decode token");
+        assert_eq!(claims.sid, None);
+        assert_eq!(claims.tenant_id, None);
+    }
+
     #[test]
     fn test_jwt_service_expired_token() {
         // Create a service with very short expiration
@@ -513,6 +702,8 @@ mod tests {
             permissions,
             exp,
             iat,
+            sid: None,
+            tenant_id: None,
         };
 
         let encoding_key = jsonwebtoken::EncodingKey::from_secret("test-secret".as_bytes());
@@ -541,6 +732,7 @@ mod tests {
                 roles: vec!["admin".to_string()],
             }),
             resource_access: None,
+            tenant_id: None,
         };
 
         let permissions = extract_permissions_from_keycloak(&claims);
@@ -562,6 +754,7 @@ mod tests {
                 roles: vec!["viewer".to_string()],
             }),
             resource_access: None,
+            tenant_id: None,
         };
 
         let permissions = extract_permissions_from_keycloak(&claims);
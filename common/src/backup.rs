@@ -0,0 +1,403 @@
+// Disaster-recovery snapshots
+// Purpose: Capture the cluster's configuration (jobs and their variables, webhooks,
+// calendars, blackout windows, connection profiles, job state and tracked TLS
+// materials) in one consistent read, keep the snapshot in file storage, and rebuild
+// that state on an empty cluster running a compatible release
+//
+// Secrets (webhook signing keys, sensitive variable values and connection profile
+// endpoints) are taken out of the rows and sealed with age to recipients given when
+// the snapshot is taken; a restore needs a matching identity.
+
+use crate::db::migrations;
+use crate::errors::{DatabaseError, StorageError, ValidationError};
+use crate::import_export::{decrypt_with_identity, encrypt_to_recipients, ImportExportError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Layout version of the snapshot document; bump when its structure changes
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+/// Tables a snapshot holds, in restore order: parents before the rows that reference them
+pub const SNAPSHOT_TABLES: [&str; 8] = [
+    "calendars",
+    "connection_profiles",
+    "jobs",
+    "variables",
+    "webhooks",
+    "blackout_windows",
+    "job_state",
+    "tls_materials",
+];
+
+/// Storage directory snapshots are written to and restored from
+pub const BACKUP_PREFIX: &str = "backups";
+
+/// Error type for snapshot and restore operations
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    #[error("Validation error: {0}")]
+    Validation(#[from] ValidationError),
+
+    #[error("Invalid snapshot: {0}")]
+    InvalidSnapshot(String),
+
+    #[error("Incompatible snapshot: {0}")]
+    Incompatible(String),
+
+    #[error("Cluster is not empty, found existing rows in: {0}")]
+    NotEmpty(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+}
+
+impl From<ImportExportError> for BackupError {
+    fn from(err: ImportExportError) -> Self {
+        match err {
+            ImportExportError::Validation(e) => BackupError::Validation(e),
+            e => BackupError::Encryption(e.to_string()),
+        }
+    }
+}
+
+impl From<sqlx::Error> for BackupError {
+    fn from(err: sqlx::Error) -> Self {
+        BackupError::Database(err.into())
+    }
+}
+
+/// Point-in-time copy of the cluster's configuration
+///
+/// Rows are kept as stored except for their secrets, which are sealed in `secrets`.
+/// Job state of tenants with residency rules stays in their store; the snapshot holds
+/// the pointers to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub format_version: u32,
+    /// Latest migration of the release that took the snapshot
+    pub schema_version: Option<i64>,
+    pub system_version: String,
+    pub created_at: DateTime<Utc>,
+    pub created_by: String,
+    /// Rows of each of [`SNAPSHOT_TABLES`]
+    pub tables: BTreeMap<String, Vec<Value>>,
+    /// ASCII-armored age file holding the secrets taken out of the rows
+    pub secrets: Option<String>,
+}
+
+/// What a snapshot contains, returned by backup and restore
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotSummary {
+    pub path: String,
+    pub format_version: u32,
+    pub schema_version: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    /// Row count of each table
+    pub rows: BTreeMap<String, usize>,
+}
+
+/// Sealed secrets by table, row id and column
+type Secrets = BTreeMap<String, BTreeMap<String, BTreeMap<String, Value>>>;
+
+/// Column of a row holding a secret, sealed instead of kept in the snapshot's rows
+fn secret_column(table: &str, row: &Value) -> Option<&'static str> {
+    match table {
+        "webhooks" => Some("secret_key"),
+        "connection_profiles" => Some("endpoint"),
+        "variables" if row.get("is_sensitive") == Some(&Value::Bool(true)) => Some("value"),
+        _ => None,
+    }
+}
+
+fn row_id(table: &str, row: &Value) -> Result<String, BackupError> {
+    row.get("id")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| BackupError::InvalidSnapshot(format!("a row of {} has no id", table)))
+}
+
+impl Snapshot {
+    /// Empty snapshot stamped with this release's schema version
+    pub fn new(created_by: &str, system_version: &str) -> Self {
+        Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            schema_version: migrations::latest_version(),
+            system_version: system_version.to_string(),
+            created_at: Utc::now(),
+            created_by: created_by.to_string(),
+            tables: BTreeMap::new(),
+            secrets: None,
+        }
+    }
+
+    /// Tables in restore order, parents before the rows that reference them
+    pub fn restore_order(&self) -> Vec<(&'static str, &[Value])> {
+        SNAPSHOT_TABLES
+            .iter()
+            .map(|table| {
+                let rows = self.tables.get(*table).map(Vec::as_slice).unwrap_or(&[]);
+                (*table, rows)
+            })
+            .collect()
+    }
+
+    /// Take the secrets out of the rows and seal them to age recipients (`age1...`)
+    pub fn seal_secrets(&mut self, recipients: &[String]) -> Result<(), BackupError> {
+        let mut secrets = Secrets::new();
+        for (table, rows) in self.tables.iter_mut() {
+            for row in rows.iter_mut() {
+                let Some(column) = secret_column(table, row) else {
+                    continue;
+                };
+                let id = row_id(table, row)?;
+                if let Some(value) = row.as_object_mut().and_then(|row| row.remove(column)) {
+                    secrets
+                        .entry(table.clone())
+                        .or_default()
+                        .entry(id)
+                        .or_default()
+                        .insert(column.to_string(), value);
+                }
+            }
+        }
+
+        let plaintext = serde_json::to_vec(&secrets)
+            .map_err(|e| BackupError::InvalidSnapshot(e.to_string()))?;
+        self.secrets = Some(encrypt_to_recipients(&plaintext, recipients)?);
+        Ok(())
+    }
+
+    /// Put the sealed secrets back into the rows with an age identity
+    /// (`AGE-SECRET-KEY-1...`) matching one of the snapshot's recipients
+    pub fn open_secrets(&mut self, identity: &str) -> Result<(), BackupError> {
+        let Some(armored) = self.secrets.take() else {
+            return Err(BackupError::InvalidSnapshot(
+                "snapshot has no sealed secrets".to_string(),
+            ));
+        };
+        let plaintext = decrypt_with_identity(&armored, identity)?;
+        let mut secrets: Secrets = serde_json::from_slice(&plaintext)
+            .map_err(|e| BackupError::InvalidSnapshot(format!("sealed secrets: {}", e)))?;
+
+        for (table, rows) in self.tables.iter_mut() {
+            for row in rows.iter_mut() {
+                let Some(column) = secret_column(table, row) else {
+                    continue;
+                };
+                let id = row_id(table, row)?;
+                let value = secrets
+                    .get_mut(table)
+                    .and_then(|rows| rows.get_mut(&id))
+                    .and_then(|columns| columns.remove(column))
+                    .ok_or_else(|| {
+                        BackupError::InvalidSnapshot(format!(
+                            "{} of {} {} is missing from the sealed secrets",
+                            column, table, id
+                        ))
+                    })?;
+                if let Some(row) = row.as_object_mut() {
+                    row.insert(column.to_string(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Storage path the snapshot is written to
+    pub fn path(&self) -> String {
+        format!(
+            "{}/snapshot-{}.json",
+            BACKUP_PREFIX,
+            self.created_at.format("%Y%m%dT%H%M%SZ")
+        )
+    }
+
+    /// Parse a snapshot document
+    pub fn from_slice(data: &[u8]) -> Result<Self, BackupError> {
+        serde_json::from_slice(data).map_err(|e| BackupError::InvalidSnapshot(e.to_string()))
+    }
+
+    /// Whether this release can restore the snapshot
+    ///
+    /// The format must match exactly. Snapshots from an older schema restore fine since
+    /// columns added later take their defaults; a newer schema may hold columns this
+    /// release doesn't know and is rejected.
+    pub fn check_compatible(&self, schema_version: Option<i64>) -> Result<(), BackupError> {
+        if self.format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(BackupError::Incompatible(format!(
+                "snapshot format {} is not supported, expected {}",
+                self.format_version, SNAPSHOT_FORMAT_VERSION
+            )));
+        }
+
+        match (self.schema_version, schema_version) {
+            (Some(snapshot), Some(current)) if snapshot > current => {
+                Err(BackupError::Incompatible(format!(
+                    "snapshot was taken at schema version {} which is newer than this release ({}); restore it with release {} or later",
+                    snapshot, current, self.system_version
+                )))
+            }
+            (Some(_), Some(_)) => Ok(()),
+            _ => Err(BackupError::Incompatible(
+                "schema version of the snapshot or this release is unknown".to_string(),
+            )),
+        }
+    }
+
+    pub fn summary(&self, path: String) -> SnapshotSummary {
+        SnapshotSummary {
+            path,
+            format_version: self.format_version,
+            schema_version: self.schema_version,
+            created_at: self.created_at,
+            rows: self
+                .restore_order()
+                .into_iter()
+                .map(|(table, rows)| (table.to_string(), rows.len()))
+                .collect(),
+        }
+    }
+}
+
+/// Storage path of a snapshot file name, e.g. `snapshot-20260101T000000Z.json`
+///
+/// Only bare file names are accepted so a restore can't read outside the backup directory.
+pub fn snapshot_path(name: &str) -> Result<String, ValidationError> {
+    let name = name.trim();
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "name".to_string(),
+            reason: format!("'{}' is not a snapshot file name", name),
+        });
+    }
+    Ok(format!("{}/{}", BACKUP_PREFIX, name))
+}
+
+/// Columns to restore: those of the table that the snapshot rows actually carry
+pub fn restore_columns(table_columns: &[String], rows: &[Value]) -> Vec<String> {
+    table_columns
+        .iter()
+        .filter(|column| rows.iter().any(|row| row.get(column.as_str()).is_some()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn snapshot(format_version: u32, schema_version: Option<i64>) -> Snapshot {
+        Snapshot {
+            format_version,
+            schema_version,
+            system_version: "0.2.0".to_string(),
+            created_at: Utc.with_ymd_and_hms(2026, 10, 16, 8, 30, 0).unwrap(),
+            created_by: "admin".to_string(),
+            tables: BTreeMap::from([(
+                "jobs".to_string(),
+                vec![json!({"id": "00000000-0000-0000-0000-000000000001", "name": "nightly"})],
+            )]),
+            secrets: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_compatibility() {
+        let current = Some(20250101000019);
+
+        assert!(snapshot(SNAPSHOT_FORMAT_VERSION, current)
+            .check_compatible(current)
+            .is_ok());
+        assert!(snapshot(SNAPSHOT_FORMAT_VERSION, Some(20250101000010))
+            .check_compatible(current)
+            .is_ok());
+
+        for incompatible in [
+            snapshot(SNAPSHOT_FORMAT_VERSION, Some(20250101000020)),
+            snapshot(SNAPSHOT_FORMAT_VERSION + 1, current),
+            snapshot(SNAPSHOT_FORMAT_VERSION, None),
+        ] {
+            assert!(matches!(
+                incompatible.check_compatible(current),
+                Err(BackupError::Incompatible(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_paths_stay_in_backup_directory() {
+        let snapshot = snapshot(SNAPSHOT_FORMAT_VERSION, None);
+        assert_eq!(snapshot.path(), "backups/snapshot-20261016T083000Z.json");
+        assert_eq!(
+            snapshot_path("snapshot-20261016T083000Z.json").unwrap(),
+            snapshot.path()
+        );
+
+        for name in ["", "../secrets.json", "nested/snapshot.json", "a\\b.json"] {
+            assert!(snapshot_path(name).is_err(), "{} should be rejected", name);
+        }
+    }
+
+    #[test]
+    fn test_secrets_are_sealed_out_of_the_rows() {
+        use age::secrecy::ExposeSecret;
+
+        let identity = age::x25519::Identity::generate();
+        let recipients = vec![identity.to_public().to_string()];
+        let webhook_id = "00000000-0000-0000-0000-000000000002";
+
+        let mut snapshot = snapshot(SNAPSHOT_FORMAT_VERSION, None);
+        snapshot.tables.insert(
+            "webhooks".to_string(),
+            vec![json!({"id": webhook_id, "secret_key": "whsec-123", "url_path": "/hook"})],
+        );
+        snapshot.tables.insert(
+            "variables".to_string(),
+            vec![
+                json!({"id": "00000000-0000-0000-0000-000000000003", "name": "REGION", "value": "vn", "is_sensitive": false}),
+                json!({"id": "00000000-0000-0000-0000-000000000004", "name": "TOKEN", "value": "s3cr3t", "is_sensitive": true}),
+            ],
+        );
+        let original = snapshot.tables.clone();
+
+        snapshot.seal_secrets(&recipients).unwrap();
+        let stored = serde_json::to_string(&snapshot).unwrap();
+        assert!(!stored.contains("whsec-123"));
+        assert!(!stored.contains("s3cr3t"));
+        assert!(stored.contains("\"vn\""));
+
+        let mut stranger = Snapshot::from_slice(stored.as_bytes()).unwrap();
+        let other = age::x25519::Identity::generate();
+        assert!(matches!(
+            stranger.open_secrets(other.to_string().expose_secret()),
+            Err(BackupError::Encryption(_))
+        ));
+
+        let mut restored = Snapshot::from_slice(stored.as_bytes()).unwrap();
+        restored
+            .open_secrets(identity.to_string().expose_secret())
+            .unwrap();
+        assert_eq!(restored.tables, original);
+    }
+
+    #[test]
+    fn test_restore_columns_skip_columns_missing_from_snapshot() {
+        let table_columns: Vec<String> = ["id", "name", "tenant_id"]
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        let rows = vec![json!({"id": 1, "name": "nightly", "dropped_column": true})];
+
+        assert_eq!(restore_columns(&table_columns, &rows), vec!["id", "name"]);
+    }
+}
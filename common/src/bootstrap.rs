@@ -3,33 +3,35 @@
 // RECC 2025: Descriptive file name, single responsibility, DRY principle
 
 use crate::config::Settings;
-use crate::db::{DbPool, RedisPool};
+use crate::db::{DbPool, RedisConnection, RedisPool};
 use crate::queue::{NatsClient, NatsConfig};
 use crate::storage::{StorageService, StorageServiceImpl};
 use anyhow::{Context, Result};
-use redis::aio::ConnectionManager as RedisConnectionManager;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tracing::info;
 
-/// Initialize Redis connection manager for storage cache
+/// Initialize Redis connection for storage cache
 /// Used by: API server, Worker
 ///
+/// Connects to a single node, a Sentinel-managed master or a cluster depending on
+/// `settings.redis`.
+///
 /// # Errors
-/// Returns error if Redis client creation or connection manager initialization fails
+/// Returns error if the Redis connection cannot be established
 #[tracing::instrument(skip(settings))]
-pub async fn init_redis_connection_manager(settings: &Settings) -> Result<RedisConnectionManager> {
-    info!("Initializing Redis connection manager");
-
-    let redis_client = redis::Client::open(settings.redis.url.as_str())
-        .context("Failed to create Redis client")?;
+pub async fn init_redis_connection(settings: &Settings) -> Result<RedisConnection> {
+    info!("Initializing Redis connection");
 
-    let redis_conn_manager = RedisConnectionManager::new(redis_client)
+    let redis_connection = RedisConnection::connect(&settings.redis)
         .await
-        .context("Failed to initialize Redis connection manager")?;
+        .context("Failed to initialize Redis connection")?;
 
-    info!("Redis connection manager initialized");
-    Ok(redis_conn_manager)
+    info!(
+        topology = redis_connection.topology(),
+        "Redis connection initialized"
+    );
+    Ok(redis_connection)
 }
 
 /// Initialize Storage service (PostgreSQL + Redis + Filesystem)
@@ -37,11 +39,11 @@ pub async fn init_redis_connection_manager(settings: &Settings) -> Result<RedisC
 ///
 /// # Errors
 /// Returns error if storage service initialization fails
-#[tracing::instrument(skip(settings, db_pool, redis_conn_manager))]
+#[tracing::instrument(skip(settings, db_pool, redis_connection))]
 pub async fn init_storage_service(
     settings: &Settings,
     db_pool: DbPool,
-    redis_conn_manager: Arc<RedisConnectionManager>,
+    redis_connection: Arc<RedisConnection>,
 ) -> Result<Arc<dyn StorageService>> {
     info!("Initializing Storage service (PostgreSQL + Redis + Filesystem)");
 
@@ -55,11 +57,15 @@ pub async fn init_storage_service(
         info!(path = %file_base_path.display(), "Created file storage directory");
     }
 
-    let storage_service = Arc::new(StorageServiceImpl::new(
-        db_pool.pool().clone(),
-        redis_conn_manager,
-        Some(file_base_path.clone()),
-    )) as Arc<dyn StorageService>;
+    let storage_service = Arc::new(
+        StorageServiceImpl::new(
+            db_pool.pool().clone(),
+            redis_connection,
+            Some(file_base_path.clone()),
+        )
+        .with_residency(&settings.storage.residency)
+        .context("Invalid storage residency configuration")?,
+    ) as Arc<dyn StorageService>;
 
     info!(
         file_base_path = %file_base_path.display(),
@@ -71,6 +77,8 @@ pub async fn init_storage_service(
 /// Initialize NATS client with standard configuration
 /// Used by: Worker, Scheduler
 ///
+/// TLS and credentials come from `settings.nats.tls` and `settings.nats.auth`.
+///
 /// # Arguments
 /// * `settings` - Application settings
 /// * `consumer_name` - Name for the NATS consumer (e.g., "worker-consumer", "scheduler-consumer")
@@ -85,15 +93,24 @@ pub async fn init_nats_client(settings: &Settings, consumer_name: &str) -> Resul
         url: settings.nats.url.clone(),
         stream_name: settings.nats.stream_name.clone(),
         subject: "jobs.>".to_string(),
-        max_age_seconds: 86400, // 24 hours
-        max_messages: 1_000_000,
+        max_age_seconds: settings.nats.limits.max_age_seconds,
+        max_messages: settings.nats.limits.max_messages,
+        max_bytes: settings.nats.limits.max_bytes,
         consumer_name: consumer_name.to_string(),
         max_deliver: 10,
+        tenant_streams: settings.nats.tenant_streams.clone(),
+        max_message_bytes: settings.nats.max_message_bytes,
+        compression: settings.nats.compression,
+        compression_min_bytes: settings.nats.compression_min_bytes,
+        shards: settings.nats.shards,
+        worker_shards: settings.nats.worker_shards.clone(),
+        priority_subjects: settings.nats.priority_subjects,
     };
 
-    let nats_client = NatsClient::new(nats_config)
+    let client = crate::queue::nats::connect(&settings.nats)
         .await
         .context("Failed to initialize NATS client")?;
+    let nats_client = NatsClient::from_client(client, nats_config);
 
     info!("NATS client initialized");
     Ok(nats_client)
@@ -116,6 +133,31 @@ pub async fn init_database_pool(settings: &Settings) -> Result<DbPool> {
     Ok(db_pool)
 }
 
+/// Apply the database migrations embedded in the binary
+/// Used by: API server, Worker, Scheduler when started with `--migrate`
+///
+/// # Errors
+/// Returns error if any migration fails
+#[tracing::instrument(skip(db_pool))]
+pub async fn run_migrations(db_pool: &DbPool) -> Result<()> {
+    crate::db::migrations::run_migrations(db_pool)
+        .await
+        .context("Failed to apply database migrations")
+}
+
+/// Refuse to start against a database schema this binary wasn't built for
+/// Used by: API server, Worker, Scheduler
+///
+/// # Errors
+/// Returns error if migrations are pending, the schema is newer than the binary,
+/// or an applied migration doesn't match
+#[tracing::instrument(skip(db_pool))]
+pub async fn verify_schema(db_pool: &DbPool) -> Result<()> {
+    crate::db::migrations::verify_schema(db_pool)
+        .await
+        .context("Database schema check failed")
+}
+
 /// Initialize Redis pool for distributed locking
 /// Used by: Scheduler
 ///
@@ -0,0 +1,389 @@
+// Execution completion callbacks
+// Purpose: POST a signed summary of a finished execution to a URL supplied at trigger
+// time, so external orchestrators don't have to poll the executions endpoint
+
+use crate::errors::{ExecutionError, ValidationError};
+use crate::models::{Job, JobContext, JobExecution};
+use crate::webhook::{OutboundWebhook, WebhookDispatcher};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use tracing::instrument;
+use uuid::Uuid;
+
+pub use crate::webhook::{sign_payload, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER};
+
+/// Key under which the callback is stored in `JobExecution::trigger_metadata`
+pub const CALLBACK_METADATA_KEY: &str = "callback";
+
+/// Event name for completion callbacks
+pub const EXECUTION_COMPLETED_EVENT: &str = "execution.completed";
+
+/// Callback requested when triggering an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionCallback {
+    pub url: String,
+    /// Step IDs whose outputs are included in the payload
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub outputs: Vec<String>,
+}
+
+impl ExecutionCallback {
+    /// Validate the callback URL
+    ///
+    /// Rejects URLs naming loopback, private, link-local or other internal
+    /// addresses; `resolve` checks the addresses a host name resolves to.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let url = reqwest::Url::parse(&self.url)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+            .ok_or_else(|| invalid_url("must be an http(s) URL"))?;
+        check_host(&url)
+    }
+
+    /// Validate the callback URL and check that its host resolves only to public
+    /// addresses
+    ///
+    /// Workers connect through a resolver that drops internal addresses as well, so a
+    /// host re-pointed after this check still can't reach them.
+    pub async fn resolve(&self) -> Result<(), ValidationError> {
+        self.validate()?;
+        let url =
+            reqwest::Url::parse(&self.url).map_err(|_| invalid_url("must be an http(s) URL"))?;
+        let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+            return Err(invalid_url("must be an http(s) URL"));
+        };
+        let addresses: Vec<_> = tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| invalid_url("host does not resolve"))?
+            .collect();
+        if addresses.is_empty() {
+            return Err(invalid_url("host does not resolve"));
+        }
+        if addresses
+            .iter()
+            .any(|address| !is_public_address(address.ip()))
+        {
+            return Err(invalid_url("must not resolve to an internal address"));
+        }
+        Ok(())
+    }
+
+    /// Read the callback stored in an execution's trigger metadata
+    pub fn from_trigger_metadata(metadata: Option<&serde_json::Value>) -> Option<Self> {
+        metadata
+            .and_then(|m| m.get(CALLBACK_METADATA_KEY))
+            .and_then(|c| serde_json::from_value(c.clone()).ok())
+    }
+
+    /// Store the callback in an execution's trigger metadata
+    pub fn attach_to(&self, metadata: &mut Option<serde_json::Value>) {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        match metadata {
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(CALLBACK_METADATA_KEY.to_string(), value);
+            }
+            _ => *metadata = Some(serde_json::json!({ CALLBACK_METADATA_KEY: value })),
+        }
+    }
+}
+
+fn invalid_url(reason: &str) -> ValidationError {
+    ValidationError::InvalidFieldValue {
+        field: "callback.url".to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Reject IP literal and localhost hosts that aren't public
+fn check_host(url: &reqwest::Url) -> Result<(), ValidationError> {
+    let host = url.host_str().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let internal = match host.parse::<IpAddr>() {
+        Ok(ip) => !is_public_address(ip),
+        Err(_) => {
+            let host = host.trim_end_matches('.').to_ascii_lowercase();
+            host == "localhost" || host.ends_with(".localhost")
+        }
+    };
+    if internal {
+        return Err(invalid_url("must not point at an internal address"));
+    }
+    Ok(())
+}
+
+/// Whether callbacks may be sent to `ip`: loopback, private, link-local, shared,
+/// documentation, multicast and reserved addresses are internal
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        // Shared address space (carrier-grade NAT), 100.64.0.0/10
+        || (a == 100 && (64..128).contains(&b))
+        // IETF protocol assignments, 192.0.0.0/24
+        || (a == 192 && b == 0 && c == 0)
+        // Benchmarking, 198.18.0.0/15
+        || (a == 198 && (b == 18 || b == 19))
+        // Reserved, 240.0.0.0/4
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast()
+        // Unique local, fc00::/7
+        || (segments[0] & 0xfe00) == 0xfc00
+        // Link-local and deprecated site-local, fe80::/10 and fec0::/10
+        || (segments[0] & 0xffc0) == 0xfe80
+        || (segments[0] & 0xffc0) == 0xfec0
+        // Documentation, 2001:db8::/32
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8)
+        // NAT64 and IPv4-compatible addresses embedding an IPv4 address
+        || (segments[0] == 0x0064 && segments[1] == 0xff9b)
+        || segments[..6] == [0; 6])
+}
+
+/// Body POSTed to the callback URL
+#[derive(Debug, Clone, Serialize)]
+pub struct CallbackPayload {
+    pub event: &'static str,
+    pub execution_id: Uuid,
+    pub job_id: Uuid,
+    pub job_name: String,
+    pub status: String,
+    pub attempt: i32,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub duration_ms: Option<i64>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+    /// Outputs of the steps selected in `ExecutionCallback::outputs`
+    pub outputs: HashMap<String, serde_json::Value>,
+}
+
+impl CallbackPayload {
+    pub fn new(
+        job: &Job,
+        execution: &JobExecution,
+        context: &JobContext,
+        selected_outputs: &[String],
+    ) -> Self {
+        let outputs = selected_outputs
+            .iter()
+            .filter_map(|step_id| {
+                context
+                    .steps
+                    .get(step_id)
+                    .map(|step| (step_id.clone(), step.output.clone()))
+            })
+            .collect();
+
+        Self {
+            event: EXECUTION_COMPLETED_EVENT,
+            execution_id: execution.id,
+            job_id: execution.job_id,
+            job_name: job.name.clone(),
+            status: execution.status.to_string(),
+            attempt: execution.attempt,
+            started_at: execution.started_at,
+            completed_at: execution.completed_at,
+            duration_ms: execution
+                .started_at
+                .zip(execution.completed_at)
+                .map(|(start, end)| (end - start).num_milliseconds()),
+            result: execution.result.clone(),
+            error: execution.error.clone(),
+            outputs,
+        }
+    }
+}
+
+/// CallbackSender queues completion callbacks for delivery with retries
+pub struct CallbackSender {
+    dispatcher: Arc<WebhookDispatcher>,
+}
+
+impl CallbackSender {
+    pub fn new(dispatcher: Arc<WebhookDispatcher>) -> Self {
+        Self { dispatcher }
+    }
+
+    /// Queue the payload for the callback URL, returning the delivery ID
+    ///
+    /// Workers deliver it, retrying network errors, 408, 429 and 5xx responses up to
+    /// `callbacks.max_attempts` times before dead-lettering it.
+    #[instrument(skip(self, callback, payload), fields(execution_id = %payload.execution_id, url = %callback.url))]
+    pub async fn deliver(
+        &self,
+        callback: &ExecutionCallback,
+        payload: &CallbackPayload,
+    ) -> Result<Uuid, ExecutionError> {
+        let payload_json = serde_json::to_value(payload).map_err(|e| {
+            ExecutionError::HttpRequestFailed(format!("Failed to serialize callback: {}", e))
+        })?;
+
+        self.dispatcher
+            .enqueue(OutboundWebhook {
+                url: callback.url.clone(),
+                event: payload.event.to_string(),
+                job_id: Some(payload.job_id),
+                execution_id: Some(payload.execution_id),
+                payload: payload_json,
+            })
+            .await
+            .map_err(|e| {
+                ExecutionError::HttpRequestFailed(format!("Failed to queue callback: {}", e))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StepOutput;
+
+    #[test]
+    fn test_callback_round_trips_through_trigger_metadata() {
+        let callback = ExecutionCallback {
+            url: "https://orchestrator.example.com/hooks/cron".to_string(),
+            outputs: vec!["fetch".to_string()],
+        };
+
+        let mut metadata = None;
+        callback.attach_to(&mut metadata);
+        assert_eq!(
+            ExecutionCallback::from_trigger_metadata(metadata.as_ref()),
+            Some(callback.clone())
+        );
+
+        let mut metadata = Some(serde_json::json!({ "user_id": "alice" }));
+        callback.attach_to(&mut metadata);
+        assert_eq!(metadata.as_ref().unwrap()["user_id"], "alice");
+        assert_eq!(
+            ExecutionCallback::from_trigger_metadata(metadata.as_ref()),
+            Some(callback)
+        );
+
+        assert_eq!(ExecutionCallback::from_trigger_metadata(None), None);
+    }
+
+    #[test]
+    fn test_callback_url_validation() {
+        let callback = |url: &str| ExecutionCallback {
+            url: url.to_string(),
+            outputs: Vec::new(),
+        };
+
+        assert!(callback("https://example.com/done").validate().is_ok());
+        assert!(callback("https://93.184.216.34/done").validate().is_ok());
+        assert!(callback("ftp://example.com/done").validate().is_err());
+        assert!(callback("not a url").validate().is_err());
+
+        // Internal addresses are rejected
+        for url in [
+            "http://10.0.0.5:8080/done",
+            "http://127.0.0.1/done",
+            "http://169.254.169.254/latest/meta-data",
+            "http://100.64.0.1/done",
+            "http://0.0.0.0/done",
+            "http://[::1]/done",
+            "http://[fd00::1]/done",
+            "http://[fe80::1]/done",
+            "http://[::ffff:192.168.1.1]/done",
+            "http://localhost:8080/done",
+            "http://api.localhost/done",
+        ] {
+            assert!(callback(url).validate().is_err(), "{} is internal", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_callback_host_must_resolve_to_public_addresses() {
+        let callback = |url: &str| ExecutionCallback {
+            url: url.to_string(),
+            outputs: Vec::new(),
+        };
+
+        assert!(callback("https://93.184.216.34/done")
+            .resolve()
+            .await
+            .is_ok());
+        assert!(callback("http://127.0.0.1/done").resolve().await.is_err());
+        assert!(callback("http://localhost/done").resolve().await.is_err());
+    }
+
+    #[test]
+    fn test_signature_matches_webhook_validation() {
+        let body = br#"{"event":"execution.completed"}"#;
+        let signature = sign_payload(body, "secret");
+        assert!(crate::webhook::validate_webhook_signature(body, &signature, "secret").unwrap());
+        assert!(!crate::webhook::validate_webhook_signature(body, &signature, "other").unwrap());
+    }
+
+    #[test]
+    fn test_payload_includes_only_selected_outputs() {
+        let job_id = Uuid::new_v4();
+        let mut execution = JobExecution::new_manual(job_id, "alice".to_string());
+        execution.status = crate::models::ExecutionStatus::Success;
+        execution.started_at = Some(Utc::now());
+        execution.completed_at = execution
+            .started_at
+            .map(|t| t + chrono::Duration::seconds(2));
+
+        let mut context = JobContext::new(execution.id, job_id);
+        for step_id in ["fetch", "transform"] {
+            context.steps.insert(
+                step_id.to_string(),
+                StepOutput {
+                    step_id: step_id.to_string(),
+                    status: "success".to_string(),
+                    output: serde_json::json!({ "step": step_id }),
+                    started_at: Utc::now(),
+                    completed_at: Utc::now(),
+                },
+            );
+        }
+
+        let job: Job = serde_json::from_value(serde_json::json!({
+            "id": job_id,
+            "name": "nightly-report",
+            "steps": [],
+            "triggers": { "scheduled": false, "manual": true },
+            "timeout_seconds": 300,
+            "max_retries": 3,
+            "definition": null,
+        }))
+        .unwrap();
+
+        let payload = CallbackPayload::new(
+            &job,
+            &execution,
+            &context,
+            &["transform".to_string(), "missing".to_string()],
+        );
+        assert_eq!(payload.status, "success");
+        assert_eq!(payload.duration_ms, Some(2000));
+        assert_eq!(payload.outputs.len(), 1);
+        assert_eq!(payload.outputs["transform"]["step"], "transform");
+    }
+}
@@ -0,0 +1,302 @@
+// Capability registry and per-tenant feature flags
+// Purpose: Tell clients which step types, backends and versions a deployment supports,
+// and gate experimental step types per tenant so new job types can be rolled out
+// environment by environment without a redeploy
+
+use crate::errors::ValidationError;
+use crate::models::{FeatureFlag, JobStep, JobType};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Every step type a job definition may use
+pub const STEP_TYPES: &[&str] = &[
+    "http_request",
+    "database_query",
+    "file_processing",
+    "sftp",
+    "graphql",
+    "email",
+    "notification",
+    "mqtt_publish",
+    "object_storage",
+    "container",
+    "wasm",
+    "script",
+    "pdf_report",
+    "transform",
+    "validate",
+    "checksum",
+    "incremental_sync",
+    "system",
+];
+
+/// Step types the worker has an executor for
+const EXECUTOR_STEP_TYPES: &[&str] = &[
+    "http_request",
+    "database_query",
+    "file_processing",
+    "sftp",
+    "graphql",
+    "email",
+    "notification",
+    "mqtt_publish",
+    "object_storage",
+    "container",
+    "wasm",
+    "script",
+    "pdf_report",
+    "transform",
+    "validate",
+    "checksum",
+    "incremental_sync",
+    "system",
+];
+
+/// Step type of the built-in maintenance tasks, which only admins may use
+pub const SYSTEM_STEP_TYPE: &str = "system";
+
+/// Prefix of the feature flags that unlock experimental step types
+pub const STEP_TYPE_FLAG_PREFIX: &str = "step_type.";
+
+/// Queue backend the API publishes to and workers consume from
+pub const QUEUE_BACKEND: &str = "nats_jetstream";
+
+/// Storage backend for job definitions, contexts and files
+pub const STORAGE_BACKEND: &str = "postgresql+redis+filesystem";
+
+/// Name used for a step type in capabilities and feature flags
+pub fn step_type_name(step_type: &JobType) -> &'static str {
+    match step_type {
+        JobType::HttpRequest { .. } => "http_request",
+        JobType::DatabaseQuery { .. } => "database_query",
+        JobType::FileProcessing { .. } => "file_processing",
+        JobType::Sftp { .. } => "sftp",
+        JobType::GraphQL { .. } => "graphql",
+        JobType::Email { .. } => "email",
+        JobType::Notification { .. } => "notification",
+        JobType::MqttPublish { .. } => "mqtt_publish",
+        JobType::ObjectStorage { .. } => "object_storage",
+        JobType::Container { .. } => "container",
+        JobType::Wasm { .. } => "wasm",
+        JobType::Script { .. } => "script",
+        JobType::PdfReport { .. } => "pdf_report",
+        JobType::Transform { .. } => "transform",
+        JobType::Validate { .. } => "validate",
+        JobType::Checksum { .. } => "checksum",
+        JobType::IncrementalSync { .. } => "incremental_sync",
+        JobType::System { .. } => SYSTEM_STEP_TYPE,
+    }
+}
+
+/// Feature flag that unlocks an experimental step type
+pub fn step_type_flag(step_type: &str) -> String {
+    format!("{}{}", STEP_TYPE_FLAG_PREFIX, step_type)
+}
+
+/// Effective flag values for a tenant: its own rows override the global ones
+pub fn resolve_flags(flags: &[FeatureFlag], tenant_id: Option<&str>) -> BTreeMap<String, bool> {
+    let mut resolved = BTreeMap::new();
+    for flag in flags.iter().filter(|f| f.tenant_id.is_none()) {
+        resolved.insert(flag.name.clone(), flag.enabled);
+    }
+    if let Some(tenant_id) = tenant_id {
+        for flag in flags
+            .iter()
+            .filter(|f| f.tenant_id.as_deref() == Some(tenant_id))
+        {
+            resolved.insert(flag.name.clone(), flag.enabled);
+        }
+    }
+    resolved
+}
+
+/// Reject steps whose experimental type isn't enabled by the resolved flags
+pub fn check_step_types(
+    steps: &[JobStep],
+    experimental_step_types: &[String],
+    flags: &BTreeMap<String, bool>,
+) -> Result<(), ValidationError> {
+    for step in steps {
+        let step_type = step_type_name(&step.step_type);
+        let experimental = experimental_step_types.iter().any(|t| t == step_type);
+        if experimental
+            && !flags
+                .get(&step_type_flag(step_type))
+                .copied()
+                .unwrap_or(false)
+        {
+            return Err(ValidationError::InvalidFieldValue {
+                field: format!("steps.{}.type", step.id),
+                reason: step_type.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Whether any step uses one of the experimental types
+pub fn uses_experimental_step_types(steps: &[JobStep], experimental_step_types: &[String]) -> bool {
+    steps.iter().any(|step| {
+        let step_type = step_type_name(&step.step_type);
+        experimental_step_types.iter().any(|t| t == step_type)
+    })
+}
+
+/// A step type and whether a tenant can use it
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutorCapability {
+    pub step_type: &'static str,
+    /// The worker has an executor for this step type
+    pub enabled: bool,
+    /// Gated by the `step_type.<name>` feature flag
+    pub experimental: bool,
+    /// Jobs using this step type can be created by the requesting tenant
+    pub available: bool,
+}
+
+/// Component and schema versions
+#[derive(Debug, Clone, Serialize)]
+pub struct Versions {
+    pub api: String,
+    /// Latest migration the binary was built with
+    pub schema: Option<i64>,
+}
+
+/// What this deployment supports, as seen by one tenant
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub executors: Vec<ExecutorCapability>,
+    pub queue_backend: &'static str,
+    pub storage_backend: &'static str,
+    pub versions: Versions,
+    pub tenant_id: Option<String>,
+    /// Effective feature flags for the tenant
+    pub features: BTreeMap<String, bool>,
+}
+
+impl Capabilities {
+    pub fn new(
+        experimental_step_types: &[String],
+        features: BTreeMap<String, bool>,
+        tenant_id: Option<String>,
+        versions: Versions,
+    ) -> Self {
+        let executors = STEP_TYPES
+            .iter()
+            .map(|&step_type| {
+                let enabled = EXECUTOR_STEP_TYPES.contains(&step_type);
+                let experimental = experimental_step_types.iter().any(|t| t == step_type);
+                let unlocked = !experimental
+                    || features
+                        .get(&step_type_flag(step_type))
+                        .copied()
+                        .unwrap_or(false);
+                ExecutorCapability {
+                    step_type,
+                    enabled,
+                    experimental,
+                    available: enabled && unlocked,
+                }
+            })
+            .collect();
+
+        Self {
+            executors,
+            queue_backend: QUEUE_BACKEND,
+            storage_backend: STORAGE_BACKEND,
+            versions,
+            tenant_id,
+            features,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn flag(name: &str, tenant_id: Option<&str>, enabled: bool) -> FeatureFlag {
+        FeatureFlag {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            tenant_id: tenant_id.map(str::to_string),
+            enabled,
+            description: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn http_step() -> JobStep {
+        serde_json::from_value(serde_json::json!({
+            "id": "fetch",
+            "name": "Fetch",
+            "type": {
+                "type": "http_request",
+                "method": "GET",
+                "url": "https://example.com",
+                "headers": HashMap::<String, String>::new(),
+                "body": null,
+                "auth": null
+            },
+            "condition": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_tenant_flags_override_global_flags() {
+        let flags = vec![
+            flag("step_type.http_request", None, false),
+            flag("step_type.http_request", Some("acme"), true),
+            flag("step_type.http_request", Some("globex"), false),
+            flag("new_dashboard", None, true),
+        ];
+
+        let acme = resolve_flags(&flags, Some("acme"));
+        assert!(acme["step_type.http_request"]);
+        assert!(acme["new_dashboard"]);
+
+        let anonymous = resolve_flags(&flags, None);
+        assert!(!anonymous["step_type.http_request"]);
+    }
+
+    #[test]
+    fn test_experimental_step_types_need_their_flag() {
+        let steps = vec![http_step()];
+        let experimental = vec!["http_request".to_string()];
+
+        assert!(check_step_types(&steps, &[], &BTreeMap::new()).is_ok());
+        assert!(uses_experimental_step_types(&steps, &experimental));
+
+        let err = check_step_types(&steps, &experimental, &BTreeMap::new()).unwrap_err();
+        assert!(err.to_string().contains("http_request"));
+
+        let flags = BTreeMap::from([("step_type.http_request".to_string(), true)]);
+        assert!(check_step_types(&steps, &experimental, &flags).is_ok());
+    }
+
+    #[test]
+    fn test_capabilities_report_availability() {
+        let experimental = vec!["database_query".to_string()];
+        let versions = Versions {
+            api: "1.0.0".to_string(),
+            schema: Some(17),
+        };
+        let capabilities = Capabilities::new(&experimental, BTreeMap::new(), None, versions);
+
+        let by_type: HashMap<_, _> = capabilities
+            .executors
+            .iter()
+            .map(|e| (e.step_type, e))
+            .collect();
+        assert!(by_type["http_request"].available);
+        assert!(by_type["database_query"].experimental);
+        assert!(!by_type["database_query"].available);
+        assert!(by_type["sftp"].enabled);
+        assert_eq!(capabilities.queue_backend, QUEUE_BACKEND);
+    }
+}
@@ -2,13 +2,15 @@
 // Requirements: 4.7
 // Property 35: Circuit breaker activation
 
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 use tracing::{info, warn};
 
 /// Circuit breaker states
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CircuitState {
     /// Circuit is closed, requests are allowed
     Closed,
@@ -65,6 +67,8 @@ pub struct CircuitBreaker {
     name: String,
     config: CircuitBreakerConfig,
     state: Arc<RwLock<CircuitBreakerState>>,
+    /// Told the breaker's name whenever it closes again after being open
+    recoveries: Option<mpsc::UnboundedSender<String>>,
 }
 
 impl CircuitBreaker {
@@ -74,9 +78,16 @@ impl CircuitBreaker {
             name: name.into(),
             config,
             state: Arc::new(RwLock::new(CircuitBreakerState::new())),
+            recoveries: None,
         }
     }
 
+    /// Send the breaker's name to `recoveries` whenever it closes after being open
+    pub fn with_recovery_notifier(mut self, recoveries: mpsc::UnboundedSender<String>) -> Self {
+        self.recoveries = Some(recoveries);
+        self
+    }
+
     /// Create a new circuit breaker with default configuration
     pub fn with_defaults(name: impl Into<String>) -> Self {
         Self::new(name, CircuitBreakerConfig::default())
@@ -99,6 +110,21 @@ impl CircuitBreaker {
 
     /// Execute a function with circuit breaker protection
     pub async fn call<F, T, E>(&self, f: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: std::future::Future<Output = Result<T, E>>,
+    {
+        self.call_counting(f, |_| true).await
+    }
+
+    /// Execute a function with circuit breaker protection, counting only the errors
+    /// `is_failure` accepts as failures of the dependency
+    ///
+    /// Other errors mean the dependency answered, so they count as successes.
+    pub async fn call_counting<F, T, E>(
+        &self,
+        f: F,
+        is_failure: impl Fn(&E) -> bool,
+    ) -> Result<T, CircuitBreakerError<E>>
     where
         F: std::future::Future<Output = Result<T, E>>,
     {
@@ -115,7 +141,11 @@ impl CircuitBreaker {
                 Ok(result)
             }
             Err(err) => {
-                self.on_failure().await;
+                if is_failure(&err) {
+                    self.on_failure().await;
+                } else {
+                    self.on_success().await;
+                }
                 Err(CircuitBreakerError::RequestFailed(err))
             }
         }
@@ -185,6 +215,9 @@ impl CircuitBreaker {
                     state.failure_count = 0;
                     state.success_count = 0;
                     state.last_failure_time = None;
+                    if let Some(recoveries) = &self.recoveries {
+                        let _ = recoveries.send(self.name.clone());
+                    }
                 }
             }
             CircuitState::Open => {
@@ -239,10 +272,16 @@ impl CircuitBreaker {
             circuit_breaker = %self.name,
             "Circuit breaker manually reset to Closed"
         );
+        let was_open = state.state != CircuitState::Closed;
         state.state = CircuitState::Closed;
         state.failure_count = 0;
         state.success_count = 0;
         state.last_failure_time = None;
+        if was_open {
+            if let Some(recoveries) = &self.recoveries {
+                let _ = recoveries.send(self.name.clone());
+            }
+        }
     }
 }
 
@@ -388,6 +427,32 @@ mod tests {
         assert_eq!(cb.get_failure_count().await, 0);
     }
 
+    #[tokio::test]
+    async fn test_recovery_notifier_told_when_circuit_closes() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 1,
+            timeout: Duration::from_millis(50),
+            success_threshold: 1,
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let cb = CircuitBreaker::new("partner-sftp", config).with_recovery_notifier(tx);
+
+        let _: Result<(), CircuitBreakerError<String>> = cb
+            .call(async { Err::<(), String>("error".to_string()) })
+            .await;
+        assert!(rx.try_recv().is_err());
+
+        sleep(Duration::from_millis(80)).await;
+        let _: Result<(), CircuitBreakerError<String>> =
+            cb.call(async { Ok::<(), String>(()) }).await;
+        assert_eq!(rx.try_recv().unwrap(), "partner-sftp");
+
+        // Successes while closed are no recovery
+        let _: Result<(), CircuitBreakerError<String>> =
+            cb.call(async { Ok::<(), String>(()) }).await;
+        assert!(rx.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_circuit_breaker_reopens_on_half_open_failure() {
         let config = CircuitBreakerConfig {
@@ -473,4 +538,26 @@ mod tests {
         assert_eq!(cb.get_failure_count().await, 0);
         assert_eq!(cb.get_state().await, CircuitState::Closed);
     }
+
+    #[tokio::test]
+    async fn test_errors_not_counted_as_failures_keep_circuit_closed() {
+        let config = CircuitBreakerConfig {
+            failure_threshold: 2,
+            timeout: Duration::from_secs(60),
+            success_threshold: 2,
+        };
+        let cb = CircuitBreaker::new("test", config);
+
+        for _ in 0..3 {
+            let result: Result<(), CircuitBreakerError<String>> = cb
+                .call_counting(async { Err::<(), String>("rejected".to_string()) }, |e| {
+                    e != "rejected"
+                })
+                .await;
+            assert!(matches!(result, Err(CircuitBreakerError::RequestFailed(_))));
+        }
+
+        assert_eq!(cb.get_failure_count().await, 0);
+        assert_eq!(cb.get_state().await, CircuitState::Closed);
+    }
 }
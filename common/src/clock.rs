@@ -0,0 +1,332 @@
+// Clock-skew detection and guard
+// Purpose: Compare this node's clock against Postgres and, optionally, an NTP server.
+// A scheduler whose clock runs ahead fires jobs early, so it stops scheduling (and
+// alerts) while the skew exceeds the configured limit
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::telemetry::{self, AlertNotifier};
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tracing::{error, info, instrument, warn};
+
+/// Seconds between the NTP epoch (1900) and the Unix epoch (1970)
+const NTP_UNIX_OFFSET_SECONDS: f64 = 2_208_988_800.0;
+
+/// How long to wait for an NTP reply
+const NTP_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Errors from clock measurements
+#[derive(Debug, thiserror::Error)]
+pub enum ClockError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("NTP query failed: {0}")]
+    Ntp(String),
+
+    #[error("Clock skew of {offset_ms}ms against {reference} exceeds the {max_ms}ms limit")]
+    Skewed {
+        reference: ClockSource,
+        offset_ms: i64,
+        max_ms: u64,
+    },
+}
+
+/// Reference clock a measurement was taken against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockSource {
+    Postgres,
+    Ntp,
+}
+
+impl ClockSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ClockSource::Postgres => "postgres",
+            ClockSource::Ntp => "ntp",
+        }
+    }
+}
+
+impl fmt::Display for ClockSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Offset of the reference clock from this node's clock
+///
+/// A positive offset means this node is behind the reference; negative means it runs
+/// ahead and would fire jobs early.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockReading {
+    pub source: ClockSource,
+    pub offset_ms: i64,
+    pub round_trip_ms: i64,
+}
+
+impl ClockReading {
+    pub fn exceeds(&self, max_skew_ms: u64) -> bool {
+        self.offset_ms.unsigned_abs() > max_skew_ms
+    }
+}
+
+/// Offset of a remote timestamp taken between two local ones, assuming symmetric latency
+pub fn midpoint_offset(
+    sent: DateTime<Utc>,
+    remote: DateTime<Utc>,
+    received: DateTime<Utc>,
+) -> (i64, i64) {
+    let round_trip = received - sent;
+    let midpoint = sent + round_trip / 2;
+    (
+        (remote - midpoint).num_milliseconds(),
+        round_trip.num_milliseconds(),
+    )
+}
+
+/// Compare this node's clock with the database server's
+#[instrument(skip(pool))]
+pub async fn postgres_reading(pool: &DbPool) -> Result<ClockReading, ClockError> {
+    let sent = Utc::now();
+    // clock_timestamp() is the wall clock, not the start of the transaction like now()
+    let remote: DateTime<Utc> = sqlx::query_scalar("SELECT clock_timestamp()")
+        .fetch_one(pool.pool())
+        .await
+        .map_err(DatabaseError::from)?;
+    let received = Utc::now();
+
+    let (offset_ms, round_trip_ms) = midpoint_offset(sent, remote, received);
+    Ok(ClockReading {
+        source: ClockSource::Postgres,
+        offset_ms,
+        round_trip_ms,
+    })
+}
+
+/// Compare this node's clock with an NTP server using a single SNTP request
+#[instrument]
+pub async fn ntp_reading(server: &str) -> Result<ClockReading, ClockError> {
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(|e| ClockError::Ntp(e.to_string()))?;
+    socket
+        .connect(server)
+        .await
+        .map_err(|e| ClockError::Ntp(format!("{}: {}", server, e)))?;
+
+    // LI = 0, version 4, mode 3 (client)
+    let mut request = [0u8; 48];
+    request[0] = 0x23;
+
+    let sent = Utc::now();
+    socket
+        .send(&request)
+        .await
+        .map_err(|e| ClockError::Ntp(e.to_string()))?;
+    let mut response = [0u8; 48];
+    let len = tokio::time::timeout(NTP_TIMEOUT, socket.recv(&mut response))
+        .await
+        .map_err(|_| ClockError::Ntp(format!("{} did not answer in time", server)))?
+        .map_err(|e| ClockError::Ntp(e.to_string()))?;
+    let received = Utc::now();
+
+    if len < response.len() {
+        return Err(ClockError::Ntp(format!("short reply of {} bytes", len)));
+    }
+    ntp_offset(&response, sent, received)
+}
+
+/// Clock offset from an SNTP reply (RFC 4330): ((T2 - T1) + (T3 - T4)) / 2
+pub fn ntp_offset(
+    response: &[u8; 48],
+    sent: DateTime<Utc>,
+    received: DateTime<Utc>,
+) -> Result<ClockReading, ClockError> {
+    if response[0] & 0x07 != 4 {
+        return Err(ClockError::Ntp("reply is not in server mode".to_string()));
+    }
+    if response[1] == 0 {
+        return Err(ClockError::Ntp(
+            "server sent a kiss-of-death reply".to_string(),
+        ));
+    }
+
+    let t1 = sent.timestamp_millis() as f64;
+    let t4 = received.timestamp_millis() as f64;
+    let t2 = ntp_timestamp_ms(&response[32..40]);
+    let t3 = ntp_timestamp_ms(&response[40..48]);
+
+    Ok(ClockReading {
+        source: ClockSource::Ntp,
+        offset_ms: (((t2 - t1) + (t3 - t4)) / 2.0).round() as i64,
+        round_trip_ms: ((t4 - t1) - (t3 - t2)).round() as i64,
+    })
+}
+
+/// Unix milliseconds of a 64-bit NTP timestamp
+fn ntp_timestamp_ms(bytes: &[u8]) -> f64 {
+    let seconds = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f64;
+    let fraction = u32::from_be_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as f64;
+    (seconds - NTP_UNIX_OFFSET_SECONDS + fraction / 4_294_967_296.0) * 1000.0
+}
+
+/// Keeps track of whether this node's clock is close enough to schedule jobs
+pub struct ClockGuard {
+    db_pool: DbPool,
+    ntp_server: Option<String>,
+    max_skew_ms: u64,
+    check_interval: Duration,
+    alert_notifier: Arc<dyn AlertNotifier>,
+    in_sync: AtomicBool,
+}
+
+impl ClockGuard {
+    pub fn new(
+        db_pool: DbPool,
+        ntp_server: Option<String>,
+        max_skew_ms: u64,
+        check_interval: Duration,
+        alert_notifier: Arc<dyn AlertNotifier>,
+    ) -> Self {
+        Self {
+            db_pool,
+            ntp_server,
+            max_skew_ms,
+            check_interval,
+            alert_notifier,
+            in_sync: AtomicBool::new(true),
+        }
+    }
+
+    /// Whether the last check found the clock within the limit
+    pub fn in_sync(&self) -> bool {
+        self.in_sync.load(Ordering::Relaxed)
+    }
+
+    /// Measure against every reference clock and update the guard
+    ///
+    /// A reference that can't be reached is logged and skipped, so an NTP outage
+    /// doesn't stop scheduling. Alerts once when the clock drifts out of the limit.
+    pub async fn check(&self) -> Result<(), ClockError> {
+        let mut readings = Vec::new();
+        match postgres_reading(&self.db_pool).await {
+            Ok(reading) => readings.push(reading),
+            Err(e) => warn!(error = %e, "Failed to compare clock with Postgres"),
+        }
+        if let Some(server) = &self.ntp_server {
+            match ntp_reading(server).await {
+                Ok(reading) => readings.push(reading),
+                Err(e) => warn!(error = %e, server = %server, "Failed to compare clock with NTP"),
+            }
+        }
+
+        for reading in &readings {
+            telemetry::record_clock_skew(reading.source.as_str(), reading.offset_ms);
+        }
+
+        match readings.iter().find(|r| r.exceeds(self.max_skew_ms)) {
+            Some(reading) => {
+                if self.in_sync.swap(false, Ordering::Relaxed) {
+                    if let Err(e) = self
+                        .alert_notifier
+                        .send_clock_skew_alert(
+                            reading.source.as_str(),
+                            reading.offset_ms,
+                            self.max_skew_ms,
+                        )
+                        .await
+                    {
+                        error!(error = %e, "Failed to send clock skew alert");
+                    }
+                }
+                Err(ClockError::Skewed {
+                    reference: reading.source,
+                    offset_ms: reading.offset_ms,
+                    max_ms: self.max_skew_ms,
+                })
+            }
+            None => {
+                if !self.in_sync.swap(true, Ordering::Relaxed) {
+                    info!("Clock back within the skew limit, scheduling resumes");
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Re-check periodically in the background
+    pub fn spawn(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.check_interval);
+            // The first tick completes immediately; startup already checked once
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.check().await {
+                    error!(error = %e, "Clock skew exceeds the limit, scheduling paused");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ntp_bytes(unix_ms: i64) -> [u8; 8] {
+        let seconds = (unix_ms / 1000) as u64 + NTP_UNIX_OFFSET_SECONDS as u64;
+        let fraction = ((unix_ms % 1000) as u64 * (1u64 << 32)) / 1000;
+        let mut bytes = [0u8; 8];
+        bytes[..4].copy_from_slice(&(seconds as u32).to_be_bytes());
+        bytes[4..].copy_from_slice(&(fraction as u32).to_be_bytes());
+        bytes
+    }
+
+    #[test]
+    fn test_midpoint_offset_of_node_running_ahead() {
+        let sent = Utc.with_ymd_and_hms(2026, 10, 16, 8, 0, 0).unwrap();
+        let received = sent + chrono::Duration::milliseconds(20);
+        // Remote clock is 2s behind the midpoint of the request
+        let remote = sent + chrono::Duration::milliseconds(10 - 2000);
+
+        let (offset_ms, round_trip_ms) = midpoint_offset(sent, remote, received);
+        assert_eq!((offset_ms, round_trip_ms), (-2000, 20));
+
+        let reading = ClockReading {
+            source: ClockSource::Postgres,
+            offset_ms,
+            round_trip_ms,
+        };
+        assert!(reading.exceeds(1000));
+        assert!(!reading.exceeds(2000));
+    }
+
+    #[test]
+    fn test_ntp_offset_from_server_reply() {
+        let sent = Utc.with_ymd_and_hms(2026, 10, 16, 8, 0, 0).unwrap();
+        let received = sent + chrono::Duration::milliseconds(40);
+        let server_ms = sent.timestamp_millis() + 1500 + 20;
+
+        let mut response = [0u8; 48];
+        response[0] = 0x24; // version 4, server mode
+        response[1] = 2; // stratum
+        response[32..40].copy_from_slice(&ntp_bytes(server_ms));
+        response[40..48].copy_from_slice(&ntp_bytes(server_ms));
+
+        let reading = ntp_offset(&response, sent, received).unwrap();
+        assert_eq!(reading.source, ClockSource::Ntp);
+        assert!((reading.offset_ms - 1500).abs() <= 1, "{:?}", reading);
+        assert!((reading.round_trip_ms - 40).abs() <= 1, "{:?}", reading);
+
+        response[1] = 0;
+        assert!(ntp_offset(&response, sent, received).is_err());
+    }
+}
@@ -1,8 +1,11 @@
 // Configuration management with layered configuration (file, env, CLI)
 // Requirements: 7.5
 
+use crate::i18n::Locale;
+use crate::models::ObjectStorageBucket;
 use config::{Config, ConfigError, Environment, File};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 /// Main settings structure containing all configuration options
@@ -17,6 +20,26 @@ pub struct Settings {
     pub scheduler: SchedulerConfig,
     pub worker: WorkerConfig,
     pub observability: ObservabilityConfig,
+    #[serde(default)]
+    pub i18n: I18nConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub idempotency: IdempotencyConfig,
+    #[serde(default)]
+    pub stats: StatsConfig,
+    #[serde(default)]
+    pub callbacks: CallbackConfig,
+    #[serde(default)]
+    pub http_client: HttpClientConfig,
+    #[serde(default)]
+    pub features: FeaturesConfig,
+    #[serde(default)]
+    pub fault_injection: FaultInjectionConfig,
+    #[serde(default)]
+    pub failover: FailoverConfig,
+    #[serde(default)]
+    pub system_jobs: SystemJobsConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,12 +54,69 @@ pub struct DatabaseConfig {
     pub max_connections: u32,
     pub min_connections: u32,
     pub connect_timeout_seconds: u64,
+    /// libpq sslmode: disable, allow, prefer, require, verify-ca or verify-full;
+    /// overrides any `sslmode` in the URL
+    #[serde(default)]
+    pub ssl_mode: Option<String>,
+    /// PEM file with the CA certificate used by verify-ca / verify-full
+    #[serde(default)]
+    pub ssl_root_cert: Option<String>,
+    /// Server-side `statement_timeout` for connections of the primary pool
+    #[serde(default)]
+    pub statement_timeout_seconds: Option<u64>,
+    /// Optional replica for read-heavy queries (execution history, stats)
+    #[serde(default)]
+    pub read_replica: Option<ReadReplicaConfig>,
+}
+
+/// Read replica pool; TLS settings are shared with the primary
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadReplicaConfig {
+    pub url: String,
+    #[serde(default = "default_replica_max_connections")]
+    pub max_connections: u32,
+    #[serde(default = "default_replica_min_connections")]
+    pub min_connections: u32,
+    /// Server-side `statement_timeout` for connections of the replica pool
+    #[serde(default)]
+    pub statement_timeout_seconds: Option<u64>,
+}
+
+fn default_replica_max_connections() -> u32 {
+    10
+}
+
+fn default_replica_min_connections() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RedisConfig {
     pub url: String,
     pub pool_size: u32,
+    /// Connect to the master elected by Redis Sentinel instead of `url`;
+    /// credentials and database still come from `url`
+    #[serde(default)]
+    pub sentinel: Option<RedisSentinelConfig>,
+    /// Connect to a Redis Cluster through these seed nodes instead of `url`
+    #[serde(default)]
+    pub cluster: Option<RedisClusterConfig>,
+}
+
+/// Redis Sentinel topology
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisSentinelConfig {
+    /// Name of the monitored master (`sentinel monitor <name> ...`)
+    pub master_name: String,
+    /// Sentinel URLs, e.g. `redis://sentinel-1:26379`
+    pub nodes: Vec<String>,
+}
+
+/// Redis Cluster topology
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisClusterConfig {
+    /// Seed node URLs; the rest of the cluster is discovered from them
+    pub nodes: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,23 +124,176 @@ pub struct NatsConfig {
     pub url: String,
     pub stream_name: String,
     pub consumer_name: String,
+    #[serde(default)]
+    pub tls: NatsTlsConfig,
+    #[serde(default)]
+    pub auth: NatsAuthConfig,
+    /// Limits of the shared job stream
+    #[serde(default)]
+    pub limits: StreamLimits,
+    /// Tenants that get their own job stream and consumer, with that stream's limits
+    #[serde(default)]
+    pub tenant_streams: BTreeMap<String, StreamLimits>,
+    /// Largest job message published inline; bigger ones are offloaded to storage.
+    /// The server's `max_payload` applies when it is lower.
+    #[serde(default = "default_nats_max_message_bytes")]
+    pub max_message_bytes: usize,
+    /// Compression of published job messages; consumers read both forms
+    #[serde(default)]
+    pub compression: MessageCompression,
+    /// Messages smaller than this are published uncompressed
+    #[serde(default = "default_nats_compression_min_bytes")]
+    pub compression_min_bytes: usize,
+    /// Split job subjects into this many shards by job id, each consumed one message
+    /// at a time, so executions of a job never run concurrently; 0 disables sharding
+    #[serde(default)]
+    pub shards: u32,
+    /// Shards this worker consumes; empty consumes all of them
+    #[serde(default)]
+    pub worker_shards: Vec<u32>,
+    /// Queue high and low priority jobs on their own subjects, which workers consume
+    /// ahead of (high) or after (low) normal priority jobs
+    #[serde(default)]
+    pub priority_subjects: bool,
+}
+
+/// Compression of published job messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageCompression {
+    #[default]
+    None,
+    Zstd,
+}
+
+fn default_nats_max_message_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_nats_compression_min_bytes() -> usize {
+    1024
+}
+
+/// Retention limits of a JetStream job stream
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamLimits {
+    #[serde(default = "default_stream_max_messages")]
+    pub max_messages: i64,
+    /// -1 for no byte limit
+    #[serde(default = "default_stream_max_bytes")]
+    pub max_bytes: i64,
+    #[serde(default = "default_stream_max_age_seconds")]
+    pub max_age_seconds: u64,
+}
+
+fn default_stream_max_messages() -> i64 {
+    1_000_000
+}
+
+fn default_stream_max_bytes() -> i64 {
+    -1
+}
+
+fn default_stream_max_age_seconds() -> u64 {
+    86400
+}
+
+impl Default for StreamLimits {
+    fn default() -> Self {
+        Self {
+            max_messages: default_stream_max_messages(),
+            max_bytes: default_stream_max_bytes(),
+            max_age_seconds: default_stream_max_age_seconds(),
+        }
+    }
+}
+
+/// TLS settings for the NATS connection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NatsTlsConfig {
+    /// Refuse to connect without TLS (implied by a `tls://` URL)
+    #[serde(default)]
+    pub required: bool,
+    /// PEM file with the CA certificates used to verify the server
+    pub ca_file: Option<String>,
+    /// PEM client certificate for mutual TLS; requires `client_key_file`
+    pub client_cert_file: Option<String>,
+    /// PEM private key matching `client_cert_file`
+    pub client_key_file: Option<String>,
+}
+
+/// Credentials for the NATS connection; at most one method may be configured
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NatsAuthConfig {
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+    /// NKey seed (starts with `SU`)
+    pub nkey_seed: Option<String>,
+    /// `.creds` file holding a user JWT and NKey seed
+    pub credentials_file: Option<String>,
+}
+
+impl NatsAuthConfig {
+    /// Names of the configured authentication methods
+    pub fn configured_methods(&self) -> Vec<&'static str> {
+        [
+            (
+                "user/password",
+                self.user.is_some() || self.password.is_some(),
+            ),
+            ("token", self.token.is_some()),
+            ("nkey_seed", self.nkey_seed.is_some()),
+            ("credentials_file", self.credentials_file.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, configured)| configured.then_some(name))
+        .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageConfig {
     #[serde(default = "default_file_base_path")]
     pub file_base_path: String,
+    /// Tenants whose execution contexts and files must stay in a given place, e.g.
+    /// in-country storage for financial data; other tenants use `file_base_path`
+    #[serde(default)]
+    pub residency: BTreeMap<String, DataResidency>,
+}
+
+/// Where a tenant's execution contexts and files are written
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum DataResidency {
+    /// A local directory, e.g. a volume on in-country storage
+    Filesystem { base_path: String },
+    /// An S3-compatible bucket in the required region
+    S3(ObjectStorageBucket),
 }
 
 fn default_file_base_path() -> String {
     "./data/files".to_string()
 }
 
+/// Placeholder of the shipped configuration for secrets that must be set per
+/// deployment; the services refuse to start with it
+pub const PLACEHOLDER_SECRET: &str = "change-me-in-production";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     pub mode: AuthMode,
+    /// Encrypts stored secrets such as the token signing keys; tokens themselves are
+    /// signed with rotating keys published at `/.well-known/jwks.json`
     pub jwt_secret: String,
     pub jwt_expiration_hours: u64,
+    /// Days a signing key is used before a new one replaces it
+    #[serde(default = "default_jwt_key_rotation_days")]
+    pub jwt_key_rotation_days: u64,
+    /// Serve the dashboard read-only to visitors without a token, e.g. NOC
+    /// wallboards; they get a guest identity limited to pages without secrets
+    #[serde(default)]
+    pub guest_access: bool,
     pub keycloak: Option<KeycloakConfig>,
 }
 
@@ -82,6 +315,42 @@ pub struct KeycloakConfig {
 pub struct SchedulerConfig {
     pub poll_interval_seconds: u64,
     pub lock_ttl_seconds: u64,
+    /// Largest tolerated difference between this node's clock and Postgres or NTP;
+    /// the scheduler refuses to schedule above it
+    #[serde(default = "default_max_clock_skew_ms")]
+    pub max_clock_skew_ms: u64,
+    #[serde(default = "default_clock_check_interval_seconds")]
+    pub clock_check_interval_seconds: u64,
+    /// NTP server (`host:port`) checked in addition to Postgres
+    #[serde(default)]
+    pub ntp_server: Option<String>,
+    /// Ticks kept in the scheduler decision journal; 0 disables the journal
+    #[serde(default = "default_journal_max_ticks")]
+    pub journal_max_ticks: u64,
+    /// Runs due longer ago than this when the scheduler finds them were missed and
+    /// follow their schedule's misfire policy
+    #[serde(default = "default_misfire_threshold_seconds")]
+    pub misfire_threshold_seconds: u64,
+}
+
+fn default_jwt_key_rotation_days() -> u64 {
+    30
+}
+
+fn default_max_clock_skew_ms() -> u64 {
+    1000
+}
+
+fn default_clock_check_interval_seconds() -> u64 {
+    60
+}
+
+fn default_journal_max_ticks() -> u64 {
+    100_000
+}
+
+fn default_misfire_threshold_seconds() -> u64 {
+    60
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,6 +358,88 @@ pub struct WorkerConfig {
     pub concurrency: u32,
     pub max_retries: u32,
     pub timeout_seconds: u64,
+    /// How often a running step reports progress; executions that miss several
+    /// heartbeats show as hung on the dashboard
+    #[serde(default = "default_heartbeat_interval_seconds")]
+    pub heartbeat_interval_seconds: u64,
+    /// Estimated memory an execution may use before it is stopped; unset only records it
+    #[serde(default)]
+    pub execution_max_memory_mb: Option<u64>,
+    /// CPU time an execution may use before it is stopped; unset only records it
+    #[serde(default)]
+    pub execution_max_cpu_seconds: Option<u64>,
+    /// How often the worker samples its memory to enforce the limits above
+    #[serde(default = "default_resource_sample_interval_ms")]
+    pub resource_sample_interval_ms: u64,
+    /// Parsed job definitions kept in memory; 0 loads every definition from storage
+    #[serde(default = "default_job_cache_max_entries")]
+    pub job_cache_max_entries: usize,
+    /// Step retries backing off at least this long are re-queued instead of waited out
+    /// in the worker; 0 always waits in the worker
+    #[serde(default = "default_deferred_retry_min_delay_seconds")]
+    pub deferred_retry_min_delay_seconds: u64,
+    /// How often a run queued behind a previous run of its job (`queue_after` and
+    /// `cancel_previous` collision policies, or `strict_ordering`) checks whether it
+    /// may start
+    #[serde(default = "default_collision_recheck_seconds")]
+    pub collision_recheck_seconds: u64,
+    /// An earlier run of a `strict_ordering` job with no heartbeat, start or creation
+    /// for this long is taken as lost and no longer holds back the runs after it
+    #[serde(default = "default_sequence_stale_after_seconds")]
+    pub sequence_stale_after_seconds: u64,
+    /// Most fuel (instructions) a WebAssembly step runs with; steps asking for more
+    /// get this much
+    #[serde(default = "default_wasm_max_fuel")]
+    pub wasm_max_fuel: u64,
+    /// Most memory a WebAssembly step runs with; steps asking for more get this much
+    #[serde(default = "default_wasm_max_memory_mb")]
+    pub wasm_max_memory_mb: u64,
+    /// Largest operation budget of a script step; steps asking for more get this much
+    #[serde(default = "default_script_max_operations")]
+    pub script_max_operations: u64,
+    /// Wall-clock limit of a WebAssembly or script run, whatever its step's timeout
+    #[serde(default = "default_sandbox_max_run_seconds")]
+    pub sandbox_max_run_seconds: u64,
+}
+
+fn default_heartbeat_interval_seconds() -> u64 {
+    15
+}
+
+fn default_resource_sample_interval_ms() -> u64 {
+    1000
+}
+
+fn default_job_cache_max_entries() -> usize {
+    1000
+}
+
+fn default_deferred_retry_min_delay_seconds() -> u64 {
+    60
+}
+
+fn default_collision_recheck_seconds() -> u64 {
+    15
+}
+
+fn default_sequence_stale_after_seconds() -> u64 {
+    86400
+}
+
+fn default_wasm_max_fuel() -> u64 {
+    10_000_000_000
+}
+
+fn default_wasm_max_memory_mb() -> u64 {
+    256
+}
+
+fn default_script_max_operations() -> u64 {
+    1_000_000_000
+}
+
+fn default_sandbox_max_run_seconds() -> u64 {
+    300
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +449,388 @@ pub struct ObservabilityConfig {
     pub tracing_endpoint: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct I18nConfig {
+    /// Locale used when neither the user nor the request specifies one
+    #[serde(default = "default_locale")]
+    pub default_locale: String,
+}
+
+fn default_locale() -> String {
+    "vi-VN".to_string()
+}
+
+impl I18nConfig {
+    /// The default locale, or the catalog default if it isn't supported
+    pub fn locale(&self) -> Locale {
+        self.default_locale.parse().unwrap_or_default()
+    }
+}
+
+impl Default for I18nConfig {
+    fn default() -> Self {
+        Self {
+            default_locale: default_locale(),
+        }
+    }
+}
+
+/// API rate limiting applied per user, API key and tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    /// Default sustained rate for identities without an admin-configured policy
+    #[serde(default = "default_requests_per_minute")]
+    pub requests_per_minute: u32,
+    /// Default token bucket capacity
+    #[serde(default = "default_burst")]
+    pub burst: u32,
+}
+
+fn default_rate_limit_enabled() -> bool {
+    true
+}
+
+fn default_requests_per_minute() -> u32 {
+    600
+}
+
+fn default_burst() -> u32 {
+    100
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_rate_limit_enabled(),
+            requests_per_minute: default_requests_per_minute(),
+            burst: default_burst(),
+        }
+    }
+}
+
+/// Replay window for `Idempotency-Key` request headers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyConfig {
+    /// How long a stored response is replayed for the same key
+    #[serde(default = "default_idempotency_ttl_seconds")]
+    pub ttl_seconds: u64,
+}
+
+fn default_idempotency_ttl_seconds() -> u64 {
+    24 * 60 * 60
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: default_idempotency_ttl_seconds(),
+        }
+    }
+}
+
+/// Background maintenance of the execution statistics rollup tables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsConfig {
+    /// How often the hourly/daily rollups are refreshed
+    #[serde(default = "default_rollup_interval_seconds")]
+    pub rollup_interval_seconds: u64,
+    /// How far back each refresh recomputes buckets (covers long-running executions)
+    #[serde(default = "default_rollup_lookback_hours")]
+    pub rollup_lookback_hours: u32,
+}
+
+fn default_rollup_interval_seconds() -> u64 {
+    300
+}
+
+fn default_rollup_lookback_hours() -> u32 {
+    48
+}
+
+impl Default for StatsConfig {
+    fn default() -> Self {
+        Self {
+            rollup_interval_seconds: default_rollup_interval_seconds(),
+            rollup_lookback_hours: default_rollup_lookback_hours(),
+        }
+    }
+}
+
+/// Outbound webhooks delivered by the worker: completion callbacks and notifications
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallbackConfig {
+    /// HMAC-SHA256 key used to sign callback bodies
+    #[serde(default = "default_callback_signing_secret")]
+    pub signing_secret: String,
+    /// Per-attempt request timeout
+    #[serde(default = "default_callback_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// Total delivery attempts before the callback is dropped
+    #[serde(default = "default_callback_max_attempts")]
+    pub max_attempts: u32,
+    /// First retry delay; later retries back off exponentially
+    #[serde(default = "default_callback_retry_base_delay_seconds")]
+    pub retry_base_delay_seconds: u64,
+    /// How often workers look for deliveries that are due
+    #[serde(default = "default_callback_poll_interval_seconds")]
+    pub poll_interval_seconds: u64,
+    /// Endpoints sent failure alerts and sunset notices as signed webhooks
+    #[serde(default)]
+    pub notification_urls: Vec<String>,
+}
+
+fn default_callback_signing_secret() -> String {
+    PLACEHOLDER_SECRET.to_string()
+}
+
+fn default_callback_timeout_seconds() -> u64 {
+    10
+}
+
+fn default_callback_max_attempts() -> u32 {
+    5
+}
+
+fn default_callback_retry_base_delay_seconds() -> u64 {
+    5
+}
+
+fn default_callback_poll_interval_seconds() -> u64 {
+    5
+}
+
+impl Default for CallbackConfig {
+    fn default() -> Self {
+        Self {
+            signing_secret: default_callback_signing_secret(),
+            timeout_seconds: default_callback_timeout_seconds(),
+            max_attempts: default_callback_max_attempts(),
+            retry_base_delay_seconds: default_callback_retry_base_delay_seconds(),
+            poll_interval_seconds: default_callback_poll_interval_seconds(),
+            notification_urls: Vec::new(),
+        }
+    }
+}
+
+/// Connection reuse for HTTP steps, one keep-alive client per target host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpClientConfig {
+    /// Whole-request timeout for HTTP steps
+    #[serde(default = "default_http_timeout_seconds")]
+    pub timeout_seconds: u64,
+    /// How long an unused pooled connection stays open
+    #[serde(default = "default_pool_idle_timeout_seconds")]
+    pub pool_idle_timeout_seconds: u64,
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    #[serde(default = "default_tcp_keepalive_seconds")]
+    pub tcp_keepalive_seconds: u64,
+    /// Hosts with a client kept; past this, the least recently used host's client is
+    /// dropped
+    #[serde(default = "default_http_max_hosts")]
+    pub max_hosts: usize,
+    /// URLs whose hosts get a connection at startup, refreshed so it never idles out
+    #[serde(default)]
+    pub prewarm_urls: Vec<String>,
+    /// How often pre-warmed hosts are touched; must be below the idle timeout
+    #[serde(default = "default_keep_warm_interval_seconds")]
+    pub keep_warm_interval_seconds: u64,
+}
+
+fn default_http_timeout_seconds() -> u64 {
+    30
+}
+
+fn default_pool_idle_timeout_seconds() -> u64 {
+    300
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    16
+}
+
+fn default_tcp_keepalive_seconds() -> u64 {
+    30
+}
+
+fn default_http_max_hosts() -> usize {
+    512
+}
+
+fn default_keep_warm_interval_seconds() -> u64 {
+    60
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout_seconds: default_http_timeout_seconds(),
+            pool_idle_timeout_seconds: default_pool_idle_timeout_seconds(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            tcp_keepalive_seconds: default_tcp_keepalive_seconds(),
+            max_hosts: default_http_max_hosts(),
+            prewarm_urls: Vec::new(),
+            keep_warm_interval_seconds: default_keep_warm_interval_seconds(),
+        }
+    }
+}
+
+/// Feature gating for phased rollouts
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeaturesConfig {
+    /// Step types (e.g. `sftp`) a tenant may only use once the `step_type.<name>`
+    /// feature flag is enabled for it or globally
+    #[serde(default)]
+    pub experimental_step_types: Vec<String>,
+}
+
+/// Chaos testing: inject faults to exercise retries and idempotency.
+/// Rates are probabilities between 0.0 and 1.0. Never enable in production.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FaultInjectionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Share of step attempts delayed by a random latency up to `step_latency_max_ms`
+    #[serde(default)]
+    pub step_latency_rate: f64,
+    #[serde(default)]
+    pub step_latency_max_ms: u64,
+    /// Share of step attempts failed before the executor runs
+    #[serde(default)]
+    pub step_failure_rate: f64,
+    /// Share of distributed lock acquisitions failed
+    #[serde(default)]
+    pub lock_failure_rate: f64,
+    /// Share of successfully processed messages NAKed so NATS redelivers them
+    #[serde(default)]
+    pub redelivery_rate: f64,
+}
+
+/// Active-passive multi-region failover
+///
+/// Each region keeps its role in its own Redis. A standby region runs scheduler and
+/// workers that consume nothing until the region is promoted through the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailoverConfig {
+    /// Name of this region, e.g. `ap-southeast-1`
+    #[serde(default = "default_failover_region")]
+    pub region: String,
+    /// Role used until the region is promoted or demoted through the API
+    #[serde(default)]
+    pub standby: bool,
+    /// How often components re-read the role and report readiness; bounds how long a
+    /// promotion takes to resume scheduling
+    #[serde(default = "default_role_check_interval_seconds")]
+    pub role_check_interval_seconds: u64,
+    /// Database replication lag above which a standby component reports not ready
+    #[serde(default = "default_max_replication_lag_seconds")]
+    pub max_replication_lag_seconds: u64,
+}
+
+fn default_failover_region() -> String {
+    "default".to_string()
+}
+
+fn default_role_check_interval_seconds() -> u64 {
+    5
+}
+
+fn default_max_replication_lag_seconds() -> u64 {
+    60
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            region: default_failover_region(),
+            standby: false,
+            role_check_interval_seconds: default_role_check_interval_seconds(),
+            max_replication_lag_seconds: default_max_replication_lag_seconds(),
+        }
+    }
+}
+
+/// Built-in self-monitoring jobs, created by the scheduler on first boot
+///
+/// The values seed the jobs when they are registered; afterwards the jobs are edited
+/// like any other job and these settings no longer apply to them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemJobsConfig {
+    /// Register the built-in jobs that were never registered before
+    #[serde(default = "default_system_jobs_enabled")]
+    pub enabled: bool,
+    /// Finished executions and delivered webhooks older than this are deleted
+    #[serde(default = "default_system_retention_days")]
+    pub retention_days: u32,
+    /// Outputs of steps tagged `pii` are purged this long after the execution finished
+    #[serde(default = "default_pii_retention_days")]
+    pub pii_retention_days: u32,
+    /// age public keys the purged outputs are encrypted to; empty scrubs them
+    #[serde(default)]
+    pub pii_recipients: Vec<String>,
+    /// PEM certificates checked for expiry, read on the worker
+    #[serde(default)]
+    pub certificate_paths: Vec<String>,
+    /// The expiry check warns this many days before a credential expires
+    #[serde(default = "default_expiry_warn_days")]
+    pub expiry_warn_days: u32,
+    /// Sensitive variables unchanged for longer than this are due for rotation
+    #[serde(default = "default_secret_max_age_days")]
+    pub secret_max_age_days: u32,
+    /// How often the canary job runs
+    #[serde(default = "default_canary_interval_seconds")]
+    pub canary_interval_seconds: u32,
+    /// The scheduler alerts once the canary hasn't succeeded for this long
+    #[serde(default = "default_canary_max_silence_seconds")]
+    pub canary_max_silence_seconds: u64,
+}
+
+fn default_system_jobs_enabled() -> bool {
+    true
+}
+
+fn default_system_retention_days() -> u32 {
+    30
+}
+
+fn default_pii_retention_days() -> u32 {
+    7
+}
+
+fn default_expiry_warn_days() -> u32 {
+    14
+}
+
+fn default_secret_max_age_days() -> u32 {
+    90
+}
+
+fn default_canary_interval_seconds() -> u32 {
+    60
+}
+
+fn default_canary_max_silence_seconds() -> u64 {
+    300
+}
+
+impl Default for SystemJobsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_system_jobs_enabled(),
+            retention_days: default_system_retention_days(),
+            pii_retention_days: default_pii_retention_days(),
+            pii_recipients: Vec::new(),
+            certificate_paths: Vec::new(),
+            expiry_warn_days: default_expiry_warn_days(),
+            secret_max_age_days: default_secret_max_age_days(),
+            canary_interval_seconds: default_canary_interval_seconds(),
+            canary_max_silence_seconds: default_canary_max_silence_seconds(),
+        }
+    }
+}
+
 impl Settings {
     /// Load configuration with layered precedence: defaults → file → env
     /// Requirements: 7.5 - Configuration hot reload support
@@ -140,11 +873,54 @@ impl Settings {
         if self.database.max_connections == 0 {
             return Err("Database max_connections must be greater than 0".to_string());
         }
+        if let Some(ssl_mode) = &self.database.ssl_mode {
+            if ssl_mode.parse::<sqlx::postgres::PgSslMode>().is_err() {
+                return Err(format!(
+                    "Database ssl_mode '{}' must be one of: disable, allow, prefer, require, verify-ca, verify-full",
+                    ssl_mode
+                ));
+            }
+        }
+        if self.database.statement_timeout_seconds == Some(0) {
+            return Err("Database statement_timeout_seconds must be greater than 0".to_string());
+        }
+        if let Some(replica) = &self.database.read_replica {
+            if replica.url.is_empty() {
+                return Err("Database read_replica.url cannot be empty".to_string());
+            }
+            if replica.max_connections == 0 {
+                return Err(
+                    "Database read_replica.max_connections must be greater than 0".to_string(),
+                );
+            }
+            if replica.statement_timeout_seconds == Some(0) {
+                return Err(
+                    "Database read_replica.statement_timeout_seconds must be greater than 0"
+                        .to_string(),
+                );
+            }
+        }
 
         // Validate Redis config
         if self.redis.url.is_empty() {
             return Err("Redis URL cannot be empty".to_string());
         }
+        if self.redis.sentinel.is_some() && self.redis.cluster.is_some() {
+            return Err("Redis sentinel and cluster cannot both be configured".to_string());
+        }
+        if let Some(sentinel) = &self.redis.sentinel {
+            if sentinel.master_name.is_empty() {
+                return Err("Redis sentinel.master_name cannot be empty".to_string());
+            }
+            if sentinel.nodes.is_empty() {
+                return Err("Redis sentinel.nodes cannot be empty".to_string());
+            }
+        }
+        if let Some(cluster) = &self.redis.cluster {
+            if cluster.nodes.is_empty() {
+                return Err("Redis cluster.nodes cannot be empty".to_string());
+            }
+        }
 
         // Validate NATS config
         if self.nats.url.is_empty() {
@@ -153,16 +929,90 @@ impl Settings {
         if self.nats.stream_name.is_empty() {
             return Err("NATS stream_name cannot be empty".to_string());
         }
+        if self.nats.tls.client_cert_file.is_some() != self.nats.tls.client_key_file.is_some() {
+            return Err(
+                "NATS tls.client_cert_file and tls.client_key_file must be set together"
+                    .to_string(),
+            );
+        }
+        let auth_methods = self.nats.auth.configured_methods();
+        if auth_methods.len() > 1 {
+            return Err(format!(
+                "Only one NATS auth method may be configured, found: {}",
+                auth_methods.join(", ")
+            ));
+        }
+        if self.nats.auth.user.is_some() != self.nats.auth.password.is_some() {
+            return Err("NATS auth.user and auth.password must be set together".to_string());
+        }
+        if let Some(tenant) = self.nats.tenant_streams.keys().find(|tenant| {
+            tenant.is_empty()
+                || !tenant
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        }) {
+            return Err(format!(
+                "NATS tenant_streams key '{}' may only contain letters, digits, '-' and '_'",
+                tenant
+            ));
+        }
+        if let Some(shard) = self
+            .nats
+            .worker_shards
+            .iter()
+            .find(|shard| **shard >= self.nats.shards)
+        {
+            return Err(format!(
+                "NATS worker_shards entry {} must be less than shards ({})",
+                shard, self.nats.shards
+            ));
+        }
+        for (name, limits) in std::iter::once(("limits", &self.nats.limits)).chain(
+            self.nats
+                .tenant_streams
+                .values()
+                .map(|l| ("tenant_streams", l)),
+        ) {
+            if limits.max_messages <= 0
+                || limits.max_age_seconds == 0
+                || !(limits.max_bytes == -1 || limits.max_bytes > 0)
+            {
+                return Err(format!(
+                    "NATS {} max_messages and max_age_seconds must be greater than 0 and max_bytes -1 or greater than 0",
+                    name
+                ));
+            }
+        }
 
         // Validate storage config
         if self.storage.file_base_path.is_empty() {
             return Err("Storage file_base_path cannot be empty".to_string());
         }
+        for (tenant, residency) in &self.storage.residency {
+            let location = match residency {
+                DataResidency::Filesystem { base_path } => base_path,
+                DataResidency::S3(bucket) => &bucket.name,
+            };
+            if location.is_empty() {
+                return Err(format!(
+                    "Storage residency of tenant '{}' needs a base_path or bucket name",
+                    tenant
+                ));
+            }
+        }
 
         // Validate auth config
         if self.auth.jwt_secret.is_empty() {
             return Err("JWT secret cannot be empty".to_string());
         }
+        if self.auth.jwt_secret == PLACEHOLDER_SECRET {
+            return Err(
+                "JWT secret is still the shipped placeholder; set auth.jwt_secret".to_string(),
+            );
+        }
+        if self.auth.jwt_key_rotation_days == 0 {
+            return Err("Auth jwt_key_rotation_days must be greater than 0".to_string());
+        }
         if matches!(self.auth.mode, AuthMode::Keycloak) && self.auth.keycloak.is_none() {
             return Err("Keycloak configuration required when auth mode is 'keycloak'".to_string());
         }
@@ -171,11 +1021,191 @@ impl Settings {
         if self.scheduler.poll_interval_seconds == 0 {
             return Err("Scheduler poll_interval_seconds must be greater than 0".to_string());
         }
+        if self.scheduler.max_clock_skew_ms == 0 || self.scheduler.clock_check_interval_seconds == 0
+        {
+            return Err(
+                "Scheduler max_clock_skew_ms and clock_check_interval_seconds must be greater than 0"
+                    .to_string(),
+            );
+        }
 
         // Validate worker config
         if self.worker.concurrency == 0 {
             return Err("Worker concurrency must be greater than 0".to_string());
         }
+        if self.worker.heartbeat_interval_seconds == 0 {
+            return Err("Worker heartbeat_interval_seconds must be greater than 0".to_string());
+        }
+        if self.worker.collision_recheck_seconds == 0 {
+            return Err("Worker collision_recheck_seconds must be greater than 0".to_string());
+        }
+        if self.worker.sequence_stale_after_seconds == 0 {
+            return Err("Worker sequence_stale_after_seconds must be greater than 0".to_string());
+        }
+        if self.worker.resource_sample_interval_ms == 0
+            || self.worker.execution_max_memory_mb == Some(0)
+            || self.worker.execution_max_cpu_seconds == Some(0)
+        {
+            return Err(
+                "Worker resource_sample_interval_ms and execution limits must be greater than 0"
+                    .to_string(),
+            );
+        }
+
+        // Validate i18n config
+        if self
+            .i18n
+            .default_locale
+            .parse::<crate::i18n::Locale>()
+            .is_err()
+        {
+            return Err(format!(
+                "Unsupported i18n default_locale: {}",
+                self.i18n.default_locale
+            ));
+        }
+
+        // Validate rate limit config
+        if self.rate_limit.enabled
+            && (self.rate_limit.requests_per_minute == 0 || self.rate_limit.burst == 0)
+        {
+            return Err(
+                "Rate limit requests_per_minute and burst must be greater than 0".to_string(),
+            );
+        }
+
+        // Validate idempotency config
+        if self.idempotency.ttl_seconds == 0 {
+            return Err("Idempotency ttl_seconds must be greater than 0".to_string());
+        }
+
+        // Validate stats config
+        if self.stats.rollup_interval_seconds == 0 || self.stats.rollup_lookback_hours == 0 {
+            return Err(
+                "Stats rollup_interval_seconds and rollup_lookback_hours must be greater than 0"
+                    .to_string(),
+            );
+        }
+
+        // Validate callback config
+        if self.callbacks.signing_secret.is_empty() {
+            return Err("Callback signing_secret cannot be empty".to_string());
+        }
+        if self.callbacks.signing_secret == PLACEHOLDER_SECRET {
+            return Err(
+                "Callback signing secret is still the shipped placeholder; set callbacks.signing_secret"
+                    .to_string(),
+            );
+        }
+        if self.callbacks.timeout_seconds == 0
+            || self.callbacks.max_attempts == 0
+            || self.callbacks.poll_interval_seconds == 0
+        {
+            return Err(
+                "Callback timeout_seconds, max_attempts and poll_interval_seconds must be greater than 0"
+                    .to_string(),
+            );
+        }
+        for url in &self.callbacks.notification_urls {
+            let valid = reqwest::Url::parse(url)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+            if !valid {
+                return Err(format!(
+                    "Callback notification_urls must be http(s) URLs: {}",
+                    url
+                ));
+            }
+        }
+
+        // Validate HTTP client config
+        let http_client = &self.http_client;
+        if http_client.timeout_seconds == 0
+            || http_client.pool_idle_timeout_seconds == 0
+            || http_client.tcp_keepalive_seconds == 0
+        {
+            return Err(
+                "HTTP client timeout_seconds, pool_idle_timeout_seconds and tcp_keepalive_seconds must be greater than 0"
+                    .to_string(),
+            );
+        }
+        if http_client.keep_warm_interval_seconds == 0
+            || http_client.keep_warm_interval_seconds >= http_client.pool_idle_timeout_seconds
+        {
+            return Err(
+                "HTTP client keep_warm_interval_seconds must be greater than 0 and below pool_idle_timeout_seconds"
+                    .to_string(),
+            );
+        }
+        if let Some(url) = http_client
+            .prewarm_urls
+            .iter()
+            .find(|url| crate::executor::http_pool::host_key(url).is_none())
+        {
+            return Err(format!("HTTP client prewarm URL '{}' is invalid", url));
+        }
+
+        // Validate fault injection config
+        let fault_injection = &self.fault_injection;
+        for (name, rate) in [
+            ("step_latency_rate", fault_injection.step_latency_rate),
+            ("step_failure_rate", fault_injection.step_failure_rate),
+            ("lock_failure_rate", fault_injection.lock_failure_rate),
+            ("redelivery_rate", fault_injection.redelivery_rate),
+        ] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(format!(
+                    "Fault injection {} must be between 0.0 and 1.0",
+                    name
+                ));
+            }
+        }
+        if fault_injection.step_latency_rate > 0.0 && fault_injection.step_latency_max_ms == 0 {
+            return Err(
+                "Fault injection step_latency_max_ms must be greater than 0 when step_latency_rate is set"
+                    .to_string(),
+            );
+        }
+
+        // Validate failover config
+        if self.failover.region.is_empty() {
+            return Err("Failover region cannot be empty".to_string());
+        }
+        if self.failover.role_check_interval_seconds == 0 {
+            return Err("Failover role_check_interval_seconds must be greater than 0".to_string());
+        }
+
+        // Validate system jobs config
+        if self.system_jobs.retention_days == 0 {
+            return Err("System jobs retention_days must be greater than 0".to_string());
+        }
+        if self.system_jobs.pii_retention_days == 0 {
+            return Err("System jobs pii_retention_days must be greater than 0".to_string());
+        }
+        if self.system_jobs.canary_interval_seconds == 0 {
+            return Err("System jobs canary_interval_seconds must be greater than 0".to_string());
+        }
+        if self.system_jobs.canary_max_silence_seconds
+            <= self.system_jobs.canary_interval_seconds as u64
+        {
+            return Err(
+                "System jobs canary_max_silence_seconds must be greater than canary_interval_seconds"
+                    .to_string(),
+            );
+        }
+
+        // Validate feature config
+        if let Some(unknown) = self
+            .features
+            .experimental_step_types
+            .iter()
+            .find(|name| !crate::capabilities::STEP_TYPES.contains(&name.as_str()))
+        {
+            return Err(format!(
+                "Unknown experimental step type '{}', expected one of: {}",
+                unknown,
+                crate::capabilities::STEP_TYPES.join(", ")
+            ));
+        }
 
         Ok(())
     }
@@ -193,39 +1223,88 @@ impl Default for Settings {
                 max_connections: 10,
                 min_connections: 2,
                 connect_timeout_seconds: 30,
+                ssl_mode: None,
+                ssl_root_cert: None,
+                statement_timeout_seconds: None,
+                read_replica: None,
             },
             redis: RedisConfig {
                 url: "redis://localhost:6379".to_string(),
                 pool_size: 10,
+                sentinel: None,
+                cluster: None,
             },
             nats: NatsConfig {
                 url: "nats://localhost:4222".to_string(),
                 stream_name: "job_stream".to_string(),
                 consumer_name: "job_consumer".to_string(),
+                tls: NatsTlsConfig::default(),
+                auth: NatsAuthConfig::default(),
+                limits: StreamLimits::default(),
+                tenant_streams: BTreeMap::new(),
+                max_message_bytes: default_nats_max_message_bytes(),
+                compression: MessageCompression::None,
+                compression_min_bytes: default_nats_compression_min_bytes(),
+                shards: 0,
+                worker_shards: Vec::new(),
+                priority_subjects: false,
             },
             storage: StorageConfig {
                 file_base_path: "./data/files".to_string(),
+                residency: BTreeMap::new(),
             },
             auth: AuthConfig {
                 mode: AuthMode::Database,
-                jwt_secret: "change-me-in-production".to_string(),
+                jwt_secret: "development-only-jwt-secret".to_string(),
                 jwt_expiration_hours: 24,
+                jwt_key_rotation_days: 30,
+                guest_access: false,
                 keycloak: None,
             },
             scheduler: SchedulerConfig {
                 poll_interval_seconds: 10,
                 lock_ttl_seconds: 30,
+                max_clock_skew_ms: default_max_clock_skew_ms(),
+                clock_check_interval_seconds: default_clock_check_interval_seconds(),
+                journal_max_ticks: default_journal_max_ticks(),
+                misfire_threshold_seconds: default_misfire_threshold_seconds(),
+                ntp_server: None,
             },
             worker: WorkerConfig {
                 concurrency: 10,
                 max_retries: 10,
                 timeout_seconds: 300,
+                heartbeat_interval_seconds: default_heartbeat_interval_seconds(),
+                execution_max_memory_mb: None,
+                execution_max_cpu_seconds: None,
+                resource_sample_interval_ms: default_resource_sample_interval_ms(),
+                job_cache_max_entries: default_job_cache_max_entries(),
+                deferred_retry_min_delay_seconds: default_deferred_retry_min_delay_seconds(),
+                collision_recheck_seconds: default_collision_recheck_seconds(),
+                sequence_stale_after_seconds: default_sequence_stale_after_seconds(),
+                wasm_max_fuel: default_wasm_max_fuel(),
+                wasm_max_memory_mb: default_wasm_max_memory_mb(),
+                script_max_operations: default_script_max_operations(),
+                sandbox_max_run_seconds: default_sandbox_max_run_seconds(),
             },
             observability: ObservabilityConfig {
                 log_level: "info".to_string(),
                 metrics_port: 9090,
                 tracing_endpoint: None,
             },
+            i18n: I18nConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            idempotency: IdempotencyConfig::default(),
+            stats: StatsConfig::default(),
+            callbacks: CallbackConfig {
+                signing_secret: "development-only-callback-secret".to_string(),
+                ..CallbackConfig::default()
+            },
+            http_client: HttpClientConfig::default(),
+            features: FeaturesConfig::default(),
+            fault_injection: FaultInjectionConfig::default(),
+            failover: FailoverConfig::default(),
+            system_jobs: SystemJobsConfig::default(),
         }
     }
 }
@@ -254,6 +1333,37 @@ mod tests {
         assert!(settings.validate().is_err());
     }
 
+    #[test]
+    fn test_validation_catches_unsupported_default_locale() {
+        let mut settings = Settings::default();
+        settings.i18n.default_locale = "fr-FR".to_string();
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_zero_rate_limit() {
+        let mut settings = Settings::default();
+        settings.rate_limit.requests_per_minute = 0;
+        assert!(settings.validate().is_err());
+
+        settings.rate_limit.enabled = false;
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation_catches_placeholder_jwt_secret() {
+        let mut settings = Settings::default();
+        settings.auth.jwt_secret = PLACEHOLDER_SECRET.to_string();
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_placeholder_callback_secret() {
+        let mut settings = Settings::default();
+        settings.callbacks.signing_secret = PLACEHOLDER_SECRET.to_string();
+        assert!(settings.validate().is_err());
+    }
+
     #[test]
     fn test_validation_catches_keycloak_mode_without_config() {
         let mut settings = Settings::default();
@@ -261,4 +1371,124 @@ mod tests {
         settings.auth.keycloak = None;
         assert!(settings.validate().is_err());
     }
+
+    #[test]
+    fn test_validation_catches_conflicting_nats_auth() {
+        let mut settings = Settings::default();
+        settings.nats.auth.token = Some("s3cr3t".to_string());
+        assert!(settings.validate().is_ok());
+
+        settings.nats.auth.credentials_file = Some("/etc/nats/worker.creds".to_string());
+        assert!(settings.validate().is_err());
+
+        settings.nats.auth = NatsAuthConfig {
+            user: Some("worker".to_string()),
+            ..NatsAuthConfig::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_client_cert_without_key() {
+        let mut settings = Settings::default();
+        settings.nats.tls.client_cert_file = Some("/etc/nats/client.pem".to_string());
+        assert!(settings.validate().is_err());
+
+        settings.nats.tls.client_key_file = Some("/etc/nats/client-key.pem".to_string());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validation_catches_bad_database_tls_and_replica_settings() {
+        let mut settings = Settings::default();
+        settings.database.ssl_mode = Some("verify-full".to_string());
+        assert!(settings.validate().is_ok());
+
+        settings.database.ssl_mode = Some("strict".to_string());
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.database.read_replica = Some(ReadReplicaConfig {
+            url: String::new(),
+            max_connections: 5,
+            min_connections: 1,
+            statement_timeout_seconds: Some(60),
+        });
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_bad_redis_topology() {
+        let mut settings = Settings::default();
+        settings.redis.sentinel = Some(RedisSentinelConfig {
+            master_name: "cron-master".to_string(),
+            nodes: vec!["redis://sentinel-1:26379".to_string()],
+        });
+        assert!(settings.validate().is_ok());
+
+        settings.redis.cluster = Some(RedisClusterConfig {
+            nodes: vec!["redis://node-1:6379".to_string()],
+        });
+        assert!(settings.validate().is_err());
+
+        settings.redis.sentinel = None;
+        assert!(settings.validate().is_ok());
+
+        settings.redis.cluster = Some(RedisClusterConfig { nodes: Vec::new() });
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_unknown_experimental_step_type() {
+        let mut settings = Settings::default();
+        settings.features.experimental_step_types = vec!["sftp".to_string()];
+        assert!(settings.validate().is_ok());
+
+        settings.features.experimental_step_types = vec!["ftp".to_string()];
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_residency_without_location() {
+        let mut settings = Settings::default();
+        let residency: DataResidency = serde_json::from_value(serde_json::json!({
+            "backend": "s3",
+            "name": "bank-vn",
+            "region": "vn-south-1",
+            "access_key_id": "key",
+            "secret_access_key": "secret"
+        }))
+        .unwrap();
+        settings
+            .storage
+            .residency
+            .insert("bank-vn".to_string(), residency);
+        assert!(settings.validate().is_ok());
+
+        settings.storage.residency.insert(
+            "other".to_string(),
+            DataResidency::Filesystem {
+                base_path: String::new(),
+            },
+        );
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_validation_catches_bad_fault_injection_rates() {
+        let mut settings = Settings::default();
+        settings.fault_injection.enabled = true;
+        settings.fault_injection.step_failure_rate = 0.2;
+        assert!(settings.validate().is_ok());
+
+        settings.fault_injection.redelivery_rate = 1.5;
+        assert!(settings.validate().is_err());
+
+        settings.fault_injection.redelivery_rate = 0.0;
+        settings.fault_injection.step_latency_rate = 0.5;
+        assert!(settings.validate().is_err());
+
+        settings.fault_injection.step_latency_max_ms = 2_000;
+        assert!(settings.validate().is_ok());
+    }
 }
@@ -0,0 +1,185 @@
+// Per-user dashboard layout
+// Purpose: Let each user choose what the dashboard landing page shows (pinned jobs,
+// stat cards, saved execution filters) instead of one default view for every team
+//
+// Layouts are keyed by the token subject rather than a local user id, so users
+// authenticated through Keycloak keep one too. Users without a saved layout get
+// `DashboardLayout::default()`.
+
+use crate::errors::ValidationError;
+use crate::models::ExecutionStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Most jobs a user may pin
+pub const MAX_PINNED_JOBS: usize = 20;
+
+/// Most execution filters a user may save
+pub const MAX_SAVED_FILTERS: usize = 20;
+
+/// Longest accepted saved filter name, in characters
+const MAX_FILTER_NAME_LEN: usize = 64;
+
+/// Trigger sources the executions page filters on
+const TRIGGER_SOURCES: &[&str] = &["scheduled", "manual", "webhook"];
+
+/// Stat card on the dashboard landing page
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum StatCard {
+    TotalJobs,
+    RunningNow,
+    SuccessRate,
+    #[serde(rename = "failed_24h")]
+    Failed24h,
+}
+
+impl StatCard {
+    pub const ALL: [StatCard; 4] = [
+        StatCard::TotalJobs,
+        StatCard::RunningNow,
+        StatCard::SuccessRate,
+        StatCard::Failed24h,
+    ];
+}
+
+/// Named execution filter, applied as the executions page's query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SavedExecutionFilter {
+    pub name: String,
+    #[serde(default)]
+    pub status: Option<ExecutionStatus>,
+    /// `scheduled`, `manual` or `webhook`
+    #[serde(default)]
+    pub trigger_source: Option<String>,
+    #[serde(default)]
+    pub job_id: Option<Uuid>,
+    /// Case-insensitive substring of the job name
+    #[serde(default)]
+    pub job_name: Option<String>,
+}
+
+/// What a user's dashboard landing page shows
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DashboardLayout {
+    /// Jobs listed first, in this order
+    #[serde(default)]
+    pub pinned_jobs: Vec<Uuid>,
+    /// Stat cards shown, in this order
+    #[serde(default = "default_stat_cards")]
+    pub stat_cards: Vec<StatCard>,
+    #[serde(default)]
+    pub saved_filters: Vec<SavedExecutionFilter>,
+}
+
+fn default_stat_cards() -> Vec<StatCard> {
+    StatCard::ALL.to_vec()
+}
+
+impl Default for DashboardLayout {
+    fn default() -> Self {
+        Self {
+            pinned_jobs: Vec::new(),
+            stat_cards: default_stat_cards(),
+            saved_filters: Vec::new(),
+        }
+    }
+}
+
+impl DashboardLayout {
+    /// Validate a layout before it is saved
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let invalid = |field: &str, reason: String| ValidationError::InvalidFieldValue {
+            field: field.to_string(),
+            reason,
+        };
+
+        if self.pinned_jobs.len() > MAX_PINNED_JOBS {
+            return Err(invalid(
+                "pinned_jobs",
+                format!("at most {} jobs can be pinned", MAX_PINNED_JOBS),
+            ));
+        }
+        if self.pinned_jobs.iter().collect::<HashSet<_>>().len() != self.pinned_jobs.len() {
+            return Err(invalid("pinned_jobs", "jobs must be unique".to_string()));
+        }
+        if self.stat_cards.iter().collect::<HashSet<_>>().len() != self.stat_cards.len() {
+            return Err(invalid("stat_cards", "cards must be unique".to_string()));
+        }
+
+        if self.saved_filters.len() > MAX_SAVED_FILTERS {
+            return Err(invalid(
+                "saved_filters",
+                format!("at most {} filters can be saved", MAX_SAVED_FILTERS),
+            ));
+        }
+        let mut names = HashSet::new();
+        for filter in &self.saved_filters {
+            let name = filter.name.trim();
+            if name.is_empty() || name.chars().count() > MAX_FILTER_NAME_LEN {
+                return Err(invalid(
+                    "saved_filters.name",
+                    format!("names must be 1 to {} characters", MAX_FILTER_NAME_LEN),
+                ));
+            }
+            if !names.insert(name.to_lowercase()) {
+                return Err(invalid(
+                    "saved_filters.name",
+                    format!("'{}' is used by more than one filter", name),
+                ));
+            }
+            if let Some(trigger_source) = &filter.trigger_source {
+                if !TRIGGER_SOURCES.contains(&trigger_source.as_str()) {
+                    return Err(invalid(
+                        "saved_filters.trigger_source",
+                        format!("'{}' must be one of {:?}", trigger_source, TRIGGER_SOURCES),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(name: &str) -> SavedExecutionFilter {
+        SavedExecutionFilter {
+            name: name.to_string(),
+            status: Some(ExecutionStatus::Failed),
+            trigger_source: Some("webhook".to_string()),
+            job_id: None,
+            job_name: Some("payroll & tax".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_layout_defaults_and_validation() {
+        let layout: DashboardLayout = serde_json::from_str(r#"{"pinned_jobs": []}"#).unwrap();
+        assert_eq!(layout, DashboardLayout::default());
+        assert!(layout.validate().is_ok());
+
+        let job_id = Uuid::new_v4();
+        let mut layout = DashboardLayout {
+            pinned_jobs: vec![job_id, job_id],
+            ..Default::default()
+        };
+        assert!(layout.validate().is_err());
+
+        layout.pinned_jobs = vec![job_id];
+        layout.saved_filters = vec![filter("Failed webhooks"), filter("failed webhooks")];
+        assert!(layout.validate().is_err());
+
+        layout.saved_filters = vec![SavedExecutionFilter {
+            trigger_source: Some("cron".to_string()),
+            ..filter("Cron")
+        }];
+        assert!(layout.validate().is_err());
+
+        layout.saved_filters = vec![filter("Failed webhooks")];
+        assert!(layout.validate().is_ok());
+    }
+}
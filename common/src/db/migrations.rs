@@ -0,0 +1,203 @@
+// Embedded schema migrations and startup schema check
+// Purpose: Every binary carries the migrations it was built with, applies them with
+// `--migrate`, and refuses to start against a schema it wasn't built for, so API,
+// worker and scheduler versions can't silently drift from the database
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use sqlx::migrate::Migrator;
+use tracing::{info, instrument};
+
+/// Migrations compiled in from the workspace `migrations/` directory
+pub static MIGRATOR: Migrator = sqlx::migrate!("../migrations");
+
+/// Command-line flag that applies pending migrations and exits
+pub const MIGRATE_FLAG: &str = "--migrate";
+
+/// A row of the `_sqlx_migrations` bookkeeping table
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub success: bool,
+    pub checksum: Vec<u8>,
+}
+
+/// Whether the process was started with `--migrate`
+pub fn migrate_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == MIGRATE_FLAG)
+}
+
+/// Latest schema version this binary was built with
+pub fn latest_version() -> Option<i64> {
+    embedded_migrations().last().map(|(version, _)| *version)
+}
+
+fn embedded_migrations() -> Vec<(i64, &'static [u8])> {
+    MIGRATOR
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .map(|m| (m.version, m.checksum.as_ref()))
+        .collect()
+}
+
+/// Apply every pending migration
+#[instrument(skip(pool))]
+pub async fn run_migrations(pool: &DbPool) -> Result<(), DatabaseError> {
+    info!(target_version = ?latest_version(), "Applying database migrations");
+
+    MIGRATOR
+        .run(pool.pool())
+        .await
+        .map_err(|e| DatabaseError::MigrationFailed(e.to_string()))?;
+
+    info!(version = ?latest_version(), "Database migrations applied");
+    Ok(())
+}
+
+/// Verify the database schema matches the migrations embedded in this binary
+///
+/// Fails when migrations are pending, when the database has migrations this binary
+/// doesn't know (a newer release migrated it), when an applied migration was edited,
+/// or when a previous migration run failed part-way.
+#[instrument(skip(pool))]
+pub async fn verify_schema(pool: &DbPool) -> Result<(), DatabaseError> {
+    let table_exists: bool =
+        sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(pool.pool())
+            .await?;
+
+    let applied = if table_exists {
+        sqlx::query_as::<_, AppliedMigration>(
+            "SELECT version, success, checksum FROM _sqlx_migrations ORDER BY version",
+        )
+        .fetch_all(pool.pool())
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    check_compatibility(&embedded_migrations(), &applied)?;
+
+    info!(version = ?latest_version(), "Database schema version verified");
+    Ok(())
+}
+
+fn check_compatibility(
+    embedded: &[(i64, &[u8])],
+    applied: &[AppliedMigration],
+) -> Result<(), DatabaseError> {
+    if let Some(dirty) = applied.iter().find(|m| !m.success) {
+        return Err(DatabaseError::SchemaMismatch(format!(
+            "migration {} failed part-way; repair it manually, then run with {}",
+            dirty.version, MIGRATE_FLAG
+        )));
+    }
+
+    let binary_version = embedded.last().map(|(version, _)| *version);
+    let database_version = applied.last().map(|m| m.version);
+
+    for migration in applied {
+        match embedded
+            .iter()
+            .find(|(version, _)| *version == migration.version)
+        {
+            None => {
+                return Err(DatabaseError::SchemaMismatch(format!(
+                    "database has migration {} which this binary doesn't know (database at {}, binary built for {}); deploy the matching release",
+                    migration.version,
+                    describe_version(database_version),
+                    describe_version(binary_version)
+                )));
+            }
+            Some((_, checksum)) if *checksum != migration.checksum.as_slice() => {
+                return Err(DatabaseError::SchemaMismatch(format!(
+                    "migration {} was modified after it was applied",
+                    migration.version
+                )));
+            }
+            Some(_) => {}
+        }
+    }
+
+    let pending: Vec<String> = embedded
+        .iter()
+        .filter(|(version, _)| !applied.iter().any(|m| m.version == *version))
+        .map(|(version, _)| version.to_string())
+        .collect();
+    if !pending.is_empty() {
+        return Err(DatabaseError::SchemaMismatch(format!(
+            "database at {} but this binary requires {} (pending: {}); run with {} first",
+            describe_version(database_version),
+            describe_version(binary_version),
+            pending.join(", "),
+            MIGRATE_FLAG
+        )));
+    }
+
+    Ok(())
+}
+
+fn describe_version(version: Option<i64>) -> String {
+    version.map_or_else(|| "no migrations".to_string(), |v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMBEDDED: &[(i64, &[u8])] = &[(1, b"one"), (2, b"two"), (3, b"three")];
+
+    fn applied(version: i64, checksum: &[u8]) -> AppliedMigration {
+        AppliedMigration {
+            version,
+            success: true,
+            checksum: checksum.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_embedded_migrations_are_ordered() {
+        let versions: Vec<i64> = embedded_migrations().iter().map(|(v, _)| *v).collect();
+        assert!(!versions.is_empty());
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(latest_version(), versions.last().copied());
+    }
+
+    #[test]
+    fn test_matching_schema_is_compatible() {
+        let current = vec![applied(1, b"one"), applied(2, b"two"), applied(3, b"three")];
+        assert!(check_compatibility(EMBEDDED, &current).is_ok());
+    }
+
+    #[test]
+    fn test_pending_and_newer_schemas_are_rejected() {
+        let behind = vec![applied(1, b"one")];
+        let err = check_compatibility(EMBEDDED, &behind).unwrap_err();
+        assert!(err.to_string().contains("pending: 2, 3"));
+        assert!(err.to_string().contains(MIGRATE_FLAG));
+
+        let err = check_compatibility(EMBEDDED, &[]).unwrap_err();
+        assert!(err.to_string().contains("no migrations"));
+
+        let ahead = vec![
+            applied(1, b"one"),
+            applied(2, b"two"),
+            applied(3, b"three"),
+            applied(4, b"four"),
+        ];
+        let err = check_compatibility(EMBEDDED, &ahead).unwrap_err();
+        assert!(err.to_string().contains("doesn't know"));
+    }
+
+    #[test]
+    fn test_edited_and_dirty_migrations_are_rejected() {
+        let edited = vec![applied(1, b"one"), applied(2, b"TWO"), applied(3, b"three")];
+        let err = check_compatibility(EMBEDDED, &edited).unwrap_err();
+        assert!(err.to_string().contains("modified"));
+
+        let mut dirty = vec![applied(1, b"one"), applied(2, b"two"), applied(3, b"three")];
+        dirty[2].success = false;
+        let err = check_compatibility(EMBEDDED, &dirty).unwrap_err();
+        assert!(err.to_string().contains("failed part-way"));
+    }
+}
@@ -1,9 +1,10 @@
 // Database layer module
 // Requirements: 12.4 - PostgreSQL connection pool with compile-time query checking
 
+pub mod migrations;
 pub mod pool;
 pub mod redis;
 pub mod repositories;
 
 pub use pool::DbPool;
-pub use redis::RedisPool;
+pub use redis::{RedisConnection, RedisPool};
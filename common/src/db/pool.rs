@@ -3,15 +3,42 @@
 
 use crate::config::DatabaseConfig;
 use crate::errors::DatabaseError;
-use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions, PgSslMode};
+use std::str::FromStr;
 use std::time::Duration;
 use tracing::{info, instrument};
 
 /// Database connection pool wrapper
-/// Provides a managed connection pool to PostgreSQL with health checking
+/// Provides a managed connection pool to PostgreSQL with health checking,
+/// plus an optional read replica pool for read-heavy queries
 #[derive(Debug, Clone)]
 pub struct DbPool {
     pool: PgPool,
+    read_pool: Option<PgPool>,
+}
+
+/// Build connect options for `url` with the TLS settings and statement timeout
+fn connect_options(
+    url: &str,
+    config: &DatabaseConfig,
+    statement_timeout_seconds: Option<u64>,
+) -> Result<PgConnectOptions, DatabaseError> {
+    let mut options = PgConnectOptions::from_str(url)
+        .map_err(|e| DatabaseError::ConnectionFailed(format!("Invalid database URL: {}", e)))?;
+
+    if let Some(ssl_mode) = &config.ssl_mode {
+        let ssl_mode = PgSslMode::from_str(ssl_mode)
+            .map_err(|e| DatabaseError::ConnectionFailed(e.to_string()))?;
+        options = options.ssl_mode(ssl_mode);
+    }
+    if let Some(root_cert) = &config.ssl_root_cert {
+        options = options.ssl_root_cert(root_cert);
+    }
+    if let Some(timeout) = statement_timeout_seconds {
+        options = options.options([("statement_timeout", format!("{}s", timeout))]);
+    }
+
+    Ok(options)
 }
 
 impl DbPool {
@@ -34,7 +61,11 @@ impl DbPool {
             .max_connections(config.max_connections)
             .min_connections(config.min_connections)
             .acquire_timeout(Duration::from_secs(config.connect_timeout_seconds))
-            .connect(&config.url)
+            .connect_with(connect_options(
+                &config.url,
+                config,
+                config.statement_timeout_seconds,
+            )?)
             .await
             .map_err(|e| {
                 tracing::error!(error = %e, "Failed to create database pool");
@@ -44,10 +75,53 @@ impl DbPool {
         info!(
             max_connections = config.max_connections,
             min_connections = config.min_connections,
+            ssl_mode = ?config.ssl_mode,
+            statement_timeout_seconds = ?config.statement_timeout_seconds,
             "Database connection pool initialized successfully"
         );
 
-        Ok(Self { pool })
+        let read_pool = match &config.read_replica {
+            Some(replica) => {
+                let read_pool = PgPoolOptions::new()
+                    .max_connections(replica.max_connections)
+                    .min_connections(replica.min_connections)
+                    .acquire_timeout(Duration::from_secs(config.connect_timeout_seconds))
+                    .connect_with(connect_options(
+                        &replica.url,
+                        config,
+                        replica.statement_timeout_seconds,
+                    )?)
+                    .await
+                    .map_err(|e| {
+                        tracing::error!(error = %e, "Failed to create read replica pool");
+                        DatabaseError::ConnectionFailed(format!("Read replica: {}", e))
+                    })?;
+
+                info!(
+                    max_connections = replica.max_connections,
+                    statement_timeout_seconds = ?replica.statement_timeout_seconds,
+                    "Read replica pool initialized successfully"
+                );
+                Some(read_pool)
+            }
+            None => None,
+        };
+
+        Ok(Self { pool, read_pool })
+    }
+
+    /// Pool whose queries fail fast because no database is listening, for tests of
+    /// code that tolerates database errors
+    #[cfg(test)]
+    pub(crate) fn unreachable() -> Self {
+        let pool = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(100))
+            .connect_lazy("postgresql://cron@127.0.0.1:1/unreachable")
+            .expect("static URL is valid");
+        Self {
+            pool,
+            read_pool: None,
+        }
     }
 
     /// Get a reference to the underlying pool
@@ -57,6 +131,13 @@ impl DbPool {
         &self.pool
     }
 
+    /// Pool for read-heavy queries that tolerate replica lag
+    ///
+    /// Returns the read replica pool when one is configured, otherwise the primary.
+    pub fn read_pool(&self) -> &PgPool {
+        self.read_pool.as_ref().unwrap_or(&self.pool)
+    }
+
     /// Perform a health check on the database connection
     ///
     /// # Requirements
@@ -74,6 +155,16 @@ impl DbPool {
                 DatabaseError::HealthCheckFailed(e.to_string())
             })?;
 
+        if let Some(read_pool) = &self.read_pool {
+            sqlx::query("SELECT 1")
+                .execute(read_pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Read replica health check failed");
+                    DatabaseError::HealthCheckFailed(format!("Read replica: {}", e))
+                })?;
+        }
+
         tracing::debug!("Database health check passed");
         Ok(())
     }
@@ -96,6 +187,9 @@ impl DbPool {
     pub async fn close(&self) {
         info!("Closing database connection pool");
         self.pool.close().await;
+        if let Some(read_pool) = &self.read_pool {
+            read_pool.close().await;
+        }
         info!("Database connection pool closed");
     }
 }
@@ -112,6 +206,10 @@ mod tests {
             max_connections: 5,
             min_connections: 1,
             connect_timeout_seconds: 5,
+            ssl_mode: None,
+            ssl_root_cert: None,
+            statement_timeout_seconds: None,
+            read_replica: None,
         };
 
         let result = DbPool::new(&config).await;
@@ -126,6 +224,10 @@ mod tests {
             max_connections: 5,
             min_connections: 1,
             connect_timeout_seconds: 5,
+            ssl_mode: None,
+            ssl_root_cert: None,
+            statement_timeout_seconds: None,
+            read_replica: None,
         };
 
         let pool = DbPool::new(&config).await.unwrap();
@@ -133,6 +235,27 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_connect_options_apply_tls_and_statement_timeout() {
+        let config = DatabaseConfig {
+            url: "postgresql://cron@db.internal/cron?sslmode=disable".to_string(),
+            max_connections: 5,
+            min_connections: 1,
+            connect_timeout_seconds: 5,
+            ssl_mode: Some("verify-full".to_string()),
+            ssl_root_cert: Some("/etc/ssl/rds-ca.pem".to_string()),
+            statement_timeout_seconds: Some(30),
+            read_replica: None,
+        };
+
+        let options = connect_options(&config.url, &config, Some(30)).unwrap();
+        assert!(matches!(options.get_ssl_mode(), PgSslMode::VerifyFull));
+        assert_eq!(options.get_options(), Some("-c statement_timeout=30s"));
+
+        let options = connect_options(&config.url, &config, None).unwrap();
+        assert_eq!(options.get_options(), None);
+    }
+
     #[test]
     fn test_pool_size_tracking() {
         // This test verifies the pool size methods are available
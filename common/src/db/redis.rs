@@ -1,16 +1,228 @@
 // Redis connection pool and health check
 // Requirements: 4.1
+//
+// Supports a single node, a Sentinel-managed master and Redis Cluster. Every topology
+// is exposed as a `RedisConnection`, so the lock backend and the storage cache don't
+// care which one is deployed.
 
-use crate::config::RedisConfig;
+use crate::config::{RedisClusterConfig, RedisConfig, RedisSentinelConfig};
 use crate::errors::StorageError;
-use redis::aio::ConnectionManager;
-use redis::Client;
-use tracing::{info, instrument};
+use redis::aio::{ConnectionLike, ConnectionManager, MultiplexedConnection};
+use redis::cluster::ClusterClient;
+use redis::cluster_async::ClusterConnection;
+use redis::sentinel::{SentinelClient, SentinelNodeConnectionInfo, SentinelServerType};
+use redis::{Client, Cmd, ErrorKind, IntoConnectionInfo, Pipeline, RedisError, RedisFuture, Value};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, instrument, warn};
+
+/// Connection to whichever Redis topology is configured
+///
+/// Cheap to clone; clones share the underlying connection.
+#[derive(Clone)]
+pub enum RedisConnection {
+    /// Single node, reconnecting automatically
+    Standalone(ConnectionManager),
+    /// Master discovered through Sentinel, re-resolved on failover
+    Sentinel(SentinelConnection),
+    /// Redis Cluster, following slot migrations and failovers
+    Cluster(ClusterConnection),
+}
+
+impl RedisConnection {
+    /// Connect using the topology selected in `config`
+    #[instrument(skip(config))]
+    pub async fn connect(config: &RedisConfig) -> Result<Self, StorageError> {
+        if let Some(sentinel) = &config.sentinel {
+            return SentinelConnection::connect(&config.url, sentinel)
+                .await
+                .map(Self::Sentinel);
+        }
+        if let Some(cluster) = &config.cluster {
+            return connect_cluster(cluster).await.map(Self::Cluster);
+        }
+
+        let client = Client::open(config.url.as_str()).map_err(|e| {
+            StorageError::ConnectionFailed(format!("Failed to create Redis client: {}", e))
+        })?;
+        let manager = ConnectionManager::new(client).await.map_err(|e| {
+            StorageError::ConnectionFailed(format!("Failed to create connection manager: {}", e))
+        })?;
+
+        Ok(Self::Standalone(manager))
+    }
+
+    /// Topology name for logs and health output
+    pub fn topology(&self) -> &'static str {
+        match self {
+            Self::Standalone(_) => "standalone",
+            Self::Sentinel(_) => "sentinel",
+            Self::Cluster(_) => "cluster",
+        }
+    }
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            Self::Standalone(conn) => conn.req_packed_command(cmd),
+            Self::Sentinel(conn) => conn.req_packed_command(cmd),
+            Self::Cluster(conn) => conn.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            Self::Standalone(conn) => conn.req_packed_commands(cmd, offset, count),
+            Self::Sentinel(conn) => conn.req_packed_commands(cmd, offset, count),
+            Self::Cluster(conn) => conn.req_packed_commands(cmd, offset, count),
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            Self::Standalone(conn) => conn.get_db(),
+            Self::Sentinel(conn) => conn.get_db(),
+            Self::Cluster(conn) => conn.get_db(),
+        }
+    }
+}
+
+async fn connect_cluster(config: &RedisClusterConfig) -> Result<ClusterConnection, StorageError> {
+    let client = ClusterClient::new(config.nodes.clone()).map_err(|e| {
+        StorageError::ConnectionFailed(format!("Failed to create Redis cluster client: {}", e))
+    })?;
+
+    client.get_async_connection().await.map_err(|e| {
+        StorageError::ConnectionFailed(format!("Failed to connect to Redis cluster: {}", e))
+    })
+}
+
+/// Connection to the current master of a Sentinel-monitored group
+///
+/// When the master stops accepting writes or connections, Sentinel is asked for the
+/// newly promoted master and the connection is replaced for every clone.
+#[derive(Clone)]
+pub struct SentinelConnection {
+    sentinel: Arc<Mutex<SentinelClient>>,
+    connection: Arc<RwLock<MultiplexedConnection>>,
+    db: i64,
+}
+
+impl SentinelConnection {
+    async fn connect(url: &str, config: &RedisSentinelConfig) -> Result<Self, StorageError> {
+        // The master URL carries the credentials and database; its host is
+        // replaced by whatever Sentinel reports
+        let master_info = url
+            .into_connection_info()
+            .map_err(|e| StorageError::ConnectionFailed(format!("Invalid Redis URL: {}", e)))?;
+        let db = master_info.redis.db;
+
+        let mut sentinel = SentinelClient::build(
+            config.nodes.clone(),
+            config.master_name.clone(),
+            Some(SentinelNodeConnectionInfo {
+                tls_mode: None,
+                redis_connection_info: Some(master_info.redis),
+            }),
+            SentinelServerType::Master,
+        )
+        .map_err(|e| {
+            StorageError::ConnectionFailed(format!("Failed to create Sentinel client: {}", e))
+        })?;
+
+        let connection = sentinel.get_async_connection().await.map_err(|e| {
+            StorageError::ConnectionFailed(format!(
+                "Failed to connect to Sentinel master '{}': {}",
+                config.master_name, e
+            ))
+        })?;
+
+        Ok(Self {
+            sentinel: Arc::new(Mutex::new(sentinel)),
+            connection: Arc::new(RwLock::new(connection)),
+            db,
+        })
+    }
+
+    /// Ask Sentinel for the current master and swap in a connection to it
+    async fn reconnect(&self) -> Result<MultiplexedConnection, RedisError> {
+        let connection = self.sentinel.lock().await.get_async_connection().await?;
+        *self.connection.write().await = connection.clone();
+        info!("Reconnected to Redis master reported by Sentinel");
+        Ok(connection)
+    }
+}
+
+/// Errors after which the master may have moved
+fn is_failover_error(error: &RedisError) -> bool {
+    error.is_io_error()
+        || error.is_connection_dropped()
+        || error.is_connection_refusal()
+        || error.kind() == ErrorKind::ReadOnly
+}
+
+/// Errors that guarantee the command was not applied, so it is safe to resend
+fn is_retryable_after_failover(error: &RedisError) -> bool {
+    error.is_connection_refusal() || error.kind() == ErrorKind::ReadOnly
+}
+
+impl ConnectionLike for SentinelConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        Box::pin(async move {
+            let mut connection = self.connection.read().await.clone();
+            match connection.req_packed_command(cmd).await {
+                Err(e) if is_failover_error(&e) => {
+                    warn!(error = %e, "Redis master unavailable, asking Sentinel for the current master");
+                    let mut connection = self.reconnect().await?;
+                    if is_retryable_after_failover(&e) {
+                        connection.req_packed_command(cmd).await
+                    } else {
+                        Err(e)
+                    }
+                }
+                result => result,
+            }
+        })
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        Box::pin(async move {
+            let mut connection = self.connection.read().await.clone();
+            match connection.req_packed_commands(cmd, offset, count).await {
+                Err(e) if is_failover_error(&e) => {
+                    warn!(error = %e, "Redis master unavailable, asking Sentinel for the current master");
+                    let mut connection = self.reconnect().await?;
+                    if is_retryable_after_failover(&e) {
+                        connection.req_packed_commands(cmd, offset, count).await
+                    } else {
+                        Err(e)
+                    }
+                }
+                result => result,
+            }
+        })
+    }
+
+    fn get_db(&self) -> i64 {
+        self.db
+    }
+}
 
 /// Redis connection pool wrapper
 #[derive(Clone)]
 pub struct RedisPool {
-    manager: ConnectionManager,
+    connection: RedisConnection,
 }
 
 impl RedisPool {
@@ -20,22 +232,19 @@ impl RedisPool {
     pub async fn new(config: &RedisConfig) -> Result<Self, StorageError> {
         info!("Initializing Redis connection pool");
 
-        let client = Client::open(config.url.as_str()).map_err(|e| {
-            StorageError::ConnectionFailed(format!("Failed to create Redis client: {}", e))
-        })?;
+        let connection = RedisConnection::connect(config).await?;
 
-        let manager = ConnectionManager::new(client).await.map_err(|e| {
-            StorageError::ConnectionFailed(format!("Failed to create connection manager: {}", e))
-        })?;
+        info!(
+            topology = connection.topology(),
+            "Redis connection pool initialized successfully"
+        );
 
-        info!("Redis connection pool initialized successfully");
-
-        Ok(Self { manager })
+        Ok(Self { connection })
     }
 
     /// Get a connection from the pool
-    pub fn get_connection(&self) -> ConnectionManager {
-        self.manager.clone()
+    pub fn get_connection(&self) -> RedisConnection {
+        self.connection.clone()
     }
 
     /// Health check - verify Redis connection is working
@@ -71,6 +280,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
 
         let pool = RedisPool::new(&config).await;
@@ -83,6 +294,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
 
         let pool = RedisPool::new(&config).await.unwrap();
@@ -95,9 +308,40 @@ mod tests {
         let config = RedisConfig {
             url: "redis://invalid-host:9999".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
 
         let result = RedisPool::new(&config).await;
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_failover_errors_only_retry_when_unapplied() {
+        let readonly = RedisError::from((ErrorKind::ReadOnly, "READONLY"));
+        assert!(is_failover_error(&readonly));
+        assert!(is_retryable_after_failover(&readonly));
+
+        let dropped = RedisError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(is_failover_error(&dropped));
+        assert!(!is_retryable_after_failover(&dropped));
+
+        let wrong_type = RedisError::from((ErrorKind::TypeError, "WRONGTYPE"));
+        assert!(!is_failover_error(&wrong_type));
+    }
+
+    #[tokio::test]
+    async fn test_sentinel_connection_fails_without_sentinels() {
+        let config = RedisConfig {
+            url: "redis://localhost:6379".to_string(),
+            pool_size: 10,
+            sentinel: Some(RedisSentinelConfig {
+                master_name: "cron-master".to_string(),
+                nodes: vec!["redis://invalid-host:26379".to_string()],
+            }),
+            cluster: None,
+        };
+
+        assert!(RedisPool::new(&config).await.is_err());
+    }
 }
@@ -0,0 +1,135 @@
+// Backup repository implementation
+// Purpose: Read every snapshot table in one repeatable-read transaction and write them
+// back in one transaction, column-for-column, so restored rows keep their ids and
+// stored values
+
+use crate::backup::{restore_columns, BackupError, Snapshot, SNAPSHOT_TABLES};
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use serde_json::Value;
+use tracing::instrument;
+
+/// Repository for disaster-recovery snapshots
+pub struct BackupRepository {
+    pool: DbPool,
+}
+
+impl BackupRepository {
+    /// Create a new BackupRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Read every snapshot table as of a single point in time
+    ///
+    /// Reads go to the primary: a lagging replica would produce a stale snapshot.
+    #[instrument(skip(self))]
+    pub async fn capture(
+        &self,
+        created_by: &str,
+        system_version: &str,
+    ) -> Result<Snapshot, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await?;
+
+        let mut snapshot = Snapshot::new(created_by, system_version);
+        for table in SNAPSHOT_TABLES {
+            let rows: Value = sqlx::query_scalar(&capture_query(table))
+                .fetch_one(&mut *tx)
+                .await?;
+            let rows: Vec<Value> = serde_json::from_value(rows).map_err(|e| {
+                DatabaseError::QueryFailed(format!("Failed to read {}: {}", table, e))
+            })?;
+            snapshot.tables.insert(table.to_string(), rows);
+        }
+        tx.commit().await?;
+
+        Ok(snapshot)
+    }
+
+    /// Insert every row of the snapshot into an empty cluster
+    ///
+    /// Runs in one transaction: if any table already has rows, or any insert fails,
+    /// nothing is written.
+    #[instrument(skip(self, snapshot), fields(created_at = %snapshot.created_at))]
+    pub async fn restore(&self, snapshot: &Snapshot) -> Result<(), BackupError> {
+        let mut tx = self.pool.pool().begin().await?;
+        // Keep concurrent writers out between the emptiness check and the inserts
+        sqlx::query(&format!(
+            "LOCK TABLE {} IN SHARE ROW EXCLUSIVE MODE",
+            SNAPSHOT_TABLES.join(", ")
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        let mut occupied = Vec::new();
+        for (table, _) in snapshot.restore_order() {
+            let has_rows: bool =
+                sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {})", table))
+                    .fetch_one(&mut *tx)
+                    .await?;
+            if has_rows {
+                occupied.push(table);
+            }
+        }
+        if !occupied.is_empty() {
+            return Err(BackupError::NotEmpty(occupied.join(", ")));
+        }
+
+        for (table, rows) in snapshot.restore_order() {
+            if rows.is_empty() {
+                continue;
+            }
+
+            let table_columns: Vec<String> = sqlx::query_scalar(
+                r#"
+                SELECT column_name::TEXT
+                FROM information_schema.columns
+                WHERE table_schema = current_schema() AND table_name = $1
+                ORDER BY ordinal_position
+                "#,
+            )
+            .bind(table)
+            .fetch_all(&mut *tx)
+            .await?;
+
+            let columns = restore_columns(&table_columns, rows)
+                .iter()
+                .map(|column| format!("\"{}\"", column))
+                .collect::<Vec<_>>()
+                .join(", ");
+            sqlx::query(&format!(
+                "INSERT INTO {table} ({columns}) SELECT {columns} FROM jsonb_populate_recordset(NULL::{table}, $1)",
+                table = table,
+                columns = columns
+            ))
+            .bind(Value::Array(rows.to_vec()))
+            .execute(&mut *tx)
+            .await?;
+
+            tracing::debug!(table = table, rows = rows.len(), "Restored table");
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Rows of a snapshot table as a JSON array
+///
+/// Rows are ordered by creation, or by key for job state. Links of blackout windows
+/// to the scheduled triggers of their deferred runs are dropped, as those triggers
+/// aren't part of a snapshot.
+fn capture_query(table: &str) -> String {
+    let (row, order) = match table {
+        "job_state" => ("to_jsonb(t)", "t.job_id, t.key"),
+        "blackout_windows" => ("to_jsonb(t) - 'deferred_trigger_id'", "t.created_at, t.id"),
+        _ => ("to_jsonb(t)", "t.created_at, t.id"),
+    };
+    format!(
+        "SELECT COALESCE(jsonb_agg({} ORDER BY {}), '[]'::jsonb) FROM {} t",
+        row, order, table
+    )
+}
@@ -0,0 +1,157 @@
+// Blackout window repository implementation
+// Purpose: Periods during which the scheduler must not publish a job, e.g. a
+// month-end close or a maintenance window
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::{BlackoutWindow, ScheduledTriggerStatus};
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+use uuid::Uuid;
+
+const BLACKOUT_WINDOW_COLUMNS: &str =
+    "id, job_id, name, starts_at, ends_at, behavior, deferred_trigger_id, created_by, created_at";
+
+/// Repository for blackout window database operations
+#[derive(Clone)]
+pub struct BlackoutWindowRepository {
+    pool: DbPool,
+}
+
+impl BlackoutWindowRepository {
+    /// Create a new BlackoutWindowRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert a new window
+    #[instrument(skip(self, window), fields(window_id = %window.id, job_id = %window.job_id))]
+    pub async fn create(&self, window: &BlackoutWindow) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO blackout_windows (
+                id, job_id, name, starts_at, ends_at, behavior, created_by, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(window.id)
+        .bind(window.job_id)
+        .bind(&window.name)
+        .bind(window.starts_at)
+        .bind(window.ends_at)
+        .bind(window.behavior.to_string())
+        .bind(&window.created_by)
+        .bind(window.created_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        tracing::info!(
+            starts_at = %window.starts_at,
+            ends_at = %window.ends_at,
+            behavior = %window.behavior,
+            "Blackout window created"
+        );
+        Ok(())
+    }
+
+    /// List a job's windows, latest first
+    #[instrument(skip(self))]
+    pub async fn find_by_job_id(&self, job_id: Uuid) -> Result<Vec<BlackoutWindow>, DatabaseError> {
+        let windows = sqlx::query_as::<_, BlackoutWindow>(&format!(
+            "SELECT {} FROM blackout_windows WHERE job_id = $1 ORDER BY starts_at DESC",
+            BLACKOUT_WINDOW_COLUMNS
+        ))
+        .bind(job_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(windows)
+    }
+
+    /// Windows of every job that are active at `now`
+    #[instrument(skip(self))]
+    pub async fn find_active(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<BlackoutWindow>, DatabaseError> {
+        let windows = sqlx::query_as::<_, BlackoutWindow>(&format!(
+            "SELECT {} FROM blackout_windows WHERE starts_at <= $1 AND ends_at > $1",
+            BLACKOUT_WINDOW_COLUMNS
+        ))
+        .bind(now)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(windows)
+    }
+
+    /// Windows of one job that are active at `now`
+    #[instrument(skip(self))]
+    pub async fn find_active_for_job(
+        &self,
+        job_id: Uuid,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<BlackoutWindow>, DatabaseError> {
+        let windows = sqlx::query_as::<_, BlackoutWindow>(&format!(
+            r#"
+            SELECT {} FROM blackout_windows
+            WHERE job_id = $1 AND starts_at <= $2 AND ends_at > $2
+            "#,
+            BLACKOUT_WINDOW_COLUMNS
+        ))
+        .bind(job_id)
+        .bind(now)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(windows)
+    }
+
+    /// Delete a window, returning whether it existed
+    ///
+    /// A run already deferred to the window's end stays scheduled.
+    #[instrument(skip(self))]
+    pub async fn delete(&self, job_id: Uuid, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM blackout_windows WHERE id = $1 AND job_id = $2")
+            .bind(id)
+            .bind(job_id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Schedule the window's deferred run at its end, once
+    ///
+    /// Claiming the window and creating the trigger happen in one statement, so
+    /// with several scheduler nodes only one trigger is made. Returns the new
+    /// trigger's id, or None if the window already has one.
+    #[instrument(skip(self))]
+    pub async fn defer_run(&self, id: Uuid) -> Result<Option<Uuid>, DatabaseError> {
+        let trigger_id: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            WITH claimed AS (
+                UPDATE blackout_windows
+                SET deferred_trigger_id = $2
+                WHERE id = $1 AND deferred_trigger_id IS NULL
+                RETURNING job_id, name, ends_at
+            )
+            INSERT INTO scheduled_triggers (id, job_id, execute_at, status, created_by, created_at)
+            SELECT $2, job_id, ends_at, $3, 'blackout:' || name, NOW()
+            FROM claimed
+            RETURNING id
+            "#,
+        )
+        .bind(id)
+        .bind(Uuid::new_v4())
+        .bind(ScheduledTriggerStatus::Pending.to_string())
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        if let Some(trigger_id) = trigger_id {
+            tracing::info!(trigger_id = %trigger_id, "Run deferred to the end of the blackout window");
+        }
+        Ok(trigger_id)
+    }
+}
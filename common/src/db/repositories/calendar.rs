@@ -0,0 +1,153 @@
+// Calendar repository implementation
+// Purpose: Admin-managed business-day calendars that cron schedules name
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::schedule::calendar::{Calendar, Holiday};
+use chrono::{NaiveDate, Utc, Weekday};
+use sqlx::{postgres::PgRow, Row};
+use tracing::instrument;
+
+const CALENDAR_COLUMNS: &str =
+    "id, name, description, weekend, vietnam_holidays, holidays, working_days, created_at, updated_at";
+
+/// Repository for calendar database operations
+#[derive(Clone)]
+pub struct CalendarRepository {
+    pool: DbPool,
+}
+
+impl CalendarRepository {
+    /// Create a new CalendarRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all calendars by name
+    #[instrument(skip(self))]
+    pub async fn find_all(&self) -> Result<Vec<Calendar>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM calendars ORDER BY name",
+            CALENDAR_COLUMNS
+        ))
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter().map(calendar_from_row).collect()
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<Calendar>, DatabaseError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM calendars WHERE name = $1",
+            CALENDAR_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        row.as_ref().map(calendar_from_row).transpose()
+    }
+
+    /// Create a calendar or replace the one with the same name
+    #[instrument(skip(self, holidays, working_days))]
+    pub async fn upsert(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        weekend: &[Weekday],
+        vietnam_holidays: bool,
+        holidays: &[Holiday],
+        working_days: &[NaiveDate],
+    ) -> Result<Calendar, DatabaseError> {
+        let weekend: Vec<i16> = weekend
+            .iter()
+            .map(|day| day.number_from_monday() as i16)
+            .collect();
+        let holidays = serde_json::to_value(holidays).map_err(|e| {
+            DatabaseError::QueryFailed(format!("Failed to serialize holidays: {}", e))
+        })?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO calendars (name, description, weekend, vietnam_holidays, holidays,
+                                   working_days, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (name)
+            DO UPDATE SET description = EXCLUDED.description,
+                          weekend = EXCLUDED.weekend,
+                          vietnam_holidays = EXCLUDED.vietnam_holidays,
+                          holidays = EXCLUDED.holidays,
+                          working_days = EXCLUDED.working_days,
+                          updated_at = EXCLUDED.updated_at
+            RETURNING {}
+            "#,
+            CALENDAR_COLUMNS
+        ))
+        .bind(name)
+        .bind(description)
+        .bind(&weekend)
+        .bind(vietnam_holidays)
+        .bind(&holidays)
+        .bind(working_days)
+        .bind(Utc::now())
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        tracing::info!(name = %name, "Calendar saved");
+        calendar_from_row(&row)
+    }
+
+    /// Names of the jobs whose schedule follows the calendar
+    #[instrument(skip(self))]
+    pub async fn find_job_names_using(&self, name: &str) -> Result<Vec<String>, DatabaseError> {
+        let names = sqlx::query_scalar(
+            "SELECT name FROM jobs WHERE definition -> 'schedule' -> 'calendar' ->> 'name' = $1 ORDER BY name",
+        )
+        .bind(name)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(names)
+    }
+
+    /// Delete a calendar, returning whether it existed
+    #[instrument(skip(self))]
+    pub async fn delete(&self, name: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM calendars WHERE name = $1")
+            .bind(name)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn calendar_from_row(row: &PgRow) -> Result<Calendar, DatabaseError> {
+    let weekend: Vec<i16> = row.try_get("weekend")?;
+    let weekend = weekend
+        .into_iter()
+        .map(|day| match day {
+            1..=7 => Ok(Weekday::try_from(day as u8 - 1).expect("weekday in range")),
+            _ => Err(DatabaseError::QueryFailed(format!(
+                "Invalid weekend day: {}",
+                day
+            ))),
+        })
+        .collect::<Result<_, _>>()?;
+    let holidays: serde_json::Value = row.try_get("holidays")?;
+    let holidays = serde_json::from_value(holidays)
+        .map_err(|e| DatabaseError::QueryFailed(format!("Failed to parse holidays: {}", e)))?;
+
+    Ok(Calendar {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        description: row.try_get("description")?,
+        weekend,
+        vietnam_holidays: row.try_get("vietnam_holidays")?,
+        holidays,
+        working_days: row.try_get("working_days")?,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
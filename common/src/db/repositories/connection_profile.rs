@@ -0,0 +1,291 @@
+// Connection profile repository implementation
+// Purpose: Admin-managed endpoints and credentials that job steps name, and the
+// verification probes queued when they change
+
+use crate::connection_profile::{
+    ConnectionEndpoint, ConnectionProfile, ConnectionVerification, VerificationStatus,
+};
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use chrono::Utc;
+use sqlx::{postgres::PgRow, Row};
+use std::str::FromStr;
+use tracing::instrument;
+
+const PROFILE_COLUMNS: &str = "id, name, description, endpoint, created_at, updated_at";
+
+const VERIFICATION_COLUMNS: &str = "v.id, v.connection_name, v.job_id, j.name AS job_name, \
+     v.status, v.error, v.requested_at, v.checked_at";
+
+/// Repository for connection profile database operations
+#[derive(Clone)]
+pub struct ConnectionProfileRepository {
+    pool: DbPool,
+}
+
+impl ConnectionProfileRepository {
+    /// Create a new ConnectionProfileRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all connection profiles by name
+    #[instrument(skip(self))]
+    pub async fn find_all(&self) -> Result<Vec<ConnectionProfile>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM connection_profiles ORDER BY name",
+            PROFILE_COLUMNS
+        ))
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter().map(profile_from_row).collect()
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find_by_name(
+        &self,
+        name: &str,
+    ) -> Result<Option<ConnectionProfile>, DatabaseError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM connection_profiles WHERE name = $1",
+            PROFILE_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        row.as_ref().map(profile_from_row).transpose()
+    }
+
+    /// Load the profiles with the given names; missing names are left out
+    #[instrument(skip(self))]
+    pub async fn find_by_names(
+        &self,
+        names: &[String],
+    ) -> Result<Vec<ConnectionProfile>, DatabaseError> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM connection_profiles WHERE name = ANY($1)",
+            PROFILE_COLUMNS
+        ))
+        .bind(names)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter().map(profile_from_row).collect()
+    }
+
+    /// Create a profile or replace the one with the same name
+    #[instrument(skip(self, endpoint))]
+    pub async fn upsert(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        endpoint: &ConnectionEndpoint,
+    ) -> Result<ConnectionProfile, DatabaseError> {
+        let endpoint_json = serde_json::to_value(endpoint).map_err(|e| {
+            DatabaseError::QueryFailed(format!("Failed to serialize endpoint: {}", e))
+        })?;
+
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO connection_profiles (name, description, kind, endpoint, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (name)
+            DO UPDATE SET description = EXCLUDED.description,
+                          kind = EXCLUDED.kind,
+                          endpoint = EXCLUDED.endpoint,
+                          updated_at = EXCLUDED.updated_at
+            RETURNING {}
+            "#,
+            PROFILE_COLUMNS
+        ))
+        .bind(name)
+        .bind(description)
+        .bind(endpoint.kind())
+        .bind(&endpoint_json)
+        .bind(Utc::now())
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        tracing::info!(name = %name, kind = endpoint.kind(), "Connection profile saved");
+        profile_from_row(&row)
+    }
+
+    /// Names of the jobs whose steps use the profile
+    #[instrument(skip(self))]
+    pub async fn find_job_names_using(&self, name: &str) -> Result<Vec<String>, DatabaseError> {
+        let names = sqlx::query_scalar(
+            r#"
+            SELECT name FROM jobs
+            WHERE definition->'steps' @> jsonb_build_array(jsonb_build_object('connection', $1::text))
+            ORDER BY name
+            "#,
+        )
+        .bind(name)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(names)
+    }
+
+    /// Delete a profile, returning whether it existed
+    #[instrument(skip(self))]
+    pub async fn delete(&self, name: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM connection_profiles WHERE name = $1")
+            .bind(name)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Queue a verification probe for every job whose steps use the profile
+    ///
+    /// Replaces the previous probes of the profile, so the report only covers the jobs
+    /// using it now. Returns the number of probes queued.
+    #[instrument(skip(self))]
+    pub async fn request_verifications(&self, name: &str) -> Result<u64, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query("DELETE FROM connection_verifications WHERE connection_name = $1")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO connection_verifications (connection_name, job_id)
+            SELECT $1, id FROM jobs
+            WHERE definition->'steps' @> jsonb_build_array(jsonb_build_object('connection', $1::text))
+            "#,
+        )
+        .bind(name)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        tracing::info!(
+            name = %name,
+            jobs = result.rows_affected(),
+            "Connection verification probes queued"
+        );
+        Ok(result.rows_affected())
+    }
+
+    /// Probes waiting to run, oldest first
+    #[instrument(skip(self))]
+    pub async fn pending_verifications(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<ConnectionVerification>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM connection_verifications v
+            JOIN jobs j ON j.id = v.job_id
+            WHERE v.status = 'pending'
+            ORDER BY v.requested_at
+            LIMIT $1
+            "#,
+            VERIFICATION_COLUMNS
+        ))
+        .bind(limit)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter().map(verification_from_row).collect()
+    }
+
+    /// The latest probes of the jobs using the profile, failed ones first
+    #[instrument(skip(self))]
+    pub async fn verifications(
+        &self,
+        name: &str,
+    ) -> Result<Vec<ConnectionVerification>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM connection_verifications v
+            JOIN jobs j ON j.id = v.job_id
+            WHERE v.connection_name = $1
+            ORDER BY CASE v.status WHEN 'failed' THEN 0 WHEN 'pending' THEN 1 ELSE 2 END,
+                     j.name
+            "#,
+            VERIFICATION_COLUMNS
+        ))
+        .bind(name)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter().map(verification_from_row).collect()
+    }
+
+    /// Record the outcome of a probe; `error` is why the job would break
+    ///
+    /// A probe requeued by a newer profile change in the meantime stays pending, since
+    /// its outcome is for the previous version of the profile.
+    #[instrument(skip(self, verification), fields(job_id = %verification.job_id))]
+    pub async fn record_verification(
+        &self,
+        verification: &ConnectionVerification,
+        error: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        let status = match error {
+            Some(_) => VerificationStatus::Failed,
+            None => VerificationStatus::Passed,
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE connection_verifications
+            SET status = $1, error = $2, checked_at = $3
+            WHERE id = $4 AND requested_at = $5
+            "#,
+        )
+        .bind(status.to_string())
+        .bind(error)
+        .bind(Utc::now())
+        .bind(verification.id)
+        .bind(verification.requested_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn profile_from_row(row: &PgRow) -> Result<ConnectionProfile, DatabaseError> {
+    let endpoint: serde_json::Value = row.try_get("endpoint")?;
+    let endpoint = serde_json::from_value(endpoint).map_err(|e| {
+        DatabaseError::QueryFailed(format!("Failed to parse connection endpoint: {}", e))
+    })?;
+
+    Ok(ConnectionProfile {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        description: row.try_get("description")?,
+        endpoint,
+        created_at: row.try_get("created_at")?,
+        updated_at: row.try_get("updated_at")?,
+    })
+}
+
+fn verification_from_row(row: &PgRow) -> Result<ConnectionVerification, DatabaseError> {
+    let status: String = row.try_get("status")?;
+    let status = VerificationStatus::from_str(&status).map_err(DatabaseError::QueryFailed)?;
+
+    Ok(ConnectionVerification {
+        id: row.try_get("id")?,
+        connection_name: row.try_get("connection_name")?,
+        job_id: row.try_get("job_id")?,
+        job_name: row.try_get("job_name")?,
+        status,
+        error: row.try_get("error")?,
+        requested_at: row.try_get("requested_at")?,
+        checked_at: row.try_get("checked_at")?,
+    })
+}
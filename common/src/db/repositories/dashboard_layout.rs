@@ -0,0 +1,69 @@
+// Dashboard layout repository implementation
+// Purpose: Dashboard landing page layout of each user
+
+use crate::dashboard_layout::DashboardLayout;
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use chrono::Utc;
+use sqlx::types::Json;
+use tracing::instrument;
+
+/// Repository for per-user dashboard layouts
+#[derive(Clone)]
+pub struct DashboardLayoutRepository {
+    pool: DbPool,
+}
+
+impl DashboardLayoutRepository {
+    /// Create a new DashboardLayoutRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Saved layout of a user, if they have one
+    #[instrument(skip(self))]
+    pub async fn find(&self, subject: &str) -> Result<Option<DashboardLayout>, DatabaseError> {
+        let layout: Option<Json<DashboardLayout>> =
+            sqlx::query_scalar("SELECT layout FROM dashboard_layouts WHERE subject = $1")
+                .bind(subject)
+                .fetch_optional(self.pool.pool())
+                .await?;
+
+        Ok(layout.map(|Json(layout)| layout))
+    }
+
+    /// Create or replace a user's layout
+    #[instrument(skip(self, layout))]
+    pub async fn upsert(
+        &self,
+        subject: &str,
+        layout: &DashboardLayout,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO dashboard_layouts (subject, layout, updated_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (subject)
+            DO UPDATE SET layout = EXCLUDED.layout, updated_at = EXCLUDED.updated_at
+            "#,
+        )
+        .bind(subject)
+        .bind(Json(layout))
+        .bind(Utc::now())
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Drop a user's layout so they get the default one again
+    #[instrument(skip(self))]
+    pub async fn delete(&self, subject: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM dashboard_layouts WHERE subject = $1")
+            .bind(subject)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,318 @@
+// Erasure repository implementation
+// Purpose: Queries of subject data erasures and their hash-chained reports
+
+use crate::db::DbPool;
+use crate::erasure::{ErasureMatch, ErasureReport};
+use crate::errors::DatabaseError;
+use serde_json::Value;
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use tracing::instrument;
+use uuid::Uuid;
+
+const SELECT_COLUMNS: &str =
+    "id, identifier_hash, requested_by, matches, skipped_files, created_at, previous_hash, hash";
+
+/// An execution whose context may contain an identifier
+#[derive(Debug, Clone)]
+pub struct ContextCandidate {
+    pub execution_id: Uuid,
+    pub job_id: Uuid,
+}
+
+/// A captured webhook request containing an identifier
+#[derive(Debug, Clone)]
+pub struct PayloadCandidate {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub data: Value,
+}
+
+/// An execution whose result or error column contains an identifier
+#[derive(Debug, Clone)]
+pub struct ExecutionRecordCandidate {
+    pub execution_id: Uuid,
+    pub job_id: Uuid,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A job state value containing an identifier
+#[derive(Debug, Clone)]
+pub struct StateCandidate {
+    pub job_id: Uuid,
+    pub key: String,
+    pub value: Value,
+}
+
+/// Repository for subject data erasures
+#[derive(Clone)]
+pub struct ErasureRepository {
+    pool: DbPool,
+}
+
+impl ErasureRepository {
+    /// Create a new ErasureRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Executions after `after`, by id, whose stored context contains the identifier
+    /// in any case, or that keep their context or files elsewhere
+    #[instrument(skip(self, identifier))]
+    pub async fn context_candidates(
+        &self,
+        identifier: &str,
+        after: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<ContextCandidate>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, job_id FROM job_executions
+            WHERE ($2::uuid IS NULL OR id > $2)
+              AND (
+                strpos(lower(context::text), lower($1)) > 0
+                OR context ? 'residency'
+                OR jsonb_array_length(COALESCE(context->'files', '[]'::jsonb)) > 0
+              )
+            ORDER BY id
+            LIMIT $3
+            "#,
+        )
+        .bind(identifier)
+        .bind(after)
+        .bind(limit)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ContextCandidate {
+                    execution_id: row.try_get("id")?,
+                    job_id: row.try_get("job_id")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Captured webhook requests containing the identifier in any case, or kept in
+    /// their tenant's store
+    #[instrument(skip(self, identifier))]
+    pub async fn webhook_payload_candidates(
+        &self,
+        identifier: &str,
+    ) -> Result<Vec<PayloadCandidate>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, job_id, data FROM webhook_payloads
+            WHERE strpos(lower(data::text), lower($1)) > 0
+               OR data->'payload' ? 'residency'
+            ORDER BY id
+            "#,
+        )
+        .bind(identifier)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(PayloadCandidate {
+                    id: row.try_get("id")?,
+                    job_id: row.try_get("job_id")?,
+                    data: row.try_get("data")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Replace the data of a captured webhook request
+    #[instrument(skip(self, data))]
+    pub async fn update_webhook_payload(
+        &self,
+        id: Uuid,
+        data: &Value,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE webhook_payloads SET data = $1 WHERE id = $2")
+            .bind(data)
+            .bind(id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Executions whose result or error contains the identifier in any case
+    #[instrument(skip(self, identifier))]
+    pub async fn execution_record_candidates(
+        &self,
+        identifier: &str,
+    ) -> Result<Vec<ExecutionRecordCandidate>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, job_id, result, error FROM job_executions
+            WHERE strpos(lower(COALESCE(result, '')), lower($1)) > 0
+               OR strpos(lower(COALESCE(error, '')), lower($1)) > 0
+            ORDER BY id
+            "#,
+        )
+        .bind(identifier)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(ExecutionRecordCandidate {
+                    execution_id: row.try_get("id")?,
+                    job_id: row.try_get("job_id")?,
+                    result: row.try_get("result")?,
+                    error: row.try_get("error")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Replace the result and error of an execution
+    #[instrument(skip(self, result, error))]
+    pub async fn update_execution_record(
+        &self,
+        execution_id: Uuid,
+        result: Option<&str>,
+        error: Option<&str>,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE job_executions SET result = $1, error = $2 WHERE id = $3")
+            .bind(result)
+            .bind(error)
+            .bind(execution_id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Job state values containing the identifier in any case, or kept in their
+    /// tenant's store
+    #[instrument(skip(self, identifier))]
+    pub async fn state_candidates(
+        &self,
+        identifier: &str,
+    ) -> Result<Vec<StateCandidate>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT job_id, key, value FROM job_state
+            WHERE strpos(lower(value::text), lower($1)) > 0
+               OR value ? 'residency'
+            ORDER BY job_id, key
+            "#,
+        )
+        .bind(identifier)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(StateCandidate {
+                    job_id: row.try_get("job_id")?,
+                    key: row.try_get("key")?,
+                    value: row.try_get("value")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Replace a job state value
+    #[instrument(skip(self, value))]
+    pub async fn update_state(
+        &self,
+        job_id: Uuid,
+        key: &str,
+        value: &Value,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE job_state SET value = $1 WHERE job_id = $2 AND key = $3")
+            .bind(value)
+            .bind(job_id)
+            .bind(key)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Seal a report onto the end of the chain and save it
+    ///
+    /// The table is locked for the transaction, so concurrent erasures can't both
+    /// chain to the same report.
+    #[instrument(skip(self, identifier_hash, matches, skipped_files))]
+    pub async fn append(
+        &self,
+        identifier_hash: String,
+        requested_by: String,
+        matches: Vec<ErasureMatch>,
+        skipped_files: Vec<String>,
+    ) -> Result<ErasureReport, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query("LOCK TABLE erasure_reports IN EXCLUSIVE MODE")
+            .execute(&mut *tx)
+            .await?;
+        let previous_hash: Option<String> =
+            sqlx::query_scalar("SELECT hash FROM erasure_reports ORDER BY sequence DESC LIMIT 1")
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        let report = ErasureReport::seal(
+            identifier_hash,
+            requested_by,
+            matches,
+            skipped_files,
+            previous_hash,
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO erasure_reports
+                (id, identifier_hash, requested_by, matches, skipped_files, created_at, previous_hash, hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(report.id)
+        .bind(&report.identifier_hash)
+        .bind(&report.requested_by)
+        .bind(sqlx::types::Json(&report.matches))
+        .bind(sqlx::types::Json(&report.skipped_files))
+        .bind(report.created_at)
+        .bind(&report.previous_hash)
+        .bind(&report.hash)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(report)
+    }
+
+    /// All reports, oldest first
+    #[instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<ErasureReport>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM erasure_reports ORDER BY sequence",
+            SELECT_COLUMNS
+        ))
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        rows.iter().map(report_from_row).collect()
+    }
+}
+
+fn report_from_row(row: &PgRow) -> Result<ErasureReport, DatabaseError> {
+    let matches: sqlx::types::Json<Vec<ErasureMatch>> = row.try_get("matches")?;
+    let skipped_files: sqlx::types::Json<Vec<String>> = row.try_get("skipped_files")?;
+    Ok(ErasureReport {
+        id: row.try_get("id")?,
+        identifier_hash: row.try_get("identifier_hash")?,
+        requested_by: row.try_get("requested_by")?,
+        matches: matches.0,
+        skipped_files: skipped_files.0,
+        created_at: row.try_get("created_at")?,
+        previous_hash: row.try_get("previous_hash")?,
+        hash: row.try_get("hash")?,
+    })
+}
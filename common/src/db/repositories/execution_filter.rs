@@ -0,0 +1,129 @@
+// Saved execution filter repository implementation
+// Purpose: Named execution filters shared by link or with a team
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::execution_filter::{ExecutionFilterCriteria, SavedFilter};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::Row;
+use tracing::instrument;
+use uuid::Uuid;
+
+const SELECT_COLUMNS: &str = "id, name, owner, team, criteria, created_at, updated_at";
+
+/// Repository for saved execution filters
+#[derive(Clone)]
+pub struct ExecutionFilterRepository {
+    pool: DbPool,
+}
+
+impl ExecutionFilterRepository {
+    /// Create a new ExecutionFilterRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a new filter
+    #[instrument(skip(self, filter), fields(filter_id = %filter.id))]
+    pub async fn create(&self, filter: &SavedFilter) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO execution_filters (id, name, owner, team, criteria, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(filter.id)
+        .bind(&filter.name)
+        .bind(&filter.owner)
+        .bind(&filter.team)
+        .bind(Json(&filter.criteria))
+        .bind(filter.created_at)
+        .bind(filter.updated_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find a filter by id
+    #[instrument(skip(self))]
+    pub async fn find(&self, id: Uuid) -> Result<Option<SavedFilter>, DatabaseError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM execution_filters WHERE id = $1",
+            SELECT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.read_pool())
+        .await?;
+
+        row.map(|row| map_row(&row)).transpose()
+    }
+
+    /// Filters saved by a user, plus those shared with `team`, by name
+    #[instrument(skip(self))]
+    pub async fn list_visible(
+        &self,
+        owner: &str,
+        team: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM execution_filters WHERE owner = $1 OR ($2::TEXT IS NOT NULL AND team = $2) ORDER BY name",
+            SELECT_COLUMNS
+        ))
+        .bind(owner)
+        .bind(team)
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        rows.iter().map(map_row).collect()
+    }
+
+    /// Replace the name, team and criteria of a filter
+    #[instrument(skip(self, filter), fields(filter_id = %filter.id))]
+    pub async fn update(&self, filter: &SavedFilter) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE execution_filters
+            SET name = $2, team = $3, criteria = $4, updated_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(filter.id)
+        .bind(&filter.name)
+        .bind(&filter.team)
+        .bind(Json(&filter.criteria))
+        .bind(filter.updated_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a filter
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM execution_filters WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+fn map_row(row: &PgRow) -> Result<SavedFilter, DatabaseError> {
+    let Json(criteria): Json<ExecutionFilterCriteria> = row.try_get("criteria")?;
+    let created_at: DateTime<Utc> = row.try_get("created_at")?;
+    let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+    Ok(SavedFilter {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        owner: row.try_get("owner")?,
+        team: row.try_get("team")?,
+        criteria,
+        created_at,
+        updated_at,
+    })
+}
@@ -0,0 +1,108 @@
+// Failure note repository implementation
+// Purpose: Remediation notes attached to failure fingerprints
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::FailureNote;
+use chrono::Utc;
+use tracing::instrument;
+
+/// Repository for failure note database operations
+#[derive(Clone)]
+pub struct FailureNoteRepository {
+    pool: DbPool,
+}
+
+impl FailureNoteRepository {
+    /// Create a new FailureNoteRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+    ) -> Result<Option<FailureNote>, DatabaseError> {
+        let note = sqlx::query_as::<_, FailureNote>(
+            r#"
+            SELECT fingerprint, note, runbook_url, updated_by, created_at, updated_at
+            FROM failure_notes
+            WHERE fingerprint = $1
+            "#,
+        )
+        .bind(fingerprint)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(note)
+    }
+
+    /// Notes of any of the fingerprints; fingerprints without a note are left out
+    #[instrument(skip(self, fingerprints), fields(count = fingerprints.len()))]
+    pub async fn find_by_fingerprints(
+        &self,
+        fingerprints: &[String],
+    ) -> Result<Vec<FailureNote>, DatabaseError> {
+        if fingerprints.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let notes = sqlx::query_as::<_, FailureNote>(
+            r#"
+            SELECT fingerprint, note, runbook_url, updated_by, created_at, updated_at
+            FROM failure_notes
+            WHERE fingerprint = ANY($1)
+            "#,
+        )
+        .bind(fingerprints)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(notes)
+    }
+
+    /// Create the fingerprint's note or replace it
+    #[instrument(skip(self, note))]
+    pub async fn upsert(
+        &self,
+        fingerprint: &str,
+        note: &str,
+        runbook_url: Option<&str>,
+        updated_by: &str,
+    ) -> Result<FailureNote, DatabaseError> {
+        let note = sqlx::query_as::<_, FailureNote>(
+            r#"
+            INSERT INTO failure_notes (fingerprint, note, runbook_url, updated_by, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (fingerprint)
+            DO UPDATE SET note = EXCLUDED.note,
+                          runbook_url = EXCLUDED.runbook_url,
+                          updated_by = EXCLUDED.updated_by,
+                          updated_at = EXCLUDED.updated_at
+            RETURNING fingerprint, note, runbook_url, updated_by, created_at, updated_at
+            "#,
+        )
+        .bind(fingerprint)
+        .bind(note)
+        .bind(runbook_url)
+        .bind(updated_by)
+        .bind(Utc::now())
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        tracing::info!(fingerprint = %fingerprint, "Failure note saved");
+        Ok(note)
+    }
+
+    /// Delete the fingerprint's note, returning whether it had one
+    #[instrument(skip(self))]
+    pub async fn delete(&self, fingerprint: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM failure_notes WHERE fingerprint = $1")
+            .bind(fingerprint)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
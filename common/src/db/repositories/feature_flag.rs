@@ -0,0 +1,108 @@
+// Feature flag repository implementation
+// Purpose: Admin-managed feature flags, global or per tenant, read on every request so
+// changes take effect without a restart
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::FeatureFlag;
+use chrono::Utc;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Repository for feature flag database operations
+#[derive(Clone)]
+pub struct FeatureFlagRepository {
+    pool: DbPool,
+}
+
+impl FeatureFlagRepository {
+    /// Create a new FeatureFlagRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all flags, global rows first
+    #[instrument(skip(self))]
+    pub async fn find_all(&self) -> Result<Vec<FeatureFlag>, DatabaseError> {
+        let flags = sqlx::query_as::<_, FeatureFlag>(
+            r#"
+            SELECT id, name, tenant_id, enabled, description, created_at, updated_at
+            FROM feature_flags
+            ORDER BY name, tenant_id NULLS FIRST
+            "#,
+        )
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(flags)
+    }
+
+    /// Global flags plus the tenant's own rows, if a tenant is given
+    #[instrument(skip(self))]
+    pub async fn find_for_tenant(
+        &self,
+        tenant_id: Option<&str>,
+    ) -> Result<Vec<FeatureFlag>, DatabaseError> {
+        let flags = sqlx::query_as::<_, FeatureFlag>(
+            r#"
+            SELECT id, name, tenant_id, enabled, description, created_at, updated_at
+            FROM feature_flags
+            WHERE tenant_id IS NULL OR tenant_id = $1
+            ORDER BY name, tenant_id NULLS FIRST
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(flags)
+    }
+
+    /// Create or replace a flag for a tenant, or globally when `tenant_id` is None
+    #[instrument(skip(self))]
+    pub async fn upsert(
+        &self,
+        name: &str,
+        tenant_id: Option<&str>,
+        enabled: bool,
+        description: Option<&str>,
+    ) -> Result<FeatureFlag, DatabaseError> {
+        let flag = sqlx::query_as::<_, FeatureFlag>(
+            r#"
+            INSERT INTO feature_flags (name, tenant_id, enabled, description, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (name, (COALESCE(tenant_id, '')))
+            DO UPDATE SET enabled = EXCLUDED.enabled,
+                          description = COALESCE(EXCLUDED.description, feature_flags.description),
+                          updated_at = EXCLUDED.updated_at
+            RETURNING id, name, tenant_id, enabled, description, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .bind(tenant_id)
+        .bind(enabled)
+        .bind(description)
+        .bind(Utc::now())
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        tracing::info!(
+            name = %name,
+            tenant_id = ?tenant_id,
+            enabled = enabled,
+            "Feature flag saved"
+        );
+        Ok(flag)
+    }
+
+    /// Delete a flag, returning whether it existed
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,96 @@
+// Job state repository implementation
+// Purpose: Per-job key/value state kept across executions
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::JobStateEntry;
+use chrono::Utc;
+use std::collections::HashMap;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Repository for job state database operations
+#[derive(Clone)]
+pub struct JobStateRepository {
+    pool: DbPool,
+}
+
+impl JobStateRepository {
+    /// Create a new JobStateRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Every value of the job's state, ordered by key
+    #[instrument(skip(self))]
+    pub async fn find_by_job(&self, job_id: Uuid) -> Result<Vec<JobStateEntry>, DatabaseError> {
+        let entries = sqlx::query_as::<_, JobStateEntry>(
+            r#"
+            SELECT job_id, key, value, updated_by_execution, updated_at
+            FROM job_state
+            WHERE job_id = $1
+            ORDER BY key
+            "#,
+        )
+        .bind(job_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Write some keys of the job's state in one transaction, replacing their values
+    ///
+    /// `execution_id` is the execution whose step wrote them, if any.
+    #[instrument(skip(self, values), fields(keys = values.len()))]
+    pub async fn upsert_many(
+        &self,
+        job_id: Uuid,
+        values: &HashMap<String, serde_json::Value>,
+        execution_id: Option<Uuid>,
+    ) -> Result<(), DatabaseError> {
+        let now = Utc::now();
+        let mut tx = self.pool.pool().begin().await?;
+        for (key, value) in values {
+            sqlx::query(
+                r#"
+                INSERT INTO job_state (job_id, key, value, updated_by_execution, updated_at)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (job_id, key)
+                DO UPDATE SET value = EXCLUDED.value,
+                              updated_by_execution = EXCLUDED.updated_by_execution,
+                              updated_at = EXCLUDED.updated_at
+                "#,
+            )
+            .bind(job_id)
+            .bind(key)
+            .bind(value)
+            .bind(execution_id)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        tracing::info!(job_id = %job_id, keys = values.len(), "Job state saved");
+        Ok(())
+    }
+
+    /// Delete a key of the job's state, returning the value it had if it was set
+    #[instrument(skip(self))]
+    pub async fn delete(
+        &self,
+        job_id: Uuid,
+        key: &str,
+    ) -> Result<Option<serde_json::Value>, DatabaseError> {
+        let value = sqlx::query_scalar(
+            "DELETE FROM job_state WHERE job_id = $1 AND key = $2 RETURNING value",
+        )
+        .bind(job_id)
+        .bind(key)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(value)
+    }
+}
@@ -0,0 +1,117 @@
+// JWT signing key repository implementation
+// Purpose: Signing keys shared by all API instances, and their rotation
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::jwt_keys::JwtSigningKey;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use tracing::instrument;
+
+const SELECT_COLUMNS: &str = "kid, public_key, encrypted_private_key, created_at, retired_at";
+
+/// Repository for JWT signing keys
+#[derive(Clone)]
+pub struct JwtKeyRepository {
+    pool: DbPool,
+}
+
+impl JwtKeyRepository {
+    /// Create a new JwtKeyRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// The signing key and the keys retired after the given time, newest first
+    #[instrument(skip(self))]
+    pub async fn list_retired_after(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<JwtSigningKey>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM jwt_signing_keys
+            WHERE retired_at IS NULL OR retired_at > $1
+            ORDER BY created_at DESC
+            "#,
+            SELECT_COLUMNS
+        ))
+        .bind(since)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter().map(map_row).collect()
+    }
+
+    /// Make `key` the signing key unless the current one was created after `due_before`;
+    /// returns whether it was saved
+    ///
+    /// An advisory lock makes concurrent rotations on several instances create one key.
+    #[instrument(skip(self, key), fields(kid = %key.kid))]
+    pub async fn rotate(
+        &self,
+        key: &JwtSigningKey,
+        due_before: DateTime<Utc>,
+    ) -> Result<bool, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext('jwt_signing_keys'))")
+            .execute(&mut *tx)
+            .await?;
+
+        let current: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM jwt_signing_keys WHERE retired_at IS NULL AND created_at > $1)",
+        )
+        .bind(due_before)
+        .fetch_one(&mut *tx)
+        .await?;
+        if current {
+            return Ok(false);
+        }
+
+        sqlx::query("UPDATE jwt_signing_keys SET retired_at = $1 WHERE retired_at IS NULL")
+            .bind(key.created_at)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            r#"
+            INSERT INTO jwt_signing_keys (kid, public_key, encrypted_private_key, created_at, retired_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&key.kid)
+        .bind(&key.public_key)
+        .bind(&key.encrypted_private_key)
+        .bind(key.created_at)
+        .bind(key.retired_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Delete keys retired before the given time
+    #[instrument(skip(self))]
+    pub async fn delete_retired_before(&self, before: DateTime<Utc>) -> Result<u64, DatabaseError> {
+        let result = sqlx::query("DELETE FROM jwt_signing_keys WHERE retired_at < $1")
+            .bind(before)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+fn map_row(row: &PgRow) -> Result<JwtSigningKey, DatabaseError> {
+    let created_at: DateTime<Utc> = row.try_get("created_at")?;
+    let retired_at: Option<DateTime<Utc>> = row.try_get("retired_at")?;
+    Ok(JwtSigningKey {
+        kid: row.try_get("kid")?,
+        public_key: row.try_get("public_key")?,
+        encrypted_private_key: row.try_get("encrypted_private_key")?,
+        created_at,
+        retired_at,
+    })
+}
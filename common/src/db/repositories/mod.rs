@@ -3,14 +3,62 @@
 
 mod queries;
 
+pub mod backup;
+pub mod blackout_window;
+pub mod calendar;
+pub mod connection_profile;
+pub mod dashboard_layout;
+pub mod erasure;
 pub mod execution;
+pub mod execution_filter;
+pub mod failure_note;
+pub mod feature_flag;
 pub mod job;
+pub mod job_state;
+pub mod jwt_key;
+pub mod rate_limit_policy;
+pub mod scheduled_trigger;
+pub mod scheduler_drain;
+pub mod scheduler_journal;
+pub mod session;
+pub mod stats;
+pub mod status_page;
+pub mod system_job;
+pub mod tls_material;
+pub mod usage;
 pub mod user;
 pub mod variable;
 pub mod webhook;
+pub mod webhook_delivery;
+pub mod webhook_payload;
 
-pub use execution::{ExecutionFilter, ExecutionRepository};
-pub use job::JobRepository;
+pub use backup::BackupRepository;
+pub use blackout_window::BlackoutWindowRepository;
+pub use calendar::CalendarRepository;
+pub use connection_profile::ConnectionProfileRepository;
+pub use dashboard_layout::DashboardLayoutRepository;
+pub use erasure::ErasureRepository;
+pub use execution::{
+    ExecutionExportFilter, ExecutionExportRow, ExecutionFilter, ExecutionRepository, FailureGroup,
+};
+pub use execution_filter::ExecutionFilterRepository;
+pub use failure_note::FailureNoteRepository;
+pub use feature_flag::FeatureFlagRepository;
+pub use job::{JobRepository, SunsetJob};
+pub use job_state::JobStateRepository;
+pub use jwt_key::JwtKeyRepository;
+pub use rate_limit_policy::RateLimitPolicyRepository;
+pub use scheduled_trigger::ScheduledTriggerRepository;
+pub use scheduler_drain::SchedulerDrainRepository;
+pub use scheduler_journal::{SchedulerJournalFilter, SchedulerJournalRepository};
+pub use session::SessionRepository;
+pub use stats::{ExecutionTotals, StatsRepository};
+pub use status_page::StatusPageRepository;
+pub use system_job::{DeadLetterSummary, StaleSecret, SystemJobRepository, SystemJobStatus};
+pub use tls_material::TlsMaterialRepository;
+pub use usage::{TenantUsage, UsageRepository};
 pub use user::UserRepository;
 pub use variable::VariableRepository;
 pub use webhook::WebhookRepository;
+pub use webhook_delivery::{AttemptOutcome, WebhookDeliveryFilter, WebhookDeliveryRepository};
+pub use webhook_payload::WebhookPayloadRepository;
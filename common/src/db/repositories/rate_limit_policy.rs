@@ -0,0 +1,118 @@
+// Rate limit policy repository implementation
+// Purpose: Admin-configurable API rate limits per user, API key or tenant
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::{RateLimitPolicy, RateLimitScope};
+use chrono::Utc;
+use tracing::instrument;
+
+/// Repository for rate limit policy database operations
+#[derive(Clone)]
+pub struct RateLimitPolicyRepository {
+    pool: DbPool,
+}
+
+impl RateLimitPolicyRepository {
+    /// Create a new RateLimitPolicyRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// List all configured policies
+    #[instrument(skip(self))]
+    pub async fn find_all(&self) -> Result<Vec<RateLimitPolicy>, DatabaseError> {
+        let policies = sqlx::query_as::<_, RateLimitPolicy>(
+            r#"
+            SELECT id, scope, identity, requests_per_minute, burst, created_at, updated_at
+            FROM rate_limit_policies
+            ORDER BY scope, identity
+            "#,
+        )
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(policies)
+    }
+
+    /// Find the policy for a single identity
+    #[instrument(skip(self))]
+    pub async fn find(
+        &self,
+        scope: RateLimitScope,
+        identity: &str,
+    ) -> Result<Option<RateLimitPolicy>, DatabaseError> {
+        let policy = sqlx::query_as::<_, RateLimitPolicy>(
+            r#"
+            SELECT id, scope, identity, requests_per_minute, burst, created_at, updated_at
+            FROM rate_limit_policies
+            WHERE scope = $1 AND identity = $2
+            "#,
+        )
+        .bind(scope.to_string())
+        .bind(identity)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(policy)
+    }
+
+    /// Create or replace the policy for an identity
+    #[instrument(skip(self))]
+    pub async fn upsert(
+        &self,
+        scope: RateLimitScope,
+        identity: &str,
+        requests_per_minute: i32,
+        burst: i32,
+    ) -> Result<RateLimitPolicy, DatabaseError> {
+        let policy = sqlx::query_as::<_, RateLimitPolicy>(
+            r#"
+            INSERT INTO rate_limit_policies (scope, identity, requests_per_minute, burst, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (scope, identity)
+            DO UPDATE SET requests_per_minute = EXCLUDED.requests_per_minute,
+                          burst = EXCLUDED.burst,
+                          updated_at = EXCLUDED.updated_at
+            RETURNING id, scope, identity, requests_per_minute, burst, created_at, updated_at
+            "#,
+        )
+        .bind(scope.to_string())
+        .bind(identity)
+        .bind(requests_per_minute)
+        .bind(burst)
+        .bind(Utc::now())
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        tracing::info!(
+            scope = %scope,
+            identity = %identity,
+            requests_per_minute = requests_per_minute,
+            burst = burst,
+            "Rate limit policy saved"
+        );
+        Ok(policy)
+    }
+
+    /// Delete the policy for an identity, returning whether one existed
+    #[instrument(skip(self))]
+    pub async fn delete(
+        &self,
+        scope: RateLimitScope,
+        identity: &str,
+    ) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM rate_limit_policies
+            WHERE scope = $1 AND identity = $2
+            "#,
+        )
+        .bind(scope.to_string())
+        .bind(identity)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
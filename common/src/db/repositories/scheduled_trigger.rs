@@ -0,0 +1,162 @@
+// Scheduled trigger repository implementation
+// Purpose: One-off "run at" executions dispatched by the scheduler
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::{ScheduledTrigger, ScheduledTriggerStatus};
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+use uuid::Uuid;
+
+const SCHEDULED_TRIGGER_COLUMNS: &str = "id, job_id, execute_at, variables, callback, status, \
+     execution_id, error, created_by, created_at, dispatched_at";
+
+/// Repository for scheduled trigger database operations
+#[derive(Clone)]
+pub struct ScheduledTriggerRepository {
+    pool: DbPool,
+}
+
+impl ScheduledTriggerRepository {
+    /// Create a new ScheduledTriggerRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Insert a new pending trigger
+    #[instrument(skip(self, trigger), fields(trigger_id = %trigger.id, job_id = %trigger.job_id))]
+    pub async fn create(&self, trigger: &ScheduledTrigger) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_triggers (
+                id, job_id, execute_at, variables, callback, status, created_by, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(trigger.id)
+        .bind(trigger.job_id)
+        .bind(trigger.execute_at)
+        .bind(serde_json::to_value(&trigger.variables).unwrap_or_default())
+        .bind(&trigger.callback)
+        .bind(trigger.status.to_string())
+        .bind(&trigger.created_by)
+        .bind(trigger.created_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        tracing::info!(execute_at = %trigger.execute_at, "Scheduled trigger created");
+        Ok(())
+    }
+
+    /// List a job's triggers, most recent first
+    #[instrument(skip(self))]
+    pub async fn find_by_job_id(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<ScheduledTrigger>, DatabaseError> {
+        let triggers = sqlx::query_as::<_, ScheduledTrigger>(&format!(
+            "SELECT {} FROM scheduled_triggers WHERE job_id = $1 ORDER BY execute_at DESC",
+            SCHEDULED_TRIGGER_COLUMNS
+        ))
+        .bind(job_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(triggers)
+    }
+
+    /// Cancel a pending trigger, returning whether one was cancelled
+    #[instrument(skip(self))]
+    pub async fn cancel(&self, job_id: Uuid, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE scheduled_triggers
+            SET status = $3
+            WHERE id = $1 AND job_id = $2 AND status = $4
+            "#,
+        )
+        .bind(id)
+        .bind(job_id)
+        .bind(ScheduledTriggerStatus::Cancelled.to_string())
+        .bind(ScheduledTriggerStatus::Pending.to_string())
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Claim pending triggers whose time has come
+    ///
+    /// Claimed rows are marked dispatched in the same statement, and locked rows are
+    /// skipped, so concurrent scheduler nodes never dispatch a trigger twice.
+    #[instrument(skip(self))]
+    pub async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<ScheduledTrigger>, DatabaseError> {
+        let triggers = sqlx::query_as::<_, ScheduledTrigger>(&format!(
+            r#"
+            UPDATE scheduled_triggers
+            SET status = $3, dispatched_at = $1
+            WHERE id IN (
+                SELECT id FROM scheduled_triggers
+                WHERE status = $4 AND execute_at <= $1
+                ORDER BY execute_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            SCHEDULED_TRIGGER_COLUMNS
+        ))
+        .bind(now)
+        .bind(limit)
+        .bind(ScheduledTriggerStatus::Dispatched.to_string())
+        .bind(ScheduledTriggerStatus::Pending.to_string())
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(triggers)
+    }
+
+    /// Record the execution created for a claimed trigger
+    #[instrument(skip(self))]
+    pub async fn mark_dispatched(&self, id: Uuid, execution_id: Uuid) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE scheduled_triggers SET execution_id = $2 WHERE id = $1")
+            .bind(id)
+            .bind(execution_id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Return a claimed trigger to pending so the next poll retries it
+    #[instrument(skip(self))]
+    pub async fn release(&self, id: Uuid) -> Result<(), DatabaseError> {
+        sqlx::query(
+            "UPDATE scheduled_triggers SET status = $2, dispatched_at = NULL WHERE id = $1",
+        )
+        .bind(id)
+        .bind(ScheduledTriggerStatus::Pending.to_string())
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Mark a claimed trigger as failed
+    #[instrument(skip(self))]
+    pub async fn mark_failed(&self, id: Uuid, error: &str) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE scheduled_triggers SET status = $2, error = $3 WHERE id = $1")
+            .bind(id)
+            .bind(ScheduledTriggerStatus::Failed.to_string())
+            .bind(error)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+}
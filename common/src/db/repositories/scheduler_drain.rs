@@ -0,0 +1,71 @@
+// Scheduler drain repository implementation
+// Purpose: Drained scheduler nodes, read by the schedulers on every tick
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use tracing::instrument;
+
+/// Node of the drain that applies to every scheduler node
+pub const ALL_NODES: &str = "*";
+
+/// Repository for scheduler drains
+#[derive(Clone)]
+pub struct SchedulerDrainRepository {
+    pool: DbPool,
+}
+
+impl SchedulerDrainRepository {
+    /// Create a new SchedulerDrainRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Drain one node, or every node when `node` is None
+    #[instrument(skip(self))]
+    pub async fn drain(&self, node: Option<&str>, drained_by: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO scheduler_drains (node, drained_by)
+            VALUES ($1, $2)
+            ON CONFLICT (node) DO UPDATE SET drained_by = $2, drained_at = NOW()
+            "#,
+        )
+        .bind(node.unwrap_or(ALL_NODES))
+        .bind(drained_by)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Resume one node, or every node when `node` is None
+    #[instrument(skip(self))]
+    pub async fn resume(&self, node: Option<&str>) -> Result<(), DatabaseError> {
+        sqlx::query("DELETE FROM scheduler_drains WHERE $1::TEXT IS NULL OR node = $1")
+            .bind(node)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether every node is drained
+    #[instrument(skip(self))]
+    pub async fn all_drained(&self) -> Result<bool, DatabaseError> {
+        self.is_drained(ALL_NODES).await
+    }
+
+    /// Whether the node is drained, by itself or with every node
+    #[instrument(skip(self))]
+    pub async fn is_drained(&self, node: &str) -> Result<bool, DatabaseError> {
+        let drained: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM scheduler_drains WHERE node IN ($1, $2))",
+        )
+        .bind(node)
+        .bind(ALL_NODES)
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        Ok(drained)
+    }
+}
@@ -0,0 +1,148 @@
+// Scheduler journal repository implementation
+// Purpose: Ring table of per-tick scheduler decisions
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::{JobDecision, SchedulerTick, TickStatus};
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::FromRow;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Filter for listing journal ticks
+#[derive(Debug, Clone, Default)]
+pub struct SchedulerJournalFilter {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Ticks that decided about this job, plus ticks that evaluated no jobs
+    /// (standby, clock skew, failed) since those explain a job not firing too
+    pub job_id: Option<Uuid>,
+    pub node: Option<String>,
+    pub limit: i64,
+}
+
+#[derive(FromRow)]
+struct TickRow {
+    id: i64,
+    node: String,
+    started_at: DateTime<Utc>,
+    duration_ms: i64,
+    #[sqlx(try_from = "String")]
+    status: TickStatus,
+    jobs_due: i32,
+    jobs_fired: i32,
+    jobs_skipped: i32,
+    decisions: Json<Vec<JobDecision>>,
+    error: Option<String>,
+}
+
+impl From<TickRow> for SchedulerTick {
+    fn from(row: TickRow) -> Self {
+        Self {
+            id: row.id,
+            node: row.node,
+            started_at: row.started_at,
+            duration_ms: row.duration_ms,
+            status: row.status,
+            jobs_due: row.jobs_due,
+            jobs_fired: row.jobs_fired,
+            jobs_skipped: row.jobs_skipped,
+            decisions: row.decisions.0,
+            error: row.error,
+        }
+    }
+}
+
+/// Repository for the scheduler decision journal
+#[derive(Clone)]
+pub struct SchedulerJournalRepository {
+    pool: DbPool,
+}
+
+impl SchedulerJournalRepository {
+    /// Create a new SchedulerJournalRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Append a tick and trim the journal to the newest `max_ticks` rows
+    #[instrument(skip(self, tick), fields(node = %tick.node, status = %tick.status))]
+    pub async fn record(&self, tick: &SchedulerTick, max_ticks: i64) -> Result<i64, DatabaseError> {
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO scheduler_journal (
+                node, started_at, duration_ms, status, jobs_due, jobs_fired, jobs_skipped,
+                decisions, error
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+        )
+        .bind(&tick.node)
+        .bind(tick.started_at)
+        .bind(tick.duration_ms)
+        .bind(tick.status.to_string())
+        .bind(tick.jobs_due)
+        .bind(tick.jobs_fired)
+        .bind(tick.jobs_skipped)
+        .bind(Json(&tick.decisions))
+        .bind(&tick.error)
+        .fetch_one(self.pool.pool())
+        .await?;
+
+        // Ids are only approximately dense across nodes, which is close enough for a ring
+        sqlx::query("DELETE FROM scheduler_journal WHERE id <= $1")
+            .bind(id - max_ticks)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(id)
+    }
+
+    /// List ticks, most recent first
+    #[instrument(skip(self))]
+    pub async fn list(
+        &self,
+        filter: &SchedulerJournalFilter,
+    ) -> Result<Vec<SchedulerTick>, DatabaseError> {
+        let job_filter = filter
+            .job_id
+            .map(|job_id| serde_json::json!([{ "job_id": job_id }]));
+
+        let rows = sqlx::query_as::<_, TickRow>(
+            r#"
+            SELECT id, node, started_at, duration_ms, status, jobs_due, jobs_fired,
+                   jobs_skipped, decisions, error
+            FROM scheduler_journal
+            WHERE ($1::TIMESTAMPTZ IS NULL OR started_at >= $1)
+                AND ($2::TIMESTAMPTZ IS NULL OR started_at < $2)
+                AND ($3::JSONB IS NULL OR decisions @> $3 OR status <> 'ran')
+                AND ($4::TEXT IS NULL OR node = $4)
+            ORDER BY started_at DESC, id DESC
+            LIMIT $5
+            "#,
+        )
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(job_filter)
+        .bind(&filter.node)
+        .bind(filter.limit)
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        Ok(rows.into_iter().map(SchedulerTick::from).collect())
+    }
+
+    /// Whether a node with this id has journaled a tick
+    #[instrument(skip(self))]
+    pub async fn has_node(&self, node: &str) -> Result<bool, DatabaseError> {
+        let found: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM scheduler_journal WHERE node = $1)")
+                .bind(node)
+                .fetch_one(self.pool.read_pool())
+                .await?;
+
+        Ok(found)
+    }
+}
@@ -0,0 +1,128 @@
+// Session repository implementation
+// Purpose: Logins in database authentication mode, so single sessions can be listed
+// and revoked
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::UserSession;
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+use uuid::Uuid;
+
+const SELECT_COLUMNS: &str =
+    "id, user_id, user_agent, ip_address, created_at, last_seen_at, expires_at, revoked_at";
+
+/// Repository for user sessions
+#[derive(Clone)]
+pub struct SessionRepository {
+    pool: DbPool,
+}
+
+impl SessionRepository {
+    /// Create a new SessionRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a new session
+    #[instrument(skip(self, session), fields(session_id = %session.id, user_id = %session.user_id))]
+    pub async fn create(&self, session: &UserSession) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_sessions (
+                id, user_id, user_agent, ip_address, created_at,
+                last_seen_at, expires_at, revoked_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(session.id)
+        .bind(session.user_id)
+        .bind(&session.user_agent)
+        .bind(&session.ip_address)
+        .bind(session.created_at)
+        .bind(session.last_seen_at)
+        .bind(session.expires_at)
+        .bind(session.revoked_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find a session by id
+    ///
+    /// Reads the primary so a session is found right after login and a revocation
+    /// takes effect immediately.
+    #[instrument(skip(self))]
+    pub async fn find(&self, id: Uuid) -> Result<Option<UserSession>, DatabaseError> {
+        let session = sqlx::query_as::<_, UserSession>(&format!(
+            "SELECT {} FROM user_sessions WHERE id = $1",
+            SELECT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(session)
+    }
+
+    /// Sessions of a user that are neither revoked nor expired, most recently used first
+    #[instrument(skip(self))]
+    pub async fn list_active(&self, user_id: Uuid) -> Result<Vec<UserSession>, DatabaseError> {
+        let sessions = sqlx::query_as::<_, UserSession>(&format!(
+            r#"
+            SELECT {} FROM user_sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            ORDER BY last_seen_at DESC
+            "#,
+            SELECT_COLUMNS
+        ))
+        .bind(user_id)
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        Ok(sessions)
+    }
+
+    /// Record an authenticated request on a session
+    #[instrument(skip(self))]
+    pub async fn touch(&self, id: Uuid) -> Result<(), DatabaseError> {
+        sqlx::query("UPDATE user_sessions SET last_seen_at = NOW() WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Move the expiry of an active session forward when its token is refreshed
+    #[instrument(skip(self))]
+    pub async fn extend(&self, id: Uuid, expires_at: DateTime<Utc>) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_sessions SET expires_at = $2, last_seen_at = NOW()
+            WHERE id = $1 AND revoked_at IS NULL AND expires_at > NOW()
+            "#,
+        )
+        .bind(id)
+        .bind(expires_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Revoke a session; returns false if it doesn't exist or was already revoked
+    #[instrument(skip(self))]
+    pub async fn revoke(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE user_sessions SET revoked_at = NOW() WHERE id = $1 AND revoked_at IS NULL",
+        )
+        .bind(id)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,212 @@
+// Execution statistics rollup repository
+// Purpose: Maintain hourly/daily per-job aggregates and serve dashboard statistics from them
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use tracing::instrument;
+
+/// Aggregated execution counts and durations over a time range
+#[derive(Debug, Clone, Default, Serialize, FromRow)]
+pub struct ExecutionTotals {
+    pub total_executions: i64,
+    pub successful_executions: i64,
+    pub failed_executions: i64,
+    /// Executions with a start and end time, whose durations `total_duration_ms` sums
+    pub timed_executions: i64,
+    pub total_duration_ms: i64,
+    pub max_duration_ms: i64,
+}
+
+impl ExecutionTotals {
+    /// Success rate in percent, `None` when nothing ran
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.total_executions > 0)
+            .then(|| self.successful_executions as f64 / self.total_executions as f64 * 100.0)
+    }
+
+    /// Mean duration of the executions that have one, in milliseconds
+    pub fn average_duration_ms(&self) -> Option<i64> {
+        (self.timed_executions > 0).then(|| self.total_duration_ms / self.timed_executions)
+    }
+}
+
+/// Repository for execution statistics rollups
+#[derive(Clone)]
+pub struct StatsRepository {
+    pool: DbPool,
+}
+
+impl StatsRepository {
+    /// Create a new StatsRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Recompute hourly buckets for executions finished since `since`
+    ///
+    /// Executions are counted in the hour they finished in, and only when they ran
+    /// to success or failure, so the success and failure counts add up to the total;
+    /// cancelled, expired and rejected executions are left out. The buckets from
+    /// `since` on are replaced in one transaction, so the rollup is idempotent and
+    /// a bucket whose executions all moved out doesn't keep stale counts.
+    #[instrument(skip(self))]
+    pub async fn refresh_hourly(&self, since: DateTime<Utc>) -> Result<u64, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query(
+            "DELETE FROM execution_stats_hourly WHERE bucket_start >= date_trunc('hour', $1::timestamptz)",
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO execution_stats_hourly (
+                job_id, bucket_start, total_executions, successful_executions,
+                failed_executions, timed_executions, total_duration_ms, max_duration_ms,
+                updated_at
+            )
+            SELECT
+                job_id,
+                date_trunc('hour', completed_at) AS bucket_start,
+                COUNT(*),
+                COUNT(*) FILTER (WHERE status = 'success'),
+                COUNT(*) FILTER (WHERE status IN ('failed', 'timeout', 'dead_letter')),
+                COUNT(started_at),
+                COALESCE(SUM(EXTRACT(EPOCH FROM (completed_at - started_at)) * 1000), 0)::BIGINT,
+                COALESCE(MAX(EXTRACT(EPOCH FROM (completed_at - started_at)) * 1000), 0)::BIGINT,
+                NOW()
+            FROM job_executions
+            WHERE completed_at >= date_trunc('hour', $1::timestamptz)
+              AND status IN ('success', 'failed', 'timeout', 'dead_letter')
+            GROUP BY job_id, date_trunc('hour', completed_at)
+            "#,
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Recompute daily buckets from the hourly rollup for days since `since`
+    ///
+    /// Like the hourly buckets, the days from `since` on are replaced in one
+    /// transaction.
+    #[instrument(skip(self))]
+    pub async fn refresh_daily(&self, since: DateTime<Utc>) -> Result<u64, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query(
+            "DELETE FROM execution_stats_daily WHERE bucket_start >= date_trunc('day', $1::timestamptz, 'UTC')",
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO execution_stats_daily (
+                job_id, bucket_start, total_executions, successful_executions,
+                failed_executions, timed_executions, total_duration_ms, max_duration_ms,
+                updated_at
+            )
+            SELECT
+                job_id,
+                date_trunc('day', bucket_start, 'UTC') AS day_start,
+                SUM(total_executions),
+                SUM(successful_executions),
+                SUM(failed_executions),
+                SUM(timed_executions),
+                SUM(total_duration_ms),
+                MAX(max_duration_ms),
+                NOW()
+            FROM execution_stats_hourly
+            WHERE bucket_start >= date_trunc('day', $1::timestamptz, 'UTC')
+            GROUP BY job_id, date_trunc('day', bucket_start, 'UTC')
+            "#,
+        )
+        .bind(since)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Totals across all jobs from hourly buckets starting at or after `since`
+    #[instrument(skip(self))]
+    pub async fn hourly_totals_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<ExecutionTotals, DatabaseError> {
+        self.totals_since("execution_stats_hourly", since).await
+    }
+
+    /// Totals across all jobs from daily buckets starting at or after `since`
+    #[instrument(skip(self))]
+    pub async fn daily_totals_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<ExecutionTotals, DatabaseError> {
+        self.totals_since("execution_stats_daily", since).await
+    }
+
+    async fn totals_since(
+        &self,
+        table: &str,
+        since: DateTime<Utc>,
+    ) -> Result<ExecutionTotals, DatabaseError> {
+        let query = format!(
+            r#"
+            SELECT
+                COALESCE(SUM(total_executions), 0)::BIGINT AS total_executions,
+                COALESCE(SUM(successful_executions), 0)::BIGINT AS successful_executions,
+                COALESCE(SUM(failed_executions), 0)::BIGINT AS failed_executions,
+                COALESCE(SUM(timed_executions), 0)::BIGINT AS timed_executions,
+                COALESCE(SUM(total_duration_ms), 0)::BIGINT AS total_duration_ms,
+                COALESCE(MAX(max_duration_ms), 0)::BIGINT AS max_duration_ms
+            FROM {}
+            WHERE bucket_start >= $1
+            "#,
+            table
+        );
+
+        let totals = sqlx::query_as::<_, ExecutionTotals>(&query)
+            .bind(since)
+            .fetch_one(self.pool.read_pool())
+            .await?;
+
+        Ok(totals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_totals_rates() {
+        let totals = ExecutionTotals {
+            total_executions: 8,
+            successful_executions: 6,
+            failed_executions: 2,
+            timed_executions: 5,
+            total_duration_ms: 4000,
+            max_duration_ms: 1200,
+        };
+        assert_eq!(totals.success_rate(), Some(75.0));
+        assert_eq!(totals.average_duration_ms(), Some(800));
+
+        let empty = ExecutionTotals::default();
+        assert_eq!(empty.success_rate(), None);
+        assert_eq!(empty.average_duration_ms(), None);
+    }
+}
@@ -0,0 +1,188 @@
+// Status page repository implementation
+// Purpose: Token-scoped public status pages and the job statuses they show
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::ExecutionStatus;
+use crate::status_page::{PublicJobStatus, StatusPage};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::Row;
+use tracing::instrument;
+use uuid::Uuid;
+
+const SELECT_COLUMNS: &str = "id, name, job_ids, created_by, created_at, updated_at";
+
+/// Repository for public status pages
+#[derive(Clone)]
+pub struct StatusPageRepository {
+    pool: DbPool,
+}
+
+impl StatusPageRepository {
+    /// Create a new StatusPageRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Save a new status page with the hash of its token
+    #[instrument(skip(self, page, token_hash), fields(status_page_id = %page.id))]
+    pub async fn create(&self, page: &StatusPage, token_hash: &str) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO status_pages (id, name, token_hash, job_ids, created_by, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(page.id)
+        .bind(&page.name)
+        .bind(token_hash)
+        .bind(&page.job_ids)
+        .bind(&page.created_by)
+        .bind(page.created_at)
+        .bind(page.updated_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Find a status page by id
+    #[instrument(skip(self))]
+    pub async fn find(&self, id: Uuid) -> Result<Option<StatusPage>, DatabaseError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM status_pages WHERE id = $1",
+            SELECT_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.read_pool())
+        .await?;
+
+        row.map(|row| map_row(&row)).transpose()
+    }
+
+    /// Find the status page a token opens
+    #[instrument(skip(self, token_hash))]
+    pub async fn find_by_token_hash(
+        &self,
+        token_hash: &str,
+    ) -> Result<Option<StatusPage>, DatabaseError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM status_pages WHERE token_hash = $1",
+            SELECT_COLUMNS
+        ))
+        .bind(token_hash)
+        .fetch_optional(self.pool.read_pool())
+        .await?;
+
+        row.map(|row| map_row(&row)).transpose()
+    }
+
+    /// All status pages, by name
+    #[instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<StatusPage>, DatabaseError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM status_pages ORDER BY name",
+            SELECT_COLUMNS
+        ))
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        rows.iter().map(map_row).collect()
+    }
+
+    /// Replace the name and jobs of a status page
+    #[instrument(skip(self, page), fields(status_page_id = %page.id))]
+    pub async fn update(&self, page: &StatusPage) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE status_pages SET name = $2, job_ids = $3, updated_at = $4 WHERE id = $1",
+        )
+        .bind(page.id)
+        .bind(&page.name)
+        .bind(&page.job_ids)
+        .bind(page.updated_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Replace the token of a status page; links with the old token stop working
+    #[instrument(skip(self, token_hash))]
+    pub async fn rotate_token(&self, id: Uuid, token_hash: &str) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            "UPDATE status_pages SET token_hash = $2, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .bind(token_hash)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Delete a status page
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query("DELETE FROM status_pages WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Latest status of each job, in the order given; deleted jobs are left out
+    #[instrument(skip(self, job_ids), fields(jobs = job_ids.len()))]
+    pub async fn job_statuses(
+        &self,
+        job_ids: &[Uuid],
+    ) -> Result<Vec<PublicJobStatus>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT j.name, latest.status, latest.created_at AS last_run_at, s.last_success_at
+            FROM UNNEST($1::UUID[]) WITH ORDINALITY AS page(job_id, position)
+            JOIN jobs j ON j.id = page.job_id
+            LEFT JOIN LATERAL (
+                SELECT status, created_at
+                FROM job_executions
+                WHERE job_id = j.id
+                ORDER BY created_at DESC
+                LIMIT 1
+            ) latest ON TRUE
+            LEFT JOIN job_stats s ON s.job_id = j.id
+            ORDER BY page.position
+            "#,
+        )
+        .bind(job_ids)
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let status: Option<String> = row.try_get("status")?;
+                let last_run_at: Option<DateTime<Utc>> = row.try_get("last_run_at")?;
+                let last_success_at: Option<DateTime<Utc>> = row.try_get("last_success_at")?;
+                Ok(PublicJobStatus {
+                    name: row.try_get("name")?,
+                    status: status.and_then(|s| s.parse::<ExecutionStatus>().ok()),
+                    last_run_at,
+                    last_success_at,
+                })
+            })
+            .collect()
+    }
+}
+
+fn map_row(row: &PgRow) -> Result<StatusPage, DatabaseError> {
+    let created_at: DateTime<Utc> = row.try_get("created_at")?;
+    let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
+    Ok(StatusPage {
+        id: row.try_get("id")?,
+        name: row.try_get("name")?,
+        job_ids: row.try_get("job_ids")?,
+        created_by: row.try_get("created_by")?,
+        created_at,
+        updated_at,
+    })
+}
@@ -0,0 +1,355 @@
+// System job repository implementation
+// Purpose: Registration of the built-in system jobs and the queries their
+// maintenance tasks run
+
+use super::job::insert_job;
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::Job;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::Row;
+use std::collections::HashSet;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Dead-lettered executions of one job
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetterSummary {
+    pub job_id: Uuid,
+    pub job_name: String,
+    pub count: i64,
+    pub oldest_at: DateTime<Utc>,
+}
+
+/// A sensitive variable due for rotation
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleSecret {
+    pub name: String,
+    /// `global` or `job`
+    pub scope_type: String,
+    pub scope_id: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A finished execution that may hold PII-tagged step outputs
+#[derive(Debug, Clone)]
+pub struct PiiPurgeCandidate {
+    pub execution_id: Uuid,
+    pub job_id: Uuid,
+    /// Result and error of the execution, which may quote the outputs
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A registered system job and when it last succeeded
+#[derive(Debug, Clone)]
+pub struct SystemJobStatus {
+    pub job_id: Uuid,
+    pub job_name: String,
+    pub enabled: bool,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Repository for the built-in system jobs
+#[derive(Clone)]
+pub struct SystemJobRepository {
+    pool: DbPool,
+}
+
+impl SystemJobRepository {
+    /// Create a new SystemJobRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Create the job unless a job was ever registered under `key`
+    ///
+    /// The registration and the job are written in one transaction, so concurrent
+    /// boots create the job once. Returns false when the key was already registered.
+    #[instrument(skip(self, job), fields(job_name = %job.name))]
+    pub async fn register(&self, key: &str, job: &Job) -> Result<bool, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO system_job_registrations (key, job_id)
+            VALUES ($1, $2)
+            ON CONFLICT (key) DO NOTHING
+            "#,
+        )
+        .bind(key)
+        .bind(job.id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected()
+            > 0;
+        if !inserted {
+            return Ok(false);
+        }
+
+        insert_job(&mut *tx, job).await?;
+        tx.commit().await?;
+
+        tracing::info!(key = key, job_id = %job.id, "System job registered");
+        Ok(true)
+    }
+
+    /// The job registered under `key`, if it still exists, with its latest success
+    #[instrument(skip(self))]
+    pub async fn status(&self, key: &str) -> Result<Option<SystemJobStatus>, DatabaseError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                j.id, j.name, j.enabled, j.updated_at,
+                (SELECT MAX(e.completed_at) FROM job_executions e
+                 WHERE e.job_id = j.id AND e.status = 'success') AS last_success_at
+            FROM system_job_registrations r
+            JOIN jobs j ON j.id = r.job_id
+            WHERE r.key = $1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        row.map(|row| {
+            Ok(SystemJobStatus {
+                job_id: row.try_get("id")?,
+                job_name: row.try_get("name")?,
+                enabled: row.try_get("enabled")?,
+                last_success_at: row.try_get("last_success_at")?,
+                updated_at: row.try_get("updated_at")?,
+            })
+        })
+        .transpose()
+    }
+
+    /// Dead-lettered executions grouped by job, largest backlog first
+    #[instrument(skip(self))]
+    pub async fn dead_letter_summary(&self) -> Result<Vec<DeadLetterSummary>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT e.job_id, j.name, COUNT(*) AS count, MIN(e.created_at) AS oldest_at
+            FROM job_executions e
+            JOIN jobs j ON j.id = e.job_id
+            WHERE e.status = 'dead_letter'
+            GROUP BY e.job_id, j.name
+            ORDER BY count DESC, j.name
+            "#,
+        )
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(DeadLetterSummary {
+                    job_id: row.try_get("job_id")?,
+                    job_name: row.try_get("name")?,
+                    count: row.try_get("count")?,
+                    oldest_at: row.try_get("oldest_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Delete finished executions completed before `before`, `batch_size` rows at a time
+    ///
+    /// Dead-lettered executions are kept until someone deals with them. Their status
+    /// history goes with them. Returns the number of executions deleted.
+    #[instrument(skip(self))]
+    pub async fn delete_finished_executions(
+        &self,
+        before: DateTime<Utc>,
+        batch_size: i64,
+    ) -> Result<u64, DatabaseError> {
+        let mut deleted = 0;
+        loop {
+            let batch = sqlx::query(
+                r#"
+                DELETE FROM job_executions
+                WHERE id IN (
+                    SELECT id FROM job_executions
+                    WHERE status IN ('success', 'failed', 'timeout', 'cancelled', 'expired', 'rejected')
+                      AND completed_at < $1
+                    LIMIT $2
+                )
+                "#,
+            )
+            .bind(before)
+            .bind(batch_size)
+            .execute(self.pool.pool())
+            .await?
+            .rows_affected();
+            deleted += batch;
+            if batch < batch_size as u64 {
+                return Ok(deleted);
+            }
+        }
+    }
+
+    /// Delete webhooks delivered before `before`, with their attempt logs
+    #[instrument(skip(self))]
+    pub async fn delete_delivered_webhooks(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<u64, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM webhook_deliveries
+            WHERE status = 'delivered' AND delivered_at < $1
+            "#,
+        )
+        .bind(before)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Finished executions completed before `before` whose PII hasn't been purged,
+    /// oldest first
+    ///
+    /// Contexts kept in tenant storage are only a pointer in PostgreSQL, so all of
+    /// them are candidates and the purge reads them to find out.
+    #[instrument(skip(self))]
+    pub async fn pii_purge_candidates(
+        &self,
+        before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PiiPurgeCandidate>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, job_id, result, error FROM job_executions
+            WHERE status IN ('success', 'failed', 'timeout', 'cancelled', 'expired', 'dead_letter')
+              AND completed_at < $1
+              AND pii_purged_at IS NULL
+              AND (context ? 'pii_steps' OR context ? 'residency')
+            ORDER BY completed_at
+            LIMIT $2
+            "#,
+        )
+        .bind(before)
+        .bind(limit)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(PiiPurgeCandidate {
+                    execution_id: row.try_get("id")?,
+                    job_id: row.try_get("job_id")?,
+                    result: row.try_get("result")?,
+                    error: row.try_get("error")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Mark an execution's PII as purged, saving the candidate's result and error as
+    /// the purge redacted them, and add the purge to the audit trail
+    ///
+    /// Executions without PII-tagged outputs are only marked.
+    #[instrument(skip(self, step_ids))]
+    pub async fn record_pii_purge(
+        &self,
+        candidate: &PiiPurgeCandidate,
+        step_ids: &[String],
+        action: &str,
+    ) -> Result<(), DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query(
+            "UPDATE job_executions SET pii_purged_at = NOW(), result = $2, error = $3 WHERE id = $1",
+        )
+        .bind(candidate.execution_id)
+        .bind(&candidate.result)
+        .bind(&candidate.error)
+        .execute(&mut *tx)
+        .await?;
+        if !step_ids.is_empty() {
+            sqlx::query(
+                r#"
+                INSERT INTO pii_purges (execution_id, job_id, step_ids, action)
+                VALUES ($1, $2, $3, $4)
+                "#,
+            )
+            .bind(candidate.execution_id)
+            .bind(candidate.job_id)
+            .bind(step_ids)
+            .bind(action)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Which of `ids` are jobs that still exist
+    #[instrument(skip(self, ids), fields(count = ids.len()))]
+    pub async fn existing_job_ids(&self, ids: &[Uuid]) -> Result<HashSet<Uuid>, DatabaseError> {
+        let rows = sqlx::query("SELECT id FROM jobs WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch_all(self.pool.pool())
+            .await?;
+
+        rows.iter()
+            .map(|row| row.try_get("id").map_err(DatabaseError::from))
+            .collect()
+    }
+
+    /// Which of `ids` are executions that still exist
+    #[instrument(skip(self, ids), fields(count = ids.len()))]
+    pub async fn existing_execution_ids(
+        &self,
+        ids: &[Uuid],
+    ) -> Result<HashSet<Uuid>, DatabaseError> {
+        let rows = sqlx::query("SELECT id FROM job_executions WHERE id = ANY($1)")
+            .bind(ids)
+            .fetch_all(self.pool.pool())
+            .await?;
+
+        rows.iter()
+            .map(|row| row.try_get("id").map_err(DatabaseError::from))
+            .collect()
+    }
+
+    /// Sensitive variables last changed before `before`, oldest first
+    #[instrument(skip(self))]
+    pub async fn stale_secrets(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<StaleSecret>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT name, scope_type, scope_id, updated_at
+            FROM variables
+            WHERE is_sensitive = true AND updated_at < $1
+            ORDER BY updated_at
+            "#,
+        )
+        .bind(before)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(StaleSecret {
+                    name: row.try_get("name")?,
+                    scope_type: row.try_get("scope_type")?,
+                    scope_id: row.try_get("scope_id")?,
+                    updated_at: row.try_get("updated_at")?,
+                })
+            })
+            .collect()
+    }
+
+    /// Check the database answers queries
+    #[instrument(skip(self))]
+    pub async fn ping(&self) -> Result<(), DatabaseError> {
+        sqlx::query("SELECT 1").execute(self.pool.pool()).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+// TLS material repository implementation
+// Purpose: Certificates and keys whose expiry is tracked, and the credentials of any
+// kind that expire before a given time
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::{CredentialExpiry, CredentialSource, TlsMaterial};
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use tracing::instrument;
+use uuid::Uuid;
+
+const TLS_MATERIAL_COLUMNS: &str =
+    "id, name, kind, description, expires_at, created_at, updated_at";
+
+/// Repository for tracked TLS materials
+#[derive(Clone)]
+pub struct TlsMaterialRepository {
+    pool: DbPool,
+}
+
+impl TlsMaterialRepository {
+    /// Create a new TlsMaterialRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Start tracking a certificate or key
+    #[instrument(skip(self, material), fields(name = %material.name))]
+    pub async fn create(&self, material: &TlsMaterial) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO tls_materials (
+                id, name, kind, description, expires_at, created_at, updated_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(material.id)
+        .bind(&material.name)
+        .bind(material.kind.to_string())
+        .bind(&material.description)
+        .bind(material.expires_at)
+        .bind(material.created_at)
+        .bind(material.updated_at)
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// All tracked materials, soonest expiry first
+    #[instrument(skip(self))]
+    pub async fn list(&self) -> Result<Vec<TlsMaterial>, DatabaseError> {
+        let materials = sqlx::query_as::<_, TlsMaterial>(&format!(
+            "SELECT {} FROM tls_materials ORDER BY expires_at, name",
+            TLS_MATERIAL_COLUMNS
+        ))
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(materials)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<TlsMaterial>, DatabaseError> {
+        let material = sqlx::query_as::<_, TlsMaterial>(&format!(
+            "SELECT {} FROM tls_materials WHERE id = $1",
+            TLS_MATERIAL_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(material)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<TlsMaterial>, DatabaseError> {
+        let material = sqlx::query_as::<_, TlsMaterial>(&format!(
+            "SELECT {} FROM tls_materials WHERE name = $1",
+            TLS_MATERIAL_COLUMNS
+        ))
+        .bind(name)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(material)
+    }
+
+    /// Update a material, typically with the expiry of its renewed replacement
+    #[instrument(skip(self, material), fields(id = %material.id))]
+    pub async fn update(&self, material: &TlsMaterial) -> Result<(), DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE tls_materials
+            SET name = $2,
+                kind = $3,
+                description = $4,
+                expires_at = $5,
+                updated_at = $6
+            WHERE id = $1
+            "#,
+        )
+        .bind(material.id)
+        .bind(&material.name)
+        .bind(material.kind.to_string())
+        .bind(&material.description)
+        .bind(material.expires_at)
+        .bind(Utc::now())
+        .execute(self.pool.pool())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound(format!(
+                "TLS material not found: {}",
+                material.id
+            )));
+        }
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: Uuid) -> Result<(), DatabaseError> {
+        let result = sqlx::query("DELETE FROM tls_materials WHERE id = $1")
+            .bind(id)
+            .execute(self.pool.pool())
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(DatabaseError::NotFound(format!(
+                "TLS material not found: {}",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Variables with an expiry and TLS materials expiring before `before`, soonest
+    /// first; already expired ones are included
+    #[instrument(skip(self))]
+    pub async fn expiring_credentials(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<CredentialExpiry>, DatabaseError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT 'variable' AS source, id, name, NULL::VARCHAR AS kind, expires_at
+            FROM variables
+            WHERE expires_at IS NOT NULL AND expires_at < $1
+            UNION ALL
+            SELECT 'tls_material' AS source, id, name, kind, expires_at
+            FROM tls_materials
+            WHERE expires_at < $1
+            ORDER BY expires_at, name
+            "#,
+        )
+        .bind(before)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let source = match row.try_get::<String, _>("source")?.as_str() {
+                    "variable" => CredentialSource::Variable,
+                    _ => CredentialSource::TlsMaterial,
+                };
+                let kind = row
+                    .try_get::<Option<String>, _>("kind")?
+                    .map(|kind| kind.parse())
+                    .transpose()
+                    .map_err(|e: String| DatabaseError::QueryFailed(e))?;
+                Ok(CredentialExpiry {
+                    source,
+                    id: Some(row.try_get("id")?),
+                    name: row.try_get("name")?,
+                    kind,
+                    expires_at: row.try_get("expires_at")?,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,123 @@
+// Usage repository implementation
+// Purpose: Per-tenant usage for internal chargeback. Executions, compute time and
+// stored context size come from job_executions; notifications from notification_events
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::ExecutionStatus;
+use crate::usage::NotificationKind;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Usage of one tenant over a period; `tenant_id` is `None` for jobs created without one
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct TenantUsage {
+    pub tenant_id: Option<String>,
+    pub executions: i64,
+    /// Sum of the run times of finished executions, from start to completion,
+    /// whatever they finished with
+    pub compute_seconds: f64,
+    /// Size of the stored execution contexts
+    pub storage_bytes: i64,
+    pub notifications: i64,
+}
+
+/// Repository for tenant usage
+#[derive(Clone)]
+pub struct UsageRepository {
+    pool: DbPool,
+}
+
+impl UsageRepository {
+    /// Create a new UsageRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a notification sent for a job, billed to the job's current tenant
+    #[instrument(skip(self))]
+    pub async fn record_notification(
+        &self,
+        job_id: Uuid,
+        kind: NotificationKind,
+    ) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO notification_events (job_id, tenant_id, kind)
+            VALUES ($1, (SELECT tenant_id FROM jobs WHERE id = $1), $2)
+            "#,
+        )
+        .bind(job_id)
+        .bind(kind.as_str())
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Usage per tenant for executions created and notifications sent in `[from, to)`
+    ///
+    /// With `tenant_id`, only that tenant's row is returned. Compute time is metered for
+    /// every terminal status, so failed, timed-out and cancelled runs are billed too.
+    #[instrument(skip(self))]
+    pub async fn tenant_usage(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        tenant_id: Option<&str>,
+    ) -> Result<Vec<TenantUsage>, DatabaseError> {
+        // Untenanted rows are keyed by '' so both sides can be full-joined on equality
+        let usage = sqlx::query_as::<_, TenantUsage>(
+            r#"
+            WITH executions AS (
+                SELECT
+                    COALESCE(j.tenant_id, '') AS tenant_key,
+                    COUNT(*) AS executions,
+                    COALESCE(
+                        SUM(EXTRACT(EPOCH FROM (e.completed_at - e.started_at)))
+                            FILTER (WHERE e.status = ANY($4)),
+                        0
+                    )::FLOAT8 AS compute_seconds,
+                    COALESCE(SUM(pg_column_size(e.context)), 0)::BIGINT AS storage_bytes
+                FROM job_executions e
+                JOIN jobs j ON j.id = e.job_id
+                WHERE e.created_at >= $1 AND e.created_at < $2
+                    AND ($3::TEXT IS NULL OR j.tenant_id = $3)
+                GROUP BY 1
+            ),
+            notifications AS (
+                SELECT COALESCE(tenant_id, '') AS tenant_key, COUNT(*) AS notifications
+                FROM notification_events
+                WHERE created_at >= $1 AND created_at < $2
+                    AND ($3::TEXT IS NULL OR tenant_id = $3)
+                GROUP BY 1
+            )
+            SELECT
+                NULLIF(COALESCE(x.tenant_key, n.tenant_key), '') AS tenant_id,
+                COALESCE(x.executions, 0) AS executions,
+                COALESCE(x.compute_seconds, 0)::FLOAT8 AS compute_seconds,
+                COALESCE(x.storage_bytes, 0)::BIGINT AS storage_bytes,
+                COALESCE(n.notifications, 0) AS notifications
+            FROM executions x
+            FULL OUTER JOIN notifications n ON n.tenant_key = x.tenant_key
+            ORDER BY 1 NULLS FIRST
+            "#,
+        )
+        .bind(from)
+        .bind(to)
+        .bind(tenant_id)
+        .bind(
+            ExecutionStatus::TERMINAL
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .fetch_all(self.pool.read_pool())
+        .await?;
+
+        Ok(usage)
+    }
+}
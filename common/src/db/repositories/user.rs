@@ -30,9 +30,9 @@ impl UserRepository {
             r#"
             INSERT INTO users (
                 id, username, password_hash, email, enabled,
-                created_at, updated_at
+                locale, tenant_id, created_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
         )
         .bind(&user.id)
@@ -40,6 +40,8 @@ impl UserRepository {
         .bind(&user.password_hash)
         .bind(&user.email)
         .bind(user.enabled)
+        .bind(&user.locale)
+        .bind(&user.tenant_id)
         .bind(user.created_at)
         .bind(user.updated_at)
         .execute(self.pool.pool())
@@ -57,7 +59,8 @@ impl UserRepository {
     pub async fn find_by_username(&self, username: &str) -> Result<Option<User>, DatabaseError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, password_hash, email, enabled, created_at, updated_at
+            SELECT id, username, password_hash, email, enabled, locale, tenant_id,
+                   created_at, updated_at
             FROM users
             WHERE username = $1
             "#,
@@ -74,7 +77,8 @@ impl UserRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DatabaseError> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, password_hash, email, enabled, created_at, updated_at
+            SELECT id, username, password_hash, email, enabled, locale, tenant_id,
+                   created_at, updated_at
             FROM users
             WHERE id = $1
             "#,
@@ -91,7 +95,8 @@ impl UserRepository {
     pub async fn find_all(&self) -> Result<Vec<User>, DatabaseError> {
         let users = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, password_hash, email, enabled, created_at, updated_at
+            SELECT id, username, password_hash, email, enabled, locale, tenant_id,
+                   created_at, updated_at
             FROM users
             ORDER BY created_at DESC
             "#,
@@ -115,7 +120,9 @@ impl UserRepository {
                 password_hash = $3,
                 email = $4,
                 enabled = $5,
-                updated_at = $6
+                locale = $6,
+                tenant_id = $7,
+                updated_at = $8
             WHERE id = $1
             "#,
         )
@@ -124,6 +131,8 @@ impl UserRepository {
         .bind(&user.password_hash)
         .bind(&user.email)
         .bind(user.enabled)
+        .bind(&user.locale)
+        .bind(&user.tenant_id)
         .bind(Utc::now())
         .execute(self.pool.pool())
         .await?;
@@ -5,7 +5,7 @@ use crate::db::DbPool;
 use crate::errors::DatabaseError;
 use crate::models::{Variable, VariableScope};
 use chrono::Utc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tracing::instrument;
 use uuid::Uuid;
 
@@ -36,7 +36,7 @@ impl VariableRepository {
     pub async fn find_global_variables(&self) -> Result<HashMap<String, String>, DatabaseError> {
         let variables = sqlx::query_as::<_, Variable>(
             r#"
-            SELECT id, name, value, is_sensitive, scope, created_at, updated_at
+            SELECT id, name, value, is_sensitive, scope, expires_at, created_at, updated_at
             FROM variables
             WHERE scope_type = 'global'
             "#,
@@ -69,7 +69,7 @@ impl VariableRepository {
     ) -> Result<HashMap<String, String>, DatabaseError> {
         let variables = sqlx::query_as::<_, Variable>(
             r#"
-            SELECT id, name, value, is_sensitive, scope, created_at, updated_at
+            SELECT id, name, value, is_sensitive, scope, expires_at, created_at, updated_at
             FROM variables
             WHERE scope_type = 'job' AND scope_id = $1
             "#,
@@ -114,6 +114,54 @@ impl VariableRepository {
         Ok(variables)
     }
 
+    /// Names of the sensitive variables visible to a job (global or job-scoped)
+    #[instrument(skip(self))]
+    pub async fn find_sensitive_names_for_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<HashSet<String>, DatabaseError> {
+        let names = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT name
+            FROM variables
+            WHERE is_sensitive
+              AND (scope_type = 'global' OR (scope_type = 'job' AND scope_id = $1))
+            "#,
+        )
+        .bind(job_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(names.into_iter().collect())
+    }
+
+    /// Value of the sensitive variable `name` visible to a job; a job-scoped variable
+    /// takes precedence over a global one
+    #[instrument(skip(self))]
+    pub async fn find_sensitive_for_job(
+        &self,
+        job_id: Uuid,
+        name: &str,
+    ) -> Result<Option<String>, DatabaseError> {
+        let value = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT value
+            FROM variables
+            WHERE is_sensitive
+              AND name = $2
+              AND (scope_type = 'global' OR (scope_type = 'job' AND scope_id = $1))
+            ORDER BY scope_type = 'job' DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(job_id)
+        .bind(name)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        value.map(|value| self.decrypt_value(&value)).transpose()
+    }
+
     /// Create a new variable
     ///
     /// # Requirements
@@ -136,9 +184,9 @@ impl VariableRepository {
             r#"
             INSERT INTO variables (
                 id, name, value, is_sensitive, scope_type, scope_id,
-                created_at, updated_at
+                expires_at, created_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             "#,
         )
         .bind(&variable.id)
@@ -147,6 +195,7 @@ impl VariableRepository {
         .bind(variable.is_sensitive)
         .bind(&scope_type)
         .bind(scope_id)
+        .bind(variable.expires_at)
         .bind(variable.created_at)
         .bind(variable.updated_at)
         .execute(self.pool.pool())
@@ -166,7 +215,7 @@ impl VariableRepository {
     pub async fn find_by_id(&self, id: Uuid) -> Result<Option<Variable>, DatabaseError> {
         let mut variable = sqlx::query_as::<_, Variable>(
             r#"
-            SELECT id, name, value, is_sensitive, scope, created_at, updated_at
+            SELECT id, name, value, is_sensitive, scope, expires_at, created_at, updated_at
             FROM variables
             WHERE id = $1
             "#,
@@ -199,13 +248,13 @@ impl VariableRepository {
 
         let query = if scope_id.is_some() {
             r#"
-            SELECT id, name, value, is_sensitive, scope, created_at, updated_at
+            SELECT id, name, value, is_sensitive, scope, expires_at, created_at, updated_at
             FROM variables
             WHERE name = $1 AND scope_type = $2 AND scope_id = $3
             "#
         } else {
             r#"
-            SELECT id, name, value, is_sensitive, scope, created_at, updated_at
+            SELECT id, name, value, is_sensitive, scope, expires_at, created_at, updated_at
             FROM variables
             WHERE name = $1 AND scope_type = $2 AND scope_id IS NULL
             "#
@@ -250,7 +299,8 @@ impl VariableRepository {
             SET name = $2,
                 value = $3,
                 is_sensitive = $4,
-                updated_at = $5
+                expires_at = $5,
+                updated_at = $6
             WHERE id = $1
             "#,
         )
@@ -258,6 +308,7 @@ impl VariableRepository {
         .bind(&variable.name)
         .bind(&value)
         .bind(variable.is_sensitive)
+        .bind(variable.expires_at)
         .bind(Utc::now())
         .execute(self.pool.pool())
         .await?;
@@ -308,7 +359,7 @@ impl VariableRepository {
     pub async fn list_all(&self) -> Result<Vec<Variable>, DatabaseError> {
         let mut variables = sqlx::query_as::<_, Variable>(
             r#"
-            SELECT id, name, value, is_sensitive, scope, created_at, updated_at
+            SELECT id, name, value, is_sensitive, scope, expires_at, created_at, updated_at
             FROM variables
             ORDER BY created_at DESC
             "#,
@@ -0,0 +1,273 @@
+// Webhook delivery repository implementation
+// Purpose: Persistent queue and per-attempt log of outbound webhooks
+
+use crate::callback::EXECUTION_COMPLETED_EVENT;
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::{WebhookDelivery, WebhookDeliveryAttempt, WebhookDeliveryStatus};
+use chrono::{DateTime, Utc};
+use tracing::instrument;
+use uuid::Uuid;
+
+const WEBHOOK_DELIVERY_COLUMNS: &str = "id, endpoint_url, event, job_id, execution_id, payload, \
+     status, attempts, next_attempt_at, last_response_status, last_error, created_at, delivered_at";
+
+/// Result of one delivery attempt, recorded with [`WebhookDeliveryRepository::record_attempt`]
+#[derive(Debug, Clone)]
+pub struct AttemptOutcome {
+    pub attempt: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    /// Status of the delivery after this attempt
+    pub status: WebhookDeliveryStatus,
+    /// When to try again, for deliveries still pending
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+/// Filter for listing deliveries
+#[derive(Debug, Clone, Default)]
+pub struct WebhookDeliveryFilter {
+    pub endpoint_url: Option<String>,
+    pub status: Option<WebhookDeliveryStatus>,
+    pub limit: i64,
+}
+
+/// Repository for outbound webhook deliveries
+#[derive(Clone)]
+pub struct WebhookDeliveryRepository {
+    pool: DbPool,
+}
+
+impl WebhookDeliveryRepository {
+    /// Create a new WebhookDeliveryRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Queue a delivery for its first attempt, returning its ID
+    ///
+    /// Completion callbacks are keyed by execution: queuing one again returns the
+    /// delivery already queued for the execution.
+    #[instrument(skip(self, delivery), fields(delivery_id = %delivery.id, event = %delivery.event))]
+    pub async fn create(&self, delivery: &WebhookDelivery) -> Result<Uuid, DatabaseError> {
+        let inserted: Option<Uuid> = sqlx::query_scalar(&format!(
+            r#"
+            INSERT INTO webhook_deliveries (
+                id, endpoint_url, event, job_id, execution_id, payload, status, attempts,
+                next_attempt_at, created_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            ON CONFLICT (execution_id, event) WHERE event = '{}' DO NOTHING
+            RETURNING id
+            "#,
+            EXECUTION_COMPLETED_EVENT
+        ))
+        .bind(delivery.id)
+        .bind(&delivery.endpoint_url)
+        .bind(&delivery.event)
+        .bind(delivery.job_id)
+        .bind(delivery.execution_id)
+        .bind(&delivery.payload)
+        .bind(delivery.status.to_string())
+        .bind(delivery.attempts)
+        .bind(delivery.next_attempt_at)
+        .bind(delivery.created_at)
+        .fetch_optional(self.pool.pool())
+        .await?;
+        if let Some(id) = inserted {
+            return Ok(id);
+        }
+
+        let existing: Uuid = sqlx::query_scalar(
+            "SELECT id FROM webhook_deliveries WHERE execution_id = $1 AND event = $2",
+        )
+        .bind(delivery.execution_id)
+        .bind(&delivery.event)
+        .fetch_one(self.pool.pool())
+        .await?;
+        Ok(existing)
+    }
+
+    /// Claim pending deliveries that are due
+    ///
+    /// Claimed rows are leased to `lease_id` by pushing `next_attempt_at` to
+    /// `lease_until`, and locked rows are skipped, so concurrent workers never send
+    /// the same attempt. A worker that dies mid-attempt leaves the delivery to be
+    /// retried after the lease.
+    #[instrument(skip(self))]
+    pub async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_id: Uuid,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            r#"
+            UPDATE webhook_deliveries
+            SET next_attempt_at = $2, lease_id = $5
+            WHERE id IN (
+                SELECT id FROM webhook_deliveries
+                WHERE status = $4 AND next_attempt_at <= $1
+                ORDER BY next_attempt_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {}
+            "#,
+            WEBHOOK_DELIVERY_COLUMNS
+        ))
+        .bind(now)
+        .bind(lease_until)
+        .bind(limit)
+        .bind(WebhookDeliveryStatus::Pending.to_string())
+        .bind(lease_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Log an attempt and move the delivery to its resulting status
+    ///
+    /// Only the holder of the delivery's lease records the attempt; returns false,
+    /// recording nothing, once the lease was lost to another worker.
+    #[instrument(skip(self, outcome), fields(attempt = outcome.attempt, status = %outcome.status))]
+    pub async fn record_attempt(
+        &self,
+        delivery_id: Uuid,
+        lease_id: Uuid,
+        outcome: &AttemptOutcome,
+    ) -> Result<bool, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        let updated = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2,
+                attempts = $3,
+                next_attempt_at = COALESCE($4, next_attempt_at),
+                last_response_status = $5,
+                last_error = $6,
+                delivered_at = CASE WHEN $2 = 'delivered' THEN NOW() ELSE delivered_at END,
+                lease_id = NULL
+            WHERE id = $1 AND lease_id = $7 AND status = $8
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(outcome.status.to_string())
+        .bind(outcome.attempt)
+        .bind(outcome.next_attempt_at)
+        .bind(outcome.response_status)
+        .bind(&outcome.error)
+        .bind(lease_id)
+        .bind(WebhookDeliveryStatus::Pending.to_string())
+        .execute(&mut *tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_delivery_attempts (
+                delivery_id, attempt, response_status, error, duration_ms
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(outcome.attempt)
+        .bind(outcome.response_status)
+        .bind(&outcome.error)
+        .bind(outcome.duration_ms)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// List deliveries, most recent first
+    #[instrument(skip(self))]
+    pub async fn list(
+        &self,
+        filter: &WebhookDeliveryFilter,
+    ) -> Result<Vec<WebhookDelivery>, DatabaseError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            r#"
+            SELECT {}
+            FROM webhook_deliveries
+            WHERE ($1::TEXT IS NULL OR endpoint_url = $1)
+                AND ($2::TEXT IS NULL OR status = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+            WEBHOOK_DELIVERY_COLUMNS
+        ))
+        .bind(&filter.endpoint_url)
+        .bind(filter.status.map(|status| status.to_string()))
+        .bind(filter.limit)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(deliveries)
+    }
+
+    /// Find a delivery by ID
+    #[instrument(skip(self))]
+    pub async fn find_by_id(&self, id: Uuid) -> Result<Option<WebhookDelivery>, DatabaseError> {
+        let delivery = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            "SELECT {} FROM webhook_deliveries WHERE id = $1",
+            WEBHOOK_DELIVERY_COLUMNS
+        ))
+        .bind(id)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(delivery)
+    }
+
+    /// Attempts of a delivery, oldest first
+    #[instrument(skip(self))]
+    pub async fn find_attempts(
+        &self,
+        delivery_id: Uuid,
+    ) -> Result<Vec<WebhookDeliveryAttempt>, DatabaseError> {
+        let attempts = sqlx::query_as::<_, WebhookDeliveryAttempt>(
+            r#"
+            SELECT attempt, response_status, error, duration_ms, attempted_at
+            FROM webhook_delivery_attempts
+            WHERE delivery_id = $1
+            ORDER BY attempt, id
+            "#,
+        )
+        .bind(delivery_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(attempts)
+    }
+
+    /// Send a dead-lettered delivery again with a fresh set of attempts
+    ///
+    /// Returns whether the delivery was dead-lettered and is now pending.
+    #[instrument(skip(self))]
+    pub async fn redeliver(&self, id: Uuid) -> Result<bool, DatabaseError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $2, attempts = 0, next_attempt_at = NOW()
+            WHERE id = $1 AND status = $3
+            "#,
+        )
+        .bind(id)
+        .bind(WebhookDeliveryStatus::Pending.to_string())
+        .bind(WebhookDeliveryStatus::DeadLetter.to_string())
+        .execute(self.pool.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,112 @@
+// Webhook payload repository implementation
+// Purpose: Recent webhook trigger requests per webhook, for job simulations
+
+use crate::db::DbPool;
+use crate::errors::DatabaseError;
+use crate::models::CapturedWebhookPayload;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Requests kept per webhook; older ones are pruned when a new one is recorded
+pub const CAPTURED_PAYLOADS_PER_WEBHOOK: i64 = 20;
+
+/// Repository for captured webhook trigger requests
+#[derive(Clone)]
+pub struct WebhookPayloadRepository {
+    pool: DbPool,
+}
+
+impl WebhookPayloadRepository {
+    /// Create a new WebhookPayloadRepository
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record a request and prune the webhook's older ones, returning those pruned
+    #[instrument(skip(self, payload), fields(payload_id = %payload.id, webhook_id = %payload.webhook_id))]
+    pub async fn record(
+        &self,
+        payload: &CapturedWebhookPayload,
+    ) -> Result<Vec<CapturedWebhookPayload>, DatabaseError> {
+        let mut tx = self.pool.pool().begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_payloads (id, webhook_id, job_id, execution_id, data, received_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(payload.id)
+        .bind(payload.webhook_id)
+        .bind(payload.job_id)
+        .bind(payload.execution_id)
+        .bind(sqlx::types::Json(&payload.data))
+        .bind(payload.received_at)
+        .execute(&mut *tx)
+        .await?;
+
+        let pruned = sqlx::query_as::<_, CapturedWebhookPayload>(
+            r#"
+            DELETE FROM webhook_payloads
+            WHERE webhook_id = $1
+              AND id NOT IN (
+                  SELECT id FROM webhook_payloads
+                  WHERE webhook_id = $1
+                  ORDER BY received_at DESC
+                  LIMIT $2
+              )
+            RETURNING id, webhook_id, job_id, execution_id, data, received_at
+            "#,
+        )
+        .bind(payload.webhook_id)
+        .bind(CAPTURED_PAYLOADS_PER_WEBHOOK)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(pruned)
+    }
+
+    /// Captured requests of a job, newest first
+    #[instrument(skip(self))]
+    pub async fn find_by_job(
+        &self,
+        job_id: Uuid,
+    ) -> Result<Vec<CapturedWebhookPayload>, DatabaseError> {
+        let payloads = sqlx::query_as::<_, CapturedWebhookPayload>(
+            r#"
+            SELECT id, webhook_id, job_id, execution_id, data, received_at
+            FROM webhook_payloads
+            WHERE job_id = $1
+            ORDER BY received_at DESC
+            "#,
+        )
+        .bind(job_id)
+        .fetch_all(self.pool.pool())
+        .await?;
+
+        Ok(payloads)
+    }
+
+    /// One captured request of a job
+    #[instrument(skip(self))]
+    pub async fn find_for_job(
+        &self,
+        job_id: Uuid,
+        payload_id: Uuid,
+    ) -> Result<Option<CapturedWebhookPayload>, DatabaseError> {
+        let payload = sqlx::query_as::<_, CapturedWebhookPayload>(
+            r#"
+            SELECT id, webhook_id, job_id, execution_id, data, received_at
+            FROM webhook_payloads
+            WHERE job_id = $1 AND id = $2
+            "#,
+        )
+        .bind(job_id)
+        .bind(payload_id)
+        .fetch_optional(self.pool.pool())
+        .await?;
+
+        Ok(payload)
+    }
+}
@@ -0,0 +1,118 @@
+// Job deprecation and sunset
+// Purpose: Retire jobs in stages: a deprecated job keeps running but warns on every
+// trigger and only accepts edits from its owners, then the scheduler disables it at
+// its sunset date and notifies the owner
+
+use crate::errors::ValidationError;
+use crate::models::Job;
+use chrono::{DateTime, Utc};
+
+/// Longest accepted deprecation reason
+pub const MAX_REASON_LENGTH: usize = 500;
+
+/// Validate a deprecation request
+pub fn validate_deprecation(
+    sunset_at: DateTime<Utc>,
+    reason: Option<&str>,
+    now: DateTime<Utc>,
+) -> Result<(), ValidationError> {
+    if sunset_at <= now {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "sunset_at".to_string(),
+            reason: "must be in the future".to_string(),
+        });
+    }
+    if reason.is_some_and(|r| r.len() > MAX_REASON_LENGTH) {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "reason".to_string(),
+            reason: format!("must be at most {} characters", MAX_REASON_LENGTH),
+        });
+    }
+    Ok(())
+}
+
+/// Whether a user may edit the job
+///
+/// Anyone may edit a job that isn't deprecated. A deprecated job only accepts edits
+/// from its owner's contact address, or from a platform admin so jobs nobody owns
+/// can still be rescued.
+pub fn can_edit(job: &Job, user_email: Option<&str>, is_admin: bool) -> bool {
+    if !job.deprecated || is_admin {
+        return true;
+    }
+    match (&job.owner, user_email) {
+        (Some(owner), Some(email)) => owner.is_contact(email),
+        _ => false,
+    }
+}
+
+/// Whether the job is deprecated and its sunset date has passed
+pub fn is_past_sunset(job: &Job, now: DateTime<Utc>) -> bool {
+    job.deprecated && job.sunset_at.is_some_and(|sunset_at| sunset_at <= now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::JobOwner;
+    use chrono::Duration;
+
+    fn job(deprecated: bool, owner_email: Option<&str>) -> Job {
+        let mut job: Job = serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::new_v4(),
+            "name": "nightly-report",
+            "description": null,
+            "schedule": null,
+            "steps": [],
+            "triggers": {"scheduled": true, "manual": true, "webhook": null},
+            "enabled": true,
+            "timeout_seconds": 300,
+            "max_retries": 3,
+            "definition": null
+        }))
+        .unwrap();
+        job.deprecated = deprecated;
+        job.sunset_at = deprecated.then(|| Utc::now() + Duration::days(30));
+        job.owner = owner_email.map(|email| JobOwner {
+            team: "data".to_string(),
+            contact_email: Some(email.to_string()),
+            slack: None,
+            runbook_url: None,
+        });
+        job
+    }
+
+    #[test]
+    fn test_validate_deprecation() {
+        let now = Utc::now();
+        assert!(validate_deprecation(now + Duration::days(7), Some("replaced"), now).is_ok());
+        assert!(validate_deprecation(now - Duration::seconds(1), None, now).is_err());
+
+        let long_reason = "x".repeat(MAX_REASON_LENGTH + 1);
+        assert!(validate_deprecation(now + Duration::days(7), Some(&long_reason), now).is_err());
+    }
+
+    #[test]
+    fn test_only_owners_and_admins_edit_deprecated_jobs() {
+        assert!(can_edit(&job(false, None), None, false));
+
+        let owned = job(true, Some("data-team@example.com"));
+        assert!(can_edit(&owned, Some("Data-Team@example.com"), false));
+        assert!(!can_edit(&owned, Some("someone@example.com"), false));
+        assert!(!can_edit(&owned, None, false));
+        assert!(can_edit(&owned, None, true));
+
+        let zombie = job(true, None);
+        assert!(!can_edit(&zombie, Some("someone@example.com"), false));
+        assert!(can_edit(&zombie, None, true));
+    }
+
+    #[test]
+    fn test_is_past_sunset() {
+        let job = job(true, None);
+        let sunset_at = job.sunset_at.unwrap();
+        assert!(!is_past_sunset(&job, sunset_at - Duration::seconds(1)));
+        assert!(is_past_sunset(&job, sunset_at));
+        assert!(!is_past_sunset(&self::job(false, None), sunset_at));
+    }
+}
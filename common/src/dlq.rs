@@ -3,9 +3,18 @@
 // Property 36: Dead letter queue placement
 // Property 38: Dead letter queue isolation
 
-use crate::errors::ExecutionError;
-use crate::models::{ExecutionStatus, JobExecution};
+use crate::db::repositories::execution::ExecutionRepository;
+use crate::db::repositories::job::JobRepository;
+use crate::db::DbPool;
+use crate::errors::{DatabaseError, ExecutionError};
+use crate::models::{ExecutionStatus, Job, JobExecution};
+use crate::queue::JobPublisher;
+use crate::replay::{ReplayOrigin, ReplaySnapshot};
 use crate::retry::MAX_RETRIES;
+use crate::storage::StorageService;
+use crate::trigger_variables::apply_trigger_variables;
+use std::sync::Arc;
+use tokio::sync::mpsc;
 use tracing::{info, warn};
 use uuid::Uuid;
 
@@ -67,7 +76,9 @@ impl DeadLetterQueue {
         );
 
         // Update execution status to DeadLetter
-        execution.status = ExecutionStatus::DeadLetter;
+        execution
+            .transition_to(ExecutionStatus::DeadLetter)
+            .map_err(|e| ExecutionError::InvalidJobDefinition(e.to_string()))?;
 
         // Append DLQ information to error message
         let dlq_info = format!(
@@ -167,6 +178,132 @@ pub struct DlqStats {
 
 use serde::{Deserialize, Serialize};
 
+/// User recorded on executions replayed after a dependency recovered
+const RECOVERY_REPLAY_USER: &str = "dead-letter-replay";
+
+/// Replays dead letters once the dependency they failed at recovers
+///
+/// Only jobs whose dead letter policy has `auto_replay` are replayed, oldest dead
+/// letter first and at most `max_replays` per job and recovery. Each replay runs with
+/// the inputs of the dead letter, like a manual replay.
+pub struct DeadLetterReplayer {
+    job_repo: JobRepository,
+    execution_repo: ExecutionRepository,
+    storage_service: Arc<dyn StorageService>,
+    publisher: Arc<dyn JobPublisher>,
+}
+
+impl DeadLetterReplayer {
+    pub fn new(
+        db_pool: DbPool,
+        storage_service: Arc<dyn StorageService>,
+        publisher: Arc<dyn JobPublisher>,
+    ) -> Self {
+        Self {
+            job_repo: JobRepository::new(db_pool.clone()),
+            execution_repo: ExecutionRepository::new(db_pool),
+            storage_service,
+            publisher,
+        }
+    }
+
+    /// Replay in the background whenever a dependency is sent to the returned sender,
+    /// e.g. by circuit breakers closing again
+    pub fn spawn(self: Arc<Self>) -> mpsc::UnboundedSender<String> {
+        let (recoveries, mut recovered) = mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            while let Some(dependency) = recovered.recv().await {
+                if let Err(e) = self.replay(&dependency).await {
+                    warn!(dependency = %dependency, error = %e, "Failed to replay dead letters");
+                }
+            }
+        });
+        recoveries
+    }
+
+    /// Replay the dead letters linked to a recovered dependency; returns how many
+    /// replays were queued
+    pub async fn replay(&self, dependency: &str) -> Result<usize, DatabaseError> {
+        let mut replayed = 0;
+        for job_id in self
+            .execution_repo
+            .find_jobs_with_dead_letters(dependency)
+            .await?
+        {
+            let Some(job) = self.job_repo.find_by_id(job_id).await? else {
+                continue;
+            };
+            let Some(policy) = job
+                .dead_letter_policy
+                .as_ref()
+                .filter(|policy| policy.auto_replay && job.enabled)
+            else {
+                continue;
+            };
+
+            let dead_letters = self
+                .execution_repo
+                .claim_dead_letters(job.id, dependency, policy.max_replays as i64)
+                .await?;
+            for original in dead_letters {
+                match self.replay_one(&job, &original, dependency).await {
+                    Ok(execution_id) => {
+                        replayed += 1;
+                        info!(
+                            execution_id = %execution_id,
+                            original_execution_id = %original.id,
+                            job_id = %job.id,
+                            dependency = %dependency,
+                            "Dead letter replayed after its dependency recovered"
+                        );
+                    }
+                    Err(e) => {
+                        warn!(
+                            original_execution_id = %original.id,
+                            error = %e,
+                            "Failed to replay dead letter, keeping it for the next recovery"
+                        );
+                        self.execution_repo.unclaim_dead_letter(original.id).await?;
+                    }
+                }
+            }
+        }
+        Ok(replayed)
+    }
+
+    /// Queue a replay of one dead letter with the inputs it originally ran with
+    async fn replay_one(
+        &self,
+        job: &Job,
+        original: &JobExecution,
+        dependency: &str,
+    ) -> Result<Uuid, anyhow::Error> {
+        let context = self
+            .storage_service
+            .load_context(original.job_id, original.id)
+            .await
+            .ok();
+        let snapshot = ReplaySnapshot::capture(original, context.as_ref());
+
+        let mut execution = JobExecution::new_manual(job.id, RECOVERY_REPLAY_USER.to_string());
+        apply_trigger_variables(
+            &mut execution,
+            &snapshot.variables,
+            RECOVERY_REPLAY_USER,
+            "replay",
+        );
+        ReplayOrigin::new(original.id, format!("{} recovery", dependency))
+            .attach_to(&mut execution.trigger_metadata);
+
+        self.storage_service
+            .store_context(&snapshot.into_context(execution.id, job.id))
+            .await?;
+        self.execution_repo.create(&execution).await?;
+        self.publisher.publish_for_job(&execution, &job).await?;
+        Ok(execution.id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
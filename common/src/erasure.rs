@@ -0,0 +1,683 @@
+// Subject data erasure
+// Purpose: Honour erasure requests of data subjects (GDPR art. 17, PDPD): find an
+// identifier such as an email address in stored execution contexts, their files,
+// the result and error of executions, job state and captured webhook payloads, and
+// redact it everywhere once an operator confirms what was found
+//
+// A search returns the matches and a confirmation code covering them; the erasure
+// repeats the search and only proceeds if the code still matches. Each erasure is
+// recorded in a report holding a keyed hash (HMAC) of the identifier, never the
+// identifier itself, so a short identifier such as a phone number can't be found by
+// hashing guesses without the server's secret. Reports form a hash chain, so
+// editing or deleting one breaks the chain.
+// Executions still running may write back their own copy of the context; searching
+// again after the erasure shows anything left.
+
+use crate::db::repositories::erasure::ErasureRepository;
+use crate::db::DbPool;
+use crate::errors::{DatabaseError, StorageError, ValidationError};
+use crate::models::JobContext;
+use crate::storage::{residency, StorageService};
+use chrono::{DateTime, SubsecRound, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+/// What a found identifier is replaced with
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Shortest identifier accepted, so a typo can't redact common words
+pub(crate) const MIN_IDENTIFIER_LEN: usize = 4;
+const MAX_IDENTIFIER_LEN: usize = 320;
+
+/// Execution files larger than this are reported as skipped instead of scanned
+const MAX_SCANNED_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Executions whose contexts are loaded per query
+const SCAN_BATCH_SIZE: i64 = 200;
+
+/// Error type for erasure searches and erasures
+#[derive(Debug, thiserror::Error)]
+pub enum ErasureError {
+    #[error("Database error: {0}")]
+    Database(#[from] DatabaseError),
+
+    #[error("Storage error: {0}")]
+    Storage(#[from] StorageError),
+
+    #[error("Validation error: {0}")]
+    Validation(#[from] ValidationError),
+
+    #[error("The matches changed since the search; search again and confirm the new matches")]
+    ConfirmationMismatch,
+}
+
+/// Where an identifier was found, and how often
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ErasureMatch {
+    /// Variables, step outputs or webhook data of an execution
+    ExecutionContext {
+        execution_id: Uuid,
+        job_id: Uuid,
+        occurrences: usize,
+    },
+    /// A text file recorded in an execution's context
+    ExecutionFile {
+        execution_id: Uuid,
+        job_id: Uuid,
+        path: String,
+        occurrences: usize,
+    },
+    /// A captured webhook request kept for job simulations
+    WebhookPayload {
+        payload_id: Uuid,
+        job_id: Uuid,
+        occurrences: usize,
+    },
+    /// The result or error recorded on an execution
+    ExecutionRecord {
+        execution_id: Uuid,
+        job_id: Uuid,
+        occurrences: usize,
+    },
+    /// A value of a job's state
+    JobState {
+        job_id: Uuid,
+        key: String,
+        occurrences: usize,
+    },
+}
+
+/// Matches of an identifier, to be confirmed by an operator
+#[derive(Debug, Clone, Serialize)]
+pub struct ErasureSearch {
+    pub identifier_hash: String,
+    pub matches: Vec<ErasureMatch>,
+    /// Execution files that couldn't be scanned: binary or too large
+    pub skipped_files: Vec<String>,
+    /// Pass back to the erasure to confirm exactly these matches
+    pub confirmation: String,
+}
+
+/// Record of one erasure, chained to the previous one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErasureReport {
+    pub id: Uuid,
+    /// HMAC-SHA-256 of the lowercased identifier, keyed with the server secret
+    pub identifier_hash: String,
+    pub requested_by: String,
+    pub matches: Vec<ErasureMatch>,
+    pub skipped_files: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    /// Hash of the report before this one; `None` for the first report
+    pub previous_hash: Option<String>,
+    /// SHA-256 over all other fields
+    pub hash: String,
+}
+
+/// Fields of a report covered by its hash, in a fixed order
+#[derive(Serialize)]
+struct SealedFields<'a> {
+    id: Uuid,
+    identifier_hash: &'a str,
+    requested_by: &'a str,
+    matches: &'a [ErasureMatch],
+    skipped_files: &'a [String],
+    created_at: DateTime<Utc>,
+    previous_hash: Option<&'a str>,
+}
+
+impl ErasureReport {
+    /// Build a report following the one with `previous_hash`
+    pub fn seal(
+        identifier_hash: String,
+        requested_by: String,
+        matches: Vec<ErasureMatch>,
+        skipped_files: Vec<String>,
+        previous_hash: Option<String>,
+    ) -> Self {
+        let mut report = Self {
+            id: Uuid::new_v4(),
+            identifier_hash,
+            requested_by,
+            matches,
+            skipped_files,
+            // PostgreSQL keeps microseconds; the hash must survive the round trip
+            created_at: Utc::now().trunc_subsecs(6),
+            previous_hash,
+            hash: String::new(),
+        };
+        report.hash = report.compute_hash();
+        report
+    }
+
+    fn compute_hash(&self) -> String {
+        let fields = SealedFields {
+            id: self.id,
+            identifier_hash: &self.identifier_hash,
+            requested_by: &self.requested_by,
+            matches: &self.matches,
+            skipped_files: &self.skipped_files,
+            created_at: self.created_at,
+            previous_hash: self.previous_hash.as_deref(),
+        };
+        let encoded = serde_json::to_vec(&fields).expect("report fields serialize");
+        hex::encode(Sha256::digest(encoded))
+    }
+}
+
+/// Whether reports, oldest first, are unmodified and none is missing
+pub fn verify_chain(reports: &[ErasureReport]) -> bool {
+    let mut previous: Option<&str> = None;
+    for report in reports {
+        if report.previous_hash.as_deref() != previous || report.hash != report.compute_hash() {
+            return false;
+        }
+        previous = Some(&report.hash);
+    }
+    true
+}
+
+/// Trimmed identifier, if it is long enough to search for safely
+pub fn validate_identifier(identifier: &str) -> Result<&str, ValidationError> {
+    let identifier = identifier.trim();
+    let len = identifier.chars().count();
+    if !(MIN_IDENTIFIER_LEN..=MAX_IDENTIFIER_LEN).contains(&len) {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "identifier".to_string(),
+            reason: format!(
+                "must be {} to {} characters",
+                MIN_IDENTIFIER_LEN, MAX_IDENTIFIER_LEN
+            ),
+        });
+    }
+    Ok(identifier)
+}
+
+/// Keyed hash an identifier is recorded under; case doesn't matter
+///
+/// Reports made before the key changed hash the same identifier differently.
+pub fn hash_identifier(identifier: &str, key: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any size");
+    mac.update(b"erasure-identifier:");
+    mac.update(identifier.to_lowercase().as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Code confirming a set of matches of an identifier
+pub fn confirmation(identifier_hash: &str, matches: &[ErasureMatch]) -> String {
+    let encoded = serde_json::to_vec(matches).expect("matches serialize");
+    let mut hasher = Sha256::new();
+    hasher.update(identifier_hash.as_bytes());
+    hasher.update(encoded);
+    hex::encode(hasher.finalize())
+}
+
+/// Replace every occurrence of the identifier, ignoring ASCII case; returns the
+/// text and the number of occurrences replaced
+pub fn redact_text(text: &str, identifier: &str) -> (String, usize) {
+    let haystack = text.to_ascii_lowercase();
+    let needle = identifier.to_ascii_lowercase();
+    let mut redacted = String::with_capacity(text.len());
+    let mut occurrences = 0;
+    let mut rest = 0;
+    // ASCII lowercasing keeps byte offsets, so they index the original text
+    for (start, _) in haystack.match_indices(&needle) {
+        redacted.push_str(&text[rest..start]);
+        redacted.push_str(REDACTED);
+        rest = start + needle.len();
+        occurrences += 1;
+    }
+    redacted.push_str(&text[rest..]);
+    (redacted, occurrences)
+}
+
+/// Redact the identifier in all strings and object keys of a JSON value
+pub fn redact_json(value: &mut Value, identifier: &str) -> usize {
+    match value {
+        Value::String(text) => {
+            let (redacted, occurrences) = redact_text(text, identifier);
+            if occurrences > 0 {
+                *text = redacted;
+            }
+            occurrences
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .map(|item| redact_json(item, identifier))
+            .sum(),
+        Value::Object(map) => {
+            let mut occurrences = 0;
+            for (key, mut item) in std::mem::take(map) {
+                occurrences += redact_json(&mut item, identifier);
+                let (key, in_key) = redact_text(&key, identifier);
+                occurrences += in_key;
+                map.insert(key, item);
+            }
+            occurrences
+        }
+        _ => 0,
+    }
+}
+
+/// Redact the identifier in a string in place; returns the occurrences replaced
+fn redact_string(text: &mut String, identifier: &str) -> usize {
+    let (redacted, occurrences) = redact_text(text, identifier);
+    if occurrences > 0 {
+        *text = redacted;
+    }
+    occurrences
+}
+
+/// Redact the identifier in a context's variables, step outputs, webhook data,
+/// failure and compensation records and job state; ids and file paths are left alone
+pub(crate) fn redact_context(context: &mut JobContext, identifier: &str) -> usize {
+    let mut occurrences = 0;
+    for value in context
+        .variables
+        .values_mut()
+        .chain(context.state.values_mut())
+        .chain(context.pending_state.values_mut())
+    {
+        occurrences += redact_json(value, identifier);
+    }
+    for step in context.steps.values_mut() {
+        occurrences += redact_json(&mut step.output, identifier);
+    }
+    if let Some(webhook) = &mut context.webhook {
+        occurrences += redact_json(&mut webhook.payload, identifier);
+        for value in webhook
+            .query_params
+            .values_mut()
+            .chain(webhook.headers.values_mut())
+        {
+            occurrences += redact_string(value, identifier);
+        }
+    }
+    for route in &mut context.failure_routes {
+        occurrences += redact_string(&mut route.error, identifier);
+    }
+    for compensation in &mut context.compensations {
+        if let Some(output) = &mut compensation.output {
+            occurrences += redact_json(&mut output.output, identifier);
+        }
+        if let Some(error) = &mut compensation.error {
+            occurrences += redact_string(error, identifier);
+        }
+    }
+    occurrences
+}
+
+/// Searches for and erases a data subject's identifier across stored data
+pub struct SubjectErasure {
+    repo: ErasureRepository,
+    storage: Arc<dyn StorageService>,
+    hash_key: Vec<u8>,
+}
+
+impl SubjectErasure {
+    /// Create a new SubjectErasure over the database and file storage, hashing
+    /// identifiers with `hash_key`
+    pub fn new(db_pool: DbPool, storage: Arc<dyn StorageService>, hash_key: &str) -> Self {
+        Self {
+            repo: ErasureRepository::new(db_pool),
+            storage,
+            hash_key: hash_key.as_bytes().to_vec(),
+        }
+    }
+
+    /// Find the identifier without changing anything
+    pub async fn search(&self, identifier: &str) -> Result<ErasureSearch, ErasureError> {
+        let identifier = validate_identifier(identifier)?;
+        let (matches, skipped_files) = self.scan(identifier, false).await?;
+        let identifier_hash = hash_identifier(identifier, &self.hash_key);
+
+        Ok(ErasureSearch {
+            confirmation: confirmation(&identifier_hash, &matches),
+            identifier_hash,
+            matches,
+            skipped_files,
+        })
+    }
+
+    /// Redact the identifier if the matches are still the ones `confirmation` was
+    /// issued for, and record the erasure
+    pub async fn erase(
+        &self,
+        identifier: &str,
+        confirmation: &str,
+        requested_by: &str,
+    ) -> Result<ErasureReport, ErasureError> {
+        let identifier = validate_identifier(identifier)?;
+        if self.search(identifier).await?.confirmation != confirmation {
+            return Err(ErasureError::ConfirmationMismatch);
+        }
+
+        let (matches, skipped_files) = self.scan(identifier, true).await?;
+        let report = self
+            .repo
+            .append(
+                hash_identifier(identifier, &self.hash_key),
+                requested_by.to_string(),
+                matches,
+                skipped_files,
+            )
+            .await?;
+
+        info!(
+            report_id = %report.id,
+            matches = report.matches.len(),
+            "Subject data erased"
+        );
+        Ok(report)
+    }
+
+    /// Look for the identifier everywhere, redacting it if `redact` is set
+    async fn scan(
+        &self,
+        identifier: &str,
+        redact: bool,
+    ) -> Result<(Vec<ErasureMatch>, Vec<String>), ErasureError> {
+        let mut matches = Vec::new();
+        let mut skipped_files = Vec::new();
+
+        let mut after = None;
+        loop {
+            let candidates = self
+                .repo
+                .context_candidates(identifier, after, SCAN_BATCH_SIZE)
+                .await?;
+            for candidate in &candidates {
+                let mut context = match self
+                    .storage
+                    .load_context(candidate.job_id, candidate.execution_id)
+                    .await
+                {
+                    Ok(context) => context,
+                    Err(StorageError::NotFound(_)) => continue,
+                    Err(e) => return Err(e.into()),
+                };
+
+                let occurrences = redact_context(&mut context, identifier);
+                if occurrences > 0 {
+                    if redact {
+                        self.storage.store_context(&context).await?;
+                    }
+                    matches.push(ErasureMatch::ExecutionContext {
+                        execution_id: candidate.execution_id,
+                        job_id: candidate.job_id,
+                        occurrences,
+                    });
+                }
+
+                for file in &context.files {
+                    if file.size > MAX_SCANNED_FILE_BYTES {
+                        skipped_files.push(file.path.clone());
+                        continue;
+                    }
+                    let data = match self.storage.load_file(&file.path).await {
+                        Ok(data) => data,
+                        Err(StorageError::NotFound(_)) => continue,
+                        Err(e) => return Err(e.into()),
+                    };
+                    let Ok(text) = String::from_utf8(data) else {
+                        skipped_files.push(file.path.clone());
+                        continue;
+                    };
+                    let (redacted, occurrences) = redact_text(&text, identifier);
+                    if occurrences > 0 {
+                        if redact {
+                            self.storage
+                                .store_file(&file.path, redacted.as_bytes())
+                                .await?;
+                        }
+                        matches.push(ErasureMatch::ExecutionFile {
+                            execution_id: candidate.execution_id,
+                            job_id: candidate.job_id,
+                            path: file.path.clone(),
+                            occurrences,
+                        });
+                    }
+                }
+            }
+
+            after = candidates.last().map(|candidate| candidate.execution_id);
+            if (candidates.len() as i64) < SCAN_BATCH_SIZE {
+                break;
+            }
+        }
+
+        for mut record in self.repo.execution_record_candidates(identifier).await? {
+            let occurrences: usize = [&mut record.result, &mut record.error]
+                .into_iter()
+                .flatten()
+                .map(|text| redact_string(text, identifier))
+                .sum();
+            if occurrences > 0 {
+                if redact {
+                    self.repo
+                        .update_execution_record(
+                            record.execution_id,
+                            record.result.as_deref(),
+                            record.error.as_deref(),
+                        )
+                        .await?;
+                }
+                matches.push(ErasureMatch::ExecutionRecord {
+                    execution_id: record.execution_id,
+                    job_id: record.job_id,
+                    occurrences,
+                });
+            }
+        }
+
+        for mut state in self.repo.state_candidates(identifier).await? {
+            // Values of tenants with residency rules are redacted in their store
+            if let Some(path) = residency::pointer_path(&state.value, state.job_id) {
+                let occurrences = self.redact_resident(path, identifier, redact).await?;
+                if occurrences > 0 {
+                    matches.push(ErasureMatch::JobState {
+                        job_id: state.job_id,
+                        key: state.key,
+                        occurrences,
+                    });
+                }
+                continue;
+            }
+            let occurrences = redact_json(&mut state.value, identifier);
+            if occurrences > 0 {
+                if redact {
+                    self.repo
+                        .update_state(state.job_id, &state.key, &state.value)
+                        .await?;
+                }
+                matches.push(ErasureMatch::JobState {
+                    job_id: state.job_id,
+                    key: state.key,
+                    occurrences,
+                });
+            }
+        }
+
+        for mut payload in self.repo.webhook_payload_candidates(identifier).await? {
+            let resident = payload
+                .data
+                .get("payload")
+                .and_then(|data| residency::pointer_path(data, payload.job_id));
+            if let Some(path) = resident {
+                let occurrences = self.redact_resident(path, identifier, redact).await?;
+                if occurrences > 0 {
+                    matches.push(ErasureMatch::WebhookPayload {
+                        payload_id: payload.id,
+                        job_id: payload.job_id,
+                        occurrences,
+                    });
+                }
+                continue;
+            }
+            let occurrences = redact_json(&mut payload.data, identifier);
+            if occurrences > 0 {
+                if redact {
+                    self.repo
+                        .update_webhook_payload(payload.id, &payload.data)
+                        .await?;
+                }
+                matches.push(ErasureMatch::WebhookPayload {
+                    payload_id: payload.id,
+                    job_id: payload.job_id,
+                    occurrences,
+                });
+            }
+        }
+
+        matches.sort();
+        skipped_files.sort();
+        skipped_files.dedup();
+        Ok((matches, skipped_files))
+    }
+
+    /// Redact a JSON value kept in a tenant's store, returning the occurrences
+    async fn redact_resident(
+        &self,
+        path: &str,
+        identifier: &str,
+        redact: bool,
+    ) -> Result<usize, ErasureError> {
+        let data = match self.storage.load_file(path).await {
+            Ok(data) => data,
+            Err(StorageError::NotFound(_)) => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        let mut value: Value =
+            serde_json::from_slice(&data).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        let occurrences = redact_json(&mut value, identifier);
+        if occurrences > 0 && redact {
+            let data =
+                serde_json::to_vec(&value).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+            self.storage.store_file(path, &data).await?;
+        }
+        Ok(occurrences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{OnFailure, StepOutput};
+    use serde_json::json;
+
+    const KEY: &[u8] = b"test-server-secret";
+
+    #[test]
+    fn test_redact_json_ignores_ascii_case() {
+        let mut value = json!({
+            "email": "An.Nguyen@Example.com",
+            "note": "cc an.nguyen@example.com and an.nguyen@example.com",
+            "an.nguyen@example.com": {"id": 7},
+            "items": [1, "other@example.com"],
+        });
+
+        assert_eq!(redact_json(&mut value, "an.nguyen@example.com"), 4);
+        assert_eq!(
+            value,
+            json!({
+                "email": REDACTED,
+                "note": format!("cc {} and {}", REDACTED, REDACTED),
+                REDACTED: {"id": 7},
+                "items": [1, "other@example.com"],
+            })
+        );
+        assert_eq!(redact_json(&mut value, "an.nguyen@example.com"), 0);
+    }
+
+    #[test]
+    fn test_redact_context_covers_failures_compensations_and_state() {
+        let email = "an.nguyen@example.com";
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context.record_failure_route(
+            "notify",
+            format!("Mailbox {} is full", email),
+            OnFailure::ContinueNextStep,
+        );
+        context.record_compensation(
+            "refund",
+            "charge",
+            Ok(StepOutput {
+                step_id: "refund".to_string(),
+                status: "success".to_string(),
+                output: json!({"customer": email}),
+                started_at: Utc::now(),
+                completed_at: Utc::now(),
+            }),
+        );
+        context.record_compensation("undo", "charge", Err(format!("No account {}", email)));
+        context
+            .state
+            .insert("last_customer".to_string(), json!(email));
+        context
+            .pending_state
+            .insert("next_customer".to_string(), json!({"email": email}));
+
+        assert_eq!(redact_context(&mut context, email), 5);
+        assert!(!serde_json::to_string(&context).unwrap().contains(email));
+        assert_eq!(redact_context(&mut context, email), 0);
+    }
+
+    #[test]
+    fn test_validate_identifier() {
+        assert_eq!(validate_identifier("  0912345678 ").unwrap(), "0912345678");
+        assert!(validate_identifier("abc").is_err());
+        assert!(validate_identifier(&"a".repeat(321)).is_err());
+    }
+
+    #[test]
+    fn test_report_chain_detects_tampering() {
+        let first = ErasureReport::seal(
+            hash_identifier("an@example.com", KEY),
+            "admin".to_string(),
+            vec![ErasureMatch::WebhookPayload {
+                payload_id: Uuid::new_v4(),
+                job_id: Uuid::new_v4(),
+                occurrences: 1,
+            }],
+            Vec::new(),
+            None,
+        );
+        let second = ErasureReport::seal(
+            hash_identifier("binh@example.com", KEY),
+            "admin".to_string(),
+            Vec::new(),
+            Vec::new(),
+            Some(first.hash.clone()),
+        );
+        assert!(verify_chain(&[first.clone(), second.clone()]));
+
+        // A report removed from the middle or edited breaks the chain
+        assert!(!verify_chain(&[second.clone()]));
+        let mut edited = first.clone();
+        edited.requested_by = "someone-else".to_string();
+        assert!(!verify_chain(&[edited, second]));
+    }
+
+    #[test]
+    fn test_confirmation_covers_matches() {
+        let hash = hash_identifier("AN@example.com", KEY);
+        assert_eq!(hash, hash_identifier("an@example.com", KEY));
+
+        // Without the key the hash can't be recomputed from a guess
+        assert_ne!(hash, hash_identifier("an@example.com", b"other-key"));
+        assert_ne!(hash, hex::encode(Sha256::digest(b"an@example.com")));
+
+        let matches = vec![ErasureMatch::ExecutionContext {
+            execution_id: Uuid::new_v4(),
+            job_id: Uuid::new_v4(),
+            occurrences: 2,
+        }];
+        assert_ne!(confirmation(&hash, &matches), confirmation(&hash, &[]));
+    }
+}
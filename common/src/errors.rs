@@ -9,6 +9,9 @@ pub enum ScheduleError {
     #[error("Invalid cron expression '{expression}': {reason}")]
     InvalidCronExpression { expression: String, reason: String },
 
+    #[error("Invalid ISO 8601 repeating interval '{expression}': {reason}")]
+    InvalidIso8601Expression { expression: String, reason: String },
+
     #[error("Invalid timezone: {0}")]
     InvalidTimezone(String),
 
@@ -20,6 +23,9 @@ pub enum ScheduleError {
 
     #[error("No next execution time available for {schedule_type} schedule")]
     NoNextExecution { schedule_type: String },
+
+    #[error("Unknown calendar: {0}")]
+    UnknownCalendar(String),
 }
 
 /// Job execution errors
@@ -40,6 +46,9 @@ pub enum ExecutionError {
     #[error("HTTP request failed: {0}")]
     HttpRequestFailed(String),
 
+    #[error("GraphQL request returned errors: {0}")]
+    GraphQLErrors(String),
+
     #[error("Database connection failed: {0}")]
     DatabaseConnectionFailed(String),
 
@@ -67,6 +76,52 @@ pub enum ExecutionError {
     #[error("Storage operation failed: {0}")]
     StorageFailed(String),
 
+    #[error("Email sending failed: {0}")]
+    EmailSendFailed(String),
+
+    #[error("MQTT publish failed: {0}")]
+    MqttPublishFailed(String),
+
+    #[error("Object storage operation failed: {0}")]
+    ObjectStorageFailed(String),
+
+    #[error("Container failed: {0}")]
+    ContainerFailed(String),
+
+    #[error("WebAssembly plugin failed: {0}")]
+    WasmFailed(String),
+
+    #[error("Script failed: {0}")]
+    ScriptFailed(String),
+
+    #[error("PDF report failed: {0}")]
+    PdfFailed(String),
+
+    #[error("Transformation failed: {0}")]
+    TransformFailed(String),
+
+    #[error("Data validation failed: {0}")]
+    DataValidationFailed(String),
+
+    #[error("Checksum mismatch: {0}")]
+    ChecksumMismatch(String),
+
+    #[error("Incremental sync failed: {0}")]
+    IncrementalSyncFailed(String),
+
+    #[error("Notification failed: {0}")]
+    NotificationFailed(String),
+
+    #[error("Notification rejected: {0}")]
+    NotificationRejected(String),
+
+    #[error("Rate limited: {message}")]
+    RateLimited {
+        message: String,
+        /// How long the service asked to wait, from its `Retry-After`
+        retry_after: Option<std::time::Duration>,
+    },
+
     #[error("Variable resolution failed: {0}")]
     VariableResolutionFailed(String),
 
@@ -84,6 +139,33 @@ pub enum ExecutionError {
 
     #[error("Failed to save job context: {0}")]
     ContextSaveFailed(String),
+
+    #[error("Injected fault: {0}")]
+    FaultInjected(String),
+
+    #[error("System check failed: {0}")]
+    SystemCheckFailed(String),
+}
+
+impl ExecutionError {
+    /// Whether another attempt of the step could succeed
+    ///
+    /// Failures that can't are requests the dependency answered and refused; they
+    /// don't say it is down either, so they don't count toward its circuit breaker.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            ExecutionError::NotificationRejected(_) | ExecutionError::GraphQLErrors(_)
+        )
+    }
+
+    /// How long the dependency asked to wait before the next attempt
+    pub fn retry_after(&self) -> Option<std::time::Duration> {
+        match self {
+            ExecutionError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
 }
 
 /// Authentication and authorization errors
@@ -92,6 +174,9 @@ pub enum AuthError {
     #[error("Invalid credentials")]
     InvalidCredentials,
 
+    #[error("User account is disabled")]
+    AccountDisabled,
+
     #[error("Invalid JWT token: {0}")]
     InvalidToken(String),
 
@@ -154,8 +239,14 @@ pub enum DatabaseError {
     #[error("Foreign key violation: {0}")]
     ForeignKeyViolation(String),
 
+    #[error("Version conflict: {0}")]
+    VersionConflict(String),
+
     #[error("Migration failed: {0}")]
     MigrationFailed(String),
+
+    #[error("Incompatible database schema: {0}")]
+    SchemaMismatch(String),
 }
 
 /// Storage errors
@@ -173,6 +264,9 @@ pub enum StorageError {
     #[error("MinIO error: {0}")]
     MinioError(String),
 
+    #[error("Object storage error: {0}")]
+    ObjectStorageError(String),
+
     #[error("Filesystem error: {0}")]
     FileSystemError(String),
 
@@ -0,0 +1,192 @@
+// Saved execution filters
+// Purpose: Keep named execution filters server-side so a view can be opened from a
+// link (`?filter_id=`) or from the team's list instead of re-entering query parameters
+//
+// Anyone who can read executions may open a filter by id; the id is what gets shared.
+// Filters with a team are also listed to everyone browsing that team's filters. Only
+// the owner may change or delete a filter.
+
+use crate::errors::ValidationError;
+use crate::models::ExecutionStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest accepted filter name, in characters
+const MAX_NAME_LEN: usize = 64;
+
+/// Longest accepted team name (matches `JobOwner::MAX_TEAM_LENGTH`)
+const MAX_TEAM_LEN: usize = 100;
+
+/// Trigger sources executions are filtered on
+const TRIGGER_SOURCES: &[&str] = &["scheduled", "manual", "webhook", "backfill"];
+
+/// What a saved filter matches; unset fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExecutionFilterCriteria {
+    #[serde(default)]
+    pub status: Option<ExecutionStatus>,
+    #[serde(default)]
+    pub job_id: Option<Uuid>,
+    /// Case-insensitive substring of the job name
+    #[serde(default)]
+    pub job_name: Option<String>,
+    /// `scheduled`, `manual` or `webhook`
+    #[serde(default)]
+    pub trigger_source: Option<String>,
+    /// Job metric label, as `name=value`
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Executions created at or after this time
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Executions created before this time
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl ExecutionFilterCriteria {
+    /// Validate criteria before they are saved or applied
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let invalid = |field: &str, reason: String| ValidationError::InvalidFieldValue {
+            field: field.to_string(),
+            reason,
+        };
+
+        if let Some(trigger_source) = &self.trigger_source {
+            if !TRIGGER_SOURCES.contains(&trigger_source.as_str()) {
+                return Err(invalid(
+                    "trigger_source",
+                    format!("'{}' must be one of {:?}", trigger_source, TRIGGER_SOURCES),
+                ));
+            }
+        }
+        if let Some(tag) = &self.tag {
+            if parse_tag(tag).is_none() {
+                return Err(invalid(
+                    "tag",
+                    format!("'{}' must look like name=value", tag),
+                ));
+            }
+        }
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err(invalid("from", "must be earlier than `to`".to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Metric label name and value of the tag, if one is set
+    pub fn tag_label(&self) -> Option<(&str, &str)> {
+        self.tag.as_deref().and_then(parse_tag)
+    }
+
+    /// Fill the criteria left unset here from a saved filter
+    pub fn or(self, saved: ExecutionFilterCriteria) -> Self {
+        Self {
+            status: self.status.or(saved.status),
+            job_id: self.job_id.or(saved.job_id),
+            job_name: self.job_name.or(saved.job_name),
+            trigger_source: self.trigger_source.or(saved.trigger_source),
+            tag: self.tag.or(saved.tag),
+            from: self.from.or(saved.from),
+            to: self.to.or(saved.to),
+        }
+    }
+}
+
+/// Split a `name=value` tag; both sides must be non-empty
+fn parse_tag(tag: &str) -> Option<(&str, &str)> {
+    let (name, value) = tag.split_once('=')?;
+    let (name, value) = (name.trim(), value.trim());
+    (!name.is_empty() && !value.is_empty()).then_some((name, value))
+}
+
+/// A named, shareable execution filter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub id: Uuid,
+    pub name: String,
+    /// Token subject of the user who saved it
+    pub owner: String,
+    /// Team the filter is shared with, if any
+    pub team: Option<String>,
+    pub criteria: ExecutionFilterCriteria,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Validate the name and team of a filter being saved
+pub fn validate_saved_filter(
+    name: &str,
+    team: Option<&str>,
+    criteria: &ExecutionFilterCriteria,
+) -> Result<(), ValidationError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "name".to_string(),
+            reason: format!("must be 1 to {} characters", MAX_NAME_LEN),
+        });
+    }
+    if let Some(team) = team {
+        let team = team.trim();
+        if team.is_empty() || team.len() > MAX_TEAM_LEN {
+            return Err(ValidationError::InvalidFieldValue {
+                field: "team".to_string(),
+                reason: format!("must be 1 to {} characters", MAX_TEAM_LEN),
+            });
+        }
+    }
+    criteria.validate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_criteria_validation_and_overrides() {
+        let now = Utc::now();
+        let saved = ExecutionFilterCriteria {
+            status: Some(ExecutionStatus::Failed),
+            trigger_source: Some("webhook".to_string()),
+            tag: Some("domain = payments".to_string()),
+            from: Some(now - Duration::days(1)),
+            to: Some(now),
+            ..Default::default()
+        };
+        assert!(saved.validate().is_ok());
+        assert_eq!(saved.tag_label(), Some(("domain", "payments")));
+
+        // Query parameters win over the saved criteria
+        let applied = ExecutionFilterCriteria {
+            status: Some(ExecutionStatus::Timeout),
+            ..Default::default()
+        }
+        .or(saved.clone());
+        assert_eq!(applied.status, Some(ExecutionStatus::Timeout));
+        assert_eq!(applied.trigger_source.as_deref(), Some("webhook"));
+
+        let reversed = ExecutionFilterCriteria {
+            from: saved.to,
+            to: saved.from,
+            ..Default::default()
+        };
+        assert!(reversed.validate().is_err());
+
+        for tag in ["domain", "=payments", "domain="] {
+            let criteria = ExecutionFilterCriteria {
+                tag: Some(tag.to_string()),
+                ..Default::default()
+            };
+            assert!(criteria.validate().is_err(), "{} should be rejected", tag);
+        }
+
+        assert!(validate_saved_filter("  ", None, &saved).is_err());
+        assert!(validate_saved_filter("Payroll failures", Some(""), &saved).is_err());
+        assert!(validate_saved_filter("Payroll failures", Some("payments"), &saved).is_ok());
+    }
+}
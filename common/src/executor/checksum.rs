@@ -0,0 +1,239 @@
+// Checksum executor implementation
+// Purpose: Verify that a file in storage arrived intact, e.g. against the `.sha256`
+// sidecar published next to a partner file
+//
+// Without an expected value the step only reports the digest, so a later step can
+// publish it. With one, a mismatch fails the step and the file is not processed.
+
+use crate::errors::ExecutionError;
+use crate::executor::object_storage::internal_path;
+use crate::executor::JobExecutor;
+use crate::models::{ChecksumAlgorithm, JobContext, JobStep, JobType, StepOutput};
+use crate::storage::StorageService;
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use chrono::Utc;
+use md5::Md5;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Largest checksum file read; sidecars list one digest per file
+const MAX_SIDECAR_BYTES: usize = 1024 * 1024;
+
+/// ChecksumExecutor runs checksum steps
+pub struct ChecksumExecutor {
+    storage_service: Arc<dyn StorageService>,
+    reference_resolver: Arc<ReferenceResolver>,
+}
+
+impl ChecksumExecutor {
+    /// Create a new ChecksumExecutor reading files from the given storage
+    pub fn new(storage_service: Arc<dyn StorageService>) -> Self {
+        Self {
+            storage_service,
+            reference_resolver: Arc::new(ReferenceResolver::new()),
+        }
+    }
+
+    fn resolve(
+        &self,
+        field: &str,
+        value: &str,
+        context: &JobContext,
+    ) -> Result<String, ExecutionError> {
+        self.reference_resolver
+            .resolve(value, context)
+            .map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to resolve references in {}: {}",
+                    field, e
+                ))
+            })
+    }
+
+    async fn load(&self, path: &str) -> Result<Vec<u8>, ExecutionError> {
+        self.storage_service.load_file(path).await.map_err(|e| {
+            ExecutionError::StorageFailed(format!("Failed to load file '{}': {}", path, e))
+        })
+    }
+}
+
+#[async_trait]
+impl JobExecutor for ChecksumExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Checksum {
+            path,
+            algorithm,
+            expected,
+            expected_path,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "ChecksumExecutor can only execute Checksum job types".to_string(),
+            ));
+        };
+
+        if expected.is_some() && expected_path.is_some() {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "Checksum steps take at most one of expected and expected_path".to_string(),
+            ));
+        }
+
+        let path = internal_path(&self.resolve("path", path, context)?, context)?;
+        let data = self.load(&path).await?;
+        let checksum = digest(*algorithm, &data);
+
+        let expected = match (expected, expected_path) {
+            (Some(expected), None) => Some(self.resolve("expected", expected, context)?),
+            (None, Some(sidecar)) => {
+                let sidecar =
+                    internal_path(&self.resolve("expected_path", sidecar, context)?, context)?;
+                let contents = self.load(&sidecar).await?;
+                if contents.len() > MAX_SIDECAR_BYTES {
+                    return Err(ExecutionError::InvalidJobDefinition(format!(
+                        "Checksum file '{}' exceeds {} bytes",
+                        sidecar, MAX_SIDECAR_BYTES
+                    )));
+                }
+                let contents = String::from_utf8_lossy(&contents);
+                let filename = path.rsplit('/').next().unwrap_or(&path);
+                let expected = sidecar_digest(&contents, filename).ok_or_else(|| {
+                    ExecutionError::ChecksumMismatch(format!(
+                        "Checksum file '{}' has no digest for '{}'",
+                        sidecar, filename
+                    ))
+                })?;
+                Some(expected)
+            }
+            _ => None,
+        };
+
+        if let Some(expected) = &expected {
+            let expected = expected.trim().to_ascii_lowercase();
+            if !is_digest(&expected, *algorithm) {
+                return Err(ExecutionError::InvalidJobDefinition(format!(
+                    "Expected checksum '{}' is not a {} digest",
+                    expected, algorithm
+                )));
+            }
+            if expected != checksum {
+                return Err(ExecutionError::ChecksumMismatch(format!(
+                    "'{}' has {} {}, expected {}",
+                    path, algorithm, checksum, expected
+                )));
+            }
+            tracing::info!(path = %path, algorithm = %algorithm, "Checksum verified");
+        }
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "path": path,
+                "algorithm": algorithm,
+                "checksum": checksum,
+                "size_bytes": data.len(),
+                "verified": expected.is_some(),
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// Lowercase hex digest of the data
+fn digest(algorithm: ChecksumAlgorithm, data: &[u8]) -> String {
+    match algorithm {
+        ChecksumAlgorithm::Md5 => hex::encode(Md5::digest(data)),
+        ChecksumAlgorithm::Sha256 => hex::encode(Sha256::digest(data)),
+    }
+}
+
+/// Whether `value` is a hex digest of the algorithm's length
+fn is_digest(value: &str, algorithm: ChecksumAlgorithm) -> bool {
+    let length = match algorithm {
+        ChecksumAlgorithm::Md5 => 32,
+        ChecksumAlgorithm::Sha256 => 64,
+    };
+    value.len() == length && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Digest for `filename` in a checksum file: a `<digest>  <name>` line for it (the
+/// name may have a directory or a `*` binary marker), or the only digest in the file
+fn sidecar_digest(contents: &str, filename: &str) -> Option<String> {
+    let entries: Vec<(&str, Option<&str>)> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once(char::is_whitespace) {
+            Some((digest, name)) => (digest, Some(name.trim().trim_start_matches('*'))),
+            None => (line, None),
+        })
+        .collect();
+
+    let named = entries.iter().find(|(_, name)| {
+        name.is_some_and(|name| name == filename || name.ends_with(&format!("/{}", filename)))
+    });
+    match (named, entries.as_slice()) {
+        (Some((digest, _)), _) => Some(digest.to_string()),
+        (None, [(digest, _)]) => Some(digest.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digests() {
+        assert_eq!(
+            digest(ChecksumAlgorithm::Sha256, b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            digest(ChecksumAlgorithm::Md5, b"abc"),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert!(is_digest(
+            "900150983cd24fb0d6963f7d28e17f72",
+            ChecksumAlgorithm::Md5
+        ));
+        assert!(!is_digest(
+            "900150983cd24fb0d6963f7d28e17f72",
+            ChecksumAlgorithm::Sha256
+        ));
+    }
+
+    #[test]
+    fn test_sidecar_digest() {
+        let listing = "# generated\naaa  orders.csv\nbbb *out/invoices.csv\n";
+        assert_eq!(
+            sidecar_digest(listing, "invoices.csv"),
+            Some("bbb".to_string())
+        );
+        assert_eq!(
+            sidecar_digest(listing, "orders.csv"),
+            Some("aaa".to_string())
+        );
+        assert_eq!(sidecar_digest(listing, "other.csv"), None);
+
+        // A single digest applies whatever name it was written with
+        assert_eq!(
+            sidecar_digest("ccc\n", "orders.csv"),
+            Some("ccc".to_string())
+        );
+        assert_eq!(
+            sidecar_digest("ccc  orders.csv.part", "orders.csv"),
+            Some("ccc".to_string())
+        );
+    }
+}
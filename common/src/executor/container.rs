@@ -0,0 +1,558 @@
+// Container executor implementation
+// Purpose: Run a one-shot Docker container as a step, as an escape hatch for
+// workloads no other step type covers
+//
+// The worker talks to the Docker daemon named by DOCKER_HOST (the local socket by
+// default). Context files are copied into the container rather than bind-mounted, so
+// the worker may itself run in a container. The container is removed afterwards,
+// also when the step is dropped mid-run, e.g. by its timeout or a cancellation;
+// its logs are kept in the execution's files either way, with the secrets of the
+// step's environment masked. Containers left behind by a worker that died are
+// removed by `remove_orphaned_containers` when a worker starts.
+
+use crate::db::repositories::execution::ExecutionRepository;
+use crate::errors::ExecutionError;
+use crate::executor::object_storage::internal_path;
+use crate::executor::template::{render_field, string_variables};
+use crate::executor::JobExecutor;
+use crate::models::{ContainerMount, FileMetadata, JobContext, JobStep, JobType, StepOutput};
+use crate::storage::StorageService;
+use async_trait::async_trait;
+use bollard::container::{
+    Config, CreateContainerOptions, KillContainerOptions, ListContainersOptions, LogOutput,
+    LogsOptions, RemoveContainerOptions, UploadToContainerOptions, WaitContainerOptions,
+};
+use bollard::errors::Error as DockerError;
+use bollard::image::CreateImageOptions;
+use bollard::models::HostConfig;
+use bollard::Docker;
+use chrono::Utc;
+use futures::{StreamExt, TryStreamExt};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Most log output kept per stream, in bytes
+const MAX_LOG_BYTES: usize = 1024 * 1024;
+
+/// Log output returned in the step output, in bytes from the end of each stream
+const OUTPUT_LOG_TAIL_BYTES: usize = 16 * 1024;
+
+/// Label marking containers started by the worker
+const EXECUTION_LABEL: &str = "enterprise-cron.execution-id";
+
+/// ContainerExecutor runs container steps
+pub struct ContainerExecutor {
+    storage_service: Arc<dyn StorageService>,
+    default_timeout_seconds: u64,
+}
+
+impl ContainerExecutor {
+    /// Create a new ContainerExecutor; steps without a timeout get the given one
+    pub fn new(storage_service: Arc<dyn StorageService>, default_timeout_seconds: u64) -> Self {
+        Self {
+            storage_service,
+            default_timeout_seconds,
+        }
+    }
+
+    /// Tar archive of the mounted files, to be extracted at `/`
+    async fn mount_archive(
+        &self,
+        mounts: &[ContainerMount],
+        context: &JobContext,
+        variables: &HashMap<String, String>,
+    ) -> Result<Vec<u8>, ExecutionError> {
+        let mut archive = tar::Builder::new(Vec::new());
+        for (i, mount) in mounts.iter().enumerate() {
+            let source = render_field(
+                &mount.source,
+                context,
+                variables,
+                &format!("mounts[{}].source", i),
+            )?;
+            let source = internal_path(&source, context)?;
+            let target = container_path(&render_field(
+                &mount.target,
+                context,
+                variables,
+                &format!("mounts[{}].target", i),
+            )?)?;
+
+            let data = self.storage_service.load_file(&source).await.map_err(|e| {
+                ExecutionError::StorageFailed(format!("Failed to load '{}': {}", source, e))
+            })?;
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(Utc::now().timestamp().max(0) as u64);
+            archive
+                .append_data(&mut header, &target, data.as_slice())
+                .map_err(|e| {
+                    ExecutionError::ContainerFailed(format!("Failed to pack '{}': {}", source, e))
+                })?;
+        }
+
+        archive
+            .into_inner()
+            .map_err(|e| ExecutionError::ContainerFailed(format!("Failed to pack mounts: {}", e)))
+    }
+
+    /// Pull the image unless it's already present
+    async fn ensure_image(&self, docker: &Docker, image: &str) -> Result<(), ExecutionError> {
+        match docker.inspect_image(image).await {
+            Ok(_) => return Ok(()),
+            Err(DockerError::DockerResponseServerError {
+                status_code: 404, ..
+            }) => {}
+            Err(e) => return Err(docker_error("inspect image", e)),
+        }
+
+        let (from_image, tag) = image_reference(image);
+        tracing::info!(image = %image, "Pulling container image");
+        docker
+            .create_image(
+                Some(CreateImageOptions {
+                    from_image,
+                    tag,
+                    ..Default::default()
+                }),
+                None,
+                None,
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .map_err(|e| docker_error("pull image", e))?;
+        Ok(())
+    }
+
+    /// Wait for the container to exit; returns its exit code
+    async fn wait(&self, docker: &Docker, id: &str) -> Result<i64, ExecutionError> {
+        let mut waits = docker.wait_container(id, None::<WaitContainerOptions<String>>);
+        match waits.next().await {
+            Some(Ok(response)) => Ok(response.status_code),
+            // Non-zero exits come back as errors
+            Some(Err(DockerError::DockerContainerWaitError { code, .. })) => Ok(code),
+            Some(Err(e)) => Err(docker_error("wait for container", e)),
+            None => Err(ExecutionError::ContainerFailed(
+                "Docker closed the wait without an exit code".to_string(),
+            )),
+        }
+    }
+
+    /// Stdout and stderr of the container, each cut at `MAX_LOG_BYTES`
+    async fn logs(&self, docker: &Docker, id: &str) -> (Vec<u8>, Vec<u8>) {
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut logs = docker.logs(
+            id,
+            Some(LogsOptions::<String> {
+                stdout: true,
+                stderr: true,
+                ..Default::default()
+            }),
+        );
+        while let Some(chunk) = logs.next().await {
+            let (buffer, bytes) = match chunk {
+                Ok(LogOutput::StdOut { message }) => (&mut stdout, message),
+                Ok(LogOutput::StdErr { message }) => (&mut stderr, message),
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::warn!(container_id = %id, error = %e, "Failed to read container logs");
+                    break;
+                }
+            };
+            let room = MAX_LOG_BYTES.saturating_sub(buffer.len());
+            buffer.extend_from_slice(&bytes[..bytes.len().min(room)]);
+        }
+        (stdout, stderr)
+    }
+
+    /// Keep the logs with the execution's files
+    async fn store_logs(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+        stdout: &[u8],
+        stderr: &[u8],
+    ) -> Result<FileMetadata, ExecutionError> {
+        let mut log = Vec::with_capacity(stdout.len() + stderr.len() + 32);
+        log.extend_from_slice(b"==> stdout <==\n");
+        log.extend_from_slice(stdout);
+        log.extend_from_slice(b"\n==> stderr <==\n");
+        log.extend_from_slice(stderr);
+
+        let filename = format!("{}.log", step.id);
+        let path = format!(
+            "jobs/{}/executions/{}/container/{}",
+            context.job_id, context.execution_id, filename
+        );
+        self.storage_service
+            .store_file(&path, &log)
+            .await
+            .map_err(|e| {
+                ExecutionError::StorageFailed(format!("Failed to store container logs: {}", e))
+            })?;
+
+        let metadata = FileMetadata {
+            path,
+            filename,
+            size: log.len() as u64,
+            mime_type: Some("text/plain".to_string()),
+            row_count: None,
+            created_at: Utc::now(),
+        };
+        context.add_file_metadata(metadata.clone());
+        Ok(metadata)
+    }
+}
+
+/// Normalized absolute path inside the container, without the leading slash
+fn container_path(target: &str) -> Result<String, ExecutionError> {
+    let invalid = || {
+        ExecutionError::InvalidJobDefinition(format!(
+            "Mount target '{}' must be an absolute file path",
+            target
+        ))
+    };
+    if !target.starts_with('/') || target.ends_with('/') {
+        return Err(invalid());
+    }
+    let segments: Vec<&str> = target
+        .split('/')
+        .filter(|s| !s.is_empty() && *s != ".")
+        .collect();
+    if segments.is_empty() || segments.contains(&"..") {
+        return Err(invalid());
+    }
+    Ok(segments.join("/"))
+}
+
+/// Image and tag to pull; images without a tag or digest get `latest`, since Docker
+/// would otherwise pull every tag
+fn image_reference(image: &str) -> (String, String) {
+    if image.contains('@') {
+        return (image.to_string(), String::new());
+    }
+    let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match image[name_start..].rfind(':') {
+        Some(i) => (
+            image[..name_start + i].to_string(),
+            image[name_start + i + 1..].to_string(),
+        ),
+        None => (image.to_string(), "latest".to_string()),
+    }
+}
+
+/// The last `max` bytes of a log as text
+fn log_tail(log: &[u8], max: usize) -> String {
+    String::from_utf8_lossy(&log[log.len().saturating_sub(max)..]).into_owned()
+}
+
+fn docker_error(action: &str, e: DockerError) -> ExecutionError {
+    ExecutionError::ContainerFailed(format!("Failed to {}: {}", action, e))
+}
+
+/// Remove a container, killing it first if it still runs
+async fn remove_container(docker: &Docker, id: &str) {
+    if let Err(e) = docker
+        .remove_container(
+            id,
+            Some(RemoveContainerOptions {
+                force: true,
+                ..Default::default()
+            }),
+        )
+        .await
+    {
+        tracing::warn!(container_id = %id, error = %e, "Failed to remove container");
+    }
+}
+
+/// Removes its container when dropped before `remove` ran, so a step dropped
+/// mid-run doesn't leave the container running
+struct ContainerGuard {
+    docker: Docker,
+    id: Option<String>,
+}
+
+impl ContainerGuard {
+    async fn remove(mut self) {
+        if let Some(id) = self.id.take() {
+            remove_container(&self.docker, &id).await;
+        }
+    }
+}
+
+impl Drop for ContainerGuard {
+    fn drop(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        let docker = self.docker.clone();
+        tracing::warn!(container_id = %id, "Step dropped while its container ran, removing it");
+        tokio::spawn(async move { remove_container(&docker, &id).await });
+    }
+}
+
+/// Remove the containers of finished or unknown executions, e.g. left behind by a
+/// worker that died mid-step; returns how many were removed
+///
+/// Containers of executions still running are kept, as another worker may be
+/// running them; a later sweep removes them once their execution finished.
+pub async fn remove_orphaned_containers(
+    execution_repo: &ExecutionRepository,
+) -> Result<usize, ExecutionError> {
+    let docker =
+        Docker::connect_with_local_defaults().map_err(|e| docker_error("connect to Docker", e))?;
+    let containers = docker
+        .list_containers(Some(ListContainersOptions::<String> {
+            all: true,
+            filters: HashMap::from([("label".to_string(), vec![EXECUTION_LABEL.to_string()])]),
+            ..Default::default()
+        }))
+        .await
+        .map_err(|e| docker_error("list containers", e))?;
+
+    let mut removed = 0;
+    for container in containers {
+        let Some(id) = container.id else {
+            continue;
+        };
+        let execution_id = container
+            .labels
+            .as_ref()
+            .and_then(|labels| labels.get(EXECUTION_LABEL))
+            .and_then(|value| value.parse::<Uuid>().ok());
+        let finished = match execution_id {
+            Some(execution_id) => match execution_repo.find_by_id(execution_id).await {
+                Ok(Some(execution)) => execution.status.is_terminal(),
+                Ok(None) => true,
+                Err(e) => {
+                    tracing::warn!(container_id = %id, error = %e, "Failed to look up the container's execution, keeping it");
+                    continue;
+                }
+            },
+            None => true,
+        };
+        if finished {
+            tracing::info!(container_id = %id, execution_id = ?execution_id, "Removing orphaned container");
+            remove_container(&docker, &id).await;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[async_trait]
+impl JobExecutor for ContainerExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Container {
+            image,
+            command,
+            env,
+            mounts,
+            timeout_seconds,
+            memory_mb,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "ContainerExecutor can only execute Container job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for container steps".to_string(),
+            ));
+        }
+
+        let variables = string_variables(context);
+        let image = render_field(image, context, &variables, "image")?;
+        let command = command
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| render_field(arg, context, &variables, &format!("command[{}]", i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut env = env
+            .iter()
+            .map(|(name, value)| {
+                render_field(value, context, &variables, &format!("env.{}", name))
+                    .map(|value| format!("{}={}", name, value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // The step's environment comes last, so it wins over the same name in `env`
+        env.extend(
+            context
+                .env
+                .vars()
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value)),
+        );
+        let archive = self.mount_archive(mounts, context, &variables).await?;
+        let timeout_seconds = timeout_seconds.unwrap_or(self.default_timeout_seconds);
+
+        let docker = Docker::connect_with_local_defaults()
+            .map_err(|e| docker_error("connect to Docker", e))?;
+        self.ensure_image(&docker, &image).await?;
+
+        let config = Config {
+            image: Some(image.clone()),
+            cmd: (!command.is_empty()).then_some(command),
+            env: Some(env),
+            labels: Some(HashMap::from([(
+                EXECUTION_LABEL.to_string(),
+                context.execution_id.to_string(),
+            )])),
+            host_config: Some(HostConfig {
+                memory: memory_mb.map(|mb| (mb * 1024 * 1024) as i64),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let container = docker
+            .create_container(None::<CreateContainerOptions<String>>, config)
+            .await
+            .map_err(|e| docker_error("create container", e))?;
+        let id = container.id;
+        tracing::info!(container_id = %id, image = %image, "Container created");
+        let guard = ContainerGuard {
+            docker: docker.clone(),
+            id: Some(id.clone()),
+        };
+
+        let run = async {
+            if !mounts.is_empty() {
+                docker
+                    .upload_to_container(
+                        &id,
+                        Some(UploadToContainerOptions {
+                            path: "/".to_string(),
+                            ..Default::default()
+                        }),
+                        archive.into(),
+                    )
+                    .await
+                    .map_err(|e| docker_error("copy mounts into container", e))?;
+            }
+            docker
+                .start_container::<String>(&id, None)
+                .await
+                .map_err(|e| docker_error("start container", e))?;
+
+            match tokio::time::timeout(
+                Duration::from_secs(timeout_seconds),
+                self.wait(&docker, &id),
+            )
+            .await
+            {
+                Ok(exit_code) => exit_code.map(Some),
+                Err(_) => {
+                    tracing::warn!(container_id = %id, timeout_seconds, "Container timed out");
+                    if let Err(e) = docker
+                        .kill_container(&id, None::<KillContainerOptions<String>>)
+                        .await
+                    {
+                        tracing::warn!(container_id = %id, error = %e, "Failed to kill container");
+                    }
+                    Ok(None)
+                }
+            }
+        };
+        let exit_code = run.await;
+
+        // Keep the logs and clean up whatever happened
+        let (stdout, stderr) = self.logs(&docker, &id).await;
+        let stdout = context.env.redact_bytes(stdout);
+        let stderr = context.env.redact_bytes(stderr);
+        let log_file = self.store_logs(step, context, &stdout, &stderr).await;
+        guard.remove().await;
+
+        let exit_code = match exit_code? {
+            Some(code) => code,
+            None => return Err(ExecutionError::Timeout(timeout_seconds)),
+        };
+        let log_file = log_file?;
+        if exit_code != 0 {
+            return Err(ExecutionError::ContainerFailed(format!(
+                "Container exited with code {}: {}",
+                exit_code,
+                log_tail(&stderr, 1024).trim()
+            )));
+        }
+
+        tracing::info!(container_id = %id, "Container completed");
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "image": image,
+                "container_id": id,
+                "exit_code": exit_code,
+                "stdout": log_tail(&stdout, OUTPUT_LOG_TAIL_BYTES),
+                "stderr": log_tail(&stderr, OUTPUT_LOG_TAIL_BYTES),
+                "log_file": log_file,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_paths() {
+        assert_eq!(container_path("/data/input.csv").unwrap(), "data/input.csv");
+        assert_eq!(
+            container_path("//data/./input.csv").unwrap(),
+            "data/input.csv"
+        );
+        assert!(container_path("data/input.csv").is_err());
+        assert!(container_path("/data/").is_err());
+        assert!(container_path("/../etc/passwd").is_err());
+        assert!(container_path("/").is_err());
+    }
+
+    #[test]
+    fn test_image_references() {
+        assert_eq!(
+            image_reference("alpine"),
+            ("alpine".to_string(), "latest".to_string())
+        );
+        assert_eq!(
+            image_reference("python:3.12-slim"),
+            ("python".to_string(), "3.12-slim".to_string())
+        );
+        assert_eq!(
+            image_reference("registry.local:5000/tools/report"),
+            (
+                "registry.local:5000/tools/report".to_string(),
+                "latest".to_string()
+            )
+        );
+        assert_eq!(
+            image_reference("registry.local:5000/tools/report:v2"),
+            (
+                "registry.local:5000/tools/report".to_string(),
+                "v2".to_string()
+            )
+        );
+        assert_eq!(
+            image_reference("alpine@sha256:abcd"),
+            ("alpine@sha256:abcd".to_string(), String::new())
+        );
+    }
+}
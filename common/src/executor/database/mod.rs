@@ -2,27 +2,34 @@
 // Requirements: 3.7, 3.8, 3.9, 3.10
 // Tách theo RECC 2025 rules - Mỗi database type một file riêng
 
+mod mongodb;
 mod mysql;
 mod oracle;
 mod postgresql;
+mod sqlite;
 
 use crate::errors::ExecutionError;
+use crate::executor::output_sink::OutputSink;
 use crate::executor::JobExecutor;
 use crate::models::{DatabaseType, JobContext, JobStep, JobType, QueryType, StepOutput};
+use crate::storage::StorageService;
 use crate::worker::reference::ReferenceResolver;
 use async_trait::async_trait;
 use chrono::Utc;
 use std::sync::Arc;
 use std::time::Duration;
 
+pub use mongodb::MongoDBExecutor;
 pub use mysql::MySQLExecutor;
 pub use oracle::OracleExecutor;
 pub use postgresql::PostgreSQLExecutor;
+pub use sqlite::SqliteExecutor;
 
 /// DatabaseExecutor executes database query jobs
 pub struct DatabaseExecutor {
     timeout: Duration,
     reference_resolver: Arc<ReferenceResolver>,
+    output_storage: Option<Arc<dyn StorageService>>,
 }
 
 impl DatabaseExecutor {
@@ -31,6 +38,7 @@ impl DatabaseExecutor {
         Self {
             timeout: Duration::from_secs(timeout_seconds),
             reference_resolver: Arc::new(ReferenceResolver::new()),
+            output_storage: None,
         }
     }
 
@@ -39,9 +47,16 @@ impl DatabaseExecutor {
         Self {
             timeout: Duration::from_secs(timeout_seconds),
             reference_resolver,
+            output_storage: None,
         }
     }
 
+    /// Storage that steps with `stream_output` write their rows into
+    pub fn with_output_storage(mut self, storage: Arc<dyn StorageService>) -> Self {
+        self.output_storage = Some(storage);
+        self
+    }
+
     /// Get the timeout duration
     pub fn timeout(&self) -> Duration {
         self.timeout
@@ -130,13 +145,80 @@ impl JobExecutor for DatabaseExecutor {
                     parameters: resolved_parameters,
                 }
             }
+            QueryType::Mongo {
+                collection,
+                operation,
+            } => QueryType::Mongo {
+                collection: self
+                    .reference_resolver
+                    .resolve(collection, context)
+                    .map_err(|e| {
+                        ExecutionError::InvalidJobDefinition(format!(
+                            "Failed to resolve collection references: {}",
+                            e
+                        ))
+                    })?,
+                operation: *operation,
+            },
+        };
+
+        // Values bound to the query's placeholders, e.g. an incremental sync's watermark
+        let parameters = std::mem::take(&mut context.query_parameters);
+        if !parameters.is_empty()
+            && (*database_type == DatabaseType::MongoDB
+                || !matches!(resolved_query_type, QueryType::RawSql))
+        {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "Query parameters can only be bound to raw SQL queries".to_string(),
+            ));
+        }
+
+        // Large result sets go to storage; only a summary enters the context
+        let mut sink = if step.stream_output {
+            let storage = self.output_storage.as_ref().ok_or_else(|| {
+                ExecutionError::InvalidJobDefinition(
+                    "stream_output is not available: no output storage configured".to_string(),
+                )
+            })?;
+            Some(OutputSink::create(Arc::clone(storage), context, &step.id, "jsonl").await?)
+        } else {
+            None
         };
 
         // Execute the database query based on database type
         // Requirement 3.7: Support PostgreSQL, MySQL, and Oracle
-        let output = match database_type {
-            DatabaseType::PostgreSQL => {
-                let executor = PostgreSQLExecutor::new(self.timeout);
+        let output = match (database_type, sink.as_mut()) {
+            (DatabaseType::MongoDB, _) => {
+                let QueryType::Mongo {
+                    collection,
+                    operation,
+                } = &resolved_query_type
+                else {
+                    return Err(ExecutionError::InvalidJobDefinition(
+                        "MongoDB steps need a mongo query_type".to_string(),
+                    ));
+                };
+                let executor = MongoDBExecutor::new(self.timeout);
+                executor
+                    .execute_operation(
+                        &resolved_connection_string,
+                        collection,
+                        *operation,
+                        &resolved_query,
+                    )
+                    .await?
+            }
+            // PostgreSQL, MySQL and Oracle raw SQL rows are written as they are fetched
+            (DatabaseType::PostgreSQL, Some(sink))
+                if matches!(resolved_query_type, QueryType::RawSql) =>
+            {
+                let executor = PostgreSQLExecutor::new(self.timeout).with_parameters(parameters);
+                executor
+                    .stream_query(&resolved_connection_string, &resolved_query, sink)
+                    .await?
+            }
+            (DatabaseType::PostgreSQL, _) => {
+                let executor = PostgreSQLExecutor::new(self.timeout).with_parameters(parameters);
                 executor
                     .execute_query(
                         &resolved_connection_string,
@@ -145,8 +227,16 @@ impl JobExecutor for DatabaseExecutor {
                     )
                     .await?
             }
-            DatabaseType::MySQL => {
-                let executor = MySQLExecutor::new(self.timeout);
+            (DatabaseType::MySQL, Some(sink))
+                if matches!(resolved_query_type, QueryType::RawSql) =>
+            {
+                let executor = MySQLExecutor::new(self.timeout).with_parameters(parameters);
+                executor
+                    .stream_query(&resolved_connection_string, &resolved_query, sink)
+                    .await?
+            }
+            (DatabaseType::MySQL, _) => {
+                let executor = MySQLExecutor::new(self.timeout).with_parameters(parameters);
                 executor
                     .execute_query(
                         &resolved_connection_string,
@@ -155,8 +245,16 @@ impl JobExecutor for DatabaseExecutor {
                     )
                     .await?
             }
-            DatabaseType::Oracle => {
-                let executor = OracleExecutor::new(self.timeout);
+            (DatabaseType::Oracle, Some(sink))
+                if matches!(resolved_query_type, QueryType::RawSql) =>
+            {
+                let executor = OracleExecutor::new(self.timeout).with_parameters(parameters);
+                executor
+                    .stream_query(&resolved_connection_string, &resolved_query, sink)
+                    .await?
+            }
+            (DatabaseType::Oracle, _) => {
+                let executor = OracleExecutor::new(self.timeout).with_parameters(parameters);
                 executor
                     .execute_query(
                         &resolved_connection_string,
@@ -165,6 +263,40 @@ impl JobExecutor for DatabaseExecutor {
                     )
                     .await?
             }
+            (DatabaseType::Sqlite, _) => {
+                let executor = SqliteExecutor::new(self.timeout).with_parameters(parameters);
+                executor
+                    .execute_query(
+                        &resolved_connection_string,
+                        &resolved_query,
+                        &resolved_query_type,
+                    )
+                    .await?
+            }
+        };
+
+        // SQLite, MongoDB and stored procedures return the full result set; move its
+        // rows to storage
+        let output = match sink {
+            Some(mut sink) => {
+                let mut output = output;
+                if let Some(serde_json::Value::Array(rows)) =
+                    output.as_object_mut().and_then(|o| o.remove("rows"))
+                {
+                    for row in rows {
+                        sink.write_record(row).await?;
+                    }
+                }
+                let summary = sink.finish().await?;
+                if let Some(object) = output.as_object_mut() {
+                    object.insert(
+                        "output".to_string(),
+                        serde_json::to_value(summary).unwrap_or_default(),
+                    );
+                }
+                output
+            }
+            None => output,
         };
 
         let completed_at = Utc::now();
@@ -179,6 +311,49 @@ impl JobExecutor for DatabaseExecutor {
     }
 }
 
+/// Placeholder of the `index`th (1-based) bound parameter in a raw SQL query;
+/// MongoDB queries have none
+pub fn placeholder(database_type: &DatabaseType, index: usize) -> Option<String> {
+    match database_type {
+        DatabaseType::PostgreSQL => Some(format!("${}", index)),
+        DatabaseType::Oracle => Some(format!(":{}", index)),
+        DatabaseType::MySQL | DatabaseType::Sqlite => Some("?".to_string()),
+        DatabaseType::MongoDB => None,
+    }
+}
+
+/// A parameter as the drivers bind it; arrays and objects bind as their JSON text
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum BindValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl From<&serde_json::Value> for BindValue {
+    fn from(value: &serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => BindValue::Null,
+            serde_json::Value::Bool(b) => BindValue::Bool(*b),
+            serde_json::Value::Number(n) => match n.as_i64() {
+                Some(i) => BindValue::Int(i),
+                None => BindValue::Float(n.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(s) => BindValue::Text(s.clone()),
+            other => BindValue::Text(other.to_string()),
+        }
+    }
+}
+
+/// Error for a MongoDB operation sent to a SQL database
+fn mongo_on_sql_database() -> ExecutionError {
+    ExecutionError::InvalidJobDefinition(
+        "mongo query_type is only supported with database_type mongodb".to_string(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,8 +382,16 @@ mod tests {
             },
             condition: None,
             on_failure: None,
+            compensate_with: Vec::new(),
+            acquire_lock: None,
+            set_state: Default::default(),
+            env: Default::default(),
             timeout_seconds: None,
             retry_count: None,
+            stream_output: false,
+            pii: false,
+            dependency: None,
+            connection: None,
         };
 
         let mut context = JobContext {
@@ -218,6 +401,14 @@ mod tests {
             steps: std::collections::HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: std::collections::HashMap::new(),
+            pending_state: std::collections::HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         let result = executor.execute(&step, &mut context).await;
@@ -0,0 +1,389 @@
+// MongoDB executor implementation
+// Runs find/aggregate/insert/update on one collection. Query bodies are Extended JSON,
+// so `{"$oid": ..}` and `{"$date": ..}` values reach the server with their BSON types;
+// result documents come back as relaxed Extended JSON.
+
+use crate::errors::ExecutionError;
+use crate::models::MongoOperation;
+use futures::TryStreamExt;
+use mongodb::action::Action;
+use mongodb::bson::{Bson, Document};
+use mongodb::options::{ClientOptions, UpdateModifications};
+use mongodb::{Client, Collection};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+/// Body of a `find` operation
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FindBody {
+    #[serde(default)]
+    filter: Option<serde_json::Value>,
+    #[serde(default)]
+    projection: Option<serde_json::Value>,
+    #[serde(default)]
+    sort: Option<serde_json::Value>,
+    #[serde(default)]
+    limit: Option<i64>,
+}
+
+/// Body of an `aggregate` operation
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AggregateBody {
+    pipeline: Vec<serde_json::Value>,
+}
+
+/// Body of an `insert` operation
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct InsertBody {
+    documents: Vec<serde_json::Value>,
+}
+
+/// Body of an `update` operation
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct UpdateBody {
+    filter: serde_json::Value,
+    update: serde_json::Value,
+    #[serde(default)]
+    upsert: bool,
+}
+
+/// Parsed operation, ready to send
+#[derive(Debug)]
+enum MongoCommand {
+    Find {
+        filter: Document,
+        projection: Option<Document>,
+        sort: Option<Document>,
+        limit: Option<i64>,
+    },
+    Aggregate {
+        pipeline: Vec<Document>,
+    },
+    Insert {
+        documents: Vec<Document>,
+    },
+    Update {
+        filter: Document,
+        update: UpdateModifications,
+        upsert: bool,
+    },
+}
+
+impl MongoCommand {
+    /// Parse the step's query body for an operation
+    fn parse(operation: MongoOperation, body: &str) -> Result<Self, ExecutionError> {
+        let invalid = |e: serde_json::Error| {
+            ExecutionError::InvalidJobDefinition(format!(
+                "Invalid MongoDB {:?} body: {}",
+                operation, e
+            ))
+        };
+        // An empty find body reads the whole collection
+        let body = if body.trim().is_empty() { "{}" } else { body };
+
+        Ok(match operation {
+            MongoOperation::Find => {
+                let body: FindBody = serde_json::from_str(body).map_err(invalid)?;
+                MongoCommand::Find {
+                    filter: body
+                        .filter
+                        .map(|v| to_document("filter", v))
+                        .transpose()?
+                        .unwrap_or_default(),
+                    projection: body
+                        .projection
+                        .map(|v| to_document("projection", v))
+                        .transpose()?,
+                    sort: body.sort.map(|v| to_document("sort", v)).transpose()?,
+                    limit: body.limit,
+                }
+            }
+            MongoOperation::Aggregate => {
+                let body: AggregateBody = serde_json::from_str(body).map_err(invalid)?;
+                MongoCommand::Aggregate {
+                    pipeline: body
+                        .pipeline
+                        .into_iter()
+                        .map(|stage| to_document("pipeline stage", stage))
+                        .collect::<Result<_, _>>()?,
+                }
+            }
+            MongoOperation::Insert => {
+                let body: InsertBody = serde_json::from_str(body).map_err(invalid)?;
+                if body.documents.is_empty() {
+                    return Err(ExecutionError::InvalidJobDefinition(
+                        "MongoDB insert needs at least one document".to_string(),
+                    ));
+                }
+                MongoCommand::Insert {
+                    documents: body
+                        .documents
+                        .into_iter()
+                        .map(|doc| to_document("document", doc))
+                        .collect::<Result<_, _>>()?,
+                }
+            }
+            MongoOperation::Update => {
+                let body: UpdateBody = serde_json::from_str(body).map_err(invalid)?;
+                // A modifier document, or an aggregation pipeline
+                let update = match body.update {
+                    serde_json::Value::Array(stages) => UpdateModifications::Pipeline(
+                        stages
+                            .into_iter()
+                            .map(|stage| to_document("update pipeline stage", stage))
+                            .collect::<Result<_, _>>()?,
+                    ),
+                    update => UpdateModifications::Document(to_document("update", update)?),
+                };
+                MongoCommand::Update {
+                    filter: to_document("filter", body.filter)?,
+                    update,
+                    upsert: body.upsert,
+                }
+            }
+        })
+    }
+}
+
+/// Convert Extended JSON into a BSON document
+fn to_document(field: &str, value: serde_json::Value) -> Result<Document, ExecutionError> {
+    let bson = Bson::try_from(value).map_err(|e| {
+        ExecutionError::InvalidJobDefinition(format!(
+            "Invalid Extended JSON in MongoDB {}: {}",
+            field, e
+        ))
+    })?;
+    match bson {
+        Bson::Document(document) => Ok(document),
+        other => Err(ExecutionError::InvalidJobDefinition(format!(
+            "MongoDB {} must be a document, got {:?}",
+            field,
+            other.element_type()
+        ))),
+    }
+}
+
+/// MongoDB query executor
+pub struct MongoDBExecutor {
+    timeout: Duration,
+}
+
+impl MongoDBExecutor {
+    /// Create a new MongoDB executor
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// Run an operation on a collection of the connection string's database
+    #[tracing::instrument(skip(self, connection_string, body), fields(database_type = "mongodb"))]
+    pub async fn execute_operation(
+        &self,
+        connection_string: &str,
+        collection: &str,
+        operation: MongoOperation,
+        body: &str,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        // Bad bodies fail before connecting
+        let command = MongoCommand::parse(operation, body)?;
+
+        tracing::info!("Connecting to MongoDB database");
+
+        let mut options = ClientOptions::parse(connection_string).await.map_err(|e| {
+            ExecutionError::DatabaseConnectionFailed(format!(
+                "Invalid MongoDB connection string: {}",
+                e
+            ))
+        })?;
+        let database = options.default_database.clone().ok_or_else(|| {
+            ExecutionError::InvalidJobDefinition(
+                "MongoDB connection string must name a database, e.g. mongodb://host/orders"
+                    .to_string(),
+            )
+        })?;
+        options.connect_timeout = Some(self.timeout);
+        options.server_selection_timeout = Some(self.timeout);
+
+        let client = Client::with_options(options).map_err(|e| {
+            ExecutionError::DatabaseConnectionFailed(format!("Failed to connect to MongoDB: {}", e))
+        })?;
+        let collection = client
+            .database(&database)
+            .collection::<Document>(collection);
+
+        let result = tokio::time::timeout(self.timeout, self.run(&collection, command))
+            .await
+            .map_err(|_| ExecutionError::Timeout(self.timeout.as_secs()))?;
+
+        client.shutdown().await;
+
+        result
+    }
+
+    /// Send a parsed operation
+    async fn run(
+        &self,
+        collection: &Collection<Document>,
+        command: MongoCommand,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        let query_failed = |e: mongodb::error::Error| {
+            ExecutionError::DatabaseQueryFailed(format!("MongoDB operation failed: {}", e))
+        };
+
+        let result = match command {
+            MongoCommand::Find {
+                filter,
+                projection,
+                sort,
+                limit,
+            } => {
+                let documents: Vec<Document> = collection
+                    .find(filter)
+                    .optional(projection, |find, projection| find.projection(projection))
+                    .optional(sort, |find, sort| find.sort(sort))
+                    .optional(limit, |find, limit| find.limit(limit))
+                    .await
+                    .map_err(query_failed)?
+                    .try_collect()
+                    .await
+                    .map_err(query_failed)?;
+                rows(documents)
+            }
+            MongoCommand::Aggregate { pipeline } => {
+                let documents: Vec<Document> = collection
+                    .aggregate(pipeline)
+                    .await
+                    .map_err(query_failed)?
+                    .try_collect()
+                    .await
+                    .map_err(query_failed)?;
+                rows(documents)
+            }
+            MongoCommand::Insert { documents } => {
+                let inserted = collection
+                    .insert_many(documents)
+                    .await
+                    .map_err(query_failed)?;
+                let mut ids: Vec<(usize, Bson)> = inserted.inserted_ids.into_iter().collect();
+                ids.sort_by_key(|(index, _)| *index);
+                json!({
+                    "inserted_count": ids.len(),
+                    "inserted_ids": ids
+                        .into_iter()
+                        .map(|(_, id)| id.into_relaxed_extjson())
+                        .collect::<Vec<_>>(),
+                })
+            }
+            MongoCommand::Update {
+                filter,
+                update,
+                upsert,
+            } => {
+                let updated = collection
+                    .update_many(filter, update)
+                    .upsert(upsert)
+                    .await
+                    .map_err(query_failed)?;
+                json!({
+                    "matched_count": updated.matched_count,
+                    "modified_count": updated.modified_count,
+                    "upserted_id": updated.upserted_id.map(Bson::into_relaxed_extjson),
+                })
+            }
+        };
+
+        tracing::info!("MongoDB operation completed");
+
+        Ok(result)
+    }
+}
+
+/// Result documents, shaped like the SQL executors' rows so `stream_output` works the same
+fn rows(documents: Vec<Document>) -> serde_json::Value {
+    let rows: Vec<serde_json::Value> = documents
+        .into_iter()
+        .map(|document| Bson::Document(document).into_relaxed_extjson())
+        .collect();
+    json!({
+        "row_count": rows.len(),
+        "rows": rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_find_with_extended_json() {
+        let command = MongoCommand::parse(
+            MongoOperation::Find,
+            r#"{"filter": {"_id": {"$oid": "65a1f0c2e4b0a1b2c3d4e5f6"}, "created_at": {"$gte": {"$date": "2025-01-01T00:00:00Z"}}}, "sort": {"created_at": -1}, "limit": 10}"#,
+        )
+        .unwrap();
+        match command {
+            MongoCommand::Find {
+                filter,
+                projection,
+                sort,
+                limit,
+            } => {
+                assert!(matches!(filter.get("_id"), Some(Bson::ObjectId(_))));
+                let created_at = filter.get_document("created_at").unwrap();
+                assert!(matches!(created_at.get("$gte"), Some(Bson::DateTime(_))));
+                assert!(projection.is_none());
+                assert_eq!(sort.unwrap().get_i32("created_at").unwrap(), -1);
+                assert_eq!(limit, Some(10));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+
+        assert!(matches!(
+            MongoCommand::parse(MongoOperation::Find, "").unwrap(),
+            MongoCommand::Find { filter, .. } if filter.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_bodies() {
+        // Pipeline stages must be documents
+        assert!(MongoCommand::parse(MongoOperation::Aggregate, r#"{"pipeline": [1]}"#).is_err());
+        // Unknown keys are typos, not options
+        assert!(MongoCommand::parse(MongoOperation::Find, r#"{"fliter": {}}"#).is_err());
+        assert!(MongoCommand::parse(MongoOperation::Insert, r#"{"documents": []}"#).is_err());
+        assert!(MongoCommand::parse(
+            MongoOperation::Update,
+            r#"{"filter": {}, "update": "status"}"#
+        )
+        .is_err());
+
+        let command = MongoCommand::parse(
+            MongoOperation::Update,
+            r#"{"filter": {"status": "pending"}, "update": [{"$set": {"status": "done"}}], "upsert": true}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            command,
+            MongoCommand::Update {
+                update: UpdateModifications::Pipeline(_),
+                upsert: true,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_rows_are_relaxed_extended_json() {
+        let mut document = Document::new();
+        document.insert("name", "payroll");
+        document.insert("count", 3_i64);
+        let output = rows(vec![document]);
+        assert_eq!(output["row_count"], 1);
+        assert_eq!(output["rows"][0]["name"], "payroll");
+        assert_eq!(output["rows"][0]["count"], 3);
+    }
+}
@@ -1,20 +1,33 @@
 // MySQL executor implementation
 // Requirements: 3.10 - Execute MySQL queries and stored procedures
 
+use super::{mongo_on_sql_database, BindValue};
 use crate::errors::ExecutionError;
+use crate::executor::output_sink::OutputSink;
 use crate::models::QueryType;
+use mysql_async::prelude::Protocol;
 use serde_json::json;
 use std::time::Duration;
 
 /// MySQL query executor
 pub struct MySQLExecutor {
     _timeout: Duration,
+    parameters: Vec<serde_json::Value>,
 }
 
 impl MySQLExecutor {
     /// Create a new MySQL executor
     pub fn new(timeout: Duration) -> Self {
-        Self { _timeout: timeout }
+        Self {
+            _timeout: timeout,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Values bound to the `?` placeholders of raw SQL queries, in order
+    pub fn with_parameters(mut self, parameters: Vec<serde_json::Value>) -> Self {
+        self.parameters = parameters;
+        self
     }
 
     /// Execute MySQL query
@@ -25,23 +38,7 @@ impl MySQLExecutor {
         query: &str,
         query_type: &QueryType,
     ) -> Result<serde_json::Value, ExecutionError> {
-        tracing::info!("Connecting to MySQL database");
-
-        // Parse connection string
-        let opts = mysql_async::Opts::from_url(connection_string).map_err(|e| {
-            ExecutionError::DatabaseConnectionFailed(format!(
-                "Invalid MySQL connection string: {}",
-                e
-            ))
-        })?;
-
-        // Create connection pool
-        let pool = mysql_async::Pool::new(opts);
-
-        // Get connection
-        let mut conn = pool.get_conn().await.map_err(|e| {
-            ExecutionError::DatabaseConnectionFailed(format!("Failed to connect to MySQL: {}", e))
-        })?;
+        let (pool, mut conn) = Self::connect(connection_string).await?;
 
         let result = match query_type {
             QueryType::RawSql => self.execute_raw_sql(&mut conn, query).await?,
@@ -52,6 +49,7 @@ impl MySQLExecutor {
                 self.execute_stored_procedure(&mut conn, procedure_name, parameters)
                     .await?
             }
+            QueryType::Mongo { .. } => return Err(mongo_on_sql_database()),
         };
 
         // Disconnect
@@ -63,6 +61,83 @@ impl MySQLExecutor {
         Ok(result)
     }
 
+    /// Run a raw SQL query, writing each row to `sink` as it is read off the
+    /// connection instead of collecting the result set
+    #[tracing::instrument(
+        skip(self, connection_string, query, sink),
+        fields(database_type = "mysql")
+    )]
+    pub async fn stream_query(
+        &self,
+        connection_string: &str,
+        query: &str,
+        sink: &mut OutputSink,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        use mysql_async::prelude::*;
+
+        let (pool, mut conn) = Self::connect(connection_string).await?;
+
+        let result = if self.parameters.is_empty() {
+            match conn.query_iter(query).await {
+                Ok(rows) => write_rows(rows, sink).await,
+                Err(e) => Err(query_failed(e)),
+            }
+        } else {
+            match conn.exec_iter(query, self.bind_parameters()).await {
+                Ok(rows) => write_rows(rows, sink).await,
+                Err(e) => Err(query_failed(e)),
+            }
+        };
+
+        drop(conn);
+        pool.disconnect().await.map_err(|e| {
+            ExecutionError::DatabaseQueryFailed(format!("Failed to disconnect from MySQL: {}", e))
+        })?;
+
+        let row_count = result?;
+        tracing::info!("MySQL query streamed {} rows", row_count);
+        Ok(json!({ "row_count": row_count }))
+    }
+
+    /// Open a pool for the connection string and take a connection from it
+    async fn connect(
+        connection_string: &str,
+    ) -> Result<(mysql_async::Pool, mysql_async::Conn), ExecutionError> {
+        tracing::info!("Connecting to MySQL database");
+
+        // Parse connection string
+        let opts = mysql_async::Opts::from_url(connection_string).map_err(|e| {
+            ExecutionError::DatabaseConnectionFailed(format!(
+                "Invalid MySQL connection string: {}",
+                e
+            ))
+        })?;
+
+        // Create connection pool
+        let pool = mysql_async::Pool::new(opts);
+
+        // Get connection
+        let conn = pool.get_conn().await.map_err(|e| {
+            ExecutionError::DatabaseConnectionFailed(format!("Failed to connect to MySQL: {}", e))
+        })?;
+
+        Ok((pool, conn))
+    }
+
+    /// The executor's parameters as MySQL values
+    fn bind_parameters(&self) -> Vec<mysql_async::Value> {
+        self.parameters
+            .iter()
+            .map(|parameter| match BindValue::from(parameter) {
+                BindValue::Null => mysql_async::Value::NULL,
+                BindValue::Bool(b) => mysql_async::Value::from(b),
+                BindValue::Int(i) => mysql_async::Value::from(i),
+                BindValue::Float(f) => mysql_async::Value::from(f),
+                BindValue::Text(text) => mysql_async::Value::from(text),
+            })
+            .collect()
+    }
+
     /// Execute raw SQL query on MySQL
     #[tracing::instrument(skip(self, conn, query))]
     async fn execute_raw_sql(
@@ -74,40 +149,16 @@ impl MySQLExecutor {
 
         tracing::debug!("Executing raw SQL query on MySQL");
 
-        // Execute query and fetch results
-        let result: Vec<mysql_async::Row> = conn.query(query).await.map_err(|e| {
-            ExecutionError::DatabaseQueryFailed(format!("MySQL query failed: {}", e))
-        })?;
+        // Execute query and fetch results; bound parameters need a prepared statement
+        let result: Vec<mysql_async::Row> = if self.parameters.is_empty() {
+            conn.query(query).await
+        } else {
+            conn.exec(query, self.bind_parameters()).await
+        }
+        .map_err(query_failed)?;
 
         // Convert rows to JSON
-        let mut result_rows = Vec::new();
-        for row in result {
-            let mut row_map = serde_json::Map::new();
-
-            // Get column names from the row
-            let columns = row.columns_ref();
-
-            for (i, column) in columns.iter().enumerate() {
-                let column_name = column.name_str();
-
-                // Try to get value as different types
-                let value: serde_json::Value = if let Some(v) = row.get::<Option<String>, _>(i) {
-                    json!(v)
-                } else if let Some(v) = row.get::<Option<i64>, _>(i) {
-                    json!(v)
-                } else if let Some(v) = row.get::<Option<f64>, _>(i) {
-                    json!(v)
-                } else if let Some(v) = row.get::<Option<bool>, _>(i) {
-                    json!(v)
-                } else {
-                    json!(null)
-                };
-
-                row_map.insert(column_name.to_string(), value);
-            }
-
-            result_rows.push(serde_json::Value::Object(row_map));
-        }
+        let result_rows: Vec<serde_json::Value> = result.iter().map(row_to_json).collect();
 
         let result = json!({
             "rows": result_rows,
@@ -162,3 +213,50 @@ impl MySQLExecutor {
         Ok(result)
     }
 }
+
+/// Write the rows of a result to `sink` one by one, returning how many there were
+async fn write_rows<P: Protocol>(
+    mut rows: mysql_async::QueryResult<'_, 'static, P>,
+    sink: &mut OutputSink,
+) -> Result<u64, ExecutionError> {
+    let mut row_count: u64 = 0;
+    while let Some(row) = rows.next().await.map_err(query_failed)? {
+        sink.write_record(row_to_json(&row)).await?;
+        row_count += 1;
+    }
+    rows.drop_result().await.map_err(query_failed)?;
+    Ok(row_count)
+}
+
+fn query_failed(e: mysql_async::Error) -> ExecutionError {
+    ExecutionError::DatabaseQueryFailed(format!("MySQL query failed: {}", e))
+}
+
+/// Convert a MySQL row to a JSON object keyed by column name
+fn row_to_json(row: &mysql_async::Row) -> serde_json::Value {
+    let mut row_map = serde_json::Map::new();
+
+    // Get column names from the row
+    let columns = row.columns_ref();
+
+    for (i, column) in columns.iter().enumerate() {
+        let column_name = column.name_str();
+
+        // Try to get value as different types
+        let value: serde_json::Value = if let Some(v) = row.get::<Option<String>, _>(i) {
+            json!(v)
+        } else if let Some(v) = row.get::<Option<i64>, _>(i) {
+            json!(v)
+        } else if let Some(v) = row.get::<Option<f64>, _>(i) {
+            json!(v)
+        } else if let Some(v) = row.get::<Option<bool>, _>(i) {
+            json!(v)
+        } else {
+            json!(null)
+        };
+
+        row_map.insert(column_name.to_string(), value);
+    }
+
+    serde_json::Value::Object(row_map)
+}
@@ -1,20 +1,35 @@
 // Oracle executor implementation
 // Requirements: 3.8 - Execute Oracle queries and stored procedures
 
+use super::{mongo_on_sql_database, BindValue};
 use crate::errors::ExecutionError;
+use crate::executor::output_sink::OutputSink;
 use crate::models::QueryType;
 use serde_json::json;
 use std::time::Duration;
 
+/// Rows fetched ahead of the sink while streaming
+const STREAM_BUFFER_ROWS: usize = 256;
+
 /// Oracle query executor
 pub struct OracleExecutor {
     _timeout: Duration,
+    parameters: Vec<serde_json::Value>,
 }
 
 impl OracleExecutor {
     /// Create a new Oracle executor
     pub fn new(timeout: Duration) -> Self {
-        Self { _timeout: timeout }
+        Self {
+            _timeout: timeout,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Values bound to `:1`, `:2`, .. of raw SQL queries
+    pub fn with_parameters(mut self, parameters: Vec<serde_json::Value>) -> Self {
+        self.parameters = parameters;
+        self
     }
 
     /// Execute Oracle query
@@ -25,37 +40,7 @@ impl OracleExecutor {
         query: &str,
         query_type: &QueryType,
     ) -> Result<serde_json::Value, ExecutionError> {
-        tracing::info!("Connecting to Oracle database");
-
-        // Parse connection string (format: username/password@host:port/service_name)
-        let parts: Vec<&str> = connection_string.split('@').collect();
-        if parts.len() != 2 {
-            return Err(ExecutionError::DatabaseConnectionFailed(
-                "Invalid Oracle connection string format. Expected: username/password@host:port/service_name".to_string(),
-            ));
-        }
-
-        let credentials = parts[0];
-        let connect_string = parts[1];
-
-        let cred_parts: Vec<&str> = credentials.split('/').collect();
-        if cred_parts.len() != 2 {
-            return Err(ExecutionError::DatabaseConnectionFailed(
-                "Invalid Oracle credentials format. Expected: username/password".to_string(),
-            ));
-        }
-
-        let username = cred_parts[0];
-        let password = cred_parts[1];
-
-        // Create connection
-        let conn =
-            oracle::Connection::connect(username, password, connect_string).map_err(|e| {
-                ExecutionError::DatabaseConnectionFailed(format!(
-                    "Failed to connect to Oracle: {}",
-                    e
-                ))
-            })?;
+        let conn = connect(connection_string)?;
 
         let result = match query_type {
             QueryType::RawSql => self.execute_raw_sql(&conn, query).await?,
@@ -66,6 +51,7 @@ impl OracleExecutor {
                 self.execute_stored_procedure(&conn, procedure_name, parameters)
                     .await?
             }
+            QueryType::Mongo { .. } => return Err(mongo_on_sql_database()),
         };
 
         // Close connection
@@ -76,6 +62,73 @@ impl OracleExecutor {
         Ok(result)
     }
 
+    /// Run a raw SQL query, writing each row to `sink` as it is fetched instead of
+    /// collecting the result set
+    ///
+    /// The driver blocks, so rows are fetched on a blocking thread and handed over
+    /// through a bounded channel.
+    #[tracing::instrument(
+        skip(self, connection_string, query, sink),
+        fields(database_type = "oracle")
+    )]
+    pub async fn stream_query(
+        &self,
+        connection_string: &str,
+        query: &str,
+        sink: &mut OutputSink,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(STREAM_BUFFER_ROWS);
+        let connection_string = connection_string.to_string();
+        let query = query.to_string();
+        let parameters = self.parameters.clone();
+        let fetch = tokio::task::spawn_blocking(move || -> Result<(), ExecutionError> {
+            let conn = connect(&connection_string)?;
+            let bound = bind_parameters(&parameters);
+            let bound: Vec<&dyn oracle::sql_type::ToSql> =
+                bound.iter().map(|parameter| parameter.as_ref()).collect();
+            let rows = conn.query(&query, &bound).map_err(|e| {
+                ExecutionError::DatabaseQueryFailed(format!("Oracle query failed: {}", e))
+            })?;
+            for row_result in rows {
+                let row = row_result.map_err(|e| {
+                    ExecutionError::DatabaseQueryFailed(format!(
+                        "Failed to fetch Oracle row: {}",
+                        e
+                    ))
+                })?;
+                // The sink failed and stopped receiving
+                if tx.blocking_send(row_to_json(&row)).is_err() {
+                    break;
+                }
+            }
+            conn.close().map_err(|e| {
+                ExecutionError::DatabaseQueryFailed(format!(
+                    "Failed to close Oracle connection: {}",
+                    e
+                ))
+            })
+        });
+
+        let mut row_count: u64 = 0;
+        let mut written = Ok(());
+        while let Some(row) = rx.recv().await {
+            if let Err(e) = sink.write_record(row).await {
+                written = Err(e);
+                break;
+            }
+            row_count += 1;
+        }
+        drop(rx);
+
+        fetch.await.map_err(|e| {
+            ExecutionError::DatabaseQueryFailed(format!("Oracle fetch task failed: {}", e))
+        })??;
+        written?;
+
+        tracing::info!("Oracle query streamed {} rows", row_count);
+        Ok(json!({ "row_count": row_count }))
+    }
+
     /// Execute raw SQL query on Oracle
     #[tracing::instrument(skip(self, conn, query))]
     async fn execute_raw_sql(
@@ -86,7 +139,12 @@ impl OracleExecutor {
         tracing::debug!("Executing raw SQL query on Oracle");
 
         // Execute query
-        let rows = conn.query(query, &[]).map_err(|e| {
+        let parameters = bind_parameters(&self.parameters);
+        let parameters: Vec<&dyn oracle::sql_type::ToSql> = parameters
+            .iter()
+            .map(|parameter| parameter.as_ref())
+            .collect();
+        let rows = conn.query(query, &parameters).map_err(|e| {
             ExecutionError::DatabaseQueryFailed(format!("Oracle query failed: {}", e))
         })?;
 
@@ -97,29 +155,7 @@ impl OracleExecutor {
                 ExecutionError::DatabaseQueryFailed(format!("Failed to fetch Oracle row: {}", e))
             })?;
 
-            let mut row_map = serde_json::Map::new();
-
-            // Get column info
-            let column_info = row.column_info();
-
-            for (i, col_info) in column_info.iter().enumerate() {
-                let column_name = col_info.name();
-
-                // Try to get value as different types
-                let value: serde_json::Value = if let Ok(v) = row.get::<usize, String>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.get::<usize, i64>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.get::<usize, f64>(i) {
-                    json!(v)
-                } else {
-                    json!(null)
-                };
-
-                row_map.insert(column_name.to_string(), value);
-            }
-
-            result_rows.push(serde_json::Value::Object(row_map));
+            result_rows.push(row_to_json(&row));
         }
 
         let result = json!({
@@ -194,3 +230,77 @@ impl OracleExecutor {
         Ok(result)
     }
 }
+
+/// Connect with a `username/password@host:port/service_name` connection string
+fn connect(connection_string: &str) -> Result<oracle::Connection, ExecutionError> {
+    tracing::info!("Connecting to Oracle database");
+
+    // Parse connection string (format: username/password@host:port/service_name)
+    let parts: Vec<&str> = connection_string.split('@').collect();
+    if parts.len() != 2 {
+        return Err(ExecutionError::DatabaseConnectionFailed(
+            "Invalid Oracle connection string format. Expected: username/password@host:port/service_name".to_string(),
+        ));
+    }
+
+    let credentials = parts[0];
+    let connect_string = parts[1];
+
+    let cred_parts: Vec<&str> = credentials.split('/').collect();
+    if cred_parts.len() != 2 {
+        return Err(ExecutionError::DatabaseConnectionFailed(
+            "Invalid Oracle credentials format. Expected: username/password".to_string(),
+        ));
+    }
+
+    let username = cred_parts[0];
+    let password = cred_parts[1];
+
+    // Create connection
+    oracle::Connection::connect(username, password, connect_string).map_err(|e| {
+        ExecutionError::DatabaseConnectionFailed(format!("Failed to connect to Oracle: {}", e))
+    })
+}
+
+/// Parameters as Oracle values; booleans bind as 0 or 1
+fn bind_parameters(parameters: &[serde_json::Value]) -> Vec<Box<dyn oracle::sql_type::ToSql>> {
+    parameters
+        .iter()
+        .map(|parameter| -> Box<dyn oracle::sql_type::ToSql> {
+            match BindValue::from(parameter) {
+                BindValue::Null => Box::new(None::<String>),
+                BindValue::Bool(b) => Box::new(i64::from(b)),
+                BindValue::Int(i) => Box::new(i),
+                BindValue::Float(f) => Box::new(f),
+                BindValue::Text(text) => Box::new(text),
+            }
+        })
+        .collect()
+}
+
+/// Convert an Oracle row to a JSON object keyed by column name
+fn row_to_json(row: &oracle::Row) -> serde_json::Value {
+    let mut row_map = serde_json::Map::new();
+
+    // Get column info
+    let column_info = row.column_info();
+
+    for (i, col_info) in column_info.iter().enumerate() {
+        let column_name = col_info.name();
+
+        // Try to get value as different types
+        let value: serde_json::Value = if let Ok(v) = row.get::<usize, String>(i) {
+            json!(v)
+        } else if let Ok(v) = row.get::<usize, i64>(i) {
+            json!(v)
+        } else if let Ok(v) = row.get::<usize, f64>(i) {
+            json!(v)
+        } else {
+            json!(null)
+        };
+
+        row_map.insert(column_name.to_string(), value);
+    }
+
+    serde_json::Value::Object(row_map)
+}
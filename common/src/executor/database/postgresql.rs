@@ -1,23 +1,37 @@
 // PostgreSQL executor implementation
 // Requirements: 3.9 - Execute PostgreSQL queries and stored procedures
 
+use super::{mongo_on_sql_database, BindValue};
 use crate::errors::ExecutionError;
+use crate::executor::output_sink::OutputSink;
 use crate::models::QueryType;
 use chrono::Utc;
+use futures::TryStreamExt;
 use serde_json::json;
-use sqlx::postgres::PgPoolOptions;
-use sqlx::{Column, PgPool, Row};
+use sqlx::postgres::{PgArguments, PgPoolOptions, PgRow};
+use sqlx::query::Query;
+use sqlx::{Column, PgPool, Postgres, Row};
 use std::time::Duration;
 
 /// PostgreSQL query executor
 pub struct PostgreSQLExecutor {
     timeout: Duration,
+    parameters: Vec<serde_json::Value>,
 }
 
 impl PostgreSQLExecutor {
     /// Create a new PostgreSQL executor
     pub fn new(timeout: Duration) -> Self {
-        Self { timeout }
+        Self {
+            timeout,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Values bound to `$1`, `$2`, .. of raw SQL queries
+    pub fn with_parameters(mut self, parameters: Vec<serde_json::Value>) -> Self {
+        self.parameters = parameters;
+        self
     }
 
     /// Execute PostgreSQL query
@@ -31,20 +45,7 @@ impl PostgreSQLExecutor {
         query: &str,
         query_type: &QueryType,
     ) -> Result<serde_json::Value, ExecutionError> {
-        tracing::info!("Connecting to PostgreSQL database");
-
-        // Create connection pool with timeout
-        let pool = PgPoolOptions::new()
-            .max_connections(1)
-            .acquire_timeout(self.timeout)
-            .connect(connection_string)
-            .await
-            .map_err(|e| {
-                ExecutionError::DatabaseConnectionFailed(format!(
-                    "Failed to connect to PostgreSQL: {}",
-                    e
-                ))
-            })?;
+        let pool = self.connect(connection_string).await?;
 
         let result = match query_type {
             QueryType::RawSql => self.execute_raw_sql(&pool, query).await?,
@@ -55,6 +56,7 @@ impl PostgreSQLExecutor {
                 self.execute_stored_procedure(&pool, procedure_name, parameters)
                     .await?
             }
+            QueryType::Mongo { .. } => return Err(mongo_on_sql_database()),
         };
 
         // Close the pool
@@ -63,6 +65,85 @@ impl PostgreSQLExecutor {
         Ok(result)
     }
 
+    /// Run a raw SQL query, writing each row to `sink` as it arrives instead of
+    /// collecting the result set
+    #[tracing::instrument(
+        skip(self, connection_string, query, sink),
+        fields(database_type = "postgresql")
+    )]
+    pub async fn stream_query(
+        &self,
+        connection_string: &str,
+        query: &str,
+        sink: &mut OutputSink,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        let pool = self.connect(connection_string).await?;
+
+        let mut rows = self.bind(sqlx::query(query)).fetch(&pool);
+        let mut row_count: u64 = 0;
+        let result = loop {
+            match rows.try_next().await {
+                Ok(Some(row)) => {
+                    if let Err(e) = sink.write_record(row_to_json(&row)).await {
+                        break Err(e);
+                    }
+                    row_count += 1;
+                }
+                Ok(None) => break Ok(json!({ "row_count": row_count })),
+                Err(e) => {
+                    break Err(ExecutionError::DatabaseQueryFailed(format!(
+                        "PostgreSQL query failed: {}",
+                        e
+                    )))
+                }
+            }
+        };
+        drop(rows);
+
+        pool.close().await;
+
+        tracing::info!("PostgreSQL query streamed {} rows", row_count);
+        result
+    }
+
+    /// Bind the executor's parameters to a raw SQL query; RFC 3339 text binds as a
+    /// `timestamptz`, so it compares with timestamp columns without a cast
+    fn bind<'q>(
+        &self,
+        mut query: Query<'q, Postgres, PgArguments>,
+    ) -> Query<'q, Postgres, PgArguments> {
+        for parameter in &self.parameters {
+            query = match BindValue::from(parameter) {
+                BindValue::Null => query.bind(None::<String>),
+                BindValue::Bool(b) => query.bind(b),
+                BindValue::Int(i) => query.bind(i),
+                BindValue::Float(f) => query.bind(f),
+                BindValue::Text(text) => match chrono::DateTime::parse_from_rfc3339(&text) {
+                    Ok(timestamp) => query.bind(timestamp.with_timezone(&Utc)),
+                    Err(_) => query.bind(text),
+                },
+            };
+        }
+        query
+    }
+
+    /// Open a single-connection pool with the executor's timeout
+    async fn connect(&self, connection_string: &str) -> Result<PgPool, ExecutionError> {
+        tracing::info!("Connecting to PostgreSQL database");
+
+        PgPoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(self.timeout)
+            .connect(connection_string)
+            .await
+            .map_err(|e| {
+                ExecutionError::DatabaseConnectionFailed(format!(
+                    "Failed to connect to PostgreSQL: {}",
+                    e
+                ))
+            })
+    }
+
     /// Execute raw SQL query on PostgreSQL
     #[tracing::instrument(skip(self, pool, query))]
     async fn execute_raw_sql(
@@ -72,46 +153,15 @@ impl PostgreSQLExecutor {
     ) -> Result<serde_json::Value, ExecutionError> {
         tracing::debug!("Executing raw SQL query");
 
-        let rows = sqlx::query(query).fetch_all(pool).await.map_err(|e| {
-            ExecutionError::DatabaseQueryFailed(format!("PostgreSQL query failed: {}", e))
-        })?;
-
-        let mut result_rows = Vec::new();
-        for row in rows {
-            let mut row_map = serde_json::Map::new();
-
-            for (i, column) in row.columns().iter().enumerate() {
-                let column_name = column.name();
-
-                let value: serde_json::Value = if let Ok(v) = row.try_get::<String, _>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.try_get::<i32, _>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.try_get::<i64, _>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.try_get::<f64, _>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.try_get::<bool, _>(i) {
-                    json!(v)
-                } else if let Ok(v) = row.try_get::<chrono::NaiveDateTime, _>(i) {
-                    json!(v.to_string())
-                } else if let Ok(v) = row.try_get::<chrono::DateTime<Utc>, _>(i) {
-                    json!(v.to_rfc3339())
-                } else if let Ok(v) = row.try_get::<serde_json::Value, _>(i) {
-                    v
-                } else {
-                    row.try_get::<Option<String>, _>(i)
-                        .ok()
-                        .flatten()
-                        .map(|s| json!(s))
-                        .unwrap_or(json!(null))
-                };
-
-                row_map.insert(column_name.to_string(), value);
-            }
+        let rows = self
+            .bind(sqlx::query(query))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| {
+                ExecutionError::DatabaseQueryFailed(format!("PostgreSQL query failed: {}", e))
+            })?;
 
-            result_rows.push(serde_json::Value::Object(row_map));
-        }
+        let result_rows: Vec<serde_json::Value> = rows.iter().map(row_to_json).collect();
 
         let result = json!({
             "rows": result_rows,
@@ -168,3 +218,40 @@ impl PostgreSQLExecutor {
         Ok(result)
     }
 }
+
+/// Convert a PostgreSQL row to a JSON object keyed by column name
+fn row_to_json(row: &PgRow) -> serde_json::Value {
+    let mut row_map = serde_json::Map::new();
+
+    for (i, column) in row.columns().iter().enumerate() {
+        let column_name = column.name();
+
+        let value: serde_json::Value = if let Ok(v) = row.try_get::<String, _>(i) {
+            json!(v)
+        } else if let Ok(v) = row.try_get::<i32, _>(i) {
+            json!(v)
+        } else if let Ok(v) = row.try_get::<i64, _>(i) {
+            json!(v)
+        } else if let Ok(v) = row.try_get::<f64, _>(i) {
+            json!(v)
+        } else if let Ok(v) = row.try_get::<bool, _>(i) {
+            json!(v)
+        } else if let Ok(v) = row.try_get::<chrono::NaiveDateTime, _>(i) {
+            json!(v.to_string())
+        } else if let Ok(v) = row.try_get::<chrono::DateTime<Utc>, _>(i) {
+            json!(v.to_rfc3339())
+        } else if let Ok(v) = row.try_get::<serde_json::Value, _>(i) {
+            v
+        } else {
+            row.try_get::<Option<String>, _>(i)
+                .ok()
+                .flatten()
+                .map(|s| json!(s))
+                .unwrap_or(json!(null))
+        };
+
+        row_map.insert(column_name.to_string(), value);
+    }
+
+    serde_json::Value::Object(row_map)
+}
@@ -0,0 +1,301 @@
+// SQLite executor implementation
+// Runs raw SQL against a local database file, for maintenance jobs on edge deployments.
+// The connection string is a file path (`/var/lib/edge/app.db`) or a `sqlite:` URL,
+// so it can come from a reference such as `{{variables.edge_db_path}}`.
+
+use super::{mongo_on_sql_database, BindValue};
+use crate::errors::ExecutionError;
+use crate::models::QueryType;
+use serde_json::json;
+use sqlx::query::Query;
+use sqlx::sqlite::{SqliteArguments, SqliteConnectOptions, SqlitePoolOptions, SqliteRow};
+use sqlx::{Column, Row, Sqlite, SqlitePool};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// SQLite query executor
+pub struct SqliteExecutor {
+    timeout: Duration,
+    parameters: Vec<serde_json::Value>,
+}
+
+impl SqliteExecutor {
+    /// Create a new SQLite executor
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Values bound to the `?` placeholders of the query, in order
+    pub fn with_parameters(mut self, parameters: Vec<serde_json::Value>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// Execute SQLite query
+    #[tracing::instrument(skip(self, connection_string, query), fields(database_type = "sqlite"))]
+    pub async fn execute_query(
+        &self,
+        connection_string: &str,
+        query: &str,
+        query_type: &QueryType,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        match query_type {
+            QueryType::RawSql => {}
+            QueryType::StoredProcedure { .. } => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "SQLite has no stored procedures; use a raw_sql query".to_string(),
+                ))
+            }
+            QueryType::Mongo { .. } => return Err(mongo_on_sql_database()),
+        }
+
+        let pool = self.connect(connection_string).await?;
+        let result = self.execute_raw_sql(&pool, query).await;
+        pool.close().await;
+
+        result
+    }
+
+    /// Bind the executor's parameters to the query
+    fn bind<'q>(
+        &self,
+        mut query: Query<'q, Sqlite, SqliteArguments<'q>>,
+    ) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+        for parameter in &self.parameters {
+            query = match BindValue::from(parameter) {
+                BindValue::Null => query.bind(None::<String>),
+                BindValue::Bool(b) => query.bind(b),
+                BindValue::Int(i) => query.bind(i),
+                BindValue::Float(f) => query.bind(f),
+                BindValue::Text(text) => query.bind(text),
+            };
+        }
+        query
+    }
+
+    /// Open a single-connection pool on an existing database file
+    async fn connect(&self, connection_string: &str) -> Result<SqlitePool, ExecutionError> {
+        tracing::info!("Opening SQLite database");
+
+        let options = connect_options(connection_string)?.busy_timeout(self.timeout);
+
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(self.timeout)
+            .connect_with(options)
+            .await
+            .map_err(|e| {
+                ExecutionError::DatabaseConnectionFailed(format!(
+                    "Failed to open SQLite database: {}",
+                    e
+                ))
+            })
+    }
+
+    /// Execute raw SQL query on SQLite
+    #[tracing::instrument(skip(self, pool, query))]
+    async fn execute_raw_sql(
+        &self,
+        pool: &SqlitePool,
+        query: &str,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        tracing::debug!("Executing raw SQL query on SQLite");
+
+        let query = self.bind(sqlx::query(query));
+        let rows = tokio::time::timeout(self.timeout, query.fetch_all(pool))
+            .await
+            .map_err(|_| ExecutionError::Timeout(self.timeout.as_secs()))?
+            .map_err(|e| {
+                ExecutionError::DatabaseQueryFailed(format!("SQLite query failed: {}", e))
+            })?;
+
+        let result_rows: Vec<serde_json::Value> = rows.iter().map(row_to_json).collect();
+
+        let result = json!({
+            "rows": result_rows,
+            "row_count": result_rows.len(),
+        });
+
+        tracing::info!("SQLite query returned {} rows", result_rows.len());
+
+        Ok(result)
+    }
+}
+
+/// Connect options for a file path or `sqlite:` URL
+///
+/// The file must already exist: a mistyped path fails the step instead of quietly
+/// creating an empty database. In-memory databases are rejected since nothing would
+/// outlive the step.
+fn connect_options(connection_string: &str) -> Result<SqliteConnectOptions, ExecutionError> {
+    let connection_string = connection_string.trim();
+    if connection_string.is_empty() {
+        return Err(ExecutionError::InvalidJobDefinition(
+            "SQLite connection string must be a database file path".to_string(),
+        ));
+    }
+    if connection_string.contains(":memory:") || connection_string.contains("mode=memory") {
+        return Err(ExecutionError::InvalidJobDefinition(
+            "In-memory SQLite databases are not supported; give a database file path".to_string(),
+        ));
+    }
+
+    let options = if connection_string.starts_with("sqlite:") {
+        SqliteConnectOptions::from_str(connection_string).map_err(|e| {
+            ExecutionError::DatabaseConnectionFailed(format!(
+                "Invalid SQLite connection string: {}",
+                e
+            ))
+        })?
+    } else {
+        SqliteConnectOptions::new().filename(Path::new(connection_string))
+    };
+
+    Ok(options.create_if_missing(false))
+}
+
+/// Convert a SQLite row to a JSON object keyed by column name
+fn row_to_json(row: &SqliteRow) -> serde_json::Value {
+    let mut row_map = serde_json::Map::new();
+
+    for (i, column) in row.columns().iter().enumerate() {
+        // SQLite values are dynamically typed; try the storage classes in turn
+        let value: serde_json::Value = if let Ok(Some(v)) = row.try_get::<Option<i64>, _>(i) {
+            json!(v)
+        } else if let Ok(Some(v)) = row.try_get::<Option<f64>, _>(i) {
+            json!(v)
+        } else if let Ok(Some(v)) = row.try_get::<Option<String>, _>(i) {
+            json!(v)
+        } else if let Ok(Some(v)) = row.try_get::<Option<Vec<u8>>, _>(i) {
+            json!(hex::encode(v))
+        } else {
+            json!(null)
+        };
+
+        row_map.insert(column.name().to_string(), value);
+    }
+
+    serde_json::Value::Object(row_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connect_options_accept_paths_and_urls() {
+        let options = connect_options("/var/lib/edge/app.db").unwrap();
+        assert_eq!(options.get_filename(), Path::new("/var/lib/edge/app.db"));
+
+        let options = connect_options("sqlite:///var/lib/edge/app.db?mode=ro").unwrap();
+        assert_eq!(options.get_filename(), Path::new("/var/lib/edge/app.db"));
+
+        assert!(connect_options("  ").is_err());
+        assert!(connect_options("sqlite::memory:").is_err());
+        assert!(connect_options("sqlite://app.db?mode=memory").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_returns_rows_as_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("edge.db");
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE readings (id INTEGER, sensor TEXT, value REAL, raw BLOB)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO readings VALUES (1, 'boiler', 71.5, x'0aff'), (2, NULL, 3, NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let executor = SqliteExecutor::new(Duration::from_secs(5));
+        let output = executor
+            .execute_query(
+                path.to_str().unwrap(),
+                "SELECT * FROM readings ORDER BY id",
+                &QueryType::RawSql,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(output["row_count"], 2);
+        assert_eq!(output["rows"][0]["sensor"], "boiler");
+        assert_eq!(output["rows"][0]["value"], 71.5);
+        assert_eq!(output["rows"][0]["raw"], "0aff");
+        assert_eq!(output["rows"][1]["sensor"], serde_json::Value::Null);
+        assert_eq!(output["rows"][1]["value"], 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_parameters_are_bound_not_spliced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("edge.db");
+        let pool = SqlitePool::connect_with(
+            SqliteConnectOptions::new()
+                .filename(&path)
+                .create_if_missing(true),
+        )
+        .await
+        .unwrap();
+        sqlx::query("CREATE TABLE tags (id INTEGER, name TEXT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO tags VALUES (1, 'a'), (2, 'b'), (3, 'c')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        let run = |parameters: Vec<serde_json::Value>| {
+            let path = path.clone();
+            async move {
+                SqliteExecutor::new(Duration::from_secs(5))
+                    .with_parameters(parameters)
+                    .execute_query(
+                        path.to_str().unwrap(),
+                        "SELECT * FROM tags WHERE id > ? OR name = ? ORDER BY id",
+                        &QueryType::RawSql,
+                    )
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let output = run(vec![json!(1), json!("a")]).await;
+        assert_eq!(output["row_count"], 3);
+
+        // Quotes in a value can't change the query
+        let output = run(vec![json!(2), json!("x' OR '1'='1")]).await;
+        assert_eq!(output["row_count"], 1);
+        assert_eq!(output["rows"][0]["name"], "c");
+    }
+
+    #[tokio::test]
+    async fn test_missing_database_file_is_not_created() {
+        let path = std::env::temp_dir().join(format!("missing-{}.db", uuid::Uuid::new_v4()));
+        let executor = SqliteExecutor::new(Duration::from_secs(5));
+
+        let result = executor
+            .execute_query(path.to_str().unwrap(), "SELECT 1", &QueryType::RawSql)
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(ExecutionError::DatabaseConnectionFailed(_))
+        ));
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,418 @@
+// Email job executor implementation
+// Purpose: Send notification and report emails over SMTP from a job, with the subject
+// and body filled in from the job context and files from storage (e.g. reports
+// written by a file processing step) attached
+
+use crate::errors::ExecutionError;
+use crate::executor::template::{render_field, string_variables};
+use crate::executor::JobExecutor;
+use crate::models::{
+    EmailAttachment, JobContext, JobStep, JobType, SmtpConfig, SmtpTls, StepOutput,
+};
+use crate::storage::StorageService;
+use async_trait::async_trait;
+use chrono::Utc;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Largest total size of the attachments of one email, in bytes
+const MAX_ATTACHMENT_BYTES: usize = 25 * 1024 * 1024;
+
+/// Sender and recipients of an email, after references are resolved
+#[derive(Debug, Default)]
+struct Envelope {
+    from: String,
+    to: Vec<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    reply_to: Option<String>,
+}
+
+/// Attachment loaded from storage
+struct LoadedAttachment {
+    filename: String,
+    content_type: String,
+    data: Vec<u8>,
+}
+
+/// EmailExecutor sends email steps through SMTP
+pub struct EmailExecutor {
+    storage_service: Arc<dyn StorageService>,
+    timeout_seconds: u64,
+}
+
+impl EmailExecutor {
+    /// Create a new EmailExecutor with the specified SMTP timeout
+    pub fn new(storage_service: Arc<dyn StorageService>, timeout_seconds: u64) -> Self {
+        Self {
+            storage_service,
+            timeout_seconds,
+        }
+    }
+
+    /// Content type for an attachment without an explicit one
+    fn guess_content_type(filename: &str) -> &'static str {
+        let extension = filename
+            .rsplit_once('.')
+            .map(|(_, ext)| ext.to_ascii_lowercase())
+            .unwrap_or_default();
+        match extension.as_str() {
+            "csv" => "text/csv",
+            "txt" | "log" => "text/plain",
+            "html" | "htm" => "text/html",
+            "json" => "application/json",
+            "xml" => "application/xml",
+            "pdf" => "application/pdf",
+            "zip" => "application/zip",
+            "xls" => "application/vnd.ms-excel",
+            "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+            _ => "application/octet-stream",
+        }
+    }
+
+    /// Load the attachments from storage, enforcing the total size limit
+    async fn load_attachments(
+        &self,
+        attachments: &[EmailAttachment],
+        context: &JobContext,
+        variables: &HashMap<String, String>,
+    ) -> Result<Vec<LoadedAttachment>, ExecutionError> {
+        let mut loaded = Vec::with_capacity(attachments.len());
+        let mut total_bytes = 0;
+        for attachment in attachments {
+            let path = render_field(&attachment.path, context, variables, "attachment path")?;
+            let data = self.storage_service.load_file(&path).await.map_err(|e| {
+                ExecutionError::StorageFailed(format!(
+                    "Failed to load attachment '{}': {}",
+                    path, e
+                ))
+            })?;
+
+            total_bytes += data.len();
+            if total_bytes > MAX_ATTACHMENT_BYTES {
+                return Err(ExecutionError::EmailSendFailed(format!(
+                    "attachments exceed {} bytes",
+                    MAX_ATTACHMENT_BYTES
+                )));
+            }
+
+            let filename = match &attachment.filename {
+                Some(filename) => {
+                    render_field(filename, context, variables, "attachment filename")?
+                }
+                None => path.rsplit('/').next().unwrap_or(&path).to_string(),
+            };
+            let content_type = attachment
+                .content_type
+                .clone()
+                .unwrap_or_else(|| Self::guess_content_type(&filename).to_string());
+            loaded.push(LoadedAttachment {
+                filename,
+                content_type,
+                data,
+            });
+        }
+        Ok(loaded)
+    }
+
+    /// Build the MIME message
+    fn build_message(
+        envelope: &Envelope,
+        subject: String,
+        body: String,
+        html: bool,
+        attachments: Vec<LoadedAttachment>,
+    ) -> Result<Message, ExecutionError> {
+        let mailbox = |address: &str| {
+            address.parse::<Mailbox>().map_err(|e| {
+                ExecutionError::InvalidJobDefinition(format!(
+                    "Invalid email address '{}': {}",
+                    address, e
+                ))
+            })
+        };
+
+        if envelope.to.is_empty() && envelope.cc.is_empty() && envelope.bcc.is_empty() {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "Email step needs at least one recipient".to_string(),
+            ));
+        }
+
+        // A generated Message-ID is reported in the step output to trace the email
+        let mut builder = Message::builder()
+            .message_id(None)
+            .from(mailbox(&envelope.from)?)
+            .subject(subject);
+        for address in &envelope.to {
+            builder = builder.to(mailbox(address)?);
+        }
+        for address in &envelope.cc {
+            builder = builder.cc(mailbox(address)?);
+        }
+        for address in &envelope.bcc {
+            builder = builder.bcc(mailbox(address)?);
+        }
+        if let Some(reply_to) = &envelope.reply_to {
+            builder = builder.reply_to(mailbox(reply_to)?);
+        }
+
+        let body = if html {
+            SinglePart::html(body)
+        } else {
+            SinglePart::plain(body)
+        };
+
+        let message = if attachments.is_empty() {
+            builder.singlepart(body)
+        } else {
+            let mut parts = MultiPart::mixed().singlepart(body);
+            for attachment in attachments {
+                let content_type = ContentType::parse(&attachment.content_type).map_err(|e| {
+                    ExecutionError::InvalidJobDefinition(format!(
+                        "Invalid content type '{}' for attachment '{}': {}",
+                        attachment.content_type, attachment.filename, e
+                    ))
+                })?;
+                parts = parts.singlepart(
+                    Attachment::new(attachment.filename).body(attachment.data, content_type),
+                );
+            }
+            builder.multipart(parts)
+        };
+
+        message.map_err(|e| {
+            ExecutionError::InvalidJobDefinition(format!("Failed to build email: {}", e))
+        })
+    }
+
+    /// SMTP transport for a step's server settings
+    fn transport(
+        &self,
+        smtp: &SmtpConfig,
+        host: &str,
+        credentials: Option<Credentials>,
+    ) -> Result<AsyncSmtpTransport<Tokio1Executor>, ExecutionError> {
+        let mut builder = match smtp.tls {
+            SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+            SmtpTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+            SmtpTls::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+                host,
+            )),
+        }
+        .map_err(|e| {
+            ExecutionError::EmailSendFailed(format!("Invalid SMTP server '{}': {}", host, e))
+        })?
+        .timeout(Some(Duration::from_secs(self.timeout_seconds)));
+
+        if let Some(port) = smtp.port {
+            builder = builder.port(port);
+        }
+        if let Some(credentials) = credentials {
+            builder = builder.credentials(credentials);
+        }
+        Ok(builder.build())
+    }
+}
+
+#[async_trait]
+impl JobExecutor for EmailExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Email {
+            smtp,
+            from,
+            to,
+            cc,
+            bcc,
+            reply_to,
+            subject,
+            body,
+            html,
+            attachments,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "EmailExecutor can only execute Email job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for email steps".to_string(),
+            ));
+        }
+
+        let variables = string_variables(context);
+        let render_all = |addresses: &[String], field: &str| {
+            addresses
+                .iter()
+                .map(|address| render_field(address, context, &variables, field))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let envelope = Envelope {
+            from: render_field(from, context, &variables, "from")?,
+            to: render_all(to, "to")?,
+            cc: render_all(cc, "cc")?,
+            bcc: render_all(bcc, "bcc")?,
+            reply_to: match reply_to {
+                Some(reply_to) => Some(render_field(reply_to, context, &variables, "reply_to")?),
+                None => None,
+            },
+        };
+        let subject = render_field(subject, context, &variables, "subject")?;
+        let body = render_field(body, context, &variables, "body")?;
+
+        let host = render_field(&smtp.host, context, &variables, "smtp.host")?;
+        let credentials = match (&smtp.username, &smtp.password) {
+            (Some(username), Some(password)) => Some(Credentials::new(
+                render_field(username, context, &variables, "smtp.username")?,
+                render_field(password, context, &variables, "smtp.password")?,
+            )),
+            (None, None) => None,
+            _ => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "SMTP username and password must be set together".to_string(),
+                ));
+            }
+        };
+
+        let attachments = self
+            .load_attachments(attachments, context, &variables)
+            .await?;
+        let attachment_names: Vec<String> =
+            attachments.iter().map(|a| a.filename.clone()).collect();
+
+        let message = Self::build_message(&envelope, subject, body, *html, attachments)?;
+        let message_id = message.headers().get_raw("Message-ID").map(str::to_string);
+
+        tracing::info!(
+            host = %host,
+            tls = ?smtp.tls,
+            recipients = envelope.to.len() + envelope.cc.len() + envelope.bcc.len(),
+            attachments = attachment_names.len(),
+            "Sending email"
+        );
+
+        let response = self
+            .transport(smtp, &host, credentials)?
+            .send(message)
+            .await
+            .map_err(|e| {
+                let kind = if e.is_permanent() {
+                    "rejected"
+                } else {
+                    "failed"
+                };
+                ExecutionError::EmailSendFailed(format!("SMTP send {}: {}", kind, e))
+            })?;
+
+        tracing::info!(smtp_code = %response.code(), "Email accepted by SMTP server");
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "message_id": message_id,
+                "smtp_code": response.code().to_string(),
+                "to": envelope.to,
+                "cc": envelope.cc,
+                "bcc_count": envelope.bcc.len(),
+                "attachments": attachment_names,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> Envelope {
+        Envelope {
+            from: "Cron <cron@example.com>".to_string(),
+            to: vec!["ops@example.com".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_guess_content_type() {
+        assert_eq!(EmailExecutor::guess_content_type("report.CSV"), "text/csv");
+        assert_eq!(
+            EmailExecutor::guess_content_type("sales.xlsx"),
+            "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+        );
+        assert_eq!(
+            EmailExecutor::guess_content_type("archive"),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_build_message_with_attachment() {
+        let attachments = vec![LoadedAttachment {
+            filename: "report.csv".to_string(),
+            content_type: "text/csv".to_string(),
+            data: b"branch,total\nHN,10\n".to_vec(),
+        }];
+        let message = EmailExecutor::build_message(
+            &envelope(),
+            "Daily report".to_string(),
+            "See attached".to_string(),
+            false,
+            attachments,
+        )
+        .unwrap();
+
+        let raw = String::from_utf8(message.formatted()).unwrap();
+        assert!(raw.contains("Subject: Daily report"));
+        assert!(raw.contains("multipart/mixed"));
+        assert!(raw.contains("filename=\"report.csv\""));
+        assert!(message.headers().get_raw("Message-ID").is_some());
+    }
+
+    #[test]
+    fn test_build_message_rejects_bad_input() {
+        let no_recipients = Envelope {
+            to: Vec::new(),
+            ..envelope()
+        };
+        assert!(EmailExecutor::build_message(
+            &no_recipients,
+            String::new(),
+            String::new(),
+            false,
+            Vec::new()
+        )
+        .is_err());
+
+        let bad_address = Envelope {
+            to: vec!["not an address".to_string()],
+            ..envelope()
+        };
+        assert!(matches!(
+            EmailExecutor::build_message(
+                &bad_address,
+                String::new(),
+                String::new(),
+                false,
+                Vec::new()
+            ),
+            Err(ExecutionError::InvalidJobDefinition(_))
+        ));
+    }
+}
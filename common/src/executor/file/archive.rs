@@ -0,0 +1,465 @@
+// Archive processor
+// Create and extract zip and tar.gz archives in storage, since partner files
+// usually arrive packed
+//
+// Archives are built and unpacked in memory. Extraction skips directories, links
+// and anything but regular files, rejects entries escaping the destination, and is
+// bounded in entry count and unpacked size.
+
+use crate::errors::ExecutionError;
+use crate::models::{FileMetadata, JobContext, ProgressReporter};
+use crate::storage::StorageService;
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashSet;
+use std::io::{Cursor, Read, Write};
+use std::path::{Component, Path};
+use std::sync::Arc;
+use tracing::{info, instrument};
+use zip::result::ZipError;
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Most files an extraction may produce
+const MAX_EXTRACTED_ENTRIES: usize = 10_000;
+
+/// Most bytes an extraction may produce, so a small archive can't fill memory
+const MAX_EXTRACTED_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Supported archive formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Zip,
+    TarGz,
+}
+
+impl ArchiveFormat {
+    fn mime_type(self) -> &'static str {
+        match self {
+            Self::Zip => "application/zip",
+            Self::TarGz => "application/gzip",
+        }
+    }
+}
+
+/// Archive processor
+pub struct ArchiveProcessor {
+    storage: Arc<dyn StorageService>,
+}
+
+impl ArchiveProcessor {
+    /// Create a new archive processor
+    pub fn new(storage: Arc<dyn StorageService>) -> Self {
+        Self { storage }
+    }
+
+    /// Pack files from storage into an archive, each under its file name
+    #[instrument(skip(self, password, context))]
+    pub async fn compress(
+        &self,
+        source_paths: &[String],
+        destination_path: &str,
+        format: ArchiveFormat,
+        password: Option<&str>,
+        context: &JobContext,
+    ) -> Result<FileMetadata, ExecutionError> {
+        info!(
+            "Compressing {} files into: {}",
+            source_paths.len(),
+            destination_path
+        );
+        if source_paths.is_empty() {
+            return Err(ExecutionError::FileProcessingFailed(
+                "No files to compress".to_string(),
+            ));
+        }
+        if password.is_some() && format != ArchiveFormat::Zip {
+            return Err(ExecutionError::FileProcessingFailed(
+                "Passwords are only supported for zip archives".to_string(),
+            ));
+        }
+
+        let mut names = HashSet::new();
+        let mut entries = Vec::with_capacity(source_paths.len());
+        for (index, source) in source_paths.iter().enumerate() {
+            let name = file_name(source).to_string();
+            if !names.insert(name.clone()) {
+                return Err(ExecutionError::FileProcessingFailed(format!(
+                    "More than one file is named '{}'",
+                    name
+                )));
+            }
+            let data = self.storage.load_file(source).await.map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!(
+                    "Failed to load file '{}': {}",
+                    source, e
+                ))
+            })?;
+            entries.push((name, data));
+            context
+                .progress
+                .rows(index as u64 + 1, Some(source_paths.len() as u64));
+        }
+
+        let password = password.map(str::to_string);
+        let progress = context.progress.clone();
+        let archive = tokio::task::spawn_blocking(move || match format {
+            ArchiveFormat::Zip => build_zip(&entries, password.as_deref(), &progress),
+            ArchiveFormat::TarGz => build_tar_gz(&entries, &progress),
+        })
+        .await
+        .map_err(|e| {
+            ExecutionError::FileProcessingFailed(format!("Compression task failed: {}", e))
+        })??;
+
+        self.storage
+            .store_file(destination_path, &archive)
+            .await
+            .map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!("Failed to store archive: {}", e))
+            })?;
+
+        Ok(FileMetadata {
+            path: destination_path.to_string(),
+            filename: file_name(destination_path).to_string(),
+            size: archive.len() as u64,
+            mime_type: Some(format.mime_type().to_string()),
+            row_count: None,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Unpack an archive from storage under `destination_dir`
+    #[instrument(skip(self, password, context))]
+    pub async fn extract(
+        &self,
+        source_path: &str,
+        destination_dir: &str,
+        format: ArchiveFormat,
+        password: Option<&str>,
+        context: &JobContext,
+    ) -> Result<Vec<FileMetadata>, ExecutionError> {
+        info!(
+            "Extracting archive: {} into: {}",
+            source_path, destination_dir
+        );
+
+        let archive = self.storage.load_file(source_path).await.map_err(|e| {
+            ExecutionError::FileProcessingFailed(format!("Failed to load archive: {}", e))
+        })?;
+        let password = password.map(str::to_string);
+        let progress = context.progress.clone();
+        let entries = tokio::task::spawn_blocking(move || match format {
+            ArchiveFormat::Zip => unpack_zip(&archive, password.as_deref(), &progress),
+            ArchiveFormat::TarGz => unpack_tar_gz(&archive, &progress),
+        })
+        .await
+        .map_err(|e| {
+            ExecutionError::FileProcessingFailed(format!("Extraction task failed: {}", e))
+        })??;
+
+        let destination_dir = destination_dir.trim_end_matches('/');
+        let mut files = Vec::with_capacity(entries.len());
+        for (index, (name, data)) in entries.iter().enumerate() {
+            let path = format!("{}/{}", destination_dir, name);
+            self.storage.store_file(&path, data).await.map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!(
+                    "Failed to store extracted file '{}': {}",
+                    path, e
+                ))
+            })?;
+            context
+                .progress
+                .rows(index as u64 + 1, Some(entries.len() as u64));
+
+            files.push(FileMetadata {
+                filename: file_name(&path).to_string(),
+                path,
+                size: data.len() as u64,
+                mime_type: None,
+                row_count: None,
+                created_at: Utc::now(),
+            });
+        }
+        Ok(files)
+    }
+}
+
+/// Last segment of a storage path
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn build_zip(
+    entries: &[(String, Vec<u8>)],
+    password: Option<&str>,
+    progress: &ProgressReporter,
+) -> Result<Vec<u8>, ExecutionError> {
+    let mut options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    if let Some(password) = password {
+        options = options.with_aes_encryption(AesMode::Aes256, password);
+    }
+
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let mut packed = PackProgress::new(entries, progress);
+    for (name, data) in entries {
+        writer
+            .start_file(name.as_str(), options)
+            .and_then(|_| writer.write_all(data).map_err(ZipError::from))
+            .map_err(|e| zip_error("write", e))?;
+        packed.add(data);
+    }
+    let archive = writer.finish().map_err(|e| zip_error("write", e))?;
+    Ok(archive.into_inner())
+}
+
+fn build_tar_gz(
+    entries: &[(String, Vec<u8>)],
+    progress: &ProgressReporter,
+) -> Result<Vec<u8>, ExecutionError> {
+    let tar_error = |e: std::io::Error| {
+        ExecutionError::FileProcessingFailed(format!("Failed to write tar.gz archive: {}", e))
+    };
+
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    let mtime = Utc::now().timestamp().max(0) as u64;
+    let mut packed = PackProgress::new(entries, progress);
+    for (name, data) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        builder
+            .append_data(&mut header, name, data.as_slice())
+            .map_err(tar_error)?;
+        packed.add(data);
+    }
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .map_err(tar_error)
+}
+
+/// Files of a zip archive as (relative path, contents)
+fn unpack_zip(
+    archive: &[u8],
+    password: Option<&str>,
+    progress: &ProgressReporter,
+) -> Result<Vec<(String, Vec<u8>)>, ExecutionError> {
+    let mut zip = ZipArchive::new(Cursor::new(archive)).map_err(|e| zip_error("read", e))?;
+    let mut budget = ExtractionBudget::default();
+    let mut entries = Vec::new();
+
+    for index in 0..zip.len() {
+        // Archives may mix encrypted and plain entries; only the first take a password
+        let encrypted = zip
+            .by_index_raw(index)
+            .map_err(|e| zip_error("read", e))?
+            .encrypted();
+        let mut file = match password {
+            Some(password) if encrypted => zip.by_index_decrypt(index, password.as_bytes()),
+            _ => zip.by_index(index),
+        }
+        .map_err(|e| match e {
+            ZipError::UnsupportedArchive(ZipError::PASSWORD_REQUIRED) => {
+                ExecutionError::FileProcessingFailed(
+                    "Zip archive is password-protected; set options.password".to_string(),
+                )
+            }
+            ZipError::InvalidPassword => {
+                ExecutionError::FileProcessingFailed("Wrong zip password".to_string())
+            }
+            e => zip_error("read", e),
+        })?;
+        if !file.is_file() {
+            continue;
+        }
+        let name = file
+            .enclosed_name()
+            .as_deref()
+            .and_then(relative_path)
+            .ok_or_else(|| unsafe_entry(file.name()))?;
+
+        let data = budget.read(&mut file, &name)?;
+        progress.bytes(budget.unpacked(), None);
+        entries.push((name, data));
+    }
+    Ok(entries)
+}
+
+/// Files of a tar.gz archive as (relative path, contents)
+fn unpack_tar_gz(
+    archive: &[u8],
+    progress: &ProgressReporter,
+) -> Result<Vec<(String, Vec<u8>)>, ExecutionError> {
+    let tar_error = |e: std::io::Error| {
+        ExecutionError::FileProcessingFailed(format!("Failed to read tar.gz archive: {}", e))
+    };
+
+    let mut tar = tar::Archive::new(GzDecoder::new(archive));
+    let mut budget = ExtractionBudget::default();
+    let mut entries = Vec::new();
+
+    for entry in tar.entries().map_err(tar_error)? {
+        let mut entry = entry.map_err(tar_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry.path().map_err(tar_error)?.into_owned();
+        let name = relative_path(&path).ok_or_else(|| unsafe_entry(&path.to_string_lossy()))?;
+
+        let data = budget.read(&mut entry, &name)?;
+        progress.bytes(budget.unpacked(), None);
+        entries.push((name, data));
+    }
+    Ok(entries)
+}
+
+/// Path below the destination with `/` separators; `None` for paths escaping it
+fn relative_path(path: &Path) -> Option<String> {
+    let mut segments = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(segment) => segments.push(segment.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!segments.is_empty()).then(|| segments.join("/"))
+}
+
+fn unsafe_entry(name: &str) -> ExecutionError {
+    ExecutionError::FileProcessingFailed(format!(
+        "Archive entry '{}' points outside the destination",
+        name
+    ))
+}
+
+fn zip_error(action: &str, e: ZipError) -> ExecutionError {
+    ExecutionError::FileProcessingFailed(format!("Failed to {} zip archive: {}", action, e))
+}
+
+/// Entries and bytes left for an extraction
+struct ExtractionBudget {
+    entries: usize,
+    bytes: u64,
+}
+
+impl Default for ExtractionBudget {
+    fn default() -> Self {
+        Self {
+            entries: MAX_EXTRACTED_ENTRIES,
+            bytes: MAX_EXTRACTED_BYTES,
+        }
+    }
+}
+
+impl ExtractionBudget {
+    /// Read an entry, failing once the archive holds more than allowed; sizes in
+    /// headers can lie, so the read itself is bounded
+    fn read(&mut self, entry: &mut impl Read, name: &str) -> Result<Vec<u8>, ExecutionError> {
+        if self.entries == 0 {
+            return Err(ExecutionError::FileProcessingFailed(format!(
+                "Archive holds more than {} files",
+                MAX_EXTRACTED_ENTRIES
+            )));
+        }
+        self.entries -= 1;
+
+        let mut data = Vec::new();
+        entry
+            .take(self.bytes + 1)
+            .read_to_end(&mut data)
+            .map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!("Failed to extract '{}': {}", name, e))
+            })?;
+        if data.len() as u64 > self.bytes {
+            return Err(ExecutionError::FileProcessingFailed(format!(
+                "Archive unpacks to more than {} bytes",
+                MAX_EXTRACTED_BYTES
+            )));
+        }
+        self.bytes -= data.len() as u64;
+        Ok(data)
+    }
+
+    /// Bytes unpacked so far
+    fn unpacked(&self) -> u64 {
+        MAX_EXTRACTED_BYTES - self.bytes
+    }
+}
+
+/// Bytes of the entries packed so far, reported as the archive is built
+struct PackProgress<'a> {
+    packed: u64,
+    total: u64,
+    progress: &'a ProgressReporter,
+}
+
+impl<'a> PackProgress<'a> {
+    fn new(entries: &[(String, Vec<u8>)], progress: &'a ProgressReporter) -> Self {
+        Self {
+            packed: 0,
+            total: entries.iter().map(|(_, data)| data.len() as u64).sum(),
+            progress,
+        }
+    }
+
+    fn add(&mut self, data: &[u8]) {
+        self.packed += data.len() as u64;
+        self.progress.bytes(self.packed, Some(self.total));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries() -> Vec<(String, Vec<u8>)> {
+        vec![
+            ("orders.csv".to_string(), b"id,total\n1,100\n".to_vec()),
+            ("readme.txt".to_string(), "Xin chào".as_bytes().to_vec()),
+        ]
+    }
+
+    #[test]
+    fn test_zip_round_trip_with_password() {
+        let progress = ProgressReporter::default();
+        let archive = build_zip(&entries(), Some("s3cret"), &progress).unwrap();
+        let total = entries().iter().map(|(_, data)| data.len() as u64).sum();
+        assert_eq!(progress.latest().unwrap().total_bytes, Some(total));
+
+        assert_eq!(
+            unpack_zip(&archive, Some("s3cret"), &progress).unwrap(),
+            entries()
+        );
+        assert_eq!(progress.latest().unwrap().bytes_transferred, Some(total));
+        let error = unpack_zip(&archive, None, &progress).unwrap_err();
+        assert!(
+            error.to_string().contains("password-protected"),
+            "{}",
+            error
+        );
+        assert!(unpack_zip(&archive, Some("wrong"), &progress).is_err());
+    }
+
+    #[test]
+    fn test_tar_gz_round_trip() {
+        let progress = ProgressReporter::default();
+        let archive = build_tar_gz(&entries(), &progress).unwrap();
+        assert_eq!(unpack_tar_gz(&archive, &progress).unwrap(), entries());
+    }
+
+    #[test]
+    fn test_relative_path_rejects_escapes() {
+        assert_eq!(
+            relative_path(Path::new("./in/2025/orders.csv")).as_deref(),
+            Some("in/2025/orders.csv")
+        );
+        assert_eq!(relative_path(Path::new("../etc/passwd")), None);
+        assert_eq!(relative_path(Path::new("/etc/passwd")), None);
+        assert_eq!(relative_path(Path::new(".")), None);
+    }
+}
@@ -2,7 +2,7 @@
 // Requirements: 15.3, 15.4, 15.8 - Read/write CSV files with configurable delimiters
 
 use crate::errors::ExecutionError;
-use crate::models::{FileMetadata, FileProcessingOptions, JobContext};
+use crate::models::{FileMetadata, FileProcessingOptions, JobContext, ProgressReporter};
 use crate::storage::StorageService;
 use chrono::Utc;
 use csv::{ReaderBuilder, WriterBuilder};
@@ -10,6 +10,11 @@ use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::{info, instrument};
 
+use super::run_blocking;
+
+/// Rows between progress reports while reading or writing
+const PROGRESS_REPORT_ROWS: usize = 1000;
+
 /// CSV file processor
 pub struct CsvProcessor {
     storage: Arc<dyn StorageService>,
@@ -22,13 +27,13 @@ impl CsvProcessor {
     }
 
     /// Read CSV file from MinIO and parse to JSON
-    #[instrument(skip(self, _options, _context))]
+    #[instrument(skip(self, _options, context))]
     pub async fn read(
         &self,
         source_path: &str,
         delimiter: char,
         _options: &FileProcessingOptions,
-        _context: &JobContext,
+        context: &JobContext,
     ) -> Result<Value, ExecutionError> {
         info!(
             "Reading CSV file from: {} with delimiter: {:?}",
@@ -40,91 +45,27 @@ impl CsvProcessor {
             ExecutionError::FileProcessingFailed(format!("Failed to load file: {}", e))
         })?;
 
-        // Parse CSV file
-        let mut reader = ReaderBuilder::new()
-            .delimiter(delimiter as u8)
-            .from_reader(file_data.as_slice());
-
-        let mut rows = Vec::new();
-
-        // Read all records
-        for result in reader.records() {
-            let record = result.map_err(|e| {
-                ExecutionError::FileProcessingFailed(format!("Failed to parse CSV record: {}", e))
-            })?;
-
-            let mut row_data = Vec::new();
-            for field in record.iter() {
-                // Try to parse as number, otherwise keep as string
-                if let Ok(num) = field.parse::<f64>() {
-                    row_data.push(json!(num));
-                } else if let Ok(b) = field.parse::<bool>() {
-                    row_data.push(json!(b));
-                } else if field.is_empty() {
-                    row_data.push(Value::Null);
-                } else {
-                    row_data.push(json!(field));
-                }
-            }
-            rows.push(Value::Array(row_data));
-        }
-
-        Ok(Value::Array(rows))
+        let progress = context.progress.clone();
+        run_blocking(move || parse_csv(&file_data, delimiter, &progress)).await
     }
 
     /// Write CSV file from JSON data
     #[instrument(skip(self, data))]
     pub async fn write(
         &self,
-        data: &Value,
+        data: Value,
         destination_path: &str,
         delimiter: char,
-        _context: &JobContext,
+        context: &JobContext,
     ) -> Result<FileMetadata, ExecutionError> {
         info!(
             "Writing CSV file to: {} with delimiter: {:?}",
             destination_path, delimiter
         );
 
-        let mut buffer = Vec::new();
-        let mut writer = WriterBuilder::new()
-            .delimiter(delimiter as u8)
-            .from_writer(&mut buffer);
-
-        // Write data rows
-        if let Value::Array(rows) = data {
-            for row in rows {
-                if let Value::Array(cells) = row {
-                    let string_cells: Vec<String> = cells
-                        .iter()
-                        .map(|cell| match cell {
-                            Value::String(s) => s.clone(),
-                            Value::Number(n) => n.to_string(),
-                            Value::Bool(b) => b.to_string(),
-                            Value::Null => String::new(),
-                            _ => cell.to_string(),
-                        })
-                        .collect();
-
-                    writer.write_record(&string_cells).map_err(|e| {
-                        ExecutionError::FileProcessingFailed(format!(
-                            "Failed to write CSV record: {}",
-                            e
-                        ))
-                    })?;
-                }
-            }
-        } else {
-            return Err(ExecutionError::FileProcessingFailed(
-                "Invalid data format for CSV export. Expected array of arrays.".to_string(),
-            ));
-        }
-
-        writer.flush().map_err(|e| {
-            ExecutionError::FileProcessingFailed(format!("Failed to flush CSV writer: {}", e))
-        })?;
-
-        drop(writer);
+        let progress = context.progress.clone();
+        let (buffer, row_count) =
+            run_blocking(move || render_csv(&data, delimiter, &progress)).await?;
 
         // Upload to MinIO
         let file_size = buffer.len() as u64;
@@ -138,13 +79,6 @@ impl CsvProcessor {
                 ))
             })?;
 
-        // Count rows
-        let row_count = if let Value::Array(rows) = data {
-            rows.len()
-        } else {
-            0
-        };
-
         Ok(FileMetadata {
             path: destination_path.to_string(),
             filename: destination_path
@@ -159,3 +93,120 @@ impl CsvProcessor {
         })
     }
 }
+
+/// Parse CSV data to an array of rows
+fn parse_csv(
+    file_data: &[u8],
+    delimiter: char,
+    progress: &ProgressReporter,
+) -> Result<Value, ExecutionError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(delimiter as u8)
+        .from_reader(file_data);
+
+    let mut rows = Vec::new();
+    let total_bytes = file_data.len() as u64;
+
+    // Read all records
+    let mut records = reader.records();
+    while let Some(result) = records.next() {
+        let record = result.map_err(|e| {
+            ExecutionError::FileProcessingFailed(format!("Failed to parse CSV record: {}", e))
+        })?;
+
+        let mut row_data = Vec::new();
+        for field in record.iter() {
+            // Try to parse as number, otherwise keep as string
+            if let Ok(num) = field.parse::<f64>() {
+                row_data.push(json!(num));
+            } else if let Ok(b) = field.parse::<bool>() {
+                row_data.push(json!(b));
+            } else if field.is_empty() {
+                row_data.push(Value::Null);
+            } else {
+                row_data.push(json!(field));
+            }
+        }
+        rows.push(Value::Array(row_data));
+
+        if rows.len() % PROGRESS_REPORT_ROWS == 0 {
+            progress.rows(rows.len() as u64, None);
+            progress.bytes(records.reader().position().byte(), Some(total_bytes));
+        }
+    }
+    progress.rows(rows.len() as u64, None);
+    progress.bytes(total_bytes, Some(total_bytes));
+
+    Ok(Value::Array(rows))
+}
+
+/// Render an array of rows as CSV, returning the file and its row count
+fn render_csv(
+    data: &Value,
+    delimiter: char,
+    progress: &ProgressReporter,
+) -> Result<(Vec<u8>, usize), ExecutionError> {
+    let Value::Array(rows) = data else {
+        return Err(ExecutionError::FileProcessingFailed(
+            "Invalid data format for CSV export. Expected array of arrays.".to_string(),
+        ));
+    };
+
+    let mut buffer = Vec::new();
+    let mut writer = WriterBuilder::new()
+        .delimiter(delimiter as u8)
+        .from_writer(&mut buffer);
+
+    // Write data rows
+    let total_rows = rows.len() as u64;
+    for (index, row) in rows.iter().enumerate() {
+        if index % PROGRESS_REPORT_ROWS == 0 {
+            progress.rows(index as u64, Some(total_rows));
+        }
+        if let Value::Array(cells) = row {
+            let string_cells: Vec<String> = cells
+                .iter()
+                .map(|cell| match cell {
+                    Value::String(s) => s.clone(),
+                    Value::Number(n) => n.to_string(),
+                    Value::Bool(b) => b.to_string(),
+                    Value::Null => String::new(),
+                    _ => cell.to_string(),
+                })
+                .collect();
+
+            writer.write_record(&string_cells).map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!("Failed to write CSV record: {}", e))
+            })?;
+        }
+    }
+
+    writer.flush().map_err(|e| {
+        ExecutionError::FileProcessingFailed(format!("Failed to flush CSV writer: {}", e))
+    })?;
+    drop(writer);
+    progress.rows(total_rows, Some(total_rows));
+
+    Ok((buffer, rows.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_and_parse_round_trip() {
+        let progress = ProgressReporter::default();
+        let data = json!([["id", "name", "active"], [1.5, null, true]]);
+
+        let (buffer, row_count) = render_csv(&data, ';', &progress).unwrap();
+        assert_eq!(row_count, 2);
+        assert_eq!(buffer, b"id;name;active\n1.5;;true\n".to_vec());
+
+        // The first line is read as the header
+        let parsed = parse_csv(&buffer, ';', &progress).unwrap();
+        assert_eq!(parsed, json!([[1.5, null, true]]));
+        assert_eq!(progress.latest().unwrap().rows_processed, Some(1));
+        assert!(render_csv(&json!({"a": 1}), ',', &progress).is_err());
+    }
+}
@@ -2,7 +2,7 @@
 // Requirements: 15.1, 15.2, 15.5, 15.7 - Read/write XLSX files
 
 use crate::errors::ExecutionError;
-use crate::models::{FileMetadata, FileProcessingOptions, JobContext};
+use crate::models::{FileMetadata, FileProcessingOptions, JobContext, ProgressReporter};
 use crate::storage::StorageService;
 use calamine::{open_workbook_auto_from_rs, Reader, Sheets};
 use chrono::Utc;
@@ -12,6 +12,8 @@ use std::io::Cursor;
 use std::sync::Arc;
 use tracing::{info, instrument};
 
+use super::run_blocking;
+
 /// Excel file processor
 pub struct ExcelProcessor {
     storage: Arc<dyn StorageService>,
@@ -29,7 +31,7 @@ impl ExcelProcessor {
         &self,
         source_path: &str,
         options: &FileProcessingOptions,
-        _context: &JobContext,
+        context: &JobContext,
     ) -> Result<Value, ExecutionError> {
         info!("Reading Excel file from: {}", source_path);
 
@@ -38,22 +40,23 @@ impl ExcelProcessor {
             ExecutionError::FileProcessingFailed(format!("Failed to load file: {}", e))
         })?;
 
-        // Parse Excel file
-        let cursor = Cursor::new(file_data);
-        let mut workbook: Sheets<_> = open_workbook_auto_from_rs(cursor).map_err(|e| {
-            ExecutionError::FileProcessingFailed(format!("Failed to parse Excel file: {}", e))
-        })?;
+        // Parse Excel file; parsing runs off the async runtime, a sheet at a time
+        let mut workbook = run_blocking(move || {
+            open_workbook_auto_from_rs(Cursor::new(file_data)).map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!("Failed to parse Excel file: {}", e))
+            })
+        })
+        .await?;
 
         // Determine which sheets to read
         let sheets_data = if let Some(sheet_name) = &options.sheet_name {
-            let range = workbook.worksheet_range(sheet_name).map_err(|e| {
-                ExecutionError::FileProcessingFailed(format!(
-                    "Sheet '{}' not found: {}",
-                    sheet_name, e
-                ))
-            })?;
-
-            let sheet_json = self.parse_excel_range(&range)?;
+            let name = sheet_name.clone();
+            let (_, sheet_json, _) = run_blocking(move || {
+                read_sheet(workbook, &name, |e| {
+                    format!("Sheet '{}' not found: {}", name, e)
+                })
+            })
+            .await?;
             json!({ sheet_name: sheet_json })
         } else if let Some(sheet_index) = options.sheet_index {
             let sheet_names = workbook.sheet_names();
@@ -65,99 +68,50 @@ impl ExcelProcessor {
                 )));
             }
 
-            let sheet_name = &sheet_names[sheet_index];
-            let range = workbook.worksheet_range(sheet_name).map_err(|e| {
-                ExecutionError::FileProcessingFailed(format!("Failed to read sheet: {}", e))
-            })?;
-
-            let sheet_json = self.parse_excel_range(&range)?;
+            let sheet_name = sheet_names[sheet_index].clone();
+            let name = sheet_name.clone();
+            let (_, sheet_json, _) = run_blocking(move || {
+                read_sheet(workbook, &name, |e| format!("Failed to read sheet: {}", e))
+            })
+            .await?;
             json!({ sheet_name: sheet_json })
         } else {
-            // Read all sheets
+            // Read all sheets, reporting rows after each one
             let mut all_sheets = serde_json::Map::new();
+            let mut rows_read = 0;
             for sheet_name in workbook.sheet_names() {
-                let range = workbook.worksheet_range(&sheet_name).map_err(|e| {
-                    ExecutionError::FileProcessingFailed(format!("Failed to read sheet: {}", e))
-                })?;
-
-                let sheet_json = self.parse_excel_range(&range)?;
-                all_sheets.insert(sheet_name.to_string(), sheet_json);
+                let name = sheet_name.clone();
+                // The resource guard can stop an oversized workbook between sheets
+                let (rest, sheet_json, height) = run_blocking(move || {
+                    read_sheet(workbook, &name, |e| format!("Failed to read sheet: {}", e))
+                })
+                .await?;
+                workbook = rest;
+                rows_read += height;
+                context.progress.rows(rows_read, None);
+                all_sheets.insert(sheet_name, sheet_json);
             }
             Value::Object(all_sheets)
         };
 
-        Ok(sheets_data)
-    }
-
-    /// Parse Excel range to JSON array
-    fn parse_excel_range(
-        &self,
-        range: &calamine::Range<calamine::Data>,
-    ) -> Result<Value, ExecutionError> {
-        let mut rows = Vec::new();
-
-        for row in range.rows() {
-            let mut row_data = Vec::new();
-            for cell in row {
-                let cell_value = match cell {
-                    calamine::Data::Int(i) => json!(i),
-                    calamine::Data::Float(f) => json!(f),
-                    calamine::Data::String(s) => json!(s),
-                    calamine::Data::Bool(b) => json!(b),
-                    calamine::Data::DateTime(dt) => json!(dt.as_f64()),
-                    calamine::Data::Error(e) => json!(format!("ERROR: {:?}", e)),
-                    calamine::Data::Empty => Value::Null,
-                    _ => Value::Null,
-                };
-                row_data.push(cell_value);
-            }
-            rows.push(Value::Array(row_data));
-        }
+        let rows_read = count_rows(&sheets_data) as u64;
+        context.progress.rows(rows_read, Some(rows_read));
 
-        Ok(Value::Array(rows))
+        Ok(sheets_data)
     }
 
     /// Write Excel file from JSON data
     #[instrument(skip(self, data))]
     pub async fn write(
         &self,
-        data: &Value,
+        data: Value,
         destination_path: &str,
-        _context: &JobContext,
+        context: &JobContext,
     ) -> Result<FileMetadata, ExecutionError> {
         info!("Writing Excel file to: {}", destination_path);
 
-        let mut workbook = Workbook::new();
-
-        // Handle data structure
-        match data {
-            Value::Object(sheets) => {
-                for (sheet_name, sheet_data) in sheets {
-                    let worksheet = workbook.add_worksheet();
-                    worksheet.set_name(sheet_name).map_err(|e| {
-                        ExecutionError::FileProcessingFailed(format!("Invalid sheet name: {}", e))
-                    })?;
-                    self.write_excel_sheet(worksheet, sheet_data)?;
-                }
-            }
-            Value::Array(_) => {
-                let worksheet = workbook.add_worksheet();
-                worksheet.set_name("Sheet1").map_err(|e| {
-                    ExecutionError::FileProcessingFailed(format!("Failed to set sheet name: {}", e))
-                })?;
-                self.write_excel_sheet(worksheet, data)?;
-            }
-            _ => {
-                return Err(ExecutionError::FileProcessingFailed(
-                    "Invalid data format for Excel export".to_string(),
-                ));
-            }
-        }
-
-        // Save workbook to bytes
-        let buffer = workbook.save_to_buffer().map_err(|e| {
-            ExecutionError::FileProcessingFailed(format!("Failed to save Excel file: {}", e))
-        })?;
+        let progress = context.progress.clone();
+        let (buffer, row_count) = run_blocking(move || render_workbook(&data, &progress)).await?;
 
         // Upload to MinIO
         let file_size = buffer.len() as u64;
@@ -171,9 +125,6 @@ impl ExcelProcessor {
                 ))
             })?;
 
-        // Count rows
-        let row_count = self.count_rows(data);
-
         Ok(FileMetadata {
             path: destination_path.to_string(),
             filename: destination_path
@@ -189,100 +140,180 @@ impl ExcelProcessor {
             created_at: Utc::now(),
         })
     }
+}
 
-    /// Write data to Excel worksheet
-    fn write_excel_sheet(
-        &self,
-        worksheet: &mut Worksheet,
-        data: &Value,
-    ) -> Result<(), ExecutionError> {
-        if let Value::Array(rows) = data {
-            for (row_idx, row) in rows.iter().enumerate() {
-                if let Value::Array(cells) = row {
-                    for (col_idx, cell) in cells.iter().enumerate() {
-                        let row_num = row_idx as u32;
-                        let col_num = col_idx as u16;
+/// Read and parse one sheet, handing the workbook back for the next
+fn read_sheet(
+    mut workbook: Sheets<Cursor<Vec<u8>>>,
+    sheet_name: &str,
+    describe_error: impl FnOnce(calamine::Error) -> String,
+) -> Result<(Sheets<Cursor<Vec<u8>>>, Value, u64), ExecutionError> {
+    let range = workbook
+        .worksheet_range(sheet_name)
+        .map_err(|e| ExecutionError::FileProcessingFailed(describe_error(e)))?;
+    let sheet_json = parse_excel_range(&range)?;
+    Ok((workbook, sheet_json, range.height() as u64))
+}
 
-                        match cell {
-                            Value::Number(n) => {
-                                if let Some(i) = n.as_i64() {
-                                    worksheet.write_number(row_num, col_num, i as f64).map_err(
-                                        |e| {
-                                            ExecutionError::FileProcessingFailed(format!(
-                                                "Failed to write number: {}",
-                                                e
-                                            ))
-                                        },
-                                    )?;
-                                } else if let Some(f) = n.as_f64() {
-                                    worksheet.write_number(row_num, col_num, f).map_err(|e| {
+/// Parse Excel range to JSON array
+fn parse_excel_range(range: &calamine::Range<calamine::Data>) -> Result<Value, ExecutionError> {
+    let mut rows = Vec::new();
+
+    for row in range.rows() {
+        let mut row_data = Vec::new();
+        for cell in row {
+            let cell_value = match cell {
+                calamine::Data::Int(i) => json!(i),
+                calamine::Data::Float(f) => json!(f),
+                calamine::Data::String(s) => json!(s),
+                calamine::Data::Bool(b) => json!(b),
+                calamine::Data::DateTime(dt) => json!(dt.as_f64()),
+                calamine::Data::Error(e) => json!(format!("ERROR: {:?}", e)),
+                calamine::Data::Empty => Value::Null,
+                _ => Value::Null,
+            };
+            row_data.push(cell_value);
+        }
+        rows.push(Value::Array(row_data));
+    }
+
+    Ok(Value::Array(rows))
+}
+
+/// Render JSON rows, or sheets of rows keyed by name, as a workbook, returning the
+/// file and its row count
+fn render_workbook(
+    data: &Value,
+    progress: &ProgressReporter,
+) -> Result<(Vec<u8>, usize), ExecutionError> {
+    let mut workbook = Workbook::new();
+    let total_rows = count_rows(data) as u64;
+    let mut rows_written = 0;
+
+    // Handle data structure
+    match data {
+        Value::Object(sheets) => {
+            for (sheet_name, sheet_data) in sheets {
+                let worksheet = workbook.add_worksheet();
+                worksheet.set_name(sheet_name).map_err(|e| {
+                    ExecutionError::FileProcessingFailed(format!("Invalid sheet name: {}", e))
+                })?;
+                write_excel_sheet(worksheet, sheet_data)?;
+                rows_written += count_rows(sheet_data) as u64;
+                progress.rows(rows_written, Some(total_rows));
+            }
+        }
+        Value::Array(_) => {
+            let worksheet = workbook.add_worksheet();
+            worksheet.set_name("Sheet1").map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!("Failed to set sheet name: {}", e))
+            })?;
+            write_excel_sheet(worksheet, data)?;
+            progress.rows(total_rows, Some(total_rows));
+        }
+        _ => {
+            return Err(ExecutionError::FileProcessingFailed(
+                "Invalid data format for Excel export".to_string(),
+            ));
+        }
+    }
+
+    // Save workbook to bytes
+    let buffer = workbook.save_to_buffer().map_err(|e| {
+        ExecutionError::FileProcessingFailed(format!("Failed to save Excel file: {}", e))
+    })?;
+
+    Ok((buffer, total_rows as usize))
+}
+
+/// Write data to Excel worksheet
+fn write_excel_sheet(worksheet: &mut Worksheet, data: &Value) -> Result<(), ExecutionError> {
+    if let Value::Array(rows) = data {
+        for (row_idx, row) in rows.iter().enumerate() {
+            if let Value::Array(cells) = row {
+                for (col_idx, cell) in cells.iter().enumerate() {
+                    let row_num = row_idx as u32;
+                    let col_num = col_idx as u16;
+
+                    match cell {
+                        Value::Number(n) => {
+                            if let Some(i) = n.as_i64() {
+                                worksheet.write_number(row_num, col_num, i as f64).map_err(
+                                    |e| {
                                         ExecutionError::FileProcessingFailed(format!(
                                             "Failed to write number: {}",
                                             e
                                         ))
-                                    })?;
-                                }
-                            }
-                            Value::String(s) => {
-                                worksheet.write_string(row_num, col_num, s).map_err(|e| {
+                                    },
+                                )?;
+                            } else if let Some(f) = n.as_f64() {
+                                worksheet.write_number(row_num, col_num, f).map_err(|e| {
                                     ExecutionError::FileProcessingFailed(format!(
-                                        "Failed to write string: {}",
+                                        "Failed to write number: {}",
                                         e
                                     ))
                                 })?;
                             }
-                            Value::Bool(b) => {
-                                worksheet.write_boolean(row_num, col_num, *b).map_err(|e| {
+                        }
+                        Value::String(s) => {
+                            worksheet.write_string(row_num, col_num, s).map_err(|e| {
+                                ExecutionError::FileProcessingFailed(format!(
+                                    "Failed to write string: {}",
+                                    e
+                                ))
+                            })?;
+                        }
+                        Value::Bool(b) => {
+                            worksheet.write_boolean(row_num, col_num, *b).map_err(|e| {
+                                ExecutionError::FileProcessingFailed(format!(
+                                    "Failed to write boolean: {}",
+                                    e
+                                ))
+                            })?;
+                        }
+                        Value::Null => {
+                            worksheet
+                                .write_blank(row_num, col_num, &Format::new())
+                                .map_err(|e| {
                                     ExecutionError::FileProcessingFailed(format!(
-                                        "Failed to write boolean: {}",
+                                        "Failed to write blank: {}",
+                                        e
+                                    ))
+                                })?;
+                        }
+                        _ => {
+                            worksheet
+                                .write_string(row_num, col_num, &cell.to_string())
+                                .map_err(|e| {
+                                    ExecutionError::FileProcessingFailed(format!(
+                                        "Failed to write value: {}",
                                         e
                                     ))
                                 })?;
-                            }
-                            Value::Null => {
-                                worksheet
-                                    .write_blank(row_num, col_num, &Format::new())
-                                    .map_err(|e| {
-                                        ExecutionError::FileProcessingFailed(format!(
-                                            "Failed to write blank: {}",
-                                            e
-                                        ))
-                                    })?;
-                            }
-                            _ => {
-                                worksheet
-                                    .write_string(row_num, col_num, &cell.to_string())
-                                    .map_err(|e| {
-                                        ExecutionError::FileProcessingFailed(format!(
-                                            "Failed to write value: {}",
-                                            e
-                                        ))
-                                    })?;
-                            }
                         }
                     }
                 }
             }
         }
-
-        Ok(())
     }
 
-    /// Count rows in data
-    fn count_rows(&self, data: &Value) -> usize {
-        match data {
-            Value::Array(rows) => rows.len(),
-            Value::Object(sheets) => sheets
-                .values()
-                .filter_map(|v| {
-                    if let Value::Array(rows) = v {
-                        Some(rows.len())
-                    } else {
-                        None
-                    }
-                })
-                .sum(),
-            _ => 0,
-        }
+    Ok(())
+}
+
+/// Count rows in data
+fn count_rows(data: &Value) -> usize {
+    match data {
+        Value::Array(rows) => rows.len(),
+        Value::Object(sheets) => sheets
+            .values()
+            .filter_map(|v| {
+                if let Value::Array(rows) = v {
+                    Some(rows.len())
+                } else {
+                    None
+                }
+            })
+            .sum(),
+        _ => 0,
     }
 }
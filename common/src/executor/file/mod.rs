@@ -1,9 +1,11 @@
 // File processing executor module
-// Requirements: 15.1-15.12 - File processing with Excel/CSV support
+// Requirements: 15.1-15.12 - File processing with Excel/CSV/Parquet support, plus zip/tar.gz archives
 // Tách theo RECC 2025 rules - Tách theo file format
 
+mod archive;
 mod csv;
 mod excel;
+mod parquet;
 mod transformations;
 
 use crate::errors::ExecutionError;
@@ -17,15 +19,19 @@ use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::instrument;
 
+pub use archive::{ArchiveFormat, ArchiveProcessor};
 pub use csv::CsvProcessor;
 pub use excel::ExcelProcessor;
+pub use parquet::ParquetProcessor;
 pub use transformations::TransformationEngine;
 
-/// FileProcessingExecutor handles Excel and CSV file processing
+/// FileProcessingExecutor handles Excel, CSV and Parquet file processing and archives
 pub struct FileProcessingExecutor {
     _storage: Arc<dyn StorageService>,
     excel_processor: ExcelProcessor,
     csv_processor: CsvProcessor,
+    parquet_processor: ParquetProcessor,
+    archive_processor: ArchiveProcessor,
     transformation_engine: TransformationEngine,
 }
 
@@ -36,6 +42,8 @@ impl FileProcessingExecutor {
             _storage: Arc::clone(&storage),
             excel_processor: ExcelProcessor::new(Arc::clone(&storage)),
             csv_processor: CsvProcessor::new(Arc::clone(&storage)),
+            parquet_processor: ParquetProcessor::new(Arc::clone(&storage)),
+            archive_processor: ArchiveProcessor::new(Arc::clone(&storage)),
             transformation_engine: TransformationEngine::new(),
         }
     }
@@ -59,6 +67,48 @@ impl FileProcessingExecutor {
     }
 }
 
+/// Run CPU-bound parsing or rendering on the blocking thread pool, so it stalls
+/// neither the worker's other tasks nor the step's heartbeats
+async fn run_blocking<T, F>(work: F) -> Result<T, ExecutionError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ExecutionError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work).await.map_err(|e| {
+        ExecutionError::FileProcessingFailed(format!("File processing task failed: {}", e))
+    })?
+}
+
+/// Name of a format in step outputs
+fn format_name(format: &FileFormat) -> &'static str {
+    match format {
+        FileFormat::Excel => "excel",
+        FileFormat::Csv { .. } => "csv",
+        FileFormat::Parquet => "parquet",
+        FileFormat::Zip => "zip",
+        FileFormat::TarGz => "tar_gz",
+    }
+}
+
+fn archive_read_write(format: &FileFormat) -> ExecutionError {
+    ExecutionError::FileProcessingFailed(format!(
+        "{} archives support only the compress and extract operations",
+        format_name(format)
+    ))
+}
+
+/// Archive format for compress and extract, which don't apply to spreadsheets
+fn archive_format(format: &FileFormat) -> Result<ArchiveFormat, ExecutionError> {
+    match format {
+        FileFormat::Zip => Ok(ArchiveFormat::Zip),
+        FileFormat::TarGz => Ok(ArchiveFormat::TarGz),
+        other => Err(ExecutionError::FileProcessingFailed(format!(
+            "Compress and extract need a zip or tar_gz format, not {}",
+            format_name(other)
+        ))),
+    }
+}
+
 #[async_trait]
 impl super::JobExecutor for FileProcessingExecutor {
     /// Execute file processing step
@@ -103,6 +153,14 @@ impl super::JobExecutor for FileProcessingExecutor {
                             .read(source, *delimiter, options, context)
                             .await?
                     }
+                    FileFormat::Parquet => {
+                        self.parquet_processor
+                            .read(source, options, context)
+                            .await?
+                    }
+                    FileFormat::Zip | FileFormat::TarGz => {
+                        return Err(archive_read_write(format));
+                    }
                 };
 
                 // Apply transformations if specified
@@ -123,6 +181,8 @@ impl super::JobExecutor for FileProcessingExecutor {
                                 .to_string(),
                         ),
                         FileFormat::Csv { .. } => Some("text/csv".to_string()),
+                        FileFormat::Parquet => Some("application/vnd.apache.parquet".to_string()),
+                        FileFormat::Zip | FileFormat::TarGz => None,
                     },
                     row_count: Some(row_count),
                     created_at: Utc::now(),
@@ -132,10 +192,7 @@ impl super::JobExecutor for FileProcessingExecutor {
 
                 json!({
                     "operation": "read",
-                    "format": match format {
-                        FileFormat::Excel => "excel",
-                        FileFormat::Csv { .. } => "csv",
-                    },
+                    "format": format_name(format),
                     "source_path": source,
                     "row_count": row_count,
                     "data": data
@@ -161,29 +218,107 @@ impl super::JobExecutor for FileProcessingExecutor {
                 let file_metadata = match format {
                     FileFormat::Excel => {
                         self.excel_processor
-                            .write(&data, destination, context)
+                            .write(data, destination, context)
                             .await?
                     }
                     FileFormat::Csv { delimiter } => {
                         self.csv_processor
-                            .write(&data, destination, *delimiter, context)
+                            .write(data, destination, *delimiter, context)
+                            .await?
+                    }
+                    FileFormat::Parquet => {
+                        self.parquet_processor
+                            .write(data, destination, options, context)
                             .await?
                     }
+                    FileFormat::Zip | FileFormat::TarGz => {
+                        return Err(archive_read_write(format));
+                    }
                 };
 
                 context.add_file_metadata(file_metadata.clone());
 
                 json!({
                     "operation": "write",
-                    "format": match format {
-                        FileFormat::Excel => "excel",
-                        FileFormat::Csv { .. } => "csv",
-                    },
+                    "format": format_name(format),
                     "destination_path": destination,
                     "file_size": file_metadata.size,
                     "row_count": file_metadata.row_count
                 })
             }
+            FileOperation::Compress => {
+                let format_kind = archive_format(format)?;
+                let destination = destination_path.as_ref().ok_or_else(|| {
+                    ExecutionError::FileProcessingFailed(
+                        "destination_path is required for Compress operation".to_string(),
+                    )
+                })?;
+                let sources: Vec<String> = source_path
+                    .iter()
+                    .chain(options.source_paths.iter())
+                    .cloned()
+                    .collect();
+
+                let file_metadata = self
+                    .archive_processor
+                    .compress(
+                        &sources,
+                        destination,
+                        format_kind,
+                        options.password.as_deref(),
+                        context,
+                    )
+                    .await?;
+
+                context.add_file_metadata(file_metadata.clone());
+
+                json!({
+                    "operation": "compress",
+                    "format": format_name(format),
+                    "source_paths": sources,
+                    "destination_path": destination,
+                    "file_count": sources.len(),
+                    "file_size": file_metadata.size
+                })
+            }
+            FileOperation::Extract => {
+                let format_kind = archive_format(format)?;
+                let source = source_path.as_ref().ok_or_else(|| {
+                    ExecutionError::FileProcessingFailed(
+                        "source_path is required for Extract operation".to_string(),
+                    )
+                })?;
+                let destination = destination_path.as_ref().ok_or_else(|| {
+                    ExecutionError::FileProcessingFailed(
+                        "destination_path is required for Extract operation".to_string(),
+                    )
+                })?;
+
+                let files = self
+                    .archive_processor
+                    .extract(
+                        source,
+                        destination,
+                        format_kind,
+                        options.password.as_deref(),
+                        context,
+                    )
+                    .await?;
+
+                let paths: Vec<&String> = files.iter().map(|file| &file.path).collect();
+                let output = json!({
+                    "operation": "extract",
+                    "format": format_name(format),
+                    "source_path": source,
+                    "destination_path": destination,
+                    "file_count": files.len(),
+                    "files": paths
+                });
+                for file in files {
+                    context.add_file_metadata(file);
+                }
+                output
+            }
         };
 
         let completed_at = Utc::now();
@@ -0,0 +1,286 @@
+// Parquet file processor
+// Read columnar exports from data lakes and write step results to Parquet
+//
+// Rows are JSON objects keyed by column name in both directions. Writing infers
+// the schema from the rows; reading can project a subset of the columns.
+
+use crate::errors::ExecutionError;
+use crate::models::{FileMetadata, FileProcessingOptions, JobContext, ProgressReporter};
+use crate::storage::StorageService;
+use arrow_array::RecordBatch;
+use arrow_json::reader::infer_json_schema_from_iterator;
+use arrow_json::writer::JsonArray;
+use arrow_json::{ReaderBuilder, WriterBuilder};
+use bytes::Bytes;
+use chrono::Utc;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::{ArrowWriter, ProjectionMask};
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+use super::run_blocking;
+
+/// Rows per record batch while reading or writing
+const BATCH_ROWS: usize = 1024;
+
+/// MIME type of Parquet files
+const PARQUET_MIME_TYPE: &str = "application/vnd.apache.parquet";
+
+/// Parquet file processor
+pub struct ParquetProcessor {
+    storage: Arc<dyn StorageService>,
+}
+
+impl ParquetProcessor {
+    /// Create a new Parquet processor
+    pub fn new(storage: Arc<dyn StorageService>) -> Self {
+        Self { storage }
+    }
+
+    /// Read a Parquet file into an array of row objects, keeping only
+    /// `options.columns` if given
+    #[instrument(skip(self, options, context))]
+    pub async fn read(
+        &self,
+        source_path: &str,
+        options: &FileProcessingOptions,
+        context: &JobContext,
+    ) -> Result<Value, ExecutionError> {
+        info!("Reading Parquet file from: {}", source_path);
+
+        let file_data = self.storage.load_file(source_path).await.map_err(|e| {
+            ExecutionError::FileProcessingFailed(format!("Failed to load file: {}", e))
+        })?;
+
+        let columns = options.columns.clone();
+        let progress = context.progress.clone();
+        run_blocking(move || parse_parquet(file_data, &columns, &progress)).await
+    }
+
+    /// Write an array of row objects to a Parquet file, with the schema inferred
+    /// from the first `options.schema_sample_rows` rows (all rows by default)
+    #[instrument(skip(self, data, options, context))]
+    pub async fn write(
+        &self,
+        data: Value,
+        destination_path: &str,
+        options: &FileProcessingOptions,
+        context: &JobContext,
+    ) -> Result<FileMetadata, ExecutionError> {
+        info!("Writing Parquet file to: {}", destination_path);
+
+        let options = options.clone();
+        let progress = context.progress.clone();
+        let (buffer, row_count) =
+            run_blocking(move || render_parquet(&data, &options, &progress)).await?;
+
+        let file_size = buffer.len() as u64;
+        self.storage
+            .store_file(destination_path, &buffer)
+            .await
+            .map_err(|e| {
+                ExecutionError::FileProcessingFailed(format!("Failed to store file: {}", e))
+            })?;
+
+        Ok(FileMetadata {
+            path: destination_path.to_string(),
+            filename: destination_path
+                .rsplit('/')
+                .next()
+                .unwrap_or("output.parquet")
+                .to_string(),
+            size: file_size,
+            mime_type: Some(PARQUET_MIME_TYPE.to_string()),
+            row_count: Some(row_count),
+            created_at: Utc::now(),
+        })
+    }
+}
+
+/// Parse a Parquet file into row objects, keeping only `columns` if given
+fn parse_parquet(
+    file_data: Vec<u8>,
+    columns: &[String],
+    progress: &ProgressReporter,
+) -> Result<Value, ExecutionError> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(Bytes::from(file_data))
+        .map_err(|e| parquet_error("read", e))?
+        .with_batch_size(BATCH_ROWS);
+    let total_rows = builder.metadata().file_metadata().num_rows().max(0) as u64;
+
+    let builder = if columns.is_empty() {
+        builder
+    } else {
+        let schema = builder.schema();
+        let indices = columns
+            .iter()
+            .map(|column| {
+                schema.index_of(column).map_err(|_| {
+                    ExecutionError::FileProcessingFailed(format!(
+                        "Column '{}' not found in Parquet file",
+                        column
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+        builder.with_projection(mask)
+    };
+
+    let reader = builder.build().map_err(|e| parquet_error("read", e))?;
+    let mut rows = Vec::new();
+    for batch in reader {
+        let batch = batch.map_err(|e| parquet_error("read", e))?;
+        rows.extend(batch_to_rows(&batch)?);
+        progress.rows(rows.len() as u64, Some(total_rows));
+    }
+
+    Ok(Value::Array(rows))
+}
+
+/// Render row objects as a Parquet file, returning the file and its row count
+fn render_parquet(
+    data: &Value,
+    options: &FileProcessingOptions,
+    progress: &ProgressReporter,
+) -> Result<(Vec<u8>, usize), ExecutionError> {
+    let rows = json_rows(data, &options.columns)?;
+    let sample_rows = options.schema_sample_rows.unwrap_or(rows.len());
+    let schema =
+        infer_json_schema_from_iterator(rows.iter().take(sample_rows).map(Ok)).map_err(|e| {
+            ExecutionError::FileProcessingFailed(format!("Failed to infer Parquet schema: {}", e))
+        })?;
+    if schema.fields().is_empty() {
+        return Err(ExecutionError::FileProcessingFailed(
+            "Cannot write a Parquet file without columns".to_string(),
+        ));
+    }
+    let schema = Arc::new(schema);
+
+    let mut decoder = ReaderBuilder::new(Arc::clone(&schema))
+        .with_batch_size(BATCH_ROWS)
+        .build_decoder()
+        .map_err(|e| parquet_error("write", e))?;
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(properties))
+        .map_err(|e| parquet_error("write", e))?;
+
+    let total_rows = rows.len() as u64;
+    let mut written = 0;
+    for chunk in rows.chunks(BATCH_ROWS) {
+        // Values that don't fit the inferred type fail here
+        decoder
+            .serialize(chunk)
+            .map_err(|e| parquet_error("write", e))?;
+        if let Some(batch) = decoder.flush().map_err(|e| parquet_error("write", e))? {
+            writer
+                .write(&batch)
+                .map_err(|e| parquet_error("write", e))?;
+        }
+        written += chunk.len() as u64;
+        progress.rows(written, Some(total_rows));
+    }
+    let buffer = writer.into_inner().map_err(|e| parquet_error("write", e))?;
+
+    Ok((buffer, rows.len()))
+}
+
+/// Rows of a record batch as JSON objects, with nulls kept
+fn batch_to_rows(batch: &RecordBatch) -> Result<Vec<Value>, ExecutionError> {
+    let mut writer = WriterBuilder::new()
+        .with_explicit_nulls(true)
+        .build::<_, JsonArray>(Vec::new());
+    writer
+        .write(batch)
+        .and_then(|_| writer.finish())
+        .map_err(|e| parquet_error("read", e))?;
+
+    let json = writer.into_inner();
+    if json.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_slice(&json).map_err(|e| {
+        ExecutionError::FileProcessingFailed(format!("Failed to convert Parquet rows: {}", e))
+    })
+}
+
+/// The objects of `data`, reduced to `columns` if given
+fn json_rows(data: &Value, columns: &[String]) -> Result<Vec<Value>, ExecutionError> {
+    let invalid = || {
+        ExecutionError::FileProcessingFailed(
+            "Invalid data format for Parquet export. Expected array of objects.".to_string(),
+        )
+    };
+
+    let Value::Array(rows) = data else {
+        return Err(invalid());
+    };
+    rows.iter()
+        .map(|row| {
+            let Value::Object(fields) = row else {
+                return Err(invalid());
+            };
+            if columns.is_empty() {
+                return Ok(row.clone());
+            }
+            let projected: Map<String, Value> = columns
+                .iter()
+                .map(|column| {
+                    let value = fields.get(column).cloned().unwrap_or(Value::Null);
+                    (column.clone(), value)
+                })
+                .collect();
+            Ok(Value::Object(projected))
+        })
+        .collect()
+}
+
+fn parquet_error(action: &str, e: impl std::fmt::Display) -> ExecutionError {
+    ExecutionError::FileProcessingFailed(format!("Failed to {} Parquet file: {}", action, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_json_rows_projects_columns() {
+        let data = json!([
+            { "id": 1, "branch": "HN", "total": 100.5 },
+            { "id": 2, "total": 20 }
+        ]);
+
+        let rows = json_rows(&data, &["branch".to_string(), "id".to_string()]).unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                json!({ "branch": "HN", "id": 1 }),
+                json!({ "branch": null, "id": 2 })
+            ]
+        );
+        assert!(json_rows(&json!([[1, 2]]), &[]).is_err());
+    }
+
+    #[test]
+    fn test_batch_to_rows_keeps_nulls() {
+        let rows = vec![json!({ "id": 1, "name": "a" }), json!({ "id": 2 })];
+        let schema = Arc::new(infer_json_schema_from_iterator(rows.iter().map(Ok)).unwrap());
+        let mut decoder = ReaderBuilder::new(schema).build_decoder().unwrap();
+        decoder.serialize(&rows).unwrap();
+        let batch = decoder.flush().unwrap().unwrap();
+
+        assert_eq!(
+            batch_to_rows(&batch).unwrap(),
+            vec![
+                json!({ "id": 1, "name": "a" }),
+                json!({ "id": 2, "name": null })
+            ]
+        );
+    }
+}
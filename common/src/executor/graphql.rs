@@ -0,0 +1,494 @@
+// GraphQL job executor implementation
+// Purpose: Send queries and mutations over HTTP with variables resolved from the job
+// context, keep errors reported by the GraphQL server apart from transport failures
+// (only the latter are retried), and use persisted queries so large queries aren't
+// sent on every run
+
+use crate::errors::ExecutionError;
+use crate::executor::http::HttpExecutor;
+use crate::executor::http_pool::HttpClientPool;
+use crate::executor::JobExecutor;
+use crate::models::{HttpAuth, JobContext, JobStep, JobType, PersistedQuery, StepOutput};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Error code servers return for a persisted query hash they haven't cached
+const PERSISTED_QUERY_NOT_FOUND: &str = "PERSISTED_QUERY_NOT_FOUND";
+
+/// Response of a GraphQL endpoint that carried no GraphQL errors
+#[derive(Debug)]
+enum GraphQLOutcome {
+    Data {
+        status_code: u16,
+        body: Value,
+    },
+    /// The server doesn't know the hash that was sent in place of the query
+    PersistedQueryNotFound,
+}
+
+/// GraphQLExecutor executes GraphQL query and mutation steps
+pub struct GraphQLExecutor {
+    http: HttpExecutor,
+}
+
+impl GraphQLExecutor {
+    /// Create a new GraphQLExecutor with the specified timeout
+    pub fn new(timeout_seconds: u64) -> Result<Self, ExecutionError> {
+        Ok(Self {
+            http: HttpExecutor::new(timeout_seconds)?,
+        })
+    }
+
+    /// Share the per-host client pool of the HTTP executor
+    pub fn with_client_pool(mut self, clients: Arc<HttpClientPool>) -> Self {
+        self.http = self.http.with_client_pool(clients);
+        self
+    }
+
+    /// SHA-256 hash identifying a persisted query
+    fn query_hash(query: &str) -> String {
+        hex::encode(Sha256::digest(query.as_bytes()))
+    }
+
+    /// Build the POST body; the query text is left out when only its hash is sent
+    fn request_body(
+        query: Option<&str>,
+        operation_name: Option<&str>,
+        variables: &Map<String, Value>,
+        persisted_hash: Option<&str>,
+    ) -> Value {
+        let mut body = json!({ "variables": variables });
+        if let Some(query) = query {
+            body["query"] = json!(query);
+        }
+        if let Some(operation_name) = operation_name {
+            body["operationName"] = json!(operation_name);
+        }
+        if let Some(hash) = persisted_hash {
+            body["extensions"] = json!({
+                "persistedQuery": { "version": 1, "sha256Hash": hash }
+            });
+        }
+        body
+    }
+
+    /// Whether a GraphQL error says the persisted query hash is unknown
+    fn is_persisted_query_not_found(error: &Value) -> bool {
+        error["extensions"]["code"].as_str() == Some(PERSISTED_QUERY_NOT_FOUND)
+            || error["message"].as_str() == Some("PersistedQueryNotFound")
+    }
+
+    /// One line per GraphQL error, with the path of the field that failed
+    fn format_errors(errors: &[Value]) -> String {
+        errors
+            .iter()
+            .map(|error| {
+                let message = error["message"].as_str().unwrap_or("unknown error");
+                match error["path"].as_array() {
+                    Some(path) if !path.is_empty() => {
+                        let path: Vec<String> = path
+                            .iter()
+                            .map(|segment| match segment {
+                                Value::String(s) => s.clone(),
+                                other => other.to_string(),
+                            })
+                            .collect();
+                        format!("{} (at {})", message, path.join("."))
+                    }
+                    _ => message.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Classify a response
+    ///
+    /// An `errors` list is a GraphQL error whatever the HTTP status, since servers may
+    /// answer invalid queries with 4xx; anything else that isn't a 2xx JSON body with
+    /// `data` is a transport failure.
+    fn classify_response(
+        status_code: u16,
+        response_body: &str,
+    ) -> Result<GraphQLOutcome, ExecutionError> {
+        let success = (200..300).contains(&status_code);
+        let body: Value = match serde_json::from_str(response_body) {
+            Ok(body) => body,
+            Err(_) if !success => {
+                return Err(ExecutionError::HttpRequestFailed(format!(
+                    "GraphQL request failed with status {}: {}",
+                    status_code, response_body
+                )));
+            }
+            Err(e) => {
+                return Err(ExecutionError::HttpRequestFailed(format!(
+                    "Invalid GraphQL response: {}",
+                    e
+                )));
+            }
+        };
+
+        if let Some(errors) = body["errors"].as_array().filter(|e| !e.is_empty()) {
+            if errors.iter().any(Self::is_persisted_query_not_found) {
+                return Ok(GraphQLOutcome::PersistedQueryNotFound);
+            }
+            return Err(ExecutionError::GraphQLErrors(Self::format_errors(errors)));
+        }
+
+        if !success {
+            return Err(ExecutionError::HttpRequestFailed(format!(
+                "GraphQL request failed with status {}: {}",
+                status_code, response_body
+            )));
+        }
+
+        if body.get("data").is_none() {
+            return Err(ExecutionError::HttpRequestFailed(
+                "GraphQL response has neither data nor errors".to_string(),
+            ));
+        }
+
+        Ok(GraphQLOutcome::Data { status_code, body })
+    }
+
+    /// POST a request body and classify the response
+    #[tracing::instrument(skip(self, headers, auth, body))]
+    async fn send(
+        &self,
+        url: &str,
+        headers: &HashMap<String, String>,
+        auth: &Option<HttpAuth>,
+        body: &Value,
+    ) -> Result<GraphQLOutcome, ExecutionError> {
+        let mut request = self
+            .http
+            .clients
+            .client_for(url)
+            .await?
+            .post(url)
+            .json(body);
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+        request = self.http.apply_auth(request, auth).await?;
+
+        let response = request.send().await.map_err(|e| {
+            ExecutionError::HttpRequestFailed(format!("GraphQL request failed: {}", e))
+        })?;
+        let status_code = response.status().as_u16();
+        tracing::info!(status_code, "GraphQL response received");
+
+        let response_body = response.text().await.map_err(|e| {
+            ExecutionError::HttpRequestFailed(format!("Failed to read response body: {}", e))
+        })?;
+
+        Self::classify_response(status_code, &response_body)
+    }
+}
+
+#[async_trait]
+impl JobExecutor for GraphQLExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let (url, headers, query, operation_name, variables, auth, persisted_query) =
+            match &step.step_type {
+                JobType::GraphQL {
+                    url,
+                    headers,
+                    query,
+                    operation_name,
+                    variables,
+                    auth,
+                    persisted_query,
+                } => (
+                    url,
+                    headers,
+                    query,
+                    operation_name,
+                    variables,
+                    auth,
+                    persisted_query,
+                ),
+                _ => {
+                    return Err(ExecutionError::InvalidJobDefinition(
+                        "GraphQLExecutor can only execute GraphQL job types".to_string(),
+                    ));
+                }
+            };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for GraphQL steps".to_string(),
+            ));
+        }
+
+        // The query text is sent as written: values belong in `variables`, and a
+        // resolved query would no longer match its persisted hash
+        let persisted_hash = match (persisted_query, query) {
+            (None, None) | (Some(PersistedQuery::Automatic), None) => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "GraphQL step needs a query unless its persisted query is registered"
+                        .to_string(),
+                ));
+            }
+            (None, Some(_)) => None,
+            (Some(PersistedQuery::Automatic), Some(query)) => Some(Self::query_hash(query)),
+            (Some(PersistedQuery::Registered { sha256_hash }), _) => Some(sha256_hash.clone()),
+        };
+
+        let resolver = &self.http.reference_resolver;
+        let resolved_url = resolver.resolve(url, context).map_err(|e| {
+            ExecutionError::InvalidJobDefinition(format!("Failed to resolve URL references: {}", e))
+        })?;
+
+        let mut resolved_headers = HashMap::new();
+        for (key, value) in headers {
+            let resolved_value = resolver.resolve(value, context).map_err(|e| {
+                ExecutionError::InvalidJobDefinition(format!(
+                    "Failed to resolve header value for '{}': {}",
+                    key, e
+                ))
+            })?;
+            resolved_headers.insert(key.clone(), resolved_value);
+        }
+
+        let resolved_variables = match resolver
+            .resolve_json(&Value::Object(variables.clone()), context)
+            .map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to resolve GraphQL variables: {}",
+                    e
+                ))
+            })? {
+            Value::Object(resolved) => resolved,
+            _ => Map::new(),
+        };
+
+        let resolved_auth = match auth {
+            Some(auth_config) => Some(self.http.resolve_auth_references(auth_config, context)?),
+            None => None,
+        };
+
+        tracing::info!(
+            url = %resolved_url,
+            operation_name = operation_name.as_deref().unwrap_or(""),
+            persisted = persisted_hash.is_some(),
+            "Executing GraphQL request"
+        );
+
+        // A persisted query is first sent by hash alone
+        let first_query = match persisted_hash {
+            Some(_) => None,
+            None => query.as_deref(),
+        };
+        let mut outcome = self
+            .send(
+                &resolved_url,
+                &resolved_headers,
+                &resolved_auth,
+                &Self::request_body(
+                    first_query,
+                    operation_name.as_deref(),
+                    &resolved_variables,
+                    persisted_hash.as_deref(),
+                ),
+            )
+            .await?;
+
+        // Automatic persisted queries register themselves by resending the full text
+        if let (GraphQLOutcome::PersistedQueryNotFound, Some(PersistedQuery::Automatic)) =
+            (&outcome, persisted_query)
+        {
+            tracing::debug!("Persisted query not cached by the server, sending the full query");
+            outcome = self
+                .send(
+                    &resolved_url,
+                    &resolved_headers,
+                    &resolved_auth,
+                    &Self::request_body(
+                        query.as_deref(),
+                        operation_name.as_deref(),
+                        &resolved_variables,
+                        persisted_hash.as_deref(),
+                    ),
+                )
+                .await?;
+        }
+
+        let (status_code, mut body) = match outcome {
+            GraphQLOutcome::Data { status_code, body } => (status_code, body),
+            GraphQLOutcome::PersistedQueryNotFound => {
+                return Err(ExecutionError::GraphQLErrors(format!(
+                    "Persisted query {} is not registered with the server",
+                    persisted_hash.unwrap_or_default()
+                )));
+            }
+        };
+
+        let mut output = json!({
+            "status_code": status_code,
+            "data": body["data"].take(),
+        });
+        if let Some(extensions) = body.get_mut("extensions") {
+            output["extensions"] = extensions.take();
+        }
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output,
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn graphql_step(query: Option<&str>, persisted_query: Option<PersistedQuery>) -> JobStep {
+        JobStep {
+            id: "fetch".to_string(),
+            name: "Fetch orders".to_string(),
+            step_type: JobType::GraphQL {
+                url: "http://127.0.0.1:9/graphql".to_string(),
+                headers: HashMap::new(),
+                query: query.map(str::to_string),
+                operation_name: None,
+                variables: Map::new(),
+                auth: None,
+                persisted_query,
+            },
+            condition: None,
+            on_failure: None,
+            compensate_with: Vec::new(),
+            acquire_lock: None,
+            set_state: Default::default(),
+            env: Default::default(),
+            timeout_seconds: None,
+            retry_count: None,
+            stream_output: false,
+            pii: false,
+            dependency: None,
+            connection: None,
+        }
+    }
+
+    #[test]
+    fn test_request_body_for_persisted_query() {
+        let query = "{ viewer { id } }";
+        let hash = GraphQLExecutor::query_hash(query);
+        assert_eq!(
+            hash,
+            "3c5cde484c335605fe71515655ff4723f67a754f9af53cb4c54aed06e64f87ee"
+        );
+
+        let variables = Map::from_iter([("id".to_string(), json!(7))]);
+        let body = GraphQLExecutor::request_body(None, Some("Viewer"), &variables, Some(&hash));
+        assert!(body.get("query").is_none());
+        assert_eq!(body["operationName"], "Viewer");
+        assert_eq!(body["variables"]["id"], 7);
+        assert_eq!(body["extensions"]["persistedQuery"]["sha256Hash"], hash);
+
+        let body = GraphQLExecutor::request_body(Some(query), None, &variables, None);
+        assert_eq!(body["query"], query);
+        assert!(body.get("extensions").is_none());
+    }
+
+    #[test]
+    fn test_graphql_errors_are_told_apart_from_transport_errors() {
+        let data = GraphQLExecutor::classify_response(200, r#"{"data":{"viewer":{"id":1}}}"#);
+        assert!(matches!(
+            data,
+            Ok(GraphQLOutcome::Data {
+                status_code: 200,
+                ..
+            })
+        ));
+
+        let errors =
+            r#"{"data":null,"errors":[{"message":"Not allowed","path":["orders",0,"total"]}]}"#;
+        match GraphQLExecutor::classify_response(200, errors) {
+            Err(error @ ExecutionError::GraphQLErrors(_)) => {
+                // The server answered; asking again gets the same errors
+                assert!(!error.is_retryable());
+                assert_eq!(
+                    error.to_string(),
+                    "GraphQL request returned errors: Not allowed (at orders.0.total)"
+                );
+            }
+            other => panic!("expected GraphQL errors, got {:?}", other),
+        }
+        assert!(matches!(
+            GraphQLExecutor::classify_response(400, r#"{"errors":[{"message":"Syntax"}]}"#),
+            Err(ExecutionError::GraphQLErrors(_))
+        ));
+
+        match GraphQLExecutor::classify_response(502, "Bad Gateway") {
+            Err(error @ ExecutionError::HttpRequestFailed(_)) => assert!(error.is_retryable()),
+            other => panic!("expected an HTTP failure, got {:?}", other),
+        }
+        assert!(matches!(
+            GraphQLExecutor::classify_response(200, r#"{"unexpected":true}"#),
+            Err(ExecutionError::HttpRequestFailed(_))
+        ));
+
+        let not_found = r#"{"errors":[{"message":"PersistedQueryNotFound","extensions":{"code":"PERSISTED_QUERY_NOT_FOUND"}}]}"#;
+        assert!(matches!(
+            GraphQLExecutor::classify_response(200, not_found),
+            Ok(GraphQLOutcome::PersistedQueryNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_required_unless_persisted_query_is_registered() {
+        let executor = GraphQLExecutor::new(5).unwrap();
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+
+        for persisted_query in [None, Some(PersistedQuery::Automatic)] {
+            let result = executor
+                .execute(&graphql_step(None, persisted_query), &mut context)
+                .await;
+            assert!(matches!(
+                result,
+                Err(ExecutionError::InvalidJobDefinition(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_step_type_deserializes_from_graphql_tag() {
+        let step_type: JobType = serde_json::from_value(json!({
+            "type": "graphql",
+            "url": "https://api.example.com/graphql",
+            "query": "query Orders($since: String) { orders(since: $since) { id } }",
+            "variables": { "since": "{{steps.previous.output.last_run}}" },
+            "persisted_query": { "mode": "automatic" }
+        }))
+        .unwrap();
+
+        match step_type {
+            JobType::GraphQL {
+                variables,
+                persisted_query,
+                ..
+            } => {
+                assert_eq!(variables["since"], "{{steps.previous.output.last_run}}");
+                assert_eq!(persisted_query, Some(PersistedQuery::Automatic));
+            }
+            other => panic!("expected a GraphQL step, got {:?}", other),
+        }
+    }
+}
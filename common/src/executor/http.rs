@@ -1,36 +1,45 @@
 // HTTP job executor implementation
 // Requirements: 3.1, 3.2, 3.3, 3.4, 3.5, 3.6, 4.9
 
+use crate::config::HttpClientConfig;
 use crate::errors::ExecutionError;
+use crate::executor::http_pool::HttpClientPool;
+use crate::executor::output_sink::OutputSink;
 use crate::executor::JobExecutor;
 use crate::models::{HttpAuth, HttpMethod, JobContext, JobStep, JobType, StepOutput};
+use crate::storage::StorageService;
 use crate::worker::reference::ReferenceResolver;
 use async_trait::async_trait;
 use chrono::Utc;
-use reqwest::{Client, Method, RequestBuilder};
+use reqwest::{Method, RequestBuilder};
 use serde_json::json;
 use std::sync::Arc;
-use std::time::Duration;
+
+/// Most pages a streamed response follows through `Link` headers
+const MAX_PAGES: u32 = 10_000;
+
+/// Client pool with default keep-alive settings and the given request timeout
+fn pool_with_timeout(timeout_seconds: u64) -> HttpClientPool {
+    HttpClientPool::new(HttpClientConfig {
+        timeout_seconds,
+        ..HttpClientConfig::default()
+    })
+}
 
 /// HttpExecutor executes HTTP request jobs
 pub struct HttpExecutor {
-    client: Client,
-    reference_resolver: Arc<ReferenceResolver>,
+    pub(crate) clients: Arc<HttpClientPool>,
+    pub(crate) reference_resolver: Arc<ReferenceResolver>,
+    output_storage: Option<Arc<dyn StorageService>>,
 }
 
 impl HttpExecutor {
     /// Create a new HttpExecutor with the specified timeout
     pub fn new(timeout_seconds: u64) -> Result<Self, ExecutionError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(|e| {
-                ExecutionError::HttpRequestFailed(format!("Failed to create HTTP client: {}", e))
-            })?;
-
         Ok(Self {
-            client,
+            clients: Arc::new(pool_with_timeout(timeout_seconds)),
             reference_resolver: Arc::new(ReferenceResolver::new()),
+            output_storage: None,
         })
     }
 
@@ -39,19 +48,25 @@ impl HttpExecutor {
         timeout_seconds: u64,
         reference_resolver: Arc<ReferenceResolver>,
     ) -> Result<Self, ExecutionError> {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(timeout_seconds))
-            .build()
-            .map_err(|e| {
-                ExecutionError::HttpRequestFailed(format!("Failed to create HTTP client: {}", e))
-            })?;
-
         Ok(Self {
-            client,
+            clients: Arc::new(pool_with_timeout(timeout_seconds)),
             reference_resolver,
+            output_storage: None,
         })
     }
 
+    /// Share a per-host client pool, e.g. one whose hosts are pre-warmed
+    pub fn with_client_pool(mut self, clients: Arc<HttpClientPool>) -> Self {
+        self.clients = clients;
+        self
+    }
+
+    /// Storage that steps with `stream_output` write their response bodies into
+    pub fn with_output_storage(mut self, storage: Arc<dyn StorageService>) -> Self {
+        self.output_storage = Some(storage);
+        self
+    }
+
     /// Convert HttpMethod to reqwest Method
     fn convert_method(method: &HttpMethod) -> Method {
         match method {
@@ -62,7 +77,7 @@ impl HttpExecutor {
     }
 
     /// Apply authentication to the request
-    async fn apply_auth(
+    pub(crate) async fn apply_auth(
         &self,
         mut request: RequestBuilder,
         auth: &Option<HttpAuth>,
@@ -112,7 +127,9 @@ impl HttpExecutor {
         ];
 
         let response = self
-            .client
+            .clients
+            .client_for(token_url)
+            .await?
             .post(token_url)
             .form(&params)
             .send()
@@ -148,22 +165,23 @@ impl HttpExecutor {
             })
     }
 
-    /// Execute HTTP request
-    #[tracing::instrument(skip(self))]
-    async fn execute_http_request(
+    /// Send one request with the step's headers, authentication and body
+    async fn send_request(
         &self,
-        method: &HttpMethod,
+        reqwest_method: Method,
         url: &str,
         headers: &std::collections::HashMap<String, String>,
         body: &Option<String>,
         auth: &Option<HttpAuth>,
-    ) -> Result<serde_json::Value, ExecutionError> {
-        // Requirement 3.1: Support GET, POST, PUT methods
-        let reqwest_method = Self::convert_method(method);
+    ) -> Result<reqwest::Response, ExecutionError> {
         tracing::info!("Executing HTTP {} request to: {}", reqwest_method, url);
 
         // Build the request
-        let mut request = self.client.request(reqwest_method, url);
+        let mut request = self
+            .clients
+            .client_for(url)
+            .await?
+            .request(reqwest_method, url);
 
         // Requirement 3.2: Include custom headers
         for (key, value) in headers {
@@ -181,9 +199,31 @@ impl HttpExecutor {
         }
 
         // Send the request
-        let response = request.send().await.map_err(|e| {
-            ExecutionError::HttpRequestFailed(format!("HTTP request failed: {}", e))
-        })?;
+        request
+            .send()
+            .await
+            .map_err(|e| ExecutionError::HttpRequestFailed(format!("HTTP request failed: {}", e)))
+    }
+
+    /// Execute HTTP request
+    ///
+    /// With a sink, a successful response body is written to it chunk by chunk and
+    /// left out of the result. Pages linked with `Link: <..>; rel="next"` are then
+    /// fetched with GET and appended, each on a new line.
+    #[tracing::instrument(skip(self, sink))]
+    async fn execute_http_request(
+        &self,
+        method: &HttpMethod,
+        url: &str,
+        headers: &std::collections::HashMap<String, String>,
+        body: &Option<String>,
+        auth: &Option<HttpAuth>,
+        sink: Option<&mut OutputSink>,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        // Requirement 3.1: Support GET, POST, PUT methods
+        let response = self
+            .send_request(Self::convert_method(method), url, headers, body, auth)
+            .await?;
 
         let status = response.status();
         let status_code = status.as_u16();
@@ -196,6 +236,61 @@ impl HttpExecutor {
             .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
             .collect();
 
+        if let (Some(sink), true) = (sink, status.is_success()) {
+            let mut response = response;
+            let mut pages: u32 = 1;
+            loop {
+                let next = next_page(response.url(), response.headers());
+                let mut ends_with_newline = true;
+                while let Some(chunk) = response.chunk().await.map_err(|e| {
+                    ExecutionError::HttpRequestFailed(format!(
+                        "Failed to read response body: {}",
+                        e
+                    ))
+                })? {
+                    if let Some(last) = chunk.last() {
+                        ends_with_newline = *last == b'\n';
+                    }
+                    sink.write_bytes(&chunk).await?;
+                }
+
+                let Some(next) = next else {
+                    break;
+                };
+                if pages >= MAX_PAGES {
+                    return Err(ExecutionError::HttpRequestFailed(format!(
+                        "Stopped following pagination after {} pages",
+                        MAX_PAGES
+                    )));
+                }
+                if !ends_with_newline {
+                    sink.write_bytes(b"\n").await?;
+                }
+
+                response = self
+                    .send_request(Method::GET, next.as_str(), headers, &None, auth)
+                    .await?;
+                if !response.status().is_success() {
+                    let page_status = response.status().as_u16();
+                    let page_body = response.text().await.unwrap_or_default();
+                    return Err(ExecutionError::HttpRequestFailed(format!(
+                        "HTTP request for page {} failed with status {}: {}",
+                        pages + 1,
+                        page_status,
+                        page_body
+                    )));
+                }
+                pages += 1;
+            }
+
+            return Ok(json!({
+                "status_code": status_code,
+                "status": status.canonical_reason().unwrap_or("Unknown"),
+                "headers": response_headers,
+                "pages": pages,
+            }));
+        }
+
         // Get response body
         let response_body = response.text().await.map_err(|e| {
             ExecutionError::HttpRequestFailed(format!("Failed to read response body: {}", e))
@@ -225,7 +320,7 @@ impl HttpExecutor {
     }
 
     /// Resolve references in authentication configuration
-    fn resolve_auth_references(
+    pub(crate) fn resolve_auth_references(
         &self,
         auth: &HttpAuth,
         context: &JobContext,
@@ -387,17 +482,35 @@ impl JobExecutor for HttpExecutor {
             None
         };
 
+        // Large response bodies go to storage; only a summary enters the context
+        let mut sink = if step.stream_output {
+            let storage = self.output_storage.as_ref().ok_or_else(|| {
+                ExecutionError::InvalidJobDefinition(
+                    "stream_output is not available: no output storage configured".to_string(),
+                )
+            })?;
+            Some(OutputSink::create(Arc::clone(storage), context, &step.id, "body").await?)
+        } else {
+            None
+        };
+
         // Execute the HTTP request with resolved values
-        let output = self
+        let mut output = self
             .execute_http_request(
                 method,
                 &resolved_url,
                 &resolved_headers,
                 &resolved_body,
                 &resolved_auth,
+                sink.as_mut(),
             )
             .await?;
 
+        if let Some(sink) = sink {
+            let summary = sink.finish().await?;
+            output["output"] = serde_json::to_value(summary).unwrap_or_default();
+        }
+
         let completed_at = Utc::now();
 
         // Create step output
@@ -413,11 +526,69 @@ impl JobExecutor for HttpExecutor {
     }
 }
 
+/// URL of the next page a response links to with `Link: <..>; rel="next"`
+///
+/// Links to another origin are not followed, as the step's credentials would go
+/// with them.
+fn next_page(current: &reqwest::Url, headers: &reqwest::header::HeaderMap) -> Option<reqwest::Url> {
+    headers
+        .get_all(reqwest::header::LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find_map(|link| {
+            let (target, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+            let is_next = params.split(';').any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("rel=")
+                    .map(|rel| {
+                        rel.trim_matches('"')
+                            .split_whitespace()
+                            .any(|r| r == "next")
+                    })
+                    .unwrap_or(false)
+            });
+            if !is_next {
+                return None;
+            }
+            current.join(target).ok()
+        })
+        .filter(|next| next.origin() == current.origin())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::HttpMethod;
 
+    #[test]
+    fn test_next_page_follows_same_origin_links_only() {
+        let current = reqwest::Url::parse("https://api.example.com/items?page=1").unwrap();
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::LINK,
+            r#"</items?page=1>; rel="prev", </items?page=3>; rel="next""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            next_page(&current, &headers).map(|url| url.to_string()),
+            Some("https://api.example.com/items?page=3".to_string())
+        );
+
+        headers.insert(
+            reqwest::header::LINK,
+            r#"<https://elsewhere.example.com/items?page=2>; rel="next""#
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(next_page(&current, &headers), None);
+
+        headers.remove(reqwest::header::LINK);
+        assert_eq!(next_page(&current, &headers), None);
+    }
+
     #[test]
     fn test_convert_method() {
         assert_eq!(HttpExecutor::convert_method(&HttpMethod::Get), Method::GET);
@@ -451,8 +622,16 @@ mod tests {
             },
             condition: None,
             on_failure: None,
+            compensate_with: Vec::new(),
+            acquire_lock: None,
+            set_state: Default::default(),
+            env: Default::default(),
             timeout_seconds: None,
             retry_count: None,
+            stream_output: false,
+            pii: false,
+            dependency: None,
+            connection: None,
         };
 
         let mut context = JobContext {
@@ -462,6 +641,14 @@ mod tests {
             steps: std::collections::HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: std::collections::HashMap::new(),
+            pending_state: std::collections::HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         let runtime = tokio::runtime::Runtime::new().unwrap();
@@ -0,0 +1,192 @@
+// Per-host HTTP client pool
+// Purpose: Reuse one keep-alive client per target host so jobs that fire every few
+// seconds skip the TCP and TLS handshake, and optionally open those connections before
+// the first step needs them
+
+use crate::config::HttpClientConfig;
+use crate::errors::ExecutionError;
+use reqwest::{Client, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Keep-alive HTTP clients keyed by scheme, host and port, and when each was last
+/// used
+///
+/// Clients idle past the connection idle timeout hold no connections and are dropped,
+/// as is the least recently used one past `max_hosts`, so jobs calling ever new
+/// hosts don't grow the pool without bound.
+pub struct HttpClientPool {
+    config: HttpClientConfig,
+    clients: Mutex<HashMap<String, (Client, Instant)>>,
+}
+
+impl HttpClientPool {
+    pub fn new(config: HttpClientConfig) -> Self {
+        Self {
+            config,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Client for the host of `url`, created on first use
+    pub async fn client_for(&self, url: &str) -> Result<Client, ExecutionError> {
+        let key = host_key(url).ok_or_else(|| {
+            ExecutionError::HttpRequestFailed(format!("Invalid request URL: {}", url))
+        })?;
+
+        let now = Instant::now();
+        let mut clients = self.clients.lock().unwrap();
+        if let Some((client, last_used)) = clients.get_mut(&key) {
+            *last_used = now;
+            return Ok(client.clone());
+        }
+
+        let idle_timeout = Duration::from_secs(self.config.pool_idle_timeout_seconds);
+        clients.retain(|_, (_, last_used)| now.duration_since(*last_used) < idle_timeout);
+        if clients.len() >= self.config.max_hosts.max(1) {
+            let oldest = clients
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(host, _)| host.clone());
+            if let Some(oldest) = oldest {
+                debug!(host = %oldest, "Dropping least recently used HTTP client");
+                clients.remove(&oldest);
+            }
+        }
+
+        debug!(host = %key, "Creating HTTP client for new host");
+        let client = self.build_client()?;
+        clients.insert(key, (client.clone(), now));
+        Ok(client)
+    }
+
+    /// Open a connection to the host of `url` so the next request reuses it
+    ///
+    /// Sends a HEAD request; any response, whatever its status, leaves a pooled
+    /// connection behind.
+    pub async fn warm(&self, url: &str) -> Result<(), ExecutionError> {
+        let client = self.client_for(url).await?;
+        client.head(url).send().await.map_err(|e| {
+            ExecutionError::HttpRequestFailed(format!("Failed to warm {}: {}", url, e))
+        })?;
+        Ok(())
+    }
+
+    /// Warm the configured hosts now and keep their connections from idling out
+    pub fn spawn_keep_warm(self: Arc<Self>) {
+        if self.config.prewarm_urls.is_empty() {
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(self.config.keep_warm_interval_seconds));
+            loop {
+                interval.tick().await;
+                for url in &self.config.prewarm_urls {
+                    if let Err(e) = self.warm(url).await {
+                        warn!(url = %url, error = %e, "Failed to warm HTTP connection");
+                    }
+                }
+                debug!(
+                    hosts = self.config.prewarm_urls.len(),
+                    "HTTP connections warmed"
+                );
+            }
+        });
+        info!("HTTP connection pre-warming started");
+    }
+
+    fn build_client(&self) -> Result<Client, ExecutionError> {
+        Client::builder()
+            .timeout(Duration::from_secs(self.config.timeout_seconds))
+            .pool_idle_timeout(Duration::from_secs(self.config.pool_idle_timeout_seconds))
+            .pool_max_idle_per_host(self.config.pool_max_idle_per_host)
+            .tcp_keepalive(Duration::from_secs(self.config.tcp_keepalive_seconds))
+            .tcp_nodelay(true)
+            .build()
+            .map_err(|e| {
+                ExecutionError::HttpRequestFailed(format!("Failed to create HTTP client: {}", e))
+            })
+    }
+}
+
+/// Pool key of a URL: `scheme://host:port`, with the scheme's default port filled in
+pub fn host_key(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+    Some(format!(
+        "{}://{}:{}",
+        url.scheme(),
+        url.host_str()?,
+        url.port_or_known_default()?
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_host_key_normalizes_default_ports() {
+        assert_eq!(
+            host_key("https://api.example.com/v1/orders?page=2").as_deref(),
+            Some("https://api.example.com:443")
+        );
+        assert_eq!(
+            host_key("https://api.example.com:443/health"),
+            host_key("https://api.example.com/")
+        );
+        assert_ne!(
+            host_key("http://api.example.com/"),
+            host_key("https://api.example.com/")
+        );
+        assert_eq!(host_key("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_pool_reuses_client_per_host() {
+        let pool = HttpClientPool::new(HttpClientConfig::default());
+
+        pool.client_for("https://a.example.com/one").await.unwrap();
+        pool.client_for("https://a.example.com/two").await.unwrap();
+        pool.client_for("https://b.example.com/").await.unwrap();
+        assert_eq!(pool.clients.lock().unwrap().len(), 2);
+
+        assert!(pool.client_for("/relative").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pool_drops_least_recently_used_host() {
+        let pool = HttpClientPool::new(HttpClientConfig {
+            max_hosts: 2,
+            ..HttpClientConfig::default()
+        });
+
+        pool.client_for("https://a.example.com/").await.unwrap();
+        pool.client_for("https://b.example.com/").await.unwrap();
+        pool.client_for("https://a.example.com/").await.unwrap();
+        pool.client_for("https://c.example.com/").await.unwrap();
+
+        let clients = pool.clients.lock().unwrap();
+        assert_eq!(clients.len(), 2);
+        assert!(clients.contains_key("https://a.example.com:443"));
+        assert!(!clients.contains_key("https://b.example.com:443"));
+    }
+
+    #[tokio::test]
+    async fn test_pool_drops_idle_clients() {
+        let pool = HttpClientPool::new(HttpClientConfig {
+            pool_idle_timeout_seconds: 0,
+            ..HttpClientConfig::default()
+        });
+
+        pool.client_for("https://a.example.com/").await.unwrap();
+        pool.client_for("https://b.example.com/").await.unwrap();
+
+        let clients = pool.clients.lock().unwrap();
+        assert_eq!(clients.len(), 1);
+        assert!(clients.contains_key("https://b.example.com:443"));
+    }
+}
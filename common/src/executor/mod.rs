@@ -1,10 +1,27 @@
 // Executor module for job execution
 // Provides trait and implementations for different job types
 
+pub mod checksum;
+pub mod container;
 pub mod database;
+pub mod email;
 pub mod file;
+pub mod graphql;
 pub mod http;
+pub mod http_pool;
+pub mod incremental_sync;
+pub mod mqtt;
+pub mod notification;
+pub mod object_storage;
+pub mod output_sink;
+pub mod pdf;
+pub mod script;
 pub mod sftp;
+pub mod system;
+pub mod template;
+pub mod transform;
+pub mod validate;
+pub mod wasm;
 
 use crate::errors::ExecutionError;
 use crate::models::{JobContext, JobStep, StepOutput};
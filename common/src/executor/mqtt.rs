@@ -0,0 +1,351 @@
+// MQTT publish executor implementation
+// Purpose: Push commands and readings to IoT devices through an MQTT broker on a
+// schedule, with the topic and payload filled in from the job context
+//
+// Each step opens its own connection, publishes one message and waits for the
+// acknowledgement its QoS level calls for before disconnecting, so a step only
+// succeeds once the broker has taken the message.
+
+use crate::errors::ExecutionError;
+use crate::executor::template::{render_field, string_variables};
+use crate::executor::JobExecutor;
+use crate::models::{JobContext, JobStep, JobType, MqttBroker, MqttQos, StepOutput};
+use async_trait::async_trait;
+use chrono::Utc;
+use rumqttc::{AsyncClient, Event, EventLoop, MqttOptions, Outgoing, Packet, QoS, Transport};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+/// Keep-alive of the step's connection; it only lives for one publish
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// Largest payload published, in bytes
+const MAX_PAYLOAD_BYTES: usize = 256 * 1024;
+
+/// MqttExecutor publishes MQTT publish steps
+pub struct MqttExecutor {
+    timeout_seconds: u64,
+}
+
+impl MqttExecutor {
+    /// Create a new MqttExecutor with the specified connect-and-publish timeout
+    pub fn new(timeout_seconds: u64) -> Self {
+        Self { timeout_seconds }
+    }
+
+    fn qos(qos: MqttQos) -> QoS {
+        match qos {
+            MqttQos::AtMostOnce => QoS::AtMostOnce,
+            MqttQos::AtLeastOnce => QoS::AtLeastOnce,
+            MqttQos::ExactlyOnce => QoS::ExactlyOnce,
+        }
+    }
+
+    /// Reject topics a broker would refuse to publish to
+    fn validate_topic(topic: &str) -> Result<(), ExecutionError> {
+        if topic.is_empty() {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "MQTT topic must not be empty".to_string(),
+            ));
+        }
+        if topic.contains(['+', '#', '\0']) {
+            return Err(ExecutionError::InvalidJobDefinition(format!(
+                "MQTT topic '{}' must not contain wildcards",
+                topic
+            )));
+        }
+        Ok(())
+    }
+
+    /// Connection options for a step's broker, with references resolved
+    fn options(
+        &self,
+        broker: &MqttBroker,
+        context: &JobContext,
+        variables: &HashMap<String, String>,
+    ) -> Result<MqttOptions, ExecutionError> {
+        let host = render_field(&broker.host, context, variables, "broker.host")?;
+        let port = broker
+            .port
+            .unwrap_or(if broker.tls.is_some() { 8883 } else { 1883 });
+        let client_id = match &broker.client_id {
+            Some(client_id) => render_field(client_id, context, variables, "broker.client_id")?,
+            None => format!("enterprise-cron-{}", Uuid::new_v4().simple()),
+        };
+
+        let mut options = MqttOptions::new(client_id, host, port);
+        options
+            .set_keep_alive(KEEP_ALIVE)
+            .set_clean_session(true)
+            .set_max_packet_size(MAX_PAYLOAD_BYTES + 1024, MAX_PAYLOAD_BYTES + 1024);
+
+        match (&broker.username, &broker.password) {
+            (Some(username), password) => {
+                let password = match password {
+                    Some(password) => {
+                        render_field(password, context, variables, "broker.password")?
+                    }
+                    None => String::new(),
+                };
+                options.set_credentials(
+                    render_field(username, context, variables, "broker.username")?,
+                    password,
+                );
+            }
+            (None, Some(_)) => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "MQTT password requires a username".to_string(),
+                ));
+            }
+            (None, None) => {}
+        }
+
+        if let Some(tls) = &broker.tls {
+            let pem = |value: &Option<String>, field: &str| {
+                value
+                    .as_deref()
+                    .map(|value| {
+                        render_field(value, context, variables, field).map(String::into_bytes)
+                    })
+                    .transpose()
+            };
+            let ca = pem(&tls.ca_cert, "broker.tls.ca_cert")?;
+            let client_auth = match (
+                pem(&tls.client_cert, "broker.tls.client_cert")?,
+                pem(&tls.client_key, "broker.tls.client_key")?,
+            ) {
+                (Some(cert), Some(key)) => Some((cert, key)),
+                (None, None) => None,
+                _ => {
+                    return Err(ExecutionError::InvalidJobDefinition(
+                        "MQTT client certificate and key must be set together".to_string(),
+                    ));
+                }
+            };
+            let transport = match (ca, client_auth) {
+                (Some(ca), client_auth) => Transport::tls(ca, client_auth, None),
+                (None, None) => Transport::tls_with_default_config(),
+                (None, Some(_)) => {
+                    return Err(ExecutionError::InvalidJobDefinition(
+                        "MQTT client certificates require ca_cert".to_string(),
+                    ));
+                }
+            };
+            options.set_transport(transport);
+        }
+
+        Ok(options)
+    }
+
+    /// Whether an event completes the publish for a QoS level
+    fn is_acknowledged(event: &Event, qos: QoS) -> bool {
+        matches!(
+            (qos, event),
+            (QoS::AtMostOnce, Event::Outgoing(Outgoing::Publish(_)))
+                | (QoS::AtLeastOnce, Event::Incoming(Packet::PubAck(_)))
+                | (QoS::ExactlyOnce, Event::Incoming(Packet::PubComp(_)))
+        )
+    }
+
+    /// Drive the connection until the broker has acknowledged the message
+    async fn wait_for_ack(event_loop: &mut EventLoop, qos: QoS) -> Result<(), ExecutionError> {
+        loop {
+            let event = event_loop
+                .poll()
+                .await
+                .map_err(|e| ExecutionError::MqttPublishFailed(e.to_string()))?;
+            if Self::is_acknowledged(&event, qos) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Send DISCONNECT so a retained message or session isn't left half-finished
+    async fn disconnect(client: &AsyncClient, event_loop: &mut EventLoop) {
+        if client.disconnect().await.is_err() {
+            return;
+        }
+        let _ = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Ok(event) = event_loop.poll().await {
+                if matches!(event, Event::Outgoing(Outgoing::Disconnect)) {
+                    break;
+                }
+            }
+        })
+        .await;
+    }
+}
+
+#[async_trait]
+impl JobExecutor for MqttExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::MqttPublish {
+            broker,
+            topic,
+            payload,
+            qos,
+            retain,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "MqttExecutor can only execute MqttPublish job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for MQTT steps".to_string(),
+            ));
+        }
+
+        let variables = string_variables(context);
+        let topic = render_field(topic, context, &variables, "topic")?;
+        Self::validate_topic(&topic)?;
+        let payload = render_field(payload, context, &variables, "payload")?;
+        if payload.len() > MAX_PAYLOAD_BYTES {
+            return Err(ExecutionError::InvalidJobDefinition(format!(
+                "MQTT payload is {} bytes, over the {} byte limit",
+                payload.len(),
+                MAX_PAYLOAD_BYTES
+            )));
+        }
+        let options = self.options(broker, context, &variables)?;
+        let (host, port) = options.broker_address();
+        let qos = Self::qos(*qos);
+
+        tracing::info!(
+            host = %host,
+            port,
+            topic = %topic,
+            qos = ?qos,
+            retain = *retain,
+            tls = broker.tls.is_some(),
+            "Publishing MQTT message"
+        );
+
+        let (client, mut event_loop) = AsyncClient::new(options, 10);
+        let payload_bytes = payload.len();
+        client
+            .publish(topic.clone(), qos, *retain, payload.into_bytes())
+            .await
+            .map_err(|e| ExecutionError::MqttPublishFailed(e.to_string()))?;
+
+        let published = tokio::time::timeout(
+            Duration::from_secs(self.timeout_seconds),
+            Self::wait_for_ack(&mut event_loop, qos),
+        )
+        .await;
+        match published {
+            Ok(Ok(())) => Self::disconnect(&client, &mut event_loop).await,
+            Ok(Err(e)) => return Err(e),
+            Err(_) => {
+                return Err(ExecutionError::MqttPublishFailed(format!(
+                    "Broker {}:{} didn't acknowledge the message within {}s",
+                    host, port, self.timeout_seconds
+                )));
+            }
+        }
+
+        tracing::info!(topic = %topic, "MQTT message published");
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "topic": topic,
+                "qos": qos as u8,
+                "retain": retain,
+                "payload_bytes": payload_bytes,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MqttTls;
+
+    fn broker() -> MqttBroker {
+        MqttBroker {
+            host: "mqtt.example.com".to_string(),
+            port: None,
+            client_id: Some("pump-{{plant}}".to_string()),
+            username: Some("${MQTT_USER}".to_string()),
+            password: Some("secret".to_string()),
+            tls: None,
+        }
+    }
+
+    fn context() -> JobContext {
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context
+            .variables
+            .insert("plant".to_string(), json!("hn-01"));
+        context
+            .variables
+            .insert("MQTT_USER".to_string(), json!("scheduler"));
+        context
+    }
+
+    #[test]
+    fn test_options_resolve_broker_settings() {
+        let executor = MqttExecutor::new(10);
+        let context = context();
+        let variables = string_variables(&context);
+
+        let options = executor.options(&broker(), &context, &variables).unwrap();
+        assert_eq!(
+            options.broker_address(),
+            ("mqtt.example.com".to_string(), 1883)
+        );
+        assert_eq!(options.client_id(), "pump-hn-01");
+        assert_eq!(
+            options.credentials(),
+            Some(("scheduler".to_string(), "secret".to_string()))
+        );
+
+        let mut tls_broker = broker();
+        tls_broker.tls = Some(MqttTls {
+            ca_cert: None,
+            client_cert: Some("cert".to_string()),
+            client_key: None,
+        });
+        assert!(matches!(
+            executor.options(&tls_broker, &context, &variables),
+            Err(ExecutionError::InvalidJobDefinition(_))
+        ));
+    }
+
+    #[test]
+    fn test_validate_topic() {
+        assert!(MqttExecutor::validate_topic("plants/hn-01/pump/set").is_ok());
+        assert!(MqttExecutor::validate_topic("").is_err());
+        assert!(MqttExecutor::validate_topic("plants/+/pump").is_err());
+        assert!(MqttExecutor::validate_topic("plants/#").is_err());
+    }
+
+    #[test]
+    fn test_acknowledgement_per_qos() {
+        let published = Event::Outgoing(Outgoing::Publish(0));
+        let puback = Event::Incoming(Packet::PubAck(rumqttc::PubAck::new(1)));
+        let pubcomp = Event::Incoming(Packet::PubComp(rumqttc::PubComp::new(1)));
+
+        assert!(MqttExecutor::is_acknowledged(&published, QoS::AtMostOnce));
+        assert!(!MqttExecutor::is_acknowledged(&published, QoS::AtLeastOnce));
+        assert!(MqttExecutor::is_acknowledged(&puback, QoS::AtLeastOnce));
+        assert!(!MqttExecutor::is_acknowledged(&puback, QoS::ExactlyOnce));
+        assert!(MqttExecutor::is_acknowledged(&pubcomp, QoS::ExactlyOnce));
+    }
+}
@@ -0,0 +1,321 @@
+// Chat notification executor implementation
+// Purpose: Post job results to Slack or Microsoft Teams incoming webhooks as formatted
+// messages (Slack blocks, Teams adaptive cards) instead of hand-built HTTP steps
+//
+// Both services rate-limit incoming webhooks. Each attempt of the step posts once: a
+// 429 fails it with the delay the service asks for in `Retry-After`, which the step's
+// retries wait out, 5xx responses are retried with the step's backoff, and other
+// rejections aren't retried at all.
+
+use crate::errors::ExecutionError;
+use crate::executor::http::HttpExecutor;
+use crate::executor::http_pool::HttpClientPool;
+use crate::executor::JobExecutor;
+use crate::models::{ChatPlatform, JobContext, JobStep, JobType, NotificationField, StepOutput};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Slack shows at most this many fields in one section block
+const SLACK_FIELDS_PER_SECTION: usize = 10;
+
+/// NotificationExecutor posts notification steps to chat webhooks
+pub struct NotificationExecutor {
+    http: HttpExecutor,
+}
+
+impl NotificationExecutor {
+    /// Create a new NotificationExecutor with the specified timeout
+    pub fn new(timeout_seconds: u64) -> Result<Self, ExecutionError> {
+        Ok(Self {
+            http: HttpExecutor::new(timeout_seconds)?,
+        })
+    }
+
+    /// Share the per-host client pool of the HTTP executor
+    pub fn with_client_pool(mut self, clients: Arc<HttpClientPool>) -> Self {
+        self.http = self.http.with_client_pool(clients);
+        self
+    }
+
+    /// Slack message with a header, a markdown section and the fields
+    fn slack_message(title: &str, text: Option<&str>, fields: &[NotificationField]) -> Value {
+        let mut blocks = vec![json!({
+            "type": "header",
+            "text": { "type": "plain_text", "text": title },
+        })];
+        if let Some(text) = text {
+            blocks.push(json!({
+                "type": "section",
+                "text": { "type": "mrkdwn", "text": text },
+            }));
+        }
+        for chunk in fields.chunks(SLACK_FIELDS_PER_SECTION) {
+            let fields: Vec<Value> = chunk
+                .iter()
+                .map(|field| {
+                    json!({
+                        "type": "mrkdwn",
+                        "text": format!("*{}*\n{}", field.label, field.value),
+                    })
+                })
+                .collect();
+            blocks.push(json!({ "type": "section", "fields": fields }));
+        }
+        json!({ "text": title, "blocks": blocks })
+    }
+
+    /// Teams message carrying an adaptive card with the text and a fact set
+    fn teams_message(title: &str, text: Option<&str>, fields: &[NotificationField]) -> Value {
+        let mut body = vec![json!({
+            "type": "TextBlock",
+            "text": title,
+            "size": "Medium",
+            "weight": "Bolder",
+            "wrap": true,
+        })];
+        if let Some(text) = text {
+            body.push(json!({ "type": "TextBlock", "text": text, "wrap": true }));
+        }
+        if !fields.is_empty() {
+            let facts: Vec<Value> = fields
+                .iter()
+                .map(|field| json!({ "title": field.label, "value": field.value }))
+                .collect();
+            body.push(json!({ "type": "FactSet", "facts": facts }));
+        }
+        json!({
+            "type": "message",
+            "summary": title,
+            "attachments": [{
+                "contentType": "application/vnd.microsoft.card.adaptive",
+                "content": {
+                    "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                    "type": "AdaptiveCard",
+                    "version": "1.4",
+                    "body": body,
+                },
+            }],
+        })
+    }
+
+    /// Delay a `Retry-After` header asks for, given in seconds or as an HTTP date
+    fn retry_after(value: &str) -> Option<Duration> {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+        let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+        Some(
+            (at.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Error for a post the webhook didn't accept
+    ///
+    /// Rate limits carry the delay the service asked for and 5xx responses can be
+    /// retried; the service refused any other post, so posting it again won't help.
+    fn rejection(status_code: u16, retry_after: Option<&str>, body: &str) -> ExecutionError {
+        match status_code {
+            429 => ExecutionError::RateLimited {
+                message: format!("Webhook rate limited the message: {}", body),
+                retry_after: retry_after.and_then(Self::retry_after),
+            },
+            500..=599 => ExecutionError::NotificationFailed(format!(
+                "Webhook failed with status {}: {}",
+                status_code, body
+            )),
+            _ => ExecutionError::NotificationRejected(format!(
+                "Webhook rejected the message with status {}: {}",
+                status_code, body
+            )),
+        }
+    }
+
+    /// POST the message once, returning the status code and body of the accepted post
+    #[tracing::instrument(skip(self, message))]
+    async fn post(&self, url: &str, message: &Value) -> Result<(u16, String), ExecutionError> {
+        let client = self.http.clients.client_for(url).await?;
+        let response =
+            client.post(url).json(message).send().await.map_err(|e| {
+                ExecutionError::NotificationFailed(format!("Request failed: {}", e))
+            })?;
+        let status_code = response.status().as_u16();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = response.text().await.unwrap_or_default();
+
+        if (200..300).contains(&status_code) {
+            return Ok((status_code, body));
+        }
+        Err(Self::rejection(status_code, retry_after.as_deref(), &body))
+    }
+}
+
+#[async_trait]
+impl JobExecutor for NotificationExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Notification {
+            platform,
+            webhook_url,
+            title,
+            text,
+            fields,
+            payload,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "NotificationExecutor can only execute Notification job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for notification steps".to_string(),
+            ));
+        }
+
+        let resolver = &self.http.reference_resolver;
+        let resolve = |template: &str, field: &str| {
+            resolver.resolve(template, context).map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to resolve references in {}: {}",
+                    field, e
+                ))
+            })
+        };
+
+        let url = resolve(webhook_url, "webhook_url")?;
+        let message = match payload {
+            Some(payload) => resolver.resolve_json(payload, context).map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to resolve references in payload: {}",
+                    e
+                ))
+            })?,
+            None => {
+                let title = resolve(title, "title")?;
+                let text = text
+                    .as_deref()
+                    .map(|text| resolve(text, "text"))
+                    .transpose()?;
+                let fields = fields
+                    .iter()
+                    .map(|field| {
+                        Ok(NotificationField {
+                            label: resolve(&field.label, "field label")?,
+                            value: resolve(&field.value, "field value")?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ExecutionError>>()?;
+                match platform {
+                    ChatPlatform::Slack => Self::slack_message(&title, text.as_deref(), &fields),
+                    ChatPlatform::Teams => Self::teams_message(&title, text.as_deref(), &fields),
+                }
+            }
+        };
+
+        tracing::info!(platform = ?platform, "Posting notification");
+        let (status_code, response) = self.post(&url, &message).await?;
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "platform": platform,
+                "status_code": status_code,
+                "response": response,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<NotificationField> {
+        (0..12)
+            .map(|i| NotificationField {
+                label: format!("Branch {}", i),
+                value: format!("{} rows", i * 10),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_slack_message_blocks() {
+        let message =
+            NotificationExecutor::slack_message("Daily import", Some("*Done*"), &fields());
+        assert_eq!(message["text"], "Daily import");
+
+        let blocks = message["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["type"], "header");
+        assert_eq!(blocks[1]["text"]["text"], "*Done*");
+        // Fields are split over sections of at most ten
+        assert_eq!(blocks[2]["fields"].as_array().unwrap().len(), 10);
+        assert_eq!(blocks[3]["fields"][1]["text"], "*Branch 11*\n110 rows");
+    }
+
+    #[test]
+    fn test_teams_message_adaptive_card() {
+        let message = NotificationExecutor::teams_message("Daily import", None, &fields()[..2]);
+        let card = &message["attachments"][0];
+        assert_eq!(
+            card["contentType"],
+            "application/vnd.microsoft.card.adaptive"
+        );
+
+        let body = card["content"]["body"].as_array().unwrap();
+        assert_eq!(body.len(), 2);
+        assert_eq!(body[0]["text"], "Daily import");
+        assert_eq!(body[1]["facts"][1]["value"], "10 rows");
+    }
+
+    #[test]
+    fn test_rejections() {
+        // Rate limits carry the delay the service asks for, however long
+        let error = NotificationExecutor::rejection(429, Some("120"), "slow down");
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), Some(Duration::from_secs(120)));
+        let error = NotificationExecutor::rejection(429, None, "slow down");
+        assert_eq!(error.retry_after(), None);
+
+        let error = NotificationExecutor::rejection(503, None, "unavailable");
+        assert!(error.is_retryable());
+        assert_eq!(error.retry_after(), None);
+
+        assert!(!NotificationExecutor::rejection(400, None, "invalid_payload").is_retryable());
+        assert!(!NotificationExecutor::rejection(404, None, "no_service").is_retryable());
+    }
+
+    #[test]
+    fn test_retry_after_as_http_date() {
+        let at = (Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let delay = NotificationExecutor::retry_after(&at).unwrap();
+        assert!(delay > Duration::from_secs(80) && delay <= Duration::from_secs(90));
+
+        let past = (Utc::now() - chrono::Duration::seconds(90)).to_rfc2822();
+        assert_eq!(
+            NotificationExecutor::retry_after(&past),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(NotificationExecutor::retry_after("soon"), None);
+    }
+}
@@ -0,0 +1,598 @@
+// Object storage executor implementation
+// Purpose: Copy, move, delete, list and presign objects in S3-compatible buckets
+// (AWS S3, MinIO, Ceph, R2, ...), so jobs can move artifacts between the internal
+// execution storage and external buckets
+//
+// Internal paths are confined to the job's own files: a relative path is taken
+// relative to the execution's directory, and a `jobs/...` path must belong to the job.
+// Objects copied into internal storage are added to the context's files like the
+// downloads of other steps.
+
+use crate::errors::{ExecutionError, StorageError};
+use crate::executor::template::{render_field, string_variables};
+use crate::executor::JobExecutor;
+use crate::models::{
+    FileMetadata, JobContext, JobStep, JobType, ObjectLocation, ObjectStorageBucket,
+    ObjectStorageOperation, PresignMethod, StepOutput,
+};
+use crate::storage::StorageService;
+use async_trait::async_trait;
+use chrono::Utc;
+use futures::StreamExt;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path as ObjectPath;
+use object_store::signer::Signer;
+use object_store::{ClientOptions, ObjectStore, PutPayload, WriteMultipart};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Most objects a list operation returns
+const MAX_LIST_KEYS: usize = 1000;
+
+/// Default lifetime of a presigned URL
+const DEFAULT_PRESIGN_SECONDS: u64 = 3600;
+
+/// Longest lifetime S3 accepts for a presigned URL (seven days)
+const MAX_PRESIGN_SECONDS: u64 = 7 * 24 * 3600;
+
+/// Largest internal file copied to a bucket; it is read into memory to upload it
+const MAX_UPLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Part size of multipart uploads, and how much of a download is buffered before it
+/// is written to internal storage
+const PART_BYTES: usize = 16 * 1024 * 1024;
+
+/// Parts of a multipart upload sent at once
+const MAX_CONCURRENT_PARTS: usize = 4;
+
+/// A location with its references resolved
+enum Location {
+    Internal(String),
+    Bucket(ObjectPath),
+}
+
+/// ObjectStorageExecutor runs object storage steps
+pub struct ObjectStorageExecutor {
+    storage_service: Arc<dyn StorageService>,
+    timeout_seconds: u64,
+}
+
+impl ObjectStorageExecutor {
+    /// Create a new ObjectStorageExecutor with the specified per-request timeout
+    pub fn new(storage_service: Arc<dyn StorageService>, timeout_seconds: u64) -> Self {
+        Self {
+            storage_service,
+            timeout_seconds,
+        }
+    }
+
+    /// Client for a step's bucket, with references resolved
+    fn client(
+        &self,
+        bucket: &ObjectStorageBucket,
+        context: &JobContext,
+        variables: &HashMap<String, String>,
+    ) -> Result<AmazonS3, ExecutionError> {
+        let endpoint = match &bucket.endpoint {
+            Some(endpoint) => Some(render_field(
+                endpoint,
+                context,
+                variables,
+                "bucket.endpoint",
+            )?),
+            None => None,
+        };
+        let bucket = ObjectStorageBucket {
+            name: render_field(&bucket.name, context, variables, "bucket.name")?,
+            endpoint,
+            region: render_field(&bucket.region, context, variables, "bucket.region")?,
+            access_key_id: render_field(
+                &bucket.access_key_id,
+                context,
+                variables,
+                "bucket.access_key_id",
+            )?,
+            secret_access_key: render_field(
+                &bucket.secret_access_key,
+                context,
+                variables,
+                "bucket.secret_access_key",
+            )?,
+            path_style: bucket.path_style,
+        };
+        build_client(&bucket, self.timeout_seconds)
+    }
+
+    /// Resolve a location; internal paths are confined to the job's files
+    fn location(
+        &self,
+        location: &ObjectLocation,
+        context: &JobContext,
+        variables: &HashMap<String, String>,
+        field: &str,
+    ) -> Result<Location, ExecutionError> {
+        match location {
+            ObjectLocation::Internal { path } => {
+                let path = render_field(path, context, variables, field)?;
+                Ok(Location::Internal(internal_path(&path, context)?))
+            }
+            ObjectLocation::Bucket { key } => Ok(Location::Bucket(object_key(&render_field(
+                key, context, variables, field,
+            )?)?)),
+        }
+    }
+
+    /// Copy an object, and delete the source afterwards when moving
+    async fn transfer(
+        &self,
+        store: &AmazonS3,
+        source: Location,
+        destination: Location,
+        remove_source: bool,
+        context: &mut JobContext,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        let (bytes, file) = match (&source, &destination) {
+            (Location::Internal(_), Location::Internal(_)) => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "Object storage steps need a bucket as source or destination".to_string(),
+                ));
+            }
+            (Location::Bucket(from), Location::Bucket(to)) => {
+                if remove_source {
+                    store.rename(from, to).await
+                } else {
+                    store.copy(from, to).await
+                }
+                .map_err(object_store_error)?;
+                (None, None)
+            }
+            (Location::Internal(from), Location::Bucket(to)) => {
+                let size = self.upload(store, from, to).await?;
+                if remove_source {
+                    self.storage_service.delete_file(from).await.map_err(|e| {
+                        ExecutionError::StorageFailed(format!(
+                            "Copied '{}' but failed to delete it: {}",
+                            from, e
+                        ))
+                    })?;
+                }
+                (Some(size), None)
+            }
+            (Location::Bucket(from), Location::Internal(to)) => {
+                let size = match self.download(store, from, to).await {
+                    Ok(size) => size,
+                    Err(e) => {
+                        // Don't leave a partial copy behind
+                        if let Err(delete_error) = self.storage_service.delete_file(to).await {
+                            tracing::warn!(
+                                error = %delete_error,
+                                path = %to,
+                                "Failed to delete partial download"
+                            );
+                        }
+                        return Err(e);
+                    }
+                };
+                if remove_source {
+                    store.delete(from).await.map_err(object_store_error)?;
+                }
+
+                let metadata = FileMetadata {
+                    path: to.clone(),
+                    filename: from.filename().unwrap_or("object").to_string(),
+                    size,
+                    mime_type: None,
+                    row_count: None,
+                    created_at: Utc::now(),
+                };
+                context.add_file_metadata(metadata.clone());
+                (Some(size), Some(metadata))
+            }
+        };
+
+        Ok(json!({
+            "source": describe(&source),
+            "destination": describe(&destination),
+            "bytes_transferred": bytes,
+            "file": file,
+        }))
+    }
+
+    /// Copy an internal file to a bucket, in parts when it is larger than one,
+    /// returning its size
+    async fn upload(
+        &self,
+        store: &dyn ObjectStore,
+        from: &str,
+        to: &ObjectPath,
+    ) -> Result<u64, ExecutionError> {
+        let load_failed = |e: StorageError| {
+            ExecutionError::StorageFailed(format!("Failed to load '{}': {}", from, e))
+        };
+        let size = self
+            .storage_service
+            .file_size(from)
+            .await
+            .map_err(load_failed)?;
+        if size > MAX_UPLOAD_BYTES {
+            return Err(ExecutionError::StorageFailed(format!(
+                "'{}' is {} bytes, more than the {} bytes that can be copied to a bucket",
+                from, size, MAX_UPLOAD_BYTES
+            )));
+        }
+        let data = self
+            .storage_service
+            .load_file(from)
+            .await
+            .map_err(load_failed)?;
+
+        if data.len() <= PART_BYTES {
+            store
+                .put(to, PutPayload::from(data))
+                .await
+                .map_err(object_store_error)?;
+            return Ok(size);
+        }
+
+        // A single PUT is limited to 5 GB, and sending one large body can't resume
+        let upload = store.put_multipart(to).await.map_err(object_store_error)?;
+        let mut writer = WriteMultipart::new_with_chunk_size(upload, PART_BYTES);
+        for part in data.chunks(PART_BYTES) {
+            if let Err(e) = writer.wait_for_capacity(MAX_CONCURRENT_PARTS).await {
+                let _ = writer.abort().await;
+                return Err(object_store_error(e));
+            }
+            writer.write(part);
+        }
+        writer.finish().await.map_err(object_store_error)?;
+        Ok(size)
+    }
+
+    /// Copy a bucket object to an internal file as it is downloaded, returning its
+    /// size
+    async fn download(
+        &self,
+        store: &dyn ObjectStore,
+        from: &ObjectPath,
+        to: &str,
+    ) -> Result<u64, ExecutionError> {
+        let store_failed = |e: StorageError| {
+            ExecutionError::StorageFailed(format!("Failed to store '{}': {}", to, e))
+        };
+        let mut chunks = store
+            .get(from)
+            .await
+            .map_err(object_store_error)?
+            .into_stream();
+        self.storage_service
+            .store_file(to, &[])
+            .await
+            .map_err(store_failed)?;
+
+        let mut buffer = Vec::with_capacity(PART_BYTES);
+        let mut size: u64 = 0;
+        while let Some(chunk) = chunks.next().await {
+            buffer.extend_from_slice(&chunk.map_err(object_store_error)?);
+            if buffer.len() >= PART_BYTES {
+                self.storage_service
+                    .append_file(to, &buffer)
+                    .await
+                    .map_err(store_failed)?;
+                size += buffer.len() as u64;
+                buffer.clear();
+            }
+        }
+        if !buffer.is_empty() {
+            self.storage_service
+                .append_file(to, &buffer)
+                .await
+                .map_err(store_failed)?;
+            size += buffer.len() as u64;
+        }
+        Ok(size)
+    }
+
+    /// Up to `max_keys` objects under a prefix
+    async fn list(
+        &self,
+        store: &AmazonS3,
+        prefix: Option<ObjectPath>,
+        max_keys: usize,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        let mut listing = store.list(prefix.as_ref());
+        let mut objects = Vec::new();
+        let mut truncated = false;
+        while let Some(meta) = listing.next().await {
+            let meta = meta.map_err(object_store_error)?;
+            if objects.len() == max_keys {
+                truncated = true;
+                break;
+            }
+            objects.push(json!({
+                "key": meta.location.to_string(),
+                "size": meta.size,
+                "last_modified": meta.last_modified.to_rfc3339(),
+                "e_tag": meta.e_tag,
+            }));
+        }
+
+        Ok(json!({
+            "prefix": prefix.map(|p| p.to_string()),
+            "count": objects.len(),
+            "truncated": truncated,
+            "objects": objects,
+        }))
+    }
+}
+
+/// S3 client for a bucket whose references are resolved
+pub(crate) fn build_client(
+    bucket: &ObjectStorageBucket,
+    timeout_seconds: u64,
+) -> Result<AmazonS3, ExecutionError> {
+    let mut builder = AmazonS3Builder::new()
+        .with_bucket_name(&bucket.name)
+        .with_region(&bucket.region)
+        .with_access_key_id(&bucket.access_key_id)
+        .with_secret_access_key(&bucket.secret_access_key)
+        .with_virtual_hosted_style_request(!bucket.path_style)
+        .with_client_options(
+            ClientOptions::new().with_timeout(Duration::from_secs(timeout_seconds)),
+        );
+    if let Some(endpoint) = &bucket.endpoint {
+        // Self-hosted servers on a private network often don't use TLS
+        builder = builder
+            .with_allow_http(endpoint.starts_with("http://"))
+            .with_endpoint(endpoint);
+    }
+
+    builder.build().map_err(|e| {
+        ExecutionError::InvalidJobDefinition(format!("Invalid object storage bucket: {}", e))
+    })
+}
+
+/// Internal storage path for a step's path: relative paths go under the execution's
+/// directory, `jobs/...` paths must be in the job's own directory
+pub(crate) fn internal_path(path: &str, context: &JobContext) -> Result<String, ExecutionError> {
+    let path = path.trim().trim_start_matches('/');
+    if path.is_empty() || path.split('/').any(|segment| segment == "..") {
+        return Err(ExecutionError::InvalidJobDefinition(format!(
+            "Invalid internal path '{}'",
+            path
+        )));
+    }
+
+    let job_prefix = format!("jobs/{}/", context.job_id);
+    if path.starts_with("jobs/") {
+        if !path.starts_with(&job_prefix) {
+            return Err(ExecutionError::InvalidJobDefinition(format!(
+                "Internal path '{}' belongs to another job",
+                path
+            )));
+        }
+        return Ok(path.to_string());
+    }
+    Ok(format!(
+        "{}executions/{}/{}",
+        job_prefix, context.execution_id, path
+    ))
+}
+
+/// Parse an object key
+fn object_key(key: &str) -> Result<ObjectPath, ExecutionError> {
+    ObjectPath::parse(key.trim_start_matches('/')).map_err(|e| {
+        ExecutionError::InvalidJobDefinition(format!("Invalid object key '{}': {}", key, e))
+    })
+}
+
+fn describe(location: &Location) -> serde_json::Value {
+    match location {
+        Location::Internal(path) => json!({ "type": "internal", "path": path }),
+        Location::Bucket(key) => json!({ "type": "bucket", "key": key.to_string() }),
+    }
+}
+
+fn object_store_error(e: object_store::Error) -> ExecutionError {
+    ExecutionError::ObjectStorageFailed(e.to_string())
+}
+
+#[async_trait]
+impl JobExecutor for ObjectStorageExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::ObjectStorage { bucket, operation } = &step.step_type else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "ObjectStorageExecutor can only execute ObjectStorage job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for object storage steps".to_string(),
+            ));
+        }
+
+        let variables = string_variables(context);
+        let store = self.client(bucket, context, &variables)?;
+
+        let output = match operation {
+            ObjectStorageOperation::Copy {
+                source,
+                destination,
+            }
+            | ObjectStorageOperation::Move {
+                source,
+                destination,
+            } => {
+                let remove_source = matches!(operation, ObjectStorageOperation::Move { .. });
+                let source = self.location(source, context, &variables, "source")?;
+                let destination = self.location(destination, context, &variables, "destination")?;
+                tracing::info!(
+                    source = %describe(&source),
+                    destination = %describe(&destination),
+                    remove_source,
+                    "Transferring object"
+                );
+                self.transfer(&store, source, destination, remove_source, context)
+                    .await?
+            }
+            ObjectStorageOperation::Delete { key } => {
+                let key = object_key(&render_field(key, context, &variables, "key")?)?;
+                tracing::info!(key = %key, "Deleting object");
+                store.delete(&key).await.map_err(object_store_error)?;
+                json!({ "deleted": key.to_string() })
+            }
+            ObjectStorageOperation::List { prefix, max_keys } => {
+                let prefix = match prefix {
+                    Some(prefix) => Some(object_key(&render_field(
+                        prefix, context, &variables, "prefix",
+                    )?)?),
+                    None => None,
+                };
+                let max_keys = max_keys.unwrap_or(MAX_LIST_KEYS).clamp(1, MAX_LIST_KEYS);
+                self.list(&store, prefix, max_keys).await?
+            }
+            ObjectStorageOperation::Presign {
+                key,
+                method,
+                expires_in_seconds,
+            } => {
+                let key = object_key(&render_field(key, context, &variables, "key")?)?;
+                let expires_in = expires_in_seconds.unwrap_or(DEFAULT_PRESIGN_SECONDS);
+                if expires_in == 0 || expires_in > MAX_PRESIGN_SECONDS {
+                    return Err(ExecutionError::InvalidJobDefinition(format!(
+                        "expires_in_seconds must be 1 to {}",
+                        MAX_PRESIGN_SECONDS
+                    )));
+                }
+                let http_method = match method {
+                    PresignMethod::Get => reqwest::Method::GET,
+                    PresignMethod::Put => reqwest::Method::PUT,
+                };
+                let url = store
+                    .signed_url(http_method, &key, Duration::from_secs(expires_in))
+                    .await
+                    .map_err(object_store_error)?;
+                json!({
+                    "key": key.to_string(),
+                    "method": method,
+                    "url": url.to_string(),
+                    "expires_at": (Utc::now() + chrono::Duration::seconds(expires_in as i64)).to_rfc3339(),
+                })
+            }
+        };
+
+        tracing::info!("Object storage operation completed");
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output,
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worker::consumer::test_support::MemoryStorage;
+    use object_store::memory::InMemory;
+    use uuid::Uuid;
+
+    fn context() -> JobContext {
+        JobContext::new(Uuid::new_v4(), Uuid::new_v4())
+    }
+
+    #[test]
+    fn test_internal_paths_stay_in_the_job() {
+        let context = context();
+        assert_eq!(
+            internal_path("exports/report.csv", &context).unwrap(),
+            format!(
+                "jobs/{}/executions/{}/exports/report.csv",
+                context.job_id, context.execution_id
+            )
+        );
+
+        let own = format!("jobs/{}/executions/earlier/out.csv", context.job_id);
+        assert_eq!(internal_path(&own, &context).unwrap(), own);
+
+        let other = format!("jobs/{}/secrets.csv", Uuid::new_v4());
+        assert!(internal_path(&other, &context).is_err());
+        assert!(internal_path("../../etc/passwd", &context).is_err());
+        assert!(internal_path(" ", &context).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_presign_without_network() {
+        let bucket = ObjectStorageBucket {
+            name: "artifacts".to_string(),
+            endpoint: Some("http://minio.internal:9000".to_string()),
+            region: "us-east-1".to_string(),
+            access_key_id: "scheduler".to_string(),
+            secret_access_key: "secret".to_string(),
+            path_style: true,
+        };
+
+        let store = build_client(&bucket, 10).unwrap();
+        let url = store
+            .signed_url(
+                reqwest::Method::GET,
+                &object_key("/reports/daily.csv").unwrap(),
+                Duration::from_secs(60),
+            )
+            .await
+            .unwrap();
+
+        assert!(url
+            .as_str()
+            .starts_with("http://minio.internal:9000/artifacts/reports/daily.csv?"));
+        assert!(url.as_str().contains("X-Amz-Credential=scheduler"));
+        assert!(url.as_str().contains("X-Amz-Expires=60"));
+    }
+
+    #[tokio::test]
+    async fn test_large_objects_transfer_in_parts() {
+        let storage = Arc::new(MemoryStorage::default());
+        let executor = ObjectStorageExecutor::new(storage.clone(), 10);
+        let bucket = InMemory::new();
+        let data: Vec<u8> = (0..PART_BYTES * 2 + 10).map(|i| (i % 251) as u8).collect();
+        let source = ObjectPath::from("in/large.bin");
+        bucket
+            .put(&source, PutPayload::from(data.clone()))
+            .await
+            .unwrap();
+
+        let size = executor
+            .download(&bucket, &source, "jobs/a/large.bin")
+            .await
+            .unwrap();
+        assert_eq!(size, data.len() as u64);
+        assert_eq!(storage.load_file("jobs/a/large.bin").await.unwrap(), data);
+
+        let destination = ObjectPath::from("out/large.bin");
+        let size = executor
+            .upload(&bucket, "jobs/a/large.bin", &destination)
+            .await
+            .unwrap();
+        assert_eq!(size, data.len() as u64);
+        let uploaded = bucket
+            .get(&destination)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(uploaded.as_ref(), data.as_slice());
+    }
+}
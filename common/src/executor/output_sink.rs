@@ -0,0 +1,248 @@
+// Output sink for large step results
+// Executors write records or raw bytes here instead of building the whole result in
+// memory; only a summary of the stored object enters the job context
+
+use crate::errors::ExecutionError;
+use crate::models::{JobContext, ProgressReporter};
+use crate::storage::StorageService;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+use tracing::{info, instrument};
+
+/// Buffered bytes written to storage in one append
+const FLUSH_BYTES: usize = 1024 * 1024;
+
+/// Records kept in the summary so later steps can peek at the output
+const PREVIEW_RECORDS: usize = 5;
+
+/// Summary of a streamed output, stored in the step output in place of the data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputSummary {
+    /// Storage path of the full output
+    pub path: String,
+    pub bytes: u64,
+    /// Records written, for outputs streamed as JSON lines
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub records: Option<u64>,
+    /// First few records of the output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub preview: Vec<Value>,
+}
+
+/// Writes a step's output to a storage object in chunks
+///
+/// Records are stored as JSON lines. A sink is created fresh for every attempt, so a
+/// retried step overwrites the output of the failed one.
+pub struct OutputSink {
+    storage: Arc<dyn StorageService>,
+    path: String,
+    buffer: Vec<u8>,
+    bytes: u64,
+    records: u64,
+    preview: Vec<Value>,
+    progress: ProgressReporter,
+}
+
+impl OutputSink {
+    /// Start an empty output object for a step
+    #[instrument(skip(storage, context), fields(execution_id = %context.execution_id))]
+    pub async fn create(
+        storage: Arc<dyn StorageService>,
+        context: &JobContext,
+        step_id: &str,
+        extension: &str,
+    ) -> Result<Self, ExecutionError> {
+        let path = output_path(context, step_id, extension);
+        storage.store_file(&path, &[]).await.map_err(|e| {
+            ExecutionError::StorageFailed(format!("Failed to create output: {}", e))
+        })?;
+
+        Ok(Self {
+            storage,
+            path,
+            buffer: Vec::with_capacity(FLUSH_BYTES),
+            bytes: 0,
+            records: 0,
+            preview: Vec::new(),
+            progress: context.progress.clone(),
+        })
+    }
+
+    /// Append one record as a JSON line
+    pub async fn write_record(&mut self, record: Value) -> Result<(), ExecutionError> {
+        serde_json::to_writer(&mut self.buffer, &record).map_err(|e| {
+            ExecutionError::StorageFailed(format!("Failed to encode output record: {}", e))
+        })?;
+        self.buffer.push(b'\n');
+        self.records += 1;
+        if self.preview.len() < PREVIEW_RECORDS {
+            self.preview.push(record);
+        }
+        self.progress.rows(self.records, None);
+        self.flush_if_full().await
+    }
+
+    /// Append raw bytes
+    pub async fn write_bytes(&mut self, data: &[u8]) -> Result<(), ExecutionError> {
+        self.buffer.extend_from_slice(data);
+        self.flush_if_full().await
+    }
+
+    /// Flush what is left and summarize the output
+    pub async fn finish(mut self) -> Result<OutputSummary, ExecutionError> {
+        self.flush().await?;
+        info!(
+            path = %self.path,
+            bytes = self.bytes,
+            records = self.records,
+            "Step output streamed to storage"
+        );
+
+        Ok(OutputSummary {
+            path: self.path,
+            bytes: self.bytes,
+            records: (self.records > 0).then_some(self.records),
+            preview: self.preview,
+        })
+    }
+
+    async fn flush_if_full(&mut self) -> Result<(), ExecutionError> {
+        if self.buffer.len() >= FLUSH_BYTES {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ExecutionError> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.storage
+            .append_file(&self.path, &self.buffer)
+            .await
+            .map_err(|e| ExecutionError::StorageFailed(format!("Failed to write output: {}", e)))?;
+        self.bytes += self.buffer.len() as u64;
+        self.buffer.clear();
+        self.progress.bytes(self.bytes, None);
+        Ok(())
+    }
+}
+
+/// Storage path of a step's streamed output
+pub fn output_path(context: &JobContext, step_id: &str, extension: &str) -> String {
+    format!(
+        "jobs/{}/executions/{}/outputs/{}.{}",
+        context.job_id, context.execution_id, step_id, extension
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::StorageError;
+    use crate::models::JobContext;
+    use async_trait::async_trait;
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+    use uuid::Uuid;
+
+    #[derive(Default)]
+    struct MemoryStorage {
+        files: Mutex<HashMap<String, Vec<u8>>>,
+    }
+
+    #[async_trait]
+    impl StorageService for MemoryStorage {
+        async fn store_job_definition(&self, _: Uuid, _: &str) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        async fn load_job_definition(&self, _: Uuid) -> Result<String, StorageError> {
+            Ok("{}".to_string())
+        }
+
+        async fn store_context(&self, _: &JobContext) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        async fn load_context(
+            &self,
+            job_id: Uuid,
+            execution_id: Uuid,
+        ) -> Result<JobContext, StorageError> {
+            Ok(JobContext::new(execution_id, job_id))
+        }
+
+        async fn store_file(&self, path: &str, data: &[u8]) -> Result<String, StorageError> {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(path.to_string(), data.to_vec());
+            Ok(path.to_string())
+        }
+
+        async fn append_file(&self, path: &str, data: &[u8]) -> Result<(), StorageError> {
+            self.files
+                .lock()
+                .unwrap()
+                .entry(path.to_string())
+                .or_default()
+                .extend_from_slice(data);
+            Ok(())
+        }
+
+        async fn load_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+            self.files
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| StorageError::NotFound(path.to_string()))
+        }
+
+        async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+            self.files.lock().unwrap().remove(path);
+            Ok(())
+        }
+
+        async fn list_files(&self, _: &str) -> Result<Vec<String>, StorageError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_stream_to_storage_with_summary() {
+        let storage = Arc::new(MemoryStorage::default());
+        let context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+
+        let mut sink = OutputSink::create(storage.clone(), &context, "export", "jsonl")
+            .await
+            .unwrap();
+        for id in 0..20_000 {
+            sink.write_record(serde_json::json!({ "id": id, "name": "row" }))
+                .await
+                .unwrap();
+        }
+        let summary = sink.finish().await.unwrap();
+
+        let stored = storage.load_file(&summary.path).await.unwrap();
+        assert_eq!(summary.path, output_path(&context, "export", "jsonl"));
+        assert_eq!(summary.bytes, stored.len() as u64);
+        assert_eq!(summary.records, Some(20_000));
+        assert_eq!(summary.preview.len(), PREVIEW_RECORDS);
+        assert_eq!(stored.iter().filter(|b| **b == b'\n').count(), 20_000);
+        assert_eq!(
+            context.progress.latest().unwrap().rows_processed,
+            Some(20_000)
+        );
+
+        // A retry starts the object over
+        let sink = OutputSink::create(storage.clone(), &context, "export", "jsonl")
+            .await
+            .unwrap();
+        let summary = sink.finish().await.unwrap();
+        assert_eq!(summary.bytes, 0);
+        assert!(storage.load_file(&summary.path).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,311 @@
+// PDF report executor implementation
+// Purpose: Turn job data into a ready-to-send PDF, so a scheduled reporting job can
+// attach it to an email or upload it without a separate rendering service
+//
+// The template is HTML with Handlebars expressions, given inline or as a file in
+// storage. It is rendered with:
+// - `variables`: the job variables
+// - `steps`: the outputs of earlier steps, by step id
+// - `webhook`: the webhook data, if the execution was triggered by one
+// - `job_id` and `execution_id`
+// Values are HTML-escaped; use `{{{ ... }}}` for trusted markup. The PDF is written to
+// `output_path` and recorded as a file of the execution.
+
+use crate::errors::ExecutionError;
+use crate::executor::object_storage::internal_path;
+use crate::executor::JobExecutor;
+use crate::models::{FileMetadata, JobContext, JobStep, JobType, StepOutput};
+use crate::storage::StorageService;
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use chrono::Utc;
+use handlebars::Handlebars;
+use printpdf::{GeneratePdfOptions, PdfDocument, PdfSaveOptions};
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// A4, in millimetres
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+
+/// Page margin unless the step sets one
+const DEFAULT_MARGIN_MM: f32 = 15.0;
+
+/// Largest template accepted, inline or from storage
+const MAX_TEMPLATE_BYTES: usize = 1024 * 1024;
+
+/// A rendered report
+#[derive(Debug)]
+struct PdfReport {
+    bytes: Vec<u8>,
+    pages: usize,
+}
+
+/// PdfExecutor renders PDF report steps
+pub struct PdfExecutor {
+    storage_service: Arc<dyn StorageService>,
+    reference_resolver: Arc<ReferenceResolver>,
+}
+
+impl PdfExecutor {
+    /// Create a new PdfExecutor reading templates from and writing reports to the
+    /// given storage
+    pub fn new(storage_service: Arc<dyn StorageService>) -> Self {
+        Self {
+            storage_service,
+            reference_resolver: Arc::new(ReferenceResolver::new()),
+        }
+    }
+
+    fn resolve(
+        &self,
+        value: &str,
+        context: &JobContext,
+        field: &str,
+    ) -> Result<String, ExecutionError> {
+        self.reference_resolver
+            .resolve(value, context)
+            .map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to resolve references in {}: {}",
+                    field, e
+                ))
+            })
+    }
+
+    /// Inline template, or the contents of the template file
+    async fn load_template(
+        &self,
+        template: Option<&String>,
+        template_path: Option<&String>,
+        context: &JobContext,
+    ) -> Result<String, ExecutionError> {
+        let template = match (template, template_path) {
+            (Some(template), None) => template.clone(),
+            (None, Some(path)) => {
+                let path = internal_path(&self.resolve(path, context, "template_path")?, context)?;
+                let data = self.storage_service.load_file(&path).await.map_err(|e| {
+                    ExecutionError::StorageFailed(format!(
+                        "Failed to load template '{}': {}",
+                        path, e
+                    ))
+                })?;
+                String::from_utf8(data).map_err(|_| {
+                    ExecutionError::InvalidJobDefinition(format!(
+                        "Template '{}' is not UTF-8 text",
+                        path
+                    ))
+                })?
+            }
+            _ => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "PDF report steps need exactly one of template and template_path".to_string(),
+                ))
+            }
+        };
+
+        if template.len() > MAX_TEMPLATE_BYTES {
+            return Err(ExecutionError::InvalidJobDefinition(format!(
+                "Template of {} bytes exceeds the limit of {} bytes",
+                template.len(),
+                MAX_TEMPLATE_BYTES
+            )));
+        }
+        Ok(template)
+    }
+}
+
+#[async_trait]
+impl JobExecutor for PdfExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::PdfReport {
+            template,
+            template_path,
+            output_path,
+            landscape,
+            margin_mm,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "PdfExecutor can only execute PdfReport job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for PDF report steps".to_string(),
+            ));
+        }
+
+        let margin_mm = margin_mm.unwrap_or(DEFAULT_MARGIN_MM);
+        if !(0.0..PAGE_WIDTH_MM / 2.0).contains(&margin_mm) {
+            return Err(ExecutionError::InvalidJobDefinition(format!(
+                "margin_mm must be between 0 and {}",
+                PAGE_WIDTH_MM / 2.0
+            )));
+        }
+        let output_path =
+            internal_path(&self.resolve(output_path, context, "output_path")?, context)?;
+        let template = self
+            .load_template(template.as_ref(), template_path.as_ref(), context)
+            .await?;
+
+        let html = render_template(&template, context)?;
+        let landscape = *landscape;
+        tracing::info!(path = %output_path, "Rendering PDF report");
+        // Layout and font embedding are CPU-bound
+        let report = tokio::task::spawn_blocking(move || render_pdf(&html, landscape, margin_mm))
+            .await
+            .map_err(|e| ExecutionError::PdfFailed(format!("Render task failed: {}", e)))??;
+
+        self.storage_service
+            .store_file(&output_path, &report.bytes)
+            .await
+            .map_err(|e| {
+                ExecutionError::StorageFailed(format!(
+                    "Failed to store report '{}': {}",
+                    output_path, e
+                ))
+            })?;
+
+        let filename = output_path
+            .rsplit('/')
+            .next()
+            .unwrap_or("report.pdf")
+            .to_string();
+        let metadata = FileMetadata {
+            path: output_path.clone(),
+            filename,
+            size: report.bytes.len() as u64,
+            mime_type: Some("application/pdf".to_string()),
+            row_count: None,
+            created_at: Utc::now(),
+        };
+        context.add_file_metadata(metadata);
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "path": output_path,
+                "size": report.bytes.len(),
+                "pages": report.pages,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// HTML of the report: the template rendered with the job context
+fn render_template(template: &str, context: &JobContext) -> Result<String, ExecutionError> {
+    let steps: HashMap<&String, &serde_json::Value> = context
+        .steps
+        .iter()
+        .map(|(id, output)| (id, &output.output))
+        .collect();
+    let data = json!({
+        "variables": context.variables,
+        "steps": steps,
+        "webhook": context.webhook,
+        "job_id": context.job_id,
+        "execution_id": context.execution_id,
+    });
+
+    Handlebars::new()
+        .render_template(template, &data)
+        .map_err(|e| ExecutionError::PdfFailed(format!("Failed to render template: {}", e)))
+}
+
+/// Lay out the HTML on A4 pages
+fn render_pdf(html: &str, landscape: bool, margin_mm: f32) -> Result<PdfReport, ExecutionError> {
+    let (page_width, page_height) = if landscape {
+        (PAGE_HEIGHT_MM, PAGE_WIDTH_MM)
+    } else {
+        (PAGE_WIDTH_MM, PAGE_HEIGHT_MM)
+    };
+    let options = GeneratePdfOptions {
+        page_width: Some(page_width),
+        page_height: Some(page_height),
+        margin_top: Some(margin_mm),
+        margin_right: Some(margin_mm),
+        margin_bottom: Some(margin_mm),
+        margin_left: Some(margin_mm),
+        ..GeneratePdfOptions::default()
+    };
+
+    let mut warnings = Vec::new();
+    let document = PdfDocument::from_html(
+        html,
+        &BTreeMap::new(),
+        &BTreeMap::new(),
+        &options,
+        &mut warnings,
+    )
+    .map_err(|e| ExecutionError::PdfFailed(format!("Failed to lay out report: {}", e)))?;
+    let bytes = document.save(&PdfSaveOptions::default(), &mut warnings);
+    if !warnings.is_empty() {
+        tracing::debug!(warnings = warnings.len(), "PDF rendered with warnings");
+    }
+
+    Ok(PdfReport {
+        bytes,
+        pages: document.pages.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_render_template_escapes_values() {
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context
+            .variables
+            .insert("customer".to_string(), json!("<b>Công ty A</b>"));
+        context.set_step_output(
+            "totals".to_string(),
+            StepOutput {
+                step_id: "totals".to_string(),
+                status: "success".to_string(),
+                output: json!({"rows": [{"month": "01"}, {"month": "02"}]}),
+                started_at: Utc::now(),
+                completed_at: Utc::now(),
+            },
+        );
+
+        let html = render_template(
+            "<h1>{{variables.customer}}</h1>{{#each steps.totals.rows}}<p>{{month}}</p>{{/each}}",
+            &context,
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<h1>&lt;b&gt;Công ty A&lt;/b&gt;</h1><p>01</p><p>02</p>"
+        );
+
+        assert!(render_template("{{#each}}", &context).is_err());
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        let report = render_pdf(
+            "<html><body><h1>Báo cáo</h1><p>Tổng: 5</p></body></html>",
+            true,
+            DEFAULT_MARGIN_MM,
+        )
+        .unwrap();
+        assert!(report.bytes.starts_with(b"%PDF"));
+        assert_eq!(report.pages, 1);
+    }
+}
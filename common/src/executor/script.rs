@@ -0,0 +1,412 @@
+// Script executor implementation
+// Purpose: Massage data between steps with a small embedded Rhai script instead of a
+// separate HTTP service
+//
+// A script sees the job variables as the map `variables`, the outputs of earlier
+// steps as the map `steps` and the step's environment variables as the map `env`.
+// Changes to `variables` are written back to the job context, changes to `steps` and
+// `env` are discarded, and the value of the last expression becomes the step result.
+// Secrets of the environment are masked in the result, the logs and the variables
+// written back.
+// Scripts have no access to files or the network and are bounded by an operation
+// budget capped by the worker, size limits and a wall-clock deadline; a run whose
+// step is dropped, e.g. by the step timeout or a cancellation, stops too.
+
+use crate::errors::ExecutionError;
+use crate::executor::JobExecutor;
+use crate::models::{JobContext, JobStep, JobType, StepOutput};
+use crate::step_env::StepEnv;
+use async_trait::async_trait;
+use chrono::Utc;
+use rhai::{Dynamic, Engine, EvalAltResult, Scope};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Operation budget of a run unless the step sets one
+const DEFAULT_MAX_OPERATIONS: u64 = 10_000_000;
+
+/// Worker caps on the operation budget and run time unless configured otherwise
+const DEFAULT_OPERATIONS_CAP: u64 = 1_000_000_000;
+const DEFAULT_MAX_RUN: Duration = Duration::from_secs(300);
+
+/// Operations between checks of the deadline and cancellation of a run
+const STOP_CHECK_OPERATIONS: u64 = 1024;
+
+/// Limits on the values a script may build
+const MAX_STRING_BYTES: usize = 16 * 1024 * 1024;
+const MAX_ARRAY_SIZE: usize = 100_000;
+const MAX_MAP_SIZE: usize = 100_000;
+const MAX_CALL_LEVELS: usize = 64;
+
+/// Lines of `print`/`debug` output kept per run
+const MAX_LOG_LINES: usize = 100;
+
+/// Result of a finished run
+#[derive(Debug)]
+struct ScriptRun {
+    result: serde_json::Value,
+    variables: HashMap<String, serde_json::Value>,
+    logs: Vec<String>,
+}
+
+/// Stops a run at its deadline or once its step is dropped
+#[derive(Debug, Clone)]
+struct RunStop {
+    deadline: Instant,
+    max_run: Duration,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl RunStop {
+    fn after(max_run: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + max_run,
+            max_run,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Why the run must stop, if it must
+    fn reason(&self) -> Option<&'static str> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Some("cancelled")
+        } else if Instant::now() >= self.deadline {
+            Some("deadline")
+        } else {
+            None
+        }
+    }
+}
+
+/// Cancels the run when dropped, i.e. when the step stops waiting for it
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// ScriptExecutor runs embedded Rhai script steps
+pub struct ScriptExecutor {
+    operations_cap: u64,
+    max_run: Duration,
+}
+
+impl ScriptExecutor {
+    /// Create a new ScriptExecutor
+    pub fn new() -> Self {
+        Self {
+            operations_cap: DEFAULT_OPERATIONS_CAP,
+            max_run: DEFAULT_MAX_RUN,
+        }
+    }
+
+    /// Cap the operation budget of every run, and stop runs taking longer than `max_run`
+    pub fn with_limits(mut self, operations_cap: u64, max_run: Duration) -> Self {
+        self.operations_cap = operations_cap;
+        self.max_run = max_run;
+        self
+    }
+}
+
+impl Default for ScriptExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl JobExecutor for ScriptExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Script {
+            source,
+            max_operations,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "ScriptExecutor can only execute Script job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for script steps".to_string(),
+            ));
+        }
+
+        let source = source.clone();
+        let variables = context.variables.clone();
+        let steps: HashMap<&String, &serde_json::Value> = context
+            .steps
+            .iter()
+            .map(|(id, output)| (id, &output.output))
+            .collect();
+        let steps = serde_json::to_value(steps)
+            .map_err(|e| ExecutionError::ScriptFailed(format!("Failed to encode steps: {}", e)))?;
+        let env = context.env.to_map();
+        let max_operations = max_operations
+            .unwrap_or(DEFAULT_MAX_OPERATIONS)
+            .min(self.operations_cap);
+        let stop = RunStop::after(self.max_run);
+        let _cancel = CancelOnDrop(Arc::clone(&stop.cancelled));
+
+        tracing::info!(max_operations, "Running script");
+        // The interpreter is CPU-bound; the operation budget and the deadline bound how
+        // long it can run
+        let mut run = tokio::task::spawn_blocking(move || {
+            run_script(&source, variables, steps, env, max_operations, &stop)
+        })
+        .await
+        .map_err(|e| ExecutionError::ScriptFailed(format!("Script task failed: {}", e)))??;
+        redact_run(&mut run, &context.env);
+
+        for line in &run.logs {
+            tracing::info!("Script: {}", line);
+        }
+        context.variables = run.variables;
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "result": run.result,
+                "logs": run.logs,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// Mask the secrets of the step's environment in what the script left behind
+fn redact_run(run: &mut ScriptRun, env: &StepEnv) {
+    env.redact_json(&mut run.result);
+    run.variables
+        .values_mut()
+        .for_each(|value| env.redact_json(value));
+    for line in &mut run.logs {
+        *line = env.redact(line);
+    }
+}
+
+/// Evaluate the script against the variables, step outputs and environment
+fn run_script(
+    source: &str,
+    variables: HashMap<String, serde_json::Value>,
+    steps: serde_json::Value,
+    env: HashMap<String, String>,
+    max_operations: u64,
+    stop: &RunStop,
+) -> Result<ScriptRun, ExecutionError> {
+    let logs = Arc::new(Mutex::new(Vec::new()));
+
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(max_operations)
+        .set_max_string_size(MAX_STRING_BYTES)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_MAP_SIZE)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .disable_symbol("eval");
+    let print_logs = Arc::clone(&logs);
+    engine.on_print(move |line| push_log(&print_logs, line.to_string()));
+    let debug_logs = Arc::clone(&logs);
+    engine.on_debug(move |line, _, _| push_log(&debug_logs, line.to_string()));
+    let progress_stop = stop.clone();
+    engine.on_progress(move |operations| {
+        if operations % STOP_CHECK_OPERATIONS != 0 {
+            return None;
+        }
+        progress_stop.reason().map(Dynamic::from)
+    });
+
+    let mut scope = Scope::new();
+    scope.push_dynamic("variables", to_dynamic(&variables)?);
+    // Not a constant: assigning to a property of a constant panics inside rhai
+    scope.push_dynamic("steps", to_dynamic(&steps)?);
+    scope.push_dynamic("env", to_dynamic(&env)?);
+
+    let result = engine
+        .eval_with_scope::<Dynamic>(&mut scope, source)
+        .map_err(|e| script_error(*e, max_operations, stop))?;
+
+    let result = rhai::serde::from_dynamic(&result).map_err(|e| {
+        ExecutionError::ScriptFailed(format!("Result is not JSON-compatible: {}", e))
+    })?;
+    let variables = scope
+        .get_value::<Dynamic>("variables")
+        .ok_or_else(|| ExecutionError::ScriptFailed("variables was removed".to_string()))?;
+    let variables = rhai::serde::from_dynamic(&variables)
+        .map_err(|e| ExecutionError::ScriptFailed(format!("variables must remain a map: {}", e)))?;
+
+    let logs = std::mem::take(&mut *logs.lock().unwrap_or_else(|e| e.into_inner()));
+    Ok(ScriptRun {
+        result,
+        variables,
+        logs,
+    })
+}
+
+fn to_dynamic<T: serde::Serialize>(value: &T) -> Result<Dynamic, ExecutionError> {
+    rhai::serde::to_dynamic(value)
+        .map_err(|e| ExecutionError::ScriptFailed(format!("Failed to encode input: {}", e)))
+}
+
+fn push_log(logs: &Mutex<Vec<String>>, line: String) {
+    let mut logs = logs.lock().unwrap_or_else(|e| e.into_inner());
+    if logs.len() < MAX_LOG_LINES {
+        logs.push(line);
+    }
+}
+
+fn script_error(e: EvalAltResult, max_operations: u64, stop: &RunStop) -> ExecutionError {
+    match e {
+        EvalAltResult::ErrorTooManyOperations(_) => ExecutionError::ScriptFailed(format!(
+            "Script exceeded its budget of {} operations",
+            max_operations
+        )),
+        EvalAltResult::ErrorTerminated(..) if stop.reason() == Some("deadline") => {
+            ExecutionError::Timeout(stop.max_run.as_secs())
+        }
+        EvalAltResult::ErrorTerminated(..) => {
+            ExecutionError::ScriptFailed("Script stopped as its step was cancelled".to_string())
+        }
+        e => ExecutionError::ScriptFailed(e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unbounded() -> RunStop {
+        RunStop::after(Duration::from_secs(3600))
+    }
+
+    #[test]
+    fn test_run_script_updates_variables() {
+        let variables = HashMap::from([
+            ("name".to_string(), json!("  Alice ")),
+            ("count".to_string(), json!(2)),
+        ]);
+        let steps = json!({"fetch": {"items": [1, 2, 3]}});
+        let source = r#"
+            print("trimming");
+            variables.name.trim();
+            variables.count += steps.fetch.items.len();
+            variables.tags = ["a", "b"];
+            #{ total: variables.count }
+        "#;
+
+        let run = run_script(
+            source,
+            variables,
+            steps,
+            HashMap::new(),
+            10_000,
+            &unbounded(),
+        )
+        .unwrap();
+        assert_eq!(run.result, json!({"total": 5}));
+        assert_eq!(run.variables["name"], json!("Alice"));
+        assert_eq!(run.variables["count"], json!(5));
+        assert_eq!(run.variables["tags"], json!(["a", "b"]));
+        assert_eq!(run.logs, vec!["trimming"]);
+    }
+
+    #[test]
+    fn test_run_script_limits() {
+        let error = run_script(
+            "loop {}",
+            HashMap::new(),
+            json!({}),
+            HashMap::new(),
+            1_000,
+            &unbounded(),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("1000 operations"), "{}", error);
+
+        assert!(run_script(
+            "variables = 1",
+            HashMap::new(),
+            json!({}),
+            HashMap::new(),
+            1_000,
+            &unbounded()
+        )
+        .is_err());
+        assert!(run_script(
+            "eval(\"1\")",
+            HashMap::new(),
+            json!({}),
+            HashMap::new(),
+            1_000,
+            &unbounded()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_script_env_secrets_are_masked() {
+        let mut env = StepEnv::default();
+        env.add_secret("API_TOKEN".to_string(), "s3cr3t-token".to_string());
+        let source = r#"
+            print("token " + env.API_TOKEN);
+            variables.leaked = env.API_TOKEN;
+            #{ header: "Bearer " + env.API_TOKEN, length: env.API_TOKEN.len() }
+        "#;
+
+        let mut run = run_script(
+            source,
+            HashMap::new(),
+            json!({}),
+            env.to_map(),
+            10_000,
+            &unbounded(),
+        )
+        .unwrap();
+        redact_run(&mut run, &env);
+        assert_eq!(run.result, json!({"header": "Bearer ***", "length": 12}));
+        assert_eq!(run.variables["leaked"], json!("***"));
+        assert_eq!(run.logs, vec!["token ***"]);
+    }
+
+    #[test]
+    fn test_run_script_stops_at_deadline_or_cancellation() {
+        let error = run_script(
+            "loop {}",
+            HashMap::new(),
+            json!({}),
+            HashMap::new(),
+            u64::MAX,
+            &RunStop::after(Duration::from_millis(50)),
+        )
+        .unwrap_err();
+        assert!(matches!(error, ExecutionError::Timeout(_)), "{}", error);
+
+        let stop = unbounded();
+        drop(CancelOnDrop(Arc::clone(&stop.cancelled)));
+        let error = run_script(
+            "loop {}",
+            HashMap::new(),
+            json!({}),
+            HashMap::new(),
+            u64::MAX,
+            &stop,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("cancelled"), "{}", error);
+    }
+}
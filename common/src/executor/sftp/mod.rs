@@ -5,10 +5,13 @@
 mod auth;
 mod connection;
 mod operations;
+mod transfer;
+mod validation;
 
 pub use auth::authenticate_session;
 pub use connection::SftpConnection;
 pub use operations::{download_file, list_files, upload_file};
+pub use validation::validate_sftp_step;
 
 use crate::errors::ExecutionError;
 use crate::executor::JobExecutor;
@@ -3,18 +3,22 @@
 // RECC 2025: Max 300 lines
 
 use crate::errors::ExecutionError;
-use crate::models::{FileMetadata, JobContext, JobStep, JobType, SftpOperation, StepOutput};
+use crate::models::{
+    FileMetadata, JobContext, JobStep, JobType, ProgressReporter, SftpAuth, SftpOperation,
+    StepOutput,
+};
 use crate::storage::StorageService;
 use crate::worker::reference::ReferenceResolver;
 use chrono::Utc;
 use serde_json::Value;
 use ssh2::Session;
-use std::io::Read;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::sync::Arc;
 use tracing::{debug, error, info, instrument};
 
 use super::connection::SftpConnection;
+use super::transfer::{read_with_progress, write_with_progress};
 
 /// Execute SFTP step
 #[instrument(skip(step, context, storage_service, reference_resolver))]
@@ -56,16 +60,26 @@ pub async fn execute_sftp_step(
         .resolve(host, context)
         .unwrap_or_else(|_| host.clone());
     let verify_host_key = options.verify_host_key;
+    let auth = auth.as_ref().ok_or_else(|| {
+        ExecutionError::SftpAuthenticationFailed(
+            "No credentials: set the step's auth or a connection".to_string(),
+        )
+    })?;
 
-    // Establish SFTP connection
-    let connection =
-        SftpConnection::connect(&host_resolved, port, auth, verify_host_key, timeout_seconds)?;
+    // ssh2 is blocking, so the connection and transfers run off the async runtime
+    let target = SftpTarget {
+        host: host_resolved,
+        port,
+        auth: auth.clone(),
+        verify_host_key,
+        timeout_seconds,
+    };
 
     // Execute operation based on type
     match operation {
         SftpOperation::Download => {
             download_operation(
-                connection.session(),
+                target,
                 remote_path,
                 context,
                 storage_service,
@@ -75,7 +89,7 @@ pub async fn execute_sftp_step(
         }
         SftpOperation::Upload => {
             upload_operation(
-                connection.session(),
+                target,
                 local_path.as_ref().unwrap_or(&String::new()),
                 remote_path,
                 options.create_directories,
@@ -88,9 +102,40 @@ pub async fn execute_sftp_step(
     }
 }
 
+/// Server an SFTP step connects to
+struct SftpTarget {
+    host: String,
+    port: u16,
+    auth: SftpAuth,
+    verify_host_key: bool,
+    timeout_seconds: u64,
+}
+
+impl SftpTarget {
+    /// Connect and run blocking SFTP work on the blocking thread pool
+    async fn run_blocking<T, F>(self, work: F) -> Result<T, ExecutionError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Session) -> Result<T, ExecutionError> + Send + 'static,
+    {
+        tokio::task::spawn_blocking(move || {
+            let connection = SftpConnection::connect(
+                &self.host,
+                self.port,
+                &self.auth,
+                self.verify_host_key,
+                self.timeout_seconds,
+            )?;
+            work(connection.session())
+        })
+        .await
+        .map_err(|e| ExecutionError::SftpOperationFailed(format!("SFTP task failed: {}", e)))?
+    }
+}
+
 /// Download file from SFTP
 async fn download_operation(
-    sess: &Session,
+    target: SftpTarget,
     remote_path: &str,
     context: &JobContext,
     storage_service: &Arc<dyn StorageService>,
@@ -102,33 +147,11 @@ async fn download_operation(
 
     info!(remote_path = %remote_path, "Downloading file from SFTP");
 
-    // Open SFTP channel
-    let sftp = sess.sftp().map_err(|e| {
-        error!(error = %e, "Failed to open SFTP channel");
-        ExecutionError::SftpOperationFailed(format!("Failed to open SFTP channel: {}", e))
-    })?;
-
-    // Get file metadata
-    let stat = sftp.stat(Path::new(&remote_path)).map_err(|e| {
-        error!(error = %e, remote_path = %remote_path, "File not found");
-        ExecutionError::SftpFileNotFound(format!("File not found: {}: {}", remote_path, e))
-    })?;
-
-    let file_size = stat.size.unwrap_or(0);
-    debug!(remote_path = %remote_path, size = file_size, "File metadata retrieved");
-
-    // Open remote file
-    let mut remote_file = sftp.open(Path::new(&remote_path)).map_err(|e| {
-        error!(error = %e, remote_path = %remote_path, "Failed to open remote file");
-        ExecutionError::SftpOperationFailed(format!("Failed to open remote file: {}", e))
-    })?;
-
-    // Read file content
-    let mut buffer = Vec::new();
-    remote_file.read_to_end(&mut buffer).map_err(|e| {
-        error!(error = %e, remote_path = %remote_path, "Failed to read file");
-        ExecutionError::SftpOperationFailed(format!("Failed to read file: {}", e))
-    })?;
+    let progress = context.progress.clone();
+    let path = remote_path.clone();
+    let buffer = target
+        .run_blocking(move |sess| read_remote_file(sess, &path, &progress))
+        .await?;
 
     // Extract filename
     let filename = Path::new(&remote_path)
@@ -192,9 +215,45 @@ async fn download_operation(
     })
 }
 
+/// Read a remote file, reporting bytes transferred
+fn read_remote_file(
+    sess: &Session,
+    remote_path: &str,
+    progress: &ProgressReporter,
+) -> Result<Vec<u8>, ExecutionError> {
+    // Open SFTP channel
+    let sftp = sess.sftp().map_err(|e| {
+        error!(error = %e, "Failed to open SFTP channel");
+        ExecutionError::SftpOperationFailed(format!("Failed to open SFTP channel: {}", e))
+    })?;
+
+    // Get file metadata
+    let stat = sftp.stat(Path::new(remote_path)).map_err(|e| {
+        error!(error = %e, remote_path = %remote_path, "File not found");
+        ExecutionError::SftpFileNotFound(format!("File not found: {}: {}", remote_path, e))
+    })?;
+
+    let file_size = stat.size.unwrap_or(0);
+    debug!(remote_path = %remote_path, size = file_size, "File metadata retrieved");
+
+    // Open remote file
+    let mut remote_file = sftp.open(Path::new(remote_path)).map_err(|e| {
+        error!(error = %e, remote_path = %remote_path, "Failed to open remote file");
+        ExecutionError::SftpOperationFailed(format!("Failed to open remote file: {}", e))
+    })?;
+
+    // Read file content, reporting bytes transferred
+    let mut buffer = Vec::with_capacity(file_size as usize);
+    read_with_progress(&mut remote_file, &mut buffer, file_size, progress).map_err(|e| {
+        error!(error = %e, remote_path = %remote_path, "Failed to read file");
+        ExecutionError::SftpOperationFailed(format!("Failed to read file: {}", e))
+    })?;
+    Ok(buffer)
+}
+
 /// Upload file to SFTP
 async fn upload_operation(
-    sess: &Session,
+    target: SftpTarget,
     local_path: &str,
     remote_path: &str,
     create_remote_dirs: bool,
@@ -216,7 +275,45 @@ async fn upload_operation(
         error!(error = %e, local_path = %local_path, "Failed to load file");
         ExecutionError::StorageFailed(format!("Failed to load file: {}", e))
     })?;
+    let size = file_data.len();
+
+    let progress = context.progress.clone();
+    let path = remote_path.clone();
+    target
+        .run_blocking(move |sess| {
+            write_remote_file(sess, &path, &file_data, create_remote_dirs, &progress)
+        })
+        .await?;
 
+    info!(
+        local_path = %local_path,
+        remote_path = %remote_path,
+        size = size,
+        "File uploaded successfully"
+    );
+
+    Ok(StepOutput {
+        step_id: "sftp_upload".to_string(),
+        status: "success".to_string(),
+        output: Value::Object(serde_json::Map::from_iter(vec![
+            ("operation".to_string(), Value::String("upload".to_string())),
+            ("local_path".to_string(), Value::String(local_path)),
+            ("remote_path".to_string(), Value::String(remote_path)),
+            ("bytes_transferred".to_string(), Value::Number(size.into())),
+        ])),
+        started_at: Utc::now(),
+        completed_at: Utc::now(),
+    })
+}
+
+/// Write a remote file, reporting bytes transferred
+fn write_remote_file(
+    sess: &Session,
+    remote_path: &str,
+    file_data: &[u8],
+    create_remote_dirs: bool,
+    progress: &ProgressReporter,
+) -> Result<(), ExecutionError> {
     // Open SFTP channel
     let sftp = sess.sftp().map_err(|e| {
         error!(error = %e, "Failed to open SFTP channel");
@@ -225,43 +322,20 @@ async fn upload_operation(
 
     // Create remote directories if needed
     if create_remote_dirs {
-        if let Some(parent) = Path::new(&remote_path).parent() {
+        if let Some(parent) = Path::new(remote_path).parent() {
             create_remote_dirs_fn(&sftp, parent)?;
         }
     }
 
     // Write file to SFTP
-    let mut remote_file = sftp.create(Path::new(&remote_path)).map_err(|e| {
+    let mut remote_file = sftp.create(Path::new(remote_path)).map_err(|e| {
         error!(error = %e, remote_path = %remote_path, "Failed to create remote file");
         ExecutionError::SftpOperationFailed(format!("Failed to create remote file: {}", e))
     })?;
 
-    std::io::Write::write_all(&mut remote_file, &file_data).map_err(|e| {
+    write_with_progress(&mut remote_file, file_data, progress).map_err(|e| {
         error!(error = %e, remote_path = %remote_path, "Failed to write file");
         ExecutionError::SftpOperationFailed(format!("Failed to write file: {}", e))
-    })?;
-
-    info!(
-        local_path = %local_path,
-        remote_path = %remote_path,
-        size = file_data.len(),
-        "File uploaded successfully"
-    );
-
-    Ok(StepOutput {
-        step_id: "sftp_upload".to_string(),
-        status: "success".to_string(),
-        output: Value::Object(serde_json::Map::from_iter(vec![
-            ("operation".to_string(), Value::String("upload".to_string())),
-            ("local_path".to_string(), Value::String(local_path)),
-            ("remote_path".to_string(), Value::String(remote_path)),
-            (
-                "bytes_transferred".to_string(),
-                Value::Number(file_data.len().into()),
-            ),
-        ])),
-        started_at: Utc::now(),
-        completed_at: Utc::now(),
     })
 }
 
@@ -354,7 +428,8 @@ pub async fn upload_file(
         ExecutionError::SftpOperationFailed(format!("Failed to create remote file: {}", e))
     })?;
 
-    std::io::Write::write_all(&mut remote_file, &file_data)
+    remote_file
+        .write_all(&file_data)
         .map_err(|e| ExecutionError::SftpOperationFailed(format!("Failed to write file: {}", e)))?;
 
     Ok(())
@@ -0,0 +1,79 @@
+// SFTP transfers with progress reporting
+// Copies in chunks so long transfers report bytes transferred while they run
+
+use crate::models::ProgressReporter;
+use std::io::{Read, Write};
+
+/// Bytes copied between progress reports
+const TRANSFER_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Read everything from `reader`, reporting bytes transferred after each chunk
+pub(super) fn read_with_progress(
+    reader: &mut impl Read,
+    buffer: &mut Vec<u8>,
+    total_bytes: u64,
+    progress: &ProgressReporter,
+) -> std::io::Result<()> {
+    let total = (total_bytes > 0).then_some(total_bytes);
+    let mut chunk = vec![0u8; TRANSFER_CHUNK_BYTES];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        buffer.extend_from_slice(&chunk[..read]);
+        progress.bytes(buffer.len() as u64, total);
+    }
+    Ok(())
+}
+
+/// Write all of `data`, reporting bytes transferred after each chunk
+pub(super) fn write_with_progress(
+    writer: &mut impl Write,
+    data: &[u8],
+    progress: &ProgressReporter,
+) -> std::io::Result<()> {
+    let total = data.len() as u64;
+    let mut written = 0;
+    for chunk in data.chunks(TRANSFER_CHUNK_BYTES) {
+        writer.write_all(chunk)?;
+        written += chunk.len() as u64;
+        progress.bytes(written, Some(total));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfers_report_bytes_per_chunk() {
+        let data = vec![7u8; TRANSFER_CHUNK_BYTES * 2 + 10];
+        let progress = ProgressReporter::default();
+
+        let mut buffer = Vec::new();
+        read_with_progress(
+            &mut data.as_slice(),
+            &mut buffer,
+            data.len() as u64,
+            &progress,
+        )
+        .unwrap();
+        assert_eq!(buffer, data);
+        let reported = progress.latest().unwrap();
+        assert_eq!(reported.bytes_transferred, Some(data.len() as u64));
+        assert_eq!(reported.fraction(), Some(1.0));
+
+        progress.reset();
+        let mut written = Vec::new();
+        write_with_progress(&mut written, &data, &progress).unwrap();
+        assert_eq!(written, data);
+        assert_eq!(
+            progress.latest().unwrap().total_bytes,
+            Some(data.len() as u64)
+        );
+    }
+}
@@ -0,0 +1,115 @@
+// SFTP step validation
+// Purpose: Reject SFTP steps the executor could never run when a job is saved or
+// imported, rather than on its first execution
+
+use crate::errors::ValidationError;
+use crate::models::{JobStep, JobType, SftpAuth, SftpOperation};
+
+/// Validate an SFTP step's fields; steps of other types pass
+///
+/// Host and credentials may be left out when the step names a connection profile,
+/// which fills them in at run time.
+pub fn validate_sftp_step(step: &JobStep) -> Result<(), ValidationError> {
+    let JobType::Sftp {
+        operation,
+        host,
+        port,
+        auth,
+        remote_path,
+        local_path,
+        ..
+    } = &step.step_type
+    else {
+        return Ok(());
+    };
+    let invalid = |field: &str, reason: &str| ValidationError::InvalidFieldValue {
+        field: format!("steps.{}.{}", step.id, field),
+        reason: reason.to_string(),
+    };
+
+    if remote_path.trim().is_empty() {
+        return Err(invalid("remote_path", "must not be empty"));
+    }
+    if *port == 0 {
+        return Err(invalid("port", "must be between 1 and 65535"));
+    }
+    if matches!(operation, SftpOperation::Upload)
+        && local_path
+            .as_deref()
+            .is_none_or(|path| path.trim().is_empty())
+    {
+        return Err(invalid("local_path", "is required for uploads"));
+    }
+    if step.connection.is_some() {
+        return Ok(());
+    }
+
+    if host.trim().is_empty() {
+        return Err(invalid("host", "is required without a connection"));
+    }
+    match auth {
+        None => Err(invalid("auth", "is required without a connection")),
+        Some(SftpAuth::Password { username, .. } | SftpAuth::SshKey { username, .. })
+            if username.trim().is_empty() =>
+        {
+            Err(invalid("auth.username", "must not be empty"))
+        }
+        Some(SftpAuth::SshKey {
+            private_key_path, ..
+        }) if private_key_path.trim().is_empty() => {
+            Err(invalid("auth.private_key_path", "must not be empty"))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(config: serde_json::Value) -> JobStep {
+        let mut step_type = serde_json::json!({
+            "type": "sftp",
+            "operation": "download",
+            "host": "sftp.example.com",
+            "auth": {"type": "password", "username": "etl", "password": "secret"},
+            "remote_path": "/outbound/report.csv",
+            "local_path": null,
+            "options": {
+                "wildcard_pattern": null,
+                "recursive": false,
+                "create_directories": true,
+                "verify_host_key": true
+            }
+        });
+        for (key, value) in config.as_object().unwrap() {
+            step_type[key] = value.clone();
+        }
+        serde_json::from_value(serde_json::json!({
+            "id": "fetch",
+            "name": "Fetch report",
+            "type": step_type,
+            "condition": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_sftp_step() {
+        assert!(validate_sftp_step(&step(serde_json::json!({}))).is_ok());
+        assert!(validate_sftp_step(&step(serde_json::json!({"remote_path": " "}))).is_err());
+        assert!(validate_sftp_step(&step(serde_json::json!({"port": 0}))).is_err());
+        assert!(validate_sftp_step(&step(serde_json::json!({"operation": "upload"}))).is_err());
+        assert!(validate_sftp_step(&step(serde_json::json!({
+            "operation": "upload",
+            "local_path": "exports/report.csv"
+        })))
+        .is_ok());
+
+        // Host and credentials can come from a connection profile
+        let mut anonymous = step(serde_json::json!({"host": "", "auth": null}));
+        assert!(validate_sftp_step(&anonymous).is_err());
+        anonymous.connection = Some("partner-sftp".to_string());
+        assert!(validate_sftp_step(&anonymous).is_ok());
+    }
+}
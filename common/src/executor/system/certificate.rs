@@ -0,0 +1,135 @@
+// Certificate expiry
+// Purpose: Read the notAfter date of PEM certificates. Only the DER fields in front of
+// the validity period are walked, so no X.509 parser is needed.
+
+use base64::Engine;
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_EXPLICIT_VERSION: u8 = 0xA0;
+const TAG_UTC_TIME: u8 = 0x17;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+
+/// Expiry of every certificate in a PEM bundle, in bundle order
+pub fn certificate_expiries(pem: &str) -> Result<Vec<DateTime<Utc>>, String> {
+    let mut expiries = Vec::new();
+    let mut rest = pem;
+
+    while let Some(start) = rest.find(PEM_BEGIN) {
+        let body = &rest[start + PEM_BEGIN.len()..];
+        let end = body
+            .find(PEM_END)
+            .ok_or_else(|| "Unterminated PEM certificate".to_string())?;
+        let encoded: String = body[..end].chars().filter(|c| !c.is_whitespace()).collect();
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|e| format!("Invalid PEM certificate: {}", e))?;
+        expiries.push(not_after(&der)?);
+        rest = &body[end + PEM_END.len()..];
+    }
+
+    if expiries.is_empty() {
+        return Err("No PEM certificate found".to_string());
+    }
+    Ok(expiries)
+}
+
+/// `tbsCertificate.validity.notAfter` of a DER certificate
+fn not_after(der: &[u8]) -> Result<DateTime<Utc>, String> {
+    let malformed = || "Malformed DER certificate".to_string();
+
+    let (certificate, _) = expect(der, TAG_SEQUENCE).ok_or_else(malformed)?;
+    let (tbs, _) = expect(certificate, TAG_SEQUENCE).ok_or_else(malformed)?;
+
+    // version is optional, then serialNumber, signature and issuer come first
+    let (tag, _, mut fields) = read_tlv(tbs).ok_or_else(malformed)?;
+    let skip = if tag == TAG_EXPLICIT_VERSION { 3 } else { 2 };
+    for _ in 0..skip {
+        fields = read_tlv(fields).ok_or_else(malformed)?.2;
+    }
+
+    let (validity, _) = expect(fields, TAG_SEQUENCE).ok_or_else(malformed)?;
+    let (_, _, validity) = read_tlv(validity).ok_or_else(malformed)?;
+    let (tag, time, _) = read_tlv(validity).ok_or_else(malformed)?;
+    let time = std::str::from_utf8(time).map_err(|_| malformed())?;
+
+    let time = match tag {
+        TAG_UTC_TIME => {
+            // RFC 5280: two-digit years from 50 are 19xx
+            let century = if time.get(..2).is_some_and(|yy| yy >= "50") {
+                "19"
+            } else {
+                "20"
+            };
+            format!("{}{}", century, time)
+        }
+        TAG_GENERALIZED_TIME => time.to_string(),
+        _ => return Err(malformed()),
+    };
+    NaiveDateTime::parse_from_str(&time, "%Y%m%d%H%M%SZ")
+        .map(|time| time.and_utc())
+        .map_err(|e| format!("Invalid certificate notAfter '{}': {}", time, e))
+}
+
+/// Content of the leading element if it has `tag`, and the bytes after it
+fn expect(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (found, content, rest) = read_tlv(input)?;
+    (found == tag).then_some((content, rest))
+}
+
+/// Tag, content and remaining bytes of the leading DER element
+fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, input) = input.split_first()?;
+    let (&first, mut input) = input.split_first()?;
+
+    let length = if first < 0x80 {
+        first as usize
+    } else {
+        let octets = (first & 0x7f) as usize;
+        if octets == 0 || octets > 4 || input.len() < octets {
+            return None;
+        }
+        let length = input[..octets]
+            .iter()
+            .fold(0usize, |length, &b| (length << 8) | b as usize);
+        input = &input[octets..];
+        length
+    };
+
+    (input.len() >= length).then(|| (tag, &input[..length], &input[length..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const CERTIFICATE: &str = "-----BEGIN CERTIFICATE-----
+MIIBfjCCASOgAwIBAgIUaQD5aMC2rrzxTqkUoVLTKe8nkCkwCgYIKoZIzj0EAwIw
+FDESMBAGA1UEAwwJY3Jvbi10ZXN0MB4XDTI1MDEwMTAwMDAwMFoXDTMwMDEwMTAw
+MDAwMFowFDESMBAGA1UEAwwJY3Jvbi10ZXN0MFkwEwYHKoZIzj0CAQYIKoZIzj0D
+AQcDQgAETWcaFllxWdlsdfiPelIpPOHu3JvdZF8FW/3aAcWMv7bsyU+dfUIwXSpy
+lofmofc898VtGrr7x3p2MPJnUox7FaNTMFEwHQYDVR0OBBYEFAScQ5Lcg+3SyCrt
+gqjpp9Gk+n1yMB8GA1UdIwQYMBaAFAScQ5Lcg+3SyCrtgqjpp9Gk+n1yMA8GA1Ud
+EwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDSQAwRgIhANamogwyyt1vo7e4KJ9xRRWU
+cvnBfCZOe9xTJ4e2dG9JAiEA51IzmbSnNuFZKdRz6Zb2VIdKkl9ED4wXSfm9LeUz
+NM8=
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_certificate_expiries_reads_not_after() {
+        let expected = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(certificate_expiries(CERTIFICATE).unwrap(), vec![expected]);
+
+        let bundle = format!("{}{}", CERTIFICATE, CERTIFICATE);
+        assert_eq!(certificate_expiries(&bundle).unwrap().len(), 2);
+
+        assert!(certificate_expiries("not a certificate").is_err());
+        let truncated = CERTIFICATE.replace("NM8=", "");
+        assert!(certificate_expiries(&truncated).is_err());
+    }
+}
@@ -0,0 +1,751 @@
+// System task executor
+// Purpose: Run the maintenance tasks of the built-in system jobs: DLQ report,
+// retention cleanup, PII purge, storage GC, expiry checks, the pipeline canary and
+// connection verification
+
+mod certificate;
+mod probe;
+
+pub use certificate::certificate_expiries;
+pub use probe::probe_step;
+
+use crate::connection_profile::ConnectionVerification;
+use crate::db::repositories::connection_profile::ConnectionProfileRepository;
+use crate::db::repositories::system_job::{PiiPurgeCandidate, SystemJobRepository};
+use crate::db::repositories::tls_material::TlsMaterialRepository;
+use crate::db::DbPool;
+use crate::erasure::{redact_context, redact_text, MIN_IDENTIFIER_LEN};
+use crate::errors::{ExecutionError, StorageError};
+use crate::executor::JobExecutor;
+use crate::import_export::encrypt_to_recipients;
+use crate::models::{
+    CredentialExpiry, CredentialSource, Job, JobContext, JobStep, JobType, StepOutput, SystemTask,
+};
+use crate::storage::StorageService;
+use crate::telemetry::AlertNotifier;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Executions deleted per statement by the retention cleanup
+const RETENTION_BATCH_SIZE: i64 = 1000;
+
+/// Executions whose contexts the PII purge loads per query
+const PII_PURGE_BATCH_SIZE: i64 = 100;
+
+/// Key of the marker a purged PII output is replaced with
+const PII_PURGED_AT_KEY: &str = "pii_purged_at";
+
+/// Directory under the storage root holding per-job and per-execution files
+const JOBS_DIR: &str = "jobs";
+
+/// Connection verification probes run per system job execution
+const VERIFICATION_BATCH_SIZE: i64 = 100;
+
+/// Time a single connection probe may take
+const PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// SystemTaskExecutor runs `system` steps
+pub struct SystemTaskExecutor {
+    repo: SystemJobRepository,
+    tls_material_repo: TlsMaterialRepository,
+    connection_profile_repo: ConnectionProfileRepository,
+    storage: Arc<dyn StorageService>,
+    file_base_path: PathBuf,
+    alert_notifier: Option<Arc<dyn AlertNotifier>>,
+}
+
+impl SystemTaskExecutor {
+    /// Create a new SystemTaskExecutor; `file_base_path` is the storage root the
+    /// storage GC sweeps
+    pub fn new(
+        db_pool: DbPool,
+        storage: Arc<dyn StorageService>,
+        file_base_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            repo: SystemJobRepository::new(db_pool.clone()),
+            tls_material_repo: TlsMaterialRepository::new(db_pool.clone()),
+            connection_profile_repo: ConnectionProfileRepository::new(db_pool),
+            storage,
+            file_base_path: file_base_path.into(),
+            alert_notifier: None,
+        }
+    }
+
+    /// Send expiry warnings through `alert_notifier`; without one they are only logged
+    pub fn with_alert_notifier(mut self, alert_notifier: Arc<dyn AlertNotifier>) -> Self {
+        self.alert_notifier = Some(alert_notifier);
+        self
+    }
+
+    /// Dead-lettered executions per job
+    async fn dlq_report(&self) -> Result<serde_json::Value, ExecutionError> {
+        let jobs = self
+            .repo
+            .dead_letter_summary()
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+        let total: i64 = jobs.iter().map(|job| job.count).sum();
+
+        if total > 0 {
+            warn!(
+                total = total,
+                jobs = jobs.len(),
+                "Dead-lettered executions waiting for attention"
+            );
+        }
+        Ok(serde_json::json!({ "total": total, "jobs": jobs }))
+    }
+
+    /// Delete finished executions and delivered webhooks past the retention period
+    async fn retention_cleanup(
+        &self,
+        retention_days: u32,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        if retention_days == 0 {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "retention_days must be greater than 0".to_string(),
+            ));
+        }
+        let cutoff = Utc::now() - Duration::days(retention_days as i64);
+
+        let executions_deleted = self
+            .repo
+            .delete_finished_executions(cutoff, RETENTION_BATCH_SIZE)
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+        let webhook_deliveries_deleted = self
+            .repo
+            .delete_delivered_webhooks(cutoff)
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+
+        info!(
+            executions_deleted = executions_deleted,
+            webhook_deliveries_deleted = webhook_deliveries_deleted,
+            "Retention cleanup finished"
+        );
+        Ok(serde_json::json!({
+            "cutoff": cutoff,
+            "executions_deleted": executions_deleted,
+            "webhook_deliveries_deleted": webhook_deliveries_deleted,
+        }))
+    }
+
+    /// Scrub, or encrypt to `recipients`, the PII-tagged step outputs of executions
+    /// finished before the PII retention period
+    async fn pii_purge(
+        &self,
+        retention_days: u32,
+        recipients: &[String],
+    ) -> Result<serde_json::Value, ExecutionError> {
+        if retention_days == 0 {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "retention_days must be greater than 0".to_string(),
+            ));
+        }
+        let cutoff = Utc::now() - Duration::days(retention_days as i64);
+        let action = if recipients.is_empty() {
+            "scrub"
+        } else {
+            "encrypt"
+        };
+        let mut executions_purged = 0;
+        let mut outputs_purged = 0;
+
+        loop {
+            let mut candidates = self
+                .repo
+                .pii_purge_candidates(cutoff, PII_PURGE_BATCH_SIZE)
+                .await
+                .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+
+            for candidate in &mut candidates {
+                let step_ids = self.purge_execution_pii(candidate, recipients).await?;
+                self.repo
+                    .record_pii_purge(candidate, &step_ids, action)
+                    .await
+                    .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+                if !step_ids.is_empty() {
+                    info!(
+                        execution_id = %candidate.execution_id,
+                        job_id = %candidate.job_id,
+                        steps = ?step_ids,
+                        action = action,
+                        "Audit log: PII step outputs purged"
+                    );
+                    executions_purged += 1;
+                    outputs_purged += step_ids.len();
+                }
+            }
+
+            if (candidates.len() as i64) < PII_PURGE_BATCH_SIZE {
+                break;
+            }
+        }
+
+        info!(
+            executions_purged = executions_purged,
+            outputs_purged = outputs_purged,
+            "PII purge finished"
+        );
+        Ok(serde_json::json!({
+            "cutoff": cutoff,
+            "action": action,
+            "executions_purged": executions_purged,
+            "outputs_purged": outputs_purged,
+        }))
+    }
+
+    /// Purge the PII-tagged outputs in one execution's context, and their values
+    /// from the candidate's result and error; returns the steps whose outputs were
+    /// purged
+    async fn purge_execution_pii(
+        &self,
+        candidate: &mut PiiPurgeCandidate,
+        recipients: &[String],
+    ) -> Result<Vec<String>, ExecutionError> {
+        let mut context = match self
+            .storage
+            .load_context(candidate.job_id, candidate.execution_id)
+            .await
+        {
+            Ok(context) => context,
+            // A context that was never stored holds no outputs
+            Err(StorageError::NotFound(_)) => return Ok(Vec::new()),
+            Err(e) => return Err(ExecutionError::StorageFailed(e.to_string())),
+        };
+
+        let purged = purge_pii_outputs(&mut context, recipients, Utc::now())?;
+        if purged.step_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.storage
+            .store_context(&context)
+            .await
+            .map_err(|e| ExecutionError::StorageFailed(e.to_string()))?;
+        for text in candidate
+            .result
+            .iter_mut()
+            .chain(candidate.error.iter_mut())
+        {
+            for value in &purged.values {
+                *text = redact_text(text, value).0;
+            }
+        }
+        Ok(purged.step_ids)
+    }
+
+    /// Remove `jobs/{job_id}` and `jobs/{job_id}/executions/{execution_id}` trees
+    /// whose job or execution no longer exists
+    async fn storage_gc(&self) -> Result<serde_json::Value, ExecutionError> {
+        let jobs_dir = self.file_base_path.join(JOBS_DIR);
+        let mut jobs_removed = 0;
+        let mut executions_removed = 0;
+
+        let job_dirs = uuid_dirs(&jobs_dir).await?;
+        let ids: Vec<Uuid> = job_dirs.iter().map(|(id, _)| *id).collect();
+        let existing = self
+            .repo
+            .existing_job_ids(&ids)
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+
+        for (job_id, job_dir) in job_dirs {
+            if !existing.contains(&job_id) {
+                remove_tree(&job_dir).await?;
+                jobs_removed += 1;
+                continue;
+            }
+
+            let execution_dirs = uuid_dirs(&job_dir.join("executions")).await?;
+            let ids: Vec<Uuid> = execution_dirs.iter().map(|(id, _)| *id).collect();
+            let existing: HashSet<Uuid> = self
+                .repo
+                .existing_execution_ids(&ids)
+                .await
+                .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+            for (execution_id, execution_dir) in execution_dirs {
+                if !existing.contains(&execution_id) {
+                    remove_tree(&execution_dir).await?;
+                    executions_removed += 1;
+                }
+            }
+        }
+
+        info!(
+            jobs_removed = jobs_removed,
+            executions_removed = executions_removed,
+            "Storage GC finished"
+        );
+        Ok(serde_json::json!({
+            "jobs_removed": jobs_removed,
+            "executions_removed": executions_removed,
+        }))
+    }
+
+    /// Warn about credentials expiring within `warn_days`
+    ///
+    /// Covered are certificate files, variables and TLS materials with a recorded
+    /// expiry, and sensitive variables that reach `secret_max_age_days` without being
+    /// rotated. Expired ones are included. Fails only when a certificate can't be read.
+    async fn expiry_check(
+        &self,
+        certificate_paths: &[String],
+        warn_days: u32,
+        secret_max_age_days: u32,
+    ) -> Result<serde_json::Value, ExecutionError> {
+        let now = Utc::now();
+        let warn_before = now + Duration::days(warn_days as i64);
+        let max_age = Duration::days(secret_max_age_days as i64);
+        let mut unreadable = Vec::new();
+
+        let mut expiring = self
+            .tls_material_repo
+            .expiring_credentials(warn_before)
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+
+        let stale_secrets = self
+            .repo
+            .stale_secrets(warn_before - max_age)
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+        expiring.extend(stale_secrets.into_iter().map(|secret| CredentialExpiry {
+            source: CredentialSource::VariableRotation,
+            id: None,
+            name: secret.name,
+            kind: None,
+            expires_at: secret.updated_at + max_age,
+        }));
+
+        let mut certificates = Vec::new();
+        for path in certificate_paths {
+            let expiries = match tokio::fs::read_to_string(path).await {
+                Ok(pem) => certificate_expiries(&pem),
+                Err(e) => Err(e.to_string()),
+            };
+            match expiries {
+                Ok(expiries) => {
+                    for not_after in expiries {
+                        if not_after < warn_before {
+                            expiring.push(CredentialExpiry {
+                                source: CredentialSource::CertificateFile,
+                                id: None,
+                                name: path.clone(),
+                                kind: None,
+                                expires_at: not_after,
+                            });
+                        }
+                        certificates.push(serde_json::json!({
+                            "path": path,
+                            "not_after": not_after,
+                            "days_left": (not_after - now).num_days(),
+                        }));
+                    }
+                }
+                Err(e) => unreadable.push(format!("certificate {} unreadable: {}", path, e)),
+            }
+        }
+        expiring.sort_by_key(|credential| credential.expires_at);
+
+        if !expiring.is_empty() {
+            warn!(
+                count = expiring.len(),
+                warn_days = warn_days,
+                "Credentials are about to expire"
+            );
+            if let Some(notifier) = &self.alert_notifier {
+                if let Err(e) = notifier.send_expiry_warning(&expiring, warn_days).await {
+                    warn!(error = %e, "Failed to send credential expiry warning");
+                }
+            }
+        }
+
+        if !unreadable.is_empty() {
+            return Err(ExecutionError::SystemCheckFailed(unreadable.join("; ")));
+        }
+        Ok(serde_json::json!({
+            "warn_days": warn_days,
+            "expiring": expiring,
+            "certificates": certificates,
+        }))
+    }
+
+    /// Write, read back and delete a file and query the database
+    async fn canary(&self, context: &JobContext) -> Result<serde_json::Value, ExecutionError> {
+        let started = std::time::Instant::now();
+
+        self.repo
+            .ping()
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+
+        let path = format!("system/canary/{}", context.execution_id);
+        let payload = context.execution_id.to_string();
+        self.storage
+            .store_file(&path, payload.as_bytes())
+            .await
+            .map_err(|e| ExecutionError::StorageFailed(e.to_string()))?;
+        let read_back = self
+            .storage
+            .load_file(&path)
+            .await
+            .map_err(|e| ExecutionError::StorageFailed(e.to_string()))?;
+        self.storage
+            .delete_file(&path)
+            .await
+            .map_err(|e| ExecutionError::StorageFailed(e.to_string()))?;
+        if read_back != payload.as_bytes() {
+            return Err(ExecutionError::SystemCheckFailed(
+                "canary file read back different content".to_string(),
+            ));
+        }
+
+        Ok(serde_json::json!({ "latency_ms": started.elapsed().as_millis() as u64 }))
+    }
+
+    /// Probe the jobs queued for verification after their connection profile changed
+    ///
+    /// Each job's steps using the profile get it applied, then connect and log in
+    /// without running. A job that would break is logged and recorded as failed.
+    async fn connection_verification(&self) -> Result<serde_json::Value, ExecutionError> {
+        let pending = self
+            .connection_profile_repo
+            .pending_verifications(VERIFICATION_BATCH_SIZE)
+            .await
+            .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+
+        let mut failed = Vec::new();
+        for verification in &pending {
+            let error = self.verify_job(verification).await.err();
+            if let Some(error) = &error {
+                warn!(
+                    connection = %verification.connection_name,
+                    job_id = %verification.job_id,
+                    job_name = %verification.job_name,
+                    error = %error,
+                    "Job would break with the changed connection profile"
+                );
+                failed.push(serde_json::json!({
+                    "connection": verification.connection_name,
+                    "job_id": verification.job_id,
+                    "job_name": verification.job_name,
+                    "error": error,
+                }));
+            }
+            self.connection_profile_repo
+                .record_verification(verification, error.as_deref())
+                .await
+                .map_err(|e| ExecutionError::DatabaseQueryFailed(e.to_string()))?;
+        }
+
+        info!(
+            checked = pending.len(),
+            failed = failed.len(),
+            "Connection verification finished"
+        );
+        Ok(serde_json::json!({
+            "checked": pending.len(),
+            "passed": pending.len() - failed.len(),
+            "failed": failed,
+        }))
+    }
+
+    /// Probe a job's steps using the verification's profile; the error is why the job
+    /// would break
+    async fn verify_job(&self, verification: &ConnectionVerification) -> Result<(), String> {
+        let profile = self
+            .connection_profile_repo
+            .find_by_name(&verification.connection_name)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| {
+                format!(
+                    "Connection profile '{}' no longer exists",
+                    verification.connection_name
+                )
+            })?;
+        let definition = self
+            .storage
+            .load_job_definition(verification.job_id)
+            .await
+            .map_err(|e| format!("Failed to load job definition: {}", e))?;
+        let mut job: Job = serde_json::from_str(&definition)
+            .map_err(|e| format!("Failed to parse job definition: {}", e))?;
+
+        for step in job
+            .steps
+            .iter_mut()
+            .filter(|step| step.connection.as_deref() == Some(profile.name.as_str()))
+        {
+            profile
+                .apply_to(step)
+                .map_err(|e| format!("Step '{}': {}", step.id, e))?;
+            probe_step(step, &profile, PROBE_TIMEOUT)
+                .await
+                .map_err(|e| format!("Step '{}': {}", step.id, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobExecutor for SystemTaskExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id, step_name = %step.name))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let task = match &step.step_type {
+            JobType::System { task } => task,
+            _ => {
+                return Err(ExecutionError::InvalidJobType(
+                    "Expected System job type".to_string(),
+                ))
+            }
+        };
+
+        let output = match task {
+            SystemTask::DlqReport => self.dlq_report().await?,
+            SystemTask::RetentionCleanup { retention_days } => {
+                self.retention_cleanup(*retention_days).await?
+            }
+            SystemTask::PiiPurge {
+                retention_days,
+                recipients,
+            } => self.pii_purge(*retention_days, recipients).await?,
+            SystemTask::StorageGc => self.storage_gc().await?,
+            SystemTask::ExpiryCheck {
+                certificate_paths,
+                warn_days,
+                secret_max_age_days,
+            } => {
+                self.expiry_check(certificate_paths, *warn_days, *secret_max_age_days)
+                    .await?
+            }
+            SystemTask::Canary => self.canary(context).await?,
+            SystemTask::ConnectionVerification => self.connection_verification().await?,
+        };
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output,
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// What purging an execution's PII-tagged outputs removed
+#[derive(Debug, Default)]
+struct PurgedPii {
+    /// Steps whose outputs were replaced
+    step_ids: Vec<String>,
+    /// Values of those outputs, longest first
+    values: Vec<String>,
+}
+
+/// Replace the outputs of the context's PII-tagged steps with a purge marker, or
+/// their age encryption when `recipients` are given, and redact their values
+/// wherever else the context holds them
+///
+/// Later steps' outputs, variables, state and errors can hold copies of a tagged
+/// output through references. Values shorter than an erasure identifier are left
+/// alone, so a short value like `ok` doesn't redact every word containing it.
+/// Outputs purged before are left alone.
+fn purge_pii_outputs(
+    context: &mut JobContext,
+    recipients: &[String],
+    now: DateTime<Utc>,
+) -> Result<PurgedPii, ExecutionError> {
+    let mut purged = PurgedPii::default();
+    for step_id in &context.pii_steps {
+        let Some(step) = context.steps.get_mut(step_id) else {
+            continue;
+        };
+        if step.output.get(PII_PURGED_AT_KEY).is_some() {
+            continue;
+        }
+
+        collect_values(&step.output, &mut purged.values);
+        step.output = if recipients.is_empty() {
+            serde_json::json!({ PII_PURGED_AT_KEY: now })
+        } else {
+            let plaintext = serde_json::to_vec(&step.output).map_err(|e| {
+                ExecutionError::SystemCheckFailed(format!("Failed to encode output: {}", e))
+            })?;
+            let encrypted = encrypt_to_recipients(&plaintext, recipients).map_err(|e| {
+                ExecutionError::InvalidJobDefinition(format!("Failed to encrypt PII: {}", e))
+            })?;
+            serde_json::json!({ PII_PURGED_AT_KEY: now, "encrypted": encrypted })
+        };
+        purged.step_ids.push(step_id.clone());
+    }
+
+    // Longer values first, so a value inside another doesn't split it
+    purged
+        .values
+        .sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    purged.values.dedup();
+    for value in &purged.values {
+        redact_context(context, value);
+    }
+    Ok(purged)
+}
+
+/// Strings and numbers in a JSON value long enough to redact by value
+fn collect_values(value: &serde_json::Value, values: &mut Vec<String>) {
+    let text = match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Number(number) => number.to_string(),
+        serde_json::Value::Array(items) => {
+            items.iter().for_each(|item| collect_values(item, values));
+            return;
+        }
+        serde_json::Value::Object(map) => {
+            map.values().for_each(|item| collect_values(item, values));
+            return;
+        }
+        _ => return,
+    };
+    if text.trim().len() >= MIN_IDENTIFIER_LEN {
+        values.push(text);
+    }
+}
+
+/// Subdirectories of `dir` named by a UUID; none when `dir` doesn't exist
+async fn uuid_dirs(dir: &Path) -> Result<Vec<(Uuid, PathBuf)>, ExecutionError> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(ExecutionError::StorageFailed(e.to_string())),
+    };
+
+    let mut dirs = Vec::new();
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|e| ExecutionError::StorageFailed(e.to_string()))?
+    {
+        let is_dir = entry
+            .file_type()
+            .await
+            .map(|file_type| file_type.is_dir())
+            .unwrap_or(false);
+        let id = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| Uuid::parse_str(name).ok());
+        if let (true, Some(id)) = (is_dir, id) {
+            dirs.push((id, entry.path()));
+        }
+    }
+    Ok(dirs)
+}
+
+async fn remove_tree(dir: &Path) -> Result<(), ExecutionError> {
+    tokio::fs::remove_dir_all(dir).await.map_err(|e| {
+        ExecutionError::StorageFailed(format!("Failed to remove {}: {}", dir.display(), e))
+    })?;
+    info!(path = %dir.display(), "Removed orphaned storage");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::StepOutput;
+
+    use crate::erasure::REDACTED;
+    use crate::models::OnFailure;
+
+    fn pii_context() -> JobContext {
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        for step_id in ["lookup", "notify"] {
+            let now = Utc::now();
+            context.set_step_output(
+                step_id.to_string(),
+                StepOutput {
+                    step_id: step_id.to_string(),
+                    status: "success".to_string(),
+                    output: serde_json::json!({ "email": "an@example.com" }),
+                    started_at: now,
+                    completed_at: now,
+                },
+            );
+        }
+        context.tag_pii("lookup");
+        context
+    }
+
+    #[test]
+    fn test_purge_pii_outputs_scrubs_tagged_steps_once() {
+        let mut context = pii_context();
+
+        let purged = purge_pii_outputs(&mut context, &[], Utc::now()).unwrap();
+        assert_eq!(purged.step_ids, vec!["lookup"]);
+        assert_eq!(purged.values, vec!["an@example.com"]);
+        assert!(context.steps["lookup"].output.get("email").is_none());
+        assert!(context.steps["lookup"]
+            .output
+            .get(PII_PURGED_AT_KEY)
+            .is_some());
+
+        assert!(purge_pii_outputs(&mut context, &[], Utc::now())
+            .unwrap()
+            .step_ids
+            .is_empty());
+    }
+
+    #[test]
+    fn test_purge_pii_outputs_redacts_copies_in_the_context() {
+        let mut context = pii_context();
+        context
+            .variables
+            .insert("customer".to_string(), serde_json::json!("an@example.com"));
+        context.record_failure_route(
+            "notify",
+            "Mailbox AN@example.com is full".to_string(),
+            OnFailure::ContinueNextStep,
+        );
+
+        purge_pii_outputs(&mut context, &[], Utc::now()).unwrap();
+        assert_eq!(
+            context.steps["notify"].output,
+            serde_json::json!({ "email": REDACTED })
+        );
+        assert_eq!(context.variables["customer"], serde_json::json!(REDACTED));
+        assert_eq!(
+            context.failure_routes[0].error,
+            format!("Mailbox {} is full", REDACTED)
+        );
+    }
+
+    #[test]
+    fn test_purge_pii_outputs_encrypts_to_recipients() {
+        let identity = age::x25519::Identity::generate();
+        let recipients = vec![identity.to_public().to_string()];
+        let mut context = pii_context();
+
+        purge_pii_outputs(&mut context, &recipients, Utc::now()).unwrap();
+        let encrypted = context.steps["lookup"].output["encrypted"]
+            .as_str()
+            .unwrap();
+        assert!(encrypted.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!encrypted.contains("example.com"));
+
+        let mut context = pii_context();
+        let recipients = vec!["not-a-key".to_string()];
+        assert!(purge_pii_outputs(&mut context, &recipients, Utc::now()).is_err());
+    }
+}
@@ -0,0 +1,133 @@
+// Connection probes
+// Purpose: Check that a step can still reach and log in to its endpoint without
+// running the step: an HTTP HEAD, a `SELECT 1` or an SFTP login
+
+use crate::connection_profile::{ConnectionEndpoint, ConnectionProfile};
+use crate::errors::ExecutionError;
+use crate::executor::database::{
+    MongoDBExecutor, MySQLExecutor, OracleExecutor, PostgreSQLExecutor, SqliteExecutor,
+};
+use crate::executor::http::HttpExecutor;
+use crate::executor::sftp::SftpConnection;
+use crate::models::{DatabaseType, JobStep, JobType, MongoOperation, QueryType};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// Probe a step that `profile` was applied to
+///
+/// An HTTP endpoint answering 401 or 403 rejected the credentials; any other answer
+/// means the step got through. A step url still holding `{{...}}` references can't be
+/// resolved outside a run, so the profile's base URL is probed instead.
+pub async fn probe_step(
+    step: &JobStep,
+    profile: &ConnectionProfile,
+    timeout: Duration,
+) -> Result<(), ExecutionError> {
+    match &step.step_type {
+        JobType::HttpRequest {
+            url, headers, auth, ..
+        }
+        | JobType::GraphQL {
+            url, headers, auth, ..
+        } => {
+            let url = match &profile.endpoint {
+                ConnectionEndpoint::Http { base_url, .. } if url.contains("{{") => base_url,
+                _ => url,
+            };
+            let executor = HttpExecutor::new(timeout.as_secs())?;
+            let mut request = executor.clients.client_for(url).await?.head(url);
+            for (name, value) in headers {
+                request = request.header(name, value);
+            }
+            let response = executor
+                .apply_auth(request, auth)
+                .await?
+                .send()
+                .await
+                .map_err(|e| {
+                    ExecutionError::HttpRequestFailed(format!("HTTP request failed: {}", e))
+                })?;
+            match response.status() {
+                status @ (StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                    Err(ExecutionError::HttpRequestFailed(format!(
+                        "{} rejected the credentials with HTTP {}",
+                        url, status
+                    )))
+                }
+                _ => Ok(()),
+            }
+        }
+        JobType::DatabaseQuery {
+            database_type,
+            connection_string,
+            query_type,
+            ..
+        } => {
+            match database_type {
+                DatabaseType::PostgreSQL => {
+                    PostgreSQLExecutor::new(timeout)
+                        .execute_query(connection_string, "SELECT 1", &QueryType::RawSql)
+                        .await?
+                }
+                DatabaseType::MySQL => {
+                    MySQLExecutor::new(timeout)
+                        .execute_query(connection_string, "SELECT 1", &QueryType::RawSql)
+                        .await?
+                }
+                DatabaseType::Oracle => {
+                    OracleExecutor::new(timeout)
+                        .execute_query(connection_string, "SELECT 1 FROM DUAL", &QueryType::RawSql)
+                        .await?
+                }
+                DatabaseType::Sqlite => {
+                    SqliteExecutor::new(timeout)
+                        .execute_query(connection_string, "SELECT 1", &QueryType::RawSql)
+                        .await?
+                }
+                // Reading one document proves the login and the step's read access
+                DatabaseType::MongoDB => {
+                    let QueryType::Mongo { collection, .. } = query_type else {
+                        return Err(ExecutionError::InvalidJobDefinition(
+                            "MongoDB steps need a mongo query_type".to_string(),
+                        ));
+                    };
+                    MongoDBExecutor::new(timeout)
+                        .execute_operation(
+                            connection_string,
+                            collection,
+                            MongoOperation::Find,
+                            r#"{"limit": 1}"#,
+                        )
+                        .await?
+                }
+            };
+            Ok(())
+        }
+        JobType::Sftp {
+            host,
+            port,
+            auth,
+            options,
+            ..
+        } => {
+            let auth = auth.clone().ok_or_else(|| {
+                ExecutionError::SftpAuthenticationFailed(
+                    "No SFTP credentials configured".to_string(),
+                )
+            })?;
+            let (host, port) = (host.clone(), *port);
+            let verify_host_key = options.verify_host_key;
+            let timeout_seconds = timeout.as_secs();
+            tokio::task::spawn_blocking(move || {
+                SftpConnection::connect(&host, port, &auth, verify_host_key, timeout_seconds)
+                    .map(|_| ())
+            })
+            .await
+            .map_err(|e| ExecutionError::SftpConnectionFailed(e.to_string()))?
+        }
+        _ => Err(ExecutionError::InvalidJobType(format!(
+            "Step '{}' has no connection to probe",
+            step.id
+        ))),
+    }
+}
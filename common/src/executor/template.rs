@@ -0,0 +1,94 @@
+// Step field templating shared by executors
+// Purpose: Fill in the text fields of a step (addresses, topics, keys, commands, ...)
+// from the job context before the executor uses them
+
+use crate::errors::ExecutionError;
+use crate::models::JobContext;
+use crate::substitution::VariableSubstitutor;
+use crate::worker::reference::ReferenceResolver;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Context variables as the strings `${VAR}` placeholders are replaced with
+pub(crate) fn string_variables(context: &JobContext) -> HashMap<String, String> {
+    context
+        .variables
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+/// Fill in `{{...}}` references, then `${VAR}` placeholders from the context variables
+///
+/// `variables` are the context's `string_variables`; `field` names the field in the
+/// error when either step fails.
+pub(crate) fn render_field(
+    template: &str,
+    context: &JobContext,
+    variables: &HashMap<String, String>,
+    field: &str,
+) -> Result<String, ExecutionError> {
+    static RESOLVER: OnceLock<ReferenceResolver> = OnceLock::new();
+    static SUBSTITUTOR: OnceLock<VariableSubstitutor> = OnceLock::new();
+
+    let resolved = RESOLVER
+        .get_or_init(ReferenceResolver::new)
+        .resolve(template, context)
+        .map_err(|e| {
+            ExecutionError::VariableResolutionFailed(format!(
+                "Failed to resolve references in {}: {}",
+                field, e
+            ))
+        })?;
+    SUBSTITUTOR
+        .get_or_init(VariableSubstitutor::default)
+        .substitute(&resolved, variables)
+        .map_err(|e| {
+            ExecutionError::VariableResolutionFailed(format!(
+                "Failed to substitute variables in {}: {}",
+                field, e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_string_variables() {
+        let mut context = JobContext::new(uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        context.variables.insert("branch".to_string(), json!("HN"));
+        context.variables.insert("day".to_string(), json!(16));
+
+        let variables = string_variables(&context);
+        let substitutor = VariableSubstitutor::default();
+        assert_eq!(
+            substitutor
+                .substitute("Report ${branch} day ${day}", &variables)
+                .unwrap(),
+            "Report HN day 16"
+        );
+    }
+
+    #[test]
+    fn test_render_field_names_the_field_on_error() {
+        let context = JobContext::new(uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let variables = string_variables(&context);
+
+        match render_field("${missing}", &context, &variables, "topic") {
+            Err(ExecutionError::VariableResolutionFailed(message)) => {
+                assert!(message.contains("topic"), "{}", message)
+            }
+            other => panic!("expected a resolution error, got {:?}", other),
+        }
+    }
+}
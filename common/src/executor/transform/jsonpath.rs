@@ -0,0 +1,647 @@
+// JSONPath evaluator (RFC 9535)
+// Supports the root `$`, member names (`.name`, `['name']`), indexes (negative from
+// the end), wildcards, slices (`[start:end:step]`), unions (`[0,2]`), descendants
+// (`..name`) and filters (`[?@.price < 10 && @.tag]`) with comparisons, `&&`,
+// `||`, `!` and parentheses. Function extensions are not supported.
+
+use serde_json::Value;
+
+/// A parsed JSONPath expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Wildcard,
+    Slice {
+        start: Option<i64>,
+        end: Option<i64>,
+        step: Option<i64>,
+    },
+    Filter(Box<Filter>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Or(Box<Filter>, Box<Filter>),
+    And(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    /// The query selects at least one node
+    Exists(Query),
+    Compare(Operand, CompareOp, Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Query(Query),
+    Literal(Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// Query inside a filter, from the root (`$`) or the current node (`@`)
+#[derive(Debug, Clone, PartialEq)]
+struct Query {
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    /// Parse an expression starting with `$`
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let mut parser = Parser::new(expression);
+        parser.skip_whitespace();
+        let query = parser.query()?;
+        if query.relative {
+            return Err("JSONPath must start with '$'".to_string());
+        }
+        parser.skip_whitespace();
+        if !parser.at_end() {
+            return Err(parser.error("unexpected character"));
+        }
+        Ok(Self {
+            segments: query.segments,
+        })
+    }
+
+    /// Nodes selected in `document`, in document order
+    pub fn query<'a>(&self, document: &'a Value) -> Vec<&'a Value> {
+        select(&self.segments, document, document)
+    }
+
+    /// Whether the expression selects at most one node (names and indexes only)
+    pub fn is_definite(&self) -> bool {
+        is_singular(&self.segments)
+    }
+
+    /// The selected node of a definite expression (`null` when missing), otherwise
+    /// an array of the selected nodes
+    pub fn evaluate(&self, document: &Value) -> Value {
+        let nodes = self.query(document);
+        if self.is_definite() {
+            nodes.first().map(|v| (*v).clone()).unwrap_or(Value::Null)
+        } else {
+            Value::Array(nodes.into_iter().cloned().collect())
+        }
+    }
+}
+
+fn is_singular(segments: &[Segment]) -> bool {
+    segments.iter().all(|segment| {
+        matches!(segment, Segment::Child(selectors)
+            if matches!(selectors.as_slice(), [Selector::Name(_)] | [Selector::Index(_)]))
+    })
+}
+
+fn select<'a>(segments: &[Segment], root: &'a Value, current: &'a Value) -> Vec<&'a Value> {
+    let mut nodes = vec![current];
+    for segment in segments {
+        let mut next = Vec::new();
+        for node in nodes {
+            match segment {
+                Segment::Child(selectors) => {
+                    for selector in selectors {
+                        apply(selector, node, root, &mut next);
+                    }
+                }
+                Segment::Descendant(selectors) => {
+                    for descendant in descendants(node) {
+                        for selector in selectors {
+                            apply(selector, descendant, root, &mut next);
+                        }
+                    }
+                }
+            }
+        }
+        nodes = next;
+    }
+    nodes
+}
+
+/// The node and everything below it, parents before children
+fn descendants(node: &Value) -> Vec<&Value> {
+    let mut nodes = vec![node];
+    let mut index = 0;
+    while index < nodes.len() {
+        let children: Vec<&Value> = match nodes[index] {
+            Value::Array(items) => items.iter().collect(),
+            Value::Object(fields) => fields.values().collect(),
+            _ => Vec::new(),
+        };
+        nodes.splice(index + 1..index + 1, children);
+        index += 1;
+    }
+    nodes
+}
+
+fn apply<'a>(selector: &Selector, node: &'a Value, root: &'a Value, out: &mut Vec<&'a Value>) {
+    match (selector, node) {
+        (Selector::Name(name), Value::Object(fields)) => out.extend(fields.get(name)),
+        (Selector::Index(index), Value::Array(items)) => {
+            let index = if *index < 0 {
+                items.len() as i64 + index
+            } else {
+                *index
+            };
+            if index >= 0 {
+                out.extend(items.get(index as usize));
+            }
+        }
+        (Selector::Wildcard, Value::Array(items)) => out.extend(items),
+        (Selector::Wildcard, Value::Object(fields)) => out.extend(fields.values()),
+        (Selector::Slice { start, end, step }, Value::Array(items)) => {
+            out.extend(slice_indexes(items.len() as i64, *start, *end, *step).map(|i| &items[i]))
+        }
+        (Selector::Filter(filter), Value::Array(items)) => {
+            out.extend(items.iter().filter(|item| test(filter, root, item)))
+        }
+        (Selector::Filter(filter), Value::Object(fields)) => {
+            out.extend(fields.values().filter(|item| test(filter, root, item)))
+        }
+        _ => {}
+    }
+}
+
+/// Array indexes selected by a slice, following RFC 9535 section 2.3.4.2
+fn slice_indexes(
+    len: i64,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+) -> impl Iterator<Item = usize> {
+    let step = step.unwrap_or(1);
+    let normalize = |i: i64| if i < 0 { len + i } else { i };
+    let (lower, upper, indexes): (i64, i64, Vec<i64>);
+    if step > 0 {
+        lower = start.map(normalize).unwrap_or(0).clamp(0, len);
+        upper = end.map(normalize).unwrap_or(len).clamp(0, len);
+        indexes = (lower..upper).step_by(step as usize).collect();
+    } else if step < 0 {
+        upper = start.map(normalize).unwrap_or(len - 1).clamp(-1, len - 1);
+        lower = end.map(normalize).unwrap_or(-1).clamp(-1, len - 1);
+        indexes = ((lower + 1)..=upper)
+            .rev()
+            .step_by(step.unsigned_abs() as usize)
+            .collect();
+    } else {
+        indexes = Vec::new();
+    }
+    indexes.into_iter().map(|i| i as usize)
+}
+
+fn test(filter: &Filter, root: &Value, current: &Value) -> bool {
+    match filter {
+        Filter::Or(left, right) => test(left, root, current) || test(right, root, current),
+        Filter::And(left, right) => test(left, root, current) && test(right, root, current),
+        Filter::Not(inner) => !test(inner, root, current),
+        Filter::Exists(query) => !run_query(query, root, current).is_empty(),
+        Filter::Compare(left, op, right) => {
+            let left = operand_value(left, root, current);
+            let right = operand_value(right, root, current);
+            compare(left, *op, right)
+        }
+    }
+}
+
+fn run_query<'a>(query: &Query, root: &'a Value, current: &'a Value) -> Vec<&'a Value> {
+    let start = if query.relative { current } else { root };
+    select(&query.segments, root, start)
+}
+
+/// Value of a comparison operand; queries that don't select exactly one node have none
+fn operand_value<'a>(
+    operand: &'a Operand,
+    root: &'a Value,
+    current: &'a Value,
+) -> Option<&'a Value> {
+    match operand {
+        Operand::Literal(value) => Some(value),
+        Operand::Query(query) => match run_query(query, root, current).as_slice() {
+            [value] => Some(value),
+            _ => None,
+        },
+    }
+}
+
+fn compare(left: Option<&Value>, op: CompareOp, right: Option<&Value>) -> bool {
+    match op {
+        CompareOp::Eq => equal(left, right),
+        CompareOp::Ne => !equal(left, right),
+        CompareOp::Lt => less(left, right),
+        CompareOp::Le => less(left, right) || equal(left, right),
+        CompareOp::Gt => less(right, left),
+        CompareOp::Ge => less(right, left) || equal(left, right),
+    }
+}
+
+fn equal(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (None, None) => true,
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.as_f64() == b.as_f64(),
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn less(left: Option<&Value>, right: Option<&Value>) -> bool {
+    match (left, right) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.as_f64() < b.as_f64(),
+        (Some(Value::String(a)), Some(Value::String(b))) => a < b,
+        _ => false,
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(expression: &str) -> Self {
+        Self {
+            chars: expression.chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let matches = s
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.peek_at(i) == Some(c));
+        if matches {
+            self.pos += s.chars().count();
+        }
+        matches
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected '{}'", c)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("Invalid JSONPath at position {}: {}", self.pos, message)
+    }
+
+    /// `$` or `@` followed by segments
+    fn query(&mut self) -> Result<Query, String> {
+        let relative = match self.peek() {
+            Some('$') => false,
+            Some('@') => true,
+            _ => return Err(self.error("expected '$' or '@'")),
+        };
+        self.pos += 1;
+
+        let mut segments = Vec::new();
+        loop {
+            if self.eat_str("..") {
+                let selectors = if self.peek() == Some('[') {
+                    self.bracket()?
+                } else {
+                    vec![self.dot_selector()?]
+                };
+                segments.push(Segment::Descendant(selectors));
+            } else if self.eat('.') {
+                segments.push(Segment::Child(vec![self.dot_selector()?]));
+            } else if self.peek() == Some('[') {
+                segments.push(Segment::Child(self.bracket()?));
+            } else {
+                break;
+            }
+        }
+        Ok(Query { relative, segments })
+    }
+
+    /// `*` or a member name after `.`
+    fn dot_selector(&mut self) -> Result<Selector, String> {
+        if self.eat('*') {
+            return Ok(Selector::Wildcard);
+        }
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-' || !c.is_ascii())
+        {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(self.error("expected a member name"));
+        }
+        Ok(Selector::Name(self.chars[start..self.pos].iter().collect()))
+    }
+
+    /// `[selector, ...]`
+    fn bracket(&mut self) -> Result<Vec<Selector>, String> {
+        self.expect('[')?;
+        let mut selectors = Vec::new();
+        loop {
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+            self.skip_whitespace();
+            if self.eat(']') {
+                return Ok(selectors);
+            }
+            self.expect(',')?;
+        }
+    }
+
+    fn selector(&mut self) -> Result<Selector, String> {
+        match self.peek() {
+            Some('\'' | '"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.pos += 1;
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.pos += 1;
+                // `?(...)` from older JSONPath dialects parses as a parenthesized filter
+                self.skip_whitespace();
+                Ok(Selector::Filter(Box::new(self.or()?)))
+            }
+            _ => {
+                let start = self.optional_integer()?;
+                self.skip_whitespace();
+                if !self.eat(':') {
+                    return start
+                        .map(Selector::Index)
+                        .ok_or_else(|| self.error("expected a selector"));
+                }
+                self.skip_whitespace();
+                let end = self.optional_integer()?;
+                self.skip_whitespace();
+                let step = if self.eat(':') {
+                    self.skip_whitespace();
+                    self.optional_integer()?
+                } else {
+                    None
+                };
+                Ok(Selector::Slice { start, end, step })
+            }
+        }
+    }
+
+    fn optional_integer(&mut self) -> Result<Option<i64>, String> {
+        let start = self.pos;
+        self.eat('-');
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Ok(None);
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        text.parse()
+            .map(Some)
+            .map_err(|_| self.error(&format!("invalid integer '{}'", text)))
+    }
+
+    /// Quoted string with JSON-style escapes
+    fn string(&mut self) -> Result<String, String> {
+        let quote = self.peek().ok_or_else(|| self.error("expected a string"))?;
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            let c = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            if c == quote {
+                return Ok(value);
+            }
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            let escaped = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            value.push(match escaped {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                    self.pos += 4;
+                    u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| self.error("invalid unicode escape"))?
+                }
+                other => other,
+            });
+        }
+    }
+
+    fn or(&mut self) -> Result<Filter, String> {
+        let mut filter = self.and()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat_str("||") {
+                return Ok(filter);
+            }
+            self.skip_whitespace();
+            filter = Filter::Or(Box::new(filter), Box::new(self.and()?));
+        }
+    }
+
+    fn and(&mut self) -> Result<Filter, String> {
+        let mut filter = self.unary()?;
+        loop {
+            self.skip_whitespace();
+            if !self.eat_str("&&") {
+                return Ok(filter);
+            }
+            self.skip_whitespace();
+            filter = Filter::And(Box::new(filter), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Filter, String> {
+        if self.peek() == Some('!') && self.peek_at(1) != Some('=') {
+            self.pos += 1;
+            self.skip_whitespace();
+            return Ok(Filter::Not(Box::new(self.unary()?)));
+        }
+        if self.eat('(') {
+            self.skip_whitespace();
+            let filter = self.or()?;
+            self.skip_whitespace();
+            self.expect(')')?;
+            return Ok(filter);
+        }
+
+        let left = self.operand()?;
+        self.skip_whitespace();
+        let Some(op) = self.compare_op() else {
+            return match left {
+                Operand::Query(query) => Ok(Filter::Exists(query)),
+                Operand::Literal(_) => Err(self.error("expected a comparison operator")),
+            };
+        };
+        self.skip_whitespace();
+        let right = self.operand()?;
+        Ok(Filter::Compare(left, op, right))
+    }
+
+    fn compare_op(&mut self) -> Option<CompareOp> {
+        [
+            ("==", CompareOp::Eq),
+            ("!=", CompareOp::Ne),
+            ("<=", CompareOp::Le),
+            (">=", CompareOp::Ge),
+            ("<", CompareOp::Lt),
+            (">", CompareOp::Gt),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat_str(token))
+        .map(|(_, op)| op)
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        match self.peek() {
+            Some('$' | '@') => Ok(Operand::Query(self.query()?)),
+            Some('\'' | '"') => Ok(Operand::Literal(Value::String(self.string()?))),
+            _ if self.eat_str("true") => Ok(Operand::Literal(Value::Bool(true))),
+            _ if self.eat_str("false") => Ok(Operand::Literal(Value::Bool(false))),
+            _ if self.eat_str("null") => Ok(Operand::Literal(Value::Null)),
+            _ => {
+                let start = self.pos;
+                while self
+                    .peek()
+                    .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+                {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                serde_json::from_str::<serde_json::Number>(&text)
+                    .map(|n| Operand::Literal(Value::Number(n)))
+                    .map_err(|_| self.error("expected a query or literal"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn store() -> Value {
+        json!({
+            "store": {
+                "book": [
+                    { "category": "reference", "author": "Nigel Rees", "price": 8.95 },
+                    { "category": "fiction", "author": "Evelyn Waugh", "price": 12.99 },
+                    { "category": "fiction", "author": "Herman Melville", "price": 8.99, "isbn": "0-553-21311-3" }
+                ],
+                "bicycle": { "color": "red", "price": 399 }
+            }
+        })
+    }
+
+    fn eval(expression: &str) -> Value {
+        JsonPath::parse(expression).unwrap().evaluate(&store())
+    }
+
+    #[test]
+    fn test_definite_paths_return_the_node() {
+        assert_eq!(eval("$.store.bicycle.color"), json!("red"));
+        assert_eq!(
+            eval("$['store']['book'][-1].author"),
+            json!("Herman Melville")
+        );
+        assert_eq!(eval("$.store.missing"), Value::Null);
+    }
+
+    #[test]
+    fn test_wildcards_slices_and_descendants() {
+        assert_eq!(
+            eval("$.store.book[*].author"),
+            json!(["Nigel Rees", "Evelyn Waugh", "Herman Melville"])
+        );
+        assert_eq!(eval("$.store.book[0:2].price"), json!([8.95, 12.99]));
+        assert_eq!(eval("$.store.book[::-2].price"), json!([8.99, 8.95]));
+        assert_eq!(
+            eval("$.store.book[0,2].category"),
+            json!(["reference", "fiction"])
+        );
+        assert_eq!(eval("$..isbn"), json!(["0-553-21311-3"]));
+        assert_eq!(eval("$..price").as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(
+            eval("$.store.book[?@.price < 10].author"),
+            json!(["Nigel Rees", "Herman Melville"])
+        );
+        assert_eq!(
+            eval("$.store.book[?(@.category == 'fiction' && !@.isbn)].author"),
+            json!(["Evelyn Waugh"])
+        );
+        assert_eq!(
+            eval("$.store.book[?@.price > $.store.bicycle.price || @.isbn].price"),
+            json!([8.99])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(JsonPath::parse("store.book").is_err());
+        assert!(JsonPath::parse("@.price").is_err());
+        assert!(JsonPath::parse("$.store[").is_err());
+        assert!(JsonPath::parse("$[?1]").is_err());
+    }
+}
@@ -0,0 +1,245 @@
+// Transformation executor implementation
+// Purpose: Reshape a document from an earlier step or a storage file without calling
+// out to an external service
+//
+// The document is a `{{...}}` reference into the context or a file in storage, in
+// JSON or XML. JSON given as a string (e.g. an HTTP response body) is parsed first;
+// XML is converted to JSON (see `xml`). The result of the JSONPath expression, or
+// the object of mapped expressions, is the step output, so later steps reference it
+// as `{{steps.<step id>...}}`.
+
+pub mod jsonpath;
+pub mod xml;
+
+use crate::errors::ExecutionError;
+use crate::executor::object_storage::internal_path;
+use crate::executor::JobExecutor;
+use crate::models::{
+    JobContext, JobStep, JobType, StepOutput, TransformInputFormat, Transformation,
+};
+use crate::storage::StorageService;
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use chrono::Utc;
+use jsonpath::JsonPath;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Largest document accepted from storage
+const MAX_DOCUMENT_BYTES: usize = 64 * 1024 * 1024;
+
+/// TransformExecutor runs transformation steps
+pub struct TransformExecutor {
+    storage_service: Arc<dyn StorageService>,
+    reference_resolver: Arc<ReferenceResolver>,
+}
+
+impl TransformExecutor {
+    /// Create a new TransformExecutor reading input files from the given storage
+    pub fn new(storage_service: Arc<dyn StorageService>) -> Self {
+        Self {
+            storage_service,
+            reference_resolver: Arc::new(ReferenceResolver::new()),
+        }
+    }
+
+    /// The document to transform, as JSON
+    async fn load_document(
+        &self,
+        input: Option<&String>,
+        input_path: Option<&String>,
+        format: TransformInputFormat,
+        context: &JobContext,
+    ) -> Result<Value, ExecutionError> {
+        let document = match (input, input_path) {
+            (Some(input), None) => self
+                .reference_resolver
+                .resolve_json(&Value::String(input.clone()), context)
+                .map_err(|e| {
+                    ExecutionError::VariableResolutionFailed(format!(
+                        "Failed to resolve references in input: {}",
+                        e
+                    ))
+                })?,
+            (None, Some(path)) => {
+                let path = self
+                    .reference_resolver
+                    .resolve(path, context)
+                    .map_err(|e| {
+                        ExecutionError::VariableResolutionFailed(format!(
+                            "Failed to resolve references in input_path: {}",
+                            e
+                        ))
+                    })?;
+                let path = internal_path(&path, context)?;
+                let data = self.storage_service.load_file(&path).await.map_err(|e| {
+                    ExecutionError::StorageFailed(format!(
+                        "Failed to load document '{}': {}",
+                        path, e
+                    ))
+                })?;
+                if data.len() > MAX_DOCUMENT_BYTES {
+                    return Err(ExecutionError::TransformFailed(format!(
+                        "Document '{}' of {} bytes exceeds the limit of {} bytes",
+                        path,
+                        data.len(),
+                        MAX_DOCUMENT_BYTES
+                    )));
+                }
+                let text = String::from_utf8(data).map_err(|_| {
+                    ExecutionError::TransformFailed(format!(
+                        "Document '{}' is not UTF-8 text",
+                        path
+                    ))
+                })?;
+                Value::String(text)
+            }
+            _ => {
+                return Err(ExecutionError::InvalidJobDefinition(
+                    "Transform steps need exactly one of input and input_path".to_string(),
+                ))
+            }
+        };
+
+        parse_document(document, format)
+    }
+}
+
+#[async_trait]
+impl JobExecutor for TransformExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Transform {
+            input,
+            input_path,
+            input_format,
+            transformation,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "TransformExecutor can only execute Transform job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for transform steps".to_string(),
+            ));
+        }
+
+        // Bad expressions fail before the document is loaded
+        let transformation = CompiledTransformation::compile(transformation)?;
+        let document = self
+            .load_document(input.as_ref(), input_path.as_ref(), *input_format, context)
+            .await?;
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: transformation.apply(&document),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// A transformation with its expressions parsed
+enum CompiledTransformation {
+    Single(JsonPath),
+    Mapping(Vec<(String, JsonPath)>),
+}
+
+impl CompiledTransformation {
+    fn compile(transformation: &Transformation) -> Result<Self, ExecutionError> {
+        let parse = |expression: &str| {
+            JsonPath::parse(expression).map_err(ExecutionError::InvalidJobDefinition)
+        };
+        match transformation {
+            Transformation::JsonPath { expression } => Ok(Self::Single(parse(expression)?)),
+            Transformation::JsonPathMapping { fields } => fields
+                .iter()
+                .map(|(field, expression)| Ok((field.clone(), parse(expression)?)))
+                .collect::<Result<_, ExecutionError>>()
+                .map(Self::Mapping),
+        }
+    }
+
+    fn apply(&self, document: &Value) -> Value {
+        match self {
+            Self::Single(path) => path.evaluate(document),
+            Self::Mapping(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(field, path)| (field.clone(), path.evaluate(document)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// JSON of the document: strings are parsed in the input format, JSON values from
+/// the context are used as they are
+fn parse_document(document: Value, format: TransformInputFormat) -> Result<Value, ExecutionError> {
+    match (format, document) {
+        (TransformInputFormat::Json, Value::String(text)) => serde_json::from_str(&text)
+            .map_err(|e| ExecutionError::TransformFailed(format!("Invalid JSON document: {}", e))),
+        (TransformInputFormat::Json, document) => Ok(document),
+        (TransformInputFormat::Xml, Value::String(text)) => {
+            xml::to_json(&text).map_err(ExecutionError::TransformFailed)
+        }
+        (TransformInputFormat::Xml, _) => Err(ExecutionError::TransformFailed(
+            "XML input must be a string".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_mapping_over_http_body() {
+        let transformation = CompiledTransformation::compile(&Transformation::JsonPathMapping {
+            fields: BTreeMap::from([
+                ("ids".to_string(), "$.data[*].id".to_string()),
+                ("first".to_string(), "$.data[0].name".to_string()),
+            ]),
+        })
+        .unwrap();
+        let document = parse_document(
+            json!(r#"{"data": [{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]}"#),
+            TransformInputFormat::Json,
+        )
+        .unwrap();
+
+        assert_eq!(
+            transformation.apply(&document),
+            json!({ "ids": [1, 2], "first": "a" })
+        );
+    }
+
+    #[test]
+    fn test_xml_document() {
+        let document = parse_document(
+            json!("<rates><rate code=\"USD\">25400</rate><rate code=\"EUR\">27100</rate></rates>"),
+            TransformInputFormat::Xml,
+        )
+        .unwrap();
+        let path = JsonPath::parse("$.rates.rate[?@['@code'] == 'EUR']['#text']").unwrap();
+
+        assert_eq!(path.evaluate(&document), json!(["27100"]));
+        assert!(parse_document(json!({"a": 1}), TransformInputFormat::Xml).is_err());
+        assert!(CompiledTransformation::compile(&Transformation::JsonPath {
+            expression: "data[0]".to_string()
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,91 @@
+// XML to JSON conversion for transformation steps
+//
+// The document becomes `{ "<root>": <element> }`. An element with neither attributes
+// nor child elements is its text; otherwise it is an object with `@name` for each
+// attribute, one field per child element name (an array when the name repeats) and
+// `#text` for its own text. Names are local names, without namespace prefixes.
+// Text is kept as strings.
+
+use serde_json::{Map, Value};
+
+/// Convert an XML document to JSON
+pub fn to_json(document: &str) -> Result<Value, String> {
+    let document =
+        roxmltree::Document::parse(document).map_err(|e| format!("Invalid XML: {}", e))?;
+    let root = document.root_element();
+
+    let mut wrapper = Map::new();
+    wrapper.insert(root.tag_name().name().to_string(), element(root));
+    Ok(Value::Object(wrapper))
+}
+
+fn element(node: roxmltree::Node) -> Value {
+    let text: String = node
+        .children()
+        .filter(|child| child.is_text())
+        .filter_map(|child| child.text())
+        .collect();
+    let text = text.trim();
+
+    let children: Vec<roxmltree::Node> = node.children().filter(|c| c.is_element()).collect();
+    if node.attributes().len() == 0 && children.is_empty() {
+        return Value::String(text.to_string());
+    }
+
+    let mut fields = Map::new();
+    for attribute in node.attributes() {
+        fields.insert(
+            format!("@{}", attribute.name()),
+            Value::String(attribute.value().to_string()),
+        );
+    }
+    for child in children {
+        let name = child.tag_name().name().to_string();
+        let value = element(child);
+        match fields.get_mut(&name) {
+            Some(Value::Array(items)) => items.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                fields.insert(name, value);
+            }
+        }
+    }
+    if !text.is_empty() {
+        fields.insert("#text".to_string(), Value::String(text.to_string()));
+    }
+    Value::Object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_json() {
+        let xml = r#"<?xml version="1.0"?>
+            <ns:orders xmlns:ns="urn:orders" date="2024-05-01">
+                <order id="1"><total>10.5</total></order>
+                <order id="2"><total>7</total><note>gấp</note></order>
+                <summary>2 orders</summary>
+            </ns:orders>"#;
+
+        assert_eq!(
+            to_json(xml).unwrap(),
+            json!({
+                "orders": {
+                    "@date": "2024-05-01",
+                    "order": [
+                        { "@id": "1", "total": "10.5" },
+                        { "@id": "2", "total": "7", "note": "gấp" }
+                    ],
+                    "summary": "2 orders"
+                }
+            })
+        );
+        assert!(to_json("<open>").is_err());
+    }
+}
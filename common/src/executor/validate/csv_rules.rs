@@ -0,0 +1,307 @@
+// CSV column rules
+// Rows are checked cell by cell against the rule of their column; violation paths
+// are `row <n>, column '<name>'` with rows numbered from 1 after the header
+
+use super::Violation;
+use crate::models::{CsvColumnRule, CsvValueType};
+use chrono::NaiveDate;
+use csv::ReaderBuilder;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Header and rows of CSV data, every cell as text
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Parse CSV text whose first record is the header
+    pub fn parse(text: &str, delimiter: char) -> Result<Self, String> {
+        if !delimiter.is_ascii() {
+            return Err(format!(
+                "Delimiter {:?} is not an ASCII character",
+                delimiter
+            ));
+        }
+        let mut reader = ReaderBuilder::new()
+            .delimiter(delimiter as u8)
+            .flexible(true)
+            .from_reader(text.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| format!("Failed to read CSV header: {}", e))?
+            .iter()
+            .map(str::to_string)
+            .collect();
+        let rows = reader
+            .records()
+            .map(|record| {
+                record
+                    .map(|r| r.iter().map(str::to_string).collect())
+                    .map_err(|e| format!("Failed to parse CSV record: {}", e))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { headers, rows })
+    }
+
+    /// Rows given as JSON objects, e.g. from a database query step; the header is
+    /// every key in order of first appearance
+    pub fn from_objects(rows: &[Value]) -> Result<Self, String> {
+        let mut headers: Vec<String> = Vec::new();
+        for (index, row) in rows.iter().enumerate() {
+            let Value::Object(fields) = row else {
+                return Err(format!("Row {} is not an object", index + 1));
+            };
+            for key in fields.keys() {
+                if !headers.contains(key) {
+                    headers.push(key.clone());
+                }
+            }
+        }
+        let rows = rows
+            .iter()
+            .map(|row| {
+                headers
+                    .iter()
+                    .map(|header| match row.get(header) {
+                        None | Some(Value::Null) => String::new(),
+                        Some(Value::String(s)) => s.clone(),
+                        Some(value) => value.to_string(),
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(Self { headers, rows })
+    }
+}
+
+/// A column rule with its pattern compiled
+struct CompiledRule<'a> {
+    rule: &'a CsvColumnRule,
+    pattern: Option<Regex>,
+}
+
+/// Every violation of the column rules in `table`
+pub fn validate(
+    table: &Table,
+    columns: &[CsvColumnRule],
+    strict_columns: bool,
+) -> Result<Vec<Violation>, String> {
+    let rules = columns
+        .iter()
+        .map(|rule| {
+            let pattern = rule
+                .pattern
+                .as_deref()
+                .map(|p| {
+                    // The whole value has to match, not a substring of it
+                    Regex::new(&format!("^(?:{})$", p)).map_err(|e| {
+                        format!("Invalid pattern '{}' for column '{}': {}", p, rule.name, e)
+                    })
+                })
+                .transpose()?;
+            Ok(CompiledRule { rule, pattern })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let mut violations = Vec::new();
+    let mut checked = Vec::new();
+    for compiled in &rules {
+        match table.headers.iter().position(|h| h == &compiled.rule.name) {
+            Some(index) => checked.push((index, compiled)),
+            None if compiled.rule.required => violations.push(Violation::new(
+                "header",
+                format!("missing required column '{}'", compiled.rule.name),
+            )),
+            None => {}
+        }
+    }
+    if strict_columns {
+        for header in &table.headers {
+            if !columns.iter().any(|rule| &rule.name == header) {
+                violations.push(Violation::new(
+                    "header",
+                    format!("unexpected column '{}'", header),
+                ));
+            }
+        }
+    }
+
+    let mut seen: Vec<HashSet<&str>> = vec![HashSet::new(); checked.len()];
+    for (row_index, row) in table.rows.iter().enumerate() {
+        if row.len() > table.headers.len() {
+            violations.push(Violation::new(
+                format!("row {}", row_index + 1),
+                format!(
+                    "has {} fields, the header has {}",
+                    row.len(),
+                    table.headers.len()
+                ),
+            ));
+        }
+        for ((column, compiled), seen) in checked.iter().zip(seen.iter_mut()) {
+            let value = row.get(*column).map(String::as_str).unwrap_or("");
+            let path = format!("row {}, column '{}'", row_index + 1, compiled.rule.name);
+            if let Some(message) = check_value(compiled, value) {
+                violations.push(Violation::new(path, message));
+            } else if compiled.rule.unique && !value.is_empty() && !seen.insert(value) {
+                violations.push(Violation::new(path, format!("duplicate value '{}'", value)));
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Why a value breaks its column's rule, if it does
+fn check_value(compiled: &CompiledRule, value: &str) -> Option<String> {
+    let rule = compiled.rule;
+    if value.is_empty() {
+        return rule.required.then(|| "value is required".to_string());
+    }
+
+    match rule.value_type {
+        Some(CsvValueType::Integer) => match value.parse::<i64>() {
+            Ok(n) => {
+                if let Some(message) = check_bounds(rule, n as f64) {
+                    return Some(message);
+                }
+            }
+            Err(_) => return Some(format!("'{}' is not an integer", value)),
+        },
+        Some(CsvValueType::Number) => match value.parse::<f64>() {
+            Ok(n) if n.is_finite() => {
+                if let Some(message) = check_bounds(rule, n) {
+                    return Some(message);
+                }
+            }
+            _ => return Some(format!("'{}' is not a number", value)),
+        },
+        Some(CsvValueType::Boolean) => {
+            if !matches!(value.to_ascii_lowercase().as_str(), "true" | "false") {
+                return Some(format!("'{}' is not a boolean", value));
+            }
+        }
+        Some(CsvValueType::Date) => {
+            let format = rule.date_format.as_deref().unwrap_or("%Y-%m-%d");
+            if NaiveDate::parse_from_str(value, format).is_err() {
+                return Some(format!("'{}' is not a date in format '{}'", value, format));
+            }
+        }
+        Some(CsvValueType::String) | None => {}
+    }
+
+    if let Some(max_length) = rule.max_length {
+        if value.chars().count() > max_length {
+            return Some(format!("is longer than {} characters", max_length));
+        }
+    }
+    if compiled
+        .pattern
+        .as_ref()
+        .is_some_and(|re| !re.is_match(value))
+    {
+        return Some(format!(
+            "'{}' does not match pattern '{}'",
+            value,
+            rule.pattern.as_deref().unwrap_or_default()
+        ));
+    }
+    if !rule.allowed_values.is_empty() && !rule.allowed_values.iter().any(|v| v == value) {
+        return Some(format!(
+            "'{}' is not one of {}",
+            value,
+            rule.allowed_values.join(", ")
+        ));
+    }
+    None
+}
+
+fn check_bounds(rule: &CsvColumnRule, n: f64) -> Option<String> {
+    if let Some(min) = rule.min.filter(|min| n < *min) {
+        return Some(format!("{} is less than {}", n, min));
+    }
+    if let Some(max) = rule.max.filter(|max| n > *max) {
+        return Some(format!("{} is greater than {}", n, max));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules() -> Vec<CsvColumnRule> {
+        vec![
+            CsvColumnRule {
+                name: "id".to_string(),
+                required: true,
+                unique: true,
+                pattern: Some("[A-Z]{3}[0-9]+".to_string()),
+                ..Default::default()
+            },
+            CsvColumnRule {
+                name: "amount".to_string(),
+                required: true,
+                value_type: Some(CsvValueType::Number),
+                min: Some(0.0),
+                ..Default::default()
+            },
+            CsvColumnRule {
+                name: "paid_on".to_string(),
+                value_type: Some(CsvValueType::Date),
+                date_format: Some("%d/%m/%Y".to_string()),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_partner_csv_violations() {
+        let table = Table::parse(
+            "id;amount;paid_on;note\n\
+             ABC1;10.5;01/02/2024;\n\
+             ABC1;-1;2024-02-01;\n\
+             x2;;;",
+            ';',
+        )
+        .unwrap();
+
+        let messages: Vec<String> = validate(&table, &rules(), true)
+            .unwrap()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                "header: unexpected column 'note'",
+                "row 2, column 'id': duplicate value 'ABC1'",
+                "row 2, column 'amount': -1 is less than 0",
+                "row 2, column 'paid_on': '2024-02-01' is not a date in format '%d/%m/%Y'",
+                "row 3, column 'id': 'x2' does not match pattern '[A-Z]{3}[0-9]+'",
+                "row 3, column 'amount': value is required",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rows_as_objects() {
+        let table = Table::from_objects(&[
+            json!({ "id": "ABC1", "amount": 3 }),
+            json!({ "id": "ABC2", "amount": null }),
+        ])
+        .unwrap();
+
+        let violations = validate(&table, &rules(), false).unwrap();
+        assert_eq!(violations.len(), 1);
+        assert_eq!(
+            violations[0].to_string(),
+            "row 2, column 'amount': value is required"
+        );
+        assert!(Table::from_objects(&[json!([1, 2])]).is_err());
+    }
+}
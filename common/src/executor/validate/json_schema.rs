@@ -0,0 +1,602 @@
+// JSON Schema validator
+// Covers the assertion keywords of draft 2020-12 and draft-07: type, enum, const,
+// numeric and string bounds, pattern, format (date, date-time, time, email, uuid,
+// ipv4), array and object keywords, allOf/anyOf/oneOf/not, if/then/else and `$ref`
+// to `#` or a JSON pointer within the schema (`#/$defs/...`, `#/definitions/...`).
+// Remote references and `$dynamicRef` are not supported.
+
+use super::Violation;
+use chrono::{DateTime, NaiveDate, NaiveTime};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Deepest chain of `$ref`s followed, so cyclic schemas terminate
+const MAX_REF_DEPTH: usize = 64;
+
+/// A schema with its regular expressions compiled
+pub struct JsonSchema {
+    root: Value,
+    patterns: HashMap<String, Regex>,
+}
+
+impl JsonSchema {
+    /// Check that the schema is usable and compile its patterns
+    pub fn compile(schema: &Value) -> Result<Self, String> {
+        if !matches!(schema, Value::Object(_) | Value::Bool(_)) {
+            return Err("JSON Schema must be an object or a boolean".to_string());
+        }
+        let mut patterns = HashMap::new();
+        collect_patterns(schema, &mut patterns)?;
+        Ok(Self {
+            root: schema.clone(),
+            patterns,
+        })
+    }
+
+    /// Every violation of the schema in `instance`
+    pub fn validate(&self, instance: &Value) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        self.check(&self.root, instance, "", 0, &mut violations);
+        violations
+    }
+
+    fn is_valid(&self, schema: &Value, instance: &Value, depth: usize) -> bool {
+        let mut violations = Vec::new();
+        self.check(schema, instance, "", depth, &mut violations);
+        violations.is_empty()
+    }
+
+    fn check(
+        &self,
+        schema: &Value,
+        instance: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<Violation>,
+    ) {
+        let schema = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                out.push(Violation::new(path, "is not allowed"));
+                return;
+            }
+            Value::Object(schema) => schema,
+            _ => return,
+        };
+        let mut fail = |message: String| out.push(Violation::new(path, message));
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve_ref(reference) {
+                Some(_) if depth >= MAX_REF_DEPTH => {
+                    fail(format!("$ref '{}' nests too deeply", reference))
+                }
+                Some(target) => self.check(target, instance, path, depth + 1, out),
+                None => fail(format!("$ref '{}' cannot be resolved", reference)),
+            }
+        }
+        let mut fail = |message: String| out.push(Violation::new(path, message));
+
+        if let Some(types) = schema.get("type") {
+            let allowed: Vec<&str> = match types {
+                Value::String(t) => vec![t.as_str()],
+                Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !allowed.is_empty() && !allowed.iter().any(|t| has_type(instance, t)) {
+                fail(format!(
+                    "must be of type {}, found {}",
+                    allowed.join(" or "),
+                    type_name(instance)
+                ));
+            }
+        }
+        if let Some(Value::Array(values)) = schema.get("enum") {
+            if !values.iter().any(|v| json_equal(v, instance)) {
+                fail(format!("must be one of {}", Value::Array(values.clone())));
+            }
+        }
+        if let Some(value) = schema.get("const") {
+            if !json_equal(value, instance) {
+                fail(format!("must equal {}", value));
+            }
+        }
+
+        match instance {
+            Value::Number(n) => {
+                self.check_number(schema, n.as_f64().unwrap_or(f64::NAN), &mut fail)
+            }
+            Value::String(s) => self.check_string(schema, s, &mut fail),
+            _ => {}
+        }
+        match instance {
+            Value::Array(items) => self.check_array(schema, items, path, depth, out),
+            Value::Object(fields) => self.check_object(schema, fields, path, depth, out),
+            _ => {}
+        }
+        self.check_combinators(schema, instance, path, depth, out);
+    }
+
+    fn check_number(&self, schema: &Map<String, Value>, n: f64, fail: &mut impl FnMut(String)) {
+        let bound = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+        if let Some(min) = bound("minimum").filter(|min| n < *min) {
+            fail(format!("must be >= {}", min));
+        }
+        if let Some(max) = bound("maximum").filter(|max| n > *max) {
+            fail(format!("must be <= {}", max));
+        }
+        if let Some(min) = bound("exclusiveMinimum").filter(|min| n <= *min) {
+            fail(format!("must be > {}", min));
+        }
+        if let Some(max) = bound("exclusiveMaximum").filter(|max| n >= *max) {
+            fail(format!("must be < {}", max));
+        }
+        if let Some(divisor) = bound("multipleOf").filter(|d| *d > 0.0) {
+            let quotient = n / divisor;
+            if (quotient - quotient.round()).abs() > 1e-9 {
+                fail(format!("must be a multiple of {}", divisor));
+            }
+        }
+    }
+
+    fn check_string(&self, schema: &Map<String, Value>, s: &str, fail: &mut impl FnMut(String)) {
+        let length = s.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                fail(format!("must be at least {} characters long", min));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                fail(format!("must be at most {} characters long", max));
+            }
+        }
+        if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+            if self.patterns.get(pattern).is_some_and(|re| !re.is_match(s)) {
+                fail(format!("must match pattern '{}'", pattern));
+            }
+        }
+        if let Some(format) = schema.get("format").and_then(Value::as_str) {
+            if !matches_format(format, s) {
+                fail(format!("must be a valid {}", format));
+            }
+        }
+    }
+
+    fn check_array(
+        &self,
+        schema: &Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        depth: usize,
+        out: &mut Vec<Violation>,
+    ) {
+        if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+            if (items.len() as u64) < min {
+                out.push(Violation::new(
+                    path,
+                    format!("must have at least {} items", min),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+            if items.len() as u64 > max {
+                out.push(Violation::new(
+                    path,
+                    format!("must have at most {} items", max),
+                ));
+            }
+        }
+        if schema.get("uniqueItems") == Some(&Value::Bool(true)) {
+            let duplicate = items
+                .iter()
+                .enumerate()
+                .any(|(i, a)| items[..i].iter().any(|b| json_equal(a, b)));
+            if duplicate {
+                out.push(Violation::new(path, "must not contain duplicate items"));
+            }
+        }
+
+        // `prefixItems` (2020-12) or an array of `items` (draft-07) checks by position,
+        // `items` (2020-12) or `additionalItems` (draft-07) the rest
+        let (prefix, rest) = match (schema.get("prefixItems"), schema.get("items")) {
+            (Some(Value::Array(prefix)), rest) => (prefix.as_slice(), rest),
+            (None, Some(Value::Array(prefix))) => {
+                (prefix.as_slice(), schema.get("additionalItems"))
+            }
+            (_, rest) => (&[][..], rest),
+        };
+        for (index, item) in items.iter().enumerate() {
+            let item_schema = prefix.get(index).or(rest);
+            if let Some(item_schema) = item_schema {
+                let item_path = format!("{}/{}", path, index);
+                self.check(item_schema, item, &item_path, depth, out);
+            }
+        }
+
+        if let Some(contains) = schema.get("contains") {
+            let matching = items
+                .iter()
+                .filter(|item| self.is_valid(contains, item, depth))
+                .count() as u64;
+            let min = schema
+                .get("minContains")
+                .and_then(Value::as_u64)
+                .unwrap_or(1);
+            if matching < min {
+                out.push(Violation::new(
+                    path,
+                    format!("must contain at least {} matching item(s)", min),
+                ));
+            }
+            if let Some(max) = schema.get("maxContains").and_then(Value::as_u64) {
+                if matching > max {
+                    out.push(Violation::new(
+                        path,
+                        format!("must contain at most {} matching item(s)", max),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_object(
+        &self,
+        schema: &Map<String, Value>,
+        fields: &Map<String, Value>,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<Violation>,
+    ) {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    out.push(Violation::new(
+                        path,
+                        format!("missing required property '{}'", name),
+                    ));
+                }
+            }
+        }
+        if let Some(min) = schema.get("minProperties").and_then(Value::as_u64) {
+            if (fields.len() as u64) < min {
+                out.push(Violation::new(
+                    path,
+                    format!("must have at least {} properties", min),
+                ));
+            }
+        }
+        if let Some(max) = schema.get("maxProperties").and_then(Value::as_u64) {
+            if fields.len() as u64 > max {
+                out.push(Violation::new(
+                    path,
+                    format!("must have at most {} properties", max),
+                ));
+            }
+        }
+        if let Some(Value::Object(dependent)) = schema.get("dependentRequired") {
+            for (name, required) in dependent {
+                if !fields.contains_key(name) {
+                    continue;
+                }
+                for other in required
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                {
+                    if !fields.contains_key(other) {
+                        out.push(Violation::new(
+                            path,
+                            format!("property '{}' requires property '{}'", name, other),
+                        ));
+                    }
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let pattern_properties = schema.get("patternProperties").and_then(Value::as_object);
+        for (name, value) in fields {
+            let property_path = format!("{}/{}", path, escape_pointer(name));
+            if let Some(names) = schema.get("propertyNames") {
+                if !self.is_valid(names, &Value::String(name.clone()), depth) {
+                    out.push(Violation::new(
+                        &property_path,
+                        "property name is not allowed",
+                    ));
+                }
+            }
+
+            let mut matched = false;
+            if let Some(property_schema) = properties.and_then(|p| p.get(name)) {
+                matched = true;
+                self.check(property_schema, value, &property_path, depth, out);
+            }
+            for (pattern, property_schema) in pattern_properties.into_iter().flatten() {
+                if self
+                    .patterns
+                    .get(pattern)
+                    .is_some_and(|re| re.is_match(name))
+                {
+                    matched = true;
+                    self.check(property_schema, value, &property_path, depth, out);
+                }
+            }
+            if matched {
+                continue;
+            }
+            match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => out.push(Violation::new(
+                    &property_path,
+                    "additional property is not allowed",
+                )),
+                Some(additional) => self.check(additional, value, &property_path, depth, out),
+                None => {}
+            }
+        }
+    }
+
+    fn check_combinators(
+        &self,
+        schema: &Map<String, Value>,
+        instance: &Value,
+        path: &str,
+        depth: usize,
+        out: &mut Vec<Violation>,
+    ) {
+        if let Some(Value::Array(schemas)) = schema.get("allOf") {
+            for sub_schema in schemas {
+                self.check(sub_schema, instance, path, depth, out);
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("anyOf") {
+            if !schemas.iter().any(|s| self.is_valid(s, instance, depth)) {
+                out.push(Violation::new(path, "must match at least one anyOf schema"));
+            }
+        }
+        if let Some(Value::Array(schemas)) = schema.get("oneOf") {
+            let matching = schemas
+                .iter()
+                .filter(|s| self.is_valid(s, instance, depth))
+                .count();
+            if matching != 1 {
+                out.push(Violation::new(
+                    path,
+                    format!("must match exactly one oneOf schema, matched {}", matching),
+                ));
+            }
+        }
+        if let Some(not) = schema.get("not") {
+            if self.is_valid(not, instance, depth) {
+                out.push(Violation::new(path, "must not match the 'not' schema"));
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let branch = if self.is_valid(condition, instance, depth) {
+                schema.get("then")
+            } else {
+                schema.get("else")
+            };
+            if let Some(branch) = branch {
+                self.check(branch, instance, path, depth, out);
+            }
+        }
+    }
+
+    /// Target of a `$ref` within this schema
+    fn resolve_ref(&self, reference: &str) -> Option<&Value> {
+        let pointer = reference.strip_prefix('#')?;
+        let pointer = percent_decode(pointer);
+        self.root.pointer(&pointer)
+    }
+}
+
+fn collect_patterns(schema: &Value, patterns: &mut HashMap<String, Regex>) -> Result<(), String> {
+    let compile = |pattern: &str, patterns: &mut HashMap<String, Regex>| {
+        let regex = Regex::new(pattern)
+            .map_err(|e| format!("Invalid pattern '{}' in schema: {}", pattern, e))?;
+        patterns.insert(pattern.to_string(), regex);
+        Ok::<_, String>(())
+    };
+    match schema {
+        Value::Object(fields) => {
+            for (keyword, value) in fields {
+                match (keyword.as_str(), value) {
+                    ("pattern", Value::String(pattern)) => compile(pattern, patterns)?,
+                    ("patternProperties", Value::Object(properties)) => {
+                        for (pattern, property_schema) in properties {
+                            compile(pattern, patterns)?;
+                            collect_patterns(property_schema, patterns)?;
+                        }
+                    }
+                    // Values of these are data, not schemas
+                    ("enum" | "const" | "examples" | "default", _) => {}
+                    _ => collect_patterns(value, patterns)?,
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                collect_patterns(item, patterns)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn has_type(instance: &Value, type_name: &str) -> bool {
+    match type_name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => instance
+            .as_f64()
+            .is_some_and(|n| instance.is_i64() || instance.is_u64() || n.fract() == 0.0),
+        _ => false,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Equality where 1 and 1.0 are the same number
+fn json_equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| json_equal(a, b))
+        }
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(key, value)| b.get(key).is_some_and(|other| json_equal(value, other)))
+        }
+        _ => a == b,
+    }
+}
+
+fn matches_format(format: &str, value: &str) -> bool {
+    match format {
+        "date" => NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok(),
+        "date-time" => DateTime::parse_from_rfc3339(value).is_ok(),
+        "time" => NaiveTime::parse_from_str(value.trim_end_matches('Z'), "%H:%M:%S%.f").is_ok(),
+        "email" => value.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty()
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !value.chars().any(char::is_whitespace)
+                && !domain.contains('@')
+        }),
+        "uuid" => uuid::Uuid::parse_str(value).is_ok(),
+        "ipv4" => value.parse::<std::net::Ipv4Addr>().is_ok(),
+        // Unknown formats are annotations only
+        _ => true,
+    }
+}
+
+/// Escape a property name for use in a JSON pointer
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+/// Decode `%XX` escapes of a `$ref` fragment
+fn percent_decode(fragment: &str) -> String {
+    let bytes = fragment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| fragment.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn violations(schema: Value, instance: Value) -> Vec<String> {
+        JsonSchema::compile(&schema)
+            .unwrap()
+            .validate(&instance)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_partner_file_violations() {
+        let schema = json!({
+            "type": "array",
+            "items": { "$ref": "#/$defs/invoice" },
+            "$defs": {
+                "invoice": {
+                    "type": "object",
+                    "required": ["id", "amount"],
+                    "additionalProperties": false,
+                    "properties": {
+                        "id": { "type": "string", "pattern": "^INV-[0-9]+$" },
+                        "amount": { "type": "number", "exclusiveMinimum": 0 },
+                        "issued": { "type": "string", "format": "date" },
+                        "currency": { "enum": ["VND", "USD"] }
+                    }
+                }
+            }
+        });
+        let instance = json!([
+            { "id": "INV-1", "amount": 10, "issued": "2024-05-01", "currency": "VND" },
+            { "id": "X-2", "amount": 0, "issued": "2024-13-01", "note": "?" },
+            { "amount": "12" }
+        ]);
+
+        // Property order depends on serde_json's `preserve_order` feature
+        let mut found = violations(schema, instance);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                "/1/amount: must be > 0",
+                "/1/id: must match pattern '^INV-[0-9]+$'",
+                "/1/issued: must be a valid date",
+                "/1/note: additional property is not allowed",
+                "/2/amount: must be of type number, found string",
+                "/2: missing required property 'id'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combinators_and_conditionals() {
+        let schema = json!({
+            "oneOf": [{ "type": "integer" }, { "type": "string" }],
+            "if": { "type": "integer" },
+            "then": { "minimum": 10 },
+            "not": { "const": 42 }
+        });
+
+        assert!(violations(schema.clone(), json!(12)).is_empty());
+        assert!(violations(schema.clone(), json!("a")).is_empty());
+        assert_eq!(
+            violations(schema.clone(), json!(5)),
+            vec!["/: must be >= 10"]
+        );
+        assert_eq!(
+            violations(schema, json!(42)),
+            vec!["/: must not match the 'not' schema"]
+        );
+    }
+
+    #[test]
+    fn test_compile_rejects_bad_schemas() {
+        assert!(JsonSchema::compile(&json!("string")).is_err());
+        assert!(JsonSchema::compile(&json!({ "pattern": "(" })).is_err());
+        assert!(JsonSchema::compile(&json!({ "enum": ["("] })).is_ok());
+    }
+}
@@ -0,0 +1,253 @@
+// Data validation executor implementation
+// Purpose: Stop bad partner files before later steps act on them
+//
+// The data is a `{{...}}` reference into the context or a file in storage. A JSON
+// Schema is checked against the JSON document; JSON given as a string (e.g. an HTTP
+// response body) is parsed first. CSV column rules are checked against CSV text, or
+// against rows given as JSON objects. Any violation fails the step, and with it the
+// execution, listing the first `max_violations` of them.
+
+pub mod csv_rules;
+pub mod json_schema;
+
+use crate::errors::ExecutionError;
+use crate::executor::object_storage::internal_path;
+use crate::executor::JobExecutor;
+use crate::models::{JobContext, JobStep, JobType, StepOutput, ValidationRules};
+use crate::storage::StorageService;
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use chrono::Utc;
+use csv_rules::Table;
+use json_schema::JsonSchema;
+use serde_json::{json, Value};
+use std::fmt;
+use std::sync::Arc;
+
+/// Largest file accepted from storage
+const MAX_DOCUMENT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Violations listed in the error when the step doesn't set `max_violations`
+const DEFAULT_MAX_VIOLATIONS: usize = 50;
+
+/// Where in the data a rule is broken, and how
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// JSON pointer of the value, or row and column of a CSV cell
+    pub path: String,
+    pub message: String,
+}
+
+impl Violation {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        let path = path.into();
+        Self {
+            path: if path.is_empty() {
+                "/".to_string()
+            } else {
+                path
+            },
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// ValidateExecutor runs data validation steps
+pub struct ValidateExecutor {
+    storage_service: Arc<dyn StorageService>,
+    reference_resolver: Arc<ReferenceResolver>,
+}
+
+impl ValidateExecutor {
+    /// Create a new ValidateExecutor reading input files from the given storage
+    pub fn new(storage_service: Arc<dyn StorageService>) -> Self {
+        Self {
+            storage_service,
+            reference_resolver: Arc::new(ReferenceResolver::new()),
+        }
+    }
+
+    /// The data to validate; files are returned as text
+    async fn load_data(
+        &self,
+        input: Option<&String>,
+        input_path: Option<&String>,
+        context: &JobContext,
+    ) -> Result<Value, ExecutionError> {
+        match (input, input_path) {
+            (Some(input), None) => self
+                .reference_resolver
+                .resolve_json(&Value::String(input.clone()), context)
+                .map_err(|e| {
+                    ExecutionError::VariableResolutionFailed(format!(
+                        "Failed to resolve references in input: {}",
+                        e
+                    ))
+                }),
+            (None, Some(path)) => {
+                let path = self
+                    .reference_resolver
+                    .resolve(path, context)
+                    .map_err(|e| {
+                        ExecutionError::VariableResolutionFailed(format!(
+                            "Failed to resolve references in input_path: {}",
+                            e
+                        ))
+                    })?;
+                let path = internal_path(&path, context)?;
+                let data = self.storage_service.load_file(&path).await.map_err(|e| {
+                    ExecutionError::StorageFailed(format!("Failed to load file '{}': {}", path, e))
+                })?;
+                if data.len() > MAX_DOCUMENT_BYTES {
+                    return Err(ExecutionError::DataValidationFailed(format!(
+                        "File '{}' of {} bytes exceeds the limit of {} bytes",
+                        path,
+                        data.len(),
+                        MAX_DOCUMENT_BYTES
+                    )));
+                }
+                String::from_utf8(data).map(Value::String).map_err(|_| {
+                    ExecutionError::DataValidationFailed(format!(
+                        "File '{}' is not UTF-8 text",
+                        path
+                    ))
+                })
+            }
+            _ => Err(ExecutionError::InvalidJobDefinition(
+                "Validate steps need exactly one of input and input_path".to_string(),
+            )),
+        }
+    }
+}
+
+#[async_trait]
+impl JobExecutor for ValidateExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Validate {
+            input,
+            input_path,
+            rules,
+            max_violations,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "ValidateExecutor can only execute Validate job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for validate steps".to_string(),
+            ));
+        }
+
+        let (violations, output) = match rules {
+            ValidationRules::JsonSchema { schema } => {
+                // A bad schema fails before the data is loaded
+                let schema =
+                    JsonSchema::compile(schema).map_err(ExecutionError::InvalidJobDefinition)?;
+                let data = self
+                    .load_data(input.as_ref(), input_path.as_ref(), context)
+                    .await?;
+                let document = parse_json(data)?;
+                (schema.validate(&document), json!({ "valid": true }))
+            }
+            ValidationRules::CsvColumns {
+                columns,
+                delimiter,
+                strict_columns,
+            } => {
+                let data = self
+                    .load_data(input.as_ref(), input_path.as_ref(), context)
+                    .await?;
+                let table = match data {
+                    Value::String(text) => Table::parse(&text, *delimiter),
+                    Value::Array(rows) => Table::from_objects(&rows),
+                    _ => Err("CSV data must be text or an array of row objects".to_string()),
+                }
+                .map_err(ExecutionError::DataValidationFailed)?;
+                let violations = csv_rules::validate(&table, columns, *strict_columns)
+                    .map_err(ExecutionError::InvalidJobDefinition)?;
+                let output = json!({
+                    "valid": true,
+                    "rows": table.rows.len(),
+                    "columns": table.headers,
+                });
+                (violations, output)
+            }
+        };
+
+        if !violations.is_empty() {
+            return Err(ExecutionError::DataValidationFailed(describe(
+                &violations,
+                max_violations.unwrap_or(DEFAULT_MAX_VIOLATIONS),
+            )));
+        }
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output,
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// JSON of the data: strings are parsed, JSON values from the context are used as
+/// they are
+fn parse_json(data: Value) -> Result<Value, ExecutionError> {
+    match data {
+        Value::String(text) => serde_json::from_str(&text).map_err(|e| {
+            ExecutionError::DataValidationFailed(format!("Invalid JSON document: {}", e))
+        }),
+        data => Ok(data),
+    }
+}
+
+/// "<count> violation(s): <path>: <message>; ..." listing at most `limit` of them
+fn describe(violations: &[Violation], limit: usize) -> String {
+    let listed: Vec<String> = violations
+        .iter()
+        .take(limit)
+        .map(ToString::to_string)
+        .collect();
+    let mut description = format!("{} violation(s): {}", violations.len(), listed.join("; "));
+    if violations.len() > listed.len() {
+        description.push_str(&format!(" (and {} more)", violations.len() - listed.len()));
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_truncates_violations() {
+        let violations = vec![
+            Violation::new("", "must be of type object, found array"),
+            Violation::new("/0/id", "missing"),
+            Violation::new("/1/id", "missing"),
+        ];
+
+        assert_eq!(
+            describe(&violations, 2),
+            "3 violation(s): /: must be of type object, found array; /0/id: missing (and 1 more)"
+        );
+        assert!(parse_json(Value::String("{".to_string())).is_err());
+    }
+}
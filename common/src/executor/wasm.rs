@@ -0,0 +1,401 @@
+// WebAssembly plugin executor implementation
+// Purpose: Run custom step logic shipped as a WebAssembly module, so users can add
+// step types without forking the crate
+//
+// Plugins run in the wasmi interpreter with no access to the host besides an optional
+// `env.log(ptr: i32, len: i32)` import. A module exports its `memory` and:
+// - `alloc(len: i32) -> i32`, returning a buffer the input is written to
+// - `run(ptr: i32, len: i32) -> i64`, taking the input
+//   `{"config": ..., "context": ..., "env": ...}` as JSON and returning the output JSON
+//   as `(ptr << 32) | len`; `env` holds the step's environment variables, whose
+//   secrets are masked in the plugin's logs and output
+// An output object with an `error` string fails the step. Runs are bounded by an
+// instruction budget (fuel) and a memory limit, both capped by the worker, and by a
+// wall-clock deadline. The interpreter has no epoch interruption, so a run past its
+// deadline fails the step while its thread burns the rest of its capped fuel.
+
+use crate::errors::ExecutionError;
+use crate::executor::object_storage::internal_path;
+use crate::executor::JobExecutor;
+use crate::models::{JobContext, JobStep, JobType, StepOutput};
+use crate::step_env::StepEnv;
+use crate::storage::StorageService;
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use wasmi::core::TrapCode;
+use wasmi::{Caller, Engine, Extern, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Instruction budget of a run unless the step sets one
+const DEFAULT_FUEL: u64 = 1_000_000_000;
+
+/// Memory limit of a run unless the step sets one
+const DEFAULT_MEMORY_MB: u64 = 64;
+
+/// Worker caps on fuel, memory and run time unless configured otherwise
+const DEFAULT_MAX_FUEL: u64 = 10_000_000_000;
+const DEFAULT_MAX_MEMORY_MB: u64 = 256;
+const DEFAULT_MAX_RUN: Duration = Duration::from_secs(300);
+
+/// Largest output a plugin may return
+const MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Log lines kept per run, and the length each is cut at
+const MAX_LOG_LINES: usize = 100;
+const MAX_LOG_LINE_BYTES: usize = 1024;
+
+/// Directory under the storage root for plugins shared by all jobs
+const PLUGINS_DIR: &str = "plugins/";
+
+/// Store data of a run
+struct PluginState {
+    limits: StoreLimits,
+    logs: Vec<String>,
+}
+
+/// Result of a finished run
+#[derive(Debug)]
+struct PluginRun {
+    output: serde_json::Value,
+    fuel_consumed: u64,
+    logs: Vec<String>,
+}
+
+/// WasmExecutor runs WebAssembly plugin steps
+pub struct WasmExecutor {
+    storage_service: Arc<dyn StorageService>,
+    reference_resolver: Arc<ReferenceResolver>,
+    max_fuel: u64,
+    max_memory_mb: u64,
+    max_run: Duration,
+}
+
+impl WasmExecutor {
+    /// Create a new WasmExecutor loading modules from the given storage
+    pub fn new(storage_service: Arc<dyn StorageService>) -> Self {
+        Self {
+            storage_service,
+            reference_resolver: Arc::new(ReferenceResolver::new()),
+            max_fuel: DEFAULT_MAX_FUEL,
+            max_memory_mb: DEFAULT_MAX_MEMORY_MB,
+            max_run: DEFAULT_MAX_RUN,
+        }
+    }
+
+    /// Cap the fuel and memory of every run, and fail runs taking longer than `max_run`
+    pub fn with_limits(mut self, max_fuel: u64, max_memory_mb: u64, max_run: Duration) -> Self {
+        self.max_fuel = max_fuel;
+        self.max_memory_mb = max_memory_mb;
+        self.max_run = max_run;
+        self
+    }
+}
+
+#[async_trait]
+impl JobExecutor for WasmExecutor {
+    #[tracing::instrument(skip(self, step, context), fields(step_id = %step.id))]
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        let started_at = Utc::now();
+
+        let JobType::Wasm {
+            module,
+            config,
+            fuel,
+            memory_mb,
+        } = &step.step_type
+        else {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "WasmExecutor can only execute Wasm job types".to_string(),
+            ));
+        };
+
+        if step.stream_output {
+            return Err(ExecutionError::InvalidJobDefinition(
+                "stream_output is not supported for wasm steps".to_string(),
+            ));
+        }
+
+        let module = self
+            .reference_resolver
+            .resolve(module, context)
+            .map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to resolve references in module: {}",
+                    e
+                ))
+            })?;
+        let module = module_path(&module, context)?;
+        let wasm = self.storage_service.load_file(&module).await.map_err(|e| {
+            ExecutionError::StorageFailed(format!("Failed to load module '{}': {}", module, e))
+        })?;
+
+        let input = plugin_input(config, context)?;
+        let fuel = fuel.unwrap_or(DEFAULT_FUEL).min(self.max_fuel);
+        let memory_mb = memory_mb
+            .unwrap_or(DEFAULT_MEMORY_MB)
+            .min(self.max_memory_mb);
+        let memory_bytes = memory_mb * 1024 * 1024;
+
+        tracing::info!(module = %module, fuel, memory_mb, "Running WebAssembly plugin");
+        // The interpreter is CPU-bound; fuel bounds how long it can run
+        let plugin = tokio::task::spawn_blocking(move || {
+            run_plugin(&wasm, &input, fuel, memory_bytes as usize)
+        });
+        let mut run = tokio::time::timeout(self.max_run, plugin)
+            .await
+            .map_err(|_| {
+                tracing::warn!(module = %module, max_run_seconds = self.max_run.as_secs(), "Plugin ran past its deadline");
+                ExecutionError::Timeout(self.max_run.as_secs())
+            })?
+            .map_err(|e| ExecutionError::WasmFailed(format!("Plugin task failed: {}", e)))??;
+        redact_run(&mut run, &context.env);
+
+        for line in &run.logs {
+            tracing::info!(module = %module, "Plugin: {}", line);
+        }
+        if let Some(error) = run.output.get("error").and_then(|e| e.as_str()) {
+            return Err(ExecutionError::WasmFailed(error.to_string()));
+        }
+
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: json!({
+                "module": module,
+                "result": run.output,
+                "fuel_consumed": run.fuel_consumed,
+                "logs": run.logs,
+            }),
+            started_at,
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// Storage path of a module: shared plugins are under `plugins/`, anything else
+/// must be a file of the job
+fn module_path(module: &str, context: &JobContext) -> Result<String, ExecutionError> {
+    let path = module.trim().trim_start_matches('/');
+    if path.starts_with(PLUGINS_DIR) {
+        if path.len() == PLUGINS_DIR.len() || path.split('/').any(|segment| segment == "..") {
+            return Err(ExecutionError::InvalidJobDefinition(format!(
+                "Invalid plugin path '{}'",
+                path
+            )));
+        }
+        return Ok(path.to_string());
+    }
+    internal_path(path, context)
+}
+
+/// JSON input of a run: the step's config, the job context and the step's environment
+fn plugin_input(
+    config: &serde_json::Value,
+    context: &JobContext,
+) -> Result<Vec<u8>, ExecutionError> {
+    serde_json::to_vec(&json!({
+        "config": config,
+        "context": context,
+        "env": context.env.to_map(),
+    }))
+    .map_err(|e| ExecutionError::WasmFailed(format!("Failed to encode input: {}", e)))
+}
+
+/// Mask the secrets of the step's environment in what the plugin returned
+fn redact_run(run: &mut PluginRun, env: &StepEnv) {
+    env.redact_json(&mut run.output);
+    for line in &mut run.logs {
+        *line = env.redact(line);
+    }
+}
+
+/// Instantiate the module and call its entrypoint with the input
+fn run_plugin(
+    wasm: &[u8],
+    input: &[u8],
+    fuel: u64,
+    memory_bytes: usize,
+) -> Result<PluginRun, ExecutionError> {
+    let mut config = wasmi::Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, wasm).map_err(|e| {
+        ExecutionError::InvalidJobDefinition(format!("Invalid WebAssembly module: {}", e))
+    })?;
+
+    let mut store = Store::new(
+        &engine,
+        PluginState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(memory_bytes)
+                .instances(1)
+                .build(),
+            logs: Vec::new(),
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+    store
+        .set_fuel(fuel)
+        .map_err(|e| ExecutionError::WasmFailed(e.to_string()))?;
+
+    let mut linker = Linker::<PluginState>::new(&engine);
+    linker
+        .func_wrap(
+            "env",
+            "log",
+            |mut caller: Caller<'_, PluginState>, ptr: i32, len: i32| {
+                if caller.data().logs.len() >= MAX_LOG_LINES {
+                    return;
+                }
+                let Some(memory) = caller.get_export("memory").and_then(Extern::into_memory) else {
+                    return;
+                };
+                let mut buffer = vec![0; (len as u32 as usize).min(MAX_LOG_LINE_BYTES)];
+                if memory
+                    .read(&caller, ptr as u32 as usize, &mut buffer)
+                    .is_ok()
+                {
+                    let line = String::from_utf8_lossy(&buffer).into_owned();
+                    caller.data_mut().logs.push(line);
+                }
+            },
+        )
+        .map_err(|e| ExecutionError::WasmFailed(e.to_string()))?;
+
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| plugin_error("instantiate module", e, fuel))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| ExecutionError::WasmFailed("Module exports no memory".to_string()))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|e| ExecutionError::WasmFailed(format!("Module entrypoint alloc: {}", e)))?;
+    let run = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "run")
+        .map_err(|e| ExecutionError::WasmFailed(format!("Module entrypoint run: {}", e)))?;
+
+    let len = i32::try_from(input.len())
+        .map_err(|_| ExecutionError::WasmFailed("Input is too large".to_string()))?;
+    let ptr = alloc
+        .call(&mut store, len)
+        .map_err(|e| plugin_error("allocate input", e, fuel))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input)
+        .map_err(|e| ExecutionError::WasmFailed(format!("Failed to write input: {}", e)))?;
+
+    let packed = run
+        .call(&mut store, (ptr, len))
+        .map_err(|e| plugin_error("run", e, fuel))? as u64;
+    let (output_ptr, output_len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    if output_len > MAX_OUTPUT_BYTES {
+        return Err(ExecutionError::WasmFailed(format!(
+            "Output of {} bytes exceeds the limit of {} bytes",
+            output_len, MAX_OUTPUT_BYTES
+        )));
+    }
+    let mut output = vec![0; output_len];
+    memory
+        .read(&store, output_ptr, &mut output)
+        .map_err(|e| ExecutionError::WasmFailed(format!("Failed to read output: {}", e)))?;
+    let output = serde_json::from_slice(&output)
+        .map_err(|e| ExecutionError::WasmFailed(format!("Output is not JSON: {}", e)))?;
+
+    let fuel_consumed = fuel - store.get_fuel().unwrap_or(0);
+    Ok(PluginRun {
+        output,
+        fuel_consumed,
+        logs: store.into_data().logs,
+    })
+}
+
+fn plugin_error(action: &str, e: wasmi::Error, fuel: u64) -> ExecutionError {
+    if e.as_trap_code() == Some(TrapCode::OutOfFuel) {
+        return ExecutionError::WasmFailed(format!(
+            "Plugin ran out of fuel ({} instructions)",
+            fuel
+        ));
+    }
+    ExecutionError::WasmFailed(format!("Failed to {}: {}", action, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    /// Echoes the input back; `log`s "hi"; loops forever if the input is "{}"
+    const ECHO: &str = r#"
+        (module
+          (import "env" "log" (func $log (param i32 i32)))
+          (memory (export "memory") 2)
+          (data (i32.const 0) "hi")
+          (func (export "alloc") (param i32) (result i32) i32.const 1024)
+          (func (export "run") (param $ptr i32) (param $len i32) (result i64)
+            (call $log (i32.const 0) (i32.const 2))
+            (if (i32.eq (local.get $len) (i32.const 2))
+              (then (loop $spin (br $spin))))
+            (i64.or
+              (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+              (i64.extend_i32_u (local.get $len)))))
+    "#;
+
+    #[test]
+    fn test_run_plugin() {
+        let wasm = wat::parse_str(ECHO).unwrap();
+
+        let run = run_plugin(&wasm, br#"{"config":{"a":1}}"#, 10_000, 1 << 20).unwrap();
+        assert_eq!(run.output, json!({"config": {"a": 1}}));
+        assert_eq!(run.logs, vec!["hi"]);
+        assert!(run.fuel_consumed > 0);
+
+        let error = run_plugin(&wasm, b"{}", 10_000, 1 << 20).unwrap_err();
+        assert!(error.to_string().contains("out of fuel"), "{}", error);
+
+        // Two pages don't fit in one page of memory
+        assert!(run_plugin(&wasm, b"{}", 10_000, 1 << 16).is_err());
+    }
+
+    #[test]
+    fn test_plugin_sees_env_with_secrets_masked() {
+        let wasm = wat::parse_str(ECHO).unwrap();
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context
+            .env
+            .add_secret("API_TOKEN".to_string(), "s3cr3t-token".to_string());
+
+        let input = plugin_input(&json!({}), &context).unwrap();
+        assert!(String::from_utf8_lossy(&input).contains("s3cr3t-token"));
+
+        let mut run = run_plugin(&wasm, &input, 100_000, 1 << 20).unwrap();
+        redact_run(&mut run, &context.env);
+        assert_eq!(run.output["env"], json!({"API_TOKEN": "***"}));
+    }
+
+    #[test]
+    fn test_module_path() {
+        let context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(
+            module_path("/plugins/csv.wasm", &context).unwrap(),
+            "plugins/csv.wasm"
+        );
+        assert!(module_path("plugins/../secrets.wasm", &context).is_err());
+        assert!(module_path("plugins/", &context).is_err());
+        assert_eq!(
+            module_path("plugin.wasm", &context).unwrap(),
+            format!(
+                "jobs/{}/executions/{}/plugin.wasm",
+                context.job_id, context.execution_id
+            )
+        );
+    }
+}
@@ -0,0 +1,436 @@
+// Active-passive multi-region failover
+// Purpose: Track whether this region is active or standby, gate scheduling and job
+// consumption on that role, and report per-component readiness so operators can tell
+// whether a standby region is able to take over
+
+use crate::config::FailoverConfig;
+use crate::db::DbPool;
+use crate::errors::{DatabaseError, StorageError, ValidationError};
+use chrono::{DateTime, Duration, Utc};
+use redis::aio::ConnectionLike;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use tokio::sync::watch;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+/// A component is considered gone after missing this many role checks
+const STALE_AFTER_CHECKS: i32 = 3;
+
+/// Readiness entries not refreshed for this long are dropped (restarted instances)
+const STATUS_RETENTION_HOURS: i64 = 1;
+
+/// Role of this region in an active-passive pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionRole {
+    /// Schedules and executes jobs
+    Active,
+    /// Consumes nothing, waiting to be promoted
+    Standby,
+}
+
+impl RegionRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegionRole::Active => "active",
+            RegionRole::Standby => "standby",
+        }
+    }
+
+    /// Role stored for the region, or the configured one when none was ever set
+    pub fn resolve(stored: Option<RegionRole>, config: &FailoverConfig) -> Self {
+        stored.unwrap_or(if config.standby {
+            RegionRole::Standby
+        } else {
+            RegionRole::Active
+        })
+    }
+}
+
+impl fmt::Display for RegionRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RegionRole {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "active" => Ok(RegionRole::Active),
+            "standby" => Ok(RegionRole::Standby),
+            other => Err(ValidationError::InvalidFieldValue {
+                field: "role".to_string(),
+                reason: format!("'{}' is not active or standby", other),
+            }),
+        }
+    }
+}
+
+/// Replication state of the database this region is connected to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReplicationStatus {
+    /// The database is a read-only replica that has not been promoted yet
+    pub in_recovery: bool,
+    /// The replica has replayed everything it received
+    pub caught_up: bool,
+    /// Time since the last replayed transaction; `None` on a primary
+    pub lag_seconds: Option<f64>,
+}
+
+impl ReplicationStatus {
+    /// Whether the data is fresh enough to take over from
+    pub fn is_ready(&self, max_lag_seconds: u64) -> bool {
+        !self.in_recovery
+            || self.caught_up
+            || self
+                .lag_seconds
+                .is_some_and(|lag| lag <= max_lag_seconds as f64)
+    }
+}
+
+/// Read the replication state of the region's database
+#[instrument(skip(pool))]
+pub async fn check_replication(pool: &DbPool) -> Result<ReplicationStatus, DatabaseError> {
+    let status = sqlx::query_as::<_, ReplicationStatus>(
+        r#"
+        SELECT
+            pg_is_in_recovery() AS in_recovery,
+            COALESCE(pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn(), false) AS caught_up,
+            EXTRACT(EPOCH FROM (now() - pg_last_xact_replay_timestamp()))::FLOAT8 AS lag_seconds
+        "#,
+    )
+    .fetch_one(pool.pool())
+    .await?;
+
+    Ok(status)
+}
+
+/// Readiness reported by one scheduler or worker process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    /// `scheduler` or `worker`
+    pub component: String,
+    pub instance: String,
+    /// Role the process is currently acting on
+    pub role: RegionRole,
+    pub ready: bool,
+    pub replication: Option<ReplicationStatus>,
+    pub error: Option<String>,
+    pub reported_at: DateTime<Utc>,
+}
+
+impl ComponentStatus {
+    /// Whether the process stopped reporting
+    pub fn is_stale(&self, now: DateTime<Utc>, check_interval_seconds: u64) -> bool {
+        now - self.reported_at
+            > Duration::seconds(check_interval_seconds as i64) * STALE_AFTER_CHECKS
+    }
+}
+
+/// Whether a region can run on its own: a live scheduler and worker, and every live
+/// component ready
+pub fn region_ready(
+    components: &[ComponentStatus],
+    now: DateTime<Utc>,
+    check_interval_seconds: u64,
+) -> bool {
+    let live: Vec<&ComponentStatus> = components
+        .iter()
+        .filter(|c| !c.is_stale(now, check_interval_seconds))
+        .collect();
+
+    ["scheduler", "worker"]
+        .iter()
+        .all(|component| live.iter().any(|c| c.component == *component))
+        && live.iter().all(|c| c.ready)
+}
+
+/// Region role and component readiness, kept in the region's own Redis
+pub struct RegionRoleStore<C> {
+    conn: C,
+    region: String,
+}
+
+impl<C> RegionRoleStore<C>
+where
+    C: ConnectionLike + Clone + Send + Sync,
+{
+    pub fn new(conn: C, region: impl Into<String>) -> Self {
+        Self {
+            conn,
+            region: region.into(),
+        }
+    }
+
+    fn role_key(&self) -> String {
+        format!("failover:{}:role", self.region)
+    }
+
+    fn components_key(&self) -> String {
+        format!("failover:{}:components", self.region)
+    }
+
+    /// Role set through promotion or demotion, if any
+    pub async fn role(&self) -> Result<Option<RegionRole>, StorageError> {
+        let mut conn = self.conn.clone();
+        let role: Option<String> = conn
+            .get(self.role_key())
+            .await
+            .map_err(|e| StorageError::RedisError(e.to_string()))?;
+
+        role.map(|role| {
+            role.parse()
+                .map_err(|e: ValidationError| StorageError::InvalidJson(e.to_string()))
+        })
+        .transpose()
+    }
+
+    /// Switch the region's role; components pick it up on their next role check
+    #[instrument(skip(self), fields(region = %self.region))]
+    pub async fn set_role(&self, role: RegionRole) -> Result<(), StorageError> {
+        let mut conn = self.conn.clone();
+        conn.set::<_, _, ()>(self.role_key(), role.as_str())
+            .await
+            .map_err(|e| StorageError::RedisError(e.to_string()))
+    }
+
+    /// Publish the readiness of one process
+    pub async fn report(&self, status: &ComponentStatus) -> Result<(), StorageError> {
+        let value =
+            serde_json::to_string(status).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        let mut conn = self.conn.clone();
+        conn.hset::<_, _, _, ()>(
+            self.components_key(),
+            format!("{}:{}", status.component, status.instance),
+            value,
+        )
+        .await
+        .map_err(|e| StorageError::RedisError(e.to_string()))
+    }
+
+    /// Readiness of every process that reported recently, oldest entries pruned
+    pub async fn components(&self) -> Result<Vec<ComponentStatus>, StorageError> {
+        let mut conn = self.conn.clone();
+        let entries: HashMap<String, String> = conn
+            .hgetall(self.components_key())
+            .await
+            .map_err(|e| StorageError::RedisError(e.to_string()))?;
+
+        let cutoff = Utc::now() - Duration::hours(STATUS_RETENTION_HOURS);
+        let mut components = Vec::new();
+        let mut expired = Vec::new();
+        for (field, value) in entries {
+            match serde_json::from_str::<ComponentStatus>(&value) {
+                Ok(status) if status.reported_at >= cutoff => components.push(status),
+                _ => expired.push(field),
+            }
+        }
+        if !expired.is_empty() {
+            conn.hdel::<_, _, ()>(self.components_key(), expired)
+                .await
+                .map_err(|e| StorageError::RedisError(e.to_string()))?;
+        }
+
+        components.sort_by(|a, b| (&a.component, &a.instance).cmp(&(&b.component, &b.instance)));
+        Ok(components)
+    }
+}
+
+/// Keeps a process's view of the region role current and reports its readiness
+pub struct RoleWatcher<C> {
+    store: RegionRoleStore<C>,
+    db_pool: DbPool,
+    config: FailoverConfig,
+    component: &'static str,
+    instance: String,
+}
+
+impl<C> RoleWatcher<C>
+where
+    C: ConnectionLike + Clone + Send + Sync + 'static,
+{
+    pub fn new(conn: C, db_pool: DbPool, config: FailoverConfig, component: &'static str) -> Self {
+        let hostname = std::env::var("HOSTNAME").unwrap_or_else(|_| Uuid::new_v4().to_string());
+        Self {
+            store: RegionRoleStore::new(conn, config.region.clone()),
+            db_pool,
+            component,
+            instance: format!("{}-{}", hostname, std::process::id()),
+            config,
+        }
+    }
+
+    /// Read the current role and keep re-checking it in the background
+    ///
+    /// Falls back to the configured role when Redis can't be read at startup.
+    pub async fn spawn(self) -> watch::Receiver<RegionRole> {
+        let role = match self.store.role().await {
+            Ok(stored) => RegionRole::resolve(stored, &self.config),
+            Err(e) => {
+                warn!(error = %e, "Failed to read region role, using the configured one");
+                RegionRole::resolve(None, &self.config)
+            }
+        };
+        info!(
+            region = %self.config.region,
+            component = self.component,
+            role = %role,
+            "Region role loaded"
+        );
+
+        let (role_tx, role_rx) = watch::channel(role);
+        tokio::spawn(async move { self.run(role_tx).await });
+        role_rx
+    }
+
+    async fn run(self, role_tx: watch::Sender<RegionRole>) {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+            self.config.role_check_interval_seconds,
+        ));
+
+        loop {
+            interval.tick().await;
+
+            match self.store.role().await {
+                Ok(stored) => {
+                    let role = RegionRole::resolve(stored, &self.config);
+                    if *role_tx.borrow() != role {
+                        info!(
+                            region = %self.config.region,
+                            component = self.component,
+                            role = %role,
+                            "Region role changed"
+                        );
+                        let _ = role_tx.send(role);
+                    }
+                }
+                Err(e) => warn!(error = %e, "Failed to read region role, keeping the current one"),
+            }
+
+            let role = *role_tx.borrow();
+            let status = self.status(role).await;
+            if let Err(e) = self.store.report(&status).await {
+                warn!(error = %e, "Failed to report component readiness");
+            }
+        }
+    }
+
+    async fn status(&self, role: RegionRole) -> ComponentStatus {
+        let (replication, error) = match check_replication(&self.db_pool).await {
+            Ok(replication) => (Some(replication), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let ready = replication
+            .as_ref()
+            .is_some_and(|r| r.is_ready(self.config.max_replication_lag_seconds));
+
+        ComponentStatus {
+            component: self.component.to_string(),
+            instance: self.instance.clone(),
+            role,
+            ready,
+            replication,
+            error,
+            reported_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(name: &str, ready: bool, reported_at: DateTime<Utc>) -> ComponentStatus {
+        ComponentStatus {
+            component: name.to_string(),
+            instance: format!("{}-1", name),
+            role: RegionRole::Standby,
+            ready,
+            replication: None,
+            error: None,
+            reported_at,
+        }
+    }
+
+    #[test]
+    fn test_stored_role_overrides_configured_one() {
+        let standby = FailoverConfig {
+            standby: true,
+            ..FailoverConfig::default()
+        };
+
+        assert_eq!(RegionRole::resolve(None, &standby), RegionRole::Standby);
+        assert_eq!(
+            RegionRole::resolve(None, &FailoverConfig::default()),
+            RegionRole::Active
+        );
+        assert_eq!(
+            RegionRole::resolve(Some(RegionRole::Active), &standby),
+            RegionRole::Active
+        );
+        assert_eq!(
+            "standby".parse::<RegionRole>().unwrap(),
+            RegionRole::Standby
+        );
+        assert!("primary".parse::<RegionRole>().is_err());
+    }
+
+    #[test]
+    fn test_replica_ready_when_caught_up_or_within_lag() {
+        let replica = |caught_up, lag_seconds| ReplicationStatus {
+            in_recovery: true,
+            caught_up,
+            lag_seconds,
+        };
+
+        assert!(replica(true, Some(600.0)).is_ready(60));
+        assert!(replica(false, Some(30.0)).is_ready(60));
+        assert!(!replica(false, Some(120.0)).is_ready(60));
+        assert!(!replica(false, None).is_ready(60));
+
+        let primary = ReplicationStatus {
+            in_recovery: false,
+            caught_up: false,
+            lag_seconds: None,
+        };
+        assert!(primary.is_ready(60));
+    }
+
+    #[test]
+    fn test_region_ready_needs_live_scheduler_and_worker() {
+        let now = Utc::now();
+        let stale = now - Duration::seconds(60);
+
+        assert!(region_ready(
+            &[
+                component("scheduler", true, now),
+                component("worker", true, now),
+                component("worker", false, stale),
+            ],
+            now,
+            5
+        ));
+        assert!(!region_ready(
+            &[
+                component("scheduler", true, now),
+                component("worker", false, now)
+            ],
+            now,
+            5
+        ));
+        assert!(!region_ready(
+            &[
+                component("scheduler", true, now),
+                component("worker", true, stale)
+            ],
+            now,
+            5
+        ));
+    }
+}
@@ -0,0 +1,208 @@
+// Fault injection for chaos testing
+// Purpose: Inject step latency, step failures, lock acquisition failures and NATS
+// redeliveries at configurable rates to verify retry and idempotency behavior
+
+use crate::config::FaultInjectionConfig;
+use crate::errors::{ExecutionError, StorageError};
+use crate::executor::JobExecutor;
+use crate::lock::{DistributedLock, LockGuard};
+use crate::models::{JobContext, JobStep, StepOutput};
+use crate::telemetry::record_injected_fault;
+use async_trait::async_trait;
+use rand::Rng;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Decides which operations get a fault, based on the configured rates
+#[derive(Debug, Clone)]
+pub struct FaultInjector {
+    config: FaultInjectionConfig,
+}
+
+impl FaultInjector {
+    pub fn new(config: FaultInjectionConfig) -> Self {
+        Self { config }
+    }
+
+    /// Build an injector when fault injection is enabled in config
+    pub fn from_config(config: &FaultInjectionConfig) -> Option<Arc<Self>> {
+        if !config.enabled {
+            return None;
+        }
+
+        warn!(
+            step_latency_rate = config.step_latency_rate,
+            step_latency_max_ms = config.step_latency_max_ms,
+            step_failure_rate = config.step_failure_rate,
+            lock_failure_rate = config.lock_failure_rate,
+            redelivery_rate = config.redelivery_rate,
+            "Fault injection is ENABLED - do not run this configuration in production"
+        );
+        Some(Arc::new(Self::new(config.clone())))
+    }
+
+    fn roll(rate: f64) -> bool {
+        rate > 0.0 && rand::thread_rng().gen_bool(rate.min(1.0))
+    }
+
+    /// Artificial latency to add before a step runs, if any
+    pub fn step_latency(&self) -> Option<Duration> {
+        if self.config.step_latency_max_ms == 0 || !Self::roll(self.config.step_latency_rate) {
+            return None;
+        }
+        let millis = rand::thread_rng().gen_range(1..=self.config.step_latency_max_ms);
+        record_injected_fault("step_latency");
+        Some(Duration::from_millis(millis))
+    }
+
+    /// Whether the next step attempt should fail
+    pub fn should_fail_step(&self) -> bool {
+        let fail = Self::roll(self.config.step_failure_rate);
+        if fail {
+            record_injected_fault("step_failure");
+        }
+        fail
+    }
+
+    /// Whether the next lock acquisition should fail
+    pub fn should_fail_lock(&self) -> bool {
+        let fail = Self::roll(self.config.lock_failure_rate);
+        if fail {
+            record_injected_fault("lock_failure");
+        }
+        fail
+    }
+
+    /// Whether a successfully processed message should be NAKed for redelivery
+    pub fn should_redeliver(&self) -> bool {
+        let redeliver = Self::roll(self.config.redelivery_rate);
+        if redeliver {
+            record_injected_fault("redelivery");
+        }
+        redeliver
+    }
+}
+
+/// Executor wrapper that delays or fails steps before delegating
+pub struct FaultInjectingExecutor {
+    inner: Arc<dyn JobExecutor>,
+    injector: Arc<FaultInjector>,
+}
+
+impl FaultInjectingExecutor {
+    pub fn new(inner: Arc<dyn JobExecutor>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+
+    /// Wrap the executor when an injector is configured, otherwise return it unchanged
+    pub fn wrap(
+        inner: Arc<dyn JobExecutor>,
+        injector: Option<&Arc<FaultInjector>>,
+    ) -> Arc<dyn JobExecutor> {
+        match injector {
+            Some(injector) => Arc::new(Self::new(inner, injector.clone())),
+            None => inner,
+        }
+    }
+}
+
+#[async_trait]
+impl JobExecutor for FaultInjectingExecutor {
+    async fn execute(
+        &self,
+        step: &JobStep,
+        context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        if let Some(latency) = self.injector.step_latency() {
+            info!(
+                step_id = %step.id,
+                latency_ms = latency.as_millis() as u64,
+                "Injecting step latency"
+            );
+            tokio::time::sleep(latency).await;
+        }
+
+        if self.injector.should_fail_step() {
+            warn!(step_id = %step.id, "Injecting step failure");
+            return Err(ExecutionError::FaultInjected(format!(
+                "step {} failed by fault injection",
+                step.id
+            )));
+        }
+
+        self.inner.execute(step, context).await
+    }
+}
+
+/// Lock wrapper that fails acquisitions before delegating
+pub struct FaultInjectingLock {
+    inner: Arc<dyn DistributedLock>,
+    injector: Arc<FaultInjector>,
+}
+
+impl FaultInjectingLock {
+    pub fn new(inner: Arc<dyn DistributedLock>, injector: Arc<FaultInjector>) -> Self {
+        Self { inner, injector }
+    }
+}
+
+#[async_trait]
+impl DistributedLock for FaultInjectingLock {
+    async fn acquire(&self, resource: &str, ttl: Duration) -> Result<LockGuard, StorageError> {
+        if self.injector.should_fail_lock() {
+            warn!(resource = %resource, "Injecting lock acquisition failure");
+            return Err(StorageError::RedisError(format!(
+                "Injected fault: lock acquisition failed for {}",
+                resource
+            )));
+        }
+
+        self.inner.acquire(resource, ttl).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(rate: f64) -> FaultInjectionConfig {
+        FaultInjectionConfig {
+            enabled: true,
+            step_latency_rate: rate,
+            step_latency_max_ms: 50,
+            step_failure_rate: rate,
+            lock_failure_rate: rate,
+            redelivery_rate: rate,
+        }
+    }
+
+    #[test]
+    fn test_disabled_config_builds_no_injector() {
+        assert!(FaultInjector::from_config(&FaultInjectionConfig::default()).is_none());
+        assert!(FaultInjector::from_config(&config(0.5)).is_some());
+    }
+
+    #[test]
+    fn test_zero_rates_never_inject() {
+        let injector = FaultInjector::new(config(0.0));
+        for _ in 0..100 {
+            assert!(injector.step_latency().is_none());
+            assert!(!injector.should_fail_step());
+            assert!(!injector.should_fail_lock());
+            assert!(!injector.should_redeliver());
+        }
+    }
+
+    #[test]
+    fn test_full_rates_always_inject() {
+        let injector = FaultInjector::new(config(1.0));
+        for _ in 0..100 {
+            let latency = injector.step_latency().unwrap();
+            assert!(latency <= Duration::from_millis(50));
+            assert!(injector.should_fail_step());
+            assert!(injector.should_fail_lock());
+            assert!(injector.should_redeliver());
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Failure fingerprints
+// Purpose: Group executions that failed for the same reason, so 500 identical
+// failures show up as one issue instead of 500 rows
+//
+// Error messages are normalized by replacing the parts that change from run to run
+// (ids, timestamps, addresses, counts) with placeholders. The fingerprint is a short
+// hash of the normalized message and is stored with the execution.
+
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+/// Longest normalized message kept; longer messages are cut before hashing
+pub const MAX_PATTERN_CHARS: usize = 1000;
+
+/// Replacements applied in order, most specific first
+fn rules() -> &'static [(Regex, &'static str)] {
+    static RULES: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    RULES.get_or_init(|| {
+        [
+            (
+                r"(?i)\b[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}\b",
+                "<uuid>",
+            ),
+            (
+                r"\b\d{4}-\d{2}-\d{2}(?:[T ]\d{2}:\d{2}(?::\d{2}(?:\.\d+)?)?(?:Z|[+-]\d{2}:?\d{2}|\s?UTC)?)?",
+                "<timestamp>",
+            ),
+            (r"\b\d{1,2}:\d{2}:\d{2}(?:\.\d+)?\b", "<timestamp>"),
+            (r"\b(?:\d{1,3}\.){3}\d{1,3}(?::\d{1,5})?\b", "<ip>"),
+            (r"(?i)\b(?:0x)?[0-9a-f]*\d[0-9a-f]*\b", "<hex>"),
+            (r"\d+(?:\.\d+)?", "<n>"),
+        ]
+        .into_iter()
+        .map(|(pattern, placeholder)| {
+            (
+                Regex::new(pattern).expect("Invalid fingerprint pattern"),
+                placeholder,
+            )
+        })
+        .collect()
+    })
+}
+
+/// The error message with its run-specific parts replaced by placeholders
+///
+/// Short numbers next to letters (`step2`, `S3`) and HTTP status codes are kept, as
+/// they usually tell failures apart rather than runs.
+pub fn normalize(message: &str) -> String {
+    let mut normalized = message.to_string();
+    for (index, (regex, placeholder)) in rules().iter().enumerate() {
+        let last = index == rules().len() - 1;
+        normalized = regex
+            .replace_all(&normalized, |caps: &regex::Captures| {
+                let matched = caps.get(0).expect("whole match");
+                let text = matched.as_str();
+                // Plain numbers are left to the number rule
+                if placeholder == &"<hex>"
+                    && (text.len() < 8 || text.bytes().all(|b| b.is_ascii_digit()))
+                {
+                    return text.to_string();
+                }
+                if last && keep_number(&normalized, matched.start(), matched.end()) {
+                    return text.to_string();
+                }
+                placeholder.to_string()
+            })
+            .into_owned();
+    }
+
+    let collapsed = normalized.split_whitespace().collect::<Vec<_>>().join(" ");
+    collapsed.chars().take(MAX_PATTERN_CHARS).collect()
+}
+
+/// Fingerprint of the error message: the first 16 hex digits of the SHA-256 of its
+/// normalized form
+pub fn fingerprint(message: &str) -> String {
+    let digest = Sha256::digest(normalize(message).as_bytes());
+    hex::encode(&digest[..8])
+}
+
+/// Whether `value` has the form of a fingerprint: 16 lowercase hex digits
+pub fn is_fingerprint(value: &str) -> bool {
+    value.len() == 16
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Whether the number at `start..end` of `text` is kept as it is: short numbers that
+/// are part of a word, and three-digit HTTP status codes
+fn keep_number(text: &str, start: usize, end: usize) -> bool {
+    let digits = &text[start..end];
+    if digits.contains('.') || digits.len() >= 4 {
+        return false;
+    }
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let before = text[..start].chars().next_back().is_some_and(is_word);
+    let after = text[end..].chars().next().is_some_and(is_word);
+    if before || after {
+        return true;
+    }
+    digits.len() == 3 && (100..600).contains(&digits.parse::<u16>().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_replaces_run_specific_parts() {
+        assert_eq!(
+            normalize(
+                "Execution 3f2b8c1e-9a4d-4e7b-8c2a-1d5e6f7a8b9c timed out after 30 s at 2024-05-01T10:15:30.123Z"
+            ),
+            "Execution <uuid> timed out after <n> s at <timestamp>"
+        );
+        assert_eq!(
+            normalize("connect to 10.0.3.17:5432 failed:\n  connection refused"),
+            "connect to <ip> failed: connection refused"
+        );
+        assert_eq!(
+            normalize("order 1048576 rejected, trace deadbeef42, object 0x7ffe1234"),
+            "order <n> rejected, trace <hex>, object <hex>"
+        );
+    }
+
+    #[test]
+    fn test_normalize_keeps_distinguishing_numbers() {
+        assert_eq!(
+            normalize("HTTP 503 from step2 (S3 upload)"),
+            "HTTP 503 from step2 (S3 upload)"
+        );
+        assert_eq!(
+            normalize("HTTP 404 after 12 retries"),
+            "HTTP 404 after <n> retries"
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_groups_equivalent_messages() {
+        let first = fingerprint("Row 17 of batch 20240501 failed at 10:15:30");
+        let second = fingerprint("Row 942 of batch 20240502 failed at 23:01:02");
+        assert_eq!(first, second);
+        assert!(is_fingerprint(&first));
+        assert!(!is_fingerprint("DEADBEEF00000000"));
+        assert_ne!(first, fingerprint("HTTP 500 from partner API"));
+        assert_ne!(
+            fingerprint("HTTP 500 from partner API"),
+            fingerprint("HTTP 502 from partner API")
+        );
+    }
+}
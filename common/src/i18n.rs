@@ -0,0 +1,411 @@
+// Localization of user-facing messages
+// Purpose: Serve API errors, validation messages and notification templates in
+// Vietnamese (vi-VN) or English (en-US) based on the caller's locale
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// Locale represents a supported message catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "vi-VN")]
+    ViVn,
+    #[serde(rename = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    /// All supported locales, in catalog order
+    pub const ALL: [Locale; 2] = [Locale::ViVn, Locale::EnUs];
+
+    /// BCP 47 tag for this locale (used in `Content-Language` and stored on users)
+    pub fn as_tag(&self) -> &'static str {
+        match self {
+            Locale::ViVn => "vi-VN",
+            Locale::EnUs => "en-US",
+        }
+    }
+
+    /// Pick the best supported locale from an `Accept-Language` header value
+    ///
+    /// Entries are ranked by their `q` weight; the first supported language wins.
+    /// Returns `None` if no entry matches a supported locale.
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut candidates: Vec<(f32, usize, Locale)> = header
+            .split(',')
+            .enumerate()
+            .filter_map(|(position, entry)| {
+                let mut parts = entry.trim().split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+
+                if quality <= 0.0 {
+                    return None;
+                }
+
+                tag.parse::<Locale>()
+                    .ok()
+                    .map(|locale| (quality, position, locale))
+            })
+            .collect();
+
+        // Highest weight first, header order breaks ties
+        candidates.sort_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        candidates.first().map(|(_, _, locale)| *locale)
+    }
+}
+
+impl std::fmt::Display for Locale {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_tag())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    /// Accepts full tags (`vi-VN`, `en_US`) and bare languages (`vi`, `en`), case-insensitive
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.trim().replace('_', "-").to_lowercase();
+        let language = normalized.split('-').next().unwrap_or_default();
+
+        match language {
+            "vi" => Ok(Locale::ViVn),
+            "en" => Ok(Locale::EnUs),
+            _ => Err(format!("Unsupported locale: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Locale {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// Message catalog entries as (key, vi-VN, en-US)
+///
+/// Keys are grouped by prefix:
+/// - `error.*`: generic titles per API error code
+/// - `auth.*`, `idempotency.*`, `job.*`, `execution.*`, `user.*`, `variable.*`,
+///   `dashboard.*`: specific API messages
+/// - `notification.*`: notification templates with `{placeholder}` arguments
+/// - `nav.*`: dashboard navigation labels
+const CATALOG: &[(&str, &str, &str)] = &[
+    // Generic error titles
+    ("error.unauthorized", "Chưa xác thực", "Unauthorized"),
+    ("error.forbidden", "Không có quyền truy cập", "Forbidden"),
+    ("error.not_found", "Không tìm thấy tài nguyên", "Resource not found"),
+    ("error.validation_error", "Dữ liệu không hợp lệ", "Validation failed"),
+    ("error.conflict", "Xung đột dữ liệu", "Conflict"),
+    ("error.rate_limit_exceeded", "Vượt quá giới hạn yêu cầu", "Rate limit exceeded"),
+    ("error.database_error", "Lỗi cơ sở dữ liệu", "Database error"),
+    ("error.internal_error", "Lỗi hệ thống", "Internal error"),
+    ("error.invalid_json", "Nội dung JSON không hợp lệ: {reason}", "Invalid JSON body: {reason}"),
+    // Authentication
+    ("auth.invalid_credentials", "Tên đăng nhập hoặc mật khẩu không đúng", "Invalid username or password"),
+    ("auth.username_required", "Vui lòng nhập tên đăng nhập", "Username is required"),
+    ("auth.password_required", "Vui lòng nhập mật khẩu", "Password is required"),
+    ("auth.password_too_short", "Mật khẩu phải có ít nhất 8 ký tự", "Password must be at least 8 characters"),
+    ("auth.token_required", "Thiếu token", "Token is required"),
+    ("auth.token_expired", "Token đã hết hạn", "Token has expired"),
+    ("auth.invalid_token", "Token không hợp lệ", "Invalid token"),
+    ("auth.username_exists", "Tên đăng nhập đã tồn tại", "Username already exists"),
+    ("auth.failed", "Xác thực thất bại", "Authentication failed"),
+    ("auth.account_disabled", "Tài khoản đã bị vô hiệu hóa", "User account is disabled"),
+    ("auth.too_many_attempts", "Đăng nhập sai quá nhiều lần, vui lòng thử lại sau {seconds} giây", "Too many failed login attempts, try again in {seconds} seconds"),
+    ("auth.session_not_extended", "Không thể gia hạn phiên đăng nhập", "Failed to extend session"),
+    ("auth.token_not_issued", "Không thể tạo token mới", "Failed to generate new token"),
+    ("auth.user_not_created", "Không thể tạo người dùng", "Failed to create user"),
+    ("auth.roles_not_assigned", "Không thể gán vai trò cho người dùng", "Failed to assign roles to user"),
+    ("auth.session_revoked", "Phiên đăng nhập đã bị thu hồi hoặc hết hạn", "Session has been revoked or has expired"),
+    // Idempotency keys
+    ("idempotency.invalid_key", "Idempotency-Key phải có từ 1 đến 255 ký tự", "Idempotency-Key must be between 1 and 255 characters"),
+    ("idempotency.body_too_large", "Nội dung yêu cầu quá lớn", "Request body is too large"),
+    ("idempotency.in_progress", "Yêu cầu với Idempotency-Key này đang được xử lý", "A request with this Idempotency-Key is still being processed"),
+    ("idempotency.key_mismatch", "Idempotency-Key đã được dùng cho một yêu cầu khác", "Idempotency-Key was already used for a different request"),
+    // Jobs
+    ("job.not_found", "Không tìm thấy job: {id}", "Job not found: {id}"),
+    ("job.concurrent_not_allowed", "Job đang chạy và không cho phép chạy đồng thời", "Job is already running and concurrent execution is not allowed"),
+    ("job.version_conflict", "Job đã bị người khác thay đổi, vui lòng tải lại và thử lại", "Job was modified by someone else, reload and try again"),
+    ("job.version_not_found", "Không tìm thấy phiên bản {version} của job {id}", "Version {version} of job {id} not found"),
+    ("job.version_query_conflict", "Chỉ dùng một trong hai tham số as_of hoặc version", "Use either as_of or version, not both"),
+    ("job.invalid_if_match", "Header If-Match không hợp lệ, cần là phiên bản job (ETag)", "Invalid If-Match header, expected the job version (ETag)"),
+    ("job.invalid_owner", "Thông tin người phụ trách job không hợp lệ: {reason}", "Invalid job owner: {reason}"),
+    ("job.invalid_metric_labels", "Nhãn metric của job không hợp lệ: {reason}", "Invalid job metric labels: {reason}"),
+    ("job.invalid_schedule", "Lịch chạy không hợp lệ: {reason}", "Invalid schedule: {reason}"),
+    ("job.unknown_calendar", "Không tìm thấy lịch làm việc: {name}", "Calendar not found: {name}"),
+    ("job.invalid_step", "Bước của job không hợp lệ: {reason}", "Invalid job step: {reason}"),
+    ("job.invalid_backfill", "Yêu cầu chạy bù không hợp lệ: {reason}", "Invalid backfill: {reason}"),
+    ("job.unknown_connection", "Không tìm thấy hồ sơ kết nối: {name}", "Connection profile not found: {name}"),
+    ("job.invalid_dead_letter_policy", "Chính sách dead letter của job không hợp lệ: {reason}", "Invalid job dead letter policy: {reason}"),
+    ("job.invalid_concurrency_limit", "Giới hạn đồng thời của job không hợp lệ: {reason}", "Invalid job concurrency limit: {reason}"),
+    ("job.invalid_parameter_matrix", "Ma trận tham số của job không hợp lệ: {reason}", "Invalid job parameter matrix: {reason}"),
+    ("job.invalid_wait", "Giá trị wait không hợp lệ: {wait} (ví dụ: 30s, 2m)", "Invalid wait value: {wait} (e.g. 30s, 2m)"),
+    ("job.execute_at_in_past", "execute_at phải là thời điểm trong tương lai", "execute_at must be in the future"),
+    ("job.scheduled_trigger_not_found", "Không tìm thấy lịch chạy một lần đang chờ: {id}", "Pending scheduled trigger not found: {id}"),
+    ("job.invalid_blackout_window", "Khung giờ cấm chạy cần có tên và thời điểm kết thúc sau thời điểm bắt đầu", "A blackout window needs a name and must end after it starts"),
+    ("job.blackout_window_not_found", "Không tìm thấy khung giờ cấm chạy: {id}", "Blackout window not found: {id}"),
+    ("job.webhook_payload_not_found", "Không tìm thấy yêu cầu webhook đã lưu: {id}", "Captured webhook payload not found: {id}"),
+    ("job.deprecated_owner_only", "Job {name} đã ngừng hỗ trợ, chỉ người phụ trách mới có thể chỉnh sửa", "Job {name} is deprecated and can only be edited by its owners"),
+    ("job.invalid_deprecation", "Thông tin ngừng hỗ trợ không hợp lệ: {reason}", "Invalid deprecation: {reason}"),
+    ("job.invalid_bundle", "Gói mã hóa không hợp lệ: {reason}", "Invalid encrypted bundle: {reason}"),
+    ("job.past_sunset", "Job {name} đã hết hạn sử dụng vào {sunset_at}, hãy bỏ trạng thái ngừng hỗ trợ trước khi bật lại", "Job {name} reached its sunset date at {sunset_at}; remove its deprecation before enabling it"),
+    ("job.system_steps_admin_only", "Chỉ quản trị viên mới có thể dùng bước hệ thống", "Only admins can use system steps"),
+    ("job.step_type_not_enabled", "Loại bước {step_type} đang thử nghiệm và chưa được bật cho tenant này (cờ {flag})", "Step type {step_type} is experimental and not enabled for this tenant (flag {flag})"),
+    ("job.invalid_state_key", "Khóa trạng thái gồm 1 đến {max} chữ cái, chữ số, '_' hoặc '-'", "A state key is 1 to {max} letters, digits, '_' or '-'"),
+    ("job.state_key_not_found", "Job {id} không có khóa trạng thái {key}", "Job {id} has no state key {key}"),
+    // Executions
+    ("execution.not_found", "Không tìm thấy lần thực thi: {id}", "Execution not found: {id}"),
+    ("execution.matrix_group_not_found", "Không tìm thấy nhóm lần thực thi theo ma trận: {id}", "Matrix execution group not found: {id}"),
+    ("execution.invalid_callback", "Callback không hợp lệ: {reason}", "Invalid callback: {reason}"),
+    ("execution.invalid_variables", "Biến ghi đè không hợp lệ: {reason}", "Invalid variable overrides: {reason}"),
+    ("execution.invalid_status", "Trạng thái không hợp lệ: {status}", "Invalid status value: {status}"),
+    ("execution.cannot_stop", "Không thể dừng lần thực thi có trạng thái {status}. Chỉ có thể dừng lần thực thi đang chạy.", "Cannot stop execution with status: {status}. Only running executions can be stopped."),
+    ("execution.cannot_replay", "Không thể phát lại lần thực thi có trạng thái {status}. Chỉ có thể phát lại lần thực thi đã kết thúc.", "Cannot replay execution with status: {status}. Only finished executions can be replayed."),
+    ("execution.filter_not_found", "Không tìm thấy bộ lọc đã lưu: {id}", "Saved filter not found: {id}"),
+    ("execution.invalid_filter", "Bộ lọc không hợp lệ: {reason}", "Invalid filter: {reason}"),
+    ("execution.unsupported_export_format", "Định dạng xuất không được hỗ trợ: {format}. Hãy dùng csv hoặc json", "Unsupported export format: {format}. Use csv or json"),
+    ("execution.invalid_export_range", "`from` phải sớm hơn `to`", "`from` must be earlier than `to`"),
+    ("execution.filter_not_owner", "Chỉ người tạo bộ lọc mới có thể sửa hoặc xóa nó", "Only the user who saved a filter can change or delete it"),
+    // Failure groups and remediation notes
+    ("failure.invalid_limit", "limit phải nằm trong khoảng 1 đến {max}", "limit must be between 1 and {max}"),
+    ("failure.groups_not_loaded", "Không thể tải các nhóm lỗi", "Failed to retrieve failure groups"),
+    ("failure.note_not_found", "Không có ghi chú cho dấu vân tay lỗi {fingerprint}", "No note for failure fingerprint {fingerprint}"),
+    ("failure.note_not_loaded", "Không thể tải ghi chú lỗi", "Failed to retrieve failure note"),
+    ("failure.invalid_fingerprint", "Dấu vân tay lỗi gồm 16 chữ số hex viết thường", "A failure fingerprint is 16 lowercase hex digits"),
+    ("failure.invalid_note", "note phải dài từ 1 đến {max} ký tự", "note must be between 1 and {max} characters"),
+    ("failure.invalid_runbook_url", "runbook_url phải là URL http(s)", "runbook_url must be an http(s) URL"),
+    ("failure.note_not_saved", "Không thể lưu ghi chú lỗi", "Failed to save failure note"),
+    ("failure.note_not_deleted", "Không thể xóa ghi chú lỗi", "Failed to delete failure note"),
+    ("failure.notes_not_loaded", "Không thể tải các ghi chú lỗi", "Failed to retrieve failure notes"),
+    // Outbound webhook deliveries
+    ("webhook_delivery.invalid_status", "Trạng thái gửi webhook không hợp lệ: {status}", "Invalid webhook delivery status: {status}"),
+    ("webhook_delivery.invalid_limit", "limit phải nằm trong khoảng 1 đến {max}", "limit must be between 1 and {max}"),
+    ("webhook_delivery.not_found", "Không tìm thấy lượt gửi webhook", "Webhook delivery not found"),
+    ("webhook_delivery.list_failed", "Không thể tải danh sách lượt gửi webhook", "Failed to retrieve webhook deliveries"),
+    ("webhook_delivery.not_loaded", "Không thể tải lượt gửi webhook", "Failed to retrieve webhook delivery"),
+    ("webhook_delivery.attempts_not_loaded", "Không thể tải các lần thử gửi webhook", "Failed to retrieve webhook delivery attempts"),
+    ("webhook_delivery.not_redelivered", "Không thể gửi lại webhook", "Failed to redeliver webhook"),
+    ("webhook_delivery.not_dead_letter", "Chỉ có thể gửi lại lượt gửi đã vào dead letter (trạng thái: {status})", "Only dead-lettered deliveries can be redelivered (status: {status})"),
+    // Queue administration
+    ("queue.invalid_peek_limit", "limit phải nằm trong khoảng 1 đến {max}", "limit must be between 1 and {max}"),
+    ("queue.purge_not_confirmed", "Để xác nhận xóa hàng đợi, trường confirm phải là tên stream: {stream}", "To confirm the purge, confirm must be the stream name: {stream}"),
+    // Backup and restore
+    ("backup.snapshot_not_found", "Không tìm thấy bản sao lưu: {path}", "Snapshot not found: {path}"),
+    ("backup.invalid_snapshot", "Không thể khôi phục bản sao lưu: {reason}", "Cannot restore snapshot: {reason}"),
+    ("backup.secrets_not_sealed", "Không thể mã hóa bí mật của bản sao lưu: {reason}", "Cannot seal the snapshot's secrets: {reason}"),
+    ("backup.cluster_not_empty", "Chỉ có thể khôi phục vào cụm trống; đã có dữ liệu trong: {tables}", "Restore requires an empty cluster; found existing rows in: {tables}"),
+    // Subject data erasure
+    ("erasure.invalid_identifier", "Định danh không hợp lệ: {reason}", "Invalid identifier: {reason}"),
+    ("erasure.confirmation_mismatch", "Kết quả tìm kiếm đã thay đổi từ lần tìm trước; hãy tìm lại và xác nhận kết quả mới", "The matches changed since the search; search again and confirm the new matches"),
+    // Multi-region failover
+    ("failover.database_read_only", "Cơ sở dữ liệu của region này vẫn là replica chỉ đọc; hãy promote nó trước hoặc gửi force: true", "This region's database is still a read-only replica; promote it first or send force: true"),
+    // Scheduler drain
+    ("scheduler.empty_node", "node không được để trống", "node must not be empty"),
+    ("scheduler.node_not_found", "Không có scheduler nào với node {node} trong nhật ký scheduler", "No scheduler with node {node} in the scheduler journal"),
+    ("scheduler.all_nodes_drained", "Mọi scheduler đang ở chế độ drain; hãy resume tất cả các node", "Every scheduler is drained; resume all nodes"),
+    ("scheduler.drain_not_saved", "Không thể lưu trạng thái drain của scheduler: {reason}", "Failed to save the scheduler drain: {reason}"),
+    // Usage reports
+    ("usage.invalid_month", "Tháng không hợp lệ: {reason}", "Invalid month: {reason}"),
+    // Credential expiry tracking
+    ("credential.invalid_within_days", "within_days không được lớn hơn {max}", "within_days must be at most {max}"),
+    ("credential.expirations_not_loaded", "Không thể tải danh sách thông tin xác thực sắp hết hạn", "Failed to retrieve credential expirations"),
+    ("tls_material.not_found", "Không tìm thấy chứng chỉ hoặc khóa TLS", "TLS material not found"),
+    ("tls_material.not_loaded", "Không thể tải chứng chỉ hoặc khóa TLS", "Failed to retrieve TLS material"),
+    ("tls_material.not_saved", "Không thể lưu chứng chỉ hoặc khóa TLS", "Failed to save TLS material"),
+    ("tls_material.not_deleted", "Không thể xóa chứng chỉ hoặc khóa TLS", "Failed to delete TLS material"),
+    ("tls_material.empty_name", "Tên chứng chỉ hoặc khóa TLS không được để trống", "TLS material name cannot be empty"),
+    ("tls_material.name_exists", "Chứng chỉ hoặc khóa TLS '{name}' đã tồn tại", "TLS material '{name}' already exists"),
+    // Business-day calendars
+    ("calendar.not_found", "Không tìm thấy lịch làm việc: {name}", "Calendar not found: {name}"),
+    ("calendar.not_loaded", "Không thể tải lịch làm việc", "Failed to load calendar"),
+    ("calendar.list_failed", "Không thể tải danh sách lịch làm việc", "Failed to list calendars"),
+    ("calendar.not_saved", "Không thể lưu lịch làm việc", "Failed to save calendar"),
+    ("calendar.not_deleted", "Không thể xóa lịch làm việc", "Failed to delete calendar"),
+    ("calendar.invalid_name", "Tên lịch làm việc phải dài từ 1 đến {max} ký tự", "Calendar name must be between 1 and {max} characters"),
+    ("calendar.all_weekend", "Lịch làm việc cần ít nhất một ngày trong tuần không phải ngày nghỉ cuối tuần", "A calendar needs at least one weekday that isn't weekend"),
+    ("calendar.in_use", "Lịch làm việc {name} đang được dùng bởi job: {jobs}", "Calendar {name} is still used by jobs: {jobs}"),
+    // Connection profiles
+    ("connection_profile.not_found", "Không tìm thấy hồ sơ kết nối: {name}", "Connection profile not found: {name}"),
+    ("connection_profile.not_loaded", "Không thể tải hồ sơ kết nối", "Failed to load connection profile"),
+    ("connection_profile.list_failed", "Không thể tải danh sách hồ sơ kết nối", "Failed to list connection profiles"),
+    ("connection_profile.not_saved", "Không thể lưu hồ sơ kết nối", "Failed to save connection profile"),
+    ("connection_profile.not_deleted", "Không thể xóa hồ sơ kết nối", "Failed to delete connection profile"),
+    ("connection_profile.invalid_name", "Tên hồ sơ kết nối phải dài từ 1 đến {max} ký tự", "Connection profile name must be between 1 and {max} characters"),
+    ("connection_profile.invalid_endpoint", "Endpoint của hồ sơ kết nối không hợp lệ: {reason}", "Invalid connection profile endpoint: {reason}"),
+    ("connection_profile.verification_not_queued", "Không thể xếp hàng kiểm tra kết nối", "Failed to queue connection verification"),
+    ("connection_profile.verifications_not_loaded", "Không thể tải kết quả kiểm tra kết nối", "Failed to load connection verifications"),
+    ("connection_profile.in_use", "Hồ sơ kết nối {name} đang được dùng bởi job: {jobs}", "Connection profile {name} is still used by jobs: {jobs}"),
+    // Users
+    ("user.not_found", "Không tìm thấy người dùng", "User not found"),
+    ("user.view_own_only", "Bạn chỉ có thể xem hồ sơ của chính mình", "You can only view your own profile"),
+    ("user.update_own_only", "Bạn chỉ có thể cập nhật hồ sơ của chính mình", "You can only update your own profile"),
+    ("user.admin_only_enable", "Chỉ quản trị viên mới có thể bật/tắt người dùng", "Only admins can enable/disable users"),
+    ("user.admin_only_tenant", "Chỉ quản trị viên mới có thể đổi tenant của người dùng", "Only admins can change a user's tenant"),
+    ("user.cannot_delete_self", "Bạn không thể xóa tài khoản của chính mình", "You cannot delete your own account"),
+    ("user.cannot_modify_own_roles", "Bạn không thể thay đổi vai trò của chính mình", "You cannot modify your own roles"),
+    ("user.change_own_password_only", "Bạn chỉ có thể đổi mật khẩu của chính mình", "You can only change your own password"),
+    ("user.unsupported_locale", "Ngôn ngữ không được hỗ trợ: {locale}", "Unsupported locale: {locale}"),
+    // Variables
+    ("variable.not_found", "Không tìm thấy biến: {id}", "Variable not found: {id}"),
+    // Dashboard layouts
+    ("dashboard.invalid_layout", "Bố cục dashboard không hợp lệ: {reason}", "Invalid dashboard layout: {reason}"),
+    ("dashboard.unknown_pinned_job", "Không thể ghim job không tồn tại: {id}", "Cannot pin a job that doesn't exist: {id}"),
+    // Public status pages
+    ("status_page.not_found", "Không tìm thấy trang trạng thái", "Status page not found"),
+    ("status_page.invalid", "Trang trạng thái không hợp lệ: {reason}", "Invalid status page: {reason}"),
+    ("status_page.unknown_job", "Không thể hiển thị job không tồn tại: {id}", "Cannot show a job that doesn't exist: {id}"),
+    // Login sessions
+    ("session.not_found", "Không tìm thấy phiên đăng nhập", "Session not found"),
+    ("session.own_only", "Bạn chỉ có thể quản lý phiên đăng nhập của chính mình", "You can only manage your own sessions"),
+    ("session.not_tracked", "Phiên đăng nhập chỉ được theo dõi khi đăng nhập bằng tài khoản hệ thống", "Sessions are only tracked for database logins"),
+    // Notifications
+    ("notification.consecutive_failures.subject", "[Cron] Job {job_name} thất bại liên tiếp", "[Cron] Job {job_name} is failing repeatedly"),
+    ("notification.consecutive_failures.body", "Job {job_name} ({job_id}) đã thất bại {count} lần liên tiếp", "Job {job_name} ({job_id}) has failed {count} consecutive times"),
+    ("notification.job_sunset.subject", "[Cron] Job {job_name} đã bị tắt khi hết hạn sử dụng", "[Cron] Job {job_name} was disabled at its sunset date"),
+    ("notification.job_sunset.body", "Job {job_name} ({job_id}) đã ngừng hỗ trợ và bị tắt tự động vào {sunset_at}", "Job {job_name} ({job_id}) is deprecated and was disabled automatically at {sunset_at}"),
+    ("notification.job_sunset.reason", "Lý do: {reason}", "Reason: {reason}"),
+    ("notification.credential_expiry.subject", "[Cron] {count} thông tin xác thực sắp hết hạn", "[Cron] {count} credentials are about to expire"),
+    ("notification.credential_expiry.body", "Các thông tin xác thực sau hết hạn trong vòng {warn_days} ngày:", "The following credentials expire within {warn_days} days:"),
+    ("notification.credential_expiry.expiring", "- {name} ({source}) hết hạn lúc {expires_at}, còn {days_left} ngày", "- {name} ({source}) expires at {expires_at}, {days_left} days left"),
+    ("notification.credential_expiry.expired", "- {name} ({source}) đã hết hạn lúc {expires_at}", "- {name} ({source}) expired at {expires_at}"),
+    ("notification.credential_source.variable", "biến", "variable"),
+    ("notification.credential_source.variable_rotation", "biến cần xoay vòng", "variable due for rotation"),
+    ("notification.credential_source.tls_material", "chứng chỉ/khóa TLS", "TLS material"),
+    ("notification.credential_source.certificate_file", "tệp chứng chỉ", "certificate file"),
+    ("notification.credential_source.certificate", "chứng chỉ", "certificate"),
+    ("notification.credential_source.private_key", "khóa riêng", "private key"),
+    ("notification.credential_source.ca_bundle", "gói CA", "CA bundle"),
+    ("notification.credential_source.ssh_key", "khóa SSH", "SSH key"),
+    ("notification.clock_skew.subject", "[Cron] Đồng hồ lệch so với {source}, đã tạm dừng lập lịch", "[Cron] Clock drifted from {source}, scheduling paused"),
+    ("notification.clock_skew.body", "Đồng hồ lệch {offset_ms}ms so với {source} (giới hạn {max_skew_ms}ms); việc lập lịch tạm dừng cho đến khi đồng hồ được chỉnh lại", "Clock differs from {source} by {offset_ms}ms (limit {max_skew_ms}ms); scheduling is paused until it is corrected"),
+    ("notification.owner.team", "Đội phụ trách: {team}", "Owning team: {team}"),
+    ("notification.owner.email", "Email liên hệ: {email}", "Contact email: {email}"),
+    ("notification.owner.slack", "Slack: {slack}", "Slack: {slack}"),
+    ("notification.owner.runbook", "Runbook: {url}", "Runbook: {url}"),
+    ("notification.owner.missing", "Job chưa khai báo người phụ trách", "No owner is recorded for this job"),
+    ("notification.remediation.note", "Cách xử lý đã biết: {note}", "Known fix: {note}"),
+    ("notification.remediation.runbook", "Runbook xử lý lỗi: {url}", "Failure runbook: {url}"),
+    // Dashboard navigation
+    ("nav.dashboard", "Tổng quan", "Dashboard"),
+    ("nav.jobs", "Jobs", "Jobs"),
+    ("nav.executions", "Lịch sử thực thi", "Executions"),
+    ("nav.variables", "Biến", "Variables"),
+    ("nav.dlq", "Hàng đợi lỗi", "Dead letters"),
+    ("nav.workers", "Workers", "Workers"),
+];
+
+static MESSAGES: OnceLock<HashMap<(Locale, &'static str), &'static str>> = OnceLock::new();
+
+/// Catalog indexed by (locale, key), built on first use
+fn messages() -> &'static HashMap<(Locale, &'static str), &'static str> {
+    MESSAGES.get_or_init(|| {
+        let mut messages = HashMap::with_capacity(CATALOG.len() * Locale::ALL.len());
+        for (key, vi, en) in CATALOG {
+            messages.insert((Locale::ViVn, *key), *vi);
+            messages.insert((Locale::EnUs, *key), *en);
+        }
+        messages
+    })
+}
+
+/// Look up a message in the catalog
+///
+/// Falls back to the en-US entry, then to the key itself, so a missing
+/// translation never produces an empty message.
+pub fn translate(locale: Locale, key: &str) -> &str {
+    let messages = messages();
+    messages
+        .get(&(locale, key))
+        .or_else(|| messages.get(&(Locale::EnUs, key)))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// Look up a message and substitute `{name}` placeholders with the given arguments
+pub fn translate_with(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(
+        translate(locale, key).to_string(),
+        |message, (name, value)| message.replace(&format!("{{{}}}", name), value),
+    )
+}
+
+/// Check whether a key exists in the catalog
+pub fn has_message(key: &str) -> bool {
+    messages().contains_key(&(Locale::EnUs, key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_parsing_accepts_tags_and_languages() {
+        assert_eq!("vi-VN".parse::<Locale>(), Ok(Locale::ViVn));
+        assert_eq!("vi".parse::<Locale>(), Ok(Locale::ViVn));
+        assert_eq!("en_us".parse::<Locale>(), Ok(Locale::EnUs));
+        assert_eq!("EN-GB".parse::<Locale>(), Ok(Locale::EnUs));
+        assert!("fr-FR".parse::<Locale>().is_err());
+    }
+
+    #[test]
+    fn test_accept_language_respects_quality() {
+        assert_eq!(
+            Locale::from_accept_language("en-US,en;q=0.9,vi;q=0.8"),
+            Some(Locale::EnUs)
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr-FR, vi;q=0.9, en;q=0.5"),
+            Some(Locale::ViVn)
+        );
+        assert_eq!(
+            Locale::from_accept_language("en;q=0.3, vi;q=0.7"),
+            Some(Locale::ViVn)
+        );
+        assert_eq!(Locale::from_accept_language("fr, de;q=0.5"), None);
+        assert_eq!(
+            Locale::from_accept_language("vi;q=0, en"),
+            Some(Locale::EnUs)
+        );
+    }
+
+    #[test]
+    fn test_every_key_has_both_translations() {
+        for (key, vi, en) in CATALOG {
+            assert!(!vi.is_empty(), "Missing vi-VN translation for {}", key);
+            assert!(!en.is_empty(), "Missing en-US translation for {}", key);
+        }
+    }
+
+    #[test]
+    fn test_translate_with_substitutes_placeholders() {
+        let message = translate_with(Locale::ViVn, "job.not_found", &[("id", "abc")]);
+        assert_eq!(message, "Không tìm thấy job: abc");
+
+        let message = translate_with(Locale::EnUs, "job.not_found", &[("id", "abc")]);
+        assert_eq!(message, "Job not found: abc");
+    }
+
+    #[test]
+    fn test_translate_falls_back_to_key() {
+        assert_eq!(translate(Locale::ViVn, "unknown.key"), "unknown.key");
+        assert!(!has_message("unknown.key"));
+        assert!(has_message("error.not_found"));
+    }
+
+    #[test]
+    fn test_locale_serializes_as_tag() {
+        let json = serde_json::to_string(&Locale::EnUs).unwrap();
+        assert_eq!(json, "\"en-US\"");
+        assert_eq!(Locale::default(), Locale::ViVn);
+    }
+}
@@ -0,0 +1,226 @@
+// Idempotency keys for mutating API requests
+// Purpose: Remember the response to a request carrying an `Idempotency-Key` so that
+// client retries replay it instead of executing the operation twice
+
+use crate::db::RedisConnection;
+use crate::errors::StorageError;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Response captured for replay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// Hex-encoded response body
+    body: String,
+}
+
+impl StoredResponse {
+    pub fn new(status: u16, content_type: Option<String>, body: &[u8]) -> Self {
+        Self {
+            status,
+            content_type,
+            body: hex::encode(body),
+        }
+    }
+
+    /// Decoded response body
+    pub fn body(&self) -> Vec<u8> {
+        hex::decode(&self.body).unwrap_or_default()
+    }
+}
+
+/// Record kept in Redis for each idempotency key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IdempotencyRecord {
+    request_hash: String,
+    /// `None` while the original request is still being processed
+    response: Option<StoredResponse>,
+}
+
+/// Result of claiming an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotencyState {
+    /// First time this key is seen: the caller must process the request
+    New,
+    /// The original request is still in flight
+    InProgress,
+    /// The key was already used for a different request
+    Mismatch,
+    /// The original request completed; replay its response
+    Completed(StoredResponse),
+}
+
+/// IdempotencyStore keeps idempotency records in Redis for a fixed TTL
+pub struct IdempotencyStore {
+    redis: RedisConnection,
+    ttl_seconds: u64,
+}
+
+impl IdempotencyStore {
+    pub fn new(redis: RedisConnection, ttl_seconds: u64) -> Self {
+        Self { redis, ttl_seconds }
+    }
+
+    /// Fingerprint of a request, so a reused key with a different request is detected
+    pub fn request_hash(method: &str, path: &str, body: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(method.as_bytes());
+        hasher.update(b" ");
+        hasher.update(path.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(body);
+        hex::encode(hasher.finalize())
+    }
+
+    /// Claim `key` for a request with the given hash
+    #[tracing::instrument(skip(self))]
+    pub async fn begin(
+        &self,
+        key: &str,
+        request_hash: &str,
+    ) -> Result<IdempotencyState, StorageError> {
+        let mut conn = self.redis.clone();
+        let redis_key = Self::redis_key(key);
+
+        let pending = serde_json::to_string(&IdempotencyRecord {
+            request_hash: request_hash.to_string(),
+            response: None,
+        })
+        .map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+
+        // SET NX claims the key atomically; if another request won, inspect its record
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&redis_key)
+            .arg(&pending)
+            .arg("NX")
+            .arg("EX")
+            .arg(self.ttl_seconds)
+            .query_async(&mut conn)
+            .await?;
+
+        if claimed.is_some() {
+            return Ok(IdempotencyState::New);
+        }
+
+        let existing: Option<String> = conn.get(&redis_key).await?;
+        let Some(existing) = existing else {
+            // Expired between SET and GET: treat as in flight, the client will retry
+            return Ok(IdempotencyState::InProgress);
+        };
+
+        let record: IdempotencyRecord = serde_json::from_str(&existing)
+            .map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        Ok(if record.request_hash != request_hash {
+            IdempotencyState::Mismatch
+        } else {
+            match record.response {
+                Some(response) => IdempotencyState::Completed(response),
+                None => IdempotencyState::InProgress,
+            }
+        })
+    }
+
+    /// Store the response of a request claimed with `begin`
+    #[tracing::instrument(skip(self, response))]
+    pub async fn complete(
+        &self,
+        key: &str,
+        request_hash: &str,
+        response: StoredResponse,
+    ) -> Result<(), StorageError> {
+        let mut conn = self.redis.clone();
+
+        let record = serde_json::to_string(&IdempotencyRecord {
+            request_hash: request_hash.to_string(),
+            response: Some(response),
+        })
+        .map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        let _: () = conn
+            .set_ex(Self::redis_key(key), record, self.ttl_seconds)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Release a claimed key without storing a response (e.g. after a server error)
+    #[tracing::instrument(skip(self))]
+    pub async fn release(&self, key: &str) -> Result<(), StorageError> {
+        let mut conn = self.redis.clone();
+        let _: () = conn.del(Self::redis_key(key)).await?;
+        Ok(())
+    }
+
+    fn redis_key(key: &str) -> String {
+        format!("idempotency:{}", key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedisConfig;
+
+    #[test]
+    fn test_request_hash_depends_on_method_path_and_body() {
+        let hash = IdempotencyStore::request_hash("POST", "/api/jobs", b"{}");
+        assert_eq!(
+            hash,
+            IdempotencyStore::request_hash("POST", "/api/jobs", b"{}")
+        );
+        assert_ne!(
+            hash,
+            IdempotencyStore::request_hash("POST", "/api/jobs", b"{\"a\":1}")
+        );
+        assert_ne!(
+            hash,
+            IdempotencyStore::request_hash("POST", "/api/jobs/import", b"{}")
+        );
+    }
+
+    #[test]
+    fn test_stored_response_round_trips_body() {
+        let response =
+            StoredResponse::new(200, Some("application/json".to_string()), b"{\"ok\":true}");
+        let json = serde_json::to_string(&response).unwrap();
+        let decoded: StoredResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.body(), b"{\"ok\":true}");
+        assert_eq!(decoded.status, 200);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn test_begin_detects_replay_and_mismatch() {
+        let redis = RedisConnection::connect(&RedisConfig {
+            url: "redis://127.0.0.1:6379".to_string(),
+            pool_size: 10,
+            sentinel: None,
+            cluster: None,
+        })
+        .await
+        .unwrap();
+        let store = IdempotencyStore::new(redis, 60);
+        let key = uuid::Uuid::new_v4().to_string();
+
+        assert_eq!(store.begin(&key, "a").await.unwrap(), IdempotencyState::New);
+        assert_eq!(
+            store.begin(&key, "a").await.unwrap(),
+            IdempotencyState::InProgress
+        );
+        assert_eq!(
+            store.begin(&key, "b").await.unwrap(),
+            IdempotencyState::Mismatch
+        );
+
+        let response = StoredResponse::new(202, None, b"done");
+        store.complete(&key, "a", response.clone()).await.unwrap();
+        assert_eq!(
+            store.begin(&key, "a").await.unwrap(),
+            IdempotencyState::Completed(response)
+        );
+
+        store.release(&key).await.unwrap();
+    }
+}
@@ -2,16 +2,27 @@
 // Requirements: 18.1-18.14 - Job import/export functionality
 // RECC 2025: No unwrap(), use #[tracing::instrument], proper error handling
 
+use crate::capabilities::STEP_TYPES;
 use crate::db::repositories::job::JobRepository;
+use crate::db::repositories::variable::VariableRepository;
 use crate::db::DbPool;
 use crate::errors::{DatabaseError, StorageError, ValidationError};
-use crate::models::{Job, JobStep, Schedule, TriggerConfig};
+use crate::executor::sftp::validate_sftp_step;
+use crate::models::{
+    validate_metric_labels, validate_step_flow, CollisionPolicy, ConcurrencyLimit,
+    DeadLetterPolicy, Job, JobOwner, JobPriority, JobStep, Schedule, TriggerConfig,
+};
+use crate::parameter_matrix::validate_parameter_matrix;
+use crate::schedule::RepeatingInterval;
 use crate::storage::StorageService;
+use crate::trigger_variables::check_sensitive_overrides;
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::str::FromStr;
 use tracing::{debug, info, instrument, warn};
 use uuid::Uuid;
 
@@ -32,6 +43,9 @@ pub enum ImportExportError {
 
     #[error("Not found: {0}")]
     NotFound(String),
+
+    #[error("Encryption error: {0}")]
+    Encryption(String),
 }
 
 /// Export metadata included in exported job definitions
@@ -56,7 +70,28 @@ pub struct ExportedJob {
     pub timeout_seconds: i32,
     pub max_retries: i32,
     pub allow_concurrent: bool,
+    #[serde(default)]
+    pub collision_policy: CollisionPolicy,
+    #[serde(default)]
+    pub strict_ordering: bool,
+    #[serde(default)]
+    pub priority: JobPriority,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dead_letter_policy: Option<DeadLetterPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency_limit: Option<ConcurrencyLimit>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameter_matrix: BTreeMap<String, Vec<String>>,
+    pub metadata: ExportMetadata,
+}
+
+/// Disaster-recovery bundle of job definitions with their real secret values
+///
+/// Only ever leaves the service encrypted, see `encrypt_bundle`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportBundle {
     pub metadata: ExportMetadata,
+    pub jobs: Vec<ExportedJob>,
 }
 
 /// Import result for a single job
@@ -85,6 +120,87 @@ const SENSITIVE_FIELD_PATTERNS: &[&str] = &[
 /// Requirements: 18.5 - Replace sensitive data with placeholder values
 const SENSITIVE_DATA_PLACEHOLDER: &str = "***MASKED***";
 
+/// Encrypt a bundle to age X25519 recipients (`age1...` public keys), ASCII-armored
+///
+/// Any one of the matching identities can decrypt it; the service never holds them.
+pub fn encrypt_bundle(
+    bundle: &ExportBundle,
+    recipients: &[String],
+) -> Result<String, ImportExportError> {
+    let plaintext = serde_json::to_vec(bundle)?;
+    encrypt_to_recipients(&plaintext, recipients)
+}
+
+/// Encrypt data to age X25519 recipients, ASCII-armored
+pub fn encrypt_to_recipients(
+    plaintext: &[u8],
+    recipients: &[String],
+) -> Result<String, ImportExportError> {
+    if recipients.is_empty() {
+        return Err(ValidationError::MissingField("recipients".to_string()).into());
+    }
+    let recipients = recipients
+        .iter()
+        .map(|r| {
+            age::x25519::Recipient::from_str(r.trim()).map_err(|e| {
+                ValidationError::InvalidFieldValue {
+                    field: "recipients".to_string(),
+                    reason: format!("'{}' is not an age public key: {}", r, e),
+                }
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let encryptor =
+        age::Encryptor::with_recipients(recipients.iter().map(|r| r as &dyn age::Recipient))
+            .map_err(|e| ImportExportError::Encryption(e.to_string()))?;
+
+    let mut armored = Vec::with_capacity(plaintext.len());
+    let io_error = |e: std::io::Error| ImportExportError::Encryption(e.to_string());
+    let mut writer = encryptor
+        .wrap_output(
+            age::armor::ArmoredWriter::wrap_output(&mut armored, age::armor::Format::AsciiArmor)
+                .map_err(io_error)?,
+        )
+        .map_err(io_error)?;
+    writer.write_all(plaintext).map_err(io_error)?;
+    writer
+        .finish()
+        .and_then(|armor| armor.finish())
+        .map_err(io_error)?;
+
+    String::from_utf8(armored).map_err(|e| ImportExportError::Encryption(e.to_string()))
+}
+
+/// Decrypt a bundle produced by `encrypt_bundle` with an age identity (`AGE-SECRET-KEY-1...`)
+pub fn decrypt_bundle(armored: &str, identity: &str) -> Result<ExportBundle, ImportExportError> {
+    let plaintext = decrypt_with_identity(armored, identity)?;
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+/// Decrypt data produced by `encrypt_to_recipients` with an age identity
+pub fn decrypt_with_identity(armored: &str, identity: &str) -> Result<Vec<u8>, ImportExportError> {
+    let identity = age::x25519::Identity::from_str(identity.trim()).map_err(|_| {
+        ValidationError::InvalidFieldValue {
+            field: "identity".to_string(),
+            reason: "is not an age secret key".to_string(),
+        }
+    })?;
+
+    let decryptor =
+        age::Decryptor::new_buffered(age::armor::ArmoredReader::new(armored.trim().as_bytes()))
+            .map_err(|e| ImportExportError::Encryption(e.to_string()))?;
+    let mut reader = decryptor
+        .decrypt(std::iter::once(&identity as &dyn age::Identity))
+        .map_err(|e| ImportExportError::Encryption(e.to_string()))?;
+
+    let mut plaintext = Vec::new();
+    reader
+        .read_to_end(&mut plaintext)
+        .map_err(|e| ImportExportError::Encryption(e.to_string()))?;
+    Ok(plaintext)
+}
+
 /// Job import/export service trait
 #[async_trait]
 pub trait ImportExportService: Send + Sync {
@@ -102,6 +218,16 @@ pub trait ImportExportService: Send + Sync {
         exported_by: String,
     ) -> Result<Vec<ExportedJob>, ImportExportError>;
 
+    /// Export jobs unmasked into a bundle encrypted to the given age recipients
+    ///
+    /// Unlike the bulk export, any job that can't be exported fails the whole bundle.
+    async fn export_encrypted_bundle(
+        &self,
+        job_ids: Vec<Uuid>,
+        exported_by: String,
+        recipients: &[String],
+    ) -> Result<String, ImportExportError>;
+
     /// Import a single job
     async fn import_job(
         &self,
@@ -116,6 +242,12 @@ pub trait ImportExportService: Send + Sync {
         sensitive_data: HashMap<String, HashMap<String, String>>,
     ) -> Result<Vec<ImportResult>, ImportExportError>;
 
+    /// Import every job of a decrypted bundle with its secret values intact
+    async fn import_bundle(
+        &self,
+        bundle: ExportBundle,
+    ) -> Result<Vec<ImportResult>, ImportExportError>;
+
     /// Generate export filename
     fn generate_export_filename(job_name: &str) -> String;
 
@@ -147,6 +279,66 @@ impl<S: StorageService> ImportExportServiceImpl<S> {
         }
     }
 
+    /// Build the exported form of a job, masking secrets unless `mask` is false
+    async fn build_exported_job(
+        &self,
+        job_id: Uuid,
+        exported_by: String,
+        mask: bool,
+    ) -> Result<ExportedJob, ImportExportError> {
+        let repo = JobRepository::new(self.db_pool.clone());
+        let job = repo
+            .find_by_id(job_id)
+            .await?
+            .ok_or_else(|| ImportExportError::NotFound(format!("Job not found: {}", job_id)))?;
+
+        let definition_json = self.storage_service.load_job_definition(job_id).await?;
+        let mut job_definition: serde_json::Value = serde_json::from_str(&definition_json)?;
+
+        if mask {
+            Self::mask_sensitive_data_recursive(&mut job_definition);
+        }
+
+        let schedule: Option<Schedule> = job_definition
+            .get("schedule")
+            .and_then(|v| serde_json::from_value(v.clone()).ok());
+
+        let steps: Vec<JobStep> = job_definition
+            .get("steps")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        let triggers: TriggerConfig = job_definition
+            .get("triggers")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default();
+
+        let metadata = ExportMetadata {
+            export_date: Utc::now(),
+            exported_by,
+            system_version: self.system_version.clone(),
+        };
+
+        Ok(ExportedJob {
+            id: job.id,
+            name: job.name,
+            description: job.description,
+            schedule,
+            steps,
+            triggers,
+            timeout_seconds: job.timeout_seconds,
+            max_retries: job.max_retries,
+            allow_concurrent: job.allow_concurrent,
+            collision_policy: job.collision_policy,
+            strict_ordering: job.strict_ordering,
+            priority: job.priority,
+            dead_letter_policy: job.dead_letter_policy,
+            concurrency_limit: job.concurrency_limit,
+            parameter_matrix: job.parameter_matrix,
+            metadata,
+        })
+    }
+
     /// Check if a field name is sensitive
     fn is_sensitive_field(field_name: &str) -> bool {
         let field_lower = field_name.to_lowercase();
@@ -229,51 +421,9 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
     ) -> Result<ExportedJob, ImportExportError> {
         info!(job_id = %job_id, "Exporting job");
 
-        let repo = JobRepository::new(self.db_pool.clone());
-        let job = repo
-            .find_by_id(job_id)
-            .await?
-            .ok_or_else(|| ImportExportError::NotFound(format!("Job not found: {}", job_id)))?;
+        let exported_job = self.build_exported_job(job_id, exported_by, true).await?;
 
-        let definition_json = self.storage_service.load_job_definition(job_id).await?;
-        let mut job_definition: serde_json::Value = serde_json::from_str(&definition_json)?;
-
-        Self::mask_sensitive_data_recursive(&mut job_definition);
-
-        let schedule: Option<Schedule> = job_definition
-            .get("schedule")
-            .and_then(|v| serde_json::from_value(v.clone()).ok());
-
-        let steps: Vec<JobStep> = job_definition
-            .get("steps")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        let triggers: TriggerConfig = job_definition
-            .get("triggers")
-            .and_then(|v| serde_json::from_value(v.clone()).ok())
-            .unwrap_or_default();
-
-        let metadata = ExportMetadata {
-            export_date: Utc::now(),
-            exported_by,
-            system_version: self.system_version.clone(),
-        };
-
-        let exported_job = ExportedJob {
-            id: job.id,
-            name: job.name.clone(),
-            description: job.description.clone(),
-            schedule,
-            steps,
-            triggers,
-            timeout_seconds: job.timeout_seconds,
-            max_retries: job.max_retries,
-            allow_concurrent: job.allow_concurrent,
-            metadata,
-        };
-
-        info!(job_id = %job_id, job_name = %job.name, "Job exported successfully");
+        info!(job_id = %job_id, job_name = %exported_job.name, "Job exported successfully");
         Ok(exported_job)
     }
 
@@ -302,6 +452,41 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
         Ok(exported_jobs)
     }
 
+    #[instrument(skip(self, recipients), fields(count = job_ids.len(), exported_by = %exported_by))]
+    async fn export_encrypted_bundle(
+        &self,
+        job_ids: Vec<Uuid>,
+        exported_by: String,
+        recipients: &[String],
+    ) -> Result<String, ImportExportError> {
+        info!(count = job_ids.len(), "Exporting encrypted job bundle");
+
+        let mut jobs = Vec::with_capacity(job_ids.len());
+        for job_id in job_ids {
+            jobs.push(
+                self.build_exported_job(job_id, exported_by.clone(), false)
+                    .await?,
+            );
+        }
+
+        let bundle = ExportBundle {
+            metadata: ExportMetadata {
+                export_date: Utc::now(),
+                exported_by,
+                system_version: self.system_version.clone(),
+            },
+            jobs,
+        };
+        let armored = encrypt_bundle(&bundle, recipients)?;
+
+        info!(
+            count = bundle.jobs.len(),
+            recipients = recipients.len(),
+            "Encrypted job bundle exported"
+        );
+        Ok(armored)
+    }
+
     #[instrument(skip(self, job_definition, sensitive_data))]
     async fn import_job(
         &self,
@@ -335,6 +520,14 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
         let schedule: Option<Schedule> = job_definition
             .get("schedule")
             .and_then(|v| serde_json::from_value(v.clone()).ok());
+        if let Some(Schedule::Iso8601 { expression, .. }) = &schedule {
+            RepeatingInterval::parse(expression).map_err(|e| {
+                ImportExportError::Validation(ValidationError::InvalidFieldValue {
+                    field: "schedule.expression".to_string(),
+                    reason: e.to_string(),
+                })
+            })?;
+        }
 
         let steps: Vec<JobStep> = job_definition
             .get("steps")
@@ -342,6 +535,10 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
             .ok_or_else(|| {
                 ImportExportError::Validation(ValidationError::MissingField("steps".to_string()))
             })?;
+        for step in &steps {
+            validate_sftp_step(step)?;
+        }
+        validate_step_flow(&steps)?;
 
         let triggers: TriggerConfig = job_definition
             .get("triggers")
@@ -365,6 +562,86 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
 
+        let collision_policy: CollisionPolicy = match job_definition.get("collision_policy") {
+            Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+                .map_err(|e| ValidationError::InvalidJson(format!("collision_policy: {}", e)))?,
+            _ => CollisionPolicy::default(),
+        };
+
+        let strict_ordering: bool = job_definition
+            .get("strict_ordering")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let priority: JobPriority = match job_definition.get("priority") {
+            Some(value) if !value.is_null() => serde_json::from_value(value.clone())
+                .map_err(|e| ValidationError::InvalidJson(format!("priority: {}", e)))?,
+            _ => JobPriority::default(),
+        };
+
+        let dead_letter_policy: Option<DeadLetterPolicy> =
+            match job_definition.get("dead_letter_policy") {
+                Some(value) if !value.is_null() => {
+                    let policy: DeadLetterPolicy =
+                        serde_json::from_value(value.clone()).map_err(|e| {
+                            ValidationError::InvalidJson(format!("dead_letter_policy: {}", e))
+                        })?;
+                    policy.validate()?;
+                    Some(policy)
+                }
+                _ => None,
+            };
+
+        let concurrency_limit: Option<ConcurrencyLimit> =
+            match job_definition.get("concurrency_limit") {
+                Some(value) if !value.is_null() => {
+                    let limit: ConcurrencyLimit =
+                        serde_json::from_value(value.clone()).map_err(|e| {
+                            ValidationError::InvalidJson(format!("concurrency_limit: {}", e))
+                        })?;
+                    limit.validate()?;
+                    Some(limit)
+                }
+                _ => None,
+            };
+
+        let owner: Option<JobOwner> = match job_definition.get("owner") {
+            Some(value) if !value.is_null() => {
+                let owner: JobOwner = serde_json::from_value(value.clone())
+                    .map_err(|e| ValidationError::InvalidJson(format!("owner: {}", e)))?;
+                owner.validate()?;
+                Some(owner)
+            }
+            _ => None,
+        };
+
+        let metric_labels: BTreeMap<String, String> = match job_definition.get("metric_labels") {
+            Some(value) if !value.is_null() => {
+                let labels = serde_json::from_value(value.clone())
+                    .map_err(|e| ValidationError::InvalidJson(format!("metric_labels: {}", e)))?;
+                validate_metric_labels(&labels)?;
+                labels
+            }
+            _ => BTreeMap::new(),
+        };
+
+        let parameter_matrix: BTreeMap<String, Vec<String>> =
+            match job_definition.get("parameter_matrix") {
+                Some(value) if !value.is_null() => {
+                    let matrix = serde_json::from_value(value.clone()).map_err(|e| {
+                        ValidationError::InvalidJson(format!("parameter_matrix: {}", e))
+                    })?;
+                    validate_parameter_matrix(&matrix)?;
+                    // Parameters are run variables and may not override sensitive ones
+                    let sensitive = VariableRepository::new(self.db_pool.clone(), None)
+                        .find_sensitive_names_for_job(job_id)
+                        .await?;
+                    check_sensitive_overrides(matrix.keys(), &sensitive)?;
+                    matrix
+                }
+                _ => BTreeMap::new(),
+            };
+
         let definition_json = serde_json::to_string_pretty(&job_definition)?;
         self.storage_service
             .store_job_definition(job_id, &definition_json)
@@ -379,10 +656,24 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
             steps,
             triggers,
             enabled: true,
+            paused: false,
             timeout_seconds,
             max_retries,
             allow_concurrent,
+            collision_policy,
+            strict_ordering,
+            priority,
+            dead_letter_policy,
+            concurrency_limit,
+            owner,
+            metric_labels,
+            parameter_matrix,
             definition: Some(job_definition.clone()),
+            version: 1,
+            deprecated: false,
+            sunset_at: None,
+            deprecation_reason: None,
+            tenant_id: None,
             created_at: now,
             updated_at: now,
         };
@@ -448,6 +739,26 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
         Ok(results)
     }
 
+    #[instrument(skip(self, bundle), fields(count = bundle.jobs.len()))]
+    async fn import_bundle(
+        &self,
+        bundle: ExportBundle,
+    ) -> Result<Vec<ImportResult>, ImportExportError> {
+        info!(
+            count = bundle.jobs.len(),
+            exported_by = %bundle.metadata.exported_by,
+            export_date = %bundle.metadata.export_date,
+            "Importing encrypted job bundle"
+        );
+
+        let definitions = bundle
+            .jobs
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.import_jobs_bulk(definitions, HashMap::new()).await
+    }
+
     fn generate_export_filename(job_name: &str) -> String {
         let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
         let sanitized_name = job_name
@@ -536,6 +847,18 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
                     ValidationError::MissingField(format!("steps[{}].type", idx)),
                 ));
             }
+            let step_type = match &step["type"] {
+                serde_json::Value::String(step_type) => Some(step_type.as_str()),
+                step_type => step_type.get("type").and_then(|v| v.as_str()),
+            };
+            if !step_type.is_some_and(|step_type| STEP_TYPES.contains(&step_type)) {
+                return Err(ImportExportError::Validation(
+                    ValidationError::InvalidFieldValue {
+                        field: format!("steps[{}].type", idx),
+                        reason: format!("must be one of {}", STEP_TYPES.join(", ")),
+                    },
+                ));
+            }
         }
 
         debug!("Job definition schema validation passed");
@@ -579,6 +902,7 @@ impl<S: StorageService> ImportExportService for ImportExportServiceImpl<S> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{HttpAuth, HttpMethod, JobType};
     use crate::storage::service::StorageServiceImpl;
 
     #[test]
@@ -616,6 +940,110 @@ mod tests {
         assert_eq!(password, SENSITIVE_DATA_PLACEHOLDER);
     }
 
+    fn bundle() -> ExportBundle {
+        let metadata = ExportMetadata {
+            export_date: Utc::now(),
+            exported_by: "alice".to_string(),
+            system_version: "1.0.0".to_string(),
+        };
+        let steps = vec![JobStep {
+            id: "step1".to_string(),
+            name: "Step 1".to_string(),
+            step_type: JobType::HttpRequest {
+                method: HttpMethod::Get,
+                url: "https://example.com".to_string(),
+                headers: HashMap::new(),
+                body: None,
+                auth: Some(HttpAuth::Basic {
+                    username: "user".to_string(),
+                    password: "secret123".to_string(),
+                }),
+            },
+            condition: None,
+            on_failure: None,
+            compensate_with: Vec::new(),
+            acquire_lock: None,
+            set_state: BTreeMap::new(),
+            env: BTreeMap::new(),
+            timeout_seconds: None,
+            retry_count: None,
+            stream_output: false,
+            pii: false,
+            dependency: None,
+            connection: None,
+        }];
+        ExportBundle {
+            metadata: metadata.clone(),
+            jobs: vec![ExportedJob {
+                id: Uuid::new_v4(),
+                name: "nightly-report".to_string(),
+                description: None,
+                schedule: None,
+                steps,
+                triggers: TriggerConfig::default(),
+                timeout_seconds: 300,
+                max_retries: 3,
+                allow_concurrent: false,
+                collision_policy: CollisionPolicy::Skip,
+                strict_ordering: false,
+                priority: JobPriority::Normal,
+                dead_letter_policy: None,
+                concurrency_limit: None,
+                parameter_matrix: BTreeMap::new(),
+                metadata,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_encrypted_bundle_round_trips_with_secrets() {
+        use age::secrecy::ExposeSecret;
+
+        let identity = age::x25519::Identity::generate();
+        let other = age::x25519::Identity::generate();
+        let recipients = vec![
+            identity.to_public().to_string(),
+            other.to_public().to_string(),
+        ];
+
+        let armored = encrypt_bundle(&bundle(), &recipients).unwrap();
+        assert!(armored.starts_with("-----BEGIN AGE ENCRYPTED FILE-----"));
+        assert!(!armored.contains("secret123"));
+
+        // Any recipient's identity decrypts the bundle
+        for key in [&identity, &other] {
+            let decrypted = decrypt_bundle(&armored, key.to_string().expose_secret()).unwrap();
+            let definition = serde_json::to_value(&decrypted.jobs[0]).unwrap();
+            assert_eq!(
+                definition["steps"][0]["type"]["auth"]["password"],
+                "secret123"
+            );
+            assert_eq!(decrypted.metadata.exported_by, "alice");
+        }
+
+        let stranger = age::x25519::Identity::generate();
+        assert!(matches!(
+            decrypt_bundle(&armored, stranger.to_string().expose_secret()),
+            Err(ImportExportError::Encryption(_))
+        ));
+    }
+
+    #[test]
+    fn test_encrypted_bundle_rejects_bad_keys() {
+        assert!(matches!(
+            encrypt_bundle(&bundle(), &[]),
+            Err(ImportExportError::Validation(_))
+        ));
+        assert!(matches!(
+            encrypt_bundle(&bundle(), &["not-a-key".to_string()]),
+            Err(ImportExportError::Validation(_))
+        ));
+        assert!(matches!(
+            decrypt_bundle("garbage", "AGE-SECRET-KEY-invalid"),
+            Err(ImportExportError::Validation(_))
+        ));
+    }
+
     #[test]
     fn test_validate_job_definition_valid() {
         let job_def = serde_json::json!({
@@ -631,4 +1059,25 @@ mod tests {
             ImportExportServiceImpl::<StorageServiceImpl>::validate_job_definition(&job_def);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_validate_job_definition_checks_step_types() {
+        let job_def = |step_type: &str| {
+            serde_json::json!({
+                "name": "partner-files",
+                "steps": [{
+                    "id": "fetch",
+                    "name": "Fetch",
+                    "type": {"type": step_type, "operation": "download"}
+                }]
+            })
+        };
+        let validate = ImportExportServiceImpl::<StorageServiceImpl>::validate_job_definition;
+
+        assert!(validate(&job_def("sftp")).is_ok());
+        assert!(matches!(
+            validate(&job_def("ftp")),
+            Err(ImportExportError::Validation(_))
+        ));
+    }
 }
@@ -0,0 +1,412 @@
+// JWT signing key rotation
+// Purpose: Sign tokens with Ed25519 keys that rotate automatically, and publish the
+// public keys as a JWKS so other internal services can validate our tokens
+//
+// Keys are shared by all API instances through the database. The private keys are
+// encrypted with AES-256-GCM under a key derived from `auth.jwt_secret`. A new key is
+// published in the JWKS for `JWKS_MAX_AGE_SECONDS` before it signs anything, so
+// services that cached the JWKS already know it; the key it replaces keeps signing
+// meanwhile. A key signs tokens until it's `jwt_key_rotation_days` old; after it's
+// replaced it stays in the JWKS for one token lifetime past its last token, so the
+// tokens it signed stay valid until they expire. An instance that sees a `kid` it
+// doesn't know reloads the keys.
+
+use crate::db::repositories::JwtKeyRepository;
+use crate::errors::AuthError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{DecodingKey, EncodingKey};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf::{Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::signature::{Ed25519KeyPair, KeyPair};
+use serde::Serialize;
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+use tracing::{error, info, instrument, warn};
+
+/// Algorithm of the signing keys
+pub const JWT_KEY_ALGORITHM: &str = "EdDSA";
+
+/// How long clients may cache the JWKS, and so how long a new key is published before
+/// it signs tokens
+pub const JWKS_MAX_AGE_SECONDS: u64 = 300;
+
+/// Least time between reloads caused by unknown key ids
+const MIN_RELOAD_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// HKDF info for the key that encrypts stored private keys
+const KEY_ENCRYPTION_INFO: &[u8] = b"enterprise-cron jwt signing keys";
+
+/// A signing key as stored
+#[derive(Debug, Clone)]
+pub struct JwtSigningKey {
+    pub kid: String,
+    /// Raw 32-byte Ed25519 public key
+    pub public_key: Vec<u8>,
+    /// Nonce followed by the AES-256-GCM sealed PKCS#8 private key
+    pub encrypted_private_key: Vec<u8>,
+    pub created_at: DateTime<Utc>,
+    /// When a newer key replaced it; `None` for the key that signs new tokens
+    pub retired_at: Option<DateTime<Utc>>,
+}
+
+/// A public key in JWKS form
+#[derive(Debug, Clone, Serialize)]
+pub struct Jwk {
+    pub kty: &'static str,
+    pub crv: &'static str,
+    pub alg: &'static str,
+    #[serde(rename = "use")]
+    pub key_use: &'static str,
+    pub kid: String,
+    pub x: String,
+}
+
+/// The document served at `/.well-known/jwks.json`
+#[derive(Debug, Clone, Serialize)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// A key ready for signing and verifying
+struct LoadedKey {
+    kid: String,
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    public_key: Vec<u8>,
+    created_at: DateTime<Utc>,
+}
+
+/// The signing keys shared by all API instances
+pub struct JwtKeyRing {
+    repository: JwtKeyRepository,
+    cipher: LessSafeKey,
+    rotation_interval: Duration,
+    token_lifetime: Duration,
+    /// Newest first
+    keys: RwLock<Vec<LoadedKey>>,
+    last_reload: Mutex<Option<Instant>>,
+}
+
+impl JwtKeyRing {
+    /// Create a key ring; call `rotate_if_due` before signing the first token
+    pub fn new(
+        repository: JwtKeyRepository,
+        secret: &str,
+        rotation_days: u64,
+        token_lifetime_hours: u64,
+    ) -> Self {
+        Self {
+            repository,
+            cipher: key_encryption_key(secret),
+            rotation_interval: Duration::days(rotation_days as i64),
+            token_lifetime: Duration::hours(token_lifetime_hours as i64),
+            keys: RwLock::new(Vec::new()),
+            last_reload: Mutex::new(None),
+        }
+    }
+
+    /// Create a new signing key if the current one is older than the rotation interval
+    /// (or there is none), then reload the keys
+    ///
+    /// Safe to run on every instance at once; only one of them creates the key.
+    #[instrument(skip(self))]
+    pub async fn rotate_if_due(&self) -> Result<bool, AuthError> {
+        let now = Utc::now();
+        // A signing key this instance can't decrypt (e.g. after `jwt_secret` changed)
+        // is replaced right away
+        let due_before = if self.signing_key().is_some() {
+            now - self.rotation_interval
+        } else {
+            now
+        };
+
+        let key = generate_key(&self.cipher, now)?;
+        let rotated = self
+            .repository
+            .rotate(&key, due_before)
+            .await
+            .map_err(|e| {
+                AuthError::AuthenticationFailed(format!("Failed to rotate keys: {}", e))
+            })?;
+        if rotated {
+            info!(kid = %key.kid, "JWT signing key rotated");
+        }
+
+        self.reload().await?;
+        Ok(rotated)
+    }
+
+    /// Delete keys whose tokens have all expired
+    #[instrument(skip(self))]
+    pub async fn prune(&self) -> Result<u64, AuthError> {
+        self.repository
+            .delete_retired_before(Utc::now() - self.key_lifetime_after_retiring())
+            .await
+            .map_err(|e| AuthError::AuthenticationFailed(format!("Failed to prune keys: {}", e)))
+    }
+
+    /// Load the keys that sign or may have signed unexpired tokens
+    #[instrument(skip(self))]
+    pub async fn reload(&self) -> Result<(), AuthError> {
+        let stored = self
+            .repository
+            .list_retired_after(Utc::now() - self.key_lifetime_after_retiring())
+            .await
+            .map_err(|e| AuthError::AuthenticationFailed(format!("Failed to load keys: {}", e)))?;
+
+        let keys: Vec<LoadedKey> = stored
+            .iter()
+            .filter_map(|key| match load_key(&self.cipher, key) {
+                Ok(loaded) => Some(loaded),
+                Err(e) => {
+                    error!(kid = %key.kid, error = %e, "Skipping unreadable JWT signing key");
+                    None
+                }
+            })
+            .collect();
+
+        *self.keys.write().expect("JWT key lock poisoned") = keys;
+        *self.last_reload.lock().expect("JWT key lock poisoned") = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Make sure the key a token names is loaded, before validating it
+    pub async fn ensure_token_key(&self, token: &str) {
+        if let Ok(Some(kid)) = jsonwebtoken::decode_header(token).map(|header| header.kid) {
+            self.ensure_key(&kid).await;
+        }
+    }
+
+    /// Make sure a key id is loaded if it exists, reloading at most every few seconds
+    pub async fn ensure_key(&self, kid: &str) {
+        if self.decoding_key(kid).is_some() {
+            return;
+        }
+        let recently_reloaded = self
+            .last_reload
+            .lock()
+            .expect("JWT key lock poisoned")
+            .is_some_and(|at| at.elapsed() < MIN_RELOAD_INTERVAL);
+        if recently_reloaded {
+            return;
+        }
+        if let Err(e) = self.reload().await {
+            warn!(kid = %kid, error = %e, "Failed to reload JWT signing keys");
+        }
+    }
+
+    /// How long a replaced key is kept: it may sign tokens until its successor has
+    /// been published, and those stay valid for one token lifetime
+    fn key_lifetime_after_retiring(&self) -> Duration {
+        self.token_lifetime + Duration::seconds(JWKS_MAX_AGE_SECONDS as i64)
+    }
+
+    /// Id and key that sign new tokens: the newest key published for at least
+    /// `JWKS_MAX_AGE_SECONDS`, or the newest one if no older key is left
+    pub fn signing_key(&self) -> Option<(String, EncodingKey)> {
+        let published_before = Utc::now() - Duration::seconds(JWKS_MAX_AGE_SECONDS as i64);
+        let keys = self.keys.read().expect("JWT key lock poisoned");
+        keys.iter()
+            .find(|key| key.created_at <= published_before)
+            .or_else(|| keys.first())
+            .map(|key| (key.kid.clone(), key.encoding_key.clone()))
+    }
+
+    /// Key that verifies tokens with the given key id
+    pub fn decoding_key(&self, kid: &str) -> Option<DecodingKey> {
+        self.keys
+            .read()
+            .expect("JWT key lock poisoned")
+            .iter()
+            .find(|key| key.kid == kid)
+            .map(|key| key.decoding_key.clone())
+    }
+
+    /// Public keys that validate unexpired tokens
+    pub fn jwks(&self) -> Jwks {
+        let keys = self.keys.read().expect("JWT key lock poisoned");
+        Jwks {
+            keys: keys
+                .iter()
+                .map(|key| public_jwk(&key.kid, &key.public_key))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl JwtKeyRing {
+    /// A key ring without a database, for tests that add their keys by hand
+    pub(crate) fn test_ring(secret: &str) -> Self {
+        Self::new(
+            JwtKeyRepository::new(crate::db::DbPool::unreachable()),
+            secret,
+            30,
+            24,
+        )
+    }
+
+    /// Add a key created at `created_at`, returning its id
+    pub(crate) fn insert_test_key(&self, created_at: DateTime<Utc>) -> String {
+        let key = load_key(
+            &self.cipher,
+            &generate_key(&self.cipher, created_at).unwrap(),
+        )
+        .unwrap();
+        let kid = key.kid.clone();
+        let mut keys = self.keys.write().unwrap();
+        keys.push(key);
+        keys.sort_by_key(|key| std::cmp::Reverse(key.created_at));
+        kid
+    }
+}
+
+fn key_encryption_key(secret: &str) -> LessSafeKey {
+    let prk = Salt::new(HKDF_SHA256, &[]).extract(secret.as_bytes());
+    let okm = prk
+        .expand(&[KEY_ENCRYPTION_INFO], &AES_256_GCM)
+        .expect("AES-256 key length is valid for HKDF-SHA256");
+    LessSafeKey::new(UnboundKey::from(okm))
+}
+
+/// Generate a signing key with its private key encrypted
+fn generate_key(
+    cipher: &LessSafeKey,
+    created_at: DateTime<Utc>,
+) -> Result<JwtSigningKey, AuthError> {
+    let rng = SystemRandom::new();
+    let failed = |what: &str| AuthError::AuthenticationFailed(format!("Failed to {}", what));
+
+    let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(|_| failed("generate key"))?;
+    let pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| failed("generate key"))?;
+
+    let mut id = [0u8; 4];
+    rng.fill(&mut id).map_err(|_| failed("generate key id"))?;
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce).map_err(|_| failed("generate nonce"))?;
+
+    let mut sealed = pkcs8.as_ref().to_vec();
+    cipher
+        .seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::empty(),
+            &mut sealed,
+        )
+        .map_err(|_| failed("encrypt key"))?;
+    let mut encrypted_private_key = nonce.to_vec();
+    encrypted_private_key.extend_from_slice(&sealed);
+
+    Ok(JwtSigningKey {
+        kid: format!("{}-{}", created_at.format("%Y%m%d"), hex::encode(id)),
+        public_key: pair.public_key().as_ref().to_vec(),
+        encrypted_private_key,
+        created_at,
+        retired_at: None,
+    })
+}
+
+/// Decrypt a stored key
+fn load_key(cipher: &LessSafeKey, key: &JwtSigningKey) -> Result<LoadedKey, AuthError> {
+    let unreadable = || AuthError::InvalidToken(format!("Key {} can't be decrypted", key.kid));
+    if key.encrypted_private_key.len() <= NONCE_LEN {
+        return Err(unreadable());
+    }
+
+    let (nonce, sealed) = key.encrypted_private_key.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| unreadable())?;
+    let mut sealed = sealed.to_vec();
+    let pkcs8 = cipher
+        .open_in_place(nonce, Aad::empty(), &mut sealed)
+        .map_err(|_| unreadable())?;
+
+    Ok(LoadedKey {
+        kid: key.kid.clone(),
+        encoding_key: EncodingKey::from_ed_der(pkcs8),
+        decoding_key: DecodingKey::from_ed_der(&key.public_key),
+        public_key: key.public_key.clone(),
+        created_at: key.created_at,
+    })
+}
+
+fn public_jwk(kid: &str, public_key: &[u8]) -> Jwk {
+    Jwk {
+        kty: "OKP",
+        crv: "Ed25519",
+        alg: JWT_KEY_ALGORITHM,
+        key_use: "sig",
+        kid: kid.to_string(),
+        x: URL_SAFE_NO_PAD.encode(public_key),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{decode, encode, Algorithm, Header, Validation};
+
+    #[derive(Debug, Serialize, serde::Deserialize)]
+    struct Claims {
+        sub: String,
+        exp: i64,
+    }
+
+    #[test]
+    fn test_generated_keys_sign_and_verify() {
+        let cipher = key_encryption_key("test-secret");
+        let stored = generate_key(&cipher, Utc::now()).unwrap();
+        assert_eq!(stored.public_key.len(), 32);
+
+        let key = load_key(&cipher, &stored).unwrap();
+        let mut header = Header::new(Algorithm::EdDSA);
+        header.kid = Some(key.kid.clone());
+        let claims = Claims {
+            sub: "user-123".to_string(),
+            exp: (Utc::now() + Duration::hours(1)).timestamp(),
+        };
+        let token = encode(&header, &claims, &key.encoding_key).unwrap();
+
+        // Other services validate with the published JWK
+        let jwk: jsonwebtoken::jwk::Jwk = serde_json::from_value(
+            serde_json::to_value(public_jwk(&key.kid, &key.public_key)).unwrap(),
+        )
+        .unwrap();
+        let decoded = decode::<Claims>(
+            &token,
+            &DecodingKey::from_jwk(&jwk).unwrap(),
+            &Validation::new(Algorithm::EdDSA),
+        )
+        .unwrap();
+        assert_eq!(decoded.claims.sub, "user-123");
+    }
+
+    #[tokio::test]
+    async fn test_new_keys_are_published_before_they_sign() {
+        let ring = JwtKeyRing::test_ring("test-secret");
+        let old = ring.insert_test_key(Utc::now() - Duration::days(30));
+        assert_eq!(ring.signing_key().unwrap().0, old);
+
+        // A key created just now is in the JWKS but doesn't sign yet
+        let new = ring.insert_test_key(Utc::now());
+        assert_eq!(ring.signing_key().unwrap().0, old);
+        let published: Vec<String> = ring.jwks().keys.into_iter().map(|key| key.kid).collect();
+        assert!(published.contains(&new));
+
+        // Once clients have had time to refetch the JWKS, it takes over
+        let newer =
+            ring.insert_test_key(Utc::now() - Duration::seconds(JWKS_MAX_AGE_SECONDS as i64));
+        assert_eq!(ring.signing_key().unwrap().0, newer);
+    }
+
+    #[test]
+    fn test_keys_need_the_same_secret() {
+        let stored = generate_key(&key_encryption_key("test-secret"), Utc::now()).unwrap();
+        assert!(load_key(&key_encryption_key("other-secret"), &stored).is_err());
+
+        let mut truncated = stored.clone();
+        truncated.encrypted_private_key.truncate(NONCE_LEN);
+        assert!(load_key(&key_encryption_key("test-secret"), &truncated).is_err());
+    }
+}
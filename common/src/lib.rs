@@ -1,24 +1,49 @@
 // Common library for shared code across scheduler, worker, and API
 
 pub mod auth;
+pub mod backup;
 pub mod bootstrap;
+pub mod callback;
+pub mod capabilities;
 pub mod circuit_breaker;
+pub mod clock;
 pub mod config;
+pub mod connection_profile;
+pub mod dashboard_layout;
 pub mod db;
+pub mod deprecation;
 pub mod dlq;
+pub mod erasure;
 pub mod errors;
+pub mod execution_filter;
 pub mod executor;
+pub mod failover;
+pub mod fault_injection;
+pub mod fingerprint;
+pub mod i18n;
+pub mod idempotency;
 pub mod import_export;
+pub mod jwt_keys;
 pub mod lock;
 pub mod middleware;
 pub mod models;
+pub mod parameter_matrix;
 pub mod queue;
 pub mod rate_limit;
+pub mod replay;
+pub mod resource_guard;
 pub mod retry;
 pub mod schedule;
 pub mod scheduler;
+pub mod semaphore;
+pub mod simulation;
+pub mod status_page;
+pub mod step_env;
 pub mod storage;
 pub mod substitution;
+pub mod system_jobs;
 pub mod telemetry;
+pub mod trigger_variables;
+pub mod usage;
 pub mod webhook;
 pub mod worker;
@@ -5,6 +5,10 @@ use crate::db::RedisPool;
 use crate::errors::StorageError;
 use async_trait::async_trait;
 use redis::AsyncCommands;
+#[cfg(test)]
+use std::collections::HashMap;
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time::{sleep, Instant};
 use tracing::{debug, info, instrument, warn};
@@ -17,11 +21,19 @@ pub trait DistributedLock: Send + Sync {
     async fn acquire(&self, resource: &str, ttl: Duration) -> Result<LockGuard, StorageError>;
 }
 
+/// Where the lock of a guard is kept
+enum LockStore {
+    Redis(RedisPool),
+    /// Locks faked in tests, by resource
+    #[cfg(test)]
+    Memory(Arc<Mutex<MemoryLocks>>),
+}
+
 /// Lock guard that automatically releases the lock when dropped
 pub struct LockGuard {
     resource: String,
     lock_value: String,
-    pool: RedisPool,
+    store: LockStore,
     acquired_at: Instant,
     ttl: Duration,
 }
@@ -31,7 +43,28 @@ impl LockGuard {
     /// Requirements: 4.1 - Lock extension for long operations
     #[instrument(skip(self), fields(resource = %self.resource, ttl_seconds = ?self.ttl.as_secs()))]
     pub async fn extend(&mut self, additional_ttl: Duration) -> Result<(), StorageError> {
-        let mut conn = self.pool.get_connection();
+        let pool = match &self.store {
+            LockStore::Redis(pool) => pool,
+            #[cfg(test)]
+            LockStore::Memory(locks) => {
+                let mut locks = locks.lock().unwrap();
+                match locks.get_mut(&self.resource) {
+                    Some((value, expires_at))
+                        if *value == self.lock_value && *expires_at > Instant::now() =>
+                    {
+                        self.ttl += additional_ttl;
+                        *expires_at = Instant::now() + self.ttl;
+                        return Ok(());
+                    }
+                    _ => {
+                        return Err(StorageError::RedisError(
+                            "Lock no longer owned by this guard".to_string(),
+                        ))
+                    }
+                }
+            }
+        };
+        let mut conn = pool.get_connection();
         let key = format!("lock:{}", self.resource);
 
         // Check if we still own the lock
@@ -63,6 +96,11 @@ impl LockGuard {
         Ok(())
     }
 
+    /// Restart the lock's TTL from now, keeping it while its holder still works
+    pub async fn renew(&mut self) -> Result<(), StorageError> {
+        self.extend(Duration::ZERO).await
+    }
+
     /// Get the resource name this lock guards
     pub fn resource(&self) -> &str {
         &self.resource
@@ -72,14 +110,33 @@ impl LockGuard {
     pub fn elapsed(&self) -> Duration {
         self.acquired_at.elapsed()
     }
+
+    /// Get the lock's TTL
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
 }
 
 impl Drop for LockGuard {
     fn drop(&mut self) {
+        let pool = match &self.store {
+            LockStore::Redis(pool) => pool.clone(),
+            #[cfg(test)]
+            LockStore::Memory(locks) => {
+                let mut locks = locks.lock().unwrap();
+                if locks
+                    .get(&self.resource)
+                    .is_some_and(|(value, _)| *value == self.lock_value)
+                {
+                    locks.remove(&self.resource);
+                }
+                return;
+            }
+        };
+
         // Release the lock asynchronously
         let resource = self.resource.clone();
         let lock_value = self.lock_value.clone();
-        let pool = self.pool.clone();
 
         tokio::spawn(async move {
             if let Err(e) = release_lock(&pool, &resource, &lock_value).await {
@@ -152,7 +209,7 @@ impl RedLock {
             Ok(LockGuard {
                 resource: resource.to_string(),
                 lock_value,
-                pool: self.pool.clone(),
+                store: LockStore::Redis(self.pool.clone()),
                 acquired_at: Instant::now(),
                 ttl,
             })
@@ -252,6 +309,54 @@ async fn release_lock(
     Ok(())
 }
 
+/// Value and expiry of each lock held in memory, by resource
+#[cfg(test)]
+type MemoryLocks = HashMap<String, (String, Instant)>;
+
+/// Lock kept in memory, expiring like the Redis one, for tests of code taking locks
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub(crate) struct MemoryLock {
+    locks: Arc<Mutex<MemoryLocks>>,
+}
+
+#[cfg(test)]
+impl MemoryLock {
+    /// Let the lock on `resource` expire, as if its holder had stopped renewing it
+    pub(crate) fn expire(&self, resource: &str) {
+        self.locks.lock().unwrap().remove(resource);
+    }
+}
+
+#[cfg(test)]
+#[async_trait]
+impl DistributedLock for MemoryLock {
+    async fn acquire(&self, resource: &str, ttl: Duration) -> Result<LockGuard, StorageError> {
+        let lock_value = Uuid::new_v4().to_string();
+        let mut locks = self.locks.lock().unwrap();
+        if locks
+            .get(resource)
+            .is_some_and(|(_, expires_at)| *expires_at > Instant::now())
+        {
+            return Err(StorageError::RedisError(format!(
+                "Lock already held for resource: {}",
+                resource
+            )));
+        }
+        locks.insert(
+            resource.to_string(),
+            (lock_value.clone(), Instant::now() + ttl),
+        );
+        Ok(LockGuard {
+            resource: resource.to_string(),
+            lock_value,
+            store: LockStore::Memory(Arc::clone(&self.locks)),
+            acquired_at: Instant::now(),
+            ttl,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +368,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
         let pool = RedisPool::new(&config).await.unwrap();
         let lock = RedLock::new(pool);
@@ -288,6 +395,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
         let pool = RedisPool::new(&config).await.unwrap();
         let lock1 = RedLock::new(pool.clone());
@@ -311,6 +420,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
         let pool = RedisPool::new(&config).await.unwrap();
         let lock = RedLock::new(pool);
@@ -331,6 +442,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
         let pool = RedisPool::new(&config).await.unwrap();
         let lock = RedLock::new(pool.clone());
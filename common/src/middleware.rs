@@ -293,6 +293,11 @@ impl IntoResponse for AuthError {
                 "INVALID_CREDENTIALS",
                 "Invalid username or password",
             ),
+            AuthError::AccountDisabled => (
+                StatusCode::UNAUTHORIZED,
+                "ACCOUNT_DISABLED",
+                "User account is disabled",
+            ),
             AuthError::InvalidToken(_) => (
                 StatusCode::UNAUTHORIZED,
                 "INVALID_TOKEN",
@@ -349,6 +354,8 @@ mod tests {
             permissions: vec!["job:read".to_string()],
             exp: 0,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -414,6 +421,8 @@ mod tests {
             permissions: vec!["job:read".to_string(), "job:write".to_string()],
             exp: 0,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
         let user = AuthenticatedUser(claims);
 
@@ -429,6 +438,8 @@ mod tests {
             permissions: vec!["job:read".to_string()],
             exp: 0,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
         let user = AuthenticatedUser(claims);
 
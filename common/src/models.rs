@@ -1,8 +1,9 @@
+use crate::step_env::StepEnv;
 use chrono::{DateTime, Utc};
 use chrono_tz::Tz;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use sqlx::FromRow;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::str::FromStr;
 use uuid::Uuid;
 
@@ -40,18 +41,386 @@ pub struct Job {
     pub triggers: TriggerConfig,
     #[serde(default)]
     pub enabled: bool,
+    /// The scheduler stops creating runs while the job stays enabled: manual and
+    /// webhook triggers still run it
+    #[serde(default)]
+    pub paused: bool,
     pub timeout_seconds: i32,
     pub max_retries: i32,
     #[serde(default)]
     pub allow_concurrent: bool,
+    /// What happens when the job is due while a previous run is still active and
+    /// `allow_concurrent` is false
+    #[serde(default)]
+    #[sqlx(try_from = "String")]
+    pub collision_policy: CollisionPolicy,
+    /// Executions run one at a time in the order they were created: the worker holds
+    /// an execution back until every earlier one has finished, retries included
+    #[serde(default)]
+    pub strict_ordering: bool,
+    /// Queued executions of higher priority jobs are taken first
+    #[serde(default)]
+    #[sqlx(try_from = "String")]
+    pub priority: JobPriority,
+    /// Dead-letter runs failing at a dependency and replay them once it recovers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub dead_letter_policy: Option<DeadLetterPolicy>,
+    /// Most executions of the job running at once across all workers, and what
+    /// happens to the ones over the limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub concurrency_limit: Option<ConcurrencyLimit>,
+    /// Owning team and on-call contacts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(skip)]
+    pub owner: Option<JobOwner>,
+    /// Extra labels on the job's execution metrics, e.g. `domain=payments`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[sqlx(skip)]
+    pub metric_labels: BTreeMap<String, String>,
+    /// Parameter values to fan each scheduled run out over, e.g. `branch: [HN, HCM]`;
+    /// one execution per combination, with the parameters as variables
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    #[sqlx(skip)]
+    pub parameter_matrix: BTreeMap<String, Vec<String>>,
     #[sqlx(json)]
     pub definition: Option<serde_json::Value>,
+    /// Optimistic concurrency version, bumped on every update
+    #[serde(default = "default_job_version")]
+    pub version: i32,
+    /// The job is being retired: it warns on trigger, only its owners can edit it
+    /// and the scheduler disables it at `sunset_at`
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sunset_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecation_reason: Option<String>,
+    /// Tenant the job's usage is billed to: the tenant of the user who created it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
     #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
     #[serde(default = "Utc::now")]
     pub updated_at: DateTime<Utc>,
 }
 
+fn default_job_version() -> i32 {
+    1
+}
+
+/// CollisionPolicy decides what a due run of a non-concurrent job does while a
+/// previous run is still active
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionPolicy {
+    /// Drop the due run
+    #[default]
+    Skip,
+    /// Queue the due run to start once the previous run finishes; at most one run
+    /// waits at a time
+    QueueAfter,
+    /// Stop the previous run after its current step and start the due run once it
+    /// has stopped
+    CancelPrevious,
+}
+
+impl std::fmt::Display for CollisionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollisionPolicy::Skip => write!(f, "skip"),
+            CollisionPolicy::QueueAfter => write!(f, "queue_after"),
+            CollisionPolicy::CancelPrevious => write!(f, "cancel_previous"),
+        }
+    }
+}
+
+impl FromStr for CollisionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(CollisionPolicy::Skip),
+            "queue_after" => Ok(CollisionPolicy::QueueAfter),
+            "cancel_previous" => Ok(CollisionPolicy::CancelPrevious),
+            _ => Err(format!("Invalid collision policy: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for CollisionPolicy {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// JobPriority decides which queued executions workers take first
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    /// Bulk work that can wait, e.g. large file transfers
+    Low,
+    #[default]
+    Normal,
+    /// Taken ahead of everything else queued, e.g. settlement
+    High,
+}
+
+impl JobPriority {
+    /// Every priority, highest first
+    pub const ALL: [JobPriority; 3] = [JobPriority::High, JobPriority::Normal, JobPriority::Low];
+
+    pub fn is_normal(&self) -> bool {
+        *self == JobPriority::Normal
+    }
+}
+
+impl std::fmt::Display for JobPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobPriority::Low => write!(f, "low"),
+            JobPriority::Normal => write!(f, "normal"),
+            JobPriority::High => write!(f, "high"),
+        }
+    }
+}
+
+impl FromStr for JobPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "low" => Ok(JobPriority::Low),
+            "normal" => Ok(JobPriority::Normal),
+            "high" => Ok(JobPriority::High),
+            _ => Err(format!("Invalid job priority: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for JobPriority {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// DeadLetterPolicy dead-letters runs of a job that fail at a step with a
+/// `dependency`, linked to that dependency, instead of leaving them failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeadLetterPolicy {
+    /// Replay the dead letters once the dependency's circuit breaker closes again
+    #[serde(default)]
+    pub auto_replay: bool,
+    /// Most dead letters of the job replayed per recovery, oldest first
+    #[serde(default = "default_max_replays")]
+    pub max_replays: u32,
+}
+
+fn default_max_replays() -> u32 {
+    20
+}
+
+impl DeadLetterPolicy {
+    /// Largest accepted `max_replays`
+    pub const MAX_REPLAYS: u32 = 1000;
+
+    /// Validate the policy fields
+    pub fn validate(&self) -> Result<(), crate::errors::ValidationError> {
+        if self.max_replays == 0 || self.max_replays > Self::MAX_REPLAYS {
+            return Err(crate::errors::ValidationError::InvalidFieldValue {
+                field: "dead_letter_policy.max_replays".to_string(),
+                reason: format!("must be between 1 and {}", Self::MAX_REPLAYS),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// ConcurrencyLimit caps the executions of a job running at the same time
+///
+/// Workers share the count through Redis, so the limit holds across the fleet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConcurrencyLimit {
+    pub max_concurrent: u32,
+    #[serde(default)]
+    pub overflow: ConcurrencyOverflow,
+}
+
+impl ConcurrencyLimit {
+    /// Largest accepted `max_concurrent`
+    pub const MAX_CONCURRENT: u32 = 1000;
+
+    /// Validate the limit fields
+    pub fn validate(&self) -> Result<(), crate::errors::ValidationError> {
+        if self.max_concurrent == 0 || self.max_concurrent > Self::MAX_CONCURRENT {
+            return Err(crate::errors::ValidationError::InvalidFieldValue {
+                field: "concurrency_limit.max_concurrent".to_string(),
+                reason: format!("must be between 1 and {}", Self::MAX_CONCURRENT),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// ConcurrencyOverflow is what happens to an execution over the concurrency limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrencyOverflow {
+    /// The execution is `queued` and starts once a running one finishes
+    #[default]
+    Queue,
+    /// The execution ends as `rejected` without running
+    Reject,
+}
+
+/// JobOwner records who is responsible for a job and how to reach them
+///
+/// At least one contact (email or Slack) is required so a failing job always
+/// has someone to page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobOwner {
+    pub team: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contact_email: Option<String>,
+    /// Slack channel (`#channel`) or user handle (`@user`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slack: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runbook_url: Option<String>,
+}
+
+impl JobOwner {
+    /// Longest accepted team name
+    pub const MAX_TEAM_LENGTH: usize = 100;
+
+    /// Whether `email` is the owner's contact address (case-insensitive)
+    pub fn is_contact(&self, email: &str) -> bool {
+        self.contact_email
+            .as_deref()
+            .is_some_and(|contact| contact.eq_ignore_ascii_case(email.trim()))
+    }
+
+    /// Validate the owner fields
+    pub fn validate(&self) -> Result<(), crate::errors::ValidationError> {
+        use crate::errors::ValidationError;
+
+        let invalid = |field: &str, reason: &str| ValidationError::InvalidFieldValue {
+            field: format!("owner.{}", field),
+            reason: reason.to_string(),
+        };
+
+        let team = self.team.trim();
+        if team.is_empty() {
+            return Err(ValidationError::MissingField("owner.team".to_string()));
+        }
+        if team.len() > Self::MAX_TEAM_LENGTH {
+            return Err(invalid("team", "must be at most 100 characters"));
+        }
+
+        if self.contact_email.is_none() && self.slack.is_none() {
+            return Err(ValidationError::MissingField(
+                "owner.contact_email or owner.slack".to_string(),
+            ));
+        }
+
+        if let Some(email) = &self.contact_email {
+            let valid = email.split_once('@').is_some_and(|(local, domain)| {
+                !local.is_empty()
+                    && domain.contains('.')
+                    && !domain.starts_with('.')
+                    && !domain.ends_with('.')
+                    && !domain.contains('@')
+            });
+            if !valid || email.chars().any(char::is_whitespace) {
+                return Err(invalid("contact_email", "must be a valid email address"));
+            }
+        }
+
+        if let Some(slack) = &self.slack {
+            let valid = (slack.starts_with('#') || slack.starts_with('@'))
+                && slack.len() > 1
+                && !slack.chars().any(char::is_whitespace);
+            if !valid {
+                return Err(invalid(
+                    "slack",
+                    "must be a channel (#channel) or handle (@user)",
+                ));
+            }
+        }
+
+        if let Some(runbook_url) = &self.runbook_url {
+            let valid = reqwest::Url::parse(runbook_url)
+                .is_ok_and(|url| matches!(url.scheme(), "http" | "https") && url.has_host());
+            if !valid {
+                return Err(invalid("runbook_url", "must be an http(s) URL"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Most custom metric labels a job may declare
+pub const MAX_METRIC_LABELS: usize = 5;
+
+/// Longest accepted metric label value
+pub const MAX_METRIC_LABEL_VALUE_LENGTH: usize = 64;
+
+/// Labels every job metric already carries
+const RESERVED_METRIC_LABELS: [&str; 5] = ["job_id", "job_name", "reason", "le", "quantile"];
+
+/// Validate a job's custom metric labels
+///
+/// Names must be valid Prometheus label names and must not shadow the labels
+/// the job metrics already carry. Each label multiplies the series of every job
+/// metric, so only a handful are allowed.
+pub fn validate_metric_labels(
+    labels: &BTreeMap<String, String>,
+) -> Result<(), crate::errors::ValidationError> {
+    use crate::errors::ValidationError;
+
+    if labels.len() > MAX_METRIC_LABELS {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "metric_labels".to_string(),
+            reason: format!("at most {} labels are allowed", MAX_METRIC_LABELS),
+        });
+    }
+
+    for (name, value) in labels {
+        let invalid = |reason: &str| ValidationError::InvalidFieldValue {
+            field: format!("metric_labels.{}", name),
+            reason: reason.to_string(),
+        };
+
+        let mut chars = name.chars();
+        let valid_name = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name || name.starts_with("__") {
+            return Err(invalid(
+                "name must match [a-zA-Z_][a-zA-Z0-9_]* and not start with __",
+            ));
+        }
+        if RESERVED_METRIC_LABELS.contains(&name.as_str()) {
+            return Err(invalid("name is reserved"));
+        }
+        if value.trim().is_empty() {
+            return Err(invalid("value must not be empty"));
+        }
+        if value.len() > MAX_METRIC_LABEL_VALUE_LENGTH {
+            return Err(invalid("value must be at most 64 characters"));
+        }
+    }
+
+    Ok(())
+}
+
 /// JobStep represents a single step in a multi-step job
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JobStep {
@@ -60,12 +429,252 @@ pub struct JobStep {
     #[serde(rename = "type")]
     pub step_type: JobType,
     pub condition: Option<String>,
+    /// Where the job goes when the step fails after its retries; `fail_job` when
+    /// left out
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub on_failure: Option<String>,
+    pub on_failure: Option<OnFailure>,
+    /// Steps undoing this step's effects, e.g. deleting the file it uploaded; they
+    /// run in order when a later step fails the job, and not otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compensate_with: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub timeout_seconds: Option<i32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub retry_count: Option<i32>,
+    /// Stream the step's result into a storage object and keep only a summary of it
+    /// in the job context, for outputs too large to hold in memory
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stream_output: bool,
+    /// The output contains personal data; the PII purge scrubs or encrypts it once
+    /// `system_jobs.pii_retention_days` have passed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pii: bool,
+    /// External system the step depends on, e.g. `partner-sftp`; steps naming the
+    /// same dependency share one circuit breaker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependency: Option<String>,
+    /// Connection profile the step's endpoint and credentials come from, e.g.
+    /// `erp-prod`; see [`crate::connection_profile`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection: Option<String>,
+    /// Named lock the step holds while it runs, so steps of any job touching the
+    /// same resource run one at a time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acquire_lock: Option<StepLock>,
+    /// Keys of the job's state written when the step succeeds, e.g.
+    /// `last_processed_id: "{{steps.fetch.output.max_id}}"`; values may reference
+    /// the step's own output. They are staged until the whole execution succeeds
+    /// and committed together
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub set_state: BTreeMap<String, serde_json::Value>,
+    /// Environment variables of container, script and wasm steps, taken from job
+    /// variables or sensitive variables rather than spliced into the command or
+    /// source; see [`crate::step_env`]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, EnvSource>,
+}
+
+/// Where a step environment variable takes its value from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvSource {
+    /// A value with `{{...}}` references, e.g. `{{region}}`
+    Value(String),
+    /// A sensitive variable, by name; its value is masked in the step's logs, output
+    /// and errors
+    Secret(String),
+}
+
+/// StepLock is a distributed lock on a named resource, e.g. `ledger`, held by a
+/// step while it runs
+///
+/// The worker renews the lock every third of `ttl_seconds` while the step runs and
+/// fails the step if a renewal fails; a crashed worker's lock expires after
+/// `ttl_seconds`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepLock {
+    pub resource: String,
+    pub ttl_seconds: u32,
+    /// How long the step waits for the lock before failing
+    #[serde(default = "default_lock_wait_seconds")]
+    pub wait_timeout_seconds: u32,
+}
+
+fn default_lock_wait_seconds() -> u32 {
+    60
+}
+
+impl StepLock {
+    /// Largest accepted `ttl_seconds` and `wait_timeout_seconds`
+    pub const MAX_SECONDS: u32 = 86_400;
+
+    /// Validate the lock fields
+    pub fn validate(&self, step_id: &str) -> Result<(), crate::errors::ValidationError> {
+        let invalid =
+            |field: &str, reason: String| crate::errors::ValidationError::InvalidFieldValue {
+                field: format!("steps.{}.acquire_lock.{}", step_id, field),
+                reason,
+            };
+        if self.resource.trim().is_empty() {
+            return Err(invalid("resource", "must not be empty".to_string()));
+        }
+        if self.ttl_seconds == 0 || self.ttl_seconds > Self::MAX_SECONDS {
+            return Err(invalid(
+                "ttl_seconds",
+                format!("must be between 1 and {}", Self::MAX_SECONDS),
+            ));
+        }
+        if self.wait_timeout_seconds > Self::MAX_SECONDS {
+            return Err(invalid(
+                "wait_timeout_seconds",
+                format!("must be at most {}", Self::MAX_SECONDS),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// OnFailure routes a job past a step that failed after its retries
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// The job fails at the step; `stop` in older definitions
+    #[default]
+    #[serde(alias = "stop")]
+    FailJob,
+    /// The failure is recorded and the next step runs; `continue` in older definitions
+    #[serde(alias = "continue")]
+    ContinueNextStep,
+    /// The job carries on at a later step, skipping the steps in between
+    GotoStep(String),
+    /// The named steps run in order, then the job fails; they don't run otherwise
+    RunCompensation(Vec<String>),
+}
+
+/// Steps only run as another step's compensation
+pub fn compensation_step_ids(steps: &[JobStep]) -> HashSet<&str> {
+    steps
+        .iter()
+        .flat_map(|step| {
+            let routed = match &step.on_failure {
+                Some(OnFailure::RunCompensation(step_ids)) => step_ids.as_slice(),
+                _ => &[],
+            };
+            routed.iter().chain(&step.compensate_with)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Validate the steps' on_failure routes, compensation steps, locks, state keys and
+/// incremental sync sources
+///
+/// `goto_step` must name a later step that isn't a compensation step, so a
+/// failing job can't loop, and compensation steps must be other steps of the job.
+pub fn validate_step_flow(steps: &[JobStep]) -> Result<(), crate::errors::ValidationError> {
+    let compensation_steps = compensation_step_ids(steps);
+    for (index, step) in steps.iter().enumerate() {
+        let invalid =
+            |field: &str, reason: String| crate::errors::ValidationError::InvalidFieldValue {
+                field: format!("steps.{}.{}", step.id, field),
+                reason,
+            };
+        let position = |id: &str| steps.iter().position(|other| other.id == id);
+        let unknown_step = |step_ids: &[String]| {
+            step_ids
+                .iter()
+                .find(|id| **id == step.id || position(id).is_none())
+                .map(|id| format!("{} is not another step of the job", id))
+        };
+        match &step.on_failure {
+            Some(OnFailure::GotoStep(target)) => {
+                if position(target).is_none_or(|target| target <= index) {
+                    return Err(invalid(
+                        "on_failure",
+                        format!("{} is not a later step", target),
+                    ));
+                }
+                if compensation_steps.contains(target.as_str()) {
+                    return Err(invalid(
+                        "on_failure",
+                        format!("{} only runs as compensation", target),
+                    ));
+                }
+            }
+            Some(OnFailure::RunCompensation(step_ids)) => {
+                if step_ids.is_empty() {
+                    return Err(invalid(
+                        "on_failure",
+                        "names no compensation steps".to_string(),
+                    ));
+                }
+                if let Some(reason) = unknown_step(step_ids) {
+                    return Err(invalid("on_failure", reason));
+                }
+            }
+            Some(OnFailure::FailJob | OnFailure::ContinueNextStep) | None => {}
+        }
+        if let Some(reason) = unknown_step(&step.compensate_with) {
+            return Err(invalid("compensate_with", reason));
+        }
+        if let Some(lock) = &step.acquire_lock {
+            lock.validate(&step.id)?;
+        }
+        if !step.env.is_empty()
+            && !matches!(
+                step.step_type,
+                JobType::Container { .. } | JobType::Script { .. } | JobType::Wasm { .. }
+            )
+        {
+            return Err(invalid(
+                "env",
+                "is only supported for container, script and wasm steps".to_string(),
+            ));
+        }
+        if let Some(name) = step
+            .env
+            .keys()
+            .find(|name| !crate::step_env::is_valid_name(name))
+        {
+            return Err(invalid(
+                "env",
+                format!("{} is not a valid environment variable name", name),
+            ));
+        }
+        if let Some(key) = step
+            .set_state
+            .keys()
+            .find(|key| !JobStateEntry::is_valid_key(key))
+        {
+            return Err(invalid(
+                "set_state",
+                format!("{} is not a valid state key", key),
+            ));
+        }
+        if let JobType::IncrementalSync {
+            watermark_key,
+            source,
+            ..
+        } = &step.step_type
+        {
+            if !JobStateEntry::is_valid_key(watermark_key) {
+                return Err(invalid(
+                    "type.watermark_key",
+                    format!("{} is not a valid state key", watermark_key),
+                ));
+            }
+            if !matches!(
+                **source,
+                JobType::HttpRequest { .. } | JobType::DatabaseQuery { .. }
+            ) {
+                return Err(invalid(
+                    "type.source",
+                    "must be an http_request or database_query".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
 }
 
 /// TriggerConfig defines how a job can be triggered
@@ -102,16 +711,79 @@ pub enum Schedule {
         timezone: Tz,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         end_date: Option<DateTime<Utc>>,
+        /// Runs that haven't started this long after their due time are expired
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        valid_for_seconds: Option<u32>,
+        /// Business-day calendar the runs follow
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        calendar: Option<ScheduleCalendar>,
+        /// Runs missed while no scheduler was running; `fire_immediately` when left out
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        misfire_policy: Option<MisfirePolicy>,
     },
     FixedDelay {
         delay_seconds: u32,
     },
     FixedRate {
         interval_seconds: u32,
+        /// Runs missed while no scheduler was running; `fire_immediately` when left out
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        misfire_policy: Option<MisfirePolicy>,
     },
     OneTime {
         execute_at: DateTime<Utc>,
+        /// The run is expired if it hasn't started this long after `execute_at`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        valid_for_seconds: Option<u32>,
+        /// A run missed while no scheduler was running; `fire_immediately` when left out
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        misfire_policy: Option<MisfirePolicy>,
     },
+    /// An ISO 8601 repeating interval, e.g. `R5/2024-01-01T00:00:00Z/P1D`
+    Iso8601 {
+        expression: String,
+        /// Runs that haven't started this long after their due time are expired
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        valid_for_seconds: Option<u32>,
+        /// Runs missed while no scheduler was running; `fire_immediately` when left out
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        misfire_policy: Option<MisfirePolicy>,
+    },
+}
+
+/// ScheduleCalendar names the calendar a cron schedule follows and how
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScheduleCalendar {
+    pub name: String,
+    #[serde(default)]
+    pub policy: CalendarPolicy,
+}
+
+/// CalendarPolicy decides what happens to a run due on a day its calendar closes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarPolicy {
+    /// Runs due on weekends and holidays are skipped
+    #[default]
+    BusinessDaysOnly,
+    /// Runs due on holidays are skipped; weekends are left to the cron expression
+    SkipHolidays,
+    /// Runs due on a weekend or holiday move to the next business day, same time
+    NextBusinessDay,
+}
+
+/// MisfirePolicy decides what happens to runs whose time passed while no scheduler
+/// was running, e.g. during an outage or a deployment
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MisfirePolicy {
+    /// The missed runs are made up for by one run as soon as a scheduler is back
+    #[default]
+    FireImmediately,
+    /// The missed runs are dropped; the job runs again at its next time
+    Skip,
+    /// Every missed run is made, oldest first
+    CatchUpAll,
 }
 
 /// JobType defines the type of operation a job step performs
@@ -120,13 +792,17 @@ pub enum Schedule {
 pub enum JobType {
     HttpRequest {
         method: HttpMethod,
+        /// A path under the base URL when the step uses a connection
         url: String,
         headers: HashMap<String, String>,
         body: Option<String>,
         auth: Option<HttpAuth>,
     },
     DatabaseQuery {
+        /// Both may be left out when the step uses a connection
+        #[serde(default)]
         database_type: DatabaseType,
+        #[serde(default)]
         connection_string: String,
         query: String,
         query_type: QueryType,
@@ -140,79 +816,611 @@ pub enum JobType {
     },
     Sftp {
         operation: SftpOperation,
+        /// Host, port and auth may be left out when the step uses a connection
+        #[serde(default)]
         host: String,
+        #[serde(default = "default_sftp_port")]
         port: u16,
-        auth: SftpAuth,
+        #[serde(default)]
+        auth: Option<SftpAuth>,
         remote_path: String,
         local_path: Option<String>,
         options: SftpOptions,
     },
-}
-
-/// HttpMethod represents HTTP request methods
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "UPPERCASE")]
-pub enum HttpMethod {
-    Get,
-    Post,
-    Put,
-}
-
-/// HttpAuth represents HTTP authentication methods
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum HttpAuth {
-    Basic {
-        username: String,
-        password: String,
+    /// GraphQL query or mutation sent as an HTTP POST
+    #[serde(rename = "graphql")]
+    GraphQL {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        /// May be left out when `persisted_query` is registered with the server
+        #[serde(default)]
+        query: Option<String>,
+        #[serde(default)]
+        operation_name: Option<String>,
+        /// A string that is a single `{{...}}` reference takes the referenced value as is
+        #[serde(default)]
+        variables: serde_json::Map<String, serde_json::Value>,
+        #[serde(default)]
+        auth: Option<HttpAuth>,
+        #[serde(default)]
+        persisted_query: Option<PersistedQuery>,
     },
-    Bearer {
-        token: String,
+    /// Email sent over SMTP, optionally with files from storage attached
+    Email {
+        smtp: SmtpConfig,
+        from: String,
+        to: Vec<String>,
+        #[serde(default)]
+        cc: Vec<String>,
+        #[serde(default)]
+        bcc: Vec<String>,
+        #[serde(default)]
+        reply_to: Option<String>,
+        /// `${VAR}` placeholders and `{{...}}` references are filled in from the context
+        subject: String,
+        body: String,
+        /// Send the body as HTML instead of plain text
+        #[serde(default)]
+        html: bool,
+        #[serde(default)]
+        attachments: Vec<EmailAttachment>,
     },
-    OAuth2 {
-        client_id: String,
-        client_secret: String,
-        token_url: String,
+    /// Message posted to a Slack or Microsoft Teams incoming webhook
+    Notification {
+        platform: ChatPlatform,
+        webhook_url: String,
+        /// Headline; also the preview text of the notification
+        title: String,
+        /// Markdown body
+        #[serde(default)]
+        text: Option<String>,
+        /// Label/value pairs, e.g. rows processed or duration
+        #[serde(default)]
+        fields: Vec<NotificationField>,
+        /// Sent as is instead of the generated message: Slack message JSON with
+        /// `blocks`, or a Teams message with an adaptive card attachment
+        #[serde(default)]
+        payload: Option<serde_json::Value>,
+    },
+    /// Message published to an MQTT broker
+    MqttPublish {
+        broker: MqttBroker,
+        topic: String,
+        payload: String,
+        #[serde(default)]
+        qos: MqttQos,
+        /// Keep the message on the broker for clients that subscribe later
+        #[serde(default)]
+        retain: bool,
+    },
+    /// Copy, move, delete, list or presign objects in an S3-compatible bucket
+    ObjectStorage {
+        bucket: ObjectStorageBucket,
+        operation: ObjectStorageOperation,
+    },
+    /// One-shot Docker container; the step fails when it exits non-zero
+    Container {
+        image: String,
+        /// Replaces the image's default command
+        #[serde(default)]
+        command: Vec<String>,
+        #[serde(default)]
+        env: HashMap<String, String>,
+        /// Context files copied into the container before it starts
+        #[serde(default)]
+        mounts: Vec<ContainerMount>,
+        /// Defaults to one hour
+        #[serde(default)]
+        timeout_seconds: Option<u64>,
+        #[serde(default)]
+        memory_mb: Option<u64>,
+    },
+    /// WebAssembly plugin from storage, called with the step config and context
+    Wasm {
+        /// Storage path of the module: `plugins/...` for shared plugins, otherwise
+        /// a file of the job
+        module: String,
+        /// Passed to the plugin as is
+        #[serde(default)]
+        config: serde_json::Value,
+        /// Instruction budget; defaults to one billion
+        #[serde(default)]
+        fuel: Option<u64>,
+        /// Defaults to 64 MB
+        #[serde(default)]
+        memory_mb: Option<u64>,
+    },
+    /// Rhai script that can read and change the job variables
+    Script {
+        source: String,
+        /// Operation budget; defaults to ten million
+        #[serde(default)]
+        max_operations: Option<u64>,
     },
+    /// Render a Handlebars HTML template with the job context into a PDF file
+    PdfReport {
+        /// Inline template; either this or `template_path` is required
+        #[serde(default)]
+        template: Option<String>,
+        /// Template file in storage, relative paths are files of the execution
+        #[serde(default)]
+        template_path: Option<String>,
+        /// Where the PDF is written; relative paths are files of the execution
+        output_path: String,
+        #[serde(default)]
+        landscape: bool,
+        /// Page margin on all sides; defaults to 15mm
+        #[serde(default)]
+        margin_mm: Option<f32>,
+    },
+    /// Reshape a JSON or XML document with JSONPath; the result is the step output
+    Transform {
+        /// `{{...}}` reference to the document, e.g. `{{steps.fetch.output.body}}`;
+        /// either this or `input_path` is required
+        #[serde(default)]
+        input: Option<String>,
+        /// File in storage holding the document; relative paths are files of the execution
+        #[serde(default)]
+        input_path: Option<String>,
+        #[serde(default)]
+        input_format: TransformInputFormat,
+        transformation: Transformation,
+    },
+    /// Check a document against a JSON Schema, or CSV data against column rules;
+    /// the step fails with the list of violations
+    Validate {
+        /// `{{...}}` reference to the data, e.g. `{{steps.fetch.body}}`; either this
+        /// or `input_path` is required
+        #[serde(default)]
+        input: Option<String>,
+        /// File in storage holding the data; relative paths are files of the execution
+        #[serde(default)]
+        input_path: Option<String>,
+        rules: ValidationRules,
+        /// Violations listed in the error; all of them are counted. Defaults to 50
+        #[serde(default)]
+        max_violations: Option<usize>,
+    },
+    /// Checksum of a file in storage; with an expected value the step fails on a
+    /// mismatch, e.g. to verify a `.sha256` sidecar after an SFTP download
+    Checksum {
+        /// File in storage; relative paths are files of the execution
+        path: String,
+        #[serde(default)]
+        algorithm: ChecksumAlgorithm,
+        /// Expected hex digest; may be a `{{...}}` reference
+        #[serde(default)]
+        expected: Option<String>,
+        /// File holding the expected digest, as written by `sha256sum`/`md5sum` or
+        /// just the hex digest; at most one of this and `expected`
+        #[serde(default)]
+        expected_path: Option<String>,
+    },
+    /// Query or API call for the records changed since a high-watermark kept in the
+    /// job state; the watermark advances to the largest value returned only once the
+    /// whole execution succeeded, so a failed run fetches the same records again
+    IncrementalSync {
+        /// Job state key holding the watermark
+        watermark_key: String,
+        /// Watermark of the first run, before the job state holds one, e.g. `0` or
+        /// `"1970-01-01T00:00:00Z"`
+        initial_watermark: serde_json::Value,
+        /// An http_request or database_query step type, filtered with `{{watermark}}`;
+        /// raw SQL binds it as a parameter and URLs get it percent-encoded
+        source: Box<JobType>,
+        /// Dotted path to the records in the source's output; defaults to `rows` for
+        /// a query and `body` for an API call
+        #[serde(default)]
+        records_path: Option<String>,
+        /// Field of each record whose largest value becomes the next watermark,
+        /// e.g. `updated_at` or `id`
+        watermark_field: String,
+    },
+    /// Built-in maintenance task; only admins may add these steps
+    System { task: SystemTask },
 }
 
-/// DatabaseType represents supported database systems
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Digest computed by a checksum step
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
-pub enum DatabaseType {
-    #[serde(rename = "postgresql")]
-    PostgreSQL,
-    #[serde(rename = "mysql")]
-    MySQL,
-    #[serde(rename = "oracle")]
-    Oracle,
+pub enum ChecksumAlgorithm {
+    Md5,
+    #[default]
+    Sha256,
 }
 
-/// QueryType represents types of database queries
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum QueryType {
-    RawSql,
-    StoredProcedure {
-        procedure_name: String,
-        parameters: Vec<String>,
+impl std::fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChecksumAlgorithm::Md5 => write!(f, "md5"),
+            ChecksumAlgorithm::Sha256 => write!(f, "sha256"),
+        }
+    }
+}
+
+/// What a validation step checks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ValidationRules {
+    /// JSON Schema (draft 2020-12 or draft-07); `$ref` may point within the schema
+    JsonSchema { schema: serde_json::Value },
+    /// CSV text, or rows as objects from an earlier step, checked column by column
+    CsvColumns {
+        columns: Vec<CsvColumnRule>,
+        #[serde(default = "default_csv_delimiter")]
+        delimiter: char,
+        /// Columns without a rule are violations
+        #[serde(default)]
+        strict_columns: bool,
     },
 }
 
-/// FileOperation represents file processing operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum FileOperation {
-    Read,
-    Write,
+fn default_csv_delimiter() -> char {
+    ','
 }
 
-/// FileFormat represents supported file formats
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-pub enum FileFormat {
-    Excel,
-    Csv { delimiter: char },
+/// Rule for one CSV column; empty values only have to satisfy `required`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CsvColumnRule {
+    pub name: String,
+    /// The column must exist and no value may be empty
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default, rename = "type")]
+    pub value_type: Option<CsvValueType>,
+    /// `chrono` format of `date` values; defaults to `%Y-%m-%d`
+    #[serde(default)]
+    pub date_format: Option<String>,
+    /// Regular expression the whole value must match
+    #[serde(default)]
+    pub pattern: Option<String>,
+    #[serde(default)]
+    pub allowed_values: Vec<String>,
+    /// Bounds of `integer` and `number` values
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    #[serde(default)]
+    pub unique: bool,
+}
+
+/// Type a CSV column's values must parse as
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvValueType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Date,
+}
+
+/// Format of a transformation step's document
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransformInputFormat {
+    #[default]
+    Json,
+    /// Converted to JSON first: attributes become `@name` fields and mixed text `#text`
+    Xml,
+}
+
+/// What a transformation step computes from its document
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "language", rename_all = "snake_case")]
+pub enum Transformation {
+    /// One JSONPath expression; a path of names and indexes gives the node itself,
+    /// any other path the array of matched nodes
+    #[serde(rename = "jsonpath")]
+    JsonPath { expression: String },
+    /// An object with one JSONPath expression per field
+    #[serde(rename = "jsonpath_mapping")]
+    JsonPathMapping { fields: BTreeMap<String, String> },
+}
+
+/// File copied into a container step's container
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContainerMount {
+    /// Internal storage path, e.g. a file an earlier step wrote
+    pub source: String,
+    /// Absolute path in the container
+    pub target: String,
+}
+
+/// How a GraphQL step uses persisted queries
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PersistedQuery {
+    /// Send the SHA-256 hash of the query first, and the full text only when the
+    /// server has not cached it yet
+    Automatic,
+    /// Query registered with the server ahead of time; only its hash is sent
+    Registered { sha256_hash: String },
+}
+
+/// S3-compatible bucket an object storage step works on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectStorageBucket {
+    pub name: String,
+    /// Defaults to AWS S3; set for MinIO, Ceph, R2 and the like
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default = "default_object_storage_region")]
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Address objects as `endpoint/bucket/key` instead of `bucket.endpoint/key`,
+    /// as most self-hosted servers expect
+    #[serde(default)]
+    pub path_style: bool,
+}
+
+fn default_object_storage_region() -> String {
+    "us-east-1".to_string()
+}
+
+/// Where an object is copied or moved from or to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ObjectLocation {
+    /// File in the internal execution storage, e.g. a file an earlier step wrote
+    Internal { path: String },
+    /// Object in the step's bucket
+    Bucket { key: String },
+}
+
+/// Operation of an object storage step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ObjectStorageOperation {
+    Copy {
+        source: ObjectLocation,
+        destination: ObjectLocation,
+    },
+    /// Copy, then delete the source
+    Move {
+        source: ObjectLocation,
+        destination: ObjectLocation,
+    },
+    Delete {
+        key: String,
+    },
+    List {
+        #[serde(default)]
+        prefix: Option<String>,
+        /// Defaults to 1000
+        #[serde(default)]
+        max_keys: Option<usize>,
+    },
+    /// Time-limited URL that needs no credentials
+    Presign {
+        key: String,
+        #[serde(default)]
+        method: PresignMethod,
+        /// Defaults to one hour; at most seven days
+        #[serde(default)]
+        expires_in_seconds: Option<u64>,
+    },
+}
+
+/// What a presigned URL allows
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PresignMethod {
+    /// Download the object
+    #[default]
+    Get,
+    /// Upload the object
+    Put,
+}
+
+/// MQTT broker an MQTT publish step connects to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MqttBroker {
+    pub host: String,
+    /// Defaults to 8883 with TLS and 1883 without
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Defaults to a random ID per run
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Connect over TLS; verified against the system roots unless a CA is given
+    #[serde(default)]
+    pub tls: Option<MqttTls>,
+}
+
+/// TLS settings of an MQTT connection, as PEM
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MqttTls {
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    /// Client certificate and key for brokers that authenticate devices by certificate
+    #[serde(default)]
+    pub client_cert: Option<String>,
+    #[serde(default)]
+    pub client_key: Option<String>,
+}
+
+/// MQTT delivery guarantee
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttQos {
+    /// QoS 0: sent once, not acknowledged
+    #[default]
+    AtMostOnce,
+    /// QoS 1: acknowledged by the broker, may be delivered twice
+    AtLeastOnce,
+    /// QoS 2: delivered exactly once through the four-step handshake
+    ExactlyOnce,
+}
+
+/// Chat service a notification step posts to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChatPlatform {
+    Slack,
+    Teams,
+}
+
+/// Label/value pair shown in a notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationField {
+    pub label: String,
+    pub value: String,
+}
+
+/// SMTP server an email step sends through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to 587 for STARTTLS, 465 for implicit TLS and 25 without TLS
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+/// How the SMTP connection is encrypted
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpTls {
+    /// Upgrade a plain connection with STARTTLS, failing if the server doesn't offer it
+    #[default]
+    Starttls,
+    /// TLS from the first byte (SMTPS)
+    Implicit,
+    /// Unencrypted; only for relays on a trusted network
+    None,
+}
+
+/// File from the storage service attached to an email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailAttachment {
+    /// Storage path, e.g. a report written by a file processing step
+    pub path: String,
+    /// Name shown to the recipient; defaults to the last segment of `path`
+    #[serde(default)]
+    pub filename: Option<String>,
+    /// Defaults to a type guessed from the file extension
+    #[serde(default)]
+    pub content_type: Option<String>,
+}
+
+/// HttpMethod represents HTTP request methods
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+}
+
+/// HttpAuth represents HTTP authentication methods
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HttpAuth {
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+    OAuth2 {
+        client_id: String,
+        client_secret: String,
+        token_url: String,
+    },
+}
+
+/// DatabaseType represents supported database systems
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseType {
+    #[default]
+    #[serde(rename = "postgresql")]
+    PostgreSQL,
+    #[serde(rename = "mysql")]
+    MySQL,
+    #[serde(rename = "oracle")]
+    Oracle,
+    #[serde(rename = "mongodb")]
+    MongoDB,
+    /// Local database file; the connection string is its path or a `sqlite:` URL
+    #[serde(rename = "sqlite")]
+    Sqlite,
+}
+
+/// QueryType represents types of database queries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QueryType {
+    RawSql,
+    StoredProcedure {
+        procedure_name: String,
+        parameters: Vec<String>,
+    },
+    /// MongoDB operation on one collection; the step's `query` holds its body as
+    /// Extended JSON
+    Mongo {
+        collection: String,
+        operation: MongoOperation,
+    },
+}
+
+/// MongoOperation represents operations a MongoDB step can run
+///
+/// Query bodies:
+/// - `find`: `{"filter": {..}, "projection": {..}, "sort": {..}, "limit": 100}`
+/// - `aggregate`: `{"pipeline": [..]}`
+/// - `insert`: `{"documents": [..]}`
+/// - `update`: `{"filter": {..}, "update": {..} or [..], "upsert": false}` (all matches)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MongoOperation {
+    Find,
+    Aggregate,
+    Insert,
+    Update,
+}
+
+/// FileOperation represents file processing operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOperation {
+    Read,
+    Write,
+    /// Pack `source_path` and `options.source_paths` into the archive at
+    /// `destination_path`
+    Compress,
+    /// Unpack the archive at `source_path` under the directory `destination_path`
+    Extract,
+}
+
+/// FileFormat represents supported file formats
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FileFormat {
+    Excel,
+    Csv {
+        delimiter: char,
+    },
+    /// Rows are JSON objects keyed by column name
+    Parquet,
+    Zip,
+    TarGz,
 }
 
 /// FileProcessingOptions contains options for file processing
@@ -222,6 +1430,19 @@ pub struct FileProcessingOptions {
     pub sheet_index: Option<usize>,
     pub transformations: Vec<DataTransformation>,
     pub streaming: bool,
+    /// More files to compress besides `source_path`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_paths: Vec<String>,
+    /// Zip password: archives are created with AES-256, extraction also accepts
+    /// legacy ZipCrypto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Parquet columns to read or write; all columns when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+    /// Rows the Parquet schema is inferred from when writing; all rows by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema_sample_rows: Option<usize>,
 }
 
 /// DataTransformation represents data transformation rules
@@ -242,7 +1463,7 @@ pub enum SftpOperation {
 }
 
 /// SftpAuth represents SFTP authentication methods
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum SftpAuth {
     Password {
@@ -255,6 +1476,10 @@ pub enum SftpAuth {
     },
 }
 
+pub(crate) fn default_sftp_port() -> u16 {
+    22
+}
+
 /// SftpOptions contains options for SFTP operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SftpOptions {
@@ -264,6 +1489,42 @@ pub struct SftpOptions {
     pub verify_host_key: bool,
 }
 
+/// SystemTask is a maintenance task run by the built-in system jobs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum SystemTask {
+    /// Report dead-lettered executions per job
+    DlqReport,
+    /// Delete finished executions and delivered webhooks older than `retention_days`;
+    /// dead-lettered executions are kept
+    RetentionCleanup { retention_days: u32 },
+    /// Remove stored files of jobs and executions that no longer exist
+    StorageGc,
+    /// Warn about certificates, variables and TLS materials expiring within
+    /// `warn_days`, including sensitive variables reaching `secret_max_age_days`
+    /// without rotation
+    ExpiryCheck {
+        /// PEM files on the worker, e.g. the database CA certificate
+        #[serde(default)]
+        certificate_paths: Vec<String>,
+        warn_days: u32,
+        secret_max_age_days: u32,
+    },
+    /// Round trip through the database and file storage; a failing or silent canary
+    /// means the pipeline itself is broken
+    Canary,
+    /// Scrub the PII-tagged step outputs of executions finished more than
+    /// `retention_days` ago, or encrypt them to age `recipients` if any are given
+    PiiPurge {
+        retention_days: u32,
+        #[serde(default)]
+        recipients: Vec<String>,
+    },
+    /// Probe the jobs queued for verification after a connection profile changed,
+    /// recording which would break with it
+    ConnectionVerification,
+}
+
 // ============================================================================
 // JobExecution Models
 // ============================================================================
@@ -288,6 +1549,11 @@ pub struct JobExecution {
     pub result: Option<String>,
     pub error: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Version of the job definition when the execution was created; see
+    /// `GET /api/jobs/{id}?version=N`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub job_version: Option<i32>,
 }
 
 impl JobExecution {
@@ -317,6 +1583,7 @@ impl JobExecution {
             result: None,
             error: None,
             created_at: Utc::now(),
+            job_version: None,
         }
     }
 
@@ -349,6 +1616,7 @@ impl JobExecution {
             result: None,
             error: None,
             created_at: Utc::now(),
+            job_version: None,
         }
     }
 
@@ -386,9 +1654,43 @@ impl JobExecution {
             result: None,
             error: None,
             created_at: Utc::now(),
+            job_version: None,
+        }
+    }
+
+    /// Create a new pending execution replaying the run a schedule was due to make
+    /// at `scheduled_for`
+    ///
+    /// The idempotency key is derived from the due time, so each window of a job
+    /// is backfilled at most once.
+    pub fn new_backfill(job_id: Uuid, scheduled_for: DateTime<Utc>, backfill_id: Uuid) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            job_id,
+            idempotency_key: Self::backfill_key(job_id, scheduled_for),
+            status: ExecutionStatus::Pending,
+            attempt: 1,
+            trigger_source: TriggerSource::Backfill,
+            trigger_metadata: Some(serde_json::json!({
+                SCHEDULED_FOR_METADATA_KEY: scheduled_for,
+                "backfill_id": backfill_id,
+            })),
+            current_step: None,
+            context: serde_json::json!({}),
+            started_at: None,
+            completed_at: None,
+            result: None,
+            error: None,
+            created_at: Utc::now(),
+            job_version: None,
         }
     }
 
+    /// Idempotency key of the backfill run of a job's window due at `scheduled_for`
+    pub fn backfill_key(job_id: Uuid, scheduled_for: DateTime<Utc>) -> String {
+        format!("backfill-{}-{}", job_id, scheduled_for.timestamp())
+    }
+
     /// Create a new execution with custom parameters (for advanced use cases)
     ///
     /// # Arguments
@@ -420,10 +1722,151 @@ impl JobExecution {
             result: None,
             error: None,
             created_at: Utc::now(),
+            job_version: None,
+        }
+    }
+
+    /// Move the execution to `next`, returning the status it left
+    ///
+    /// Entering `Running` stamps `started_at` unless the execution already started;
+    /// entering a terminal status stamps `completed_at`, which a run dead-lettered
+    /// after it failed keeps from when it failed.
+    pub fn transition_to(
+        &mut self,
+        next: ExecutionStatus,
+    ) -> Result<ExecutionStatus, IllegalTransition> {
+        if !self.status.can_transition_to(&next) {
+            return Err(IllegalTransition {
+                from: self.status.clone(),
+                to: next,
+            });
+        }
+
+        let now = Utc::now();
+        if next == ExecutionStatus::Running {
+            self.started_at.get_or_insert(now);
+        }
+        if next.is_terminal() && !self.status.is_terminal() {
+            self.completed_at = Some(now);
+        }
+        Ok(std::mem::replace(&mut self.status, next))
+    }
+
+    /// Due time the scheduler made the run for, if recorded
+    pub fn scheduled_for(&self) -> Option<DateTime<Utc>> {
+        self.trigger_metadata
+            .as_ref()
+            .and_then(|m| m.get(SCHEDULED_FOR_METADATA_KEY))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+    }
+
+    /// How long after its due time the run is starting
+    ///
+    /// Counts from `scheduled_for` when recorded, since the scheduler may create a
+    /// run late, e.g. catching up after downtime; from `created_at` otherwise.
+    pub fn start_delay(&self, now: DateTime<Utc>) -> chrono::Duration {
+        now - self.scheduled_for().unwrap_or(self.created_at)
+    }
+
+    /// Variable overrides supplied when the execution was triggered
+    pub fn variable_overrides(&self) -> HashMap<String, serde_json::Value> {
+        self.trigger_metadata
+            .as_ref()
+            .and_then(|m| m.get(VARIABLES_METADATA_KEY))
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Store variable overrides in the execution's trigger metadata
+    pub fn set_variable_overrides(&mut self, variables: &HashMap<String, serde_json::Value>) {
+        if variables.is_empty() {
+            return;
+        }
+        let value = serde_json::to_value(variables).unwrap_or_default();
+        match &mut self.trigger_metadata {
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(VARIABLES_METADATA_KEY.to_string(), value);
+            }
+            metadata => *metadata = Some(serde_json::json!({ VARIABLES_METADATA_KEY: value })),
+        }
+    }
+}
+
+/// Key under which variable overrides are stored in `JobExecution::trigger_metadata`
+pub const VARIABLES_METADATA_KEY: &str = "variables";
+
+/// Key under which the due time of a scheduled run is stored in
+/// `JobExecution::trigger_metadata`
+pub const SCHEDULED_FOR_METADATA_KEY: &str = "scheduled_for";
+
+/// Heartbeats a running execution may miss before the dashboard reports it as hung
+pub const MISSED_HEARTBEATS_BEFORE_HUNG: u32 = 3;
+
+/// Progress of a running execution, sent periodically by the worker while a step runs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExecutionProgress {
+    pub step_id: String,
+    /// Zero-based position of the current step
+    pub step_index: usize,
+    pub total_steps: usize,
+    /// Seconds since the execution started
+    pub elapsed_seconds: u64,
+    /// Seconds spent in the current step
+    pub step_elapsed_seconds: u64,
+    /// Overall completion: steps already completed plus the current step's reported
+    /// share, when either is known
+    pub percent: Option<u8>,
+    /// Rows or bytes reported by the current step's executor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step: Option<StepProgress>,
+}
+
+impl ExecutionProgress {
+    pub fn new(
+        step_id: String,
+        step_index: usize,
+        total_steps: usize,
+        elapsed_seconds: u64,
+        step_elapsed_seconds: u64,
+        step: Option<StepProgress>,
+    ) -> Self {
+        let step_fraction = step.as_ref().and_then(StepProgress::fraction);
+        let percent = match step_fraction {
+            Some(fraction) => {
+                Some(((step_index as f64 + fraction) * 100.0 / total_steps as f64) as u8)
+            }
+            None => (total_steps > 1).then(|| (step_index * 100 / total_steps) as u8),
+        };
+        Self {
+            step_id,
+            step_index,
+            total_steps,
+            elapsed_seconds,
+            step_elapsed_seconds,
+            percent,
+            step,
         }
     }
 }
 
+/// Whether a running execution has stopped sending heartbeats
+///
+/// Executions that never sent one are measured from when they started, so a worker
+/// that died before its first heartbeat is caught too.
+pub fn heartbeat_stale(
+    heartbeat_at: Option<DateTime<Utc>>,
+    started_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+    heartbeat_interval_seconds: u64,
+) -> bool {
+    let last_sign_of_life = match heartbeat_at.or(started_at) {
+        Some(at) => at,
+        None => return false,
+    };
+    let allowed = heartbeat_interval_seconds * MISSED_HEARTBEATS_BEFORE_HUNG as u64;
+    (now - last_sign_of_life).num_seconds() > allowed as i64
+}
+
 /// ExecutionStatus represents the status of a job execution
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -436,6 +1879,65 @@ pub enum ExecutionStatus {
     DeadLetter,
     Cancelling,
     Cancelled,
+    /// Didn't start within the start window of its schedule
+    Expired,
+    /// Waiting for a running execution of the job to finish, at its concurrency limit
+    Queued,
+    /// Over the job's concurrency limit, whose overflow is rejected
+    Rejected,
+}
+
+impl ExecutionStatus {
+    /// Statuses of finished executions
+    pub const TERMINAL: [ExecutionStatus; 7] = [
+        ExecutionStatus::Success,
+        ExecutionStatus::Failed,
+        ExecutionStatus::Timeout,
+        ExecutionStatus::DeadLetter,
+        ExecutionStatus::Cancelled,
+        ExecutionStatus::Expired,
+        ExecutionStatus::Rejected,
+    ];
+
+    /// Whether the execution has finished and its status will no longer change
+    pub fn is_terminal(&self) -> bool {
+        Self::TERMINAL.contains(self)
+    }
+
+    /// Whether the execution ran and failed: it errored, timed out or was
+    /// dead-lettered, the statuses that count towards a failure alert
+    pub fn is_failure(&self) -> bool {
+        matches!(
+            self,
+            ExecutionStatus::Failed | ExecutionStatus::Timeout | ExecutionStatus::DeadLetter
+        )
+    }
+
+    /// Whether an execution in this status may move to `next`
+    ///
+    /// Staying in a non-terminal status is allowed, so a redelivered message can mark
+    /// its execution running again.
+    pub fn can_transition_to(&self, next: &ExecutionStatus) -> bool {
+        use ExecutionStatus::*;
+
+        match (self, next) {
+            (from, to) if from == to => !from.is_terminal(),
+            (Pending, Running | Failed | Cancelled | Expired | Queued | Rejected) => true,
+            (Queued, Running | Failed | Cancelled | Expired) => true,
+            (Running, Pending | Success | Failed | Timeout | Cancelling | Cancelled) => true,
+            (Cancelling, Success | Failed | Timeout | Cancelled) => true,
+            (Failed | Timeout, DeadLetter) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Status change the execution state machine doesn't allow
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Illegal execution status transition from {from} to {to}")]
+pub struct IllegalTransition {
+    pub from: ExecutionStatus,
+    pub to: ExecutionStatus,
 }
 
 impl std::fmt::Display for ExecutionStatus {
@@ -449,6 +1951,9 @@ impl std::fmt::Display for ExecutionStatus {
             ExecutionStatus::DeadLetter => write!(f, "dead_letter"),
             ExecutionStatus::Cancelling => write!(f, "cancelling"),
             ExecutionStatus::Cancelled => write!(f, "cancelled"),
+            ExecutionStatus::Expired => write!(f, "expired"),
+            ExecutionStatus::Queued => write!(f, "queued"),
+            ExecutionStatus::Rejected => write!(f, "rejected"),
         }
     }
 }
@@ -466,6 +1971,9 @@ impl FromStr for ExecutionStatus {
             "dead_letter" => Ok(ExecutionStatus::DeadLetter),
             "cancelling" => Ok(ExecutionStatus::Cancelling),
             "cancelled" => Ok(ExecutionStatus::Cancelled),
+            "expired" => Ok(ExecutionStatus::Expired),
+            "queued" => Ok(ExecutionStatus::Queued),
+            "rejected" => Ok(ExecutionStatus::Rejected),
             _ => Err(format!("Invalid execution status: {}", s)),
         }
     }
@@ -484,8 +1992,14 @@ impl TryFrom<String> for ExecutionStatus {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum TriggerSource {
     Scheduled,
-    Manual { user_id: String },
-    Webhook { webhook_url: String },
+    Manual {
+        user_id: String,
+    },
+    Webhook {
+        webhook_url: String,
+    },
+    /// A past run of the job's schedule replayed with `POST /api/jobs/{id}/backfill`
+    Backfill,
 }
 
 impl std::fmt::Display for TriggerSource {
@@ -494,6 +2008,7 @@ impl std::fmt::Display for TriggerSource {
             TriggerSource::Scheduled => write!(f, "scheduled"),
             TriggerSource::Manual { .. } => write!(f, "manual"),
             TriggerSource::Webhook { .. } => write!(f, "webhook"),
+            TriggerSource::Backfill => write!(f, "backfill"),
         }
     }
 }
@@ -512,6 +2027,7 @@ impl FromStr for TriggerSource {
             "webhook" => Ok(TriggerSource::Webhook {
                 webhook_url: String::new(),
             }),
+            "backfill" => Ok(TriggerSource::Backfill),
             _ => Err(format!("Invalid trigger source: {}", s)),
         }
     }
@@ -535,6 +2051,34 @@ pub struct JobContext {
     pub steps: HashMap<String, StepOutput>,
     pub webhook: Option<WebhookData>,
     pub files: Vec<FileMetadata>,
+    /// Steps whose output is tagged as personal data
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pii_steps: Vec<String>,
+    /// Steps that failed and the on_failure route taken for each, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failure_routes: Vec<FailureRoute>,
+    /// Compensation steps run after the job failed, in the order they ran; kept
+    /// apart from the step outputs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub compensations: Vec<CompensationResult>,
+    /// The job's state kept across executions, as loaded when the execution started
+    /// plus what it committed; see `JobStep::set_state`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub state: HashMap<String, serde_json::Value>,
+    /// State staged by the steps, written to the job's state only once the whole
+    /// execution succeeded, e.g. the watermark of an incremental sync
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub pending_state: HashMap<String, serde_json::Value>,
+    /// Where executors report incremental progress of the running step
+    #[serde(skip)]
+    pub progress: ProgressReporter,
+    /// Environment of the running step, with its secret values; never persisted
+    #[serde(skip)]
+    pub env: StepEnv,
+    /// Values the running database step binds to its query's placeholders, in
+    /// order; never persisted
+    #[serde(skip)]
+    pub query_parameters: Vec<serde_json::Value>,
 }
 
 impl JobContext {
@@ -548,6 +2092,14 @@ impl JobContext {
             steps: HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: HashMap::new(),
+            pending_state: HashMap::new(),
+            progress: ProgressReporter::default(),
+            env: StepEnv::default(),
+            query_parameters: Vec::new(),
         }
     }
 
@@ -563,6 +2115,43 @@ impl JobContext {
         self.steps.insert(step_id, output);
     }
 
+    /// Record that a step's output contains personal data
+    pub fn tag_pii(&mut self, step_id: &str) {
+        if !self.pii_steps.iter().any(|id| id == step_id) {
+            self.pii_steps.push(step_id.to_string());
+        }
+    }
+
+    /// Record a failed step and the route the job took past it
+    pub fn record_failure_route(&mut self, step_id: &str, error: String, route: OnFailure) {
+        self.failure_routes.push(FailureRoute {
+            step_id: step_id.to_string(),
+            error,
+            route,
+            failed_at: Utc::now(),
+        });
+    }
+
+    /// Record the outcome of a compensation step run for `compensates`
+    pub fn record_compensation(
+        &mut self,
+        step_id: &str,
+        compensates: &str,
+        result: Result<StepOutput, String>,
+    ) {
+        let (output, error) = match result {
+            Ok(output) => (Some(output), None),
+            Err(error) => (None, Some(error)),
+        };
+        self.compensations.push(CompensationResult {
+            step_id: step_id.to_string(),
+            compensates: compensates.to_string(),
+            output,
+            error,
+            completed_at: Utc::now(),
+        });
+    }
+
     /// Get variable value by name
     /// Requirements: 2.3 - Variable resolution from Job Context
     pub fn get_variable(&self, name: &str) -> Option<&serde_json::Value> {
@@ -618,28 +2207,137 @@ impl JobContext {
     }
 }
 
-/// StepOutput stores the output of a single job step
+/// FailureRoute records a failed step and where the job went from it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FailureRoute {
+    pub step_id: String,
+    pub error: String,
+    pub route: OnFailure,
+    pub failed_at: DateTime<Utc>,
+}
+
+/// CompensationResult is the outcome of one compensation step
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct StepOutput {
+pub struct CompensationResult {
     pub step_id: String,
-    pub status: String,
-    pub output: serde_json::Value,
-    pub started_at: DateTime<Utc>,
+    /// The step whose effects were undone, or the failed step for a
+    /// `run_compensation` route
+    pub compensates: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<StepOutput>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
     pub completed_at: DateTime<Utc>,
 }
 
-/// WebhookData stores data from webhook triggers
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WebhookData {
-    pub payload: serde_json::Value,
-    pub query_params: HashMap<String, String>,
-    pub headers: HashMap<String, String>,
+/// Incremental progress reported by an executor for the step it is running
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StepProgress {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rows_processed: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_rows: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes_transferred: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
 }
 
-/// FileMetadata stores metadata about files processed or generated
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileMetadata {
-    pub path: String,
+impl StepProgress {
+    /// Completed share of the step, when the executor knows the total
+    pub fn fraction(&self) -> Option<f64> {
+        let (done, total) = match (self.total_bytes, self.total_rows) {
+            (Some(total), _) => (self.bytes_transferred.unwrap_or(0), total),
+            (None, Some(total)) => (self.rows_processed.unwrap_or(0), total),
+            (None, None) => return None,
+        };
+        if total == 0 {
+            return None;
+        }
+        Some((done as f64 / total as f64).min(1.0))
+    }
+}
+
+/// Shared slot executors write step progress into and heartbeats read it from
+///
+/// Clones share the slot, so progress reported on a retry's copy of the context
+/// is still seen by the worker.
+#[derive(Debug, Clone, Default)]
+pub struct ProgressReporter {
+    latest: std::sync::Arc<std::sync::Mutex<Option<StepProgress>>>,
+}
+
+impl ProgressReporter {
+    /// Record rows processed so far
+    pub fn rows(&self, processed: u64, total: Option<u64>) {
+        self.update(|progress| {
+            progress.rows_processed = Some(processed);
+            progress.total_rows = total;
+        });
+    }
+
+    /// Record bytes transferred so far
+    pub fn bytes(&self, transferred: u64, total: Option<u64>) {
+        self.update(|progress| {
+            progress.bytes_transferred = Some(transferred);
+            progress.total_bytes = total;
+        });
+    }
+
+    /// Latest progress reported for the current step
+    pub fn latest(&self) -> Option<StepProgress> {
+        self.latest.lock().ok().and_then(|latest| latest.clone())
+    }
+
+    /// Forget the previous step's progress
+    pub fn reset(&self) {
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = None;
+        }
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut StepProgress)) {
+        if let Ok(mut latest) = self.latest.lock() {
+            apply(latest.get_or_insert_with(StepProgress::default));
+        }
+    }
+}
+
+/// StepOutput stores the output of a single job step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepOutput {
+    pub step_id: String,
+    pub status: String,
+    pub output: serde_json::Value,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// WebhookData stores data from webhook triggers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookData {
+    pub payload: serde_json::Value,
+    pub query_params: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+}
+
+/// Webhook trigger request kept so the job can be simulated against it later
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CapturedWebhookPayload {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub job_id: Uuid,
+    /// Execution the request triggered
+    pub execution_id: Option<Uuid>,
+    #[sqlx(json)]
+    pub data: WebhookData,
+    pub received_at: DateTime<Utc>,
+}
+
+/// FileMetadata stores metadata about files processed or generated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMetadata {
+    pub path: String,
     pub filename: String,
     pub size: u64,
     pub mime_type: Option<String>,
@@ -660,6 +2358,11 @@ pub struct Variable {
     pub is_sensitive: bool,
     #[sqlx(try_from = "String")]
     pub scope: VariableScope,
+    /// When the secret stops working (e.g. an SFTP key or API token); tracked by the
+    /// expiry check
+    #[sqlx(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -705,6 +2408,110 @@ impl TryFrom<String> for VariableScope {
     }
 }
 
+// ============================================================================
+// TLS Material and Credential Expiry Models
+// ============================================================================
+
+/// TlsMaterialKind is the kind of a tracked certificate or key
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TlsMaterialKind {
+    Certificate,
+    PrivateKey,
+    CaBundle,
+    SshKey,
+}
+
+impl std::fmt::Display for TlsMaterialKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsMaterialKind::Certificate => write!(f, "certificate"),
+            TlsMaterialKind::PrivateKey => write!(f, "private_key"),
+            TlsMaterialKind::CaBundle => write!(f, "ca_bundle"),
+            TlsMaterialKind::SshKey => write!(f, "ssh_key"),
+        }
+    }
+}
+
+impl FromStr for TlsMaterialKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "certificate" => Ok(TlsMaterialKind::Certificate),
+            "private_key" => Ok(TlsMaterialKind::PrivateKey),
+            "ca_bundle" => Ok(TlsMaterialKind::CaBundle),
+            "ssh_key" => Ok(TlsMaterialKind::SshKey),
+            _ => Err(format!("Invalid TLS material kind: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for TlsMaterialKind {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// TlsMaterial records the expiry of a certificate or key held outside the platform,
+/// e.g. a partner's SFTP host key or a client certificate on the workers
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct TlsMaterial {
+    pub id: Uuid,
+    pub name: String,
+    #[sqlx(try_from = "String")]
+    pub kind: TlsMaterialKind,
+    pub description: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Where an expiring credential is recorded
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialSource {
+    /// A variable with `expires_at` set
+    Variable,
+    /// A sensitive variable due for rotation; it expires at its maximum age
+    VariableRotation,
+    TlsMaterial,
+    /// A certificate file configured for the expiry check
+    CertificateFile,
+}
+
+impl std::fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialSource::Variable => write!(f, "variable"),
+            CredentialSource::VariableRotation => write!(f, "variable_rotation"),
+            CredentialSource::TlsMaterial => write!(f, "tls_material"),
+            CredentialSource::CertificateFile => write!(f, "certificate_file"),
+        }
+    }
+}
+
+/// CredentialExpiry is a secret, key or certificate and when it expires
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CredentialExpiry {
+    pub source: CredentialSource,
+    /// Variable or TLS material ID; none for certificate files
+    pub id: Option<Uuid>,
+    /// Variable name, TLS material name or certificate path
+    pub name: String,
+    pub kind: Option<TlsMaterialKind>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CredentialExpiry {
+    /// Whole days left until expiry; negative once expired
+    pub fn days_left(&self, now: DateTime<Utc>) -> i64 {
+        (self.expires_at - now).num_days()
+    }
+}
+
 // ============================================================================
 // User and Authentication Models
 // ============================================================================
@@ -718,10 +2525,39 @@ pub struct User {
     pub password_hash: String,
     pub email: Option<String>,
     pub enabled: bool,
+    /// Preferred message locale tag (e.g. "vi-VN"); `None` uses the configured default
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Tenant the user acts for; jobs they create are billed and limited as its
+    #[serde(default)]
+    pub tenant_id: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// UserSession is one login in database authentication mode; tokens carry its id as `sid`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct UserSession {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// User-Agent of the login request
+    pub user_agent: Option<String>,
+    /// Client IP of the login request
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    /// Last authenticated request, updated at most once a minute
+    pub last_seen_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl UserSession {
+    /// Whether tokens of this session are still accepted
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none() && self.expires_at > Utc::now()
+    }
+}
+
 /// Role represents a role with associated permissions
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Role {
@@ -740,6 +2576,12 @@ pub struct UserClaims {
     pub permissions: Vec<String>, // User permissions
     pub exp: i64,                 // Expiration time (Unix timestamp)
     pub iat: i64,                 // Issued at (Unix timestamp)
+    /// Session the token was issued for; `None` for Keycloak tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// Tenant of the user, from the users table or the Keycloak `tenant_id` claim
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
 }
 
 // ============================================================================
@@ -777,3 +2619,1043 @@ pub struct WebhookResponse {
     pub execution_id: Uuid,
     pub message: String,
 }
+
+// ============================================================================
+// Rate Limit Models
+// ============================================================================
+
+/// RateLimitScope identifies which kind of caller a rate limit applies to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitScope {
+    User,
+    ApiKey,
+    Tenant,
+}
+
+impl std::fmt::Display for RateLimitScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateLimitScope::User => write!(f, "user"),
+            RateLimitScope::ApiKey => write!(f, "api_key"),
+            RateLimitScope::Tenant => write!(f, "tenant"),
+        }
+    }
+}
+
+impl FromStr for RateLimitScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "user" => Ok(RateLimitScope::User),
+            "api_key" => Ok(RateLimitScope::ApiKey),
+            "tenant" => Ok(RateLimitScope::Tenant),
+            _ => Err(format!("Invalid rate limit scope: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for RateLimitScope {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// RateLimitPolicy overrides the default API rate limit for one identity
+/// (a user ID, a hashed API key or a tenant ID)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RateLimitPolicy {
+    pub id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub scope: RateLimitScope,
+    pub identity: String,
+    pub requests_per_minute: i32,
+    pub burst: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Feature Flag Models
+// ============================================================================
+
+/// FeatureFlag turns a feature on or off globally (`tenant_id` is None)
+/// or for a single tenant, overriding the global value
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FeatureFlag {
+    pub id: Uuid,
+    pub name: String,
+    pub tenant_id: Option<String>,
+    pub enabled: bool,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Scheduled Trigger Models
+// ============================================================================
+
+/// ScheduledTriggerStatus tracks a one-off "run at" request
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduledTriggerStatus {
+    Pending,
+    Dispatched,
+    Cancelled,
+    Failed,
+}
+
+impl std::fmt::Display for ScheduledTriggerStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduledTriggerStatus::Pending => write!(f, "pending"),
+            ScheduledTriggerStatus::Dispatched => write!(f, "dispatched"),
+            ScheduledTriggerStatus::Cancelled => write!(f, "cancelled"),
+            ScheduledTriggerStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl FromStr for ScheduledTriggerStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(ScheduledTriggerStatus::Pending),
+            "dispatched" => Ok(ScheduledTriggerStatus::Dispatched),
+            "cancelled" => Ok(ScheduledTriggerStatus::Cancelled),
+            "failed" => Ok(ScheduledTriggerStatus::Failed),
+            _ => Err(format!("Invalid scheduled trigger status: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for ScheduledTriggerStatus {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// ScheduledTrigger is a one-time execution of a job requested for a specific
+/// time, independent of the job's recurring schedule
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ScheduledTrigger {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub execute_at: DateTime<Utc>,
+    /// Variable overrides merged into the execution context
+    #[sqlx(json)]
+    pub variables: HashMap<String, serde_json::Value>,
+    /// Completion callback (`callback::ExecutionCallback`) for the execution
+    pub callback: Option<serde_json::Value>,
+    #[sqlx(try_from = "String")]
+    pub status: ScheduledTriggerStatus,
+    pub execution_id: Option<Uuid>,
+    pub error: Option<String>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub dispatched_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// Blackout Window Models
+// ============================================================================
+
+/// BlackoutBehavior decides what happens to runs due during a blackout window
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackoutBehavior {
+    /// Runs due during the window are dropped
+    #[default]
+    Skip,
+    /// One run is made when the window ends
+    Defer,
+}
+
+impl std::fmt::Display for BlackoutBehavior {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlackoutBehavior::Skip => write!(f, "skip"),
+            BlackoutBehavior::Defer => write!(f, "defer"),
+        }
+    }
+}
+
+impl FromStr for BlackoutBehavior {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "skip" => Ok(BlackoutBehavior::Skip),
+            "defer" => Ok(BlackoutBehavior::Defer),
+            _ => Err(format!("Invalid blackout behavior: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for BlackoutBehavior {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// BlackoutWindow is a period, e.g. a month-end close or maintenance window,
+/// during which the scheduler doesn't publish a job
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BlackoutWindow {
+    pub id: Uuid,
+    pub job_id: Uuid,
+    pub name: String,
+    pub starts_at: DateTime<Utc>,
+    /// Exclusive; runs are published again from this time
+    pub ends_at: DateTime<Utc>,
+    #[sqlx(try_from = "String")]
+    pub behavior: BlackoutBehavior,
+    /// One-time trigger made for the run deferred to the end of the window
+    pub deferred_trigger_id: Option<Uuid>,
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BlackoutWindow {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+}
+
+// ============================================================================
+// Failure Note Models
+// ============================================================================
+
+/// FailureNote is remediation advice attached to a failure fingerprint, shown with
+/// every execution that fails the same way
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct FailureNote {
+    /// Fingerprint of the normalized error message; see `crate::fingerprint`
+    pub fingerprint: String,
+    pub note: String,
+    pub runbook_url: Option<String>,
+    pub updated_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Job State Models
+// ============================================================================
+
+/// JobStateEntry is one value a job keeps across its executions, e.g. the
+/// `last_processed_id` cursor of an incremental sync
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct JobStateEntry {
+    pub job_id: Uuid,
+    pub key: String,
+    pub value: serde_json::Value,
+    /// Execution whose step last wrote the value; `None` when set through the API
+    pub updated_by_execution: Option<Uuid>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobStateEntry {
+    /// Longest accepted key
+    pub const MAX_KEY_LEN: usize = 255;
+
+    /// Keys are letters, digits, `_` and `-`, so `{{state.<key>.<path>}}` can't be
+    /// ambiguous
+    pub fn is_valid_key(key: &str) -> bool {
+        !key.is_empty()
+            && key.len() <= Self::MAX_KEY_LEN
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    }
+}
+
+// ============================================================================
+// Webhook Delivery Models
+// ============================================================================
+
+/// WebhookDeliveryStatus tracks an outbound webhook through its retries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    /// Waiting for its first or next attempt
+    Pending,
+    Delivered,
+    /// Out of attempts, or rejected by the endpoint; kept for redelivery
+    DeadLetter,
+}
+
+impl std::fmt::Display for WebhookDeliveryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WebhookDeliveryStatus::Pending => write!(f, "pending"),
+            WebhookDeliveryStatus::Delivered => write!(f, "delivered"),
+            WebhookDeliveryStatus::DeadLetter => write!(f, "dead_letter"),
+        }
+    }
+}
+
+impl FromStr for WebhookDeliveryStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pending" => Ok(WebhookDeliveryStatus::Pending),
+            "delivered" => Ok(WebhookDeliveryStatus::Delivered),
+            "dead_letter" => Ok(WebhookDeliveryStatus::DeadLetter),
+            _ => Err(format!("Invalid webhook delivery status: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for WebhookDeliveryStatus {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// WebhookDelivery is one signed outbound POST, persisted until it is delivered
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_url: String,
+    /// Event name sent in the `X-Cron-Event` header, e.g. `execution.completed`
+    pub event: String,
+    pub job_id: Option<Uuid>,
+    pub execution_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+    #[sqlx(try_from = "String")]
+    pub status: WebhookDeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// WebhookDeliveryAttempt is the log entry of one POST of a delivery
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct WebhookDeliveryAttempt {
+    pub attempt: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    pub attempted_at: DateTime<Utc>,
+}
+
+// ============================================================================
+// Scheduler Journal Models
+// ============================================================================
+
+/// TickStatus records whether a scheduler tick evaluated jobs, and if not, why
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TickStatus {
+    /// Due jobs were evaluated
+    Ran,
+    /// The node's region was standby
+    Standby,
+    /// The node's clock was out of sync
+    ClockSkew,
+    /// The node was drained and publishes no new executions
+    Draining,
+    /// Loading the due jobs failed
+    Failed,
+}
+
+impl std::fmt::Display for TickStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TickStatus::Ran => write!(f, "ran"),
+            TickStatus::Standby => write!(f, "standby"),
+            TickStatus::ClockSkew => write!(f, "clock_skew"),
+            TickStatus::Draining => write!(f, "draining"),
+            TickStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+impl FromStr for TickStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ran" => Ok(TickStatus::Ran),
+            "standby" => Ok(TickStatus::Standby),
+            "clock_skew" => Ok(TickStatus::ClockSkew),
+            "draining" => Ok(TickStatus::Draining),
+            "failed" => Ok(TickStatus::Failed),
+            _ => Err(format!("Invalid tick status: {}", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for TickStatus {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::from_str(&s)
+    }
+}
+
+/// What a scheduler tick did with one due job
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobDecisionOutcome {
+    Fired,
+    Skipped,
+    /// Firing was attempted but errored
+    Failed,
+}
+
+/// Why a due job was not fired
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// A run is still active and the collision policy is `skip`
+    CollisionPolicy,
+    /// Another scheduler node holds the job's lock
+    LockHeld,
+    /// More jobs were due than `max_jobs_per_poll`
+    PollLimit,
+    /// The schedule's calendar is closed today
+    Calendar,
+    /// The schedule names a calendar that doesn't exist or couldn't be loaded
+    CalendarMissing,
+    /// A blackout window of the job is active; with `defer`, the run is made
+    /// when the window ends
+    Blackout,
+    /// The runs due were missed while no scheduler was running and the misfire
+    /// policy is `skip`
+    Misfire,
+}
+
+/// JobDecision is the journal entry of one due job in a tick
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JobDecision {
+    pub job_id: Uuid,
+    pub job_name: String,
+    pub outcome: JobDecisionOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<SkipReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Executions queued by a fired job, one per fan-out target
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub execution_ids: Vec<Uuid>,
+}
+
+impl JobDecision {
+    pub fn fired(job: &Job, execution_ids: Vec<Uuid>) -> Self {
+        Self::new(job, JobDecisionOutcome::Fired, None, None, execution_ids)
+    }
+
+    pub fn skipped(job: &Job, reason: SkipReason) -> Self {
+        Self::new(
+            job,
+            JobDecisionOutcome::Skipped,
+            Some(reason),
+            None,
+            Vec::new(),
+        )
+    }
+
+    pub fn failed(job: &Job, error: String) -> Self {
+        Self::new(
+            job,
+            JobDecisionOutcome::Failed,
+            None,
+            Some(error),
+            Vec::new(),
+        )
+    }
+
+    fn new(
+        job: &Job,
+        outcome: JobDecisionOutcome,
+        reason: Option<SkipReason>,
+        error: Option<String>,
+        execution_ids: Vec<Uuid>,
+    ) -> Self {
+        Self {
+            job_id: job.id,
+            job_name: job.name.clone(),
+            outcome,
+            reason,
+            error,
+            execution_ids,
+        }
+    }
+}
+
+/// SchedulerTick is one poll of a scheduler node, as kept in the decision journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerTick {
+    pub id: i64,
+    /// Scheduler node that ran the tick and held the locks of the jobs it fired
+    pub node: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub status: TickStatus,
+    pub jobs_due: i32,
+    pub jobs_fired: i32,
+    pub jobs_skipped: i32,
+    pub decisions: Vec<JobDecision>,
+    pub error: Option<String>,
+}
+
+impl SchedulerTick {
+    /// A tick with its counters derived from `decisions`
+    pub fn new(
+        node: String,
+        started_at: DateTime<Utc>,
+        duration_ms: i64,
+        status: TickStatus,
+        decisions: Vec<JobDecision>,
+        error: Option<String>,
+    ) -> Self {
+        let count = |outcome| decisions.iter().filter(|d| d.outcome == outcome).count() as i32;
+        Self {
+            id: 0,
+            node,
+            started_at,
+            duration_ms,
+            status,
+            jobs_due: decisions.len() as i32,
+            jobs_fired: count(JobDecisionOutcome::Fired),
+            jobs_skipped: count(JobDecisionOutcome::Skipped),
+            decisions,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_state_machine() {
+        let mut execution = JobExecution::new_scheduled(Uuid::new_v4(), "key".to_string());
+
+        assert_eq!(
+            execution.transition_to(ExecutionStatus::Running),
+            Ok(ExecutionStatus::Pending)
+        );
+        let started_at = execution.started_at;
+        assert!(started_at.is_some());
+
+        // Redelivery keeps the original start time
+        assert!(execution.transition_to(ExecutionStatus::Running).is_ok());
+        assert_eq!(execution.started_at, started_at);
+
+        assert!(execution.transition_to(ExecutionStatus::Cancelling).is_ok());
+        assert!(execution.transition_to(ExecutionStatus::Success).is_ok());
+        assert!(execution.completed_at.is_some());
+
+        assert_eq!(
+            execution.transition_to(ExecutionStatus::Running),
+            Err(IllegalTransition {
+                from: ExecutionStatus::Success,
+                to: ExecutionStatus::Running,
+            })
+        );
+        assert!(execution.transition_to(ExecutionStatus::Success).is_err());
+        assert_eq!(execution.status, ExecutionStatus::Success);
+
+        assert!(ExecutionStatus::Failed.can_transition_to(&ExecutionStatus::DeadLetter));
+        assert!(!ExecutionStatus::Cancelled.can_transition_to(&ExecutionStatus::DeadLetter));
+        assert!(!ExecutionStatus::Pending.can_transition_to(&ExecutionStatus::Success));
+        assert!(ExecutionStatus::Pending.can_transition_to(&ExecutionStatus::Expired));
+        assert!(!ExecutionStatus::Running.can_transition_to(&ExecutionStatus::Expired));
+        assert!(ExecutionStatus::Expired.is_terminal());
+
+        assert!(ExecutionStatus::Pending.can_transition_to(&ExecutionStatus::Queued));
+        assert!(ExecutionStatus::Queued.can_transition_to(&ExecutionStatus::Queued));
+        assert!(ExecutionStatus::Queued.can_transition_to(&ExecutionStatus::Running));
+        assert!(!ExecutionStatus::Running.can_transition_to(&ExecutionStatus::Queued));
+        assert!(ExecutionStatus::Pending.can_transition_to(&ExecutionStatus::Rejected));
+        assert!(!ExecutionStatus::Rejected.can_transition_to(&ExecutionStatus::Running));
+        assert!(ExecutionStatus::Rejected.is_terminal());
+    }
+
+    #[test]
+    fn test_dead_letter_keeps_completion_time() {
+        let mut execution = JobExecution::new_scheduled(Uuid::new_v4(), "key".to_string());
+        execution.transition_to(ExecutionStatus::Running).unwrap();
+        execution.transition_to(ExecutionStatus::Timeout).unwrap();
+        let completed_at = execution.completed_at;
+        assert!(completed_at.is_some());
+
+        // Usage meters run time up to the timeout, not up to the dead-lettering
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        execution
+            .transition_to(ExecutionStatus::DeadLetter)
+            .unwrap();
+        assert_eq!(execution.completed_at, completed_at);
+    }
+
+    #[test]
+    fn test_failure_statuses() {
+        let failures: Vec<_> = ExecutionStatus::TERMINAL
+            .iter()
+            .filter(|status| status.is_failure())
+            .collect();
+        assert_eq!(
+            failures,
+            [
+                &ExecutionStatus::Failed,
+                &ExecutionStatus::Timeout,
+                &ExecutionStatus::DeadLetter
+            ]
+        );
+        assert!(!ExecutionStatus::Running.is_failure());
+    }
+
+    fn owner() -> JobOwner {
+        JobOwner {
+            team: "payments".to_string(),
+            contact_email: Some("payments-oncall@example.com".to_string()),
+            slack: Some("#payments-alerts".to_string()),
+            runbook_url: Some("https://wiki.example.com/runbooks/settlement".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_job_owner_validation_accepts_complete_owner() {
+        assert!(owner().validate().is_ok());
+
+        let slack_only = JobOwner {
+            contact_email: None,
+            runbook_url: None,
+            ..owner()
+        };
+        assert!(slack_only.validate().is_ok());
+    }
+
+    #[test]
+    fn test_job_owner_validation_rejects_bad_fields() {
+        let cases = [
+            JobOwner {
+                team: "  ".to_string(),
+                ..owner()
+            },
+            JobOwner {
+                contact_email: None,
+                slack: None,
+                ..owner()
+            },
+            JobOwner {
+                contact_email: Some("not-an-email".to_string()),
+                ..owner()
+            },
+            JobOwner {
+                slack: Some("payments-alerts".to_string()),
+                ..owner()
+            },
+            JobOwner {
+                runbook_url: Some("ftp://wiki.example.com/runbook".to_string()),
+                ..owner()
+            },
+        ];
+
+        for case in cases {
+            assert!(
+                case.validate().is_err(),
+                "expected {:?} to be rejected",
+                case
+            );
+        }
+    }
+
+    #[test]
+    fn test_metric_labels_validation() {
+        let labels = |pairs: &[(&str, &str)]| -> BTreeMap<String, String> {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect()
+        };
+
+        assert!(validate_metric_labels(&labels(&[])).is_ok());
+        assert!(
+            validate_metric_labels(&labels(&[("domain", "payments"), ("tier", "gold")])).is_ok()
+        );
+
+        assert!(validate_metric_labels(&labels(&[("1domain", "payments")])).is_err());
+        assert!(validate_metric_labels(&labels(&[("domain-name", "payments")])).is_err());
+        assert!(validate_metric_labels(&labels(&[("__name__", "payments")])).is_err());
+        assert!(validate_metric_labels(&labels(&[("job_name", "payments")])).is_err());
+        assert!(validate_metric_labels(&labels(&[("domain", " ")])).is_err());
+        assert!(validate_metric_labels(&labels(&[("domain", &"x".repeat(65))])).is_err());
+        assert!(validate_metric_labels(&labels(&[
+            ("a", "1"),
+            ("b", "2"),
+            ("c", "3"),
+            ("d", "4"),
+            ("e", "5"),
+            ("f", "6"),
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_system_step_serde_shape() {
+        let step: JobStep = serde_json::from_value(serde_json::json!({
+            "id": "cleanup",
+            "name": "Retention cleanup",
+            "type": {
+                "type": "system",
+                "task": {"name": "retention_cleanup", "retention_days": 30}
+            },
+            "condition": null
+        }))
+        .unwrap();
+        assert!(matches!(
+            step.step_type,
+            JobType::System {
+                task: SystemTask::RetentionCleanup { retention_days: 30 }
+            }
+        ));
+
+        let expiry: SystemTask = serde_json::from_value(serde_json::json!({
+            "name": "expiry_check", "warn_days": 14, "secret_max_age_days": 90
+        }))
+        .unwrap();
+        assert_eq!(
+            expiry,
+            SystemTask::ExpiryCheck {
+                certificate_paths: vec![],
+                warn_days: 14,
+                secret_max_age_days: 90
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(SystemTask::Canary).unwrap(),
+            serde_json::json!({"name": "canary"})
+        );
+    }
+
+    #[test]
+    fn test_variable_overrides_round_trip_through_trigger_metadata() {
+        let mut execution = JobExecution::new_manual(Uuid::new_v4(), "alice".to_string());
+        assert!(execution.variable_overrides().is_empty());
+
+        execution.trigger_metadata =
+            Some(serde_json::json!({ "callback": { "url": "https://example.com" } }));
+        let variables =
+            HashMap::from([("report_date".to_string(), serde_json::json!("2026-10-15"))]);
+        execution.set_variable_overrides(&variables);
+
+        assert_eq!(execution.variable_overrides(), variables);
+        assert!(execution.trigger_metadata.as_ref().unwrap()["callback"].is_object());
+    }
+
+    #[test]
+    fn test_start_delay_counts_from_scheduled_for() {
+        let now = Utc::now();
+        let mut execution = JobExecution::new_scheduled(Uuid::new_v4(), "run".to_string());
+        execution.created_at = now - chrono::Duration::seconds(30);
+        assert_eq!(execution.start_delay(now), chrono::Duration::seconds(30));
+
+        // A run created late for an old due time is as late as its due time
+        execution.trigger_metadata = Some(serde_json::json!({
+            SCHEDULED_FOR_METADATA_KEY: now - chrono::Duration::seconds(600)
+        }));
+        assert_eq!(
+            execution.scheduled_for(),
+            Some(now - chrono::Duration::seconds(600))
+        );
+        assert_eq!(execution.start_delay(now), chrono::Duration::seconds(600));
+    }
+
+    #[test]
+    fn test_collision_policy_round_trip() {
+        for policy in [
+            CollisionPolicy::Skip,
+            CollisionPolicy::QueueAfter,
+            CollisionPolicy::CancelPrevious,
+        ] {
+            let json = serde_json::to_value(policy).unwrap();
+            assert_eq!(json, serde_json::json!(policy.to_string()));
+            assert_eq!(policy.to_string().parse::<CollisionPolicy>(), Ok(policy));
+        }
+        assert_eq!(CollisionPolicy::default(), CollisionPolicy::Skip);
+        assert!("later".parse::<CollisionPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_blackout_window_is_active() {
+        let starts_at = Utc::now();
+        let window = BlackoutWindow {
+            id: Uuid::new_v4(),
+            job_id: Uuid::new_v4(),
+            name: "Month-end close".to_string(),
+            starts_at,
+            ends_at: starts_at + chrono::Duration::hours(2),
+            behavior: "defer".parse().unwrap(),
+            deferred_trigger_id: None,
+            created_by: "admin".to_string(),
+            created_at: starts_at,
+        };
+
+        assert_eq!(window.behavior, BlackoutBehavior::Defer);
+        assert!(window.is_active(starts_at));
+        assert!(window.is_active(starts_at + chrono::Duration::minutes(119)));
+        assert!(!window.is_active(window.ends_at));
+        assert!(!window.is_active(starts_at - chrono::Duration::seconds(1)));
+    }
+
+    #[test]
+    fn test_step_flow_validation() {
+        let steps: Vec<JobStep> = serde_json::from_value(serde_json::json!([
+            {"id": "fetch", "name": "Fetch", "type": {"type": "system", "task": {"name": "canary"}},
+             "condition": null, "on_failure": {"run_compensation": ["undo"]}},
+            {"id": "load", "name": "Load", "type": {"type": "system", "task": {"name": "canary"}},
+             "condition": null, "on_failure": "continue_next_step"},
+            {"id": "report", "name": "Report", "type": {"type": "system", "task": {"name": "canary"}},
+             "condition": null, "on_failure": {"goto_step": "undo"}},
+            {"id": "undo", "name": "Undo", "type": {"type": "system", "task": {"name": "canary"}},
+             "condition": null}
+        ]))
+        .unwrap();
+        assert_eq!(
+            steps[0].on_failure,
+            Some(OnFailure::RunCompensation(vec!["undo".to_string()]))
+        );
+        assert_eq!(steps[1].on_failure, Some(OnFailure::ContinueNextStep));
+        assert!(validate_step_flow(&steps).is_ok());
+
+        // Definitions written before the routes were named keep loading
+        assert_eq!(
+            serde_json::from_value::<OnFailure>(serde_json::json!("stop")).unwrap(),
+            OnFailure::FailJob
+        );
+        assert_eq!(
+            serde_json::from_value::<OnFailure>(serde_json::json!("continue")).unwrap(),
+            OnFailure::ContinueNextStep
+        );
+        assert_eq!(compensation_step_ids(&steps), HashSet::from(["undo"]));
+
+        // Going back could loop, and compensation must name other steps
+        let mut looping = steps.clone();
+        looping[2].on_failure = Some(OnFailure::GotoStep("fetch".to_string()));
+        assert!(validate_step_flow(&looping).is_err());
+        let mut unknown = steps.clone();
+        unknown[0].on_failure = Some(OnFailure::RunCompensation(vec!["fetch".to_string()]));
+        assert!(validate_step_flow(&unknown).is_err());
+        unknown[0].on_failure = Some(OnFailure::RunCompensation(vec![]));
+        assert!(validate_step_flow(&unknown).is_err());
+
+        // Steps declare what undoes them
+        let mut saga = steps.clone();
+        saga[1].compensate_with = vec!["undo".to_string()];
+        assert!(validate_step_flow(&saga).is_ok());
+        saga[1].compensate_with = vec!["missing".to_string()];
+        assert!(validate_step_flow(&saga).is_err());
+        saga[1].compensate_with = vec![];
+        saga[2].on_failure = Some(OnFailure::GotoStep("undo".to_string()));
+        assert!(validate_step_flow(&saga).is_err());
+    }
+
+    #[test]
+    fn test_job_state_keys() {
+        assert!(JobStateEntry::is_valid_key("last_processed_id"));
+        assert!(JobStateEntry::is_valid_key("orders-cursor"));
+        for invalid in ["", "cursor.id", "last id", "{{x}}"] {
+            assert!(!JobStateEntry::is_valid_key(invalid), "{}", invalid);
+        }
+        assert!(!JobStateEntry::is_valid_key(&"k".repeat(256)));
+    }
+
+    #[test]
+    fn test_incremental_sync_step_validation() {
+        let mut steps: Vec<JobStep> = serde_json::from_value(serde_json::json!([{
+            "id": "sync",
+            "name": "Sync orders",
+            "type": {
+                "type": "incremental_sync",
+                "watermark_key": "orders_updated_at",
+                "initial_watermark": "1970-01-01T00:00:00Z",
+                "source": {
+                    "type": "database_query",
+                    "query": "SELECT * FROM orders WHERE updated_at > '{{watermark}}'",
+                    "query_type": {"type": "raw_sql"}
+                },
+                "watermark_field": "updated_at"
+            },
+            "condition": null
+        }]))
+        .unwrap();
+        assert!(validate_step_flow(&steps).is_ok());
+
+        let mut bad_key = steps.clone();
+        if let JobType::IncrementalSync { watermark_key, .. } = &mut bad_key[0].step_type {
+            *watermark_key = "orders.updated_at".to_string();
+        }
+        assert!(validate_step_flow(&bad_key).is_err());
+
+        // Only queries and API calls can be synced incrementally
+        if let JobType::IncrementalSync { source, .. } = &mut steps[0].step_type {
+            **source = JobType::System {
+                task: SystemTask::Canary,
+            };
+        }
+        assert!(validate_step_flow(&steps).is_err());
+    }
+
+    #[test]
+    fn test_step_env_validation() {
+        let mut steps: Vec<JobStep> = serde_json::from_value(serde_json::json!([{
+            "id": "report",
+            "name": "Build report",
+            "type": {"type": "script", "source": "env.API_TOKEN.len()"},
+            "condition": null,
+            "env": {
+                "API_TOKEN": {"secret": "reporting_token"},
+                "REGION": {"value": "{{region}}"}
+            }
+        }]))
+        .unwrap();
+        assert_eq!(
+            steps[0].env["API_TOKEN"],
+            EnvSource::Secret("reporting_token".to_string())
+        );
+        assert!(validate_step_flow(&steps).is_ok());
+
+        let mut bad_name = steps.clone();
+        bad_name[0]
+            .env
+            .insert("1-TOKEN".to_string(), EnvSource::Value("x".to_string()));
+        assert!(validate_step_flow(&bad_name).is_err());
+
+        // Only process-based steps take an environment
+        steps[0].step_type = JobType::System {
+            task: SystemTask::Canary,
+        };
+        assert!(validate_step_flow(&steps).is_err());
+    }
+
+    #[test]
+    fn test_step_lock_defaults_and_limits() {
+        let mut lock: StepLock =
+            serde_json::from_value(serde_json::json!({ "resource": "ledger", "ttl_seconds": 300 }))
+                .unwrap();
+        assert_eq!(lock.wait_timeout_seconds, 60);
+        assert!(lock.validate("post").is_ok());
+
+        lock.ttl_seconds = 0;
+        assert!(lock.validate("post").is_err());
+        lock.ttl_seconds = 300;
+        lock.resource = " ".to_string();
+        assert!(lock.validate("post").is_err());
+    }
+
+    #[test]
+    fn test_dead_letter_policy_defaults_and_limits() {
+        let policy: DeadLetterPolicy = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(!policy.auto_replay);
+        assert_eq!(policy.max_replays, 20);
+        assert!(policy.validate().is_ok());
+
+        let policy: DeadLetterPolicy =
+            serde_json::from_value(serde_json::json!({ "auto_replay": true, "max_replays": 0 }))
+                .unwrap();
+        assert!(policy.validate().is_err());
+    }
+
+    #[test]
+    fn test_execution_progress_and_heartbeat_staleness() {
+        let progress = ExecutionProgress::new("transfer".to_string(), 1, 4, 3600, 3000, None);
+        assert_eq!(progress.percent, Some(25));
+        assert_eq!(
+            ExecutionProgress::new("only".to_string(), 0, 1, 10, 10, None).percent,
+            None
+        );
+
+        let now = Utc::now();
+        let started = Some(now - chrono::Duration::hours(1));
+        let recent = Some(now - chrono::Duration::seconds(20));
+        assert!(!heartbeat_stale(recent, started, now, 15));
+        assert!(heartbeat_stale(
+            Some(now - chrono::Duration::seconds(46)),
+            started,
+            now,
+            15
+        ));
+        assert!(heartbeat_stale(None, started, now, 15));
+        assert!(!heartbeat_stale(None, None, now, 15));
+    }
+
+    #[test]
+    fn test_reported_step_progress_feeds_execution_percent() {
+        let context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        let retry_copy = context.clone();
+        retry_copy.progress.bytes(512, Some(1024));
+        retry_copy.progress.rows(300, None);
+
+        let step = context.progress.latest().unwrap();
+        assert_eq!(step.bytes_transferred, Some(512));
+        assert_eq!(step.rows_processed, Some(300));
+        assert_eq!(step.fraction(), Some(0.5));
+
+        let progress = ExecutionProgress::new("upload".to_string(), 1, 2, 60, 30, Some(step));
+        assert_eq!(progress.percent, Some(75));
+
+        context.progress.reset();
+        assert!(retry_copy.progress.latest().is_none());
+    }
+
+    #[test]
+    fn test_scheduler_tick_counts_decisions() {
+        let job_id = Uuid::new_v4();
+        let decision = |outcome, reason| JobDecision {
+            job_id,
+            job_name: "nightly-report".to_string(),
+            outcome,
+            reason,
+            error: None,
+            execution_ids: Vec::new(),
+        };
+        let decisions = vec![
+            decision(JobDecisionOutcome::Fired, None),
+            decision(JobDecisionOutcome::Skipped, Some(SkipReason::LockHeld)),
+            decision(JobDecisionOutcome::Failed, None),
+        ];
+
+        let tick = SchedulerTick::new(
+            "scheduler-1".to_string(),
+            Utc::now(),
+            12,
+            TickStatus::Ran,
+            decisions,
+            None,
+        );
+        assert_eq!(tick.jobs_due, 3);
+        assert_eq!(tick.jobs_fired, 1);
+        assert_eq!(tick.jobs_skipped, 1);
+
+        // Skipped decisions stay compact in the journal
+        assert_eq!(
+            serde_json::to_value(&tick.decisions[1]).unwrap(),
+            serde_json::json!({
+                "job_id": job_id,
+                "job_name": "nightly-report",
+                "outcome": "skipped",
+                "reason": "lock_held"
+            })
+        );
+    }
+}
@@ -0,0 +1,389 @@
+// Execution parameter matrix
+// Purpose: Fan each scheduled run of a job out into one execution per combination of
+// parameter values (e.g. one per branch) instead of maintaining near-identical jobs
+// per parameter set, and report the executions of one run as a group
+//
+// The parameters reach the steps as trigger variables, so `{{branch}}` resolves to the
+// combination's value. Executions of one group share a `group_id` in their trigger
+// metadata and don't hold each other back under the job's collision policy.
+
+use crate::errors::ValidationError;
+use crate::models::{ExecutionStatus, JobExecution};
+use crate::trigger_variables::is_valid_variable_name;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use uuid::Uuid;
+
+/// Key under which the matrix run is stored in `JobExecution::trigger_metadata`
+pub const MATRIX_METADATA_KEY: &str = "matrix";
+
+/// Most executions a single scheduled run may fan out into
+pub const MAX_MATRIX_COMBINATIONS: usize = 50;
+
+/// Longest accepted parameter value, in bytes
+const MAX_PARAMETER_VALUE_LEN: usize = 256;
+
+/// Validate a job's parameter matrix
+///
+/// Names follow the `${NAME}` placeholder syntax. Each parameter needs at least one
+/// value, values must be non-empty and unique, and the matrix may not expand to more
+/// than [`MAX_MATRIX_COMBINATIONS`] executions per run.
+pub fn validate_parameter_matrix(
+    matrix: &BTreeMap<String, Vec<String>>,
+) -> Result<(), ValidationError> {
+    let mut total: usize = 1;
+    for (name, values) in matrix {
+        let invalid = |reason: &str| ValidationError::InvalidFieldValue {
+            field: format!("parameter_matrix.{}", name),
+            reason: reason.to_string(),
+        };
+
+        if !is_valid_variable_name(name) {
+            return Err(invalid("name must match [a-zA-Z_][a-zA-Z0-9_]*"));
+        }
+        if values.is_empty() {
+            return Err(invalid("at least one value is required"));
+        }
+        if values.iter().any(|value| value.trim().is_empty()) {
+            return Err(invalid("values must not be empty"));
+        }
+        if values
+            .iter()
+            .any(|value| value.len() > MAX_PARAMETER_VALUE_LEN)
+        {
+            return Err(invalid(&format!(
+                "values must be at most {} bytes",
+                MAX_PARAMETER_VALUE_LEN
+            )));
+        }
+        if values.iter().collect::<HashSet<_>>().len() != values.len() {
+            return Err(invalid("values must be unique"));
+        }
+        total = total.saturating_mul(values.len());
+    }
+
+    if total > MAX_MATRIX_COMBINATIONS {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "parameter_matrix".to_string(),
+            reason: format!(
+                "expands to {} runs, at most {} are allowed",
+                total, MAX_MATRIX_COMBINATIONS
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Every combination of parameter values, the last parameter varying fastest
+///
+/// An empty matrix has no combinations; the job then runs once without parameters.
+pub fn combinations(matrix: &BTreeMap<String, Vec<String>>) -> Vec<BTreeMap<String, String>> {
+    if matrix.is_empty() {
+        return Vec::new();
+    }
+    matrix
+        .iter()
+        .fold(vec![BTreeMap::new()], |combos, (name, values)| {
+            combos
+                .into_iter()
+                .flat_map(|combo| {
+                    values.iter().map(move |value| {
+                        let mut next = combo.clone();
+                        next.insert(name.clone(), value.clone());
+                        next
+                    })
+                })
+                .collect()
+        })
+}
+
+/// The executions one scheduled run of a job creates
+///
+/// One execution without a matrix, otherwise one per combination sharing a new group.
+pub fn scheduled_executions(
+    job_id: Uuid,
+    matrix: &BTreeMap<String, Vec<String>>,
+) -> Vec<JobExecution> {
+    let new_execution =
+        || JobExecution::new_scheduled(job_id, format!("{}:{}", job_id, Uuid::new_v4()));
+
+    let combinations = combinations(matrix);
+    if combinations.is_empty() {
+        return vec![new_execution()];
+    }
+
+    let group_id = Uuid::new_v4();
+    let total = combinations.len();
+    combinations
+        .into_iter()
+        .enumerate()
+        .map(|(index, parameters)| {
+            let mut execution = new_execution();
+            MatrixRun {
+                group_id,
+                index,
+                total,
+                parameters,
+            }
+            .apply_to(&mut execution);
+            execution
+        })
+        .collect()
+}
+
+/// One execution of a fanned-out scheduled run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatrixRun {
+    /// Shared by the executions of one scheduled run
+    pub group_id: Uuid,
+    /// Position of this combination in the group, from 0
+    pub index: usize,
+    /// Executions in the group
+    pub total: usize,
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl MatrixRun {
+    /// Read the matrix run stored in an execution's trigger metadata
+    pub fn from_trigger_metadata(metadata: Option<&serde_json::Value>) -> Option<Self> {
+        metadata
+            .and_then(|m| m.get(MATRIX_METADATA_KEY))
+            .and_then(|run| serde_json::from_value(run.clone()).ok())
+    }
+
+    /// Store the run in an execution's trigger metadata and its parameters as variables
+    pub fn apply_to(&self, execution: &mut JobExecution) {
+        let variables: HashMap<String, serde_json::Value> = self
+            .parameters
+            .iter()
+            .map(|(name, value)| (name.clone(), serde_json::Value::String(value.clone())))
+            .collect();
+        execution.set_variable_overrides(&variables);
+
+        let value = serde_json::to_value(self).unwrap_or_default();
+        match &mut execution.trigger_metadata {
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(MATRIX_METADATA_KEY.to_string(), value);
+            }
+            metadata => *metadata = Some(serde_json::json!({ MATRIX_METADATA_KEY: value })),
+        }
+    }
+}
+
+/// Group of an execution, if it is part of a fanned-out run
+pub fn matrix_group_id(execution: &JobExecution) -> Option<Uuid> {
+    MatrixRun::from_trigger_metadata(execution.trigger_metadata.as_ref()).map(|run| run.group_id)
+}
+
+/// Overall status of the executions of one fanned-out run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixGroupStatus {
+    /// Some executions haven't finished yet
+    Running,
+    Success,
+    /// Finished, some executions succeeded and some didn't
+    PartialFailure,
+    /// Finished without any successful execution
+    Failed,
+}
+
+impl MatrixGroupStatus {
+    /// Summarize the statuses of a group's executions
+    pub fn summarize<'a>(statuses: impl IntoIterator<Item = &'a ExecutionStatus>) -> Self {
+        let (mut succeeded, mut unsuccessful) = (0, 0);
+        for status in statuses {
+            match status {
+                ExecutionStatus::Success => succeeded += 1,
+                status if status.is_terminal() => unsuccessful += 1,
+                _ => return MatrixGroupStatus::Running,
+            }
+        }
+        match (succeeded, unsuccessful) {
+            (_, 0) => MatrixGroupStatus::Success,
+            (0, _) => MatrixGroupStatus::Failed,
+            _ => MatrixGroupStatus::PartialFailure,
+        }
+    }
+}
+
+/// One execution of a matrix group report
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixGroupRun {
+    pub execution_id: Uuid,
+    pub index: usize,
+    pub parameters: BTreeMap<String, String>,
+    pub status: ExecutionStatus,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+}
+
+/// Grouped status of the executions of one fanned-out run
+#[derive(Debug, Clone, Serialize)]
+pub struct MatrixGroupReport {
+    pub group_id: Uuid,
+    pub job_id: Uuid,
+    pub status: MatrixGroupStatus,
+    /// Executions in the group
+    pub total: usize,
+    /// Executions per status, e.g. `{"success": 2, "failed": 1}`
+    pub counts: BTreeMap<String, usize>,
+    /// Executions ordered by their position in the matrix
+    pub runs: Vec<MatrixGroupRun>,
+}
+
+impl MatrixGroupReport {
+    /// Build the report of a group from its executions; `None` without any
+    pub fn from_executions(group_id: Uuid, executions: Vec<JobExecution>) -> Option<Self> {
+        let job_id = executions.first()?.job_id;
+
+        let mut runs: Vec<MatrixGroupRun> = executions
+            .into_iter()
+            .filter_map(|execution| {
+                let run = MatrixRun::from_trigger_metadata(execution.trigger_metadata.as_ref())
+                    .filter(|run| run.group_id == group_id)?;
+                Some(MatrixGroupRun {
+                    execution_id: execution.id,
+                    index: run.index,
+                    parameters: run.parameters,
+                    status: execution.status,
+                    started_at: execution.started_at,
+                    completed_at: execution.completed_at,
+                    error: execution.error,
+                })
+            })
+            .collect();
+        if runs.is_empty() {
+            return None;
+        }
+        runs.sort_by_key(|run| run.index);
+
+        let mut counts = BTreeMap::new();
+        for run in &runs {
+            *counts.entry(run.status.to_string()).or_insert(0) += 1;
+        }
+
+        Some(Self {
+            group_id,
+            job_id,
+            status: MatrixGroupStatus::summarize(runs.iter().map(|run| &run.status)),
+            total: runs.len(),
+            counts,
+            runs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(name, values)| {
+                (
+                    name.to_string(),
+                    values.iter().map(|v| v.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_validate_parameter_matrix() {
+        assert!(validate_parameter_matrix(&BTreeMap::new()).is_ok());
+        assert!(validate_parameter_matrix(&matrix(&[("branch", &["HN", "HCM", "DN"])])).is_ok());
+
+        assert!(validate_parameter_matrix(&matrix(&[("1branch", &["HN"])])).is_err());
+        assert!(validate_parameter_matrix(&matrix(&[("branch", &[])])).is_err());
+        assert!(validate_parameter_matrix(&matrix(&[("branch", &["HN", " "])])).is_err());
+        assert!(validate_parameter_matrix(&matrix(&[("branch", &["HN", "HN"])])).is_err());
+
+        let values: Vec<String> = (0..8).map(|i| i.to_string()).collect();
+        let values: Vec<&str> = values.iter().map(String::as_str).collect();
+        let too_many = matrix(&[("a", &values), ("b", &values)]);
+        let err = validate_parameter_matrix(&too_many).unwrap_err();
+        assert!(err.to_string().contains("64"));
+    }
+
+    #[test]
+    fn test_combinations() {
+        assert!(combinations(&BTreeMap::new()).is_empty());
+
+        let combos = combinations(&matrix(&[("branch", &["HN", "HCM"]), ("env", &["a", "b"])]));
+        let pairs: Vec<(String, String)> = combos
+            .iter()
+            .map(|c| (c["branch"].clone(), c["env"].clone()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("HN".to_string(), "a".to_string()),
+                ("HN".to_string(), "b".to_string()),
+                ("HCM".to_string(), "a".to_string()),
+                ("HCM".to_string(), "b".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scheduled_executions_fan_out() {
+        let job_id = Uuid::new_v4();
+        assert_eq!(scheduled_executions(job_id, &BTreeMap::new()).len(), 1);
+
+        let executions = scheduled_executions(job_id, &matrix(&[("branch", &["HN", "HCM"])]));
+        assert_eq!(executions.len(), 2);
+
+        let runs: Vec<MatrixRun> = executions
+            .iter()
+            .map(|e| MatrixRun::from_trigger_metadata(e.trigger_metadata.as_ref()).unwrap())
+            .collect();
+        assert_eq!(runs[0].group_id, runs[1].group_id);
+        assert_eq!((runs[1].index, runs[1].total), (1, 2));
+        assert_ne!(executions[0].idempotency_key, executions[1].idempotency_key);
+        assert_eq!(
+            executions[1].variable_overrides().get("branch"),
+            Some(&serde_json::json!("HCM"))
+        );
+    }
+
+    #[test]
+    fn test_group_status_summary() {
+        use ExecutionStatus::*;
+        assert_eq!(
+            MatrixGroupStatus::summarize(&[Success, Running]),
+            MatrixGroupStatus::Running
+        );
+        assert_eq!(
+            MatrixGroupStatus::summarize(&[Success, Success]),
+            MatrixGroupStatus::Success
+        );
+        assert_eq!(
+            MatrixGroupStatus::summarize(&[Success, Failed]),
+            MatrixGroupStatus::PartialFailure
+        );
+        assert_eq!(
+            MatrixGroupStatus::summarize(&[Timeout, Expired]),
+            MatrixGroupStatus::Failed
+        );
+    }
+
+    #[test]
+    fn test_group_report() {
+        let mut executions =
+            scheduled_executions(Uuid::new_v4(), &matrix(&[("branch", &["HN", "HCM"])]));
+        let group_id = matrix_group_id(&executions[0]).unwrap();
+        executions.reverse();
+        executions[0].status = ExecutionStatus::Failed;
+        executions[1].status = ExecutionStatus::Success;
+
+        let report = MatrixGroupReport::from_executions(group_id, executions).unwrap();
+        assert_eq!(report.status, MatrixGroupStatus::PartialFailure);
+        assert_eq!(report.runs[0].parameters["branch"], "HN");
+        assert_eq!(report.counts["failed"], 1);
+        assert!(MatrixGroupReport::from_executions(group_id, Vec::new()).is_none());
+    }
+}
@@ -0,0 +1,75 @@
+// Job message compression
+// Publishers compress a message body with zstd and say so in the `Content-Encoding`
+// header; consumers decode by that header, so compressed and plain messages can share
+// a stream while publishers are switched over.
+
+use crate::errors::QueueError;
+use async_nats::HeaderMap;
+use std::borrow::Cow;
+
+/// Header naming the encoding of a compressed message body
+pub const CONTENT_ENCODING_HEADER: &str = "Content-Encoding";
+
+/// `Content-Encoding` of zstd-compressed bodies
+pub const ZSTD_ENCODING: &str = "zstd";
+
+/// zstd level; low levels keep publishing cheap while still shrinking JSON well
+const ZSTD_LEVEL: i32 = 3;
+
+/// Largest body a compressed message may expand to
+const MAX_DECOMPRESSED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Compress a body with zstd
+pub fn compress(payload: &[u8]) -> Result<Vec<u8>, QueueError> {
+    zstd::bulk::compress(payload, ZSTD_LEVEL).map_err(|e| {
+        QueueError::SerializationFailed(format!("Failed to compress job message: {}", e))
+    })
+}
+
+/// The plain body of a message, decompressed when its headers say it is compressed
+pub fn decode<'a>(
+    payload: &'a [u8],
+    headers: Option<&HeaderMap>,
+) -> Result<Cow<'a, [u8]>, QueueError> {
+    let encoding = headers
+        .and_then(|headers| headers.get(CONTENT_ENCODING_HEADER))
+        .map(|value| value.as_str());
+    match encoding {
+        None => Ok(Cow::Borrowed(payload)),
+        Some(ZSTD_ENCODING) => zstd::bulk::decompress(payload, MAX_DECOMPRESSED_BYTES)
+            .map(Cow::Owned)
+            .map_err(|e| {
+                QueueError::DeserializationFailed(format!(
+                    "Failed to decompress job message: {}",
+                    e
+                ))
+            }),
+        Some(other) => Err(QueueError::DeserializationFailed(format!(
+            "Unsupported message encoding: {}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_by_content_encoding() {
+        let body = br#"{"context": "snapshot"}"#.repeat(100);
+        let compressed = compress(&body).unwrap();
+        assert!(compressed.len() < body.len());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_ENCODING_HEADER, ZSTD_ENCODING);
+        assert_eq!(
+            decode(&compressed, Some(&headers)).unwrap(),
+            body.as_slice()
+        );
+        assert_eq!(decode(&body, None).unwrap(), body.as_slice());
+
+        headers.insert(CONTENT_ENCODING_HEADER, "br");
+        assert!(decode(&body, Some(&headers)).is_err());
+    }
+}
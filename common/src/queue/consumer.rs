@@ -1,16 +1,23 @@
 // Job consumer implementation for NATS JetStream
 
 use crate::errors::QueueError;
+use crate::failover::RegionRole;
+use crate::fault_injection::FaultInjector;
+use crate::models::JobPriority;
+use crate::queue::compression;
 use crate::queue::nats::NatsClient;
-use crate::queue::publisher::JobMessage;
+use crate::queue::publisher::{JobMessage, PAYLOAD_REF_HEADER};
+use crate::storage::StorageService;
+use async_nats::jetstream::consumer::pull::Stream;
 use async_nats::jetstream::consumer::PullConsumer;
 use async_nats::jetstream::Message;
+use futures::stream::SelectAll;
 use futures::StreamExt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Notify;
-use tracing::{error, info, instrument, warn};
+use tokio::sync::{watch, Notify};
+use tracing::{debug, error, info, instrument, warn};
 
 /// Job consumer trait for consuming jobs from the queue
 #[async_trait::async_trait]
@@ -32,11 +39,21 @@ pub type JobHandler = Arc<
 
 /// NATS-based job consumer implementation
 pub struct NatsJobConsumer {
-    consumer: PullConsumer,
+    /// One consumer per job stream (shared and per-tenant) and priority lane
+    consumers: Vec<(JobPriority, PullConsumer)>,
     handler: JobHandler,
     batch_size: usize,
     shutdown_flag: Arc<AtomicBool>,
     shutdown_notify: Arc<Notify>,
+    fault_injector: Option<Arc<FaultInjector>>,
+    region_role: Option<watch::Receiver<RegionRole>>,
+    payload_store: Option<Arc<dyn StorageService>>,
+}
+
+/// Why consumption of a message stream stopped
+enum ConsumeExit {
+    Shutdown,
+    Standby,
 }
 
 impl NatsJobConsumer {
@@ -45,15 +62,18 @@ impl NatsJobConsumer {
     pub async fn new(client: NatsClient, handler: JobHandler) -> Result<Self, QueueError> {
         info!("Creating NATS job consumer");
 
-        // Get or create consumer
-        let consumer = client.get_or_create_consumer().await?;
+        // Get or create a consumer on every job stream
+        let consumers = client.get_or_create_consumers().await?;
 
         Ok(Self {
-            consumer,
+            consumers,
             handler,
             batch_size: 10,
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             shutdown_notify: Arc::new(Notify::new()),
+            fault_injector: None,
+            region_role: None,
+            payload_store: None,
         })
     }
 
@@ -63,6 +83,63 @@ impl NatsJobConsumer {
         self
     }
 
+    /// Redeliver some successfully processed messages to exercise idempotency
+    pub fn with_fault_injector(mut self, injector: Arc<FaultInjector>) -> Self {
+        self.fault_injector = Some(injector);
+        self
+    }
+
+    /// Only consume while the region is active (multi-region failover)
+    pub fn with_region_role(mut self, region_role: watch::Receiver<RegionRole>) -> Self {
+        self.region_role = Some(region_role);
+        self
+    }
+
+    /// Load job messages that publishers offloaded to storage
+    pub fn with_payload_store(mut self, storage: Arc<dyn StorageService>) -> Self {
+        self.payload_store = Some(storage);
+        self
+    }
+
+    fn is_active(&self) -> bool {
+        self.region_role
+            .as_ref()
+            .is_none_or(|role| *role.borrow() == RegionRole::Active)
+    }
+
+    /// Wait until the region is active; returns false if shutdown comes first
+    async fn wait_until_active(&self) -> bool {
+        let Some(mut role) = self.region_role.clone() else {
+            return true;
+        };
+        if *role.borrow_and_update() == RegionRole::Active {
+            return true;
+        }
+
+        info!("Region is standby, not consuming jobs until it is promoted");
+        loop {
+            if self.shutdown_flag.load(Ordering::Relaxed) {
+                return false;
+            }
+
+            tokio::select! {
+                changed = role.changed() => {
+                    if changed.is_err() {
+                        warn!("Region role watcher stopped, not consuming jobs");
+                        return false;
+                    }
+                    if *role.borrow_and_update() == RegionRole::Active {
+                        info!("Region promoted to active, consuming jobs");
+                        return true;
+                    }
+                }
+                _ = self.shutdown_notify.notified() => {
+                    return false;
+                }
+            }
+        }
+    }
+
     /// Process a single message with exactly-once semantics
     #[instrument(skip(self, message), fields(
         message_id = ?message.info().map(|i| i.stream_sequence),
@@ -75,8 +152,22 @@ impl NatsJobConsumer {
 
         info!(stream_sequence = info.stream_sequence, "Processing message");
 
+        // Offloaded messages carry only a reference to the stored job message
+        let payload_ref = message
+            .headers
+            .as_ref()
+            .and_then(|headers| headers.get(PAYLOAD_REF_HEADER))
+            .map(|value| value.as_str().to_string());
+        let payload = match &payload_ref {
+            Some(path) => self.load_offloaded(path).await?,
+            None => message.payload.to_vec(),
+        };
+
+        // Compressed bodies name their encoding in a header
+        let payload = compression::decode(&payload, message.headers.as_ref())?;
+
         // Deserialize job message
-        let job_message: JobMessage = serde_json::from_slice(&message.payload).map_err(|e| {
+        let job_message: JobMessage = serde_json::from_slice(&payload).map_err(|e| {
             QueueError::DeserializationFailed(format!("Failed to deserialize job message: {}", e))
         })?;
 
@@ -87,6 +178,23 @@ impl NatsJobConsumer {
             "Deserialized job message"
         );
 
+        // A delayed message delivered early goes back on the queue until it is due,
+        // without reaching the handler
+        if let Some(delay) = job_message.remaining_delay(chrono::Utc::now()) {
+            message
+                .ack_with(async_nats::jetstream::AckKind::Nak(Some(delay)))
+                .await
+                .map_err(|e| QueueError::AckFailed(format!("Failed to delay message: {}", e)))?;
+
+            debug!(
+                execution_id = %job_message.execution_id,
+                delay_secs = delay.as_secs(),
+                "Delayed message not due yet, redelivery scheduled"
+            );
+
+            return Ok(());
+        }
+
         // Call the handler
         match (self.handler)(job_message.clone()).await {
             Ok(()) => {
@@ -95,6 +203,30 @@ impl NatsJobConsumer {
                     "Job processed successfully"
                 );
 
+                if self
+                    .fault_injector
+                    .as_ref()
+                    .is_some_and(|injector| injector.should_redeliver())
+                {
+                    message
+                        .ack_with(async_nats::jetstream::AckKind::Nak(None))
+                        .await
+                        .map_err(|e| {
+                            QueueError::AckFailed(format!(
+                                "Failed to negative acknowledge message: {}",
+                                e
+                            ))
+                        })?;
+
+                    warn!(
+                        stream_sequence = info.stream_sequence,
+                        execution_id = %job_message.execution_id,
+                        "Injected redelivery of processed message"
+                    );
+
+                    return Ok(());
+                }
+
                 // Acknowledge the message (exactly-once delivery)
                 message.ack().await.map_err(|e| {
                     QueueError::AckFailed(format!("Failed to acknowledge message: {}", e))
@@ -105,6 +237,10 @@ impl NatsJobConsumer {
                     "Message acknowledged"
                 );
 
+                if let Some(path) = &payload_ref {
+                    self.delete_offloaded(path).await;
+                }
+
                 Ok(())
             }
             Err(e) => {
@@ -137,64 +273,126 @@ impl NatsJobConsumer {
             }
         }
     }
-}
 
-#[async_trait::async_trait]
-impl JobConsumer for NatsJobConsumer {
-    #[instrument(skip(self))]
-    async fn start(&self) -> Result<(), QueueError> {
-        info!("Starting job consumer");
-
-        // Create a stream of messages
-        let mut messages = self.consumer.messages().await.map_err(|e| {
-            QueueError::ConsumeFailed(format!("Failed to create message stream: {}", e))
+    /// Read an offloaded job message from storage
+    async fn load_offloaded(&self, path: &str) -> Result<Vec<u8>, QueueError> {
+        let storage = self.payload_store.as_ref().ok_or_else(|| {
+            QueueError::DeserializationFailed(format!(
+                "Job message was offloaded to {} but no payload store is configured",
+                path
+            ))
         })?;
+        storage.load_file(path).await.map_err(|e| {
+            QueueError::DeserializationFailed(format!(
+                "Failed to load offloaded job message {}: {}",
+                path, e
+            ))
+        })
+    }
 
-        info!("Consumer started, waiting for messages");
+    /// Remove an offloaded job message once it is acknowledged; a leftover file
+    /// only wastes space
+    async fn delete_offloaded(&self, path: &str) {
+        if let Some(storage) = &self.payload_store {
+            if let Err(e) = storage.delete_file(path).await {
+                warn!(payload_ref = %path, error = %e, "Failed to delete offloaded job message");
+            }
+        }
+    }
 
-        // Process messages until shutdown is requested
+    /// Process messages until shutdown is requested or the region becomes standby
+    ///
+    /// Lanes are ordered high to low priority; a lower lane is only read when every
+    /// higher one has nothing ready.
+    async fn consume(&self, lanes: &mut [SelectAll<Stream>; 3]) -> ConsumeExit {
+        let [high, normal, low] = lanes;
         loop {
             // Check shutdown flag
             if self.shutdown_flag.load(Ordering::Relaxed) {
                 info!("Shutdown requested, stopping consumer");
-                break;
+                return ConsumeExit::Shutdown;
+            }
+            if !self.is_active() {
+                return ConsumeExit::Standby;
             }
 
             // Fetch next batch of messages with timeout
             let timeout_duration = Duration::from_secs(5);
 
-            tokio::select! {
-                // Wait for next message
-                message_result = messages.next() => {
-                    match message_result {
-                        Some(Ok(message)) => {
-                            // Process message
-                            if let Err(e) = self.process_message(message).await {
-                                error!(error = %e, "Failed to process message");
-                                // Continue processing other messages
-                            }
-                        }
-                        Some(Err(e)) => {
-                            error!(error = %e, "Error receiving message");
-                            // Wait a bit before retrying
-                            tokio::time::sleep(Duration::from_secs(1)).await;
-                        }
-                        None => {
-                            warn!("Message stream ended unexpectedly");
-                            break;
-                        }
-                    }
-                }
+            let message_result = tokio::select! {
+                biased;
+
                 // Wait for shutdown notification
                 _ = self.shutdown_notify.notified() => {
                     info!("Shutdown notification received");
-                    break;
+                    return ConsumeExit::Shutdown;
                 }
-                // Timeout to check shutdown flag periodically
+                // Wait for next message, highest priority first
+                message_result = high.next(), if !high.is_empty() => message_result,
+                message_result = normal.next(), if !normal.is_empty() => message_result,
+                message_result = low.next(), if !low.is_empty() => message_result,
+                // Timeout to check shutdown flag and region role periodically
                 _ = tokio::time::sleep(timeout_duration) => {
-                    // Just loop back to check shutdown flag
                     continue;
                 }
+            };
+
+            match message_result {
+                Some(Ok(message)) => {
+                    // Process message
+                    if let Err(e) = self.process_message(message).await {
+                        error!(error = %e, "Failed to process message");
+                        // Continue processing other messages
+                    }
+                }
+                Some(Err(e)) => {
+                    error!(error = %e, "Error receiving message");
+                    // Wait a bit before retrying
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+                None => {
+                    warn!("Message stream ended unexpectedly");
+                    return ConsumeExit::Shutdown;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl JobConsumer for NatsJobConsumer {
+    #[instrument(skip(self))]
+    async fn start(&self) -> Result<(), QueueError> {
+        info!("Starting job consumer");
+
+        // A standby region holds no message stream, so it pulls nothing from NATS;
+        // after a demotion the stream is dropped and unacked messages are redelivered
+        loop {
+            if !self.wait_until_active().await {
+                break;
+            }
+
+            // Pull from every job stream, so a backlog in one tenant's stream doesn't
+            // starve the others; each priority gets its own lane
+            let mut lanes = [SelectAll::new(), SelectAll::new(), SelectAll::new()];
+            for (priority, consumer) in &self.consumers {
+                let lane = match priority {
+                    JobPriority::High => &mut lanes[0],
+                    JobPriority::Normal => &mut lanes[1],
+                    JobPriority::Low => &mut lanes[2],
+                };
+                lane.push(consumer.messages().await.map_err(|e| {
+                    QueueError::ConsumeFailed(format!("Failed to create message stream: {}", e))
+                })?);
+            }
+
+            info!("Consumer started, waiting for messages");
+
+            match self.consume(&mut lanes).await {
+                ConsumeExit::Shutdown => break,
+                ConsumeExit::Standby => {
+                    info!("Region demoted to standby, stopped consuming jobs");
+                }
             }
         }
 
@@ -223,6 +421,10 @@ mod tests {
             idempotency_key: "test-key".to_string(),
             attempt: 1,
             published_at: Utc::now(),
+            not_before: None,
+            retry_attempt: None,
+            holds: None,
+            priority: JobPriority::Normal,
         };
 
         let json = serde_json::to_vec(&message).unwrap();
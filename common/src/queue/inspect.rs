@@ -0,0 +1,311 @@
+// JetStream queue inspection for operators
+// Purpose: Stream/consumer stats, message peeking and purging without the nats CLI
+
+use crate::errors::QueueError;
+use crate::queue::compression;
+use crate::queue::nats::NatsClient;
+use crate::queue::publisher::JobMessage;
+use async_nats::jetstream::stream::Stream;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::Serialize;
+use std::collections::HashMap;
+use tracing::{info, instrument};
+
+/// Most messages returned by a single peek
+pub const MAX_PEEK_MESSAGES: usize = 100;
+
+/// Most sequence numbers a single peek looks up, bounding the cost of sparse streams
+const MAX_PEEK_SCAN: u64 = 1_000;
+
+/// Stream-level counters
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamStats {
+    pub name: String,
+    pub subjects: Vec<String>,
+    pub messages: u64,
+    pub bytes: u64,
+    pub first_sequence: u64,
+    pub last_sequence: u64,
+    pub first_timestamp: Option<DateTime<Utc>>,
+    pub last_timestamp: Option<DateTime<Utc>>,
+    pub consumer_count: usize,
+}
+
+/// Per-consumer delivery counters
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsumerStats {
+    pub name: String,
+    /// Messages not yet delivered to the consumer
+    pub num_pending: u64,
+    /// Messages delivered but not yet acknowledged
+    pub num_ack_pending: usize,
+    /// Messages delivered more than once
+    pub num_redelivered: usize,
+    /// Pull requests waiting for messages
+    pub num_waiting: usize,
+    pub delivered_stream_sequence: u64,
+    pub ack_floor_stream_sequence: u64,
+    pub max_deliver: i64,
+}
+
+/// Snapshot of the job queue
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStats {
+    pub stream: StreamStats,
+    pub consumers: Vec<ConsumerStats>,
+}
+
+/// A message read from the stream without consuming it
+#[derive(Debug, Clone, Serialize)]
+pub struct PeekedMessage {
+    pub sequence: u64,
+    pub subject: String,
+    pub published_at: Option<DateTime<Utc>>,
+    pub headers: HashMap<String, String>,
+    /// Decoded job message, when the payload is one
+    pub job: Option<JobMessage>,
+    /// Raw payload text, when it isn't a job message
+    pub payload: Option<String>,
+    pub size_bytes: usize,
+}
+
+/// Result of a purge
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeResult {
+    pub purged: u64,
+    pub subject: Option<String>,
+}
+
+/// Read-mostly view of the job stream for the admin API
+pub struct QueueInspector<'a> {
+    client: &'a NatsClient,
+}
+
+impl<'a> QueueInspector<'a> {
+    pub fn new(client: &'a NatsClient) -> Self {
+        Self { client }
+    }
+
+    async fn stream(&self) -> Result<Stream, QueueError> {
+        let name = &self.client.config().stream_name;
+        self.client
+            .jetstream()
+            .get_stream(name)
+            .await
+            .map_err(|e| QueueError::StreamNotFound(format!("{}: {}", name, e)))
+    }
+
+    /// Stream counters plus the counters of every consumer on the stream
+    #[instrument(skip(self))]
+    pub async fn stats(&self) -> Result<QueueStats, QueueError> {
+        let mut stream = self.stream().await?;
+        let info = stream
+            .info()
+            .await
+            .map_err(|e| QueueError::HealthCheck(format!("Failed to read stream info: {}", e)))?
+            .clone();
+
+        let mut consumers: Vec<ConsumerStats> = stream
+            .consumers()
+            .map_ok(|consumer| ConsumerStats {
+                name: consumer.name,
+                num_pending: consumer.num_pending,
+                num_ack_pending: consumer.num_ack_pending,
+                num_redelivered: consumer.num_redelivered,
+                num_waiting: consumer.num_waiting,
+                delivered_stream_sequence: consumer.delivered.stream_sequence,
+                ack_floor_stream_sequence: consumer.ack_floor.stream_sequence,
+                max_deliver: consumer.config.max_deliver,
+            })
+            .try_collect()
+            .await
+            .map_err(|e| QueueError::HealthCheck(format!("Failed to list consumers: {}", e)))?;
+        consumers.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(QueueStats {
+            stream: StreamStats {
+                name: info.config.name,
+                subjects: info.config.subjects,
+                messages: info.state.messages,
+                bytes: info.state.bytes,
+                first_sequence: info.state.first_sequence,
+                last_sequence: info.state.last_sequence,
+                first_timestamp: to_chrono(info.state.first_timestamp),
+                last_timestamp: to_chrono(info.state.last_timestamp),
+                consumer_count: info.state.consumer_count,
+            },
+            consumers,
+        })
+    }
+
+    /// Read up to `limit` messages starting at sequence `start` (default: the oldest)
+    ///
+    /// Messages stay in the stream; sequences removed by acks or purges are skipped,
+    /// and at most `MAX_PEEK_SCAN` sequences are looked up per call.
+    #[instrument(skip(self))]
+    pub async fn peek(
+        &self,
+        start: Option<u64>,
+        limit: usize,
+    ) -> Result<Vec<PeekedMessage>, QueueError> {
+        let mut stream = self.stream().await?;
+        let state = stream
+            .info()
+            .await
+            .map_err(|e| QueueError::HealthCheck(format!("Failed to read stream info: {}", e)))?
+            .state;
+
+        let limit = limit.clamp(1, MAX_PEEK_MESSAGES);
+        let mut sequence = start
+            .unwrap_or(state.first_sequence)
+            .max(state.first_sequence);
+        let scan_end = state
+            .last_sequence
+            .min(sequence.saturating_add(MAX_PEEK_SCAN - 1));
+        let mut messages = Vec::new();
+
+        while messages.len() < limit && sequence <= scan_end && state.messages > 0 {
+            // Interior gaps are expected: acked messages are removed from work-queue streams
+            if let Ok(raw) = stream.get_raw_message(sequence).await {
+                let published_at = to_chrono(raw.time);
+                let message = async_nats::Message::try_from(raw).map_err(|e| {
+                    QueueError::DeserializationFailed(format!(
+                        "Failed to decode message {}: {}",
+                        sequence, e
+                    ))
+                })?;
+                messages.push(peeked_message(sequence, published_at, message));
+            }
+            sequence += 1;
+        }
+
+        Ok(messages)
+    }
+
+    /// Remove messages from the stream, optionally only those on `subject`
+    #[instrument(skip(self))]
+    pub async fn purge(&self, subject: Option<&str>) -> Result<PurgeResult, QueueError> {
+        let stream = self.stream().await?;
+        let response = match subject {
+            Some(subject) => stream.purge().filter(subject).await,
+            None => stream.purge().await,
+        }
+        .map_err(|e| QueueError::ConsumeFailed(format!("Failed to purge stream: {}", e)))?;
+
+        info!(purged = response.purged, subject = ?subject, "Job stream purged");
+        Ok(PurgeResult {
+            purged: response.purged,
+            subject: subject.map(str::to_string),
+        })
+    }
+}
+
+fn peeked_message(
+    sequence: u64,
+    published_at: Option<DateTime<Utc>>,
+    message: async_nats::Message,
+) -> PeekedMessage {
+    let headers = message
+        .headers
+        .as_ref()
+        .map(|headers| {
+            headers
+                .iter()
+                .filter_map(|(name, values)| {
+                    values
+                        .first()
+                        .map(|value| (name.to_string(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let body = compression::decode(&message.payload, message.headers.as_ref())
+        .unwrap_or(std::borrow::Cow::Borrowed(&message.payload[..]));
+    let job = serde_json::from_slice::<JobMessage>(&body).ok();
+    let payload = job
+        .is_none()
+        .then(|| String::from_utf8_lossy(&body).into_owned());
+
+    PeekedMessage {
+        sequence,
+        subject: message.subject.to_string(),
+        published_at,
+        headers,
+        job,
+        payload,
+        size_bytes: message.payload.len(),
+    }
+}
+
+/// JetStream reports an all-zero timestamp for empty streams
+fn to_chrono(timestamp: time::OffsetDateTime) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(timestamp.unix_timestamp(), timestamp.nanosecond())
+        .filter(|t| t.timestamp() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::JobExecution;
+    use uuid::Uuid;
+
+    fn message(payload: Vec<u8>) -> async_nats::Message {
+        let mut headers = async_nats::HeaderMap::new();
+        headers.insert("Nats-Msg-Id", "manual-1");
+        async_nats::Message {
+            subject: "jobs.1234".into(),
+            reply: None,
+            length: payload.len(),
+            payload: payload.into(),
+            headers: Some(headers),
+            status: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_peeked_message_decodes_job_messages() {
+        let execution = JobExecution::new_manual(Uuid::new_v4(), "alice".to_string());
+        let payload = serde_json::to_vec(&JobMessage::from(&execution)).unwrap();
+
+        let peeked = peeked_message(7, None, message(payload));
+        assert_eq!(peeked.sequence, 7);
+        assert_eq!(peeked.subject, "jobs.1234");
+        assert_eq!(peeked.headers["Nats-Msg-Id"], "manual-1");
+        assert_eq!(peeked.job.unwrap().execution_id, execution.id);
+        assert!(peeked.payload.is_none());
+    }
+
+    #[test]
+    fn test_peeked_message_decompresses_job_messages() {
+        let execution = JobExecution::new_manual(Uuid::new_v4(), "alice".to_string());
+        let payload = serde_json::to_vec(&JobMessage::from(&execution)).unwrap();
+        let mut message = message(compression::compress(&payload).unwrap());
+        message.headers.as_mut().unwrap().insert(
+            compression::CONTENT_ENCODING_HEADER,
+            compression::ZSTD_ENCODING,
+        );
+
+        let peeked = peeked_message(9, None, message);
+        assert_eq!(peeked.job.unwrap().execution_id, execution.id);
+        assert_eq!(peeked.headers["Content-Encoding"], "zstd");
+    }
+
+    #[test]
+    fn test_peeked_message_keeps_unknown_payloads_as_text() {
+        let peeked = peeked_message(8, None, message(b"not a job".to_vec()));
+        assert!(peeked.job.is_none());
+        assert_eq!(peeked.payload.as_deref(), Some("not a job"));
+        assert_eq!(peeked.size_bytes, 9);
+    }
+
+    #[test]
+    fn test_empty_stream_timestamps_are_omitted() {
+        assert_eq!(to_chrono(time::OffsetDateTime::UNIX_EPOCH), None);
+        let timestamp =
+            to_chrono(time::OffsetDateTime::from_unix_timestamp(1_760_000_000).unwrap());
+        assert_eq!(timestamp.map(|t| t.timestamp()), Some(1_760_000_000));
+    }
+}
@@ -1,9 +1,12 @@
 // Queue module for NATS JetStream integration
 
+pub mod compression;
 pub mod consumer;
+pub mod inspect;
 pub mod nats;
 pub mod publisher;
 
 pub use consumer::{JobConsumer, JobHandler, NatsJobConsumer};
+pub use inspect::QueueInspector;
 pub use nats::{NatsClient, NatsConfig};
-pub use publisher::{JobMessage, JobPublisher, NatsJobPublisher};
+pub use publisher::{JobMessage, JobPublisher, NatsJobPublisher, OffloadedMessage};
@@ -1,14 +1,25 @@
 // NATS JetStream client implementation for job queue
 
+use crate::config::{MessageCompression, StreamLimits};
 use crate::errors::QueueError;
+use crate::models::JobPriority;
 use async_nats::jetstream::{
-    consumer::PullConsumer,
-    stream::{Config as StreamConfig, RetentionPolicy, Stream},
+    consumer::{pull::Config as PullConsumerConfig, AckPolicy, PullConsumer},
+    stream::{Config as StreamConfig, DiscardPolicy, RetentionPolicy, Stream},
     Context as JetStreamContext,
 };
+use async_nats::ConnectOptions;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+/// Subject prefix of jobs queued on a tenant's own stream
+///
+/// Kept outside `jobs.>` so tenant streams don't overlap the shared one.
+const TENANT_SUBJECT_PREFIX: &str = "tenant_jobs";
 
 /// NATS configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,10 +34,26 @@ pub struct NatsConfig {
     pub max_age_seconds: u64,
     /// Maximum number of messages to retain
     pub max_messages: i64,
+    /// Maximum stream size in bytes, -1 for no limit
+    pub max_bytes: i64,
     /// Consumer name for workers
     pub consumer_name: String,
     /// Maximum number of delivery attempts
     pub max_deliver: i64,
+    /// Tenants with a dedicated stream, and that stream's limits
+    pub tenant_streams: BTreeMap<String, StreamLimits>,
+    /// Largest message published inline, before the server's `max_payload` applies
+    pub max_message_bytes: usize,
+    /// Compression of published messages
+    pub compression: MessageCompression,
+    /// Messages smaller than this are published uncompressed
+    pub compression_min_bytes: usize,
+    /// Number of job subject shards, 0 when subjects aren't sharded
+    pub shards: u32,
+    /// Shards this process consumes; empty for all of them
+    pub worker_shards: Vec<u32>,
+    /// Queue high and low priority jobs on their own subjects, consumed high first
+    pub priority_subjects: bool,
 }
 
 impl Default for NatsConfig {
@@ -37,12 +64,275 @@ impl Default for NatsConfig {
             subject: "jobs.>".to_string(),
             max_age_seconds: 86400, // 24 hours
             max_messages: 1_000_000,
+            max_bytes: -1,
             consumer_name: "job-workers".to_string(),
             max_deliver: 10,
+            tenant_streams: BTreeMap::new(),
+            max_message_bytes: 1024 * 1024,
+            compression: MessageCompression::None,
+            compression_min_bytes: 1024,
+            shards: 0,
+            worker_shards: Vec::new(),
+            priority_subjects: false,
+        }
+    }
+}
+
+impl NatsConfig {
+    /// Subject for a job of `tenant_id`: on the tenant's own stream when it has one,
+    /// otherwise on the shared stream
+    pub fn job_subject(
+        &self,
+        tenant_id: Option<&str>,
+        job_id: &Uuid,
+        priority: JobPriority,
+    ) -> String {
+        let lane = self.lane(priority);
+        match tenant_id.filter(|tenant| self.tenant_streams.contains_key(*tenant)) {
+            Some(tenant) => tenant_job_subject(tenant, job_id, self.shards, lane),
+            None => shared_job_subject(&self.stream_name, job_id, self.shards, lane),
+        }
+    }
+
+    /// Priority lane of the subjects for jobs of `priority`, when priorities have
+    /// their own subjects
+    pub fn lane(&self, priority: JobPriority) -> Option<JobPriority> {
+        self.priority_subjects.then_some(priority)
+    }
+
+    /// Subject prefix of the jobs on each stream, in the order of `stream_configs`
+    fn job_subject_prefixes(&self) -> Vec<String> {
+        std::iter::once(shared_subject_prefix(&self.stream_name))
+            .chain(self.tenant_streams.keys().map(|t| tenant_subject_prefix(t)))
+            .collect()
+    }
+
+    /// Durable consumers to create on the stream whose jobs are under `prefix`, with
+    /// the priority of the jobs each one consumes
+    ///
+    /// Unsharded, every worker shares one consumer. Sharded, each consumed shard gets
+    /// its own consumer with a single message in flight, so executions of a job are
+    /// handled one at a time even when several workers consume the same shard. With
+    /// priority subjects, every priority gets its own consumers.
+    pub fn consumer_configs(&self, prefix: &str) -> Vec<(JobPriority, PullConsumerConfig)> {
+        if !self.priority_subjects {
+            return self
+                .lane_consumer_configs(prefix, &self.consumer_name, false)
+                .into_iter()
+                .map(|config| (JobPriority::Normal, config))
+                .collect();
+        }
+
+        JobPriority::ALL
+            .into_iter()
+            .flat_map(|priority| {
+                let name = match priority {
+                    JobPriority::Normal => self.consumer_name.clone(),
+                    _ => format!("{}-{}", self.consumer_name, priority),
+                };
+                self.lane_consumer_configs(&lane_prefix(prefix, Some(priority)), &name, true)
+                    .into_iter()
+                    .map(move |config| (priority, config))
+            })
+            .collect()
+    }
+
+    /// Consumers of the jobs under `prefix`; `filtered` when other subjects on the
+    /// stream belong to other consumers
+    fn lane_consumer_configs(
+        &self,
+        prefix: &str,
+        consumer_name: &str,
+        filtered: bool,
+    ) -> Vec<PullConsumerConfig> {
+        let base = PullConsumerConfig {
+            durable_name: Some(consumer_name.to_string()),
+            ack_policy: AckPolicy::Explicit,
+            max_deliver: self.max_deliver,
+            ack_wait: Duration::from_secs(300), // 5 minutes timeout
+            ..Default::default()
+        };
+        if self.shards == 0 {
+            if !filtered {
+                return vec![base];
+            }
+            return vec![PullConsumerConfig {
+                filter_subject: format!("{}.*", prefix),
+                ..base
+            }];
+        }
+
+        let shards: Vec<u32> = if self.worker_shards.is_empty() {
+            (0..self.shards).collect()
+        } else {
+            self.worker_shards.clone()
+        };
+        shards
+            .into_iter()
+            .map(|shard| PullConsumerConfig {
+                durable_name: Some(format!("{}-shard-{}", consumer_name, shard)),
+                filter_subject: format!("{}.shard.{}.*", prefix, shard),
+                max_ack_pending: 1,
+                ..base.clone()
+            })
+            .collect()
+    }
+
+    /// Every job stream to provision: the shared one, then one per configured tenant
+    pub fn stream_configs(&self) -> Vec<StreamConfig> {
+        let shared = StreamConfig {
+            name: self.stream_name.clone(),
+            subjects: vec![self.subject.clone()],
+            retention: RetentionPolicy::WorkQueue, // Messages deleted after acknowledgment
+            max_age: Duration::from_secs(self.max_age_seconds),
+            max_messages: self.max_messages,
+            max_bytes: self.max_bytes,
+            ..Default::default()
+        };
+
+        // A full tenant stream rejects that tenant's new jobs instead of dropping
+        // queued ones
+        let tenants = self
+            .tenant_streams
+            .iter()
+            .map(|(tenant, limits)| StreamConfig {
+                name: tenant_stream_name(&self.stream_name, tenant),
+                subjects: vec![format!("{}.{}.>", TENANT_SUBJECT_PREFIX, tenant)],
+                retention: RetentionPolicy::WorkQueue,
+                discard: DiscardPolicy::New,
+                max_age: Duration::from_secs(limits.max_age_seconds),
+                max_messages: limits.max_messages,
+                max_bytes: limits.max_bytes,
+                ..Default::default()
+            });
+
+        std::iter::once(shared).chain(tenants).collect()
+    }
+}
+
+/// Shard of a job, when subjects are split into `shards` shards
+pub fn job_shard(job_id: &Uuid, shards: u32) -> Option<u32> {
+    (shards > 0).then(|| (job_id.as_u128() % u128::from(shards)) as u32)
+}
+
+/// Subject of a job on the shared stream, in priority `lane` when priorities have
+/// their own subjects
+pub fn shared_job_subject(
+    stream_name: &str,
+    job_id: &Uuid,
+    shards: u32,
+    lane: Option<JobPriority>,
+) -> String {
+    job_subject(
+        &lane_prefix(&shared_subject_prefix(stream_name), lane),
+        job_id,
+        shards,
+    )
+}
+
+/// Subject of a job on its tenant's stream, in priority `lane` when priorities have
+/// their own subjects
+pub fn tenant_job_subject(
+    tenant: &str,
+    job_id: &Uuid,
+    shards: u32,
+    lane: Option<JobPriority>,
+) -> String {
+    job_subject(
+        &lane_prefix(&tenant_subject_prefix(tenant), lane),
+        job_id,
+        shards,
+    )
+}
+
+fn shared_subject_prefix(stream_name: &str) -> String {
+    format!("jobs.{}", stream_name.to_lowercase())
+}
+
+fn tenant_subject_prefix(tenant: &str) -> String {
+    format!("{}.{}", TENANT_SUBJECT_PREFIX, tenant)
+}
+
+/// `<prefix>.<priority>` for high and low priority jobs; normal priority jobs stay
+/// under the stream's prefix, so enabling priority subjects doesn't move them
+fn lane_prefix(prefix: &str, lane: Option<JobPriority>) -> String {
+    match lane {
+        Some(priority @ (JobPriority::High | JobPriority::Low)) => {
+            format!("{}.{}", prefix, priority)
         }
+        _ => prefix.to_string(),
+    }
+}
+
+/// `<prefix>.<job id>`, or `<prefix>.shard.<shard>.<job id>` when sharded
+fn job_subject(prefix: &str, job_id: &Uuid, shards: u32) -> String {
+    match job_shard(job_id, shards) {
+        Some(shard) => format!("{}.shard.{}.{}", prefix, shard, job_id),
+        None => format!("{}.{}", prefix, job_id),
     }
 }
 
+/// Name of a tenant's job stream
+pub fn tenant_stream_name(stream_name: &str, tenant: &str) -> String {
+    format!("{}_{}", stream_name, tenant)
+}
+
+/// Build connection options with the TLS and credentials from the settings
+pub async fn connect_options(
+    config: &crate::config::NatsConfig,
+) -> Result<ConnectOptions, QueueError> {
+    let auth = &config.auth;
+    let mut options = if let Some(path) = &auth.credentials_file {
+        ConnectOptions::with_credentials_file(path)
+            .await
+            .map_err(|e| {
+                QueueError::Connection(format!(
+                    "Failed to read NATS credentials file {}: {}",
+                    path, e
+                ))
+            })?
+    } else if let Some(seed) = &auth.nkey_seed {
+        ConnectOptions::with_nkey(seed.clone())
+    } else if let Some(token) = &auth.token {
+        ConnectOptions::with_token(token.clone())
+    } else if let (Some(user), Some(password)) = (&auth.user, &auth.password) {
+        ConnectOptions::with_user_and_password(user.clone(), password.clone())
+    } else {
+        ConnectOptions::new()
+    };
+
+    let tls = &config.tls;
+    if tls.required {
+        options = options.require_tls(true);
+    }
+    if let Some(ca_file) = &tls.ca_file {
+        options = options.add_root_certificates(PathBuf::from(ca_file));
+    }
+    if let (Some(cert), Some(key)) = (&tls.client_cert_file, &tls.client_key_file) {
+        options = options.add_client_certificate(PathBuf::from(cert), PathBuf::from(key));
+    }
+
+    Ok(options)
+}
+
+/// Connect to NATS using the URL, TLS and credentials from the settings
+#[instrument(skip(config), fields(url = %config.url, tls_required = config.tls.required))]
+pub async fn connect(config: &crate::config::NatsConfig) -> Result<async_nats::Client, QueueError> {
+    let auth_method = config
+        .auth
+        .configured_methods()
+        .first()
+        .copied()
+        .unwrap_or("none");
+    info!(auth_method, "Connecting to NATS server");
+
+    connect_options(config)
+        .await?
+        .connect(config.url.as_str())
+        .await
+        .map_err(|e| QueueError::Connection(format!("Failed to connect to NATS: {}", e)))
+}
+
 /// NATS JetStream client
 pub struct NatsClient {
     client: async_nats::Client,
@@ -62,96 +352,90 @@ impl NatsClient {
         }
     }
 
-    /// Create a new NATS client and connect to the server
-    #[instrument(skip(config), fields(url = %config.url))]
-    pub async fn new(config: NatsConfig) -> Result<Self, QueueError> {
-        info!("Connecting to NATS server");
-
-        // Connect to NATS server
-        let client = async_nats::connect(&config.url)
-            .await
-            .map_err(|e| QueueError::Connection(format!("Failed to connect to NATS: {}", e)))?;
-
+    /// Create a new NATS client and connect to the server with the TLS and
+    /// credentials from the settings
+    pub async fn new(
+        settings: &crate::config::NatsConfig,
+        config: NatsConfig,
+    ) -> Result<Self, QueueError> {
+        let client = connect(settings).await?;
         info!("Connected to NATS server successfully");
 
-        // Get JetStream context
-        let jetstream = async_nats::jetstream::new(client.clone());
-
-        Ok(Self {
-            client,
-            jetstream,
-            config,
-        })
+        Ok(Self::from_client(client, config))
     }
 
-    /// Initialize the job stream with retention policy
+    /// Create the shared and per-tenant job streams, applying configured limits
+    ///
+    /// Safe to run from every scheduler and worker at startup; existing streams are
+    /// updated so changed limits take effect.
     #[instrument(skip(self))]
-    pub async fn initialize_stream(&self) -> Result<Stream, QueueError> {
-        info!(
-            stream_name = %self.config.stream_name,
-            "Initializing JetStream stream"
-        );
+    pub async fn initialize_streams(&self) -> Result<Vec<Stream>, QueueError> {
+        let mut streams = Vec::new();
+        for stream_config in self.config.stream_configs() {
+            streams.push(self.initialize_stream(stream_config).await?);
+        }
+        Ok(streams)
+    }
 
-        // Create or get existing stream
-        let stream_config = StreamConfig {
-            name: self.config.stream_name.clone(),
-            subjects: vec![self.config.subject.clone()],
-            retention: RetentionPolicy::WorkQueue, // Messages deleted after acknowledgment
-            max_age: Duration::from_secs(self.config.max_age_seconds),
-            max_messages: self.config.max_messages,
-            ..Default::default()
-        };
+    /// Create a stream, or bring an existing one up to date with its config
+    #[instrument(skip(self, stream_config), fields(stream_name = %stream_config.name))]
+    async fn initialize_stream(&self, stream_config: StreamConfig) -> Result<Stream, QueueError> {
+        info!("Initializing JetStream stream");
 
         let stream = self
             .jetstream
-            .get_or_create_stream(stream_config)
+            .get_or_create_stream(stream_config.clone())
             .await
             .map_err(|e| QueueError::StreamCreation(format!("Failed to create stream: {}", e)))?;
+        // Some settings (retention) can't change on a live stream; keep it running as is
+        if let Err(e) = self.jetstream.update_stream(stream_config).await {
+            warn!(error = %e, "Failed to apply stream limits, keeping the existing ones");
+        }
 
-        info!(
-            stream_name = %self.config.stream_name,
-            "Stream initialized successfully"
-        );
+        info!("Stream initialized successfully");
 
         Ok(stream)
     }
 
-    /// Create or get consumer for job processing
+    /// Create or get the consumers on every job stream, one per consumed shard when
+    /// subjects are sharded
     #[instrument(skip(self))]
-    pub async fn get_or_create_consumer(&self) -> Result<PullConsumer, QueueError> {
-        info!(
-            consumer_name = %self.config.consumer_name,
-            "Creating consumer"
-        );
+    pub async fn get_or_create_consumers(
+        &self,
+    ) -> Result<Vec<(JobPriority, PullConsumer)>, QueueError> {
+        let mut consumers = Vec::new();
+        let streams = self.initialize_streams().await?;
+        for (stream, prefix) in streams.iter().zip(self.config.job_subject_prefixes()) {
+            for (priority, consumer_config) in self.config.consumer_configs(&prefix) {
+                let consumer = self.get_or_create_consumer(stream, consumer_config).await?;
+                consumers.push((priority, consumer));
+            }
+        }
+        Ok(consumers)
+    }
 
-        // Get the stream first
-        let stream = self
-            .jetstream
-            .get_stream(&self.config.stream_name)
-            .await
-            .map_err(|e| QueueError::StreamNotFound(format!("Stream not found: {}", e)))?;
+    /// Create or get consumer for job processing on one stream
+    #[instrument(skip(self, stream, consumer_config), fields(consumer_name = ?consumer_config.durable_name))]
+    async fn get_or_create_consumer(
+        &self,
+        stream: &Stream,
+        consumer_config: PullConsumerConfig,
+    ) -> Result<PullConsumer, QueueError> {
+        info!(filter_subject = %consumer_config.filter_subject, "Creating consumer");
 
-        // Create consumer configuration
-        let consumer_config = async_nats::jetstream::consumer::pull::Config {
-            durable_name: Some(self.config.consumer_name.clone()),
-            ack_policy: async_nats::jetstream::consumer::AckPolicy::Explicit,
-            max_deliver: self.config.max_deliver,
-            ack_wait: Duration::from_secs(300), // 5 minutes timeout
-            ..Default::default()
-        };
+        let name = consumer_config.durable_name.clone().unwrap_or_default();
 
-        // Create or get existing consumer
+        // Create or get existing consumer; a shard consumer can't be added next to an
+        // unsharded one on the same work-queue stream, so switching modes needs the
+        // old consumers removed
         let consumer = stream
-            .get_or_create_consumer(&self.config.consumer_name, consumer_config)
+            .get_or_create_consumer(&name, consumer_config)
             .await
             .map_err(|e| {
-                QueueError::ConsumerCreation(format!("Failed to create consumer: {}", e))
+                QueueError::ConsumerCreation(format!("Failed to create consumer {}: {}", name, e))
             })?;
 
-        info!(
-            consumer_name = %self.config.consumer_name,
-            "Consumer created successfully"
-        );
+        info!("Consumer created successfully");
 
         Ok(consumer)
     }
@@ -171,6 +455,15 @@ impl NatsClient {
         &self.config
     }
 
+    /// Largest message that may be published: the configured limit, lowered to the
+    /// server's `max_payload` when the server announced a smaller one
+    pub fn max_message_bytes(&self) -> usize {
+        match self.client.server_info().max_payload {
+            0 => self.config.max_message_bytes,
+            server_max => self.config.max_message_bytes.min(server_max),
+        }
+    }
+
     /// Health check - verify connection is alive
     #[instrument(skip(self))]
     pub async fn health_check(&self) -> Result<(), QueueError> {
@@ -198,5 +491,134 @@ mod tests {
         assert_eq!(config.max_messages, 1_000_000);
         assert_eq!(config.consumer_name, "job-workers");
         assert_eq!(config.max_deliver, 10);
+        assert_eq!(config.stream_configs().len(), 1);
+    }
+
+    #[test]
+    fn test_tenant_streams_are_isolated() {
+        let mut config = NatsConfig::default();
+        config.tenant_streams.insert(
+            "acme".to_string(),
+            StreamLimits {
+                max_messages: 500,
+                ..StreamLimits::default()
+            },
+        );
+        let job_id = Uuid::new_v4();
+
+        assert_eq!(
+            config.job_subject(Some("acme"), &job_id, JobPriority::Normal),
+            format!("tenant_jobs.acme.{}", job_id)
+        );
+        assert_eq!(
+            config.job_subject(Some("globex"), &job_id, JobPriority::Normal),
+            format!("jobs.jobs.{}", job_id)
+        );
+        assert_eq!(
+            config.job_subject(None, &job_id, JobPriority::Normal),
+            format!("jobs.jobs.{}", job_id)
+        );
+
+        let streams = config.stream_configs();
+        assert_eq!(streams.len(), 2);
+        assert_eq!(streams[1].name, "JOBS_acme");
+        assert_eq!(streams[1].subjects, vec!["tenant_jobs.acme.>".to_string()]);
+        assert_eq!(streams[1].max_messages, 500);
+        assert_eq!(streams[1].discard, DiscardPolicy::New);
+        // The tenant subject isn't captured by the shared stream's `jobs.>`
+        assert!(!streams[1].subjects[0].starts_with("jobs."));
+    }
+
+    #[test]
+    fn test_sharded_subjects_and_consumers() {
+        let mut config = NatsConfig {
+            shards: 4,
+            ..NatsConfig::default()
+        };
+        let job_id = Uuid::from_u128(4 * 1000 + 3);
+
+        assert_eq!(job_shard(&job_id, 0), None);
+        assert_eq!(job_shard(&job_id, 4), Some(3));
+        assert_eq!(
+            config.job_subject(None, &job_id, JobPriority::Normal),
+            format!("jobs.jobs.shard.3.{}", job_id)
+        );
+
+        // Every shard by default, each with one message in flight
+        let consumers: Vec<_> = config
+            .consumer_configs("jobs.jobs")
+            .into_iter()
+            .map(|(_, consumer)| consumer)
+            .collect();
+        assert_eq!(consumers.len(), 4);
+        assert_eq!(
+            consumers[3].durable_name.as_deref(),
+            Some("job-workers-shard-3")
+        );
+        assert_eq!(consumers[3].filter_subject, "jobs.jobs.shard.3.*");
+        assert_eq!(consumers[3].max_ack_pending, 1);
+
+        config.worker_shards = vec![1, 2];
+        let consumers = config.consumer_configs("tenant_jobs.acme");
+        assert_eq!(consumers.len(), 2);
+        assert_eq!(consumers[0].1.filter_subject, "tenant_jobs.acme.shard.1.*");
+
+        config.shards = 0;
+        let consumers = config.consumer_configs("jobs.jobs");
+        assert_eq!(consumers.len(), 1);
+        assert_eq!(consumers[0].0, JobPriority::Normal);
+        assert!(consumers[0].1.filter_subject.is_empty());
+    }
+
+    #[test]
+    fn test_priority_subjects_and_consumers() {
+        let mut config = NatsConfig {
+            priority_subjects: true,
+            ..NatsConfig::default()
+        };
+        let job_id = Uuid::new_v4();
+
+        // Normal priority jobs keep their subject; the others get a lane of their own
+        assert_eq!(
+            config.job_subject(None, &job_id, JobPriority::Normal),
+            format!("jobs.jobs.{}", job_id)
+        );
+        assert_eq!(
+            config.job_subject(None, &job_id, JobPriority::High),
+            format!("jobs.jobs.high.{}", job_id)
+        );
+
+        let consumers = config.consumer_configs("jobs.jobs");
+        let lanes: Vec<_> = consumers
+            .iter()
+            .map(|(priority, consumer)| {
+                (
+                    *priority,
+                    consumer.durable_name.as_deref().unwrap(),
+                    consumer.filter_subject.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            lanes,
+            vec![
+                (JobPriority::High, "job-workers-high", "jobs.jobs.high.*"),
+                (JobPriority::Normal, "job-workers", "jobs.jobs.*"),
+                (JobPriority::Low, "job-workers-low", "jobs.jobs.low.*"),
+            ]
+        );
+
+        config.shards = 2;
+        assert_eq!(
+            config.job_subject(None, &Uuid::from_u128(3), JobPriority::Low),
+            format!("jobs.jobs.low.shard.1.{}", Uuid::from_u128(3))
+        );
+        let consumers = config.consumer_configs("jobs.jobs");
+        assert_eq!(consumers.len(), 6);
+        assert_eq!(consumers[0].1.filter_subject, "jobs.jobs.high.shard.0.*");
+        assert_eq!(
+            consumers[0].1.durable_name.as_deref(),
+            Some("job-workers-high-shard-0")
+        );
     }
 }
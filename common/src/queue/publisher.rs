@@ -1,10 +1,14 @@
 // Job publisher implementation for NATS JetStream
 
+use crate::config::MessageCompression;
 use crate::errors::QueueError;
-use crate::models::JobExecution;
+use crate::models::{Job, JobExecution, JobPriority};
+use crate::queue::compression::{self, CONTENT_ENCODING_HEADER, ZSTD_ENCODING};
 use crate::queue::nats::NatsClient;
+use crate::storage::StorageService;
 use async_nats::jetstream::context::PublishAckFuture;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tracing::{info, instrument, warn};
 use uuid::Uuid;
@@ -22,6 +26,70 @@ pub struct JobMessage {
     pub attempt: i32,
     /// Timestamp when message was published
     pub published_at: chrono::DateTime<chrono::Utc>,
+    /// Earliest time the message may be processed; earlier deliveries go back on the queue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Step retry attempt to resume at, for executions re-queued between retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_attempt: Option<u32>,
+    /// Times the message was put back while a previous run of the job was active
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub holds: Option<u32>,
+    /// Priority of the job, deciding the subject the message is queued on
+    #[serde(default, skip_serializing_if = "JobPriority::is_normal")]
+    pub priority: JobPriority,
+}
+
+impl JobMessage {
+    /// The same message at the priority of its job
+    pub fn with_priority(self, priority: JobPriority) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Message that resumes an execution's failing step at `retry_attempt`, not before
+    /// `not_before`
+    pub fn retry(
+        execution: &JobExecution,
+        retry_attempt: u32,
+        not_before: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            not_before: Some(not_before),
+            retry_attempt: Some(retry_attempt),
+            ..Self::from(execution)
+        }
+    }
+
+    /// The same message, put back on the queue until `not_before` because a previous
+    /// run of the job is still active
+    pub fn held_back(&self, not_before: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            published_at: chrono::Utc::now(),
+            not_before: Some(not_before),
+            holds: Some(self.holds.unwrap_or(0) + 1),
+            ..self.clone()
+        }
+    }
+
+    /// Time left until the message may be processed, if it is delayed
+    pub fn remaining_delay(&self, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+        self.not_before
+            .and_then(|not_before| (not_before - now).to_std().ok())
+            .filter(|delay| !delay.is_zero())
+    }
+
+    /// JetStream deduplication id, distinct for every re-queued retry and every hold
+    /// of an execution
+    pub fn deduplication_id(&self) -> String {
+        let id = match self.retry_attempt {
+            Some(attempt) => format!("{}:retry:{}", self.idempotency_key, attempt),
+            None => self.idempotency_key.clone(),
+        };
+        match self.holds {
+            Some(holds) => format!("{}:hold:{}", id, holds),
+            None => id,
+        }
+    }
 }
 
 impl From<&JobExecution> for JobMessage {
@@ -32,16 +100,83 @@ impl From<&JobExecution> for JobMessage {
             idempotency_key: execution.idempotency_key.clone(),
             attempt: execution.attempt,
             published_at: chrono::Utc::now(),
+            not_before: None,
+            retry_attempt: None,
+            holds: None,
+            priority: JobPriority::Normal,
         }
     }
 }
 
+/// Header carrying the storage path of a job message too large to publish inline
+pub const PAYLOAD_REF_HEADER: &str = "Payload-Ref";
+
+/// Room kept under the size limit for the message headers
+const HEADER_ALLOWANCE: usize = 1024;
+
+/// Storage prefix of offloaded job messages
+const OFFLOAD_PREFIX: &str = "queue-payloads";
+
+/// Body published in place of an offloaded job message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OffloadedMessage {
+    /// Storage path of the serialized job message, also sent in `Payload-Ref`
+    pub payload_ref: String,
+    pub size_bytes: usize,
+}
+
+/// Storage path for an offloaded job message, distinct per retry and hold
+pub fn offload_path(message: &JobMessage) -> String {
+    let name: String = message
+        .deduplication_id()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}/{}/{}.json", OFFLOAD_PREFIX, message.execution_id, name)
+}
+
 /// JobPublisher trait for publishing jobs to the queue
 #[async_trait::async_trait]
 pub trait JobPublisher: Send + Sync {
     /// Publish a job execution to the queue
     async fn publish(&self, execution: &JobExecution) -> Result<(), QueueError>;
 
+    /// Publish an execution of a tenant's job
+    ///
+    /// Publishers with per-tenant streams queue it on the tenant's stream; by default
+    /// the tenant is ignored.
+    async fn publish_for_tenant(
+        &self,
+        execution: &JobExecution,
+        _tenant_id: Option<&str>,
+    ) -> Result<(), QueueError> {
+        self.publish(execution).await
+    }
+
+    /// Publish an execution of `job`, on its tenant's stream and at its priority
+    ///
+    /// Publishers without priority subjects queue it like any other execution.
+    async fn publish_for_job(&self, execution: &JobExecution, job: &Job) -> Result<(), QueueError> {
+        self.publish_for_tenant(execution, job.tenant_id.as_deref())
+            .await
+    }
+
+    /// Publish a message that must not be processed before its `not_before`
+    ///
+    /// Consumers put early deliveries back on the queue with a matching delay, so the
+    /// wait holds no worker slot.
+    async fn publish_delayed(
+        &self,
+        message: &JobMessage,
+        tenant_id: Option<&str>,
+    ) -> Result<(), QueueError>;
+
     /// Publish a job execution with retry logic
     async fn publish_with_retry(
         &self,
@@ -53,18 +188,17 @@ pub trait JobPublisher: Send + Sync {
 /// NATS-based job publisher implementation
 pub struct NatsJobPublisher {
     client: NatsClient,
-    subject_prefix: String,
     publish_timeout: Duration,
+    payload_store: Option<Arc<dyn StorageService>>,
 }
 
 impl NatsJobPublisher {
     /// Create a new NATS job publisher
     pub fn new(client: NatsClient) -> Self {
-        let subject_prefix = format!("jobs.{}", client.config().stream_name.to_lowercase());
         Self {
             client,
-            subject_prefix,
             publish_timeout: Duration::from_secs(5),
+            payload_store: None,
         }
     }
 
@@ -74,42 +208,82 @@ impl NatsJobPublisher {
         self
     }
 
-    /// Get the subject for a job
-    fn get_subject(&self, job_id: &Uuid) -> String {
-        format!("{}.{}", self.subject_prefix, job_id)
+    /// Offload messages over the size limit to storage instead of failing to publish
+    pub fn with_payload_store(mut self, storage: Arc<dyn StorageService>) -> Self {
+        self.payload_store = Some(storage);
+        self
     }
-}
-
-#[async_trait::async_trait]
-impl JobPublisher for NatsJobPublisher {
-    #[instrument(skip(self), fields(
-        execution_id = %execution.id,
-        job_id = %execution.job_id,
-        idempotency_key = %execution.idempotency_key,
-        attempt = execution.attempt
-    ))]
-    async fn publish(&self, execution: &JobExecution) -> Result<(), QueueError> {
-        info!("Publishing job execution to queue");
-
-        // Create message from execution
-        let message = JobMessage::from(execution);
 
+    /// Publish a message on its job's subject and wait for the stream's acknowledgment
+    async fn publish_message(
+        &self,
+        message: &JobMessage,
+        tenant_id: Option<&str>,
+    ) -> Result<(), QueueError> {
         // Serialize message
-        let payload = serde_json::to_vec(&message).map_err(|e| {
+        let mut payload = serde_json::to_vec(message).map_err(|e| {
             QueueError::SerializationFailed(format!("Failed to serialize job message: {}", e))
         })?;
 
-        // Get subject for this job
-        let subject = self.get_subject(&execution.job_id);
+        // Get subject for this job, on the tenant's own stream when it has one, in the
+        // job's shard when subjects are sharded and in its priority's lane
+        let subject =
+            self.client
+                .config()
+                .job_subject(tenant_id, &message.job_id, message.priority);
 
         // Publish to JetStream with deduplication headers
         let jetstream = self.client.jetstream();
 
         // Create headers for deduplication
         let mut headers = async_nats::HeaderMap::new();
-        headers.insert("Nats-Msg-Id", execution.idempotency_key.as_str());
-        headers.insert("Job-Id", execution.job_id.to_string().as_str());
-        headers.insert("Execution-Id", execution.id.to_string().as_str());
+        headers.insert("Nats-Msg-Id", message.deduplication_id().as_str());
+        headers.insert("Job-Id", message.job_id.to_string().as_str());
+        headers.insert("Execution-Id", message.execution_id.to_string().as_str());
+
+        let config = self.client.config();
+        if config.compression == MessageCompression::Zstd
+            && payload.len() >= config.compression_min_bytes
+        {
+            payload = compression::compress(&payload)?;
+            headers.insert(CONTENT_ENCODING_HEADER, ZSTD_ENCODING);
+        }
+
+        // Messages over the limit travel through storage; consumers load them back
+        let max_bytes = self
+            .client
+            .max_message_bytes()
+            .saturating_sub(HEADER_ALLOWANCE);
+        if payload.len() > max_bytes {
+            let storage = self.payload_store.as_ref().ok_or_else(|| {
+                QueueError::PublishFailed(format!(
+                    "Job message of {} bytes exceeds the {} byte limit and no payload store is configured",
+                    payload.len(),
+                    max_bytes
+                ))
+            })?;
+            let path = offload_path(message);
+            storage.store_file(&path, &payload).await.map_err(|e| {
+                QueueError::PublishFailed(format!("Failed to offload job message: {}", e))
+            })?;
+            info!(
+                size_bytes = payload.len(),
+                payload_ref = %path,
+                "Job message offloaded to storage"
+            );
+
+            headers.insert(PAYLOAD_REF_HEADER, path.as_str());
+            payload = serde_json::to_vec(&OffloadedMessage {
+                payload_ref: path,
+                size_bytes: payload.len(),
+            })
+            .map_err(|e| {
+                QueueError::SerializationFailed(format!(
+                    "Failed to serialize offloaded message: {}",
+                    e
+                ))
+            })?;
+        }
 
         let publish_future: PublishAckFuture = jetstream
             .publish_with_headers(subject.clone(), headers, payload.into())
@@ -137,6 +311,59 @@ impl JobPublisher for NatsJobPublisher {
             ))),
         }
     }
+}
+
+#[async_trait::async_trait]
+impl JobPublisher for NatsJobPublisher {
+    async fn publish(&self, execution: &JobExecution) -> Result<(), QueueError> {
+        self.publish_for_tenant(execution, None).await
+    }
+
+    #[instrument(skip(self), fields(
+        execution_id = %execution.id,
+        job_id = %execution.job_id,
+        idempotency_key = %execution.idempotency_key,
+        attempt = execution.attempt
+    ))]
+    async fn publish_for_tenant(
+        &self,
+        execution: &JobExecution,
+        tenant_id: Option<&str>,
+    ) -> Result<(), QueueError> {
+        info!("Publishing job execution to queue");
+
+        self.publish_message(&JobMessage::from(execution), tenant_id)
+            .await
+    }
+
+    #[instrument(skip(self, job), fields(
+        execution_id = %execution.id,
+        job_id = %execution.job_id,
+        priority = %job.priority
+    ))]
+    async fn publish_for_job(&self, execution: &JobExecution, job: &Job) -> Result<(), QueueError> {
+        info!("Publishing job execution to queue");
+
+        let message = JobMessage::from(execution).with_priority(job.priority);
+        self.publish_message(&message, job.tenant_id.as_deref())
+            .await
+    }
+
+    #[instrument(skip(self, message), fields(
+        execution_id = %message.execution_id,
+        job_id = %message.job_id,
+        not_before = ?message.not_before,
+        retry_attempt = ?message.retry_attempt
+    ))]
+    async fn publish_delayed(
+        &self,
+        message: &JobMessage,
+        tenant_id: Option<&str>,
+    ) -> Result<(), QueueError> {
+        info!("Publishing delayed job message to queue");
+
+        self.publish_message(message, tenant_id).await
+    }
 
     #[instrument(skip(self), fields(
         execution_id = %execution.id,
@@ -199,6 +426,7 @@ mod tests {
             result: None,
             error: None,
             created_at: Utc::now(),
+            job_version: None,
         };
 
         let message = JobMessage::from(&execution);
@@ -216,6 +444,10 @@ mod tests {
             idempotency_key: "test-key".to_string(),
             attempt: 1,
             published_at: Utc::now(),
+            not_before: None,
+            retry_attempt: None,
+            holds: None,
+            priority: JobPriority::Normal,
         };
 
         let json = serde_json::to_string(&message).unwrap();
@@ -224,5 +456,65 @@ mod tests {
         assert_eq!(message.execution_id, deserialized.execution_id);
         assert_eq!(message.job_id, deserialized.job_id);
         assert_eq!(message.idempotency_key, deserialized.idempotency_key);
+        assert!(deserialized.not_before.is_none());
+        assert!(!json.contains("not_before"));
+        assert!(!json.contains("priority"));
+
+        let json = serde_json::to_string(&message.with_priority(JobPriority::High)).unwrap();
+        let deserialized: JobMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.priority, JobPriority::High);
+    }
+
+    #[test]
+    fn test_retry_message_is_delayed_and_deduplicated_per_attempt() {
+        let execution = JobExecution::new_scheduled(Uuid::new_v4(), "job:1".to_string());
+        let now = Utc::now();
+        let message = JobMessage::retry(&execution, 4, now + chrono::Duration::minutes(5));
+
+        assert_eq!(message.execution_id, execution.id);
+        assert_eq!(message.remaining_delay(now), Some(Duration::from_secs(300)));
+        assert_eq!(
+            message.remaining_delay(now + chrono::Duration::minutes(6)),
+            None
+        );
+        assert_eq!(message.deduplication_id(), "job:1:retry:4");
+        assert_eq!(JobMessage::from(&execution).deduplication_id(), "job:1");
+        assert_eq!(JobMessage::from(&execution).remaining_delay(now), None);
+    }
+
+    #[test]
+    fn test_held_back_message_is_deduplicated_per_hold() {
+        let execution = JobExecution::new_scheduled(Uuid::new_v4(), "job:1".to_string());
+        let now = Utc::now();
+        let held = JobMessage::from(&execution).held_back(now + chrono::Duration::seconds(15));
+
+        assert_eq!(held.execution_id, execution.id);
+        assert_eq!(held.remaining_delay(now), Some(Duration::from_secs(15)));
+        assert_eq!(held.deduplication_id(), "job:1:hold:1");
+        assert_eq!(held.held_back(now).deduplication_id(), "job:1:hold:2");
+
+        let retry = JobMessage::retry(&execution, 2, now).held_back(now);
+        assert_eq!(retry.retry_attempt, Some(2));
+        assert_eq!(retry.deduplication_id(), "job:1:retry:2:hold:1");
+    }
+
+    #[test]
+    fn test_offload_path_is_distinct_per_retry_and_hold() {
+        let execution = JobExecution::new_scheduled(Uuid::new_v4(), "job:1/a".to_string());
+        let message = JobMessage::from(&execution);
+
+        assert_eq!(
+            offload_path(&message),
+            format!("queue-payloads/{}/job_1_a.json", execution.id)
+        );
+        let retry = JobMessage::retry(&execution, 3, Utc::now());
+        assert_eq!(
+            offload_path(&retry),
+            format!("queue-payloads/{}/job_1_a_retry_3.json", execution.id)
+        );
+        assert_ne!(
+            offload_path(&retry.held_back(Utc::now())),
+            offload_path(&retry)
+        );
     }
 }
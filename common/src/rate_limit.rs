@@ -1,16 +1,73 @@
+use crate::db::RedisConnection;
 use crate::errors::StorageError;
+use crate::models::RateLimitScope;
 use redis::AsyncCommands;
 use uuid::Uuid;
 
+/// Atomic token bucket: refill by elapsed time, then try to take one token.
+/// Returns `{allowed, tokens_left}` with tokens as a string to keep the fraction.
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local refill_per_ms = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1])
+local ts = tonumber(state[2])
+if tokens == nil or ts == nil then
+    tokens = capacity
+    ts = now
+end
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * refill_per_ms)
+local allowed = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+end
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / refill_per_ms) + 1000)
+return {allowed, tostring(tokens)}
+"#;
+
+/// Outcome of a token bucket check, used to build `X-RateLimit-*` headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// Bucket capacity (maximum burst)
+    pub limit: u32,
+    /// Whole tokens left after this request
+    pub remaining: u32,
+    /// Seconds until the bucket is full again
+    pub reset_after_secs: u64,
+    /// Seconds until the next request would be allowed (only when denied)
+    pub retry_after_secs: Option<u64>,
+}
+
+impl RateLimitDecision {
+    /// Derive the decision from the bucket state left by the script
+    pub fn from_bucket(allowed: bool, tokens: f64, requests_per_minute: u32, burst: u32) -> Self {
+        let refill_per_sec = requests_per_minute.max(1) as f64 / 60.0;
+        let tokens = tokens.clamp(0.0, burst as f64);
+
+        Self {
+            allowed,
+            limit: burst,
+            remaining: tokens.floor() as u32,
+            reset_after_secs: ((burst as f64 - tokens) / refill_per_sec).ceil() as u64,
+            retry_after_secs: (!allowed)
+                .then(|| (((1.0 - tokens) / refill_per_sec).ceil() as u64).max(1)),
+        }
+    }
+}
+
 /// RateLimiter provides rate limiting functionality using Redis
 /// Requirements: 16.11 - Webhook rate limiting with Redis
 pub struct RateLimiter {
-    redis_client: redis::Client,
+    redis: RedisConnection,
 }
 
 impl RateLimiter {
-    pub fn new(redis_client: redis::Client) -> Self {
-        Self { redis_client }
+    pub fn new(redis: RedisConnection) -> Self {
+        Self { redis }
     }
 
     /// Check if a webhook request should be rate limited
@@ -31,7 +88,7 @@ impl RateLimiter {
         max_requests: u32,
         window_seconds: u32,
     ) -> Result<bool, StorageError> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:webhook:{}", webhook_id);
         let now = chrono::Utc::now().timestamp();
@@ -79,7 +136,7 @@ impl RateLimiter {
         webhook_id: Uuid,
         window_seconds: u32,
     ) -> Result<u32, StorageError> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:webhook:{}", webhook_id);
         let now = chrono::Utc::now().timestamp();
@@ -98,7 +155,7 @@ impl RateLimiter {
     /// Requirements: 16.11 - Rate limit management
     #[tracing::instrument(skip(self))]
     pub async fn reset_rate_limit(&self, webhook_id: Uuid) -> Result<(), StorageError> {
-        let mut conn = self.redis_client.get_multiplexed_async_connection().await?;
+        let mut conn = self.redis.clone();
 
         let key = format!("rate_limit:webhook:{}", webhook_id);
         let _: () = conn.del(&key).await?;
@@ -107,20 +164,94 @@ impl RateLimiter {
 
         Ok(())
     }
+
+    /// Take one token from the bucket of an API caller identity
+    ///
+    /// The bucket holds up to `burst` tokens and refills at `requests_per_minute`.
+    #[tracing::instrument(skip(self))]
+    pub async fn check_token_bucket(
+        &self,
+        scope: RateLimitScope,
+        identity: &str,
+        requests_per_minute: u32,
+        burst: u32,
+    ) -> Result<RateLimitDecision, StorageError> {
+        let mut conn = self.redis.clone();
+
+        let key = format!("rate_limit:bucket:{}:{}", scope, identity);
+        let refill_per_ms = requests_per_minute.max(1) as f64 / 60_000.0;
+        let now_ms = chrono::Utc::now().timestamp_millis();
+
+        let (allowed, tokens): (i64, String) = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+            .key(&key)
+            .arg(burst)
+            .arg(refill_per_ms)
+            .arg(now_ms)
+            .invoke_async(&mut conn)
+            .await?;
+
+        let tokens = tokens.parse::<f64>().unwrap_or(0.0);
+        let decision =
+            RateLimitDecision::from_bucket(allowed == 1, tokens, requests_per_minute, burst);
+
+        if !decision.allowed {
+            tracing::warn!(
+                scope = %scope,
+                identity = %identity,
+                requests_per_minute = requests_per_minute,
+                burst = burst,
+                "API rate limit exceeded"
+            );
+        }
+
+        Ok(decision)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::RedisConfig;
+
+    #[test]
+    fn test_decision_allowed_reports_remaining_and_reset() {
+        // 60 rpm refills one token per second
+        let decision = RateLimitDecision::from_bucket(true, 7.5, 60, 10);
+        assert!(decision.allowed);
+        assert_eq!(decision.limit, 10);
+        assert_eq!(decision.remaining, 7);
+        assert_eq!(decision.reset_after_secs, 3);
+        assert_eq!(decision.retry_after_secs, None);
+    }
+
+    #[test]
+    fn test_decision_denied_reports_retry_after() {
+        let decision = RateLimitDecision::from_bucket(false, 0.25, 30, 5);
+        assert!(!decision.allowed);
+        assert_eq!(decision.remaining, 0);
+        // 30 rpm = 0.5 tokens/sec, 0.75 tokens missing
+        assert_eq!(decision.retry_after_secs, Some(2));
+        assert_eq!(decision.reset_after_secs, 10);
+    }
 
     // Note: These tests require a running Redis instance
     // They are integration tests and should be run with --ignored flag
 
+    async fn redis() -> RedisConnection {
+        RedisConnection::connect(&RedisConfig {
+            url: "redis://127.0.0.1:6379".to_string(),
+            pool_size: 10,
+            sentinel: None,
+            cluster: None,
+        })
+        .await
+        .unwrap()
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_rate_limit_allows_requests_within_limit() {
-        let redis_client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
-        let rate_limiter = RateLimiter::new(redis_client);
+        let rate_limiter = RateLimiter::new(redis().await);
         let webhook_id = Uuid::new_v4();
 
         // Reset before test
@@ -146,8 +277,7 @@ mod tests {
     #[tokio::test]
     #[ignore]
     async fn test_rate_limit_blocks_requests_over_limit() {
-        let redis_client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
-        let rate_limiter = RateLimiter::new(redis_client);
+        let rate_limiter = RateLimiter::new(redis().await);
         let webhook_id = Uuid::new_v4();
 
         // Reset before test
@@ -170,11 +300,32 @@ mod tests {
         assert!(!allowed, "Request over limit should be blocked");
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn test_token_bucket_blocks_after_burst() {
+        let rate_limiter = RateLimiter::new(redis().await);
+        let identity = Uuid::new_v4().to_string();
+
+        for i in 1..=3 {
+            let decision = rate_limiter
+                .check_token_bucket(RateLimitScope::User, &identity, 1, 3)
+                .await
+                .unwrap();
+            assert!(decision.allowed, "Request {} should be allowed", i);
+        }
+
+        let decision = rate_limiter
+            .check_token_bucket(RateLimitScope::User, &identity, 1, 3)
+            .await
+            .unwrap();
+        assert!(!decision.allowed);
+        assert!(decision.retry_after_secs.is_some());
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_rate_limit_resets_after_window() {
-        let redis_client = redis::Client::open("redis://127.0.0.1:6379").unwrap();
-        let rate_limiter = RateLimiter::new(redis_client);
+        let rate_limiter = RateLimiter::new(redis().await);
         let webhook_id = Uuid::new_v4();
 
         // Reset before test
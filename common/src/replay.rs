@@ -0,0 +1,139 @@
+// Execution replay
+// Purpose: Re-run an execution with the variable values and webhook payload it saw
+// originally, so a bug can be reproduced deterministically even after variables or
+// upstream data have changed
+
+use crate::models::{JobContext, JobExecution, WebhookData};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Key under which the replay origin is stored in `JobExecution::trigger_metadata`
+pub const REPLAY_METADATA_KEY: &str = "replay_of";
+
+/// Inputs captured when the original execution ran
+#[derive(Debug, Clone, Default)]
+pub struct ReplaySnapshot {
+    pub variables: HashMap<String, serde_json::Value>,
+    pub webhook: Option<WebhookData>,
+}
+
+impl ReplaySnapshot {
+    /// Capture the original execution's inputs
+    ///
+    /// Variables come from the stored context, with the execution's trigger overrides
+    /// on top in case the context was saved before they were applied. Without a stored
+    /// context only the overrides are known.
+    pub fn capture(original: &JobExecution, context: Option<&JobContext>) -> Self {
+        let mut variables = context.map(|c| c.variables.clone()).unwrap_or_default();
+        variables.extend(original.variable_overrides());
+
+        Self {
+            variables,
+            webhook: context.and_then(|c| c.webhook.clone()),
+        }
+    }
+
+    /// Initial context for the replay, holding the captured inputs but no step outputs
+    pub fn into_context(self, execution_id: Uuid, job_id: Uuid) -> JobContext {
+        let mut context = JobContext::new(execution_id, job_id);
+        context.variables = self.variables;
+        context.webhook = self.webhook;
+        context
+    }
+}
+
+/// Which execution a replay reproduces and who asked for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayOrigin {
+    pub execution_id: Uuid,
+    pub replayed_by: String,
+    pub replayed_at: DateTime<Utc>,
+}
+
+impl ReplayOrigin {
+    pub fn new(execution_id: Uuid, replayed_by: impl Into<String>) -> Self {
+        Self {
+            execution_id,
+            replayed_by: replayed_by.into(),
+            replayed_at: Utc::now(),
+        }
+    }
+
+    /// Read the replay origin stored in an execution's trigger metadata
+    pub fn from_trigger_metadata(metadata: Option<&serde_json::Value>) -> Option<Self> {
+        metadata
+            .and_then(|m| m.get(REPLAY_METADATA_KEY))
+            .and_then(|o| serde_json::from_value(o.clone()).ok())
+    }
+
+    /// Store the replay origin in an execution's trigger metadata
+    pub fn attach_to(&self, metadata: &mut Option<serde_json::Value>) {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        match metadata {
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(REPLAY_METADATA_KEY.to_string(), value);
+            }
+            _ => *metadata = Some(serde_json::json!({ REPLAY_METADATA_KEY: value })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_snapshot_prefers_trigger_overrides_over_context() {
+        let job_id = Uuid::new_v4();
+        let mut original = JobExecution::new_manual(job_id, "alice".to_string());
+        original.set_variable_overrides(&HashMap::from([(
+            "REPORT_DATE".to_string(),
+            json!("2026-10-15"),
+        )]));
+
+        let mut context = JobContext::new(original.id, job_id);
+        context
+            .variables
+            .insert("REPORT_DATE".to_string(), json!("stale"));
+        context
+            .variables
+            .insert("REGION".to_string(), json!("north"));
+        context.set_webhook_data(WebhookData {
+            payload: json!({"order_id": 42}),
+            query_params: HashMap::new(),
+            headers: HashMap::new(),
+        });
+
+        let snapshot = ReplaySnapshot::capture(&original, Some(&context));
+        assert_eq!(snapshot.variables["REPORT_DATE"], json!("2026-10-15"));
+        assert_eq!(snapshot.variables["REGION"], json!("north"));
+        assert_eq!(snapshot.webhook.as_ref().unwrap().payload["order_id"], 42);
+
+        let replay_id = Uuid::new_v4();
+        let replay_context = snapshot.into_context(replay_id, job_id);
+        assert_eq!(replay_context.execution_id, replay_id);
+        assert!(replay_context.steps.is_empty());
+        assert!(replay_context.webhook.is_some());
+
+        let without_context = ReplaySnapshot::capture(&original, None);
+        assert_eq!(without_context.variables.len(), 1);
+        assert!(without_context.webhook.is_none());
+    }
+
+    #[test]
+    fn test_origin_round_trips_through_trigger_metadata() {
+        let origin = ReplayOrigin::new(Uuid::new_v4(), "alice");
+        let mut metadata = Some(json!({"variables": {"REGION": "north"}}));
+        origin.attach_to(&mut metadata);
+
+        assert_eq!(
+            ReplayOrigin::from_trigger_metadata(metadata.as_ref()),
+            Some(origin)
+        );
+        assert_eq!(metadata.unwrap()["variables"]["REGION"], "north");
+        assert!(ReplayOrigin::from_trigger_metadata(None).is_none());
+    }
+}
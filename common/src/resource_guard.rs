@@ -0,0 +1,467 @@
+// Per-execution resource accounting and limits
+// Purpose: Track the memory and CPU each running execution uses and stop one that
+// crosses the configured limits, so a single pathological step (a huge Excel parse)
+// fails on its own instead of getting the whole worker OOM-killed with every other
+// in-flight execution
+//
+// Enforcement limits:
+// - CPU is the CPU time of the worker thread while it polls the execution's future
+//   (CLOCK_THREAD_CPUTIME_ID), so time spent waiting or descheduled isn't charged.
+//   Work the execution hands off, such as `spawn_blocking` tasks, container and
+//   process steps or queries running on a database, isn't charged either.
+// - Memory is an estimate: growth of the worker's RSS is split across the running
+//   executions by the CPU each used since the last sample.
+// - Limits are checked every `resource_sample_interval_ms` and an execution over one
+//   stops at its next poll, so a step that never yields runs on until it does.
+
+use crate::telemetry;
+use futures::task::AtomicWaker;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Resource an execution can run out of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Memory,
+    Cpu,
+}
+
+impl Resource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Resource::Memory => "memory",
+            Resource::Cpu => "cpu",
+        }
+    }
+}
+
+/// An execution was stopped for going over a limit
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Execution exceeded its {} limit ({used} > {limit})", resource.as_str())]
+pub struct LimitExceeded {
+    pub resource: Resource,
+    pub used: String,
+    pub limit: String,
+}
+
+/// Kill thresholds for a single execution; `None` only records usage
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResourceLimits {
+    pub max_memory_bytes: Option<u64>,
+    pub max_cpu: Option<Duration>,
+}
+
+/// Resources an execution has used so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct ResourceUsage {
+    pub peak_memory_bytes: u64,
+    pub cpu_seconds: f64,
+}
+
+/// Exceeded resource, stored as 0 (none) or 1 + the `Resource` discriminant
+const NOT_EXCEEDED: u8 = 0;
+
+struct Tracked {
+    cpu_nanos: AtomicU64,
+    /// CPU used since the last sample, used to attribute memory growth
+    window_cpu_nanos: AtomicU64,
+    memory_bytes: AtomicU64,
+    peak_memory_bytes: AtomicU64,
+    exceeded: AtomicU8,
+    waker: AtomicWaker,
+}
+
+impl Tracked {
+    fn new() -> Self {
+        Self {
+            cpu_nanos: AtomicU64::new(0),
+            window_cpu_nanos: AtomicU64::new(0),
+            memory_bytes: AtomicU64::new(0),
+            peak_memory_bytes: AtomicU64::new(0),
+            exceeded: AtomicU8::new(NOT_EXCEEDED),
+            waker: AtomicWaker::new(),
+        }
+    }
+
+    fn usage(&self) -> ResourceUsage {
+        ResourceUsage {
+            peak_memory_bytes: self.peak_memory_bytes.load(Ordering::Relaxed),
+            cpu_seconds: Duration::from_nanos(self.cpu_nanos.load(Ordering::Relaxed)).as_secs_f64(),
+        }
+    }
+
+    fn exceeded(&self) -> Option<Resource> {
+        match self.exceeded.load(Ordering::Relaxed) {
+            1 => Some(Resource::Memory),
+            2 => Some(Resource::Cpu),
+            _ => None,
+        }
+    }
+
+    /// Flag the execution and wake it so it stops at its next poll
+    fn kill(&self, resource: Resource) -> bool {
+        let code = match resource {
+            Resource::Memory => 1,
+            Resource::Cpu => 2,
+        };
+        let first = self
+            .exceeded
+            .compare_exchange(NOT_EXCEEDED, code, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok();
+        self.waker.wake();
+        first
+    }
+}
+
+/// Samples the worker's memory and enforces the limits of every tracked execution
+///
+/// Memory is shared by the whole process, so RSS growth between two samples is split
+/// across the running executions by the CPU each used in that window: the execution
+/// busy parsing a large file is the one allocating. CPU is the thread CPU time spent
+/// polling the execution's future, which is where synchronous parsing work happens.
+pub struct ResourceGuard {
+    limits: ResourceLimits,
+    sample_interval: Duration,
+    executions: Mutex<HashMap<Uuid, Arc<Tracked>>>,
+    last_rss: AtomicU64,
+}
+
+impl ResourceGuard {
+    pub fn new(limits: ResourceLimits, sample_interval: Duration) -> Self {
+        Self {
+            limits,
+            sample_interval,
+            executions: Mutex::new(HashMap::new()),
+            last_rss: AtomicU64::new(current_rss_bytes().unwrap_or_default()),
+        }
+    }
+
+    /// Start accounting for an execution; it is dropped from the guard with the meter
+    pub fn track(self: &Arc<Self>, execution_id: Uuid) -> ExecutionMeter {
+        let tracked = Arc::new(Tracked::new());
+        if let Ok(mut executions) = self.executions.lock() {
+            executions.insert(execution_id, Arc::clone(&tracked));
+        }
+        ExecutionMeter {
+            guard: Arc::clone(self),
+            execution_id,
+            tracked,
+        }
+    }
+
+    /// Number of executions being accounted for
+    pub fn running_executions(&self) -> usize {
+        self.executions
+            .lock()
+            .map(|executions| executions.len())
+            .unwrap_or_default()
+    }
+
+    /// Resident memory of the worker at the last sample, if the platform reports it
+    pub fn memory_bytes(&self) -> Option<u64> {
+        Some(self.last_rss.load(Ordering::Relaxed)).filter(|rss| *rss > 0)
+    }
+
+    /// Take one memory sample and enforce the limits
+    pub fn sample(&self) {
+        let rss = current_rss_bytes();
+        if let Some(rss) = rss {
+            telemetry::update_worker_memory(rss);
+        }
+
+        let Ok(executions) = self.executions.lock() else {
+            return;
+        };
+        let tracked: Vec<(&Uuid, &Arc<Tracked>)> = executions.iter().collect();
+
+        if let Some(rss) = rss {
+            let previous = self.last_rss.swap(rss, Ordering::Relaxed);
+            let mut estimates: Vec<u64> = tracked
+                .iter()
+                .map(|(_, t)| t.memory_bytes.load(Ordering::Relaxed))
+                .collect();
+            let weights: Vec<u64> = tracked
+                .iter()
+                .map(|(_, t)| t.window_cpu_nanos.swap(0, Ordering::Relaxed))
+                .collect();
+            attribute_growth(&mut estimates, &weights, rss as i64 - previous as i64);
+
+            for ((_, t), estimate) in tracked.iter().zip(estimates) {
+                t.memory_bytes.store(estimate, Ordering::Relaxed);
+                t.peak_memory_bytes.fetch_max(estimate, Ordering::Relaxed);
+            }
+        }
+
+        for (execution_id, t) in &tracked {
+            if let Some(exceeded) = self.check_limits(t) {
+                if t.kill(exceeded.resource) {
+                    warn!(
+                        execution_id = %execution_id,
+                        resource = exceeded.resource.as_str(),
+                        used = %exceeded.used,
+                        limit = %exceeded.limit,
+                        "Stopping execution over its resource limit"
+                    );
+                    telemetry::record_resource_kill(exceeded.resource.as_str());
+                }
+            }
+        }
+    }
+
+    fn check_limits(&self, tracked: &Tracked) -> Option<LimitExceeded> {
+        let usage = tracked.usage();
+        if let Some(max) = self.limits.max_memory_bytes {
+            if usage.peak_memory_bytes > max {
+                return Some(LimitExceeded {
+                    resource: Resource::Memory,
+                    used: format!("{} MiB", usage.peak_memory_bytes / MIB),
+                    limit: format!("{} MiB", max / MIB),
+                });
+            }
+        }
+        if let Some(max) = self.limits.max_cpu {
+            if usage.cpu_seconds > max.as_secs_f64() {
+                return Some(LimitExceeded {
+                    resource: Resource::Cpu,
+                    used: format!("{:.1}s", usage.cpu_seconds),
+                    limit: format!("{}s", max.as_secs()),
+                });
+            }
+        }
+        None
+    }
+
+    /// Sample periodically in the background
+    pub fn spawn(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.sample_interval);
+            loop {
+                interval.tick().await;
+                self.sample();
+            }
+        });
+    }
+}
+
+const MIB: u64 = 1024 * 1024;
+
+/// Accounting handle for one execution
+pub struct ExecutionMeter {
+    guard: Arc<ResourceGuard>,
+    execution_id: Uuid,
+    tracked: Arc<Tracked>,
+}
+
+impl ExecutionMeter {
+    /// Run part of the execution, charging its polls to this execution
+    ///
+    /// Returns an error instead of the output once the guard has flagged the
+    /// execution; the inner future is dropped at that point.
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, LimitExceeded> {
+        let mut future = std::pin::pin!(future);
+        std::future::poll_fn(|cx| {
+            if self.tracked.exceeded().is_some() {
+                return Poll::Ready(Err(self.exceeded_error()));
+            }
+            self.tracked.waker.register(cx.waker());
+
+            let started = CpuClock::start();
+            let result = future.as_mut().poll(cx);
+            let nanos = started.elapsed_nanos();
+            self.tracked.cpu_nanos.fetch_add(nanos, Ordering::Relaxed);
+            self.tracked
+                .window_cpu_nanos
+                .fetch_add(nanos, Ordering::Relaxed);
+
+            result.map(Ok)
+        })
+        .await
+    }
+
+    /// Usage so far
+    pub fn usage(&self) -> ResourceUsage {
+        self.tracked.usage()
+    }
+
+    fn exceeded_error(&self) -> LimitExceeded {
+        self.guard
+            .check_limits(&self.tracked)
+            .unwrap_or_else(|| LimitExceeded {
+                resource: self.tracked.exceeded().unwrap_or(Resource::Memory),
+                used: "unknown".to_string(),
+                limit: "unknown".to_string(),
+            })
+    }
+}
+
+impl Drop for ExecutionMeter {
+    fn drop(&mut self) {
+        if let Ok(mut executions) = self.guard.executions.lock() {
+            executions.remove(&self.execution_id);
+        }
+        let usage = self.tracked.usage();
+        info!(
+            execution_id = %self.execution_id,
+            peak_memory_bytes = usage.peak_memory_bytes,
+            cpu_seconds = usage.cpu_seconds,
+            "Execution resource usage"
+        );
+        telemetry::record_execution_resources(usage.peak_memory_bytes, usage.cpu_seconds);
+    }
+}
+
+/// Split a change in process memory across executions in proportion to their weights
+///
+/// With no weight (every execution idle) the change is split evenly. Estimates never
+/// go below zero.
+pub fn attribute_growth(estimates: &mut [u64], weights: &[u64], delta: i64) {
+    if estimates.is_empty() || delta == 0 {
+        return;
+    }
+    let total: u128 = weights.iter().map(|w| *w as u128).sum();
+    for (i, estimate) in estimates.iter_mut().enumerate() {
+        let share = if total == 0 {
+            delta as i128 / weights.len().max(1) as i128
+        } else {
+            delta as i128 * weights.get(i).copied().unwrap_or_default() as i128 / total as i128
+        };
+        *estimate = (*estimate as i128 + share).clamp(0, u64::MAX as i128) as u64;
+    }
+}
+
+/// CPU time of the current thread, falling back to wall time where the platform has
+/// no thread CPU clock
+enum CpuClock {
+    Thread(u64),
+    Wall(Instant),
+}
+
+impl CpuClock {
+    fn start() -> Self {
+        match thread_cpu_nanos() {
+            Some(nanos) => CpuClock::Thread(nanos),
+            None => CpuClock::Wall(Instant::now()),
+        }
+    }
+
+    /// CPU used by this thread since `start`; a poll never moves between threads
+    fn elapsed_nanos(&self) -> u64 {
+        match self {
+            CpuClock::Thread(started) => thread_cpu_nanos()
+                .unwrap_or(*started)
+                .saturating_sub(*started),
+            CpuClock::Wall(started) => started.elapsed().as_nanos() as u64,
+        }
+    }
+}
+
+/// CPU time the current thread has used, in nanoseconds
+#[cfg(unix)]
+fn thread_cpu_nanos() -> Option<u64> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `ts` is a valid timespec for clock_gettime to write into
+    let rc = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) };
+    (rc == 0).then(|| ts.tv_sec as u64 * 1_000_000_000 + ts.tv_nsec as u64)
+}
+
+#[cfg(not(unix))]
+fn thread_cpu_nanos() -> Option<u64> {
+    None
+}
+
+/// Resident set size of this process, where the platform exposes it
+pub fn current_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_growth_follows_cpu_weights() {
+        let mut estimates = vec![0, 100, 0];
+        attribute_growth(&mut estimates, &[3, 1, 0], 400);
+        assert_eq!(estimates, vec![300, 200, 0]);
+
+        // Shrinking memory never makes an estimate negative
+        attribute_growth(&mut estimates, &[0, 1, 0], -1000);
+        assert_eq!(estimates, vec![300, 0, 0]);
+
+        // Idle executions share growth evenly
+        attribute_growth(&mut estimates, &[0, 0, 0], 30);
+        assert_eq!(estimates, vec![310, 10, 10]);
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tworker\nVmPeak:\t  900 kB\nVmRSS:\t  2048 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(2048 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tworker\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_execution_over_cpu_limit_is_stopped() {
+        let guard = Arc::new(ResourceGuard::new(
+            ResourceLimits {
+                max_memory_bytes: None,
+                max_cpu: Some(Duration::from_millis(20)),
+            },
+            Duration::from_secs(1),
+        ));
+        let meter = guard.track(Uuid::new_v4());
+
+        // A busy poll well over the limit
+        let result = meter
+            .run(async {
+                let started = CpuClock::start();
+                while started.elapsed_nanos() < 50_000_000 {
+                    std::hint::spin_loop();
+                }
+            })
+            .await;
+        assert!(result.is_ok());
+        assert!(meter.usage().cpu_seconds >= 0.05);
+
+        guard.sample();
+        let err = meter.run(async {}).await.unwrap_err();
+        assert_eq!(err.resource, Resource::Cpu);
+
+        drop(meter);
+        assert!(guard.executions.lock().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_blocked_poll_is_not_charged_as_cpu() {
+        let guard = Arc::new(ResourceGuard::new(
+            ResourceLimits::default(),
+            Duration::from_secs(1),
+        ));
+        let meter = guard.track(Uuid::new_v4());
+
+        // A poll blocked for 100ms uses next to no CPU
+        meter
+            .run(async { std::thread::sleep(Duration::from_millis(100)) })
+            .await
+            .unwrap();
+        assert!(meter.usage().cpu_seconds < 0.05);
+    }
+}
@@ -1,12 +1,20 @@
 // Schedule parsing and calculation module
 //
 // This module implements schedule parsing and next execution time calculation
-// for all schedule types: Cron, FixedDelay, FixedRate, and OneTime.
+// for all schedule types: Cron, FixedDelay, FixedRate, OneTime, and Iso8601.
 //
 // Requirements: 1.1, 1.2, 1.3, 1.4, 1.5, 1.6, 1.7
 
+pub mod calendar;
+mod describe;
+mod iso8601;
+
+pub use describe::describe_cron_expression;
+pub use iso8601::{IsoDuration, RepeatingInterval};
+
 use crate::errors::ScheduleError;
-use crate::models::Schedule;
+use crate::models::{CalendarPolicy, MisfirePolicy, Schedule, ScheduleCalendar};
+use calendar::Calendar;
 use chrono::{DateTime, Duration, Utc};
 use chrono_tz::Tz;
 use cron::Schedule as CronSchedule;
@@ -15,6 +23,8 @@ use std::str::FromStr;
 /// ScheduleTrigger trait defines the interface for calculating next execution times
 pub trait ScheduleTrigger {
     /// Calculate the next execution time based on the schedule and last execution time
+    ///
+    /// A cron schedule's calendar is not consulted; see `Schedule::next_execution_time_in`.
     fn next_execution_time(
         &self,
         last_execution: Option<DateTime<Utc>>,
@@ -34,19 +44,30 @@ impl ScheduleTrigger for Schedule {
                 expression,
                 timezone,
                 end_date,
-            } => calculate_cron_next_execution(expression, *timezone, *end_date, last_execution),
+                ..
+            } => calculate_cron_next_execution(
+                expression,
+                *timezone,
+                *end_date,
+                last_execution,
+                None,
+            ),
 
             Schedule::FixedDelay { delay_seconds } => {
                 calculate_fixed_delay_next_execution(*delay_seconds, last_execution)
             }
 
-            Schedule::FixedRate { interval_seconds } => {
-                calculate_fixed_rate_next_execution(*interval_seconds, last_execution)
-            }
+            Schedule::FixedRate {
+                interval_seconds, ..
+            } => calculate_fixed_rate_next_execution(*interval_seconds, last_execution),
 
-            Schedule::OneTime { execute_at } => {
+            Schedule::OneTime { execute_at, .. } => {
                 calculate_one_time_next_execution(*execute_at, last_execution)
             }
+
+            Schedule::Iso8601 { expression, .. } => {
+                Ok(RepeatingInterval::parse(expression)?.next_after(last_execution))
+            }
         }
     }
 
@@ -67,33 +88,319 @@ impl ScheduleTrigger for Schedule {
                 last_execution.is_some()
             }
 
+            // Repeating intervals are complete once their repetitions are used up
+            Schedule::Iso8601 { expression, .. } => {
+                last_execution.is_some()
+                    && RepeatingInterval::parse(expression)
+                        .is_ok_and(|interval| interval.next_after(last_execution).is_none())
+            }
+
             // Fixed delay and fixed rate jobs never complete
             Schedule::FixedDelay { .. } | Schedule::FixedRate { .. } => false,
         }
     }
 }
 
+impl Schedule {
+    /// How long after its due time a run may still start; later runs are expired
+    ///
+    /// Only schedules tied to a wall-clock time have one.
+    pub fn start_window(&self) -> Option<Duration> {
+        match self {
+            Schedule::Cron {
+                valid_for_seconds, ..
+            }
+            | Schedule::OneTime {
+                valid_for_seconds, ..
+            }
+            | Schedule::Iso8601 {
+                valid_for_seconds, ..
+            } => valid_for_seconds.map(|seconds| Duration::seconds(seconds as i64)),
+            Schedule::FixedDelay { .. } | Schedule::FixedRate { .. } => None,
+        }
+    }
+
+    /// The business-day calendar a cron schedule follows
+    pub fn calendar(&self) -> Option<&ScheduleCalendar> {
+        match self {
+            Schedule::Cron { calendar, .. } => calendar.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Next execution time with the schedule's calendar applied
+    ///
+    /// `calendar` is the one the schedule names; a schedule naming a calendar
+    /// that isn't given fails with `UnknownCalendar`.
+    pub fn next_execution_time_in(
+        &self,
+        last_execution: Option<DateTime<Utc>>,
+        calendar: Option<&Calendar>,
+    ) -> Result<Option<DateTime<Utc>>, ScheduleError> {
+        match self {
+            Schedule::Cron {
+                expression,
+                timezone,
+                end_date,
+                calendar: Some(wanted),
+                ..
+            } => {
+                let calendar = calendar
+                    .filter(|c| c.name == wanted.name)
+                    .ok_or_else(|| ScheduleError::UnknownCalendar(wanted.name.clone()))?;
+                calculate_cron_next_execution(
+                    expression,
+                    *timezone,
+                    *end_date,
+                    last_execution,
+                    Some((calendar, wanted.policy)),
+                )
+            }
+            _ => self.next_execution_time(last_execution),
+        }
+    }
+
+    /// The runs the schedule was due to make from `from` up to `to`, oldest first
+    ///
+    /// For replaying past windows. A fixed-delay schedule's runs depend on when
+    /// each one finished, so it has none to list. `calendar` is the one the
+    /// schedule names. More than `limit` runs fail with `InvalidConfiguration`.
+    pub fn runs_between(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        calendar: Option<&Calendar>,
+        limit: usize,
+    ) -> Result<Vec<DateTime<Utc>>, ScheduleError> {
+        let mut next = match self {
+            Schedule::FixedDelay { .. } => {
+                return Err(ScheduleError::InvalidConfiguration(
+                    "a fixed-delay schedule has no run times to replay".to_string(),
+                ))
+            }
+            Schedule::FixedRate { .. } => Some(from),
+            Schedule::OneTime { execute_at, .. } => Some(*execute_at),
+            _ => self.next_run_after(from - Duration::seconds(1), calendar)?,
+        };
+
+        let mut runs = Vec::new();
+        while let Some(at) = next.filter(|at| *at <= to) {
+            if at >= from {
+                if runs.len() == limit {
+                    return Err(ScheduleError::InvalidConfiguration(format!(
+                        "more than {} runs between {} and {}",
+                        limit, from, to
+                    )));
+                }
+                runs.push(at);
+            }
+            next = match self {
+                Schedule::OneTime { .. } => None,
+                _ => self.next_run_after(at, calendar)?,
+            };
+        }
+        Ok(runs)
+    }
+
+    /// The next run after `after`; a cron schedule without one has ended
+    fn next_run_after(
+        &self,
+        after: DateTime<Utc>,
+        calendar: Option<&Calendar>,
+    ) -> Result<Option<DateTime<Utc>>, ScheduleError> {
+        match self.next_execution_time_in(Some(after), calendar) {
+            Err(ScheduleError::NoNextExecution { .. }) => Ok(None),
+            result => result,
+        }
+    }
+
+    /// What happens to runs missed while no scheduler was running
+    pub fn misfire_policy(&self) -> MisfirePolicy {
+        match self {
+            Schedule::Cron { misfire_policy, .. }
+            | Schedule::FixedRate { misfire_policy, .. }
+            | Schedule::OneTime { misfire_policy, .. }
+            | Schedule::Iso8601 { misfire_policy, .. } => misfire_policy.unwrap_or_default(),
+            Schedule::FixedDelay { .. } => MisfirePolicy::default(),
+        }
+    }
+
+    /// The runs due after `last_run` up to `now`, with the misfire policy applied
+    ///
+    /// Before its first run a cron schedule looks for runs after `since`, when its
+    /// job was created. Runs due more than `threshold` before `now` were missed.
+    /// `calendar` is the one the schedule names, if it exists. `None` when no run
+    /// is due.
+    pub fn due_runs(
+        &self,
+        last_run: Option<DateTime<Utc>>,
+        since: DateTime<Utc>,
+        now: DateTime<Utc>,
+        threshold: Duration,
+        calendar: Option<&Calendar>,
+    ) -> Result<Option<DueRuns>, ScheduleError> {
+        let mut reference = match self {
+            Schedule::Cron { .. } => Some(last_run.unwrap_or(since)),
+            _ => last_run,
+        };
+        let mut due = Vec::new();
+        while due.len() < MAX_DUE_RUNS {
+            let next = match (self, reference) {
+                // The first run of an interval schedule is due right away
+                (Schedule::FixedDelay { .. } | Schedule::FixedRate { .. }, None) => Some(now),
+                _ if calendar.is_some() => self.next_execution_time_in(reference, calendar)?,
+                _ => self.next_execution_time(reference)?,
+            };
+            match next {
+                Some(at) if at <= now && reference.is_none_or(|last| at > last) => {
+                    due.push(at);
+                    reference = Some(at);
+                }
+                _ => break,
+            }
+        }
+
+        let Some(&latest) = due.last() else {
+            return Ok(None);
+        };
+        let missed = due.iter().filter(|at| now - **at > threshold).count();
+        let due_runs = match self.misfire_policy() {
+            _ if missed == 0 => DueRuns {
+                fire: due,
+                missed,
+                through: latest,
+            },
+            MisfirePolicy::FireImmediately => DueRuns {
+                fire: vec![latest],
+                missed,
+                through: now,
+            },
+            MisfirePolicy::Skip => DueRuns {
+                fire: due.split_off(missed),
+                missed,
+                through: now,
+            },
+            // Runs past MAX_DUE_RUNS are caught up on by the next polls
+            MisfirePolicy::CatchUpAll => DueRuns {
+                fire: due,
+                missed,
+                through: latest,
+            },
+        };
+        Ok(Some(due_runs))
+    }
+}
+
+/// DueRuns are the runs a scheduler makes for a schedule in one poll
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DueRuns {
+    /// Due times of the runs to make, oldest first; empty when the misfire
+    /// policy dropped them all
+    pub fire: Vec<DateTime<Utc>>,
+    /// How many of the runs found were missed
+    pub missed: usize,
+    /// The runs up to here are handled; the next ones are due after it
+    pub through: DateTime<Utc>,
+}
+
+/// Runs of a schedule looked at in one poll
+const MAX_DUE_RUNS: usize = 1000;
+
+/// Cron runs looked at for one that the calendar allows
+const MAX_CALENDAR_CANDIDATES: usize = 1000;
+
 /// Parse and validate a cron expression
 ///
+/// Macros such as `@daily` and `@every 5m` are expanded first, see
+/// `expand_cron_macro`.
+///
 /// Requirements: 1.1 - Parse Quartz syntax with second precision
 pub fn parse_cron_expression(expression: &str) -> Result<CronSchedule, ScheduleError> {
-    CronSchedule::from_str(expression).map_err(|e| ScheduleError::InvalidCronExpression {
+    let expanded = expand_cron_macro(expression)?;
+    CronSchedule::from_str(&expanded).map_err(|e| ScheduleError::InvalidCronExpression {
         expression: expression.to_string(),
         reason: e.to_string(),
     })
 }
 
+/// The cron expression a macro stands for; other expressions are returned unchanged
+///
+/// Supports `@yearly` (`@annually`), `@monthly`, `@weekly`, `@daily` (`@midnight`),
+/// `@hourly` and `@every <n><s|m|h|d>`. An `@every` interval has to divide a
+/// minute, hour or day evenly so that runs land on the same wall-clock times every
+/// day; other intervals belong in a fixed-rate schedule.
+pub fn expand_cron_macro(expression: &str) -> Result<String, ScheduleError> {
+    let expression = expression.trim();
+    let invalid = |reason: String| ScheduleError::InvalidCronExpression {
+        expression: expression.to_string(),
+        reason,
+    };
+    let Some(name) = expression.strip_prefix('@') else {
+        return Ok(expression.to_string());
+    };
+
+    let expanded = match name.to_ascii_lowercase().as_str() {
+        "yearly" | "annually" => "0 0 0 1 1 * *".to_string(),
+        "monthly" => "0 0 0 1 * * *".to_string(),
+        "weekly" => "0 0 0 * * SUN *".to_string(),
+        "daily" | "midnight" => "0 0 0 * * * *".to_string(),
+        "hourly" => "0 0 * * * * *".to_string(),
+        macro_name => {
+            let Some(interval) = macro_name.strip_prefix("every ") else {
+                return Err(invalid(format!("unknown macro @{}", name)));
+            };
+            let interval = interval.trim();
+            let (count, unit) = interval.split_at(interval.len().saturating_sub(1));
+            let count: u32 = count
+                .parse()
+                .ok()
+                .filter(|count| *count > 0)
+                .ok_or_else(|| invalid(format!("invalid @every interval '{}'", interval)))?;
+            let step = |count: u32| {
+                if count == 1 {
+                    "*".to_string()
+                } else {
+                    format!("*/{}", count)
+                }
+            };
+            match unit {
+                "s" if 60 % count == 0 => format!("{} * * * * * *", step(count)),
+                "m" if 60 % count == 0 => format!("0 {} * * * * *", step(count)),
+                "h" if 24 % count == 0 => format!("0 0 {} * * * *", step(count)),
+                "d" if count == 1 => "0 0 0 * * * *".to_string(),
+                "s" | "m" | "h" | "d" => {
+                    return Err(invalid(format!(
+                        "@every {} doesn't divide a minute, hour or day evenly; \
+                         use a fixed_rate schedule instead",
+                        interval
+                    )))
+                }
+                _ => {
+                    return Err(invalid(format!(
+                        "invalid @every unit in '{}', expected s, m, h or d",
+                        interval
+                    )))
+                }
+            }
+        }
+    };
+    Ok(expanded)
+}
+
 /// Calculate next execution time for cron schedules
 ///
 /// Requirements:
 /// - 1.1: Parse cron expression using Quartz syntax with second precision
 /// - 1.2: Evaluate cron expression in specified timezone
 /// - 1.7: Stop scheduling after end date
+///
+/// With a calendar, runs it skips are passed over and runs it moves are moved.
 fn calculate_cron_next_execution(
     expression: &str,
     timezone: Tz,
     end_date: Option<DateTime<Utc>>,
     last_execution: Option<DateTime<Utc>>,
+    calendar: Option<(&Calendar, CalendarPolicy)>,
 ) -> Result<Option<DateTime<Utc>>, ScheduleError> {
     // Parse the cron expression
     let schedule = parse_cron_expression(expression)?;
@@ -105,13 +412,16 @@ fn calculate_cron_next_execution(
     let reference_in_tz = reference_time.with_timezone(&timezone);
 
     // Find the next execution time in the job's timezone
-    let next_in_tz =
-        schedule
-            .after(&reference_in_tz)
-            .next()
-            .ok_or_else(|| ScheduleError::NoNextExecution {
-                schedule_type: "cron".to_string(),
-            })?;
+    let mut runs = schedule.after(&reference_in_tz);
+    let next_in_tz = match calendar {
+        None => runs.next(),
+        Some((calendar, policy)) => runs
+            .take(MAX_CALENDAR_CANDIDATES)
+            .find_map(|run| calendar.apply(policy, run)),
+    }
+    .ok_or_else(|| ScheduleError::NoNextExecution {
+        schedule_type: "cron".to_string(),
+    })?;
 
     // Convert back to UTC
     let next_utc = next_in_tz.with_timezone(&Utc);
@@ -207,6 +517,51 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_cron_macros_expand() {
+        assert_eq!(expand_cron_macro("@daily").unwrap(), "0 0 0 * * * *");
+        assert_eq!(expand_cron_macro("@HOURLY").unwrap(), "0 0 * * * * *");
+        assert_eq!(expand_cron_macro("@every 5m").unwrap(), "0 */5 * * * * *");
+        assert_eq!(expand_cron_macro("@every 1h").unwrap(), "0 0 * * * * *");
+        assert_eq!(expand_cron_macro("@every 30s").unwrap(), "*/30 * * * * * *");
+        assert_eq!(
+            expand_cron_macro("0 0 12 * * * *").unwrap(),
+            "0 0 12 * * * *"
+        );
+        for macro_name in ["@yearly", "@monthly", "@weekly", "@every 6h", "@every 1d"] {
+            assert!(parse_cron_expression(macro_name).is_ok(), "{}", macro_name);
+        }
+
+        // Intervals that would drift against the clock are rejected
+        for invalid in [
+            "@every 7m",
+            "@every 5h",
+            "@every 2d",
+            "@every 5x",
+            "@every m",
+            "@often",
+        ] {
+            assert!(parse_cron_expression(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_every_macro_schedules_on_the_interval() {
+        let schedule = Schedule::Cron {
+            expression: "@every 15m".to_string(),
+            timezone: chrono_tz::UTC,
+            end_date: None,
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
+        };
+        let last = DateTime::parse_from_rfc3339("2025-05-01T10:07:00Z")
+            .unwrap()
+            .to_utc();
+        let next = schedule.next_execution_time(Some(last)).unwrap().unwrap();
+        assert_eq!(next.to_rfc3339(), "2025-05-01T10:15:00+00:00");
+    }
+
     #[test]
     fn test_default_timezone() {
         let tz = default_timezone();
@@ -233,6 +588,7 @@ mod tests {
     fn test_fixed_rate_first_execution() {
         let schedule = Schedule::FixedRate {
             interval_seconds: 60,
+            misfire_policy: None,
         };
         let next = schedule.next_execution_time(None).unwrap();
         assert!(next.is_some());
@@ -242,6 +598,7 @@ mod tests {
     fn test_fixed_rate_subsequent_execution() {
         let schedule = Schedule::FixedRate {
             interval_seconds: 60,
+            misfire_policy: None,
         };
         let last = Utc::now();
         let next = schedule.next_execution_time(Some(last)).unwrap().unwrap();
@@ -252,7 +609,11 @@ mod tests {
     #[test]
     fn test_one_time_not_executed() {
         let execute_at = Utc::now() + Duration::hours(1);
-        let schedule = Schedule::OneTime { execute_at };
+        let schedule = Schedule::OneTime {
+            execute_at,
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
         let next = schedule.next_execution_time(None).unwrap();
         assert_eq!(next, Some(execute_at));
     }
@@ -260,7 +621,11 @@ mod tests {
     #[test]
     fn test_one_time_already_executed() {
         let execute_at = Utc::now() + Duration::hours(1);
-        let schedule = Schedule::OneTime { execute_at };
+        let schedule = Schedule::OneTime {
+            execute_at,
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
         let last = Utc::now();
         let next = schedule.next_execution_time(Some(last)).unwrap();
         assert_eq!(next, None);
@@ -269,17 +634,97 @@ mod tests {
     #[test]
     fn test_one_time_is_complete() {
         let execute_at = Utc::now() + Duration::hours(1);
-        let schedule = Schedule::OneTime { execute_at };
+        let schedule = Schedule::OneTime {
+            execute_at,
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
         assert!(!schedule.is_complete(None));
         assert!(schedule.is_complete(Some(Utc::now())));
     }
 
+    #[test]
+    fn test_iso8601_runs_its_repetitions() {
+        let schedule: Schedule = serde_json::from_value(serde_json::json!({
+            "type": "iso8601",
+            "expression": "R2/2024-01-01T00:00:00Z/P1D"
+        }))
+        .unwrap();
+        let first = schedule.next_execution_time(None).unwrap().unwrap();
+        assert_eq!(first.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+        let second = schedule.next_execution_time(Some(first)).unwrap().unwrap();
+        assert_eq!(second, first + Duration::days(1));
+        assert_eq!(schedule.next_execution_time(Some(second)).unwrap(), None);
+
+        assert!(!schedule.is_complete(None));
+        assert!(!schedule.is_complete(Some(first)));
+        assert!(schedule.is_complete(Some(second)));
+
+        let invalid = Schedule::Iso8601 {
+            expression: "R2/2024-01-01T00:00:00Z".to_string(),
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
+        assert!(invalid.next_execution_time(None).is_err());
+    }
+
+    #[test]
+    fn test_runs_between() {
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc();
+        let from = at("2024-03-01T00:00:00Z");
+        let to = at("2024-03-04T00:00:00Z");
+
+        let daily = Schedule::Cron {
+            expression: "0 0 2 * * * *".to_string(),
+            timezone: chrono_tz::UTC,
+            end_date: None,
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
+        };
+        assert_eq!(
+            daily.runs_between(from, to, None, 10).unwrap(),
+            vec![
+                at("2024-03-01T02:00:00Z"),
+                at("2024-03-02T02:00:00Z"),
+                at("2024-03-03T02:00:00Z")
+            ]
+        );
+        assert!(daily.runs_between(from, to, None, 2).is_err());
+
+        let hourly = Schedule::FixedRate {
+            interval_seconds: 3600,
+            misfire_policy: None,
+        };
+        let runs = hourly.runs_between(from, to, None, 100).unwrap();
+        assert_eq!(runs.len(), 73);
+        assert_eq!(runs[0], from);
+        assert_eq!(runs[72], to);
+
+        let one_time = Schedule::OneTime {
+            execute_at: at("2024-03-02T09:00:00Z"),
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
+        assert_eq!(one_time.runs_between(from, to, None, 10).unwrap().len(), 1);
+        assert!(one_time
+            .runs_between(to, to + Duration::days(1), None, 10)
+            .unwrap()
+            .is_empty());
+
+        let delay = Schedule::FixedDelay { delay_seconds: 60 };
+        assert!(delay.runs_between(from, to, None, 10).is_err());
+    }
+
     #[test]
     fn test_cron_with_end_date() {
         let schedule = Schedule::Cron {
             expression: "0 0 12 * * * *".to_string(),
             timezone: default_timezone(),
             end_date: Some(Utc::now() - Duration::days(1)), // End date in the past
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
         };
         let next = schedule.next_execution_time(None).unwrap();
         // Should return None because end date has passed
@@ -293,6 +738,9 @@ mod tests {
             expression: "0 0 12 * * * *".to_string(),
             timezone: default_timezone(),
             end_date: Some(end_date),
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
         };
         let last_execution = Utc::now();
         assert!(schedule.is_complete(Some(last_execution)));
@@ -309,8 +757,176 @@ mod tests {
     fn test_fixed_rate_never_complete() {
         let schedule = Schedule::FixedRate {
             interval_seconds: 60,
+            misfire_policy: None,
         };
         assert!(!schedule.is_complete(None));
         assert!(!schedule.is_complete(Some(Utc::now())));
     }
+
+    #[test]
+    fn test_cron_follows_calendar() {
+        let calendar = Calendar {
+            id: uuid::Uuid::new_v4(),
+            name: "vn".to_string(),
+            description: None,
+            weekend: vec![chrono::Weekday::Sat, chrono::Weekday::Sun],
+            vietnam_holidays: true,
+            holidays: Vec::new(),
+            working_days: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let schedule = |policy| Schedule::Cron {
+            expression: "0 0 9 * * * *".to_string(),
+            timezone: default_timezone(),
+            end_date: None,
+            valid_for_seconds: None,
+            calendar: Some(ScheduleCalendar {
+                name: "vn".to_string(),
+                policy,
+            }),
+            misfire_policy: None,
+        };
+        // 09:00 on Tuesday 29 April 2025; 30 April and 1 May are holidays
+        let last = DateTime::parse_from_rfc3339("2025-04-29T09:00:00+07:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let next = |policy| {
+            schedule(policy)
+                .next_execution_time_in(Some(last), Some(&calendar))
+                .unwrap()
+                .unwrap()
+                .to_rfc3339()
+        };
+
+        assert_eq!(
+            next(CalendarPolicy::BusinessDaysOnly),
+            "2025-05-02T02:00:00+00:00"
+        );
+        assert_eq!(
+            next(CalendarPolicy::NextBusinessDay),
+            "2025-05-02T02:00:00+00:00"
+        );
+
+        // From Friday, the weekend is skipped unless only holidays are
+        let last = last + Duration::days(3);
+        let next = |policy| {
+            schedule(policy)
+                .next_execution_time_in(Some(last), Some(&calendar))
+                .unwrap()
+                .unwrap()
+                .to_rfc3339()
+        };
+        assert_eq!(
+            next(CalendarPolicy::BusinessDaysOnly),
+            "2025-05-05T02:00:00+00:00"
+        );
+        assert_eq!(
+            next(CalendarPolicy::SkipHolidays),
+            "2025-05-03T02:00:00+00:00"
+        );
+
+        assert!(matches!(
+            schedule(CalendarPolicy::SkipHolidays).next_execution_time_in(Some(last), None),
+            Err(ScheduleError::UnknownCalendar(_))
+        ));
+    }
+
+    #[test]
+    fn test_start_window_only_for_wall_clock_schedules() {
+        let schedule: Schedule = serde_json::from_value(serde_json::json!({
+            "type": "cron",
+            "expression": "0 0 9 * * MON-FRI *",
+            "timezone": "Asia/Ho_Chi_Minh",
+            "valid_for_seconds": 900
+        }))
+        .unwrap();
+        assert_eq!(schedule.start_window(), Some(Duration::minutes(15)));
+
+        let schedule = Schedule::OneTime {
+            execute_at: Utc::now(),
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
+        assert_eq!(schedule.start_window(), None);
+
+        let schedule = Schedule::FixedRate {
+            interval_seconds: 60,
+            misfire_policy: None,
+        };
+        assert_eq!(schedule.start_window(), None);
+    }
+
+    #[test]
+    fn test_due_runs_follow_misfire_policy() {
+        let schedule = |policy: &str| -> Schedule {
+            serde_json::from_value(serde_json::json!({
+                "type": "cron",
+                "expression": "0 0 * * * * *",
+                "timezone": "UTC",
+                "misfire_policy": policy
+            }))
+            .unwrap()
+        };
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc();
+        let last = at("2025-05-01T00:00:00Z");
+        let due = |policy: &str, now: &str| {
+            schedule(policy)
+                .due_runs(Some(last), last, at(now), Duration::seconds(60), None)
+                .unwrap()
+        };
+
+        // The 01:00 and 02:00 runs were missed; 03:00 is on time
+        let now = "2025-05-01T03:00:30Z";
+        assert_eq!(
+            due("fire_immediately", now),
+            Some(DueRuns {
+                fire: vec![at("2025-05-01T03:00:00Z")],
+                missed: 2,
+                through: at(now),
+            })
+        );
+        assert_eq!(
+            due("skip", now).unwrap().fire,
+            vec![at("2025-05-01T03:00:00Z")]
+        );
+        assert_eq!(
+            due("catch_up_all", now),
+            Some(DueRuns {
+                fire: vec![
+                    at("2025-05-01T01:00:00Z"),
+                    at("2025-05-01T02:00:00Z"),
+                    at("2025-05-01T03:00:00Z"),
+                ],
+                missed: 2,
+                through: at("2025-05-01T03:00:00Z"),
+            })
+        );
+
+        // Only missed runs: skip drops them all
+        assert!(due("skip", "2025-05-01T02:30:00Z").unwrap().fire.is_empty());
+        assert_eq!(due("skip", "2025-05-01T00:30:00Z"), None);
+        assert_eq!(
+            due("skip", "2025-05-01T01:00:10Z").unwrap().fire,
+            vec![at("2025-05-01T01:00:00Z")]
+        );
+
+        // A one-time run missed by a day
+        let execute_at = at("2025-04-30T09:00:00Z");
+        let one_time = |policy| Schedule::OneTime {
+            execute_at,
+            valid_for_seconds: None,
+            misfire_policy: policy,
+        };
+        let now = at("2025-05-01T09:00:00Z");
+        let fire = |policy| {
+            one_time(policy)
+                .due_runs(None, last, now, Duration::seconds(60), None)
+                .unwrap()
+                .unwrap()
+                .fire
+        };
+        assert_eq!(fire(None), vec![execute_at]);
+        assert!(fire(Some(MisfirePolicy::Skip)).is_empty());
+    }
 }
@@ -0,0 +1,376 @@
+// Business-day calendars
+// Purpose: Named calendars admins define so cron schedules can skip weekends and
+// public holidays, or move their runs to the next business day
+//
+// Vietnamese public holidays are computed for any year: the solar ones are fixed
+// dates, Tết and the Hùng Kings' festival come from the lunisolar calendar at UTC+7.
+// The government shifts days around them most years; those bridge days and make-up
+// working days are entered on the calendar itself.
+
+use crate::models::CalendarPolicy;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use uuid::Uuid;
+
+/// Furthest a run is moved looking for a business day
+const MAX_DAYS_AHEAD: i64 = 366;
+
+/// Calendar of the days a schedule treats as non-business days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Calendar {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Days of the week that are never business days
+    pub weekend: Vec<Weekday>,
+    /// Observe the Vietnamese public holidays of every year
+    pub vietnam_holidays: bool,
+    /// Further days off, e.g. the bridge days decided for this year's Tết
+    pub holidays: Vec<Holiday>,
+    /// Days worked although they fall on a weekend or holiday
+    pub working_days: Vec<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A named day off
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: String,
+}
+
+impl Holiday {
+    fn new(date: NaiveDate, name: &str) -> Self {
+        Self {
+            date,
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Calendar {
+    /// The holiday on `date`, unless it is a working day
+    pub fn holiday(&self, date: NaiveDate) -> Option<Holiday> {
+        if self.working_days.contains(&date) {
+            return None;
+        }
+        if let Some(holiday) = self.holidays.iter().find(|h| h.date == date) {
+            return Some(holiday.clone());
+        }
+        if self.vietnam_holidays {
+            return vietnam_public_holidays(date.year())
+                .into_iter()
+                .find(|h| h.date == date);
+        }
+        None
+    }
+
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holiday(date).is_some()
+    }
+
+    /// Whether `date` is neither a weekend day nor a holiday, or is a working day
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        if self.working_days.contains(&date) {
+            return true;
+        }
+        !self.weekend.contains(&date.weekday()) && !self.is_holiday(date)
+    }
+
+    /// Every holiday of a year, in date order
+    pub fn holidays_in(&self, year: i32) -> Vec<Holiday> {
+        let mut holidays: Vec<Holiday> = self
+            .holidays
+            .iter()
+            .filter(|h| h.date.year() == year)
+            .cloned()
+            .collect();
+        if self.vietnam_holidays {
+            for holiday in vietnam_public_holidays(year) {
+                if !holidays.iter().any(|h| h.date == holiday.date) {
+                    holidays.push(holiday);
+                }
+            }
+        }
+        holidays.retain(|h| !self.working_days.contains(&h.date));
+        holidays.sort_by_key(|h| h.date);
+        holidays
+    }
+
+    /// When a run due at `run` happens under `policy`, or None if it is skipped
+    pub fn apply<Tz: TimeZone>(
+        &self,
+        policy: CalendarPolicy,
+        run: DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        let date = run.date_naive();
+        match policy {
+            CalendarPolicy::BusinessDaysOnly => self.is_business_day(date).then_some(run),
+            CalendarPolicy::SkipHolidays => (!self.is_holiday(date)).then_some(run),
+            CalendarPolicy::NextBusinessDay => {
+                let day = (0..=MAX_DAYS_AHEAD)
+                    .map(|offset| date + Duration::days(offset))
+                    .find(|day| self.is_business_day(*day))?;
+                if day == date {
+                    return Some(run);
+                }
+                // Same wall-clock time; the earlier instant if DST makes it ambiguous
+                run.timezone()
+                    .from_local_datetime(&day.and_time(run.time()))
+                    .earliest()
+            }
+        }
+    }
+}
+
+/// Vietnamese public holidays of a year (Labor Code 2019, article 112)
+///
+/// Tết is taken as the last day of the lunar year and the first four of the new
+/// one, and National Day as 2 September and the day before it.
+pub fn vietnam_public_holidays(year: i32) -> Vec<Holiday> {
+    let mut holidays = Vec::new();
+    let mut fixed = |month: u32, day: u32, name: &str| {
+        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
+            holidays.push(Holiday::new(date, name));
+        }
+    };
+    fixed(1, 1, "Tết Dương lịch");
+    fixed(4, 30, "Ngày Giải phóng miền Nam");
+    fixed(5, 1, "Ngày Quốc tế Lao động");
+    fixed(9, 1, "Quốc khánh");
+    fixed(9, 2, "Quốc khánh");
+
+    // Tết of the lunar year starting in this solar year, and the end of the
+    // previous one when it falls in early January
+    for lunar_year in [year, year + 1] {
+        if let Some(new_year) = lunar_to_solar(1, 1, lunar_year) {
+            for offset in -1..=3 {
+                let date = new_year + Duration::days(offset);
+                if date.year() == year {
+                    holidays.push(Holiday::new(date, "Tết Nguyên đán"));
+                }
+            }
+        }
+    }
+    if let Some(date) = lunar_to_solar(10, 3, year) {
+        holidays.push(Holiday::new(date, "Giỗ Tổ Hùng Vương"));
+    }
+
+    holidays.sort_by_key(|h| h.date);
+    holidays.dedup_by_key(|h| h.date);
+    holidays
+}
+
+// Lunisolar calendar at UTC+7, after Hồ Ngọc Đức's algorithm: months start on the
+// day of the new moon and the month holding the winter solstice is the 11th.
+
+/// Time zone of the Vietnamese calendar, in hours
+const TIME_ZONE: f64 = 7.0;
+
+/// Julian day number of 0001-01-01 minus one
+const JULIAN_DAY_OFFSET: i64 = 1_721_425;
+
+fn julian_day(date: NaiveDate) -> i64 {
+    date.num_days_from_ce() as i64 + JULIAN_DAY_OFFSET
+}
+
+fn from_julian_day(jd: i64) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(i32::try_from(jd - JULIAN_DAY_OFFSET).ok()?)
+}
+
+/// Julian date of the k-th new moon after 1900-01-01
+fn new_moon(k: f64) -> f64 {
+    let t = k / 1236.85;
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let dr = PI / 180.0;
+    let mut jd1 = 2415020.75933 + 29.53058868 * k + 0.0001178 * t2 - 0.000000155 * t3;
+    jd1 += 0.00033 * ((166.56 + 132.87 * t - 0.009173 * t2) * dr).sin();
+    let m = 359.2242 + 29.10535608 * k - 0.0000333 * t2 - 0.00000347 * t3;
+    let mpr = 306.0253 + 385.81691806 * k + 0.0107306 * t2 + 0.00001236 * t3;
+    let f = 21.2964 + 390.67050646 * k - 0.0016528 * t2 - 0.00000239 * t3;
+    let mut c1 = (0.1734 - 0.000393 * t) * (m * dr).sin() + 0.0021 * (2.0 * dr * m).sin();
+    c1 = c1 - 0.4068 * (mpr * dr).sin() + 0.0161 * (dr * 2.0 * mpr).sin();
+    c1 -= 0.0004 * (dr * 3.0 * mpr).sin();
+    c1 = c1 + 0.0104 * (dr * 2.0 * f).sin() - 0.0051 * (dr * (m + mpr)).sin();
+    c1 = c1 - 0.0074 * (dr * (m - mpr)).sin() + 0.0004 * (dr * (2.0 * f + m)).sin();
+    c1 = c1 - 0.0004 * (dr * (2.0 * f - m)).sin() - 0.0006 * (dr * (2.0 * f + mpr)).sin();
+    c1 = c1 + 0.0010 * (dr * (2.0 * f - mpr)).sin() + 0.0005 * (dr * (2.0 * mpr + m)).sin();
+    let delta_t = if t < -11.0 {
+        0.001 + 0.000839 * t + 0.0002261 * t2 - 0.00000845 * t3 - 0.000000081 * t * t3
+    } else {
+        -0.000278 + 0.000265 * t + 0.000262 * t2
+    };
+    jd1 + c1 - delta_t
+}
+
+/// Sun longitude in radians at a Julian date
+fn sun_longitude(jdn: f64) -> f64 {
+    let t = (jdn - 2451545.0) / 36525.0;
+    let t2 = t * t;
+    let dr = PI / 180.0;
+    let m = 357.52910 + 35999.05030 * t - 0.0001559 * t2 - 0.00000048 * t * t2;
+    let l0 = 280.46645 + 36000.76983 * t + 0.0003032 * t2;
+    let mut dl = (1.914600 - 0.004817 * t - 0.000014 * t2) * (dr * m).sin();
+    dl += (0.019993 - 0.000101 * t) * (dr * 2.0 * m).sin() + 0.000290 * (dr * 3.0 * m).sin();
+    let l = (l0 + dl) * dr;
+    l - PI * 2.0 * (l / (PI * 2.0)).floor()
+}
+
+/// Sector (0-11, 30 degrees each) of the sun's longitude at the start of a day
+fn sun_sector(day: i64) -> i64 {
+    (sun_longitude(day as f64 - 0.5 - TIME_ZONE / 24.0) / PI * 6.0).floor() as i64
+}
+
+/// Day of the k-th new moon
+fn new_moon_day(k: i64) -> i64 {
+    (new_moon(k as f64) + 0.5 + TIME_ZONE / 24.0).floor() as i64
+}
+
+/// First day of the 11th lunar month of a year
+fn lunar_month_11(year: i32) -> Option<i64> {
+    let off = julian_day(NaiveDate::from_ymd_opt(year, 12, 31)?) - 2415021;
+    let k = (off as f64 / 29.530588853).floor() as i64;
+    let new_moon = new_moon_day(k);
+    if sun_sector(new_moon) >= 9 {
+        Some(new_moon_day(k - 1))
+    } else {
+        Some(new_moon)
+    }
+}
+
+/// Months after the 11th at which the leap month falls
+fn leap_month_offset(a11: i64) -> i64 {
+    let k = ((a11 as f64 - 2415021.076998695) / 29.530588853 + 0.5).floor() as i64;
+    let mut i = 1;
+    let mut arc = sun_sector(new_moon_day(k + i));
+    loop {
+        let last = arc;
+        i += 1;
+        arc = sun_sector(new_moon_day(k + i));
+        if arc == last || i >= 14 {
+            break;
+        }
+    }
+    i - 1
+}
+
+/// Solar date of a day in a regular (not leap) lunar month
+fn lunar_to_solar(day: u32, month: u32, year: i32) -> Option<NaiveDate> {
+    let (a11, b11) = if month < 11 {
+        (lunar_month_11(year - 1)?, lunar_month_11(year)?)
+    } else {
+        (lunar_month_11(year)?, lunar_month_11(year + 1)?)
+    };
+    let k = (0.5 + (a11 as f64 - 2415021.076998695) / 29.530588853).floor() as i64;
+    let mut off = month as i64 - 11;
+    if off < 0 {
+        off += 12;
+    }
+    // A year of 13 months has a leap month; the months from it on shift by one
+    if b11 - a11 > 365 && off >= leap_month_offset(a11) {
+        off += 1;
+    }
+    from_julian_day(new_moon_day(k + off) + day as i64 - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Asia::Ho_Chi_Minh;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn calendar() -> Calendar {
+        Calendar {
+            id: Uuid::new_v4(),
+            name: "vn-banking".to_string(),
+            description: None,
+            weekend: vec![Weekday::Sat, Weekday::Sun],
+            vietnam_holidays: true,
+            holidays: vec![Holiday::new(date(2025, 5, 2), "Nghỉ bù")],
+            working_days: vec![date(2025, 4, 26)],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_lunar_holidays() {
+        // Tết and the Hùng Kings' festival (10th day of the 3rd lunar month)
+        assert_eq!(lunar_to_solar(1, 1, 2024), Some(date(2024, 2, 10)));
+        assert_eq!(lunar_to_solar(1, 1, 2025), Some(date(2025, 1, 29)));
+        assert_eq!(lunar_to_solar(1, 1, 2026), Some(date(2026, 2, 17)));
+        assert_eq!(lunar_to_solar(10, 3, 2024), Some(date(2024, 4, 18)));
+        assert_eq!(lunar_to_solar(10, 3, 2025), Some(date(2025, 4, 7)));
+        // 2023 has a leap 2nd month, so the 3rd month starts a month later
+        assert_eq!(lunar_to_solar(10, 3, 2023), Some(date(2023, 4, 29)));
+    }
+
+    #[test]
+    fn test_vietnam_public_holidays() {
+        let dates: Vec<NaiveDate> = vietnam_public_holidays(2025)
+            .into_iter()
+            .map(|h| h.date)
+            .collect();
+        assert_eq!(
+            dates,
+            vec![
+                date(2025, 1, 1),
+                date(2025, 1, 28),
+                date(2025, 1, 29),
+                date(2025, 1, 30),
+                date(2025, 1, 31),
+                date(2025, 2, 1),
+                date(2025, 4, 7),
+                date(2025, 4, 30),
+                date(2025, 5, 1),
+                date(2025, 9, 1),
+                date(2025, 9, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_business_days() {
+        let calendar = calendar();
+        assert!(calendar.is_business_day(date(2025, 4, 29)));
+        assert!(!calendar.is_business_day(date(2025, 4, 30)));
+        assert!(!calendar.is_business_day(date(2025, 5, 2)));
+        assert!(!calendar.is_business_day(date(2025, 5, 3)));
+        // A Saturday worked in exchange for the bridge day
+        assert!(calendar.is_business_day(date(2025, 4, 26)));
+        assert_eq!(
+            calendar.holiday(date(2025, 4, 7)).map(|h| h.name),
+            Some("Giỗ Tổ Hùng Vương".to_string())
+        );
+    }
+
+    #[test]
+    fn test_apply_policies() {
+        let calendar = calendar();
+        // Wednesday 30 April 2025, 09:00
+        let run = Ho_Chi_Minh.with_ymd_and_hms(2025, 4, 30, 9, 0, 0).unwrap();
+        assert_eq!(calendar.apply(CalendarPolicy::BusinessDaysOnly, run), None);
+        assert_eq!(calendar.apply(CalendarPolicy::SkipHolidays, run), None);
+        // 1 and 2 May are holidays, then the weekend
+        assert_eq!(
+            calendar.apply(CalendarPolicy::NextBusinessDay, run),
+            Some(Ho_Chi_Minh.with_ymd_and_hms(2025, 5, 5, 9, 0, 0).unwrap())
+        );
+
+        let saturday = Ho_Chi_Minh.with_ymd_and_hms(2025, 5, 10, 9, 0, 0).unwrap();
+        assert_eq!(
+            calendar.apply(CalendarPolicy::SkipHolidays, saturday),
+            Some(saturday)
+        );
+        assert_eq!(
+            calendar.apply(CalendarPolicy::BusinessDaysOnly, saturday),
+            None
+        );
+    }
+}
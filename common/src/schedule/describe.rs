@@ -0,0 +1,216 @@
+// Human-readable cron descriptions
+// Purpose: Spell out what a cron expression means for the job form, e.g.
+// `0 30 9 * * MON-FRI *` becomes "At 09:30, Monday through Friday"
+
+use super::{expand_cron_macro, parse_cron_expression};
+use crate::errors::ScheduleError;
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Describe a cron expression or macro in English
+///
+/// Common shapes get a sentence ("Every 5 minutes", "At 02:00, on day 1 of the
+/// month"); anything else is described field by field. Invalid expressions fail
+/// as they would when scheduling.
+pub fn describe_cron_expression(expression: &str) -> Result<String, ScheduleError> {
+    parse_cron_expression(expression)?;
+    let expanded = expand_cron_macro(expression)?;
+    let fields: Vec<&str> = expanded.split_whitespace().collect();
+    let [second, minute, hour, day_of_month, month, day_of_week, rest @ ..] = fields.as_slice()
+    else {
+        return Err(ScheduleError::InvalidCronExpression {
+            expression: expression.to_string(),
+            reason: "expected 6 or 7 fields".to_string(),
+        });
+    };
+
+    let time = describe_time(second, minute, hour);
+    let mut qualifiers = Vec::new();
+    if !is_any(day_of_week) {
+        qualifiers.push(describe_field(day_of_week, Some(&WEEKDAYS[..])));
+    }
+    if !is_any(day_of_month) {
+        let days = describe_field(day_of_month, None);
+        if day_of_month.parse::<u32>().is_ok() {
+            qualifiers.push(format!("on day {} of the month", days));
+        } else {
+            qualifiers.push(format!("on days {} of the month", days));
+        }
+    }
+    if !is_any(month) {
+        qualifiers.push(format!("in {}", describe_field(month, Some(&MONTHS[..]))));
+    }
+    if let Some(year) = rest.first().filter(|year| !is_any(year)) {
+        qualifiers.push(format!("in {}", describe_field(year, None)));
+    }
+
+    if qualifiers.is_empty() && time.starts_with("At") {
+        qualifiers.push("every day".to_string());
+    }
+    if qualifiers.is_empty() {
+        return Ok(time);
+    }
+    Ok(format!("{}, {}", time, qualifiers.join(", ")))
+}
+
+/// Describe the second, minute and hour fields
+fn describe_time(second: &str, minute: &str, hour: &str) -> String {
+    let number = |field: &str| field.parse::<u32>().ok();
+    match (number(second), number(minute), number(hour)) {
+        (Some(0), Some(m), Some(h)) => return format!("At {:02}:{:02}", h, m),
+        (Some(s), Some(m), Some(h)) => return format!("At {:02}:{:02}:{:02}", h, m, s),
+        _ => {}
+    }
+
+    let every = |count: u32, unit: &str| match count {
+        1 => format!("Every {}", unit),
+        count => format!("Every {} {}s", count, unit),
+    };
+    if is_any(minute) && is_any(hour) {
+        if let Some(count) = step(second) {
+            return every(count, "second");
+        }
+    }
+    if number(second) == Some(0) && is_any(hour) {
+        if let Some(count) = step(minute) {
+            return every(count, "minute");
+        }
+    }
+    if number(second) == Some(0) && number(minute) == Some(0) {
+        if let Some(count) = step(hour) {
+            return every(count, "hour");
+        }
+    }
+
+    match (number(second), number(minute)) {
+        (Some(0), Some(m)) => match step(hour) {
+            Some(count) => format!("At minute {} of {}", m, every(count, "hour").to_lowercase()),
+            None => format!("At minute {} past hour {}", m, describe_field(hour, None)),
+        },
+        _ => format!(
+            "At second {}, minute {}, hour {}",
+            describe_field(second, None),
+            describe_field(minute, None),
+            describe_field(hour, None)
+        ),
+    }
+}
+
+/// Describe a field's comma-separated values, ranges and steps
+///
+/// `names` spells out numbered values: 1 is the first name, and three-letter
+/// abbreviations of a name are accepted as well.
+fn describe_field(field: &str, names: Option<&[&str]>) -> String {
+    let name = |value: &str| -> String {
+        let Some(names) = names else {
+            return value.to_string();
+        };
+        let found = match value.parse::<usize>() {
+            Ok(index) => index.checked_sub(1).and_then(|index| names.get(index)),
+            Err(_) => names
+                .iter()
+                .find(|full| full[..3].eq_ignore_ascii_case(value)),
+        };
+        found.map_or_else(|| value.to_string(), |name| name.to_string())
+    };
+
+    field
+        .split(',')
+        .map(|part| {
+            if is_any(part) {
+                return "every value".to_string();
+            }
+            if let Some(count) = step(part) {
+                return format!("every {}", count);
+            }
+            if let Some((start, every)) = part.split_once('/') {
+                return format!("every {} from {}", every, name(start));
+            }
+            match part.split_once('-') {
+                Some((start, end)) => format!("{} through {}", name(start), name(end)),
+                None => name(part),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The step of a field repeating over its whole range: `*` is 1, `*/n` and `0/n` are n
+fn step(field: &str) -> Option<u32> {
+    if field == "*" {
+        return Some(1);
+    }
+    field
+        .strip_prefix("*/")
+        .or_else(|| field.strip_prefix("0/"))
+        .and_then(|count| count.parse().ok())
+}
+
+fn is_any(field: &str) -> bool {
+    field == "*" || field == "?"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_common_expressions() {
+        let cases = [
+            ("0 0 12 * * * *", "At 12:00, every day"),
+            ("0 30 9 * * MON-FRI *", "At 09:30, Monday through Friday"),
+            (
+                "0 0 0 1 1 * *",
+                "At 00:00, on day 1 of the month, in January",
+            ),
+            ("0 */5 * * * * *", "Every 5 minutes"),
+            ("*/10 * * * * *", "Every 10 seconds"),
+            ("0 0 */2 * * * *", "Every 2 hours"),
+            ("0 15 * * * * *", "At minute 15 of every hour"),
+            (
+                "0 0 9-17 * * 2,4 *",
+                "At minute 0 past hour 9 through 17, Monday, Wednesday",
+            ),
+            ("@every 5m", "Every 5 minutes"),
+            ("@weekly", "At 00:00, Sunday"),
+            ("@hourly", "Every hour"),
+        ];
+        for (expression, description) in cases {
+            assert_eq!(
+                describe_cron_expression(expression).unwrap(),
+                description,
+                "{}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_describe_rejects_invalid_expressions() {
+        assert!(describe_cron_expression("invalid").is_err());
+        assert!(describe_cron_expression("@every 7m").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+// ISO 8601 repeating intervals
+// Purpose: Run jobs on recurrences written the way external contracts state them,
+// e.g. `R5/2024-01-01T00:00:00Z/P1D` for five daily runs from 1 January 2024
+
+use crate::errors::ScheduleError;
+use chrono::{DateTime, Duration, FixedOffset, Months, Utc};
+
+/// Average Gregorian month, used only to estimate how many periods have passed
+const AVERAGE_MONTH_SECONDS: i64 = 2_629_746;
+
+/// A parsed `R[n]/<start>/<duration>` or `R[n]/<start>/<end>` expression
+///
+/// `Rn` runs n times, the first at the start; a bare `R` repeats without end.
+/// Calendar units in the duration follow the start's UTC offset, so `P1M` from
+/// 31 January lands on the last day of February.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepeatingInterval {
+    pub repetitions: Option<u32>,
+    pub start: DateTime<FixedOffset>,
+    pub period: IsoDuration,
+}
+
+/// An ISO 8601 duration; years count as 12 months and weeks as 7 days
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IsoDuration {
+    pub months: u32,
+    pub days: u32,
+    pub seconds: u32,
+}
+
+impl RepeatingInterval {
+    /// Parse a repeating interval expression
+    pub fn parse(expression: &str) -> Result<Self, ScheduleError> {
+        let invalid = |reason: &str| ScheduleError::InvalidIso8601Expression {
+            expression: expression.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let mut parts = expression.trim().split('/');
+        let (Some(repeat), Some(start), Some(period), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid("expected R[n]/<start>/<duration or end>"));
+        };
+
+        let repetitions = match repeat.strip_prefix('R') {
+            Some("") => None,
+            Some(count) => match count.parse::<u32>() {
+                Ok(count) if count > 0 => Some(count),
+                _ => return Err(invalid("the repetition count must be a positive number")),
+            },
+            None => return Err(invalid("must start with R or R<n>")),
+        };
+        let start = DateTime::parse_from_rfc3339(start)
+            .map_err(|_| invalid("the start must be an RFC 3339 date-time with an offset"))?;
+
+        let period = if period.starts_with('P') {
+            IsoDuration::parse(period).map_err(|reason| invalid(&reason))?
+        } else {
+            let end = DateTime::parse_from_rfc3339(period)
+                .map_err(|_| invalid("the end must be an RFC 3339 date-time with an offset"))?;
+            let seconds = u32::try_from((end - start).num_seconds())
+                .ok()
+                .filter(|seconds| *seconds > 0)
+                .ok_or_else(|| invalid("the end must be after the start"))?;
+            IsoDuration {
+                seconds,
+                ..IsoDuration::default()
+            }
+        };
+
+        Ok(Self {
+            repetitions,
+            start,
+            period,
+        })
+    }
+
+    /// The first run strictly after `after`, or the first run at all without it
+    pub fn next_after(&self, after: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+        let Some(after) = after else {
+            return self.occurrence(0);
+        };
+
+        // Estimate how many periods have passed, then settle on the exact one
+        let elapsed = (after - self.start.to_utc()).num_seconds();
+        let mut n = u32::try_from(elapsed / self.period.approximate_seconds()).unwrap_or(0);
+        while n > 0 && self.occurrence_unbounded(n)? > after {
+            n -= 1;
+        }
+        while self.occurrence_unbounded(n)? <= after {
+            n = n.checked_add(1)?;
+        }
+        self.occurrence(n)
+    }
+
+    /// The run after `n` periods, if the repetitions reach it
+    fn occurrence(&self, n: u32) -> Option<DateTime<Utc>> {
+        if self.repetitions.is_some_and(|repetitions| n >= repetitions) {
+            return None;
+        }
+        self.occurrence_unbounded(n)
+    }
+
+    fn occurrence_unbounded(&self, n: u32) -> Option<DateTime<Utc>> {
+        let months = self.period.months.checked_mul(n)?;
+        let days = Duration::try_days(i64::from(self.period.days) * i64::from(n))?;
+        let seconds = Duration::try_seconds(i64::from(self.period.seconds) * i64::from(n))?;
+        self.start
+            .checked_add_months(Months::new(months))?
+            .checked_add_signed(days)?
+            .checked_add_signed(seconds)
+            .map(|at| at.to_utc())
+    }
+}
+
+impl IsoDuration {
+    /// Parse `PnYnMnWnDTnHnMnS`; fractions aren't supported
+    pub fn parse(duration: &str) -> Result<Self, String> {
+        let Some(rest) = duration.strip_prefix('P') else {
+            return Err("the duration must start with P".to_string());
+        };
+        let (date, time) = match rest.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (rest, None),
+        };
+
+        let mut parsed = Self::default();
+        let mut components = 0;
+        for (part, in_time) in [(date, false), (time.unwrap_or_default(), true)] {
+            let mut number = String::new();
+            for c in part.chars() {
+                if c.is_ascii_digit() {
+                    number.push(c);
+                    continue;
+                }
+                let value: u32 = number
+                    .parse()
+                    .map_err(|_| format!("expected a number before {} in the duration", c))?;
+                number.clear();
+                let total = match (in_time, c) {
+                    (false, 'Y') => value.checked_mul(12).map(|m| (&mut parsed.months, m)),
+                    (false, 'M') => Some((&mut parsed.months, value)),
+                    (false, 'W') => value.checked_mul(7).map(|d| (&mut parsed.days, d)),
+                    (false, 'D') => Some((&mut parsed.days, value)),
+                    (true, 'H') => value.checked_mul(3600).map(|s| (&mut parsed.seconds, s)),
+                    (true, 'M') => value.checked_mul(60).map(|s| (&mut parsed.seconds, s)),
+                    (true, 'S') => Some((&mut parsed.seconds, value)),
+                    _ => return Err(format!("unexpected {} in the duration", c)),
+                };
+                let (field, amount) = total.ok_or("the duration is too long")?;
+                *field = field
+                    .checked_add(amount)
+                    .ok_or("the duration is too long")?;
+                components += 1;
+            }
+            if !number.is_empty() {
+                return Err("the duration ends without a unit".to_string());
+            }
+        }
+
+        if time.is_some_and(str::is_empty) || components == 0 {
+            return Err("the duration has no components".to_string());
+        }
+        if parsed == Self::default() {
+            return Err("the duration must not be zero".to_string());
+        }
+        Ok(parsed)
+    }
+
+    /// Rough length in seconds, never zero
+    fn approximate_seconds(&self) -> i64 {
+        (i64::from(self.months) * AVERAGE_MONTH_SECONDS
+            + i64::from(self.days) * 86_400
+            + i64::from(self.seconds))
+        .max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc()
+    }
+
+    #[test]
+    fn test_parse_durations() {
+        assert_eq!(
+            IsoDuration::parse("P1Y2M10DT2H30M").unwrap(),
+            IsoDuration {
+                months: 14,
+                days: 10,
+                seconds: 9000
+            }
+        );
+        assert_eq!(IsoDuration::parse("P2W").unwrap().days, 14);
+        assert_eq!(IsoDuration::parse("PT15M").unwrap().seconds, 900);
+        for invalid in ["P", "PT", "P1", "1D", "P1.5D", "PT1D", "P0D", "P1H"] {
+            assert!(IsoDuration::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_repeating_interval_runs() {
+        let interval = RepeatingInterval::parse("R3/2024-01-01T00:00:00Z/P1D").unwrap();
+        assert_eq!(interval.next_after(None), Some(at("2024-01-01T00:00:00Z")));
+        assert_eq!(
+            interval.next_after(Some(at("2024-01-01T00:00:00Z"))),
+            Some(at("2024-01-02T00:00:00Z"))
+        );
+        assert_eq!(
+            interval.next_after(Some(at("2024-01-02T12:00:00Z"))),
+            Some(at("2024-01-03T00:00:00Z"))
+        );
+        // Three runs, so none after the third
+        assert_eq!(interval.next_after(Some(at("2024-01-03T00:00:00Z"))), None);
+
+        // Unbounded, far from the start
+        let interval = RepeatingInterval::parse("R/2024-01-01T08:00:00+07:00/PT6H").unwrap();
+        assert_eq!(
+            interval.next_after(Some(at("2025-06-15T03:00:00Z"))),
+            Some(at("2025-06-15T07:00:00Z"))
+        );
+
+        // Months follow the calendar
+        let interval = RepeatingInterval::parse("R/2024-01-31T00:00:00Z/P1M").unwrap();
+        assert_eq!(
+            interval.next_after(Some(at("2024-02-01T00:00:00Z"))),
+            Some(at("2024-02-29T00:00:00Z"))
+        );
+        assert_eq!(
+            interval.next_after(Some(at("2024-12-01T00:00:00Z"))),
+            Some(at("2024-12-31T00:00:00Z"))
+        );
+
+        // Start and end instead of a duration
+        let interval =
+            RepeatingInterval::parse("R2/2024-01-01T00:00:00Z/2024-01-01T12:00:00Z").unwrap();
+        assert_eq!(
+            interval.next_after(Some(at("2024-01-01T00:00:00Z"))),
+            Some(at("2024-01-01T12:00:00Z"))
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_expressions() {
+        for invalid in [
+            "2024-01-01T00:00:00Z/P1D",
+            "R0/2024-01-01T00:00:00Z/P1D",
+            "Rx/2024-01-01T00:00:00Z/P1D",
+            "R5/2024-01-01/P1D",
+            "R5/2024-01-01T00:00:00Z",
+            "R5/2024-01-02T00:00:00Z/2024-01-01T00:00:00Z",
+            "R5/2024-01-01T00:00:00Z/P1D/extra",
+        ] {
+            assert!(RepeatingInterval::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
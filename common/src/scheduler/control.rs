@@ -0,0 +1,73 @@
+// Scheduler control messages
+// Purpose: Drain schedulers before a deploy or maintenance. A draining scheduler keeps
+// polling, journaling its ticks and reporting readiness, but publishes no new
+// executions until it is resumed.
+//
+// Drains are kept in the database and read by every scheduler on each tick, so they
+// survive restarts. The control message only makes nodes apply a change at once;
+// each node that applied it replies with a `SchedulerControlAck`.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Subject the API publishes scheduler control messages on
+///
+/// Kept outside `jobs.>`, which the job stream captures.
+pub const SCHEDULER_CONTROL_SUBJECT: &str = "scheduler_control";
+
+/// How long the API collects acks of a control message
+pub const SCHEDULER_CONTROL_ACK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What a control message asks of the schedulers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerCommand {
+    /// Stop publishing new executions
+    Drain,
+    /// Publish new executions again
+    Resume,
+}
+
+/// A control message for the scheduler nodes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchedulerControl {
+    pub command: SchedulerCommand,
+    /// Only the node with this id, as recorded in the scheduler journal; every node
+    /// when left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+}
+
+impl SchedulerControl {
+    /// Whether the message is meant for the given node
+    pub fn targets(&self, node_id: &str) -> bool {
+        self.node.as_deref().is_none_or(|node| node == node_id)
+    }
+}
+
+/// Reply of a scheduler node that applied a control message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SchedulerControlAck {
+    pub node: String,
+    pub draining: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_control_message_targets() {
+        let drain_all: SchedulerControl =
+            serde_json::from_value(serde_json::json!({ "command": "drain" })).unwrap();
+        assert_eq!(drain_all.command, SchedulerCommand::Drain);
+        assert!(drain_all.targets("scheduler-0-1"));
+
+        let drain_one = SchedulerControl {
+            command: SchedulerCommand::Drain,
+            node: Some("scheduler-0-1".to_string()),
+        };
+        assert!(drain_one.targets("scheduler-0-1"));
+        assert!(!drain_one.targets("scheduler-1-1"));
+    }
+}
@@ -1,16 +1,41 @@
 // Scheduler engine implementation
 // Requirements: 7.1, 4.1, 9.4
 
+use crate::callback::ExecutionCallback;
+use crate::clock::ClockGuard;
+use crate::db::repositories::blackout_window::BlackoutWindowRepository;
+use crate::db::repositories::calendar::CalendarRepository;
 use crate::db::repositories::execution::ExecutionRepository;
 use crate::db::repositories::job::JobRepository;
+use crate::db::repositories::scheduled_trigger::ScheduledTriggerRepository;
+use crate::db::repositories::scheduler_drain::SchedulerDrainRepository;
+use crate::db::repositories::scheduler_journal::SchedulerJournalRepository;
 use crate::db::DbPool;
+use crate::errors::{DatabaseError, ScheduleError};
+use crate::failover::RegionRole;
 use crate::lock::DistributedLock;
-use crate::models::{ExecutionStatus, Job, JobExecution};
+use crate::models::{
+    BlackoutBehavior, BlackoutWindow, CalendarPolicy, CollisionPolicy, ExecutionStatus, Job,
+    JobDecision, JobDecisionOutcome, JobExecution, Schedule, ScheduledTrigger, SchedulerTick,
+    SkipReason, TickStatus, SCHEDULED_FOR_METADATA_KEY,
+};
+use crate::parameter_matrix;
 use crate::queue::JobPublisher;
+use crate::schedule::calendar::Calendar;
+use crate::schedule::DueRuns;
+use crate::scheduler::control::{
+    SchedulerCommand, SchedulerControl, SchedulerControlAck, SCHEDULER_CONTROL_SUBJECT,
+};
+use crate::telemetry::{self, AlertNotifier, LogAlertNotifier};
+use crate::trigger_variables::apply_trigger_variables;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tokio::time::{interval, sleep};
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
@@ -24,6 +49,8 @@ pub struct SchedulerConfig {
     pub lock_ttl_seconds: u64,
     /// Maximum number of jobs to process per poll
     pub max_jobs_per_poll: usize,
+    /// Runs found this long after their due time were missed (in seconds)
+    pub misfire_threshold_seconds: u64,
 }
 
 impl Default for SchedulerConfig {
@@ -32,6 +59,7 @@ impl Default for SchedulerConfig {
             poll_interval_seconds: 10,
             lock_ttl_seconds: 30,
             max_jobs_per_poll: 100,
+            misfire_threshold_seconds: 60,
         }
     }
 }
@@ -54,8 +82,18 @@ pub struct SchedulerEngine {
     config: SchedulerConfig,
     job_repo: Arc<JobRepository>,
     execution_repo: Arc<ExecutionRepository>,
+    scheduled_trigger_repo: Arc<ScheduledTriggerRepository>,
+    calendar_repo: Arc<CalendarRepository>,
+    blackout_repo: Arc<BlackoutWindowRepository>,
+    drain_repo: Arc<SchedulerDrainRepository>,
     lock: Arc<dyn DistributedLock>,
     publisher: Arc<dyn JobPublisher>,
+    alert_notifier: Arc<dyn AlertNotifier>,
+    region_role: Option<watch::Receiver<RegionRole>>,
+    clock_guard: Option<Arc<ClockGuard>>,
+    journal: Option<(Arc<SchedulerJournalRepository>, i64)>,
+    node_id: String,
+    draining: AtomicBool,
     shutdown_tx: tokio::sync::broadcast::Sender<()>,
 }
 
@@ -68,17 +106,144 @@ impl SchedulerEngine {
         publisher: Arc<dyn JobPublisher>,
     ) -> Self {
         let (shutdown_tx, _shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let node_id = format!(
+            "{}-{}",
+            std::env::var("HOSTNAME").unwrap_or_else(|_| Uuid::new_v4().to_string()),
+            std::process::id()
+        );
 
         Self {
             config,
             job_repo: Arc::new(JobRepository::new(db_pool.clone())),
-            execution_repo: Arc::new(ExecutionRepository::new(db_pool)),
+            execution_repo: Arc::new(ExecutionRepository::new(db_pool.clone())),
+            scheduled_trigger_repo: Arc::new(ScheduledTriggerRepository::new(db_pool.clone())),
+            calendar_repo: Arc::new(CalendarRepository::new(db_pool.clone())),
+            blackout_repo: Arc::new(BlackoutWindowRepository::new(db_pool.clone())),
+            drain_repo: Arc::new(SchedulerDrainRepository::new(db_pool)),
             lock,
             publisher,
+            alert_notifier: Arc::new(LogAlertNotifier::default()),
+            region_role: None,
+            clock_guard: None,
+            journal: None,
+            node_id,
+            draining: AtomicBool::new(false),
             shutdown_tx,
         }
     }
 
+    /// Use a custom notifier for sunset notices
+    pub fn with_alert_notifier(mut self, alert_notifier: Arc<dyn AlertNotifier>) -> Self {
+        self.alert_notifier = alert_notifier;
+        self
+    }
+
+    /// Only schedule while the region is active (multi-region failover)
+    pub fn with_region_role(mut self, region_role: watch::Receiver<RegionRole>) -> Self {
+        self.region_role = Some(region_role);
+        self
+    }
+
+    /// Whether this region schedules jobs; always true without failover
+    fn is_active(&self) -> bool {
+        self.region_role
+            .as_ref()
+            .is_none_or(|role| *role.borrow() == RegionRole::Active)
+    }
+
+    /// Stop scheduling while this node's clock is skewed
+    pub fn with_clock_guard(mut self, clock_guard: Arc<ClockGuard>) -> Self {
+        self.clock_guard = Some(clock_guard);
+        self
+    }
+
+    /// Record every tick in the decision journal, keeping the newest `max_ticks`
+    pub fn with_journal(mut self, repo: SchedulerJournalRepository, max_ticks: i64) -> Self {
+        self.journal = Some((Arc::new(repo), max_ticks));
+        self
+    }
+
+    /// Id of this scheduler node, as recorded in the journal
+    pub fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    /// Whether this node is drained and publishes no new executions
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Drain this node, or resume it, until the next tick reads the stored drains
+    ///
+    /// A tick already running finishes its jobs. While drained the node keeps polling,
+    /// journaling its ticks and reporting readiness, but fires no jobs or triggers.
+    pub fn set_draining(&self, draining: bool) {
+        if self.draining.swap(draining, Ordering::SeqCst) != draining {
+            if draining {
+                warn!(node = %self.node_id, "Scheduler draining, no new executions are published");
+            } else {
+                info!(node = %self.node_id, "Scheduler resumed");
+            }
+        }
+        telemetry::record_scheduler_draining(draining);
+    }
+
+    /// Drain and resume this node as control messages arrive
+    pub fn spawn_control_listener(self: Arc<Self>, client: async_nats::Client) {
+        tokio::spawn(async move {
+            let mut subscriber = match client.subscribe(SCHEDULER_CONTROL_SUBJECT).await {
+                Ok(subscriber) => subscriber,
+                Err(e) => {
+                    error!(error = %e, "Failed to subscribe to scheduler control messages");
+                    return;
+                }
+            };
+            info!("Subscribed to scheduler control messages");
+
+            while let Some(msg) = subscriber.next().await {
+                match serde_json::from_slice::<SchedulerControl>(&msg.payload) {
+                    Ok(control) if control.targets(&self.node_id) => {
+                        self.set_draining(control.command == SchedulerCommand::Drain);
+                        if let Some(reply) = msg.reply {
+                            self.acknowledge(&client, reply).await;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!(error = %e, "Ignoring malformed scheduler control message"),
+                }
+            }
+        });
+    }
+
+    /// Tell the sender of a control message this node applied it
+    async fn acknowledge(&self, client: &async_nats::Client, reply: async_nats::Subject) {
+        let ack = SchedulerControlAck {
+            node: self.node_id.clone(),
+            draining: self.is_draining(),
+        };
+        let payload = match serde_json::to_vec(&ack) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!(error = %e, "Failed to encode scheduler control ack");
+                return;
+            }
+        };
+        if let Err(e) = client.publish(reply, payload.into()).await {
+            warn!(error = %e, "Failed to acknowledge scheduler control message");
+        }
+    }
+
+    /// Follow the stored drains, so a drain outlives restarts and missed messages;
+    /// keeps the current state when they can't be read
+    async fn refresh_draining(&self) {
+        match self.drain_repo.is_drained(&self.node_id).await {
+            Ok(draining) => self.set_draining(draining),
+            Err(e) => {
+                warn!(error = %e, "Failed to read scheduler drains, keeping the current state")
+            }
+        }
+    }
+
     /// Get a shutdown signal receiver
     pub fn shutdown_receiver(&self) -> tokio::sync::broadcast::Receiver<()> {
         self.shutdown_tx.subscribe()
@@ -90,21 +255,31 @@ impl SchedulerEngine {
     /// - 4.1: Acquire distributed lock before scheduling
     /// - 7.1: Ensure only one scheduler node processes each job
     /// - 17.9: Allow concurrent execution if configured
-    /// - 17.10: Reject new triggers if concurrent execution not allowed
-    #[instrument(skip(self, job), fields(job_id = %job.id, job_name = %job.name))]
-    async fn process_job(&self, job: &Job) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    /// - 17.10: Apply the job's collision policy if concurrent execution not allowed
+    ///
+    /// `due` are the runs to make, claimed by moving the job's schedule cursor on
+    /// from `cursor`; without it a single run is made.
+    #[instrument(skip(self, job, due), fields(job_id = %job.id, job_name = %job.name))]
+    async fn process_job(
+        &self,
+        job: &Job,
+        cursor: Option<DateTime<Utc>>,
+        due: Option<&DueRuns>,
+    ) -> Result<JobDecision, Box<dyn std::error::Error + Send + Sync>> {
         // Check if concurrent execution is allowed
-        // Requirement 17.10: Reject if concurrent execution not allowed and job is running
+        // Requirement 17.10: A job that may not run concurrently follows its collision policy
         if !job.allow_concurrent {
-            match self.execution_repo.has_running_execution(job.id).await {
+            match self.collision_allows_run(job).await {
                 Ok(true) => {
-                    debug!(
-                        "Job has running execution and concurrent execution not allowed, skipping"
-                    );
-                    return Ok(());
+                    // No run in the way, or the policy queues this one after it
                 }
                 Ok(false) => {
-                    // No running execution, proceed
+                    debug!(
+                        collision_policy = %job.collision_policy,
+                        "Job has an active run and concurrent execution not allowed, skipping"
+                    );
+                    self.drop_due_runs(job, cursor, due).await;
+                    return Ok(JobDecision::skipped(job, SkipReason::CollisionPolicy));
                 }
                 Err(e) => {
                     warn!(error = %e, "Failed to check for running executions");
@@ -127,44 +302,100 @@ impl SchedulerEngine {
             Err(e) => {
                 // Another scheduler node is processing this job
                 debug!(error = %e, "Failed to acquire lock, skipping job");
-                return Ok(());
+                return Ok(JobDecision::skipped(job, SkipReason::LockHeld));
             }
         };
 
-        // Load job definition from MinIO to get schedule information
-        // For now, we'll create a basic execution
-        // TODO: Load full job definition from MinIO in future tasks
-
-        // Create job execution using factory method
-        let idempotency_key = format!("{}:{}", job.id, Uuid::new_v4());
-        let execution = JobExecution::new_scheduled(job.id, idempotency_key);
-
-        // Save execution to database
-        match self.execution_repo.create(&execution).await {
-            Ok(()) => {
-                info!(execution_id = %execution.id, "Job execution created");
+        // The cursor has moved if another node made the runs since they were loaded
+        if let Some(due) = due {
+            match self.job_repo.find_schedule_cursor(job.id).await {
+                Ok(current) if current == cursor => {}
+                Ok(_) => {
+                    debug!("Runs already made by another scheduler node, skipping job");
+                    return Ok(JobDecision::skipped(job, SkipReason::LockHeld));
+                }
+                Err(e) => {
+                    error!(error = %e, "Failed to read schedule cursor");
+                    return Err(Box::new(e));
+                }
             }
-            Err(e) => {
-                error!(error = %e, "Failed to create job execution");
-                return Err(Box::new(e));
+            if due.missed > 0 {
+                info!(
+                    missed = due.missed,
+                    runs = due.fire.len(),
+                    "Making runs missed while no scheduler was running"
+                );
             }
         }
 
-        // Publish job to queue
-        match self.publisher.publish(&execution).await {
-            Ok(()) => {
-                info!(execution_id = %execution.id, "Job published to queue");
+        if !job.allow_concurrent && job.collision_policy == CollisionPolicy::CancelPrevious {
+            if let Err(e) = self.cancel_previous_runs(job).await {
+                warn!(error = %e, "Failed to cancel previous runs");
             }
-            Err(e) => {
-                error!(error = %e, "Failed to publish job to queue");
-                // Update execution status to failed
-                let mut failed_execution = execution.clone();
-                failed_execution.status = ExecutionStatus::Failed;
-                failed_execution.error = Some(format!("Failed to publish to queue: {}", e));
-                let _ = self.execution_repo.update(&failed_execution).await;
-                return Err(Box::new(e));
+        }
+
+        let due_times: Vec<Option<DateTime<Utc>>> = match due {
+            Some(due) => due.fire.iter().copied().map(Some).collect(),
+            None => vec![None],
+        };
+
+        // One execution per run, or one per combination of the job's parameter matrix.
+        // The cursor moves past each run once it is published, so a failure leaves the
+        // remaining runs for the next poll instead of dropping them.
+        let mut execution_ids = Vec::new();
+        let mut claimed = cursor;
+        for due_at in due_times {
+            for mut execution in
+                parameter_matrix::scheduled_executions(job.id, &job.parameter_matrix)
+            {
+                if let Some(due_at) = due_at {
+                    attach_scheduled_for(&mut execution, due_at);
+                }
+
+                // Save execution to database
+                match self.execution_repo.create(&execution).await {
+                    Ok(()) => {
+                        info!(execution_id = %execution.id, "Job execution created");
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Failed to create job execution");
+                        return Err(Box::new(e));
+                    }
+                }
+
+                // Publish job to queue
+                match self.publisher.publish_for_job(&execution, &job).await {
+                    Ok(()) => {
+                        info!(execution_id = %execution.id, "Job published to queue");
+                        execution_ids.push(execution.id);
+                    }
+                    Err(e) => {
+                        error!(error = %e, "Failed to publish job to queue");
+                        // Update execution status to failed
+                        let mut failed_execution = execution.clone();
+                        failed_execution.error = Some(format!("Failed to publish to queue: {}", e));
+                        if let Ok(previous) =
+                            failed_execution.transition_to(ExecutionStatus::Failed)
+                        {
+                            let _ = self
+                                .execution_repo
+                                .transition(&failed_execution, &previous)
+                                .await;
+                        }
+                        return Err(Box::new(e));
+                    }
+                }
+            }
+            if let Some(due_at) = due_at {
+                self.move_schedule_cursor(job.id, &mut claimed, due_at)
+                    .await?;
             }
         }
+        // Runs dropped by the misfire policy between and after the fired ones
+        if let Some(due) = due.filter(|due| claimed != Some(due.through)) {
+            self.move_schedule_cursor(job.id, &mut claimed, due.through)
+                .await?;
+        }
 
         // Update job stats
         if let Err(e) = self.job_repo.update_stats(job.id, true).await {
@@ -175,9 +406,479 @@ impl SchedulerEngine {
         drop(lock_guard);
         debug!("Lock released");
 
+        Ok(JobDecision::fired(job, execution_ids))
+    }
+
+    /// Evaluate the jobs due now, returning the decision taken for each
+    ///
+    /// Jobs beyond `max_jobs_per_poll` are left for the next poll and journaled as skipped.
+    async fn decide_due_jobs(
+        &self,
+    ) -> Result<Vec<JobDecision>, Box<dyn std::error::Error + Send + Sync>> {
+        let now = Utc::now();
+
+        // Find all enabled jobs
+        let jobs = match self.job_repo.find_jobs_due(now).await {
+            Ok(jobs) => jobs,
+            Err(e) => {
+                error!(error = %e, "Failed to query jobs from database");
+                return Err(Box::new(e));
+            }
+        };
+        let cursors = match self.job_repo.find_schedule_cursors().await {
+            Ok(cursors) => cursors,
+            Err(e) => {
+                error!(error = %e, "Failed to query schedule cursors from database");
+                return Err(Box::new(e));
+            }
+        };
+
+        debug!(job_count = jobs.len(), "Found jobs to evaluate");
+
+        let calendars = self.load_calendars(&jobs).await;
+        let blackouts = self.load_blackouts().await;
+        let mut decisions = Vec::new();
+
+        // Process each job
+        for (index, job) in jobs.iter().enumerate() {
+            // Check if job is due based on schedule
+            if !self.is_job_due(job) {
+                continue;
+            }
+
+            // Jobs whose schedule can't be read run on every poll
+            let schedule = job_schedule(job);
+            if let Some(wanted) = schedule.as_ref().and_then(|s| s.calendar()) {
+                if !calendars.contains_key(&wanted.name) {
+                    warn!(
+                        job_id = %job.id,
+                        calendar = %wanted.name,
+                        "Schedule names a calendar that doesn't exist, skipping"
+                    );
+                    decisions.push(JobDecision::skipped(job, SkipReason::CalendarMissing));
+                    continue;
+                }
+            }
+            let cursor = cursors.get(&job.id).copied();
+            let due = match &schedule {
+                Some(schedule) => match self.due_runs(job, schedule, cursor, &calendars, now) {
+                    Ok(Some(due)) => Some(due),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        warn!(job_id = %job.id, error = %e, "Failed to calculate due runs");
+                        decisions.push(JobDecision::failed(job, e.to_string()));
+                        continue;
+                    }
+                },
+                None => None,
+            };
+
+            if let Some(schedule) = &schedule {
+                if !calendar_allows_run(schedule, &calendars, now) {
+                    debug!(job_id = %job.id, "Job's calendar is closed today, skipping");
+                    self.drop_due_runs(job, cursor, due.as_ref()).await;
+                    decisions.push(JobDecision::skipped(job, SkipReason::Calendar));
+                    continue;
+                }
+            }
+
+            if let Some(windows) = blackouts.get(&job.id) {
+                self.defer_past_blackouts(windows).await;
+                debug!(job_id = %job.id, "Job is in a blackout window, skipping");
+                self.drop_due_runs(job, cursor, due.as_ref()).await;
+                decisions.push(JobDecision::skipped(job, SkipReason::Blackout));
+                continue;
+            }
+
+            if let Some(due) = due.as_ref().filter(|due| due.fire.is_empty()) {
+                info!(
+                    job_id = %job.id,
+                    missed = due.missed,
+                    "Runs missed while no scheduler was running dropped by the skip misfire policy"
+                );
+                self.drop_due_runs(job, cursor, Some(due)).await;
+                decisions.push(JobDecision::skipped(job, SkipReason::Misfire));
+                continue;
+            }
+
+            if index >= self.config.max_jobs_per_poll {
+                decisions.push(JobDecision::skipped(job, SkipReason::PollLimit));
+                continue;
+            }
+
+            // Process the job
+            match self.process_job(job, cursor, due.as_ref()).await {
+                Ok(decision) => decisions.push(decision),
+                Err(e) => {
+                    error!(
+                        job_id = %job.id,
+                        job_name = %job.name,
+                        error = %e,
+                        "Failed to process job"
+                    );
+                    // Continue processing other jobs
+                    decisions.push(JobDecision::failed(job, e.to_string()));
+                }
+            }
+        }
+
+        Ok(decisions)
+    }
+
+    /// One poll: retire sunset jobs, fire due jobs and dispatch due triggers
+    async fn tick(&self) -> (TickStatus, Vec<JobDecision>, Option<String>) {
+        if !self.is_active() {
+            debug!("Region is standby, skipping poll");
+            return (TickStatus::Standby, Vec::new(), None);
+        }
+        if self
+            .clock_guard
+            .as_ref()
+            .is_some_and(|guard| !guard.in_sync())
+        {
+            warn!("Clock skew exceeds the limit, skipping poll");
+            return (TickStatus::ClockSkew, Vec::new(), None);
+        }
+        self.refresh_draining().await;
+        if self.is_draining() {
+            debug!("Scheduler is draining, skipping poll");
+            return (TickStatus::Draining, Vec::new(), None);
+        }
+        debug!("Polling for jobs due");
+
+        // Retire jobs past their sunset before they can fire again
+        if let Err(e) = self.process_sunsets().await {
+            error!(error = %e, "Error disabling jobs past their sunset date");
+        }
+
+        let outcome = match self.decide_due_jobs().await {
+            Ok(decisions) => {
+                let count = fired_count(&decisions);
+                if count > 0 {
+                    info!(jobs_processed = count, "Processed due jobs");
+                } else {
+                    debug!("No jobs due for execution");
+                }
+                (TickStatus::Ran, decisions, None)
+            }
+            Err(e) => {
+                error!(error = %e, "Error processing due jobs");
+                (TickStatus::Failed, Vec::new(), Some(e.to_string()))
+            }
+        };
+
+        match self.process_due_triggers().await {
+            Ok(count) if count > 0 => {
+                info!(triggers_dispatched = count, "Dispatched scheduled triggers");
+            }
+            Ok(_) => {}
+            Err(e) => {
+                error!(error = %e, "Error dispatching scheduled triggers");
+            }
+        }
+
+        outcome
+    }
+
+    /// Append a tick to the decision journal, if enabled
+    async fn record_tick(&self, tick: SchedulerTick) {
+        let Some((repo, max_ticks)) = &self.journal else {
+            return;
+        };
+        if let Err(e) = repo.record(&tick, *max_ticks).await {
+            warn!(error = %e, "Failed to record scheduler tick in the journal");
+        }
+    }
+
+    /// Whether a due run of a job that may not run concurrently should be created
+    ///
+    /// `skip` drops it while any run is pending or running. `queue_after` and
+    /// `cancel_previous` create it unless a run is already waiting to start, so at
+    /// most one run queues behind the active one; workers hold it until that one ends.
+    async fn collision_allows_run(&self, job: &Job) -> Result<bool, DatabaseError> {
+        let blocked = match job.collision_policy {
+            CollisionPolicy::Skip => self.execution_repo.has_running_execution(job.id).await?,
+            CollisionPolicy::QueueAfter | CollisionPolicy::CancelPrevious => {
+                self.execution_repo.has_pending_execution(job.id).await?
+            }
+        };
+        Ok(!blocked)
+    }
+
+    /// Ask the job's running executions to stop after their current step
+    async fn cancel_previous_runs(&self, job: &Job) -> Result<(), DatabaseError> {
+        for mut previous in self.execution_repo.find_active(job.id).await? {
+            // Runs already stopping are left alone
+            let Ok(from) = previous.transition_to(ExecutionStatus::Cancelling) else {
+                continue;
+            };
+            previous.error = Some("Stopped for a newer run (cancel_previous policy)".to_string());
+            match self.execution_repo.transition(&previous, &from).await {
+                Ok(()) => {
+                    info!(previous_execution_id = %previous.id, "Previous run asked to stop")
+                }
+                // Finished meanwhile
+                Err(DatabaseError::VersionConflict(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
         Ok(())
     }
 
+    /// Disable deprecated jobs whose sunset date has passed and notify their owners
+    #[instrument(skip(self))]
+    pub async fn process_sunsets(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let jobs = self.job_repo.disable_sunset_jobs(Utc::now()).await?;
+
+        for job in &jobs {
+            info!(
+                job_id = %job.id,
+                job_name = %job.name,
+                sunset_at = %job.sunset_at,
+                "Deprecated job disabled at its sunset date"
+            );
+            if let Err(e) = self
+                .alert_notifier
+                .send_sunset_notice(
+                    &job.id,
+                    &job.name,
+                    job.sunset_at,
+                    job.deprecation_reason.as_deref(),
+                    job.owner.as_ref(),
+                )
+                .await
+            {
+                warn!(job_id = %job.id, error = %e, "Failed to send sunset notice");
+            }
+        }
+
+        Ok(jobs.len())
+    }
+
+    /// Dispatch one-off "run at" triggers whose time has come
+    ///
+    /// These bypass the job's recurring schedule entirely: each trigger becomes a
+    /// single manual execution carrying the trigger's variable overrides.
+    #[instrument(skip(self))]
+    pub async fn process_due_triggers(
+        &self,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let triggers = self
+            .scheduled_trigger_repo
+            .claim_due(Utc::now(), self.config.max_jobs_per_poll as i64)
+            .await?;
+
+        let mut dispatched_count = 0;
+        for trigger in &triggers {
+            match self.dispatch_trigger(trigger).await {
+                Ok(true) => dispatched_count += 1,
+                Ok(false) => {}
+                Err(e) => {
+                    error!(trigger_id = %trigger.id, error = %e, "Failed to dispatch scheduled trigger");
+                    if let Err(e) = self
+                        .scheduled_trigger_repo
+                        .mark_failed(trigger.id, &e.to_string())
+                        .await
+                    {
+                        warn!(trigger_id = %trigger.id, error = %e, "Failed to mark scheduled trigger as failed");
+                    }
+                }
+            }
+        }
+
+        Ok(dispatched_count)
+    }
+
+    /// Create and publish the execution for a claimed trigger
+    ///
+    /// Returns `Ok(false)` when the trigger was put back for a later poll because
+    /// the job is already running and doesn't allow concurrent executions, or is
+    /// in a deferring blackout window, and when a skipping window dropped it.
+    #[instrument(skip(self, trigger), fields(trigger_id = %trigger.id, job_id = %trigger.job_id))]
+    async fn dispatch_trigger(
+        &self,
+        trigger: &ScheduledTrigger,
+    ) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+        let job = self
+            .job_repo
+            .find_by_id(trigger.job_id)
+            .await?
+            .ok_or_else(|| format!("Job {} no longer exists", trigger.job_id))?;
+
+        let blackouts = self
+            .blackout_repo
+            .find_active_for_job(job.id, Utc::now())
+            .await?;
+        if let Some(window) = blackouts
+            .iter()
+            .find(|w| w.behavior == BlackoutBehavior::Skip)
+        {
+            info!(window = %window.name, "Job is in a blackout window, skipping scheduled trigger");
+            self.scheduled_trigger_repo
+                .mark_failed(
+                    trigger.id,
+                    &format!("Skipped during blackout window '{}'", window.name),
+                )
+                .await?;
+            return Ok(false);
+        }
+        if !blackouts.is_empty() {
+            debug!("Job is in a blackout window, retrying on next poll");
+            self.scheduled_trigger_repo.release(trigger.id).await?;
+            return Ok(false);
+        }
+
+        if !job.allow_concurrent && self.execution_repo.has_running_execution(job.id).await? {
+            debug!("Job is running and concurrent execution not allowed, retrying on next poll");
+            self.scheduled_trigger_repo.release(trigger.id).await?;
+            return Ok(false);
+        }
+
+        let mut execution = JobExecution::new_manual(job.id, trigger.created_by.clone());
+        execution.trigger_metadata = Some(serde_json::json!({
+            "scheduled_trigger_id": trigger.id,
+            "execute_at": trigger.execute_at,
+        }));
+        apply_trigger_variables(
+            &mut execution,
+            &trigger.variables,
+            &trigger.created_by,
+            "scheduled",
+        );
+        if let Some(callback) = trigger
+            .callback
+            .clone()
+            .and_then(|c| serde_json::from_value::<ExecutionCallback>(c).ok())
+        {
+            callback.attach_to(&mut execution.trigger_metadata);
+        }
+
+        self.execution_repo.create(&execution).await?;
+
+        if let Err(e) = self.publisher.publish_for_job(&execution, &job).await {
+            let mut failed_execution = execution.clone();
+            failed_execution.error = Some(format!("Failed to publish to queue: {}", e));
+            if let Ok(previous) = failed_execution.transition_to(ExecutionStatus::Failed) {
+                let _ = self
+                    .execution_repo
+                    .transition(&failed_execution, &previous)
+                    .await;
+            }
+            return Err(Box::new(e));
+        }
+
+        self.scheduled_trigger_repo
+            .mark_dispatched(trigger.id, execution.id)
+            .await?;
+        info!(execution_id = %execution.id, "Scheduled trigger dispatched");
+
+        Ok(true)
+    }
+
+    /// Active blackout windows by job; on error no job is held back
+    async fn load_blackouts(&self) -> HashMap<Uuid, Vec<BlackoutWindow>> {
+        let mut blackouts: HashMap<Uuid, Vec<BlackoutWindow>> = HashMap::new();
+        match self.blackout_repo.find_active(Utc::now()).await {
+            Ok(windows) => {
+                for window in windows {
+                    blackouts.entry(window.job_id).or_default().push(window);
+                }
+            }
+            Err(e) => warn!(error = %e, "Failed to load blackout windows"),
+        }
+        blackouts
+    }
+
+    /// Make sure each deferring window has its run scheduled for when it ends
+    async fn defer_past_blackouts(&self, windows: &[BlackoutWindow]) {
+        for window in windows {
+            if window.behavior != BlackoutBehavior::Defer || window.deferred_trigger_id.is_some() {
+                continue;
+            }
+            if let Err(e) = self.blackout_repo.defer_run(window.id).await {
+                warn!(window_id = %window.id, error = %e, "Failed to defer run past blackout window");
+            }
+        }
+    }
+
+    /// The job's runs due now, following its schedule's calendar if that exists
+    fn due_runs(
+        &self,
+        job: &Job,
+        schedule: &Schedule,
+        cursor: Option<DateTime<Utc>>,
+        calendars: &HashMap<String, Calendar>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<DueRuns>, ScheduleError> {
+        let calendar = schedule
+            .calendar()
+            .and_then(|wanted| calendars.get(&wanted.name));
+        let threshold = chrono::Duration::seconds(self.config.misfire_threshold_seconds as i64);
+        schedule.due_runs(cursor, job.created_at, now, threshold, calendar)
+    }
+
+    /// Move the job's schedule cursor from `claimed` to `to`, failing when another
+    /// node moved it meanwhile
+    async fn move_schedule_cursor(
+        &self,
+        job_id: Uuid,
+        claimed: &mut Option<DateTime<Utc>>,
+        to: DateTime<Utc>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self
+            .job_repo
+            .advance_schedule_cursor(job_id, *claimed, to)
+            .await
+        {
+            Ok(true) => {
+                *claimed = Some(to);
+                Ok(())
+            }
+            Ok(false) => {
+                warn!(job_id = %job_id, "Schedule cursor moved by another scheduler node");
+                Err("Schedule cursor moved by another scheduler node".into())
+            }
+            Err(e) => {
+                error!(job_id = %job_id, error = %e, "Failed to advance schedule cursor");
+                Err(Box::new(e))
+            }
+        }
+    }
+
+    /// Move the job's schedule cursor past runs that won't be made
+    async fn drop_due_runs(&self, job: &Job, cursor: Option<DateTime<Utc>>, due: Option<&DueRuns>) {
+        let Some(due) = due else {
+            return;
+        };
+        if let Err(e) = self
+            .job_repo
+            .advance_schedule_cursor(job.id, cursor, due.through)
+            .await
+        {
+            warn!(job_id = %job.id, error = %e, "Failed to advance schedule cursor");
+        }
+    }
+
+    /// Calendars named by the jobs' schedules, by name
+    ///
+    /// Nothing is loaded when no job names one; on error none are, so the jobs
+    /// naming one are skipped until the calendars load again.
+    async fn load_calendars(&self, jobs: &[Job]) -> HashMap<String, Calendar> {
+        let wanted = jobs
+            .iter()
+            .any(|job| job_schedule(job).is_some_and(|s| s.calendar().is_some()));
+        if !wanted {
+            return HashMap::new();
+        }
+        match self.calendar_repo.find_all().await {
+            Ok(calendars) => calendars.into_iter().map(|c| (c.name.clone(), c)).collect(),
+            Err(e) => {
+                warn!(error = %e, "Failed to load calendars");
+                HashMap::new()
+            }
+        }
+    }
+
     /// Calculate if a job is due for execution
     ///
     /// Requirements:
@@ -199,7 +900,7 @@ impl SchedulerEngine {
         // For now, we'll consider all enabled jobs with scheduled trigger as due
         // TODO: Implement proper schedule calculation in future iterations
         // This requires loading the full job definition from MinIO
-        job.enabled
+        job.enabled && !job.paused
     }
 }
 
@@ -223,20 +924,18 @@ impl Scheduler for SchedulerEngine {
         loop {
             tokio::select! {
                 _ = poll_interval.tick() => {
-                    debug!("Polling for jobs due");
-
-                    match self.process_due_jobs().await {
-                        Ok(count) => {
-                            if count > 0 {
-                                info!(jobs_processed = count, "Processed due jobs");
-                            } else {
-                                debug!("No jobs due for execution");
-                            }
-                        }
-                        Err(e) => {
-                            error!(error = %e, "Error processing due jobs");
-                        }
-                    }
+                    let started_at = Utc::now();
+                    let timer = Instant::now();
+                    let (status, decisions, error) = self.tick().await;
+                    let tick = SchedulerTick::new(
+                        self.node_id.clone(),
+                        started_at,
+                        timer.elapsed().as_millis() as i64,
+                        status,
+                        decisions,
+                        error,
+                    );
+                    self.record_tick(tick).await;
                 }
                 _ = shutdown_rx.recv() => {
                     info!("Shutdown signal received, stopping scheduler");
@@ -274,47 +973,68 @@ impl Scheduler for SchedulerEngine {
     /// - 4.1: Use distributed locking
     #[instrument(skip(self))]
     async fn process_due_jobs(&self) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
-        // Find all enabled jobs
-        let jobs = match self.job_repo.find_jobs_due(Utc::now()).await {
-            Ok(jobs) => jobs,
-            Err(e) => {
-                error!(error = %e, "Failed to query jobs from database");
-                return Err(Box::new(e));
-            }
-        };
-
-        debug!(job_count = jobs.len(), "Found jobs to evaluate");
-
-        let mut processed_count = 0;
+        let decisions = self.decide_due_jobs().await?;
+        Ok(fired_count(&decisions))
+    }
+}
 
-        // Process each job
-        for job in jobs.iter().take(self.config.max_jobs_per_poll) {
-            // Check if job is due based on schedule
-            if !self.is_job_due(job) {
-                continue;
-            }
+/// The job's schedule, from its stored definition if it wasn't loaded
+fn job_schedule(job: &Job) -> Option<Schedule> {
+    job.schedule.clone().or_else(|| {
+        let schedule = job.definition.as_ref()?.get("schedule")?;
+        serde_json::from_value(schedule.clone()).ok()
+    })
+}
 
-            // Process the job
-            match self.process_job(job).await {
-                Ok(()) => {
-                    processed_count += 1;
-                }
-                Err(e) => {
-                    error!(
-                        job_id = %job.id,
-                        job_name = %job.name,
-                        error = %e,
-                        "Failed to process job"
-                    );
-                    // Continue processing other jobs
-                }
-            }
+/// Whether the schedule's calendar is open on `now`'s date in the schedule's timezone
+///
+/// Due jobs fire without their run times being computed, so a calendar closes the
+/// whole day: business-day policies need a business day, `skip_holidays` only a day
+/// that isn't a holiday. A calendar that doesn't exist holds the job back.
+fn calendar_allows_run(
+    schedule: &Schedule,
+    calendars: &HashMap<String, Calendar>,
+    now: DateTime<Utc>,
+) -> bool {
+    let Schedule::Cron {
+        timezone,
+        calendar: Some(wanted),
+        ..
+    } = schedule
+    else {
+        return true;
+    };
+    let Some(calendar) = calendars.get(&wanted.name) else {
+        warn!(calendar = %wanted.name, "Schedule names an unknown calendar");
+        return false;
+    };
+    let today = now.with_timezone(timezone).date_naive();
+    match wanted.policy {
+        CalendarPolicy::BusinessDaysOnly | CalendarPolicy::NextBusinessDay => {
+            calendar.is_business_day(today)
         }
+        CalendarPolicy::SkipHolidays => !calendar.is_holiday(today),
+    }
+}
 
-        Ok(processed_count)
+/// Record the due time a scheduled run was made for in its trigger metadata
+fn attach_scheduled_for(execution: &mut JobExecution, due_at: DateTime<Utc>) {
+    let value = serde_json::json!(due_at);
+    match &mut execution.trigger_metadata {
+        Some(serde_json::Value::Object(map)) => {
+            map.insert(SCHEDULED_FOR_METADATA_KEY.to_string(), value);
+        }
+        metadata => *metadata = Some(serde_json::json!({ SCHEDULED_FOR_METADATA_KEY: value })),
     }
 }
 
+fn fired_count(decisions: &[JobDecision]) -> usize {
+    decisions
+        .iter()
+        .filter(|decision| decision.outcome == JobDecisionOutcome::Fired)
+        .count()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -333,9 +1053,72 @@ mod tests {
             poll_interval_seconds: 5,
             lock_ttl_seconds: 60,
             max_jobs_per_poll: 50,
+            misfire_threshold_seconds: 60,
         };
         assert_eq!(config.poll_interval_seconds, 5);
         assert_eq!(config.lock_ttl_seconds, 60);
         assert_eq!(config.max_jobs_per_poll, 50);
     }
+
+    #[test]
+    fn test_calendar_allows_run() {
+        let calendar = Calendar {
+            id: Uuid::new_v4(),
+            name: "vn".to_string(),
+            description: None,
+            weekend: vec![chrono::Weekday::Sat, chrono::Weekday::Sun],
+            vietnam_holidays: true,
+            holidays: Vec::new(),
+            working_days: Vec::new(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let calendars = HashMap::from([("vn".to_string(), calendar)]);
+        let schedule = |name: &str, policy| Schedule::Cron {
+            expression: "0 0 9 * * * *".to_string(),
+            timezone: chrono_tz::Asia::Ho_Chi_Minh,
+            end_date: None,
+            valid_for_seconds: None,
+            calendar: Some(crate::models::ScheduleCalendar {
+                name: name.to_string(),
+                policy,
+            }),
+            misfire_policy: None,
+        };
+        let at = |rfc3339: &str| DateTime::parse_from_rfc3339(rfc3339).unwrap().to_utc();
+
+        // 30 April 2025 is a holiday from 17:00 UTC the day before
+        let holiday = at("2025-04-29T17:30:00Z");
+        let saturday = at("2025-05-03T02:00:00Z");
+        assert!(calendar_allows_run(
+            &schedule("vn", CalendarPolicy::BusinessDaysOnly),
+            &calendars,
+            at("2025-04-29T16:30:00Z")
+        ));
+        assert!(!calendar_allows_run(
+            &schedule("vn", CalendarPolicy::BusinessDaysOnly),
+            &calendars,
+            holiday
+        ));
+        assert!(!calendar_allows_run(
+            &schedule("vn", CalendarPolicy::SkipHolidays),
+            &calendars,
+            holiday
+        ));
+        assert!(calendar_allows_run(
+            &schedule("vn", CalendarPolicy::SkipHolidays),
+            &calendars,
+            saturday
+        ));
+        assert!(!calendar_allows_run(
+            &schedule("vn", CalendarPolicy::NextBusinessDay),
+            &calendars,
+            saturday
+        ));
+        assert!(!calendar_allows_run(
+            &schedule("missing", CalendarPolicy::BusinessDaysOnly),
+            &calendars,
+            at("2025-04-29T16:30:00Z")
+        ));
+    }
 }
@@ -1,6 +1,11 @@
 // Scheduler module for job trigger detection and publishing
 // Requirements: 7.1, 4.1
 
+pub mod control;
 pub mod engine;
 
+pub use control::{
+    SchedulerCommand, SchedulerControl, SchedulerControlAck, SCHEDULER_CONTROL_ACK_TIMEOUT,
+    SCHEDULER_CONTROL_SUBJECT,
+};
 pub use engine::{Scheduler, SchedulerConfig, SchedulerEngine};
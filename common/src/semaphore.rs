@@ -0,0 +1,194 @@
+// Distributed counting semaphore backed by Redis
+// Purpose: Cap how many executions of a job run at once across all workers
+//
+// Holders are members of a sorted set scored by when their permit lapses, so a worker
+// that dies without releasing frees its slot once the TTL passes.
+
+use crate::db::RedisPool;
+use crate::errors::StorageError;
+use async_trait::async_trait;
+use tracing::{debug, instrument, warn};
+
+/// Atomically drop lapsed holders, then admit `holder` if it already holds a permit or
+/// a slot is free. Returns 1 when admitted.
+const ACQUIRE_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local expires_at = tonumber(ARGV[2])
+local limit = tonumber(ARGV[3])
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now)
+if redis.call('ZSCORE', KEYS[1], ARGV[4]) or redis.call('ZCARD', KEYS[1]) < limit then
+    redis.call('ZADD', KEYS[1], expires_at, ARGV[4])
+    redis.call('PEXPIREAT', KEYS[1], expires_at)
+    return 1
+end
+return 0
+"#;
+
+/// Distributed semaphore trait for limiting concurrent holders of a resource
+#[async_trait]
+pub trait DistributedSemaphore: Send + Sync {
+    /// Take one of `limit` permits on the resource for `holder`, without waiting
+    ///
+    /// Returns `None` when all permits are taken. A holder asking again keeps its
+    /// permit, with the TTL renewed.
+    async fn try_acquire(
+        &self,
+        resource: &str,
+        holder: &str,
+        limit: u32,
+        ttl: std::time::Duration,
+    ) -> Result<Option<SemaphorePermit>, StorageError>;
+}
+
+/// Permit that returns its slot when dropped
+pub struct SemaphorePermit {
+    resource: String,
+    holder: String,
+    pool: Option<RedisPool>,
+}
+
+impl SemaphorePermit {
+    /// Get the resource name this permit is for
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+}
+
+#[cfg(test)]
+impl SemaphorePermit {
+    /// Permit of a semaphore faked in tests, releasing nothing when dropped
+    pub(crate) fn unbacked(resource: &str, holder: &str) -> Self {
+        Self {
+            resource: resource.to_string(),
+            holder: holder.to_string(),
+            pool: None,
+        }
+    }
+}
+
+impl Drop for SemaphorePermit {
+    fn drop(&mut self) {
+        let Some(pool) = self.pool.take() else {
+            return;
+        };
+        let key = semaphore_key(&self.resource);
+        let holder = std::mem::take(&mut self.holder);
+
+        tokio::spawn(async move {
+            let mut conn = pool.get_connection();
+            let released: Result<i64, _> = redis::cmd("ZREM")
+                .arg(&key)
+                .arg(&holder)
+                .query_async(&mut conn)
+                .await;
+            match released {
+                Ok(_) => debug!(key = %key, holder = %holder, "Semaphore permit released"),
+                Err(e) => warn!(
+                    key = %key,
+                    holder = %holder,
+                    error = %e,
+                    "Failed to release semaphore permit on drop"
+                ),
+            }
+        });
+    }
+}
+
+/// Semaphore whose permits live in Redis
+pub struct RedisSemaphore {
+    pool: RedisPool,
+}
+
+impl RedisSemaphore {
+    /// Create a new RedisSemaphore
+    pub fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl DistributedSemaphore for RedisSemaphore {
+    #[instrument(skip(self), fields(ttl_seconds = ttl.as_secs()))]
+    async fn try_acquire(
+        &self,
+        resource: &str,
+        holder: &str,
+        limit: u32,
+        ttl: std::time::Duration,
+    ) -> Result<Option<SemaphorePermit>, StorageError> {
+        let mut conn = self.pool.get_connection();
+        let now = chrono::Utc::now().timestamp_millis();
+        let expires_at = now + ttl.as_millis() as i64;
+
+        let admitted: i32 = redis::Script::new(ACQUIRE_SCRIPT)
+            .key(semaphore_key(resource))
+            .arg(now)
+            .arg(expires_at)
+            .arg(limit)
+            .arg(holder)
+            .invoke_async(&mut conn)
+            .await
+            .map_err(|e| {
+                StorageError::RedisError(format!("Failed to acquire semaphore permit: {}", e))
+            })?;
+
+        if admitted == 0 {
+            debug!(resource = %resource, limit, "All semaphore permits taken");
+            return Ok(None);
+        }
+        debug!(resource = %resource, holder = %holder, "Semaphore permit acquired");
+        Ok(Some(SemaphorePermit {
+            resource: resource.to_string(),
+            holder: holder.to_string(),
+            pool: Some(self.pool.clone()),
+        }))
+    }
+}
+
+fn semaphore_key(resource: &str) -> String {
+    format!("semaphore:{}", resource)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RedisConfig;
+    use std::time::Duration;
+
+    #[tokio::test]
+    #[ignore] // Requires Redis to be running
+    async fn test_semaphore_admits_up_to_limit() {
+        let config = RedisConfig {
+            url: "redis://localhost:6379".to_string(),
+            pool_size: 10,
+            sentinel: None,
+            cluster: None,
+        };
+        let semaphore = RedisSemaphore::new(RedisPool::new(&config).await.unwrap());
+        let ttl = Duration::from_secs(10);
+
+        let first = semaphore.try_acquire("limited", "a", 2, ttl).await.unwrap();
+        let second = semaphore.try_acquire("limited", "b", 2, ttl).await.unwrap();
+        assert!(first.is_some() && second.is_some());
+        assert!(semaphore
+            .try_acquire("limited", "c", 2, ttl)
+            .await
+            .unwrap()
+            .is_none());
+
+        // A holder asking again keeps its slot
+        assert!(semaphore
+            .try_acquire("limited", "a", 2, ttl)
+            .await
+            .unwrap()
+            .is_some());
+
+        drop(second);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(semaphore
+            .try_acquire("limited", "c", 2, ttl)
+            .await
+            .unwrap()
+            .is_some());
+    }
+}
@@ -0,0 +1,233 @@
+// Job simulation against captured webhook payloads
+// Purpose: Check a job's `{{webhook...}}` mappings against real partner requests before
+// they go live, without running anything
+//
+// A simulation is a dry run: every step's configuration is resolved against the
+// captured payload and the trigger variables it carried, but nothing is sent, queried
+// or written. References to the output of an earlier step can't be known without
+// running it, so they are left in place and listed instead of reported as errors.
+
+use crate::capabilities::step_type_name;
+use crate::models::{JobContext, JobStep};
+use crate::worker::reference::ReferenceResolver;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Dry-run result of one step
+#[derive(Debug, Clone, Serialize)]
+pub struct StepSimulation {
+    pub step_id: String,
+    pub name: String,
+    pub step_type: &'static str,
+    /// Step configuration with the references that could be resolved filled in
+    pub resolved: serde_json::Value,
+    /// References to the outputs of earlier steps, only known in a real run
+    pub pending_references: Vec<String>,
+    /// References that failed to resolve
+    pub errors: Vec<String>,
+}
+
+/// Dry-run result of a job
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    /// Whether every reference resolved, or depends only on an earlier step
+    pub valid: bool,
+    pub steps: Vec<StepSimulation>,
+}
+
+/// Resolve every step of a job against a context, without running anything
+pub fn simulate_steps(steps: &[JobStep], context: &JobContext) -> SimulationReport {
+    let resolver = ReferenceResolver::new();
+    let mut earlier: HashSet<&str> = HashSet::new();
+
+    let steps: Vec<StepSimulation> = steps
+        .iter()
+        .map(|step| {
+            let mut simulation = StepSimulation {
+                step_id: step.id.clone(),
+                name: step.name.clone(),
+                step_type: step_type_name(&step.step_type),
+                resolved: serde_json::Value::Null,
+                pending_references: Vec::new(),
+                errors: Vec::new(),
+            };
+            let config = serde_json::to_value(&step.step_type).unwrap_or_default();
+            simulation.resolved =
+                resolve_value(&config, context, &resolver, &earlier, &mut simulation);
+            earlier.insert(step.id.as_str());
+            simulation
+        })
+        .collect();
+
+    SimulationReport {
+        valid: steps.iter().all(|step| step.errors.is_empty()),
+        steps,
+    }
+}
+
+/// Resolve the references in every string of a step configuration
+fn resolve_value(
+    value: &serde_json::Value,
+    context: &JobContext,
+    resolver: &ReferenceResolver,
+    earlier: &HashSet<&str>,
+    simulation: &mut StepSimulation,
+) -> serde_json::Value {
+    match value {
+        serde_json::Value::String(template) => serde_json::Value::String(resolve_template(
+            template, context, resolver, earlier, simulation,
+        )),
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|item| resolve_value(item, context, resolver, earlier, simulation))
+                .collect(),
+        ),
+        serde_json::Value::Object(fields) => serde_json::Value::Object(
+            fields
+                .iter()
+                .map(|(key, item)| {
+                    (
+                        key.clone(),
+                        resolve_value(item, context, resolver, earlier, simulation),
+                    )
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+fn resolve_template(
+    template: &str,
+    context: &JobContext,
+    resolver: &ReferenceResolver,
+    earlier: &HashSet<&str>,
+    simulation: &mut StepSimulation,
+) -> String {
+    let mut resolved = template.to_string();
+    for reference in resolver.references(template) {
+        if let Some(path) = reference.strip_prefix("steps.") {
+            let step_id = path.split('.').next().unwrap_or_default();
+            if earlier.contains(step_id) {
+                push_unique(&mut simulation.pending_references, reference.to_string());
+            } else {
+                push_unique(
+                    &mut simulation.errors,
+                    format!(
+                        "'{}': step '{}' doesn't run before this step",
+                        reference, step_id
+                    ),
+                );
+            }
+            continue;
+        }
+
+        match resolver.resolve_reference(reference, context) {
+            Ok(value) => {
+                resolved = resolved.replace(&format!("{{{{{}}}}}", reference), &value);
+            }
+            Err(e) => push_unique(&mut simulation.errors, format!("'{}': {}", reference, e)),
+        }
+    }
+    resolved
+}
+
+fn push_unique(items: &mut Vec<String>, item: String) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HttpMethod, JobType, WebhookData};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn http_step(id: &str, url: &str, body: Option<&str>) -> JobStep {
+        JobStep {
+            id: id.to_string(),
+            name: id.to_string(),
+            step_type: JobType::HttpRequest {
+                method: HttpMethod::Post,
+                url: url.to_string(),
+                headers: HashMap::new(),
+                body: body.map(str::to_string),
+                auth: None,
+            },
+            condition: None,
+            on_failure: None,
+            compensate_with: Vec::new(),
+            acquire_lock: None,
+            set_state: Default::default(),
+            env: Default::default(),
+            timeout_seconds: None,
+            retry_count: None,
+            stream_output: false,
+            pii: false,
+            dependency: None,
+            connection: None,
+        }
+    }
+
+    fn context() -> JobContext {
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context.set_webhook_data(WebhookData {
+            payload: json!({ "order": { "id": 42, "branch": "HN" } }),
+            query_params: HashMap::new(),
+            headers: HashMap::new(),
+        });
+        context
+    }
+
+    #[test]
+    fn test_simulation_resolves_webhook_mappings() {
+        let steps = vec![
+            http_step(
+                "notify",
+                "https://partner.example.com/orders/{{webhook.payload.order.id}}",
+                Some(r#"{"branch":"{{webhook.payload.order.branch}}"}"#),
+            ),
+            http_step(
+                "ack",
+                "https://partner.example.com/ack/{{steps.notify.output.status_code}}",
+                None,
+            ),
+        ];
+
+        let report = simulate_steps(&steps, &context());
+        assert!(report.valid);
+        assert_eq!(report.steps[0].step_type, "http_request");
+        assert_eq!(
+            report.steps[0].resolved["url"],
+            "https://partner.example.com/orders/42"
+        );
+        assert_eq!(report.steps[0].resolved["body"], r#"{"branch":"HN"}"#);
+        assert_eq!(
+            report.steps[1].pending_references,
+            vec!["steps.notify.output.status_code"]
+        );
+        assert!(report.steps[1].resolved["url"]
+            .as_str()
+            .unwrap()
+            .contains("{{steps.notify.output.status_code}}"));
+    }
+
+    #[test]
+    fn test_simulation_reports_broken_mappings() {
+        let steps = vec![http_step(
+            "notify",
+            "https://partner.example.com/{{webhook.payload.order.customer}}/{{steps.later.output}}",
+            None,
+        )];
+
+        let report = simulate_steps(&steps, &context());
+        assert!(!report.valid);
+        assert_eq!(report.steps[0].errors.len(), 2);
+        assert!(report.steps[0].errors[0].contains("customer"));
+        assert!(report.steps[0].errors[1].contains("doesn't run before this step"));
+    }
+}
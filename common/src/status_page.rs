@@ -0,0 +1,103 @@
+// Public status pages
+// Purpose: Read-only, token-scoped pages showing selected jobs' latest status, for
+// embedding in partner-facing pages (e.g. "daily file delivery status") without
+// giving partners dashboard accounts
+//
+// The token in the page URL is the only credential. It is returned once when the page
+// is created and stored as a SHA-256 hash, so a leaked database dump can't be used to
+// open pages. A page exposes job names, latest status and timestamps, nothing else.
+
+use crate::errors::ValidationError;
+use crate::models::ExecutionStatus;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use uuid::Uuid;
+
+/// Most jobs one status page may show
+pub const MAX_STATUS_PAGE_JOBS: usize = 50;
+
+/// Longest accepted status page name, in characters
+const MAX_NAME_LEN: usize = 100;
+
+/// A status page and the jobs it shows, in display order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusPage {
+    pub id: Uuid,
+    pub name: String,
+    pub job_ids: Vec<Uuid>,
+    /// Token subject of the admin who created it
+    pub created_by: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// What a status page shows for one job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicJobStatus {
+    pub name: String,
+    /// Status of the latest execution; `None` if the job never ran
+    pub status: Option<ExecutionStatus>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+/// Generate a status page token
+pub fn generate_token() -> String {
+    let random_bytes: [u8; 32] = rand::thread_rng().gen();
+    hex::encode(random_bytes)
+}
+
+/// Hash of a token, as stored and looked up
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Validate the name and jobs of a status page being saved
+pub fn validate_status_page(name: &str, job_ids: &[Uuid]) -> Result<(), ValidationError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_LEN {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "name".to_string(),
+            reason: format!("must be 1 to {} characters", MAX_NAME_LEN),
+        });
+    }
+    if job_ids.is_empty() || job_ids.len() > MAX_STATUS_PAGE_JOBS {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "job_ids".to_string(),
+            reason: format!("must list 1 to {} jobs", MAX_STATUS_PAGE_JOBS),
+        });
+    }
+    if job_ids.iter().collect::<HashSet<_>>().len() != job_ids.len() {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "job_ids".to_string(),
+            reason: "jobs must be unique".to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokens_and_validation() {
+        let token = generate_token();
+        assert_eq!(token.len(), 64);
+        assert_ne!(token, generate_token());
+        assert_eq!(hash_token(&token), hash_token(&token));
+        assert_ne!(hash_token(&token), token);
+
+        let job_id = Uuid::new_v4();
+        assert!(validate_status_page("Daily file delivery", &[job_id]).is_ok());
+        assert!(validate_status_page(" ", &[job_id]).is_err());
+        assert!(validate_status_page("Daily file delivery", &[]).is_err());
+        assert!(validate_status_page("Daily file delivery", &[job_id, job_id]).is_err());
+
+        let too_many: Vec<Uuid> = (0..=MAX_STATUS_PAGE_JOBS).map(|_| Uuid::new_v4()).collect();
+        assert!(validate_status_page("Daily file delivery", &too_many).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+// Step environment
+// Purpose: Hand container, script and wasm steps environment variables taken from job
+// variables or sensitive variables, instead of splicing secrets into their commands
+// or sources
+//
+// Secret values are resolved only for the step that declares them, are never
+// persisted with the context, and are masked wherever the step's logs, output or
+// errors are kept.
+
+use crate::connection_profile::MASKED_SECRET;
+use crate::db::repositories::variable::VariableRepository;
+use crate::errors::ExecutionError;
+use crate::models::{EnvSource, JobContext, JobStep};
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Source of the secret values of step environments
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Value of the secret `name` visible to the job, if there is one
+    async fn secret(&self, job_id: Uuid, name: &str) -> Result<Option<String>, ExecutionError>;
+}
+
+#[async_trait]
+impl SecretProvider for VariableRepository {
+    async fn secret(&self, job_id: Uuid, name: &str) -> Result<Option<String>, ExecutionError> {
+        self.find_sensitive_for_job(job_id, name)
+            .await
+            .map_err(|e| {
+                ExecutionError::VariableResolutionFailed(format!(
+                    "Failed to load secret '{}': {}",
+                    name, e
+                ))
+            })
+    }
+}
+
+/// Whether `name` is a portable environment variable name: letters, digits and
+/// underscores, not starting with a digit
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Resolved environment of the running step
+#[derive(Debug, Clone, Default)]
+pub struct StepEnv {
+    vars: Vec<(String, String)>,
+    /// Secret values, longest first so overlapping secrets are masked whole
+    secrets: Vec<String>,
+}
+
+impl StepEnv {
+    /// Resolve the `env` of a step against the context and the secret provider
+    ///
+    /// Fails when a secret is missing or no provider is configured.
+    pub async fn resolve(
+        step: &JobStep,
+        context: &JobContext,
+        resolver: &ReferenceResolver,
+        secrets: Option<&dyn SecretProvider>,
+    ) -> Result<Self, ExecutionError> {
+        let mut env = Self::default();
+        for (name, source) in &step.env {
+            match source {
+                EnvSource::Value(template) => {
+                    let value = resolver.resolve(template, context).map_err(|e| {
+                        ExecutionError::VariableResolutionFailed(format!(
+                            "Failed to resolve env.{}: {}",
+                            name, e
+                        ))
+                    })?;
+                    env.vars.push((name.clone(), value));
+                }
+                EnvSource::Secret(secret) => {
+                    let provider = secrets.ok_or_else(|| {
+                        ExecutionError::VariableResolutionFailed(format!(
+                            "env.{} reads a secret, but no secret provider is configured",
+                            name
+                        ))
+                    })?;
+                    let value =
+                        provider
+                            .secret(context.job_id, secret)
+                            .await?
+                            .ok_or_else(|| {
+                                ExecutionError::VariableResolutionFailed(format!(
+                                    "env.{}: secret '{}' not found",
+                                    name, secret
+                                ))
+                            })?;
+                    env.add_secret(name.clone(), value);
+                }
+            }
+        }
+        Ok(env)
+    }
+
+    /// Add a secret variable, e.g. in tests
+    pub fn add_secret(&mut self, name: String, value: String) {
+        if !value.is_empty() {
+            self.secrets.push(value.clone());
+            self.secrets
+                .sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        }
+        self.vars.push((name, value));
+    }
+
+    /// Name and value of each variable
+    pub fn vars(&self) -> &[(String, String)] {
+        &self.vars
+    }
+
+    /// The variables as a name to value map
+    pub fn to_map(&self) -> std::collections::HashMap<String, String> {
+        self.vars.iter().cloned().collect()
+    }
+
+    /// Whether the step declared no environment
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// Text with every secret value masked
+    pub fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            if text.contains(secret.as_str()) {
+                text.replace(secret.as_str(), MASKED_SECRET)
+            } else {
+                text
+            }
+        })
+    }
+
+    /// Raw output, e.g. a process log, with every secret value masked; left as is
+    /// when the step has no secrets
+    pub fn redact_bytes(&self, bytes: Vec<u8>) -> Vec<u8> {
+        if self.secrets.is_empty() {
+            return bytes;
+        }
+        self.redact(&String::from_utf8_lossy(&bytes)).into_bytes()
+    }
+
+    /// Mask every secret value in the strings and keys of a JSON value
+    pub fn redact_json(&self, value: &mut serde_json::Value) {
+        if self.secrets.is_empty() {
+            return;
+        }
+        match value {
+            serde_json::Value::String(text) => *text = self.redact(text),
+            serde_json::Value::Array(items) => {
+                items.iter_mut().for_each(|item| self.redact_json(item))
+            }
+            serde_json::Value::Object(map) => {
+                *map = std::mem::take(map)
+                    .into_iter()
+                    .map(|(key, mut item)| {
+                        self.redact_json(&mut item);
+                        (self.redact(&key), item)
+                    })
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    struct Secrets(HashMap<String, String>);
+
+    #[async_trait]
+    impl SecretProvider for Secrets {
+        async fn secret(
+            &self,
+            _job_id: Uuid,
+            name: &str,
+        ) -> Result<Option<String>, ExecutionError> {
+            Ok(self.0.get(name).cloned())
+        }
+    }
+
+    fn step(env: serde_json::Value) -> JobStep {
+        serde_json::from_value(json!({
+            "id": "report",
+            "name": "Build report",
+            "type": {"type": "script", "source": "1"},
+            "condition": null,
+            "env": env,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resolve_values_and_secrets() {
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context
+            .variables
+            .insert("region".to_string(), json!("eu-west"));
+        let secrets = Secrets(HashMap::from([(
+            "reporting_token".to_string(),
+            "s3cr3t-token".to_string(),
+        )]));
+        let step = step(json!({
+            "API_TOKEN": {"secret": "reporting_token"},
+            "REGION": {"value": "{{region}}"}
+        }));
+
+        let env = StepEnv::resolve(&step, &context, &ReferenceResolver::new(), Some(&secrets))
+            .await
+            .unwrap();
+        let vars = env.to_map();
+        assert_eq!(vars["API_TOKEN"], "s3cr3t-token");
+        assert_eq!(vars["REGION"], "eu-west");
+
+        // Only secrets are masked
+        assert_eq!(
+            env.redact("token=s3cr3t-token region=eu-west"),
+            "token=*** region=eu-west"
+        );
+        let mut output = json!({"echo": ["s3cr3t-token"], "s3cr3t-token": 1, "n": 2});
+        env.redact_json(&mut output);
+        assert_eq!(output, json!({"echo": ["***"], "***": 1, "n": 2}));
+
+        // A missing secret or provider fails the step rather than running it without
+        let missing = step(json!({ "API_TOKEN": {"secret": "unknown"} }));
+        assert!(StepEnv::resolve(
+            &missing,
+            &context,
+            &ReferenceResolver::new(),
+            Some(&secrets)
+        )
+        .await
+        .is_err());
+        assert!(
+            StepEnv::resolve(&step, &context, &ReferenceResolver::new(), None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_redact_overlapping_secrets() {
+        let mut env = StepEnv::default();
+        env.add_secret("SHORT".to_string(), "abc".to_string());
+        env.add_secret("LONG".to_string(), "abcdef".to_string());
+        env.add_secret("EMPTY".to_string(), String::new());
+        assert_eq!(env.redact("abcdef abc x"), "*** *** x");
+        assert_eq!(env.redact_bytes(b"log: abc".to_vec()), b"log: ***".to_vec());
+
+        // Logs of steps without secrets are kept byte for byte
+        let bytes = vec![0xff, b'a', b'b', b'c'];
+        assert_eq!(StepEnv::default().redact_bytes(bytes.clone()), bytes);
+    }
+
+    #[test]
+    fn test_valid_names() {
+        assert!(is_valid_name("API_TOKEN"));
+        assert!(is_valid_name("_private1"));
+        assert!(!is_valid_name("1TOKEN"));
+        assert!(!is_valid_name("API-TOKEN"));
+        assert!(!is_valid_name(""));
+    }
+}
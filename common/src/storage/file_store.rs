@@ -0,0 +1,382 @@
+// File store for execution files: a local directory or an S3-compatible bucket
+// Requirements: 13.7 - Store execution files
+//
+// The default store is `storage.file_base_path`; tenants with data residency rules
+// get their own store, see `StorageConfig::residency`.
+//
+// Buckets can't append to an object, so each append to `path` is its own object under
+// `path.parts/`, named by the time it was written; reads concatenate the object at
+// `path`, if any, and its parts in order.
+
+use crate::errors::StorageError;
+use crate::executor::object_storage::build_client;
+use crate::models::ObjectStorageBucket;
+use chrono::Utc;
+use futures::TryStreamExt;
+use object_store::path::Path as ObjectPath;
+use object_store::{ObjectStore, PutPayload};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+/// Timeout of a single request to a bucket
+const BUCKET_REQUEST_TIMEOUT_SECONDS: u64 = 60;
+
+/// Suffix of the prefix holding the appended parts of a bucket object
+const PARTS_SUFFIX: &str = ".parts";
+
+/// Where execution files are kept
+pub(crate) enum FileStore {
+    Filesystem(PathBuf),
+    Bucket {
+        name: String,
+        client: Arc<dyn ObjectStore>,
+    },
+}
+
+impl FileStore {
+    /// Store writing to an S3-compatible bucket
+    pub(crate) fn bucket(bucket: &ObjectStorageBucket) -> Result<Self, StorageError> {
+        let client = build_client(bucket, BUCKET_REQUEST_TIMEOUT_SECONDS)
+            .map_err(|e| StorageError::ConnectionFailed(e.to_string()))?;
+        Ok(Self::Bucket {
+            name: bucket.name.clone(),
+            client: Arc::new(client),
+        })
+    }
+
+    /// Human-readable location, for logs
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Self::Filesystem(base) => base.display().to_string(),
+            Self::Bucket { name, .. } => format!("s3://{}", name),
+        }
+    }
+
+    pub(crate) async fn put(&self, path: &str, data: &[u8]) -> Result<(), StorageError> {
+        match self {
+            Self::Filesystem(base) => {
+                let file_path = base.join(path);
+
+                // Create parent directories
+                if let Some(parent) = file_path.parent() {
+                    fs::create_dir_all(parent).await.map_err(|e| {
+                        error!(error = %e, path = %path, "Failed to create directories");
+                        StorageError::FileSystemError(e.to_string())
+                    })?;
+                }
+
+                // Write file
+                fs::write(&file_path, data).await.map_err(|e| {
+                    error!(error = %e, path = %path, "Failed to write file");
+                    StorageError::FileSystemError(e.to_string())
+                })?;
+            }
+            Self::Bucket { client, .. } => {
+                // Parts appended to an earlier version would otherwise follow the new one
+                delete_parts(client.as_ref(), path).await?;
+                client
+                    .put(&ObjectPath::from(path), PutPayload::from(data.to_vec()))
+                    .await
+                    .map_err(|e| bucket_error("write", path, e))?;
+            }
+        }
+
+        info!(path = %path, size = data.len(), "File stored");
+        Ok(())
+    }
+
+    /// Append to a file, creating it if missing; buckets store the data as a new part
+    pub(crate) async fn append(&self, path: &str, data: &[u8]) -> Result<(), StorageError> {
+        match self {
+            Self::Filesystem(base) => {
+                let file_path = base.join(path);
+
+                if let Some(parent) = file_path.parent() {
+                    fs::create_dir_all(parent).await.map_err(|e| {
+                        error!(error = %e, path = %path, "Failed to create directories");
+                        StorageError::FileSystemError(e.to_string())
+                    })?;
+                }
+
+                let mut file = fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&file_path)
+                    .await
+                    .map_err(|e| {
+                        error!(error = %e, path = %path, "Failed to open file for append");
+                        StorageError::FileSystemError(e.to_string())
+                    })?;
+                file.write_all(data).await.map_err(|e| {
+                    error!(error = %e, path = %path, "Failed to append to file");
+                    StorageError::FileSystemError(e.to_string())
+                })?;
+            }
+            Self::Bucket { client, .. } => {
+                // A part per append: concurrent appends never overwrite each other and
+                // an append only uploads its own bytes
+                let part = format!(
+                    "{}{}/{:020}-{}",
+                    path,
+                    PARTS_SUFFIX,
+                    Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+                    Uuid::new_v4()
+                );
+                client
+                    .put(&ObjectPath::from(part), PutPayload::from(data.to_vec()))
+                    .await
+                    .map_err(|e| bucket_error("append to", path, e))?;
+            }
+        }
+
+        debug!(path = %path, size = data.len(), "File appended");
+        Ok(())
+    }
+
+    /// Contents of a file; `NotFound` if it doesn't exist
+    pub(crate) async fn get(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        let data = match self {
+            Self::Filesystem(base) => fs::read(base.join(path)).await.map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    return StorageError::NotFound(path.to_string());
+                }
+                error!(error = %e, path = %path, "Failed to read file");
+                StorageError::FileSystemError(e.to_string())
+            })?,
+            Self::Bucket { client, .. } => {
+                let client = client.as_ref();
+                let base = match read_object(client, &ObjectPath::from(path), path).await {
+                    Ok(data) => Some(data),
+                    Err(StorageError::NotFound(_)) => None,
+                    Err(e) => return Err(e),
+                };
+                let parts = list_parts(client, path).await?;
+                if base.is_none() && parts.is_empty() {
+                    return Err(StorageError::NotFound(path.to_string()));
+                }
+
+                let mut data = base.unwrap_or_default();
+                for part in &parts {
+                    data.extend_from_slice(&read_object(client, part, path).await?);
+                }
+                data
+            }
+        };
+
+        debug!(path = %path, size = data.len(), "File loaded");
+        Ok(data)
+    }
+
+    /// Size of a file in bytes, without reading it; `NotFound` if it doesn't exist
+    pub(crate) async fn size(&self, path: &str) -> Result<u64, StorageError> {
+        match self {
+            Self::Filesystem(base) => fs::metadata(base.join(path))
+                .await
+                .map(|metadata| metadata.len())
+                .map_err(|e| {
+                    if e.kind() == std::io::ErrorKind::NotFound {
+                        return StorageError::NotFound(path.to_string());
+                    }
+                    error!(error = %e, path = %path, "Failed to read file metadata");
+                    StorageError::FileSystemError(e.to_string())
+                }),
+            Self::Bucket { client, .. } => {
+                let base = match client.head(&ObjectPath::from(path)).await {
+                    Ok(object) => Some(object.size),
+                    Err(object_store::Error::NotFound { .. }) => None,
+                    Err(e) => return Err(bucket_error("read", path, e)),
+                };
+                let prefix = ObjectPath::from(format!("{}{}", path, PARTS_SUFFIX));
+                let parts: Vec<u64> = client
+                    .list(Some(&prefix))
+                    .map_ok(|object| object.size)
+                    .try_collect()
+                    .await
+                    .map_err(|e| bucket_error("list parts of", path, e))?;
+                if base.is_none() && parts.is_empty() {
+                    return Err(StorageError::NotFound(path.to_string()));
+                }
+                Ok(base.unwrap_or_default() + parts.iter().sum::<u64>())
+            }
+        }
+    }
+
+    pub(crate) async fn delete(&self, path: &str) -> Result<(), StorageError> {
+        match self {
+            Self::Filesystem(base) => {
+                fs::remove_file(base.join(path)).await.map_err(|e| {
+                    error!(error = %e, path = %path, "Failed to delete file");
+                    StorageError::FileSystemError(e.to_string())
+                })?;
+            }
+            Self::Bucket { client, .. } => {
+                let parts = delete_parts(client.as_ref(), path).await?;
+                match client.delete(&ObjectPath::from(path)).await {
+                    Ok(()) => {}
+                    // A file only ever appended to has no object of its own
+                    Err(object_store::Error::NotFound { .. }) if parts > 0 => {}
+                    Err(e) => return Err(bucket_error("delete", path, e)),
+                }
+            }
+        }
+
+        info!(path = %path, "File deleted");
+        Ok(())
+    }
+
+    /// Entries directly under a prefix, as `prefix/name`
+    pub(crate) async fn list(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        let mut files = Vec::new();
+        match self {
+            Self::Filesystem(base) => {
+                let mut entries = fs::read_dir(base.join(prefix)).await.map_err(|e| {
+                    error!(error = %e, prefix = %prefix, "Failed to read directory");
+                    StorageError::FileSystemError(e.to_string())
+                })?;
+
+                while let Some(entry) = entries
+                    .next_entry()
+                    .await
+                    .map_err(|e| StorageError::FileSystemError(e.to_string()))?
+                {
+                    if let Ok(file_name) = entry.file_name().into_string() {
+                        files.push(format!("{}/{}", prefix, file_name));
+                    }
+                }
+            }
+            Self::Bucket { client, .. } => {
+                let listing = client
+                    .list_with_delimiter(Some(&ObjectPath::from(prefix)))
+                    .await
+                    .map_err(|e| bucket_error("list", prefix, e))?;
+                // A file's parts are listed as the file
+                let mut names: Vec<String> = listing
+                    .common_prefixes
+                    .iter()
+                    .chain(listing.objects.iter().map(|object| &object.location))
+                    .filter_map(|path| path.filename())
+                    .map(|name| name.strip_suffix(PARTS_SUFFIX).unwrap_or(name).to_string())
+                    .collect();
+                names.sort();
+                names.dedup();
+                files.extend(names.iter().map(|name| format!("{}/{}", prefix, name)));
+            }
+        }
+
+        debug!(prefix = %prefix, count = files.len(), "Files listed");
+        Ok(files)
+    }
+}
+
+/// Contents of one bucket object of the file at `path`
+async fn read_object(
+    client: &dyn ObjectStore,
+    location: &ObjectPath,
+    path: &str,
+) -> Result<Vec<u8>, StorageError> {
+    let object = client
+        .get(location)
+        .await
+        .map_err(|e| bucket_error("read", path, e))?;
+    let bytes = object
+        .bytes()
+        .await
+        .map_err(|e| bucket_error("read", path, e))?;
+    Ok(bytes.to_vec())
+}
+
+/// Appended parts of the file at `path`, in the order they were written
+async fn list_parts(client: &dyn ObjectStore, path: &str) -> Result<Vec<ObjectPath>, StorageError> {
+    let prefix = ObjectPath::from(format!("{}{}", path, PARTS_SUFFIX));
+    let mut parts: Vec<ObjectPath> = client
+        .list(Some(&prefix))
+        .map_ok(|object| object.location)
+        .try_collect()
+        .await
+        .map_err(|e| bucket_error("list parts of", path, e))?;
+    parts.sort();
+    Ok(parts)
+}
+
+/// Delete the appended parts of the file at `path`, returning how many there were
+async fn delete_parts(client: &dyn ObjectStore, path: &str) -> Result<usize, StorageError> {
+    let parts = list_parts(client, path).await?;
+    for part in &parts {
+        match client.delete(part).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(bucket_error("delete parts of", path, e)),
+        }
+    }
+    Ok(parts.len())
+}
+
+fn bucket_error(action: &str, path: &str, e: object_store::Error) -> StorageError {
+    if let object_store::Error::NotFound { .. } = e {
+        return StorageError::NotFound(path.to_string());
+    }
+    error!(error = %e, path = %path, "Failed to {} object", action);
+    StorageError::ObjectStorageError(format!("Failed to {} '{}': {}", action, path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object_store::memory::InMemory;
+
+    #[tokio::test]
+    async fn test_bucket_store_appends_and_lists() {
+        let store = FileStore::Bucket {
+            name: "test".to_string(),
+            client: Arc::new(InMemory::new()),
+        };
+
+        store.append("jobs/a/log.txt", b"one ").await.unwrap();
+        store.append("jobs/a/log.txt", b"two").await.unwrap();
+        store.put("jobs/a/out/data.csv", b"x").await.unwrap();
+
+        assert_eq!(store.get("jobs/a/log.txt").await.unwrap(), b"one two");
+        assert_eq!(store.size("jobs/a/log.txt").await.unwrap(), 7);
+        let mut listed = store.list("jobs/a").await.unwrap();
+        listed.sort();
+        assert_eq!(listed, vec!["jobs/a/log.txt", "jobs/a/out"]);
+
+        store.delete("jobs/a/log.txt").await.unwrap();
+        assert!(matches!(
+            store.get("jobs/a/log.txt").await,
+            Err(StorageError::NotFound(_))
+        ));
+        assert!(matches!(
+            store.size("jobs/a/log.txt").await,
+            Err(StorageError::NotFound(_))
+        ));
+
+        // Writing a file drops what was appended to its earlier version
+        store.append("jobs/a/out/data.csv", b"y").await.unwrap();
+        assert_eq!(store.get("jobs/a/out/data.csv").await.unwrap(), b"xy");
+        assert_eq!(store.size("jobs/a/out/data.csv").await.unwrap(), 2);
+        store.put("jobs/a/out/data.csv", b"z").await.unwrap();
+        store.append("jobs/a/out/data.csv", b"!").await.unwrap();
+        assert_eq!(store.get("jobs/a/out/data.csv").await.unwrap(), b"z!");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_bucket_appends_are_all_kept() {
+        let store = Arc::new(FileStore::Bucket {
+            name: "test".to_string(),
+            client: Arc::new(InMemory::new()),
+        });
+
+        let appends = (0..20).map(|_| {
+            let store = Arc::clone(&store);
+            tokio::spawn(async move { store.append("jobs/a/log.txt", b"ab").await })
+        });
+        for append in futures::future::join_all(appends).await {
+            append.unwrap().unwrap();
+        }
+        assert_eq!(store.get("jobs/a/log.txt").await.unwrap(), b"ab".repeat(20));
+    }
+}
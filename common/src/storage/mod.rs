@@ -1,8 +1,10 @@
 // Storage module for PostgreSQL + Redis + Filesystem
 // Requirements: 13.2, 13.3, 13.7 - Storage for job definitions and execution context
 
+mod file_store;
 pub mod postgres_storage;
 pub mod redis_client;
+pub mod residency;
 
 pub use postgres_storage::{StorageService, StorageServiceImpl};
 pub use redis_client::RedisClient;
@@ -2,15 +2,20 @@
 // Requirements: 13.2, 13.3, 13.7 - Store and load job definitions and execution context
 // RECC 2025: No unwrap(), use #[tracing::instrument], proper error handling
 
+use super::file_store::FileStore;
+use crate::config::DataResidency;
+use crate::db::RedisConnection;
 use crate::errors::StorageError;
 use crate::models::JobContext;
 use async_trait::async_trait;
 use redis::AsyncCommands;
 use serde_json;
 use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::fs;
+use std::time::Instant;
+use tokio::sync::RwLock;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
@@ -40,28 +45,52 @@ pub trait StorageService: Send + Sync {
     /// Store file to filesystem
     async fn store_file(&self, path: &str, data: &[u8]) -> Result<String, StorageError>;
 
+    /// Append to a file in the filesystem, creating it if missing
+    async fn append_file(&self, path: &str, data: &[u8]) -> Result<(), StorageError>;
+
     /// Load file from filesystem
     async fn load_file(&self, path: &str) -> Result<Vec<u8>, StorageError>;
 
+    /// Size of a file in bytes; stores that can't tell without reading it load it
+    async fn file_size(&self, path: &str) -> Result<u64, StorageError> {
+        Ok(self.load_file(path).await?.len() as u64)
+    }
+
     /// Delete file from filesystem
     async fn delete_file(&self, path: &str) -> Result<(), StorageError>;
 
     /// List files in filesystem with prefix
     async fn list_files(&self, prefix: &str) -> Result<Vec<String>, StorageError>;
+
+    /// Tenant of a job whose data must stay in the tenant's own store, if any
+    async fn resident_tenant(&self, _job_id: Uuid) -> Result<Option<String>, StorageError> {
+        Ok(None)
+    }
 }
 
 /// Storage service implementation using PostgreSQL + Redis cache + Filesystem
 #[derive(Clone)]
 pub struct StorageServiceImpl {
     db_pool: PgPool,
-    redis: Arc<redis::aio::ConnectionManager>,
-    file_base_path: PathBuf,
+    redis: Arc<RedisConnection>,
+    files: Arc<FileStore>,
+    /// Stores of tenants with data residency rules
+    residency: Arc<BTreeMap<String, FileStore>>,
+    /// Tenant of each job seen, if it has residency rules, and when it was looked up;
+    /// a job's tenant never changes
+    job_tenants: Arc<RwLock<HashMap<Uuid, (Option<String>, Instant)>>>,
 }
 
 // Redis key prefixes
 const REDIS_JOB_DEF_PREFIX: &str = "storage:job_def:";
 const REDIS_JOB_CTX_PREFIX: &str = "storage:job_ctx:";
 
+/// File holding a resident tenant's context in the execution's directory
+const RESIDENT_CONTEXT_FILE: &str = "context.json";
+
+/// Jobs whose tenant is cached; the oldest lookup is dropped past this
+const MAX_CACHED_JOB_TENANTS: usize = 10_000;
+
 // Redis TTL
 const JOB_DEF_TTL: i64 = 7 * 24 * 60 * 60; // 7 days
 const JOB_CTX_TTL: i64 = 30 * 24 * 60 * 60; // 30 days
@@ -69,7 +98,7 @@ const JOB_CTX_TTL: i64 = 30 * 24 * 60 * 60; // 30 days
 impl StorageServiceImpl {
     pub fn new(
         db_pool: PgPool,
-        redis: Arc<redis::aio::ConnectionManager>,
+        redis: Arc<RedisConnection>,
         file_base_path: Option<PathBuf>,
     ) -> Self {
         let file_base_path = file_base_path.unwrap_or_else(|| PathBuf::from("./data/files"));
@@ -80,10 +109,32 @@ impl StorageServiceImpl {
         Self {
             db_pool,
             redis,
-            file_base_path,
+            files: Arc::new(FileStore::Filesystem(file_base_path)),
+            residency: Arc::new(BTreeMap::new()),
+            job_tenants: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Keep the execution contexts and files of the given tenants in their own stores
+    pub fn with_residency(
+        mut self,
+        residency: &BTreeMap<String, DataResidency>,
+    ) -> Result<Self, StorageError> {
+        let mut stores = BTreeMap::new();
+        for (tenant, location) in residency {
+            let store = match location {
+                DataResidency::Filesystem { base_path } => {
+                    FileStore::Filesystem(PathBuf::from(base_path))
+                }
+                DataResidency::S3(bucket) => FileStore::bucket(bucket)?,
+            };
+            info!(tenant_id = %tenant, location = %store.describe(), "Data residency configured");
+            stores.insert(tenant.clone(), store);
+        }
+        self.residency = Arc::new(stores);
+        Ok(self)
+    }
+
     fn redis_job_def_key(job_id: Uuid) -> String {
         format!("{}{}", REDIS_JOB_DEF_PREFIX, job_id)
     }
@@ -92,8 +143,128 @@ impl StorageServiceImpl {
         format!("{}{}:{}", REDIS_JOB_CTX_PREFIX, job_id, execution_id)
     }
 
-    fn get_file_path(&self, path: &str) -> PathBuf {
-        self.file_base_path.join(path)
+    fn resident_context_path(job_id: Uuid, execution_id: Uuid) -> String {
+        format!(
+            "jobs/{}/executions/{}/{}",
+            job_id, execution_id, RESIDENT_CONTEXT_FILE
+        )
+    }
+
+    /// Tenant of a job if its data must stay in the tenant's own store
+    async fn lookup_resident_tenant(&self, job_id: Uuid) -> Result<Option<String>, StorageError> {
+        if self.residency.is_empty() {
+            return Ok(None);
+        }
+        if let Some((tenant, _)) = self.job_tenants.read().await.get(&job_id) {
+            return Ok(tenant.clone());
+        }
+
+        let tenant: Option<String> = sqlx::query_scalar("SELECT tenant_id FROM jobs WHERE id = $1")
+            .bind(job_id)
+            .fetch_optional(&self.db_pool)
+            .await
+            .map_err(|e| {
+                error!(error = %e, job_id = %job_id, "Failed to look up job tenant");
+                StorageError::DatabaseError(e.to_string())
+            })?
+            .flatten();
+        let tenant = tenant.filter(|tenant| self.residency.contains_key(tenant));
+
+        let mut job_tenants = self.job_tenants.write().await;
+        if job_tenants.len() >= MAX_CACHED_JOB_TENANTS && !job_tenants.contains_key(&job_id) {
+            let oldest = job_tenants
+                .iter()
+                .min_by_key(|(_, (_, looked_up))| *looked_up)
+                .map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                job_tenants.remove(&oldest);
+            }
+        }
+        job_tenants.insert(job_id, (tenant.clone(), Instant::now()));
+        Ok(tenant)
+    }
+
+    /// Store of a file; files under `jobs/<job_id>/` follow the job's tenant
+    async fn file_store(&self, path: &str) -> Result<&FileStore, StorageError> {
+        let job_id = path
+            .trim_start_matches('/')
+            .strip_prefix("jobs/")
+            .and_then(|rest| rest.split('/').next())
+            .and_then(|id| id.parse::<Uuid>().ok());
+
+        if let Some(job_id) = job_id {
+            if let Some(tenant) = self.lookup_resident_tenant(job_id).await? {
+                if let Some(store) = self.residency.get(&tenant) {
+                    return Ok(store);
+                }
+            }
+        }
+        Ok(&self.files)
+    }
+
+    /// Context stored in PostgreSQL
+    async fn query_context(&self, execution_id: Uuid) -> Result<JobContext, StorageError> {
+        let row = sqlx::query!(
+            "SELECT context FROM job_executions WHERE id = $1",
+            execution_id
+        )
+        .fetch_optional(&self.db_pool)
+        .await
+        .map_err(|e| StorageError::DatabaseError(e.to_string()))?
+        .ok_or_else(|| StorageError::NotFound(format!("Execution {} not found", execution_id)))?;
+
+        let context_value = row.context.ok_or_else(|| {
+            StorageError::NotFound(format!("Context for execution {} not found", execution_id))
+        })?;
+        serde_json::from_value(context_value).map_err(|e| StorageError::InvalidJson(e.to_string()))
+    }
+
+    /// Write a resident tenant's context to its store; PostgreSQL only records where
+    /// it is and Redis doesn't cache it
+    async fn store_resident_context(
+        &self,
+        tenant: &str,
+        context: &JobContext,
+    ) -> Result<(), StorageError> {
+        let data =
+            serde_json::to_vec(context).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        let path = Self::resident_context_path(context.job_id, context.execution_id);
+        self.file_store(&path).await?.put(&path, &data).await?;
+
+        sqlx::query("UPDATE job_executions SET context = $1 WHERE id = $2")
+            .bind(super::residency::pointer(tenant, &path))
+            .bind(context.execution_id)
+            .execute(&self.db_pool)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to record resident context location");
+                StorageError::DatabaseError(e.to_string())
+            })?;
+
+        info!(
+            job_id = %context.job_id,
+            execution_id = %context.execution_id,
+            tenant_id = %tenant,
+            "Context stored in tenant storage"
+        );
+        Ok(())
+    }
+
+    /// Load a resident tenant's context; contexts stored before the tenant's
+    /// residency was configured are still read from PostgreSQL
+    async fn load_resident_context(
+        &self,
+        job_id: Uuid,
+        execution_id: Uuid,
+    ) -> Result<JobContext, StorageError> {
+        let path = Self::resident_context_path(job_id, execution_id);
+        match self.file_store(&path).await?.get(&path).await {
+            Ok(data) => {
+                serde_json::from_slice(&data).map_err(|e| StorageError::InvalidJson(e.to_string()))
+            }
+            Err(StorageError::NotFound(_)) => self.query_context(execution_id).await,
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -198,6 +369,10 @@ impl StorageService for StorageServiceImpl {
     async fn store_context(&self, context: &JobContext) -> Result<(), StorageError> {
         debug!(job_id = %context.job_id, execution_id = %context.execution_id, "Storing context");
 
+        if let Some(tenant) = self.lookup_resident_tenant(context.job_id).await? {
+            return self.store_resident_context(&tenant, context).await;
+        }
+
         let json_value = serde_json::to_value(context).map_err(|e| {
             error!(error = %e, "Failed to serialize context");
             StorageError::InvalidJson(e.to_string())
@@ -241,6 +416,10 @@ impl StorageService for StorageServiceImpl {
     ) -> Result<JobContext, StorageError> {
         debug!(job_id = %job_id, execution_id = %execution_id, "Loading context");
 
+        if self.lookup_resident_tenant(job_id).await?.is_some() {
+            return self.load_resident_context(job_id, execution_id).await;
+        }
+
         let redis_key = Self::redis_job_ctx_key(job_id, execution_id);
 
         // Try Redis first
@@ -257,20 +436,7 @@ impl StorageService for StorageServiceImpl {
         }
 
         // Query PostgreSQL
-        let row = sqlx::query!(
-            "SELECT context FROM job_executions WHERE id = $1",
-            execution_id
-        )
-        .fetch_optional(&self.db_pool)
-        .await
-        .map_err(|e| StorageError::DatabaseError(e.to_string()))?
-        .ok_or_else(|| StorageError::NotFound(format!("Execution {} not found", execution_id)))?;
-
-        let context_value = row.context.ok_or_else(|| {
-            StorageError::NotFound(format!("Context for execution {} not found", execution_id))
-        })?;
-        let context: JobContext = serde_json::from_value(context_value)
-            .map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        let context = self.query_context(execution_id).await?;
 
         // Cache in Redis
         let json_str = serde_json::to_string(&context)
@@ -289,74 +455,37 @@ impl StorageService for StorageServiceImpl {
 
     #[instrument(skip(self, data), fields(path = %path, size = data.len()))]
     async fn store_file(&self, path: &str, data: &[u8]) -> Result<String, StorageError> {
-        let file_path = self.get_file_path(path);
-
-        // Create parent directories
-        if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent).await.map_err(|e| {
-                error!(error = %e, path = %path, "Failed to create directories");
-                StorageError::FileSystemError(e.to_string())
-            })?;
-        }
-
-        // Write file
-        fs::write(&file_path, data).await.map_err(|e| {
-            error!(error = %e, path = %path, "Failed to write file");
-            StorageError::FileSystemError(e.to_string())
-        })?;
-
-        info!(path = %path, size = data.len(), "File stored");
+        self.file_store(path).await?.put(path, data).await?;
         Ok(path.to_string())
     }
 
+    #[instrument(skip(self, data), fields(path = %path, size = data.len()))]
+    async fn append_file(&self, path: &str, data: &[u8]) -> Result<(), StorageError> {
+        self.file_store(path).await?.append(path, data).await
+    }
+
     #[instrument(skip(self), fields(path = %path))]
     async fn load_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
-        let file_path = self.get_file_path(path);
-
-        let data = fs::read(&file_path).await.map_err(|e| {
-            error!(error = %e, path = %path, "Failed to read file");
-            StorageError::FileSystemError(e.to_string())
-        })?;
+        self.file_store(path).await?.get(path).await
+    }
 
-        debug!(path = %path, size = data.len(), "File loaded");
-        Ok(data)
+    #[instrument(skip(self), fields(path = %path))]
+    async fn file_size(&self, path: &str) -> Result<u64, StorageError> {
+        self.file_store(path).await?.size(path).await
     }
 
     #[instrument(skip(self), fields(path = %path))]
     async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
-        let file_path = self.get_file_path(path);
-
-        fs::remove_file(&file_path).await.map_err(|e| {
-            error!(error = %e, path = %path, "Failed to delete file");
-            StorageError::FileSystemError(e.to_string())
-        })?;
-
-        info!(path = %path, "File deleted");
-        Ok(())
+        self.file_store(path).await?.delete(path).await
     }
 
     #[instrument(skip(self), fields(prefix = %prefix))]
     async fn list_files(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
-        let dir_path = self.get_file_path(prefix);
-
-        let mut files = Vec::new();
-        let mut entries = fs::read_dir(&dir_path).await.map_err(|e| {
-            error!(error = %e, prefix = %prefix, "Failed to read directory");
-            StorageError::FileSystemError(e.to_string())
-        })?;
-
-        while let Some(entry) = entries
-            .next_entry()
-            .await
-            .map_err(|e| StorageError::FileSystemError(e.to_string()))?
-        {
-            if let Ok(file_name) = entry.file_name().into_string() {
-                files.push(format!("{}/{}", prefix, file_name));
-            }
-        }
+        self.file_store(prefix).await?.list(prefix).await
+    }
 
-        debug!(prefix = %prefix, count = files.len(), "Files listed");
-        Ok(files)
+    async fn resident_tenant(&self, job_id: Uuid) -> Result<Option<String>, StorageError> {
+        self.lookup_resident_tenant(job_id).await
     }
 }
 
@@ -391,10 +520,18 @@ impl StorageService for Arc<dyn StorageService> {
         (**self).store_file(path, data).await
     }
 
+    async fn append_file(&self, path: &str, data: &[u8]) -> Result<(), StorageError> {
+        (**self).append_file(path, data).await
+    }
+
     async fn load_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
         (**self).load_file(path).await
     }
 
+    async fn file_size(&self, path: &str) -> Result<u64, StorageError> {
+        (**self).file_size(path).await
+    }
+
     async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
         (**self).delete_file(path).await
     }
@@ -402,4 +539,8 @@ impl StorageService for Arc<dyn StorageService> {
     async fn list_files(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
         (**self).list_files(prefix).await
     }
+
+    async fn resident_tenant(&self, job_id: Uuid) -> Result<Option<String>, StorageError> {
+        (**self).resident_tenant(job_id).await
+    }
 }
@@ -2,42 +2,38 @@
 // RECC 2025: No unwrap(), use #[tracing::instrument], proper error handling
 
 use crate::config::RedisConfig;
+use crate::db::RedisConnection;
 use crate::errors::StorageError;
-use redis::aio::ConnectionManager;
-use redis::Client;
 use std::sync::Arc;
 use tracing::{info, instrument};
 
 /// Redis client wrapper with connection pooling
 #[derive(Clone)]
 pub struct RedisClient {
-    manager: Arc<ConnectionManager>,
+    connection: Arc<RedisConnection>,
 }
 
 impl RedisClient {
-    /// Create a new Redis client with connection manager
-    /// Connection manager provides automatic reconnection and connection pooling
+    /// Create a new Redis client for the configured topology
+    /// Every topology reconnects automatically; Sentinel and Cluster also follow failovers
     #[instrument(skip(config))]
     pub async fn new(config: &RedisConfig) -> Result<Self, StorageError> {
         info!(url = %config.url, "Connecting to Redis");
 
-        let client = Client::open(config.url.as_str()).map_err(|e| {
-            StorageError::RedisError(format!("Failed to create Redis client: {}", e))
-        })?;
+        let connection = RedisConnection::connect(config).await?;
 
-        let manager = ConnectionManager::new(client)
-            .await
-            .map_err(|e| StorageError::RedisError(format!("Failed to connect to Redis: {}", e)))?;
-
-        info!("Redis connection established");
+        info!(
+            topology = connection.topology(),
+            "Redis connection established"
+        );
         Ok(Self {
-            manager: Arc::new(manager),
+            connection: Arc::new(connection),
         })
     }
 
     /// Get a connection from the pool
-    pub fn get_connection(&self) -> ConnectionManager {
-        (*self.manager).clone()
+    pub fn get_connection(&self) -> RedisConnection {
+        (*self.connection).clone()
     }
 
     /// Health check - ping Redis
@@ -64,6 +60,8 @@ mod tests {
         let config = RedisConfig {
             url: "redis://localhost:6379".to_string(),
             pool_size: 10,
+            sentinel: None,
+            cluster: None,
         };
         assert!(!config.url.is_empty());
     }
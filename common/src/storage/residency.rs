@@ -0,0 +1,269 @@
+// Resident values: JSON kept in a tenant's own store, with a pointer in PostgreSQL
+// Purpose: Keep the captured webhook requests and job state of tenants with data
+// residency rules out of the shared database, as their contexts and files are
+//
+// Values stored before a tenant's residency was configured stay in PostgreSQL and
+// are read from there.
+
+use super::StorageService;
+use crate::db::repositories::{JobStateRepository, WebhookPayloadRepository};
+use crate::errors::{DatabaseError, StorageError};
+use crate::models::{CapturedWebhookPayload, JobStateEntry, WebhookData};
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Pointer stored in PostgreSQL in place of a value kept in a tenant's store
+pub fn pointer(tenant: &str, path: &str) -> Value {
+    serde_json::json!({ "residency": tenant, "path": path })
+}
+
+/// Path of the value a stored value points to, if it is a pointer to a file of the job
+///
+/// Pointers naming another job's files are not followed.
+pub fn pointer_path(value: &Value, job_id: Uuid) -> Option<&str> {
+    let object = value.as_object()?;
+    if object.len() != 2 || !object.contains_key("residency") {
+        return None;
+    }
+    object
+        .get("path")?
+        .as_str()
+        .filter(|path| path.starts_with(&format!("jobs/{}/", job_id)))
+}
+
+/// Storage path of a captured webhook request of a resident tenant
+pub fn webhook_payload_path(job_id: Uuid, payload_id: Uuid) -> String {
+    format!("jobs/{}/webhook_payloads/{}.json", job_id, payload_id)
+}
+
+/// Storage path of one version of a job state value of a resident tenant
+///
+/// Every write goes to a new path, so a state write that fails in PostgreSQL leaves
+/// the values it points to as they were.
+pub fn state_path(job_id: Uuid, key: &str, version: Uuid) -> String {
+    format!("jobs/{}/state/{}/{}.json", job_id, key, version)
+}
+
+/// Keep `value` in the tenant's store if the job's tenant has residency rules,
+/// returning what PostgreSQL should hold: the value itself or a pointer to it
+pub async fn store_value(
+    storage: &dyn StorageService,
+    job_id: Uuid,
+    path: &str,
+    value: &Value,
+) -> Result<Value, StorageError> {
+    let Some(tenant) = storage.resident_tenant(job_id).await? else {
+        return Ok(value.clone());
+    };
+    let data = serde_json::to_vec(value).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+    storage.store_file(path, &data).await?;
+    Ok(pointer(&tenant, path))
+}
+
+/// Value a PostgreSQL value of the job stands for, loaded from the tenant's store
+/// when it is a pointer
+pub async fn load_value(
+    storage: &dyn StorageService,
+    job_id: Uuid,
+    value: Value,
+) -> Result<Value, StorageError> {
+    let Some(path) = pointer_path(&value, job_id) else {
+        return Ok(value);
+    };
+    let data = storage.load_file(path).await?;
+    serde_json::from_slice(&data).map_err(|e| StorageError::InvalidJson(e.to_string()))
+}
+
+/// Delete the tenant's copy of a value PostgreSQL no longer holds
+pub async fn delete_value(
+    storage: &dyn StorageService,
+    job_id: Uuid,
+    value: &Value,
+) -> Result<(), StorageError> {
+    match pointer_path(value, job_id) {
+        Some(path) => match storage.delete_file(path).await {
+            Ok(()) | Err(StorageError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        },
+        None => Ok(()),
+    }
+}
+
+fn database_error(e: DatabaseError) -> StorageError {
+    StorageError::DatabaseError(e.to_string())
+}
+
+/// Delete files written for a write that didn't happen or values no longer held,
+/// logging failures; the files are unreachable either way
+async fn delete_values(storage: &dyn StorageService, job_id: Uuid, values: &[Value]) {
+    for value in values {
+        if let Err(e) = delete_value(storage, job_id, value).await {
+            warn!(error = %e, job_id = %job_id, "Failed to delete resident value");
+        }
+    }
+}
+
+/// Every value of the job's state, ordered by key, with resident values loaded
+pub async fn load_state(
+    storage: &dyn StorageService,
+    repo: &JobStateRepository,
+    job_id: Uuid,
+) -> Result<Vec<JobStateEntry>, StorageError> {
+    let mut entries = repo.find_by_job(job_id).await.map_err(database_error)?;
+    for entry in &mut entries {
+        entry.value = load_value(storage, job_id, std::mem::take(&mut entry.value)).await?;
+    }
+    Ok(entries)
+}
+
+/// Write keys of the job's state, all or none, keeping the values in the tenant's
+/// store if the job's tenant has residency rules
+///
+/// `execution_id` is the execution whose step wrote them, if any.
+pub async fn save_state(
+    storage: &dyn StorageService,
+    repo: &JobStateRepository,
+    job_id: Uuid,
+    values: &HashMap<String, Value>,
+    execution_id: Option<Uuid>,
+) -> Result<(), StorageError> {
+    if storage.resident_tenant(job_id).await?.is_none() {
+        return repo
+            .upsert_many(job_id, values, execution_id)
+            .await
+            .map_err(database_error);
+    }
+
+    let previous: Vec<Value> = repo
+        .find_by_job(job_id)
+        .await
+        .map_err(database_error)?
+        .into_iter()
+        .filter(|entry| values.contains_key(&entry.key))
+        .map(|entry| entry.value)
+        .collect();
+
+    let mut pointers = HashMap::with_capacity(values.len());
+    for (key, value) in values {
+        let path = state_path(job_id, key, Uuid::new_v4());
+        match store_value(storage, job_id, &path, value).await {
+            Ok(pointer) => {
+                pointers.insert(key.clone(), pointer);
+            }
+            Err(e) => {
+                delete_values(storage, job_id, &pointers.into_values().collect::<Vec<_>>()).await;
+                return Err(e);
+            }
+        }
+    }
+
+    if let Err(e) = repo.upsert_many(job_id, &pointers, execution_id).await {
+        delete_values(storage, job_id, &pointers.into_values().collect::<Vec<_>>()).await;
+        return Err(database_error(e));
+    }
+    delete_values(storage, job_id, &previous).await;
+    Ok(())
+}
+
+/// Delete a key of the job's state and its resident value, returning whether it
+/// was set
+pub async fn delete_state(
+    storage: &dyn StorageService,
+    repo: &JobStateRepository,
+    job_id: Uuid,
+    key: &str,
+) -> Result<bool, StorageError> {
+    match repo.delete(job_id, key).await.map_err(database_error)? {
+        Some(value) => {
+            delete_values(storage, job_id, &[value]).await;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Record a captured webhook request, keeping it in the tenant's store if the job's
+/// tenant has residency rules; PostgreSQL then holds a pointer as its payload
+pub async fn record_webhook_payload(
+    storage: &dyn StorageService,
+    repo: &WebhookPayloadRepository,
+    payload: &CapturedWebhookPayload,
+) -> Result<(), StorageError> {
+    let data = serde_json::to_value(&payload.data)
+        .map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+    let path = webhook_payload_path(payload.job_id, payload.id);
+    let stored = store_value(storage, payload.job_id, &path, &data).await?;
+
+    let mut record = payload.clone();
+    if stored != data {
+        record.data = WebhookData {
+            payload: stored.clone(),
+            query_params: HashMap::new(),
+            headers: HashMap::new(),
+        };
+    }
+    let pruned = match repo.record(&record).await {
+        Ok(pruned) => pruned,
+        Err(e) => {
+            delete_values(storage, payload.job_id, &[stored]).await;
+            return Err(database_error(e));
+        }
+    };
+    for pruned in pruned.iter().filter(|pruned| is_resident_payload(pruned)) {
+        delete_values(storage, pruned.job_id, &[pruned.data.payload.clone()]).await;
+    }
+    Ok(())
+}
+
+/// Whether PostgreSQL only holds a pointer to the captured request
+fn is_resident_payload(payload: &CapturedWebhookPayload) -> bool {
+    payload.data.query_params.is_empty()
+        && payload.data.headers.is_empty()
+        && pointer_path(&payload.data.payload, payload.job_id).is_some()
+}
+
+/// A captured webhook request with its resident data loaded
+pub async fn load_webhook_payload(
+    storage: &dyn StorageService,
+    mut payload: CapturedWebhookPayload,
+) -> Result<CapturedWebhookPayload, StorageError> {
+    if is_resident_payload(&payload) {
+        let data = load_value(
+            storage,
+            payload.job_id,
+            std::mem::take(&mut payload.data.payload),
+        )
+        .await?;
+        payload.data =
+            serde_json::from_value(data).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_pointers_to_the_jobs_files_are_followed() {
+        let job_id = Uuid::new_v4();
+        let path = state_path(job_id, "cursor", Uuid::new_v4());
+
+        assert_eq!(
+            pointer_path(&pointer("acme", &path), job_id),
+            Some(path.as_str())
+        );
+        // Values that merely look like pointers
+        assert_eq!(pointer_path(&pointer("acme", &path), Uuid::new_v4()), None);
+        assert_eq!(
+            pointer_path(
+                &serde_json::json!({"residency": "acme", "path": path, "n": 1}),
+                job_id
+            ),
+            None
+        );
+        assert_eq!(pointer_path(&serde_json::json!("cursor"), job_id), None);
+    }
+}
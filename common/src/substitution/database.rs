@@ -86,6 +86,13 @@ pub fn substitute_database_job(
                     }
                 }
                 QueryType::RawSql => QueryType::RawSql,
+                QueryType::Mongo {
+                    collection,
+                    operation,
+                } => QueryType::Mongo {
+                    collection: substitutor.substitute(collection, variables)?,
+                    operation: *operation,
+                },
             };
 
             Ok(JobType::DatabaseQuery {
@@ -0,0 +1,390 @@
+// Built-in system jobs
+// Purpose: Register the self-monitoring jobs on first boot and alert when the canary
+// job stops succeeding, which means scheduling, queueing or execution is broken
+
+use crate::config::SystemJobsConfig;
+use crate::db::repositories::system_job::{SystemJobRepository, SystemJobStatus};
+use crate::failover::RegionRole;
+use crate::models::{Job, JobPriority, JobStep, JobType, Schedule, SystemTask, TriggerConfig};
+use crate::schedule::default_timezone;
+use crate::telemetry::AlertNotifier;
+use chrono::{DateTime, Utc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+/// Registration key of the canary job
+pub const CANARY_KEY: &str = "canary";
+
+/// Registration key of the connection verification job, which the API triggers when
+/// a connection profile changes
+pub const CONNECTION_VERIFICATION_KEY: &str = "connection_verification";
+
+/// A built-in job and the key it is registered under
+#[derive(Debug, Clone)]
+pub struct SystemJob {
+    pub key: &'static str,
+    pub job: Job,
+}
+
+/// The built-in jobs, seeded from the config
+pub fn builtin_jobs(config: &SystemJobsConfig) -> Vec<SystemJob> {
+    let daily_at = |hour: u32| Schedule::Cron {
+        expression: format!("0 0 {} * * * *", hour),
+        timezone: default_timezone(),
+        end_date: None,
+        valid_for_seconds: None,
+        calendar: None,
+    };
+
+    vec![
+        SystemJob {
+            key: "dlq_report",
+            job: system_job(
+                "system.dlq-report",
+                "Daily report of dead-lettered executions per job",
+                daily_at(8),
+                SystemTask::DlqReport,
+                300,
+            ),
+        },
+        SystemJob {
+            key: "retention_cleanup",
+            job: system_job(
+                "system.retention-cleanup",
+                "Delete finished executions and delivered webhooks past the retention period",
+                daily_at(2),
+                SystemTask::RetentionCleanup {
+                    retention_days: config.retention_days,
+                },
+                3600,
+            ),
+        },
+        SystemJob {
+            key: "pii_purge",
+            job: system_job(
+                "system.pii-purge",
+                "Scrub or encrypt PII-tagged step outputs past the PII retention period",
+                daily_at(1),
+                SystemTask::PiiPurge {
+                    retention_days: config.pii_retention_days,
+                    recipients: config.pii_recipients.clone(),
+                },
+                3600,
+            ),
+        },
+        SystemJob {
+            key: "storage_gc",
+            job: system_job(
+                "system.storage-gc",
+                "Remove stored files of deleted jobs and executions",
+                daily_at(3),
+                SystemTask::StorageGc,
+                3600,
+            ),
+        },
+        SystemJob {
+            key: "expiry_check",
+            job: system_job(
+                "system.expiry-check",
+                "Warn when certificates, keys or secrets are about to expire",
+                daily_at(7),
+                SystemTask::ExpiryCheck {
+                    certificate_paths: config.certificate_paths.clone(),
+                    warn_days: config.expiry_warn_days,
+                    secret_max_age_days: config.secret_max_age_days,
+                },
+                300,
+            ),
+        },
+        SystemJob {
+            key: CANARY_KEY,
+            job: system_job(
+                "system.canary",
+                "Synthetic job; failing or silent means the pipeline itself is broken",
+                Schedule::FixedRate {
+                    interval_seconds: config.canary_interval_seconds,
+                    misfire_policy: None,
+                },
+                SystemTask::Canary,
+                30,
+            ),
+        },
+        SystemJob {
+            key: CONNECTION_VERIFICATION_KEY,
+            job: system_job(
+                "system.connection-verification",
+                "Probe the jobs using a changed connection profile and report which would break",
+                // Catches probes whose immediate trigger was lost
+                Schedule::Cron {
+                    expression: "0 30 * * * * *".to_string(),
+                    timezone: default_timezone(),
+                    end_date: None,
+                    valid_for_seconds: None,
+                    calendar: None,
+                },
+                SystemTask::ConnectionVerification,
+                1800,
+            ),
+        },
+    ]
+}
+
+/// A scheduled, manually triggerable job with a single system step
+fn system_job(
+    name: &str,
+    description: &str,
+    schedule: Schedule,
+    task: SystemTask,
+    timeout_seconds: i32,
+) -> Job {
+    let id = Uuid::new_v4();
+    let now = Utc::now();
+    let steps = vec![JobStep {
+        id: "run".to_string(),
+        name: description.to_string(),
+        step_type: JobType::System { task },
+        condition: None,
+        on_failure: None,
+        compensate_with: Vec::new(),
+        acquire_lock: None,
+        set_state: Default::default(),
+        env: Default::default(),
+        timeout_seconds: None,
+        retry_count: None,
+        stream_output: false,
+        pii: false,
+        dependency: None,
+        connection: None,
+    }];
+    let triggers = TriggerConfig {
+        scheduled: true,
+        manual: true,
+        webhook: None,
+    };
+
+    let definition = serde_json::json!({
+        "id": id,
+        "name": name,
+        "description": description,
+        "schedule": schedule,
+        "steps": steps,
+        "triggers": triggers,
+        "enabled": true,
+        "timeout_seconds": timeout_seconds,
+        "max_retries": 0,
+        "allow_concurrent": false,
+        "collision_policy": "skip",
+    });
+
+    Job {
+        id,
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        schedule: Some(schedule),
+        steps,
+        triggers,
+        enabled: true,
+        paused: false,
+        timeout_seconds,
+        max_retries: 0,
+        allow_concurrent: false,
+        collision_policy: Default::default(),
+        strict_ordering: false,
+        priority: JobPriority::Normal,
+        dead_letter_policy: None,
+        concurrency_limit: None,
+        owner: None,
+        metric_labels: Default::default(),
+        parameter_matrix: Default::default(),
+        definition: Some(definition),
+        version: 1,
+        deprecated: false,
+        sunset_at: None,
+        deprecation_reason: None,
+        tenant_id: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+/// Create the built-in jobs that were never registered
+///
+/// A job that was registered before is left alone even if it was edited or deleted
+/// since. A job that fails to register (e.g. its name is taken) is logged and
+/// skipped. Returns the number of jobs created.
+pub async fn register_system_jobs(repo: &SystemJobRepository, config: &SystemJobsConfig) -> usize {
+    let mut registered = 0;
+    for system_job in builtin_jobs(config) {
+        match repo.register(system_job.key, &system_job.job).await {
+            Ok(true) => {
+                info!(key = system_job.key, job_name = %system_job.job.name, "Registered system job");
+                registered += 1;
+            }
+            Ok(false) => {}
+            Err(e) => {
+                warn!(key = system_job.key, job_name = %system_job.job.name, error = %e, "Failed to register system job")
+            }
+        }
+    }
+    registered
+}
+
+/// Whether the canary has gone without a success for longer than `max_silence`
+///
+/// Silence is counted from the last success, or from the last edit of the job when
+/// that is later, so re-enabling a disabled canary doesn't alert at once.
+pub fn canary_silent(
+    status: &SystemJobStatus,
+    now: DateTime<Utc>,
+    max_silence: chrono::Duration,
+) -> bool {
+    let since = status
+        .last_success_at
+        .map_or(status.updated_at, |success| success.max(status.updated_at));
+    status.enabled && now - since > max_silence
+}
+
+/// Alerts when the canary job stops succeeding
+///
+/// A broken pipeline may not run the canary at all, so its own failure alerts aren't
+/// enough; the scheduler also watches for the canary going silent.
+pub struct CanaryWatch {
+    repo: SystemJobRepository,
+    alert_notifier: Arc<dyn AlertNotifier>,
+    check_interval: Duration,
+    max_silence: chrono::Duration,
+    region_role: Option<watch::Receiver<RegionRole>>,
+    healthy: AtomicBool,
+}
+
+impl CanaryWatch {
+    pub fn new(
+        repo: SystemJobRepository,
+        alert_notifier: Arc<dyn AlertNotifier>,
+        config: &SystemJobsConfig,
+    ) -> Self {
+        Self {
+            repo,
+            alert_notifier,
+            check_interval: Duration::from_secs(config.canary_interval_seconds as u64),
+            max_silence: chrono::Duration::seconds(config.canary_max_silence_seconds as i64),
+            region_role: None,
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    /// Only watch while the region is active; a standby region runs no jobs
+    pub fn with_region_role(mut self, region_role: watch::Receiver<RegionRole>) -> Self {
+        self.region_role = Some(region_role);
+        self
+    }
+
+    /// Check the canary once, alerting when it first goes silent
+    pub async fn check(&self) {
+        let active = self
+            .region_role
+            .as_ref()
+            .is_none_or(|role| *role.borrow() == RegionRole::Active);
+        if !active {
+            return;
+        }
+
+        let status = match self.repo.status(CANARY_KEY).await {
+            Ok(Some(status)) => status,
+            Ok(None) => return,
+            Err(e) => {
+                warn!(error = %e, "Failed to check the canary job");
+                return;
+            }
+        };
+
+        if !canary_silent(&status, Utc::now(), self.max_silence) {
+            if !self.healthy.swap(true, Ordering::Relaxed) {
+                info!(job_id = %status.job_id, "Canary job is succeeding again");
+            }
+            return;
+        }
+        if self.healthy.swap(false, Ordering::Relaxed) {
+            let missed_runs = (self.max_silence.num_seconds() as u64
+                / self.check_interval.as_secs().max(1)) as u32;
+            error!(
+                job_id = %status.job_id,
+                last_success_at = ?status.last_success_at,
+                "Canary job has not succeeded, the job pipeline may be broken"
+            );
+            if let Err(e) = self
+                .alert_notifier
+                .send_alert(&status.job_id, &status.job_name, missed_runs)
+                .await
+            {
+                error!(error = %e, "Failed to send canary alert");
+            }
+        }
+    }
+
+    /// Check the canary every canary interval in the background
+    pub fn spawn(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.check_interval);
+            loop {
+                interval.tick().await;
+                self.check().await;
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_builtin_jobs_definitions_load_as_jobs() {
+        let jobs = builtin_jobs(&SystemJobsConfig::default());
+
+        let keys: HashSet<_> = jobs.iter().map(|j| j.key).collect();
+        assert_eq!(keys.len(), jobs.len());
+        assert!(keys.contains(CANARY_KEY));
+
+        for system_job in &jobs {
+            assert!(system_job.job.name.starts_with("system."));
+            // The worker loads jobs from their stored definition
+            let definition = system_job.job.definition.clone().unwrap();
+            let loaded: Job = serde_json::from_value(definition).unwrap();
+            assert_eq!(loaded.id, system_job.job.id);
+            assert!(matches!(
+                loaded.steps.as_slice(),
+                [JobStep {
+                    step_type: JobType::System { .. },
+                    ..
+                }]
+            ));
+        }
+    }
+
+    #[test]
+    fn test_canary_silence_counts_from_latest_success_or_edit() {
+        let now = Utc::now();
+        let max_silence = chrono::Duration::minutes(5);
+        let status =
+            |enabled, last_success_minutes: Option<i64>, updated_minutes| SystemJobStatus {
+                job_id: Uuid::new_v4(),
+                job_name: "system.canary".to_string(),
+                enabled,
+                last_success_at: last_success_minutes.map(|m| now - chrono::Duration::minutes(m)),
+                updated_at: now - chrono::Duration::minutes(updated_minutes),
+            };
+
+        assert!(!canary_silent(&status(true, Some(1), 60), now, max_silence));
+        assert!(canary_silent(&status(true, Some(10), 60), now, max_silence));
+        assert!(canary_silent(&status(true, None, 60), now, max_silence));
+        // Recently re-enabled
+        assert!(!canary_silent(&status(true, Some(10), 2), now, max_silence));
+        assert!(!canary_silent(&status(false, None, 60), now, max_silence));
+    }
+}
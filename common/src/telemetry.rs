@@ -2,17 +2,29 @@
 // Telemetry module for structured logging, metrics, and tracing
 // Requirements: 5.1, 5.2, 5.3, 5.4, 5.5, 5.6, 5.7, 5.8, 5.9
 
+use crate::i18n::{self, Locale};
+use crate::models::{CredentialExpiry, FailureNote, JobOwner};
 use anyhow::Result;
-use metrics::{counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram};
-use metrics_exporter_prometheus::PrometheusBuilder;
-use opentelemetry::trace::TracerProvider as _;
+use chrono::{DateTime, Utc};
+use metrics::{
+    counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram, Label,
+};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{
     trace::{RandomIdGenerator, Sampler, TracerProvider},
     Resource,
 };
+use prometheus_client::metrics::exemplar::HistogramWithExemplars;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::registry::{Registry, Unit};
+use std::collections::{BTreeMap, HashSet};
 use std::net::SocketAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 use uuid::Uuid;
 
@@ -125,14 +137,96 @@ pub fn shutdown_tracer() {
     global::shutdown_tracer_provider();
 }
 
+/// Upper bounds of the `job_execution_duration_seconds` buckets
+const JOB_DURATION_BUCKETS: [f64; 11] = [
+    0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0, 3600.0,
+];
+
+/// Most job series `job_execution_duration_seconds` keeps; later series are only
+/// recorded in the `job_duration_seconds` summary
+const MAX_DURATION_HISTOGRAM_SERIES: usize = 10_000;
+
+/// Content type of an OpenMetrics exposition, the only format that carries exemplars
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+type SeriesLabels = Vec<(String, String)>;
+
+/// `job_execution_duration_seconds`: job durations as a histogram whose buckets carry
+/// the trace exemplar of their latest sample
+///
+/// The Prometheus recorder has no exemplars, so this histogram lives in its own
+/// registry and is only encoded on OpenMetrics scrapes. `job_duration_seconds` stays
+/// the summary existing dashboards query.
+struct DurationHistogram {
+    registry: Registry,
+    family: Family<SeriesLabels, HistogramWithExemplars<SeriesLabels>>,
+    series: Mutex<HashSet<SeriesLabels>>,
+}
+
+impl DurationHistogram {
+    fn global() -> &'static DurationHistogram {
+        static HISTOGRAM: OnceLock<DurationHistogram> = OnceLock::new();
+        HISTOGRAM.get_or_init(DurationHistogram::new)
+    }
+
+    fn new() -> Self {
+        let family = Family::new_with_constructor(new_duration_histogram as fn() -> _);
+        let mut registry = Registry::default();
+        registry.register_with_unit(
+            "job_execution_duration",
+            "Duration of job executions in seconds, with trace exemplars",
+            Unit::Seconds,
+            family.clone(),
+        );
+        DurationHistogram {
+            registry,
+            family,
+            series: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Record a sample, unless the series is new and the histogram is full
+    fn observe(&self, series: SeriesLabels, value: f64, exemplar: Option<SeriesLabels>) {
+        let Ok(mut known) = self.series.lock() else {
+            return;
+        };
+        if !known.contains(&series) {
+            if known.len() >= MAX_DURATION_HISTOGRAM_SERIES {
+                return;
+            }
+            known.insert(series.clone());
+        }
+        drop(known);
+        self.family.get_or_create(&series).observe(value, exemplar);
+    }
+
+    /// OpenMetrics exposition of the histogram, ending with `# EOF`
+    fn encode(&self) -> String {
+        let mut body = String::new();
+        if let Err(e) = prometheus_client::encoding::text::encode(&mut body, &self.registry) {
+            tracing::warn!(error = %e, "Failed to encode job duration histogram");
+            body.clear();
+            body.push_str("# EOF\n");
+        }
+        body
+    }
+}
+
+fn new_duration_histogram() -> HistogramWithExemplars<SeriesLabels> {
+    HistogramWithExemplars::new(JOB_DURATION_BUCKETS.into_iter())
+}
+
 /// Initialize Prometheus metrics exporter
 ///
 /// This function sets up the Prometheus metrics exporter and registers all metrics:
 /// - job_success_total: Counter for successful job executions
 /// - job_failed_total: Counter for failed job executions
-/// - job_duration_seconds: Histogram for job execution duration
+/// - job_duration_seconds: Summary for job execution duration
 /// - job_queue_size: Gauge for current queue size
 ///
+/// Metrics are served on `/metrics`. Scrapers that accept OpenMetrics also get
+/// `job_execution_duration_seconds`, the duration histogram with trace exemplars.
+///
 /// Requirements: 5.3, 5.4, 5.5, 5.6
 #[tracing::instrument(skip_all)]
 pub fn init_metrics(metrics_port: u16) -> Result<()> {
@@ -140,12 +234,19 @@ pub fn init_metrics(metrics_port: u16) -> Result<()> {
         .parse()
         .map_err(|e| anyhow::anyhow!("Invalid metrics port: {}", e))?;
 
-    // Build and install the Prometheus exporter
-    PrometheusBuilder::new()
-        .with_http_listener(addr)
-        .install()
+    // Install the Prometheus recorder; the endpoint below renders it
+    let handle = PrometheusBuilder::new()
+        .install_recorder()
         .map_err(|e| anyhow::anyhow!("Failed to install Prometheus exporter: {}", e))?;
 
+    let listener = std::net::TcpListener::bind(addr)
+        .and_then(|listener| {
+            listener.set_nonblocking(true)?;
+            tokio::net::TcpListener::from_std(listener)
+        })
+        .map_err(|e| anyhow::anyhow!("Failed to bind metrics endpoint: {}", e))?;
+    serve_metrics(listener, handle);
+
     // Describe all metrics for better Prometheus integration
     describe_counter!(
         "job_success_total",
@@ -157,6 +258,42 @@ pub fn init_metrics(metrics_port: u16) -> Result<()> {
         "Duration of job executions in seconds"
     );
     describe_gauge!("job_queue_size", "Current number of jobs in the queue");
+    describe_counter!(
+        "faults_injected_total",
+        "Total number of faults injected by the chaos testing layer"
+    );
+    describe_gauge!(
+        "clock_skew_milliseconds",
+        "Offset of a reference clock (Postgres, NTP) from this node's clock"
+    );
+    describe_gauge!(
+        "scheduler_draining",
+        "1 while this scheduler node is drained and publishes no new executions"
+    );
+    describe_gauge!(
+        "worker_memory_rss_bytes",
+        "Resident set size of the worker process"
+    );
+    describe_histogram!(
+        "execution_peak_memory_bytes",
+        "Estimated peak memory of an execution"
+    );
+    describe_histogram!(
+        "execution_cpu_seconds",
+        "CPU time spent running an execution"
+    );
+    describe_counter!(
+        "execution_resource_kills_total",
+        "Total number of executions stopped for exceeding a memory or CPU limit"
+    );
+    describe_histogram!(
+        "step_lock_wait_seconds",
+        "Time a step waited for its acquire_lock resource"
+    );
+    describe_counter!(
+        "step_lock_timeouts_total",
+        "Total number of steps failed waiting for their acquire_lock resource"
+    );
 
     tracing::info!(
         metrics_port = metrics_port,
@@ -167,13 +304,83 @@ pub fn init_metrics(metrics_port: u16) -> Result<()> {
     Ok(())
 }
 
+/// Serve `/metrics` from the installed recorder and keep its histograms up to date
+fn serve_metrics(listener: tokio::net::TcpListener, handle: PrometheusHandle) {
+    use axum::{http::header, routing::get, Router};
+
+    let upkeep_handle = handle.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            upkeep_handle.run_upkeep();
+        }
+    });
+
+    let app = Router::new().route(
+        "/metrics",
+        get(move |headers: axum::http::HeaderMap| {
+            let handle = handle.clone();
+            async move {
+                let openmetrics = headers
+                    .get(header::ACCEPT)
+                    .and_then(|accept| accept.to_str().ok())
+                    .is_some_and(|accept| accept.contains("application/openmetrics-text"));
+                if openmetrics {
+                    let mut body = handle.render();
+                    body.push_str(&DurationHistogram::global().encode());
+                    ([(header::CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)], body)
+                } else {
+                    (
+                        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                        handle.render(),
+                    )
+                }
+            }
+        }),
+    );
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "Metrics endpoint stopped");
+        }
+    });
+}
+
+/// Labels of a job metric: the job ID and name, then the job's custom labels
+fn job_labels(job_id: &Uuid, job_name: &str, custom: &BTreeMap<String, String>) -> Vec<Label> {
+    let mut labels = vec![
+        Label::new("job_id", job_id.to_string()),
+        Label::new("job_name", job_name.to_string()),
+    ];
+    labels.extend(
+        custom
+            .iter()
+            .map(|(name, value)| Label::new(name.clone(), value.clone())),
+    );
+    labels
+}
+
 /// Record a successful job execution
 ///
 /// Increments the job_success_total counter
 /// Requirements: 5.3
 #[inline]
 pub fn record_job_success(job_id: &Uuid, job_name: &str) {
-    counter!("job_success_total", "job_id" => job_id.to_string(), "job_name" => job_name.to_string()).increment(1);
+    record_labeled_job_success(job_id, job_name, &BTreeMap::new());
+}
+
+/// Record a successful job execution with the job's custom metric labels
+#[inline]
+pub fn record_labeled_job_success(
+    job_id: &Uuid,
+    job_name: &str,
+    custom_labels: &BTreeMap<String, String>,
+) {
+    counter!(
+        "job_success_total",
+        job_labels(job_id, job_name, custom_labels)
+    )
+    .increment(1);
 }
 
 /// Record a failed job execution
@@ -182,27 +389,70 @@ pub fn record_job_success(job_id: &Uuid, job_name: &str) {
 /// Requirements: 5.4
 #[inline]
 pub fn record_job_failure(job_id: &Uuid, job_name: &str, reason: &str) {
-    counter!(
-        "job_failed_total",
-        "job_id" => job_id.to_string(),
-        "job_name" => job_name.to_string(),
-        "reason" => reason.to_string()
-    )
-    .increment(1);
+    record_labeled_job_failure(job_id, job_name, reason, &BTreeMap::new());
+}
+
+/// Record a failed job execution with the job's custom metric labels
+#[inline]
+pub fn record_labeled_job_failure(
+    job_id: &Uuid,
+    job_name: &str,
+    reason: &str,
+    custom_labels: &BTreeMap<String, String>,
+) {
+    let mut labels = job_labels(job_id, job_name, custom_labels);
+    labels.push(Label::new("reason", reason.to_string()));
+    counter!("job_failed_total", labels).increment(1);
 }
 
 /// Record job execution duration
 ///
-/// Records the duration in the job_duration_seconds histogram
+/// Records the duration in the job_duration_seconds summary
 /// Requirements: 5.5
 #[inline]
 pub fn record_job_duration(job_id: &Uuid, job_name: &str, duration_seconds: f64) {
-    histogram!(
-        "job_duration_seconds",
-        "job_id" => job_id.to_string(),
-        "job_name" => job_name.to_string()
-    )
-    .record(duration_seconds);
+    record_labeled_job_duration(job_id, job_name, duration_seconds, &BTreeMap::new(), &[]);
+}
+
+/// Record job execution duration with the job's custom metric labels
+///
+/// The sample also goes to `job_execution_duration_seconds`, where it becomes its
+/// bucket's exemplar, labelled with `exemplar_labels` and the trace ID of the current
+/// span when it is exported to OpenTelemetry.
+pub fn record_labeled_job_duration(
+    job_id: &Uuid,
+    job_name: &str,
+    duration_seconds: f64,
+    custom_labels: &BTreeMap<String, String>,
+    exemplar_labels: &[(&str, String)],
+) {
+    let labels = job_labels(job_id, job_name, custom_labels);
+
+    let mut exemplar: SeriesLabels = Vec::new();
+    let span_context = tracing::Span::current()
+        .context()
+        .span()
+        .span_context()
+        .clone();
+    if span_context.is_valid() {
+        exemplar.push(("trace_id".to_string(), span_context.trace_id().to_string()));
+    }
+    exemplar.extend(
+        exemplar_labels
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.clone())),
+    );
+    let series = labels
+        .iter()
+        .map(|label| (label.key().to_string(), label.value().to_string()))
+        .collect();
+    DurationHistogram::global().observe(
+        series,
+        duration_seconds,
+        (!exemplar.is_empty()).then_some(exemplar),
+    );
+
+    histogram!("job_duration_seconds", labels).record(duration_seconds);
 }
 
 /// Update the job queue size gauge
@@ -214,6 +464,71 @@ pub fn update_queue_size(size: i64) {
     gauge!("job_queue_size").set(size as f64);
 }
 
+/// Record a fault injected by the chaos testing layer
+///
+/// Increments the faults_injected_total counter
+#[inline]
+pub fn record_injected_fault(kind: &str) {
+    counter!("faults_injected_total", "kind" => kind.to_string()).increment(1);
+}
+
+/// Record the last measured clock offset against a reference clock
+///
+/// Sets the clock_skew_milliseconds gauge
+#[inline]
+pub fn record_clock_skew(source: &str, offset_ms: i64) {
+    gauge!("clock_skew_milliseconds", "source" => source.to_string()).set(offset_ms as f64);
+}
+
+/// Record whether this scheduler node is drained
+///
+/// Sets the scheduler_draining gauge
+#[inline]
+pub fn record_scheduler_draining(draining: bool) {
+    gauge!("scheduler_draining").set(if draining { 1.0 } else { 0.0 });
+}
+
+/// Update the worker memory gauge
+///
+/// Sets the worker_memory_rss_bytes gauge
+#[inline]
+pub fn update_worker_memory(rss_bytes: u64) {
+    gauge!("worker_memory_rss_bytes").set(rss_bytes as f64);
+}
+
+/// Record the resources a finished execution used
+///
+/// Records the execution_peak_memory_bytes and execution_cpu_seconds histograms
+#[inline]
+pub fn record_execution_resources(peak_memory_bytes: u64, cpu_seconds: f64) {
+    histogram!("execution_peak_memory_bytes").record(peak_memory_bytes as f64);
+    histogram!("execution_cpu_seconds").record(cpu_seconds);
+}
+
+/// Record an execution stopped by the resource guard
+///
+/// Increments the execution_resource_kills_total counter
+#[inline]
+pub fn record_resource_kill(resource: &str) {
+    counter!("execution_resource_kills_total", "resource" => resource.to_string()).increment(1);
+}
+
+/// Record how long a step waited for its lock
+///
+/// Records the step_lock_wait_seconds histogram
+#[inline]
+pub fn record_step_lock_wait(resource: &str, wait_seconds: f64) {
+    histogram!("step_lock_wait_seconds", "resource" => resource.to_string()).record(wait_seconds);
+}
+
+/// Record a step that gave up waiting for its lock
+///
+/// Increments the step_lock_timeouts_total counter
+#[inline]
+pub fn record_step_lock_timeout(resource: &str) {
+    counter!("step_lock_timeouts_total", "resource" => resource.to_string()).increment(1);
+}
+
 /// Alert notification interface
 ///
 /// This trait defines the interface for sending alert notifications
@@ -222,6 +537,11 @@ pub fn update_queue_size(size: i64) {
 /// Requirements: 5.8
 #[async_trait::async_trait]
 pub trait AlertNotifier: Send + Sync {
+    /// Locale the notifier's messages are rendered in
+    fn locale(&self) -> Locale {
+        Locale::default()
+    }
+
     /// Send an alert notification for consecutive job failures
     async fn send_alert(
         &self,
@@ -229,6 +549,83 @@ pub trait AlertNotifier: Send + Sync {
         job_name: &str,
         consecutive_failures: u32,
     ) -> Result<()>;
+
+    /// Send a consecutive-failure alert that includes the job's owner and contacts,
+    /// and the remediation note of the latest failure if it has one
+    ///
+    /// Notifiers that cannot render ownership fall back to `send_alert`.
+    async fn send_owned_alert(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        consecutive_failures: u32,
+        _owner: Option<&JobOwner>,
+        _remediation: Option<&FailureNote>,
+    ) -> Result<()> {
+        self.send_alert(job_id, job_name, consecutive_failures)
+            .await
+    }
+
+    /// Tell a deprecated job's owner that it was disabled at its sunset date
+    async fn send_sunset_notice(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        sunset_at: DateTime<Utc>,
+        reason: Option<&str>,
+        owner: Option<&JobOwner>,
+    ) -> Result<()> {
+        let (subject, body) =
+            render_sunset_notice(self.locale(), job_id, job_name, sunset_at, reason, owner);
+        tracing::warn!(
+            job_id = %job_id,
+            job_name = job_name,
+            owner_team = owner.map(|o| o.team.as_str()),
+            alert_type = "job_sunset",
+            subject = %subject,
+            "NOTICE: {}",
+            body
+        );
+        Ok(())
+    }
+
+    /// Tell operators this node's clock drifted past the limit and scheduling stopped
+    async fn send_clock_skew_alert(
+        &self,
+        source: &str,
+        offset_ms: i64,
+        max_skew_ms: u64,
+    ) -> Result<()> {
+        let (subject, body) =
+            render_clock_skew_alert(self.locale(), source, offset_ms, max_skew_ms);
+        tracing::error!(
+            source = source,
+            offset_ms = offset_ms,
+            max_skew_ms = max_skew_ms,
+            alert_type = "clock_skew",
+            subject = %subject,
+            "ALERT: {}",
+            body
+        );
+        Ok(())
+    }
+
+    /// Warn operators about secrets and certificates expiring within `warn_days`
+    async fn send_expiry_warning(
+        &self,
+        expiring: &[CredentialExpiry],
+        warn_days: u32,
+    ) -> Result<()> {
+        let (subject, body) = render_expiry_warning(self.locale(), expiring, warn_days, Utc::now());
+        tracing::warn!(
+            count = expiring.len(),
+            alert_type = "credential_expiry",
+            subject = %subject,
+            "ALERT: {}",
+            body
+        );
+        Ok(())
+    }
 }
 
 /// Check if an alert should be triggered based on consecutive failures
@@ -240,18 +637,228 @@ pub fn should_trigger_alert(consecutive_failures: u32) -> bool {
     consecutive_failures >= 3
 }
 
+/// Render the subject and body of a consecutive-failure alert in the given locale
+///
+/// The body ends with the job's owner and contacts (or a note that none is recorded).
+pub fn render_alert(
+    locale: Locale,
+    job_id: &Uuid,
+    job_name: &str,
+    consecutive_failures: u32,
+    owner: Option<&JobOwner>,
+    remediation: Option<&FailureNote>,
+) -> (String, String) {
+    let job_id = job_id.to_string();
+    let count = consecutive_failures.to_string();
+    let args = [
+        ("job_name", job_name),
+        ("job_id", job_id.as_str()),
+        ("count", count.as_str()),
+    ];
+
+    let mut body = i18n::translate_with(locale, "notification.consecutive_failures.body", &args);
+    for line in render_owner(locale, owner) {
+        body.push('\n');
+        body.push_str(&line);
+    }
+    if let Some(remediation) = remediation {
+        body.push('\n');
+        body.push_str(&i18n::translate_with(
+            locale,
+            "notification.remediation.note",
+            &[("note", &remediation.note)],
+        ));
+        if let Some(url) = &remediation.runbook_url {
+            body.push('\n');
+            body.push_str(&i18n::translate_with(
+                locale,
+                "notification.remediation.runbook",
+                &[("url", url)],
+            ));
+        }
+    }
+
+    (
+        i18n::translate_with(locale, "notification.consecutive_failures.subject", &args),
+        body,
+    )
+}
+
+/// Render the subject and body of a job sunset notice in the given locale
+pub fn render_sunset_notice(
+    locale: Locale,
+    job_id: &Uuid,
+    job_name: &str,
+    sunset_at: DateTime<Utc>,
+    reason: Option<&str>,
+    owner: Option<&JobOwner>,
+) -> (String, String) {
+    let job_id = job_id.to_string();
+    let sunset_at = sunset_at.to_rfc3339();
+    let args = [
+        ("job_name", job_name),
+        ("job_id", job_id.as_str()),
+        ("sunset_at", sunset_at.as_str()),
+    ];
+
+    let mut body = i18n::translate_with(locale, "notification.job_sunset.body", &args);
+    if let Some(reason) = reason {
+        body.push('\n');
+        body.push_str(&i18n::translate_with(
+            locale,
+            "notification.job_sunset.reason",
+            &[("reason", reason)],
+        ));
+    }
+    for line in render_owner(locale, owner) {
+        body.push('\n');
+        body.push_str(&line);
+    }
+
+    (
+        i18n::translate_with(locale, "notification.job_sunset.subject", &args),
+        body,
+    )
+}
+
+/// Render the subject and body of a clock skew alert in the given locale
+pub fn render_clock_skew_alert(
+    locale: Locale,
+    source: &str,
+    offset_ms: i64,
+    max_skew_ms: u64,
+) -> (String, String) {
+    let offset_ms = offset_ms.to_string();
+    let max_skew_ms = max_skew_ms.to_string();
+    let args = [
+        ("source", source),
+        ("offset_ms", offset_ms.as_str()),
+        ("max_skew_ms", max_skew_ms.as_str()),
+    ];
+    (
+        i18n::translate_with(locale, "notification.clock_skew.subject", &args),
+        i18n::translate_with(locale, "notification.clock_skew.body", &args),
+    )
+}
+
+/// Render the subject and body of a credential expiry warning in the given locale
+///
+/// The body lists one credential per line, soonest expiry first.
+pub fn render_expiry_warning(
+    locale: Locale,
+    expiring: &[CredentialExpiry],
+    warn_days: u32,
+    now: DateTime<Utc>,
+) -> (String, String) {
+    let count = expiring.len().to_string();
+    let warn_days = warn_days.to_string();
+    let mut body = i18n::translate_with(
+        locale,
+        "notification.credential_expiry.body",
+        &[("warn_days", warn_days.as_str())],
+    );
+
+    let mut expiring: Vec<&CredentialExpiry> = expiring.iter().collect();
+    expiring.sort_by_key(|credential| credential.expires_at);
+    for credential in expiring {
+        let source = credential
+            .kind
+            .map_or_else(|| credential.source.to_string(), |kind| kind.to_string());
+        let source = i18n::translate(
+            locale,
+            &format!("notification.credential_source.{}", source),
+        )
+        .to_string();
+        let expires_at = credential.expires_at.to_rfc3339();
+        let days_left = credential.days_left(now);
+        let days = days_left.to_string();
+        let args = [
+            ("name", credential.name.as_str()),
+            ("source", source.as_str()),
+            ("expires_at", expires_at.as_str()),
+            ("days_left", days.as_str()),
+        ];
+        let key = if credential.expires_at <= now {
+            "notification.credential_expiry.expired"
+        } else {
+            "notification.credential_expiry.expiring"
+        };
+        body.push('\n');
+        body.push_str(&i18n::translate_with(locale, key, &args));
+    }
+
+    (
+        i18n::translate_with(
+            locale,
+            "notification.credential_expiry.subject",
+            &[("count", count.as_str())],
+        ),
+        body,
+    )
+}
+
+/// Localized owner/contact lines for failure notifications
+fn render_owner(locale: Locale, owner: Option<&JobOwner>) -> Vec<String> {
+    let Some(owner) = owner else {
+        return vec![i18n::translate(locale, "notification.owner.missing").to_string()];
+    };
+
+    let mut lines = vec![i18n::translate_with(
+        locale,
+        "notification.owner.team",
+        &[("team", &owner.team)],
+    )];
+    if let Some(email) = &owner.contact_email {
+        lines.push(i18n::translate_with(
+            locale,
+            "notification.owner.email",
+            &[("email", email)],
+        ));
+    }
+    if let Some(slack) = &owner.slack {
+        lines.push(i18n::translate_with(
+            locale,
+            "notification.owner.slack",
+            &[("slack", slack)],
+        ));
+    }
+    if let Some(url) = &owner.runbook_url {
+        lines.push(i18n::translate_with(
+            locale,
+            "notification.owner.runbook",
+            &[("url", url)],
+        ));
+    }
+    lines
+}
+
 /// Log-based alert notifier (default implementation)
 ///
-/// This implementation logs alerts at ERROR level
+/// This implementation logs alerts at ERROR level, rendered in its locale
+/// (`i18n.default_locale`, as alerts aren't sent in reply to a request)
 /// In production, this could be replaced with integrations to:
 /// - Email notifications
 /// - Slack/Teams webhooks
 /// - PagerDuty
 /// - Custom alerting systems
-pub struct LogAlertNotifier;
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogAlertNotifier {
+    locale: Locale,
+}
+
+impl LogAlertNotifier {
+    /// Notifier logging its messages in `locale`
+    pub fn new(locale: Locale) -> Self {
+        Self { locale }
+    }
+}
 
 #[async_trait::async_trait]
 impl AlertNotifier for LogAlertNotifier {
+    fn locale(&self) -> Locale {
+        self.locale
+    }
+
     #[tracing::instrument(skip(self))]
     async fn send_alert(
         &self,
@@ -259,13 +866,36 @@ impl AlertNotifier for LogAlertNotifier {
         job_name: &str,
         consecutive_failures: u32,
     ) -> Result<()> {
+        self.send_owned_alert(job_id, job_name, consecutive_failures, None, None)
+            .await
+    }
+
+    #[tracing::instrument(skip(self, owner, remediation))]
+    async fn send_owned_alert(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        consecutive_failures: u32,
+        owner: Option<&JobOwner>,
+        remediation: Option<&FailureNote>,
+    ) -> Result<()> {
+        let (subject, body) = render_alert(
+            self.locale,
+            job_id,
+            job_name,
+            consecutive_failures,
+            owner,
+            remediation,
+        );
         tracing::error!(
             job_id = %job_id,
             job_name = job_name,
             consecutive_failures = consecutive_failures,
+            owner_team = owner.map(|o| o.team.as_str()),
             alert_type = "consecutive_failures",
-            "ALERT: Job has failed {} consecutive times",
-            consecutive_failures
+            subject = %subject,
+            "ALERT: {}",
+            body
         );
         Ok(())
     }
@@ -306,6 +936,30 @@ mod tests {
         update_queue_size(10);
     }
 
+    #[test]
+    fn test_duration_histogram_carries_exemplars_and_is_bounded() {
+        let histogram = DurationHistogram::new();
+        let series = |name: &str| vec![("job_name".to_string(), name.to_string())];
+        histogram.observe(
+            series("settle"),
+            2.5,
+            Some(vec![("trace_id".to_string(), "4bf92f35".to_string())]),
+        );
+        for i in 1..MAX_DURATION_HISTOGRAM_SERIES {
+            histogram.observe(series(&format!("job-{}", i)), 1.0, None);
+        }
+        histogram.observe(series("one-too-many"), 1.0, None);
+        // Known series keep recording once the histogram is full
+        histogram.observe(series("settle"), 0.2, None);
+
+        let body = histogram.encode();
+        assert!(body.contains("# TYPE job_execution_duration_seconds histogram"));
+        assert!(body.contains(r#"# {trace_id="4bf92f35"} 2.5"#));
+        assert!(body.contains(r#"job_execution_duration_seconds_count{job_name="settle"} 2"#));
+        assert!(!body.contains("one-too-many"));
+        assert!(body.ends_with("# EOF\n"));
+    }
+
     #[test]
     fn test_should_trigger_alert() {
         assert!(!should_trigger_alert(0));
@@ -318,9 +972,147 @@ mod tests {
 
     #[tokio::test]
     async fn test_log_alert_notifier() {
-        let notifier = LogAlertNotifier;
+        let notifier = LogAlertNotifier::new(Locale::EnUs);
         let job_id = Uuid::new_v4();
         let result = notifier.send_alert(&job_id, "test-job", 3).await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_render_alert_is_localized() {
+        let job_id = Uuid::new_v4();
+
+        let (subject, body) = render_alert(Locale::EnUs, &job_id, "backup", 4, None, None);
+        assert_eq!(subject, "[Cron] Job backup is failing repeatedly");
+        assert_eq!(
+            body,
+            format!(
+                "Job backup ({}) has failed 4 consecutive times\nNo owner is recorded for this job",
+                job_id
+            )
+        );
+
+        let (subject, _) = render_alert(Locale::ViVn, &job_id, "backup", 4, None, None);
+        assert_eq!(subject, "[Cron] Job backup thất bại liên tiếp");
+    }
+
+    #[test]
+    fn test_render_clock_skew_alert_is_localized() {
+        let (_, body) = render_clock_skew_alert(Locale::EnUs, "postgres", -1500, 500);
+        assert_eq!(
+            body,
+            "Clock differs from postgres by -1500ms (limit 500ms); scheduling is paused until it is corrected"
+        );
+
+        let (subject, _) = render_clock_skew_alert(Locale::ViVn, "postgres", -1500, 500);
+        assert_eq!(
+            subject,
+            "[Cron] Đồng hồ lệch so với postgres, đã tạm dừng lập lịch"
+        );
+    }
+
+    #[test]
+    fn test_render_alert_includes_owner_contacts() {
+        let job_id = Uuid::new_v4();
+        let owner = JobOwner {
+            team: "payments".to_string(),
+            contact_email: None,
+            slack: Some("#payments-oncall".to_string()),
+            runbook_url: Some("https://wiki.example.com/runbooks/backup".to_string()),
+        };
+
+        let (_, body) = render_alert(Locale::EnUs, &job_id, "backup", 3, Some(&owner), None);
+        let lines: Vec<&str> = body.lines().skip(1).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Owning team: payments",
+                "Slack: #payments-oncall",
+                "Runbook: https://wiki.example.com/runbooks/backup",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_alert_includes_remediation() {
+        let job_id = Uuid::new_v4();
+        let remediation = FailureNote {
+            fingerprint: "0123456789abcdef".to_string(),
+            note: "Partner rotates its certificate monthly; re-import it".to_string(),
+            runbook_url: Some("https://wiki.example.com/runbooks/partner-tls".to_string()),
+            updated_by: "alice".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let (_, body) = render_alert(Locale::EnUs, &job_id, "backup", 3, None, Some(&remediation));
+        let lines: Vec<&str> = body.lines().skip(2).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Known fix: Partner rotates its certificate monthly; re-import it",
+                "Failure runbook: https://wiki.example.com/runbooks/partner-tls",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_sunset_notice() {
+        let job_id = Uuid::new_v4();
+        let sunset_at = "2026-11-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+
+        let (subject, body) = render_sunset_notice(
+            Locale::EnUs,
+            &job_id,
+            "legacy-sync",
+            sunset_at,
+            Some("Replaced by orders-sync"),
+            None,
+        );
+        assert_eq!(
+            subject,
+            "[Cron] Job legacy-sync was disabled at its sunset date"
+        );
+        assert_eq!(
+            body,
+            format!(
+                "Job legacy-sync ({}) is deprecated and was disabled automatically at 2026-11-01T00:00:00+00:00\nReason: Replaced by orders-sync\nNo owner is recorded for this job",
+                job_id
+            )
+        );
+    }
+
+    #[test]
+    fn test_render_expiry_warning_lists_soonest_first() {
+        let now = "2026-11-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let credential = |name: &str, kind, days: i64| CredentialExpiry {
+            source: crate::models::CredentialSource::TlsMaterial,
+            id: Some(Uuid::new_v4()),
+            name: name.to_string(),
+            kind,
+            expires_at: now + chrono::Duration::days(days),
+        };
+        let expiring = [
+            credential(
+                "partner-sftp",
+                Some(crate::models::TlsMaterialKind::SshKey),
+                10,
+            ),
+            credential("old-client-cert", None, -2),
+        ];
+
+        let (subject, body) = render_expiry_warning(Locale::EnUs, &expiring, 14, now);
+        assert_eq!(subject, "[Cron] 2 credentials are about to expire");
+        assert_eq!(
+            body,
+            "The following credentials expire within 14 days:\n\
+             - old-client-cert (TLS material) expired at 2026-10-30T00:00:00+00:00\n\
+             - partner-sftp (SSH key) expires at 2026-11-11T00:00:00+00:00, 10 days left"
+        );
+
+        let (_, body) = render_expiry_warning(Locale::ViVn, &expiring[..1], 14, now);
+        assert!(body.ends_with(
+            "- partner-sftp (khóa SSH) hết hạn lúc 2026-11-11T00:00:00+00:00, còn 10 ngày"
+        ));
+    }
 }
@@ -0,0 +1,256 @@
+// Trigger-time variable overrides
+// Purpose: Let manual, scheduled one-off and webhook triggers parameterize a single
+// run of a job instead of cloning the job per parameter set
+//
+// Precedence, lowest to highest: global variables, job-scoped variables, trigger
+// variables. Sensitive stored variables can never be overridden from a trigger, so a
+// caller can't redirect credentials or secrets for a run.
+
+use crate::errors::ValidationError;
+use crate::models::JobExecution;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Key under which the override audit record is stored in `JobExecution::trigger_metadata`
+pub const VARIABLES_AUDIT_METADATA_KEY: &str = "variables_audit";
+
+/// Most variables a single trigger may override
+pub const MAX_TRIGGER_VARIABLES: usize = 50;
+
+/// Longest accepted value, in bytes
+const MAX_VARIABLE_VALUE_LEN: usize = 4096;
+
+/// Validate trigger variables and normalize their values to strings
+///
+/// Names follow the `${NAME}` placeholder syntax. Values must be strings, numbers or
+/// booleans; non-string scalars are stored as their JSON text so `{{NAME}}`
+/// references resolve to them.
+pub fn normalize_trigger_variables(
+    variables: HashMap<String, serde_json::Value>,
+) -> Result<HashMap<String, serde_json::Value>, ValidationError> {
+    if variables.len() > MAX_TRIGGER_VARIABLES {
+        return Err(ValidationError::InvalidFieldValue {
+            field: "variables".to_string(),
+            reason: format!(
+                "at most {} variables can be overridden",
+                MAX_TRIGGER_VARIABLES
+            ),
+        });
+    }
+
+    variables
+        .into_iter()
+        .map(|(name, value)| {
+            if !is_valid_variable_name(&name) {
+                return Err(ValidationError::InvalidFieldValue {
+                    field: format!("variables.{}", name),
+                    reason: "name must start with a letter or underscore and contain only letters, digits and underscores".to_string(),
+                });
+            }
+
+            let text = match value {
+                serde_json::Value::String(s) => s,
+                serde_json::Value::Number(n) => n.to_string(),
+                serde_json::Value::Bool(b) => b.to_string(),
+                _ => {
+                    return Err(ValidationError::InvalidFieldValue {
+                        field: format!("variables.{}", name),
+                        reason: "value must be a string, number or boolean".to_string(),
+                    })
+                }
+            };
+            if text.len() > MAX_VARIABLE_VALUE_LEN {
+                return Err(ValidationError::InvalidFieldValue {
+                    field: format!("variables.{}", name),
+                    reason: format!("value must be at most {} bytes", MAX_VARIABLE_VALUE_LEN),
+                });
+            }
+
+            Ok((name, serde_json::Value::String(text)))
+        })
+        .collect()
+}
+
+/// Reject overrides of sensitive stored variables
+///
+/// `names` are the variables a trigger or a job's parameter matrix sets for a run.
+pub fn check_sensitive_overrides<'a>(
+    names: impl IntoIterator<Item = &'a String>,
+    sensitive_names: &HashSet<String>,
+) -> Result<(), ValidationError> {
+    let mut blocked: Vec<&str> = names
+        .into_iter()
+        .filter(|name| sensitive_names.contains(*name))
+        .map(String::as_str)
+        .collect();
+    if blocked.is_empty() {
+        return Ok(());
+    }
+
+    blocked.sort_unstable();
+    Err(ValidationError::InvalidFieldValue {
+        field: "variables".to_string(),
+        reason: format!(
+            "sensitive variables cannot be overridden: {}",
+            blocked.join(", ")
+        ),
+    })
+}
+
+pub(crate) fn is_valid_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Store validated trigger variables and their audit record on an execution
+pub fn apply_trigger_variables(
+    execution: &mut JobExecution,
+    variables: &HashMap<String, serde_json::Value>,
+    overridden_by: &str,
+    source: &str,
+) {
+    if variables.is_empty() {
+        return;
+    }
+
+    let audit = VariableOverrideAudit::new(overridden_by, source, variables);
+    tracing::info!(
+        execution_id = %execution.id,
+        job_id = %execution.job_id,
+        overridden_by = %audit.overridden_by,
+        source = %audit.source,
+        variables = ?audit.names,
+        "Audit log: Trigger variable overrides"
+    );
+
+    execution.set_variable_overrides(variables);
+    audit.attach_to(&mut execution.trigger_metadata);
+}
+
+/// Who overrode which variables for an execution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariableOverrideAudit {
+    /// User ID, webhook path or scheduled trigger creator
+    pub overridden_by: String,
+    /// Trigger kind: `manual`, `webhook`, `scheduled` or `replay`
+    pub source: String,
+    /// Sorted names of the overridden variables
+    pub names: Vec<String>,
+    pub overridden_at: DateTime<Utc>,
+}
+
+impl VariableOverrideAudit {
+    pub fn new(
+        overridden_by: impl Into<String>,
+        source: impl Into<String>,
+        variables: &HashMap<String, serde_json::Value>,
+    ) -> Self {
+        let mut names: Vec<String> = variables.keys().cloned().collect();
+        names.sort_unstable();
+
+        Self {
+            overridden_by: overridden_by.into(),
+            source: source.into(),
+            names,
+            overridden_at: Utc::now(),
+        }
+    }
+
+    /// Read the audit record stored in an execution's trigger metadata
+    pub fn from_trigger_metadata(metadata: Option<&serde_json::Value>) -> Option<Self> {
+        metadata
+            .and_then(|m| m.get(VARIABLES_AUDIT_METADATA_KEY))
+            .and_then(|a| serde_json::from_value(a.clone()).ok())
+    }
+
+    /// Store the audit record in an execution's trigger metadata
+    pub fn attach_to(&self, metadata: &mut Option<serde_json::Value>) {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        match metadata {
+            Some(serde_json::Value::Object(map)) => {
+                map.insert(VARIABLES_AUDIT_METADATA_KEY.to_string(), value);
+            }
+            _ => *metadata = Some(serde_json::json!({ VARIABLES_AUDIT_METADATA_KEY: value })),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_stringifies_scalars() {
+        let variables = HashMap::from([
+            ("REPORT_DATE".to_string(), json!("2026-10-15")),
+            ("batch_size".to_string(), json!(500)),
+            ("_dry_run".to_string(), json!(true)),
+        ]);
+
+        let normalized = normalize_trigger_variables(variables).unwrap();
+        assert_eq!(normalized["REPORT_DATE"], json!("2026-10-15"));
+        assert_eq!(normalized["batch_size"], json!("500"));
+        assert_eq!(normalized["_dry_run"], json!("true"));
+    }
+
+    #[test]
+    fn test_normalize_rejects_bad_names_and_values() {
+        for (name, value) in [
+            ("1st", json!("x")),
+            ("report-date", json!("x")),
+            ("", json!("x")),
+            ("filters", json!({ "region": "north" })),
+            ("ids", json!([1, 2])),
+            ("missing", json!(null)),
+        ] {
+            let variables = HashMap::from([(name.to_string(), value)]);
+            assert!(
+                normalize_trigger_variables(variables).is_err(),
+                "expected {:?} to be rejected",
+                name
+            );
+        }
+
+        let too_many = (0..=MAX_TRIGGER_VARIABLES)
+            .map(|i| (format!("VAR_{}", i), json!("x")))
+            .collect();
+        assert!(normalize_trigger_variables(too_many).is_err());
+    }
+
+    #[test]
+    fn test_sensitive_variables_cannot_be_overridden() {
+        let sensitive = HashSet::from(["API_TOKEN".to_string()]);
+        let allowed = HashMap::from([("REPORT_DATE".to_string(), json!("2026-10-15"))]);
+        assert!(check_sensitive_overrides(allowed.keys(), &sensitive).is_ok());
+
+        let blocked = HashMap::from([("API_TOKEN".to_string(), json!("stolen"))]);
+        let err = check_sensitive_overrides(blocked.keys(), &sensitive).unwrap_err();
+        assert!(err.to_string().contains("API_TOKEN"));
+
+        // Parameter matrices set run variables just like trigger overrides
+        let matrix = std::collections::BTreeMap::from([(
+            "API_TOKEN".to_string(),
+            vec!["a".to_string(), "b".to_string()],
+        )]);
+        assert!(check_sensitive_overrides(matrix.keys(), &sensitive).is_err());
+    }
+
+    #[test]
+    fn test_audit_round_trips_through_trigger_metadata() {
+        let variables =
+            HashMap::from([("b".to_string(), json!("2")), ("a".to_string(), json!("1"))]);
+        let audit = VariableOverrideAudit::new("alice", "manual", &variables);
+        assert_eq!(audit.names, vec!["a", "b"]);
+
+        let mut metadata = Some(json!({ "callback": { "url": "https://example.com" } }));
+        audit.attach_to(&mut metadata);
+        assert!(metadata.as_ref().unwrap()["callback"].is_object());
+        assert_eq!(
+            VariableOverrideAudit::from_trigger_metadata(metadata.as_ref()),
+            Some(audit)
+        );
+    }
+}
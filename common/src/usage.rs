@@ -0,0 +1,216 @@
+// Tenant usage tracking
+// Purpose: Attribute notifications to tenants and define the monthly reporting window
+// used for internal chargeback
+
+use crate::db::repositories::usage::UsageRepository;
+use crate::errors::ValidationError;
+use crate::models::{CredentialExpiry, FailureNote, JobOwner};
+use crate::telemetry::AlertNotifier;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// What kind of notification was sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// Consecutive-failure alert
+    Alert,
+    /// Execution completion callback
+    Callback,
+    /// Deprecated job disabled at its sunset date
+    SunsetNotice,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::Alert => "alert",
+            NotificationKind::Callback => "callback",
+            NotificationKind::SunsetNotice => "sunset_notice",
+        }
+    }
+}
+
+impl fmt::Display for NotificationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A calendar month in UTC, the billing period of a usage report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsageMonth {
+    year: i32,
+    month: u32,
+}
+
+impl UsageMonth {
+    /// The month containing `now`
+    pub fn containing(now: DateTime<Utc>) -> Self {
+        Self {
+            year: now.year(),
+            month: now.month(),
+        }
+    }
+
+    /// Parse a `YYYY-MM` month
+    pub fn parse(value: &str) -> Result<Self, ValidationError> {
+        let invalid = || ValidationError::InvalidFieldValue {
+            field: "month".to_string(),
+            reason: format!("'{}' is not a month in YYYY-MM format", value),
+        };
+        let date = NaiveDate::parse_from_str(&format!("{}-01", value.trim()), "%Y-%m-%d")
+            .map_err(|_| invalid())?;
+        Ok(Self {
+            year: date.year(),
+            month: date.month(),
+        })
+    }
+
+    /// First instant of the month
+    pub fn start(&self) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(self.year, self.month, 1, 0, 0, 0)
+            .single()
+            .expect("first of the month is a valid UTC instant")
+    }
+
+    /// First instant of the following month
+    pub fn end(&self) -> DateTime<Utc> {
+        let (year, month) = if self.month == 12 {
+            (self.year + 1, 1)
+        } else {
+            (self.year, self.month + 1)
+        };
+        Self { year, month }.start()
+    }
+}
+
+impl fmt::Display for UsageMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+/// Record a notification for usage reporting; failures are logged, never propagated
+pub async fn record_notification(
+    usage_repo: &UsageRepository,
+    job_id: Uuid,
+    kind: NotificationKind,
+) {
+    if let Err(e) = usage_repo.record_notification(job_id, kind).await {
+        warn!(job_id = %job_id, kind = %kind, error = %e, "Failed to record notification usage");
+    }
+}
+
+/// Alert notifier wrapper that records every notification it sends
+pub struct UsageRecordingNotifier {
+    inner: Arc<dyn AlertNotifier>,
+    usage_repo: UsageRepository,
+}
+
+impl UsageRecordingNotifier {
+    pub fn new(inner: Arc<dyn AlertNotifier>, usage_repo: UsageRepository) -> Self {
+        Self { inner, usage_repo }
+    }
+}
+
+#[async_trait]
+impl AlertNotifier for UsageRecordingNotifier {
+    async fn send_alert(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        consecutive_failures: u32,
+    ) -> Result<()> {
+        self.inner
+            .send_alert(job_id, job_name, consecutive_failures)
+            .await?;
+        record_notification(&self.usage_repo, *job_id, NotificationKind::Alert).await;
+        Ok(())
+    }
+
+    async fn send_owned_alert(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        consecutive_failures: u32,
+        owner: Option<&JobOwner>,
+        remediation: Option<&FailureNote>,
+    ) -> Result<()> {
+        self.inner
+            .send_owned_alert(job_id, job_name, consecutive_failures, owner, remediation)
+            .await?;
+        record_notification(&self.usage_repo, *job_id, NotificationKind::Alert).await;
+        Ok(())
+    }
+
+    async fn send_sunset_notice(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        sunset_at: DateTime<Utc>,
+        reason: Option<&str>,
+        owner: Option<&JobOwner>,
+    ) -> Result<()> {
+        self.inner
+            .send_sunset_notice(job_id, job_name, sunset_at, reason, owner)
+            .await?;
+        record_notification(&self.usage_repo, *job_id, NotificationKind::SunsetNotice).await;
+        Ok(())
+    }
+
+    /// Not tied to a job, so not billed to a tenant
+    async fn send_clock_skew_alert(
+        &self,
+        source: &str,
+        offset_ms: i64,
+        max_skew_ms: u64,
+    ) -> Result<()> {
+        self.inner
+            .send_clock_skew_alert(source, offset_ms, max_skew_ms)
+            .await
+    }
+
+    /// Not tied to a job, so not billed to a tenant
+    async fn send_expiry_warning(
+        &self,
+        expiring: &[CredentialExpiry],
+        warn_days: u32,
+    ) -> Result<()> {
+        self.inner.send_expiry_warning(expiring, warn_days).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_bounds_cover_the_calendar_month() {
+        let month = UsageMonth::parse("2026-02").unwrap();
+        assert_eq!(month.to_string(), "2026-02");
+        assert_eq!(month.start().to_rfc3339(), "2026-02-01T00:00:00+00:00");
+        assert_eq!(month.end().to_rfc3339(), "2026-03-01T00:00:00+00:00");
+
+        let december = UsageMonth::parse("2026-12").unwrap();
+        assert_eq!(december.end().to_rfc3339(), "2027-01-01T00:00:00+00:00");
+
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 8, 30, 0).unwrap();
+        assert_eq!(UsageMonth::containing(now).to_string(), "2026-10");
+    }
+
+    #[test]
+    fn test_month_rejects_other_formats() {
+        for value in ["2026-13", "2026", "10-2026", "2026-10-01", ""] {
+            assert!(
+                UsageMonth::parse(value).is_err(),
+                "{} should be rejected",
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,372 @@
+// Outbound webhook delivery
+// Purpose: Persist signed outbound POSTs and deliver them from the worker with
+// exponential retries, dead-lettering those that run out of attempts
+
+use super::{sign_payload, DELIVERY_HEADER, EVENT_HEADER, SIGNATURE_HEADER};
+use crate::callback::{is_public_address, ExecutionCallback, EXECUTION_COMPLETED_EVENT};
+use crate::config::CallbackConfig;
+use crate::db::repositories::usage::UsageRepository;
+use crate::db::repositories::webhook_delivery::{AttemptOutcome, WebhookDeliveryRepository};
+use crate::errors::{DatabaseError, ExecutionError};
+use crate::models::{WebhookDelivery, WebhookDeliveryStatus};
+use crate::retry::{ExponentialBackoff, RetryStrategy};
+use crate::usage::{self, NotificationKind};
+use chrono::{DateTime, Utc};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
+
+/// Longest delay between two delivery attempts
+const MAX_RETRY_DELAY_SECS: u64 = 300;
+
+/// Deliveries claimed per poll
+const CLAIM_BATCH_SIZE: i64 = 50;
+
+/// A webhook to queue for delivery
+#[derive(Debug, Clone)]
+pub struct OutboundWebhook {
+    pub url: String,
+    pub event: String,
+    pub job_id: Option<Uuid>,
+    pub execution_id: Option<Uuid>,
+    pub payload: serde_json::Value,
+}
+
+/// Resolver for completion callbacks that drops internal addresses, so a callback
+/// host re-pointed at one after the trigger accepted it still can't reach it
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| is_public_address(address.ip()))
+                .collect();
+            if addresses.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addresses.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// WebhookDispatcher queues outbound webhooks and delivers them with retries
+pub struct WebhookDispatcher {
+    repo: WebhookDeliveryRepository,
+    /// Client for operator-configured notification endpoints
+    client: reqwest::Client,
+    /// Client for user-supplied callback URLs: public addresses only, no redirects
+    callback_client: reqwest::Client,
+    config: CallbackConfig,
+    backoff: ExponentialBackoff,
+    usage_repo: Option<UsageRepository>,
+}
+
+impl WebhookDispatcher {
+    pub fn new(
+        repo: WebhookDeliveryRepository,
+        config: CallbackConfig,
+    ) -> Result<Self, ExecutionError> {
+        let build_error = |e: reqwest::Error| {
+            ExecutionError::HttpRequestFailed(format!("Failed to build webhook client: {}", e))
+        };
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .build()
+            .map_err(build_error)?;
+        let callback_client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_seconds))
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(build_error)?;
+        let backoff = ExponentialBackoff::with_config(
+            config.retry_base_delay_seconds,
+            MAX_RETRY_DELAY_SECS,
+            0.1,
+        );
+
+        Ok(Self {
+            repo,
+            client,
+            callback_client,
+            config,
+            backoff,
+            usage_repo: None,
+        })
+    }
+
+    /// Record delivered completion callbacks for tenant usage reports
+    pub fn with_usage(mut self, usage_repo: UsageRepository) -> Self {
+        self.usage_repo = Some(usage_repo);
+        self
+    }
+
+    /// Endpoints that receive notification webhooks
+    pub fn notification_urls(&self) -> &[String] {
+        &self.config.notification_urls
+    }
+
+    /// Queue a webhook, returning its delivery ID; a worker delivers it on its next
+    /// poll
+    ///
+    /// A completion callback is queued once per execution, so queuing it again
+    /// returns the delivery already queued.
+    #[instrument(skip(self, webhook), fields(event = %webhook.event, url = %webhook.url))]
+    pub async fn enqueue(&self, webhook: OutboundWebhook) -> Result<Uuid, DatabaseError> {
+        let now = Utc::now();
+        let delivery = WebhookDelivery {
+            id: Uuid::new_v4(),
+            endpoint_url: webhook.url,
+            event: webhook.event,
+            job_id: webhook.job_id,
+            execution_id: webhook.execution_id,
+            payload: webhook.payload,
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            next_attempt_at: now,
+            last_response_status: None,
+            last_error: None,
+            created_at: now,
+            delivered_at: None,
+        };
+        let delivery_id = self.repo.create(&delivery).await?;
+        if delivery_id == delivery.id {
+            info!(delivery_id = %delivery_id, "Webhook queued for delivery");
+        } else {
+            info!(delivery_id = %delivery_id, "Callback already queued for the execution");
+        }
+        Ok(delivery_id)
+    }
+
+    /// Deliver due webhooks every `callbacks.poll_interval_seconds`
+    pub fn spawn(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(self.config.poll_interval_seconds));
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.deliver_due().await {
+                    error!(error = %e, "Failed to claim due webhook deliveries");
+                }
+            }
+        });
+        info!("Webhook delivery started");
+    }
+
+    /// Attempt every delivery that is due, returning how many were attempted
+    ///
+    /// The batch is attempted concurrently, so every attempt ends within the lease.
+    pub async fn deliver_due(&self) -> Result<usize, DatabaseError> {
+        let now = Utc::now();
+        // Long enough for one attempt; a worker that dies mid-attempt releases it then
+        let lease_id = Uuid::new_v4();
+        let lease_until = now + chrono::Duration::seconds(self.config.timeout_seconds as i64 + 30);
+        let deliveries = self
+            .repo
+            .claim_due(now, lease_id, lease_until, CLAIM_BATCH_SIZE)
+            .await?;
+
+        let count = deliveries.len();
+        futures::future::join_all(
+            deliveries
+                .iter()
+                .map(|delivery| self.attempt(delivery, lease_id)),
+        )
+        .await;
+        Ok(count)
+    }
+
+    /// POST a delivery once and record the outcome
+    #[instrument(skip(self, delivery), fields(delivery_id = %delivery.id, event = %delivery.event, url = %delivery.endpoint_url))]
+    async fn attempt(&self, delivery: &WebhookDelivery, lease_id: Uuid) {
+        let attempt = delivery.attempts + 1;
+        let body = match serde_json::to_vec(&delivery.payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!(error = %e, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+        let signature = sign_payload(&body, &self.config.signing_secret);
+
+        let started = Instant::now();
+        let (response_status, failure) = match self.client_for(delivery) {
+            Err(reason) => (None, Some((false, reason))),
+            Ok(client) => match client
+                .post(&delivery.endpoint_url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(EVENT_HEADER, &delivery.event)
+                .header(DELIVERY_HEADER, delivery.id.to_string())
+                .body(body)
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    (Some(response.status().as_u16() as i32), None)
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    (
+                        Some(status.as_u16() as i32),
+                        Some((
+                            is_retryable_status(status),
+                            format!("endpoint returned {}", status),
+                        )),
+                    )
+                }
+                Err(e) => (None, Some((true, format!("request failed: {}", e)))),
+            },
+        };
+        let duration_ms = started.elapsed().as_millis() as i64;
+
+        let outcome = match failure {
+            None => {
+                info!(attempt, "Webhook delivered");
+                AttemptOutcome {
+                    attempt,
+                    response_status,
+                    error: None,
+                    duration_ms,
+                    status: WebhookDeliveryStatus::Delivered,
+                    next_attempt_at: None,
+                }
+            }
+            Some((retryable, reason)) => {
+                let next_attempt_at = retryable
+                    .then(|| {
+                        next_attempt_at(
+                            &self.backoff,
+                            self.config.max_attempts,
+                            attempt,
+                            Utc::now(),
+                        )
+                    })
+                    .flatten();
+                match next_attempt_at {
+                    Some(at) => {
+                        warn!(attempt, reason = %reason, next_attempt_at = %at, "Webhook delivery failed, will retry")
+                    }
+                    None => {
+                        error!(attempt, reason = %reason, "Webhook delivery failed, dead-lettered")
+                    }
+                }
+                AttemptOutcome {
+                    attempt,
+                    response_status,
+                    error: Some(reason),
+                    duration_ms,
+                    status: if next_attempt_at.is_some() {
+                        WebhookDeliveryStatus::Pending
+                    } else {
+                        WebhookDeliveryStatus::DeadLetter
+                    },
+                    next_attempt_at,
+                }
+            }
+        };
+
+        match self
+            .repo
+            .record_attempt(delivery.id, lease_id, &outcome)
+            .await
+        {
+            Ok(true) if outcome.status == WebhookDeliveryStatus::Delivered => {
+                self.record_usage(delivery).await
+            }
+            Ok(true) => {}
+            Ok(false) => warn!("Webhook delivery lease lost, attempt not recorded"),
+            Err(e) => error!(error = %e, "Failed to record webhook delivery attempt"),
+        }
+    }
+
+    /// Client to deliver with; callbacks naming an internal IP literal are refused,
+    /// as the public-only resolver never sees those
+    fn client_for(&self, delivery: &WebhookDelivery) -> Result<&reqwest::Client, String> {
+        if delivery.event != EXECUTION_COMPLETED_EVENT {
+            return Ok(&self.client);
+        }
+        ExecutionCallback {
+            url: delivery.endpoint_url.clone(),
+            outputs: Vec::new(),
+        }
+        .validate()
+        .map_err(|e| format!("callback URL refused: {}", e))?;
+        Ok(&self.callback_client)
+    }
+
+    async fn record_usage(&self, delivery: &WebhookDelivery) {
+        // Notifications are recorded when they are raised; only callbacks are billed here
+        if delivery.event != EXECUTION_COMPLETED_EVENT {
+            return;
+        }
+        if let (Some(usage_repo), Some(job_id)) = (&self.usage_repo, delivery.job_id) {
+            usage::record_notification(usage_repo, job_id, NotificationKind::Callback).await;
+        }
+    }
+}
+
+/// When to retry after failed attempt number `attempt`, or None once out of attempts
+pub fn next_attempt_at(
+    backoff: &ExponentialBackoff,
+    max_attempts: u32,
+    attempt: i32,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    if attempt >= max_attempts as i32 {
+        return None;
+    }
+    let delay = backoff
+        .next_delay(attempt.max(1) as u32 - 1)
+        .unwrap_or(Duration::from_secs(MAX_RETRY_DELAY_SECS));
+    Some(now + chrono::Duration::from_std(delay).unwrap_or_default())
+}
+
+/// Whether a failed response is worth retrying: 408, 429 and 5xx are, other 4xx are not
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_only_transient_statuses_are_retried() {
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(StatusCode::REQUEST_TIMEOUT));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::GONE));
+    }
+
+    #[test]
+    fn test_retries_back_off_until_attempts_run_out() {
+        let backoff = ExponentialBackoff::with_config(5, MAX_RETRY_DELAY_SECS, 0.0);
+        let now = Utc::now();
+
+        assert_eq!(
+            next_attempt_at(&backoff, 3, 1, now),
+            Some(now + chrono::Duration::seconds(5))
+        );
+        assert_eq!(
+            next_attempt_at(&backoff, 3, 2, now),
+            Some(now + chrono::Duration::seconds(15))
+        );
+        assert_eq!(next_attempt_at(&backoff, 3, 3, now), None);
+
+        // Long backoffs are capped
+        assert_eq!(
+            next_attempt_at(&backoff, 10, 6, now),
+            Some(now + chrono::Duration::seconds(300))
+        );
+    }
+}
@@ -1,8 +1,27 @@
+// Webhooks: inbound trigger URLs and signatures, and outbound signed delivery
+// (completion callbacks and notification webhooks)
+
+pub mod delivery;
+pub mod notifier;
+
 use crate::errors::ValidationError;
+use hmac::{Hmac, Mac};
 use rand::Rng;
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
+pub use delivery::{OutboundWebhook, WebhookDispatcher};
+pub use notifier::WebhookAlertNotifier;
+
+/// Header carrying the hex-encoded HMAC-SHA256 of an outbound request body
+pub const SIGNATURE_HEADER: &str = "X-Cron-Signature";
+
+/// Header naming the outbound event
+pub const EVENT_HEADER: &str = "X-Cron-Event";
+
+/// Header carrying the delivery ID, constant across delivery retries
+pub const DELIVERY_HEADER: &str = "X-Cron-Delivery";
+
 /// Generate a unique webhook URL path
 /// Requirements: 16.1 - Generate unique webhook URLs for jobs
 ///
@@ -35,6 +54,16 @@ pub fn generate_webhook_secret() -> String {
     hex::encode(random_bytes)
 }
 
+/// Hex-encoded HMAC-SHA256 signature of an outbound webhook body
+///
+/// Receivers can verify it with [`validate_webhook_signature`].
+pub fn sign_payload(body: &[u8], secret: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
 /// Validate HMAC-SHA256 signature for webhook request
 /// Requirements: 16.7, 16.8 - Validate webhook signatures
 ///
@@ -52,8 +81,6 @@ pub fn validate_webhook_signature(
     signature: &str,
     secret: &str,
 ) -> Result<bool, ValidationError> {
-    use hmac::{Hmac, Mac};
-
     type HmacSha256 = Hmac<Sha256>;
 
     // Create HMAC instance with secret key
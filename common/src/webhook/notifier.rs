@@ -0,0 +1,168 @@
+// Notification webhooks
+// Purpose: Send failure alerts, sunset notices, clock skew alerts and credential expiry
+// warnings to the configured notification endpoints through the webhook delivery queue
+
+use super::delivery::{OutboundWebhook, WebhookDispatcher};
+use crate::models::{CredentialExpiry, FailureNote, JobOwner};
+use crate::telemetry::AlertNotifier;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Event name for consecutive-failure alerts
+pub const JOB_FAILING_EVENT: &str = "job.failing";
+
+/// Event name for sunset notices
+pub const JOB_SUNSET_EVENT: &str = "job.sunset";
+
+/// Event name for clock skew alerts
+pub const CLOCK_SKEW_EVENT: &str = "clock.skew";
+
+/// Event name for credential expiry warnings
+pub const CREDENTIAL_EXPIRING_EVENT: &str = "credential.expiring";
+
+/// Alert notifier wrapper that also queues each notification as a webhook
+pub struct WebhookAlertNotifier {
+    inner: Arc<dyn AlertNotifier>,
+    dispatcher: Arc<WebhookDispatcher>,
+}
+
+impl WebhookAlertNotifier {
+    pub fn new(inner: Arc<dyn AlertNotifier>, dispatcher: Arc<WebhookDispatcher>) -> Self {
+        Self { inner, dispatcher }
+    }
+
+    /// Queue the event for every notification endpoint
+    ///
+    /// Queueing failures are logged rather than returned, so they never mask the
+    /// wrapped notifier's result.
+    async fn enqueue(&self, event: &str, job_id: Option<Uuid>, payload: serde_json::Value) {
+        for url in self.dispatcher.notification_urls() {
+            let webhook = OutboundWebhook {
+                url: url.clone(),
+                event: event.to_string(),
+                job_id,
+                execution_id: None,
+                payload: payload.clone(),
+            };
+            if let Err(e) = self.dispatcher.enqueue(webhook).await {
+                warn!(event = event, url = %url, error = %e, "Failed to queue notification webhook");
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AlertNotifier for WebhookAlertNotifier {
+    async fn send_alert(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        consecutive_failures: u32,
+    ) -> Result<()> {
+        self.send_owned_alert(job_id, job_name, consecutive_failures, None, None)
+            .await
+    }
+
+    async fn send_owned_alert(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        consecutive_failures: u32,
+        owner: Option<&JobOwner>,
+        remediation: Option<&FailureNote>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .send_owned_alert(job_id, job_name, consecutive_failures, owner, remediation)
+            .await;
+        self.enqueue(
+            JOB_FAILING_EVENT,
+            Some(*job_id),
+            serde_json::json!({
+                "event": JOB_FAILING_EVENT,
+                "job_id": job_id,
+                "job_name": job_name,
+                "consecutive_failures": consecutive_failures,
+                "owner": owner,
+                "remediation": remediation,
+            }),
+        )
+        .await;
+        result
+    }
+
+    async fn send_sunset_notice(
+        &self,
+        job_id: &Uuid,
+        job_name: &str,
+        sunset_at: DateTime<Utc>,
+        reason: Option<&str>,
+        owner: Option<&JobOwner>,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .send_sunset_notice(job_id, job_name, sunset_at, reason, owner)
+            .await;
+        self.enqueue(
+            JOB_SUNSET_EVENT,
+            Some(*job_id),
+            serde_json::json!({
+                "event": JOB_SUNSET_EVENT,
+                "job_id": job_id,
+                "job_name": job_name,
+                "sunset_at": sunset_at,
+                "reason": reason,
+                "owner": owner,
+            }),
+        )
+        .await;
+        result
+    }
+
+    async fn send_clock_skew_alert(
+        &self,
+        source: &str,
+        offset_ms: i64,
+        max_skew_ms: u64,
+    ) -> Result<()> {
+        let result = self
+            .inner
+            .send_clock_skew_alert(source, offset_ms, max_skew_ms)
+            .await;
+        self.enqueue(
+            CLOCK_SKEW_EVENT,
+            None,
+            serde_json::json!({
+                "event": CLOCK_SKEW_EVENT,
+                "source": source,
+                "offset_ms": offset_ms,
+                "max_skew_ms": max_skew_ms,
+            }),
+        )
+        .await;
+        result
+    }
+
+    async fn send_expiry_warning(
+        &self,
+        expiring: &[CredentialExpiry],
+        warn_days: u32,
+    ) -> Result<()> {
+        let result = self.inner.send_expiry_warning(expiring, warn_days).await;
+        self.enqueue(
+            CREDENTIAL_EXPIRING_EVENT,
+            None,
+            serde_json::json!({
+                "event": CREDENTIAL_EXPIRING_EVENT,
+                "warn_days": warn_days,
+                "credentials": expiring,
+            }),
+        )
+        .await;
+        result
+    }
+}
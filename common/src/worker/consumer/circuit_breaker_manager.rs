@@ -2,13 +2,15 @@
 // Requirements: 4.5 - Circuit breaker pattern for external system failures
 
 use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use crate::worker::registry::CircuitBreakerStatus;
 use std::collections::HashMap;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc, RwLock};
 
 /// Circuit breaker manager handles creation and retrieval of circuit breakers
 pub struct CircuitBreakerManager {
     breakers: RwLock<HashMap<String, CircuitBreaker>>,
     config: CircuitBreakerConfig,
+    recoveries: Option<mpsc::UnboundedSender<String>>,
 }
 
 impl CircuitBreakerManager {
@@ -17,9 +19,16 @@ impl CircuitBreakerManager {
         Self {
             breakers: RwLock::new(HashMap::new()),
             config,
+            recoveries: None,
         }
     }
 
+    /// Send a breaker's target to `recoveries` whenever it closes after being open
+    pub fn with_recovery_notifier(mut self, recoveries: mpsc::UnboundedSender<String>) -> Self {
+        self.recoveries = Some(recoveries);
+        self
+    }
+
     /// Get or create a circuit breaker for a target
     pub async fn get_or_create(&self, target: &str) -> CircuitBreaker {
         // Check if circuit breaker exists
@@ -38,8 +47,26 @@ impl CircuitBreakerManager {
             return cb.clone();
         }
 
-        let cb = CircuitBreaker::new(target, self.config.clone());
+        let mut cb = CircuitBreaker::new(target, self.config.clone());
+        if let Some(recoveries) = &self.recoveries {
+            cb = cb.with_recovery_notifier(recoveries.clone());
+        }
         breakers.insert(target.to_string(), cb.clone());
         cb
     }
+
+    /// State of every circuit breaker, by target
+    pub async fn statuses(&self) -> Vec<CircuitBreakerStatus> {
+        let breakers = self.breakers.read().await;
+        let mut statuses = Vec::with_capacity(breakers.len());
+        for (target, cb) in breakers.iter() {
+            statuses.push(CircuitBreakerStatus {
+                target: target.clone(),
+                state: cb.get_state().await,
+                failure_count: cb.get_failure_count().await,
+            });
+        }
+        statuses.sort_by(|a, b| a.target.cmp(&b.target));
+        statuses
+    }
 }
@@ -0,0 +1,171 @@
+// Job definition cache - keeps parsed job definitions in the worker
+// High-frequency jobs would otherwise load and parse the same definition from storage
+// on every message
+
+use crate::models::Job;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+/// Subject the API publishes on when a job is updated or deleted
+///
+/// Kept outside `jobs.>`, which the job stream captures.
+pub fn job_changed_subject(job_id: Uuid) -> String {
+    format!("job_changes.{}", job_id)
+}
+
+struct CachedJob {
+    updated_at: DateTime<Utc>,
+    definition: Job,
+    last_used: Instant,
+}
+
+/// Parsed job definitions keyed by job id and `updated_at`
+///
+/// An entry is only served while the job row still has the `updated_at` it was loaded
+/// with, so a missed invalidation can't hand out a stale definition.
+pub struct JobDefinitionCache {
+    entries: RwLock<HashMap<Uuid, CachedJob>>,
+    max_entries: usize,
+}
+
+impl JobDefinitionCache {
+    /// Create a cache holding up to `max_entries` definitions; 0 disables caching
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            entries: RwLock::new(HashMap::new()),
+            max_entries,
+        }
+    }
+
+    /// Definition loaded for this version of the job, if cached
+    pub async fn get(&self, job_id: Uuid, updated_at: DateTime<Utc>) -> Option<Job> {
+        let mut entries = self.entries.write().await;
+        let entry = entries.get_mut(&job_id)?;
+        if entry.updated_at != updated_at {
+            entries.remove(&job_id);
+            return None;
+        }
+        entry.last_used = Instant::now();
+        Some(entry.definition.clone())
+    }
+
+    /// Cache a definition, evicting the least recently used one when full
+    pub async fn insert(&self, job_id: Uuid, updated_at: DateTime<Utc>, definition: Job) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.write().await;
+        if entries.len() >= self.max_entries && !entries.contains_key(&job_id) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(id, _)| *id);
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            job_id,
+            CachedJob {
+                updated_at,
+                definition,
+                last_used: Instant::now(),
+            },
+        );
+    }
+
+    /// Drop a job's cached definition
+    pub async fn invalidate(&self, job_id: Uuid) {
+        if self.entries.write().await.remove(&job_id).is_some() {
+            debug!(job_id = %job_id, "Job definition evicted from cache");
+        }
+    }
+
+    /// Evict definitions as the API reports job changes
+    pub fn spawn_invalidation(self: std::sync::Arc<Self>, client: async_nats::Client) {
+        tokio::spawn(async move {
+            let mut subscriber = match client.subscribe("job_changes.*").await {
+                Ok(subscriber) => subscriber,
+                Err(e) => {
+                    error!(error = %e, "Failed to subscribe to job changes, cache relies on updated_at only");
+                    return;
+                }
+            };
+            info!("Subscribed to job changes for definition cache invalidation");
+
+            while let Some(msg) = subscriber.next().await {
+                match msg
+                    .subject
+                    .rsplit('.')
+                    .next()
+                    .and_then(|id| id.parse::<Uuid>().ok())
+                {
+                    Some(job_id) => self.invalidate(job_id).await,
+                    None => warn!(subject = %msg.subject, "Ignoring malformed job change subject"),
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(name: &str) -> Job {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "name": name,
+            "steps": [],
+            "triggers": { "scheduled": false, "manual": true },
+            "timeout_seconds": 300,
+            "max_retries": 3,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cache_hits_only_matching_version() {
+        let cache = JobDefinitionCache::new(2);
+        let job_id = Uuid::new_v4();
+        let loaded_at = Utc::now();
+
+        cache.insert(job_id, loaded_at, job("nightly")).await;
+        assert_eq!(cache.get(job_id, loaded_at).await.unwrap().name, "nightly");
+
+        // The job was updated since it was cached
+        let updated_at = loaded_at + chrono::Duration::seconds(1);
+        assert!(cache.get(job_id, updated_at).await.is_none());
+        assert!(cache.get(job_id, loaded_at).await.is_none());
+
+        cache.insert(job_id, updated_at, job("nightly-v2")).await;
+        cache.invalidate(job_id).await;
+        assert!(cache.get(job_id, updated_at).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cache_evicts_least_recently_used() {
+        let cache = JobDefinitionCache::new(2);
+        let now = Utc::now();
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        cache.insert(a, now, job("a")).await;
+        cache.insert(b, now, job("b")).await;
+        assert!(cache.get(a, now).await.is_some());
+        cache.insert(c, now, job("c")).await;
+
+        assert!(cache.get(a, now).await.is_some());
+        assert!(cache.get(b, now).await.is_none());
+        assert!(cache.get(c, now).await.is_some());
+
+        let disabled = JobDefinitionCache::new(0);
+        disabled.insert(a, now, job("a")).await;
+        assert!(disabled.get(a, now).await.is_none());
+    }
+}
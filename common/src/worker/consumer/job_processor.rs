@@ -1,33 +1,126 @@
 // Job processor - handles job message processing and execution orchestration
 // Requirements: 13.4, 13.7, 13.8 - Load job definition from storage and execute multi-step jobs
 
+use crate::callback::{CallbackPayload, CallbackSender, ExecutionCallback};
+use crate::connection_profile;
+use crate::db::repositories::connection_profile::ConnectionProfileRepository;
 use crate::db::repositories::execution::ExecutionRepository;
+use crate::db::repositories::failure_note::FailureNoteRepository;
 use crate::db::repositories::job::JobRepository;
+use crate::errors::{DatabaseError, ExecutionError};
 use crate::executor::JobExecutor;
-use crate::models::{ExecutionStatus, Job, JobContext, JobExecution, TriggerSource};
-use crate::queue::JobMessage;
+use crate::fingerprint;
+use crate::lock::{DistributedLock, LockGuard};
+use crate::models::{
+    CollisionPolicy, ConcurrencyOverflow, ExecutionStatus, Job, JobContext, JobExecution,
+    TriggerSource,
+};
+use crate::parameter_matrix::matrix_group_id;
+use crate::queue::{JobMessage, JobPublisher};
+use crate::resource_guard::ResourceGuard;
 use crate::retry::RetryStrategy;
+use crate::semaphore::{DistributedSemaphore, SemaphorePermit};
+use crate::step_env::SecretProvider;
 use crate::storage::StorageService;
+use crate::telemetry::{self, should_trigger_alert, AlertNotifier};
 use crate::worker::context::ContextManager;
 use crate::worker::reference::ReferenceResolver;
 use chrono::Utc;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info, instrument, warn};
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, warn};
 
-use super::{CircuitBreakerManager, StepExecutor};
+use super::step_executor::DeferredRetry;
+use super::{CircuitBreakerManager, JobDefinitionCache, StepExecutor};
+
+/// Re-queues step retries with a long backoff instead of sleeping in the worker
+#[derive(Clone)]
+pub struct DeferredRetries {
+    pub publisher: Arc<dyn JobPublisher>,
+    /// Shortest backoff handed back to the queue
+    pub min_delay: Duration,
+}
+
+/// Puts runs of non-concurrent jobs back on the queue while a previous run is active
+#[derive(Clone)]
+pub struct CollisionHold {
+    pub publisher: Arc<dyn JobPublisher>,
+    /// How long a held run waits before checking the previous run again
+    pub recheck_interval: Duration,
+}
+
+/// Runs executions of `strict_ordering` jobs one at a time, oldest first
+///
+/// An execution runs while holding the job's sequence lock and only once every
+/// earlier execution has finished; otherwise it is put back on the queue.
+#[derive(Clone)]
+pub struct SequenceLock {
+    pub lock: Arc<dyn DistributedLock>,
+    pub publisher: Arc<dyn JobPublisher>,
+    /// How long a held execution waits before checking its turn again
+    pub recheck_interval: Duration,
+    /// How long an earlier execution may go without a heartbeat, start or creation
+    /// before it is taken as lost and stops holding back the later ones
+    pub stale_after: Duration,
+}
+
+/// Enforces the `concurrency_limit` of jobs across workers
+///
+/// A running execution holds one of the job's slots in a Redis semaphore. An
+/// execution finding every slot taken is put back on the queue as queued, or ends as
+/// rejected, depending on the job's overflow setting.
+#[derive(Clone)]
+pub struct ConcurrencyLimiter {
+    pub semaphore: Arc<dyn DistributedSemaphore>,
+    pub publisher: Arc<dyn JobPublisher>,
+    /// How long a queued execution waits before checking for a free slot again
+    pub recheck_interval: Duration,
+}
+
+/// Time the sequence lock and concurrency slots outlive the job's timeout, covering
+/// finalization
+const SEQUENCE_LOCK_MARGIN: Duration = Duration::from_secs(60);
 
 /// Job processor handles the complete job execution lifecycle
 pub struct JobProcessor {
     job_repo: Arc<JobRepository>,
     execution_repo: Arc<ExecutionRepository>,
-    _context_manager: Arc<dyn ContextManager>,
+    failure_note_repo: Arc<FailureNoteRepository>,
+    connection_profile_repo: Arc<ConnectionProfileRepository>,
+    secrets: Arc<dyn SecretProvider>,
+    context_manager: Arc<dyn ContextManager>,
     storage_service: Arc<dyn StorageService>,
     http_executor: Arc<dyn JobExecutor>,
     database_executor: Arc<dyn JobExecutor>,
     file_executor: Arc<dyn JobExecutor>,
+    sftp_executor: Arc<dyn JobExecutor>,
+    graphql_executor: Arc<dyn JobExecutor>,
+    email_executor: Arc<dyn JobExecutor>,
+    notification_executor: Arc<dyn JobExecutor>,
+    mqtt_executor: Arc<dyn JobExecutor>,
+    object_storage_executor: Arc<dyn JobExecutor>,
+    container_executor: Arc<dyn JobExecutor>,
+    wasm_executor: Arc<dyn JobExecutor>,
+    script_executor: Arc<dyn JobExecutor>,
+    pdf_executor: Arc<dyn JobExecutor>,
+    transform_executor: Arc<dyn JobExecutor>,
+    validate_executor: Arc<dyn JobExecutor>,
+    checksum_executor: Arc<dyn JobExecutor>,
+    system_executor: Arc<dyn JobExecutor>,
     retry_strategy: Arc<dyn RetryStrategy>,
     circuit_breaker_manager: Arc<CircuitBreakerManager>,
     reference_resolver: Arc<ReferenceResolver>,
+    alert_notifier: Arc<dyn AlertNotifier>,
+    callback_sender: Arc<CallbackSender>,
+    heartbeat_interval: Duration,
+    resource_guard: Arc<ResourceGuard>,
+    job_cache: Arc<JobDefinitionCache>,
+    deferred_retries: Option<DeferredRetries>,
+    collision_hold: Option<CollisionHold>,
+    sequence_lock: Option<SequenceLock>,
+    concurrency_limiter: Option<ConcurrencyLimiter>,
+    step_locks: Option<Arc<dyn DistributedLock>>,
     nats_client: Option<async_nats::Client>,
 }
 
@@ -37,27 +130,81 @@ impl JobProcessor {
     pub fn new(
         job_repo: Arc<JobRepository>,
         execution_repo: Arc<ExecutionRepository>,
+        failure_note_repo: Arc<FailureNoteRepository>,
+        connection_profile_repo: Arc<ConnectionProfileRepository>,
+        secrets: Arc<dyn SecretProvider>,
         context_manager: Arc<dyn ContextManager>,
         storage_service: Arc<dyn StorageService>,
         http_executor: Arc<dyn JobExecutor>,
         database_executor: Arc<dyn JobExecutor>,
         file_executor: Arc<dyn JobExecutor>,
+        sftp_executor: Arc<dyn JobExecutor>,
+        graphql_executor: Arc<dyn JobExecutor>,
+        email_executor: Arc<dyn JobExecutor>,
+        notification_executor: Arc<dyn JobExecutor>,
+        mqtt_executor: Arc<dyn JobExecutor>,
+        object_storage_executor: Arc<dyn JobExecutor>,
+        container_executor: Arc<dyn JobExecutor>,
+        wasm_executor: Arc<dyn JobExecutor>,
+        script_executor: Arc<dyn JobExecutor>,
+        pdf_executor: Arc<dyn JobExecutor>,
+        transform_executor: Arc<dyn JobExecutor>,
+        validate_executor: Arc<dyn JobExecutor>,
+        checksum_executor: Arc<dyn JobExecutor>,
+        system_executor: Arc<dyn JobExecutor>,
         retry_strategy: Arc<dyn RetryStrategy>,
         circuit_breaker_manager: Arc<CircuitBreakerManager>,
         reference_resolver: Arc<ReferenceResolver>,
+        alert_notifier: Arc<dyn AlertNotifier>,
+        callback_sender: Arc<CallbackSender>,
+        heartbeat_interval: Duration,
+        resource_guard: Arc<ResourceGuard>,
+        job_cache: Arc<JobDefinitionCache>,
+        deferred_retries: Option<DeferredRetries>,
+        collision_hold: Option<CollisionHold>,
+        sequence_lock: Option<SequenceLock>,
+        concurrency_limiter: Option<ConcurrencyLimiter>,
+        step_locks: Option<Arc<dyn DistributedLock>>,
         nats_client: Option<async_nats::Client>,
     ) -> Self {
         Self {
             job_repo,
             execution_repo,
-            _context_manager: context_manager,
+            failure_note_repo,
+            connection_profile_repo,
+            secrets,
+            context_manager,
             storage_service,
             http_executor,
             database_executor,
             file_executor,
+            sftp_executor,
+            graphql_executor,
+            email_executor,
+            notification_executor,
+            mqtt_executor,
+            object_storage_executor,
+            container_executor,
+            wasm_executor,
+            script_executor,
+            pdf_executor,
+            transform_executor,
+            validate_executor,
+            checksum_executor,
+            system_executor,
             retry_strategy,
             circuit_breaker_manager,
             reference_resolver,
+            alert_notifier,
+            callback_sender,
+            heartbeat_interval,
+            resource_guard,
+            job_cache,
+            deferred_retries,
+            collision_hold,
+            sequence_lock,
+            concurrency_limiter,
+            step_locks,
             nats_client,
         }
     }
@@ -78,20 +225,67 @@ impl JobProcessor {
         }
 
         // Load job metadata and definition
-        let (_job_metadata, job_definition) = self.load_job(&job_message).await?;
+        let (job_metadata, job_definition) = self.load_job(&job_message).await?;
 
         // Create or load execution record
         let mut execution = self.create_or_load_execution(&job_message).await?;
 
-        // Update status to Running
-        execution.status = ExecutionStatus::Running;
-        execution.started_at = Some(Utc::now());
-        if let Err(e) = self.execution_repo.update(&execution).await {
-            error!(error = %e, "Failed to update execution status to Running");
+        // A scheduled run that missed its start window must not run late
+        if self
+            .expire_if_late(&job_metadata, &job_definition, &mut execution)
+            .await
+        {
+            return Ok(());
         }
 
-        self.publish_status_change(execution.id, execution.job_id, "running")
-            .await;
+        // A run queued behind a previous run of the job waits until that one is done
+        if self
+            .hold_for_previous_run(&job_message, &job_metadata, &execution)
+            .await?
+        {
+            return Ok(());
+        }
+        let waits_for_previous_run = self.waits_for_previous_run(&job_metadata, &execution);
+
+        // An execution of a strictly ordered job waits for every earlier one; the
+        // sequence lock is held until this execution is done with
+        let _sequence_guard = match self
+            .take_sequence_turn(&job_message, &job_metadata, &execution)
+            .await?
+        {
+            Some(Some(guard)) => Some(guard),
+            Some(None) => return Ok(()),
+            None => None,
+        };
+
+        // A job at its concurrency limit queues or rejects the execution; the slot is
+        // held until this execution is done with
+        let _concurrency_permit = match self
+            .take_concurrency_slot(&job_message, &job_metadata, &mut execution)
+            .await?
+        {
+            Some(Some(permit)) => Some(permit),
+            Some(None) => return Ok(()),
+            None => None,
+        };
+
+        // Update status to Running, announcing it once the change is committed
+        let started = if waits_for_previous_run {
+            match self
+                .start_unless_previous_run(&job_message, &job_metadata, &mut execution)
+                .await?
+            {
+                Some(started) => started,
+                None => return Ok(()),
+            }
+        } else {
+            self.transition(&mut execution, ExecutionStatus::Running)
+                .await
+        };
+        if started {
+            self.publish_status_change(execution.id, execution.job_id, "running")
+                .await;
+        }
 
         // Initialize or load job context
         let mut context = self
@@ -99,25 +293,87 @@ impl JobProcessor {
             .await?;
 
         // Execute job steps
-        let step_executor = StepExecutor::new(
+        let mut step_executor = StepExecutor::new(
             Arc::clone(&self.http_executor),
             Arc::clone(&self.database_executor),
             Arc::clone(&self.file_executor),
+            Arc::clone(&self.sftp_executor),
+            Arc::clone(&self.graphql_executor),
+            Arc::clone(&self.email_executor),
+            Arc::clone(&self.notification_executor),
+            Arc::clone(&self.mqtt_executor),
+            Arc::clone(&self.object_storage_executor),
+            Arc::clone(&self.container_executor),
+            Arc::clone(&self.wasm_executor),
+            Arc::clone(&self.script_executor),
+            Arc::clone(&self.pdf_executor),
+            Arc::clone(&self.transform_executor),
+            Arc::clone(&self.validate_executor),
+            Arc::clone(&self.checksum_executor),
+            Arc::clone(&self.system_executor),
             Arc::clone(&self.storage_service),
             Arc::clone(&self.reference_resolver),
             Arc::clone(&self.circuit_breaker_manager),
             Arc::clone(&self.retry_strategy),
             Arc::clone(&self.execution_repo),
-        );
+        )
+        .with_heartbeats(self.heartbeat_interval, self.nats_client.clone())
+        .with_resource_meter(self.resource_guard.track(execution.id))
+        .with_secrets(Arc::clone(&self.secrets));
+        if let Some(deferred_retries) = &self.deferred_retries {
+            step_executor = step_executor.with_deferred_retries(deferred_retries.min_delay);
+        }
+        if let Some(step_locks) = &self.step_locks {
+            step_executor = step_executor.with_step_locks(Arc::clone(step_locks));
+        }
+        if let Some(attempt) = job_message.retry_attempt {
+            info!(
+                retry_attempt = attempt,
+                "Resuming execution at a queued step retry"
+            );
+            step_executor = step_executor.resume_retry(attempt);
+        }
 
-        let execution_result = step_executor
-            .execute_all_steps(&job_definition, &mut context, &mut execution)
-            .await;
+        // Steps naming a connection profile run against it as it is now
+        let execution_result = match self.apply_connection_profiles(&job_definition).await {
+            Ok(job) => {
+                step_executor
+                    .execute_all_steps(&job, &mut context, &mut execution)
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        let execution_result = match execution_result {
+            Err(e) => match e.downcast::<DeferredRetry>() {
+                Ok(retry) => {
+                    match self
+                        .requeue_retry(&job_metadata, &mut execution, &context, retry)
+                        .await
+                    {
+                        Ok(()) => return Ok(()),
+                        Err(e) => Err(e),
+                    }
+                }
+                Err(e) => Err(e),
+            },
+            result => result,
+        };
+
+        // State staged by the steps, such as their `set_state` or an incremental
+        // sync's watermark, is written only once every step succeeded
+        let execution_result = match execution_result {
+            Ok(()) => self.commit_pending_state(&mut context).await,
+            Err(e) => Err(e),
+        };
 
         // Update final execution status
-        let final_status = self
+        let committed = self
             .finalize_execution(&mut execution, execution_result)
             .await;
+        if committed {
+            self.dead_letter_at_dependency(&job_metadata, &job_definition, &mut execution)
+                .await;
+        }
 
         // Save final context to storage
         if let Err(e) = self.storage_service.store_context(&context).await {
@@ -126,12 +382,51 @@ impl JobProcessor {
             info!("Final job context saved to storage successfully");
         }
 
-        self.publish_status_change(execution.id, execution.job_id, final_status)
+        if committed {
+            self.publish_status_change(
+                execution.id,
+                execution.job_id,
+                &execution.status.to_string(),
+            )
             .await;
+            record_execution_metrics(&job_metadata, &execution);
+        }
+
+        if execution.status.is_failure() {
+            self.notify_consecutive_failures(&job_metadata, &execution)
+                .await;
+        }
+
+        if execution.status.is_terminal() {
+            self.dispatch_callback(&job_metadata, &execution, &context)
+                .await;
+        }
 
         Ok(())
     }
 
+    /// Write the state the steps staged to the job's state, all keys or none
+    async fn commit_pending_state(&self, context: &mut JobContext) -> Result<(), anyhow::Error> {
+        if context.pending_state.is_empty() {
+            return Ok(());
+        }
+
+        self.context_manager
+            .save_state(context.job_id, context.execution_id, &context.pending_state)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to commit staged job state");
+                anyhow::anyhow!("Failed to commit staged job state: {}", e)
+            })?;
+        info!(
+            keys = ?context.pending_state.keys().collect::<Vec<_>>(),
+            "Staged job state committed"
+        );
+        let committed = std::mem::take(&mut context.pending_state);
+        context.state.extend(committed);
+        Ok(())
+    }
+
     /// Check if job has already been executed (idempotency check)
     async fn check_idempotency(&self, job_message: &JobMessage) -> Result<bool, anyhow::Error> {
         match self
@@ -144,7 +439,9 @@ impl JobProcessor {
                 | ExecutionStatus::Failed
                 | ExecutionStatus::Timeout
                 | ExecutionStatus::DeadLetter
-                | ExecutionStatus::Cancelled => {
+                | ExecutionStatus::Cancelled
+                | ExecutionStatus::Expired
+                | ExecutionStatus::Rejected => {
                     info!(
                         existing_execution_id = %existing_execution.id,
                         status = ?existing_execution.status,
@@ -165,7 +462,7 @@ impl JobProcessor {
         }
     }
 
-    /// Load job metadata and definition from database and MinIO
+    /// Load job metadata from the database and the definition from cache or storage
     async fn load_job(&self, job_message: &JobMessage) -> Result<(Job, Job), anyhow::Error> {
         // Load job metadata from database
         let job_metadata = match self.job_repo.find_by_id(job_message.job_id).await {
@@ -186,6 +483,15 @@ impl JobProcessor {
             "Loaded job metadata from database"
         );
 
+        if let Some(job) = self
+            .job_cache
+            .get(job_metadata.id, job_metadata.updated_at)
+            .await
+        {
+            debug!(step_count = job.steps.len(), "Using cached job definition");
+            return Ok((job_metadata, job));
+        }
+
         // Load full job definition from storage
         let job_definition_json = match self
             .storage_service
@@ -217,9 +523,41 @@ impl JobProcessor {
             "Loaded and parsed job definition from storage"
         );
 
+        self.job_cache
+            .insert(job_metadata.id, job_metadata.updated_at, job.clone())
+            .await;
+
         Ok((job_metadata, job))
     }
 
+    /// The job with the connection profiles its steps name applied
+    async fn apply_connection_profiles(&self, job: &Job) -> Result<Job, anyhow::Error> {
+        let names: Vec<String> = connection_profile::referenced_connections(&job.steps)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        if names.is_empty() {
+            return Ok(job.clone());
+        }
+
+        let profiles: HashMap<_, _> = self
+            .connection_profile_repo
+            .find_by_names(&names)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to load connection profiles");
+                anyhow::anyhow!("Failed to load connection profiles: {}", e)
+            })?
+            .into_iter()
+            .map(|profile| (profile.name.clone(), profile))
+            .collect();
+
+        let mut job = job.clone();
+        connection_profile::apply_connection_profiles(&mut job.steps, &profiles)?;
+        debug!(connections = ?names, "Applied connection profiles");
+        Ok(job)
+    }
+
     /// Create new or load existing execution record
     async fn create_or_load_execution(
         &self,
@@ -265,7 +603,7 @@ impl JobProcessor {
         job: &Job,
         execution: &JobExecution,
     ) -> Result<JobContext, anyhow::Error> {
-        match self
+        let mut context = match self
             .storage_service
             .load_context(job.id, execution.id)
             .await
@@ -275,43 +613,566 @@ impl JobProcessor {
                     steps_completed = ctx.steps.len(),
                     "Loaded existing job context from storage"
                 );
-                Ok(ctx)
+                ctx
             }
             Err(_) => {
                 info!("Initializing new job context");
-                Ok(JobContext::new(execution.id, job.id))
+                JobContext::new(execution.id, job.id)
+            }
+        };
+
+        // Trigger variables take precedence over anything already in the context;
+        // re-applying them to a resumed context is harmless since they never change
+        let overrides = execution.variable_overrides();
+        if !overrides.is_empty() {
+            let mut names: Vec<&String> = overrides.keys().collect();
+            names.sort_unstable();
+            info!(variables = ?names, "Applying trigger variable overrides");
+            context.variables.extend(overrides);
+        }
+
+        // The job state may have changed since a resumed context was saved
+        context.state = self.context_manager.load_state(job.id).await?;
+
+        Ok(context)
+    }
+
+    /// Put the execution back on the queue to resume at a step retry after its backoff
+    ///
+    /// The execution waits as pending; only a failure to queue the retry fails it.
+    async fn requeue_retry(
+        &self,
+        job: &Job,
+        execution: &mut JobExecution,
+        context: &JobContext,
+        retry: DeferredRetry,
+    ) -> Result<(), anyhow::Error> {
+        let Some(deferred_retries) = &self.deferred_retries else {
+            return Err(retry.into());
+        };
+
+        if let Err(e) = self.storage_service.store_context(context).await {
+            error!(error = %e, "Failed to save job context before queueing retry");
+            return Err(anyhow::anyhow!("{} (context not saved: {})", retry, e));
+        }
+
+        let message = JobMessage::retry(execution, retry.attempt, retry.not_before)
+            .with_priority(job.priority);
+        if let Err(e) = deferred_retries
+            .publisher
+            .publish_delayed(&message, job.tenant_id.as_deref())
+            .await
+        {
+            error!(error = %e, "Failed to queue step retry");
+            return Err(anyhow::anyhow!("{} (retry not queued: {})", retry, e));
+        }
+
+        info!(
+            step_id = %retry.step_id,
+            retry_attempt = retry.attempt,
+            not_before = %retry.not_before,
+            "Step retry queued"
+        );
+
+        execution.error = Some(retry.to_string());
+        if self.transition(execution, ExecutionStatus::Pending).await {
+            self.publish_status_change(execution.id, execution.job_id, "pending")
+                .await;
+        }
+
+        Ok(())
+    }
+
+    /// Expire a scheduled run that hasn't started within its schedule's start window
+    ///
+    /// The window counts from the run's due time, or from when the scheduler created
+    /// the execution if that isn't recorded. Runs that already started, e.g. ones waiting on the queue for a step retry, are left alone.
+    async fn expire_if_late(
+        &self,
+        job_metadata: &Job,
+        job_definition: &Job,
+        execution: &mut JobExecution,
+    ) -> bool {
+        let Some(window) = job_definition
+            .schedule
+            .as_ref()
+            .and_then(|schedule| schedule.start_window())
+        else {
+            return false;
+        };
+        if execution.trigger_source != TriggerSource::Scheduled
+            || !matches!(
+                execution.status,
+                ExecutionStatus::Pending | ExecutionStatus::Queued
+            )
+            || execution.started_at.is_some()
+        {
+            return false;
+        }
+
+        let late_by = execution.start_delay(Utc::now());
+        if late_by <= window {
+            return false;
+        }
+
+        warn!(
+            late_by_seconds = late_by.num_seconds(),
+            valid_for_seconds = window.num_seconds(),
+            "Scheduled run missed its start window, expiring it"
+        );
+        execution.error = Some(format!(
+            "Not started within {}s of its scheduled time ({}s late)",
+            window.num_seconds(),
+            late_by.num_seconds()
+        ));
+        if self.transition(execution, ExecutionStatus::Expired).await {
+            self.publish_status_change(execution.id, execution.job_id, "expired")
+                .await;
+            record_execution_metrics(job_metadata, execution);
+        }
+        true
+    }
+
+    /// Whether the run waits behind previous runs of the job: it is a new run of a
+    /// non-concurrent job whose collision policy queues it
+    fn waits_for_previous_run(&self, job: &Job, execution: &JobExecution) -> bool {
+        self.collision_hold.is_some()
+            && !job.allow_concurrent
+            && job.collision_policy != CollisionPolicy::Skip
+            && execution.status == ExecutionStatus::Pending
+    }
+
+    /// Put the message back on the queue while a previous run of the job is active
+    ///
+    /// Only runs that `waits_for_previous_run` are held. Returns whether the message
+    /// was put back. The run checks again when it starts, see
+    /// `start_unless_previous_run`.
+    async fn hold_for_previous_run(
+        &self,
+        job_message: &JobMessage,
+        job: &Job,
+        execution: &JobExecution,
+    ) -> Result<bool, anyhow::Error> {
+        if !self.waits_for_previous_run(job, execution) {
+            return Ok(false);
+        }
+
+        let active = self
+            .execution_repo
+            .find_active(job.id)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to check for active runs: {}", e))?;
+        // Executions fanned out from the same run aren't previous runs of each other
+        let group = matrix_group_id(execution);
+        let Some(previous) = active.iter().find(|active| {
+            active.id != execution.id && (group.is_none() || matrix_group_id(active) != group)
+        }) else {
+            return Ok(false);
+        };
+
+        self.hold_behind(job_message, job, previous).await?;
+        Ok(true)
+    }
+
+    /// Move a run waiting behind previous runs to running, unless another worker
+    /// started one since `hold_for_previous_run` checked
+    ///
+    /// The check and the status change are one transaction, so two runs can't both
+    /// start. Returns `None` when the run was held back, otherwise whether the status
+    /// change was committed.
+    async fn start_unless_previous_run(
+        &self,
+        job_message: &JobMessage,
+        job: &Job,
+        execution: &mut JobExecution,
+    ) -> Result<Option<bool>, anyhow::Error> {
+        let mut started = execution.clone();
+        let previous_status = match started.transition_to(ExecutionStatus::Running) {
+            Ok(previous_status) => previous_status,
+            Err(e) => {
+                warn!(error = %e, "Execution status not changed");
+                return Ok(Some(false));
+            }
+        };
+
+        match self
+            .execution_repo
+            .start_unless_active(&started, &previous_status, matrix_group_id(execution))
+            .await
+        {
+            Ok(None) => {
+                *execution = started;
+                Ok(Some(true))
+            }
+            Ok(Some(previous)) => {
+                self.hold_behind(job_message, job, &previous).await?;
+                Ok(None)
+            }
+            // Stopped meanwhile; the usual transition reloads the stored status
+            Err(DatabaseError::VersionConflict(_)) => Ok(Some(
+                self.transition(execution, ExecutionStatus::Running).await,
+            )),
+            Err(e) => {
+                error!(error = %e, "Failed to persist execution status");
+                Ok(Some(false))
+            }
+        }
+    }
+
+    /// Put the message back on the queue to check again once `previous` may be done
+    async fn hold_behind(
+        &self,
+        job_message: &JobMessage,
+        job: &Job,
+        previous: &JobExecution,
+    ) -> Result<(), anyhow::Error> {
+        let Some(collision_hold) = &self.collision_hold else {
+            return Ok(());
+        };
+        let not_before = Utc::now()
+            + chrono::Duration::from_std(collision_hold.recheck_interval).unwrap_or_default();
+        let message = job_message.held_back(not_before);
+        collision_hold
+            .publisher
+            .publish_delayed(&message, job.tenant_id.as_deref())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to hold run behind the previous run: {}", e))?;
+
+        info!(
+            previous_execution_id = %previous.id,
+            previous_status = %previous.status,
+            collision_policy = %job.collision_policy,
+            holds = message.holds,
+            "Previous run still active, run held back"
+        );
+        Ok(())
+    }
+
+    /// Take the job's sequence lock for an execution of a `strict_ordering` job
+    ///
+    /// Returns `None` when the job isn't strictly ordered, `Some(None)` when the
+    /// message was put back because the lock is held or an earlier execution hasn't
+    /// finished, and the lock otherwise. Earlier executions waiting on the queue for
+    /// a step retry are pending, so they keep their place too, unless they have been
+    /// silent for the lock's `stale_after`.
+    async fn take_sequence_turn(
+        &self,
+        job_message: &JobMessage,
+        job: &Job,
+        execution: &JobExecution,
+    ) -> Result<Option<Option<LockGuard>>, anyhow::Error> {
+        let Some(sequence_lock) = &self.sequence_lock else {
+            return Ok(None);
+        };
+        if !job.strict_ordering {
+            return Ok(None);
+        }
+
+        let ttl = Duration::from_secs(job.timeout_seconds.max(0) as u64) + SEQUENCE_LOCK_MARGIN;
+        let guard = match sequence_lock
+            .lock
+            .acquire(&format!("strict_ordering:{}", job.id), ttl)
+            .await
+        {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                debug!(error = %e, "Sequence lock not acquired");
+                None
+            }
+        };
+
+        // Checked under the lock, so an earlier execution can't start in between
+        let active_since =
+            Utc::now() - chrono::Duration::from_std(sequence_lock.stale_after).unwrap_or_default();
+        let earlier = match &guard {
+            Some(_) => self
+                .execution_repo
+                .find_earlier_unfinished(job.id, execution.created_at, execution.id, active_since)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to check for earlier runs: {}", e))?,
+            None => None,
+        };
+        if guard.is_some() && earlier.is_none() {
+            return Ok(Some(guard));
+        }
+        drop(guard);
+
+        let not_before = Utc::now()
+            + chrono::Duration::from_std(sequence_lock.recheck_interval).unwrap_or_default();
+        let message = job_message.held_back(not_before);
+        sequence_lock
+            .publisher
+            .publish_delayed(&message, job.tenant_id.as_deref())
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to hold run behind earlier runs: {}", e))?;
+
+        info!(
+            earlier_execution_id = ?earlier.as_ref().map(|earlier| earlier.id),
+            holds = message.holds,
+            "Strictly ordered run not yet due, run held back"
+        );
+        Ok(Some(None))
+    }
+
+    /// Take a slot under the job's `concurrency_limit` for an execution
+    ///
+    /// Returns `None` when the job has no limit, `Some(None)` when every slot was taken
+    /// and the execution was queued or rejected, and the slot otherwise. A queued
+    /// execution is put back on the queue to check for a free slot again later.
+    async fn take_concurrency_slot(
+        &self,
+        job_message: &JobMessage,
+        job: &Job,
+        execution: &mut JobExecution,
+    ) -> Result<Option<Option<SemaphorePermit>>, anyhow::Error> {
+        let (Some(limiter), Some(limit)) = (&self.concurrency_limiter, &job.concurrency_limit)
+        else {
+            return Ok(None);
+        };
+
+        let ttl = Duration::from_secs(job.timeout_seconds.max(0) as u64) + SEQUENCE_LOCK_MARGIN;
+        let permit = limiter
+            .semaphore
+            .try_acquire(
+                &format!("concurrency:{}", job.id),
+                &execution.id.to_string(),
+                limit.max_concurrent,
+                ttl,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to take a concurrency slot: {}", e))?;
+        if permit.is_some() {
+            return Ok(Some(permit));
+        }
+
+        match limit.overflow {
+            ConcurrencyOverflow::Reject => {
+                warn!(
+                    max_concurrent = limit.max_concurrent,
+                    "Job at its concurrency limit, run rejected"
+                );
+                execution.error = Some(format!(
+                    "Rejected: {} executions of the job were already running",
+                    limit.max_concurrent
+                ));
+                if self.transition(execution, ExecutionStatus::Rejected).await {
+                    self.publish_status_change(execution.id, execution.job_id, "rejected")
+                        .await;
+                    record_execution_metrics(job, execution);
+                }
+            }
+            ConcurrencyOverflow::Queue => {
+                if execution.status != ExecutionStatus::Queued
+                    && self.transition(execution, ExecutionStatus::Queued).await
+                {
+                    self.publish_status_change(execution.id, execution.job_id, "queued")
+                        .await;
+                }
+
+                let not_before = Utc::now()
+                    + chrono::Duration::from_std(limiter.recheck_interval).unwrap_or_default();
+                let message = job_message.held_back(not_before);
+                limiter
+                    .publisher
+                    .publish_delayed(&message, job.tenant_id.as_deref())
+                    .await
+                    .map_err(|e| anyhow::anyhow!("Failed to queue run for a free slot: {}", e))?;
+
+                info!(
+                    max_concurrent = limit.max_concurrent,
+                    holds = message.holds,
+                    "Job at its concurrency limit, run queued"
+                );
             }
         }
+        Ok(Some(None))
     }
 
     /// Finalize execution with result
+    ///
+    /// Returns whether the final status was committed. An execution stopped
+    /// gracefully ends as cancelled; one force-cancelled meanwhile stays cancelled.
     async fn finalize_execution(
         &self,
         execution: &mut JobExecution,
         result: Result<(), anyhow::Error>,
-    ) -> &'static str {
-        let final_status = match &result {
+    ) -> bool {
+        let next = match &result {
             Ok(()) => {
                 info!("Job execution completed successfully");
-                execution.status = ExecutionStatus::Success;
-                execution.completed_at = Some(Utc::now());
                 execution.result = Some("Job completed successfully".to_string());
-                "success"
+                ExecutionStatus::Success
             }
             Err(e) => {
                 error!(error = %e, "Job execution failed");
-                execution.status = ExecutionStatus::Failed;
-                execution.completed_at = Some(Utc::now());
                 execution.error = Some(e.to_string());
-                "failed"
+                if execution.status == ExecutionStatus::Cancelling {
+                    ExecutionStatus::Cancelled
+                } else if matches!(
+                    e.downcast_ref::<ExecutionError>(),
+                    Some(ExecutionError::Timeout(_))
+                ) {
+                    ExecutionStatus::Timeout
+                } else {
+                    ExecutionStatus::Failed
+                }
             }
         };
 
-        if let Err(e) = self.execution_repo.update(execution).await {
-            error!(error = %e, "Failed to update final execution status");
+        self.transition(execution, next).await
+    }
+
+    /// Dead-letter a failed run of a job with a dead letter policy when the step it
+    /// failed at names a dependency, linking the dead letter to that dependency
+    async fn dead_letter_at_dependency(
+        &self,
+        job_metadata: &Job,
+        job_definition: &Job,
+        execution: &mut JobExecution,
+    ) {
+        if job_metadata.dead_letter_policy.is_none()
+            || !matches!(
+                execution.status,
+                ExecutionStatus::Failed | ExecutionStatus::Timeout
+            )
+        {
+            return;
         }
+        let Some(dependency) = execution
+            .current_step
+            .as_ref()
+            .and_then(|step_id| job_definition.steps.iter().find(|s| &s.id == step_id))
+            .and_then(|step| step.dependency.clone())
+        else {
+            return;
+        };
 
-        final_status
+        if let Err(e) = self
+            .execution_repo
+            .link_dependency(execution.id, &dependency)
+            .await
+        {
+            error!(error = %e, "Failed to link dead letter to its dependency");
+            return;
+        }
+        if self
+            .transition(execution, ExecutionStatus::DeadLetter)
+            .await
+        {
+            info!(dependency = %dependency, "Execution dead-lettered at its dependency");
+        }
+    }
+
+    /// Move the execution to `next` and persist the change with its history
+    ///
+    /// When the stored row moved on meanwhile (a stop request), the transition is
+    /// retried from the stored status. Returns whether the change was committed;
+    /// status events must only be published after that.
+    async fn transition(&self, execution: &mut JobExecution, next: ExecutionStatus) -> bool {
+        for _ in 0..2 {
+            let previous = match execution.transition_to(next.clone()) {
+                Ok(previous) => previous,
+                Err(e) => {
+                    warn!(error = %e, "Execution status not changed");
+                    return false;
+                }
+            };
+
+            match self.execution_repo.transition(execution, &previous).await {
+                Ok(()) => return true,
+                Err(DatabaseError::VersionConflict(reason)) => {
+                    debug!(reason = %reason, "Execution status changed meanwhile, reloading");
+                    match self.execution_repo.find_by_id(execution.id).await {
+                        Ok(Some(stored)) => execution.status = stored.status,
+                        Ok(None) => {
+                            error!("Execution disappeared while changing its status");
+                            return false;
+                        }
+                        Err(e) => {
+                            error!(error = %e, "Failed to reload execution status");
+                            execution.status = previous;
+                            return false;
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!(error = %e, status = %next, "Failed to persist execution status");
+                    execution.status = previous;
+                    return false;
+                }
+            }
+        }
+
+        false
+    }
+
+    /// Queue the completion callback requested at trigger time, if any
+    ///
+    /// The callback is persisted before the message is acknowledged; workers deliver it
+    /// (with retries) from the webhook delivery queue. It is keyed by execution, so
+    /// processing the execution again doesn't send a second callback.
+    async fn dispatch_callback(&self, job: &Job, execution: &JobExecution, context: &JobContext) {
+        let Some(callback) =
+            ExecutionCallback::from_trigger_metadata(execution.trigger_metadata.as_ref())
+        else {
+            return;
+        };
+
+        let payload = CallbackPayload::new(job, execution, context, &callback.outputs);
+        if let Err(e) = self.callback_sender.deliver(&callback, &payload).await {
+            error!(
+                error = %e,
+                execution_id = %payload.execution_id,
+                url = %callback.url,
+                "Failed to queue execution callback"
+            );
+        }
+    }
+
+    /// Alert the job's owner once a job keeps failing
+    ///
+    /// The alert carries the remediation note of the latest failure's fingerprint.
+    ///
+    /// Requirements: 5.8 - Alert on consecutive failures
+    async fn notify_consecutive_failures(&self, job: &Job, execution: &JobExecution) {
+        let consecutive_failures =
+            match self.execution_repo.count_consecutive_failures(job.id).await {
+                Ok(count) => u32::try_from(count).unwrap_or(u32::MAX),
+                Err(e) => {
+                    warn!(error = %e, "Failed to count consecutive failures");
+                    return;
+                }
+            };
+
+        if !should_trigger_alert(consecutive_failures) {
+            return;
+        }
+
+        let remediation = match execution.error.as_deref().map(fingerprint::fingerprint) {
+            Some(fingerprint) => self
+                .failure_note_repo
+                .find_by_fingerprint(&fingerprint)
+                .await
+                .unwrap_or_else(|e| {
+                    warn!(error = %e, "Failed to load failure note");
+                    None
+                }),
+            None => None,
+        };
+
+        if let Err(e) = self
+            .alert_notifier
+            .send_owned_alert(
+                &job.id,
+                &job.name,
+                consecutive_failures,
+                job.owner.as_ref(),
+                remediation.as_ref(),
+            )
+            .await
+        {
+            warn!(error = %e, "Failed to send consecutive failure alert");
+        }
     }
 
     /// Publish execution status change event to NATS for SSE broadcasting
@@ -338,3 +1199,219 @@ impl JobProcessor {
         }
     }
 }
+
+/// Record a finished execution in the job metrics, with the job's custom labels
+fn record_execution_metrics(job: &Job, execution: &JobExecution) {
+    let labels = &job.metric_labels;
+    match execution.status {
+        ExecutionStatus::Success => {
+            telemetry::record_labeled_job_success(&job.id, &job.name, labels)
+        }
+        ExecutionStatus::Failed
+        | ExecutionStatus::Timeout
+        | ExecutionStatus::Expired
+        | ExecutionStatus::Rejected
+        | ExecutionStatus::DeadLetter => telemetry::record_labeled_job_failure(
+            &job.id,
+            &job.name,
+            &execution.status.to_string(),
+            labels,
+        ),
+        _ => {}
+    }
+
+    if let (Some(started_at), Some(completed_at)) = (execution.started_at, execution.completed_at) {
+        let duration = (completed_at - started_at).num_milliseconds() as f64 / 1000.0;
+        telemetry::record_labeled_job_duration(
+            &job.id,
+            &job.name,
+            duration,
+            labels,
+            &[("execution_id", execution.id.to_string())],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{
+        job, job_processor, MemorySemaphore, RecordingPublisher, ScriptedExecutor,
+    };
+    use super::*;
+    use crate::lock::MemoryLock;
+    use crate::models::ConcurrencyLimit;
+
+    fn limited_job(overflow: ConcurrencyOverflow) -> Job {
+        let mut job = job(serde_json::json!([]));
+        job.concurrency_limit = Some(ConcurrencyLimit {
+            max_concurrent: 1,
+            overflow,
+        });
+        job
+    }
+
+    fn limiter(publisher: &Arc<RecordingPublisher>) -> ConcurrencyLimiter {
+        ConcurrencyLimiter {
+            semaphore: Arc::new(MemorySemaphore::default()),
+            publisher: Arc::clone(publisher) as Arc<dyn JobPublisher>,
+            recheck_interval: Duration::from_secs(15),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_slot_queues_run_over_the_limit() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let processor = job_processor(
+            Arc::new(ScriptedExecutor::default()),
+            Some(limiter(&publisher)),
+        );
+        let job = limited_job(ConcurrencyOverflow::Queue);
+
+        let mut first = JobExecution::new_manual(job.id, "alice".to_string());
+        let slot = processor
+            .take_concurrency_slot(&JobMessage::from(&first), &job, &mut first)
+            .await
+            .unwrap();
+        assert!(matches!(slot, Some(Some(_))));
+        assert!(publisher.delayed.lock().unwrap().is_empty());
+
+        // The second run finds the slot taken and goes back on the queue
+        let mut second = JobExecution::new_manual(job.id, "alice".to_string());
+        let message = JobMessage::from(&second);
+        let slot = processor
+            .take_concurrency_slot(&message, &job, &mut second)
+            .await
+            .unwrap();
+        assert!(matches!(slot, Some(None)));
+        assert!(second.error.is_none());
+
+        let delayed = publisher.delayed.lock().unwrap();
+        assert_eq!(delayed.len(), 1);
+        assert_eq!(delayed[0].execution_id, second.id);
+        assert_eq!(delayed[0].holds, Some(1));
+        assert!(delayed[0].not_before.unwrap() > Utc::now() + chrono::Duration::seconds(10));
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_slot_rejects_run_over_the_limit() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let processor = job_processor(
+            Arc::new(ScriptedExecutor::default()),
+            Some(limiter(&publisher)),
+        );
+        let job = limited_job(ConcurrencyOverflow::Reject);
+
+        let mut first = JobExecution::new_manual(job.id, "alice".to_string());
+        let slot = processor
+            .take_concurrency_slot(&JobMessage::from(&first), &job, &mut first)
+            .await
+            .unwrap();
+        assert!(matches!(slot, Some(Some(_))));
+
+        // The second run is rejected rather than queued
+        let mut second = JobExecution::new_manual(job.id, "alice".to_string());
+        let slot = processor
+            .take_concurrency_slot(&JobMessage::from(&second), &job, &mut second)
+            .await
+            .unwrap();
+        assert!(matches!(slot, Some(None)));
+        assert!(second.error.unwrap().starts_with("Rejected"));
+        assert!(publisher.delayed.lock().unwrap().is_empty());
+    }
+
+    fn ordered_processor(publisher: &Arc<RecordingPublisher>, locks: &MemoryLock) -> JobProcessor {
+        let mut processor = job_processor(Arc::new(ScriptedExecutor::default()), None);
+        processor.sequence_lock = Some(SequenceLock {
+            lock: Arc::new(locks.clone()),
+            publisher: Arc::clone(publisher) as Arc<dyn JobPublisher>,
+            recheck_interval: Duration::from_secs(15),
+            stale_after: Duration::from_secs(86400),
+        });
+        processor
+    }
+
+    #[tokio::test]
+    async fn test_sequence_turn_waits_while_another_run_holds_it() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let locks = MemoryLock::default();
+        let processor = ordered_processor(&publisher, &locks);
+        let mut job = job(serde_json::json!([]));
+        job.strict_ordering = true;
+        let _running = locks
+            .acquire(
+                &format!("strict_ordering:{}", job.id),
+                Duration::from_secs(60),
+            )
+            .await
+            .unwrap();
+
+        // Put back on the queue without looking at earlier runs
+        let execution = JobExecution::new_manual(job.id, "alice".to_string());
+        let turn = processor
+            .take_sequence_turn(&JobMessage::from(&execution), &job, &execution)
+            .await
+            .unwrap();
+        assert!(matches!(turn, Some(None)));
+
+        let delayed = publisher.delayed.lock().unwrap();
+        assert_eq!(delayed.len(), 1);
+        assert_eq!(delayed[0].execution_id, execution.id);
+        assert_eq!(delayed[0].holds, Some(1));
+        assert!(delayed[0].not_before.unwrap() > Utc::now() + chrono::Duration::seconds(10));
+    }
+
+    #[tokio::test]
+    async fn test_sequence_turn_released_when_earlier_runs_cant_be_checked() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let locks = MemoryLock::default();
+        let processor = ordered_processor(&publisher, &locks);
+        let mut job = job(serde_json::json!([]));
+        job.strict_ordering = true;
+
+        // The database is unreachable, so the run fails and doesn't keep the lock
+        let execution = JobExecution::new_manual(job.id, "alice".to_string());
+        let turn = processor
+            .take_sequence_turn(&JobMessage::from(&execution), &job, &execution)
+            .await;
+        assert!(turn.is_err());
+        assert!(locks
+            .acquire(
+                &format!("strict_ordering:{}", job.id),
+                Duration::from_secs(60)
+            )
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_sequence_turn_not_needed_without_strict_ordering() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let processor = ordered_processor(&publisher, &MemoryLock::default());
+        let job = job(serde_json::json!([]));
+
+        let execution = JobExecution::new_manual(job.id, "alice".to_string());
+        let turn = processor
+            .take_sequence_turn(&JobMessage::from(&execution), &job, &execution)
+            .await
+            .unwrap();
+        assert!(turn.is_none());
+        assert!(publisher.delayed.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_slot_not_needed_without_a_limit() {
+        let publisher = Arc::new(RecordingPublisher::default());
+        let processor = job_processor(
+            Arc::new(ScriptedExecutor::default()),
+            Some(limiter(&publisher)),
+        );
+        let job = job(serde_json::json!([]));
+
+        let mut execution = JobExecution::new_manual(job.id, "alice".to_string());
+        let slot = processor
+            .take_concurrency_slot(&JobMessage::from(&execution), &job, &mut execution)
+            .await
+            .unwrap();
+        assert!(slot.is_none());
+    }
+}
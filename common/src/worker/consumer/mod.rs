@@ -3,26 +3,43 @@
 // Tách theo RECC 2025 rules - Tách theo responsibility
 
 mod circuit_breaker_manager;
+mod job_cache;
 mod job_processor;
 mod step_executor;
+#[cfg(test)]
+pub(crate) mod test_support;
 
+use crate::callback::CallbackSender;
 use crate::circuit_breaker::CircuitBreakerConfig;
+use crate::db::repositories::connection_profile::ConnectionProfileRepository;
 use crate::db::repositories::execution::ExecutionRepository;
+use crate::db::repositories::failure_note::FailureNoteRepository;
 use crate::db::repositories::job::JobRepository;
 use crate::errors::QueueError;
 use crate::executor::JobExecutor;
+use crate::failover::RegionRole;
+use crate::fault_injection::FaultInjector;
+use crate::lock::DistributedLock;
 use crate::queue::consumer::JobConsumer;
 use crate::queue::{JobHandler, JobMessage, NatsClient, NatsJobConsumer};
+use crate::resource_guard::ResourceGuard;
 use crate::retry::{ExponentialBackoff, RetryStrategy};
+use crate::step_env::SecretProvider;
 use crate::storage::StorageService;
+use crate::telemetry::AlertNotifier;
 use crate::worker::context::ContextManager;
 use crate::worker::reference::ReferenceResolver;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch};
 use tracing::{info, instrument};
 
 pub use circuit_breaker_manager::CircuitBreakerManager;
-pub use job_processor::JobProcessor;
-pub use step_executor::StepExecutor;
+pub use job_cache::{job_changed_subject, JobDefinitionCache};
+pub use job_processor::{
+    CollisionHold, ConcurrencyLimiter, DeferredRetries, JobProcessor, SequenceLock,
+};
+pub use step_executor::{DeferredRetry, StepExecutor};
 
 /// Worker job consumer that processes jobs from the queue
 #[allow(dead_code)]
@@ -35,6 +52,21 @@ pub struct WorkerJobConsumer {
     http_executor: Arc<dyn JobExecutor>,
     database_executor: Arc<dyn JobExecutor>,
     file_executor: Arc<dyn JobExecutor>,
+    sftp_executor: Arc<dyn JobExecutor>,
+    graphql_executor: Arc<dyn JobExecutor>,
+    email_executor: Arc<dyn JobExecutor>,
+    notification_executor: Arc<dyn JobExecutor>,
+    mqtt_executor: Arc<dyn JobExecutor>,
+    object_storage_executor: Arc<dyn JobExecutor>,
+    container_executor: Arc<dyn JobExecutor>,
+    wasm_executor: Arc<dyn JobExecutor>,
+    script_executor: Arc<dyn JobExecutor>,
+    pdf_executor: Arc<dyn JobExecutor>,
+    transform_executor: Arc<dyn JobExecutor>,
+    validate_executor: Arc<dyn JobExecutor>,
+    checksum_executor: Arc<dyn JobExecutor>,
+    system_executor: Arc<dyn JobExecutor>,
+    circuit_breaker_manager: Arc<CircuitBreakerManager>,
     nats_client: Option<async_nats::Client>,
 }
 
@@ -46,29 +78,98 @@ impl WorkerJobConsumer {
         nats_client: NatsClient,
         job_repo: Arc<JobRepository>,
         execution_repo: Arc<ExecutionRepository>,
+        failure_note_repo: Arc<FailureNoteRepository>,
+        connection_profile_repo: Arc<ConnectionProfileRepository>,
+        secrets: Arc<dyn SecretProvider>,
         context_manager: Arc<dyn ContextManager>,
         storage_service: Arc<dyn StorageService>,
         http_executor: Arc<dyn JobExecutor>,
         database_executor: Arc<dyn JobExecutor>,
         file_executor: Arc<dyn JobExecutor>,
+        sftp_executor: Arc<dyn JobExecutor>,
+        graphql_executor: Arc<dyn JobExecutor>,
+        email_executor: Arc<dyn JobExecutor>,
+        notification_executor: Arc<dyn JobExecutor>,
+        mqtt_executor: Arc<dyn JobExecutor>,
+        object_storage_executor: Arc<dyn JobExecutor>,
+        container_executor: Arc<dyn JobExecutor>,
+        wasm_executor: Arc<dyn JobExecutor>,
+        script_executor: Arc<dyn JobExecutor>,
+        pdf_executor: Arc<dyn JobExecutor>,
+        transform_executor: Arc<dyn JobExecutor>,
+        validate_executor: Arc<dyn JobExecutor>,
+        checksum_executor: Arc<dyn JobExecutor>,
+        system_executor: Arc<dyn JobExecutor>,
+        alert_notifier: Arc<dyn AlertNotifier>,
+        callback_sender: Arc<CallbackSender>,
+        heartbeat_interval: Duration,
+        resource_guard: Arc<ResourceGuard>,
+        job_cache: Arc<JobDefinitionCache>,
+        deferred_retries: Option<DeferredRetries>,
+        collision_hold: Option<CollisionHold>,
+        sequence_lock: Option<SequenceLock>,
+        concurrency_limiter: Option<ConcurrencyLimiter>,
+        step_locks: Option<Arc<dyn DistributedLock>>,
+        dependency_recoveries: Option<mpsc::UnboundedSender<String>>,
         nats_client_for_status: Option<async_nats::Client>,
     ) -> Result<Self, QueueError> {
         info!("Creating worker job consumer with MinIO integration");
 
+        let mut circuit_breaker_manager = CircuitBreakerManager::new(CircuitBreakerConfig {
+            failure_threshold: 5,
+            timeout: std::time::Duration::from_secs(60),
+            success_threshold: 2,
+        });
+        // Breakers closing again report their dependency as recovered
+        if let Some(recoveries) = dependency_recoveries {
+            circuit_breaker_manager = circuit_breaker_manager.with_recovery_notifier(recoveries);
+        }
+        let circuit_breaker_manager = Arc::new(circuit_breaker_manager);
+
         // Create handler with all dependencies
         let handler = Self::create_handler_static(
             Arc::clone(&job_repo),
             Arc::clone(&execution_repo),
+            failure_note_repo,
+            connection_profile_repo,
+            secrets,
             Arc::clone(&context_manager),
             Arc::clone(&storage_service),
             Arc::clone(&http_executor),
             Arc::clone(&database_executor),
             Arc::clone(&file_executor),
+            Arc::clone(&sftp_executor),
+            Arc::clone(&graphql_executor),
+            Arc::clone(&email_executor),
+            Arc::clone(&notification_executor),
+            Arc::clone(&mqtt_executor),
+            Arc::clone(&object_storage_executor),
+            Arc::clone(&container_executor),
+            Arc::clone(&wasm_executor),
+            Arc::clone(&script_executor),
+            Arc::clone(&pdf_executor),
+            Arc::clone(&transform_executor),
+            Arc::clone(&validate_executor),
+            Arc::clone(&checksum_executor),
+            Arc::clone(&system_executor),
+            Arc::clone(&circuit_breaker_manager),
+            alert_notifier,
+            callback_sender,
+            heartbeat_interval,
+            resource_guard,
+            job_cache,
+            deferred_retries,
+            collision_hold,
+            sequence_lock,
+            concurrency_limiter,
+            step_locks,
             nats_client_for_status.clone(),
         );
 
         // Create NATS consumer with the handler
-        let consumer = NatsJobConsumer::new(nats_client, handler).await?;
+        let consumer = NatsJobConsumer::new(nats_client, handler)
+            .await?
+            .with_payload_store(Arc::clone(&storage_service));
 
         Ok(Self {
             consumer,
@@ -79,10 +180,42 @@ impl WorkerJobConsumer {
             http_executor,
             database_executor,
             file_executor,
+            sftp_executor,
+            graphql_executor,
+            email_executor,
+            notification_executor,
+            mqtt_executor,
+            object_storage_executor,
+            container_executor,
+            wasm_executor,
+            script_executor,
+            pdf_executor,
+            transform_executor,
+            validate_executor,
+            checksum_executor,
+            system_executor,
+            circuit_breaker_manager,
             nats_client: nats_client_for_status,
         })
     }
 
+    /// Inject queue redeliveries for chaos testing
+    pub fn with_fault_injector(mut self, injector: Arc<FaultInjector>) -> Self {
+        self.consumer = self.consumer.with_fault_injector(injector);
+        self
+    }
+
+    /// Only consume jobs while the region is active (multi-region failover)
+    pub fn with_region_role(mut self, region_role: watch::Receiver<RegionRole>) -> Self {
+        self.consumer = self.consumer.with_region_role(region_role);
+        self
+    }
+
+    /// Circuit breakers shared by every job this consumer runs
+    pub fn circuit_breakers(&self) -> Arc<CircuitBreakerManager> {
+        Arc::clone(&self.circuit_breaker_manager)
+    }
+
     /// Start consuming jobs from the queue
     pub async fn start(&self) -> Result<(), QueueError> {
         info!("Starting worker job consumer");
@@ -99,33 +232,83 @@ impl WorkerJobConsumer {
     fn create_handler_static(
         job_repo: Arc<JobRepository>,
         execution_repo: Arc<ExecutionRepository>,
+        failure_note_repo: Arc<FailureNoteRepository>,
+        connection_profile_repo: Arc<ConnectionProfileRepository>,
+        secrets: Arc<dyn SecretProvider>,
         context_manager: Arc<dyn ContextManager>,
         storage_service: Arc<dyn StorageService>,
         http_executor: Arc<dyn JobExecutor>,
         database_executor: Arc<dyn JobExecutor>,
         file_executor: Arc<dyn JobExecutor>,
+        sftp_executor: Arc<dyn JobExecutor>,
+        graphql_executor: Arc<dyn JobExecutor>,
+        email_executor: Arc<dyn JobExecutor>,
+        notification_executor: Arc<dyn JobExecutor>,
+        mqtt_executor: Arc<dyn JobExecutor>,
+        object_storage_executor: Arc<dyn JobExecutor>,
+        container_executor: Arc<dyn JobExecutor>,
+        wasm_executor: Arc<dyn JobExecutor>,
+        script_executor: Arc<dyn JobExecutor>,
+        pdf_executor: Arc<dyn JobExecutor>,
+        transform_executor: Arc<dyn JobExecutor>,
+        validate_executor: Arc<dyn JobExecutor>,
+        checksum_executor: Arc<dyn JobExecutor>,
+        system_executor: Arc<dyn JobExecutor>,
+        circuit_breaker_manager: Arc<CircuitBreakerManager>,
+        alert_notifier: Arc<dyn AlertNotifier>,
+        callback_sender: Arc<CallbackSender>,
+        heartbeat_interval: Duration,
+        resource_guard: Arc<ResourceGuard>,
+        job_cache: Arc<JobDefinitionCache>,
+        deferred_retries: Option<DeferredRetries>,
+        collision_hold: Option<CollisionHold>,
+        sequence_lock: Option<SequenceLock>,
+        concurrency_limiter: Option<ConcurrencyLimiter>,
+        step_locks: Option<Arc<dyn DistributedLock>>,
         nats_client: Option<async_nats::Client>,
     ) -> JobHandler {
         let retry_strategy: Arc<dyn RetryStrategy> = Arc::new(ExponentialBackoff::new());
-        let circuit_breaker_manager = Arc::new(CircuitBreakerManager::new(CircuitBreakerConfig {
-            failure_threshold: 5,
-            timeout: std::time::Duration::from_secs(60),
-            success_threshold: 2,
-        }));
         let reference_resolver = Arc::new(ReferenceResolver::new());
 
         Arc::new(move |job_message: JobMessage| {
             let processor = JobProcessor::new(
                 Arc::clone(&job_repo),
                 Arc::clone(&execution_repo),
+                Arc::clone(&failure_note_repo),
+                Arc::clone(&connection_profile_repo),
+                Arc::clone(&secrets),
                 Arc::clone(&context_manager),
                 Arc::clone(&storage_service),
                 Arc::clone(&http_executor),
                 Arc::clone(&database_executor),
                 Arc::clone(&file_executor),
+                Arc::clone(&sftp_executor),
+                Arc::clone(&graphql_executor),
+                Arc::clone(&email_executor),
+                Arc::clone(&notification_executor),
+                Arc::clone(&mqtt_executor),
+                Arc::clone(&object_storage_executor),
+                Arc::clone(&container_executor),
+                Arc::clone(&wasm_executor),
+                Arc::clone(&script_executor),
+                Arc::clone(&pdf_executor),
+                Arc::clone(&transform_executor),
+                Arc::clone(&validate_executor),
+                Arc::clone(&checksum_executor),
+                Arc::clone(&system_executor),
                 Arc::clone(&retry_strategy),
                 Arc::clone(&circuit_breaker_manager),
                 Arc::clone(&reference_resolver),
+                Arc::clone(&alert_notifier),
+                Arc::clone(&callback_sender),
+                heartbeat_interval,
+                Arc::clone(&resource_guard),
+                Arc::clone(&job_cache),
+                deferred_retries.clone(),
+                collision_hold.clone(),
+                sequence_lock.clone(),
+                concurrency_limiter.clone(),
+                step_locks.clone(),
                 nats_client.clone(),
             );
 
@@ -1,57 +1,256 @@
 // Step executor - handles individual step execution with retry and circuit breaker
 // Requirements: 13.4 - Execute steps sequentially with retry logic
 
+use crate::circuit_breaker::CircuitBreakerError;
 use crate::db::repositories::execution::ExecutionRepository;
 
+use crate::errors::{ExecutionError, StorageError};
+use crate::executor::incremental_sync::IncrementalSyncExecutor;
 use crate::executor::JobExecutor;
-use crate::models::{ExecutionStatus, Job, JobContext, JobExecution, JobStep, JobType, StepOutput};
+use crate::lock::{DistributedLock, LockGuard};
+use crate::models::{
+    compensation_step_ids, ExecutionProgress, ExecutionStatus, Job, JobContext, JobExecution,
+    JobStep, JobType, OnFailure, ProgressReporter, StepLock, StepOutput,
+};
+use crate::resource_guard::ExecutionMeter;
 use crate::retry::RetryStrategy;
+use crate::step_env::{SecretProvider, StepEnv};
 use crate::storage::StorageService;
+use crate::telemetry;
 use crate::worker::reference::ReferenceResolver;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 use tracing::{error, info, instrument, warn};
+use uuid::Uuid;
 
 use super::CircuitBreakerManager;
 
+/// How often a step waiting for its lock tries again
+const STEP_LOCK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Shortest interval between renewals of a step's lock
+const MIN_LOCK_RENEWAL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A step retry handed back to the queue instead of waited out in the worker
+#[derive(Debug, thiserror::Error)]
+#[error("Step {step_id} failed, retry {attempt} queued for {not_before}: {error}")]
+pub struct DeferredRetry {
+    pub step_id: String,
+    /// Retry attempt the step resumes at
+    pub attempt: u32,
+    pub not_before: DateTime<Utc>,
+    /// Error of the failed attempt
+    pub error: String,
+}
+
+/// Stores and publishes an execution's progress heartbeats
+struct HeartbeatSender {
+    execution_repo: Arc<ExecutionRepository>,
+    nats_client: Option<async_nats::Client>,
+    execution_id: Uuid,
+    job_id: Uuid,
+}
+
+impl HeartbeatSender {
+    /// Store a progress heartbeat and publish it for SSE broadcasting
+    async fn send(&self, progress: ExecutionProgress) {
+        if let Err(e) = self
+            .execution_repo
+            .record_heartbeat(self.execution_id, &progress)
+            .await
+        {
+            warn!(error = %e, "Failed to record execution heartbeat");
+        }
+
+        if let Some(client) = &self.nats_client {
+            let event = serde_json::json!({
+                "type": "execution_heartbeat",
+                "execution_id": self.execution_id,
+                "job_id": self.job_id,
+                "progress": progress,
+            });
+
+            if let Ok(payload) = serde_json::to_vec(&event) {
+                let subject = format!("status.execution.{}", self.execution_id);
+                if let Err(e) = client.publish(subject, payload.into()).await {
+                    warn!(error = %e, "Failed to publish execution heartbeat");
+                }
+            }
+        }
+    }
+}
+
+/// Heartbeat task of a running step, stopped when dropped
+struct HeartbeatTask(tokio::task::JoinHandle<()>);
+
+impl Drop for HeartbeatTask {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
 /// Step executor handles execution of individual job steps
 pub struct StepExecutor {
     http_executor: Arc<dyn JobExecutor>,
     database_executor: Arc<dyn JobExecutor>,
     file_executor: Arc<dyn JobExecutor>,
+    sftp_executor: Arc<dyn JobExecutor>,
+    graphql_executor: Arc<dyn JobExecutor>,
+    email_executor: Arc<dyn JobExecutor>,
+    notification_executor: Arc<dyn JobExecutor>,
+    mqtt_executor: Arc<dyn JobExecutor>,
+    object_storage_executor: Arc<dyn JobExecutor>,
+    container_executor: Arc<dyn JobExecutor>,
+    wasm_executor: Arc<dyn JobExecutor>,
+    script_executor: Arc<dyn JobExecutor>,
+    pdf_executor: Arc<dyn JobExecutor>,
+    transform_executor: Arc<dyn JobExecutor>,
+    validate_executor: Arc<dyn JobExecutor>,
+    checksum_executor: Arc<dyn JobExecutor>,
+    incremental_sync_executor: Arc<dyn JobExecutor>,
+    system_executor: Arc<dyn JobExecutor>,
     storage_service: Arc<dyn StorageService>,
-    _reference_resolver: Arc<ReferenceResolver>,
+    reference_resolver: Arc<ReferenceResolver>,
     circuit_breaker_manager: Arc<CircuitBreakerManager>,
     retry_strategy: Arc<dyn RetryStrategy>,
     execution_repo: Arc<ExecutionRepository>,
+    heartbeat_interval: Option<Duration>,
+    nats_client: Option<async_nats::Client>,
+    resource_meter: Option<ExecutionMeter>,
+    deferred_retry_min_delay: Option<Duration>,
+    resume_attempt: Option<u32>,
+    step_locks: Option<Arc<dyn DistributedLock>>,
+    secrets: Option<Arc<dyn SecretProvider>>,
 }
 
 impl StepExecutor {
     /// Create a new step executor
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         http_executor: Arc<dyn JobExecutor>,
         database_executor: Arc<dyn JobExecutor>,
         file_executor: Arc<dyn JobExecutor>,
+        sftp_executor: Arc<dyn JobExecutor>,
+        graphql_executor: Arc<dyn JobExecutor>,
+        email_executor: Arc<dyn JobExecutor>,
+        notification_executor: Arc<dyn JobExecutor>,
+        mqtt_executor: Arc<dyn JobExecutor>,
+        object_storage_executor: Arc<dyn JobExecutor>,
+        container_executor: Arc<dyn JobExecutor>,
+        wasm_executor: Arc<dyn JobExecutor>,
+        script_executor: Arc<dyn JobExecutor>,
+        pdf_executor: Arc<dyn JobExecutor>,
+        transform_executor: Arc<dyn JobExecutor>,
+        validate_executor: Arc<dyn JobExecutor>,
+        checksum_executor: Arc<dyn JobExecutor>,
+        system_executor: Arc<dyn JobExecutor>,
         storage_service: Arc<dyn StorageService>,
         reference_resolver: Arc<ReferenceResolver>,
         circuit_breaker_manager: Arc<CircuitBreakerManager>,
         retry_strategy: Arc<dyn RetryStrategy>,
         execution_repo: Arc<ExecutionRepository>,
     ) -> Self {
+        // Incremental syncs run their sources on the same executors
+        let incremental_sync_executor: Arc<dyn JobExecutor> =
+            Arc::new(IncrementalSyncExecutor::new(
+                Arc::clone(&http_executor),
+                Arc::clone(&database_executor),
+            ));
         Self {
             http_executor,
             database_executor,
             file_executor,
+            sftp_executor,
+            graphql_executor,
+            email_executor,
+            notification_executor,
+            mqtt_executor,
+            object_storage_executor,
+            container_executor,
+            wasm_executor,
+            script_executor,
+            pdf_executor,
+            transform_executor,
+            validate_executor,
+            checksum_executor,
+            incremental_sync_executor,
+            system_executor,
             storage_service,
-            _reference_resolver: reference_resolver,
+            reference_resolver,
             circuit_breaker_manager,
             retry_strategy,
             execution_repo,
+            heartbeat_interval: None,
+            nats_client: None,
+            resource_meter: None,
+            deferred_retry_min_delay: None,
+            resume_attempt: None,
+            step_locks: None,
+            secrets: None,
         }
     }
 
+    /// Send progress heartbeats while steps run
+    ///
+    /// Each heartbeat is stored on the execution row and, with a NATS client,
+    /// published for the dashboard's live updates.
+    pub fn with_heartbeats(
+        mut self,
+        interval: Duration,
+        nats_client: Option<async_nats::Client>,
+    ) -> Self {
+        self.heartbeat_interval = Some(interval);
+        self.nats_client = nats_client;
+        self
+    }
+
+    /// Account the steps' memory and CPU, stopping them when the meter's guard does
+    pub fn with_resource_meter(mut self, meter: ExecutionMeter) -> Self {
+        self.resource_meter = Some(meter);
+        self
+    }
+
+    /// Hand retries backing off at least `min_delay` back to the queue
+    ///
+    /// The step then fails with [`DeferredRetry`] instead of sleeping.
+    pub fn with_deferred_retries(mut self, min_delay: Duration) -> Self {
+        self.deferred_retry_min_delay = Some(min_delay);
+        self
+    }
+
+    /// Take the locks of steps with `acquire_lock` from `lock`
+    ///
+    /// Without it such steps run unlocked.
+    pub fn with_step_locks(mut self, lock: Arc<dyn DistributedLock>) -> Self {
+        self.step_locks = Some(lock);
+        self
+    }
+
+    /// Read the secrets of step environments from `secrets`
+    ///
+    /// Without it steps with secret environment variables fail.
+    pub fn with_secrets(mut self, secrets: Arc<dyn SecretProvider>) -> Self {
+        self.secrets = Some(secrets);
+        self
+    }
+
+    /// Resume a re-queued execution: steps with an output in the context are skipped
+    /// and the first remaining step continues at retry `attempt`
+    pub fn resume_retry(mut self, attempt: u32) -> Self {
+        self.resume_attempt = Some(attempt);
+        self
+    }
+
     /// Execute all job steps sequentially
+    ///
+    /// A step failing after its retries routes the job by its `on_failure`; the
+    /// route taken is recorded in the context. When the job fails at a step, the
+    /// steps completed before it are compensated in reverse order by their
+    /// `compensate_with` steps. Compensation steps only run as compensation, and
+    /// their results are recorded apart from the step outputs.
     #[instrument(skip(self, job, context, execution), fields(job_id = %job.id, job_name = %job.name))]
     pub async fn execute_all_steps(
         &self,
@@ -64,82 +263,416 @@ impl StepExecutor {
             "Executing job steps sequentially"
         );
 
-        for (index, step) in job.steps.iter().enumerate() {
-            info!(
-                step_index = index,
-                step_id = %step.id,
-                step_name = %step.name,
-                "Executing step"
-            );
+        let mut resume_attempt = self.resume_attempt;
+        let compensation_steps = compensation_step_ids(&job.steps);
+        let mut completed = Vec::new();
 
-            // Check for cancellation
-            if self.check_cancellation(execution).await? {
-                return Err(anyhow::anyhow!("Execution cancelled"));
+        let mut index = 0;
+        while let Some(step) = job.steps.get(index) {
+            index += 1;
+            if compensation_steps.contains(step.id.as_str()) {
+                continue;
+            }
+            if resume_attempt.is_some()
+                && (context.has_step_output(&step.id)
+                    || context
+                        .failure_routes
+                        .iter()
+                        .any(|route| route.step_id == step.id))
+            {
+                info!(step_id = %step.id, "Step completed before the retry, skipping");
+                if context.has_step_output(&step.id) {
+                    completed.push(step);
+                }
+                continue;
             }
+            let first_attempt = resume_attempt.take().unwrap_or(0);
 
-            // Update current step
-            execution.current_step = Some(step.id.clone());
-            if let Err(e) = self.execution_repo.update(execution).await {
-                warn!(error = %e, "Failed to update current step");
+            let failure = match self
+                .run_step(job, index - 1, step, context, execution, first_attempt)
+                .await?
+            {
+                Ok(step_output) => {
+                    self.complete_step(step, step_output, context).await?;
+                    completed.push(step);
+                    continue;
+                }
+                Err(failure) => failure,
+            };
+
+            let route = step.on_failure.clone().unwrap_or_default();
+            context.record_failure_route(&step.id, failure.to_string(), route.clone());
+            let failure = match route {
+                OnFailure::FailJob => failure,
+                OnFailure::ContinueNextStep => {
+                    warn!(step_id = %step.id, error = %failure, "Step failed, continuing with the next step");
+                    self.save_context(context).await?;
+                    continue;
+                }
+                OnFailure::GotoStep(target) => match job
+                    .steps
+                    .iter()
+                    .position(|other| other.id == target)
+                    .filter(|position| *position >= index)
+                {
+                    Some(position) => {
+                        warn!(step_id = %step.id, target = %target, error = %failure, "Step failed, going to step");
+                        index = position;
+                        self.save_context(context).await?;
+                        continue;
+                    }
+                    None => {
+                        failure.context(format!("on_failure target {} is not a later step", target))
+                    }
+                },
+                OnFailure::RunCompensation(step_ids) => {
+                    warn!(step_id = %step.id, error = %failure, "Step failed, running compensation steps");
+                    self.run_compensation(job, &step.id, &step_ids, context, execution)
+                        .await?;
+                    failure
+                }
+            };
+
+            // Undo the completed steps, newest first
+            for completed_step in completed.iter().rev() {
+                self.run_compensation(
+                    job,
+                    &completed_step.id,
+                    &completed_step.compensate_with,
+                    context,
+                    execution,
+                )
+                .await?;
             }
+            execution.current_step = Some(step.id.clone());
+            return Err(failure);
+        }
+
+        info!(
+            total_steps = job.steps.len(),
+            "All steps completed successfully"
+        );
+        Ok(())
+    }
+
+    /// Store a completed step's output in the context and persist it
+    async fn complete_step(
+        &self,
+        step: &JobStep,
+        step_output: StepOutput,
+        context: &mut JobContext,
+    ) -> Result<(), anyhow::Error> {
+        context.set_step_output(step.id.clone(), step_output);
+        if step.pii {
+            context.tag_pii(&step.id);
+        }
+        self.write_state(step, context)?;
+
+        // Persist context after each step
+        self.save_context(context).await?;
+
+        info!(
+            step_id = %step.id,
+            completed_steps = context.completed_steps_count(),
+            "Context saved to storage after step completion"
+        );
+        Ok(())
+    }
 
-            // Check step condition
-            if let Some(condition) = &step.condition {
-                info!(condition = %condition, "Step has condition, evaluating");
-                // TODO: Implement proper condition evaluation
+    /// Resolve a completed step's `set_state` values and stage them in the context
+    ///
+    /// Staged state is written to the job's state only once the whole execution
+    /// succeeded, so a failed or retried execution leaves it as it was.
+    fn write_state(&self, step: &JobStep, context: &mut JobContext) -> Result<(), anyhow::Error> {
+        if step.set_state.is_empty() {
+            return Ok(());
+        }
+
+        let mut values = HashMap::with_capacity(step.set_state.len());
+        for (key, template) in &step.set_state {
+            let value = self
+                .reference_resolver
+                .resolve_json(template, context)
+                .map_err(|e| anyhow::anyhow!("Step {} set_state.{}: {}", step.id, key, e))?;
+            values.insert(key.clone(), value);
+        }
+
+        info!(step_id = %step.id, keys = ?step.set_state.keys().collect::<Vec<_>>(), "Job state staged");
+        context.pending_state.extend(values);
+        Ok(())
+    }
+
+    /// Run the steps compensating `compensates` in order, recording their results
+    ///
+    /// A failing compensation step is recorded and the rest still run.
+    async fn run_compensation(
+        &self,
+        job: &Job,
+        compensates: &str,
+        step_ids: &[String],
+        context: &mut JobContext,
+        execution: &mut JobExecution,
+    ) -> Result<(), anyhow::Error> {
+        if step_ids.is_empty() {
+            return Ok(());
+        }
+        for step_id in step_ids {
+            let Some((index, step)) = job
+                .steps
+                .iter()
+                .enumerate()
+                .find(|(_, step)| &step.id == step_id)
+            else {
+                warn!(step_id = %step_id, "Compensation step not found, skipping");
+                continue;
+            };
+            // A compensation step isn't retried later, as the job has failed already
+            let result = match self.run_step(job, index, step, context, execution, 0).await {
+                Ok(result) => result,
+                Err(e) if e.is::<DeferredRetry>() => Err(e),
+                Err(e) => return Err(e),
             }
+            .map_err(|failure| {
+                error!(step_id = %step.id, compensates = %compensates, error = %failure, "Compensation step failed");
+                failure.to_string()
+            });
+            context.record_compensation(&step.id, compensates, result);
+        }
+        self.save_context(context).await
+    }
 
-            // Execute step with timeout
-            let timeout_duration = Duration::from_secs(job.timeout_seconds as u64);
-            let step_result =
-                timeout(timeout_duration, self.execute_single_step(step, context)).await;
+    /// Run one step
+    ///
+    /// The inner error is the step failing, which `on_failure` routes; the outer
+    /// one stops the job regardless, e.g. a cancellation or a deferred retry.
+    async fn run_step(
+        &self,
+        job: &Job,
+        index: usize,
+        step: &JobStep,
+        context: &mut JobContext,
+        execution: &mut JobExecution,
+        first_attempt: u32,
+    ) -> Result<Result<StepOutput, anyhow::Error>, anyhow::Error> {
+        info!(
+            step_index = index,
+            step_id = %step.id,
+            step_name = %step.name,
+            "Executing step"
+        );
 
-            match step_result {
-                Ok(Ok(step_output)) => {
-                    info!(step_id = %step.id, "Step completed successfully");
-                    context.set_step_output(step.id.clone(), step_output);
+        // Check for cancellation
+        if self.check_cancellation(execution).await? {
+            return Err(anyhow::anyhow!("Execution cancelled"));
+        }
 
-                    // Persist context after each step
-                    if let Err(e) = self.storage_service.store_context(context).await {
-                        error!(error = %e, "Failed to save context to storage after step");
-                        return Err(anyhow::anyhow!("Failed to save context: {}", e));
-                    }
+        // Update current step
+        execution.current_step = Some(step.id.clone());
+        if let Err(e) = self.execution_repo.update(execution).await {
+            warn!(error = %e, "Failed to update current step");
+        }
+
+        // Check step condition
+        if let Some(condition) = &step.condition {
+            info!(condition = %condition, "Step has condition, evaluating");
+            // TODO: Implement proper condition evaluation
+        }
+
+        // Heartbeats run on their own task, from the wait for the step's lock until
+        // the step finishes, so neither a long wait nor a step blocking its worker
+        // thread can starve them
+        let reporter = context.progress.clone();
+        reporter.reset();
+        let _heartbeats = self
+            .heartbeat_interval
+            .map(|interval| self.spawn_heartbeats(job, index, step, execution, reporter, interval));
 
+        // Hold the step's lock, if it names one, until the step finishes
+        let mut step_lock = match &step.acquire_lock {
+            Some(step_lock) => match self.acquire_step_lock(step, step_lock).await {
+                Ok(guard) => guard,
+                Err(e) => {
+                    error!(step_id = %step.id, error = %e, "Step lock not acquired");
+                    return Ok(Err(e));
+                }
+            },
+            None => None,
+        };
+
+        // Execute step with timeout
+        let timeout_duration = Duration::from_secs(job.timeout_seconds as u64);
+        let step_future = async {
+            let step_future = timeout(
+                timeout_duration,
+                self.execute_single_step(step, context, first_attempt),
+            );
+            match &self.resource_meter {
+                Some(meter) => meter.run(step_future).await,
+                None => Ok(step_future.await),
+            }
+        };
+        let step_result = match step_lock.as_mut() {
+            Some(guard) => match hold_lock(guard, step_future).await {
+                Ok(step_result) => step_result,
+                Err(e) => {
+                    error!(step_id = %step.id, error = %e, "Step lock lost, stopping the step");
+                    return Ok(Err(anyhow::anyhow!(
+                        "Step {} lost lock {}: {}",
+                        step.id,
+                        guard.resource(),
+                        e
+                    )));
+                }
+            },
+            None => step_future.await,
+        };
+
+        match step_result {
+            Ok(Ok(Ok(step_output))) => {
+                info!(step_id = %step.id, "Step completed successfully");
+                Ok(Ok(step_output))
+            }
+            Ok(Ok(Err(e))) if e.is::<DeferredRetry>() => {
+                info!(step_id = %step.id, error = %e, "Step retry handed back to the queue");
+                Err(e)
+            }
+            Ok(Ok(Err(e))) => {
+                error!(step_id = %step.id, error = %e, "Step execution failed");
+                Ok(Err(anyhow::anyhow!("Step {} failed: {}", step.id, e)))
+            }
+            Ok(Err(_)) => {
+                error!(step_id = %step.id, "Step execution timed out");
+                Ok(Err(anyhow::Error::new(ExecutionError::Timeout(
+                    job.timeout_seconds as u64,
+                ))
+                .context(format!("Step {} timed out", step.id))))
+            }
+            Err(e) => {
+                error!(step_id = %step.id, error = %e, "Step stopped by the resource guard");
+                Err(anyhow::anyhow!("Step {} stopped: {}", step.id, e))
+            }
+        }
+    }
+
+    /// Wait for a step's lock, trying again until its wait timeout runs out
+    async fn acquire_step_lock(
+        &self,
+        step: &JobStep,
+        step_lock: &StepLock,
+    ) -> Result<Option<LockGuard>, anyhow::Error> {
+        let Some(locks) = &self.step_locks else {
+            warn!(
+                step_id = %step.id,
+                resource = %step_lock.resource,
+                "No step lock backend, running the step unlocked"
+            );
+            return Ok(None);
+        };
+
+        let resource = format!("step:{}", step_lock.resource);
+        let ttl = Duration::from_secs(u64::from(step_lock.ttl_seconds));
+        let wait_timeout = Duration::from_secs(u64::from(step_lock.wait_timeout_seconds));
+        let started = Instant::now();
+        loop {
+            match locks.acquire(&resource, ttl).await {
+                Ok(guard) => {
+                    let waited = started.elapsed();
+                    telemetry::record_step_lock_wait(&step_lock.resource, waited.as_secs_f64());
                     info!(
                         step_id = %step.id,
-                        completed_steps = context.completed_steps_count(),
-                        "Context saved to storage after step completion"
+                        resource = %step_lock.resource,
+                        waited_ms = waited.as_millis() as u64,
+                        "Step lock acquired"
                     );
+                    return Ok(Some(guard));
                 }
-                Ok(Err(e)) => {
-                    error!(step_id = %step.id, error = %e, "Step execution failed");
-                    return Err(anyhow::anyhow!("Step {} failed: {}", step.id, e));
+                Err(e) if started.elapsed() >= wait_timeout => {
+                    telemetry::record_step_lock_timeout(&step_lock.resource);
+                    return Err(anyhow::anyhow!(
+                        "Step {} gave up waiting {}s for lock {}: {}",
+                        step.id,
+                        step_lock.wait_timeout_seconds,
+                        step_lock.resource,
+                        e
+                    ));
                 }
                 Err(_) => {
-                    error!(step_id = %step.id, "Step execution timed out");
-                    execution.status = ExecutionStatus::Timeout;
-                    return Err(anyhow::anyhow!("Step {} timed out", step.id));
+                    let remaining = wait_timeout.saturating_sub(started.elapsed());
+                    tokio::time::sleep(STEP_LOCK_POLL_INTERVAL.min(remaining)).await;
                 }
             }
         }
+    }
 
-        info!(
-            total_steps = job.steps.len(),
-            "All steps completed successfully"
-        );
-        Ok(())
+    /// Persist the context between steps
+    async fn save_context(&self, context: &JobContext) -> Result<(), anyhow::Error> {
+        self.storage_service
+            .store_context(context)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to save context to storage after step");
+                anyhow::anyhow!("Failed to save context: {}", e)
+            })
+    }
+
+    /// Send heartbeats for a step every `interval` until the returned task is dropped
+    fn spawn_heartbeats(
+        &self,
+        job: &Job,
+        index: usize,
+        step: &JobStep,
+        execution: &JobExecution,
+        reporter: ProgressReporter,
+        interval: Duration,
+    ) -> HeartbeatTask {
+        let sender = HeartbeatSender {
+            execution_repo: Arc::clone(&self.execution_repo),
+            nats_client: self.nats_client.clone(),
+            execution_id: execution.id,
+            job_id: execution.job_id,
+        };
+        let step_id = step.id.clone();
+        let total_steps = job.steps.len();
+        let execution_started = execution.started_at;
+        let step_started = Instant::now();
+
+        HeartbeatTask(tokio::spawn(async move {
+            let mut heartbeat = tokio::time::interval(interval);
+            loop {
+                heartbeat.tick().await;
+                let progress = ExecutionProgress::new(
+                    step_id.clone(),
+                    index,
+                    total_steps,
+                    execution_started
+                        .map(|at| (Utc::now() - at).num_seconds().max(0) as u64)
+                        .unwrap_or_default(),
+                    step_started.elapsed().as_secs(),
+                    reporter.latest(),
+                );
+                sender.send(progress).await;
+            }
+        }))
     }
 
     /// Check if execution has been cancelled
-    async fn check_cancellation(&self, execution: &JobExecution) -> Result<bool, anyhow::Error> {
+    ///
+    /// A requested stop is copied into `execution.status`, so the execution is
+    /// finalized from the stored status.
+    async fn check_cancellation(
+        &self,
+        execution: &mut JobExecution,
+    ) -> Result<bool, anyhow::Error> {
         match self.execution_repo.find_by_id(execution.id).await {
             Ok(Some(current_execution)) => match current_execution.status {
                 ExecutionStatus::Cancelling => {
                     info!("Graceful cancellation requested, stopping after current step");
+                    execution.status = current_execution.status;
                     Ok(true)
                 }
                 ExecutionStatus::Cancelled => {
                     info!("Force cancellation detected, stopping immediately");
+                    execution.status = current_execution.status;
                     Ok(true)
                 }
                 _ => Ok(false),
@@ -161,36 +694,81 @@ impl StepExecutor {
         &self,
         step: &JobStep,
         context: &mut JobContext,
+        first_attempt: u32,
     ) -> Result<StepOutput, anyhow::Error> {
         // Route to appropriate executor
         let executor: &Arc<dyn JobExecutor> = match &step.step_type {
             JobType::HttpRequest { .. } => &self.http_executor,
             JobType::DatabaseQuery { .. } => &self.database_executor,
             JobType::FileProcessing { .. } => &self.file_executor,
-            JobType::Sftp { .. } => {
-                return Err(anyhow::anyhow!("SFTP not yet implemented"));
-            }
+            JobType::Sftp { .. } => &self.sftp_executor,
+            JobType::GraphQL { .. } => &self.graphql_executor,
+            JobType::Email { .. } => &self.email_executor,
+            JobType::Notification { .. } => &self.notification_executor,
+            JobType::MqttPublish { .. } => &self.mqtt_executor,
+            JobType::ObjectStorage { .. } => &self.object_storage_executor,
+            JobType::Container { .. } => &self.container_executor,
+            JobType::Wasm { .. } => &self.wasm_executor,
+            JobType::Script { .. } => &self.script_executor,
+            JobType::PdfReport { .. } => &self.pdf_executor,
+            JobType::Transform { .. } => &self.transform_executor,
+            JobType::Validate { .. } => &self.validate_executor,
+            JobType::Checksum { .. } => &self.checksum_executor,
+            JobType::IncrementalSync { .. } => &self.incremental_sync_executor,
+            JobType::System { .. } => &self.system_executor,
         };
 
+        // The step's environment is only held while it runs; its secrets are masked in
+        // whatever the step leaves behind
+        context.env = StepEnv::resolve(
+            step,
+            context,
+            &self.reference_resolver,
+            self.secrets.as_deref(),
+        )
+        .await?;
+        let result = self
+            .execute_with_retries(step, executor, context, first_attempt)
+            .await;
+        let env = std::mem::take(&mut context.env);
+        result.map(|mut step_output| {
+            env.redact_json(&mut step_output.output);
+            step_output
+        })
+    }
+
+    /// Run a step on its executor until it succeeds or its retries run out
+    async fn execute_with_retries(
+        &self,
+        step: &JobStep,
+        executor: &Arc<dyn JobExecutor>,
+        context: &mut JobContext,
+        first_attempt: u32,
+    ) -> Result<StepOutput, anyhow::Error> {
         // Execute with retry logic
-        let mut attempt = 0;
+        let mut attempt = first_attempt;
         let mut last_error = None;
 
         while self.retry_strategy.should_retry(attempt) {
             info!(attempt = attempt + 1, "Executing step attempt");
 
-            // Get circuit breaker
-            let circuit_breaker = self
-                .circuit_breaker_manager
-                .get_or_create(&format!("{}_{}", step.id, step.name))
-                .await;
+            // Get circuit breaker, shared by the steps naming the same dependency
+            let target = step
+                .dependency
+                .clone()
+                .unwrap_or_else(|| format!("{}_{}", step.id, step.name));
+            let circuit_breaker = self.circuit_breaker_manager.get_or_create(&target).await;
 
             // Clone context for this attempt
             let mut context_clone = context.clone();
 
-            // Execute with circuit breaker
+            // Execute with circuit breaker; failures retrying can't fix aren't the
+            // dependency being down
             match circuit_breaker
-                .call(executor.execute(step, &mut context_clone))
+                .call_counting(
+                    executor.execute(step, &mut context_clone),
+                    ExecutionError::is_retryable,
+                )
                 .await
             {
                 Ok(step_output) => {
@@ -199,15 +777,46 @@ impl StepExecutor {
                     return Ok(step_output);
                 }
                 Err(e) => {
+                    let (retryable, retry_after) = match &e {
+                        CircuitBreakerError::RequestFailed(e) => {
+                            (e.is_retryable(), e.retry_after())
+                        }
+                        CircuitBreakerError::CircuitOpen { .. } => (true, None),
+                    };
+                    let e = context.env.redact(&e.to_string());
                     warn!(error = %e, attempt = attempt + 1, "Step execution failed");
-                    last_error = Some(e);
                     attempt += 1;
 
-                    // Calculate delay before next retry
+                    if !retryable {
+                        last_error = Some(e);
+                        break;
+                    }
+
+                    // Calculate delay before next retry, waiting at least as long as the
+                    // dependency asked
                     if let Some(delay) = self.retry_strategy.next_delay(attempt) {
+                        let delay = retry_after.map_or(delay, |retry_after| retry_after.max(delay));
+                        // Long backoffs wait on the queue rather than in this worker
+                        if self
+                            .deferred_retry_min_delay
+                            .is_some_and(|min_delay| delay >= min_delay)
+                            && self.retry_strategy.should_retry(attempt)
+                        {
+                            return Err(DeferredRetry {
+                                step_id: step.id.clone(),
+                                attempt,
+                                not_before: Utc::now()
+                                    + chrono::Duration::from_std(delay).unwrap_or_default(),
+                                error: e,
+                            }
+                            .into());
+                        }
+
+                        last_error = Some(e);
                         info!(delay_secs = delay.as_secs(), "Waiting before retry");
                         tokio::time::sleep(delay).await;
                     } else {
+                        last_error = Some(e);
                         break;
                     }
                 }
@@ -219,9 +828,294 @@ impl StepExecutor {
         Err(anyhow::anyhow!(
             "Step execution failed after {} attempts: {}",
             attempt,
-            last_error
-                .map(|e| e.to_string())
-                .unwrap_or_else(|| "Unknown error".to_string())
+            last_error.unwrap_or_else(|| "Unknown error".to_string())
         ))
     }
 }
+
+/// Run `work` while renewing `guard` every third of its TTL, so the lock outlives a
+/// step running past the TTL but still expires soon after a worker dies
+///
+/// Fails, dropping `work`, as soon as the lock can't be renewed, since another
+/// worker may hold it by then.
+async fn hold_lock<F: Future>(guard: &mut LockGuard, work: F) -> Result<F::Output, StorageError> {
+    let renew_every = (guard.ttl() / 3).max(MIN_LOCK_RENEWAL_INTERVAL);
+    let mut renewals =
+        tokio::time::interval_at(tokio::time::Instant::now() + renew_every, renew_every);
+    tokio::pin!(work);
+    loop {
+        tokio::select! {
+            output = &mut work => return Ok(output),
+            _ = renewals.tick() => guard.renew().await?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{job, step_executor, MemoryStorage, ScriptedExecutor};
+    use super::*;
+    use crate::lock::MemoryLock;
+    use serde_json::json;
+    use std::sync::atomic::Ordering;
+
+    fn step(id: &str, routing: serde_json::Value) -> serde_json::Value {
+        let mut step = json!({
+            "id": id,
+            "name": id,
+            "type": {"type": "system", "task": {"name": "canary"}},
+            "condition": null,
+        });
+        step.as_object_mut()
+            .unwrap()
+            .extend(routing.as_object().cloned().unwrap_or_default());
+        step
+    }
+
+    /// Run the job's steps, failing `failing`, and return the outcome, the
+    /// steps run in order and the context
+    async fn run(
+        steps: serde_json::Value,
+        failing: &[&str],
+    ) -> (Result<(), anyhow::Error>, Vec<String>, JobContext) {
+        let job = job(steps);
+        let executor = Arc::new(ScriptedExecutor::failing(failing));
+        let steps = step_executor(executor.clone(), Arc::new(MemoryStorage::default()));
+        let mut execution = JobExecution::new_manual(job.id, "tester".to_string());
+        let mut context = JobContext::new(execution.id, job.id);
+        let result = steps
+            .execute_all_steps(&job, &mut context, &mut execution)
+            .await;
+        (result, executor.steps_run(), context)
+    }
+
+    #[tokio::test]
+    async fn test_continue_next_step_records_failure() {
+        let (result, steps_run, context) = run(
+            json!([
+                step("extract", json!({"on_failure": "continue"})),
+                step("load", json!({})),
+            ]),
+            &["extract"],
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(steps_run, ["extract", "load"]);
+        assert!(context.has_step_output("load"));
+        assert_eq!(context.failure_routes.len(), 1);
+        assert_eq!(context.failure_routes[0].step_id, "extract");
+        assert_eq!(context.failure_routes[0].route, OnFailure::ContinueNextStep);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_step_is_not_retried() {
+        let job = job(json!([step("notify", json!({}))]));
+        let run_with = |executor: Arc<ScriptedExecutor>| {
+            let job = job.clone();
+            async move {
+                let steps = step_executor(executor, Arc::new(MemoryStorage::default()));
+                let mut execution = JobExecution::new_manual(job.id, "tester".to_string());
+                let mut context = JobContext::new(execution.id, job.id);
+                steps
+                    .execute_all_steps(&job, &mut context, &mut execution)
+                    .await
+            }
+        };
+
+        let failing = Arc::new(ScriptedExecutor::failing(&["notify"]));
+        assert!(run_with(failing.clone()).await.is_err());
+        assert!(failing.calls.lock().unwrap().len() > 1);
+
+        let rejecting = Arc::new(ScriptedExecutor::failing(&["notify"]).rejecting());
+        assert!(run_with(rejecting.clone()).await.is_err());
+        assert_eq!(rejecting.calls.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_goto_step_skips_steps_in_between() {
+        let (result, steps_run, context) = run(
+            json!([
+                step("extract", json!({"on_failure": {"goto_step": "report"}})),
+                step("load", json!({})),
+                step("report", json!({})),
+            ]),
+            &["extract"],
+        )
+        .await;
+
+        assert!(result.is_ok());
+        assert_eq!(steps_run, ["extract", "report"]);
+        assert!(!context.has_step_output("load"));
+        assert!(context.has_step_output("report"));
+    }
+
+    #[tokio::test]
+    async fn test_fail_job_compensates_completed_steps_newest_first() {
+        let (result, steps_run, context) = run(
+            json!([
+                step("upload", json!({"compensate_with": ["delete_upload"]})),
+                step("publish", json!({"compensate_with": ["unpublish"]})),
+                step("notify", json!({"on_failure": "stop"})),
+                step("delete_upload", json!({})),
+                step("unpublish", json!({})),
+            ]),
+            &["notify"],
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            steps_run,
+            ["upload", "publish", "notify", "unpublish", "delete_upload"]
+        );
+        let compensated: Vec<_> = context
+            .compensations
+            .iter()
+            .map(|c| (c.step_id.as_str(), c.compensates.as_str()))
+            .collect();
+        assert_eq!(
+            compensated,
+            [("unpublish", "publish"), ("delete_upload", "upload")]
+        );
+        assert_eq!(context.failure_routes[0].route, OnFailure::FailJob);
+    }
+
+    #[tokio::test]
+    async fn test_run_compensation_runs_named_steps_then_fails() {
+        let (result, steps_run, context) = run(
+            json!([
+                step(
+                    "charge",
+                    json!({"on_failure": {"run_compensation": ["refund", "alert"]}})
+                ),
+                step("ship", json!({})),
+                step("refund", json!({})),
+                step("alert", json!({})),
+            ]),
+            &["charge", "refund"],
+        )
+        .await;
+
+        // A failing compensation step is recorded and the others still run
+        assert!(result.is_err());
+        assert_eq!(steps_run, ["charge", "refund", "alert"]);
+        assert_eq!(context.compensations.len(), 2);
+        assert!(context.compensations[0].error.is_some());
+        assert!(context.compensations[1].output.is_some());
+        assert!(!context.has_step_output("ship"));
+    }
+
+    #[tokio::test]
+    async fn test_set_state_is_staged_until_the_execution_succeeds() {
+        let (result, _, context) = run(
+            json!([
+                step(
+                    "fetch",
+                    json!({"set_state": {"last_step": "{{steps.fetch.output.step}}"}})
+                ),
+                step("load", json!({})),
+            ]),
+            &["load"],
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            context.pending_state.get("last_step"),
+            Some(&json!("fetch"))
+        );
+        assert!(context.state.is_empty());
+    }
+
+    fn locked_step(id: &str, ttl_seconds: u32, wait_timeout_seconds: u32) -> serde_json::Value {
+        step(
+            id,
+            json!({"acquire_lock": {
+                "resource": "ledger",
+                "ttl_seconds": ttl_seconds,
+                "wait_timeout_seconds": wait_timeout_seconds,
+            }}),
+        )
+    }
+
+    /// Run `job` on a step executor taking its locks from `locks`
+    async fn run_locked(
+        job: &Job,
+        executor: Arc<ScriptedExecutor>,
+        locks: &MemoryLock,
+    ) -> Result<(), anyhow::Error> {
+        let steps = step_executor(executor, Arc::new(MemoryStorage::default()))
+            .with_step_locks(Arc::new(locks.clone()));
+        let mut execution = JobExecution::new_manual(job.id, "tester".to_string());
+        let mut context = JobContext::new(execution.id, job.id);
+        steps
+            .execute_all_steps(job, &mut context, &mut execution)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_step_lock_serializes_steps() {
+        let job = job(json!([locked_step("post", 5, 10)]));
+        let executor = Arc::new(ScriptedExecutor::default().with_delay(Duration::from_millis(300)));
+        let locks = MemoryLock::default();
+
+        // The second run waits for the first to release the lock
+        let (first, second) = tokio::join!(
+            run_locked(&job, executor.clone(), &locks),
+            run_locked(&job, executor.clone(), &locks)
+        );
+        assert!(first.is_ok());
+        assert!(second.is_ok());
+        assert_eq!(executor.calls.lock().unwrap().len(), 2);
+        assert_eq!(executor.max_running.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_step_fails_when_its_lock_wait_times_out() {
+        let job = job(json!([locked_step("post", 5, 0)]));
+        let executor = Arc::new(ScriptedExecutor::default());
+        let locks = MemoryLock::default();
+        let _held = locks
+            .acquire("step:ledger", Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let error = run_locked(&job, executor.clone(), &locks)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("gave up waiting"));
+        assert!(executor.calls.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_step_lock_is_renewed_and_step_fails_when_it_is_lost() {
+        let job = job(json!([locked_step("post", 1, 0)]));
+        let locks = MemoryLock::default();
+
+        // Renewed while the step runs past its TTL, so no one else takes it
+        let executor = Arc::new(ScriptedExecutor::default().with_delay(Duration::from_secs(2)));
+        let contender = locks.clone();
+        let contended = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(1500)).await;
+            contender
+                .acquire("step:ledger", Duration::from_secs(1))
+                .await
+                .is_err()
+        });
+        assert!(run_locked(&job, executor, &locks).await.is_ok());
+        assert!(contended.await.unwrap());
+
+        // Lost while the step runs
+        let executor = Arc::new(ScriptedExecutor::default().with_delay(Duration::from_secs(5)));
+        let expiring = locks.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            expiring.expire("step:ledger");
+        });
+        let started = Instant::now();
+        let error = run_locked(&job, executor, &locks).await.unwrap_err();
+        assert!(error.to_string().contains("lost lock ledger"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,354 @@
+// Fakes for tests of the job processor and step executor
+// Repositories use a database that isn't there, so their calls fail fast and the
+// code under test takes its error paths for them.
+
+use crate::callback::CallbackSender;
+use crate::config::CallbackConfig;
+use crate::db::repositories::connection_profile::ConnectionProfileRepository;
+use crate::db::repositories::execution::ExecutionRepository;
+use crate::db::repositories::failure_note::FailureNoteRepository;
+use crate::db::repositories::job::JobRepository;
+use crate::db::repositories::variable::VariableRepository;
+use crate::db::repositories::webhook_delivery::WebhookDeliveryRepository;
+use crate::db::DbPool;
+use crate::errors::{ExecutionError, QueueError, StorageError};
+use crate::executor::JobExecutor;
+use crate::models::{Job, JobContext, JobExecution, JobStep, StepOutput};
+use crate::queue::{JobMessage, JobPublisher};
+use crate::resource_guard::{ResourceGuard, ResourceLimits};
+use crate::retry::ExponentialBackoff;
+use crate::semaphore::{DistributedSemaphore, SemaphorePermit};
+use crate::storage::StorageService;
+use crate::telemetry::LogAlertNotifier;
+use crate::webhook::WebhookDispatcher;
+use crate::worker::context::JobContextManager;
+use crate::worker::reference::ReferenceResolver;
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use uuid::Uuid;
+
+use super::{
+    CircuitBreakerManager, ConcurrencyLimiter, JobDefinitionCache, JobProcessor, StepExecutor,
+};
+
+/// Job with the given steps, e.g. `json!([{"id": "a", ...}])`
+pub(crate) fn job(steps: serde_json::Value) -> Job {
+    serde_json::from_value(serde_json::json!({
+        "id": Uuid::new_v4(),
+        "name": "test-job",
+        "schedule": null,
+        "steps": steps,
+        "triggers": {"scheduled": false, "manual": true, "webhook": null},
+        "timeout_seconds": 30,
+        "max_retries": 0,
+        "definition": null,
+    }))
+    .expect("test job is valid")
+}
+
+/// Executor failing the steps it is told to and succeeding the others
+#[derive(Default)]
+pub(crate) struct ScriptedExecutor {
+    failing: HashSet<String>,
+    rejecting: bool,
+    delay: Duration,
+    running: AtomicUsize,
+    /// Most steps that ran at the same time
+    pub(crate) max_running: AtomicUsize,
+    /// Ids of the steps executed, in order
+    pub(crate) calls: Mutex<Vec<String>>,
+}
+
+impl ScriptedExecutor {
+    /// Executor failing every attempt of the given steps
+    pub(crate) fn failing(step_ids: &[&str]) -> Self {
+        Self {
+            failing: step_ids.iter().map(|id| id.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Fail the failing steps as refused requests, which retrying can't fix
+    pub(crate) fn rejecting(mut self) -> Self {
+        self.rejecting = true;
+        self
+    }
+
+    /// Executor taking `delay` to run each step
+    pub(crate) fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Ids of the steps executed, each once however often it was retried
+    pub(crate) fn steps_run(&self) -> Vec<String> {
+        let mut steps = self.calls.lock().unwrap().clone();
+        steps.dedup();
+        steps
+    }
+}
+
+#[async_trait]
+impl JobExecutor for ScriptedExecutor {
+    async fn execute(
+        &self,
+        step: &JobStep,
+        _context: &mut JobContext,
+    ) -> Result<StepOutput, ExecutionError> {
+        self.calls.lock().unwrap().push(step.id.clone());
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_running.fetch_max(running, Ordering::SeqCst);
+        tokio::time::sleep(self.delay).await;
+        self.running.fetch_sub(1, Ordering::SeqCst);
+
+        if self.failing.contains(&step.id) && self.rejecting {
+            return Err(ExecutionError::NotificationRejected(format!(
+                "{} rejected as scripted",
+                step.id
+            )));
+        }
+        if self.failing.contains(&step.id) {
+            return Err(ExecutionError::SystemCheckFailed(format!(
+                "{} failed as scripted",
+                step.id
+            )));
+        }
+        Ok(StepOutput {
+            step_id: step.id.clone(),
+            status: "success".to_string(),
+            output: serde_json::json!({"step": step.id}),
+            started_at: Utc::now(),
+            completed_at: Utc::now(),
+        })
+    }
+}
+
+/// Storage keeping files and contexts in memory
+#[derive(Default)]
+pub(crate) struct MemoryStorage {
+    files: Mutex<HashMap<String, Vec<u8>>>,
+    contexts: Mutex<HashMap<Uuid, JobContext>>,
+}
+
+#[async_trait]
+impl StorageService for MemoryStorage {
+    async fn store_job_definition(&self, _: Uuid, _: &str) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn load_job_definition(&self, job_id: Uuid) -> Result<String, StorageError> {
+        Err(StorageError::NotFound(job_id.to_string()))
+    }
+
+    async fn store_context(&self, context: &JobContext) -> Result<(), StorageError> {
+        self.contexts
+            .lock()
+            .unwrap()
+            .insert(context.execution_id, context.clone());
+        Ok(())
+    }
+
+    async fn load_context(
+        &self,
+        job_id: Uuid,
+        execution_id: Uuid,
+    ) -> Result<JobContext, StorageError> {
+        Ok(self
+            .contexts
+            .lock()
+            .unwrap()
+            .get(&execution_id)
+            .cloned()
+            .unwrap_or_else(|| JobContext::new(execution_id, job_id)))
+    }
+
+    async fn store_file(&self, path: &str, data: &[u8]) -> Result<String, StorageError> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), data.to_vec());
+        Ok(path.to_string())
+    }
+
+    async fn append_file(&self, path: &str, data: &[u8]) -> Result<(), StorageError> {
+        self.files
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_default()
+            .extend_from_slice(data);
+        Ok(())
+    }
+
+    async fn load_file(&self, path: &str) -> Result<Vec<u8>, StorageError> {
+        self.files
+            .lock()
+            .unwrap()
+            .get(path)
+            .cloned()
+            .ok_or_else(|| StorageError::NotFound(path.to_string()))
+    }
+
+    async fn delete_file(&self, path: &str) -> Result<(), StorageError> {
+        self.files.lock().unwrap().remove(path);
+        Ok(())
+    }
+
+    async fn list_files(&self, prefix: &str) -> Result<Vec<String>, StorageError> {
+        Ok(self
+            .files
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|path| path.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Publisher recording the messages put back on the queue
+#[derive(Default)]
+pub(crate) struct RecordingPublisher {
+    pub(crate) delayed: Mutex<Vec<JobMessage>>,
+}
+
+#[async_trait]
+impl JobPublisher for RecordingPublisher {
+    async fn publish(&self, _execution: &JobExecution) -> Result<(), QueueError> {
+        Ok(())
+    }
+
+    async fn publish_delayed(
+        &self,
+        message: &JobMessage,
+        _tenant_id: Option<&str>,
+    ) -> Result<(), QueueError> {
+        self.delayed.lock().unwrap().push(message.clone());
+        Ok(())
+    }
+
+    async fn publish_with_retry(
+        &self,
+        _execution: &JobExecution,
+        _max_retries: u32,
+    ) -> Result<(), QueueError> {
+        Ok(())
+    }
+}
+
+/// Semaphore whose permits are held until the test ends
+#[derive(Default)]
+pub(crate) struct MemorySemaphore {
+    holders: Mutex<HashMap<String, HashSet<String>>>,
+}
+
+#[async_trait]
+impl DistributedSemaphore for MemorySemaphore {
+    async fn try_acquire(
+        &self,
+        resource: &str,
+        holder: &str,
+        limit: u32,
+        _ttl: Duration,
+    ) -> Result<Option<SemaphorePermit>, StorageError> {
+        let mut holders = self.holders.lock().unwrap();
+        let holders = holders.entry(resource.to_string()).or_default();
+        if !holders.contains(holder) && holders.len() >= limit as usize {
+            return Ok(None);
+        }
+        holders.insert(holder.to_string());
+        Ok(Some(SemaphorePermit::unbacked(resource, holder)))
+    }
+}
+
+/// Job processor running every step type on `executor`
+pub(crate) fn job_processor(
+    executor: Arc<dyn JobExecutor>,
+    concurrency_limiter: Option<ConcurrencyLimiter>,
+) -> JobProcessor {
+    let pool = DbPool::unreachable();
+    let storage: Arc<dyn StorageService> = Arc::new(MemoryStorage::default());
+    let dispatcher = WebhookDispatcher::new(
+        WebhookDeliveryRepository::new(pool.clone()),
+        CallbackConfig::default(),
+    )
+    .expect("webhook client builds");
+    JobProcessor::new(
+        Arc::new(JobRepository::new(pool.clone())),
+        Arc::new(ExecutionRepository::new(pool.clone())),
+        Arc::new(FailureNoteRepository::new(pool.clone())),
+        Arc::new(ConnectionProfileRepository::new(pool.clone())),
+        Arc::new(VariableRepository::new(pool, None)),
+        Arc::new(JobContextManager::new(Arc::clone(&storage))),
+        storage,
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        executor,
+        Arc::new(ExponentialBackoff::with_config(0, 0, 0.0)),
+        Arc::new(CircuitBreakerManager::new(Default::default())),
+        Arc::new(ReferenceResolver::new()),
+        Arc::new(LogAlertNotifier::default()),
+        Arc::new(CallbackSender::new(Arc::new(dispatcher))),
+        Duration::from_secs(30),
+        Arc::new(ResourceGuard::new(
+            ResourceLimits::default(),
+            Duration::from_secs(1),
+        )),
+        Arc::new(JobDefinitionCache::new(16)),
+        None,
+        None,
+        None,
+        concurrency_limiter,
+        None,
+        None,
+    )
+}
+
+/// Step executor running every step type on `executor`
+pub(crate) fn step_executor(
+    executor: Arc<dyn JobExecutor>,
+    storage: Arc<dyn StorageService>,
+) -> StepExecutor {
+    StepExecutor::new(
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        Arc::clone(&executor),
+        executor,
+        storage,
+        Arc::new(ReferenceResolver::new()),
+        Arc::new(CircuitBreakerManager::new(Default::default())),
+        Arc::new(ExponentialBackoff::with_config(0, 0, 0.0)),
+        Arc::new(ExecutionRepository::new(DbPool::unreachable())),
+    )
+}
@@ -2,10 +2,12 @@
 // Requirements: 13.7, 13.8 - Load and save Job Context from storage
 // RECC 2025: No unwrap(), use #[tracing::instrument], proper error handling
 
+use crate::db::repositories::JobStateRepository;
 use crate::errors::ExecutionError;
 use crate::models::JobContext;
-use crate::storage::StorageService;
+use crate::storage::{residency, StorageService};
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::{debug, error, info, instrument};
 use uuid::Uuid;
@@ -33,19 +35,44 @@ pub trait ContextManager: Send + Sync {
         job_id: Uuid,
         execution_id: Uuid,
     ) -> Result<JobContext, ExecutionError>;
+
+    /// Load the state a job keeps across its executions, by key
+    async fn load_state(
+        &self,
+        job_id: Uuid,
+    ) -> Result<HashMap<String, serde_json::Value>, ExecutionError>;
+
+    /// Write keys of a job's state on behalf of one of its executions
+    async fn save_state(
+        &self,
+        job_id: Uuid,
+        execution_id: Uuid,
+        values: &HashMap<String, serde_json::Value>,
+    ) -> Result<(), ExecutionError>;
 }
 
 /// Job context manager implementation using Storage service
 /// Requirements: 13.7, 13.8 - Manage Job Context with storage
 pub struct JobContextManager {
     storage_service: Arc<dyn StorageService>,
+    state_repo: Option<JobStateRepository>,
 }
 
 impl JobContextManager {
     /// Create a new JobContextManager with Storage service
     /// Requirements: 13.7 - Initialize context manager with storage
     pub fn new(storage_service: Arc<dyn StorageService>) -> Self {
-        Self { storage_service }
+        Self {
+            storage_service,
+            state_repo: None,
+        }
+    }
+
+    /// Keep job state in the `job_state` table, or in their tenant's store for tenants
+    /// with data residency rules; without it jobs have no state and writing it fails
+    pub fn with_state_store(mut self, state_repo: JobStateRepository) -> Self {
+        self.state_repo = Some(state_repo);
+        self
     }
 }
 
@@ -145,6 +172,69 @@ impl ContextManager for JobContextManager {
         );
         Ok(context)
     }
+
+    /// Load the state a job keeps across its executions, by key
+    #[instrument(skip(self), fields(job_id = %job_id))]
+    async fn load_state(
+        &self,
+        job_id: Uuid,
+    ) -> Result<HashMap<String, serde_json::Value>, ExecutionError> {
+        let Some(state_repo) = &self.state_repo else {
+            return Ok(HashMap::new());
+        };
+
+        let entries = residency::load_state(self.storage_service.as_ref(), state_repo, job_id)
+            .await
+            .map_err(|e| {
+                error!(error = %e, job_id = %job_id, "Failed to load job state");
+                ExecutionError::ContextLoadFailed(format!(
+                    "Failed to load state of job {}: {}",
+                    job_id, e
+                ))
+            })?;
+
+        debug!(job_id = %job_id, keys = entries.len(), "Job state loaded");
+        Ok(entries
+            .into_iter()
+            .map(|entry| (entry.key, entry.value))
+            .collect())
+    }
+
+    /// Write keys of a job's state on behalf of one of its executions
+    #[instrument(skip(self, values), fields(job_id = %job_id, execution_id = %execution_id))]
+    async fn save_state(
+        &self,
+        job_id: Uuid,
+        execution_id: Uuid,
+        values: &HashMap<String, serde_json::Value>,
+    ) -> Result<(), ExecutionError> {
+        let Some(state_repo) = &self.state_repo else {
+            return Err(ExecutionError::ContextSaveFailed(
+                "No job state store configured".to_string(),
+            ));
+        };
+
+        residency::save_state(
+            self.storage_service.as_ref(),
+            state_repo,
+            job_id,
+            values,
+            Some(execution_id),
+        )
+        .await
+        .map_err(|e| {
+            error!(
+                error = %e,
+                job_id = %job_id,
+                execution_id = %execution_id,
+                "Failed to save job state"
+            );
+            ExecutionError::ContextSaveFailed(format!(
+                "Failed to save state of job {}: {}",
+                job_id, e
+            ))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -205,6 +295,10 @@ mod tests {
             Ok("mock_file_path".to_string())
         }
 
+        async fn append_file(&self, _path: &str, _data: &[u8]) -> Result<(), StorageError> {
+            Ok(())
+        }
+
         async fn load_file(&self, _path: &str) -> Result<Vec<u8>, StorageError> {
             Ok(vec![])
         }
@@ -3,6 +3,7 @@
 pub mod consumer;
 pub mod context;
 pub mod reference;
+pub mod registry;
 
 pub use consumer::WorkerJobConsumer;
 pub use context::{ContextManager, JobContextManager};
@@ -1,4 +1,4 @@
-// Reference resolver for variables and step outputs
+// Reference resolver for variables, step outputs and job state
 // Requirements: 14.1, 14.2, 14.4
 
 use crate::models::JobContext;
@@ -61,8 +61,20 @@ impl ReferenceResolver {
         Ok(result)
     }
 
-    /// Resolve a single reference
-    fn resolve_reference(&self, reference: &str, context: &JobContext) -> Result<String, String> {
+    /// References in a template, e.g. `steps.fetch.output.id` for `{{steps.fetch.output.id}}`
+    pub fn references<'a>(&self, template: &'a str) -> Vec<&'a str> {
+        self.get_pattern()
+            .captures_iter(template)
+            .filter_map(|cap| cap.get(1).map(|reference| reference.as_str().trim()))
+            .collect()
+    }
+
+    /// Resolve a single reference, given without its braces
+    pub fn resolve_reference(
+        &self,
+        reference: &str,
+        context: &JobContext,
+    ) -> Result<String, String> {
         // Check if it's a step output reference (starts with "steps.")
         if reference.starts_with("steps.") {
             self.resolve_step_output(reference, context)
@@ -71,6 +83,11 @@ impl ReferenceResolver {
         else if reference.starts_with("webhook.") {
             self.resolve_webhook_data(reference, context)
         }
+        // Check if it's a job state reference (starts with "state.")
+        else if reference.starts_with("state.") {
+            let value = self.state_value(reference, context)?;
+            self.navigate_json_path(value, &[])
+        }
         // Otherwise, treat as a variable reference
         else {
             self.resolve_variable(reference, context)
@@ -148,6 +165,26 @@ impl ReferenceResolver {
         }
     }
 
+    /// The job state value a reference like "state.cursor.id" points at; state
+    /// staged by earlier steps of the execution shadows the stored state
+    fn state_value<'a>(
+        &self,
+        reference: &str,
+        context: &'a JobContext,
+    ) -> Result<&'a serde_json::Value, String> {
+        let parts: Vec<&str> = reference.split('.').collect();
+        let key = parts
+            .get(1)
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| format!("Invalid state reference: {}", reference))?;
+        let value = context
+            .pending_state
+            .get(*key)
+            .or_else(|| context.state.get(*key))
+            .ok_or_else(|| format!("State key '{}' not set for the job", key))?;
+        self.navigate_json_value(value, &parts[2..])
+    }
+
     /// Resolve a variable reference
     fn resolve_variable(&self, reference: &str, context: &JobContext) -> Result<String, String> {
         context
@@ -164,6 +201,24 @@ impl ReferenceResolver {
         value: &serde_json::Value,
         path: &[&str],
     ) -> Result<String, String> {
+        let current = self.navigate_json_value(value, path)?;
+
+        // Convert the final value to a string
+        match current {
+            serde_json::Value::String(s) => Ok(s.clone()),
+            serde_json::Value::Number(n) => Ok(n.to_string()),
+            serde_json::Value::Bool(b) => Ok(b.to_string()),
+            serde_json::Value::Null => Ok("null".to_string()),
+            _ => Ok(current.to_string()),
+        }
+    }
+
+    /// Navigate a JSON path and return the value it points at
+    fn navigate_json_value<'a>(
+        &self,
+        value: &'a serde_json::Value,
+        path: &[&str],
+    ) -> Result<&'a serde_json::Value, String> {
         let mut current = value;
 
         for part in path {
@@ -180,15 +235,89 @@ impl ReferenceResolver {
             }
         }
 
-        // Convert the final value to a string
-        match current {
-            serde_json::Value::String(s) => Ok(s.clone()),
-            serde_json::Value::Number(n) => Ok(n.to_string()),
-            serde_json::Value::Bool(b) => Ok(b.to_string()),
-            serde_json::Value::Null => Ok("null".to_string()),
-            _ => Ok(current.to_string()),
+        Ok(current)
+    }
+
+    /// Resolve references in every string of a JSON value
+    ///
+    /// A string that is exactly one reference becomes the referenced value itself, so
+    /// numbers, booleans and objects keep their type.
+    pub fn resolve_json(
+        &self,
+        value: &serde_json::Value,
+        context: &JobContext,
+    ) -> Result<serde_json::Value, String> {
+        match value {
+            serde_json::Value::String(template) => match self.whole_reference(template) {
+                Some(reference) => self.resolve_reference_value(reference, context),
+                None => self
+                    .resolve(template, context)
+                    .map(serde_json::Value::String),
+            },
+            serde_json::Value::Array(items) => items
+                .iter()
+                .map(|item| self.resolve_json(item, context))
+                .collect::<Result<Vec<_>, _>>()
+                .map(serde_json::Value::Array),
+            serde_json::Value::Object(fields) => fields
+                .iter()
+                .map(|(key, item)| Ok((key.clone(), self.resolve_json(item, context)?)))
+                .collect::<Result<serde_json::Map<_, _>, String>>()
+                .map(serde_json::Value::Object),
+            _ => Ok(value.clone()),
+        }
+    }
+
+    /// The reference when `template` consists of a single `{{...}}` and nothing else
+    fn whole_reference<'a>(&self, template: &'a str) -> Option<&'a str> {
+        let cap = self.get_pattern().captures(template)?;
+        let full_match = cap.get(0)?;
+        if full_match.start() == 0 && full_match.end() == template.len() {
+            Some(cap.get(1)?.as_str().trim())
+        } else {
+            None
         }
     }
+
+    /// Resolve a single reference to its JSON value
+    fn resolve_reference_value(
+        &self,
+        reference: &str,
+        context: &JobContext,
+    ) -> Result<serde_json::Value, String> {
+        let parts: Vec<&str> = reference.split('.').collect();
+        let resolved = if reference.starts_with("steps.") {
+            if parts.len() < 3 {
+                return Err(format!("Invalid step reference: {}", reference));
+            }
+            let step_output = context
+                .steps
+                .get(parts[1])
+                .ok_or_else(|| format!("Step '{}' not found in context", parts[1]))?;
+            self.navigate_json_value(&step_output.output, &parts[2..])
+                .cloned()
+        } else if reference.starts_with("webhook.") && parts.get(1) == Some(&"payload") {
+            let webhook_data = context
+                .webhook
+                .as_ref()
+                .ok_or_else(|| "No webhook data in context".to_string())?;
+            self.navigate_json_value(&webhook_data.payload, &parts[2..])
+                .cloned()
+        } else if reference.starts_with("webhook.") {
+            self.resolve_webhook_data(reference, context)
+                .map(serde_json::Value::String)
+        } else if reference.starts_with("state.") {
+            self.state_value(reference, context).cloned()
+        } else {
+            context
+                .variables
+                .get(reference)
+                .cloned()
+                .ok_or_else(|| format!("Variable '{}' not found in context", reference))
+        };
+
+        resolved.map_err(|e| format!("Failed to resolve '{}': {}", reference, e))
+    }
 }
 
 impl Default for ReferenceResolver {
@@ -215,6 +344,14 @@ mod tests {
             steps: HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: HashMap::new(),
+            pending_state: HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         context
@@ -237,6 +374,14 @@ mod tests {
             steps: HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: HashMap::new(),
+            pending_state: HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         let step_output = StepOutput {
@@ -260,6 +405,50 @@ mod tests {
         assert_eq!(result, "User ID: 123");
     }
 
+    #[test]
+    fn test_resolve_json_keeps_whole_reference_types() {
+        let resolver = ReferenceResolver::new();
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context
+            .variables
+            .insert("region".to_string(), serde_json::json!("eu"));
+        context.steps.insert(
+            "lookup".to_string(),
+            StepOutput {
+                step_id: "lookup".to_string(),
+                status: "success".to_string(),
+                output: serde_json::json!({"data": {"id": 123, "tags": ["a", "b"]}}),
+                started_at: Utc::now(),
+                completed_at: Utc::now(),
+            },
+        );
+
+        let resolved = resolver
+            .resolve_json(
+                &serde_json::json!({
+                    "id": "{{steps.lookup.data.id}}",
+                    "tags": "{{ steps.lookup.data.tags }}",
+                    "label": "user-{{steps.lookup.data.id}}-{{region}}",
+                    "limit": 10,
+                }),
+                &context,
+            )
+            .unwrap();
+
+        assert_eq!(
+            resolved,
+            serde_json::json!({
+                "id": 123,
+                "tags": ["a", "b"],
+                "label": "user-123-eu",
+                "limit": 10,
+            })
+        );
+        assert!(resolver
+            .resolve_json(&serde_json::json!(["{{missing}}"]), &context)
+            .is_err());
+    }
+
     #[test]
     fn test_resolve_webhook_payload() {
         let resolver = ReferenceResolver::new();
@@ -277,6 +466,14 @@ mod tests {
                 headers: HashMap::new(),
             }),
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: HashMap::new(),
+            pending_state: HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         let template = "Processing action for user {{webhook.payload.user_id}}";
@@ -295,6 +492,14 @@ mod tests {
             steps: HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: HashMap::new(),
+            pending_state: HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         let template = "Value: {{missing_var}}";
@@ -316,6 +521,14 @@ mod tests {
             steps: HashMap::new(),
             webhook: None,
             files: Vec::new(),
+            pii_steps: Vec::new(),
+            failure_routes: Vec::new(),
+            compensations: Vec::new(),
+            state: HashMap::new(),
+            pending_state: HashMap::new(),
+            progress: Default::default(),
+            env: Default::default(),
+            query_parameters: Vec::new(),
         };
 
         context
@@ -330,4 +543,45 @@ mod tests {
 
         assert_eq!(result, "https://api.example.com:443/api/v1");
     }
+
+    #[test]
+    fn test_resolve_job_state() {
+        let resolver = ReferenceResolver::new();
+        let mut context = JobContext::new(Uuid::new_v4(), Uuid::new_v4());
+        context
+            .state
+            .insert("last_processed_id".to_string(), serde_json::json!(4200));
+        context.state.insert(
+            "cursor".to_string(),
+            serde_json::json!({"table": "orders", "id": 17}),
+        );
+
+        let result = resolver
+            .resolve(
+                "SELECT * FROM orders WHERE id > {{state.last_processed_id}}",
+                &context,
+            )
+            .unwrap();
+        assert_eq!(result, "SELECT * FROM orders WHERE id > 4200");
+        assert_eq!(
+            resolver
+                .resolve_json(&serde_json::json!("{{state.cursor.id}}"), &context)
+                .unwrap(),
+            serde_json::json!(17)
+        );
+
+        let result = resolver.resolve("{{state.missing}}", &context);
+        assert!(result.unwrap_err().contains("State key 'missing' not set"));
+
+        // Later steps see the state earlier steps staged
+        context
+            .pending_state
+            .insert("last_processed_id".to_string(), serde_json::json!(4300));
+        assert_eq!(
+            resolver
+                .resolve("{{state.last_processed_id}}", &context)
+                .unwrap(),
+            "4300"
+        );
+    }
 }
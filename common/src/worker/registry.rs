@@ -0,0 +1,198 @@
+// Worker registry
+// Purpose: Let each worker publish its load and circuit-breaker states to Redis so the
+// dashboard can show every worker, and which targets they have stopped calling, in
+// one place
+//
+// Circuit breakers live in each worker's memory, so the registry is the only view of
+// them outside the worker. Entries of workers that stopped reporting are shown as
+// stale for a while and then dropped.
+
+use crate::circuit_breaker::CircuitState;
+use crate::errors::StorageError;
+use crate::resource_guard::ResourceGuard;
+use crate::worker::consumer::CircuitBreakerManager;
+use chrono::{DateTime, Duration, Utc};
+use redis::aio::ConnectionLike;
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Redis hash holding one entry per worker instance
+const REGISTRY_KEY: &str = "workers:registry";
+
+/// How often a worker reports
+pub const REPORT_INTERVAL_SECONDS: i64 = 15;
+
+/// A worker is considered gone after missing this many reports
+const STALE_AFTER_REPORTS: i64 = 3;
+
+/// Entries not refreshed for this long are dropped (restarted instances)
+const RETENTION_HOURS: i64 = 1;
+
+/// State of the circuit breaker a worker keeps for one target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerStatus {
+    pub target: String,
+    pub state: CircuitState,
+    pub failure_count: u32,
+}
+
+/// What a worker last reported about itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerStatus {
+    pub instance: String,
+    pub region: String,
+    pub started_at: DateTime<Utc>,
+    pub reported_at: DateTime<Utc>,
+    pub running_executions: usize,
+    pub memory_bytes: Option<u64>,
+    pub circuit_breakers: Vec<CircuitBreakerStatus>,
+}
+
+impl WorkerStatus {
+    /// Whether the worker has missed enough reports to be presumed gone
+    pub fn is_stale(&self, now: DateTime<Utc>) -> bool {
+        now - self.reported_at > Duration::seconds(REPORT_INTERVAL_SECONDS * STALE_AFTER_REPORTS)
+    }
+}
+
+/// Worker statuses, kept in Redis
+pub struct WorkerRegistry<C> {
+    conn: C,
+}
+
+impl<C> WorkerRegistry<C>
+where
+    C: ConnectionLike + Clone + Send + Sync,
+{
+    pub fn new(conn: C) -> Self {
+        Self { conn }
+    }
+
+    /// Publish the status of one worker
+    pub async fn report(&self, status: &WorkerStatus) -> Result<(), StorageError> {
+        let value =
+            serde_json::to_string(status).map_err(|e| StorageError::InvalidJson(e.to_string()))?;
+        let mut conn = self.conn.clone();
+        conn.hset::<_, _, _, ()>(REGISTRY_KEY, &status.instance, value)
+            .await
+            .map_err(|e| StorageError::RedisError(e.to_string()))
+    }
+
+    /// Every worker that reported within the retention window, oldest entries pruned
+    pub async fn workers(&self) -> Result<Vec<WorkerStatus>, StorageError> {
+        let mut conn = self.conn.clone();
+        let entries: HashMap<String, String> = conn
+            .hgetall(REGISTRY_KEY)
+            .await
+            .map_err(|e| StorageError::RedisError(e.to_string()))?;
+
+        let cutoff = Utc::now() - Duration::hours(RETENTION_HOURS);
+        let mut workers = Vec::new();
+        let mut expired = Vec::new();
+        for (field, value) in entries {
+            match serde_json::from_str::<WorkerStatus>(&value) {
+                Ok(status) if status.reported_at >= cutoff => workers.push(status),
+                _ => expired.push(field),
+            }
+        }
+        if !expired.is_empty() {
+            conn.hdel::<_, _, ()>(REGISTRY_KEY, expired)
+                .await
+                .map_err(|e| StorageError::RedisError(e.to_string()))?;
+        }
+
+        workers.sort_by(|a, b| a.instance.cmp(&b.instance));
+        Ok(workers)
+    }
+}
+
+/// Periodically reports this worker to the registry
+pub struct WorkerReporter<C> {
+    registry: WorkerRegistry<C>,
+    instance: String,
+    region: String,
+    started_at: DateTime<Utc>,
+    resource_guard: Arc<ResourceGuard>,
+    circuit_breakers: Arc<CircuitBreakerManager>,
+}
+
+impl<C> WorkerReporter<C>
+where
+    C: ConnectionLike + Clone + Send + Sync + 'static,
+{
+    pub fn new(
+        conn: C,
+        region: impl Into<String>,
+        resource_guard: Arc<ResourceGuard>,
+        circuit_breakers: Arc<CircuitBreakerManager>,
+    ) -> Self {
+        let hostname = std::env::var("HOSTNAME").unwrap_or_else(|_| Uuid::new_v4().to_string());
+        Self {
+            registry: WorkerRegistry::new(conn),
+            instance: format!("{}-{}", hostname, std::process::id()),
+            region: region.into(),
+            started_at: Utc::now(),
+            resource_guard,
+            circuit_breakers,
+        }
+    }
+
+    /// Start reporting in the background
+    pub fn spawn(self) {
+        info!(instance = %self.instance, "Reporting worker status to the registry");
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                REPORT_INTERVAL_SECONDS as u64,
+            ));
+            loop {
+                interval.tick().await;
+                let status = WorkerStatus {
+                    instance: self.instance.clone(),
+                    region: self.region.clone(),
+                    started_at: self.started_at,
+                    reported_at: Utc::now(),
+                    running_executions: self.resource_guard.running_executions(),
+                    memory_bytes: self.resource_guard.memory_bytes(),
+                    circuit_breakers: self.circuit_breakers.statuses().await,
+                };
+                if let Err(e) = self.registry.report(&status).await {
+                    warn!(error = %e, "Failed to report worker status");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_goes_stale_after_missed_reports() {
+        let now = Utc::now();
+        let mut status = WorkerStatus {
+            instance: "worker-1".to_string(),
+            region: "hn".to_string(),
+            started_at: now - Duration::hours(2),
+            reported_at: now - Duration::seconds(REPORT_INTERVAL_SECONDS),
+            running_executions: 2,
+            memory_bytes: None,
+            circuit_breakers: vec![CircuitBreakerStatus {
+                target: "https://erp.example.com".to_string(),
+                state: CircuitState::Open,
+                failure_count: 5,
+            }],
+        };
+        assert!(!status.is_stale(now));
+
+        status.reported_at = now - Duration::seconds(REPORT_INTERVAL_SECONDS * 4);
+        assert!(status.is_stale(now));
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["circuit_breakers"][0]["state"], "open");
+    }
+}
@@ -44,6 +44,8 @@ fn property_64_database_authentication() {
             password_hash: password_hash.clone(),
             email: Some(format!("{}@example.com", username)),
             enabled: true,
+            locale: None,
+            tenant_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -153,6 +155,8 @@ fn property_74_database_user_storage() {
             password_hash: password_hash.clone(),
             email: Some(email.clone()),
             enabled: true,
+            locale: None,
+            tenant_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -33,6 +33,8 @@ fn property_66_read_permission_enforcement() {
             permissions,
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -77,6 +79,8 @@ fn property_67_write_permission_enforcement() {
             permissions,
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -121,6 +125,8 @@ fn property_68_execute_permission_enforcement() {
             permissions,
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -165,6 +171,8 @@ fn property_69_delete_permission_enforcement() {
             permissions,
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -209,6 +217,8 @@ fn property_70_execution_read_permission_enforcement() {
             permissions,
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -252,6 +262,8 @@ mod audit_logging_tests {
             permissions: vec!["job:read".to_string()],
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let mut request = Request::builder()
@@ -325,6 +337,8 @@ fn property_multiple_permissions_all_checked() {
             permissions: permissions.clone(),
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -362,6 +376,8 @@ fn property_empty_permissions_rejects_all() {
             permissions: vec![],
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
@@ -398,6 +414,8 @@ fn property_admin_permissions_grant_all_access() {
             ],
             exp: 9999999999,
             iat: 0,
+            sid: None,
+            tenant_id: None,
         };
 
         let user = AuthenticatedUser(claims);
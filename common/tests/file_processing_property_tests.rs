@@ -80,6 +80,10 @@ fn arb_file_processing_options() -> impl Strategy<Value = FileProcessingOptions>
                 sheet_index,
                 transformations: vec![], // Empty for now
                 streaming,
+                source_paths: vec![],
+                password: None,
+                columns: vec![],
+                schema_sample_rows: None,
             },
         )
 }
@@ -148,6 +152,10 @@ fn property_excel_file_reading() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -170,6 +178,10 @@ fn property_excel_file_reading() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -223,6 +235,10 @@ fn property_excel_data_structure_preservation() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -243,6 +259,10 @@ fn property_excel_data_structure_preservation() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -318,6 +338,10 @@ fn property_csv_file_reading() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -340,6 +364,10 @@ fn property_csv_file_reading() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -394,6 +422,10 @@ fn property_csv_delimiter_support() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -414,6 +446,10 @@ fn property_csv_delimiter_support() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -474,6 +510,10 @@ fn property_excel_sheet_selection() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -495,6 +535,10 @@ fn property_excel_sheet_selection() {
                         sheet_index: Some(sheet_index),
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -554,6 +598,10 @@ fn property_data_transformation_application() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -582,6 +630,10 @@ fn property_data_transformation_application() {
                         sheet_index: None,
                         transformations,
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -636,6 +688,10 @@ fn property_excel_write_round_trip() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -657,6 +713,10 @@ fn property_excel_write_round_trip() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -723,6 +783,10 @@ fn property_csv_write_round_trip() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -744,6 +808,10 @@ fn property_csv_write_round_trip() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -807,6 +875,10 @@ fn property_file_output_path_format() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -867,6 +939,10 @@ fn property_file_metadata_storage() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -938,6 +1014,10 @@ fn property_invalid_file_format_error_handling() {
                         sheet_index: None,
                         transformations: vec![],
                         streaming: false,
+                        source_paths: vec![],
+                        password: None,
+                        columns: vec![],
+                        schema_sample_rows: None,
                     },
                 },
                 condition: None,
@@ -1000,6 +1080,10 @@ fn test_empty_excel_data() {
                     sheet_index: None,
                     transformations: vec![],
                     streaming: false,
+                    source_paths: vec![],
+                    password: None,
+                    columns: vec![],
+                    schema_sample_rows: None,
                 },
             },
             condition: None,
@@ -1036,6 +1120,10 @@ fn test_missing_source_path_error() {
                     sheet_index: None,
                     transformations: vec![],
                     streaming: false,
+                    source_paths: vec![],
+                    password: None,
+                    columns: vec![],
+                    schema_sample_rows: None,
                 },
             },
             condition: None,
@@ -1080,6 +1168,10 @@ fn test_missing_destination_path_error() {
                     sheet_index: None,
                     transformations: vec![],
                     streaming: false,
+                    source_paths: vec![],
+                    password: None,
+                    columns: vec![],
+                    schema_sample_rows: None,
                 },
             },
             condition: None,
@@ -1127,6 +1219,10 @@ fn test_missing_write_data_error() {
                     sheet_index: None,
                     transformations: vec![],
                     streaming: false,
+                    source_paths: vec![],
+                    password: None,
+                    columns: vec![],
+                    schema_sample_rows: None,
                 },
             },
             condition: None,
@@ -19,6 +19,14 @@ fn create_test_context() -> JobContext {
         steps: HashMap::new(),
         webhook: None,
         files: Vec::new(),
+        pii_steps: Vec::new(),
+        failure_routes: Vec::new(),
+        compensations: Vec::new(),
+        state: HashMap::new(),
+        pending_state: HashMap::new(),
+        progress: Default::default(),
+        env: Default::default(),
+        query_parameters: Vec::new(),
     }
 }
 
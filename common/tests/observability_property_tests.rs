@@ -202,7 +202,7 @@ proptest! {
 // **Validates: Requirements 5.8**
 #[tokio::test]
 async fn property_46_alert_notification_sending() {
-    let notifier = LogAlertNotifier;
+    let notifier = LogAlertNotifier::default();
     let job_id = Uuid::new_v4();
 
     // Test that alert can be sent for 3 consecutive failures
@@ -264,7 +264,7 @@ async fn test_full_observability_workflow() {
     assert!(should_trigger_alert(3));
 
     // Send alert
-    let notifier = LogAlertNotifier;
+    let notifier = LogAlertNotifier::default();
     let result = notifier.send_alert(&job_id, job_name, 3).await;
     assert!(result.is_ok());
 }
@@ -276,6 +276,9 @@ fn property_job_persistence_round_trip() {
                 expression: "0 0 * * * *".to_string(),
                 timezone: chrono_tz::Asia::Ho_Chi_Minh,
                 end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
             }),
             steps: vec![
                 JobStep {
@@ -404,6 +407,9 @@ fn property_schedule_serialization() {
             expression: cron_expr.clone(),
             timezone: chrono_tz::Asia::Ho_Chi_Minh,
             end_date: None,
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
         };
         let json = serde_json::to_string(&cron_schedule).unwrap();
         let deserialized: Schedule = serde_json::from_str(&json).unwrap();
@@ -424,10 +430,13 @@ fn property_schedule_serialization() {
         }
 
         // Test FixedRate schedule
-        let rate_schedule = Schedule::FixedRate { interval_seconds };
+        let rate_schedule = Schedule::FixedRate {
+            interval_seconds,
+            misfire_policy: None,
+        };
         let json = serde_json::to_string(&rate_schedule).unwrap();
         let deserialized: Schedule = serde_json::from_str(&json).unwrap();
-        if let Schedule::FixedRate { interval_seconds: i } = deserialized {
+        if let Schedule::FixedRate { interval_seconds: i, .. } = deserialized {
             prop_assert_eq!(i, interval_seconds);
         } else {
             panic!("Expected FixedRate schedule");
@@ -436,6 +445,8 @@ fn property_schedule_serialization() {
         // Test OneTime schedule
         let onetime_schedule = Schedule::OneTime {
             execute_at: Utc::now(),
+            valid_for_seconds: None,
+            misfire_policy: None,
         };
         let json = serde_json::to_string(&onetime_schedule).unwrap();
         let deserialized: Schedule = serde_json::from_str(&json).unwrap();
@@ -491,6 +502,7 @@ fn property_variable_serialization() {
             value: value.clone(),
             is_sensitive,
             scope: scope.clone(),
+            expires_at: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -589,6 +601,9 @@ fn property_timezone_aware_scheduling() {
                 expression: expression.clone(),
                 timezone: tz,
                 end_date: None,
+                valid_for_seconds: None,
+                calendar: None,
+                misfire_policy: None,
             };
 
             // Calculate next execution time
@@ -631,6 +646,9 @@ fn property_default_timezone_application() {
             expression,
             timezone: default_timezone(),
             end_date: None,
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
         };
 
         let next = schedule.next_execution_time(None).unwrap();
@@ -683,7 +701,10 @@ fn property_fixed_rate_timing() {
     proptest!(|(
         interval_seconds in 1u32..3600u32,
     )| {
-        let schedule = Schedule::FixedRate { interval_seconds };
+        let schedule = Schedule::FixedRate {
+            interval_seconds,
+            misfire_policy: None,
+        };
 
         // First execution should be immediate
         let first = schedule.next_execution_time(None).unwrap();
@@ -713,7 +734,11 @@ fn property_one_time_job_completion() {
         hours_ahead in 1i64..168i64, // 1 hour to 1 week ahead
     )| {
         let execute_at = Utc::now() + ChronoDuration::hours(hours_ahead);
-        let schedule = Schedule::OneTime { execute_at };
+        let schedule = Schedule::OneTime {
+            execute_at,
+            valid_for_seconds: None,
+            misfire_policy: None,
+        };
 
         // Before execution, should return the scheduled time
         let next_before = schedule.next_execution_time(None).unwrap();
@@ -744,6 +769,9 @@ fn property_end_date_enforcement() {
             expression: "0 0 * * * * *".to_string(),
             timezone: default_timezone(),
             end_date: Some(end_date),
+            valid_for_seconds: None,
+            calendar: None,
+            misfire_policy: None,
         };
 
         // Calculate next execution
@@ -772,7 +800,10 @@ fn property_recurring_schedules_never_complete() {
         interval_seconds in 1u32..3600u32,
     )| {
         let delay_schedule = Schedule::FixedDelay { delay_seconds };
-        let rate_schedule = Schedule::FixedRate { interval_seconds };
+        let rate_schedule = Schedule::FixedRate {
+            interval_seconds,
+            misfire_policy: None,
+        };
 
         // These schedules should never be complete, regardless of execution history
         prop_assert!(!delay_schedule.is_complete(None));
@@ -789,6 +820,9 @@ fn property_cron_without_end_date_never_completes() {
         expression: "0 0 * * * * *".to_string(),
         timezone: default_timezone(),
         end_date: None,
+        valid_for_seconds: None,
+        calendar: None,
+        misfire_policy: None,
     };
 
     assert!(!schedule.is_complete(None));
@@ -1308,6 +1342,8 @@ fn property_distributed_lock_exclusivity() {
             let config = common::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
                 pool_size: 20,
+                sentinel: None,
+                cluster: None,
             };
             let pool = RedisPool::new(&config).await?;
 
@@ -1376,6 +1412,8 @@ fn property_single_scheduler_execution() {
             let config = common::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
                 pool_size: 100,
+                sentinel: None,
+                cluster: None,
             };
             let pool = RedisPool::new(&config).await?;
 
@@ -1452,6 +1490,8 @@ fn property_lock_ttl_expiration() {
             let config = common::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
                 pool_size: 10,
+                sentinel: None,
+                cluster: None,
             };
             let pool = RedisPool::new(&config).await?;
             let lock = RedLock::new(pool.clone());
@@ -1496,6 +1536,8 @@ fn property_lock_extension() {
             let config = common::config::RedisConfig {
                 url: "redis://localhost:6379".to_string(),
                 pool_size: 10,
+                sentinel: None,
+                cluster: None,
             };
             let pool = RedisPool::new(&config).await?;
             let lock = RedLock::new(pool.clone());
@@ -1698,6 +1740,10 @@ fn property_job_message_serialization_round_trip() {
             idempotency_key: idempotency_key.clone(),
             attempt,
             published_at: Utc::now(),
+            not_before: None,
+            retry_attempt: None,
+            holds: None,
+            priority: JobPriority::Normal,
         };
 
         // Serialize to JSON
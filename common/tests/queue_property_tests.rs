@@ -162,6 +162,10 @@ fn property_job_message_serialization_round_trip() {
             idempotency_key: idempotency_key.clone(),
             attempt,
             published_at: Utc::now(),
+            not_before: None,
+            retry_attempt: None,
+            holds: None,
+            priority: common::models::JobPriority::Normal,
         };
 
         // Serialize to JSON
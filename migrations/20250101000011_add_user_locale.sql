@@ -0,0 +1,7 @@
+-- Add preferred locale to users for localized API/dashboard messages
+-- Supported values: 'vi-VN', 'en-US' (NULL falls back to i18n.default_locale)
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS locale VARCHAR(10);
+
+COMMENT ON COLUMN users.locale IS 'Preferred message locale (vi-VN or en-US)';
@@ -0,0 +1,19 @@
+-- Create rate_limit_policies table for per-identity API rate limits
+-- Identities without a policy use the defaults from the [rate_limit] config section
+
+CREATE TABLE IF NOT EXISTS rate_limit_policies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    scope VARCHAR(20) NOT NULL CHECK (scope IN ('user', 'api_key', 'tenant')),
+    identity VARCHAR(255) NOT NULL,
+    requests_per_minute INTEGER NOT NULL CHECK (requests_per_minute > 0),
+    burst INTEGER NOT NULL CHECK (burst > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (scope, identity)
+);
+
+-- Add comment for documentation
+COMMENT ON TABLE rate_limit_policies IS 'Admin-configured API rate limit overrides per user, API key or tenant';
+COMMENT ON COLUMN rate_limit_policies.identity IS 'User ID, SHA-256 hash of the API key, or tenant ID';
+COMMENT ON COLUMN rate_limit_policies.requests_per_minute IS 'Sustained token bucket refill rate';
+COMMENT ON COLUMN rate_limit_policies.burst IS 'Token bucket capacity (maximum burst size)';
@@ -0,0 +1,36 @@
+-- Create execution statistics rollup tables
+-- Hourly and daily per-job aggregates maintained by a background task so the
+-- dashboard does not scan job_executions for success rates and durations
+
+CREATE TABLE IF NOT EXISTS execution_stats_hourly (
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    total_executions BIGINT NOT NULL DEFAULT 0,
+    successful_executions BIGINT NOT NULL DEFAULT 0,
+    failed_executions BIGINT NOT NULL DEFAULT 0,
+    total_duration_ms BIGINT NOT NULL DEFAULT 0,
+    max_duration_ms BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, bucket_start)
+);
+
+CREATE TABLE IF NOT EXISTS execution_stats_daily (
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    bucket_start TIMESTAMPTZ NOT NULL,
+    total_executions BIGINT NOT NULL DEFAULT 0,
+    successful_executions BIGINT NOT NULL DEFAULT 0,
+    failed_executions BIGINT NOT NULL DEFAULT 0,
+    total_duration_ms BIGINT NOT NULL DEFAULT 0,
+    max_duration_ms BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, bucket_start)
+);
+
+-- Create indexes for time-range dashboard queries
+CREATE INDEX idx_execution_stats_hourly_bucket ON execution_stats_hourly(bucket_start);
+CREATE INDEX idx_execution_stats_daily_bucket ON execution_stats_daily(bucket_start);
+
+-- Add comment for documentation
+COMMENT ON TABLE execution_stats_hourly IS 'Per-job execution counts and durations per hour (finished executions only)';
+COMMENT ON TABLE execution_stats_daily IS 'Per-job execution counts and durations per UTC day, rolled up from execution_stats_hourly';
+COMMENT ON COLUMN execution_stats_hourly.bucket_start IS 'Start of the hour the executions were created in';
@@ -0,0 +1,7 @@
+-- Add a version counter to jobs for optimistic concurrency control
+-- Every update bumps the version; updates carrying a stale version (If-Match) are rejected
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
+
+COMMENT ON COLUMN jobs.version IS 'Incremented on every update; exposed as the job ETag';
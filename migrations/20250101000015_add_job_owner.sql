@@ -0,0 +1,10 @@
+-- Add structured ownership metadata to jobs
+-- Shape: {"team": "...", "contact_email": "...", "slack": "#channel", "runbook_url": "https://..."}
+-- Surfaced in failure notifications and the dead letter view so on-call knows who to page
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS owner JSONB;
+
+CREATE INDEX IF NOT EXISTS idx_jobs_owner_team ON jobs ((owner->>'team'));
+
+COMMENT ON COLUMN jobs.owner IS 'Owning team and on-call contacts (team, contact_email, slack, runbook_url)';
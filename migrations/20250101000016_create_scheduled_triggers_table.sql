@@ -0,0 +1,30 @@
+-- Create scheduled_triggers table for one-off "run at" executions
+-- The scheduler dispatches pending rows once execute_at has passed; the job's
+-- recurring schedule is never modified
+
+CREATE TABLE IF NOT EXISTS scheduled_triggers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    execute_at TIMESTAMPTZ NOT NULL,
+    variables JSONB NOT NULL DEFAULT '{}'::jsonb,
+    callback JSONB,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'dispatched', 'cancelled', 'failed')),
+    execution_id UUID REFERENCES job_executions(id) ON DELETE SET NULL,
+    error TEXT,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    dispatched_at TIMESTAMPTZ
+);
+
+-- Partial index for the scheduler's due-trigger poll
+CREATE INDEX IF NOT EXISTS idx_scheduled_triggers_due
+    ON scheduled_triggers(execute_at)
+    WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_triggers_job_id ON scheduled_triggers(job_id);
+
+-- Add comment for documentation
+COMMENT ON TABLE scheduled_triggers IS 'One-time executions requested for a specific time via POST /api/jobs/{id}/trigger-at';
+COMMENT ON COLUMN scheduled_triggers.variables IS 'Variable overrides merged into the execution context';
+COMMENT ON COLUMN scheduled_triggers.execution_id IS 'Execution created when the trigger was dispatched';
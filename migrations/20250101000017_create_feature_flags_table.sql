@@ -0,0 +1,20 @@
+-- Create feature_flags table for per-tenant feature gating
+-- Rows without a tenant apply to every tenant; a tenant row overrides the global one
+
+CREATE TABLE IF NOT EXISTS feature_flags (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL,
+    tenant_id VARCHAR(255),
+    enabled BOOLEAN NOT NULL,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- One global row and at most one row per tenant for each flag
+CREATE UNIQUE INDEX IF NOT EXISTS idx_feature_flags_name_tenant
+    ON feature_flags (name, (COALESCE(tenant_id, '')));
+
+-- Add comment for documentation
+COMMENT ON TABLE feature_flags IS 'Admin-managed feature flags, e.g. step_type.<name> for experimental step types';
+COMMENT ON COLUMN feature_flags.tenant_id IS 'Tenant ID from the X-Tenant-ID header, or NULL for the global default';
@@ -0,0 +1,15 @@
+-- Deprecation and sunset workflow for jobs
+-- A deprecated job warns on trigger, only accepts edits from its owners and is
+-- disabled automatically by the scheduler once its sunset date passes
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS deprecated BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN IF NOT EXISTS sunset_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deprecation_reason TEXT;
+
+-- The scheduler sweeps enabled deprecated jobs whose sunset has passed
+CREATE INDEX IF NOT EXISTS idx_jobs_sunset ON jobs (sunset_at) WHERE deprecated AND enabled;
+
+COMMENT ON COLUMN jobs.deprecated IS 'Job is being retired and will be disabled at sunset_at';
+COMMENT ON COLUMN jobs.sunset_at IS 'When a deprecated job is automatically disabled';
+COMMENT ON COLUMN jobs.deprecation_reason IS 'Why the job is being retired';
@@ -0,0 +1,24 @@
+-- Attribute jobs to tenants and record notifications for usage reporting
+-- Executions, compute time and stored context size are derived from job_executions;
+-- notifications leave no other trace so each one is logged here
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(255);
+
+CREATE INDEX IF NOT EXISTS idx_jobs_tenant_id ON jobs(tenant_id) WHERE tenant_id IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS notification_events (
+    id BIGSERIAL PRIMARY KEY,
+    -- Kept when the job is deleted so past months still bill correctly
+    job_id UUID,
+    tenant_id VARCHAR(255),
+    kind VARCHAR(50) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_notification_events_created_at
+    ON notification_events(created_at, tenant_id);
+
+-- Add comment for documentation
+COMMENT ON COLUMN jobs.tenant_id IS 'Tenant ID from the X-Tenant-ID header when the job was created, or NULL';
+COMMENT ON TABLE notification_events IS 'One row per notification sent (alert, callback, sunset notice) for tenant usage reports';
+COMMENT ON COLUMN notification_events.tenant_id IS 'Tenant of the job at the time the notification was sent';
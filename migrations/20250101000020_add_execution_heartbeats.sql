@@ -0,0 +1,10 @@
+-- Progress heartbeats for running executions
+-- Workers refresh these while a step runs, so a long step that is still making
+-- progress can be told apart from one that has hung
+
+ALTER TABLE job_executions
+    ADD COLUMN IF NOT EXISTS heartbeat_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS progress JSONB;
+
+COMMENT ON COLUMN job_executions.heartbeat_at IS 'Last progress heartbeat from the worker running this execution';
+COMMENT ON COLUMN job_executions.progress IS 'Current step, elapsed time and optional percent from the last heartbeat';
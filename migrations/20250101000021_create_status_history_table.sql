@@ -0,0 +1,16 @@
+-- Execution status history
+-- Every status transition is recorded in the same transaction as the status update,
+-- so the history and job_executions.status never disagree
+
+CREATE TABLE IF NOT EXISTS status_history (
+    id BIGSERIAL PRIMARY KEY,
+    execution_id UUID NOT NULL REFERENCES job_executions(id) ON DELETE CASCADE,
+    from_status VARCHAR(50) NOT NULL,
+    to_status VARCHAR(50) NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_status_history_execution_id
+    ON status_history(execution_id, changed_at);
+
+COMMENT ON TABLE status_history IS 'Status transitions of job executions, oldest first per execution';
@@ -0,0 +1,8 @@
+-- Add custom Prometheus labels to jobs
+-- Shape: {"domain": "payments", "tier": "gold"}
+-- Attached to the job's execution metrics so dashboards can slice by business domain
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS metric_labels JSONB NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN jobs.metric_labels IS 'Extra labels on the job''s execution metrics (at most 5)';
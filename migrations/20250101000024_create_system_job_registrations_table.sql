@@ -0,0 +1,11 @@
+-- Built-in system jobs registered on first boot
+-- One row per built-in job ever created, so a job an operator edits or deletes is
+-- never recreated by a later boot
+
+CREATE TABLE IF NOT EXISTS system_job_registrations (
+    key VARCHAR(100) PRIMARY KEY,
+    job_id UUID NOT NULL,
+    registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE system_job_registrations IS 'Built-in system jobs already registered, by key';
@@ -0,0 +1,26 @@
+-- Secret and certificate expiry tracking
+-- Sensitive variables may record when they stop working, and certificates or keys
+-- held outside the platform are tracked in tls_materials, so the daily expiry check
+-- can warn before they expire
+
+ALTER TABLE variables ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_variables_expires_at
+    ON variables (expires_at)
+    WHERE expires_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS tls_materials (
+    id UUID PRIMARY KEY,
+    name VARCHAR(255) NOT NULL UNIQUE,
+    kind VARCHAR(20) NOT NULL
+        CHECK (kind IN ('certificate', 'private_key', 'ca_bundle', 'ssh_key')),
+    description TEXT,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tls_materials_expires_at ON tls_materials (expires_at);
+
+COMMENT ON COLUMN variables.expires_at IS 'When the secret stops working, e.g. an SFTP key or API token';
+COMMENT ON TABLE tls_materials IS 'Certificates and keys whose expiry is tracked';
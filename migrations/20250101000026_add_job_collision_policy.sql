@@ -0,0 +1,10 @@
+-- Concurrent-run collision policy of jobs
+-- Applies to jobs with allow_concurrent = false that are due while a previous run is
+-- still active: skip the due run, queue it after the previous run, or cancel the
+-- previous run and start the due one
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS collision_policy VARCHAR(20) NOT NULL DEFAULT 'skip'
+        CHECK (collision_policy IN ('skip', 'queue_after', 'cancel_previous'));
+
+COMMENT ON COLUMN jobs.collision_policy IS 'skip, queue_after or cancel_previous; used when allow_concurrent is false';
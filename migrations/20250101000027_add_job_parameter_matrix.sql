@@ -0,0 +1,13 @@
+-- Add a parameter matrix to jobs
+-- Shape: {"branch": ["HN", "HCM", "DN"], "region": ["north", "south"]}
+-- Each scheduled run fans out into one execution per combination; the executions of
+-- one run share trigger_metadata->'matrix'->>'group_id'
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS parameter_matrix JSONB NOT NULL DEFAULT '{}';
+
+COMMENT ON COLUMN jobs.parameter_matrix IS 'Parameter values each scheduled run fans out over (at most 50 combinations)';
+
+CREATE INDEX IF NOT EXISTS idx_job_executions_matrix_group
+    ON job_executions ((trigger_metadata->'matrix'->>'group_id'))
+    WHERE trigger_metadata ? 'matrix';
@@ -0,0 +1,22 @@
+-- Keep recent webhook trigger requests per webhook
+-- Jobs can be simulated against a captured request (POST /api/jobs/{id}/simulate) to
+-- check mapping changes against real partner events. Only the most recent requests
+-- of each webhook are kept; older ones are pruned as new ones arrive.
+
+CREATE TABLE IF NOT EXISTS webhook_payloads (
+    id UUID PRIMARY KEY,
+    webhook_id UUID NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    execution_id UUID,
+    -- Payload, query parameters and custom headers, as placed in the job context
+    data JSONB NOT NULL,
+    received_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_webhook_payloads_webhook_received
+    ON webhook_payloads(webhook_id, received_at DESC);
+CREATE INDEX IF NOT EXISTS idx_webhook_payloads_job_received
+    ON webhook_payloads(job_id, received_at DESC);
+
+COMMENT ON TABLE webhook_payloads IS 'Recent webhook trigger requests, replayable in job simulations';
+COMMENT ON COLUMN webhook_payloads.execution_id IS 'Execution the request triggered';
@@ -0,0 +1,12 @@
+-- Per-user dashboard landing page layout (pinned jobs, stat cards, saved filters)
+-- Keyed by the token subject: local user ids, but also Keycloak subjects that have no
+-- row in users. Users without a row get the default layout.
+
+CREATE TABLE IF NOT EXISTS dashboard_layouts (
+    subject VARCHAR(255) PRIMARY KEY,
+    layout JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE dashboard_layouts IS 'Dashboard layout chosen by each user';
+COMMENT ON COLUMN dashboard_layouts.subject IS 'Token subject (sub claim) of the user';
@@ -0,0 +1,20 @@
+-- Saved execution filters, shared by link (filter id) or with a team
+-- criteria shape: {"status": "failed", "job_id": null, "job_name": "payroll",
+--   "trigger_source": "webhook", "tag": "domain=payments", "from": null, "to": null}
+
+CREATE TABLE IF NOT EXISTS execution_filters (
+    id UUID PRIMARY KEY,
+    name VARCHAR(64) NOT NULL,
+    owner VARCHAR(255) NOT NULL,
+    team VARCHAR(100),
+    criteria JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_execution_filters_owner ON execution_filters(owner);
+CREATE INDEX IF NOT EXISTS idx_execution_filters_team ON execution_filters(team) WHERE team IS NOT NULL;
+
+COMMENT ON TABLE execution_filters IS 'Named execution filters opened with ?filter_id= on the executions list';
+COMMENT ON COLUMN execution_filters.owner IS 'Token subject (sub claim) of the user who saved the filter';
+COMMENT ON COLUMN execution_filters.team IS 'Team the filter is listed to, NULL for filters only shared by link';
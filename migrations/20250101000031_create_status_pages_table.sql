@@ -0,0 +1,16 @@
+-- Public, token-scoped status pages showing selected jobs' latest status
+-- The token itself is never stored; lookups go by its SHA-256 hash
+
+CREATE TABLE IF NOT EXISTS status_pages (
+    id UUID PRIMARY KEY,
+    name VARCHAR(100) NOT NULL,
+    token_hash CHAR(64) NOT NULL UNIQUE,
+    job_ids UUID[] NOT NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE status_pages IS 'Read-only status pages opened without login at /status/{token}';
+COMMENT ON COLUMN status_pages.token_hash IS 'Hex SHA-256 of the page token; the token is only returned when created or rotated';
+COMMENT ON COLUMN status_pages.job_ids IS 'Jobs shown on the page, in display order';
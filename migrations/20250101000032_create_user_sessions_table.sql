@@ -0,0 +1,19 @@
+-- Sessions issued by database-mode logins, so a single stolen token can be revoked
+-- without rotating the JWT secret
+
+CREATE TABLE IF NOT EXISTS user_sessions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    user_agent TEXT,
+    ip_address VARCHAR(64),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX idx_user_sessions_user ON user_sessions(user_id, last_seen_at DESC);
+
+COMMENT ON TABLE user_sessions IS 'One row per login; the id is the sid claim of the tokens issued for it';
+COMMENT ON COLUMN user_sessions.expires_at IS 'Expiry of the latest token issued for the session; refreshing moves it forward';
+COMMENT ON COLUMN user_sessions.revoked_at IS 'Set when the session is revoked; its tokens are rejected from then on';
@@ -0,0 +1,16 @@
+-- Rotating Ed25519 keys that sign database-mode tokens, shared by all API instances
+-- Public keys are published at /.well-known/jwks.json
+
+CREATE TABLE IF NOT EXISTS jwt_signing_keys (
+    kid VARCHAR(64) PRIMARY KEY,
+    public_key BYTEA NOT NULL,
+    encrypted_private_key BYTEA NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    retired_at TIMESTAMPTZ
+);
+
+-- At most one key signs new tokens
+CREATE UNIQUE INDEX idx_jwt_signing_keys_current ON jwt_signing_keys ((retired_at IS NULL)) WHERE retired_at IS NULL;
+
+COMMENT ON COLUMN jwt_signing_keys.encrypted_private_key IS 'Nonce and AES-256-GCM sealed PKCS#8 key, under a key derived from auth.jwt_secret';
+COMMENT ON COLUMN jwt_signing_keys.retired_at IS 'When a newer key replaced it; kept until the tokens it signed have expired';
@@ -0,0 +1,21 @@
+-- Purging of step outputs tagged as personal data (PII) ahead of normal retention
+-- The audit trail outlives the executions, so it has no foreign key to them
+
+ALTER TABLE job_executions
+    ADD COLUMN IF NOT EXISTS pii_purged_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS pii_purges (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    execution_id UUID NOT NULL,
+    job_id UUID NOT NULL,
+    step_ids TEXT[] NOT NULL,
+    action VARCHAR(20) NOT NULL,
+    purged_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_pii_purges_execution ON pii_purges(execution_id);
+CREATE INDEX idx_pii_purges_purged_at ON pii_purges(purged_at DESC);
+
+COMMENT ON COLUMN job_executions.pii_purged_at IS 'When the PII purge processed the execution; also set when it found no PII-tagged outputs';
+COMMENT ON TABLE pii_purges IS 'Audit trail of PII purges: which step outputs of which execution were scrubbed or encrypted';
+COMMENT ON COLUMN pii_purges.action IS 'scrub or encrypt (to the age recipients of system_jobs.pii_recipients)';
@@ -0,0 +1,23 @@
+-- Reports of subject data erasures (GDPR art. 17, PDPD)
+-- Reports hold a hash of the erased identifier, never the identifier. Each report's
+-- hash covers its fields and the previous report's hash, so editing or deleting a
+-- report breaks the chain.
+
+CREATE TABLE IF NOT EXISTS erasure_reports (
+    id UUID PRIMARY KEY,
+    sequence BIGINT GENERATED ALWAYS AS IDENTITY UNIQUE,
+    identifier_hash VARCHAR(64) NOT NULL,
+    requested_by VARCHAR(255) NOT NULL,
+    matches JSONB NOT NULL,
+    skipped_files JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL,
+    previous_hash VARCHAR(64),
+    hash VARCHAR(64) NOT NULL
+);
+
+CREATE INDEX idx_erasure_reports_identifier ON erasure_reports(identifier_hash);
+
+COMMENT ON COLUMN erasure_reports.identifier_hash IS 'SHA-256 of the lowercased identifier';
+COMMENT ON COLUMN erasure_reports.matches IS 'Contexts, files and webhook payloads the identifier was redacted from';
+COMMENT ON COLUMN erasure_reports.skipped_files IS 'Execution files that could not be scanned: binary or too large';
+COMMENT ON COLUMN erasure_reports.hash IS 'SHA-256 over the other columns (except sequence) and previous_hash';
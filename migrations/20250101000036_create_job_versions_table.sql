@@ -0,0 +1,53 @@
+-- History of job definitions, one row per job version, for "as of" audits
+-- A row is written whenever a job is created or its version is bumped, and kept
+-- after the job is deleted. Executions record the job version they were created with.
+
+CREATE TABLE IF NOT EXISTS job_versions (
+    job_id UUID NOT NULL,
+    version INTEGER NOT NULL,
+    job JSONB NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, version)
+);
+
+CREATE INDEX idx_job_versions_recorded_at ON job_versions(job_id, recorded_at DESC);
+
+ALTER TABLE job_executions
+    ADD COLUMN IF NOT EXISTS job_version INTEGER;
+
+-- Existing jobs start their history at their current version
+INSERT INTO job_versions (job_id, version, job, recorded_at)
+SELECT
+    id,
+    version,
+    jsonb_build_object(
+        'id', id,
+        'name', name,
+        'description', description,
+        'schedule', definition -> 'schedule',
+        'steps', COALESCE(definition -> 'steps', '[]'::jsonb),
+        'triggers', trigger_config,
+        'enabled', enabled,
+        'timeout_seconds', timeout_seconds,
+        'max_retries', max_retries,
+        'allow_concurrent', allow_concurrent,
+        'collision_policy', collision_policy,
+        'owner', owner,
+        'metric_labels', metric_labels,
+        'parameter_matrix', parameter_matrix,
+        'definition', definition,
+        'version', version,
+        'deprecated', deprecated,
+        'sunset_at', sunset_at,
+        'deprecation_reason', deprecation_reason,
+        'tenant_id', tenant_id,
+        'created_at', created_at,
+        'updated_at', updated_at
+    ),
+    updated_at
+FROM jobs
+ON CONFLICT (job_id, version) DO NOTHING;
+
+COMMENT ON TABLE job_versions IS 'Snapshot of each job version; the version active at time T is the latest with recorded_at <= T';
+COMMENT ON COLUMN job_versions.job IS 'The job as returned by GET /api/jobs/{id}';
+COMMENT ON COLUMN job_executions.job_version IS 'Job version current when the execution was created; NULL for executions older than job_versions';
@@ -0,0 +1,26 @@
+-- Scheduler decision journal: one compact row per scheduler tick
+-- Records which jobs were due, fired or skipped (with the reason), which node held
+-- their locks and how long the tick took. The table is a ring: each scheduler trims
+-- rows older than the newest `scheduler.journal_max_ticks` after it writes.
+
+CREATE TABLE IF NOT EXISTS scheduler_journal (
+    id BIGSERIAL PRIMARY KEY,
+    node VARCHAR(255) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    status VARCHAR(20) NOT NULL,
+    jobs_due INTEGER NOT NULL DEFAULT 0,
+    jobs_fired INTEGER NOT NULL DEFAULT 0,
+    jobs_skipped INTEGER NOT NULL DEFAULT 0,
+    decisions JSONB NOT NULL DEFAULT '[]'::jsonb,
+    error TEXT
+);
+
+CREATE INDEX idx_scheduler_journal_started_at ON scheduler_journal(started_at DESC);
+
+-- Finding the ticks that decided about one job
+CREATE INDEX idx_scheduler_journal_decisions ON scheduler_journal USING GIN (decisions jsonb_path_ops);
+
+COMMENT ON TABLE scheduler_journal IS 'Per-tick scheduler decisions, trimmed to the newest scheduler.journal_max_ticks rows';
+COMMENT ON COLUMN scheduler_journal.status IS 'ran, standby (region not active), clock_skew or failed';
+COMMENT ON COLUMN scheduler_journal.decisions IS 'Array of {job_id, job_name, outcome, reason, error, execution_ids}';
@@ -0,0 +1,8 @@
+-- Strict ordering of job executions
+-- Execution N+1 of a job with strict_ordering waits in the worker until execution N
+-- has finished, across retries and redeliveries; enforced with a per-job Redis lock
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS strict_ordering BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN jobs.strict_ordering IS 'Executions run one at a time in the order they were created';
@@ -0,0 +1,19 @@
+-- Dead letters linked to the dependency they failed at
+-- Jobs with a dead_letter_policy dead-letter runs failing at a step with a
+-- dependency; with auto_replay they are replayed once the dependency's circuit
+-- breaker closes again, oldest first
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS dead_letter_policy JSONB;
+
+ALTER TABLE job_executions
+    ADD COLUMN IF NOT EXISTS dependency VARCHAR(255),
+    ADD COLUMN IF NOT EXISTS replayed_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_job_executions_dead_letter_dependency
+    ON job_executions (dependency, job_id, created_at)
+    WHERE status = 'dead_letter' AND replayed_at IS NULL;
+
+COMMENT ON COLUMN jobs.dead_letter_policy IS '{auto_replay, max_replays}; dead-letter runs failing at a step dependency';
+COMMENT ON COLUMN job_executions.dependency IS 'Dependency a dead-lettered execution failed at';
+COMMENT ON COLUMN job_executions.replayed_at IS 'When the dead letter was replayed after its dependency recovered';
@@ -0,0 +1,22 @@
+-- Create calendars table for business-day schedules
+-- Cron schedules name a calendar to skip weekends and holidays, or to move their
+-- runs to the next business day
+
+CREATE TABLE IF NOT EXISTS calendars (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT,
+    weekend SMALLINT[] NOT NULL DEFAULT '{6,7}',
+    vietnam_holidays BOOLEAN NOT NULL DEFAULT false,
+    holidays JSONB NOT NULL DEFAULT '[]',
+    working_days DATE[] NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Add comment for documentation
+COMMENT ON TABLE calendars IS 'Admin-managed business-day calendars referenced by name from cron schedules';
+COMMENT ON COLUMN calendars.weekend IS 'ISO weekdays (1 = Monday, 7 = Sunday) that are never business days';
+COMMENT ON COLUMN calendars.vietnam_holidays IS 'Observe Vietnamese public holidays, including the lunar ones';
+COMMENT ON COLUMN calendars.holidays IS '[{date, name}] further days off, e.g. bridge days around Tết';
+COMMENT ON COLUMN calendars.working_days IS 'Make-up working days that are business days although weekends or holidays';
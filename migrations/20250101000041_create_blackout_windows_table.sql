@@ -0,0 +1,27 @@
+-- Create blackout_windows table for periods a job must not be published
+-- Runs due during a window are skipped, or with behavior 'defer' made once when
+-- the window ends through a one-time scheduled trigger
+
+CREATE TABLE IF NOT EXISTS blackout_windows (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    name VARCHAR(255) NOT NULL,
+    starts_at TIMESTAMPTZ NOT NULL,
+    ends_at TIMESTAMPTZ NOT NULL,
+    behavior VARCHAR(20) NOT NULL DEFAULT 'skip'
+        CHECK (behavior IN ('skip', 'defer')),
+    deferred_trigger_id UUID REFERENCES scheduled_triggers(id) ON DELETE SET NULL,
+    created_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK (ends_at > starts_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_blackout_windows_job_id ON blackout_windows(job_id, starts_at);
+
+-- Index for the scheduler's active-window lookup
+CREATE INDEX IF NOT EXISTS idx_blackout_windows_ends_at ON blackout_windows(ends_at);
+
+-- Add comment for documentation
+COMMENT ON TABLE blackout_windows IS 'Periods, e.g. month-end close, during which the scheduler does not publish a job';
+COMMENT ON COLUMN blackout_windows.behavior IS 'skip drops runs due in the window; defer makes one run when it ends';
+COMMENT ON COLUMN blackout_windows.deferred_trigger_id IS 'Scheduled trigger created for the deferred run';
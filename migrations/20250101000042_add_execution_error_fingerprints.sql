@@ -0,0 +1,13 @@
+-- Failure fingerprints
+-- Executions whose errors differ only in ids, timestamps or counts share a
+-- fingerprint, so repeated failures can be listed as one issue.
+-- Executions recorded before this migration have no fingerprint.
+
+ALTER TABLE job_executions
+    ADD COLUMN IF NOT EXISTS error_fingerprint VARCHAR(16);
+
+CREATE INDEX IF NOT EXISTS idx_job_executions_error_fingerprint
+    ON job_executions(error_fingerprint, created_at DESC)
+    WHERE error_fingerprint IS NOT NULL;
+
+COMMENT ON COLUMN job_executions.error_fingerprint IS 'First 16 hex digits of the SHA-256 of the normalized error message, set whenever the error is written';
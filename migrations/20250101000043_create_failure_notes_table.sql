@@ -0,0 +1,15 @@
+-- Remediation notes for failure fingerprints
+-- Executions failing with a noted fingerprint carry the note in the API and in
+-- failure alerts, so the fix for a known failure doesn't have to be rediscovered
+
+CREATE TABLE IF NOT EXISTS failure_notes (
+    fingerprint VARCHAR(16) PRIMARY KEY,
+    note TEXT NOT NULL,
+    runbook_url TEXT,
+    updated_by VARCHAR(255) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE failure_notes IS 'Remediation notes and runbook links keyed by job_executions.error_fingerprint';
+COMMENT ON COLUMN failure_notes.updated_by IS 'Subject of the user who last wrote the note';
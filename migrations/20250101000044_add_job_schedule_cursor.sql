@@ -0,0 +1,9 @@
+-- Schedule cursor of each job
+-- The scheduler looks for runs due after last_scheduled_at, so runs missed while
+-- no scheduler was running are found and handled by the job's misfire policy.
+-- Jobs without a cursor look for runs due since they were created.
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS last_scheduled_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN jobs.last_scheduled_at IS 'Latest due time the scheduler has fired, skipped or dropped runs up to';
@@ -0,0 +1,18 @@
+-- Create connection_profiles table for reusable endpoints and credentials
+-- Steps name a profile with `connection` instead of embedding hosts, connection
+-- strings and passwords, so rotating a credential is a single update
+
+CREATE TABLE IF NOT EXISTS connection_profiles (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    description TEXT,
+    kind VARCHAR(20) NOT NULL,
+    endpoint JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Add comment for documentation
+COMMENT ON TABLE connection_profiles IS 'Admin-managed SFTP, database and HTTP endpoints referenced by name from job steps';
+COMMENT ON COLUMN connection_profiles.kind IS 'http, database or sftp; the step types that may use the profile follow from it';
+COMMENT ON COLUMN connection_profiles.endpoint IS 'Endpoint with its credentials, tagged by kind; secrets may be ${VAR} references to sensitive variables';
@@ -0,0 +1,9 @@
+-- Job priority levels
+-- With nats.priority_subjects, executions of high and low priority jobs are queued on
+-- their own subjects and workers take high priority ones first
+
+ALTER TABLE jobs
+    ADD COLUMN IF NOT EXISTS priority VARCHAR(10) NOT NULL DEFAULT 'normal'
+        CHECK (priority IN ('low', 'normal', 'high'));
+
+COMMENT ON COLUMN jobs.priority IS 'low, normal or high; queued executions of higher priority jobs are taken first';
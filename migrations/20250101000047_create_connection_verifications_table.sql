@@ -0,0 +1,25 @@
+-- Create connection_verifications table for credential rotation checks
+-- When a connection profile changes, every job using it gets a pending probe; the
+-- connection verification system job connects to the new endpoint with the job's
+-- steps and records which jobs would break before the old credential is revoked
+
+CREATE TABLE IF NOT EXISTS connection_verifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    connection_name VARCHAR(100) NOT NULL,
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'passed', 'failed')),
+    error TEXT,
+    requested_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    checked_at TIMESTAMPTZ,
+    UNIQUE (connection_name, job_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_connection_verifications_pending
+    ON connection_verifications(requested_at)
+    WHERE status = 'pending';
+
+-- Add comment for documentation
+COMMENT ON TABLE connection_verifications IS 'Latest verification probe of each job against each connection profile it uses';
+COMMENT ON COLUMN connection_verifications.status IS 'pending until probed, then passed or failed; a later profile change resets it to pending';
+COMMENT ON COLUMN connection_verifications.error IS 'Why the job would break with the current profile, when failed';
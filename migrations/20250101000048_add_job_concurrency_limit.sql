@@ -0,0 +1,8 @@
+-- Add per-job concurrency limits
+-- Caps how many executions of a job run at once across all workers; executions over
+-- the limit are queued until a slot frees up or rejected outright
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS concurrency_limit JSONB;
+
+-- Add comment for documentation
+COMMENT ON COLUMN jobs.concurrency_limit IS 'Optional {max_concurrent, overflow} cap on simultaneous executions; overflow is queue or reject';
@@ -0,0 +1,7 @@
+-- Pause scheduling without disabling a job
+-- A paused job stays enabled: manual and webhook triggers still run it and its
+-- history is kept, but the scheduler creates no runs until it is resumed
+
+ALTER TABLE jobs ADD COLUMN IF NOT EXISTS paused BOOLEAN NOT NULL DEFAULT false;
+
+COMMENT ON COLUMN jobs.paused IS 'Scheduler skips the job while true; manual and webhook triggers still run it';
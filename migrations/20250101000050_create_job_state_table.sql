@@ -0,0 +1,16 @@
+-- Create job_state table for values a job keeps across its executions
+-- Steps read them as {{state.<key>}} and write them with set_state, e.g. the
+-- last_processed_id cursor of an incremental sync
+
+CREATE TABLE IF NOT EXISTS job_state (
+    job_id UUID NOT NULL REFERENCES jobs(id) ON DELETE CASCADE,
+    key VARCHAR(255) NOT NULL,
+    value JSONB NOT NULL,
+    updated_by_execution UUID,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (job_id, key)
+);
+
+-- Add comment for documentation
+COMMENT ON TABLE job_state IS 'Per-job key/value state kept across executions';
+COMMENT ON COLUMN job_state.updated_by_execution IS 'Execution whose step last wrote the value; NULL when set through the API';
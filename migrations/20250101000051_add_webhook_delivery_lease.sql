@@ -0,0 +1,8 @@
+-- Lease of claimed webhook deliveries
+-- A worker claiming a delivery stamps it with its lease; only the lease holder
+-- records the attempt, so a delivery whose lease ran out and was claimed again is
+-- not recorded twice
+
+ALTER TABLE webhook_deliveries ADD COLUMN IF NOT EXISTS lease_id UUID;
+
+COMMENT ON COLUMN webhook_deliveries.lease_id IS 'Lease of the worker attempting the delivery; NULL when not claimed';
@@ -0,0 +1,13 @@
+-- Create scheduler_drains table for schedulers drained before a deploy or maintenance
+-- Schedulers read it on every tick, so a drain outlives restarts and missed control
+-- messages. A row for node '*' drains every node.
+
+CREATE TABLE IF NOT EXISTS scheduler_drains (
+    node TEXT PRIMARY KEY,
+    drained_by VARCHAR(255) NOT NULL,
+    drained_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Add comment for documentation
+COMMENT ON TABLE scheduler_drains IS 'Scheduler nodes that publish no new executions until resumed';
+COMMENT ON COLUMN scheduler_drains.node IS 'Node id as recorded in scheduler_journal, or * for every node';
@@ -0,0 +1,16 @@
+-- One completion callback per execution
+-- A redelivered or reprocessed execution queues its callback again; the key makes
+-- that return the delivery already queued instead of POSTing a second callback
+
+-- Keep the first callback queued for each execution
+DELETE FROM webhook_deliveries later
+USING webhook_deliveries earlier
+WHERE later.event = 'execution.completed'
+  AND earlier.event = 'execution.completed'
+  AND later.execution_id = earlier.execution_id
+  AND (later.created_at, later.id) > (earlier.created_at, earlier.id);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_webhook_deliveries_callback
+    ON webhook_deliveries(execution_id, event) WHERE event = 'execution.completed';
+
+COMMENT ON INDEX idx_webhook_deliveries_callback IS 'At most one completion callback per execution';
@@ -0,0 +1,10 @@
+-- Attribute users to tenants; tokens carry the tenant so jobs, feature flags and
+-- rate limits are resolved for it instead of a client-supplied header
+-- Keycloak users get their tenant from a `tenant_id` token claim instead
+
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS tenant_id VARCHAR(255);
+
+COMMENT ON COLUMN users.tenant_id IS 'Tenant the user acts for, or NULL';
+COMMENT ON COLUMN jobs.tenant_id IS 'Tenant of the user who created the job, or NULL';
+COMMENT ON COLUMN feature_flags.tenant_id IS 'Tenant the flag applies to, or NULL for the global default';
@@ -0,0 +1,18 @@
+-- Count the executions whose duration is summed, so the mean duration isn't
+-- dragged down by executions that finished without starting
+-- Buckets now hold the executions that finished in them; buckets inside the
+-- rollup lookback are rebuilt on the next refresh, older ones keep their counts
+
+ALTER TABLE execution_stats_hourly
+    ADD COLUMN IF NOT EXISTS timed_executions BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE execution_stats_daily
+    ADD COLUMN IF NOT EXISTS timed_executions BIGINT NOT NULL DEFAULT 0;
+
+-- Older buckets only counted finished executions, nearly all of which had started
+UPDATE execution_stats_hourly SET timed_executions = total_executions;
+UPDATE execution_stats_daily SET timed_executions = total_executions;
+
+COMMENT ON TABLE execution_stats_hourly IS 'Per-job counts and durations per hour of executions that ran to success or failure';
+COMMENT ON COLUMN execution_stats_hourly.bucket_start IS 'Start of the hour the executions finished in';
+COMMENT ON COLUMN execution_stats_hourly.timed_executions IS 'Executions with a start and end time, whose durations total_duration_ms sums';
+COMMENT ON COLUMN execution_stats_daily.timed_executions IS 'Executions with a start and end time, whose durations total_duration_ms sums';
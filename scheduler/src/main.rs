@@ -2,12 +2,25 @@
 // Requirements: 9.4, 12.3, 7.6
 
 use common::bootstrap;
+use common::clock::ClockGuard;
 use common::config::Settings;
+use common::db::migrations;
+use common::db::repositories::scheduler_journal::SchedulerJournalRepository;
+use common::db::repositories::system_job::SystemJobRepository;
+use common::db::repositories::usage::UsageRepository;
+use common::db::repositories::webhook_delivery::WebhookDeliveryRepository;
+use common::failover::RoleWatcher;
+use common::fault_injection::{FaultInjectingLock, FaultInjector};
 use common::lock::RedLock;
 use common::queue::NatsJobPublisher;
 use common::scheduler::{Scheduler, SchedulerConfig, SchedulerEngine};
+use common::system_jobs::{self, CanaryWatch};
+use common::telemetry::{AlertNotifier, LogAlertNotifier};
+use common::usage::UsageRecordingNotifier;
+use common::webhook::{WebhookAlertNotifier, WebhookDispatcher};
 use std::sync::Arc;
-use tracing::info;
+use std::time::Duration;
+use tracing::{info, warn};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -32,28 +45,58 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Requirements: 12.4 - PostgreSQL connection pool
     let db_pool = bootstrap::init_database_pool(&settings).await?;
 
+    // `scheduler --migrate` applies migrations and exits; otherwise refuse to
+    // schedule against a schema this build doesn't match
+    if migrations::migrate_requested() {
+        bootstrap::run_migrations(&db_pool).await?;
+        return Ok(());
+    }
+    bootstrap::verify_schema(&db_pool).await?;
+
     // Initialize Redis connection pool
     // Requirements: 4.1 - Redis for distributed locking
     let redis_pool = bootstrap::init_redis_pool(&settings).await?;
 
+    // Follow the region's failover role; a standby region doesn't schedule
+    let region_role = RoleWatcher::new(
+        redis_pool.get_connection(),
+        db_pool.clone(),
+        settings.failover.clone(),
+        "scheduler",
+    )
+    .spawn()
+    .await;
+
     // Initialize NATS client
     // Requirements: 4.2 - NATS JetStream for job queue
     let nats_client = bootstrap::init_nats_client(&settings, &settings.nats.consumer_name).await?;
 
-    // Initialize NATS stream
-    info!("Initializing NATS stream");
-    nats_client.initialize_stream().await?;
-    info!("NATS stream initialized");
+    // Initialize the shared and per-tenant NATS streams
+    info!("Initializing NATS streams");
+    nats_client.initialize_streams().await?;
+    info!("NATS streams initialized");
+    let control_client = nats_client.client().clone();
 
     // Create distributed lock
     // Requirements: 4.1, 7.1 - Distributed locking for scheduler coordination
-    let lock = Arc::new(RedLock::new(redis_pool)) as Arc<dyn common::lock::DistributedLock>;
+    let mut lock = Arc::new(RedLock::new(redis_pool)) as Arc<dyn common::lock::DistributedLock>;
+    if let Some(injector) = FaultInjector::from_config(&settings.fault_injection) {
+        // Chaos testing: fail some lock acquisitions
+        lock = Arc::new(FaultInjectingLock::new(lock, injector));
+    }
     info!("Distributed lock initialized");
 
     // Create job publisher
     // Requirements: 4.2 - Job publisher for NATS queue
-    let publisher =
-        Arc::new(NatsJobPublisher::new(nats_client)) as Arc<dyn common::queue::JobPublisher>;
+    // Oversized job messages are offloaded to storage for workers to load
+    let storage_service = bootstrap::init_storage_service(
+        &settings,
+        db_pool.clone(),
+        Arc::new(bootstrap::init_redis_connection(&settings).await?),
+    )
+    .await?;
+    let publisher = Arc::new(NatsJobPublisher::new(nats_client).with_payload_store(storage_service))
+        as Arc<dyn common::queue::JobPublisher>;
     info!("Job publisher initialized");
 
     // Create scheduler configuration
@@ -61,16 +104,82 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         poll_interval_seconds: settings.scheduler.poll_interval_seconds,
         lock_ttl_seconds: settings.scheduler.lock_ttl_seconds,
         max_jobs_per_poll: 100,
+        misfire_threshold_seconds: settings.scheduler.misfire_threshold_seconds,
     };
 
     // Create scheduler engine
     // Requirements: 9.4 - Initialize only scheduler-specific components
-    let scheduler = SchedulerEngine::new(scheduler_config, db_pool, lock, publisher);
+    // Sunset notices are recorded for tenant usage reports
+    let mut alert_notifier: Arc<dyn AlertNotifier> = Arc::new(UsageRecordingNotifier::new(
+        Arc::new(LogAlertNotifier::new(settings.i18n.locale())),
+        UsageRepository::new(db_pool.clone()),
+    ));
+    // Notification webhooks are only queued here; workers deliver them
+    if !settings.callbacks.notification_urls.is_empty() {
+        let dispatcher = Arc::new(WebhookDispatcher::new(
+            WebhookDeliveryRepository::new(db_pool.clone()),
+            settings.callbacks.clone(),
+        )?);
+        alert_notifier = Arc::new(WebhookAlertNotifier::new(alert_notifier, dispatcher));
+    }
+
+    // Check the clock, then keep checking: a clock running ahead fires jobs early.
+    // A skewed clock at startup leaves scheduling paused instead of exiting, so the
+    // node recovers on its own once the clock is corrected rather than crash-looping
+    let clock_guard = Arc::new(ClockGuard::new(
+        db_pool.clone(),
+        settings.scheduler.ntp_server.clone(),
+        settings.scheduler.max_clock_skew_ms,
+        Duration::from_secs(settings.scheduler.clock_check_interval_seconds),
+        alert_notifier.clone(),
+    ));
+    if let Err(e) = clock_guard.check().await {
+        warn!(error = %e, "Starting with scheduling paused until the clock is within the skew limit");
+    }
+    clock_guard.clone().spawn();
+    info!("Clock skew guard started");
+
+    // Built-in self-monitoring jobs are created once; the canary is watched so a
+    // pipeline too broken to run it still alerts
+    if settings.system_jobs.enabled {
+        let system_job_repo = SystemJobRepository::new(db_pool.clone());
+        let registered =
+            system_jobs::register_system_jobs(&system_job_repo, &settings.system_jobs).await;
+        info!(registered = registered, "System jobs registered");
+        Arc::new(
+            CanaryWatch::new(
+                system_job_repo,
+                alert_notifier.clone(),
+                &settings.system_jobs,
+            )
+            .with_region_role(region_role.clone()),
+        )
+        .spawn();
+    }
+
+    let mut scheduler = SchedulerEngine::new(scheduler_config, db_pool.clone(), lock, publisher)
+        .with_alert_notifier(alert_notifier)
+        .with_region_role(region_role)
+        .with_clock_guard(clock_guard);
+    // Every tick's decisions are journaled for "why didn't my job run" investigations
+    if settings.scheduler.journal_max_ticks > 0 {
+        scheduler = scheduler.with_journal(
+            SchedulerJournalRepository::new(db_pool),
+            settings.scheduler.journal_max_ticks as i64,
+        );
+    }
     info!("Scheduler engine created");
 
+    let scheduler_clone = Arc::new(scheduler);
+
+    // Operators drain schedulers before deploys; a drained node stops publishing new
+    // executions but keeps polling and reporting readiness
+    scheduler_clone
+        .clone()
+        .spawn_control_listener(control_client);
+
     // Set up graceful shutdown
     // Requirements: 7.6 - Handle SIGTERM/SIGINT signals
-    let scheduler_clone = Arc::new(scheduler);
     let scheduler_for_shutdown = scheduler_clone.clone();
 
     tokio::spawn(async move {
@@ -4,7 +4,7 @@
 #![allow(dead_code)]
 
 use common::models::JobExecution;
-use common::queue::JobPublisher;
+use common::queue::{JobMessage, JobPublisher};
 use common::scheduler::SchedulerConfig;
 use proptest::prelude::*;
 use std::sync::Arc;
@@ -35,6 +35,15 @@ impl JobPublisher for MockJobPublisher {
         Ok(())
     }
 
+    async fn publish_delayed(
+        &self,
+        message: &JobMessage,
+        _tenant_id: Option<&str>,
+    ) -> Result<(), common::errors::QueueError> {
+        self.published.lock().await.push(message.execution_id);
+        Ok(())
+    }
+
     async fn publish_with_retry(
         &self,
         execution: &JobExecution,
@@ -63,6 +72,7 @@ async fn property_scheduler_graceful_shutdown() {
             poll_interval_seconds,
             lock_ttl_seconds,
             max_jobs_per_poll: 10,
+            misfire_threshold_seconds: 60,
         };
 
         // For this property test, we verify the configuration is valid
@@ -93,6 +103,7 @@ fn property_scheduler_component_isolation() {
             poll_interval_seconds,
             lock_ttl_seconds,
             max_jobs_per_poll,
+            misfire_threshold_seconds: 60,
         };
 
         // Verify configuration is valid
@@ -162,6 +173,7 @@ fn test_scheduler_config_custom() {
             poll_interval_seconds: poll_interval,
             lock_ttl_seconds: lock_ttl,
             max_jobs_per_poll: max_jobs,
+            misfire_threshold_seconds: 60,
         };
 
         prop_assert_eq!(config.poll_interval_seconds, poll_interval);
@@ -4,66 +4,320 @@
 
 use anyhow::Result;
 use common::bootstrap;
+use common::callback::CallbackSender;
 use common::config::Settings;
+use common::db::migrations;
+use common::db::repositories::connection_profile::ConnectionProfileRepository;
 use common::db::repositories::execution::ExecutionRepository;
+use common::db::repositories::failure_note::FailureNoteRepository;
 use common::db::repositories::job::JobRepository;
+use common::db::repositories::job_state::JobStateRepository;
+use common::db::repositories::usage::UsageRepository;
+use common::db::repositories::variable::VariableRepository;
+use common::db::repositories::webhook_delivery::WebhookDeliveryRepository;
+use common::dlq::DeadLetterReplayer;
+use common::executor::checksum::ChecksumExecutor;
+use common::executor::container::{remove_orphaned_containers, ContainerExecutor};
 use common::executor::database::DatabaseExecutor;
+use common::executor::email::EmailExecutor;
 use common::executor::file::FileProcessingExecutor;
+use common::executor::graphql::GraphQLExecutor;
 use common::executor::http::HttpExecutor;
+use common::executor::http_pool::HttpClientPool;
+use common::executor::mqtt::MqttExecutor;
+use common::executor::notification::NotificationExecutor;
+use common::executor::object_storage::ObjectStorageExecutor;
+use common::executor::pdf::PdfExecutor;
+use common::executor::script::ScriptExecutor;
+use common::executor::sftp::SftpExecutor;
+use common::executor::system::SystemTaskExecutor;
+use common::executor::transform::TransformExecutor;
+use common::executor::validate::ValidateExecutor;
+use common::executor::wasm::WasmExecutor;
 use common::executor::JobExecutor;
+use common::failover::RoleWatcher;
+use common::fault_injection::{FaultInjectingExecutor, FaultInjectingLock, FaultInjector};
+use common::lock::{DistributedLock, RedLock};
+use common::queue::{JobPublisher, NatsClient, NatsJobPublisher};
+use common::resource_guard::{ResourceGuard, ResourceLimits};
+use common::semaphore::RedisSemaphore;
+use common::telemetry::{self, AlertNotifier, LogAlertNotifier};
+use common::usage::UsageRecordingNotifier;
+use common::webhook::{WebhookAlertNotifier, WebhookDispatcher};
+use common::worker::consumer::{
+    CollisionHold, ConcurrencyLimiter, DeferredRetries, JobDefinitionCache, SequenceLock,
+};
 use common::worker::context::JobContextManager;
+use common::worker::registry::WorkerReporter;
 use common::worker::WorkerJobConsumer;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    bootstrap::init_json_tracing();
-
-    info!("Starting Vietnam Enterprise Cron Worker");
-
     // Load configuration
     let settings = Settings::load()?;
+
+    // Initialize tracing, exported to OpenTelemetry when an endpoint is configured so
+    // duration metrics can carry trace exemplars
+    telemetry::init_logging(
+        &settings.observability.log_level,
+        settings.observability.tracing_endpoint.as_deref(),
+    )?;
+
+    info!("Starting Vietnam Enterprise Cron Worker");
     info!("Configuration loaded successfully");
 
+    // Job execution metrics (with per-job labels) on `observability.metrics_port`
+    telemetry::init_metrics(settings.observability.metrics_port)?;
+
     // Initialize database pool
     let db_pool = bootstrap::init_database_pool(&settings).await?;
 
-    // Note: Migrations should be run separately before starting the worker
-    info!("Skipping migrations (should be run separately)");
+    // `worker --migrate` applies migrations and exits; otherwise refuse to run
+    // against a schema this build doesn't match
+    if migrations::migrate_requested() {
+        return bootstrap::run_migrations(&db_pool).await;
+    }
+    bootstrap::verify_schema(&db_pool).await?;
 
     // Initialize repositories
     let job_repo = Arc::new(JobRepository::new(db_pool.clone()));
     let execution_repo = Arc::new(ExecutionRepository::new(db_pool.clone()));
     info!("Repositories initialized");
 
-    // Initialize Redis connection manager for storage cache
+    // Initialize Redis connection for storage cache
     // Requirements: 13.2 - Storage for job definitions and context with Redis cache
-    let redis_conn_manager = bootstrap::init_redis_connection_manager(&settings).await?;
+    let redis_connection = bootstrap::init_redis_connection(&settings).await?;
+
+    // Follow the region's failover role; a standby region consumes nothing
+    let region_role = RoleWatcher::new(
+        redis_connection.clone(),
+        db_pool.clone(),
+        settings.failover.clone(),
+        "worker",
+    )
+    .spawn()
+    .await;
+
+    let registry_connection = redis_connection.clone();
 
     // Initialize Storage service (PostgreSQL + Redis + Filesystem)
     let storage_service =
-        bootstrap::init_storage_service(&settings, db_pool.clone(), Arc::new(redis_conn_manager))
+        bootstrap::init_storage_service(&settings, db_pool.clone(), Arc::new(redis_connection))
             .await?;
 
-    // Initialize context manager
-    let context_manager = Arc::new(JobContextManager::new(storage_service.clone()));
+    // Initialize context manager; job state lives in the job_state table, or in the
+    // tenant's store for tenants with data residency rules
+    let context_manager = Arc::new(
+        JobContextManager::new(storage_service.clone())
+            .with_state_store(JobStateRepository::new(db_pool.clone())),
+    );
 
     // Initialize executors
-    let http_executor: Arc<dyn JobExecutor> = Arc::new(HttpExecutor::new(30)?); // 30 second timeout
-    let database_executor: Arc<dyn JobExecutor> = Arc::new(DatabaseExecutor::new(300)); // 5 minute timeout
+    // HTTP and GraphQL steps reuse one keep-alive client per host; configured hosts
+    // are pre-warmed
+    let http_clients = Arc::new(HttpClientPool::new(settings.http_client.clone()));
+    Arc::clone(&http_clients).spawn_keep_warm();
+    let http_executor: Arc<dyn JobExecutor> = Arc::new(
+        HttpExecutor::new(settings.http_client.timeout_seconds)?
+            .with_client_pool(Arc::clone(&http_clients))
+            .with_output_storage(storage_service.clone()),
+    );
+    let graphql_executor: Arc<dyn JobExecutor> = Arc::new(
+        GraphQLExecutor::new(settings.http_client.timeout_seconds)?
+            .with_client_pool(Arc::clone(&http_clients)),
+    );
+    let database_executor: Arc<dyn JobExecutor> =
+        Arc::new(DatabaseExecutor::new(300).with_output_storage(storage_service.clone())); // 5 minute timeout
     let file_executor: Arc<dyn JobExecutor> =
         Arc::new(FileProcessingExecutor::new(storage_service.clone()));
+    let sftp_executor: Arc<dyn JobExecutor> =
+        Arc::new(SftpExecutor::new(storage_service.clone(), 300)); // 5 minute connect-and-transfer timeout
+    let email_executor: Arc<dyn JobExecutor> =
+        Arc::new(EmailExecutor::new(storage_service.clone(), 60)); // 1 minute SMTP timeout
+    let notification_executor: Arc<dyn JobExecutor> = Arc::new(
+        NotificationExecutor::new(settings.http_client.timeout_seconds)?
+            .with_client_pool(Arc::clone(&http_clients)),
+    );
+    let mqtt_executor: Arc<dyn JobExecutor> = Arc::new(MqttExecutor::new(30)); // 30 second connect-and-publish timeout
+    let object_storage_executor: Arc<dyn JobExecutor> =
+        Arc::new(ObjectStorageExecutor::new(storage_service.clone(), 300)); // 5 minute per-request timeout
+    let container_executor: Arc<dyn JobExecutor> =
+        Arc::new(ContainerExecutor::new(storage_service.clone(), 3600)); // 1 hour default run timeout
+
+    // Remove the containers a worker that died mid-step left behind
+    let sweep_repo = Arc::clone(&execution_repo);
+    tokio::spawn(async move {
+        match remove_orphaned_containers(&sweep_repo).await {
+            Ok(removed) => info!(removed, "Orphaned step containers removed"),
+            Err(e) => warn!(error = %e, "Failed to sweep orphaned step containers"),
+        }
+    });
+    let sandbox_max_run = Duration::from_secs(settings.worker.sandbox_max_run_seconds);
+    let wasm_executor: Arc<dyn JobExecutor> =
+        Arc::new(WasmExecutor::new(storage_service.clone()).with_limits(
+            settings.worker.wasm_max_fuel,
+            settings.worker.wasm_max_memory_mb,
+            sandbox_max_run,
+        ));
+    let script_executor: Arc<dyn JobExecutor> = Arc::new(
+        ScriptExecutor::new().with_limits(settings.worker.script_max_operations, sandbox_max_run),
+    );
+    let pdf_executor: Arc<dyn JobExecutor> = Arc::new(PdfExecutor::new(storage_service.clone()));
+    let transform_executor: Arc<dyn JobExecutor> =
+        Arc::new(TransformExecutor::new(storage_service.clone()));
+    let validate_executor: Arc<dyn JobExecutor> =
+        Arc::new(ValidateExecutor::new(storage_service.clone()));
+    let checksum_executor: Arc<dyn JobExecutor> =
+        Arc::new(ChecksumExecutor::new(storage_service.clone()));
+
+    // Chaos testing: wrap executors so steps can be delayed or failed
+    let fault_injector = FaultInjector::from_config(&settings.fault_injection);
+    let http_executor = FaultInjectingExecutor::wrap(http_executor, fault_injector.as_ref());
+    let database_executor =
+        FaultInjectingExecutor::wrap(database_executor, fault_injector.as_ref());
+    let file_executor = FaultInjectingExecutor::wrap(file_executor, fault_injector.as_ref());
+    let sftp_executor = FaultInjectingExecutor::wrap(sftp_executor, fault_injector.as_ref());
+    let graphql_executor = FaultInjectingExecutor::wrap(graphql_executor, fault_injector.as_ref());
+    let email_executor = FaultInjectingExecutor::wrap(email_executor, fault_injector.as_ref());
+    let notification_executor =
+        FaultInjectingExecutor::wrap(notification_executor, fault_injector.as_ref());
+    let mqtt_executor = FaultInjectingExecutor::wrap(mqtt_executor, fault_injector.as_ref());
+    let object_storage_executor =
+        FaultInjectingExecutor::wrap(object_storage_executor, fault_injector.as_ref());
+    let container_executor =
+        FaultInjectingExecutor::wrap(container_executor, fault_injector.as_ref());
+    let wasm_executor = FaultInjectingExecutor::wrap(wasm_executor, fault_injector.as_ref());
+    let script_executor = FaultInjectingExecutor::wrap(script_executor, fault_injector.as_ref());
+    let pdf_executor = FaultInjectingExecutor::wrap(pdf_executor, fault_injector.as_ref());
+    let transform_executor =
+        FaultInjectingExecutor::wrap(transform_executor, fault_injector.as_ref());
+    let validate_executor =
+        FaultInjectingExecutor::wrap(validate_executor, fault_injector.as_ref());
+    let checksum_executor =
+        FaultInjectingExecutor::wrap(checksum_executor, fault_injector.as_ref());
     info!("Executors initialized");
 
+    // Outbound webhooks (completion callbacks and notifications) are queued in the
+    // database and delivered by every worker with retries
+    // Alerts and callbacks are recorded for tenant usage reports
+    let usage_repo = UsageRepository::new(db_pool.clone());
+    let webhook_dispatcher = Arc::new(
+        WebhookDispatcher::new(
+            WebhookDeliveryRepository::new(db_pool.clone()),
+            settings.callbacks.clone(),
+        )?
+        .with_usage(usage_repo.clone()),
+    );
+    Arc::clone(&webhook_dispatcher).spawn();
+    let callback_sender = Arc::new(CallbackSender::new(Arc::clone(&webhook_dispatcher)));
+    let mut alert_notifier: Arc<dyn AlertNotifier> = Arc::new(UsageRecordingNotifier::new(
+        Arc::new(LogAlertNotifier::new(settings.i18n.locale())),
+        usage_repo,
+    ));
+    if !settings.callbacks.notification_urls.is_empty() {
+        alert_notifier = Arc::new(WebhookAlertNotifier::new(
+            alert_notifier,
+            webhook_dispatcher,
+        ));
+    }
+
+    // System jobs send their expiry warnings through the alert notifier
+    let system_executor: Arc<dyn JobExecutor> = Arc::new(
+        SystemTaskExecutor::new(
+            db_pool.clone(),
+            storage_service.clone(),
+            &settings.storage.file_base_path,
+        )
+        .with_alert_notifier(Arc::clone(&alert_notifier)),
+    );
+
+    // Per-execution memory and CPU guardrails
+    let resource_guard = Arc::new(ResourceGuard::new(
+        ResourceLimits {
+            max_memory_bytes: settings
+                .worker
+                .execution_max_memory_mb
+                .map(|mb| mb * 1024 * 1024),
+            max_cpu: settings
+                .worker
+                .execution_max_cpu_seconds
+                .map(Duration::from_secs),
+        },
+        Duration::from_millis(settings.worker.resource_sample_interval_ms),
+    ));
+    Arc::clone(&resource_guard).spawn();
+
     // Initialize NATS client
     let nats_client = bootstrap::init_nats_client(&settings, "worker-consumer").await?;
 
     // Clone NATS client for status publishing before moving it
     let nats_client_for_status = nats_client.client().clone();
 
+    // Job definitions cached in the worker, evicted when the API reports a change
+    let job_cache = Arc::new(JobDefinitionCache::new(
+        settings.worker.job_cache_max_entries,
+    ));
+    Arc::clone(&job_cache).spawn_invalidation(nats_client_for_status.clone());
+
+    // Step retries with a long backoff, and runs queued behind a previous run of
+    // their job, wait on the queue instead of in a worker slot
+    let requeue_publisher: Arc<dyn JobPublisher> = Arc::new(
+        NatsJobPublisher::new(NatsClient::from_client(
+            nats_client_for_status.clone(),
+            nats_client.config().clone(),
+        ))
+        .with_payload_store(storage_service.clone()),
+    );
+    let deferred_retries =
+        (settings.worker.deferred_retry_min_delay_seconds > 0).then(|| DeferredRetries {
+            publisher: Arc::clone(&requeue_publisher),
+            min_delay: Duration::from_secs(settings.worker.deferred_retry_min_delay_seconds),
+        });
+    let collision_hold = CollisionHold {
+        publisher: Arc::clone(&requeue_publisher),
+        recheck_interval: Duration::from_secs(settings.worker.collision_recheck_seconds),
+    };
+
+    // Executions of strict_ordering jobs take turns through a per-job Redis lock
+    let redis_pool = bootstrap::init_redis_pool(&settings).await?;
+    let mut sequence_lock: Arc<dyn DistributedLock> = Arc::new(RedLock::new(redis_pool.clone()));
+    if let Some(injector) = &fault_injector {
+        sequence_lock = Arc::new(FaultInjectingLock::new(sequence_lock, Arc::clone(injector)));
+    }
+    let sequence_lock = SequenceLock {
+        lock: sequence_lock,
+        publisher: Arc::clone(&requeue_publisher),
+        recheck_interval: Duration::from_secs(settings.worker.collision_recheck_seconds),
+        stale_after: Duration::from_secs(settings.worker.sequence_stale_after_seconds),
+    };
+
+    // Steps with acquire_lock serialize on their resource across jobs; the step
+    // executor polls until the step's wait timeout, so each attempt tries once
+    let mut step_locks: Arc<dyn DistributedLock> =
+        Arc::new(RedLock::with_retry(redis_pool.clone(), 1, Duration::ZERO));
+    if let Some(injector) = &fault_injector {
+        step_locks = Arc::new(FaultInjectingLock::new(step_locks, Arc::clone(injector)));
+    }
+
+    // Jobs with a concurrency_limit share their slots across workers through Redis
+    let concurrency_limiter = ConcurrencyLimiter {
+        semaphore: Arc::new(RedisSemaphore::new(redis_pool)),
+        publisher: Arc::clone(&requeue_publisher),
+        recheck_interval: Duration::from_secs(settings.worker.collision_recheck_seconds),
+    };
+
+    // Dead letters of a dependency are replayed when its circuit breaker closes again
+    let dependency_recoveries = Arc::new(DeadLetterReplayer::new(
+        db_pool.clone(),
+        storage_service.clone(),
+        requeue_publisher,
+    ))
+    .spawn();
+
     // Create worker job consumer with Storage service
     // Requirements: 13.4 - Worker supports multi-step jobs with storage integration
     // WorkerJobConsumer will create NatsJobConsumer internally with proper handler
@@ -71,11 +325,39 @@ async fn main() -> Result<()> {
         nats_client,
         job_repo,
         execution_repo,
+        Arc::new(FailureNoteRepository::new(db_pool.clone())),
+        Arc::new(ConnectionProfileRepository::new(db_pool.clone())),
+        Arc::new(VariableRepository::new(db_pool.clone(), None)),
         context_manager,
         storage_service,
         http_executor,
         database_executor,
         file_executor,
+        sftp_executor,
+        graphql_executor,
+        email_executor,
+        notification_executor,
+        mqtt_executor,
+        object_storage_executor,
+        container_executor,
+        wasm_executor,
+        script_executor,
+        pdf_executor,
+        transform_executor,
+        validate_executor,
+        checksum_executor,
+        system_executor,
+        alert_notifier,
+        callback_sender,
+        Duration::from_secs(settings.worker.heartbeat_interval_seconds),
+        Arc::clone(&resource_guard),
+        job_cache,
+        deferred_retries,
+        Some(collision_hold),
+        Some(sequence_lock),
+        Some(concurrency_limiter),
+        Some(step_locks),
+        Some(dependency_recoveries),
         Some(nats_client_for_status),
     )
     .await
@@ -83,6 +365,20 @@ async fn main() -> Result<()> {
         error!(error = %e, "Failed to create worker consumer");
         anyhow::anyhow!("Worker consumer creation error: {}", e)
     })?;
+    let worker_consumer = match fault_injector {
+        Some(injector) => worker_consumer.with_fault_injector(injector),
+        None => worker_consumer,
+    }
+    .with_region_role(region_role);
+
+    // Publish load and circuit-breaker states for the dashboard's worker registry
+    WorkerReporter::new(
+        registry_connection,
+        settings.failover.region.clone(),
+        resource_guard,
+        worker_consumer.circuit_breakers(),
+    )
+    .spawn();
 
     info!("Worker consumer created, starting job processing");
 
@@ -109,6 +405,9 @@ async fn main() -> Result<()> {
     info!("Waiting for worker to complete in-flight executions");
     let _ = worker_handle.await;
 
+    // Flush spans still buffered for the OTLP exporter
+    telemetry::shutdown_tracer();
+
     info!("Worker shutdown complete");
     Ok(())
 }
@@ -589,6 +589,10 @@ fn test_path_format_consistency() {
                     sheet_index: None,
                     transformations: vec![],
                     streaming: false,
+                    source_paths: vec![],
+                    password: None,
+                    columns: vec![],
+                    schema_sample_rows: None,
                 },
             },
         ),
@@ -598,10 +602,10 @@ fn test_path_format_consistency() {
                 operation: SftpOperation::Download,
                 host: "sftp.example.com".to_string(),
                 port: 22,
-                auth: SftpAuth::Password {
+                auth: Some(SftpAuth::Password {
                     username: "user".to_string(),
                     password: "pass".to_string(),
-                },
+                }),
                 remote_path: "/remote/path".to_string(),
                 local_path: None,
                 options: SftpOptions {